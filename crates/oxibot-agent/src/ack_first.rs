@@ -0,0 +1,79 @@
+//! Ack-first replies — say "working on it" when the answer takes a while.
+//!
+//! A turn that searches the web or runs tools can take half a minute, and
+//! the user can't tell whether the bot is stuck. When a reply isn't ready
+//! after the channel's threshold, a one-line acknowledgment is sent first
+//! ("Working on it — checking 3 sources…"), written by a cheap model or
+//! taken from a template, and the full answer follows as usual.
+
+use std::time::Duration;
+
+use oxibot_core::config::schema::AckFirstConfig;
+use oxibot_core::types::Message;
+
+/// Longest acknowledgment taken from the model.
+const MAX_ACK_CHARS: usize = 160;
+
+/// How long a reply on `channel` may take before it is acknowledged
+/// (`None` when ack-first is off for the channel).
+pub fn delay_for(config: &AckFirstConfig, channel: &str) -> Option<Duration> {
+    let ms = config.channel_after_ms.get(channel).copied().unwrap_or(config.after_ms);
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// Messages for the call that writes the acknowledgment.
+pub fn ack_messages(text: &str) -> Vec<Message> {
+    let system = "You acknowledge requests for an assistant that is still working on them. \
+        Write one short line (under 15 words) in the user's language saying what you are \
+        doing, e.g. \"Working on it — checking 3 sources…\". Don't answer the request, \
+        don't promise a time, no quotes.";
+    vec![Message::system(system), Message::user(format!("User message:\n{text}"))]
+}
+
+/// The acknowledgment in the model's output: its first non-empty line,
+/// unquoted and cut to a sensible length.
+pub fn parse_ack(output: &str) -> Option<String> {
+    let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.trim_matches(|c| c == '"' || c == '“' || c == '”').trim();
+    if line.is_empty() {
+        return None;
+    }
+    let mut ack: String = line.chars().take(MAX_ACK_CHARS).collect();
+    if ack.len() < line.len() {
+        ack.push('…');
+    }
+    Some(ack)
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_for() {
+        let config = AckFirstConfig {
+            enabled: true,
+            after_ms: 4000,
+            channel_after_ms: [("slack".to_string(), 1500), ("email".to_string(), 0)].into(),
+            ..Default::default()
+        };
+        assert_eq!(delay_for(&config, "telegram"), Some(Duration::from_millis(4000)));
+        assert_eq!(delay_for(&config, "slack"), Some(Duration::from_millis(1500)));
+        assert_eq!(delay_for(&config, "email"), None);
+    }
+
+    #[test]
+    fn test_parse_ack() {
+        assert_eq!(
+            parse_ack("\n\"Working on it — checking 3 sources…\"\nextra").as_deref(),
+            Some("Working on it — checking 3 sources…")
+        );
+        assert_eq!(parse_ack("  \n \"\" "), None);
+        let long = parse_ack(&"a".repeat(300)).unwrap();
+        assert_eq!(long.chars().count(), MAX_ACK_CHARS + 1);
+    }
+}
@@ -5,40 +5,155 @@
 //! tool calls, and publishes outbound responses.
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use tracing::{debug, error, info};
+use serde_json::Value;
+use tracing::{debug, error, info, warn};
 
+use oxibot_core::bus::commands::{CommandRouter, Permission};
+use oxibot_core::bus::events::AgentEvent;
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
+use oxibot_core::config::schema::{
+    AckFirstConfig, BrowserToolConfig, CommandsConfig, DebugFooterConfig, FileIndexConfig, FileUploadConfig, ModelPrice, PlanModeConfig, QuickReplyConfig, ResponsesConfig, TokenizerConfig, ToolBreakerConfig, ToolResultsConfig, TranslationConfig,
+};
+use oxibot_core::contacts::ContactBook;
+use oxibot_core::error::{self, ProviderError};
 use oxibot_core::session::manager::SessionManager;
-use oxibot_core::types::{Message, ToolCall};
+use oxibot_core::session::tags::detect_language;
+use oxibot_core::session::transcript::{TranscriptEvent, TranscriptLog};
+use oxibot_core::tokenizer::{self, Tokenizer};
+use oxibot_core::tool_stats::ToolStatsStore;
+use oxibot_core::types::{LlmResponse, Message, ToolCall, ToolDefinition, UsageInfo};
+use oxibot_core::usage::{TurnUsage, UsageEvent, UsageLog};
+use oxibot_cron::CronService;
+use oxibot_providers::registry::{context_window, tokenizer_for_model};
+use oxibot_providers::FileUploader;
 use oxibot_providers::traits::{LlmProvider, LlmRequestConfig};
 
-use crate::context::ContextBuilder;
+use crate::compaction::{self, Limit};
+use crate::file_index::FileIndex;
+use crate::page_cache::PageCache;
+use crate::context::{ContextBuilder, Trimmed};
+use crate::context_providers::{ContextProvider, ContextRequest};
+use crate::persona::{self, Persona};
+use crate::plan::Plan;
+use crate::tool_breaker::{self, ToolBreaker};
+use crate::forms::{self, Form, FormSpec};
+use crate::ack_first;
+use crate::quick_reply;
+use crate::translation;
+use crate::redaction::Redactor;
+use crate::responses::{self, Canned, CannedResponses};
+use crate::routing::{ModelRoute, ModelRouter, Phase};
+use crate::scratch::ScratchDirs;
+use crate::tasks::TaskStore;
+use crate::kv::KvStore;
 use crate::subagent::SubagentManager;
-use crate::tools::message::MessageTool;
+use crate::tools::calendar::{
+    CalendarBackend, CalendarCreateEventTool, CalendarListEventsTool, Tz, UserTimezone,
+};
+use crate::tools::contacts::ContactsTool;
+use crate::tools::github::{
+    GitHubClient, GitHubCommentTool, GitHubCreateIssueTool, GitHubNotificationsTool, GitHubPrDiffTool,
+};
+use crate::tools::cron::{CronAddTool, CronListTool, CronRemoveTool};
+use crate::tools::context::TurnContext;
+use crate::tools::dry_run::{self, DryRun};
+use crate::tools::message::{MessageTool, SendCallback};
+use crate::tools::pin::{format_pins, PinTool};
+use crate::tools::form::FormTool;
+use crate::tools::plan::{PlanStepTool, ProposePlanTool};
+use crate::tools::base::{invalid_arguments, parse_arguments, unknown_tool};
 use crate::tools::registry::ToolRegistry;
-use crate::tools::filesystem::{EditFileTool, ListDirTool, ReadFileTool, WriteFileTool};
+use crate::tools::attachments::SaveAttachmentTool;
+use crate::tools::filesystem::{
+    EditFileTool, ListDirTool, ReadFileTool, WorkingDir, WriteFileTool,
+};
+use crate::tools::find_files::FindFilesTool;
 use crate::tools::shell::ExecTool;
+use crate::tools::skill_secrets::{Secrets, SkillSecrets};
 use crate::tools::spawn::SpawnTool;
-use crate::tools::web::{WebFetchTool, WebSearchTool};
+use crate::tools::tag::TagSessionTool;
+use crate::tools::tasks::{TaskAddTool, TaskDoneTool, TaskListTool};
+use crate::tools::kv::{KvDeleteTool, KvGetTool, KvListTool, KvSetTool};
+use crate::tools::time::{ParseTimeTool, TimezoneConvertTool};
+use crate::tools::calc::CalcTool;
+use crate::tools::units::UnitConvertTool;
+use crate::tools::tool_stats::ToolStatsTool;
+use crate::tools::web::{RecallPageTool, WebFetchTool, WebSearchTool};
+use crate::uploads::FileUploads;
 
 /// Default maximum LLM ↔ tool iterations per user message.
 const DEFAULT_MAX_ITERATIONS: usize = 20;
 
+/// History messages loaded when the model's context window is unknown.
+const DEFAULT_HISTORY_MESSAGES: usize = 50;
+
+/// History messages loaded at most when they are trimmed to fit the
+/// context window instead.
+const MAX_HISTORY_MESSAGES: usize = 500;
+
+/// Longest tool argument excerpt in the summary of a stopped turn.
+const STOPPED_ARGS_CHARS: usize = 60;
+
+/// Default number of malformed tool calls per turn before falling back.
+const DEFAULT_MAX_BAD_TOOL_CALLS: usize = 2;
+
+/// Default number of identical failing tool calls before a turn ends.
+const DEFAULT_MAX_REPEATED_FAILURES: usize = 3;
+
+/// Added to the result of a call that failed the same way before.
+const REPEATED_FAILURE_NOTE: &str = "\n\nThis exact call has already failed. Don't repeat it: \
+    change the arguments, use another tool, or answer with what you have.";
+
+/// Session metadata field recording when the chat was greeted.
+const GREETED_FIELD: &str = "greeted_at";
+
 /// Configuration for the exec tool.
 #[derive(Clone, Debug)]
 pub struct ExecToolConfig {
     /// Timeout in seconds (default 60).
     pub timeout: u64,
+    /// Ask in chat before running dangerous commands, waiting this long for
+    /// an answer (`None` = always block them).
+    pub approval_timeout: Option<Duration>,
 }
 
 impl Default for ExecToolConfig {
     fn default() -> Self {
-        Self { timeout: 60 }
+        Self {
+            timeout: 60,
+            approval_timeout: None,
+        }
+    }
+}
+
+/// What processing one message produced.
+#[derive(Debug)]
+pub struct TurnResult {
+    /// The reply to send back.
+    pub reply: OutboundMessage,
+    /// Tokens used by the turn's LLM calls.
+    pub usage: UsageInfo,
+    /// Tool calls made, in order.
+    pub tool_calls: Vec<ToolCall>,
+    /// Debug footer for the reply (`None` unless enabled for the channel).
+    pub footer: Option<String>,
+}
+
+impl TurnResult {
+    fn new(reply: OutboundMessage) -> Self {
+        Self {
+            reply,
+            usage: UsageInfo::default(),
+            tool_calls: Vec::new(),
+            footer: None,
+        }
     }
 }
 
@@ -46,16 +161,37 @@ impl Default for ExecToolConfig {
 // AgentLoop
 // ─────────────────────────────────────────────
 
+/// Tools removed from read-only agents: they change files or state, run
+/// commands, or reach other chats and people.
+const MUTATING_TOOLS: &[&str] = &[
+    "write_file",
+    "edit_file",
+    "exec",
+    "spawn",
+    "message",
+    "pin",
+    "contacts",
+    "task_add",
+    "task_done",
+    "kv_set",
+    "kv_delete",
+    "save_attachment",
+    "form",
+    "cron_add",
+    "cron_remove",
+    "calendar_create_event",
+    "github_comment",
+    "github_create_issue",
+];
+
 /// The main agent loop: polls the message bus, calls the LLM, dispatches tools.
 pub struct AgentLoop {
     /// Message bus for inbound/outbound messages.
     bus: Arc<MessageBus>,
-    /// LLM provider.
-    provider: Arc<dyn LlmProvider>,
+    /// Provider/model selection per phase (planning vs. final answer).
+    router: ModelRouter,
     /// Workspace root.
-    _workspace: PathBuf,
-    /// Model to use (overrides provider default if set).
-    model: String,
+    workspace: PathBuf,
     /// Max LLM ↔ tool iterations per message.
     max_iterations: usize,
     /// LLM request config (temperature, max_tokens).
@@ -64,15 +200,74 @@ pub struct AgentLoop {
     tools: ToolRegistry,
     /// Context builder.
     context: ContextBuilder,
-    /// Session manager.
-    sessions: SessionManager,
-    /// Reference to the message tool (for set_context).
-    message_tool: Arc<MessageTool>,
-    /// Spawn tool reference (for set_context).
-    spawn_tool: Arc<SpawnTool>,
+    /// Session manager (shared with the pin tool).
+    sessions: Arc<SessionManager>,
+    /// Dry-run switch shared by the mutating tools.
+    dry_run: DryRun,
+    /// Skill secrets shared by `read_file` and `exec`.
+    skill_secrets: SkillSecrets,
+    /// Per-session scratch directories (`None` = disabled).
+    scratch: Option<ScratchDirs>,
+    /// Contact book file (`None` = contacts disabled).
+    contacts: Option<PathBuf>,
+    /// Usage log for analytics (`None` = disabled).
+    usage: Option<UsageLog>,
+    /// Per-reply usage footer.
+    debug_footer: DebugFooterConfig,
+    /// Model prices for the footer's cost estimate.
+    prices: HashMap<String, ModelPrice>,
+    /// Usage of the running turn per `channel:chat_id`, for the footer.
+    meters: std::sync::Mutex<HashMap<String, TurnUsage>>,
+    /// Full turn transcripts for replay (`None` = disabled).
+    transcripts: Option<TranscriptLog>,
+    /// Per-tool call stats (`None` = disabled).
+    tool_stats: Option<Arc<ToolStatsStore>>,
+    /// Tools taken out of use while they keep failing (`None` = never).
+    tool_breaker: Option<ToolBreaker>,
+    /// Default persona (`None` = no persona); sessions may override it.
+    persona: Option<&'static Persona>,
+    /// Compaction of tool results the LLM has already seen (`None` = keep all).
+    tool_results: Option<ToolResultsConfig>,
+    /// Tokenizer files and per-model selection.
+    tokenizers: TokenizerConfig,
+    /// Prompt token budget (0 = the model's context window).
+    max_context_tokens: usize,
+    /// Context window overrides per model.
+    context_windows: HashMap<String, usize>,
+    /// Quick replies for small talk (`None` = disabled).
+    quick_replies: Option<QuickReplyConfig>,
+    /// Model that classifies quick-reply candidates (`None` = planner).
+    quick_route: Option<ModelRoute>,
+    /// Acknowledgments before slow replies (`None` = disabled).
+    ack_first: Option<AckFirstConfig>,
+    /// Model writing the acknowledgments (`None` = template).
+    ack_route: Option<ModelRoute>,
+    /// Translation of messages and replies (`None` = disabled).
+    translation: Option<TranslationConfig>,
+    /// Model that translates (`None` = planner).
+    translation_route: Option<ModelRoute>,
+    /// Greeting and help texts.
+    responses: CannedResponses,
+    /// Chat commands (`/reset`, `/status`, ...).
+    commands: CommandRouter<AgentLoop>,
+    /// Show the correlation ID of errors in chat replies.
+    show_error_ids: bool,
+    /// Bad tool-call arguments allowed per turn before falling back (0 = never).
+    max_bad_tool_calls: usize,
+    /// Planning model used after too many bad tool calls (`None` = disable tools).
+    tool_fallback: Option<ModelRoute>,
+    /// Identical failing tool calls allowed per turn before it ends (0 = never).
+    max_repeated_failures: usize,
+    /// Plan-first mode for multi-step requests.
+    plan_mode: bool,
+    /// Secret redaction before LLM calls (`None` = disabled).
+    redactor: Option<Arc<Redactor>>,
+    /// Documents uploaded to the provider (`None` = sent inline).
+    uploads: Option<FileUploads>,
     /// Subagent manager (also held by SpawnTool; kept for direct access).
-    #[allow(dead_code)]
     subagent_manager: Arc<SubagentManager>,
+    /// Brave Search API key, kept to rebuild `web_search`.
+    brave_api_key: Option<String>,
 }
 
 impl AgentLoop {
@@ -96,8 +291,9 @@ impl AgentLoop {
         let request_config = request_config.unwrap_or_default();
         let exec_config = exec_config.unwrap_or_default();
         let agent_name = agent_name.unwrap_or_else(|| "Oxibot".into());
-        let sessions =
-            session_manager.unwrap_or_else(|| SessionManager::new(None).expect("failed to create session manager"));
+        let sessions = Arc::new(
+            session_manager.unwrap_or_else(|| SessionManager::new(None).expect("failed to create session manager")),
+        );
 
         let context = ContextBuilder::new(&workspace, &agent_name);
 
@@ -109,20 +305,50 @@ impl AgentLoop {
             None
         };
 
-        tools.register(Arc::new(ReadFileTool::new(allowed_dir.clone())));
-        tools.register(Arc::new(WriteFileTool::new(allowed_dir.clone())));
-        tools.register(Arc::new(EditFileTool::new(allowed_dir.clone())));
-        tools.register(Arc::new(ListDirTool::new(allowed_dir)));
-        tools.register(Arc::new(ExecTool::new(
-            workspace.clone(),
-            Some(exec_config.timeout),
-            restrict_to_workspace,
-        )));
+        let working_dir = WorkingDir::new();
+        let dry_run = DryRun::new();
+        let skill_secrets = SkillSecrets::new(&workspace);
+        tools.register(Arc::new(
+            ReadFileTool::new(allowed_dir.clone())
+                .with_working_dir(working_dir.clone())
+                .with_skill_secrets(skill_secrets.clone()),
+        ));
+        tools.register(Arc::new(
+            WriteFileTool::new(allowed_dir.clone())
+                .with_working_dir(working_dir.clone())
+                .with_dry_run(dry_run.clone()),
+        ));
+        tools.register(Arc::new(
+            EditFileTool::new(allowed_dir.clone())
+                .with_working_dir(working_dir.clone())
+                .with_dry_run(dry_run.clone()),
+        ));
+        tools.register(Arc::new(
+            ListDirTool::new(allowed_dir).with_working_dir(working_dir.clone()),
+        ));
+        tools.register(Arc::new(
+            SaveAttachmentTool::new(workspace.clone()).with_dry_run(dry_run.clone()),
+        ));
+        // Progress updates are best-effort: never block on a full outbound queue
+        let outbound = bus.outbound_sender();
+        let progress: SendCallback = Arc::new(move |msg| {
+            if let Err(e) = outbound.try_send(msg) {
+                debug!(error = %e, "dropping exec progress update");
+            }
+            Box::pin(async { Ok(()) })
+        });
+        let mut exec_tool = ExecTool::new(workspace.clone(), Some(exec_config.timeout), restrict_to_workspace)
+            .with_progress(progress)
+            .with_dry_run(dry_run.clone())
+            .with_skill_secrets(skill_secrets.clone());
+        if let Some(timeout) = exec_config.approval_timeout {
+            exec_tool = exec_tool.with_approval(bus.clone(), timeout);
+        }
+        tools.register(Arc::new(exec_tool));
         tools.register(Arc::new(WebSearchTool::new(brave_api_key.clone())));
         tools.register(Arc::new(WebFetchTool::new()));
 
-        let message_tool = Arc::new(MessageTool::new(None));
-        tools.register(message_tool.clone());
+        tools.register(Arc::new(MessageTool::new(None)));
 
         // Subagent manager + spawn tool
         let subagent_manager = Arc::new(SubagentManager::new(
@@ -130,14 +356,36 @@ impl AgentLoop {
             workspace.clone(),
             bus.clone(),
             model.clone(),
-            brave_api_key,
+            brave_api_key.clone(),
             exec_config,
             restrict_to_workspace,
             request_config.clone(),
-        ));
-
-        let spawn_tool = Arc::new(SpawnTool::new(subagent_manager.clone()));
-        tools.register(spawn_tool.clone());
+        )
+        .with_dry_run(dry_run.clone()));
+
+        tools.register(Arc::new(SpawnTool::new(subagent_manager.clone())));
+        tools.register(Arc::new(PinTool::new(sessions.clone())));
+        tools.register(Arc::new(FormTool::new(sessions.clone(), workspace.clone())));
+        tools.register(Arc::new(TagSessionTool::new(sessions.clone())));
+
+        let tasks = TaskStore::new(&workspace);
+        tools.register(Arc::new(TaskAddTool::new(tasks.clone())));
+        tools.register(Arc::new(TaskListTool::new(tasks.clone())));
+        tools.register(Arc::new(TaskDoneTool::new(tasks)));
+        let kv = KvStore::new(&workspace);
+        tools.register(Arc::new(KvGetTool::new(kv.clone())));
+        tools.register(Arc::new(KvSetTool::new(kv.clone())));
+        tools.register(Arc::new(KvDeleteTool::new(kv.clone())));
+        tools.register(Arc::new(KvListTool::new(kv)));
+        tools.register(Arc::new(FindFilesTool::new(FileIndex::new(
+            &workspace,
+            FileIndexConfig::default(),
+        ))));
+        let timezone = UserTimezone::new(workspace.join("USER.md"), Tz::UTC);
+        tools.register(Arc::new(ParseTimeTool::new(timezone.clone())));
+        tools.register(Arc::new(TimezoneConvertTool::new(timezone)));
+        tools.register(Arc::new(CalcTool));
+        tools.register(Arc::new(UnitConvertTool));
 
         info!(
             model = %model,
@@ -146,299 +394,1910 @@ impl AgentLoop {
             "agent loop initialized"
         );
 
+        let scratch = Some(ScratchDirs::new(&workspace));
+        let responses = CannedResponses::new(ResponsesConfig::default(), &workspace);
+
         Self {
             bus,
-            provider,
-            _workspace: workspace,
-            model,
+            router: ModelRouter::new(ModelRoute::new(provider, model)),
+            workspace,
             max_iterations,
             request_config,
             tools,
             context,
             sessions,
-            message_tool,
-            spawn_tool,
+            dry_run,
+            skill_secrets,
+            scratch,
+            contacts: None,
+            usage: None,
+            debug_footer: DebugFooterConfig::default(),
+            prices: HashMap::new(),
+            meters: std::sync::Mutex::new(HashMap::new()),
+            transcripts: None,
+            tool_stats: None,
+            tool_breaker: None,
+            persona: None,
+            tool_results: Some(ToolResultsConfig::default()),
+            tokenizers: TokenizerConfig::default(),
+            max_context_tokens: 0,
+            context_windows: HashMap::new(),
+            quick_replies: None,
+            quick_route: None,
+            ack_first: None,
+            ack_route: None,
+            translation: None,
+            translation_route: None,
+            responses,
+            commands: Self::chat_commands(),
+            show_error_ids: false,
+            max_bad_tool_calls: DEFAULT_MAX_BAD_TOOL_CALLS,
+            tool_fallback: None,
+            max_repeated_failures: DEFAULT_MAX_REPEATED_FAILURES,
+            plan_mode: false,
+            redactor: None,
+            uploads: None,
             subagent_manager,
+            brave_api_key,
         }
     }
 
-    /// Run the event loop: poll inbound messages and process them.
+    /// Route planning/summarization and final answers to dedicated models.
     ///
-    /// This runs indefinitely until the inbound channel is closed.
-    pub async fn run(&self) {
-        info!("agent loop started, waiting for messages");
-        loop {
-            match self.bus.consume_inbound().await {
-                Some(msg) => {
-                    let session_key = msg.session_key();
-                    debug!(session_key = %session_key, "received message");
-
-                    // Route system messages (from subagents) vs regular messages
-                    let result = if msg.channel == "system" && msg.sender_id == "subagent" {
-                        self.process_system_message(&msg).await
-                    } else {
-                        self.process_message(&msg).await
-                    };
-
-                    match result {
-                        Ok(response) => {
-                            if let Err(e) = self.bus.publish_outbound(response).await {
-                                error!(error = %e, "failed to publish outbound message");
-                            }
-                        }
-                        Err(e) => {
-                            error!(error = %e, session_key = %session_key, "message processing error");
-                            let err_msg = OutboundMessage::new(
-                                &msg.channel,
-                                &msg.chat_id,
-                                &format!("I encountered an error: {e}"),
-                            );
-                            let _ = self.bus.publish_outbound(err_msg).await;
-                        }
-                    }
-                }
-                None => {
-                    info!("inbound channel closed, agent loop exiting");
-                    break;
-                }
-            }
-        }
+    /// `None` falls back to the default provider and model for that phase.
+    pub fn with_model_routing(
+        mut self,
+        planner: Option<ModelRoute>,
+        responder: Option<ModelRoute>,
+    ) -> Self {
+        self.router = self
+            .router
+            .with_planner(planner)
+            .with_responder(responder);
+        self
     }
 
-    /// Process a single inbound message → outbound response.
+    /// Recover from tool calls with malformed JSON arguments or unknown
+    /// tool names.
     ///
-    /// This is the core agent logic:
-    /// 1. Get/create session, load history
-    /// 2. Build context messages
-    /// 3. LLM ↔ tool loop
-    /// 4. Save session, return response
-    pub async fn process_message(&self, msg: &InboundMessage) -> Result<OutboundMessage> {
-        let session_key = msg.session_key();
-
-        // Set message tool context for this conversation
-        self.message_tool
-            .set_context(&msg.channel, &msg.chat_id)
-            .await;
-
-        // Set spawn tool context for this conversation
-        self.spawn_tool
-            .set_context(&msg.channel, &msg.chat_id)
-            .await;
-
-        // Get session history
-        let history = self.sessions.get_history(&session_key, 50);
-
-        // Build LLM messages
-        let media_paths: Vec<String> = msg.media.iter().map(|m| m.path.clone()).collect();
-        let mut messages = self.context.build_messages(
-            &history,
-            &msg.content,
-            &media_paths,
-            &msg.channel,
-            &msg.chat_id,
-        );
-
-        // Get tool definitions
-        let tool_defs = self.tools.get_definitions();
-
-        // Agent loop: LLM ↔ tool calling
-        let mut final_content: Option<String> = None;
-
-        for iteration in 0..self.max_iterations {
-            debug!(iteration = iteration, "LLM call");
-
-            let response = self
-                .provider
-                .chat(
-                    &messages,
-                    Some(&tool_defs),
-                    &self.model,
-                    &self.request_config,
-                )
-                .await;
+    /// The problem is sent back to the model as the tool result. After
+    /// `max_failures` bad calls in one turn, planning switches to `fallback`;
+    /// without one, or if it fails as well, tools are disabled for the rest
+    /// of the turn. `max_failures == 0` only reports the errors.
+    pub fn with_tool_call_repair(mut self, max_failures: usize, fallback: Option<ModelRoute>) -> Self {
+        self.max_bad_tool_calls = max_failures;
+        self.tool_fallback = fallback;
+        self
+    }
 
-            if response.has_tool_calls() {
-                // Add assistant message with tool calls
-                let tool_calls: Vec<ToolCall> = response.tool_calls.clone();
-                ContextBuilder::add_assistant_message(
-                    &mut messages,
-                    response.content.clone(),
-                    tool_calls.clone(),
-                );
+    /// Stop offering tools that keep failing, as set by `config` (see
+    /// [`ToolBreaker`]).
+    pub fn with_tool_breaker(mut self, config: ToolBreakerConfig) -> Self {
+        self.tool_breaker = config.enabled.then(|| ToolBreaker::new(config));
+        self
+    }
 
-                // Execute each tool call
-                for tc in &tool_calls {
-                    let params: HashMap<String, serde_json::Value> =
-                        serde_json::from_str(&tc.function.arguments).unwrap_or_default();
+    /// End a turn once the same tool call (same tool, same arguments) has
+    /// failed `max` times, replying with the error instead of looping
+    /// (0 = never). The second failure already warns the model.
+    pub fn with_repeated_failure_limit(mut self, max: usize) -> Self {
+        self.max_repeated_failures = max;
+        self
+    }
 
-                    info!(
-                        tool = %tc.function.name,
-                        iteration = iteration,
-                        "executing tool call"
-                    );
+    /// Configure per-session scratch directories (`None` disables them).
+    ///
+    /// Enabled by default under `workspace/sessions/`.
+    pub fn with_scratch(mut self, scratch: Option<ScratchDirs>) -> Self {
+        self.scratch = scratch;
+        self
+    }
 
-                    let result = self.tools.execute(&tc.function.name, params).await;
+    /// Enable the contact book stored at `path` (`None` disables it).
+    ///
+    /// Registers the `contacts` tool and tells the agent which contact it is
+    /// talking to when the sender is known.
+    pub fn with_contacts(mut self, path: Option<PathBuf>) -> Self {
+        match path {
+            Some(ref p) => self.tools.register(Arc::new(ContactsTool::new(Some(p.clone())))),
+            None => {
+                self.tools.unregister("contacts");
+            }
+        }
+        self.contacts = path;
+        self
+    }
 
-                    debug!(
-                        tool = %tc.function.name,
-                        result_len = result.len(),
-                        "tool result"
-                    );
+    /// Enable the calendar tools on `backend` (`None` disables them).
+    ///
+    /// Times are shown and entered in the timezone named in the workspace
+    /// `USER.md`, falling back to `default_timezone` (also used by the
+    /// `parse_time` and `timezone_convert` tools).
+    pub fn with_calendar(mut self, backend: Option<Arc<dyn CalendarBackend>>, default_timezone: Tz) -> Self {
+        let timezone = UserTimezone::new(self.workspace.join("USER.md"), default_timezone);
+        self.tools.register(Arc::new(ParseTimeTool::new(timezone.clone())));
+        self.tools.register(Arc::new(TimezoneConvertTool::new(timezone.clone())));
+        match backend {
+            Some(backend) => {
+                self.tools.register(Arc::new(CalendarListEventsTool::new(backend.clone(), timezone.clone())));
+                self.tools.register(Arc::new(
+                    CalendarCreateEventTool::new(backend, timezone).with_dry_run(self.dry_run.clone()),
+                ));
+            }
+            None => {
+                self.tools.unregister("calendar_list_events");
+                self.tools.unregister("calendar_create_event");
+            }
+        }
+        self
+    }
 
-                    ContextBuilder::add_tool_result(&mut messages, &tc.id, &result);
+    /// Enable the `github_*` tools on `github` (`None` disables them).
+    pub fn with_github(mut self, github: Option<Arc<GitHubClient>>) -> Self {
+        match github {
+            Some(github) => {
+                self.tools.register(Arc::new(GitHubNotificationsTool::new(github.clone())));
+                self.tools.register(Arc::new(GitHubPrDiffTool::new(github.clone())));
+                self.tools.register(Arc::new(
+                    GitHubCommentTool::new(github.clone()).with_dry_run(self.dry_run.clone()),
+                ));
+                self.tools.register(Arc::new(
+                    GitHubCreateIssueTool::new(github).with_dry_run(self.dry_run.clone()),
+                ));
+            }
+            None => {
+                for name in [
+                    "github_notifications",
+                    "github_pr_diff",
+                    "github_comment",
+                    "github_create_issue",
+                ] {
+                    self.tools.unregister(name);
                 }
-            } else {
-                // No tool calls → final answer
-                final_content = response.content;
-                break;
             }
         }
+        self
+    }
 
-        // If we exhausted iterations without a final answer
-        let content = final_content
-            .unwrap_or_else(|| "I've completed processing but have no response to give.".into());
-
-        // Save conversation to session
-        self.sessions
-            .add_message(&session_key, Message::user(&msg.content));
-        self.sessions
-            .add_message(&session_key, Message::assistant(&content));
+    /// Webhooks completed forms may be sent to, by name
+    /// (`tools.forms.webhooks`).
+    pub fn with_form_webhooks(mut self, webhooks: HashMap<String, String>) -> Self {
+        self.tools.register(Arc::new(
+            FormTool::new(self.sessions.clone(), self.workspace.clone()).with_webhooks(webhooks),
+        ));
+        self
+    }
 
-        Ok(OutboundMessage::new(&msg.channel, &msg.chat_id, &content))
+    /// Enable the `cron_add`, `cron_list` and `cron_remove` tools on
+    /// `service` (`None` disables them).
+    ///
+    /// Times are read in the timezone named in the workspace `USER.md`,
+    /// falling back to `default_timezone`.
+    pub fn with_cron(mut self, service: Option<Arc<CronService>>, default_timezone: Tz) -> Self {
+        match service {
+            Some(service) => {
+                let timezone = UserTimezone::new(self.workspace.join("USER.md"), default_timezone);
+                self.tools.register(Arc::new(
+                    CronAddTool::new(service.clone(), timezone).with_dry_run(self.dry_run.clone()),
+                ));
+                self.tools.register(Arc::new(CronListTool::new(service.clone())));
+                self.tools.register(Arc::new(CronRemoveTool::new(service).with_dry_run(self.dry_run.clone())));
+            }
+            None => {
+                self.tools.unregister("cron_add");
+                self.tools.unregister("cron_list");
+                self.tools.unregister("cron_remove");
+            }
+        }
+        self
     }
 
-    /// Process a system message (from a subagent or cron).
+    /// Enable the `browser_fetch` tool (`None` disables it).
     ///
-    /// Parses the original `channel:chat_id` from `msg.chat_id`,
-    /// loads the original session, runs a full LLM call to summarize
-    /// the result, and routes the response back to the correct channel.
-    async fn process_system_message(&self, msg: &InboundMessage) -> Result<OutboundMessage> {
-        info!(
-            sender = %msg.sender_id,
-            chat_id = %msg.chat_id,
-            "processing system message"
-        );
+    /// Only available when built with the `browser` feature; otherwise a
+    /// warning is logged and the tool stays off.
+    pub fn with_browser(mut self, config: Option<BrowserToolConfig>) -> Self {
+        match config {
+            #[cfg(feature = "browser")]
+            Some(config) => {
+                use crate::tools::browser::BrowserFetchTool;
+                self.tools.register(Arc::new(
+                    BrowserFetchTool::new(config, self.workspace.clone()).with_working_dir(WorkingDir::new()),
+                ));
+            }
+            #[cfg(not(feature = "browser"))]
+            Some(_) => warn!("tools.browser is enabled but oxibot was built without the `browser` feature"),
+            None => {
+                self.tools.unregister("browser_fetch");
+            }
+        }
+        self
+    }
 
-        // Parse origin from chat_id format "channel:chat_id"
-        let (origin_channel, origin_chat_id) = match msg.chat_id.split_once(':') {
-            Some((ch, cid)) => (ch.to_string(), cid.to_string()),
+    /// Configure the workspace file index behind `find_files` (`None`
+    /// disables the tool). The index is built on the first search.
+    pub fn with_file_index(mut self, config: Option<FileIndexConfig>) -> Self {
+        match config {
+            Some(config) => {
+                self.tools.register(Arc::new(FindFilesTool::new(FileIndex::new(&self.workspace, config))));
+            }
             None => {
-                return Err(anyhow::anyhow!(
-                    "Invalid system message chat_id format: {}",
-                    msg.chat_id
-                ));
+                self.tools.unregister("find_files");
             }
-        };
+        }
+        self
+    }
 
-        let session_key = format!("{origin_channel}:{origin_chat_id}");
+    /// Let `web_search` use the provider's own web search (`None` keeps
+    /// Brave). The answer comes with the pages it cites; Brave is still
+    /// used when the provider call fails and a Brave key is set. A provider
+    /// without web search support is ignored with a warning.
+    pub fn with_web_search(mut self, route: Option<ModelRoute>) -> Self {
+        let route = route.filter(|route| {
+            let supported = route.provider.supports_web_search();
+            if !supported {
+                warn!(
+                    provider = route.provider.display_name(),
+                    "provider has no web search of its own, web_search uses Brave"
+                );
+            }
+            supported
+        });
+        self.tools.register(Arc::new(WebSearchTool::new(self.brave_api_key.clone()).with_native(route)));
+        self
+    }
 
-        // Set tools context to the original channel/chat
-        self.message_tool
-            .set_context(&origin_channel, &origin_chat_id)
-            .await;
-        self.spawn_tool
-            .set_context(&origin_channel, &origin_chat_id)
-            .await;
+    /// Keep `web_fetch` extractions in `cache`, here and in subagents, and
+    /// offer `recall_page` to search them (`None` fetches every time).
+    pub fn with_page_cache(mut self, cache: Option<Arc<PageCache>>) -> Self {
+        self.subagent_manager.set_page_cache(cache.clone());
+        self.tools.register(Arc::new(WebFetchTool::new().with_cache(cache.clone())));
+        if let Some(cache) = cache {
+            self.tools.register(Arc::new(RecallPageTool::new(cache)));
+        }
+        self
+    }
 
-        // Load the original session
-        let history = self.sessions.get_history(&session_key, 50);
+    /// Include the correlation ID in error replies, so users can quote it
+    /// and the admin can find the full error in the logs.
+    pub fn with_error_ids(mut self, enabled: bool) -> Self {
+        self.show_error_ids = enabled;
+        self
+    }
 
-        // Build messages with the subagent result as the "user" message
-        let mut messages =
-            self.context
-                .build_messages(&history, &msg.content, &[], &origin_channel, &origin_chat_id);
+    /// Preview `write_file`, `edit_file` and `exec` calls instead of running
+    /// them; the previews are returned to the LLM and appended to the reply.
+    ///
+    /// Also applies to subagents spawned by this agent.
+    pub fn with_dry_run(self, enabled: bool) -> Self {
+        self.dry_run.set(enabled);
+        self
+    }
 
-        let tool_defs = self.tools.get_definitions();
-        let mut final_content: Option<String> = None;
+    /// Give `exec` the secrets configured for a skill (`skills.secrets`) in
+    /// conversations that use it; see [`SkillSecrets`].
+    pub fn with_skill_secrets(mut self, secrets: Secrets) -> Self {
+        self.context = self.context.with_skill_secrets(&secrets);
+        self.skill_secrets.set(secrets);
+        self
+    }
 
-        for iteration in 0..self.max_iterations {
-            debug!(iteration = iteration, "system message LLM call");
+    /// Record messages, LLM calls and tool calls to a usage log.
+    pub fn with_usage_log(mut self, usage: Option<UsageLog>) -> Self {
+        self.usage = usage;
+        self
+    }
 
-            let response = self
-                .provider
-                .chat(&messages, Some(&tool_defs), &self.model, &self.request_config)
-                .await;
+    /// Add a footer with the models, tokens, estimated cost (from
+    /// `prices`), tools and time of the turn to replies on the channels
+    /// `config` selects.
+    pub fn with_debug_footer(mut self, config: DebugFooterConfig, prices: HashMap<String, ModelPrice>) -> Self {
+        self.debug_footer = config;
+        self.prices = prices;
+        self
+    }
 
-            if response.has_tool_calls() {
-                let tool_calls: Vec<ToolCall> = response.tool_calls.clone();
-                ContextBuilder::add_assistant_message(
-                    &mut messages,
-                    response.content.clone(),
-                    tool_calls.clone(),
-                );
+    /// Log every LLM response and tool result of each turn, for
+    /// `oxibot sessions replay`.
+    pub fn with_transcripts(mut self, transcripts: Option<TranscriptLog>) -> Self {
+        self.transcripts = transcripts;
+        self
+    }
 
-                for tc in &tool_calls {
-                    let params: HashMap<String, serde_json::Value> =
-                        serde_json::from_str(&tc.function.arguments).unwrap_or_default();
-                    let result = self.tools.execute(&tc.function.name, params).await;
-                    ContextBuilder::add_tool_result(&mut messages, &tc.id, &result);
-                }
-            } else {
-                final_content = response.content;
-                break;
+    /// Record call counts, durations and errors per tool (`None` disables
+    /// it), and register the `tool_stats` tool that reports them.
+    pub fn with_tool_stats(mut self, store: Option<Arc<ToolStatsStore>>) -> Self {
+        match store {
+            Some(ref store) => self.tools.register(Arc::new(ToolStatsTool::new(store.clone()))),
+            None => {
+                self.tools.unregister("tool_stats");
             }
         }
-
-        let content = final_content
-            .unwrap_or_else(|| "I've completed processing but have no response to give.".into());
-
-        // Save to the original session
-        self.sessions
-            .add_message(&session_key, Message::user(&msg.content));
-        self.sessions
-            .add_message(&session_key, Message::assistant(&content));
-
-        // Route response to the original channel/chat
-        Ok(OutboundMessage::new(
-            &origin_channel,
-            &origin_chat_id,
-            &content,
-        ))
+        self.tool_stats = store;
+        self
     }
 
-    /// Direct processing mode (CLI entry point).
+    /// Configure compaction of large tool results from earlier iterations
+    /// (`None` keeps them in full).
     ///
-    /// Wraps text into an `InboundMessage` on the "cli" channel and processes.
-    pub async fn process_direct(&self, text: &str) -> Result<String> {
-        let msg = InboundMessage::new("cli", "user", "direct", text);
-        let response = self.process_message(&msg).await?;
-        Ok(response.content)
+    /// Enabled by default: results over 4000 characters are truncated once
+    /// the LLM has seen them.
+    pub fn with_tool_result_compaction(mut self, config: Option<ToolResultsConfig>) -> Self {
+        self.tool_results = config;
+        self
     }
 
-    /// Get a reference to the tool registry (for testing/extension).
-    pub fn tools(&self) -> &ToolRegistry {
-        &self.tools
+    /// Configure where tokenizer files are found and which model uses
+    /// which (default: `~/.oxibot/tokenizers`, provider defaults).
+    pub fn with_tokenizers(mut self, config: TokenizerConfig) -> Self {
+        self.tokenizers = config;
+        self
     }
 
-    /// Get the model name.
-    pub fn model(&self) -> &str {
-        &self.model
+    /// Keep the prompt under `max_tokens` (0 = only the model's context
+    /// window applies).
+    pub fn with_context_budget(mut self, max_tokens: usize) -> Self {
+        self.max_context_tokens = max_tokens;
+        self
     }
-}
 
-// ─────────────────────────────────────────────
-// Tests
-// ─────────────────────────────────────────────
+    /// Context windows of models the built-in table doesn't know or gets
+    /// wrong, by model name.
+    pub fn with_context_windows(mut self, windows: HashMap<String, usize>) -> Self {
+        self.context_windows = windows;
+        self
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use async_trait::async_trait;
-    use oxibot_core::types::{LlmResponse, ToolDefinition};
+    /// Tokenizer for `model`.
+    fn tokenizer(&self, model: &str) -> Arc<dyn Tokenizer> {
+        tokenizer_for_model(model, &self.tokenizers)
+    }
 
-    /// A mock LLM provider that returns canned responses.
-    struct MockProvider {
-        /// Responses to return in sequence.
-        responses: std::sync::Mutex<Vec<LlmResponse>>,
+    /// Append the context providers' sections to the system prompt.
+    async fn add_provided_sections(&self, messages: &mut [Message], request: ContextRequest) {
+        let tokenizer = self.tokenizer(&self.router.route(Phase::Planning).model);
+        self.context
+            .add_provided_sections(messages, &request, tokenizer.as_ref())
+            .await;
     }
 
-    impl MockProvider {
+    /// Prompt token budget for `model`: its context window less the
+    /// response budget and the tool definitions, capped by
+    /// `max_context_tokens`. `None` when neither is known.
+    fn context_budget(&self, model: &str, tools: &[ToolDefinition], tokenizer: &dyn Tokenizer) -> Option<usize> {
+        let available = context_window(model, &self.context_windows).map(|window| {
+            let tools = tokenizer.count(&serde_json::to_string(tools).unwrap_or_default());
+            window.saturating_sub(self.request_config.max_tokens as usize + tools)
+        });
+        let configured = (self.max_context_tokens > 0).then_some(self.max_context_tokens);
+        match (available, configured) {
+            (Some(available), Some(configured)) => Some(available.min(configured)),
+            (available, configured) => available.or(configured),
+        }
+    }
+
+    /// Session messages to load as history: as many as may fit when the
+    /// planning model has a context budget, else the last 50.
+    fn history_limit(&self) -> usize {
+        let model = &self.router.route(Phase::Planning).model;
+        if self.max_context_tokens > 0 || context_window(model, &self.context_windows).is_some() {
+            MAX_HISTORY_MESSAGES
+        } else {
+            DEFAULT_HISTORY_MESSAGES
+        }
+    }
+
+    /// Leave out tool results, then the oldest of the `history` messages,
+    /// so `messages` fit the context budget of `model`.
+    fn fit_context(&self, messages: &mut Vec<Message>, history: &mut usize, model: &str, tools: &[ToolDefinition]) {
+        let tokenizer = self.tokenizer(model);
+        let Some(budget) = self.context_budget(model, tools, tokenizer.as_ref()) else {
+            return;
+        };
+        let trimmed = ContextBuilder::fit_to_budget(messages, history, tokenizer.as_ref(), budget);
+        if trimmed != Trimmed::default() {
+            debug!(
+                tool_results = trimmed.tool_results,
+                dropped = trimmed.messages,
+                budget,
+                tokenizer = tokenizer.name(),
+                "trimmed the prompt to fit the context budget"
+            );
+        }
+    }
+
+    /// Compact large tool results in `messages[..fresh_from]`, which the LLM
+    /// has already seen.
+    async fn compact_tool_results(
+        &self,
+        messages: &mut [Message],
+        fresh_from: usize,
+        channel: &str,
+        chat_id: &str,
+    ) {
+        let Some(ref config) = self.tool_results else { return };
+        let route = self.router.route(Phase::Planning);
+        let tokenizer = self.tokenizer(&route.model);
+        let limit = match config.max_tokens {
+            0 => Limit::Chars(config.max_chars),
+            max => Limit::Tokens(max, tokenizer.as_ref()),
+        };
+        for i in compaction::stale_results(messages, fresh_from, &limit) {
+            let Message::Tool { content, .. } = &mut messages[i] else { continue };
+            let original = content.chars().count();
+            let max_chars = limit.max_chars(content);
+            let mut compacted = None;
+            if config.summarize {
+                let request = compaction::summary_messages(content, max_chars);
+                let response = self.chat(route, &request, &[], channel, chat_id).await;
+                let failed = response.is_error();
+                compacted = response
+                    .content
+                    .filter(|s| !failed && !s.trim().is_empty())
+                    .map(|s| compaction::summarized(&s, original));
+            }
+            *content = compacted.unwrap_or_else(|| compaction::truncate(content, max_chars));
+            debug!(original, compacted = content.chars().count(), "compacted tool result");
+        }
+    }
+
+    /// Set the greeting and help texts (`/start`, `/help`, new chats).
+    pub fn with_responses(mut self, config: ResponsesConfig) -> Self {
+        self.responses = CannedResponses::new(config, &self.workspace);
+        self
+    }
+
+    /// The greeting or help text for the sender of `msg`.
+    fn canned_response(&self, kind: Canned, msg: &InboundMessage) -> String {
+        let contact = self
+            .contacts
+            .as_ref()
+            .map(|path| ContactBook::load(Some(path.clone())))
+            .and_then(|book| book.resolve_sender(&msg.channel, &msg.sender_id).map(|c| c.name.clone()));
+        let name = contact
+            .as_deref()
+            .or(msg.metadata.get("first_name").map(String::as_str))
+            .or(msg.metadata.get("username").map(String::as_str));
+        if kind == Canned::Greeting {
+            // Saves the session, so the chat counts as greeted from now on
+            self.sessions
+                .set_metadata(&msg.session_key(), GREETED_FIELD, Some(&chrono::Utc::now().to_rfc3339()));
+        }
+        self.responses.render(kind, &msg.channel, name, &self.commands.help())
+    }
+
+    /// Set the default persona (`None` = none). `/persona` overrides it per
+    /// session.
+    pub fn with_persona(mut self, persona: Option<&'static Persona>) -> Self {
+        self.persona = persona;
+        self
+    }
+
+    /// Persona for a session: its `/persona` override, else the default.
+    fn session_persona(&self, session_key: &str) -> Option<&'static Persona> {
+        match self.sessions.metadata(session_key, persona::SESSION_FIELD) {
+            Some(name) if name == persona::NONE => None,
+            Some(name) => persona::find(&name).or(self.persona),
+            None => self.persona,
+        }
+    }
+
+    /// Reply for the `/persona` command.
+    fn persona_command(&self, session_key: &str, arg: &str) -> String {
+        match arg {
+            "" => {
+                let current = self.session_persona(session_key).map_or("none", |p| p.name);
+                let mut reply = format!("🎭 Persona: {current}\n\nAvailable:\n");
+                for p in persona::PRESETS {
+                    reply.push_str(&format!("- {} — {}\n", p.name, p.summary));
+                }
+                reply.push_str("\nUse /persona <name>, /persona none or /persona default.");
+                reply
+            }
+            "default" => {
+                self.sessions.set_metadata(session_key, persona::SESSION_FIELD, None);
+                let default = self.persona.map_or("none", |p| p.name);
+                format!("🎭 Persona reset to the default ({default}).")
+            }
+            persona::NONE => {
+                self.sessions
+                    .set_metadata(session_key, persona::SESSION_FIELD, Some(persona::NONE));
+                "🎭 Persona disabled for this chat.".to_string()
+            }
+            name => match persona::find(name) {
+                Some(p) => {
+                    self.sessions.set_metadata(session_key, persona::SESSION_FIELD, Some(p.name));
+                    format!("🎭 Persona set to {}: {}", p.name, p.summary)
+                }
+                None => format!("Unknown persona '{name}'. Available: {}", persona::names()),
+            },
+        }
+    }
+
+    /// Answer small talk without the full tool loop (`None` disables it).
+    ///
+    /// `route` classifies messages that aren't canned phrases; `None` uses
+    /// the planner model.
+    pub fn with_quick_replies(
+        mut self,
+        config: Option<QuickReplyConfig>,
+        route: Option<ModelRoute>,
+    ) -> Self {
+        self.quick_replies = config;
+        self.quick_route = route;
+        self
+    }
+
+    /// Send a short acknowledgment when a reply takes longer than the
+    /// channel's threshold (`None` disables it). `route` writes the
+    /// acknowledgment; `None` sends the configured template.
+    pub fn with_ack_first(mut self, config: Option<AckFirstConfig>, route: Option<ModelRoute>) -> Self {
+        self.ack_first = config;
+        self.ack_route = route;
+        self
+    }
+
+    /// Translate messages on the configured channels into the operator's
+    /// language, and replies back into the sender's (`None` disables it).
+    /// `route` translates; `None` uses the planner model.
+    pub fn with_translation(mut self, config: Option<TranslationConfig>, route: Option<ModelRoute>) -> Self {
+        self.translation = config;
+        self.translation_route = route;
+        self
+    }
+
+    /// Enable plan-first mode (`None` disables it): the agent proposes a
+    /// plan for multi-step requests, waits for approval, then reports its
+    /// progress per step.
+    pub fn with_plan_mode(mut self, config: Option<PlanModeConfig>) -> Self {
+        match config {
+            Some(config) => {
+                let timeout = Duration::from_secs(config.approval_timeout_secs.max(1));
+                self.tools.register(Arc::new(ProposePlanTool::new(
+                    self.sessions.clone(),
+                    self.bus.clone(),
+                    config.auto_approve,
+                    timeout,
+                )));
+                self.tools.register(Arc::new(PlanStepTool::new(self.sessions.clone(), self.bus.clone())));
+                self.plan_mode = true;
+            }
+            None => {
+                self.tools.unregister("propose_plan");
+                self.tools.unregister("plan_step");
+                self.plan_mode = false;
+            }
+        }
+        self
+    }
+
+    /// Reply for the `/plan` command.
+    fn plan_command(&self, session_key: &str, arg: &str) -> String {
+        let plan = Plan::load(&self.sessions, session_key);
+        match (arg, plan) {
+            (_, None) => "No plan in this chat.".to_string(),
+            ("cancel", Some(_)) => {
+                Plan::clear(&self.sessions, session_key);
+                "🗑️ Plan dropped.".to_string()
+            }
+            (_, Some(plan)) => format!(
+                "{}
+
+Status: {:?}, {}/{} steps complete",
+                plan.render(),
+                plan.state,
+                plan.completed(),
+                plan.steps.len()
+            ),
+        }
+    }
+
+    /// Reply for the `/form` command.
+    fn form_command(&self, session_key: &str, arg: &str) -> String {
+        match (arg, Form::load(&self.sessions, session_key)) {
+            (_, None) => "No form is being filled in this chat.".to_string(),
+            ("cancel", Some(form)) => {
+                Form::clear(&self.sessions, session_key);
+                format!("🗑️ Form '{}' dropped.", form.spec.name)
+            }
+            (_, Some(form)) => form.render(),
+        }
+    }
+
+    /// Start the form an API caller attached to `msg` (see
+    /// [`forms::START_KEY`]), replacing any form in progress.
+    fn start_requested_form(&self, session_key: &str, msg: &InboundMessage) {
+        let Some(spec) = msg.metadata.get(forms::START_KEY) else {
+            return;
+        };
+        let form = serde_json::from_str::<FormSpec>(spec)
+            .map_err(anyhow::Error::from)
+            .and_then(Form::new);
+        match form {
+            Ok(form) => {
+                info!(session = %session_key, form = %form.spec.name, "form started");
+                form.save(&self.sessions, session_key);
+            }
+            Err(e) => warn!(session = %session_key, error = %e, "ignoring invalid form"),
+        }
+    }
+
+    /// Replace secrets with placeholders before LLM calls, here and in
+    /// subagents (`None` disables it).
+    pub fn with_redaction(mut self, redactor: Option<Arc<Redactor>>) -> Self {
+        self.subagent_manager.set_redactor(redactor.clone());
+        self.redactor = redactor;
+        self
+    }
+
+    /// Upload documents sent to the agent with `uploader` and attach them
+    /// by file id on this and later turns (`None` sends them inline).
+    pub fn with_file_uploads(mut self, uploader: Option<Arc<dyn FileUploader>>, config: FileUploadConfig) -> Self {
+        self.uploads = uploader.map(|uploader| FileUploads::new(uploader, self.sessions.clone(), config));
+        self
+    }
+
+    /// Add extra system prompt sections from `providers`, each given at
+    /// most `timeout` per turn.
+    pub fn with_context_providers(mut self, providers: Vec<Arc<dyn ContextProvider>>, timeout: Duration) -> Self {
+        self.context = providers
+            .into_iter()
+            .fold(self.context.with_provider_timeout(timeout), ContextBuilder::with_provider);
+        self
+    }
+
+    /// Reply for the `/files` command.
+    fn files_command(&self, session_key: &str, arg: &str) -> String {
+        let Some(ref uploads) = self.uploads else {
+            return "Document uploads are disabled.".to_string();
+        };
+        if arg == "clear" {
+            return match uploads.clear(session_key) {
+                0 => "No documents in this chat.".to_string(),
+                n => format!("🗑️ Removed {n} document(s)."),
+            };
+        }
+        let files = uploads.list(session_key);
+        if files.is_empty() {
+            return "No documents in this chat.".to_string();
+        }
+        let mut out = String::from("📎 Documents in this chat:");
+        for file in &files {
+            out.push_str(&format!("\n- {}", file.filename));
+        }
+        out
+    }
+
+    /// Call the LLM on `route` and record the call in the usage log.
+    async fn chat(
+        &self,
+        route: &ModelRoute,
+        messages: &[Message],
+        tool_defs: &[ToolDefinition],
+        channel: &str,
+        chat_id: &str,
+    ) -> LlmResponse {
+        let started = Instant::now();
+        let tools = (!tool_defs.is_empty()).then_some(tool_defs);
+        let redacted;
+        let messages = match self.redactor {
+            Some(ref r) => {
+                redacted = r.redact_messages(&conversation_key(channel, chat_id), messages);
+                &redacted[..]
+            }
+            None => messages,
+        };
+        let mut response = route
+            .provider
+            .chat(messages, tools, &route.model, &self.request_config)
+            .await;
+        if response.usage.is_none() && !response.is_error() {
+            response.usage = Some(self.estimate_usage(&route.model, messages, tool_defs, &response));
+        }
+
+        let tokens = response.usage.as_ref();
+        self.account(UsageEvent::LlmCall {
+            timestamp: chrono::Utc::now(),
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            model: route.model.clone(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            prompt_tokens: tokens.map_or(0, |u| u.prompt_tokens),
+            completion_tokens: tokens.map_or(0, |u| u.completion_tokens),
+        });
+        response
+    }
+
+    /// Record `event` in the usage log, and in its session's meter when the
+    /// chat gets the debug footer.
+    fn account(&self, event: UsageEvent) {
+        if let UsageEvent::LlmCall { ref channel, ref chat_id, .. } | UsageEvent::ToolCall { ref channel, ref chat_id, .. } = event {
+            if self.debug_footer.applies_to(channel) {
+                let mut meters = self.meters.lock().unwrap();
+                meters.entry(conversation_key(channel, chat_id)).or_default().add(&event);
+            }
+        }
+        if let Some(ref usage) = self.usage {
+            usage.record(&event);
+        }
+    }
+
+    /// Token usage for a response from a provider that reported none.
+    fn estimate_usage(
+        &self,
+        model: &str,
+        messages: &[Message],
+        tool_defs: &[ToolDefinition],
+        response: &LlmResponse,
+    ) -> UsageInfo {
+        let tokenizer = self.tokenizer(model);
+        let mut prompt = tokenizer::count_messages(tokenizer.as_ref(), messages);
+        if !tool_defs.is_empty() {
+            prompt += tokenizer.count(&serde_json::to_string(tool_defs).unwrap_or_default());
+        }
+        let reply = Message::Assistant {
+            content: response.content.clone(),
+            tool_calls: Some(response.tool_calls.clone()),
+            reasoning_content: response.reasoning_content.clone(),
+        };
+        let completion = tokenizer::count_message(tokenizer.as_ref(), &reply);
+        debug!(model, prompt, completion, tokenizer = tokenizer.name(), "estimated token usage");
+        UsageInfo {
+            prompt_tokens: prompt as u32,
+            completion_tokens: completion as u32,
+            total_tokens: (prompt + completion) as u32,
+        }
+    }
+
+    /// The text of a response that answers the user but also calls only
+    /// tools that don't exist. Some models do this; the calls are dropped
+    /// and the text is the final answer.
+    fn answer_beside_unknown_tools(&self, response: &LlmResponse) -> Option<String> {
+        if !response.has_tool_calls() || response.tool_calls.iter().any(|tc| self.tools.has(&tc.function.name)) {
+            return None;
+        }
+        let answer = response.content.as_deref().filter(|c| !c.trim().is_empty())?;
+        let names: Vec<&str> = response.tool_calls.iter().map(|tc| tc.function.name.as_str()).collect();
+        warn!(tools = ?names, "ignoring calls to unknown tools next to an answer");
+        Some(answer.to_string())
+    }
+
+    /// Execute a tool call and record it in the usage log.
+    ///
+    /// Calls to unknown tools and malformed arguments are not executed;
+    /// the `Err` holds the tool result asking the model to fix the call.
+    async fn run_tool(&self, tc: &ToolCall, channel: &str, chat_id: &str) -> Result<String, String> {
+        if !self.tools.has(&tc.function.name) {
+            warn!(tool = %tc.function.name, "call to unknown tool");
+            return Err(unknown_tool(&tc.function.name, &self.tools.tool_names()));
+        }
+        if self.tool_breaker.as_ref().is_some_and(|b| b.is_disabled(&tc.function.name)) {
+            warn!(tool = %tc.function.name, "call to disabled tool");
+            return Err(tool_breaker::disabled_result(&tc.function.name));
+        }
+        let started = Instant::now();
+        let result = match parse_arguments(&tc.function.arguments) {
+            Ok(mut params) => {
+                if let Some(ref r) = self.redactor {
+                    r.restore_params(&conversation_key(channel, chat_id), &mut params);
+                }
+                Ok(self.tools.execute(&tc.function.name, params).await)
+            }
+            Err(e) => {
+                warn!(tool = %tc.function.name, error = %e, "malformed tool-call arguments");
+                Err(invalid_arguments(&tc.function.name, &e))
+            }
+        };
+
+        let elapsed = started.elapsed();
+        let error = match result {
+            Ok(ref r) if !r.starts_with("Error") => None,
+            Ok(ref r) | Err(ref r) => Some(r.lines().next().unwrap_or_default()),
+        };
+        self.account(UsageEvent::ToolCall {
+            timestamp: chrono::Utc::now(),
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            tool: tc.function.name.clone(),
+            latency_ms: elapsed.as_millis() as u64,
+            success: error.is_none(),
+        });
+        if let Some(ref stats) = self.tool_stats {
+            stats.record(&tc.function.name, elapsed, error);
+        }
+        // Malformed calls are the model's fault, not the tool's
+        if let (Some(breaker), Ok(_)) = (&self.tool_breaker, &result) {
+            breaker.record(&tc.function.name, error);
+        }
+        result
+    }
+
+    /// Definitions of the tools offered this turn. Tools the breaker took
+    /// out of use are left out, and the system prompt in `messages` says so.
+    fn offered_tools(&self, messages: &mut [Message]) -> Vec<ToolDefinition> {
+        let mut defs = self.tools.get_definitions();
+        if let Some(ref breaker) = self.tool_breaker {
+            ContextBuilder::add_disabled_tools(messages, &breaker.filter(&mut defs));
+        }
+        defs
+    }
+
+    /// Restrict the tools available to the agent.
+    ///
+    /// `allowed` keeps only the named tools (empty = keep all). `read_only`
+    /// also removes the tools that write files, run commands, spawn
+    /// subagents or message other chats. Read-only agents should also be
+    /// built with `restrict_to_workspace`.
+    pub fn with_tool_filter(mut self, allowed: &[String], read_only: bool) -> Self {
+        for name in self.tools.tool_names() {
+            let blocked = (!allowed.is_empty() && !allowed.contains(&name))
+                || (read_only && MUTATING_TOOLS.contains(&name.as_str()));
+            if blocked {
+                self.tools.unregister(&name);
+            }
+        }
+        self
+    }
+
+    /// The session's scratch directory, the default for relative paths in
+    /// its turns (`None` when disabled or it cannot be created).
+    fn scratch_dir(&self, session_key: &str) -> Option<PathBuf> {
+        let scratch = self.scratch.as_ref()?;
+        scratch.maybe_cleanup();
+        match scratch.ensure(session_key) {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                warn!(error = %e, session_key = %session_key, "failed to create scratch directory");
+                None
+            }
+        }
+    }
+
+    /// Set the chat command prefix and permissions.
+    pub fn with_commands(mut self, config: CommandsConfig) -> Self {
+        self.commands.configure(config);
+        self
+    }
+
+    /// The built-in chat commands, answered directly without calling the
+    /// LLM.
+    fn chat_commands() -> CommandRouter<AgentLoop> {
+        let mut commands = CommandRouter::new(CommandsConfig::default());
+        commands.register("help", "", "Show available commands", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.canned_response(Canned::Help, call.msg)
+        });
+        commands.register("start", "", "Show the greeting", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.canned_response(Canned::Greeting, call.msg)
+        });
+        commands.register("reset", "", "Clear conversation history", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.sessions.clear(&call.msg.session_key());
+            agent.skill_secrets.forget(&call.msg.session_key());
+            if let Some(ref r) = agent.redactor {
+                r.forget(&call.msg.session_key());
+            }
+            "🔄 Conversation history cleared. Pinned notes are kept.".to_string()
+        });
+        commands.register("status", "", "Show session status", Permission::Anyone, |agent: &AgentLoop, call| {
+            let session_key = call.msg.session_key();
+            format!(
+                "📊 Status\n\
+                 Model: {}\n\
+                 Persona: {}\n\
+                 Session: {session_key} ({} messages)\n\
+                 Pinned notes: {}\n\
+                 Tools: {}",
+                agent.model(),
+                agent.session_persona(&session_key).map_or("none", |p| p.name),
+                agent.sessions.get_or_create(&session_key).messages.len(),
+                agent.sessions.pinned(&session_key).len(),
+                agent.tools.len(),
+            )
+        });
+        commands.register("model", "", "Show the current model", Permission::Anyone, |agent: &AgentLoop, _| {
+            agent.describe_models()
+        });
+        commands.register("persona", "[name]", "Show or switch the persona", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.persona_command(&call.msg.session_key(), call.args)
+        });
+        commands.register("pin", "<note>", "Keep a fact in context", Permission::Anyone, |agent: &AgentLoop, call| {
+            if call.args.is_empty() {
+                return format!("Usage: {}pin <note>", agent.commands.prefix());
+            }
+            let n = agent.sessions.pin(&call.msg.session_key(), call.args);
+            format!("📌 Pinned note #{n}: {}", call.args)
+        });
+        commands.register("pins", "", "List pinned notes", Permission::Anyone, |agent: &AgentLoop, call| {
+            format_pins(&agent.sessions.pinned(&call.msg.session_key()))
+        });
+        commands.register("unpin", "<n>", "Remove a pinned note", Permission::Anyone, |agent: &AgentLoop, call| {
+            let unpinned = call.args.parse::<usize>().ok().and_then(|i| agent.sessions.unpin(&call.msg.session_key(), i));
+            match unpinned {
+                Some(note) => format!("Unpinned: {note}"),
+                None => {
+                    let prefix = agent.commands.prefix();
+                    format!("Usage: {prefix}unpin <number> (see {prefix}pins for the list)")
+                }
+            }
+        });
+        commands.register("plan", "", "Show the current plan (plan cancel drops it)", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.plan_command(&call.msg.session_key(), call.args)
+        });
+        commands.register("form", "", "Show the form being filled in (form cancel drops it)", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.form_command(&call.msg.session_key(), call.args)
+        });
+        commands.register("files", "", "List attached documents (files clear removes them)", Permission::Anyone, |agent: &AgentLoop, call| {
+            agent.files_command(&call.msg.session_key(), call.args)
+        });
+        // Only reached when no turn is running (see `MessageBus::start_turn`)
+        commands.register("stop", "", "Stop the reply in progress", Permission::Anyone, |_: &AgentLoop, _| {
+            "Nothing to stop.".to_string()
+        });
+        commands
+    }
+
+    /// Run the event loop: poll inbound messages and process them.
+    ///
+    /// Messages are handled one at a time; the gateway dispatches through
+    /// [`AgentRouter`](crate::bindings::AgentRouter), which runs different
+    /// conversations concurrently.
+    ///
+    /// This runs indefinitely until the inbound channel is closed.
+    pub async fn run(&self) {
+        info!("agent loop started, waiting for messages");
+        loop {
+            match self.bus.consume_inbound().await {
+                Some(msg) => self.handle_inbound(&msg).await,
+                None => {
+                    info!("inbound channel closed, agent loop exiting");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Process one message taken off the bus and publish the reply.
+    ///
+    /// Errors are reported back to the originating chat.
+    pub async fn handle_inbound(&self, msg: &InboundMessage) {
+        let session_key = msg.session_key();
+        debug!(session_key = %session_key, "received message");
+
+        // Messages in observe-only chats are recorded, never answered
+        if msg.is_observed() {
+            return self.observe(msg, &session_key).await;
+        }
+
+        // Show the sender the message was picked up (each channel decides how)
+        if msg.channel != "system" {
+            if let Some(ack) = OutboundMessage::acknowledgment(msg) {
+                let _ = self.bus.publish_outbound(ack).await;
+            }
+        }
+
+        // Route system messages (from subagents) vs regular messages
+        let result = if msg.channel == "system" && msg.sender_id == "subagent" {
+            self.process_system_message(msg).await.map(TurnResult::new)
+        } else {
+            self.acknowledging_slow(msg, self.process_turn(msg)).await
+        };
+
+        match result {
+            Ok(turn) => {
+                let mut response = self.translate_reply(msg, &session_key, turn.reply).await.in_thread_of(msg);
+                if let Some(footer) = turn.footer {
+                    response.content = format!("{}\n\n— {footer}", response.content.trim_end());
+                }
+                // Replies to the user always go out, even when they repeat
+                // or arrive during quiet hours
+                if msg.channel != "system" {
+                    response = response.forced().as_reply();
+                }
+                if let Err(e) = self.bus.publish_outbound(response).await {
+                    error!(error = %e, "failed to publish outbound message");
+                }
+            }
+            Err(e) => {
+                // Full details go to the log only; the user gets a short message
+                let error_id = error::correlation_id();
+                error!(
+                    error = %format!("{e:#}"),
+                    error_id = %error_id,
+                    session_key = %session_key,
+                    "message processing error"
+                );
+                let err_msg = OutboundMessage::new(
+                    &msg.channel,
+                    &msg.chat_id,
+                    error::render(&e, self.show_error_ids.then_some(error_id.as_str())),
+                )
+                .in_thread_of(msg)
+                .forced()
+                .as_reply();
+                let _ = self.bus.publish_outbound(err_msg).await;
+            }
+        }
+    }
+
+    /// Process a single inbound message → outbound response.
+    ///
+    /// This is the core agent logic:
+    /// 1. Get/create session, load history
+    /// 2. Build context messages
+    /// 3. LLM ↔ tool loop
+    /// 4. Save session, return response
+    pub async fn process_message(&self, msg: &InboundMessage) -> Result<OutboundMessage> {
+        Ok(self.process_turn(msg).await?.reply)
+    }
+
+    /// Like [`process_message`](Self::process_message), but also reports
+    /// the tokens used and the tools called along the way, and the debug
+    /// footer when it is on for the channel.
+    pub async fn process_turn(&self, msg: &InboundMessage) -> Result<TurnResult> {
+        if !self.debug_footer.applies_to(&msg.channel) {
+            return self.answer(msg).await;
+        }
+        let started = Instant::now();
+        // Threads of one chat run at once; each session has its own meter
+        let key = msg.session_key();
+        self.meters.lock().unwrap().remove(&key);
+        let result = TurnContext::new(&msg.channel, &msg.chat_id, &key)
+            .scope(self.answer(msg))
+            .await;
+        let usage = self.meters.lock().unwrap().remove(&key).unwrap_or_default();
+        let mut turn = result?;
+        // Commands and canned replies cost nothing worth showing
+        if usage != TurnUsage::default() {
+            turn.footer = Some(usage.footer(&self.prices, started.elapsed()));
+        }
+        Ok(turn)
+    }
+
+    /// The turn behind [`process_turn`](Self::process_turn).
+    async fn answer(&self, msg: &InboundMessage) -> Result<TurnResult> {
+        let started = Instant::now();
+        let session_key = msg.session_key();
+
+        if let Some(reply) = self.commands.dispatch(self, msg) {
+            return Ok(TurnResult::new(OutboundMessage::new(&msg.channel, &msg.chat_id, reply)));
+        }
+        if let Some(kind) = Canned::requested_by(&msg.content, responses::is_direct(msg)) {
+            let reply = self.canned_response(kind, msg);
+            return Ok(TurnResult::new(OutboundMessage::new(&msg.channel, &msg.chat_id, reply)));
+        }
+        if self.responses.greets_new_chats(&msg.channel) && !self.sessions.exists(&session_key) {
+            let greeting = self.canned_response(Canned::Greeting, msg);
+            let greeting = OutboundMessage::new(&msg.channel, &msg.chat_id, greeting).in_thread_of(msg);
+            if let Err(e) = self.bus.publish_outbound(greeting).await {
+                warn!(error = %e, "failed to publish greeting");
+            }
+        }
+
+        // Another turn on this session (a cron job, a webhook) finishes first
+        let _session = self.sessions.lock(&session_key).await;
+        let translated = self.translate_inbound(msg, &session_key).await;
+        let msg = translated.as_ref().unwrap_or(msg);
+        self.start_requested_form(&session_key, msg);
+
+        if let Some(reply) = self.quick_reply(&session_key, msg).await {
+            info!(session = %session_key, "answered with a quick reply");
+            self.transcribe(&session_key, || TranscriptEvent::User {
+                timestamp: chrono::Utc::now(),
+                content: msg.content.clone(),
+            });
+            self.transcribe(&session_key, || TranscriptEvent::Reply {
+                timestamp: chrono::Utc::now(),
+                content: reply.clone(),
+            });
+            self.sessions
+                .add_message(&session_key, Message::user(&msg.content));
+            self.sessions
+                .add_message(&session_key, Message::assistant(&reply));
+            self.record_message(msg, started);
+            return Ok(TurnResult::new(OutboundMessage::new(&msg.channel, &msg.chat_id, reply)));
+        }
+
+        // Tools see this conversation, even while other sessions' turns run
+        let working_dir = self.scratch_dir(&session_key);
+        TurnContext::new(&msg.channel, &msg.chat_id, &session_key)
+            .with_sender(&msg.sender_id)
+            .with_working_dir(working_dir.clone())
+            .with_attachments(msg.media.clone())
+            .scope(self.run_turn(msg, &session_key, working_dir, started))
+            .await
+    }
+
+    /// The LLM ↔ tool loop of [`process_turn`](Self::process_turn).
+    async fn run_turn(
+        &self,
+        msg: &InboundMessage,
+        session_key: &str,
+        working_dir: Option<PathBuf>,
+        started: Instant,
+    ) -> Result<TurnResult> {
+        // Until dropped, "stop" from this session ends the turn early
+        let running = self.bus.start_turn(session_key);
+
+        // Get session history
+        let history = self.sessions.get_history(session_key, self.history_limit());
+        let mut history_len = history.len();
+
+        // Build LLM messages
+        let mut media_paths: Vec<String> = msg.media.iter().map(|m| m.path.clone()).collect();
+        let files = match self.uploads {
+            Some(ref uploads) => uploads.attach(session_key, &mut media_paths).await,
+            None => Vec::new(),
+        };
+        let mut messages = self.context.build_messages(
+            &history,
+            &msg.content,
+            &media_paths,
+            &msg.channel,
+            &msg.chat_id,
+        );
+        ContextBuilder::add_files(&mut messages, &files);
+        ContextBuilder::add_pinned_notes(&mut messages, &self.sessions.pinned(session_key));
+        if self.plan_mode {
+            let plan = Plan::load(&self.sessions, session_key).filter(Plan::is_active);
+            ContextBuilder::add_plan(&mut messages, plan.as_ref());
+        }
+        ContextBuilder::add_form(&mut messages, Form::load(&self.sessions, session_key).as_ref());
+        if let Some(persona) = self.session_persona(session_key) {
+            ContextBuilder::add_persona(&mut messages, persona);
+        }
+        if let Some(ref path) = self.contacts {
+            let book = ContactBook::load(Some(path.clone()));
+            if let Some(contact) = book.resolve_sender(&msg.channel, &msg.sender_id) {
+                debug!(contact = %contact.name, "sender resolved from contact book");
+                ContextBuilder::add_contact_info(&mut messages, contact);
+            }
+        }
+        if let Some(ref dir) = working_dir {
+            ContextBuilder::add_scratch_dir_info(&mut messages, dir);
+        }
+        let request = ContextRequest {
+            channel: msg.channel.clone(),
+            chat_id: msg.chat_id.clone(),
+            session_key: session_key.to_string(),
+            text: msg.content.clone(),
+        };
+        self.add_provided_sections(&mut messages, request).await;
+        self.transcribe(session_key, || TranscriptEvent::User {
+            timestamp: chrono::Utc::now(),
+            content: msg.content.clone(),
+        });
+
+        // Get tool definitions
+        let mut tool_defs = self.offered_tools(&mut messages);
+
+        // Agent loop: LLM ↔ tool calling
+        let mut final_content: Option<String> = None;
+        let mut phase = Phase::Planning;
+        let mut models_used: Vec<String> = Vec::new();
+        let mut previews: Vec<String> = Vec::new();
+        let mut bad_tool_calls = 0;
+        let mut failed_calls: HashMap<String, usize> = HashMap::new();
+        let mut fallback: Option<&ModelRoute> = None;
+        let mut turn = TurnResult::new(OutboundMessage::new(&msg.channel, &msg.chat_id, ""));
+
+        let mut interrupted = false;
+
+        'turn: for iteration in 0..self.max_iterations {
+            let route = match fallback {
+                Some(route) if phase == Phase::Planning => route,
+                _ => self.router.route(phase),
+            };
+            debug!(iteration = iteration, model = %route.model, phase = ?phase, "LLM call");
+            self.fit_context(&mut messages, &mut history_len, &route.model, &tool_defs);
+            if !models_used.contains(&route.model) {
+                models_used.push(route.model.clone());
+            }
+
+            self.bus.publish_agent_event(AgentEvent::LlmCallStarted {
+                session_key: session_key.to_string(),
+                model: route.model.clone(),
+                iteration,
+            });
+            let chat = self.chat(route, &messages, &tool_defs, &msg.channel, &msg.chat_id);
+            let Some(response) = running.until_cancelled(chat).await else {
+                break;
+            };
+            if response.is_error() {
+                return Err(ProviderError(response.content.unwrap_or_default()).into());
+            }
+            if let Some(ref usage) = response.usage {
+                turn.usage.add(usage);
+            }
+            self.transcribe(session_key, || TranscriptEvent::Assistant {
+                timestamp: chrono::Utc::now(),
+                model: route.model.clone(),
+                content: response.content.clone(),
+                tool_calls: response.tool_calls.clone(),
+            });
+
+            if let Some(answer) = self.answer_beside_unknown_tools(&response) {
+                final_content = Some(answer);
+                break;
+            }
+
+            if response.has_tool_calls() {
+                // Add assistant message with tool calls
+                let fresh_from = messages.len();
+                let tool_calls: Vec<ToolCall> = response.tool_calls.clone();
+                ContextBuilder::add_assistant_message(
+                    &mut messages,
+                    response.content.clone(),
+                    tool_calls.clone(),
+                );
+
+                // Execute each tool call
+                for tc in &tool_calls {
+                    if running.is_cancelled() {
+                        break 'turn;
+                    }
+                    info!(
+                        tool = %tc.function.name,
+                        iteration = iteration,
+                        "executing tool call"
+                    );
+                    turn.tool_calls.push(tc.clone());
+                    self.bus.publish_agent_event(AgentEvent::ToolStarted {
+                        session_key: session_key.to_string(),
+                        name: tc.function.name.clone(),
+                        args: serde_json::from_str(&tc.function.arguments)
+                            .unwrap_or_else(|_| Value::String(tc.function.arguments.clone())),
+                    });
+
+                    let tool_started = Instant::now();
+                    let tool = self.run_tool(tc, &msg.channel, &msg.chat_id);
+                    let Some(result) = running.until_cancelled(tool).await else {
+                        interrupted = true;
+                        break 'turn;
+                    };
+                    self.bus.publish_agent_event(AgentEvent::ToolFinished {
+                        session_key: session_key.to_string(),
+                        name: tc.function.name.clone(),
+                        duration_ms: tool_started.elapsed().as_millis() as u64,
+                        ok: result.is_ok(),
+                    });
+                    let failed = result.as_ref().map_or(true, |r| r.starts_with("Error"));
+                    let mut result = result.unwrap_or_else(|e| {
+                        bad_tool_calls += 1;
+                        e
+                    });
+                    if failed && self.max_repeated_failures > 0 {
+                        let failures = failed_calls.entry(call_signature(tc)).or_insert(0);
+                        *failures += 1;
+                        if *failures >= self.max_repeated_failures {
+                            warn!(tool = %tc.function.name, failures = *failures, "tool call keeps failing, ending the turn");
+                            final_content = Some(repeated_failure_reply(tc, *failures, &result));
+                            break 'turn;
+                        }
+                        if *failures > 1 {
+                            result.push_str(REPEATED_FAILURE_NOTE);
+                        }
+                    }
+
+                    debug!(
+                        tool = %tc.function.name,
+                        result_len = result.len(),
+                        "tool result"
+                    );
+
+                    if dry_run::is_preview(&result) {
+                        previews.push(result.clone());
+                    }
+                    self.transcribe(session_key, || TranscriptEvent::ToolResult {
+                        timestamp: chrono::Utc::now(),
+                        call_id: tc.id.clone(),
+                        tool: tc.function.name.clone(),
+                        result: result.clone(),
+                        duration_ms: tool_started.elapsed().as_millis() as u64,
+                    });
+                    ContextBuilder::add_tool_result(&mut messages, &tc.id, &result);
+                }
+                self.compact_tool_results(&mut messages, fresh_from, &msg.channel, &msg.chat_id)
+                    .await;
+                if self.max_bad_tool_calls > 0 && bad_tool_calls >= self.max_bad_tool_calls {
+                    bad_tool_calls = 0;
+                    match self.tool_fallback.as_ref().filter(|_| fallback.is_none()) {
+                        Some(route) => {
+                            warn!(model = %route.model, "too many malformed tool calls, switching model");
+                            fallback = Some(route);
+                        }
+                        None => {
+                            warn!("too many malformed tool calls, disabling tools for this turn");
+                            tool_defs.clear();
+                        }
+                    }
+                }
+                phase = Phase::Planning;
+            } else if phase == Phase::Planning && self.router.splits_phases() {
+                // Planner is done with tools → let the responder write the answer
+                phase = Phase::Responding;
+            } else {
+                // No tool calls → final answer
+                final_content = response.content;
+                break;
+            }
+        }
+
+        let content = match final_content {
+            Some(content) => content,
+            None if running.is_cancelled() => {
+                info!(session = %session_key, tool_calls = turn.tool_calls.len(), "turn stopped by user");
+                stopped_summary(&turn.tool_calls, interrupted)
+            }
+            // Exhausted iterations without a final answer
+            None => "I've completed processing but have no response to give.".into(),
+        };
+        drop(running);
+
+        // Save conversation to session
+        self.sessions
+            .add_message(session_key, Message::user(&msg.content));
+        self.sessions
+            .add_message(session_key, Message::assistant(&content));
+        self.auto_tag(session_key, &msg.channel, &models_used, &msg.content);
+        self.record_message(msg, started);
+
+        // Show the user exactly what was skipped, not just the LLM's account of it
+        turn.reply.content = if previews.is_empty() {
+            content
+        } else {
+            let previews: Vec<String> = previews
+                .iter()
+                .map(|p| format!("```\n{}\n```", p.trim_start_matches(dry_run::PREVIEW_PREFIX).trim()))
+                .collect();
+            format!("{content}\n\n🧪 Dry run — nothing was changed:\n\n{}", previews.join("\n\n"))
+        };
+        self.transcribe(session_key, || TranscriptEvent::Reply {
+            timestamp: chrono::Utc::now(),
+            content: turn.reply.content.clone(),
+        });
+        self.bus.publish_agent_event(AgentEvent::FinalResponse {
+            session_key: session_key.to_string(),
+            content: turn.reply.content.clone(),
+        });
+        Ok(turn)
+    }
+
+    /// Append a step to the session's transcript, if transcripts are on.
+    fn transcribe(&self, session_key: &str, event: impl FnOnce() -> TranscriptEvent) {
+        if let Some(ref transcripts) = self.transcripts {
+            transcripts.record(session_key, &event());
+        }
+    }
+
+    /// Record a message from an observe-only chat in its session and in the
+    /// observed-chats log, without running a turn.
+    async fn observe(&self, msg: &InboundMessage, session_key: &str) {
+        let sender = ["sender_name", "first_name", "username"]
+            .iter()
+            .find_map(|key| msg.metadata.get(*key).filter(|name| !name.is_empty()))
+            .unwrap_or(&msg.sender_id);
+        let line = format!("{sender}: {}", msg.content);
+
+        let _session = self.sessions.lock(session_key).await;
+        self.sessions.add_message(session_key, Message::user(&line));
+        if let Err(e) = self.context.memory().append_observed(session_key, &line) {
+            warn!(error = %e, session_key = %session_key, "failed to log observed message");
+        }
+        debug!(session_key = %session_key, "observed message recorded");
+    }
+
+    /// Record a handled user message in the usage log.
+    fn record_message(&self, msg: &InboundMessage, started: Instant) {
+        if let Some(ref usage) = self.usage {
+            usage.record(&UsageEvent::Message {
+                timestamp: chrono::Utc::now(),
+                channel: msg.channel.clone(),
+                chat_id: msg.chat_id.clone(),
+                latency_ms: started.elapsed().as_millis() as u64,
+            });
+        }
+    }
+
+    /// Run `turn`; when it is still going after the ack-first threshold of
+    /// the channel, send a short acknowledgment and let it finish.
+    async fn acknowledging_slow<T>(
+        &self,
+        msg: &InboundMessage,
+        turn: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let Some(delay) = self.ack_first.as_ref().and_then(|c| ack_first::delay_for(c, &msg.channel)) else {
+            return turn.await;
+        };
+        tokio::pin!(turn);
+        // The turn keeps running while the acknowledgment is written
+        let ack = async {
+            tokio::time::sleep(delay).await;
+            self.ack_text(msg).await
+        };
+        tokio::select! {
+            result = &mut turn => return result,
+            text = ack => {
+                let ack = OutboundMessage::new(&msg.channel, &msg.chat_id, text).in_thread_of(msg).forced().as_reply();
+                if let Err(e) = self.bus.publish_outbound(ack).await {
+                    debug!(error = %e, "failed to publish ack-first message");
+                }
+            }
+        }
+        turn.await
+    }
+
+    /// The acknowledgment for `msg`: written by the ack model, else the template.
+    async fn ack_text(&self, msg: &InboundMessage) -> String {
+        let template = self.ack_first.as_ref().map(|c| c.template.clone()).unwrap_or_default();
+        let Some(route) = self.ack_route.as_ref() else {
+            return template;
+        };
+        let response = self
+            .chat(route, &ack_first::ack_messages(&msg.content), &[], &msg.channel, &msg.chat_id)
+            .await;
+        response.content.as_deref().and_then(ack_first::parse_ack).unwrap_or(template)
+    }
+
+    /// The message translated for the operator, when its channel translates
+    /// and it isn't in an operator language. Records the sender's language
+    /// on the session.
+    async fn translate_inbound(&self, msg: &InboundMessage, session_key: &str) -> Option<InboundMessage> {
+        let config = self.translation.as_ref()?;
+        if !config.channels.get(&msg.channel).is_some_and(|c| c.inbound) || msg.content.trim().is_empty() {
+            return None;
+        }
+        let operator = &config.operator_languages;
+        let detected = match detect_language(&msg.content) {
+            // Clearly the operator's language: no call needed
+            Some(language) if translation::is_operator_language(language, operator) => translation::Detected {
+                language: language.to_string(),
+                translation: None,
+            },
+            _ => {
+                let route = self
+                    .translation_route
+                    .as_ref()
+                    .unwrap_or_else(|| self.router.route(Phase::Planning));
+                let messages = translation::detection_messages(&msg.content, operator);
+                let response = self.chat(route, &messages, &[], &msg.channel, &msg.chat_id).await;
+                let detected = response
+                    .content
+                    .as_deref()
+                    .and_then(|output| translation::parse_detection(output, operator));
+                let Some(detected) = detected else {
+                    warn!(session = %session_key, model = %route.model, "language detection failed, message left as is");
+                    return None;
+                };
+                detected
+            }
+        };
+        if self.sessions.metadata(session_key, translation::LANGUAGE_KEY).as_deref() != Some(detected.language.as_str()) {
+            self.sessions.set_metadata(session_key, translation::LANGUAGE_KEY, Some(&detected.language));
+        }
+        let text = detected.translation?;
+        debug!(session = %session_key, language = %detected.language, "translated inbound message");
+        let mut translated = msg.clone();
+        translated.content = translation::with_original(&text, &detected.language, &msg.content);
+        Some(translated)
+    }
+
+    /// `reply` translated into the sender's language, when their channel
+    /// translates replies and they don't write in an operator language.
+    async fn translate_reply(&self, msg: &InboundMessage, session_key: &str, mut reply: OutboundMessage) -> OutboundMessage {
+        let Some(ref config) = self.translation else {
+            return reply;
+        };
+        if !config.channels.get(&msg.channel).is_some_and(|c| c.replies) || reply.content.trim().is_empty() {
+            return reply;
+        }
+        let Some(language) = self.sessions.metadata(session_key, translation::LANGUAGE_KEY) else {
+            return reply;
+        };
+        if translation::is_operator_language(&language, &config.operator_languages) {
+            return reply;
+        }
+        let route = self
+            .translation_route
+            .as_ref()
+            .unwrap_or_else(|| self.router.route(Phase::Planning));
+        let messages = translation::reply_messages(&reply.content, &language);
+        let response = self.chat(route, &messages, &[], &msg.channel, &msg.chat_id).await;
+        let text = (!response.is_error())
+            .then_some(response.content)
+            .flatten()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        match text {
+            Some(text) => {
+                debug!(session = %session_key, language = %language, "translated reply");
+                reply.content = text;
+            }
+            None => warn!(session = %session_key, language = %language, "reply translation failed, sent untranslated"),
+        }
+        reply
+    }
+
+    /// Reply to small talk directly: a canned phrase, or one call on the
+    /// cheap model. `None` means the message needs the full agent.
+    async fn quick_reply(&self, session_key: &str, msg: &InboundMessage) -> Option<String> {
+        let config = self.quick_replies.as_ref()?;
+        if !msg.media.is_empty() || !quick_reply::is_candidate(&msg.content, config) {
+            return None;
+        }
+        // "yes" or "3" may well answer a form question
+        if Form::load(&self.sessions, session_key).is_some() {
+            return None;
+        }
+
+        let last_assistant = self
+            .sessions
+            .get_history(session_key, 2)
+            .into_iter()
+            .rev()
+            .find_map(|m| match m {
+                Message::Assistant { content, .. } => content,
+                _ => None,
+            });
+        if let Some(reply) = quick_reply::canned_reply(&msg.content, last_assistant.as_deref()) {
+            return Some(reply.to_string());
+        }
+
+        let route = self
+            .quick_route
+            .as_ref()
+            .unwrap_or_else(|| self.router.route(Phase::Planning));
+        let messages = quick_reply::classification_messages(&msg.content, last_assistant.as_deref());
+        let response = self
+            .chat(route, &messages, &[], &msg.channel, &msg.chat_id)
+            .await;
+        let (intent, reply) =
+            quick_reply::parse_classification(response.content.as_deref()?, config.min_confidence)?;
+        debug!(intent = ?intent, model = %route.model, "message classified as small talk");
+        Some(reply)
+    }
+
+    /// Process a system message (from a subagent or cron).
+    ///
+    /// Parses the original `channel:chat_id` from `msg.chat_id`,
+    /// loads the original session, runs a full LLM call to summarize
+    /// the result, and routes the response back to the correct channel.
+    async fn process_system_message(&self, msg: &InboundMessage) -> Result<OutboundMessage> {
+        info!(
+            sender = %msg.sender_id,
+            chat_id = %msg.chat_id,
+            "processing system message"
+        );
+
+        // Parse origin from chat_id format "channel:chat_id"
+        let (origin_channel, origin_chat_id) = match msg.chat_id.split_once(':') {
+            Some((ch, cid)) => (ch.to_string(), cid.to_string()),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Invalid system message chat_id format: {}",
+                    msg.chat_id
+                ));
+            }
+        };
+
+        let session_key = format!("{origin_channel}:{origin_chat_id}");
+        // A subagent result waits for the turn in progress on its chat
+        let _session = self.sessions.lock(&session_key).await;
+
+        // Tools act on the original channel/chat
+        let working_dir = self.scratch_dir(&session_key);
+        TurnContext::new(&origin_channel, &origin_chat_id, &session_key)
+            .with_working_dir(working_dir.clone())
+            .scope(self.run_system_turn(msg, &origin_channel, &origin_chat_id, working_dir))
+            .await
+    }
+
+    /// The LLM ↔ tool loop of [`process_system_message`](Self::process_system_message).
+    async fn run_system_turn(
+        &self,
+        msg: &InboundMessage,
+        origin_channel: &str,
+        origin_chat_id: &str,
+        working_dir: Option<PathBuf>,
+    ) -> Result<OutboundMessage> {
+        let session_key = format!("{origin_channel}:{origin_chat_id}");
+
+        // Load the original session
+        let history = self.sessions.get_history(&session_key, self.history_limit());
+        let mut history_len = history.len();
+
+        // Build messages with the subagent result as the "user" message
+        let mut messages =
+            self.context
+                .build_messages(&history, &msg.content, &[], origin_channel, origin_chat_id);
+        ContextBuilder::add_pinned_notes(&mut messages, &self.sessions.pinned(&session_key));
+        if self.plan_mode {
+            let plan = Plan::load(&self.sessions, &session_key).filter(Plan::is_active);
+            ContextBuilder::add_plan(&mut messages, plan.as_ref());
+        }
+        ContextBuilder::add_form(&mut messages, Form::load(&self.sessions, &session_key).as_ref());
+        if let Some(persona) = self.session_persona(&session_key) {
+            ContextBuilder::add_persona(&mut messages, persona);
+        }
+        if let Some(ref dir) = working_dir {
+            ContextBuilder::add_scratch_dir_info(&mut messages, dir);
+        }
+        let request = ContextRequest {
+            channel: origin_channel.to_string(),
+            chat_id: origin_chat_id.to_string(),
+            session_key: session_key.clone(),
+            text: msg.content.clone(),
+        };
+        self.add_provided_sections(&mut messages, request).await;
+
+        let tool_defs = self.offered_tools(&mut messages);
+        let mut final_content: Option<String> = None;
+
+        for iteration in 0..self.max_iterations {
+            debug!(iteration = iteration, "system message LLM call");
+
+            // Summarizing a subagent result is a planning-phase task
+            let route = self.router.route(Phase::Planning);
+            self.fit_context(&mut messages, &mut history_len, &route.model, &tool_defs);
+            let response = self
+                .chat(route, &messages, &tool_defs, origin_channel, origin_chat_id)
+                .await;
+            if response.is_error() {
+                return Err(ProviderError(response.content.unwrap_or_default()).into());
+            }
+
+            if response.has_tool_calls() {
+                let fresh_from = messages.len();
+                let tool_calls: Vec<ToolCall> = response.tool_calls.clone();
+                ContextBuilder::add_assistant_message(
+                    &mut messages,
+                    response.content.clone(),
+                    tool_calls.clone(),
+                );
+
+                for tc in &tool_calls {
+                    let result = self
+                        .run_tool(tc, origin_channel, origin_chat_id)
+                        .await
+                        .unwrap_or_else(|e| e);
+                    ContextBuilder::add_tool_result(&mut messages, &tc.id, &result);
+                }
+                self.compact_tool_results(&mut messages, fresh_from, origin_channel, origin_chat_id)
+                    .await;
+            } else {
+                final_content = response.content;
+                break;
+            }
+        }
+
+        let content = final_content
+            .unwrap_or_else(|| "I've completed processing but have no response to give.".into());
+
+        // Save to the original session
+        self.sessions
+            .add_message(&session_key, Message::user(&msg.content));
+        self.sessions
+            .add_message(&session_key, Message::assistant(&content));
+
+        // Route response to the original channel/chat
+        Ok(OutboundMessage::new(origin_channel, origin_chat_id, &content))
+    }
+
+    /// Direct processing mode (CLI entry point).
+    ///
+    /// Wraps text into an `InboundMessage` on the "cli" channel and processes.
+    pub async fn process_direct(&self, text: &str) -> Result<String> {
+        let msg = InboundMessage::new("cli", "user", "direct", text);
+        let response = self.process_message(&msg).await?;
+        Ok(response.content)
+    }
+
+    /// Get a reference to the tool registry (for testing/extension).
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
+
+    /// The message bus this agent reads from; subscribe to
+    /// [`Topic::Agent`](oxibot_core::bus::Topic::Agent) to follow its turns.
+    pub fn bus(&self) -> &Arc<MessageBus> {
+        &self.bus
+    }
+
+    /// Get the model name.
+    pub fn model(&self) -> &str {
+        &self.router.default_route().model
+    }
+
+    /// Add the automatic `channel:*`, `model:*` and `lang:*` tags to a session.
+    fn auto_tag(&self, session_key: &str, channel: &str, models: &[String], text: &str) {
+        let mut tags = vec![format!("channel:{channel}")];
+        tags.extend(models.iter().map(|m| format!("model:{m}")));
+        if let Some(lang) = detect_language(text) {
+            tags.push(format!("lang:{lang}"));
+        }
+        self.sessions.add_tags(session_key, &tags);
+    }
+
+    /// Reply for the `/model` command.
+    fn describe_models(&self) -> String {
+        let mut reply = format!("🤖 Model: {}", self.model());
+        if self.router.splits_phases() {
+            reply.push_str(&format!(
+                "\nPlanner: {}\nResponder: {}",
+                self.router.route(Phase::Planning).model,
+                self.router.route(Phase::Responding).model
+            ));
+        }
+        reply
+    }
+}
+
+/// Key of the conversation the running turn belongs to: its session (one
+/// per thread), or the chat outside a turn.
+fn conversation_key(channel: &str, chat_id: &str) -> String {
+    TurnContext::current().map_or_else(|| format!("{channel}:{chat_id}"), |turn| turn.session_key)
+}
+
+/// Identifies a tool call by tool and arguments, ignoring JSON formatting.
+fn call_signature(tc: &ToolCall) -> String {
+    let args = serde_json::from_str::<Value>(&tc.function.arguments)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|_| tc.function.arguments.trim().to_string());
+    format!("{}\n{args}", tc.function.name)
+}
+
+/// Reply for a turn ended because a tool call kept failing.
+fn repeated_failure_reply(tc: &ToolCall, failures: usize, error: &str) -> String {
+    let error = error.lines().next().unwrap_or_default();
+    format!(
+        "⚠️ I had to stop: `{}` failed {failures} times in a row with the same arguments.\n\
+         Last error: {error}",
+        tc.function.name
+    )
+}
+
+/// Reply for a turn the user stopped: the tool calls that finished and
+/// the one that was cut short, if any (the last of `tool_calls`).
+fn stopped_summary(tool_calls: &[ToolCall], interrupted: bool) -> String {
+    let (done, cut) = match tool_calls.split_last() {
+        Some((last, done)) if interrupted => (done, Some(last)),
+        _ => (tool_calls, None),
+    };
+    let describe = |tc: &ToolCall| {
+        let args: String = tc.function.arguments.chars().take(STOPPED_ARGS_CHARS).collect();
+        let ellipsis = if tc.function.arguments.chars().count() > STOPPED_ARGS_CHARS { "…" } else { "" };
+        format!("`{}` {args}{ellipsis}", tc.function.name)
+    };
+
+    let mut reply = String::from("⏹️ Stopped.");
+    if done.is_empty() {
+        reply.push_str(" No tools had run yet.");
+    } else {
+        reply.push_str(" Done so far:");
+        for tc in done {
+            reply.push_str(&format!("\n- {}", describe(tc)));
+        }
+    }
+    if let Some(tc) = cut {
+        reply.push_str(&format!("\nInterrupted: {}", describe(tc)));
+    }
+    reply
+}
+
+// ─────────────────────────────────────────────
+// Test support
+// ─────────────────────────────────────────────
+
+/// Builds agent loops for tests, so each test names only the arguments it
+/// cares about.
+#[cfg(test)]
+pub(crate) struct TestLoop {
+    bus: Arc<MessageBus>,
+    provider: Arc<dyn LlmProvider>,
+    workspace: PathBuf,
+    model: Option<String>,
+    max_iterations: usize,
+    restrict_to_workspace: bool,
+    sessions: Option<SessionManager>,
+}
+
+#[cfg(test)]
+impl TestLoop {
+    pub(crate) fn new(provider: Arc<dyn LlmProvider>, workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            bus: Arc::new(MessageBus::new(32)),
+            provider,
+            workspace: workspace.into(),
+            model: None,
+            max_iterations: 5,
+            restrict_to_workspace: false,
+            sessions: None,
+        }
+    }
+
+    pub(crate) fn bus(mut self, bus: Arc<MessageBus>) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    pub(crate) fn model(mut self, model: &str) -> Self {
+        self.model = Some(model.to_string());
+        self
+    }
+
+    pub(crate) fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub(crate) fn restrict_to_workspace(mut self) -> Self {
+        self.restrict_to_workspace = true;
+        self
+    }
+
+    pub(crate) fn sessions(mut self, sessions: SessionManager) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    pub(crate) fn build(self) -> AgentLoop {
+        AgentLoop::new(
+            self.bus,
+            self.provider,
+            self.workspace,
+            self.model,
+            Some(self.max_iterations),
+            None,
+            None,
+            None,
+            self.restrict_to_workspace,
+            self.sessions,
+            None,
+        )
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use oxibot_core::bus::events::{BusEvent, Topic};
+    use oxibot_core::types::{LlmResponse, MessageContent, ToolDefinition};
+
+    /// A mock LLM provider that returns canned responses.
+    struct MockProvider {
+        /// Responses to return in sequence.
+        responses: std::sync::Mutex<Vec<LlmResponse>>,
+        /// Messages of every call, in order.
+        requests: std::sync::Mutex<Vec<Vec<Message>>>,
+        /// Number of tools offered in every call, in order.
+        tools_offered: std::sync::Mutex<Vec<usize>>,
+    }
+
+    impl MockProvider {
         fn new(responses: Vec<LlmResponse>) -> Self {
             Self {
                 responses: std::sync::Mutex::new(responses),
+                requests: std::sync::Mutex::new(Vec::new()),
+                tools_offered: std::sync::Mutex::new(Vec::new()),
             }
         }
 
+        /// System prompts of every call, in order.
+        fn system_prompts(&self) -> Vec<String> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|msgs| match msgs.first() {
+                    Some(Message::System { content }) => Some(content.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+
         fn simple(text: &str) -> Self {
             Self::new(vec![LlmResponse {
                 content: Some(text.into()),
@@ -451,11 +2310,13 @@ mod tests {
     impl LlmProvider for MockProvider {
         async fn chat(
             &self,
-            _messages: &[Message],
-            _tools: Option<&[ToolDefinition]>,
+            messages: &[Message],
+            tools: Option<&[ToolDefinition]>,
             _model: &str,
             _config: &LlmRequestConfig,
         ) -> LlmResponse {
+            self.requests.lock().unwrap().push(messages.to_vec());
+            self.tools_offered.lock().unwrap().push(tools.map_or(0, |t| t.len()));
             let mut responses = self.responses.lock().unwrap();
             if responses.is_empty() {
                 LlmResponse {
@@ -505,6 +2366,101 @@ mod tests {
         assert_eq!(result, "Hello from Oxibot!");
     }
 
+    #[tokio::test]
+    async fn test_provider_errors_are_sanitized() {
+        let error = "Error calling LLM: 401 Unauthorized — {\"error\": \"invalid x-api-key sk-ant-123\"}";
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse::error(error),
+            LlmResponse::error(error),
+        ]));
+        let bus = Arc::new(MessageBus::new(32));
+        let workspace = std::env::temp_dir().join("oxibot_test_agent");
+        let agent = TestLoop::new(provider, workspace).bus(bus.clone()).build();
+
+        // The raw provider error is not returned as the answer
+        let err = agent.process_direct("Hi").await.unwrap_err();
+        assert_eq!(error::ErrorKind::of(&err), error::ErrorKind::Provider);
+
+        let agent = agent.with_error_ids(true);
+        agent
+            .handle_inbound(&InboundMessage::new("telegram", "u1", "c1", "Hi"))
+            .await;
+        let reply = bus.consume_outbound().await.unwrap();
+        assert!(!reply.content.contains("sk-ant"));
+        assert!(reply.content.starts_with(error::ErrorKind::Provider.user_message()));
+        assert!(reply.content.contains("(error ID: "));
+    }
+
+    #[tokio::test]
+    async fn test_handle_inbound_acknowledges_first() {
+        let bus = Arc::new(MessageBus::new(32));
+        let workspace = std::env::temp_dir().join("oxibot_test_agent");
+        let agent = TestLoop::new(Arc::new(MockProvider::simple("Hello!")), workspace)
+            .bus(bus.clone())
+            .build();
+
+        let mut msg = InboundMessage::new("telegram", "u1", "ack-test", "Hi");
+        msg.metadata.insert("message_id".into(), "77".into());
+        agent.handle_inbound(&msg).await;
+
+        let ack = bus.consume_outbound().await.unwrap();
+        assert!(ack.is_acknowledgment());
+        assert_eq!(ack.message_id(), Some("77"));
+        let reply = bus.consume_outbound().await.unwrap();
+        assert_eq!(reply.content, "Hello!");
+    }
+
+    #[tokio::test]
+    async fn test_observed_messages_are_recorded_not_answered() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let provider = Arc::new(MockProvider::simple("On Friday."));
+        let agent = TestLoop::new(provider.clone(), dir.path())
+            .bus(bus.clone())
+            .sessions(sessions)
+            .build();
+
+        let mut msg = InboundMessage::new("slack", "U1", "C1", "we ship friday").observed();
+        msg.metadata.insert("username".into(), "ann".into());
+        agent.handle_inbound(&msg).await;
+        let history = agent.sessions.get_history("slack:C1", 50);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0], Message::user("ann: we ship friday"));
+        let log = std::fs::read_to_string(agent.context.memory().observed_file()).unwrap();
+        assert!(log.contains("[slack:C1] ann: we ship friday"));
+
+        // The next addressed message is answered with the observed one in context
+        agent
+            .handle_inbound(&InboundMessage::new("slack", "U2", "C1", "when do we ship?"))
+            .await;
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "On Friday.");
+        let request = provider.requests.lock().unwrap()[0].clone();
+        assert!(request.contains(&Message::user("ann: we ship friday")));
+    }
+
+    #[tokio::test]
+    async fn test_thread_gets_own_session() {
+        use oxibot_core::bus::types::THREAD_ID_KEY;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let agent = TestLoop::new(Arc::new(MockProvider::simple("Hello!")), dir.path())
+            .bus(bus.clone())
+            .sessions(sessions)
+            .build();
+
+        let mut msg = InboundMessage::new("slack", "U1", "thread-test", "Hi");
+        msg.metadata.insert(THREAD_ID_KEY.into(), "1700.01".into());
+        agent.handle_inbound(&msg).await;
+
+        let reply = bus.consume_outbound().await.unwrap();
+        assert_eq!(reply.metadata.get(THREAD_ID_KEY).unwrap(), "1700.01");
+        assert_eq!(agent.sessions.get_history("slack:thread-test:1700.01", 50).len(), 2);
+        assert!(agent.sessions.get_history("slack:thread-test", 50).is_empty());
+    }
+
     #[tokio::test]
     async fn test_agent_tool_calling() {
         // First response: LLM requests read_file tool call
@@ -515,41 +2471,281 @@ mod tests {
 
         let tool_call = ToolCall::new(
             "call_1",
-            "read_file",
-            serde_json::json!({"path": test_file.to_str().unwrap()}).to_string(),
+            "read_file",
+            serde_json::json!({"path": test_file.to_str().unwrap()}).to_string(),
+        );
+
+        let responses = vec![
+            LlmResponse {
+                content: None,
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("The file contains: file content here".into()),
+                ..Default::default()
+            },
+        ];
+
+        let provider = Arc::new(MockProvider::new(responses));
+        let bus = Arc::new(MessageBus::new(32));
+
+        let agent = AgentLoop::new(
+            bus,
+            provider,
+            dir.path().to_path_buf(),
+            None,
+            Some(10),
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+        );
+        let mut events = agent.bus().subscribe(&[Topic::Agent]);
+
+        let result = agent.process_direct("Read test.txt").await.unwrap();
+        assert_eq!(result, "The file contains: file content here");
+
+        // Progress events: LLM call, tool start/finish, LLM call, reply
+        let mut kinds = Vec::new();
+        for _ in 0..5 {
+            let event = events.recv().await.unwrap();
+            let BusEvent::Agent(ref event) = *event else { panic!("not an agent event") };
+            assert_eq!(event.session_key(), "cli:direct");
+            match event {
+                AgentEvent::ToolStarted { name, args, .. } => {
+                    assert_eq!(name, "read_file");
+                    assert!(args["path"].as_str().unwrap().ends_with("test.txt"));
+                }
+                AgentEvent::ToolFinished { ok, .. } => assert!(ok),
+                AgentEvent::FinalResponse { content, .. } => assert_eq!(content, &result),
+                AgentEvent::LlmCallStarted { .. } => {}
+            }
+            kinds.push(serde_json::to_value(event).unwrap()["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(
+            kinds,
+            ["llm_call_started", "tool_started", "tool_finished", "llm_call_started", "final_response"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ack_first_before_slow_reply() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                tool_calls: vec![ToolCall::new(
+                    "call_1",
+                    "exec",
+                    serde_json::json!({"command": "sleep 0.3"}).to_string(),
+                )],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("Done.".into()),
+                ..Default::default()
+            },
+        ]));
+        let bus = Arc::new(MessageBus::new(32));
+        let config = AckFirstConfig {
+            enabled: true,
+            after_ms: 50,
+            ..Default::default()
+        };
+        let agent = TestLoop::new(provider, dir.path())
+            .bus(bus.clone())
+            .build()
+            .with_ack_first(Some(config), None);
+
+        agent.handle_inbound(&InboundMessage::new("telegram", "u1", "c1", "Check the logs")).await;
+        let ack = bus.consume_outbound().await.unwrap();
+        assert_eq!(ack.content, "Working on it…");
+        assert!(ack.is_forced());
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "Done.");
+
+        // A fast reply comes alone
+        agent.handle_inbound(&InboundMessage::new("telegram", "u1", "c1", "Thanks")).await;
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "(no more responses)");
+        let more = tokio::time::timeout(Duration::from_millis(100), bus.consume_outbound()).await;
+        assert!(more.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stop_interrupts_running_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let calls = vec![
+            ToolCall::new("call_1", "read_file", serde_json::json!({"path": "a.txt"}).to_string()),
+            ToolCall::new("call_2", "exec", serde_json::json!({"command": "sleep 30"}).to_string()),
+        ];
+        let provider = Arc::new(MockProvider::new(vec![LlmResponse {
+            tool_calls: calls,
+            ..Default::default()
+        }]));
+        let bus = Arc::new(MessageBus::new(32));
+        let agent = Arc::new(
+            TestLoop::new(provider, dir.path())
+                .bus(bus.clone())
+                .max_iterations(10)
+                .build(),
+        );
+
+        let turn = {
+            let agent = agent.clone();
+            tokio::spawn(async move { agent.process_direct("Do things").await })
+        };
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        bus.publish_inbound(InboundMessage::new("cli", "user", "direct", "stop"))
+            .await
+            .unwrap();
+
+        let reply = tokio::time::timeout(Duration::from_secs(5), turn)
+            .await
+            .expect("turn did not stop")
+            .unwrap()
+            .unwrap();
+        assert!(reply.starts_with("⏹️ Stopped. Done so far:\n- `read_file`"));
+        assert!(reply.contains("Interrupted: `exec` {\"command\":\"sleep 30\"}"));
+
+        // Once the turn is over, "stop" is handled like any other message
+        assert_eq!(agent.process_direct("/stop").await.unwrap(), "Nothing to stop.");
+    }
+
+    #[tokio::test]
+    async fn test_process_turn_reports_usage_and_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let usage = |prompt, completion| {
+            Some(UsageInfo {
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+                total_tokens: prompt + completion,
+            })
+        };
+        let responses = vec![
+            LlmResponse {
+                tool_calls: vec![ToolCall::new("call_1", "list_dir", r#"{"path": "."}"#)],
+                usage: usage(100, 10),
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("Empty.".into()),
+                usage: usage(150, 5),
+                ..Default::default()
+            },
+        ];
+        let agent = TestLoop::new(Arc::new(MockProvider::new(responses)), dir.path())
+            .max_iterations(10)
+            .sessions(SessionManager::new(Some(dir.path().join("store"))).unwrap())
+            .build();
+
+        let msg = InboundMessage::new("cli", "user", "turn-test", "What's here?");
+        let turn = agent.process_turn(&msg).await.unwrap();
+        assert_eq!(turn.reply.content, "Empty.");
+        assert_eq!(turn.usage, usage(250, 15).unwrap());
+        let names: Vec<&str> = turn.tool_calls.iter().map(|tc| tc.function.name.as_str()).collect();
+        assert_eq!(names, ["list_dir"]);
+    }
+
+    #[tokio::test]
+    async fn test_seen_tool_results_are_compacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = dir.path().join("big.txt");
+        std::fs::write(&big, "a".repeat(10_000)).unwrap();
+        let read = |id: &str, path: &std::path::Path| LlmResponse {
+            tool_calls: vec![ToolCall::new(
+                id,
+                "read_file",
+                serde_json::json!({"path": path.to_str().unwrap()}).to_string(),
+            )],
+            ..Default::default()
+        };
+        let provider = Arc::new(MockProvider::new(vec![
+            read("call_1", &big),
+            read("call_2", &big),
+            LlmResponse {
+                content: Some("done".into()),
+                ..Default::default()
+            },
+        ]));
+        let agent = create_test_loop(provider.clone());
+
+        assert_eq!(agent.process_direct("read it twice").await.unwrap(), "done");
+
+        let tool_results = |call: usize| -> Vec<usize> {
+            provider.requests.lock().unwrap()[call]
+                .iter()
+                .filter_map(|m| match m {
+                    Message::Tool { content, .. } => Some(content.chars().count()),
+                    _ => None,
+                })
+                .collect()
+        };
+        // The newest result is always sent in full; older ones are cut down
+        assert_eq!(tool_results(1), vec![10_000]);
+        let third = tool_results(2);
+        assert!(third[0] <= 4000);
+        assert_eq!(third[1], 10_000);
+
+        // With `summarize`, the planner model writes the replacement
+        let provider = Arc::new(MockProvider::new(vec![
+            read("call_1", &big),
+            read("call_2", &big),
+            LlmResponse {
+                content: Some("10k of the letter a".into()),
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("done".into()),
+                ..Default::default()
+            },
+        ]));
+        let agent = create_test_loop(provider.clone()).with_tool_result_compaction(Some(
+            ToolResultsConfig {
+                summarize: true,
+                ..Default::default()
+            },
+        ));
+        assert_eq!(agent.process_direct("read it twice").await.unwrap(), "done");
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[3].contains(&Message::tool_result(
+            "call_1",
+            "[compacted tool output: summary of 10000 characters]\n10k of the letter a"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_appends_previews() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("out.txt");
+        let tool_call = ToolCall::new(
+            "call_1",
+            "write_file",
+            serde_json::json!({"path": target.to_str().unwrap(), "content": "hello"}).to_string(),
         );
-
-        let responses = vec![
+        let provider = Arc::new(MockProvider::new(vec![
             LlmResponse {
                 content: None,
                 tool_calls: vec![tool_call],
                 ..Default::default()
             },
             LlmResponse {
-                content: Some("The file contains: file content here".into()),
+                content: Some("I would write out.txt.".into()),
                 ..Default::default()
             },
-        ];
-
-        let provider = Arc::new(MockProvider::new(responses));
-        let bus = Arc::new(MessageBus::new(32));
-
-        let agent = AgentLoop::new(
-            bus,
-            provider,
-            dir.path().to_path_buf(),
-            None,
-            Some(10),
-            None,
-            None,
-            None,
-            false,
-            None,
-            None,
-        );
-
-        let result = agent.process_direct("Read test.txt").await.unwrap();
-        assert_eq!(result, "The file contains: file content here");
+        ]));
+        let agent = TestLoop::new(provider, dir.path())
+            .max_iterations(10)
+            .build()
+            .with_dry_run(true);
+
+        let result = agent.process_direct("Write hello to out.txt").await.unwrap();
+        assert!(result.starts_with("I would write out.txt.\n\n🧪 Dry run — nothing was changed:"));
+        assert!(result.contains("```\nWould create "));
+        assert!(result.ends_with("nothing was written.\nhello\n```"));
+        assert!(!target.exists());
     }
 
     #[tokio::test]
@@ -586,7 +2782,827 @@ mod tests {
         assert!(names.contains(&"web_fetch".into()));
         assert!(names.contains(&"message".into()));
         assert!(names.contains(&"spawn".into()));
-        assert_eq!(names.len(), 9);
+        assert!(names.contains(&"pin".into()));
+        assert!(names.contains(&"task_add".into()));
+        assert!(names.contains(&"task_list".into()));
+        assert!(names.contains(&"task_done".into()));
+        assert!(names.contains(&"tag_session".into()));
+        assert!(names.contains(&"find_files".into()));
+        assert!(names.contains(&"parse_time".into()));
+        assert!(names.contains(&"timezone_convert".into()));
+        assert!(names.contains(&"calc".into()));
+        assert!(names.contains(&"unit_convert".into()));
+        assert!(names.contains(&"save_attachment".into()));
+        assert!(names.contains(&"form".into()));
+        assert!(names.contains(&"kv_get".into()));
+        assert!(names.contains(&"kv_set".into()));
+        assert!(names.contains(&"kv_delete".into()));
+        assert!(names.contains(&"kv_list".into()));
+        assert_eq!(names.len(), 25);
+    }
+
+    #[tokio::test]
+    async fn test_relative_write_goes_to_session_scratch() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool_call = ToolCall::new(
+            "call_1",
+            "write_file",
+            serde_json::json!({"path": "draft.txt", "content": "temp"}).to_string(),
+        );
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                content: None,
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("saved".into()),
+                ..Default::default()
+            },
+        ]));
+
+        let agent = TestLoop::new(provider, dir.path()).restrict_to_workspace().build();
+
+        let msg = InboundMessage::new("telegram", "user", "42", "save a draft");
+        agent.process_message(&msg).await.unwrap();
+
+        let scratch_file = dir.path().join("sessions").join("telegram_42").join("draft.txt");
+        assert_eq!(std::fs::read_to_string(scratch_file).unwrap(), "temp");
+        assert!(!dir.path().join("draft.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_secrets_redacted_and_restored_for_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.txt");
+        let tool_call = ToolCall::new(
+            "call_1",
+            "write_file",
+            serde_json::json!({"path": path, "content": "[API_KEY_1]"}).to_string(),
+        );
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                content: None,
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("Saved [API_KEY_1]".into()),
+                ..Default::default()
+            },
+        ]));
+        let redactor = Redactor::new(&Default::default()).unwrap();
+        let agent = create_test_loop(provider.clone()).with_redaction(Some(Arc::new(redactor)));
+
+        let key = "sk-0123456789abcdefghijklmnop";
+        let msg = InboundMessage::new("cli", "user", "direct", format!("save {key} to key.txt"));
+        let reply = agent.process_message(&msg).await.unwrap();
+
+        let requests = serde_json::to_string(&*provider.requests.lock().unwrap()).unwrap();
+        assert!(!requests.contains(key));
+        assert!(requests.contains("save [API_KEY_1] to"));
+        // The tool got the real key; the reply keeps the placeholder
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), key);
+        assert_eq!(reply.content, "Saved [API_KEY_1]");
+    }
+
+    #[tokio::test]
+    async fn test_reset_in_one_thread_keeps_other_threads_secrets() {
+        use oxibot_core::bus::types::THREAD_ID_KEY;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.txt");
+        let tool_call = ToolCall::new(
+            "call_1",
+            "write_file",
+            serde_json::json!({"path": path, "content": "[API_KEY_1]"}).to_string(),
+        );
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                content: Some("Noted [API_KEY_1]".into()),
+                ..Default::default()
+            },
+            LlmResponse {
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("Saved.".into()),
+                ..Default::default()
+            },
+        ]));
+        let redactor = Redactor::new(&Default::default()).unwrap();
+        let agent = TestLoop::new(provider, dir.path())
+            .sessions(SessionManager::new(Some(dir.path().join("store"))).unwrap())
+            .build()
+            .with_redaction(Some(Arc::new(redactor)));
+        let in_thread = |thread: &str, text: &str| {
+            let mut msg = InboundMessage::new("slack", "U1", "C1", text);
+            msg.metadata.insert(THREAD_ID_KEY.into(), thread.into());
+            msg
+        };
+
+        let key = "sk-0123456789abcdefghijklmnop";
+        agent.process_message(&in_thread("1700.01", &format!("my key is {key}"))).await.unwrap();
+        agent.process_message(&in_thread("1700.02", "/reset")).await.unwrap();
+        agent.process_message(&in_thread("1700.01", "save it to key.txt")).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), key);
+    }
+
+    #[tokio::test]
+    async fn test_plan_mode_persists_plan_across_turns() {
+        let propose = ToolCall::new(
+            "call_1",
+            "propose_plan",
+            serde_json::json!({"goal": "Tidy notes", "steps": ["Merge duplicates", "Sort by date"]}).to_string(),
+        );
+        let step = ToolCall::new("call_2", "plan_step", r#"{"step": 1, "status": "done"}"#);
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                tool_calls: vec![propose, step],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("Merged them; sorting next.".into()),
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("Continuing.".into()),
+                ..Default::default()
+            },
+        ]));
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let agent = TestLoop::new(provider.clone(), dir.path())
+            .sessions(sessions)
+            .build()
+            .with_plan_mode(Some(PlanModeConfig::default()));
+
+        agent.process_direct("tidy up my notes").await.unwrap();
+        let shown = agent.process_direct("/plan").await.unwrap();
+        assert!(shown.contains("✅ 1. Merge duplicates\n⬜ 2. Sort by date"), "{shown}");
+        assert!(shown.ends_with("Status: Approved, 1/2 steps complete"));
+
+        // The next turn sees the unfinished plan
+        agent.process_direct("go on").await.unwrap();
+        let prompt = provider.system_prompts().pop().unwrap();
+        assert!(prompt.contains("Current plan (approved, 1/2 steps complete)"), "{prompt}");
+
+        assert_eq!(agent.process_direct("/plan cancel").await.unwrap(), "🗑️ Plan dropped.");
+        assert_eq!(agent.process_direct("/plan").await.unwrap(), "No plan in this chat.");
+    }
+
+    #[tokio::test]
+    async fn test_form_started_from_inbound_metadata() {
+        let provider = Arc::new(MockProvider::new(vec![LlmResponse {
+            content: Some("What's your name?".into()),
+            ..Default::default()
+        }]));
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let agent = TestLoop::new(provider.clone(), dir.path()).sessions(sessions).build();
+
+        let mut msg = InboundMessage::new("telegram", "api", "42", "Start the intake");
+        msg.metadata.insert(
+            forms::START_KEY.into(),
+            r#"{"name": "intake", "fields": [{"name": "name"}, {"name": "email", "type": "email"}]}"#.into(),
+        );
+        agent.process_turn(&msg).await.unwrap();
+        let prompt = provider.system_prompts().pop().unwrap();
+        assert!(prompt.contains("## Form in progress"), "{prompt}");
+        assert!(prompt.contains("Still missing: `name` (text), `email` (email)"), "{prompt}");
+
+        let status = InboundMessage::new("telegram", "u1", "42", "/form");
+        let shown = agent.process_turn(&status).await.unwrap().reply.content;
+        assert!(shown.starts_with("📝 intake\n⬜ name"), "{shown}");
+        let cancel = InboundMessage::new("telegram", "u1", "42", "/form cancel");
+        assert_eq!(agent.process_turn(&cancel).await.unwrap().reply.content, "🗑️ Form 'intake' dropped.");
+        assert!(Form::load(&agent.sessions, "telegram:42").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pin_commands_bypass_llm() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let provider = Arc::new(MockProvider::simple("ok"));
+        let agent = TestLoop::new(provider, dir.path()).sessions(sessions).build();
+
+        let reply = agent.process_direct("/pin Budget is 500 EUR").await.unwrap();
+        assert!(reply.contains("Pinned note #1"));
+        assert_eq!(agent.process_direct("/pins").await.unwrap(), "1. Budget is 500 EUR");
+        assert!(agent.process_direct("/unpin 7").await.unwrap().starts_with("Usage"));
+
+        // Commands are not recorded in history and don't consume LLM responses
+        assert_eq!(agent.process_direct("hello").await.unwrap(), "ok");
+        assert_eq!(agent.sessions.get_history("cli:direct", 50).len(), 2);
+
+        assert!(agent.process_direct("/unpin 1").await.unwrap().contains("Budget"));
+        assert_eq!(agent.process_direct("/pins").await.unwrap(), "No pinned notes.");
+    }
+
+    #[tokio::test]
+    async fn test_reset_status_model_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let agent = TestLoop::new(Arc::new(MockProvider::simple("ok")), dir.path())
+            .model("test-model")
+            .sessions(sessions)
+            .build();
+
+        agent.process_direct("hello").await.unwrap();
+        agent.process_direct("/pin keep me").await.unwrap();
+
+        let status = agent.process_direct("/status").await.unwrap();
+        assert!(status.contains("Model: test-model"));
+        assert!(status.contains("Session: cli:direct (2 messages)"));
+        assert!(status.contains("Pinned notes: 1"));
+        assert_eq!(agent.process_direct("/model@oxibot").await.unwrap(), "🤖 Model: test-model");
+
+        assert!(agent.process_direct("/reset").await.unwrap().contains("cleared"));
+        assert!(agent.sessions.get_history("cli:direct", 50).is_empty());
+        assert_eq!(agent.sessions.pinned("cli:direct").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_commands_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse { content: Some("from the LLM".into()), ..Default::default() },
+            LlmResponse { content: Some("from the LLM".into()), ..Default::default() },
+        ]));
+        let agent = TestLoop::new(provider, dir.path())
+            .model("test-model")
+            .sessions(sessions)
+            .build()
+            .with_commands(CommandsConfig {
+                prefix: "!".into(),
+                admins: vec!["telegram:1".into()],
+                admin_only: vec!["reset".into()],
+                disabled: vec!["model".into()],
+            });
+        let agent = &agent;
+        let send = |sender: &str, text: &str| {
+            let msg = InboundMessage::new("telegram", sender, "42", text);
+            async move { agent.process_message(&msg).await.unwrap().content }
+        };
+
+        assert!(send("2", "!pin Budget").await.contains("Pinned note #1"));
+        assert_eq!(send("2", "!reset").await, "⛔ !reset is only available to admins.");
+        assert!(send("1", "!reset").await.contains("cleared"));
+        // Other prefixes and disabled commands go to the LLM
+        assert_eq!(send("2", "/pins").await, "from the LLM");
+        assert_eq!(send("2", "!model").await, "from the LLM");
+
+        let help = send("2", "!help").await;
+        assert!(help.contains("!pin <note> — Keep a fact in context"), "{help}");
+        assert!(help.contains("!reset — Clear conversation history (admin)"), "{help}");
+        assert!(!help.contains("!model"), "{help}");
+    }
+
+    #[tokio::test]
+    async fn test_persona_command_and_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let provider = Arc::new(MockProvider::new(vec![]));
+        let agent = TestLoop::new(provider.clone(), dir.path())
+            .sessions(sessions)
+            .build()
+            .with_persona(persona::find("concise-assistant"));
+        let concise = persona::find("concise-assistant").unwrap().prompt;
+        let sysadmin = persona::find("sysadmin-copilot").unwrap().prompt;
+
+        let listing = agent.process_direct("/persona").await.unwrap();
+        assert!(listing.starts_with("🎭 Persona: concise-assistant"));
+        assert!(listing.contains("- verbose-explainer — "));
+
+        agent.process_direct("hello there").await.unwrap();
+        assert!(agent.process_direct("/persona sysadmin-copilot").await.unwrap().contains("set to sysadmin-copilot"));
+        agent.process_direct("check the disks").await.unwrap();
+        assert!(agent.process_direct("/persona pirate").await.unwrap().starts_with("Unknown persona 'pirate'"));
+        assert!(agent.process_direct("/status").await.unwrap().contains("Persona: sysadmin-copilot"));
+        agent.process_direct("/persona none").await.unwrap();
+        agent.process_direct("what time is it").await.unwrap();
+        assert!(agent.process_direct("/persona default").await.unwrap().contains("(concise-assistant)"));
+        agent.process_direct("and now?").await.unwrap();
+
+        let prompts = provider.system_prompts();
+        assert_eq!(prompts.len(), 4);
+        assert!(prompts[0].contains(concise));
+        assert!(prompts[1].contains(sysadmin) && !prompts[1].contains(concise));
+        assert!(!prompts[2].contains("## Persona"));
+        assert!(prompts[3].contains(concise));
+        assert_eq!(
+            agent.sessions.metadata("cli:direct", persona::SESSION_FIELD),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_auto_tagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let agent = TestLoop::new(Arc::new(MockProvider::simple("¡Claro!")), dir.path())
+            .model("test-model")
+            .sessions(sessions)
+            .build();
+
+        let msg = InboundMessage::new("telegram", "user", "42", "¿Me puedes recordar la reunión para el lunes?");
+        agent.process_message(&msg).await.unwrap();
+        assert_eq!(
+            agent.sessions.tags("telegram:42"),
+            vec!["channel:telegram", "lang:es", "model:test-model"]
+        );
+
+        // Commands don't tag
+        agent.process_direct("/status").await.unwrap();
+        assert!(agent.sessions.tags("cli:direct").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_quick_replies_skip_full_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let text = |s: &str| LlmResponse {
+            content: Some(s.into()),
+            ..Default::default()
+        };
+        let provider = Arc::new(MockProvider::new(vec![
+            text(r#"{"intent": "thanks", "confidence": 0.9, "reply": "Anytime!"}"#),
+            text("Found one at 9:00. Shall I book it?"),
+            text(r#"{"intent": "other", "confidence": 0.95, "reply": ""}"#),
+            text("Booked."),
+        ]));
+        let agent = TestLoop::new(provider, dir.path())
+            .sessions(sessions)
+            .build()
+            .with_quick_replies(Some(QuickReplyConfig::default()), None);
+
+        // Canned phrase: no LLM call
+        assert_eq!(agent.process_direct("Thanks!").await.unwrap(), "You're welcome! 😊");
+        // Short message: one classification call writes the reply
+        assert_eq!(agent.process_direct("you are the best, cheers").await.unwrap(), "Anytime!");
+        // Long message: full loop
+        let reply = agent
+            .process_direct("find me a train to Paris tomorrow morning please")
+            .await
+            .unwrap();
+        assert!(reply.ends_with("Shall I book it?"));
+        // "ok" answers the question → classified as other → full loop
+        assert_eq!(agent.process_direct("ok").await.unwrap(), "Booked.");
+
+        assert_eq!(agent.sessions.get_history("cli:direct", 50).len(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_translation_round_trip() {
+        use oxibot_core::config::schema::ChannelTranslation;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let text = |s: &str| LlmResponse {
+            content: Some(s.into()),
+            ..Default::default()
+        };
+        let provider = Arc::new(MockProvider::new(vec![
+            text(r#"{"language": "de", "translation": "Where is my order? I have been waiting for a week."}"#),
+            text("It ships tomorrow."),
+            text("Sie wird morgen versandt."),
+            text("ABC123"),
+        ]));
+        let mut config = TranslationConfig::default();
+        config.channels.insert("telegram".into(), ChannelTranslation::default());
+        let agent = TestLoop::new(provider.clone(), dir.path())
+            .bus(bus.clone())
+            .sessions(sessions)
+            .build()
+            .with_translation(Some(config), None);
+
+        let msg = InboundMessage::new("telegram", "u1", "42", "Wo ist meine Bestellung? Ich warte seit einer Woche.");
+        agent.handle_inbound(&msg).await;
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "Sie wird morgen versandt.");
+        assert_eq!(agent.sessions.metadata("telegram:42", "language").as_deref(), Some("de"));
+        let stored = match &agent.sessions.get_history("telegram:42", 2)[0] {
+            Message::User { content, .. } => format!("{content:?}"),
+            other => panic!("unexpected {other:?}"),
+        };
+        assert!(stored.contains("Where is my order?") && stored.contains("Wo ist meine Bestellung?"), "{stored}");
+
+        // Clearly English: no detection call, and the reply goes out as is
+        let msg = InboundMessage::new("telegram", "u1", "42", "Thanks, what is the tracking number for it?");
+        agent.handle_inbound(&msg).await;
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "ABC123");
+        assert_eq!(provider.requests.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_greeting_and_help_skip_llm() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().join("store"))).unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let provider = Arc::new(MockProvider::new(vec![LlmResponse {
+            content: Some("Paris.".into()),
+            ..Default::default()
+        }]));
+        let agent = TestLoop::new(provider, dir.path())
+            .bus(bus.clone())
+            .sessions(sessions)
+            .build()
+            .with_responses(ResponsesConfig {
+                agent_name: "Ada".into(),
+                greet_new_chats: true,
+                ..Default::default()
+            });
+
+        let mut msg = InboundMessage::new("telegram", "1|bob", "42", "/help");
+        msg.metadata.insert("first_name".into(), "Bob".into());
+        let help = agent.process_message(&msg).await.unwrap().content;
+        assert!(help.starts_with("🤖 **Ada Commands**"));
+
+        // First message of a new chat: greeting first, then the answer
+        msg.content = "What's the capital of France?".into();
+        assert_eq!(agent.process_message(&msg).await.unwrap().content, "Paris.");
+        assert!(bus.consume_outbound().await.unwrap().content.starts_with("👋 Hi Bob! I'm Ada"));
+
+        // /start always greets; later messages of the chat are not greeted
+        msg.content = "/start".into();
+        assert!(agent.process_message(&msg).await.unwrap().content.starts_with("👋 Hi Bob!"));
+        assert!(agent.sessions.exists("telegram:42"));
+    }
+
+    #[tokio::test]
+    async fn test_usage_log_records_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool_call = ToolCall::new(
+            "call_1",
+            "list_dir",
+            serde_json::json!({"path": "."}).to_string(),
+        );
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("done".into()),
+                ..Default::default()
+            },
+        ]));
+        let log_path = dir.path().join("usage.jsonl");
+        let agent = TestLoop::new(provider, dir.path())
+            .model("test-model")
+            .sessions(SessionManager::new(Some(dir.path().join("store"))).unwrap())
+            .build()
+            .with_usage_log(Some(UsageLog::new(Some(log_path.clone()))))
+            .with_tool_stats(Some(Arc::new(ToolStatsStore::new(Some(dir.path().join("tool_stats.json"))))));
+        assert!(agent.tools.has("tool_stats"));
+
+        assert_eq!(agent.process_direct("list files").await.unwrap(), "done");
+
+        let events = UsageLog::new(Some(log_path)).read_all();
+        assert_eq!(events.len(), 4);
+        // The mock reports no usage, so it is estimated
+        assert!(matches!(&events[0], UsageEvent::LlmCall { model, prompt_tokens, .. }
+            if model == "test-model" && *prompt_tokens > 0));
+        assert!(matches!(&events[1], UsageEvent::ToolCall { tool, success: true, .. } if tool == "list_dir"));
+        assert!(matches!(&events[2], UsageEvent::LlmCall { .. }));
+        assert!(matches!(&events[3], UsageEvent::Message { channel, .. } if channel == "cli"));
+
+        let stats = ToolStatsStore::new(Some(dir.path().join("tool_stats.json"))).load();
+        assert_eq!(stats["list_dir"].calls, 1);
+        assert_eq!(stats["list_dir"].errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_debug_footer() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool_call = ToolCall::new("call_1", "list_dir", serde_json::json!({"path": "."}).to_string());
+        let reply = |content: &str| LlmResponse {
+            content: Some(content.into()),
+            usage: Some(UsageInfo {
+                prompt_tokens: 1000,
+                completion_tokens: 100,
+                total_tokens: 1100,
+            }),
+            ..Default::default()
+        };
+        let provider = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                tool_calls: vec![tool_call],
+                ..reply("")
+            },
+            reply("done"),
+            reply("hi"),
+        ]));
+        let bus = Arc::new(MessageBus::new(32));
+        let mut prices = HashMap::new();
+        prices.insert("test-model".to_string(), ModelPrice { input: 3.0, output: 15.0 });
+        let agent = TestLoop::new(provider, dir.path())
+            .bus(bus.clone())
+            .model("test-model")
+            .sessions(SessionManager::new(Some(dir.path().join("store"))).unwrap())
+            .build()
+            .with_debug_footer(
+                DebugFooterConfig {
+                    enabled: true,
+                    channels: vec!["telegram".into()],
+                },
+                prices,
+            );
+
+        agent.handle_inbound(&InboundMessage::new("telegram", "u1", "42", "list files")).await;
+        let content = bus.consume_outbound().await.unwrap().content;
+        let (text, footer) = content.split_once("\n\n— ").unwrap();
+        assert_eq!(text, "done");
+        assert!(footer.starts_with("test-model · 2000 in / 200 out · ~$0.0090 · list_dir · "), "{footer}");
+
+        // Other channels and commands get no footer
+        assert_eq!(agent.process_turn(&InboundMessage::new("cli", "u1", "direct", "hello")).await.unwrap().footer, None);
+        let help = agent.process_turn(&InboundMessage::new("telegram", "u1", "42", "/help")).await.unwrap();
+        assert_eq!(help.footer, None);
+    }
+
+    /// Calls `list_dir` for "slow" and then waits for a permit before
+    /// answering; answers anything else at once. Reports usage either way.
+    struct ThreadsProvider {
+        gate: tokio::sync::Semaphore,
+        waiting: tokio::sync::Notify,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ThreadsProvider {
+        async fn chat(
+            &self,
+            messages: &[Message],
+            _tools: Option<&[ToolDefinition]>,
+            _model: &str,
+            _config: &LlmRequestConfig,
+        ) -> LlmResponse {
+            let usage = |tokens: u32| {
+                Some(UsageInfo {
+                    prompt_tokens: tokens,
+                    completion_tokens: tokens / 10,
+                    total_tokens: tokens + tokens / 10,
+                })
+            };
+            match messages.last() {
+                Some(Message::User { content: MessageContent::Text(text) }) if text == "slow" => LlmResponse {
+                    tool_calls: vec![ToolCall::new("call_1", "list_dir", r#"{"path": "."}"#)],
+                    usage: usage(1000),
+                    ..Default::default()
+                },
+                Some(Message::Tool { .. }) => {
+                    self.waiting.notify_one();
+                    self.gate.acquire().await.unwrap().forget();
+                    LlmResponse {
+                        content: Some("slow done".into()),
+                        usage: usage(1000),
+                        ..Default::default()
+                    }
+                }
+                _ => LlmResponse {
+                    content: Some("fast done".into()),
+                    usage: usage(10),
+                    ..Default::default()
+                },
+            }
+        }
+
+        fn default_model(&self) -> &str {
+            "threads-model"
+        }
+
+        fn display_name(&self) -> &str {
+            "ThreadsProvider"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_footer_per_thread() {
+        use oxibot_core::bus::types::THREAD_ID_KEY;
+
+        let dir = tempfile::tempdir().unwrap();
+        let provider = Arc::new(ThreadsProvider {
+            gate: tokio::sync::Semaphore::new(0),
+            waiting: tokio::sync::Notify::new(),
+        });
+        let agent = Arc::new(
+            TestLoop::new(provider.clone(), dir.path())
+                .sessions(SessionManager::new(Some(dir.path().join("store"))).unwrap())
+                .build()
+                .with_debug_footer(
+                    DebugFooterConfig {
+                        enabled: true,
+                        channels: vec!["slack".into()],
+                    },
+                    HashMap::new(),
+                ),
+        );
+        let in_thread = |thread: &str, text: &str| {
+            let mut msg = InboundMessage::new("slack", "U1", "C1", text);
+            msg.metadata.insert(THREAD_ID_KEY.into(), thread.into());
+            msg
+        };
+
+        // The first thread's turn has made one call and waits in its second
+        let slow = {
+            let (agent, msg) = (agent.clone(), in_thread("1700.01", "slow"));
+            tokio::spawn(async move { agent.process_turn(&msg).await.unwrap() })
+        };
+        provider.waiting.notified().await;
+
+        // A turn in another thread of the same chat meters only itself...
+        let fast = agent.process_turn(&in_thread("1700.02", "what's new")).await.unwrap();
+        let footer = fast.footer.unwrap();
+        assert!(footer.contains("10 in / 1 out"), "{footer}");
+        assert!(!footer.contains("list_dir"), "{footer}");
+
+        // ...and leaves the first thread's count alone
+        provider.gate.add_permits(1);
+        let footer = slow.await.unwrap().footer.unwrap();
+        assert!(footer.contains("2000 in / 200 out"), "{footer}");
+        assert!(footer.contains("list_dir"), "{footer}");
+    }
+
+    #[test]
+    fn test_tool_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let make = || {
+            TestLoop::new(Arc::new(MockProvider::simple("ok")), dir.path())
+                .sessions(SessionManager::new(Some(dir.path().join("store"))).unwrap())
+                .build()
+        };
+
+        let read_only = make().with_tool_filter(&[], true);
+        assert!(read_only.tools.has("read_file"));
+        assert!(read_only.tools.has("web_fetch"));
+        for name in MUTATING_TOOLS {
+            assert!(!read_only.tools.has(name), "{name} should be removed");
+        }
+
+        let allowed = make().with_tool_filter(&["read_file".into(), "exec".into()], true);
+        assert_eq!(allowed.tools.tool_names(), vec!["read_file".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_tool_arguments_fall_back() {
+        let bad_call = |id: &str| LlmResponse {
+            content: None,
+            tool_calls: vec![ToolCall::new(id, "list_dir", "{\"path\": \".\"")],
+            ..Default::default()
+        };
+
+        // Two bad calls → the fallback model plans the rest of the turn
+        let main = Arc::new(MockProvider::new(vec![bad_call("c1"), bad_call("c2")]));
+        let strict = Arc::new(MockProvider::simple("fixed"));
+        let agent = create_test_loop(main.clone())
+            .with_tool_call_repair(2, Some(ModelRoute::new(strict.clone(), "strict-model")));
+        assert_eq!(agent.process_direct("list files").await.unwrap(), "fixed");
+        let second = main.requests.lock().unwrap()[1].clone();
+        assert!(matches!(
+            second.last(),
+            Some(Message::Tool { content, .. }) if content.contains("invalid JSON arguments")
+        ));
+        assert!(strict.responses.lock().unwrap().is_empty());
+
+        // No fallback → tools are disabled for the rest of the turn
+        let main = Arc::new(MockProvider::new(vec![bad_call("c1"), bad_call("c2")]));
+        let agent = create_test_loop(main.clone()).with_tool_call_repair(2, None);
+        assert_eq!(agent.process_direct("list files").await.unwrap(), "(no more responses)");
+        let offered = main.tools_offered.lock().unwrap().clone();
+        assert_eq!(offered.len(), 3);
+        assert!(offered[1] > 0);
+        assert_eq!(offered[2], 0);
+    }
+
+    #[tokio::test]
+    async fn test_failing_tool_is_taken_out_of_use() {
+        let fetch = |id: &str| LlmResponse {
+            tool_calls: vec![ToolCall::new(id, "web_fetch", "{\"url\": \"http://127.0.0.1:9/notes.txt\"}")],
+            ..Default::default()
+        };
+        let answer = |text: &str| LlmResponse {
+            content: Some(text.into()),
+            ..Default::default()
+        };
+        let provider = Arc::new(MockProvider::new(vec![
+            fetch("c1"),
+            fetch("c2"),
+            answer("The server doesn't answer."),
+            fetch("c3"),
+            answer("I can't fetch pages right now."),
+        ]));
+        let agent = create_test_loop(provider.clone()).with_tool_breaker(ToolBreakerConfig {
+            enabled: true,
+            min_calls: 2,
+            error_rate: 1.0,
+            ..Default::default()
+        });
+
+        agent.process_direct("fetch my notes").await.unwrap();
+        assert!(!provider.system_prompts()[0].contains("Unavailable tools"));
+
+        agent.process_direct("try again").await.unwrap();
+        let prompt = provider.system_prompts().pop().unwrap();
+        assert!(prompt.contains("## Unavailable tools"), "{prompt}");
+        assert!(prompt.contains("- web_fetch (retried in about 15 min): Error"), "{prompt}");
+        let offered = provider.tools_offered.lock().unwrap().clone();
+        assert_eq!(offered[3], offered[0] - 1);
+
+        // Called anyway (from memory of earlier turns): not run
+        let last = provider.requests.lock().unwrap().last().unwrap().clone();
+        assert!(last.iter().any(|m| matches!(
+            m,
+            Message::Tool { content, .. } if content.contains("temporarily disabled")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tools_are_corrected() {
+        let call = |id: &str, name: &str| LlmResponse {
+            content: Some("It's 4.".into()),
+            tool_calls: vec![ToolCall::new(id, name, "{\"path\": \".\"}")],
+            ..Default::default()
+        };
+
+        // Only unknown tools next to an answer → the answer is used
+        let provider = Arc::new(MockProvider::new(vec![call("c1", "calculator")]));
+        let agent = create_test_loop(provider.clone());
+        assert_eq!(agent.process_direct("2+2?").await.unwrap(), "It's 4.");
+        assert_eq!(provider.requests.lock().unwrap().len(), 1);
+
+        // Mixed with a real tool → the unknown call gets a corrective error
+        let mut mixed = call("c1", "listdir");
+        mixed.tool_calls.push(ToolCall::new("c2", "list_dir", "{\"path\": \".\"}"));
+        let provider = Arc::new(MockProvider::new(vec![mixed, LlmResponse::default()]));
+        let agent = create_test_loop(provider.clone());
+        agent.process_direct("list files").await.unwrap();
+        let second = provider.requests.lock().unwrap()[1].clone();
+        assert!(second.iter().any(|m| matches!(
+            m,
+            Message::Tool { content, tool_call_id } if tool_call_id == "c1"
+                && content.contains("no tool named `listdir`. Did you mean `list_dir`?")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failing_calls_end_the_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.txt").display().to_string();
+        let read = |id: &str, args: String| LlmResponse {
+            tool_calls: vec![ToolCall::new(id, "read_file", args)],
+            ..Default::default()
+        };
+        let provider = Arc::new(MockProvider::new(vec![
+            read("c1", serde_json::json!({"path": missing}).to_string()),
+            read("c2", format!("{{ \"path\" : {:?} }}", missing)),
+            read("c3", serde_json::json!({"path": missing}).to_string()),
+            LlmResponse::default(),
+        ]));
+        let agent = create_test_loop(provider.clone());
+
+        let reply = agent.process_direct("show the file").await.unwrap();
+        assert!(reply.starts_with("⚠️ I had to stop: `read_file` failed 3 times"), "{reply}");
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(matches!(
+            requests[2].last(),
+            Some(Message::Tool { content, .. }) if content.contains("This exact call has already failed")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_planner_and_responder_routing() {
+        let tool_call = ToolCall::new(
+            "call_1",
+            "list_dir",
+            serde_json::json!({"path": "."}).to_string(),
+        );
+        let planner = Arc::new(MockProvider::new(vec![
+            LlmResponse {
+                content: None,
+                tool_calls: vec![tool_call],
+                ..Default::default()
+            },
+            LlmResponse {
+                content: Some("planner draft".into()),
+                ..Default::default()
+            },
+        ]));
+        let responder = Arc::new(MockProvider::simple("final answer"));
+
+        let agent = create_test_loop(Arc::new(MockProvider::simple("default"))).with_model_routing(
+            Some(ModelRoute::new(planner.clone(), "cheap-model")),
+            Some(ModelRoute::new(responder.clone(), "strong-model")),
+        );
+
+        let result = agent.process_direct("list files").await.unwrap();
+        assert_eq!(result, "final answer");
+        assert!(planner.responses.lock().unwrap().is_empty());
+        assert!(responder.responses.lock().unwrap().is_empty());
+        assert_eq!(agent.model(), "mock-model");
     }
 
     #[test]
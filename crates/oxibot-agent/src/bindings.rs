@@ -0,0 +1,418 @@
+//! Per-channel agent bindings.
+//!
+//! The gateway can run several agent loops side by side — e.g. a restricted
+//! read-only agent for a public Slack channel and the full-powered one for a
+//! private Telegram DM. `AgentRouter` consumes the shared inbound queue and
+//! hands each message to the agent bound to its channel/chat, falling back
+//! to the default agent.
+//!
+//! Channels can also pick a profile per message through the `agent_profile`
+//! metadata key (e.g. a chat linked via a Telegram deep link). Such links are
+//! remembered so subagent results for that chat reach the same agent.
+//!
+//! Up to `agents.defaults.maxConcurrentSessions` conversations are worked on
+//! at once, so a slow conversation doesn't hold up the others. Messages of
+//! one conversation wait for the previous one to finish, keeping its order.
+//! A turn that panics gets a crash report; the conversation carries on with
+//! its next message.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::config::schema::AgentsConfig;
+use oxibot_core::crash::{self, CrashReporter};
+use tokio::task::{Id, JoinSet};
+use tracing::{debug, error, info, warn};
+
+use crate::agent_loop::AgentLoop;
+
+// ─────────────────────────────────────────────
+// AgentRouter
+// ─────────────────────────────────────────────
+
+/// Dispatches inbound messages to the agent bound to their channel/chat.
+pub struct AgentRouter {
+    /// Agent for messages without a binding.
+    default: Arc<AgentLoop>,
+    /// Agents keyed by profile name.
+    profiles: HashMap<String, Arc<AgentLoop>>,
+    /// Bindings (channel/chat → profile name).
+    agents: AgentsConfig,
+    /// Profiles chosen by channels via metadata, keyed by `channel:chat_id`.
+    linked: Mutex<HashMap<String, String>>,
+    /// Crash reports for turns that panic.
+    crashes: Option<CrashReporter>,
+}
+
+impl AgentRouter {
+    /// Create a router that sends every message to `default`.
+    pub fn new(default: Arc<AgentLoop>) -> Self {
+        Self {
+            default,
+            profiles: HashMap::new(),
+            agents: AgentsConfig::default(),
+            linked: Mutex::new(HashMap::new()),
+            crashes: None,
+        }
+    }
+
+    /// Use the bindings from the agents config.
+    pub fn with_bindings(mut self, agents: AgentsConfig) -> Self {
+        self.agents = agents;
+        self
+    }
+
+    /// Register the agent for a profile name.
+    pub fn with_profile(mut self, name: impl Into<String>, agent: Arc<AgentLoop>) -> Self {
+        self.profiles.insert(name.into(), agent);
+        self
+    }
+
+    /// Write a crash report when a turn panics.
+    pub fn with_crash_reporter(mut self, crashes: CrashReporter) -> Self {
+        self.crashes = Some(crashes);
+        self
+    }
+
+    /// Agent that should handle `msg`.
+    ///
+    /// An `agent_profile` metadata entry naming a known profile wins over
+    /// the configured bindings. Subagent results (`system` channel) go to the
+    /// agent bound to the chat that spawned them, so they land in the same
+    /// conversation.
+    pub fn agent_for(&self, msg: &InboundMessage) -> &Arc<AgentLoop> {
+        let (channel, chat_id) = if msg.channel == "system" {
+            msg.chat_id.split_once(':').unwrap_or(("system", &msg.chat_id))
+        } else {
+            (msg.channel.as_str(), msg.chat_id.as_str())
+        };
+        let key = format!("{channel}:{chat_id}");
+
+        if let Some(name) = msg.metadata.get("agent_profile") {
+            if self.profiles.contains_key(name) {
+                let mut linked = self.linked.lock().unwrap_or_else(|e| e.into_inner());
+                linked.insert(key, name.clone());
+            } else {
+                warn!(profile = %name, "message names unknown agent profile, ignoring");
+            }
+        }
+        self.agent_for_chat(channel, chat_id)
+    }
+
+    /// Agent bound to a chat, through a remembered link or the configured
+    /// bindings. Scheduled jobs run on the agent of the chat they report
+    /// to, so a restricted chat cannot schedule work for a stronger agent.
+    pub fn agent_for_chat(&self, channel: &str, chat_id: &str) -> &Arc<AgentLoop> {
+        let linked = self.linked.lock().unwrap_or_else(|e| e.into_inner());
+        linked
+            .get(&format!("{channel}:{chat_id}"))
+            .map(String::as_str)
+            .or_else(|| self.agents.binding_for(channel, chat_id))
+            .and_then(|name| self.profiles.get(name))
+            .unwrap_or(&self.default)
+    }
+
+    /// Consume the inbound queue and dispatch each message, working on up
+    /// to `maxConcurrentSessions` conversations at once.
+    ///
+    /// Runs until the inbound channel is closed and the last turn is done.
+    pub async fn run(&self, bus: &MessageBus) {
+        let limit = self.agents.defaults.max_concurrent_sessions.max(1);
+        info!(profiles = self.profiles.len(), limit, "agent router started, waiting for messages");
+
+        let mut turns = JoinSet::new();
+        let mut conversations: HashMap<Id, String> = HashMap::new();
+        // Conversations with a turn running → their messages waiting for it
+        let mut waiting: HashMap<String, VecDeque<InboundMessage>> = HashMap::new();
+        let mut open = true;
+        loop {
+            tokio::select! {
+                msg = bus.consume_inbound(), if open && turns.len() < limit => {
+                    let Some(msg) = msg else {
+                        info!("inbound channel closed, agent router finishing running turns");
+                        open = false;
+                        continue;
+                    };
+                    let key = conversation_key(&msg);
+                    match waiting.get_mut(&key) {
+                        Some(queue) => queue.push_back(msg),
+                        None => {
+                            waiting.insert(key.clone(), VecDeque::new());
+                            let id = self.spawn_turn(&mut turns, msg);
+                            conversations.insert(id, key);
+                        }
+                    }
+                }
+                Some(done) = turns.join_next_with_id() => {
+                    let id = match done {
+                        Ok((id, ())) => id,
+                        Err(e) => {
+                            let id = e.id();
+                            if e.is_panic() {
+                                let payload = e.into_panic();
+                                error!(error = %crash::panic_message(&*payload), "agent turn panicked");
+                                if let Some(ref crashes) = self.crashes {
+                                    crashes.report("agent turn", payload).await;
+                                }
+                            } else {
+                                error!(error = %e, "agent turn failed");
+                            }
+                            id
+                        }
+                    };
+                    let Some(key) = conversations.remove(&id) else { continue };
+                    match waiting.get_mut(&key).and_then(VecDeque::pop_front) {
+                        Some(next) => {
+                            let id = self.spawn_turn(&mut turns, next);
+                            conversations.insert(id, key);
+                        }
+                        None => {
+                            waiting.remove(&key);
+                        }
+                    }
+                }
+                else => break,
+            }
+        }
+        info!("agent router exiting");
+    }
+
+    /// Hand `msg` to its agent on a new task.
+    fn spawn_turn(&self, turns: &mut JoinSet<()>, msg: InboundMessage) -> Id {
+        let agent = self.agent_for(&msg).clone();
+        debug!(channel = %msg.channel, chat_id = %msg.chat_id, model = %agent.model(), "routing message");
+        turns.spawn(async move { agent.handle_inbound(&msg).await }).id()
+    }
+}
+
+/// Messages with the same key are handled one at a time, in order.
+/// Subagent results (`system` channel) join the chat they report to.
+fn conversation_key(msg: &InboundMessage) -> String {
+    if msg.channel == "system" {
+        msg.chat_id.clone()
+    } else {
+        msg.session_key()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_loop::TestLoop;
+    use async_trait::async_trait;
+    use oxibot_core::config::schema::AgentBinding;
+    use oxibot_core::session::SessionManager;
+    use oxibot_core::types::{LlmResponse, Message, MessageContent, ToolDefinition};
+    use oxibot_providers::traits::{LlmProvider, LlmRequestConfig};
+
+    struct EchoModelProvider;
+
+    #[async_trait]
+    impl LlmProvider for EchoModelProvider {
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: Option<&[ToolDefinition]>,
+            model: &str,
+            _config: &LlmRequestConfig,
+        ) -> LlmResponse {
+            LlmResponse {
+                content: Some(format!("from {model}")),
+                ..Default::default()
+            }
+        }
+
+        fn default_model(&self) -> &str {
+            "echo"
+        }
+
+        fn display_name(&self) -> &str {
+            "Echo"
+        }
+    }
+
+    /// Answers "slow" only once a permit is added; echoes anything else.
+    struct GatedProvider(tokio::sync::Semaphore);
+
+    #[async_trait]
+    impl LlmProvider for GatedProvider {
+        async fn chat(
+            &self,
+            messages: &[Message],
+            _tools: Option<&[ToolDefinition]>,
+            _model: &str,
+            _config: &LlmRequestConfig,
+        ) -> LlmResponse {
+            let text = match messages.last() {
+                Some(Message::User { content: MessageContent::Text(text) }) => text.clone(),
+                _ => String::new(),
+            };
+            if text == "slow" {
+                self.0.acquire().await.unwrap().forget();
+            }
+            LlmResponse {
+                content: Some(format!("re: {text}")),
+                ..Default::default()
+            }
+        }
+
+        fn default_model(&self) -> &str {
+            "gated"
+        }
+
+        fn display_name(&self) -> &str {
+            "Gated"
+        }
+    }
+
+    fn make_agent(bus: &Arc<MessageBus>, dir: &std::path::Path, model: &str) -> Arc<AgentLoop> {
+        Arc::new(
+            TestLoop::new(Arc::new(EchoModelProvider), dir)
+                .bus(bus.clone())
+                .model(model)
+                .sessions(SessionManager::new(Some(dir.join(model))).unwrap())
+                .build(),
+        )
+    }
+
+    fn binding(channel: &str, chat_id: &str, agent: &str) -> AgentBinding {
+        AgentBinding {
+            channel: channel.into(),
+            chat_id: chat_id.into(),
+            agent: agent.into(),
+        }
+    }
+
+    fn make_router(bus: &Arc<MessageBus>, dir: &std::path::Path) -> AgentRouter {
+        let agents = AgentsConfig {
+            bindings: vec![
+                binding("slack", "", "support"),
+                binding("slack", "C_OPS", "full"),
+            ],
+            ..Default::default()
+        };
+        AgentRouter::new(make_agent(bus, dir, "default-model"))
+            .with_bindings(agents)
+            .with_profile("support", make_agent(bus, dir, "support-model"))
+            .with_profile("full", make_agent(bus, dir, "full-model"))
+    }
+
+    #[test]
+    fn test_agent_for() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let router = make_router(&bus, dir.path());
+
+        let route = |channel: &str, chat_id: &str| {
+            let msg = InboundMessage::new(channel, "user", chat_id, "hi");
+            router.agent_for(&msg).model().to_string()
+        };
+        assert_eq!(route("slack", "C_SUPPORT"), "support-model");
+        assert_eq!(route("slack", "C_OPS"), "full-model");
+        assert_eq!(route("telegram", "42"), "default-model");
+        assert_eq!(route("system", "slack:C_OPS"), "full-model");
+        assert_eq!(router.agent_for_chat("slack", "C_SUPPORT").model(), "support-model");
+    }
+
+    #[test]
+    fn test_unknown_profile_falls_back_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let agents = AgentsConfig {
+            bindings: vec![binding("slack", "", "missing")],
+            ..Default::default()
+        };
+        let router = AgentRouter::new(make_agent(&bus, dir.path(), "default-model")).with_bindings(agents);
+        let msg = InboundMessage::new("slack", "user", "C1", "hi");
+        assert_eq!(router.agent_for(&msg).model(), "default-model");
+    }
+
+    #[test]
+    fn test_metadata_profile_overrides_binding() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let router = make_router(&bus, dir.path());
+
+        let mut msg = InboundMessage::new("telegram", "user", "42", "/start vip");
+        msg.metadata.insert("agent_profile".into(), "full".into());
+        assert_eq!(router.agent_for(&msg).model(), "full-model");
+
+        // Later messages and subagent results for the chat stay linked
+        let msg = InboundMessage::new("telegram", "user", "42", "hi");
+        assert_eq!(router.agent_for(&msg).model(), "full-model");
+        let msg = InboundMessage::new("system", "subagent", "telegram:42", "done");
+        assert_eq!(router.agent_for(&msg).model(), "full-model");
+
+        let mut msg = InboundMessage::new("telegram", "user", "7", "hi");
+        msg.metadata.insert("agent_profile".into(), "missing".into());
+        assert_eq!(router.agent_for(&msg).model(), "default-model");
+    }
+
+    #[tokio::test]
+    async fn test_run_dispatches_to_bound_agent() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let router = Arc::new(make_router(&bus, dir.path()));
+
+        let runner = {
+            let router = router.clone();
+            let bus = bus.clone();
+            tokio::spawn(async move { router.run(&bus).await })
+        };
+
+        bus.publish_inbound(InboundMessage::new("slack", "user", "C_SUPPORT", "hi"))
+            .await
+            .unwrap();
+        let reply = bus.consume_outbound().await.unwrap();
+        assert_eq!(reply.chat_id, "C_SUPPORT");
+        assert_eq!(reply.content, "from support-model");
+
+        bus.publish_inbound(InboundMessage::new("telegram", "user", "42", "hi"))
+            .await
+            .unwrap();
+        let reply = bus.consume_outbound().await.unwrap();
+        assert_eq!(reply.content, "from default-model");
+
+        runner.abort();
+    }
+
+    #[tokio::test]
+    async fn test_sessions_run_concurrently_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let provider = Arc::new(GatedProvider(tokio::sync::Semaphore::new(0)));
+        let agent = Arc::new(
+            TestLoop::new(provider.clone(), dir.path())
+                .bus(bus.clone())
+                .sessions(SessionManager::new(Some(dir.path().join("sessions"))).unwrap())
+                .build(),
+        );
+        let router = Arc::new(AgentRouter::new(agent));
+        let runner = {
+            let (router, bus) = (router.clone(), bus.clone());
+            tokio::spawn(async move { router.run(&bus).await })
+        };
+
+        let send = |chat: &str, text: &str| InboundMessage::new("telegram", "user", chat, text);
+        bus.publish_inbound(send("1", "slow")).await.unwrap();
+        bus.publish_inbound(send("1", "after slow")).await.unwrap();
+        bus.publish_inbound(send("2", "fast")).await.unwrap();
+
+        // Chat 2 is answered while chat 1 is still busy...
+        let reply = bus.consume_outbound().await.unwrap();
+        assert_eq!((reply.chat_id.as_str(), reply.content.as_str()), ("2", "re: fast"));
+
+        // ...and chat 1 keeps its order
+        provider.0.add_permits(1);
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "re: slow");
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "re: after slow");
+
+        runner.abort();
+    }
+}
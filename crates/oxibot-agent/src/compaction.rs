@@ -0,0 +1,171 @@
+//! Tool result compaction — bound the context used by old tool output.
+//!
+//! Tool results stay in the message list for the rest of the turn, so a
+//! 200 KB web page fetched in the first iteration is resent on every later
+//! LLM call. Once the LLM has seen a result, results over the size limit are
+//! replaced by their start and end (or an LLM summary). The newest batch of
+//! results is never touched. The size limit is in characters, or in tokens
+//! of the model's tokenizer.
+
+use oxibot_core::tokenizer::Tokenizer;
+use oxibot_core::types::Message;
+
+/// Marks a tool result that was already compacted.
+pub const COMPACTED_MARKER: &str = "[compacted tool output";
+
+/// Maximum characters of a tool result sent to the summarizer.
+const MAX_SUMMARY_INPUT: usize = 50_000;
+
+/// Room left for the marker line when truncating.
+const MARKER_BUDGET: usize = 80;
+
+/// Size limit for a tool result.
+pub enum Limit<'a> {
+    /// At most this many characters.
+    Chars(usize),
+    /// At most this many tokens.
+    Tokens(usize, &'a dyn Tokenizer),
+}
+
+impl Limit<'_> {
+    /// Whether `text` is over the limit.
+    pub fn exceeded_by(&self, text: &str) -> bool {
+        match self {
+            Limit::Chars(max) => text.chars().count() > *max,
+            Limit::Tokens(max, tokenizer) => tokenizer.count(text) > *max,
+        }
+    }
+
+    /// The limit in characters of `text`; a token limit is converted at
+    /// the text's own characters-per-token ratio.
+    pub fn max_chars(&self, text: &str) -> usize {
+        match self {
+            Limit::Chars(max) => *max,
+            Limit::Tokens(max, tokenizer) => {
+                let tokens = tokenizer.count(text).max(1);
+                let chars = text.chars().count();
+                (chars * max / tokens).max(1)
+            }
+        }
+    }
+}
+
+/// Indices of tool results before `fresh_from` that exceed `limit` and
+/// have not been compacted yet.
+pub fn stale_results(messages: &[Message], fresh_from: usize, limit: &Limit) -> Vec<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .take(fresh_from)
+        .filter_map(|(i, m)| match m {
+            Message::Tool { content, .. }
+                if !content.contains(COMPACTED_MARKER) && limit.exceeded_by(content) =>
+            {
+                Some(i)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Keep the start and end of `text`, about `max_chars` characters in total.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let budget = max_chars.saturating_sub(MARKER_BUDGET).max(2);
+    let head_chars = budget * 2 / 3;
+    let tail_chars = budget - head_chars;
+    let head_end = text.char_indices().nth(head_chars).map_or(text.len(), |(i, _)| i);
+    let tail_start = text
+        .char_indices()
+        .nth(total - tail_chars)
+        .map_or(text.len(), |(i, _)| i);
+    format!(
+        "{}\n{COMPACTED_MARKER}: {} of {total} characters omitted]\n{}",
+        &text[..head_end],
+        total - head_chars - tail_chars,
+        &text[tail_start..]
+    )
+}
+
+/// Messages asking the LLM to summarize a tool result in about `max_chars`.
+pub fn summary_messages(text: &str, max_chars: usize) -> Vec<Message> {
+    vec![
+        Message::system(format!(
+            "Summarize the tool output below for an assistant that is in the middle of a \
+             task. Keep every fact it may still need: names, numbers, paths, URLs, errors \
+             and conclusions. Drop boilerplate and repetition. Reply with the summary only, \
+             in at most {max_chars} characters."
+        )),
+        Message::user(truncate(text, MAX_SUMMARY_INPUT)),
+    ]
+}
+
+/// Tool result content for an LLM summary of `original_chars` characters.
+pub fn summarized(summary: &str, original_chars: usize) -> String {
+    format!("{COMPACTED_MARKER}: summary of {original_chars} characters]\n{}", summary.trim())
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::tokenizer::HeuristicTokenizer;
+
+    #[test]
+    fn test_truncate_keeps_head_and_tail() {
+        assert_eq!(truncate("short", 100), "short");
+
+        let text: String = "é".repeat(300) + &"z".repeat(300);
+        let out = truncate(&text, 200);
+        assert!(out.chars().count() <= 200);
+        assert!(out.starts_with(&"é".repeat(80)));
+        assert!(out.ends_with(&format!("]\n{}", "z".repeat(40))));
+        assert!(out.contains("[compacted tool output: 480 of 600 characters omitted]"));
+    }
+
+    #[test]
+    fn test_stale_results() {
+        let big = "x".repeat(50);
+        let messages = vec![
+            Message::system("sys"),
+            Message::user(big.clone()),
+            Message::tool_result("1", big.clone()),
+            Message::tool_result("2", "small"),
+            Message::tool_result("3", truncate(&"y".repeat(500), 100) + &big),
+            Message::tool_result("4", big),
+        ];
+        // The newest result (index 5) is fresh; user messages are never touched
+        assert_eq!(stale_results(&messages, 5, &Limit::Chars(20)), vec![2]);
+        assert!(stale_results(&messages, 2, &Limit::Chars(20)).is_empty());
+    }
+
+    #[test]
+    fn test_token_limit() {
+        let tokenizer = HeuristicTokenizer;
+        let limit = Limit::Tokens(10, &tokenizer);
+        assert!(!limit.exceeded_by(&"a".repeat(40)));
+        assert!(limit.exceeded_by(&"a".repeat(41)));
+        // 4 characters per token for ASCII, 1 for CJK
+        assert_eq!(limit.max_chars(&"a".repeat(400)), 40);
+        assert_eq!(limit.max_chars(&"語".repeat(400)), 10);
+        assert_eq!(Limit::Chars(7).max_chars("anything"), 7);
+    }
+
+    #[test]
+    fn test_summary_messages() {
+        let msgs = summary_messages(&"a".repeat(60_000), 500);
+        let Message::System { content } = &msgs[0] else { panic!("expected system") };
+        assert!(content.contains("at most 500 characters"));
+        let Message::User { content: oxibot_core::types::MessageContent::Text(text) } = &msgs[1] else {
+            panic!("expected user text");
+        };
+        assert!(text.chars().count() <= MAX_SUMMARY_INPUT);
+        assert_eq!(summarized(" Page lists 3 prices. ", 60_000), "[compacted tool output: summary of 60000 characters]\nPage lists 3 prices.");
+    }
+}
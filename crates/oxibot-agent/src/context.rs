@@ -1,22 +1,46 @@
 //! Context builder — constructs the system prompt and conversation messages.
 //!
 //! Port of nanobot's `agent/context.py`.
-//! Builds the system prompt from identity, bootstrap files, memory, and skills,
+//! Builds the system prompt from identity, bootstrap files, memory, open tasks
+//! and skills,
 //! then assembles the full message list for an LLM call.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::Utc;
-use oxibot_core::types::{ContentPart, ImageUrl, Message};
+use oxibot_core::contacts::Contact;
+use oxibot_core::tokenizer::{self, Tokenizer};
+use oxibot_core::types::{ContentPart, FileRef, ImageUrl, Message, MessageContent};
 use tracing::debug;
 
+use crate::context_providers::{self, ContextProvider, ContextRequest};
+use crate::forms::{self, Form};
 use crate::memory::MemoryStore;
+use crate::persona::Persona;
+use crate::plan::{self, Plan};
+use crate::tool_breaker::{self, DisabledTool};
 use crate::skills::SkillsLoader;
+use crate::tasks::TaskStore;
+use crate::tools::skill_secrets::Secrets;
 
 // ─────────────────────────────────────────────
 // Bootstrap / identity files
 // ─────────────────────────────────────────────
 
+/// What is left of a tool result left out to fit the context window.
+pub const TRIMMED_TOOL_RESULT: &str = "[tool result left out to fit the context window]";
+
+/// What [`ContextBuilder::fit_to_budget`] left out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Trimmed {
+    /// Tool results replaced with [`TRIMMED_TOOL_RESULT`].
+    pub tool_results: usize,
+    /// History messages dropped.
+    pub messages: usize,
+}
+
 /// Files that are automatically injected into the system prompt when present
 /// in the workspace root.
 const BOOTSTRAP_FILES: &[&str] = &[
@@ -25,6 +49,7 @@ const BOOTSTRAP_FILES: &[&str] = &[
     "USER.md",
     "TOOLS.md",
     "IDENTITY.md",
+    "README.md",
 ];
 
 // ─────────────────────────────────────────────
@@ -39,8 +64,14 @@ pub struct ContextBuilder {
     agent_name: String,
     /// Memory store for long-term + daily notes.
     memory: MemoryStore,
+    /// Persistent task list.
+    tasks: TaskStore,
     /// Skills loader for discovering and loading skill files.
     skills: SkillsLoader,
+    /// Extra system prompt sections, see [`context_providers`].
+    providers: Vec<Arc<dyn ContextProvider>>,
+    /// How long a provider may take before its section is skipped.
+    provider_timeout: Duration,
 }
 
 impl ContextBuilder {
@@ -48,12 +79,16 @@ impl ContextBuilder {
     pub fn new(workspace: impl Into<PathBuf>, agent_name: impl Into<String>) -> Self {
         let workspace = workspace.into();
         let memory = MemoryStore::new_lazy(&workspace);
+        let tasks = TaskStore::new(&workspace);
         let skills = SkillsLoader::new(&workspace, None);
         Self {
             workspace,
             agent_name: agent_name.into(),
             memory,
+            tasks,
             skills,
+            providers: Vec::new(),
+            provider_timeout: Duration::from_secs(2),
         }
     }
 
@@ -63,6 +98,26 @@ impl ContextBuilder {
         self
     }
 
+    /// Count env vars configured in `skills.secrets` as set when listing
+    /// skill availability (builder pattern).
+    pub fn with_skill_secrets(mut self, secrets: &Secrets) -> Self {
+        let skills = std::mem::replace(&mut self.skills, SkillsLoader::new(&self.workspace, None));
+        self.skills = skills.with_secrets(secrets);
+        self
+    }
+
+    /// Register an extra context provider (builder pattern).
+    pub fn with_provider(mut self, provider: Arc<dyn ContextProvider>) -> Self {
+        self.providers.push(provider);
+        self
+    }
+
+    /// Set how long each context provider may take (builder pattern).
+    pub fn with_provider_timeout(mut self, timeout: Duration) -> Self {
+        self.provider_timeout = timeout;
+        self
+    }
+
     /// Get a reference to the memory store.
     pub fn memory(&self) -> &MemoryStore {
         &self.memory
@@ -93,22 +148,24 @@ impl ContextBuilder {
             }
         }
 
-        // 3) Memory context (via MemoryStore)
+        // 3) Memory context (via MemoryStore), tidying files edited by hand
+        self.memory.reconcile();
         if let Some(memory) = self.memory.get_memory_context() {
             parts.push(memory);
         }
 
-        // 4) Always-on skills (full body injected)
-        let always_skills = self.skills.get_always_skills();
-        if !always_skills.is_empty() {
-            let always_content = self.skills.load_skills_for_context(&always_skills);
-            if !always_content.is_empty() {
-                parts.push(format!("# Active Skills\n\n{always_content}"));
-            }
+        // 4) Open tasks
+        if let Some(tasks) = self.tasks.get_tasks_context() {
+            parts.push(tasks);
+        }
+
+        // 5) Always-on skills (full body injected), reloaded when a SKILL.md changes
+        let (always_content, skills_summary) = self.skills.prompt_sections();
+        if !always_content.is_empty() {
+            parts.push(format!("# Active Skills\n\n{always_content}"));
         }
 
-        // 5) Skills summary (XML catalogue — agent uses read_file for on-demand loading)
-        let skills_summary = self.skills.build_skills_summary();
+        // 6) Skills summary (XML catalogue — agent uses read_file for on-demand loading)
         if !skills_summary.is_empty() {
             parts.push(format!(
                 "# Skills\n\n\
@@ -143,7 +200,8 @@ impl ContextBuilder {
              Be concise and helpful.\n\n\
              ## Memory\n\n\
              When you learn something important about the user or the project, \
-             persist it by writing to `{memory_file}` using the `write_file` or `edit_file` tool.\n\
+             persist it by writing to `{memory_file}` using the `write_file` or `edit_file` tool, \
+             as `- fact` bullets under `## Topic` headings.\n\
              For daily notes, write to `{workspace}/memory/{today}.md`.",
             name = self.agent_name,
         )
@@ -191,6 +249,174 @@ impl ContextBuilder {
         messages.push(Message::tool_result(tool_call_id, result));
     }
 
+    /// Append the session scratch directory to the system prompt.
+    pub fn add_scratch_dir_info(messages: &mut [Message], scratch_dir: &Path) {
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str(&format!(
+                "\nScratch directory: `{}`\n\
+                 Relative file paths resolve inside this directory, which is private to \
+                 this conversation. Use absolute paths for shared workspace files.",
+                scratch_dir.display()
+            ));
+        }
+    }
+
+    /// Append the session's pinned notes to the system prompt.
+    ///
+    /// Pinned notes live outside the message history, so they stay visible
+    /// no matter how much of the history is trimmed.
+    pub fn add_pinned_notes(messages: &mut [Message], pins: &[String]) {
+        if pins.is_empty() {
+            return;
+        }
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str("\n\n## Pinned Notes\n\n");
+            for (i, note) in pins.iter().enumerate() {
+                content.push_str(&format!("{}. {}\n", i + 1, note));
+            }
+        }
+    }
+
+    /// Make `messages` fit in `max_tokens`, where `messages[1..=history]`
+    /// is the session history.
+    ///
+    /// Tool results are left out first, oldest first, then the oldest
+    /// history. The system prompt and everything after the history (the
+    /// current message and this turn's tool calls) are kept, and what is
+    /// left of the history starts with a user message. `history` is updated
+    /// to the number of history messages kept.
+    pub fn fit_to_budget(
+        messages: &mut Vec<Message>,
+        history: &mut usize,
+        tokenizer: &dyn Tokenizer,
+        max_tokens: usize,
+    ) -> Trimmed {
+        let mut trimmed = Trimmed::default();
+        let mut total = tokenizer::count_messages(tokenizer, messages);
+        let placeholder = tokenizer.count(TRIMMED_TOOL_RESULT);
+        for message in messages.iter_mut() {
+            if total <= max_tokens {
+                break;
+            }
+            let Message::Tool { content, .. } = message else { continue };
+            let tokens = tokenizer.count(content);
+            if tokens <= placeholder {
+                continue;
+            }
+            *content = TRIMMED_TOOL_RESULT.to_string();
+            total = total - tokens + placeholder;
+            trimmed.tool_results += 1;
+        }
+        while total > max_tokens && *history > 0 {
+            total -= tokenizer::count_message(tokenizer, &messages.remove(1));
+            *history -= 1;
+            trimmed.messages += 1;
+        }
+        // Don't start halfway through an exchange
+        while trimmed.messages > 0 && *history > 0 && !matches!(messages[1], Message::User { .. }) {
+            messages.remove(1);
+            *history -= 1;
+            trimmed.messages += 1;
+        }
+        trimmed
+    }
+
+    /// Append the sections of the registered context providers to the
+    /// system prompt, in order and within their token budgets.
+    pub async fn add_provided_sections(
+        &self,
+        messages: &mut [Message],
+        request: &ContextRequest,
+        tokenizer: &dyn Tokenizer,
+    ) {
+        if self.providers.is_empty() {
+            return;
+        }
+        let sections =
+            context_providers::collect_sections(&self.providers, request, tokenizer, self.provider_timeout).await;
+        if let Some(Message::System { content }) = messages.first_mut() {
+            for section in sections {
+                content.push_str("\n\n");
+                content.push_str(section.trim_end());
+                content.push('\n');
+            }
+        }
+    }
+
+    /// Append plan-mode instructions (and the current plan) to the system prompt.
+    pub fn add_plan(messages: &mut [Message], plan: Option<&Plan>) {
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str("\n\n");
+            content.push_str(&plan::prompt_section(plan));
+        }
+    }
+
+    /// Tell the model which tools are switched off because they keep failing.
+    pub fn add_disabled_tools(messages: &mut [Message], disabled: &[DisabledTool]) {
+        if disabled.is_empty() {
+            return;
+        }
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str("\n\n");
+            content.push_str(&tool_breaker::prompt_section(disabled));
+        }
+    }
+
+    /// Append the form being filled in, with the fields still missing.
+    pub fn add_form(messages: &mut [Message], form: Option<&Form>) {
+        let Some(form) = form else {
+            return;
+        };
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str("\n\n");
+            content.push_str(&forms::prompt_section(form));
+        }
+    }
+
+    /// Attach uploaded files to the current (last) user message.
+    pub fn add_files(messages: &mut [Message], files: &[FileRef]) {
+        if files.is_empty() {
+            return;
+        }
+        let Some(Message::User { content }) = messages.iter_mut().rev().find(|m| matches!(m, Message::User { .. }))
+        else {
+            return;
+        };
+        let mut parts: Vec<ContentPart> = files
+            .iter()
+            .map(|file| ContentPart::File { file: file.clone() })
+            .collect();
+        match std::mem::replace(content, MessageContent::Parts(Vec::new())) {
+            MessageContent::Text(text) => parts.push(ContentPart::Text { text }),
+            MessageContent::Parts(rest) => parts.extend(rest),
+        }
+        *content = MessageContent::Parts(parts);
+    }
+
+    /// Append the active persona's instructions to the system prompt.
+    pub fn add_persona(messages: &mut [Message], persona: &Persona) {
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str(&format!("\n\n## Persona\n\n{}\n", persona.prompt));
+        }
+    }
+
+    /// Append who the user is (from the contact book) to the system prompt.
+    ///
+    /// Identities and notes come from the contact, so they are the same on
+    /// every channel the person writes from.
+    pub fn add_contact_info(messages: &mut [Message], contact: &Contact) {
+        if let Some(Message::System { content }) = messages.first_mut() {
+            content.push_str(&format!(
+                "\n\n## Current Contact\n\nYou are talking with {}. Known ids: {}.\n",
+                contact.name,
+                contact.ids.join(", ")
+            ));
+            if !contact.notes.is_empty() {
+                content.push_str(&format!("Notes: {}\n", contact.notes));
+            }
+        }
+    }
+
     /// Add an assistant message (with optional tool calls) to the message list.
     pub fn add_assistant_message(
         messages: &mut Vec<Message>,
@@ -277,7 +503,7 @@ fn base64_encode(data: &[u8]) -> String {
     use std::io::Write;
     // Simple base64 encoder without external dependency
     const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut out = Vec::with_capacity((data.len() + 2) / 3 * 4);
+    let mut out = Vec::with_capacity(data.len().div_ceil(3) * 4);
     for chunk in data.chunks(3) {
         let b0 = chunk[0] as u32;
         let b1 = if chunk.len() > 1 { chunk[1] as u32 } else { 0 };
@@ -362,6 +588,18 @@ mod tests {
         assert!(prompt.contains("Long-term Memory"));
     }
 
+    #[test]
+    fn test_build_system_prompt_with_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ContextBuilder::new(dir.path(), "Oxibot");
+        assert!(!ctx.build_system_prompt().contains("# Tasks"));
+
+        TaskStore::new(dir.path()).add("Renew domain", None).unwrap();
+        let prompt = ctx.build_system_prompt();
+        assert!(prompt.contains("You have 1 open task."));
+        assert!(prompt.contains("- #1 Renew domain"));
+    }
+
     #[test]
     fn test_build_messages_text_only() {
         let dir = tempfile::tempdir().unwrap();
@@ -389,6 +627,141 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_add_pinned_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ContextBuilder::new(dir.path(), "Oxibot");
+        let mut msgs = ctx.build_messages(&[], "hello", &[], "cli", "direct");
+        ContextBuilder::add_pinned_notes(&mut msgs, &["Budget is 500".into(), "Deadline Friday".into()]);
+        if let Message::System { content } = &msgs[0] {
+            assert!(content.contains("## Pinned Notes"));
+            assert!(content.contains("1. Budget is 500\n2. Deadline Friday"));
+        } else {
+            panic!("First message should be System");
+        }
+    }
+
+    #[test]
+    fn test_add_contact_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ContextBuilder::new(dir.path(), "Oxibot");
+        let mut msgs = ctx.build_messages(&[], "hello", &[], "slack", "C1");
+        let contact = Contact {
+            name: "Alice".into(),
+            ids: vec!["telegram:123".into(), "slack:U45".into()],
+            notes: "Prefers metric units".into(),
+        };
+        ContextBuilder::add_contact_info(&mut msgs, &contact);
+        if let Message::System { content } = &msgs[0] {
+            assert!(content.contains("You are talking with Alice. Known ids: telegram:123, slack:U45."));
+            assert!(content.contains("Notes: Prefers metric units"));
+        } else {
+            panic!("First message should be System");
+        }
+    }
+
+    #[test]
+    fn test_add_persona() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ContextBuilder::new(dir.path(), "Oxibot");
+        let mut msgs = ctx.build_messages(&[], "hello", &[], "cli", "direct");
+        let persona = crate::persona::find("concise-assistant").unwrap();
+        ContextBuilder::add_persona(&mut msgs, persona);
+        if let Message::System { content } = &msgs[0] {
+            assert!(content.contains(&format!("## Persona\n\n{}", persona.prompt)));
+        } else {
+            panic!("First message should be System");
+        }
+    }
+
+    #[test]
+    fn test_add_scratch_dir_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ContextBuilder::new(dir.path(), "Oxibot");
+        let mut msgs = ctx.build_messages(&[], "hello", &[], "cli", "direct");
+        ContextBuilder::add_scratch_dir_info(&mut msgs, Path::new("/ws/sessions/cli_direct"));
+        if let Message::System { content } = &msgs[0] {
+            assert!(content.contains("Scratch directory: `/ws/sessions/cli_direct`"));
+        } else {
+            panic!("First message should be System");
+        }
+    }
+
+    #[test]
+    fn test_add_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = ContextBuilder::new(dir.path(), "Oxibot");
+        let mut msgs = ctx.build_messages(&[Message::user("earlier")], "summarize it", &[], "cli", "direct");
+        let file = FileRef {
+            file_id: "file-1".into(),
+            filename: Some("report.pdf".into()),
+        };
+        ContextBuilder::add_files(&mut msgs, std::slice::from_ref(&file));
+        assert_eq!(msgs[1], Message::user("earlier"));
+        let json = serde_json::to_value(&msgs[2]).unwrap();
+        assert_eq!(json["content"][0], serde_json::json!({"type": "file", "file": {"file_id": "file-1", "filename": "report.pdf"}}));
+        assert_eq!(json["content"][1]["text"], "summarize it");
+    }
+
+    #[test]
+    fn test_fit_to_budget() {
+        let tokenizer = tokenizer::HeuristicTokenizer;
+        let turn = |n: usize| {
+            vec![Message::user("q".repeat(40 * n)), Message::assistant("a".repeat(40 * n))]
+        };
+        let mut messages = vec![Message::system("s".repeat(40))];
+        messages.extend(turn(1));
+        messages.extend(turn(2));
+        messages.push(Message::user("now"));
+        let full = tokenizer::count_messages(&tokenizer, &messages);
+
+        let dropped = |messages| Trimmed { tool_results: 0, messages };
+
+        let mut fits = messages.clone();
+        let mut history = 4;
+        assert_eq!(ContextBuilder::fit_to_budget(&mut fits, &mut history, &tokenizer, full), dropped(0));
+        assert_eq!(fits.len(), 6);
+
+        // One token short: the oldest exchange goes, not half of it
+        assert_eq!(ContextBuilder::fit_to_budget(&mut messages, &mut history, &tokenizer, full - 1), dropped(2));
+        assert_eq!(messages.len(), 4);
+        assert_eq!(history, 2);
+        assert!(matches!(&messages[1], Message::User { .. }));
+
+        // Nothing fits: system prompt and current message stay
+        assert_eq!(ContextBuilder::fit_to_budget(&mut messages, &mut history, &tokenizer, 1), dropped(2));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(history, 0);
+        assert_eq!(messages[1], Message::user("now"));
+    }
+
+    #[test]
+    fn test_fit_to_budget_trims_tool_results_first() {
+        let tokenizer = tokenizer::HeuristicTokenizer;
+        let mut messages = vec![
+            Message::system("s".repeat(40)),
+            Message::user("q".repeat(400)),
+            Message::assistant("a".repeat(400)),
+            Message::user("now"),
+        ];
+        ContextBuilder::add_tool_result(&mut messages, "call-1", &"r".repeat(4000));
+        ContextBuilder::add_tool_result(&mut messages, "call-2", &"r".repeat(4000));
+        let full = tokenizer::count_messages(&tokenizer, &messages);
+
+        // The older result is enough; the history stays
+        let mut history = 2;
+        let trimmed = ContextBuilder::fit_to_budget(&mut messages, &mut history, &tokenizer, full - 100);
+        assert_eq!(trimmed, Trimmed { tool_results: 1, messages: 0 });
+        assert_eq!(messages[4], Message::tool_result("call-1", TRIMMED_TOOL_RESULT));
+        assert_eq!(messages[5], Message::tool_result("call-2", "r".repeat(4000)));
+
+        // Then the other result, then the history; the current message stays
+        let trimmed = ContextBuilder::fit_to_budget(&mut messages, &mut history, &tokenizer, 50);
+        assert_eq!(trimmed, Trimmed { tool_results: 1, messages: 2 });
+        assert_eq!(messages[1], Message::user("now"));
+        assert_eq!(messages.len(), 4);
+    }
+
     #[test]
     fn test_add_tool_result() {
         let mut msgs = vec![Message::user("test")];
@@ -0,0 +1,292 @@
+//! Context providers — pluggable sections of the system prompt.
+//!
+//! A [`ContextProvider`] contributes one section to the system prompt of
+//! each turn (the workspace's git status, upcoming calendar events, ...).
+//! Providers are registered on the [`ContextBuilder`](crate::context::ContextBuilder)
+//! and run concurrently before the first LLM call of a turn. Sections are
+//! added in [`order`](ContextProvider::order), each cut to its token
+//! budget; a provider that fails or is too slow is left out of that turn.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+
+use oxibot_core::tokenizer::Tokenizer;
+
+use crate::compaction::Limit;
+use crate::tools::calendar::{CalendarBackend, UserTimezone};
+
+/// Default token budget of a section.
+pub const DEFAULT_MAX_TOKENS: usize = 500;
+
+/// Default position of a section; lower comes first.
+pub const DEFAULT_ORDER: i32 = 100;
+
+/// Appended to a section cut to its budget.
+const TRUNCATED_MARKER: &str = "\n[…truncated]";
+
+/// The turn a section is built for.
+#[derive(Clone, Debug, Default)]
+pub struct ContextRequest {
+    pub channel: String,
+    pub chat_id: String,
+    pub session_key: String,
+    /// The user's message.
+    pub text: String,
+}
+
+/// A source of one system prompt section.
+#[async_trait]
+pub trait ContextProvider: Send + Sync {
+    /// Name for logs (e.g. `"git_status"`).
+    fn name(&self) -> &str;
+
+    /// Position among the sections; lower comes first.
+    fn order(&self) -> i32 {
+        DEFAULT_ORDER
+    }
+
+    /// Longest section, in tokens; longer ones are truncated.
+    fn max_tokens(&self) -> usize {
+        DEFAULT_MAX_TOKENS
+    }
+
+    /// The section, with its own `## ` heading (`None` = nothing to add).
+    async fn provide(&self, request: &ContextRequest) -> Result<Option<String>>;
+}
+
+/// Run `providers` concurrently and return their sections in order, each
+/// within its budget. Providers that fail or take longer than `timeout`
+/// are skipped.
+pub async fn collect_sections(
+    providers: &[Arc<dyn ContextProvider>],
+    request: &ContextRequest,
+    tokenizer: &dyn Tokenizer,
+    timeout: Duration,
+) -> Vec<String> {
+    let mut tasks = JoinSet::new();
+    for (i, provider) in providers.iter().enumerate() {
+        let (provider, request) = (provider.clone(), request.clone());
+        tasks.spawn(async move {
+            let result = tokio::time::timeout(timeout, provider.provide(&request)).await;
+            (i, result)
+        });
+    }
+
+    let mut sections: Vec<(i32, usize, String)> = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((i, result)) = joined else { continue };
+        let provider = &providers[i];
+        match result {
+            Ok(Ok(Some(section))) if !section.trim().is_empty() => {
+                let limit = Limit::Tokens(provider.max_tokens(), tokenizer);
+                let section = if limit.exceeded_by(&section) {
+                    debug!(provider = provider.name(), "context section over budget, truncated");
+                    truncate(&section, limit.max_chars(&section))
+                } else {
+                    section
+                };
+                sections.push((provider.order(), i, section));
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => warn!(provider = provider.name(), error = %e, "context provider failed"),
+            Err(_) => warn!(provider = provider.name(), timeout_ms = timeout.as_millis() as u64, "context provider timed out"),
+        }
+    }
+    sections.sort_by_key(|(order, i, _)| (*order, *i));
+    debug!(sections = sections.len(), "collected context sections");
+    sections.into_iter().map(|(_, _, section)| section).collect()
+}
+
+/// The start of `section`, about `max_chars` characters in total.
+fn truncate(section: &str, max_chars: usize) -> String {
+    let keep = max_chars.saturating_sub(TRUNCATED_MARKER.len()).max(1);
+    let end = section.char_indices().nth(keep).map_or(section.len(), |(i, _)| i);
+    format!("{}{TRUNCATED_MARKER}", section[..end].trim_end())
+}
+
+// ─────────────────────────────────────────────
+// Git status
+// ─────────────────────────────────────────────
+
+/// `git status` of the workspace, when it is a repository.
+pub struct GitStatusProvider {
+    workspace: PathBuf,
+}
+
+impl GitStatusProvider {
+    pub fn new(workspace: impl Into<PathBuf>) -> Self {
+        Self {
+            workspace: workspace.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ContextProvider for GitStatusProvider {
+    fn name(&self) -> &str {
+        "git_status"
+    }
+
+    fn max_tokens(&self) -> usize {
+        300
+    }
+
+    async fn provide(&self, _request: &ContextRequest) -> Result<Option<String>> {
+        let output = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(&self.workspace)
+            .args(["status", "--short", "--branch"])
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        // Not a repository (or no git): nothing to say
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let status = String::from_utf8_lossy(&output.stdout);
+        let mut lines = status.lines();
+        let branch = lines.next().unwrap_or_default().trim_start_matches("## ");
+        let changes: Vec<&str> = lines.collect();
+        let mut section = format!("## Workspace Git Status\n\nBranch: {branch}\n");
+        if changes.is_empty() {
+            section.push_str("No uncommitted changes.\n");
+        } else {
+            section.push_str(&format!("Uncommitted changes ({}):\n```\n{}\n```\n", changes.len(), changes.join("\n")));
+        }
+        Ok(Some(section))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Calendar peek
+// ─────────────────────────────────────────────
+
+/// Calendar events in the next few hours.
+pub struct CalendarPeekProvider {
+    backend: Arc<dyn CalendarBackend>,
+    timezone: UserTimezone,
+    hours: i64,
+}
+
+impl CalendarPeekProvider {
+    pub fn new(backend: Arc<dyn CalendarBackend>, timezone: UserTimezone, hours: u32) -> Self {
+        Self {
+            backend,
+            timezone,
+            hours: i64::from(hours.max(1)),
+        }
+    }
+}
+
+#[async_trait]
+impl ContextProvider for CalendarPeekProvider {
+    fn name(&self) -> &str {
+        "calendar_peek"
+    }
+
+    async fn provide(&self, _request: &ContextRequest) -> Result<Option<String>> {
+        let tz = self.timezone.get();
+        let now = Utc::now();
+        let mut events = self
+            .backend
+            .list_events(now, now + chrono::Duration::hours(self.hours), tz)
+            .await?;
+        events.sort_by_key(|e| e.sort_key(tz));
+        let mut section = format!("## Upcoming Events (next {}h)\n\n", self.hours);
+        if events.is_empty() {
+            section.push_str("Nothing scheduled.\n");
+        }
+        for event in &events {
+            section.push_str(&format!("- {}\n", event.summary(tz)));
+        }
+        Ok(Some(section))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::tokenizer::HeuristicTokenizer;
+
+    struct Fixed {
+        name: &'static str,
+        order: i32,
+        delay_ms: u64,
+        section: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl ContextProvider for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn order(&self) -> i32 {
+            self.order
+        }
+
+        fn max_tokens(&self) -> usize {
+            20
+        }
+
+        async fn provide(&self, request: &ContextRequest) -> Result<Option<String>> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            match self.section {
+                Some("fail") => anyhow::bail!("boom"),
+                Some(s) => Ok(Some(format!("{s} for {}", request.chat_id))),
+                None => Ok(None),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_sections_orders_budgets_and_skips() {
+        let provider = |name, order, delay_ms, section| {
+            Arc::new(Fixed {
+                name,
+                order,
+                delay_ms,
+                section,
+            }) as Arc<dyn ContextProvider>
+        };
+        let long = "word ".repeat(100).leak();
+        let providers = vec![
+            provider("late", 200, 0, Some("## Late")),
+            provider("early", 10, 30, Some("## Early")),
+            provider("empty", 0, 0, None),
+            provider("broken", 0, 0, Some("fail")),
+            provider("slow", 0, 1000, Some("## Slow")),
+            provider("long", 100, 0, Some(long)),
+        ];
+        let request = ContextRequest {
+            chat_id: "42".into(),
+            ..Default::default()
+        };
+        let sections = collect_sections(&providers, &request, &HeuristicTokenizer, Duration::from_millis(200)).await;
+
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0], "## Early for 42");
+        assert!(sections[1].starts_with("word word"), "{}", sections[1]);
+        assert!(sections[1].ends_with(TRUNCATED_MARKER) && sections[1].len() < 100, "{}", sections[1]);
+        assert_eq!(sections[2], "## Late for 42");
+    }
+
+    #[tokio::test]
+    async fn test_git_status_outside_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = GitStatusProvider::new(dir.path());
+        // No git installed or not a repository: no section either way
+        assert!(provider.provide(&ContextRequest::default()).await.unwrap_or(None).is_none());
+    }
+}
@@ -0,0 +1,408 @@
+//! Workspace file index — names, sizes and modification times, optionally
+//! a trigram index of file contents, kept current in the background.
+//!
+//! Behind the `find_files` tool: looking files up in memory is instant,
+//! where `exec find …` walks the whole tree on every call and can time out
+//! on large workspaces. The first query builds the index; after that it is
+//! rescanned every `refreshSecs`, re-reading only files whose size or
+//! modification time changed.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, SystemTime};
+
+use oxibot_core::config::schema::FileIndexConfig;
+use tracing::{debug, warn};
+
+/// Files indexed at most, to bound memory on huge trees.
+const MAX_FILES: usize = 200_000;
+
+/// Bytes checked for NUL to tell binary files apart.
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// An indexed file.
+#[derive(Clone, Debug)]
+pub struct FileEntry {
+    pub size: u64,
+    pub modified: SystemTime,
+    /// Content trigrams (empty when contents are not indexed).
+    trigrams: Vec<u32>,
+}
+
+/// A file lookup.
+#[derive(Clone, Debug, Default)]
+pub struct FileQuery {
+    /// Glob (`*.md`, `notes/**/*.txt`) or plain text to find in paths.
+    pub pattern: Option<String>,
+    /// Text the file must contain (case-insensitive).
+    pub contains: Option<String>,
+    /// Only files under this workspace-relative folder.
+    pub under: Option<String>,
+    pub limit: usize,
+}
+
+/// Files matching a [`FileQuery`].
+#[derive(Clone, Debug, Default)]
+pub struct FileMatches {
+    /// Path relative to the workspace, size and modification time.
+    pub files: Vec<(String, u64, SystemTime)>,
+    /// Matches beyond `limit` (a lower bound for content searches, which
+    /// stop reading at the limit).
+    pub more: usize,
+}
+
+#[derive(Default)]
+struct IndexState {
+    files: BTreeMap<String, FileEntry>,
+    /// Trigram → files containing it.
+    postings: HashMap<u32, BTreeSet<String>>,
+}
+
+struct Inner {
+    root: PathBuf,
+    config: FileIndexConfig,
+    state: RwLock<IndexState>,
+    started: AtomicBool,
+}
+
+/// Index of the files under a workspace. Clones share the index.
+#[derive(Clone)]
+pub struct FileIndex {
+    inner: Arc<Inner>,
+}
+
+impl FileIndex {
+    pub fn new(root: &Path, config: FileIndexConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                root: root.to_path_buf(),
+                config,
+                state: RwLock::new(IndexState::default()),
+                started: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Build the index on first use and keep it fresh in the background.
+    pub async fn ensure_started(&self) {
+        if self.inner.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let index = self.clone();
+        let _ = tokio::task::spawn_blocking(move || index.refresh()).await;
+
+        let weak: Weak<Inner> = Arc::downgrade(&self.inner);
+        let interval = Duration::from_secs(self.inner.config.refresh_secs.max(1));
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                // Stop once the agent that owns the index is gone
+                let Some(inner) = weak.upgrade() else { break };
+                let index = FileIndex { inner };
+                let _ = tokio::task::spawn_blocking(move || index.refresh()).await;
+            }
+        });
+    }
+
+    /// Rescan the workspace; only new or changed files are read. Returns
+    /// the number of files added or changed, and removed.
+    pub fn refresh(&self) -> (usize, usize) {
+        let inner = &self.inner;
+        let mut seen: HashMap<String, (u64, SystemTime)> = HashMap::new();
+        let mut dirs = vec![inner.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(relative) = path.strip_prefix(&inner.root) else { continue };
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let Ok(file_type) = entry.file_type() else { continue };
+                if file_type.is_dir() {
+                    if !self.is_excluded(&format!("{relative}/")) {
+                        dirs.push(path);
+                    }
+                } else if file_type.is_file() && !self.is_excluded(&relative) {
+                    if seen.len() >= MAX_FILES {
+                        warn!(root = %inner.root.display(), "file index full, some files are not indexed");
+                        dirs.clear();
+                        break;
+                    }
+                    let Ok(meta) = entry.metadata() else { continue };
+                    let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    seen.insert(relative, (meta.len(), modified));
+                }
+            }
+        }
+
+        // Read changed files without blocking queries
+        let changed: Vec<(String, FileEntry)> = {
+            let state = inner.state.read().unwrap();
+            seen.iter()
+                .filter(|(path, (size, modified))| {
+                    state
+                        .files
+                        .get(*path)
+                        .is_none_or(|e| e.size != *size || e.modified != *modified)
+                })
+                .map(|(path, &(size, modified))| {
+                    let trigrams = self.content_trigrams(path, size);
+                    (path.clone(), FileEntry { size, modified, trigrams })
+                })
+                .collect()
+        };
+
+        let mut state = inner.state.write().unwrap();
+        let removed: Vec<String> = state.files.keys().filter(|p| !seen.contains_key(*p)).cloned().collect();
+        for path in &removed {
+            remove_file(&mut state, path);
+        }
+        let updated = changed.len();
+        for (path, entry) in changed {
+            remove_file(&mut state, &path);
+            for trigram in &entry.trigrams {
+                state.postings.entry(*trigram).or_default().insert(path.clone());
+            }
+            state.files.insert(path, entry);
+        }
+        if updated > 0 || !removed.is_empty() {
+            debug!(updated, removed = removed.len(), files = state.files.len(), "file index refreshed");
+        }
+        (updated, removed.len())
+    }
+
+    /// Files matching `query`, in path order.
+    pub fn find(&self, query: &FileQuery) -> FileMatches {
+        let state = self.inner.state.read().unwrap();
+        let pattern = query.pattern.as_deref().map(str::to_lowercase).filter(|p| !p.is_empty());
+        let under = query
+            .under
+            .as_deref()
+            .map(|u| u.trim_matches('/'))
+            .filter(|u| !u.is_empty() && *u != ".")
+            .map(|u| format!("{u}/"));
+        let needle = query.contains.as_deref().map(str::to_lowercase).filter(|c| !c.is_empty());
+
+        // Content searches start from the files holding every trigram
+        let candidates: Option<BTreeSet<String>> = match needle {
+            Some(ref needle) if self.inner.config.content && needle.len() >= 3 => {
+                let mut sets = trigrams(needle.as_bytes())
+                    .into_iter()
+                    .map(|t| state.postings.get(&t).cloned().unwrap_or_default());
+                let first = sets.next().unwrap_or_default();
+                Some(sets.fold(first, |acc, set| acc.intersection(&set).cloned().collect()))
+            }
+            _ => None,
+        };
+        let paths: Box<dyn Iterator<Item = &String>> = match candidates {
+            Some(ref set) => Box::new(set.iter()),
+            None => Box::new(state.files.keys()),
+        };
+
+        let mut matches = FileMatches::default();
+        for path in paths {
+            let lower = path.to_lowercase();
+            if under.as_ref().is_some_and(|u| !lower.starts_with(&u.to_lowercase())) {
+                continue;
+            }
+            if pattern.as_ref().is_some_and(|p| !path_matches(p, &lower)) {
+                continue;
+            }
+            let Some(entry) = state.files.get(path) else { continue };
+            if let Some(ref needle) = needle {
+                if matches.files.len() >= query.limit {
+                    // Don't read every remaining file just to count them
+                    matches.more = 1;
+                    break;
+                }
+                let Ok(text) = std::fs::read(self.inner.root.join(path)) else { continue };
+                if !String::from_utf8_lossy(&text).to_lowercase().contains(needle.as_str()) {
+                    continue;
+                }
+            }
+            if matches.files.len() < query.limit {
+                matches.files.push((path.clone(), entry.size, entry.modified));
+            } else {
+                matches.more += 1;
+            }
+        }
+        matches
+    }
+
+    /// Number of indexed files.
+    pub fn len(&self) -> usize {
+        self.inner.state.read().unwrap().files.len()
+    }
+
+    /// Whether nothing is indexed (yet).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Trigrams of a text file, if contents are indexed.
+    fn content_trigrams(&self, path: &str, size: u64) -> Vec<u32> {
+        let config = &self.inner.config;
+        if !config.content || size > config.max_file_kb * 1024 {
+            return Vec::new();
+        }
+        let Ok(data) = std::fs::read(self.inner.root.join(path)) else { return Vec::new() };
+        if data[..data.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+            return Vec::new();
+        }
+        trigrams(String::from_utf8_lossy(&data).to_lowercase().as_bytes())
+    }
+
+    /// Whether `path` is skipped: `dir/` patterns match at any depth,
+    /// `*.ext` by suffix, anything else the exact path.
+    fn is_excluded(&self, path: &str) -> bool {
+        self.inner.config.exclude.iter().any(|pattern| {
+            if let Some(ext) = pattern.strip_prefix('*') {
+                path.ends_with(ext)
+            } else if pattern.ends_with('/') {
+                path.starts_with(pattern.as_str()) || path.contains(&format!("/{pattern}"))
+            } else {
+                path == pattern
+            }
+        })
+    }
+}
+
+fn remove_file(state: &mut IndexState, path: &str) {
+    if let Some(old) = state.files.remove(path) {
+        for trigram in old.trigrams {
+            if let Some(set) = state.postings.get_mut(&trigram) {
+                set.remove(path);
+                if set.is_empty() {
+                    state.postings.remove(&trigram);
+                }
+            }
+        }
+    }
+}
+
+/// Distinct byte trigrams of `text`.
+fn trigrams(text: &[u8]) -> Vec<u32> {
+    let set: HashSet<u32> = text
+        .windows(3)
+        .map(|w| u32::from(w[0]) << 16 | u32::from(w[1]) << 8 | u32::from(w[2]))
+        .collect();
+    set.into_iter().collect()
+}
+
+/// Whether a (lowercased) path matches a (lowercased) pattern. Globs with
+/// a `/` match the whole path, other globs the file name; plain text
+/// matches anywhere in the path.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return path.contains(pattern);
+    }
+    if pattern.contains('/') {
+        glob_match(pattern.as_bytes(), path.as_bytes())
+    } else {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        glob_match(pattern.as_bytes(), name.as_bytes())
+    }
+}
+
+/// `*` and `?` stay within a path segment, `**` crosses segments.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => (0..=text.len())
+            .filter(|&i| i == 0 || text[i - 1] == b'/')
+            .any(|i| glob_match(rest, &text[i..])),
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| glob_match(rest, &text[i..])),
+        [b'?', rest @ ..] => text.first().is_some_and(|&c| c != b'/') && glob_match(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pattern: Option<&str>, contains: Option<&str>) -> FileQuery {
+        FileQuery {
+            pattern: pattern.map(str::to_string),
+            contains: contains.map(str::to_string),
+            under: None,
+            limit: 10,
+        }
+    }
+
+    fn names(matches: &FileMatches) -> Vec<&str> {
+        matches.files.iter().map(|(p, _, _)| p.as_str()).collect()
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(path_matches("*.md", "notes/todo.md"));
+        assert!(!path_matches("*.md", "notes/todo.txt"));
+        assert!(path_matches("notes/*.md", "notes/todo.md"));
+        assert!(!path_matches("notes/*.md", "notes/old/todo.md"));
+        assert!(path_matches("notes/**/*.md", "notes/old/todo.md"));
+        assert!(path_matches("**/todo.md", "todo.md"));
+        assert!(!path_matches("**/todo.md", "mytodo.md"));
+        assert!(path_matches("t?do.*", "todo.md"));
+        assert!(path_matches("old/to", "notes/old/todo.md"));
+    }
+
+    #[test]
+    fn test_index_and_incremental_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("notes/old")).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/x")).unwrap();
+        std::fs::write(dir.path().join("notes/todo.md"), "Buy Oat milk").unwrap();
+        std::fs::write(dir.path().join("notes/old/ideas.md"), "oat-based recipes").unwrap();
+        std::fs::write(dir.path().join("README.txt"), "nothing here").unwrap();
+        std::fs::write(dir.path().join("node_modules/x/index.md"), "oat").unwrap();
+
+        let config = FileIndexConfig {
+            content: true,
+            ..Default::default()
+        };
+        let index = FileIndex::new(dir.path(), config);
+        assert_eq!(index.refresh(), (3, 0));
+        assert_eq!(index.len(), 3);
+
+        assert_eq!(names(&index.find(&query(Some("*.md"), None))), ["notes/old/ideas.md", "notes/todo.md"]);
+        assert_eq!(names(&index.find(&query(None, Some("OAT")))), ["notes/old/ideas.md", "notes/todo.md"]);
+        assert_eq!(names(&index.find(&query(Some("todo"), Some("milk")))), ["notes/todo.md"]);
+        let under = FileQuery {
+            under: Some("notes/old/".into()),
+            ..query(None, None)
+        };
+        assert_eq!(names(&index.find(&under)), ["notes/old/ideas.md"]);
+
+        // Only the changed and removed files are touched
+        std::fs::write(dir.path().join("notes/todo.md"), "call the bank").unwrap();
+        std::fs::remove_file(dir.path().join("README.txt")).unwrap();
+        assert_eq!(index.refresh(), (1, 1));
+        assert_eq!(names(&index.find(&query(None, Some("oat")))), ["notes/old/ideas.md"]);
+        assert_eq!(names(&index.find(&query(None, Some("bank")))), ["notes/todo.md"]);
+        assert_eq!(index.refresh(), (0, 0));
+    }
+
+    #[test]
+    fn test_limit_reports_more() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..5 {
+            std::fs::write(dir.path().join(format!("f{i}.txt")), "x").unwrap();
+        }
+        let index = FileIndex::new(dir.path(), FileIndexConfig::default());
+        index.refresh();
+        let matches = index.find(&FileQuery {
+            limit: 2,
+            ..query(Some("*.txt"), None)
+        });
+        assert_eq!((matches.files.len(), matches.more), (2, 3));
+    }
+}
@@ -0,0 +1,405 @@
+//! Forms — collect a set of fields from the user over several turns.
+//!
+//! A form is a list of fields to ask for, each with a type. The agent
+//! starts one with the `form` tool, or an API caller does by sending an
+//! inbound message carrying the form in its [`START_KEY`] metadata
+//! (`POST /api/forms` on the gateway). The form is stored on the session
+//! and shown to the agent on every turn with the fields still missing;
+//! answers are validated against the field type before they are recorded.
+//!
+//! Once every required field is filled, the completed form is appended to
+//! `<workspace>/forms/<name>.jsonl`, posted to the form's webhook, and
+//! returned to the agent — which passes it on to the form's tool, if it
+//! names one.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use oxibot_core::datetime::parse_day;
+use oxibot_core::session::manager::SessionManager;
+
+/// Session metadata field holding the form being filled (JSON).
+pub const SESSION_FIELD: &str = "form";
+
+/// Inbound metadata key: a form (JSON [`FormSpec`]) to start in the chat
+/// before the message is answered.
+pub const START_KEY: &str = "form";
+
+/// Most fields a form may have.
+pub const MAX_FIELDS: usize = 30;
+
+/// What a field holds, and how answers are checked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    #[default]
+    Text,
+    Number,
+    Integer,
+    Boolean,
+    Email,
+    Phone,
+    /// A day, stored as YYYY-MM-DD ("tomorrow" and weekdays are resolved).
+    Date,
+    /// One of the field's `options`.
+    Choice,
+}
+
+/// One field to collect.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FormField {
+    pub name: String,
+    /// What to ask for (defaults to the name).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    #[serde(default, rename = "type")]
+    pub kind: FieldKind,
+    /// Allowed answers of a `choice` field.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl FormField {
+    /// The label, or the name when there is none.
+    pub fn label(&self) -> &str {
+        if self.label.is_empty() {
+            &self.name
+        } else {
+            &self.label
+        }
+    }
+
+    /// Short description of the expected answer, e.g. `email` or
+    /// `one of: small, large; optional`.
+    fn hint(&self) -> String {
+        let kind = match self.kind {
+            FieldKind::Choice => format!("one of: {}", self.options.join(", ")),
+            kind => serde_json::to_value(kind)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default(),
+        };
+        if self.required {
+            kind
+        } else {
+            format!("{kind}; optional")
+        }
+    }
+}
+
+/// The definition of a form.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FormSpec {
+    /// Identifies the form; completed forms go to `forms/<name>.jsonl`.
+    pub name: String,
+    pub fields: Vec<FormField>,
+    /// Name of the webhook (`tools.forms.webhooks`) the completed form is
+    /// POSTed to as JSON.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub webhook: String,
+    /// Tool the agent passes the completed values to.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tool: String,
+}
+
+/// A form being filled in a conversation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Form {
+    pub spec: FormSpec,
+    /// Validated answers, by field name.
+    pub values: BTreeMap<String, Value>,
+    pub created: DateTime<Utc>,
+}
+
+impl Form {
+    /// A new, empty form. Fails when `spec` has no fields, too many, a
+    /// repeated or unusable name, or a choice without options.
+    pub fn new(mut spec: FormSpec) -> Result<Self> {
+        spec.name = spec.name.trim().to_string();
+        if spec.name.is_empty()
+            || !spec
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        {
+            bail!("form name '{}' must be letters, digits, '-' or '_'", spec.name);
+        }
+        if spec.fields.is_empty() {
+            bail!("a form needs at least one field");
+        }
+        if spec.fields.len() > MAX_FIELDS {
+            bail!("a form can have at most {MAX_FIELDS} fields");
+        }
+        for (i, field) in spec.fields.iter_mut().enumerate() {
+            field.name = field.name.trim().to_string();
+            if field.name.is_empty() {
+                bail!("field {} has no name", i + 1);
+            }
+            if field.kind == FieldKind::Choice && field.options.is_empty() {
+                bail!("choice field '{}' needs options", field.name);
+            }
+        }
+        for (i, field) in spec.fields.iter().enumerate() {
+            if spec.fields[..i].iter().any(|f| f.name == field.name) {
+                bail!("field '{}' appears twice", field.name);
+            }
+        }
+        Ok(Self {
+            spec,
+            values: BTreeMap::new(),
+            created: Utc::now(),
+        })
+    }
+
+    /// The session's form, if one is being filled.
+    pub fn load(sessions: &SessionManager, session_key: &str) -> Option<Self> {
+        let json = sessions.metadata(session_key, SESSION_FIELD)?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Store this form as the session's form.
+    pub fn save(&self, sessions: &SessionManager, session_key: &str) {
+        let json = serde_json::to_string(self).expect("form serializes");
+        sessions.set_metadata(session_key, SESSION_FIELD, Some(&json));
+    }
+
+    /// Remove the session's form.
+    pub fn clear(sessions: &SessionManager, session_key: &str) {
+        sessions.set_metadata(session_key, SESSION_FIELD, None);
+    }
+
+    /// Validate `value` for field `name` and record it. Returns the value
+    /// as stored (dates resolved against `today`, choices as spelled in
+    /// the options).
+    pub fn set(&mut self, name: &str, value: &Value, today: NaiveDate) -> Result<Value> {
+        let Some(field) = self.spec.fields.iter().find(|f| f.name == name) else {
+            let names: Vec<&str> = self.spec.fields.iter().map(|f| f.name.as_str()).collect();
+            bail!("no field '{name}' (fields: {})", names.join(", "));
+        };
+        let value = validate(field, value, today)?;
+        self.values.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+
+    /// Required fields without a value, in form order.
+    pub fn missing(&self) -> Vec<&FormField> {
+        self.spec
+            .fields
+            .iter()
+            .filter(|f| f.required && !self.values.contains_key(&f.name))
+            .collect()
+    }
+
+    /// Whether every required field has a value.
+    pub fn is_complete(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    /// The form as a checklist.
+    pub fn render(&self) -> String {
+        let mut out = format!("📝 {}\n", self.spec.name);
+        for field in &self.spec.fields {
+            match self.values.get(&field.name) {
+                Some(value) => out.push_str(&format!("✅ {}: {}\n", field.label(), display(value))),
+                None => out.push_str(&format!("⬜ {} ({})\n", field.label(), field.hint())),
+            }
+        }
+        out.trim_end().to_string()
+    }
+
+    /// The completed form as one JSON object.
+    pub fn result(&self, session_key: &str) -> Value {
+        json!({
+            "form": self.spec.name,
+            "session": session_key,
+            "values": self.values,
+            "started": self.created.to_rfc3339(),
+            "completed": Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// A value as shown to the user (strings without quotes).
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Check `value` against `field`'s type and normalize it.
+pub fn validate(field: &FormField, value: &Value, today: NaiveDate) -> Result<Value> {
+    let label = field.label();
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    if text.is_empty() {
+        bail!("{label} is empty");
+    }
+    Ok(match field.kind {
+        FieldKind::Text => json!(text),
+        FieldKind::Number => match text.replace(',', "").parse::<f64>() {
+            Ok(n) if n.is_finite() => json!(n),
+            _ => bail!("{label} must be a number, got '{text}'"),
+        },
+        FieldKind::Integer => match text.replace(',', "").parse::<i64>() {
+            Ok(n) => json!(n),
+            Err(_) => bail!("{label} must be a whole number, got '{text}'"),
+        },
+        FieldKind::Boolean => match text.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" => json!(true),
+            "false" | "no" | "n" => json!(false),
+            _ => bail!("{label} must be yes or no, got '{text}'"),
+        },
+        FieldKind::Email => {
+            let valid = text.split_once('@').is_some_and(|(local, domain)| {
+                !local.is_empty()
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && !domain.contains('@')
+            }) && !text.contains(char::is_whitespace);
+            if !valid {
+                bail!("{label} must be an email address, got '{text}'");
+            }
+            json!(text)
+        }
+        FieldKind::Phone => {
+            let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+            let allowed = text
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '+' | ' ' | '-' | '(' | ')' | '.'));
+            if !allowed || !(7..=15).contains(&digits.len()) {
+                bail!("{label} must be a phone number, got '{text}'");
+            }
+            if text.starts_with('+') {
+                json!(format!("+{digits}"))
+            } else {
+                json!(digits)
+            }
+        }
+        FieldKind::Date => match parse_day(&text, today) {
+            Ok(day) => json!(day.format("%Y-%m-%d").to_string()),
+            Err(_) => bail!("{label} must be a date (YYYY-MM-DD, 'tomorrow' or a weekday), got '{text}'"),
+        },
+        FieldKind::Choice => match field.options.iter().find(|o| o.eq_ignore_ascii_case(&text)) {
+            Some(option) => json!(option),
+            None => bail!("{label} must be one of: {} (got '{text}')", field.options.join(", ")),
+        },
+    })
+}
+
+/// Instructions for the system prompt while `form` is being filled.
+pub fn prompt_section(form: &Form) -> String {
+    let missing: Vec<String> = form
+        .missing()
+        .iter()
+        .map(|f| format!("`{}` ({})", f.name, f.hint()))
+        .collect();
+    format!(
+        "## Form in progress\n\n\
+         You are collecting the form below from the user. Ask for the missing \
+         fields in a natural way, one or two at a time, and record each answer \
+         with the `form` tool (action 'set') as soon as you have it. If a value \
+         is rejected, tell the user why and ask again. Optional fields can be \
+         offered but not insisted on. The user can stop with /form cancel.\n\n\
+         {}\n\nStill missing: {}\n",
+        form.render(),
+        if missing.is_empty() { "nothing".to_string() } else { missing.join(", ") }
+    )
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> FormSpec {
+        serde_json::from_value(json!({
+            "name": "intake",
+            "fields": [
+                { "name": "name", "label": "Full name" },
+                { "name": "email", "type": "email" },
+                { "name": "size", "type": "choice", "options": ["Small", "Large"] },
+                { "name": "start", "type": "date" },
+                { "name": "notes", "required": false }
+            ],
+            "webhook": "https://example.com/hook"
+        }))
+        .unwrap()
+    }
+
+    /// A Thursday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 15).unwrap()
+    }
+
+    #[test]
+    fn test_form_collects_and_validates() {
+        let mut form = Form::new(spec()).unwrap();
+        assert_eq!(form.missing().len(), 4);
+
+        assert!(form.set("email", &json!("not an email"), today()).is_err());
+        assert!(form.set("size", &json!("medium"), today()).is_err());
+        assert!(form.set("colour", &json!("red"), today()).is_err());
+        assert_eq!(form.set("email", &json!(" ana@example.com "), today()).unwrap(), json!("ana@example.com"));
+        assert_eq!(form.set("size", &json!("large"), today()).unwrap(), json!("Large"));
+        assert_eq!(form.set("start", &json!("tomorrow"), today()).unwrap(), json!("2026-10-16"));
+        assert!(!form.is_complete());
+        assert_eq!(
+            form.render(),
+            "📝 intake\n⬜ Full name (text)\n✅ email: ana@example.com\n✅ size: Large\n✅ start: 2026-10-16\n⬜ notes (text; optional)"
+        );
+        assert!(prompt_section(&form).contains("Still missing: `name` (text)"));
+
+        form.set("name", &json!("Ana Pérez"), today()).unwrap();
+        assert!(form.is_complete());
+        let result = form.result("telegram:42");
+        assert_eq!(result["values"]["name"], "Ana Pérez");
+        assert_eq!(result["form"], "intake");
+    }
+
+    #[test]
+    fn test_field_validation() {
+        let field = |kind: FieldKind| FormField {
+            name: "f".into(),
+            label: String::new(),
+            kind,
+            options: Vec::new(),
+            required: true,
+        };
+        let check = |kind, value: Value| validate(&field(kind), &value, today()).ok();
+        assert_eq!(check(FieldKind::Number, json!("1,250.5")), Some(json!(1250.5)));
+        assert_eq!(check(FieldKind::Integer, json!(3)), Some(json!(3)));
+        assert_eq!(check(FieldKind::Integer, json!("3.5")), None);
+        assert_eq!(check(FieldKind::Boolean, json!("Yes")), Some(json!(true)));
+        assert_eq!(check(FieldKind::Phone, json!("+34 600 12-34-56")), Some(json!("+34600123456")));
+        assert_eq!(check(FieldKind::Phone, json!("call me")), None);
+        assert_eq!(check(FieldKind::Text, json!("  ")), None);
+
+        let mut bad = spec();
+        bad.fields[1].name = "name".into();
+        assert!(Form::new(bad).is_err());
+        let mut bad = spec();
+        bad.fields[2].options.clear();
+        assert!(Form::new(bad).is_err());
+        let mut bad = spec();
+        bad.name = "../etc".into();
+        assert!(Form::new(bad).is_err());
+    }
+}
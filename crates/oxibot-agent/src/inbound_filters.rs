@@ -0,0 +1,470 @@
+//! Built-in inbound filters — the middleware stack configured by
+//! `channels.inbound`.
+//!
+//! Filters run in this order, so spam patterns see the cleaned-up text:
+//! 1. [`QuoteNormalizer`] — curly quotes from phone keyboards → ASCII
+//! 2. [`ShortLinkExpander`] — t.co / bit.ly links → their targets (opt-in)
+//! 3. [`TrackingStripper`] — drop `utm_*`, `fbclid`, ... from links
+//! 4. [`SpamFilter`] — drop messages matching a configured pattern
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::Regex;
+use tracing::{debug, info};
+
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::bus::InboundFilter;
+use oxibot_core::config::schema::InboundFiltersConfig;
+
+/// Query parameters removed by [`TrackingStripper`] (besides `utm_*`).
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid",
+    "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "ref_src", "ref_url",
+];
+
+/// Redirects followed per short link.
+const MAX_REDIRECTS: usize = 5;
+
+/// Time allowed for expanding all short links of one message.
+const EXPANSION_BUDGET: Duration = Duration::from_secs(5);
+
+/// Build the filter stack described by the config.
+pub fn build_inbound_filters(config: &InboundFiltersConfig) -> anyhow::Result<Vec<Arc<dyn InboundFilter>>> {
+    let mut filters: Vec<Arc<dyn InboundFilter>> = Vec::new();
+    if config.normalize_quotes {
+        filters.push(Arc::new(QuoteNormalizer));
+    }
+    if config.expand_short_links {
+        filters.push(Arc::new(ShortLinkExpander::new(config.short_link_hosts.clone())));
+    }
+    if config.strip_tracking {
+        filters.push(Arc::new(TrackingStripper));
+    }
+    if !config.spam_patterns.is_empty() {
+        filters.push(Arc::new(SpamFilter::new(&config.spam_patterns)?));
+    }
+    Ok(filters)
+}
+
+/// Match `http(s)://` links in free text.
+fn url_regex() -> &'static Regex {
+    static RE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"https?://[^\s<>"'`]+"#).unwrap())
+}
+
+/// Split a matched link from punctuation that closes the sentence around it.
+fn trim_link(link: &str) -> (&str, &str) {
+    let end = link.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']).len();
+    link.split_at(end)
+}
+
+/// Lowercased host of a link, without port or userinfo.
+fn link_host(link: &str) -> Option<String> {
+    let rest = link.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    Some(host.trim_start_matches("www.").to_lowercase())
+}
+
+// ─────────────────────────────────────────────
+// QuoteNormalizer
+// ─────────────────────────────────────────────
+
+/// Replaces typographic quotes (as inserted by phone keyboards) with plain
+/// ASCII quotes, so commands, code and file names come through intact.
+pub struct QuoteNormalizer;
+
+impl QuoteNormalizer {
+    /// Normalize the quotes in `text`.
+    pub fn normalize(text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => '\'',
+                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => '"',
+                other => other,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl InboundFilter for QuoteNormalizer {
+    fn name(&self) -> &str {
+        "quotes"
+    }
+
+    async fn apply(&self, mut msg: InboundMessage) -> Option<InboundMessage> {
+        msg.content = Self::normalize(&msg.content);
+        Some(msg)
+    }
+}
+
+// ─────────────────────────────────────────────
+// TrackingStripper
+// ─────────────────────────────────────────────
+
+/// Removes tracking query parameters from links in the message.
+pub struct TrackingStripper;
+
+impl TrackingStripper {
+    /// Strip tracking parameters from a single link.
+    pub fn clean_link(link: &str) -> String {
+        let (without_fragment, fragment) = match link.split_once('#') {
+            Some((head, frag)) => (head, Some(frag)),
+            None => (link, None),
+        };
+        let Some((base, query)) = without_fragment.split_once('?') else {
+            return link.to_string();
+        };
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| {
+                let key = pair.split('=').next().unwrap_or("").to_lowercase();
+                !pair.is_empty() && !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+            })
+            .collect();
+
+        let mut out = base.to_string();
+        if !kept.is_empty() {
+            out.push('?');
+            out.push_str(&kept.join("&"));
+        }
+        if let Some(fragment) = fragment {
+            out.push('#');
+            out.push_str(fragment);
+        }
+        out
+    }
+
+    /// Strip tracking parameters from every link in `text`.
+    pub fn clean_text(text: &str) -> String {
+        url_regex()
+            .replace_all(text, |caps: &regex::Captures| {
+                let (link, trailing) = trim_link(&caps[0]);
+                format!("{}{}", Self::clean_link(link), trailing)
+            })
+            .into_owned()
+    }
+}
+
+#[async_trait]
+impl InboundFilter for TrackingStripper {
+    fn name(&self) -> &str {
+        "tracking"
+    }
+
+    async fn apply(&self, mut msg: InboundMessage) -> Option<InboundMessage> {
+        msg.content = Self::clean_text(&msg.content);
+        Some(msg)
+    }
+}
+
+// ─────────────────────────────────────────────
+// ShortLinkExpander
+// ─────────────────────────────────────────────
+
+/// Whether `ip` is reachable on the public internet.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// DNS resolver that only hands out public addresses, so a short link
+/// can't point the expander at the local network.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Resolves shortened links by following their redirects.
+///
+/// Links that fail to resolve are left unchanged. Redirects to private or
+/// loopback addresses are not followed, and expanding stops once a
+/// message has taken [`EXPANSION_BUDGET`].
+pub struct ShortLinkExpander {
+    client: reqwest::Client,
+    /// Shortener hosts (lowercase, without `www.`).
+    hosts: Vec<String>,
+    /// Follow links to private addresses (tests only).
+    allow_private: bool,
+}
+
+impl ShortLinkExpander {
+    /// Create an expander for the given shortener hosts.
+    pub fn new(hosts: Vec<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicResolver))
+            .build()
+            .expect("Failed to build HTTP client");
+        Self {
+            client,
+            hosts: hosts.iter().map(|h| h.trim_start_matches("www.").to_lowercase()).collect(),
+            allow_private: false,
+        }
+    }
+
+    /// Follow redirects for one link. Returns `None` on failure.
+    async fn resolve(&self, link: &str) -> Option<String> {
+        let mut url = reqwest::Url::parse(link).ok()?;
+        for _ in 0..=MAX_REDIRECTS {
+            let literal = url
+                .host_str()
+                .and_then(|h| h.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok());
+            if !self.allow_private && literal.is_some_and(|ip| !is_public_ip(ip)) {
+                debug!(link, url = %url, "short link points to a private address, not followed");
+                return None;
+            }
+            // The body is never read; only the final URL matters
+            let resp = match self.client.get(url.clone()).send().await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    debug!(link, error = %e, "short link expansion failed");
+                    return None;
+                }
+            };
+            if !resp.status().is_redirection() {
+                return Some(url.to_string());
+            }
+            let location = resp.headers().get(reqwest::header::LOCATION)?.to_str().ok()?;
+            url = url.join(location).ok()?;
+        }
+        debug!(link, "short link has too many redirects");
+        None
+    }
+
+    /// Expand every shortened link in `text`.
+    pub async fn expand_text(&self, text: &str) -> String {
+        let deadline = tokio::time::Instant::now() + EXPANSION_BUDGET;
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for m in url_regex().find_iter(text) {
+            let (link, _) = trim_link(m.as_str());
+            let is_short = link_host(link).is_some_and(|h| self.hosts.contains(&h));
+            if !is_short {
+                continue;
+            }
+            let Ok(resolved) = tokio::time::timeout_at(deadline, self.resolve(link)).await else {
+                debug!("short link expansion budget used up, leaving the rest");
+                break;
+            };
+            if let Some(target) = resolved {
+                out.push_str(&text[last..m.start()]);
+                out.push_str(&target);
+                last = m.start() + link.len();
+            }
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+#[async_trait]
+impl InboundFilter for ShortLinkExpander {
+    fn name(&self) -> &str {
+        "short_links"
+    }
+
+    async fn apply(&self, mut msg: InboundMessage) -> Option<InboundMessage> {
+        msg.content = self.expand_text(&msg.content).await;
+        Some(msg)
+    }
+}
+
+// ─────────────────────────────────────────────
+// SpamFilter
+// ─────────────────────────────────────────────
+
+/// Drops messages whose text matches any configured pattern.
+pub struct SpamFilter {
+    patterns: Vec<Regex>,
+}
+
+impl SpamFilter {
+    /// Compile case-insensitive patterns. Fails on an invalid regex.
+    pub fn new(patterns: &[String]) -> anyhow::Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| {
+                Regex::new(&format!("(?i){p}"))
+                    .map_err(|e| anyhow::anyhow!("invalid spam pattern '{p}': {e}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { patterns })
+    }
+}
+
+#[async_trait]
+impl InboundFilter for SpamFilter {
+    fn name(&self) -> &str {
+        "spam"
+    }
+
+    async fn apply(&self, msg: InboundMessage) -> Option<InboundMessage> {
+        if let Some(pattern) = self.patterns.iter().find(|p| p.is_match(&msg.content)) {
+            info!(
+                channel = %msg.channel,
+                sender = %msg.sender_id,
+                pattern = pattern.as_str(),
+                "dropping inbound message matching spam pattern"
+            );
+            return None;
+        }
+        Some(msg)
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn msg(text: &str) -> InboundMessage {
+        InboundMessage::new("telegram", "user", "42", text)
+    }
+
+    #[test]
+    fn test_quote_normalizer() {
+        assert_eq!(
+            QuoteNormalizer::normalize("It\u{2019}s \u{201C}done\u{201D}"),
+            "It's \"done\""
+        );
+    }
+
+    #[test]
+    fn test_tracking_stripper() {
+        assert_eq!(
+            TrackingStripper::clean_link("https://x.com/a?utm_source=tw&id=7&fbclid=abc#top"),
+            "https://x.com/a?id=7#top"
+        );
+        assert_eq!(
+            TrackingStripper::clean_link("https://x.com/a?UTM_Medium=email"),
+            "https://x.com/a"
+        );
+        assert_eq!(
+            TrackingStripper::clean_text("see (https://x.com/a?gclid=1), and https://y.org/b."),
+            "see (https://x.com/a), and https://y.org/b."
+        );
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["127.0.0.1", "10.1.2.3", "192.168.0.10", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["1.1.1.1", "104.244.42.1", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn test_link_host() {
+        assert_eq!(link_host("https://www.Bit.ly/abc").as_deref(), Some("bit.ly"));
+        assert_eq!(link_host("http://user@127.0.0.1:8080/x?y").as_deref(), Some("127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_spam_filter() {
+        let filter = SpamFilter::new(&["free crypto".into(), r"casino\s+bonus".into()]).unwrap();
+        assert!(filter.apply(msg("Get FREE Crypto now")).await.is_none());
+        assert!(filter.apply(msg("casino   bonus!")).await.is_none());
+        assert!(filter.apply(msg("what's the weather?")).await.is_some());
+        assert!(SpamFilter::new(&["(unclosed".into()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_short_link_expander() {
+        let server = MockServer::start().await;
+        let target = format!("{}/article?utm_source=x&id=3", server.uri());
+        Mock::given(method("GET"))
+            .and(path("/abc"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", target.as_str()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/article"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        // Private addresses are refused
+        let expander = ShortLinkExpander::new(vec!["127.0.0.1".into()]);
+        let text = format!("{}/abc", server.uri());
+        assert_eq!(expander.expand_text(&text).await, text);
+
+        let mut expander = ShortLinkExpander::new(vec!["127.0.0.1".into()]);
+        expander.allow_private = true;
+        let filters: Vec<Arc<dyn InboundFilter>> = vec![Arc::new(expander), Arc::new(TrackingStripper)];
+        let mut m = msg(&format!("look: {}/abc.", server.uri()));
+        for f in &filters {
+            m = f.apply(m).await.unwrap();
+        }
+        assert_eq!(m.content, format!("look: {}/article?id=3.", server.uri()));
+
+        // Other hosts are left alone
+        let expander = ShortLinkExpander::new(vec!["bit.ly".into()]);
+        let text = format!("{}/abc", server.uri());
+        assert_eq!(expander.expand_text(&text).await, text);
+    }
+
+    #[test]
+    fn test_build_inbound_filters() {
+        let config = InboundFiltersConfig::default();
+        let names: Vec<String> = build_inbound_filters(&config)
+            .unwrap()
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect();
+        assert_eq!(names, vec!["quotes", "tracking"]);
+
+        let config = InboundFiltersConfig {
+            spam_patterns: vec!["[bad".into()],
+            ..Default::default()
+        };
+        assert!(build_inbound_filters(&config).is_err());
+    }
+}
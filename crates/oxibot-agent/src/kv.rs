@@ -0,0 +1,192 @@
+//! Key-value store — small state that skills keep between runs.
+//!
+//! Polling workflows need to remember a cursor or the last id they saw.
+//! Rather than have the agent invent file formats with the filesystem
+//! tools, values live in `workspace/kv.json` and are managed with the
+//! `kv_get`, `kv_set`, `kv_delete` and `kv_list` tools. Each key belongs
+//! to a namespace: [`GLOBAL`], or one per session.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Namespace shared by every session.
+pub const GLOBAL: &str = "global";
+
+/// Longest key, in characters.
+const MAX_KEY_CHARS: usize = 200;
+
+/// Largest value, in bytes.
+const MAX_VALUE_BYTES: usize = 16 * 1024;
+
+/// Most keys per namespace.
+const MAX_KEYS: usize = 1000;
+
+/// Namespace of a session's keys.
+pub fn session_namespace(session_key: &str) -> String {
+    format!("session:{session_key}")
+}
+
+/// A stored value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+type Namespaces = BTreeMap<String, BTreeMap<String, Entry>>;
+
+/// JSON-backed key-value store in the workspace.
+///
+/// The file is re-read on every operation, like the task list; changes in
+/// this process are serialized so concurrent turns don't lose writes. A file
+/// that can't be parsed is an error rather than an empty store, so a write
+/// never replaces data that merely failed to load.
+#[derive(Debug, Clone)]
+pub struct KvStore {
+    /// Path to `kv.json`.
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl KvStore {
+    /// Create a store for `workspace` (does not touch the disk).
+    pub fn new(workspace: &Path) -> Self {
+        Self {
+            path: workspace.join("kv.json"),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// The value of `key` in `namespace`.
+    pub fn get(&self, namespace: &str, key: &str) -> anyhow::Result<Option<Entry>> {
+        Ok(self.load()?.remove(namespace).and_then(|mut entries| entries.remove(key)))
+    }
+
+    /// Store `value` under `key` in `namespace`, replacing any old value.
+    pub fn set(&self, namespace: &str, key: &str, value: &str) -> anyhow::Result<()> {
+        let key = key.trim();
+        if key.is_empty() || key.chars().count() > MAX_KEY_CHARS {
+            anyhow::bail!("key must be 1-{MAX_KEY_CHARS} characters");
+        }
+        if value.len() > MAX_VALUE_BYTES {
+            anyhow::bail!("value is {} bytes; the limit is {MAX_VALUE_BYTES}", value.len());
+        }
+        let _guard = self.lock.lock().unwrap();
+        let mut namespaces = self.load()?;
+        let entries = namespaces.entry(namespace.to_string()).or_default();
+        if !entries.contains_key(key) && entries.len() >= MAX_KEYS {
+            anyhow::bail!("{namespace} already holds {MAX_KEYS} keys; delete some first");
+        }
+        entries.insert(
+            key.to_string(),
+            Entry {
+                value: value.to_string(),
+                updated_at: Utc::now(),
+            },
+        );
+        self.save(&namespaces)
+    }
+
+    /// Remove `key` from `namespace`. Returns whether it existed.
+    pub fn delete(&self, namespace: &str, key: &str) -> anyhow::Result<bool> {
+        let _guard = self.lock.lock().unwrap();
+        let mut namespaces = self.load()?;
+        let Some(entries) = namespaces.get_mut(namespace) else {
+            return Ok(false);
+        };
+        let removed = entries.remove(key.trim()).is_some();
+        if entries.is_empty() {
+            namespaces.remove(namespace);
+        }
+        if removed {
+            self.save(&namespaces)?;
+        }
+        Ok(removed)
+    }
+
+    /// Keys of `namespace` starting with `prefix`, sorted, with their values.
+    pub fn list(&self, namespace: &str, prefix: &str) -> anyhow::Result<Vec<(String, Entry)>> {
+        Ok(self
+            .load()?
+            .remove(namespace)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .collect())
+    }
+
+    fn load(&self) -> anyhow::Result<Namespaces> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("{} is not a valid kv file; fix or remove it", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Namespaces::new()),
+            Err(e) => Err(e).with_context(|| format!("cannot read {}", self.path.display())),
+        }
+    }
+
+    fn save(&self, namespaces: &Namespaces) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so a crash or a concurrent reader never sees half a file
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(namespaces)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaces_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KvStore::new(dir.path());
+        let session = session_namespace("telegram:42");
+
+        store.set(GLOBAL, "rss.last_id", "1041").unwrap();
+        store.set(&session, "rss.last_id", "7").unwrap();
+        store.set(GLOBAL, "rss.last_id", "1042").unwrap();
+        store.set(GLOBAL, "cursor", "abc").unwrap();
+
+        assert_eq!(store.get(GLOBAL, "rss.last_id").unwrap().unwrap().value, "1042");
+        assert_eq!(store.get(&session, "rss.last_id").unwrap().unwrap().value, "7");
+        assert_eq!(store.get(&session, "cursor").unwrap(), None);
+
+        let keys: Vec<String> = store.list(GLOBAL, "").unwrap().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["cursor", "rss.last_id"]);
+        assert_eq!(store.list(GLOBAL, "rss.").unwrap().len(), 1);
+
+        // Another store on the same workspace sees the changes
+        let other = KvStore::new(dir.path());
+        assert!(other.delete(&session, "rss.last_id").unwrap());
+        assert!(!other.delete(&session, "rss.last_id").unwrap());
+        assert!(store.list(&session, "").unwrap().is_empty());
+
+        assert!(store.set(GLOBAL, " ", "x").is_err());
+        assert!(store.set(GLOBAL, "big", &"x".repeat(MAX_VALUE_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_file_is_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KvStore::new(dir.path());
+        std::fs::write(dir.path().join("kv.json"), "{ not json").unwrap();
+
+        assert!(store.get(GLOBAL, "cursor").is_err());
+        assert!(store.set(GLOBAL, "cursor", "abc").is_err());
+        assert_eq!(std::fs::read_to_string(dir.path().join("kv.json")).unwrap(), "{ not json");
+    }
+}
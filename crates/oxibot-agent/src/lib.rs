@@ -3,18 +3,65 @@
 //! This crate contains:
 //! - **tools**: Tool trait, registry, and built-in tools (filesystem, shell, web, message)
 //! - **context**: System prompt and message list construction
+//! - **context_providers**: Pluggable extra system prompt sections (git status, calendar)
+//! - **tasks**: Persistent task list surfaced in the system prompt
+//! - **kv**: Namespaced key-value store for small state kept by skills
+//! - **scratch**: Per-session scratch directories under the workspace
+//! - **persona**: Persona presets for the system prompt (`/persona`)
+//! - **routing**: Per-phase model selection (planner vs. responder)
+//! - **bindings**: Per-channel agent selection in the gateway
+//! - **inbound_filters**: Built-in inbound middleware (quotes, links, spam)
+//! - **quick_reply**: Small-talk detection that skips the full loop
+//! - **ack_first**: Short acknowledgments before replies that take a while
+//! - **translation**: Messages translated for the operator, replies back for the sender
+//! - **responses**: Greeting and help texts answered without the LLM
+//! - **compaction**: Truncation/summaries of tool results already seen by the LLM
+//! - **tool_breaker**: Tools taken out of use while they keep failing
+//! - **file_index**: Workspace file index behind `find_files`
+//! - **page_cache**: Fetched web pages kept on disk for `web_fetch` and `recall_page`
+//! - **redaction**: Secret placeholders in what is sent to LLM providers
+//! - **plan**: Plan-first mode — proposed, approved and tracked plans (`/plan`)
+//! - **forms**: Multi-turn forms — fields collected, validated and delivered (`/form`)
+//! - **uploads**: Documents uploaded to the provider and attached by file id
+//! - **replay**: Re-running recorded sessions against their recorded LLM responses
 //! - **agent_loop**: The LLM ↔ tool-calling main loop
 
 pub mod tools;
+pub mod file_index;
+pub mod page_cache;
 pub mod context;
+pub mod context_providers;
+pub mod compaction;
+pub mod tool_breaker;
 pub mod memory;
+pub mod persona;
+pub mod plan;
+pub mod forms;
+pub mod routing;
+pub mod scratch;
 pub mod skills;
+pub mod tasks;
+pub mod kv;
 pub mod subagent;
 pub mod agent_loop;
+pub mod bindings;
+pub mod inbound_filters;
+pub mod quick_reply;
+pub mod ack_first;
+pub mod translation;
+pub mod redaction;
+pub mod responses;
+pub mod uploads;
+pub mod replay;
 
-pub use agent_loop::{AgentLoop, ExecToolConfig};
+pub use agent_loop::{AgentLoop, ExecToolConfig, TurnResult};
+pub use bindings::AgentRouter;
+pub use inbound_filters::build_inbound_filters;
 pub use context::ContextBuilder;
 pub use memory::MemoryStore;
+pub use routing::{ModelRoute, ModelRouter, Phase};
+pub use scratch::ScratchDirs;
 pub use skills::SkillsLoader;
 pub use subagent::SubagentManager;
+pub use tasks::TaskStore;
 pub use tools::{Tool, ToolRegistry};
@@ -5,14 +5,30 @@
 //! The agent's memory is file-based:
 //! - **Long-term memory**: `workspace/memory/MEMORY.md` — persistent facts, prefs
 //! - **Daily notes**: `workspace/memory/YYYY-MM-DD.md` — ephemeral daily context
+//! - **Observed chats**: `workspace/memory/observed/YYYY-MM-DD.md` — messages
+//!   from chats the agent only listens to, one line each
 //!
 //! The context builder reads memory on every prompt build (passive read).
 //! The agent writes memory via the filesystem tools (active write).
-
+//!
+//! Both files are plain Markdown meant to be edited by hand: `## Section`
+//! headings with `- fact` bullets underneath. Before each read the store
+//! reconciles files that changed since it last looked ([`MemoryStore::reconcile`]),
+//! normalizing line endings, whitespace and duplicate bullets, so manual
+//! edits are picked up as they are saved. [`MemoryExport`] bundles the whole
+//! store as JSON for `oxibot memory export` / `import`.
+
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
 
-use chrono::Utc;
-use tracing::debug;
+/// Version of the [`MemoryExport`] format.
+const EXPORT_VERSION: u32 = 1;
 
 // ─────────────────────────────────────────────
 // MemoryStore
@@ -26,6 +42,8 @@ pub struct MemoryStore {
     memory_dir: PathBuf,
     /// Path to the long-term memory file.
     memory_file: PathBuf,
+    /// Modification time of each file when it was last reconciled.
+    seen: Mutex<HashMap<PathBuf, SystemTime>>,
 }
 
 impl MemoryStore {
@@ -40,6 +58,7 @@ impl MemoryStore {
         Ok(Self {
             memory_dir,
             memory_file,
+            seen: Mutex::default(),
         })
     }
 
@@ -50,6 +69,7 @@ impl MemoryStore {
         Self {
             memory_dir,
             memory_file,
+            seen: Mutex::default(),
         }
     }
 
@@ -63,7 +83,7 @@ impl MemoryStore {
     /// Overwrite the entire long-term memory file.
     pub fn write_long_term(&self, content: &str) -> std::io::Result<()> {
         self.ensure_dir()?;
-        std::fs::write(&self.memory_file, content)
+        write_replacing(&self.memory_file, content)
     }
 
     // ────────────── Daily notes ──────────────
@@ -97,6 +117,28 @@ impl MemoryStore {
         }
     }
 
+    // ────────────── Observed chats ──────────────
+
+    /// Path to today's log of observed chat messages.
+    pub fn observed_file(&self) -> PathBuf {
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        self.memory_dir.join("observed").join(format!("{today}.md"))
+    }
+
+    /// Append a message from an observe-only chat to today's log, as
+    /// `- HH:MM [chat] line`.
+    pub fn append_observed(&self, chat: &str, line: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let path = self.observed_file();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        let time = Utc::now().format("%H:%M");
+        writeln!(file, "- {time} [{chat}] {}", line.replace('\n', " "))
+    }
+
     // ────────────── Aggregation ──────────────
 
     /// List daily note files, newest first.
@@ -154,6 +196,16 @@ impl MemoryStore {
             sections.push(format!("## Today's Notes ({today})\n\n{today_content}"));
         }
 
+        // Observed chats are only pointed to; the log can get long
+        let observed = self.observed_file();
+        if observed.exists() {
+            sections.push(format!(
+                "## Observed Chats\n\nMessages from group chats you only listen to are logged in `{}` \
+                 (one file per day, UTC). Read it with read_file to answer questions about them.",
+                observed.display()
+            ));
+        }
+
         if sections.is_empty() {
             None
         } else {
@@ -161,6 +213,101 @@ impl MemoryStore {
         }
     }
 
+    // ────────────── Reconciliation ──────────────
+
+    /// Normalize `MEMORY.md` and today's notes if they changed since the
+    /// last call. Returns the number of files rewritten.
+    ///
+    /// Files that aren't valid UTF-8 are left alone, so a half-saved edit
+    /// is never overwritten.
+    pub fn reconcile(&self) -> usize {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let mut rewritten = 0;
+        for path in [self.memory_file.clone(), self.today_file()] {
+            let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if seen.get(&path) == Some(&modified) {
+                continue;
+            }
+            match std::fs::read_to_string(&path) {
+                Ok(content) => {
+                    let normalized = normalize_memory(&content);
+                    if normalized != content {
+                        match write_replacing(&path, &normalized) {
+                            Ok(()) => {
+                                debug!(file = %path.display(), "reconciled memory file");
+                                rewritten += 1;
+                            }
+                            Err(e) => warn!(file = %path.display(), error = %e, "failed to reconcile memory file"),
+                        }
+                    }
+                }
+                Err(e) => warn!(file = %path.display(), error = %e, "skipping unreadable memory file"),
+            }
+            if let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) {
+                seen.insert(path, modified);
+            }
+        }
+        rewritten
+    }
+
+    // ────────────── Export / import ──────────────
+
+    /// Bundle long-term memory and all daily notes.
+    pub fn export(&self) -> MemoryExport {
+        let daily = self
+            .list_memory_files()
+            .into_iter()
+            .filter_map(|path| {
+                let date = path.file_stem()?.to_str()?.to_string();
+                Some((date, std::fs::read_to_string(&path).ok()?))
+            })
+            .collect();
+        MemoryExport {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            long_term: self.read_long_term(),
+            daily,
+        }
+    }
+
+    /// Load an export into the store. With `replace`, the exported files
+    /// overwrite existing ones; otherwise entries missing from each file
+    /// are merged into it. Returns the number of files written.
+    pub fn import(&self, export: &MemoryExport, replace: bool) -> anyhow::Result<usize> {
+        if export.version > EXPORT_VERSION {
+            anyhow::bail!("unsupported memory export version {}", export.version);
+        }
+        if let Some(date) = export.daily.keys().find(|d| !is_daily_name(&format!("{d}.md"))) {
+            anyhow::bail!("invalid daily note date '{date}' in memory export");
+        }
+        self.ensure_dir()?;
+
+        let mut files = vec![(self.memory_file.clone(), export.long_term.as_str())];
+        for (date, content) in &export.daily {
+            files.push((self.memory_dir.join(format!("{date}.md")), content.as_str()));
+        }
+
+        let mut written = 0;
+        for (path, incoming) in files {
+            if incoming.trim().is_empty() {
+                continue;
+            }
+            let existing = std::fs::read_to_string(&path).unwrap_or_default();
+            let content = if replace {
+                normalize_memory(incoming)
+            } else {
+                merge_memory(&existing, incoming)
+            };
+            if content != existing {
+                write_replacing(&path, &content)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
     /// Path to the memory directory.
     pub fn memory_dir(&self) -> &Path {
         &self.memory_dir
@@ -180,10 +327,136 @@ impl MemoryStore {
     }
 }
 
+// ─────────────────────────────────────────────
+// Export format
+// ─────────────────────────────────────────────
+
+/// The whole memory store as one JSON document.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Contents of `MEMORY.md`.
+    pub long_term: String,
+    /// Daily notes by date (`YYYY-MM-DD`).
+    #[serde(default)]
+    pub daily: BTreeMap<String, String>,
+}
+
+// ─────────────────────────────────────────────
+// Format
+// ─────────────────────────────────────────────
+
+/// Bring a memory file into the canonical format: LF line endings, no
+/// trailing whitespace, no duplicate bullets within a section, single
+/// blank lines and a final newline.
+pub fn normalize_memory(content: &str) -> String {
+    let mut out: Vec<&str> = Vec::new();
+    let mut bullets: Vec<&str> = Vec::new();
+    for line in content.lines().map(str::trim_end) {
+        if line.starts_with('#') {
+            bullets.clear();
+        } else if let Some(item) = bullet(line) {
+            if bullets.contains(&item) {
+                continue;
+            }
+            bullets.push(item);
+        }
+        if line.is_empty() && out.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        out.push(line);
+    }
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    if out.is_empty() {
+        String::new()
+    } else {
+        out.join("\n") + "\n"
+    }
+}
+
+/// Add the lines of `incoming` missing from `existing`, section by section.
+/// Sections are matched by heading; new sections go at the end.
+pub fn merge_memory(existing: &str, incoming: &str) -> String {
+    let mut merged = sections(&normalize_memory(existing));
+    for (heading, lines) in sections(&normalize_memory(incoming)) {
+        let key = heading.as_deref().map(str::to_lowercase);
+        let target = merged
+            .iter_mut()
+            .find(|(h, _)| h.as_deref().map(str::to_lowercase) == key);
+        match target {
+            Some((_, body)) => {
+                while body.last().is_some_and(|l| l.is_empty()) {
+                    body.pop();
+                }
+                for line in lines.into_iter().filter(|l| !l.is_empty()) {
+                    if !body.iter().any(|b| b.trim() == line.trim()) {
+                        body.push(line);
+                    }
+                }
+                body.push(String::new());
+            }
+            None => merged.push((heading, lines)),
+        }
+    }
+    let mut out = String::new();
+    for (heading, lines) in merged {
+        if let Some(heading) = heading {
+            if !out.is_empty() && !out.ends_with("\n\n") {
+                out.push('\n');
+            }
+            out.push_str(&heading);
+            out.push('\n');
+        }
+        for line in lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    normalize_memory(&out)
+}
+
+/// Split Markdown into `(heading line, body lines)`; text before the first
+/// heading has no heading.
+fn sections(content: &str) -> Vec<(Option<String>, Vec<String>)> {
+    let mut sections: Vec<(Option<String>, Vec<String>)> = vec![(None, Vec::new())];
+    for line in content.lines() {
+        if line.starts_with('#') {
+            sections.push((Some(line.to_string()), Vec::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push(line.to_string());
+        }
+    }
+    if sections[0].1.iter().all(|l| l.is_empty()) {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Text of a `- item` / `* item` bullet.
+fn bullet(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .map(str::trim)
+}
+
 // ─────────────────────────────────────────────
 // Helpers
 // ─────────────────────────────────────────────
 
+/// Write `content` to a temporary file and rename it over `path`, so a
+/// reader (or an editor) never sees half a file.
+fn write_replacing(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("md.tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)
+}
+
 /// Simple glob for `YYYY-MM-DD.md` files in a directory.
 fn glob_simple(dir: &Path) -> Vec<PathBuf> {
     let Ok(entries) = std::fs::read_dir(dir) else {
@@ -192,20 +465,21 @@ fn glob_simple(dir: &Path) -> Vec<PathBuf> {
     entries
         .filter_map(|e| e.ok())
         .map(|e| e.path())
-        .filter(|p| {
-            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
-            // Match YYYY-MM-DD.md pattern
-            name.len() == 13
-                && name.ends_with(".md")
-                && name.as_bytes()[4] == b'-'
-                && name.as_bytes()[7] == b'-'
-                && name[..4].chars().all(|c| c.is_ascii_digit())
-                && name[5..7].chars().all(|c| c.is_ascii_digit())
-                && name[8..10].chars().all(|c| c.is_ascii_digit())
-        })
+        .filter(|p| is_daily_name(p.file_name().and_then(|n| n.to_str()).unwrap_or("")))
         .collect()
 }
 
+/// Whether `name` matches the `YYYY-MM-DD.md` pattern.
+fn is_daily_name(name: &str) -> bool {
+    name.len() == 13
+        && name.ends_with(".md")
+        && name.as_bytes()[4] == b'-'
+        && name.as_bytes()[7] == b'-'
+        && name[..4].chars().all(|c| c.is_ascii_digit())
+        && name[5..7].chars().all(|c| c.is_ascii_digit())
+        && name[8..10].chars().all(|c| c.is_ascii_digit())
+}
+
 // ─────────────────────────────────────────────
 // Tests
 // ─────────────────────────────────────────────
@@ -277,6 +551,28 @@ mod tests {
         assert!(content.contains("Second note."));
     }
 
+    #[test]
+    fn test_append_observed() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MemoryStore::new(dir.path()).unwrap();
+        assert!(store.get_memory_context().is_none());
+
+        store.append_observed("slack:C1", "ann: we ship\non friday").unwrap();
+        store.append_observed("slack:C1", "bob: agreed").unwrap();
+
+        let log = std::fs::read_to_string(store.observed_file()).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[slack:C1] ann: we ship on friday"));
+        assert!(lines[1].ends_with("[slack:C1] bob: agreed"));
+
+        // The context points to the log; daily notes don't include it
+        let context = store.get_memory_context().unwrap();
+        assert!(context.contains("## Observed Chats"));
+        assert!(context.contains(&store.observed_file().display().to_string()));
+        assert!(store.list_memory_files().is_empty());
+    }
+
     #[test]
     fn test_list_memory_files() {
         let dir = tempfile::tempdir().unwrap();
@@ -383,4 +679,71 @@ mod tests {
         let files = store.list_memory_files();
         assert_eq!(files.len(), 1);
     }
+
+    #[test]
+    fn test_normalize_memory() {
+        let messy = "# Memory  \r\n\r\n\r\n## Prefs\r\n- dark mode \r\n- tea\r\n-  dark mode\r\n\r\n## Work\r\n- tea\r\n\r\n";
+        assert_eq!(
+            normalize_memory(messy),
+            "# Memory\n\n## Prefs\n- dark mode\n- tea\n\n## Work\n- tea\n"
+        );
+        assert_eq!(normalize_memory("\n  \n"), "");
+    }
+
+    #[test]
+    fn test_merge_memory() {
+        let existing = "# Memory\n\n## Prefs\n- dark mode\n\n## Work\n- uses Rust\n";
+        let incoming = "## prefs\n- dark mode\n- tea\n\n## Family\n- sister Ana\n";
+        assert_eq!(
+            merge_memory(existing, incoming),
+            "# Memory\n\n## Prefs\n- dark mode\n- tea\n\n## Work\n- uses Rust\n\n## Family\n- sister Ana\n"
+        );
+        assert_eq!(merge_memory("", "- likes tea\n"), "- likes tea\n");
+    }
+
+    #[test]
+    fn test_reconcile_picks_up_manual_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MemoryStore::new(dir.path()).unwrap();
+        assert_eq!(store.reconcile(), 0);
+
+        std::fs::write(store.memory_file(), "## Prefs\r\n- tea\r\n- tea\r\n").unwrap();
+        assert_eq!(store.reconcile(), 1);
+        assert_eq!(store.read_long_term(), "## Prefs\n- tea\n");
+        // Unchanged since the last pass: nothing to do.
+        assert_eq!(store.reconcile(), 0);
+
+        // Not UTF-8: left untouched.
+        std::fs::write(store.memory_file(), b"- caf\xe9\n\n\n").unwrap();
+        assert_eq!(store.reconcile(), 0);
+        assert_eq!(std::fs::read(store.memory_file()).unwrap(), b"- caf\xe9\n\n\n");
+    }
+
+    #[test]
+    fn test_export_import() {
+        let src = tempfile::tempdir().unwrap();
+        let store = MemoryStore::new(src.path()).unwrap();
+        store.write_long_term("## Prefs\n- tea\n").unwrap();
+        std::fs::write(store.memory_dir().join("2026-01-10.md"), "# 2026-01-10\n\nShipped v1\n").unwrap();
+
+        let export = store.export();
+        let json = serde_json::to_string(&export).unwrap();
+        assert!(json.contains("\"longTerm\""));
+        let export: MemoryExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(export.daily.len(), 1);
+
+        let dst = tempfile::tempdir().unwrap();
+        let other = MemoryStore::new(dst.path()).unwrap();
+        other.write_long_term("## Prefs\n- dark mode\n").unwrap();
+        assert_eq!(other.import(&export, false).unwrap(), 2);
+        assert_eq!(other.read_long_term(), "## Prefs\n- dark mode\n- tea\n");
+        assert_eq!(other.import(&export, false).unwrap(), 0);
+
+        assert_eq!(other.import(&export, true).unwrap(), 1);
+        assert_eq!(other.read_long_term(), "## Prefs\n- tea\n");
+
+        let mut bad = export.clone();
+        bad.daily.insert("../../etc".into(), "x".into());
+        assert!(other.import(&bad, false).is_err());
+    }
 }
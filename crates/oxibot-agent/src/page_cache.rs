@@ -0,0 +1,322 @@
+//! Page cache — fetched web pages kept on disk for reuse.
+//!
+//! Documentation pages get fetched again and again, across sessions, and
+//! every fetch puts the whole page back into the context. [`PageCache`]
+//! keeps what `web_fetch` extracted (URL → cleaned text) for
+//! `tools.web.pageCache.ttlHours`, split into chunks with an embedding
+//! each. `web_fetch` serves fresh entries without a request, and
+//! `recall_page` returns only the chunks that match a question.
+//!
+//! Embeddings come from `tools.web.pageCache.embeddingModel` when set, and
+//! otherwise from a local keyword-hashing embedding that needs no API.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use oxibot_providers::Embedder;
+
+/// Model name recorded for the local hashing embedding.
+pub const LOCAL_MODEL: &str = "local-hash";
+
+/// Dimensions of the local hashing embedding.
+const LOCAL_DIMS: usize = 512;
+
+/// Target size of a chunk, in bytes; chunks end at a line break when they can.
+const CHUNK_CHARS: usize = 2000;
+
+/// Most text kept per page.
+const MAX_CACHED_CHARS: usize = 500_000;
+
+/// A page as `web_fetch` extracted it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedPage {
+    pub url: String,
+    pub final_url: String,
+    pub fetched_at: DateTime<Utc>,
+    pub extractor: String,
+    pub text: String,
+    /// Embedding model of `chunks`.
+    pub model: String,
+    pub chunks: Vec<Chunk>,
+}
+
+/// A slice of a page's text and its embedding.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Chunk {
+    /// Byte range in [`CachedPage::text`].
+    pub start: usize,
+    pub end: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// A chunk that matches a `recall` query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recalled {
+    pub url: String,
+    pub fetched_at: DateTime<Utc>,
+    /// Cosine similarity to the query.
+    pub score: f32,
+    pub text: String,
+}
+
+/// Fetched pages on disk, one JSON file per URL.
+pub struct PageCache {
+    dir: PathBuf,
+    ttl: chrono::Duration,
+    embedder: Option<Arc<dyn Embedder>>,
+}
+
+impl PageCache {
+    pub fn new(dir: PathBuf, ttl_hours: u64) -> Self {
+        Self {
+            dir,
+            ttl: chrono::Duration::hours(ttl_hours.min(i32::MAX as u64) as i64),
+            embedder: None,
+        }
+    }
+
+    /// Embed chunks with `embedder` instead of the local hashing embedding.
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// The cached page for `url`, if it hasn't expired.
+    pub fn get(&self, url: &str) -> Option<CachedPage> {
+        self.load(&self.path(url)).filter(|page| self.is_fresh(page, Utc::now()))
+    }
+
+    /// Cache what was extracted from `url`, and drop expired pages.
+    pub async fn put(&self, url: &str, final_url: &str, extractor: &str, text: &str) -> Result<()> {
+        let mut end = text.len().min(MAX_CACHED_CHARS);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let text = &text[..end];
+        let ranges = chunk_ranges(text);
+        let pieces: Vec<String> = ranges.iter().map(|&(start, end)| text[start..end].to_string()).collect();
+        let (model, embeddings) = self.embed(&pieces).await;
+        let page = CachedPage {
+            url: url.to_string(),
+            final_url: final_url.to_string(),
+            fetched_at: Utc::now(),
+            extractor: extractor.to_string(),
+            text: text.to_string(),
+            model,
+            chunks: ranges
+                .into_iter()
+                .zip(embeddings)
+                .map(|((start, end), embedding)| Chunk { start, end, embedding })
+                .collect(),
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(url), serde_json::to_string(&page)?)?;
+        debug!(url, chunks = page.chunks.len(), model = %page.model, "cached web page");
+        self.prune();
+        Ok(())
+    }
+
+    /// The `limit` chunks of cached pages (only `url`'s, when given) that
+    /// best match `query`, best first.
+    pub async fn recall(&self, query: &str, url: Option<&str>, limit: usize) -> Result<Vec<Recalled>> {
+        let now = Utc::now();
+        let pages: Vec<CachedPage> = match url {
+            Some(url) => self.load(&self.path(url)).into_iter().collect(),
+            None => match std::fs::read_dir(&self.dir) {
+                Ok(entries) => entries.flatten().filter_map(|e| self.load(&e.path())).collect(),
+                Err(_) => Vec::new(),
+            },
+        };
+        let pages: Vec<CachedPage> = pages.into_iter().filter(|page| self.is_fresh(page, now)).collect();
+
+        let local_query = local_embedding(query);
+        let remote_query = match self.embedder {
+            Some(ref embedder) if pages.iter().any(|p| p.model == embedder.model()) => {
+                Some(embedder.embed(&[query.to_string()]).await?.remove(0))
+            }
+            _ => None,
+        };
+
+        let mut hits = Vec::new();
+        for page in &pages {
+            let query = if page.model == LOCAL_MODEL {
+                &local_query
+            } else {
+                match (&self.embedder, &remote_query) {
+                    (Some(embedder), Some(query)) if page.model == embedder.model() => query,
+                    // Embedded with another model: vectors don't compare
+                    _ => continue,
+                }
+            };
+            for chunk in &page.chunks {
+                let score = cosine(query, &chunk.embedding);
+                if score > 0.0 {
+                    hits.push(Recalled {
+                        url: page.url.clone(),
+                        fetched_at: page.fetched_at,
+                        score,
+                        text: page.text.get(chunk.start..chunk.end).unwrap_or_default().trim().to_string(),
+                    });
+                }
+            }
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Embed `texts`, falling back to the local embedding if the
+    /// configured model fails. Returns the model used and the vectors.
+    async fn embed(&self, texts: &[String]) -> (String, Vec<Vec<f32>>) {
+        if let Some(ref embedder) = self.embedder {
+            match embedder.embed(texts).await {
+                Ok(vectors) => return (embedder.model().to_string(), vectors),
+                Err(e) => warn!(model = %embedder.model(), error = %e, "embedding failed, using the local embedding"),
+            }
+        }
+        (LOCAL_MODEL.to_string(), texts.iter().map(|t| local_embedding(t)).collect())
+    }
+
+    /// Remove expired pages.
+    fn prune(&self) {
+        let now = Utc::now();
+        let Ok(entries) = std::fs::read_dir(&self.dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let expired = self.load(&path).is_none_or(|page| !self.is_fresh(&page, now));
+            if expired && path.extension().is_some_and(|e| e == "json") {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
+    fn is_fresh(&self, page: &CachedPage, now: DateTime<Utc>) -> bool {
+        now - page.fetched_at < self.ttl
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let name: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+        self.dir.join(format!("{name}.json"))
+    }
+
+    fn load(&self, path: &Path) -> Option<CachedPage> {
+        let text = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+}
+
+/// Byte ranges of about [`CHUNK_CHARS`] covering `text`, cut at line
+/// breaks where possible; blank ranges are skipped.
+fn chunk_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = (start + CHUNK_CHARS).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end < text.len() {
+            if let Some(newline) = text[start..end].rfind('\n').filter(|&i| i > 0) {
+                end = start + newline + 1;
+            }
+        }
+        if !text[start..end].trim().is_empty() {
+            ranges.push((start, end));
+        }
+        start = end;
+    }
+    ranges
+}
+
+/// Keyword-hashing embedding: lowercase words hashed (FNV-1a) into
+/// [`LOCAL_DIMS`] buckets, L2-normalized.
+fn local_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0f32; LOCAL_DIMS];
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| w.chars().count() > 1) {
+        let hash = word
+            .to_lowercase()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+        vector[(hash % LOCAL_DIMS as u64) as usize] += 1.0;
+    }
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norms > 0.0 {
+        dot / norms
+    } else {
+        0.0
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recall_finds_matching_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PageCache::new(dir.path().to_path_buf(), 24);
+
+        let install = "Installation\n".to_string() + &"Run cargo install oxibot to install the binary.\n".repeat(60);
+        let config = "Configuration\n".to_string() + &"Set the telegram token in config.json under channels.\n".repeat(60);
+        cache
+            .put("https://docs.example.com/guide", "https://docs.example.com/guide/", "text", &(install + &config))
+            .await
+            .unwrap();
+        cache.put("https://example.com/other", "https://example.com/other", "text", "Unrelated page about cats.").await.unwrap();
+
+        let page = cache.get("https://docs.example.com/guide").unwrap();
+        assert_eq!(page.model, LOCAL_MODEL);
+        assert!(page.chunks.len() > 2);
+        assert!(page.chunks.iter().all(|c| page.text[..c.end].ends_with('\n')));
+
+        let hits = cache.recall("where does the telegram token go?", None, 2).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].url, "https://docs.example.com/guide");
+        assert!(hits[0].text.contains("telegram token"), "{}", hits[0].text);
+        assert!(hits[0].score >= hits[1].score);
+
+        let hits = cache.recall("telegram token", Some("https://example.com/other"), 3).await.unwrap();
+        assert!(hits.is_empty());
+        assert!(cache.recall("telegram", Some("https://example.com/missing"), 3).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_pages_are_ignored_and_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PageCache::new(dir.path().to_path_buf(), 24);
+        cache.put("https://example.com/a", "https://example.com/a", "text", "Some page").await.unwrap();
+        assert!(cache.get("https://example.com/a").is_some());
+
+        let expired = PageCache::new(dir.path().to_path_buf(), 0);
+        assert!(expired.get("https://example.com/a").is_none());
+        assert!(expired.recall("page", None, 3).await.unwrap().is_empty());
+
+        // The next put drops expired pages
+        expired.put("https://example.com/b", "https://example.com/b", "text", "Other page").await.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,78 @@
+//! Persona presets — canned styles for how the agent answers.
+//!
+//! A persona adds a `## Persona` section to the system prompt. The default
+//! comes from `agents.defaults.persona`; the `/persona` chat command
+//! overrides it per session, stored in the session's `persona` metadata.
+
+/// Session metadata field holding the persona override.
+pub const SESSION_FIELD: &str = "persona";
+
+/// Session override that disables the configured default persona.
+pub const NONE: &str = "none";
+
+/// A named persona preset.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Persona {
+    /// Name used in config and `/persona`.
+    pub name: &'static str,
+    /// One-line summary shown by `/persona`.
+    pub summary: &'static str,
+    /// Instructions added to the system prompt.
+    pub prompt: &'static str,
+}
+
+/// Built-in presets.
+pub const PRESETS: &[Persona] = &[
+    Persona {
+        name: "concise-assistant",
+        summary: "Short, direct answers without filler",
+        prompt: "Be concise. Answer in as few words as the question allows, lead with the \
+                 answer, and skip preambles, recaps and offers of further help. Use lists \
+                 only when they are shorter than prose. Ask a clarifying question only when \
+                 you cannot proceed without it.",
+    },
+    Persona {
+        name: "verbose-explainer",
+        summary: "Thorough explanations with reasoning and examples",
+        prompt: "Explain thoroughly. Start with a short answer, then walk through the \
+                 reasoning step by step, define terms the user may not know, and include a \
+                 concrete example. Point out common pitfalls and alternatives, and end with \
+                 a brief summary when the answer is long.",
+    },
+    Persona {
+        name: "sysadmin-copilot",
+        summary: "Terse ops partner: commands first, safety checks included",
+        prompt: "Act as an experienced systems administrator pairing with the user. Prefer \
+                 exact commands and config snippets over descriptions, and state the OS or \
+                 tool version they assume. Inspect before changing: check state with \
+                 read-only commands first, and mention how to verify and roll back anything \
+                 destructive. Flag security implications plainly.",
+    },
+];
+
+/// Look up a preset by name.
+pub fn find(name: &str) -> Option<&'static Persona> {
+    PRESETS.iter().find(|p| p.name == name)
+}
+
+/// Comma-separated preset names, for error messages.
+pub fn names() -> String {
+    PRESETS.iter().map(|p| p.name).collect::<Vec<_>>().join(", ")
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_presets() {
+        assert_eq!(find("sysadmin-copilot").unwrap().name, "sysadmin-copilot");
+        assert!(find("pirate").is_none());
+        assert!(find(NONE).is_none());
+        assert_eq!(names(), "concise-assistant, verbose-explainer, sysadmin-copilot");
+    }
+}
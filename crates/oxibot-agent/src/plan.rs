@@ -0,0 +1,286 @@
+//! Plans — "plan first" mode for multi-step requests.
+//!
+//! With plan mode on, the agent answers complex requests by proposing a
+//! numbered plan (`propose_plan`). The user approves or rejects it (unless
+//! plans are auto-approved), and the agent then works through the steps,
+//! reporting each one with `plan_step`. The plan is stored on the session,
+//! so it survives restarts and is shown to the agent on later turns until
+//! it is finished.
+//!
+//! ```text
+//! Proposed ──approve──▶ Approved ──all steps done/skipped──▶ Done
+//!     └──────reject───▶ Rejected
+//! ```
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use oxibot_core::session::manager::SessionManager;
+
+/// Session metadata field holding the current plan (JSON).
+pub const SESSION_FIELD: &str = "plan";
+
+/// Most steps a plan may have.
+pub const MAX_STEPS: usize = 20;
+
+/// Where a plan is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanState {
+    /// Waiting for the user's approval.
+    Proposed,
+    /// Being executed.
+    Approved,
+    /// The user said no (or didn't answer).
+    Rejected,
+    /// Every step is done or skipped.
+    Done,
+}
+
+/// Progress of one step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    InProgress,
+    Done,
+    Skipped,
+}
+
+impl StepStatus {
+    fn icon(self) -> &'static str {
+        match self {
+            StepStatus::Pending => "⬜",
+            StepStatus::InProgress => "⏳",
+            StepStatus::Done => "✅",
+            StepStatus::Skipped => "⏭️",
+        }
+    }
+}
+
+/// One step of a plan.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub text: String,
+    pub status: StepStatus,
+    /// Outcome or reason for skipping, from the agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A numbered plan for one request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    pub goal: String,
+    pub steps: Vec<PlanStep>,
+    pub state: PlanState,
+    pub created: DateTime<Utc>,
+}
+
+impl Plan {
+    /// A proposed plan. Fails without steps or with more than [`MAX_STEPS`].
+    pub fn new(goal: &str, steps: &[String]) -> Result<Self> {
+        let steps: Vec<PlanStep> = steps
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|text| PlanStep {
+                text: text.to_string(),
+                status: StepStatus::Pending,
+                note: None,
+            })
+            .collect();
+        if steps.is_empty() {
+            bail!("a plan needs at least one step");
+        }
+        if steps.len() > MAX_STEPS {
+            bail!("a plan can have at most {MAX_STEPS} steps");
+        }
+        Ok(Self {
+            goal: goal.trim().to_string(),
+            steps,
+            state: PlanState::Proposed,
+            created: Utc::now(),
+        })
+    }
+
+    /// The session's plan, if any.
+    pub fn load(sessions: &SessionManager, session_key: &str) -> Option<Self> {
+        let json = sessions.metadata(session_key, SESSION_FIELD)?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Store this plan as the session's plan.
+    pub fn save(&self, sessions: &SessionManager, session_key: &str) {
+        let json = serde_json::to_string(self).expect("plan serializes");
+        sessions.set_metadata(session_key, SESSION_FIELD, Some(&json));
+    }
+
+    /// Remove the session's plan.
+    pub fn clear(sessions: &SessionManager, session_key: &str) {
+        sessions.set_metadata(session_key, SESSION_FIELD, None);
+    }
+
+    /// Whether the agent still has to act on this plan.
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, PlanState::Proposed | PlanState::Approved)
+    }
+
+    pub fn approve(&mut self) -> Result<()> {
+        self.expect_state(PlanState::Proposed)?;
+        self.state = PlanState::Approved;
+        Ok(())
+    }
+
+    pub fn reject(&mut self) -> Result<()> {
+        self.expect_state(PlanState::Proposed)?;
+        self.state = PlanState::Rejected;
+        Ok(())
+    }
+
+    /// Update 1-based step `number`. The plan is done once no step is
+    /// pending or in progress.
+    pub fn update_step(&mut self, number: usize, status: StepStatus, note: Option<String>) -> Result<&PlanStep> {
+        self.expect_state(PlanState::Approved)?;
+        if status == StepStatus::Pending {
+            bail!("a step can only be marked in_progress, done or skipped");
+        }
+        let count = self.steps.len();
+        let Some(step) = number.checked_sub(1).and_then(|i| self.steps.get_mut(i)) else {
+            bail!("no step {number} (the plan has {count})");
+        };
+        step.status = status;
+        if note.is_some() {
+            step.note = note;
+        }
+        if self
+            .steps
+            .iter()
+            .all(|s| matches!(s.status, StepStatus::Done | StepStatus::Skipped))
+        {
+            self.state = PlanState::Done;
+        }
+        Ok(&self.steps[number - 1])
+    }
+
+    /// Number of steps done or skipped.
+    pub fn completed(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s.status, StepStatus::Done | StepStatus::Skipped))
+            .count()
+    }
+
+    /// The plan as a numbered checklist.
+    pub fn render(&self) -> String {
+        let mut out = format!("📋 {}\n", self.goal);
+        for (i, step) in self.steps.iter().enumerate() {
+            out.push_str(&format!("{} {}. {}", step.status.icon(), i + 1, step.text));
+            if let Some(ref note) = step.note {
+                out.push_str(&format!(" — {note}"));
+            }
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+
+    fn expect_state(&self, state: PlanState) -> Result<()> {
+        if self.state != state {
+            bail!("the plan is {:?}, not {:?}", self.state, state);
+        }
+        Ok(())
+    }
+}
+
+/// Instructions for the system prompt, with the session's active plan.
+pub fn prompt_section(plan: Option<&Plan>) -> String {
+    let mut out = String::from(
+        "## Plans\n\n\
+         For requests that take several distinct steps (research plus writing, \
+         changes across files, anything long-running), call `propose_plan` with \
+         a goal and numbered steps before doing the work, and wait for its \
+         result. Once approved, work through the steps in order, calling \
+         `plan_step` when you start and finish each one. Answer simple \
+         requests directly without a plan.\n",
+    );
+    match plan {
+        Some(plan) if plan.state == PlanState::Approved => {
+            out.push_str(&format!(
+                "\nCurrent plan (approved, {}/{} steps complete):\n{}\n",
+                plan.completed(),
+                plan.steps.len(),
+                plan.render()
+            ));
+        }
+        Some(plan) if plan.state == PlanState::Proposed => {
+            out.push_str(&format!("\nProposed plan, not approved yet:\n{}\n", plan.render()));
+        }
+        _ => {}
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_plan_state_machine() {
+        let mut plan = Plan::new("Write report", &steps(&["Research", " ", "Draft", "Review"])).unwrap();
+        assert_eq!(plan.steps.len(), 3);
+        assert!(plan.update_step(1, StepStatus::Done, None).is_err());
+
+        plan.approve().unwrap();
+        assert!(plan.reject().is_err());
+        plan.update_step(1, StepStatus::InProgress, None).unwrap();
+        plan.update_step(1, StepStatus::Done, Some("5 sources".into())).unwrap();
+        plan.update_step(2, StepStatus::Done, None).unwrap();
+        assert!(plan.update_step(4, StepStatus::Done, None).is_err());
+        assert!(plan.update_step(3, StepStatus::Pending, None).is_err());
+        assert_eq!(plan.state, PlanState::Approved);
+        plan.update_step(3, StepStatus::Skipped, Some("no reviewer".into())).unwrap();
+        assert_eq!(plan.state, PlanState::Done);
+        assert!(!plan.is_active());
+
+        assert_eq!(
+            plan.render(),
+            "📋 Write report\n✅ 1. Research — 5 sources\n✅ 2. Draft\n⏭️ 3. Review — no reviewer"
+        );
+    }
+
+    #[test]
+    fn test_plan_validation() {
+        assert!(Plan::new("x", &[]).is_err());
+        assert!(Plan::new("x", &steps(&[""])).is_err());
+        let many: Vec<String> = (0..=MAX_STEPS).map(|i| i.to_string()).collect();
+        assert!(Plan::new("x", &many).is_err());
+    }
+
+    #[test]
+    fn test_plan_persists_in_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+        assert!(Plan::load(&sessions, "cli:direct").is_none());
+
+        let mut plan = Plan::new("Trip", &steps(&["Flights", "Hotel"])).unwrap();
+        plan.approve().unwrap();
+        plan.save(&sessions, "cli:direct");
+
+        let reopened = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+        let loaded = Plan::load(&reopened, "cli:direct").unwrap();
+        assert_eq!(loaded, plan);
+        assert!(prompt_section(Some(&loaded)).contains("approved, 0/2 steps complete"));
+
+        Plan::clear(&reopened, "cli:direct");
+        assert!(Plan::load(&reopened, "cli:direct").is_none());
+    }
+}
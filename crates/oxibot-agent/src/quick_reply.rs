@@ -0,0 +1,172 @@
+//! Quick replies — answer small talk without the full agent loop.
+//!
+//! "thanks", "ok" and greetings don't need the system prompt, 50 messages of
+//! history and the tool list. Exact small-talk phrases get a canned reply
+//! with no LLM call at all; other short messages are classified by a single
+//! call on a cheap model, which also writes the reply when the message is
+//! trivial. Anything else goes through the normal loop.
+//!
+//! A short message that answers the assistant's last question ("ok" to
+//! "Shall I delete them?") is never treated as small talk.
+
+use serde::Deserialize;
+
+use oxibot_core::config::schema::QuickReplyConfig;
+use oxibot_core::types::Message;
+
+/// Kind of small talk recognised by the quick-reply path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Intent {
+    /// "thanks", "thank you", "gracias"
+    Thanks,
+    /// "ok", "got it", "👍"
+    Acknowledgement,
+    /// "hi", "hello", "good morning"
+    Greeting,
+    /// Anything that needs the full agent.
+    #[serde(other)]
+    Other,
+}
+
+const THANKS: &[&str] = &[
+    "thanks", "thank you", "thx", "ty", "thanks a lot", "thank you so much", "many thanks",
+    "cheers", "gracias", "muchas gracias", "merci", "danke", "obrigado", "obrigada", "grazie",
+];
+const ACKNOWLEDGEMENTS: &[&str] = &[
+    "ok", "okay", "k", "kk", "got it", "cool", "great", "nice", "perfect", "awesome",
+    "sounds good", "vale", "de acuerdo", "genial", "d'accord", "👍", "👌", "🙏", "ok thanks",
+];
+const GREETINGS: &[&str] = &[
+    "hi", "hello", "hey", "hiya", "yo", "good morning", "good afternoon", "good evening",
+    "hola", "buenos días", "buenas", "bonjour", "salut", "hallo", "ciao", "olá",
+];
+
+/// Whether `text` is short enough to be considered.
+pub fn is_candidate(text: &str, config: &QuickReplyConfig) -> bool {
+    let words = text.split_whitespace().count();
+    words > 0 && words <= config.max_words
+}
+
+/// Lowercase and strip surrounding punctuation: "Thanks!!" → "thanks".
+fn normalize(text: &str) -> String {
+    text.trim()
+        .trim_matches(|c: char| c.is_ascii_punctuation() || c == '¡' || c == '¿')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Whether the assistant's last message asked the user something.
+fn awaits_answer(last_assistant: Option<&str>) -> bool {
+    last_assistant.is_some_and(|text| text.contains('?'))
+}
+
+/// Canned reply for exact small-talk phrases, without any LLM call.
+pub fn canned_reply(text: &str, last_assistant: Option<&str>) -> Option<&'static str> {
+    let text = normalize(text);
+    if GREETINGS.contains(&text.as_str()) {
+        return Some("Hi! How can I help?");
+    }
+    // "ok" / "thanks" may be answering a question — let the agent handle it
+    if awaits_answer(last_assistant) {
+        return None;
+    }
+    if THANKS.contains(&text.as_str()) {
+        Some("You're welcome! 😊")
+    } else if ACKNOWLEDGEMENTS.contains(&text.as_str()) {
+        Some("👍")
+    } else {
+        None
+    }
+}
+
+/// Messages for the classification call.
+pub fn classification_messages(text: &str, last_assistant: Option<&str>) -> Vec<Message> {
+    let system = "You triage chat messages for an assistant. Decide whether the user's \
+        message is trivial small talk that needs no tools, memory or context: thanks, a \
+        bare acknowledgement, or a greeting. If it asks for anything, answers a question, \
+        confirms or declines an action, or you are unsure, the intent is \"other\".\n\n\
+        Respond with JSON only: {\"intent\": \"thanks\" | \"acknowledgement\" | \"greeting\" | \
+        \"other\", \"confidence\": 0.0-1.0, \"reply\": \"short friendly reply in the user's \
+        language\"}";
+    let mut user = String::new();
+    if let Some(prev) = last_assistant {
+        user.push_str(&format!("Assistant's previous message:\n{prev}\n\n"));
+    }
+    user.push_str(&format!("User message:\n{text}"));
+    vec![Message::system(system), Message::user(user)]
+}
+
+#[derive(Deserialize)]
+struct Classification {
+    intent: Intent,
+    #[serde(default)]
+    confidence: f64,
+    #[serde(default)]
+    reply: String,
+}
+
+/// Parse the classifier output; returns the reply to send when the message
+/// is small talk with at least `min_confidence`.
+pub fn parse_classification(output: &str, min_confidence: f64) -> Option<(Intent, String)> {
+    let start = output.find('{')?;
+    let end = output.rfind('}')?;
+    let parsed: Classification = serde_json::from_str(output.get(start..=end)?).ok()?;
+    let reply = parsed.reply.trim();
+    if parsed.intent == Intent::Other || parsed.confidence < min_confidence || reply.is_empty() {
+        return None;
+    }
+    Some((parsed.intent, reply.to_string()))
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canned_reply() {
+        assert_eq!(canned_reply("Thanks!!", None), Some("You're welcome! 😊"));
+        assert_eq!(canned_reply("  OK ", Some("Done, the file is saved.")), Some("👍"));
+        assert_eq!(canned_reply("¡Hola!", None), Some("Hi! How can I help?"));
+        assert_eq!(canned_reply("ok", Some("Shall I delete them?")), None);
+        assert_eq!(canned_reply("thanks, now book it", None), None);
+    }
+
+    #[test]
+    fn test_is_candidate() {
+        let config = QuickReplyConfig::default();
+        assert!(is_candidate("thanks a lot mate", &config));
+        assert!(!is_candidate("", &config));
+        assert!(!is_candidate("please summarize the report I sent yesterday", &config));
+    }
+
+    #[test]
+    fn test_parse_classification() {
+        let out = "```json\n{\"intent\": \"thanks\", \"confidence\": 0.95, \"reply\": \"De nada!\"}\n```";
+        assert_eq!(parse_classification(out, 0.8), Some((Intent::Thanks, "De nada!".into())));
+        let low = r#"{"intent": "greeting", "confidence": 0.5, "reply": "Hi"}"#;
+        assert_eq!(parse_classification(low, 0.8), None);
+        let other = r#"{"intent": "other", "confidence": 0.99, "reply": "x"}"#;
+        assert_eq!(parse_classification(other, 0.8), None);
+        let unknown = r#"{"intent": "question", "confidence": 0.99, "reply": "x"}"#;
+        assert_eq!(parse_classification(unknown, 0.8), None);
+        assert_eq!(parse_classification("not json", 0.8), None);
+    }
+
+    #[test]
+    fn test_classification_messages_include_context() {
+        let msgs = classification_messages("sure", Some("Want me to book it?"));
+        assert_eq!(msgs.len(), 2);
+        let Message::User { content: oxibot_core::types::MessageContent::Text(text) } = &msgs[1] else {
+            panic!("expected user text");
+        };
+        assert!(text.contains("Want me to book it?"));
+        assert!(text.ends_with("User message:\nsure"));
+    }
+}
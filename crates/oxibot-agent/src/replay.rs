@@ -0,0 +1,311 @@
+//! Replay — run a recorded session again to reproduce what happened.
+//!
+//! A session's [transcript](oxibot_core::session::transcript) is split into
+//! turns; [`ReplayProvider`] plays back each turn's recorded LLM responses
+//! in place of the real provider, so the agent makes the same tool calls
+//! again. [`compare`] then shows where the tools now answer differently.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::debug;
+
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::session::transcript::{TranscriptEvent, TranscriptLog};
+use oxibot_core::types::{LlmResponse, Message, ToolDefinition};
+use oxibot_providers::traits::{LlmProvider, LlmRequestConfig};
+
+use crate::agent_loop::AgentLoop;
+
+/// Answer of the provider once a turn's recorded responses are used up.
+const OUT_OF_SCRIPT: &str = "[replay: no more recorded LLM responses for this turn]";
+
+// ─────────────────────────────────────────────
+// Turns
+// ─────────────────────────────────────────────
+
+/// One recorded turn: the user's message and what followed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayTurn {
+    pub user: String,
+    /// LLM responses and tool results, in order.
+    pub steps: Vec<TranscriptEvent>,
+    /// The reply sent (`None` if the turn never finished).
+    pub reply: Option<String>,
+}
+
+impl ReplayTurn {
+    /// The recorded LLM responses.
+    fn responses(&self) -> VecDeque<LlmResponse> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step {
+                TranscriptEvent::Assistant { content, tool_calls, .. } => Some(LlmResponse {
+                    content: content.clone(),
+                    tool_calls: tool_calls.clone(),
+                    ..Default::default()
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// `(tool, result)` of each tool call.
+    pub fn tool_results(&self) -> Vec<(&str, &str)> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step {
+                TranscriptEvent::ToolResult { tool, result, .. } => Some((tool.as_str(), result.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Group transcript events into turns. Steps before the first user
+/// message are dropped.
+pub fn split_turns(events: &[TranscriptEvent]) -> Vec<ReplayTurn> {
+    let mut turns: Vec<ReplayTurn> = Vec::new();
+    for event in events {
+        match event {
+            TranscriptEvent::User { content, .. } => turns.push(ReplayTurn {
+                user: content.clone(),
+                ..Default::default()
+            }),
+            TranscriptEvent::Reply { content, .. } => {
+                if let Some(turn) = turns.last_mut() {
+                    turn.reply = Some(content.clone());
+                }
+            }
+            step => {
+                if let Some(turn) = turns.last_mut() {
+                    turn.steps.push(step.clone());
+                }
+            }
+        }
+    }
+    turns
+}
+
+// ─────────────────────────────────────────────
+// ReplayProvider
+// ─────────────────────────────────────────────
+
+/// LLM provider answering with the recorded responses of the current turn.
+pub struct ReplayProvider {
+    model: String,
+    script: Mutex<VecDeque<LlmResponse>>,
+}
+
+impl ReplayProvider {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            script: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Play back `turn` from the next call on. A turn answered without the
+    /// full loop (quick replies) gets its reply as the only response.
+    pub fn begin_turn(&self, turn: &ReplayTurn) {
+        let mut responses = turn.responses();
+        if responses.is_empty() {
+            responses.push_back(LlmResponse {
+                content: turn.reply.clone(),
+                ..Default::default()
+            });
+        }
+        *self.script.lock().unwrap() = responses;
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ReplayProvider {
+    async fn chat(
+        &self,
+        _messages: &[Message],
+        _tools: Option<&[ToolDefinition]>,
+        _model: &str,
+        _config: &LlmRequestConfig,
+    ) -> LlmResponse {
+        self.script.lock().unwrap().pop_front().unwrap_or_else(|| {
+            debug!("replay ran past the recorded responses");
+            LlmResponse {
+                content: Some(OUT_OF_SCRIPT.into()),
+                ..Default::default()
+            }
+        })
+    }
+
+    fn default_model(&self) -> &str {
+        &self.model
+    }
+
+    fn display_name(&self) -> &str {
+        "replay"
+    }
+}
+
+// ─────────────────────────────────────────────
+// Re-running
+// ─────────────────────────────────────────────
+
+/// Run `turns` of `session_key` again on `agent` and return them as
+/// replayed.
+///
+/// `agent` must use `provider` and log its transcript to `transcripts`.
+/// Turns run in the same session, so relative paths resolve to the same
+/// scratch directory; give `agent` its own session store.
+pub async fn rerun(
+    agent: &AgentLoop,
+    provider: &ReplayProvider,
+    transcripts: &TranscriptLog,
+    session_key: &str,
+    turns: &[ReplayTurn],
+) -> Result<Vec<ReplayTurn>> {
+    let (channel, chat_id) = session_key.split_once(':').unwrap_or(("cli", session_key));
+    let before = transcripts.read(session_key).len();
+    for turn in turns {
+        provider.begin_turn(turn);
+        let msg = InboundMessage::new(channel, "user", chat_id, &turn.user);
+        agent.process_message(&msg).await?;
+    }
+    Ok(split_turns(&transcripts.read(session_key)[before..]))
+}
+
+/// A step that came out differently when replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// 1-based turn number.
+    pub turn: usize,
+    /// What differs (e.g. "tool call 2 (read_file)" or "reply").
+    pub step: String,
+    pub recorded: String,
+    pub replayed: String,
+}
+
+/// Steps of `replayed` that differ from `recorded`.
+pub fn compare(recorded: &[ReplayTurn], replayed: &[ReplayTurn]) -> Vec<Divergence> {
+    const MISSING: &str = "(none)";
+    let mut divergences = Vec::new();
+    for (i, (before, after)) in recorded.iter().zip(replayed).enumerate() {
+        let (calls_before, calls_after) = (before.tool_results(), after.tool_results());
+        for j in 0..calls_before.len().max(calls_after.len()) {
+            let (a, b) = (calls_before.get(j), calls_after.get(j));
+            if a == b {
+                continue;
+            }
+            let tool = a.or(b).map(|(tool, _)| *tool).unwrap_or_default();
+            divergences.push(Divergence {
+                turn: i + 1,
+                step: format!("tool call {} ({tool})", j + 1),
+                recorded: a.map_or(MISSING, |(_, r)| r).to_string(),
+                replayed: b.map_or(MISSING, |(_, r)| r).to_string(),
+            });
+        }
+        if before.reply != after.reply {
+            divergences.push(Divergence {
+                turn: i + 1,
+                step: "reply".into(),
+                recorded: before.reply.clone().unwrap_or_else(|| MISSING.into()),
+                replayed: after.reply.clone().unwrap_or_else(|| MISSING.into()),
+            });
+        }
+    }
+    divergences
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use chrono::Utc;
+    use oxibot_core::bus::queue::MessageBus;
+    use oxibot_core::session::SessionManager;
+    use oxibot_core::types::ToolCall;
+
+    fn recorded(path: &str) -> Vec<TranscriptEvent> {
+        let now = Utc::now();
+        vec![
+            TranscriptEvent::User { timestamp: now, content: "what's in notes.txt?".into() },
+            TranscriptEvent::Assistant {
+                timestamp: now,
+                model: "gpt-4o".into(),
+                content: None,
+                tool_calls: vec![ToolCall::new("c1", "read_file", format!(r#"{{"path":"{path}"}}"#))],
+            },
+            TranscriptEvent::ToolResult {
+                timestamp: now,
+                call_id: "c1".into(),
+                tool: "read_file".into(),
+                result: "buy milk".into(),
+                duration_ms: 1,
+            },
+            TranscriptEvent::Assistant {
+                timestamp: now,
+                model: "gpt-4o".into(),
+                content: Some("It says: buy milk".into()),
+                tool_calls: Vec::new(),
+            },
+            TranscriptEvent::Reply { timestamp: now, content: "It says: buy milk".into() },
+        ]
+    }
+
+    #[test]
+    fn test_split_turns() {
+        let mut events = recorded("notes.txt");
+        events.insert(0, TranscriptEvent::Reply { timestamp: Utc::now(), content: "orphan".into() });
+        events.push(TranscriptEvent::User { timestamp: Utc::now(), content: "thanks".into() });
+
+        let turns = split_turns(&events);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].steps.len(), 3);
+        assert_eq!(turns[0].tool_results(), [("read_file", "buy milk")]);
+        assert_eq!(turns[0].reply.as_deref(), Some("It says: buy milk"));
+        assert_eq!(turns[1], ReplayTurn { user: "thanks".into(), ..Default::default() });
+    }
+
+    #[tokio::test]
+    async fn test_rerun_reports_divergence() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        let notes = workspace.join("notes.txt");
+        std::fs::write(&notes, "buy bread").unwrap();
+
+        let provider = Arc::new(ReplayProvider::new("gpt-4o"));
+        let transcripts = TranscriptLog::new(Some(dir.path().join("transcripts")));
+        let agent = AgentLoop::new(
+            Arc::new(MessageBus::new(16)),
+            provider.clone(),
+            workspace,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+            Some(SessionManager::new(Some(dir.path().join("sessions"))).unwrap()),
+            None,
+        )
+        .with_transcripts(Some(TranscriptLog::new(Some(dir.path().join("transcripts")))));
+
+        let turns = split_turns(&recorded(&notes.display().to_string()));
+        let replayed = rerun(&agent, &provider, &transcripts, "telegram:42", &turns).await.unwrap();
+
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].reply.as_deref(), Some("It says: buy milk"));
+        let divergences = compare(&turns, &replayed);
+        assert_eq!(divergences.len(), 1, "{divergences:?}");
+        assert_eq!(divergences[0].step, "tool call 1 (read_file)");
+        assert_eq!(divergences[0].replayed, "buy bread");
+        assert!(compare(&turns, &turns).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+//! Canned responses — greeting and help answered without the LLM.
+//!
+//! The `/start` and `/help` chat commands (and a bare "help" in a direct
+//! message) get the greeting and the help text. With `greetNewChats`, a conversation's first
+//! message is also preceded by the greeting. Texts come from
+//! `agents.defaults.responses`, then from `prompts/` in the workspace, then
+//! the built-in defaults, and may use `{agent_name}`, `{name}`, `{channel}`
+//! and `{commands}`.
+
+use std::path::{Path, PathBuf};
+
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::config::schema::ResponsesConfig;
+
+const DEFAULT_GREETING: &str = "👋 Hi {name}! I'm {agent_name}, your AI assistant.\n\n\
+    Send me any message and I'll do my best to help! Send /help to see what else I can do.";
+
+const DEFAULT_HELP: &str = "🤖 **{agent_name} Commands**\n\n\
+    {commands}\n\n\
+    Just send me text, photos, voice messages, or documents and I'll process them!";
+
+/// Which canned response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Canned {
+    Greeting,
+    Help,
+}
+
+impl Canned {
+    /// File name under `prompts/`.
+    fn file_name(self) -> &'static str {
+        match self {
+            Canned::Greeting => "greeting.md",
+            Canned::Help => "help.md",
+        }
+    }
+
+    /// The canned response plain `text` asks for, if any: a bare "help" in
+    /// a direct message. (`/start` and `/help` are chat commands.)
+    pub fn requested_by(text: &str, direct: bool) -> Option<Self> {
+        let text = text.trim().trim_end_matches(['?', '!', '.']);
+        (direct && text.eq_ignore_ascii_case("help")).then_some(Canned::Help)
+    }
+}
+
+/// Whether `msg` comes from a one-to-one chat (as far as its channel says).
+pub fn is_direct(msg: &InboundMessage) -> bool {
+    let meta = |key: &str| msg.metadata.get(key).map(String::as_str);
+    meta("is_group") != Some("true")
+        && meta("channel_type").is_none_or(|t| t == "im")
+        && meta("guild_id").is_none()
+}
+
+/// The configured greeting and help texts.
+#[derive(Clone, Debug)]
+pub struct CannedResponses {
+    config: ResponsesConfig,
+    prompts_dir: PathBuf,
+}
+
+impl CannedResponses {
+    /// Responses from `config`, with overrides under `workspace/prompts/`.
+    pub fn new(config: ResponsesConfig, workspace: &Path) -> Self {
+        Self {
+            config,
+            prompts_dir: workspace.join("prompts"),
+        }
+    }
+
+    /// Whether new conversations on `channel` are greeted.
+    pub fn greets_new_chats(&self, channel: &str) -> bool {
+        self.config
+            .channels
+            .get(channel)
+            .and_then(|c| c.greet_new_chats)
+            .unwrap_or(self.config.greet_new_chats)
+    }
+
+    /// The response for `channel`, with the template variables filled in.
+    /// `name` is the sender's name, if known; `commands` lists the chat
+    /// commands.
+    pub fn render(&self, kind: Canned, channel: &str, name: Option<&str>, commands: &str) -> String {
+        let overrides = self.config.channels.get(channel);
+        let (channel_text, default_text) = match kind {
+            Canned::Greeting => (overrides.and_then(|c| c.greeting.as_deref()), &self.config.greeting),
+            Canned::Help => (overrides.and_then(|c| c.help.as_deref()), &self.config.help),
+        };
+        let template = channel_text
+            .filter(|t| !t.trim().is_empty())
+            .map(str::to_string)
+            .or_else(|| self.read_prompt(&self.prompts_dir.join(channel), kind))
+            .or_else(|| Some(default_text.clone()).filter(|t| !t.trim().is_empty()))
+            .or_else(|| self.read_prompt(&self.prompts_dir, kind))
+            .unwrap_or_else(|| {
+                match kind {
+                    Canned::Greeting => DEFAULT_GREETING,
+                    Canned::Help => DEFAULT_HELP,
+                }
+                .to_string()
+            });
+        template
+            .replace("{agent_name}", &self.config.agent_name)
+            .replace("{name}", name.filter(|n| !n.is_empty()).unwrap_or("there"))
+            .replace("{channel}", channel)
+            .replace("{commands}", commands)
+    }
+
+    fn read_prompt(&self, dir: &Path, kind: Canned) -> Option<String> {
+        std::fs::read_to_string(dir.join(kind.file_name()))
+            .ok()
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::config::schema::ChannelResponses;
+
+    #[test]
+    fn test_requested_by() {
+        assert_eq!(Canned::requested_by("Help?", true), Some(Canned::Help));
+        assert_eq!(Canned::requested_by("/help", true), None);
+        assert_eq!(Canned::requested_by("help", false), None);
+        assert_eq!(Canned::requested_by("help me with this", true), None);
+    }
+
+    #[test]
+    fn test_is_direct() {
+        let mut msg = InboundMessage::new("slack", "U1", "D1", "help");
+        msg.metadata.insert("channel_type".into(), "im".into());
+        assert!(is_direct(&msg));
+        msg.metadata.insert("channel_type".into(), "channel".into());
+        assert!(!is_direct(&msg));
+
+        let mut msg = InboundMessage::new("telegram", "1|bob", "-100", "help");
+        msg.metadata.insert("is_group".into(), "true".into());
+        assert!(!is_direct(&msg));
+    }
+
+    #[test]
+    fn test_render_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = ResponsesConfig {
+            agent_name: "Ada".into(),
+            ..Default::default()
+        };
+        config.channels.insert(
+            "slack".into(),
+            ChannelResponses {
+                help: Some("Slack help for {name} on {channel}".into()),
+                greet_new_chats: Some(true),
+                ..Default::default()
+            },
+        );
+        let responses = CannedResponses::new(config, dir.path());
+
+        let commands = "/reset — Clear conversation history";
+        let greeting = responses.render(Canned::Greeting, "telegram", Some("Bob"), commands);
+        assert!(greeting.starts_with("👋 Hi Bob! I'm Ada"));
+        assert!(responses.render(Canned::Help, "telegram", None, commands).contains("/reset — Clear"));
+        assert_eq!(responses.render(Canned::Help, "slack", None, commands), "Slack help for there on slack");
+
+        // Prompt files override the built-in texts, per channel first
+        std::fs::create_dir_all(dir.path().join("prompts/discord")).unwrap();
+        std::fs::write(dir.path().join("prompts/greeting.md"), "Welcome to {agent_name}\n").unwrap();
+        std::fs::write(dir.path().join("prompts/discord/greeting.md"), "Discord welcome").unwrap();
+        assert_eq!(responses.render(Canned::Greeting, "telegram", None, commands), "Welcome to Ada");
+        assert_eq!(responses.render(Canned::Greeting, "discord", None, commands), "Discord welcome");
+
+        assert!(responses.greets_new_chats("slack"));
+        assert!(!responses.greets_new_chats("telegram"));
+    }
+}
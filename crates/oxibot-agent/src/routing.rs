@@ -0,0 +1,165 @@
+//! Model routing by task phase.
+//!
+//! The agent loop can use a cheap model while it is planning tool calls
+//! (and when summarizing subagent results) and a stronger model for the
+//! final, user-facing answer. Each phase is pinned to its own provider and
+//! model, so routing stays the same for the whole conversation.
+
+use std::sync::Arc;
+
+use oxibot_providers::traits::LlmProvider;
+
+/// Phase of a single LLM call inside the agent loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Deciding which tools to call, or summarizing results.
+    Planning,
+    /// Writing the final answer shown to the user.
+    Responding,
+}
+
+/// A provider plus the model to request from it.
+#[derive(Clone)]
+pub struct ModelRoute {
+    /// Provider serving the model.
+    pub provider: Arc<dyn LlmProvider>,
+    /// Model identifier sent to the provider.
+    pub model: String,
+}
+
+impl ModelRoute {
+    /// Create a new route.
+    pub fn new(provider: Arc<dyn LlmProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// ModelRouter
+// ─────────────────────────────────────────────
+
+/// Picks the provider/model for each phase, falling back to the default.
+#[derive(Clone)]
+pub struct ModelRouter {
+    default: ModelRoute,
+    planner: Option<ModelRoute>,
+    responder: Option<ModelRoute>,
+}
+
+impl ModelRouter {
+    /// Create a router that uses `default` for every phase.
+    pub fn new(default: ModelRoute) -> Self {
+        Self {
+            default,
+            planner: None,
+            responder: None,
+        }
+    }
+
+    /// Use a dedicated route for planning/summarization.
+    pub fn with_planner(mut self, planner: Option<ModelRoute>) -> Self {
+        self.planner = planner;
+        self
+    }
+
+    /// Use a dedicated route for final answers.
+    pub fn with_responder(mut self, responder: Option<ModelRoute>) -> Self {
+        self.responder = responder;
+        self
+    }
+
+    /// Route for the given phase.
+    pub fn route(&self, phase: Phase) -> &ModelRoute {
+        let routed = match phase {
+            Phase::Planning => self.planner.as_ref(),
+            Phase::Responding => self.responder.as_ref(),
+        };
+        routed.unwrap_or(&self.default)
+    }
+
+    /// Default route (used when no phase-specific model is configured).
+    pub fn default_route(&self) -> &ModelRoute {
+        &self.default
+    }
+
+    /// Whether final answers need a separate call on a different model.
+    ///
+    /// When true, a planning call that returns no tool calls is not used as
+    /// the answer; the responder is asked instead.
+    pub fn splits_phases(&self) -> bool {
+        self.route(Phase::Planning).model != self.route(Phase::Responding).model
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use oxibot_core::types::{LlmResponse, Message, ToolDefinition};
+    use oxibot_providers::traits::LlmRequestConfig;
+
+    struct NullProvider;
+
+    #[async_trait]
+    impl LlmProvider for NullProvider {
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: Option<&[ToolDefinition]>,
+            _model: &str,
+            _config: &LlmRequestConfig,
+        ) -> LlmResponse {
+            LlmResponse::default()
+        }
+
+        fn default_model(&self) -> &str {
+            "null"
+        }
+
+        fn display_name(&self) -> &str {
+            "Null"
+        }
+    }
+
+    fn route(model: &str) -> ModelRoute {
+        ModelRoute::new(Arc::new(NullProvider), model)
+    }
+
+    #[test]
+    fn test_default_only() {
+        let router = ModelRouter::new(route("main"));
+        assert_eq!(router.route(Phase::Planning).model, "main");
+        assert_eq!(router.route(Phase::Responding).model, "main");
+        assert!(!router.splits_phases());
+    }
+
+    #[test]
+    fn test_planner_and_responder() {
+        let router = ModelRouter::new(route("main"))
+            .with_planner(Some(route("cheap")))
+            .with_responder(Some(route("strong")));
+        assert_eq!(router.route(Phase::Planning).model, "cheap");
+        assert_eq!(router.route(Phase::Responding).model, "strong");
+        assert!(router.splits_phases());
+    }
+
+    #[test]
+    fn test_planner_only_responds_with_default() {
+        let router = ModelRouter::new(route("main")).with_planner(Some(route("cheap")));
+        assert_eq!(router.route(Phase::Responding).model, "main");
+        assert!(router.splits_phases());
+    }
+
+    #[test]
+    fn test_same_model_does_not_split() {
+        let router = ModelRouter::new(route("main")).with_responder(Some(route("main")));
+        assert!(!router.splits_phases());
+    }
+}
@@ -0,0 +1,220 @@
+//! Session-scoped scratch directories.
+//!
+//! Each conversation gets its own directory under `workspace/sessions/<key>/`.
+//! Filesystem tools resolve relative paths against it, so concurrent
+//! conversations don't overwrite each other's temporary files. Absolute
+//! paths still reach the shared workspace.
+//!
+//! Directories that have not been used for longer than the retention period
+//! are removed by [`ScratchDirs::maybe_cleanup`].
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use tracing::{debug, warn};
+
+/// Default retention for idle scratch directories (7 days).
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Minimum interval between two cleanup sweeps.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Marker file touched every time a scratch directory is used.
+const LAST_USED_MARKER: &str = ".last_used";
+
+// ─────────────────────────────────────────────
+// ScratchDirs
+// ─────────────────────────────────────────────
+
+/// Manages per-session scratch directories under `workspace/sessions/`.
+pub struct ScratchDirs {
+    root: PathBuf,
+    retention: Duration,
+    last_cleanup: Mutex<Option<Instant>>,
+}
+
+impl ScratchDirs {
+    /// Create a manager rooted at `workspace/sessions`.
+    pub fn new(workspace: &Path) -> Self {
+        Self {
+            root: workspace.join("sessions"),
+            retention: DEFAULT_RETENTION,
+            last_cleanup: Mutex::new(None),
+        }
+    }
+
+    /// Set how long an idle scratch directory is kept.
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Root directory containing all scratch directories.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Scratch directory path for a session key (not created).
+    pub fn dir_for(&self, session_key: &str) -> PathBuf {
+        self.root.join(safe_dir_name(session_key))
+    }
+
+    /// Create (if needed) and mark as used the scratch directory for a session.
+    pub fn ensure(&self, session_key: &str) -> std::io::Result<PathBuf> {
+        let dir = self.dir_for(session_key);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(LAST_USED_MARKER), b"")?;
+        Ok(dir)
+    }
+
+    /// Remove scratch directories idle for longer than the retention period.
+    ///
+    /// Returns the number of directories removed.
+    pub fn cleanup_expired(&self) -> usize {
+        let entries = match std::fs::read_dir(&self.root) {
+            Ok(e) => e,
+            Err(_) => return 0,
+        };
+
+        let now = SystemTime::now();
+        let mut removed = 0;
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let last_used = std::fs::metadata(path.join(LAST_USED_MARKER))
+                .or_else(|_| entry.metadata())
+                .and_then(|m| m.modified());
+
+            let expired = match last_used {
+                Ok(t) => now.duration_since(t).unwrap_or_default() > self.retention,
+                Err(_) => false,
+            };
+
+            if expired {
+                match std::fs::remove_dir_all(&path) {
+                    Ok(()) => {
+                        debug!(dir = %path.display(), "removed expired scratch directory");
+                        removed += 1;
+                    }
+                    Err(e) => {
+                        warn!(dir = %path.display(), error = %e, "failed to remove scratch directory");
+                    }
+                }
+            }
+        }
+
+        removed
+    }
+
+    /// Run [`cleanup_expired`](Self::cleanup_expired) at most once per hour.
+    pub fn maybe_cleanup(&self) -> usize {
+        {
+            let mut last = self.last_cleanup.lock().unwrap();
+            if last.is_some_and(|t| t.elapsed() < CLEANUP_INTERVAL) {
+                return 0;
+            }
+            *last = Some(Instant::now());
+        }
+        self.cleanup_expired()
+    }
+}
+
+/// Turn a session key (e.g. `telegram:12345`) into a safe directory name.
+fn safe_dir_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        "_".into()
+    } else {
+        name
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_dir_name() {
+        assert_eq!(safe_dir_name("telegram:12345"), "telegram_12345");
+        assert_eq!(safe_dir_name("slack:C01/thread"), "slack_C01_thread");
+        assert_eq!(safe_dir_name(".."), "_");
+        assert_eq!(safe_dir_name(""), "_");
+    }
+
+    #[test]
+    fn test_ensure_creates_isolated_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scratch = ScratchDirs::new(tmp.path());
+
+        let a = scratch.ensure("telegram:1").unwrap();
+        let b = scratch.ensure("telegram:2").unwrap();
+
+        assert_ne!(a, b);
+        assert!(a.is_dir());
+        assert!(b.is_dir());
+        assert!(a.starts_with(tmp.path().join("sessions")));
+        assert!(a.join(LAST_USED_MARKER).exists());
+    }
+
+    #[test]
+    fn test_cleanup_expired() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scratch = ScratchDirs::new(tmp.path()).with_retention(Duration::ZERO);
+        let dir = scratch.ensure("cli:direct").unwrap();
+        std::fs::write(dir.join("notes.txt"), "temp").unwrap();
+
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(scratch.cleanup_expired(), 1);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_cleanup_keeps_recent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scratch = ScratchDirs::new(tmp.path());
+        let dir = scratch.ensure("cli:direct").unwrap();
+
+        assert_eq!(scratch.cleanup_expired(), 0);
+        assert!(dir.exists());
+    }
+
+    #[test]
+    fn test_maybe_cleanup_is_throttled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scratch = ScratchDirs::new(tmp.path()).with_retention(Duration::ZERO);
+
+        scratch.ensure("a:1").unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(scratch.maybe_cleanup(), 1);
+
+        scratch.ensure("a:2").unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(scratch.maybe_cleanup(), 0);
+    }
+
+    #[test]
+    fn test_cleanup_missing_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let scratch = ScratchDirs::new(&tmp.path().join("nope"));
+        assert_eq!(scratch.cleanup_expired(), 0);
+    }
+}
@@ -34,10 +34,28 @@
 //!
 //! Use the `exec` tool to run `gh` commands ...
 //! ```
+//!
+//! ## Reloading
+//!
+//! The skills sections of the system prompt are cached and rebuilt when a
+//! `SKILL.md` is added, removed or modified, so edits take effect on the next
+//! message. Skills with broken frontmatter or metadata are logged on (re)load;
+//! [`SkillsLoader::lint`] reports them in detail (`oxibot skills lint`).
+//!
+//! ## Secrets
+//!
+//! Env vars listed in `requires.env` may be configured per skill under
+//! `skills.secrets` instead of being exported to the whole process. They
+//! then count as set, and `exec` receives them in conversations that use
+//! the skill (see [`SkillSecrets`](crate::tools::skill_secrets::SkillSecrets)).
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
-use tracing::debug;
+use serde_json::Value;
+use tracing::{debug, info, warn};
 
 // ─────────────────────────────────────────────
 // Types
@@ -83,6 +101,38 @@ pub struct SkillMeta {
     pub description: Option<String>,
 }
 
+/// Severity of a problem found by [`SkillsLoader::lint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssueLevel {
+    /// The skill, or part of its metadata, is ignored by the loader.
+    Error,
+    /// The skill loads, but probably not as intended.
+    Warning,
+}
+
+/// A problem with a skill.
+#[derive(Clone, Debug)]
+pub struct SkillIssue {
+    /// Skill name (directory name).
+    pub skill: String,
+    /// Path to the `SKILL.md` file.
+    pub path: PathBuf,
+    /// How serious the problem is.
+    pub level: IssueLevel,
+    /// What is wrong.
+    pub message: String,
+}
+
+/// Size + modification time of every `SKILL.md`, used to detect edits.
+type SkillStamps = Vec<(PathBuf, SystemTime, u64)>;
+
+/// Skills sections of the system prompt, valid while the stamps match.
+struct PromptCache {
+    stamps: SkillStamps,
+    always: String,
+    summary: String,
+}
+
 // ─────────────────────────────────────────────
 // SkillsLoader
 // ─────────────────────────────────────────────
@@ -93,6 +143,10 @@ pub struct SkillsLoader {
     workspace_skills: PathBuf,
     /// Built-in skills directory.
     builtin_skills: Option<PathBuf>,
+    /// Cached prompt sections (rebuilt when a `SKILL.md` changes).
+    cache: Mutex<Option<PromptCache>>,
+    /// Env vars configured as secrets, per skill.
+    secret_env: HashMap<String, Vec<String>>,
 }
 
 impl SkillsLoader {
@@ -104,9 +158,31 @@ impl SkillsLoader {
         Self {
             workspace_skills: workspace.join("skills"),
             builtin_skills,
+            cache: Mutex::new(None),
+            secret_env: HashMap::new(),
         }
     }
 
+    /// Count env vars configured for a skill in `skills.secrets` as set
+    /// when checking its requirements.
+    pub fn with_secrets(mut self, secrets: &HashMap<String, HashMap<String, String>>) -> Self {
+        self.secret_env = secrets
+            .iter()
+            .map(|(skill, vars)| (skill.clone(), vars.keys().cloned().collect()))
+            .collect();
+        self
+    }
+
+    /// Env vars configured as secrets of `name`.
+    fn secret_env(&self, name: &str) -> &[String] {
+        self.secret_env.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// `requires` without the env vars configured as secrets of `name`.
+    fn unmet(&self, name: &str, requires: &SkillRequires) -> SkillRequires {
+        without_secrets(requires, self.secret_env(name))
+    }
+
     // ────────────── Discovery ──────────────
 
     /// List all discovered skills.
@@ -138,7 +214,7 @@ impl SkillsLoader {
         if filter_unavailable {
             skills.retain(|s| {
                 let meta = self.get_skill_meta(&s.name);
-                check_requirements(&meta.requires)
+                check_requirements(&self.unmet(&s.name, &meta.requires))
             });
         }
 
@@ -197,7 +273,8 @@ impl SkillsLoader {
 
         for skill in &all {
             let meta = self.get_skill_meta(&skill.name);
-            let available = check_requirements(&meta.requires);
+            let requires = self.unmet(&skill.name, &meta.requires);
+            let available = check_requirements(&requires);
             let desc = meta
                 .description
                 .as_deref()
@@ -212,7 +289,7 @@ impl SkillsLoader {
             lines.push(format!("    <location>{}</location>", skill.path.display()));
 
             if !available {
-                let missing = get_missing_requirements(&meta.requires);
+                let missing = get_missing_requirements(&requires);
                 if !missing.is_empty() {
                     lines.push(format!("    <requires>{}</requires>", escape_xml(&missing)));
                 }
@@ -225,6 +302,49 @@ impl SkillsLoader {
         lines.join("\n")
     }
 
+    /// Always-on skills content and the skills summary for the system prompt.
+    ///
+    /// Both are cached until a `SKILL.md` is added, removed or modified.
+    /// Skills that fail to parse are logged whenever the cache is rebuilt.
+    pub fn prompt_sections(&self) -> (String, String) {
+        let stamps = self.stamps();
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(cached) = cache.as_ref().filter(|c| c.stamps == stamps) {
+            return (cached.always.clone(), cached.summary.clone());
+        }
+        if cache.is_some() {
+            info!("skills changed, reloading");
+        }
+        for issue in self.lint() {
+            if issue.level == IssueLevel::Error {
+                warn!(skill = %issue.skill, "{}", issue.message);
+            }
+        }
+
+        let always = self.load_skills_for_context(&self.get_always_skills());
+        let summary = self.build_skills_summary();
+        *cache = Some(PromptCache {
+            stamps,
+            always: always.clone(),
+            summary: summary.clone(),
+        });
+        (always, summary)
+    }
+
+    /// Stamp every `SKILL.md` in the workspace and built-in directories.
+    fn stamps(&self) -> SkillStamps {
+        let dirs = std::iter::once(&self.workspace_skills).chain(self.builtin_skills.as_ref());
+        let mut stamps: SkillStamps = dirs
+            .flat_map(|dir| skill_files(dir))
+            .filter_map(|(_, path)| {
+                let meta = std::fs::metadata(&path).ok()?;
+                Some((path, meta.modified().ok()?, meta.len()))
+            })
+            .collect();
+        stamps.sort();
+        stamps
+    }
+
     /// Get names of skills that should always be injected (full body).
     pub fn get_always_skills(&self) -> Vec<String> {
         self.list_skills(true)
@@ -241,43 +361,61 @@ impl SkillsLoader {
 
     /// Parse frontmatter metadata for a skill.
     pub fn get_skill_meta(&self, name: &str) -> SkillMeta {
-        let content = match self.load_skill(name) {
-            Some(c) => c,
-            None => return SkillMeta::default(),
-        };
-
-        let frontmatter = match parse_frontmatter(&content) {
-            Some(fm) => fm,
-            None => return SkillMeta::default(),
-        };
-
-        // Top-level description
-        let description = frontmatter
-            .iter()
-            .find(|(k, _)| k == "description")
-            .map(|(_, v)| v.trim_matches('"').trim_matches('\'').to_string());
-
-        // Top-level `always`
-        let always_top = frontmatter
-            .iter()
-            .find(|(k, _)| k == "always")
-            .map(|(_, v)| v == "true")
-            .unwrap_or(false);
-
-        // Parse the `metadata` field (JSON string containing nanobot config)
-        let metadata_json = frontmatter
-            .iter()
-            .find(|(k, _)| k == "metadata")
-            .map(|(_, v)| v.as_str())
-            .unwrap_or("");
+        self.load_skill(name)
+            .map(|content| parse_skill_meta(&content))
+            .unwrap_or_default()
+    }
 
-        let (nanobot_always, requires) = parse_nanobot_metadata(metadata_json);
+    /// Unmet requirements of a skill (`"CLI: gh"`, `"ENV: GITHUB_TOKEN"`);
+    /// empty when it is available.
+    pub fn missing_requirements(&self, name: &str) -> Vec<String> {
+        missing_requirements(&self.unmet(name, &self.get_skill_meta(name).requires))
+    }
 
-        SkillMeta {
-            always: always_top || nanobot_always,
-            requires,
-            description,
+    // ────────────── Validation ──────────────
+
+    /// Check every skill directory for problems the loader otherwise ignores
+    /// silently: missing `SKILL.md`, broken frontmatter, invalid metadata
+    /// JSON, malformed or unmet requirements.
+    pub fn lint(&self) -> Vec<SkillIssue> {
+        let mut issues = Vec::new();
+        let dirs = std::iter::once(&self.workspace_skills).chain(self.builtin_skills.as_ref());
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                if name.starts_with('.') {
+                    continue;
+                }
+                let file = path.join("SKILL.md");
+                let found = if !file.is_file() {
+                    vec![(IssueLevel::Warning, "no SKILL.md, directory is ignored".to_string())]
+                } else {
+                    match std::fs::read_to_string(&file) {
+                        Ok(content) => lint_skill(name, &content, self.secret_env(name)),
+                        Err(e) => vec![(IssueLevel::Error, format!("cannot read SKILL.md: {e}"))],
+                    }
+                };
+                issues.extend(found.into_iter().map(|(level, message)| SkillIssue {
+                    skill: name.to_string(),
+                    path: file.clone(),
+                    level,
+                    message,
+                }));
+            }
         }
+        issues
     }
 }
 
@@ -287,40 +425,54 @@ impl SkillsLoader {
 
 /// Scan a directory for skill subdirectories containing `SKILL.md`.
 fn scan_skill_dirs(dir: &Path, source: SkillSource, out: &mut Vec<SkillInfo>) {
+    for (name, path) in skill_files(dir) {
+        debug!(name, source = ?source, "discovered skill");
+        out.push(SkillInfo {
+            name,
+            path,
+            source: source.clone(),
+        });
+    }
+}
+
+/// Name and `SKILL.md` path of each skill subdirectory of `dir`.
+fn skill_files(dir: &Path) -> Vec<(String, PathBuf)> {
     let entries = match std::fs::read_dir(dir) {
         Ok(e) => e,
-        Err(_) => return,
+        Err(_) => return Vec::new(),
     };
 
+    let mut files = Vec::new();
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() {
             let skill_file = path.join("SKILL.md");
             if skill_file.is_file() {
                 if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    debug!(name, source = ?source, "discovered skill");
-                    out.push(SkillInfo {
-                        name: name.to_string(),
-                        path: skill_file,
-                        source: source.clone(),
-                    });
+                    files.push((name.to_string(), skill_file));
                 }
             }
         }
     }
+    files
 }
 
-/// Parse YAML-like frontmatter (between `---` delimiters) into key-value pairs.
-///
-/// Uses naive line-by-line parsing (matching nanobot's approach).
-fn parse_frontmatter(content: &str) -> Option<Vec<(String, String)>> {
+/// The raw frontmatter block between the `---` delimiters.
+fn frontmatter_block(content: &str) -> Option<&str> {
     if !content.starts_with("---") {
         return None;
     }
 
     let after_first = &content[3..];
     let end = after_first.find("\n---")?;
-    let block = &after_first[..end];
+    Some(&after_first[..end])
+}
+
+/// Parse YAML-like frontmatter (between `---` delimiters) into key-value pairs.
+///
+/// Uses naive line-by-line parsing (matching nanobot's approach).
+fn parse_frontmatter(content: &str) -> Option<Vec<(String, String)>> {
+    let block = frontmatter_block(content)?;
 
     let mut pairs = Vec::new();
     for line in block.lines() {
@@ -337,11 +489,146 @@ fn parse_frontmatter(content: &str) -> Option<Vec<(String, String)>> {
     Some(pairs)
 }
 
+/// Parse the skill metadata from a `SKILL.md`'s frontmatter.
+fn parse_skill_meta(content: &str) -> SkillMeta {
+    let frontmatter = match parse_frontmatter(content) {
+        Some(fm) => fm,
+        None => return SkillMeta::default(),
+    };
+
+    // Top-level description
+    let description = frontmatter
+        .iter()
+        .find(|(k, _)| k == "description")
+        .map(|(_, v)| v.trim_matches('"').trim_matches('\'').to_string());
+
+    // Top-level `always`
+    let always_top = frontmatter
+        .iter()
+        .find(|(k, _)| k == "always")
+        .map(|(_, v)| v == "true")
+        .unwrap_or(false);
+
+    // Parse the `metadata` field (JSON string containing nanobot config)
+    let metadata_json = frontmatter
+        .iter()
+        .find(|(k, _)| k == "metadata")
+        .map(|(_, v)| v.as_str())
+        .unwrap_or("");
+
+    let (nanobot_always, requires) = parse_nanobot_metadata(metadata_json);
+
+    SkillMeta {
+        always: always_top || nanobot_always,
+        requires,
+        description,
+    }
+}
+
+/// Validate one `SKILL.md`, returning the problems found.
+///
+/// Env vars in `secrets` are configured for the skill and count as set.
+fn lint_skill(dir_name: &str, content: &str, secrets: &[String]) -> Vec<(IssueLevel, String)> {
+    use IssueLevel::{Error, Warning};
+
+    let mut issues = Vec::new();
+    let Some(block) = frontmatter_block(content) else {
+        let message = if content.starts_with("---") {
+            "frontmatter is not closed with `---`, so it is ignored"
+        } else {
+            "missing frontmatter (the file must start with `---`)"
+        };
+        issues.push((Error, message.to_string()));
+        return issues;
+    };
+
+    for line in block.lines().map(str::trim) {
+        if !line.is_empty() && !line.starts_with('#') && !line.contains(':') {
+            issues.push((Warning, format!("frontmatter line `{line}` is not `key: value`, so it is ignored")));
+        }
+    }
+
+    let frontmatter = parse_frontmatter(content).unwrap_or_default();
+    let field = |key: &str| {
+        frontmatter
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.trim_matches('"').trim_matches('\''))
+    };
+
+    match field("name") {
+        None => issues.push((Warning, "missing `name`".to_string())),
+        Some(name) if name != dir_name => issues.push((
+            Warning,
+            format!("`name` is '{name}' but the directory is '{dir_name}'; the directory name is used"),
+        )),
+        Some(_) => {}
+    }
+    if field("description").is_none_or(str::is_empty) {
+        issues.push((Warning, "missing `description`, the skill name is shown instead".to_string()));
+    }
+    if let Some(always) = field("always") {
+        if always != "true" && always != "false" {
+            issues.push((Error, format!("`always` must be true or false, got '{always}'")));
+        }
+    }
+    if let Some(metadata) = field("metadata") {
+        lint_metadata(metadata, &mut issues);
+    }
+    if strip_frontmatter(content).trim().is_empty() {
+        issues.push((Warning, "skill body is empty".to_string()));
+    }
+
+    let missing = get_missing_requirements(&without_secrets(&parse_skill_meta(content).requires, secrets));
+    if !missing.is_empty() {
+        issues.push((Warning, format!("unavailable, missing {missing}")));
+    }
+
+    issues
+}
+
+/// Validate the `metadata` JSON field.
+fn lint_metadata(raw: &str, issues: &mut Vec<(IssueLevel, String)>) {
+    use IssueLevel::{Error, Warning};
+
+    let value: Value = match serde_json::from_str(raw) {
+        Ok(v) => v,
+        Err(e) => {
+            issues.push((
+                Error,
+                format!("`metadata` is not valid JSON ({e}), so requirements and `always` are ignored"),
+            ));
+            return;
+        }
+    };
+    let Some(nanobot) = value.get("nanobot") else {
+        issues.push((Warning, "`metadata` has no \"nanobot\" key, so it is ignored".to_string()));
+        return;
+    };
+
+    if nanobot.get("always").is_some_and(|v| !v.is_boolean()) {
+        issues.push((Error, "`nanobot.always` must be a boolean".to_string()));
+    }
+    match nanobot.get("requires") {
+        None => {}
+        Some(Value::Object(requires)) => {
+            for (key, list) in requires {
+                if key != "bins" && key != "env" {
+                    issues.push((Warning, format!("unknown requirement `{key}` (expected `bins` or `env`)")));
+                } else if !list.as_array().is_some_and(|a| a.iter().all(Value::is_string)) {
+                    issues.push((Error, format!("`requires.{key}` must be an array of strings")));
+                }
+            }
+        }
+        Some(_) => issues.push((Error, "`nanobot.requires` must be an object".to_string())),
+    }
+}
+
 /// Parse the `metadata` JSON field for nanobot-specific config.
 ///
 /// Expected format: `{"nanobot":{"always":true,"requires":{"bins":["gh"],"env":["TOKEN"]}}}`
 fn parse_nanobot_metadata(raw: &str) -> (bool, SkillRequires) {
-    let value: serde_json::Value = match serde_json::from_str(raw) {
+    let value: Value = match serde_json::from_str(raw) {
         Ok(v) => v,
         Err(_) => return (false, SkillRequires::default()),
     };
@@ -422,8 +709,21 @@ fn check_requirements(requires: &SkillRequires) -> bool {
     true
 }
 
+/// `requires` without the env vars in `secrets`.
+fn without_secrets(requires: &SkillRequires, secrets: &[String]) -> SkillRequires {
+    SkillRequires {
+        bins: requires.bins.clone(),
+        env: requires.env.iter().filter(|var| !secrets.contains(var)).cloned().collect(),
+    }
+}
+
 /// Get a human-readable list of missing requirements.
 fn get_missing_requirements(requires: &SkillRequires) -> String {
+    missing_requirements(requires).join(", ")
+}
+
+/// Each missing binary (`CLI: …`) and environment variable (`ENV: …`).
+fn missing_requirements(requires: &SkillRequires) -> Vec<String> {
     let mut missing = Vec::new();
 
     for bin in &requires.bins {
@@ -437,7 +737,7 @@ fn get_missing_requirements(requires: &SkillRequires) -> String {
         }
     }
 
-    missing.join(", ")
+    missing
 }
 
 /// Check if a binary is available on the system PATH.
@@ -779,4 +1079,136 @@ mod tests {
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].name, "available");
     }
+
+    #[test]
+    fn secrets_satisfy_env_requirements() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        create_skill(
+            &ws.join("skills"),
+            "weather",
+            "---\nname: weather\nmetadata: {\"nanobot\":{\"requires\":{\"env\":[\"__WEATHER_KEY__\",\"__OTHER_KEY__\"]}}}\n---\n\n# Weather",
+        );
+        let secrets = HashMap::from([(
+            "weather".to_string(),
+            HashMap::from([("__WEATHER_KEY__".to_string(), "k".to_string())]),
+        )]);
+
+        let loader = SkillsLoader::new(ws, None).with_secrets(&secrets);
+        assert_eq!(loader.missing_requirements("weather"), ["ENV: __OTHER_KEY__"]);
+        assert!(loader.list_skills(true).is_empty());
+
+        let mut secrets = secrets;
+        secrets.get_mut("weather").unwrap().insert("__OTHER_KEY__".into(), "o".into());
+        let loader = SkillsLoader::new(ws, None).with_secrets(&secrets);
+        assert!(loader.missing_requirements("weather").is_empty());
+        assert_eq!(loader.list_skills(true).len(), 1);
+    }
+
+    // ────────────── Reloading ──────────────
+
+    #[test]
+    fn prompt_sections_reload_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        create_skill(
+            &ws.join("skills"),
+            "always-on",
+            "---\nname: always-on\nalways: true\n---\n\n# First",
+        );
+
+        let loader = SkillsLoader::new(ws, None);
+        let (always, summary) = loader.prompt_sections();
+        assert!(always.contains("# First"));
+        assert!(summary.contains("<name>always-on</name>"));
+
+        create_skill(
+            &ws.join("skills"),
+            "always-on",
+            "---\nname: always-on\nalways: true\n---\n\n# Second version",
+        );
+        create_skill(&ws.join("skills"), "extra", "---\nname: extra\n---\n\n# Extra");
+        let (always, summary) = loader.prompt_sections();
+        assert!(always.contains("# Second version"));
+        assert!(summary.contains("<name>extra</name>"));
+
+        fs::remove_dir_all(ws.join("skills").join("extra")).unwrap();
+        assert!(!loader.prompt_sections().1.contains("extra"));
+    }
+
+    // ────────────── Validation ──────────────
+
+    fn messages(issues: &[(IssueLevel, String)], level: IssueLevel) -> Vec<&str> {
+        issues
+            .iter()
+            .filter(|(l, _)| *l == level)
+            .map(|(_, m)| m.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn lint_skill_valid() {
+        let content = "---\nname: ok\ndescription: \"Fine\"\nmetadata: {\"nanobot\":{\"always\":false,\"requires\":{\"bins\":[\"ls\"]}}}\n---\n\n# Body";
+        assert!(lint_skill("ok", content, &[]).is_empty());
+    }
+
+    #[test]
+    fn lint_skill_frontmatter_problems() {
+        let issues = lint_skill("x", "# No frontmatter", &[]);
+        assert!(messages(&issues, IssueLevel::Error)[0].contains("missing frontmatter"));
+
+        let issues = lint_skill("x", "---\nname: x\n\n# Never closed", &[]);
+        assert!(messages(&issues, IssueLevel::Error)[0].contains("not closed"));
+
+        let issues = lint_skill("x", "---\nname: other\nalways: yes\njust text\n---\n\n", &[]);
+        let errors = messages(&issues, IssueLevel::Error);
+        let warnings = messages(&issues, IssueLevel::Warning);
+        assert_eq!(errors, ["`always` must be true or false, got 'yes'"]);
+        assert!(warnings.iter().any(|w| w.contains("`just text`")));
+        assert!(warnings.iter().any(|w| w.contains("directory is 'x'")));
+        assert!(warnings.iter().any(|w| w.contains("missing `description`")));
+        assert!(warnings.iter().any(|w| w.contains("body is empty")));
+    }
+
+    #[test]
+    fn lint_skill_metadata_problems() {
+        let lint = |metadata: &str| {
+            let content = format!("---\nname: x\ndescription: d\nmetadata: {metadata}\n---\n\n# Body");
+            lint_skill("x", &content, &[])
+        };
+
+        let issues = lint("{\"nanobot\": {\"always\": true,}");
+        assert!(messages(&issues, IssueLevel::Error)[0].contains("not valid JSON"));
+
+        let issues = lint("{\"other\": {}}");
+        assert!(messages(&issues, IssueLevel::Warning)[0].contains("no \"nanobot\" key"));
+
+        let issues = lint("{\"nanobot\": {\"always\": \"yes\", \"requires\": {\"bins\": \"gh\", \"env\": [1], \"os\": []}}}");
+        let errors = messages(&issues, IssueLevel::Error);
+        assert!(errors.contains(&"`nanobot.always` must be a boolean"));
+        assert!(errors.contains(&"`requires.bins` must be an array of strings"));
+        assert!(errors.contains(&"`requires.env` must be an array of strings"));
+        assert!(messages(&issues, IssueLevel::Warning)[0].contains("unknown requirement `os`"));
+
+        let issues = lint("{\"nanobot\": {\"requires\": {\"bins\": [\"__nonexistent__\"]}}}");
+        assert_eq!(messages(&issues, IssueLevel::Warning), ["unavailable, missing CLI: __nonexistent__"]);
+    }
+
+    #[test]
+    fn lint_reports_every_skill_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        create_skill(&ws.join("skills"), "good", "---\nname: good\ndescription: d\n---\n\n# Good");
+        create_skill(&ws.join("skills"), "bad", "---\nname: bad\ndescription: d\nmetadata: {oops}\n---\n\n# Bad");
+        fs::create_dir_all(ws.join("skills").join("empty")).unwrap();
+
+        let loader = SkillsLoader::new(ws, None);
+        let issues = loader.lint();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].skill, "bad");
+        assert_eq!(issues[0].level, IssueLevel::Error);
+        assert!(issues[0].path.ends_with("bad/SKILL.md"));
+        assert_eq!(issues[1].skill, "empty");
+        assert_eq!(issues[1].message, "no SKILL.md, directory is ignored");
+    }
 }
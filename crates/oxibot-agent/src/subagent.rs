@@ -23,15 +23,20 @@ use tracing::{debug, error, info};
 
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::error::ProviderError;
 use oxibot_core::types::{Message, ToolCall};
 use oxibot_providers::traits::{LlmProvider, LlmRequestConfig};
 
 use crate::agent_loop::ExecToolConfig;
 use crate::context::ContextBuilder;
+use crate::page_cache::PageCache;
+use crate::redaction::Redactor;
+use crate::tools::base::{invalid_arguments, parse_arguments};
+use crate::tools::dry_run::DryRun;
 use crate::tools::filesystem::{ListDirTool, ReadFileTool, WriteFileTool};
 use crate::tools::registry::ToolRegistry;
 use crate::tools::shell::ExecTool;
-use crate::tools::web::{WebFetchTool, WebSearchTool};
+use crate::tools::web::{RecallPageTool, WebFetchTool, WebSearchTool};
 
 /// Maximum LLM ↔ tool iterations for a subagent task.
 const SUBAGENT_MAX_ITERATIONS: usize = 15;
@@ -80,6 +85,12 @@ pub struct SubagentManager {
     restrict_to_workspace: bool,
     /// LLM request config (temperature, max_tokens).
     request_config: LlmRequestConfig,
+    /// Dry-run switch shared with the parent agent's tools.
+    dry_run: DryRun,
+    /// Secret redaction, set by the parent agent (`None` = disabled).
+    redactor: std::sync::RwLock<Option<Arc<Redactor>>>,
+    /// Cache of fetched pages, set by the parent agent (`None` = disabled).
+    page_cache: std::sync::RwLock<Option<Arc<PageCache>>>,
     /// Currently running tasks, keyed by task ID.
     running_tasks: RwLock<HashMap<String, TaskInfo>>,
 }
@@ -106,10 +117,29 @@ impl SubagentManager {
             exec_config,
             restrict_to_workspace,
             request_config,
+            dry_run: DryRun::new(),
+            redactor: std::sync::RwLock::new(None),
+            page_cache: std::sync::RwLock::new(None),
             running_tasks: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Preview mutating tool calls while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Redact secrets from what subagents send to the LLM.
+    pub fn set_redactor(&self, redactor: Option<Arc<Redactor>>) {
+        *self.redactor.write().unwrap() = redactor;
+    }
+
+    /// Share the parent agent's page cache with subagents' `web_fetch`.
+    pub fn set_page_cache(&self, cache: Option<Arc<PageCache>>) {
+        *self.page_cache.write().unwrap() = cache;
+    }
+
     /// Spawn a subagent task in the background.
     ///
     /// Returns an immediate confirmation string.
@@ -199,15 +229,24 @@ impl SubagentManager {
         };
 
         tools.register(Arc::new(ReadFileTool::new(allowed_dir.clone())));
-        tools.register(Arc::new(WriteFileTool::new(allowed_dir.clone())));
+        tools.register(Arc::new(
+            WriteFileTool::new(allowed_dir.clone()).with_dry_run(self.dry_run.clone()),
+        ));
         tools.register(Arc::new(ListDirTool::new(allowed_dir)));
-        tools.register(Arc::new(ExecTool::new(
-            self.workspace.clone(),
-            Some(self.exec_config.timeout),
-            self.restrict_to_workspace,
-        )));
+        tools.register(Arc::new(
+            ExecTool::new(
+                self.workspace.clone(),
+                Some(self.exec_config.timeout),
+                self.restrict_to_workspace,
+            )
+            .with_dry_run(self.dry_run.clone()),
+        ));
         tools.register(Arc::new(WebSearchTool::new(self.brave_api_key.clone())));
-        tools.register(Arc::new(WebFetchTool::new()));
+        let page_cache = self.page_cache.read().unwrap().clone();
+        tools.register(Arc::new(WebFetchTool::new().with_cache(page_cache.clone())));
+        if let Some(cache) = page_cache {
+            tools.register(Arc::new(RecallPageTool::new(cache)));
+        }
 
         // Build system prompt
         let system_prompt = self.build_subagent_prompt(task);
@@ -217,14 +256,24 @@ impl SubagentManager {
 
         let tool_defs = tools.get_definitions();
         let mut final_content: Option<String> = None;
+        // Placeholders are only meaningful within this task
+        let redactor = self.redactor.read().unwrap().clone();
+        let scope = format!("subagent:{task_id}");
 
         for iteration in 0..SUBAGENT_MAX_ITERATIONS {
             debug!(task_id = %task_id, iteration = iteration, "subagent LLM call");
 
+            let request = match redactor {
+                Some(ref r) => r.redact_messages(&scope, &messages),
+                None => messages.clone(),
+            };
             let response = self
                 .provider
-                .chat(&messages, Some(&tool_defs), &self.model, &self.request_config)
+                .chat(&request, Some(&tool_defs), &self.model, &self.request_config)
                 .await;
+            if response.is_error() {
+                return Err(ProviderError(response.content.unwrap_or_default()).into());
+            }
 
             if response.has_tool_calls() {
                 let tool_calls: Vec<ToolCall> = response.tool_calls.clone();
@@ -235,9 +284,6 @@ impl SubagentManager {
                 );
 
                 for tc in &tool_calls {
-                    let params: HashMap<String, serde_json::Value> =
-                        serde_json::from_str(&tc.function.arguments).unwrap_or_default();
-
                     info!(
                         task_id = %task_id,
                         tool = %tc.function.name,
@@ -245,7 +291,15 @@ impl SubagentManager {
                         "subagent executing tool"
                     );
 
-                    let result = tools.execute(&tc.function.name, params).await;
+                    let result = match parse_arguments(&tc.function.arguments) {
+                        Ok(mut params) => {
+                            if let Some(ref r) = redactor {
+                                r.restore_params(&scope, &mut params);
+                            }
+                            tools.execute(&tc.function.name, params).await
+                        }
+                        Err(e) => invalid_arguments(&tc.function.name, &e),
+                    };
                     ContextBuilder::add_tool_result(&mut messages, &tc.id, &result);
                 }
             } else {
@@ -254,8 +308,13 @@ impl SubagentManager {
             }
         }
 
-        let result = final_content
+        let mut result = final_content
             .unwrap_or_else(|| "Subagent completed processing but produced no output.".into());
+        // The parent agent redacts the result again with its own placeholders
+        if let Some(ref r) = redactor {
+            result = r.restore(&scope, &result);
+            r.forget(&scope);
+        }
 
         info!(task_id = %task_id, result_len = result.len(), "subagent finished");
         Ok(result)
@@ -0,0 +1,267 @@
+//! Task list — persistent todo items the agent tracks across sessions.
+//!
+//! Tasks live in `workspace/tasks.json` so commitments made in one
+//! conversation ("remind me to renew the domain next week") survive restarts
+//! and are visible from every channel. Open tasks are injected into the
+//! system prompt; the agent manages them with the `task_add`, `task_list`
+//! and `task_done` tools, and the user with `oxibot tasks`.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Maximum number of open tasks listed in the system prompt.
+const MAX_PROMPT_TASKS: usize = 20;
+
+// ─────────────────────────────────────────────
+// Task
+// ─────────────────────────────────────────────
+
+/// A single todo item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// Stable numeric id (never reused).
+    pub id: u64,
+    /// What needs doing.
+    pub title: String,
+    /// Optional due date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    /// When the task was added.
+    pub created_at: DateTime<Utc>,
+    /// When the task was completed (`None` = open).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done_at: Option<DateTime<Utc>>,
+}
+
+impl Task {
+    /// Whether the task is still open.
+    pub fn is_open(&self) -> bool {
+        self.done_at.is_none()
+    }
+
+    /// One-line summary: `#3 Renew domain (due 2026-03-01, overdue)`.
+    pub fn summary(&self, today: NaiveDate) -> String {
+        let mut line = format!("#{} {}", self.id, self.title);
+        if let Some(done) = self.done_at {
+            line.push_str(&format!(" (done {})", done.format("%Y-%m-%d")));
+        } else if let Some(due) = self.due {
+            if due < today {
+                line.push_str(&format!(" (due {due}, overdue)"));
+            } else {
+                line.push_str(&format!(" (due {due})"));
+            }
+        }
+        line
+    }
+}
+
+/// Parse a due date in `YYYY-MM-DD` form.
+pub fn parse_due(s: &str) -> anyhow::Result<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("invalid due date '{s}' (expected YYYY-MM-DD)"))
+}
+
+// ─────────────────────────────────────────────
+// TaskStore
+// ─────────────────────────────────────────────
+
+/// JSON-backed task list in the workspace.
+///
+/// The file is re-read on every operation so the CLI and a running gateway
+/// see each other's changes.
+#[derive(Debug, Clone)]
+pub struct TaskStore {
+    /// Path to `tasks.json`.
+    path: PathBuf,
+}
+
+impl TaskStore {
+    /// Create a task store for `workspace` (does not touch the disk).
+    pub fn new(workspace: &Path) -> Self {
+        Self {
+            path: workspace.join("tasks.json"),
+        }
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All tasks, oldest first. A missing or invalid file yields no tasks.
+    pub fn all(&self) -> Vec<Task> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!(path = %self.path.display(), error = %e, "invalid tasks file");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Open tasks, ordered by due date (undated last), then id.
+    pub fn open(&self) -> Vec<Task> {
+        let mut tasks: Vec<Task> = self.all().into_iter().filter(Task::is_open).collect();
+        tasks.sort_by_key(|t| (t.due.is_none(), t.due, t.id));
+        tasks
+    }
+
+    /// Add a task and return it.
+    pub fn add(&self, title: &str, due: Option<NaiveDate>) -> anyhow::Result<Task> {
+        let title = title.trim();
+        if title.is_empty() {
+            anyhow::bail!("task title must not be empty");
+        }
+        let mut tasks = self.all();
+        let task = Task {
+            id: tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1,
+            title: title.to_string(),
+            due,
+            created_at: Utc::now(),
+            done_at: None,
+        };
+        tasks.push(task.clone());
+        self.save(&tasks)?;
+        Ok(task)
+    }
+
+    /// Mark a task as done and return it.
+    pub fn complete(&self, id: u64) -> anyhow::Result<Task> {
+        let mut tasks = self.all();
+        let task = tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| anyhow::anyhow!("no task #{id}"))?;
+        if !task.is_open() {
+            anyhow::bail!("task #{id} is already done");
+        }
+        task.done_at = Some(Utc::now());
+        let task = task.clone();
+        self.save(&tasks)?;
+        Ok(task)
+    }
+
+    fn save(&self, tasks: &[Task]) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(tasks)?)?;
+        Ok(())
+    }
+
+    /// Build the open-task section for the system prompt.
+    ///
+    /// Returns `None` when there are no open tasks.
+    pub fn get_tasks_context(&self) -> Option<String> {
+        let open = self.open();
+        if open.is_empty() {
+            return None;
+        }
+        let today = Utc::now().date_naive();
+        let noun = if open.len() == 1 { "task" } else { "tasks" };
+        let mut out = format!(
+            "# Tasks\n\nYou have {} open {noun}. Mention due or overdue ones when relevant, \
+             and call `task_done` once a task is finished.\n\n",
+            open.len()
+        );
+        for task in open.iter().take(MAX_PROMPT_TASKS) {
+            out.push_str(&format!("- {}\n", task.summary(today)));
+        }
+        if open.len() > MAX_PROMPT_TASKS {
+            out.push_str(&format!(
+                "- …and {} more (use `task_list`)\n",
+                open.len() - MAX_PROMPT_TASKS
+            ));
+        }
+        Some(out.trim_end().to_string())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_due(s).unwrap()
+    }
+
+    #[test]
+    fn test_add_complete_and_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskStore::new(dir.path());
+        assert!(store.all().is_empty());
+        assert!(store.get_tasks_context().is_none());
+
+        let a = store.add("Buy milk", None).unwrap();
+        let b = store.add("Renew domain", Some(date("2026-03-01"))).unwrap();
+        let c = store.add("File taxes", Some(date("2026-01-15"))).unwrap();
+        assert_eq!((a.id, b.id, c.id), (1, 2, 3));
+
+        let ids: Vec<u64> = store.open().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![3, 2, 1]);
+
+        store.complete(3).unwrap();
+        assert!(store.complete(3).is_err());
+        assert!(store.complete(42).is_err());
+        assert_eq!(store.open().len(), 2);
+        assert_eq!(store.all().len(), 3);
+
+        // Ids are never reused
+        assert_eq!(store.add("Call mom", None).unwrap().id, 4);
+    }
+
+    #[test]
+    fn test_add_rejects_empty_title() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskStore::new(dir.path());
+        assert!(store.add("  ", None).is_err());
+        assert!(!store.path().exists());
+    }
+
+    #[test]
+    fn test_summary() {
+        let today = date("2026-02-01");
+        let mut task = Task {
+            id: 7,
+            title: "Renew domain".into(),
+            due: Some(date("2026-01-30")),
+            created_at: Utc::now(),
+            done_at: None,
+        };
+        assert_eq!(task.summary(today), "#7 Renew domain (due 2026-01-30, overdue)");
+        task.due = Some(date("2026-02-10"));
+        assert_eq!(task.summary(today), "#7 Renew domain (due 2026-02-10)");
+        task.due = None;
+        assert_eq!(task.summary(today), "#7 Renew domain");
+    }
+
+    #[test]
+    fn test_tasks_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskStore::new(dir.path());
+        store.add("Buy milk", None).unwrap();
+        store.add("Call mom", None).unwrap();
+        store.add("Done already", None).unwrap();
+        store.complete(3).unwrap();
+
+        let ctx = store.get_tasks_context().unwrap();
+        assert!(ctx.starts_with("# Tasks\n\nYou have 2 open tasks."));
+        assert!(ctx.contains("- #1 Buy milk"));
+        assert!(ctx.contains("- #2 Call mom"));
+        assert!(!ctx.contains("Done already"));
+    }
+
+    #[test]
+    fn test_parse_due() {
+        assert_eq!(date("2026-03-01").to_string(), "2026-03-01");
+        assert!(parse_due("next tuesday").is_err());
+    }
+}
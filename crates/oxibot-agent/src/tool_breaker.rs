@@ -0,0 +1,259 @@
+//! Tool breaker — stop offering tools that keep failing.
+//!
+//! A `web_search` with an expired API key fails on every call, and the
+//! model keeps trying it turn after turn. [`ToolBreaker`] tracks each
+//! tool's error rate over a rolling window (`agents.defaults.toolBreaker`).
+//! A tool that trips is left out of the definitions sent to the model and
+//! named in the system prompt; after the cool-down it is offered for one
+//! trial call, which either restores it or takes it out again.
+//!
+//! The breaker is opt-in. The core filesystem and exec tools never trip:
+//! their errors (a missing file, a failing command) are nearly always the
+//! model's or the environment's, and switching them off would stop the
+//! agent from working at all.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use oxibot_core::config::schema::ToolBreakerConfig;
+use oxibot_core::types::ToolDefinition;
+use tracing::{info, warn};
+
+/// Tools that are never taken out of use.
+pub const EXEMPT_TOOLS: &[&str] = &["read_file", "write_file", "edit_file", "list_dir", "find_files", "exec"];
+
+/// Health of one tool.
+#[derive(Debug, Default)]
+struct ToolHealth {
+    /// Recent calls: when, and whether they failed.
+    calls: VecDeque<(Instant, bool)>,
+    /// Out of use until then.
+    open_until: Option<Instant>,
+    /// The cool-down is over; the next call decides.
+    trial: bool,
+    /// First line of the last failure.
+    last_error: String,
+}
+
+/// A tool taken out of use.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisabledTool {
+    pub name: String,
+    /// Why, from its last failure.
+    pub last_error: String,
+    /// Time left until it is tried again.
+    pub retry_in: Duration,
+}
+
+/// Per-tool error rates, and the tools taken out of use because of them.
+pub struct ToolBreaker {
+    config: ToolBreakerConfig,
+    tools: Mutex<HashMap<String, ToolHealth>>,
+}
+
+impl ToolBreaker {
+    pub fn new(config: ToolBreakerConfig) -> Self {
+        Self {
+            config,
+            tools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a finished call of `tool` (`error` = its failure, if any).
+    pub fn record(&self, tool: &str, error: Option<&str>) {
+        self.record_at(tool, error, Instant::now());
+    }
+
+    /// The tools currently out of use, by name.
+    pub fn disabled(&self) -> Vec<DisabledTool> {
+        self.disabled_at(Instant::now())
+    }
+
+    /// Whether `tool` is out of use.
+    pub fn is_disabled(&self, tool: &str) -> bool {
+        self.disabled().iter().any(|t| t.name == tool)
+    }
+
+    /// Remove the definitions of tools out of use from `defs`, and return
+    /// those tools.
+    pub fn filter(&self, defs: &mut Vec<ToolDefinition>) -> Vec<DisabledTool> {
+        let disabled = self.disabled();
+        defs.retain(|d| !disabled.iter().any(|t| t.name == d.function.name));
+        disabled
+    }
+
+    fn record_at(&self, tool: &str, error: Option<&str>, now: Instant) {
+        if EXEMPT_TOOLS.contains(&tool) {
+            return;
+        }
+        let window = Duration::from_secs(self.config.window_secs);
+        let mut tools = self.tools.lock().unwrap();
+        let health = tools.entry(tool.to_string()).or_default();
+        if let Some(error) = error {
+            health.last_error = error.lines().next().unwrap_or_default().to_string();
+        }
+
+        if health.trial {
+            health.trial = false;
+            health.calls.clear();
+            if error.is_some() {
+                health.open_until = Some(now + Duration::from_secs(self.config.cooldown_secs));
+                warn!(tool, error = %health.last_error, "tool failed its trial call, still disabled");
+            } else {
+                health.open_until = None;
+                info!(tool, "tool works again, re-enabled");
+            }
+            return;
+        }
+
+        health.calls.push_back((now, error.is_some()));
+        while health.calls.front().is_some_and(|(at, _)| now.duration_since(*at) >= window) {
+            health.calls.pop_front();
+        }
+        let failed = health.calls.iter().filter(|(_, failed)| *failed).count();
+        let calls = health.calls.len();
+        if self.config.enabled
+            && health.open_until.is_none()
+            && calls >= self.config.min_calls.max(1)
+            && failed as f64 >= self.config.error_rate * calls as f64
+        {
+            health.open_until = Some(now + Duration::from_secs(self.config.cooldown_secs));
+            warn!(
+                tool,
+                failed,
+                calls,
+                error = %health.last_error,
+                cooldown_secs = self.config.cooldown_secs,
+                "tool keeps failing, disabled"
+            );
+        }
+    }
+
+    fn disabled_at(&self, now: Instant) -> Vec<DisabledTool> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+        let mut tools = self.tools.lock().unwrap();
+        let mut disabled = Vec::new();
+        for (name, health) in tools.iter_mut() {
+            match health.open_until {
+                Some(until) if now < until => disabled.push(DisabledTool {
+                    name: name.clone(),
+                    last_error: health.last_error.clone(),
+                    retry_in: until - now,
+                }),
+                Some(_) => {
+                    health.open_until = None;
+                    health.trial = true;
+                    info!(tool = %name, "tool cool-down over, offering it for a trial call");
+                }
+                None => {}
+            }
+        }
+        disabled.sort_by(|a, b| a.name.cmp(&b.name));
+        disabled
+    }
+}
+
+/// Notice for the system prompt about tools out of use.
+pub fn prompt_section(disabled: &[DisabledTool]) -> String {
+    let mut out = String::from(
+        "## Unavailable tools\n\n\
+         These tools keep failing and are switched off for now. Don't try to \
+         call them; use another way, or tell the user what can't be done \
+         until they are back.\n",
+    );
+    for tool in disabled {
+        let minutes = tool.retry_in.as_secs().div_ceil(60).max(1);
+        out.push_str(&format!("- {} (retried in about {minutes} min): {}\n", tool.name, tool.last_error));
+    }
+    out
+}
+
+/// Tool result for a call to a tool out of use.
+pub fn disabled_result(tool: &str) -> String {
+    format!("Error: Tool '{tool}' is temporarily disabled because it keeps failing. Don't call it again for now.")
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> ToolBreaker {
+        ToolBreaker::new(ToolBreakerConfig {
+            enabled: true,
+            window_secs: 60,
+            min_calls: 3,
+            error_rate: 0.6,
+            cooldown_secs: 300,
+        })
+    }
+
+    #[test]
+    fn test_trips_on_error_rate() {
+        let breaker = breaker();
+        let start = Instant::now();
+        let key_error = Some("Error: Tool 'web_search' failed: 401 invalid API key");
+
+        breaker.record_at("web_search", key_error, start);
+        breaker.record_at("web_search", None, start);
+        assert!(breaker.disabled_at(start).is_empty(), "below minCalls");
+        breaker.record_at("web_search", None, start);
+        assert!(breaker.disabled_at(start).is_empty(), "1 of 3 failed");
+        breaker.record_at("web_fetch", key_error, start);
+
+        // Old calls leave the window
+        let later = start + Duration::from_secs(61);
+        breaker.record_at("web_search", key_error, later);
+        assert!(breaker.disabled_at(later).is_empty());
+        breaker.record_at("web_search", key_error, later);
+        breaker.record_at("web_search", key_error, later);
+        let disabled = breaker.disabled_at(later);
+        assert_eq!(disabled.len(), 1);
+        assert_eq!(disabled[0].name, "web_search");
+        assert_eq!(disabled[0].last_error, "Error: Tool 'web_search' failed: 401 invalid API key");
+        assert_eq!(disabled[0].retry_in, Duration::from_secs(300));
+
+        let section = prompt_section(&disabled);
+        assert!(section.contains("- web_search (retried in about 5 min): Error: Tool 'web_search' failed"), "{section}");
+    }
+
+    #[test]
+    fn test_trial_call_after_cooldown() {
+        let breaker = breaker();
+        let start = Instant::now();
+        for _ in 0..3 {
+            breaker.record_at("web_fetch", Some("Error: boom"), start);
+        }
+        assert_eq!(breaker.disabled_at(start).len(), 1);
+
+        // Cool-down over: offered again, and a failed trial takes it out
+        let retry = start + Duration::from_secs(300);
+        assert!(breaker.disabled_at(retry).is_empty());
+        breaker.record_at("web_fetch", Some("Error: boom"), retry);
+        assert_eq!(breaker.disabled_at(retry).len(), 1);
+
+        // A successful trial restores it
+        let retry = retry + Duration::from_secs(300);
+        assert!(breaker.disabled_at(retry).is_empty());
+        breaker.record_at("web_fetch", None, retry);
+        breaker.record_at("web_fetch", Some("Error: boom"), retry);
+        assert!(breaker.disabled_at(retry).is_empty());
+
+        let off = ToolBreaker::new(ToolBreakerConfig { enabled: false, min_calls: 1, ..Default::default() });
+        off.record_at("web_fetch", Some("Error: boom"), start);
+        assert!(off.disabled_at(start).is_empty());
+
+        // Core tools never trip
+        for _ in 0..5 {
+            breaker.record_at("exec", Some("Error: boom"), start);
+            breaker.record_at("read_file", Some("Error: File not found"), start);
+        }
+        assert!(breaker.disabled_at(retry).is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+//! `save_attachment` — move a file sent in chat into the workspace.
+//!
+//! Channels download attachments to `~/.oxibot/media/` under names like
+//! `AgADBAAD….pdf`. When the user says "save this as notes/todo.md", this
+//! tool moves the file to that path in the workspace, so it doesn't stay
+//! in the media directory under an opaque name.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use oxibot_core::types::MediaAttachment;
+use oxibot_core::utils;
+use serde_json::{json, Value};
+
+use super::base::{optional_string, require_string, Tool};
+use super::context::TurnContext;
+use super::dry_run::{DryRun, PREVIEW_PREFIX};
+use super::filesystem::resolve_path;
+
+/// Moves an attachment from the media directory into the workspace.
+pub struct SaveAttachmentTool {
+    workspace: PathBuf,
+    media_dir: PathBuf,
+    dry_run: DryRun,
+}
+
+impl SaveAttachmentTool {
+    pub fn new(workspace: PathBuf) -> Self {
+        Self {
+            workspace,
+            media_dir: utils::get_media_path(),
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Take attachments from `dir` instead of `~/.oxibot/media/`.
+    pub fn with_media_dir(mut self, dir: PathBuf) -> Self {
+        self.media_dir = dir;
+        self
+    }
+
+    /// Preview the move instead of doing it while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// The attachment `selector` names: a 1-based index, file name or path
+    /// among the current message's attachments. Without a selector, the
+    /// message's only attachment.
+    ///
+    /// Other files in the media directory belong to other chats, so they
+    /// can't be picked by path.
+    fn pick(&self, selector: Option<&str>, attachments: &[MediaAttachment]) -> anyhow::Result<(PathBuf, String)> {
+        let chosen = |a: &MediaAttachment| (PathBuf::from(&a.path), a.filename.clone());
+        let (path, name) = match selector.map(str::trim).filter(|s| !s.is_empty()) {
+            None => match attachments {
+                [only] => chosen(only),
+                [] => anyhow::bail!("This message has no attachment to save."),
+                _ => anyhow::bail!(
+                    "This message has {} attachments; say which one:\n{}",
+                    attachments.len(),
+                    describe(attachments)
+                ),
+            },
+            Some(s) => match s.parse::<usize>() {
+                Ok(n) => chosen(
+                    attachments
+                        .get(n.wrapping_sub(1))
+                        .ok_or_else(|| anyhow::anyhow!("No attachment #{n}; this message has {}.", attachments.len()))?,
+                ),
+                Err(_) => match attachments.iter().find(|a| a.filename.as_deref() == Some(s) || a.path == s) {
+                    Some(a) => chosen(a),
+                    None if attachments.is_empty() => anyhow::bail!("This message has no attachment to save."),
+                    None => anyhow::bail!("No attachment '{s}' in this message; it has:\n{}", describe(attachments)),
+                },
+            },
+        };
+
+        let path = path
+            .canonicalize()
+            .map_err(|_| anyhow::anyhow!("Attachment not found: {}", path.display()))?;
+        let media_dir = self.media_dir.canonicalize().unwrap_or_else(|_| self.media_dir.clone());
+        if !path.starts_with(&media_dir) || !path.is_file() {
+            anyhow::bail!("Only files received in chat (in {}) can be saved.", media_dir.display());
+        }
+        // The sender chose the original name; keep only its last component
+        let name = name
+            .as_deref()
+            .and_then(|n| Path::new(n).file_name())
+            .unwrap_or_else(|| path.file_name().unwrap_or_default())
+            .to_string_lossy()
+            .into_owned();
+        Ok((path, name))
+    }
+
+    /// Move `source` to `target`, copying when they're on different filesystems.
+    async fn save(&self, source: &Path, name: &str, target: &Path, note: &str) -> anyhow::Result<String> {
+        let shown = shown(target, &self.workspace);
+        if self.dry_run.enabled() {
+            return Ok(format!("{PREVIEW_PREFIX} Would save {name} to {shown}{note}; nothing was moved."));
+        }
+        if tokio::fs::rename(source, target).await.is_err() {
+            tokio::fs::copy(source, target).await?;
+            tokio::fs::remove_file(source).await?;
+        }
+        Ok(format!("Saved {name} to {shown}{note}"))
+    }
+}
+
+/// One line per attachment, numbered from 1.
+fn describe(attachments: &[MediaAttachment]) -> String {
+    attachments
+        .iter()
+        .enumerate()
+        .map(|(i, a)| format!("{}. {} ({})", i + 1, a.filename.as_deref().unwrap_or(&a.path), a.mime_type))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `path` with `-1`, `-2`, … before the extension until nothing exists there.
+fn free_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{stem}-{n}{ext}")))
+        .find(|p| !p.exists())
+        .expect("some suffix is free")
+}
+
+#[async_trait]
+impl Tool for SaveAttachmentTool {
+    fn name(&self) -> &str {
+        "save_attachment"
+    }
+
+    fn description(&self) -> &str {
+        "Save a file the user sent in chat into the workspace, e.g. when they say \
+         \"save this as notes/todo.md\". Moves the attachment from the media directory \
+         to the given workspace path (a path ending in '/' keeps the original file name)."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "destination": {
+                    "type": "string",
+                    "description": "Workspace-relative path, e.g. 'notes/todo.md', or a folder ending in '/'"
+                },
+                "attachment": {
+                    "type": "string",
+                    "description": "Which of this message's attachments: its number (1, 2, …) or its file name. Optional when the message has one attachment"
+                },
+                "on_conflict": {
+                    "type": "string",
+                    "enum": ["rename", "overwrite", "fail"],
+                    "description": "If the destination exists: save as name-1.ext (default), replace it, or stop"
+                }
+            },
+            "required": ["destination"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let destination = require_string(&params, "destination")?;
+        let on_conflict = optional_string(&params, "on_conflict").unwrap_or_else(|| "rename".into());
+        if !matches!(on_conflict.as_str(), "rename" | "overwrite" | "fail") {
+            anyhow::bail!("on_conflict must be rename, overwrite or fail");
+        }
+        let attachments = TurnContext::current().map(|t| t.attachments).unwrap_or_default();
+        let (source, name) = self.pick(optional_string(&params, "attachment").as_deref(), &attachments)?;
+
+        let relative = Path::new(destination.trim());
+        if relative.as_os_str().is_empty()
+            || relative.components().any(|c| !matches!(c, Component::Normal(_)))
+        {
+            anyhow::bail!("destination must be a relative path inside the workspace, without '..'");
+        }
+        let workspace = self.workspace.canonicalize().unwrap_or_else(|_| self.workspace.clone());
+        let mut target = workspace.join(relative);
+        if destination.ends_with('/') || target.is_dir() {
+            target.push(&name);
+        }
+        let target = resolve_path(&target.to_string_lossy(), None, Some(&self.workspace))?;
+        if let Some(parent) = target.parent() {
+            if !self.dry_run.enabled() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+        }
+
+        let (target, note) = match on_conflict.as_str() {
+            _ if !target.exists() => (target, String::new()),
+            "fail" => anyhow::bail!("{} already exists", shown(&target, &self.workspace)),
+            "overwrite" => (target, " (replaced the existing file)".to_string()),
+            _ => {
+                let note = format!(" ({} already existed)", shown(&target, &self.workspace));
+                (free_path(&target), note)
+            }
+        };
+        self.save(&source, &name, &target, &note).await
+    }
+}
+
+/// `path` relative to the workspace, for messages.
+fn shown(path: &Path, workspace: &Path) -> String {
+    let workspace = workspace.canonicalize().unwrap_or_else(|_| workspace.to_path_buf());
+    path.strip_prefix(&workspace).unwrap_or(path).display().to_string()
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(path: &Path, filename: Option<&str>) -> MediaAttachment {
+        MediaAttachment {
+            mime_type: "text/markdown".into(),
+            path: path.display().to_string(),
+            filename: filename.map(Into::into),
+            size: None,
+        }
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, Value> {
+        pairs.iter().map(|(k, v)| (k.to_string(), json!(v))).collect()
+    }
+
+    #[tokio::test]
+    async fn test_save_attachment_moves_and_renames() {
+        let workspace = tempfile::tempdir().unwrap();
+        let media = tempfile::tempdir().unwrap();
+        let tool = SaveAttachmentTool::new(workspace.path().to_path_buf()).with_media_dir(media.path().to_path_buf());
+
+        let first = media.path().join("AgAD1.md");
+        std::fs::write(&first, "- milk").unwrap();
+        let turn = TurnContext::new("telegram", "1", "telegram:1").with_attachments(vec![attachment(&first, None)]);
+        let result = turn.scope(tool.execute(params(&[("destination", "notes/todo.md")]))).await.unwrap();
+        assert_eq!(result, "Saved AgAD1.md to notes/todo.md");
+        assert_eq!(std::fs::read_to_string(workspace.path().join("notes/todo.md")).unwrap(), "- milk");
+        assert!(!first.exists());
+
+        // Same destination again: saved next to it, unless told otherwise
+        let second = media.path().join("AgAD2.md");
+        std::fs::write(&second, "- eggs").unwrap();
+        let turn = TurnContext::new("telegram", "1", "telegram:1").with_attachments(vec![attachment(&second, Some("list.md"))]);
+        let result = turn
+            .clone()
+            .scope(tool.execute(params(&[("destination", "notes/todo.md"), ("on_conflict", "fail")])))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("already exists"));
+        let result = turn.scope(tool.execute(params(&[("destination", "notes/todo.md")]))).await.unwrap();
+        assert!(result.starts_with("Saved list.md to notes/todo-1.md"));
+        assert_eq!(std::fs::read_to_string(workspace.path().join("notes/todo-1.md")).unwrap(), "- eggs");
+
+        // A folder keeps the original name
+        let third = media.path().join("AgAD3.md");
+        std::fs::write(&third, "x").unwrap();
+        let turn = TurnContext::new("telegram", "1", "telegram:1").with_attachments(vec![attachment(&third, Some("report.md"))]);
+        let result = turn.scope(tool.execute(params(&[("destination", "notes/")]))).await.unwrap();
+        assert_eq!(result, "Saved report.md to notes/report.md");
+    }
+
+    #[tokio::test]
+    async fn test_save_attachment_rejects_escapes() {
+        let workspace = tempfile::tempdir().unwrap();
+        let media = tempfile::tempdir().unwrap();
+        let tool = SaveAttachmentTool::new(workspace.path().to_path_buf()).with_media_dir(media.path().to_path_buf());
+        let file = media.path().join("a.txt");
+        std::fs::write(&file, "x").unwrap();
+
+        let turn = TurnContext::new("telegram", "1", "telegram:1").with_attachments(vec![attachment(&file, None)]);
+        for destination in ["../outside.txt", "/etc/passwd"] {
+            let result = turn.clone().scope(tool.execute(params(&[("destination", destination)]))).await;
+            assert!(result.is_err(), "{destination}");
+        }
+
+        // A sender-chosen file name can't climb out of the destination folder
+        let turn = TurnContext::new("telegram", "1", "telegram:1")
+            .with_attachments(vec![attachment(&file, Some("../../../../tmp/x/y.txt"))]);
+        let result = turn.scope(tool.execute(params(&[("destination", "inbox/")]))).await.unwrap();
+        assert_eq!(result, "Saved y.txt to inbox/y.txt");
+        assert!(workspace.path().join("inbox/y.txt").exists());
+
+        // Only this message's attachments, not other files in the media directory
+        let other = media.path().join("other-chat.txt");
+        std::fs::write(&other, "x").unwrap();
+        let args = params(&[("destination", "copy.txt"), ("attachment", &other.display().to_string())]);
+        assert!(tool.execute(args).await.unwrap_err().to_string().contains("no attachment"));
+        let turn = TurnContext::new("telegram", "1", "telegram:1").with_attachments(vec![attachment(&other, Some("b.txt"))]);
+        let result = turn.scope(tool.execute(params(&[("destination", "copy.txt"), ("attachment", "c.txt")]))).await;
+        assert!(result.unwrap_err().to_string().contains("No attachment 'c.txt'"));
+        assert!(other.exists());
+
+        let file = media.path().join("a2.txt");
+        std::fs::write(&file, "x").unwrap();
+
+        // Several attachments need a selector
+        let turn = TurnContext::new("telegram", "1", "telegram:1")
+            .with_attachments(vec![attachment(&file, None), attachment(&file, Some("b.txt"))]);
+        let result = turn.scope(tool.execute(params(&[("destination", "x.txt")]))).await;
+        assert!(result.unwrap_err().to_string().contains("2 attachments"));
+        assert!(file.exists());
+    }
+}
@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
 
+use oxibot_core::error::ToolError;
 use oxibot_core::types::ToolDefinition;
 
 // ─────────────────────────────────────────────
@@ -48,6 +49,72 @@ pub trait Tool: Send + Sync {
 // Param helpers
 // ─────────────────────────────────────────────
 
+/// Parse the JSON arguments of a tool call.
+///
+/// Empty arguments mean no parameters; anything else must be a JSON object.
+pub fn parse_arguments(arguments: &str) -> Result<HashMap<String, Value>, String> {
+    if arguments.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    serde_json::from_str(arguments).map_err(|e| e.to_string())
+}
+
+/// Tool result telling the model its arguments could not be parsed.
+pub fn invalid_arguments(tool: &str, error: &str) -> String {
+    ToolError {
+        tool: tool.into(),
+        message: format!(
+            "invalid JSON arguments ({error}). Call the tool again with a single JSON object matching its parameters."
+        ),
+    }
+    .to_string()
+}
+
+/// Tool result telling the model the tool it called doesn't exist, with
+/// the closest real name and the list of available tools.
+pub fn unknown_tool(tool: &str, available: &[String]) -> String {
+    let mut message = format!("there is no tool named `{tool}`.");
+    if let Some(name) = closest_name(tool, available) {
+        message.push_str(&format!(" Did you mean `{name}`?"));
+    }
+    message.push_str(&format!(
+        " Available tools: {}. Call one of these, or answer without tools.",
+        available.join(", ")
+    ));
+    ToolError {
+        tool: tool.into(),
+        message,
+    }
+    .to_string()
+}
+
+/// The name in `names` most like `name`, if any is close.
+fn closest_name<'a>(name: &str, names: &'a [String]) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    names
+        .iter()
+        .map(|n| (n, edit_distance(&name, &n.to_lowercase())))
+        .filter(|(n, d)| *d <= (n.len() / 3).max(2) || name.contains(n.as_str()) || n.contains(name.as_str()))
+        .min_by_key(|(_, d)| *d)
+        .map(|(n, _)| n.as_str())
+}
+
+/// Levenshtein distance, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
 /// Extract a required `String` param, returning a user-friendly error.
 pub fn require_string(params: &HashMap<String, Value>, key: &str) -> anyhow::Result<String> {
     params
@@ -80,6 +147,26 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_parse_arguments() {
+        assert_eq!(parse_arguments("{\"path\": \"a.txt\"}").unwrap()["path"], json!("a.txt"));
+        assert!(parse_arguments("").unwrap().is_empty());
+        assert!(parse_arguments("  ").unwrap().is_empty());
+        assert!(parse_arguments("{\"path\": \"a.txt\"").is_err());
+        assert!(parse_arguments("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_unknown_tool() {
+        let names: Vec<String> = ["list_dir", "read_file", "web_search"].map(String::from).to_vec();
+        let message = unknown_tool("readfile", &names);
+        assert!(message.starts_with("Error executing readfile: there is no tool named `readfile`."));
+        assert!(message.contains("Did you mean `read_file`?"));
+        assert!(message.contains("Available tools: list_dir, read_file, web_search."));
+        assert!(unknown_tool("search", &names).contains("`web_search`"));
+        assert!(!unknown_tool("send_email", &names).contains("Did you mean"));
+    }
+
     #[test]
     fn test_require_string_present() {
         let mut params = HashMap::new();
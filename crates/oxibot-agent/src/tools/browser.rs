@@ -0,0 +1,374 @@
+//! Browser tool — render JavaScript-heavy pages in headless Chromium.
+//!
+//! `web_fetch` only sees the HTML the server sends; `browser_fetch` runs the
+//! page's scripts first and returns the text the user would see, optionally
+//! with a full-page screenshot. Requires the `browser` feature and a local
+//! Chrome/Chromium install.
+//!
+//! Each call launches a fresh browser, so no cookies or state leak between
+//! calls. Navigation is limited to the configured domains: every document
+//! request (the page, its redirects and frames) is intercepted and checked
+//! before it is sent, and the final address is checked once more. The whole
+//! call is bounded by a timeout.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EnableParams, EventRequestPaused, FailRequestParams, RequestPattern,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::Page;
+use futures_util::StreamExt;
+use oxibot_core::config::schema::BrowserToolConfig;
+use reqwest::Url;
+use serde_json::{json, Value};
+use tracing::debug;
+
+use super::base::{optional_bool, optional_i64, optional_string, require_string, Tool};
+use super::filesystem::WorkingDir;
+
+/// How often to check for the `waitFor` selector.
+const SELECTOR_POLL: Duration = Duration::from_millis(250);
+
+/// Script returning the rendered text of the page.
+const INNER_TEXT: &str = "document.body ? document.body.innerText : ''";
+
+// ─────────────────────────────────────────────
+// BrowserFetchTool
+// ─────────────────────────────────────────────
+
+/// Renders a page in headless Chromium and extracts its text.
+pub struct BrowserFetchTool {
+    config: BrowserToolConfig,
+    /// Where screenshots go when no working directory is set.
+    workspace: PathBuf,
+    working_dir: Option<WorkingDir>,
+}
+
+/// What a rendered page yielded.
+struct RenderedPage {
+    final_url: String,
+    title: String,
+    text: String,
+    screenshot: Option<PathBuf>,
+}
+
+impl BrowserFetchTool {
+    /// Create a browser tool saving screenshots under `workspace`.
+    pub fn new(config: BrowserToolConfig, workspace: PathBuf) -> Self {
+        Self {
+            config,
+            workspace,
+            working_dir: None,
+        }
+    }
+
+    /// Save screenshots in the session working directory when one is set.
+    pub fn with_working_dir(mut self, working_dir: WorkingDir) -> Self {
+        self.working_dir = Some(working_dir);
+        self
+    }
+
+    /// Launch Chromium and drive its event handler in the background.
+    async fn launch(&self) -> anyhow::Result<(Browser, tokio::task::JoinHandle<()>)> {
+        let mut builder = BrowserConfig::builder()
+            .request_timeout(Duration::from_secs(self.config.timeout_secs.max(1)));
+        if !self.config.chrome_path.is_empty() {
+            builder = builder.chrome_executable(&self.config.chrome_path);
+        }
+        if self.config.no_sandbox {
+            builder = builder.no_sandbox();
+        }
+        let config = builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("Cannot start the browser: {e}"))?;
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .map_err(|e| anyhow::anyhow!("Cannot start the browser: {e}"))?;
+        let events = tokio::spawn(async move { while handler.next().await.is_some() {} });
+        Ok((browser, events))
+    }
+
+    /// Load `url`, wait for `wait_for`, and extract the page.
+    async fn render(
+        &self,
+        browser: &Browser,
+        url: &str,
+        wait_for: Option<&str>,
+        screenshot: bool,
+    ) -> anyhow::Result<RenderedPage> {
+        let page = browser.new_page("about:blank").await?;
+        let blocked = Arc::new(Mutex::new(None));
+        let guard = self.guard_navigation(&page, blocked.clone()).await?;
+        let loaded = page.goto(url).await.map(|_| ());
+        let rendered = self
+            .extract(&page, url, wait_for, screenshot, loaded, &blocked)
+            .await;
+        guard.abort();
+        rendered
+    }
+
+    /// Intercept every document request on `page` and fail the ones outside
+    /// the allowed domains, recording the first refusal in `blocked`.
+    async fn guard_navigation(
+        &self,
+        page: &Page,
+        blocked: Arc<Mutex<Option<String>>>,
+    ) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+        let documents = RequestPattern::builder()
+            .url_pattern("*")
+            .resource_type(ResourceType::Document)
+            .build();
+        let mut paused = page.event_listener::<EventRequestPaused>().await?;
+        page.execute(EnableParams::builder().pattern(documents).build())
+            .await?;
+
+        let page = page.clone();
+        let allowed = self.config.allowed_domains.clone();
+        Ok(tokio::spawn(async move {
+            while let Some(event) = paused.next().await {
+                let url = &event.request.url;
+                let sent = match check_url(url, &allowed) {
+                    Ok(()) => page
+                        .execute(ContinueRequestParams::new(event.request_id.clone()))
+                        .await
+                        .map(|_| ()),
+                    Err(e) => {
+                        debug!(url = %url, error = %e, "browser navigation blocked");
+                        blocked.lock().unwrap().get_or_insert_with(|| e.to_string());
+                        page.execute(FailRequestParams::new(
+                            event.request_id.clone(),
+                            ErrorReason::BlockedByClient,
+                        ))
+                        .await
+                        .map(|_| ())
+                    }
+                };
+                if let Err(e) = sent {
+                    debug!(url = %url, error = %e, "cannot answer intercepted request");
+                }
+            }
+        }))
+    }
+
+    /// Wait for `wait_for` on the loaded page and extract its text.
+    async fn extract(
+        &self,
+        page: &Page,
+        url: &str,
+        wait_for: Option<&str>,
+        screenshot: bool,
+        loaded: Result<(), chromiumoxide::error::CdpError>,
+        blocked: &Mutex<Option<String>>,
+    ) -> anyhow::Result<RenderedPage> {
+        if let Some(reason) = blocked.lock().unwrap().take() {
+            anyhow::bail!("Page redirected to a blocked address: {reason}");
+        }
+        loaded?;
+        if let Some(selector) = wait_for {
+            while page.find_element(selector).await.is_err() {
+                tokio::time::sleep(SELECTOR_POLL).await;
+            }
+        }
+
+        // Redirects and scripts may have left the allowed domains
+        let final_url = page.url().await?.unwrap_or_else(|| url.to_string());
+        check_url(&final_url, &self.config.allowed_domains)
+            .map_err(|e| anyhow::anyhow!("Page redirected to a blocked address: {e}"))?;
+
+        let title = page.get_title().await?.unwrap_or_default();
+        let text: String = page.evaluate(INNER_TEXT).await?.into_value()?;
+        let screenshot = if screenshot {
+            let png = page
+                .screenshot(ScreenshotParams::builder().full_page(true).build())
+                .await?;
+            let dir = match self.working_dir {
+                Some(ref wd) => wd.get().await.unwrap_or_else(|| self.workspace.clone()),
+                None => self.workspace.clone(),
+            };
+            let path = dir.join(format!("screenshot-{}.png", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+            tokio::fs::create_dir_all(&dir).await?;
+            tokio::fs::write(&path, png).await?;
+            Some(path)
+        } else {
+            None
+        };
+
+        Ok(RenderedPage {
+            final_url,
+            title,
+            text,
+            screenshot,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for BrowserFetchTool {
+    fn name(&self) -> &str {
+        "browser_fetch"
+    }
+
+    fn description(&self) -> &str {
+        "Render a web page in a headless browser (runs its JavaScript) and return the visible text. \
+         Use when web_fetch returns an empty or incomplete page. Can wait for a CSS selector and \
+         save a full-page screenshot."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to render"
+                },
+                "waitFor": {
+                    "type": "string",
+                    "description": "CSS selector to wait for before extracting (e.g. \"#results\")"
+                },
+                "screenshot": {
+                    "type": "boolean",
+                    "description": "Also save a full-page PNG screenshot (default false)"
+                },
+                "maxChars": {
+                    "type": "integer",
+                    "description": "Maximum characters to return (default from config)",
+                    "minimum": 100
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let url = require_string(&params, "url")?;
+        let wait_for = optional_string(&params, "waitFor").filter(|s| !s.is_empty());
+        let screenshot = optional_bool(&params, "screenshot");
+        let max_chars = optional_i64(&params, "maxChars")
+            .map(|n| n.max(100) as usize)
+            .unwrap_or(self.config.max_chars);
+
+        check_url(&url, &self.config.allowed_domains)?;
+        debug!(url = %url, "rendering web page");
+
+        let (mut browser, events) = self.launch().await?;
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        let rendered = tokio::time::timeout(
+            timeout,
+            self.render(&browser, &url, wait_for.as_deref(), screenshot),
+        )
+        .await;
+        let _ = browser.close().await;
+        let _ = browser.wait().await;
+        events.abort();
+
+        let page = match rendered {
+            Ok(page) => page?,
+            Err(_) => match wait_for {
+                Some(selector) => anyhow::bail!(
+                    "Timed out after {}s waiting for '{selector}' on {url}",
+                    timeout.as_secs()
+                ),
+                None => anyhow::bail!("Timed out after {}s loading {url}", timeout.as_secs()),
+            },
+        };
+
+        let (text, truncated) = truncate_chars(page.text, max_chars);
+        let result = json!({
+            "url": url,
+            "finalUrl": page.final_url,
+            "title": page.title,
+            "extractor": "browser",
+            "truncated": truncated,
+            "length": text.len(),
+            "text": text,
+            "screenshot": page.screenshot.map(|p| p.display().to_string()),
+        });
+
+        Ok(serde_json::to_string_pretty(&result).unwrap_or_default())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────
+
+/// Check that `url` is http(s) and its host is in `allowed` (empty = any).
+///
+/// An allowed domain also covers its subdomains.
+fn check_url(url: &str, allowed: &[String]) -> anyhow::Result<()> {
+    let parsed = Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid URL '{url}': {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        anyhow::bail!("Invalid URL '{url}': must start with http:// or https://");
+    }
+    if allowed.is_empty() {
+        return Ok(());
+    }
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+    let permitted = allowed.iter().any(|domain| {
+        let domain = domain.trim_start_matches("*.").trim_start_matches('.').to_lowercase();
+        host == domain || host.ends_with(&format!(".{domain}"))
+    });
+    if !permitted {
+        anyhow::bail!("'{host}' is not in tools.browser.allowedDomains");
+    }
+    Ok(())
+}
+
+/// Keep at most `max_chars` characters; returns whether anything was cut.
+fn truncate_chars(text: String, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => (text[..end].to_string(), true),
+        None => (text, false),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("https://example.com/a", &[]).is_ok());
+        assert!(check_url("file:///etc/passwd", &[]).is_err());
+        assert!(check_url("not a url", &[]).is_err());
+
+        let allowed = vec!["example.com".to_string(), "*.docs.rs".to_string()];
+        assert!(check_url("https://example.com", &allowed).is_ok());
+        assert!(check_url("https://WWW.Example.com/x", &allowed).is_ok());
+        assert!(check_url("http://serde.docs.rs", &allowed).is_ok());
+        assert!(check_url("https://badexample.com", &allowed).is_err());
+        let err = check_url("https://example.com.evil.io", &allowed).unwrap_err();
+        assert!(err.to_string().contains("not in tools.browser.allowedDomains"));
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("héllo".into(), 10), ("héllo".into(), false));
+        assert_eq!(truncate_chars("héllo".into(), 2), ("hé".into(), true));
+    }
+
+    #[tokio::test]
+    async fn test_browser_fetch_rejects_blocked_url() {
+        let config = BrowserToolConfig {
+            allowed_domains: vec!["example.com".into()],
+            ..Default::default()
+        };
+        let tool = BrowserFetchTool::new(config, PathBuf::from("/tmp"));
+        let mut params = HashMap::new();
+        params.insert("url".to_string(), json!("https://other.org"));
+        let err = tool.execute(params).await.unwrap_err();
+        assert!(err.to_string().contains("'other.org' is not in"));
+    }
+}
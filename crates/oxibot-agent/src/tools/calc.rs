@@ -0,0 +1,385 @@
+//! Calc tool — evaluate arithmetic without a shell or a web search.
+//!
+//! A small recursive-descent evaluator: numbers, `+ - * / % ^`,
+//! parentheses, the constants `pi` and `e` and a few functions (`sqrt`,
+//! `ln`, `sin`, `round`, `min`, ...). Nothing else is accepted, so there is
+//! nothing to sandbox.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::base::{require_string, Tool};
+
+/// Longest expression accepted.
+const MAX_EXPRESSION_CHARS: usize = 1000;
+
+/// Deepest nesting of parentheses and unary operators accepted.
+const MAX_DEPTH: usize = 64;
+
+// ─────────────────────────────────────────────
+// Evaluator
+// ─────────────────────────────────────────────
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                let exponent_sign = matches!(c, '+' | '-') && number.ends_with(['e', 'E']);
+                if c.is_ascii_digit() || c == '.' || c == '_' || matches!(c, 'e' | 'E') || exponent_sign {
+                    if c != '_' {
+                        number.push(c);
+                    }
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let value = number
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid number '{number}'"))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() {
+                    ident.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Ident(ident.to_lowercase()));
+        } else if "+-*/%^(),×÷".contains(c) {
+            let op = match c {
+                '×' => '*',
+                '÷' => '/',
+                c => c,
+            };
+            // `**` is a power too
+            chars.next();
+            if op == '*' && chars.peek() == Some(&'*') {
+                chars.next();
+                tokens.push(Token::Op('^'));
+            } else {
+                tokens.push(Token::Op(op));
+            }
+        } else {
+            bail!("unexpected character '{c}'");
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser evaluating as it goes.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            bail!("expression nested too deeply");
+        }
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// expr := term (("+" | "-") term)*
+    fn expr(&mut self) -> Result<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// term := unary (("*" | "/" | "%") unary)*
+    fn term(&mut self) -> Result<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    bail!("division by zero");
+                }
+                value /= divisor;
+            } else if self.eat('%') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    bail!("division by zero");
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// unary := ("-" | "+") unary | power
+    fn unary(&mut self) -> Result<f64> {
+        if self.eat('-') {
+            self.nested(|p| p.unary()).map(|v| -v)
+        } else if self.eat('+') {
+            self.nested(|p| p.unary())
+        } else {
+            self.power()
+        }
+    }
+
+    /// power := atom ("^" unary)?   (right-associative, binds tighter than unary minus on the left)
+    fn power(&mut self) -> Result<f64> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            let exponent = self.nested(|p| p.unary())?;
+            Ok(base.powf(exponent))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// atom := number | constant | function "(" args ")" | "(" expr ")"
+    fn atom(&mut self) -> Result<f64> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(Token::Number(value)) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(Token::Op('(')) => {
+                self.pos += 1;
+                let value = self.nested(|p| p.expr())?;
+                if !self.eat(')') {
+                    bail!("missing ')'");
+                }
+                Ok(value)
+            }
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                match name.as_str() {
+                    "pi" | "π" => return Ok(std::f64::consts::PI),
+                    "e" => return Ok(std::f64::consts::E),
+                    "tau" => return Ok(std::f64::consts::TAU),
+                    _ => {}
+                }
+                if !self.eat('(') {
+                    bail!("unknown name '{name}'");
+                }
+                let mut args = Vec::new();
+                if !self.eat(')') {
+                    loop {
+                        args.push(self.nested(|p| p.expr())?);
+                        if self.eat(')') {
+                            break;
+                        }
+                        if !self.eat(',') {
+                            bail!("expected ',' or ')' in {name}()");
+                        }
+                    }
+                }
+                call(&name, &args)
+            }
+            Some(Token::Op(op)) => bail!("unexpected '{op}'"),
+            None => bail!("unexpected end of expression"),
+        }
+    }
+}
+
+/// Apply a built-in function.
+fn call(name: &str, args: &[f64]) -> Result<f64> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => bail!("{name}() takes one argument"),
+    };
+    match name {
+        "sqrt" => one(f64::sqrt),
+        "cbrt" => one(f64::cbrt),
+        "abs" => one(f64::abs),
+        "ln" => one(f64::ln),
+        "log" | "log10" => one(f64::log10),
+        "log2" => one(f64::log2),
+        "exp" => one(f64::exp),
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "asin" => one(f64::asin),
+        "acos" => one(f64::acos),
+        "atan" => one(f64::atan),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => match args {
+            [x] => Ok(x.round()),
+            [x, digits] => {
+                let scale = 10f64.powi(*digits as i32);
+                Ok((x * scale).round() / scale)
+            }
+            _ => bail!("round() takes one or two arguments"),
+        },
+        "min" | "max" if !args.is_empty() => {
+            let pick = if name == "min" { f64::min } else { f64::max };
+            Ok(args.iter().copied().reduce(pick).unwrap_or_default())
+        }
+        "min" | "max" => bail!("{name}() needs at least one argument"),
+        _ => bail!("unknown function '{name}'"),
+    }
+}
+
+/// Evaluate an arithmetic expression.
+pub fn evaluate(expr: &str) -> Result<f64> {
+    if expr.chars().count() > MAX_EXPRESSION_CHARS {
+        bail!("expression is longer than {MAX_EXPRESSION_CHARS} characters");
+    }
+    let mut parser = Parser {
+        tokens: tokenize(expr)?,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.expr()?;
+    if let Some(token) = parser.peek() {
+        bail!("unexpected {token:?} after the expression");
+    }
+    if !value.is_finite() {
+        bail!("the result is not a finite number");
+    }
+    Ok(value)
+}
+
+/// A number without float noise: at most 12 significant digits, no
+/// trailing zeros.
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".into();
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-6..15).contains(&magnitude) {
+        return format!("{value:e}");
+    }
+    let decimals = (11 - magnitude).clamp(0, 15) as usize;
+    let text = format!("{value:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    if text == "-0" { "0".into() } else { text.to_string() }
+}
+
+// ─────────────────────────────────────────────
+// CalcTool
+// ─────────────────────────────────────────────
+
+/// Tool that evaluates arithmetic expressions.
+pub struct CalcTool;
+
+#[async_trait]
+impl Tool for CalcTool {
+    fn name(&self) -> &str {
+        "calc"
+    }
+
+    fn description(&self) -> &str {
+        "Evaluate an arithmetic expression exactly instead of doing math in your head: \
+         + - * / % ^, parentheses, pi, e and sqrt, cbrt, abs, ln, log, log2, exp, sin, \
+         cos, tan, asin, acos, atan, floor, ceil, round(x, digits), min, max."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "The expression, e.g. '(1200 * 0.21) / 12' or 'sqrt(2) ^ 3'"
+                }
+            },
+            "required": ["expression"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> Result<String> {
+        let expression = require_string(&params, "expression")?;
+        let value = evaluate(&expression)?;
+        Ok(format!("{} = {}", expression.trim(), format_number(value)))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let cases = [
+            ("1 + 2 * 3", 7.0),
+            ("(1 + 2) * 3", 9.0),
+            ("2 ^ 3 ^ 2", 512.0),
+            ("2 ** 10", 1024.0),
+            ("-2 ^ 2", -4.0),
+            ("10 % 4", 2.0),
+            ("1_000 * 1.5e3", 1_500_000.0),
+            ("7 × 6 ÷ 2", 21.0),
+            ("round(2.71828, 2)", 2.72),
+            ("max(1, sqrt(16), -3)", 4.0),
+            ("log(1000) + ln(e)", 4.0),
+        ];
+        for (expr, expected) in cases {
+            let value = evaluate(expr).unwrap();
+            assert!((value - expected).abs() < 1e-9, "{expr} = {value}");
+        }
+
+        for bad in ["1 / 0", "2 +", "foo(1)", "x + 1", "(1 + 2", "1 2", "sqrt(-1)", "rm -rf /"] {
+            assert!(evaluate(bad).is_err(), "{bad}");
+        }
+        assert!(evaluate(&"(".repeat(200)).is_err());
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(21.0), "21");
+        assert_eq!(format_number(-2.5), "-2.5");
+        assert_eq!(format_number(1.0 / 3.0), "0.333333333333");
+        assert_eq!(format_number(1e20), "1e20");
+    }
+}
@@ -0,0 +1,832 @@
+//! Calendar tools — list and create events on a CalDAV or Google calendar.
+//!
+//! Dates are read and written in the user's timezone: the `Timezone` line
+//! of the workspace `USER.md` (e.g. `- **Timezone**: Europe/Madrid`), else
+//! the configured default. Relative days ("tomorrow", "friday") are
+//! resolved in that timezone, so the LLM never converts times itself.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use oxibot_core::datetime::{parse_day, resolve_local};
+pub use oxibot_core::datetime::{Tz, UserTimezone};
+use oxibot_core::oauth::OAuthManager;
+use regex::Regex;
+use serde_json::{json, Value};
+
+use super::base::{optional_i64, optional_string, require_string, Tool};
+use super::dry_run::{DryRun, PREVIEW_PREFIX};
+
+/// Google Calendar API root.
+const GOOGLE_API_BASE: &str = "https://www.googleapis.com/calendar/v3";
+
+/// Maximum number of days listed at once.
+const MAX_LIST_DAYS: i64 = 31;
+
+/// Default event length in minutes.
+const DEFAULT_DURATION_MINUTES: i64 = 60;
+
+// ─────────────────────────────────────────────
+// Events
+// ─────────────────────────────────────────────
+
+/// When an event takes place.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventTime {
+    /// From `start` up to (excluding) `end`.
+    Timed { start: DateTime<Utc>, end: DateTime<Utc> },
+    /// Whole days from `start` up to (excluding) `end`.
+    AllDay { start: NaiveDate, end: NaiveDate },
+}
+
+/// An event read from a calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub title: String,
+    pub time: EventTime,
+    pub location: Option<String>,
+}
+
+impl Event {
+    /// Sort key: all-day events first within their day.
+    pub(crate) fn sort_key(&self, tz: Tz) -> (NaiveDate, Option<DateTime<Utc>>) {
+        match self.time {
+            EventTime::Timed { start, .. } => (start.with_timezone(&tz).date_naive(), Some(start)),
+            EventTime::AllDay { start, .. } => (start, None),
+        }
+    }
+
+    /// One line for the LLM, with times in `tz`.
+    pub fn summary(&self, tz: Tz) -> String {
+        let when = match self.time {
+            EventTime::Timed { start, end } => {
+                let (start, end) = (start.with_timezone(&tz), end.with_timezone(&tz));
+                if start.date_naive() == end.date_naive() {
+                    format!("{}–{}", start.format("%a %Y-%m-%d %H:%M"), end.format("%H:%M"))
+                } else {
+                    format!("{} – {}", start.format("%a %Y-%m-%d %H:%M"), end.format("%a %Y-%m-%d %H:%M"))
+                }
+            }
+            EventTime::AllDay { start, end } => {
+                let last = end.pred_opt().filter(|d| *d > start).unwrap_or(start);
+                if last == start {
+                    format!("{} (all day)", start.format("%a %Y-%m-%d"))
+                } else {
+                    format!("{} – {} (all day)", start.format("%a %Y-%m-%d"), last.format("%a %Y-%m-%d"))
+                }
+            }
+        };
+        match self.location {
+            Some(ref location) => format!("{when}: {} @ {location}", self.title),
+            None => format!("{when}: {}", self.title),
+        }
+    }
+}
+
+/// An event to create.
+#[derive(Debug, Clone)]
+pub struct NewEvent {
+    pub title: String,
+    pub start: DateTime<Tz>,
+    pub end: DateTime<Tz>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+}
+
+// ─────────────────────────────────────────────
+// Backends
+// ─────────────────────────────────────────────
+
+/// A calendar the tools read from and write to.
+#[async_trait]
+pub trait CalendarBackend: Send + Sync {
+    /// Events overlapping `start..end`; floating times are read in `tz`.
+    async fn list_events(&self, start: DateTime<Utc>, end: DateTime<Utc>, tz: Tz) -> Result<Vec<Event>>;
+
+    /// Create an event; returns a reference to it (URL or ID).
+    async fn create_event(&self, event: &NewEvent) -> Result<String>;
+}
+
+/// CalDAV calendar collection (Nextcloud, Fastmail, iCloud, Radicale...).
+pub struct CalDavBackend {
+    url: String,
+    username: String,
+    password: String,
+    client: reqwest::Client,
+}
+
+impl CalDavBackend {
+    /// Create a backend for the calendar collection at `url`.
+    ///
+    /// An empty `username` sends requests without authentication.
+    pub fn new(url: &str, username: &str, password: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        if self.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.username, Some(&self.password))
+        }
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for CalDavBackend {
+    async fn list_events(&self, start: DateTime<Utc>, end: DateTime<Utc>, tz: Tz) -> Result<Vec<Event>> {
+        let range = format!(
+            r#"start="{}" end="{}""#,
+            start.format("%Y%m%dT%H%M%SZ"),
+            end.format("%Y%m%dT%H%M%SZ")
+        );
+        // `expand` makes the server return each occurrence of recurring events
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><c:calendar-data><c:expand {range}/></c:calendar-data></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT"><c:time-range {range}/></c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#
+        );
+        let method = reqwest::Method::from_bytes(b"REPORT").expect("valid method");
+        let response = self
+            .request(method, &self.url)
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .context("CalDAV request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("CalDAV server returned {status}");
+        }
+        let xml = response.text().await?;
+        Ok(calendar_data(&xml)
+            .iter()
+            .flat_map(|ics| parse_ics(ics, tz))
+            .collect())
+    }
+
+    async fn create_event(&self, event: &NewEvent) -> Result<String> {
+        let uid = new_uid();
+        let url = format!("{}/{uid}.ics", self.url.trim_end_matches('/'));
+        let response = self
+            .request(reqwest::Method::PUT, &url)
+            .header("Content-Type", "text/calendar; charset=utf-8")
+            .header("If-None-Match", "*")
+            .body(to_ics(event, &uid, Utc::now()))
+            .send()
+            .await
+            .context("CalDAV request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("CalDAV server returned {status}");
+        }
+        Ok(url)
+    }
+}
+
+/// Google Calendar, authorized through an OAuth client.
+pub struct GoogleCalendarBackend {
+    oauth: Arc<OAuthManager>,
+    oauth_client: String,
+    calendar_id: String,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl GoogleCalendarBackend {
+    /// Create a backend for `calendar_id` using tokens of `oauth_client`.
+    pub fn new(oauth: Arc<OAuthManager>, oauth_client: &str, calendar_id: &str) -> Self {
+        Self {
+            oauth,
+            oauth_client: oauth_client.to_string(),
+            calendar_id: calendar_id.to_string(),
+            base_url: GOOGLE_API_BASE.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Override the API root (for tests).
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn events_url(&self) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&self.base_url)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("invalid calendar API URL: {}", self.base_url))?
+            .extend(["calendars", self.calendar_id.as_str(), "events"]);
+        Ok(url)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let token = self.oauth.access_token(&self.oauth_client).await?;
+        let response = request
+            .bearer_auth(token)
+            .send()
+            .await
+            .context("Google Calendar request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Google Calendar returned {status}");
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for GoogleCalendarBackend {
+    async fn list_events(&self, start: DateTime<Utc>, end: DateTime<Utc>, _tz: Tz) -> Result<Vec<Event>> {
+        let request = self.client.get(self.events_url()?).query(&[
+            ("timeMin", start.to_rfc3339()),
+            ("timeMax", end.to_rfc3339()),
+            ("singleEvents", "true".into()),
+            ("orderBy", "startTime".into()),
+            ("maxResults", "250".into()),
+        ]);
+        let body = self.send(request).await?;
+        Ok(body["items"]
+            .as_array()
+            .map(|items| items.iter().filter_map(google_event).collect())
+            .unwrap_or_default())
+    }
+
+    async fn create_event(&self, event: &NewEvent) -> Result<String> {
+        let timezone = event.start.timezone().name();
+        let mut body = json!({
+            "summary": event.title,
+            "start": { "dateTime": event.start.to_rfc3339(), "timeZone": timezone },
+            "end": { "dateTime": event.end.to_rfc3339(), "timeZone": timezone },
+        });
+        if let Some(ref location) = event.location {
+            body["location"] = json!(location);
+        }
+        if let Some(ref description) = event.description {
+            body["description"] = json!(description);
+        }
+        let created = self.send(self.client.post(self.events_url()?).json(&body)).await?;
+        Ok(created["htmlLink"]
+            .as_str()
+            .or(created["id"].as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+}
+
+/// Convert a Google Calendar event resource.
+fn google_event(item: &Value) -> Option<Event> {
+    let time = match (item["start"]["dateTime"].as_str(), item["end"]["dateTime"].as_str()) {
+        (Some(start), Some(end)) => EventTime::Timed {
+            start: DateTime::parse_from_rfc3339(start).ok()?.with_timezone(&Utc),
+            end: DateTime::parse_from_rfc3339(end).ok()?.with_timezone(&Utc),
+        },
+        _ => EventTime::AllDay {
+            start: item["start"]["date"].as_str()?.parse().ok()?,
+            end: item["end"]["date"].as_str()?.parse().ok()?,
+        },
+    };
+    Some(Event {
+        title: item["summary"].as_str().unwrap_or("(no title)").to_string(),
+        time,
+        location: item["location"].as_str().filter(|l| !l.is_empty()).map(String::from),
+    })
+}
+
+// ─────────────────────────────────────────────
+// iCalendar
+// ─────────────────────────────────────────────
+
+/// Contents of the `calendar-data` elements of a CalDAV multistatus response.
+fn calendar_data(xml: &str) -> Vec<String> {
+    let re = Regex::new(r"(?s)<(?:[\w-]+:)?calendar-data[^>]*>(.*?)</(?:[\w-]+:)?calendar-data>")
+        .expect("valid regex");
+    re.captures_iter(xml)
+        .map(|c| {
+            let data = c[1].trim();
+            match data.strip_prefix("<![CDATA[").and_then(|d| d.strip_suffix("]]>")) {
+                Some(raw) => raw.to_string(),
+                None => xml_unescape(data),
+            }
+        })
+        .collect()
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#xD;", "\r")
+        .replace("&amp;", "&")
+}
+
+/// Parse the VEVENTs of an iCalendar document; floating times are in `tz`.
+fn parse_ics(ics: &str, tz: Tz) -> Vec<Event> {
+    // Unfold continuation lines (RFC 5545 §3.1)
+    let unfolded = ics.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+
+    let mut events = Vec::new();
+    let mut current: Option<HashMap<String, (String, String)>> = None;
+    for line in unfolded.lines() {
+        match line {
+            "BEGIN:VEVENT" => current = Some(HashMap::new()),
+            "END:VEVENT" => {
+                if let Some(event) = current.take().and_then(|props| ics_event(&props, tz)) {
+                    events.push(event);
+                }
+            }
+            _ => {
+                let (Some(props), Some((head, value))) = (current.as_mut(), line.split_once(':')) else {
+                    continue;
+                };
+                let (name, params) = head.split_once(';').unwrap_or((head, ""));
+                props
+                    .entry(name.to_ascii_uppercase())
+                    .or_insert_with(|| (params.to_string(), value.to_string()));
+            }
+        }
+    }
+    events
+}
+
+fn ics_event(props: &HashMap<String, (String, String)>, tz: Tz) -> Option<Event> {
+    let start = props.get("DTSTART").and_then(|(p, v)| ics_time(p, v, tz))?;
+    let end = props.get("DTEND").and_then(|(p, v)| ics_time(p, v, tz));
+    let time = match (start, end) {
+        (IcsTime::Date(start), Some(IcsTime::Date(end))) => EventTime::AllDay { start, end },
+        (IcsTime::Date(start), _) => EventTime::AllDay { start, end: start.succ_opt()? },
+        (IcsTime::DateTime(start), Some(IcsTime::DateTime(end))) => EventTime::Timed { start, end },
+        (IcsTime::DateTime(start), _) => EventTime::Timed { start, end: start },
+    };
+    let text = |name: &str| props.get(name).map(|(_, v)| ics_unescape(v)).filter(|v| !v.is_empty());
+    Some(Event {
+        title: text("SUMMARY").unwrap_or_else(|| "(no title)".into()),
+        time,
+        location: text("LOCATION"),
+    })
+}
+
+enum IcsTime {
+    Date(NaiveDate),
+    DateTime(DateTime<Utc>),
+}
+
+/// Parse a DATE or DATE-TIME value with its parameters (`VALUE=DATE`, `TZID=...`).
+fn ics_time(params: &str, value: &str, tz: Tz) -> Option<IcsTime> {
+    if (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(IcsTime::Date);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(IcsTime::DateTime(naive.and_utc()));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // Unknown TZIDs (e.g. Windows zone names) are read in the user's timezone
+    let zone = params
+        .split(';')
+        .find_map(|p| p.strip_prefix("TZID="))
+        .and_then(|id| id.trim_matches('"').parse::<Tz>().ok())
+        .unwrap_or(tz);
+    Some(IcsTime::DateTime(resolve_local(zone, naive)?.with_timezone(&Utc)))
+}
+
+fn ics_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Serialize an event as an iCalendar document (times in UTC).
+fn to_ics(event: &NewEvent, uid: &str, now: DateTime<Utc>) -> String {
+    let utc = |t: &DateTime<Tz>| t.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".into(),
+        "PRODID:-//Oxibot//Calendar//EN".into(),
+        "BEGIN:VEVENT".into(),
+        format!("UID:{uid}"),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART:{}", utc(&event.start)),
+        format!("DTEND:{}", utc(&event.end)),
+        format!("SUMMARY:{}", ics_escape(&event.title)),
+    ];
+    if let Some(ref location) = event.location {
+        lines.push(format!("LOCATION:{}", ics_escape(location)));
+    }
+    if let Some(ref description) = event.description {
+        lines.push(format!("DESCRIPTION:{}", ics_escape(description)));
+    }
+    lines.extend(["END:VEVENT".to_string(), "END:VCALENDAR".into()]);
+    lines.join("\r\n") + "\r\n"
+}
+
+/// Unique ID for a new event.
+fn new_uid() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("{nanos:x}-{:x}@oxibot", std::process::id())
+}
+
+// ─────────────────────────────────────────────
+// Date parsing
+// ─────────────────────────────────────────────
+
+/// Parse "<day> HH:MM" (see [`parse_day`](oxibot_core::datetime::parse_day)) or "YYYY-MM-DDTHH:MM".
+fn parse_local_datetime(text: &str, today: NaiveDate) -> Result<NaiveDateTime> {
+    let text = text.trim();
+    let (day, time) = text
+        .rsplit_once([' ', 'T'])
+        .ok_or_else(|| anyhow::anyhow!("invalid start '{text}': expected e.g. '2026-10-16 15:00' or 'friday 15:00'"))?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+        .map_err(|_| anyhow::anyhow!("invalid time '{time}': use 24-hour HH:MM"))?;
+    Ok(parse_day(day, today)?.and_time(time))
+}
+
+// ─────────────────────────────────────────────
+// CalendarListEventsTool
+// ─────────────────────────────────────────────
+
+/// Tool to list upcoming calendar events.
+pub struct CalendarListEventsTool {
+    backend: Arc<dyn CalendarBackend>,
+    timezone: UserTimezone,
+}
+
+impl CalendarListEventsTool {
+    /// Create a new list tool.
+    pub fn new(backend: Arc<dyn CalendarBackend>, timezone: UserTimezone) -> Self {
+        Self { backend, timezone }
+    }
+
+    async fn list(&self, params: &HashMap<String, Value>, now: DateTime<Utc>) -> Result<String> {
+        let tz = self.timezone.get();
+        let today = now.with_timezone(&tz).date_naive();
+        let first = match optional_string(params, "start") {
+            Some(day) => parse_day(&day, today)?,
+            None => today,
+        };
+        let days = optional_i64(params, "days").unwrap_or(1).clamp(1, MAX_LIST_DAYS);
+        let last = first + Duration::days(days);
+        let midnight = |day: NaiveDate| {
+            resolve_local(tz, day.and_time(NaiveTime::MIN))
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(|| anyhow::anyhow!("invalid date {day}"))
+        };
+
+        let mut events = self.backend.list_events(midnight(first)?, midnight(last)?, tz).await?;
+        events.sort_by_key(|e| e.sort_key(tz));
+
+        let range = if days == 1 {
+            first.format("%a %Y-%m-%d").to_string()
+        } else {
+            format!("{} – {}", first.format("%a %Y-%m-%d"), (last - Duration::days(1)).format("%a %Y-%m-%d"))
+        };
+        if events.is_empty() {
+            return Ok(format!("No events on {range} (timezone {tz})."));
+        }
+        let lines: Vec<String> = events.iter().map(|e| format!("- {}", e.summary(tz))).collect();
+        Ok(format!("Events on {range} (timezone {tz}):\n{}", lines.join("\n")))
+    }
+}
+
+#[async_trait]
+impl Tool for CalendarListEventsTool {
+    fn name(&self) -> &str {
+        "calendar_list_events"
+    }
+
+    fn description(&self) -> &str {
+        "List events on the user's calendar. Days are in the user's timezone; pass \
+         'today', 'tomorrow', a weekday (its next occurrence) or YYYY-MM-DD."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "start": {
+                    "type": "string",
+                    "description": "First day: 'today' (default), 'tomorrow', a weekday or YYYY-MM-DD"
+                },
+                "days": {
+                    "type": "integer",
+                    "description": "Number of days to list (default 1, max 31)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        self.list(&params, Utc::now()).await
+    }
+}
+
+// ─────────────────────────────────────────────
+// CalendarCreateEventTool
+// ─────────────────────────────────────────────
+
+/// Tool to add an event to the calendar.
+pub struct CalendarCreateEventTool {
+    backend: Arc<dyn CalendarBackend>,
+    timezone: UserTimezone,
+    dry_run: DryRun,
+}
+
+impl CalendarCreateEventTool {
+    /// Create a new create-event tool.
+    pub fn new(backend: Arc<dyn CalendarBackend>, timezone: UserTimezone) -> Self {
+        Self {
+            backend,
+            timezone,
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Describe the event instead of creating it while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    async fn create(&self, params: &HashMap<String, Value>, now: DateTime<Utc>) -> Result<String> {
+        let tz = self.timezone.get();
+        let title = require_string(params, "title")?;
+        let start = parse_local_datetime(&require_string(params, "start")?, now.with_timezone(&tz).date_naive())?;
+        let start = resolve_local(tz, start).ok_or_else(|| anyhow::anyhow!("invalid start time {start}"))?;
+        let minutes = optional_i64(params, "duration_minutes").unwrap_or(DEFAULT_DURATION_MINUTES);
+        if minutes <= 0 {
+            anyhow::bail!("duration_minutes must be positive");
+        }
+        let event = NewEvent {
+            title,
+            start,
+            end: start + Duration::minutes(minutes),
+            location: optional_string(params, "location").filter(|l| !l.is_empty()),
+            description: optional_string(params, "description").filter(|d| !d.is_empty()),
+        };
+        let summary = Event {
+            title: event.title.clone(),
+            time: EventTime::Timed {
+                start: event.start.with_timezone(&Utc),
+                end: event.end.with_timezone(&Utc),
+            },
+            location: event.location.clone(),
+        }
+        .summary(tz);
+
+        if self.dry_run.enabled() {
+            return Ok(format!("{PREVIEW_PREFIX} would create event {summary} (timezone {tz})"));
+        }
+        let reference = self.backend.create_event(&event).await?;
+        Ok(format!("Created event {summary} (timezone {tz}): {reference}"))
+    }
+}
+
+#[async_trait]
+impl Tool for CalendarCreateEventTool {
+    fn name(&self) -> &str {
+        "calendar_create_event"
+    }
+
+    fn description(&self) -> &str {
+        "Add an event to the user's calendar. The start is local time in the user's \
+         timezone, as 'YYYY-MM-DD HH:MM' or '<today|tomorrow|weekday> HH:MM' (24-hour)."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "title": {
+                    "type": "string",
+                    "description": "Event title"
+                },
+                "start": {
+                    "type": "string",
+                    "description": "Local start, e.g. '2026-10-16 15:00' or 'friday 15:00'"
+                },
+                "duration_minutes": {
+                    "type": "integer",
+                    "description": "Length in minutes (default 60)"
+                },
+                "location": {
+                    "type": "string",
+                    "description": "Where the event takes place"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Notes for the event"
+                }
+            },
+            "required": ["title", "start"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        self.create(&params, Utc::now()).await
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn madrid(dir: &tempfile::TempDir) -> UserTimezone {
+        let profile = dir.path().join("USER.md");
+        std::fs::write(&profile, "## About Me\n\n- **Name**: Ana\n- **Timezone**: Europe/Madrid\n").unwrap();
+        UserTimezone::new(profile, Tz::UTC)
+    }
+
+    /// 2026-10-15 (a Thursday) 22:30 UTC — already Friday in Madrid.
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 15, 22, 30, 0).unwrap()
+    }
+
+    const MULTISTATUS: &str = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response><d:propstat><d:prop><cal:calendar-data>BEGIN:VCALENDAR
+BEGIN:VEVENT
+SUMMARY:Standup\, daily
+DTSTART;TZID=Europe/Madrid:20261016T093000
+DTEND;TZID=Europe/Madrid:20261016T094500
+LOCATION:Room &amp; 2
+END:VEVENT
+END:VCALENDAR
+</cal:calendar-data></d:prop></d:propstat></d:response>
+  <d:response><d:propstat><d:prop><cal:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+SUMMARY:Lunch with a very long
+  title
+DTSTART:20261016T110000Z
+DTEND:20261016T120000Z
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Holiday
+DTSTART;VALUE=DATE:20261016
+DTEND;VALUE=DATE:20261017
+END:VEVENT
+END:VCALENDAR]]></cal:calendar-data></d:prop></d:propstat></d:response>
+</d:multistatus>"#;
+
+    #[test]
+    fn test_parse_caldav_response() {
+        let events: Vec<Event> = calendar_data(MULTISTATUS)
+            .iter()
+            .flat_map(|ics| parse_ics(ics, Tz::UTC))
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].title, "Standup, daily");
+        assert_eq!(events[0].location.as_deref(), Some("Room & 2"));
+        assert_eq!(
+            events[0].time,
+            EventTime::Timed {
+                start: Utc.with_ymd_and_hms(2026, 10, 16, 7, 30, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2026, 10, 16, 7, 45, 0).unwrap(),
+            }
+        );
+        assert_eq!(events[1].title, "Lunch with a very long title");
+        let day = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(events[2].time, EventTime::AllDay { start: day, end: day.succ_opt().unwrap() });
+        assert_eq!(events[2].summary(Tz::UTC), "Fri 2026-10-16 (all day): Holiday");
+    }
+
+    #[test]
+    fn test_parse_local_datetime() {
+        // Thursday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(
+            parse_local_datetime("fri 15:00", today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(15, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_local_datetime("2026-11-02T09:30", today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 11, 2).unwrap().and_hms_opt(9, 30, 0).unwrap()
+        );
+        assert!(parse_local_datetime("friday 3pm", today).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_events_caldav() {
+        let server = MockServer::start().await;
+        Mock::given(method("REPORT"))
+            .and(path("/cal/personal/"))
+            .and(header("Depth", "1"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(MULTISTATUS))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let backend = Arc::new(CalDavBackend::new(&format!("{}/cal/personal/", server.uri()), "", ""));
+        let tool = CalendarListEventsTool::new(backend, madrid(&dir));
+
+        // "today" is Friday in Madrid even though it is still Thursday in UTC
+        let out = tool.list(&HashMap::new(), now()).await.unwrap();
+        assert_eq!(
+            out,
+            "Events on Fri 2026-10-16 (timezone Europe/Madrid):\n\
+             - Fri 2026-10-16 (all day): Holiday\n\
+             - Fri 2026-10-16 09:30–09:45: Standup, daily @ Room & 2\n\
+             - Fri 2026-10-16 13:00–14:00: Lunch with a very long title"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_event_caldav() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(header("If-None-Match", "*"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let backend = Arc::new(CalDavBackend::new(&server.uri(), "ana", "secret"));
+        let dry_run = DryRun::new();
+        let tool = CalendarCreateEventTool::new(backend, madrid(&dir)).with_dry_run(dry_run.clone());
+        let args = params(json!({"title": "Call with Bob", "start": "friday 15:00", "duration_minutes": 30}));
+
+        dry_run.set(true);
+        let preview = tool.create(&args, now()).await.unwrap();
+        assert!(preview.starts_with(PREVIEW_PREFIX));
+
+        dry_run.set(false);
+        let out = tool.create(&args, now()).await.unwrap();
+        // Today is Friday in Madrid, so "friday" is next week's
+        assert!(out.starts_with("Created event Fri 2026-10-23 15:00–15:30: Call with Bob"), "{out}");
+
+        let requests = server.received_requests().await.unwrap();
+        let body = String::from_utf8_lossy(&requests[0].body);
+        assert!(body.contains("DTSTART:20261023T130000Z\r\n"));
+        assert!(body.contains("DTEND:20261023T133000Z\r\n"));
+        assert!(body.contains("SUMMARY:Call with Bob\r\n"));
+        assert!(requests[0].headers.get("authorization").is_some());
+    }
+
+    #[test]
+    fn test_google_event() {
+        let timed = json!({
+            "summary": "Dentist",
+            "location": "Main St",
+            "start": {"dateTime": "2026-10-16T15:00:00+02:00"},
+            "end": {"dateTime": "2026-10-16T16:00:00+02:00"}
+        });
+        let event = google_event(&timed).unwrap();
+        assert_eq!(event.summary(Tz::Europe__Madrid), "Fri 2026-10-16 15:00–16:00: Dentist @ Main St");
+
+        let all_day = json!({"start": {"date": "2026-10-16"}, "end": {"date": "2026-10-19"}});
+        assert_eq!(
+            google_event(&all_day).unwrap().summary(Tz::UTC),
+            "Fri 2026-10-16 – Sun 2026-10-18 (all day): (no title)"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Contacts tool — look up and edit the contact book.
+//!
+//! Lets the agent resolve "email Alice the notes" to an address and record
+//! a person's identities and preferences so they follow them across channels.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use oxibot_core::contacts::ContactBook;
+use serde_json::{json, Value};
+
+use super::base::{optional_string, require_string, Tool};
+
+// ─────────────────────────────────────────────
+// ContactsTool
+// ─────────────────────────────────────────────
+
+/// Tool to list, look up, link and annotate contacts.
+pub struct ContactsTool {
+    /// Contact book file (`None` = `~/.oxibot/contacts.json`).
+    path: Option<PathBuf>,
+}
+
+impl ContactsTool {
+    /// Create a new contacts tool backed by `path`.
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    fn book(&self) -> ContactBook {
+        ContactBook::load(self.path.clone())
+    }
+}
+
+#[async_trait]
+impl Tool for ContactsTool {
+    fn name(&self) -> &str {
+        "contacts"
+    }
+
+    fn description(&self) -> &str {
+        "Manage the contact book that maps people to their ids on each channel \
+         (e.g. telegram:123, slack:U45, email:alice@x.com). Use 'lookup' to find \
+         a person's address on a channel before messaging them, 'link' to record \
+         a new id, 'note' to save their preferences, and 'list' to show everyone."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "lookup", "link", "unlink", "note"],
+                    "description": "What to do"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Contact name (all actions except 'list')"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Channel to resolve the address for (for 'lookup', e.g. 'email')"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Identity as channel:id or an email address (for 'link'/'unlink')"
+                },
+                "notes": {
+                    "type": "string",
+                    "description": "Notes/preferences replacing the current ones (for 'note')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let action = require_string(&params, "action")?;
+        let mut book = self.book();
+
+        if action == "list" {
+            if book.contacts().is_empty() {
+                return Ok("No contacts.".into());
+            }
+            return Ok(book
+                .contacts()
+                .iter()
+                .map(|c| format!("- {}", c.summary()))
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+
+        let name = require_string(&params, "name")?;
+        match action.as_str() {
+            "lookup" => {
+                let contact = book
+                    .get(&name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown contact: {name}"))?;
+                match optional_string(&params, "channel") {
+                    Some(channel) => match contact.id_on(&channel) {
+                        Some(id) => Ok(format!("{}'s {channel} id: {id}", contact.name)),
+                        None => anyhow::bail!("{} has no {channel} id", contact.name),
+                    },
+                    None => Ok(contact.summary()),
+                }
+            }
+            "link" => {
+                let id = require_string(&params, "id")?;
+                let summary = book.link(&name, &[id])?.summary();
+                book.save()?;
+                Ok(format!("Saved: {summary}"))
+            }
+            "unlink" => {
+                let id = require_string(&params, "id")?;
+                if !book.unlink(&name, &id) {
+                    anyhow::bail!("{name} has no id {id}");
+                }
+                book.save()?;
+                Ok(format!("Unlinked {id} from {name}"))
+            }
+            "note" => {
+                let notes = optional_string(&params, "notes").unwrap_or_default();
+                if !book.set_notes(&name, &notes) {
+                    anyhow::bail!("Unknown contact: {name}");
+                }
+                book.save()?;
+                Ok(format!("Updated notes for {name}"))
+            }
+            other => anyhow::bail!("Unknown action: {other}"),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_contacts_link_lookup_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = ContactsTool::new(Some(dir.path().join("contacts.json")));
+
+        assert_eq!(tool.execute(params(json!({"action": "list"}))).await.unwrap(), "No contacts.");
+
+        tool.execute(params(json!({"action": "link", "name": "Alice", "id": "alice@x.com"})))
+            .await
+            .unwrap();
+        tool.execute(params(json!({"action": "link", "name": "alice", "id": "telegram:123"})))
+            .await
+            .unwrap();
+
+        let out = tool
+            .execute(params(json!({"action": "lookup", "name": "ALICE", "channel": "email"})))
+            .await
+            .unwrap();
+        assert_eq!(out, "Alice's email id: alice@x.com");
+        assert!(tool
+            .execute(params(json!({"action": "lookup", "name": "Alice", "channel": "slack"})))
+            .await
+            .is_err());
+
+        tool.execute(params(json!({"action": "note", "name": "Alice", "notes": "Vegetarian"})))
+            .await
+            .unwrap();
+        let list = tool.execute(params(json!({"action": "list"}))).await.unwrap();
+        assert_eq!(list, "- Alice — email:alice@x.com, telegram:123 (Vegetarian)");
+    }
+
+    #[tokio::test]
+    async fn test_contacts_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = ContactsTool::new(Some(dir.path().join("contacts.json")));
+        assert!(tool.execute(params(json!({"action": "lookup", "name": "Bob"}))).await.is_err());
+        assert!(tool
+            .execute(params(json!({"action": "link", "name": "Bob", "id": "bob"})))
+            .await
+            .is_err());
+        assert!(tool
+            .execute(params(json!({"action": "unlink", "name": "Bob", "id": "telegram:1"})))
+            .await
+            .is_err());
+        assert!(tool.execute(params(json!({"action": "note", "name": "Bob"}))).await.is_err());
+        assert!(tool.execute(params(json!({"action": "link"}))).await.is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Turn context — the conversation a tool call belongs to.
+//!
+//! Tools that reply, spawn, pin or resolve relative paths need to know the
+//! current chat. Turns of different sessions run concurrently on the same
+//! tool instances, so the agent loop runs each turn inside
+//! [`TurnContext::scope`] and tools read [`TurnContext::current`]. Outside a
+//! turn (tests, standalone use) tools fall back to what their
+//! `set_context` stored.
+
+use std::future::Future;
+use std::path::PathBuf;
+
+use oxibot_core::types::MediaAttachment;
+use tokio::sync::Mutex;
+
+tokio::task_local! {
+    static CURRENT: TurnContext;
+}
+
+/// Where the turn being processed came from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TurnContext {
+    pub channel: String,
+    pub chat_id: String,
+    pub session_key: String,
+    /// Sender of the message being answered (empty for subagent results).
+    pub sender_id: String,
+    /// Default directory for relative paths (the session's scratch dir).
+    pub working_dir: Option<PathBuf>,
+    /// Files attached to the message being answered.
+    pub attachments: Vec<MediaAttachment>,
+}
+
+impl TurnContext {
+    pub fn new(channel: &str, chat_id: &str, session_key: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            session_key: session_key.to_string(),
+            sender_id: String::new(),
+            working_dir: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    /// Set the sender of the message being answered.
+    pub fn with_sender(mut self, sender_id: &str) -> Self {
+        self.sender_id = sender_id.to_string();
+        self
+    }
+
+    /// Set the default directory for relative paths.
+    pub fn with_working_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.working_dir = dir;
+        self
+    }
+
+    /// Set the files attached to the message being answered.
+    pub fn with_attachments(mut self, attachments: Vec<MediaAttachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    /// Run `fut` as this turn.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+
+    /// The turn being processed, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Self::clone).ok()
+    }
+}
+
+/// `(channel, chat_id)` of the current turn, else the value in `fallback`.
+pub async fn current_chat(fallback: &Mutex<(String, String)>) -> (String, String) {
+    match TurnContext::current() {
+        Some(turn) => (turn.channel, turn.chat_id),
+        None => fallback.lock().await.clone(),
+    }
+}
+
+/// Sender of the current turn's message, if known. Approval requests
+/// name them as the one who may answer.
+pub fn current_sender() -> Option<String> {
+    TurnContext::current()
+        .map(|turn| turn.sender_id)
+        .filter(|id| !id.is_empty())
+}
+
+/// Session key of the current turn, else the value in `fallback`.
+pub async fn current_session(fallback: &Mutex<String>) -> String {
+    match TurnContext::current() {
+        Some(turn) => turn.session_key,
+        None => fallback.lock().await.clone(),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_turns_see_their_own_context() {
+        let fallback = Mutex::new(("cli".to_string(), "direct".to_string()));
+        assert_eq!(current_chat(&fallback).await, ("cli".into(), "direct".into()));
+
+        let turn = |chat: &'static str| {
+            let fallback = &fallback;
+            TurnContext::new("telegram", chat, &format!("telegram:{chat}")).scope(async move {
+                tokio::task::yield_now().await;
+                current_chat(fallback).await.1
+            })
+        };
+        let (a, b) = tokio::join!(turn("1"), turn("2"));
+        assert_eq!((a.as_str(), b.as_str()), ("1", "2"));
+        assert_eq!(TurnContext::current(), None);
+    }
+}
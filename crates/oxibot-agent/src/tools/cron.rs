@@ -0,0 +1,466 @@
+//! Cron tools — `cron_add`, `cron_list` and `cron_remove`.
+//!
+//! Let the agent schedule recurring or one-time jobs from a conversation
+//! ("check my RSS feed every morning at 8") instead of `oxibot cron add`.
+//! Jobs are delivered to the chat that created them, and each chat only
+//! sees and removes its own jobs (the local CLI sees all of them).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use oxibot_core::datetime::{parse_time, to_iso, UserTimezone};
+use oxibot_cron::{compute_next_run_from, CronJob, CronPayload, CronSchedule, CronService, ScheduleKind};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::base::{optional_i64, optional_string, require_string, Tool};
+use super::context::current_chat;
+use super::dry_run::{DryRun, PREVIEW_PREFIX};
+
+/// Shortest interval for `every_seconds`.
+const MIN_INTERVAL_SECS: i64 = 60;
+
+/// Whether `job` delivers to `(channel, chat_id)`. The local CLI owns every job.
+fn owned_by(job: &CronJob, (channel, chat_id): &(String, String)) -> bool {
+    channel == "cli" || (job.payload.channel.as_ref() == Some(channel) && job.payload.to.as_ref() == Some(chat_id))
+}
+
+/// When a job runs, e.g. `every 1h`, `cron 0 0 8 * * * (Europe/Madrid)`.
+fn describe_schedule(schedule: &CronSchedule) -> String {
+    match schedule.kind {
+        ScheduleKind::Every => {
+            let secs = schedule.every_ms.unwrap_or(60_000) / 1000;
+            match secs {
+                s if s % 86400 == 0 => format!("every {}d", s / 86400),
+                s if s % 3600 == 0 => format!("every {}h", s / 3600),
+                s if s % 60 == 0 => format!("every {}m", s / 60),
+                s => format!("every {s}s"),
+            }
+        }
+        ScheduleKind::Cron => format!(
+            "cron {} ({})",
+            schedule.expr.as_deref().unwrap_or("?"),
+            schedule.tz.as_deref().unwrap_or("UTC")
+        ),
+        ScheduleKind::At => "once".into(),
+    }
+}
+
+/// Next run of `job` in its own timezone, or `—`.
+fn describe_next_run(job: &CronJob) -> String {
+    match (job.state.next_run_at_ms.and_then(DateTime::from_timestamp_millis), job.schedule.timezone()) {
+        (Some(at), Some(tz)) => at.with_timezone(&tz).format("%a %Y-%m-%d %H:%M %Z").to_string(),
+        _ => "—".into(),
+    }
+}
+
+// ─────────────────────────────────────────────
+// CronAddTool
+// ─────────────────────────────────────────────
+
+/// Tool to schedule a job whose result is sent to the current chat.
+pub struct CronAddTool {
+    service: Arc<CronService>,
+    timezone: UserTimezone,
+    /// `(channel, chat_id)` outside agent turns.
+    chat: Mutex<(String, String)>,
+    dry_run: DryRun,
+}
+
+impl CronAddTool {
+    /// Create a new `cron_add` tool. Times are read in `timezone`.
+    pub fn new(service: Arc<CronService>, timezone: UserTimezone) -> Self {
+        Self {
+            service,
+            timezone,
+            chat: Mutex::new(("cli".into(), "direct".into())),
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Describe the job instead of scheduling it while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set the chat used outside agent turns.
+    pub async fn set_context(&self, channel: &str, chat_id: &str) {
+        *self.chat.lock().await = (channel.to_string(), chat_id.to_string());
+    }
+
+    /// The schedule described by `params`: exactly one of `every_seconds`,
+    /// `cron` or `at`.
+    fn schedule(&self, params: &HashMap<String, Value>, now: DateTime<Utc>) -> anyhow::Result<CronSchedule> {
+        let every = optional_i64(params, "every_seconds");
+        let expr = optional_string(params, "cron").filter(|s| !s.trim().is_empty());
+        let at = optional_string(params, "at").filter(|s| !s.trim().is_empty());
+        let tz = self.timezone.get();
+
+        let schedule = match (every, expr, at) {
+            (Some(secs), None, None) => {
+                if secs < MIN_INTERVAL_SECS {
+                    anyhow::bail!("every_seconds must be at least {MIN_INTERVAL_SECS}");
+                }
+                CronSchedule::every(secs * 1000)
+            }
+            (None, Some(expr), None) => {
+                let schedule = CronSchedule::cron(expr.trim()).with_tz(tz.name());
+                if compute_next_run_from(&schedule, now.timestamp_millis()).is_none() {
+                    anyhow::bail!(
+                        "invalid cron expression '{}' (6 fields: sec min hour day month weekday, e.g. '0 0 8 * * *')",
+                        expr.trim()
+                    );
+                }
+                schedule
+            }
+            (None, None, Some(text)) => {
+                let at = parse_time(&text, now.with_timezone(&tz))?;
+                if at <= now {
+                    anyhow::bail!("{} is in the past", to_iso(&at));
+                }
+                CronSchedule::at(at.timestamp_millis()).with_tz(tz.name())
+            }
+            _ => anyhow::bail!("give exactly one of every_seconds, cron or at"),
+        };
+        Ok(schedule)
+    }
+
+    async fn add(&self, params: &HashMap<String, Value>, now: DateTime<Utc>) -> anyhow::Result<String> {
+        let name = require_string(params, "name")?;
+        let message = require_string(params, "message")?;
+        if message.trim().is_empty() {
+            anyhow::bail!("Missing required parameter: message");
+        }
+        let schedule = self.schedule(params, now)?;
+        let (channel, chat_id) = current_chat(&self.chat).await;
+
+        let mut job = CronJob::new(
+            name.trim(),
+            schedule,
+            CronPayload {
+                message,
+                deliver: true,
+                channel: Some(channel),
+                to: Some(chat_id),
+                force_delivery: false,
+            },
+        );
+        job.state.next_run_at_ms = compute_next_run_from(&job.schedule, now.timestamp_millis());
+        let summary = format!(
+            "'{}' ({}), next run {}",
+            job.name,
+            describe_schedule(&job.schedule),
+            describe_next_run(&job)
+        );
+
+        if self.dry_run.enabled() {
+            return Ok(format!("{PREVIEW_PREFIX} would schedule job {summary}"));
+        }
+        let id = self.service.add_job(job).await?;
+        Ok(format!(
+            "Scheduled job {id} {summary}. Results will be sent to this chat; remove it with cron_remove."
+        ))
+    }
+}
+
+#[async_trait]
+impl Tool for CronAddTool {
+    fn name(&self) -> &str {
+        "cron_add"
+    }
+
+    fn description(&self) -> &str {
+        "Schedule a job that runs a prompt on a schedule and sends the result to \
+         this chat, e.g. 'check my RSS feed every morning at 8'. Give exactly one of \
+         every_seconds, cron (6 fields, in the user's timezone) or at (a one-time \
+         run, like 'tomorrow 9:00'). Tell the user the job ID from the result."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Short name for the job"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "The prompt to run each time, written as an instruction to yourself"
+                },
+                "every_seconds": {
+                    "type": "integer",
+                    "description": "Run at this interval (at least 60)"
+                },
+                "cron": {
+                    "type": "string",
+                    "description": "Cron expression 'sec min hour day month weekday', e.g. '0 0 8 * * *' for 8:00 daily"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "Run once at this time, e.g. 'friday at 17:00' or 'in 2h'"
+                }
+            },
+            "required": ["name", "message"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        self.add(&params, Utc::now()).await
+    }
+}
+
+// ─────────────────────────────────────────────
+// CronListTool
+// ─────────────────────────────────────────────
+
+/// Tool to list the current chat's jobs.
+pub struct CronListTool {
+    service: Arc<CronService>,
+    /// `(channel, chat_id)` outside agent turns.
+    chat: Mutex<(String, String)>,
+}
+
+impl CronListTool {
+    /// Create a new `cron_list` tool.
+    pub fn new(service: Arc<CronService>) -> Self {
+        Self {
+            service,
+            chat: Mutex::new(("cli".into(), "direct".into())),
+        }
+    }
+
+    /// Set the chat used outside agent turns.
+    pub async fn set_context(&self, channel: &str, chat_id: &str) {
+        *self.chat.lock().await = (channel.to_string(), chat_id.to_string());
+    }
+}
+
+#[async_trait]
+impl Tool for CronListTool {
+    fn name(&self) -> &str {
+        "cron_list"
+    }
+
+    fn description(&self) -> &str {
+        "List the scheduled jobs of this chat with their ID, schedule and next run."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {},
+            "required": []
+        })
+    }
+
+    async fn execute(&self, _params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let chat = current_chat(&self.chat).await;
+        let lines: Vec<String> = self
+            .service
+            .list_jobs()
+            .await
+            .iter()
+            .filter(|job| owned_by(job, &chat))
+            .map(|job| {
+                let status = if job.enabled { "" } else { " [disabled]" };
+                format!(
+                    "{} '{}' ({}), next run {}{status}: {}",
+                    job.id,
+                    job.name,
+                    describe_schedule(&job.schedule),
+                    describe_next_run(job),
+                    job.payload.message
+                )
+            })
+            .collect();
+        if lines.is_empty() {
+            return Ok("No scheduled jobs.".into());
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+// ─────────────────────────────────────────────
+// CronRemoveTool
+// ─────────────────────────────────────────────
+
+/// Tool to remove one of the current chat's jobs.
+pub struct CronRemoveTool {
+    service: Arc<CronService>,
+    /// `(channel, chat_id)` outside agent turns.
+    chat: Mutex<(String, String)>,
+    dry_run: DryRun,
+}
+
+impl CronRemoveTool {
+    /// Create a new `cron_remove` tool.
+    pub fn new(service: Arc<CronService>) -> Self {
+        Self {
+            service,
+            chat: Mutex::new(("cli".into(), "direct".into())),
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Describe the removal instead of doing it while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set the chat used outside agent turns.
+    pub async fn set_context(&self, channel: &str, chat_id: &str) {
+        *self.chat.lock().await = (channel.to_string(), chat_id.to_string());
+    }
+}
+
+#[async_trait]
+impl Tool for CronRemoveTool {
+    fn name(&self) -> &str {
+        "cron_remove"
+    }
+
+    fn description(&self) -> &str {
+        "Remove a scheduled job of this chat by its ID (see cron_list)."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Job ID"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let id = require_string(&params, "id")?;
+        let id = id.trim();
+        let chat = current_chat(&self.chat).await;
+        let job = match self.service.get_job(id).await {
+            Some(job) if owned_by(&job, &chat) => job,
+            _ => anyhow::bail!("No job {id} in this chat"),
+        };
+
+        if self.dry_run.enabled() {
+            return Ok(format!("{PREVIEW_PREFIX} would remove job {id} '{}'", job.name));
+        }
+        self.service.remove_job(id).await?;
+        Ok(format!("Removed job {id} '{}'", job.name))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::bus::queue::MessageBus;
+    use oxibot_core::datetime::Tz;
+
+    fn make_service(dir: &tempfile::TempDir) -> Arc<CronService> {
+        Arc::new(CronService::new(
+            Arc::new(MessageBus::new(8)),
+            Some(dir.path().join("jobs.json")),
+        ))
+    }
+
+    fn timezone(dir: &tempfile::TempDir) -> UserTimezone {
+        UserTimezone::new(dir.path().join("USER.md"), "Europe/Madrid".parse::<Tz>().unwrap())
+    }
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cron_add_list_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = make_service(&dir);
+        let add = CronAddTool::new(service.clone(), timezone(&dir));
+        add.set_context("telegram", "42").await;
+
+        let out = add
+            .execute(params(json!({
+                "name": "rss",
+                "message": "Check my RSS feed",
+                "cron": "0 0 8 * * *"
+            })))
+            .await
+            .unwrap();
+        assert!(out.contains("cron 0 0 8 * * * (Europe/Madrid)"), "{out}");
+        assert!(out.contains("08:00"), "{out}");
+
+        let job = &service.list_jobs().await[0];
+        assert_eq!(job.payload.channel.as_deref(), Some("telegram"));
+        assert_eq!(job.payload.to.as_deref(), Some("42"));
+        assert!(job.payload.deliver);
+
+        // Other chats don't see or remove the job
+        let list = CronListTool::new(service.clone());
+        let remove = CronRemoveTool::new(service.clone());
+        list.set_context("telegram", "7").await;
+        remove.set_context("telegram", "7").await;
+        assert_eq!(list.execute(HashMap::new()).await.unwrap(), "No scheduled jobs.");
+        assert!(remove.execute(params(json!({"id": job.id}))).await.is_err());
+
+        list.set_context("telegram", "42").await;
+        remove.set_context("telegram", "42").await;
+        assert!(list.execute(HashMap::new()).await.unwrap().contains("Check my RSS feed"));
+        let out = remove.execute(params(json!({"id": job.id}))).await.unwrap();
+        assert_eq!(out, format!("Removed job {} 'rss'", job.id));
+        assert!(service.list_jobs().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cron_add_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = make_service(&dir);
+        let add = CronAddTool::new(service.clone(), timezone(&dir));
+        let now = Utc::now();
+
+        for bad in [
+            json!({"name": "x", "message": "m"}),
+            json!({"name": "x", "message": "m", "every_seconds": 3600, "cron": "0 0 8 * * *"}),
+            json!({"name": "x", "message": "m", "every_seconds": 5}),
+            json!({"name": "x", "message": "m", "cron": "every morning"}),
+            json!({"name": "x", "message": "m", "at": "2020-01-01 10:00"}),
+            json!({"name": "x", "message": "  ", "every_seconds": 3600}),
+        ] {
+            assert!(add.add(&params(bad.clone()), now).await.is_err(), "{bad}");
+        }
+        assert!(service.list_jobs().await.is_empty());
+
+        let out = add
+            .add(&params(json!({"name": "x", "message": "m", "every_seconds": 7200})), now)
+            .await
+            .unwrap();
+        assert!(out.contains("every 2h"), "{out}");
+    }
+
+    #[tokio::test]
+    async fn test_cron_add_dry_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = make_service(&dir);
+        let dry_run = DryRun::new();
+        dry_run.set(true);
+        let add = CronAddTool::new(service.clone(), timezone(&dir)).with_dry_run(dry_run);
+
+        let out = add
+            .execute(params(json!({"name": "once", "message": "m", "at": "in 2h"})))
+            .await
+            .unwrap();
+        assert!(out.starts_with(PREVIEW_PREFIX), "{out}");
+        assert!(service.list_jobs().await.is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Dry-run mode — preview mutating tool calls instead of running them.
+//!
+//! When enabled, `write_file`, `edit_file` and `exec` validate their
+//! arguments as usual but return a preview (a diff for file changes, the
+//! command line for exec) prefixed with [`PREVIEW_PREFIX`] instead of
+//! touching the filesystem or spawning a process.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Prefix of every dry-run tool result.
+pub const PREVIEW_PREFIX: &str = "[dry-run]";
+
+/// Lines of unchanged context around a change.
+const CONTEXT_LINES: usize = 3;
+
+/// Maximum lines in a preview before it is cut.
+const MAX_PREVIEW_LINES: usize = 80;
+
+/// Dry-run switch shared by the mutating tools.
+#[derive(Clone, Default)]
+pub struct DryRun(Arc<AtomicBool>);
+
+impl DryRun {
+    /// Create a disabled switch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable dry-run mode.
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether mutating tools should only preview.
+    pub fn enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Whether a tool result is a dry-run preview.
+pub fn is_preview(result: &str) -> bool {
+    result.starts_with(PREVIEW_PREFIX)
+}
+
+/// Unified diff of `old` → `new` as a single hunk around the changed lines.
+pub fn diff_preview(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == old_lines.len() && prefix == new_lines.len() {
+        return "(no changes)".into();
+    }
+
+    let start = prefix.saturating_sub(CONTEXT_LINES);
+    let old_end = (old_lines.len() - suffix + CONTEXT_LINES).min(old_lines.len());
+    let new_end = (new_lines.len() - suffix + CONTEXT_LINES).min(new_lines.len());
+
+    let mut body: Vec<String> = Vec::new();
+    body.extend(old_lines[start..prefix].iter().map(|l| format!(" {l}")));
+    body.extend(old_lines[prefix..old_lines.len() - suffix].iter().map(|l| format!("-{l}")));
+    body.extend(new_lines[prefix..new_lines.len() - suffix].iter().map(|l| format!("+{l}")));
+    body.extend(new_lines[new_lines.len() - suffix..new_end].iter().map(|l| format!(" {l}")));
+
+    let mut out = vec![
+        format!("--- {path}"),
+        format!("+++ {path}"),
+        format!(
+            "@@ -{},{} +{},{} @@",
+            start + 1,
+            old_end - start,
+            start + 1,
+            new_end - start
+        ),
+    ];
+    out.extend(truncate_lines(body));
+    out.join("\n")
+}
+
+/// First lines of `content`, for previews of new files.
+pub fn content_preview(content: &str) -> String {
+    truncate_lines(content.lines().map(str::to_string).collect()).join("\n")
+}
+
+fn truncate_lines(mut lines: Vec<String>) -> Vec<String> {
+    if lines.len() > MAX_PREVIEW_LINES {
+        let more = lines.len() - MAX_PREVIEW_LINES;
+        lines.truncate(MAX_PREVIEW_LINES);
+        lines.push(format!("… ({more} more lines)"));
+    }
+    lines
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_switch_is_shared() {
+        let a = DryRun::new();
+        let b = a.clone();
+        assert!(!b.enabled());
+        a.set(true);
+        assert!(b.enabled());
+    }
+
+    #[test]
+    fn test_diff_preview() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\ni\n";
+        let diff = diff_preview("notes.txt", old, new);
+        assert_eq!(
+            diff,
+            "--- notes.txt\n+++ notes.txt\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h"
+        );
+        assert_eq!(diff_preview("x", "a\n", "a\nb\n"), "--- x\n+++ x\n@@ -1,1 +1,2 @@\n a\n+b");
+        assert_eq!(diff_preview("x", "same\n", "same\n"), "(no changes)");
+        assert_eq!(diff_preview("x", "", "new\n"), "--- x\n+++ x\n@@ -1,0 +1,1 @@\n+new");
+    }
+
+    #[test]
+    fn test_previews_are_truncated() {
+        let content: String = (0..100).map(|i| format!("line {i}\n")).collect();
+        let preview = content_preview(&content);
+        assert!(preview.ends_with("line 79\n… (20 more lines)"));
+        assert!(is_preview(&format!("{PREVIEW_PREFIX} Would run `ls`")));
+    }
+}
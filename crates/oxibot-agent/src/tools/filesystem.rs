@@ -1,15 +1,53 @@
 //! Filesystem tools — read, write, edit, list directory.
 //!
 //! Port of nanobot's `agent/tools/filesystem.py`.
-//! Each tool optionally restricts paths to an `allowed_dir`.
+//! Each tool optionally restricts paths to an `allowed_dir`, and resolves
+//! relative paths against a shared [`WorkingDir`] (the session scratch dir).
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
+use tokio::sync::RwLock;
 
 use super::base::{require_string, Tool};
+use super::context::TurnContext;
+use super::dry_run::{content_preview, diff_preview, DryRun, PREVIEW_PREFIX};
+use super::skill_secrets::SkillSecrets;
+
+// ─────────────────────────────────────────────
+// Working directory
+// ─────────────────────────────────────────────
+
+/// Default directory for relative paths, shared by all filesystem tools.
+///
+/// Inside an agent turn it is the session's scratch directory from the
+/// [`TurnContext`]; otherwise the directory set here. When unset, relative
+/// paths resolve against the process working directory.
+#[derive(Clone, Default)]
+pub struct WorkingDir(Arc<RwLock<Option<PathBuf>>>);
+
+impl WorkingDir {
+    /// Create an unset working directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set (or clear) the working directory.
+    pub async fn set(&self, dir: Option<PathBuf>) {
+        *self.0.write().await = dir;
+    }
+
+    /// Current working directory, if set.
+    pub async fn get(&self) -> Option<PathBuf> {
+        match TurnContext::current() {
+            Some(turn) => turn.working_dir,
+            None => self.0.read().await.clone(),
+        }
+    }
+}
 
 // ─────────────────────────────────────────────
 // Shared path helper
@@ -17,8 +55,13 @@ use super::base::{require_string, Tool};
 
 /// Resolve a user-supplied path, optionally restricting it to `allowed_dir`.
 ///
+/// Relative paths are joined onto `base_dir` when one is given.
 /// Returns `Err` if the resolved path is outside the allowed directory.
-fn resolve_path(path: &str, allowed_dir: Option<&Path>) -> anyhow::Result<PathBuf> {
+pub(super) fn resolve_path(
+    path: &str,
+    base_dir: Option<&Path>,
+    allowed_dir: Option<&Path>,
+) -> anyhow::Result<PathBuf> {
     // Expand ~ to home directory
     let expanded = if path.starts_with("~/") || path == "~" {
         if let Some(home) = dirs_like_home() {
@@ -30,6 +73,12 @@ fn resolve_path(path: &str, allowed_dir: Option<&Path>) -> anyhow::Result<PathBu
         PathBuf::from(path)
     };
 
+    // Relative paths default to the working directory
+    let expanded = match base_dir {
+        Some(base) if expanded.is_relative() => base.join(expanded),
+        _ => expanded,
+    };
+
     // Canonicalize if the path exists, otherwise use the expanded form
     let resolved = if expanded.exists() {
         expanded.canonicalize().unwrap_or(expanded)
@@ -86,11 +135,30 @@ fn dirs_like_home() -> Option<PathBuf> {
 /// Reads and returns the entire content of a file.
 pub struct ReadFileTool {
     allowed_dir: Option<PathBuf>,
+    working_dir: WorkingDir,
+    skill_secrets: Option<SkillSecrets>,
 }
 
 impl ReadFileTool {
     pub fn new(allowed_dir: Option<PathBuf>) -> Self {
-        Self { allowed_dir }
+        Self {
+            allowed_dir,
+            working_dir: WorkingDir::new(),
+            skill_secrets: None,
+        }
+    }
+
+    /// Resolve relative paths against a shared working directory.
+    pub fn with_working_dir(mut self, working_dir: WorkingDir) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
+    /// Reading a skill's `SKILL.md` puts its secrets in use in the
+    /// conversation.
+    pub fn with_skill_secrets(mut self, skill_secrets: SkillSecrets) -> Self {
+        self.skill_secrets = Some(skill_secrets);
+        self
     }
 }
 
@@ -119,7 +187,11 @@ impl Tool for ReadFileTool {
 
     async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
         let path_str = require_string(&params, "path")?;
-        let path = resolve_path(&path_str, self.allowed_dir.as_deref())?;
+        let path = resolve_path(
+            &path_str,
+            self.working_dir.get().await.as_deref(),
+            self.allowed_dir.as_deref(),
+        )?;
 
         if !path.exists() {
             anyhow::bail!("File not found: {}", path.display());
@@ -130,6 +202,9 @@ impl Tool for ReadFileTool {
 
         let content = std::fs::read_to_string(&path)
             .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        if let Some(ref skill_secrets) = self.skill_secrets {
+            skill_secrets.note_read(&path);
+        }
         Ok(content)
     }
 }
@@ -141,11 +216,29 @@ impl Tool for ReadFileTool {
 /// Creates or overwrites a file with the given content.
 pub struct WriteFileTool {
     allowed_dir: Option<PathBuf>,
+    working_dir: WorkingDir,
+    dry_run: DryRun,
 }
 
 impl WriteFileTool {
     pub fn new(allowed_dir: Option<PathBuf>) -> Self {
-        Self { allowed_dir }
+        Self {
+            allowed_dir,
+            working_dir: WorkingDir::new(),
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Resolve relative paths against a shared working directory.
+    pub fn with_working_dir(mut self, working_dir: WorkingDir) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
+    /// Preview changes instead of writing while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
     }
 }
 
@@ -180,7 +273,26 @@ impl Tool for WriteFileTool {
     async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
         let path_str = require_string(&params, "path")?;
         let content = require_string(&params, "content")?;
-        let path = resolve_path(&path_str, self.allowed_dir.as_deref())?;
+        let path = resolve_path(
+            &path_str,
+            self.working_dir.get().await.as_deref(),
+            self.allowed_dir.as_deref(),
+        )?;
+
+        if self.dry_run.enabled() {
+            let shown = path.display().to_string();
+            let bytes = content.len();
+            return Ok(match std::fs::read_to_string(&path) {
+                Ok(old) => format!(
+                    "{PREVIEW_PREFIX} Would overwrite {shown} ({bytes} bytes); nothing was written.\n{}",
+                    diff_preview(&shown, &old, &content)
+                ),
+                Err(_) => format!(
+                    "{PREVIEW_PREFIX} Would create {shown} ({bytes} bytes); nothing was written.\n{}",
+                    content_preview(&content)
+                ),
+            });
+        }
 
         // Create parent directories if needed
         if let Some(parent) = path.parent() {
@@ -190,7 +302,7 @@ impl Tool for WriteFileTool {
             }
         }
 
-        let bytes = content.as_bytes().len();
+        let bytes = content.len();
         std::fs::write(&path, &content)
             .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", path.display()))?;
         Ok(format!("Successfully wrote {bytes} bytes to {}", path.display()))
@@ -204,11 +316,29 @@ impl Tool for WriteFileTool {
 /// Replaces a text snippet within a file (single occurrence).
 pub struct EditFileTool {
     allowed_dir: Option<PathBuf>,
+    working_dir: WorkingDir,
+    dry_run: DryRun,
 }
 
 impl EditFileTool {
     pub fn new(allowed_dir: Option<PathBuf>) -> Self {
-        Self { allowed_dir }
+        Self {
+            allowed_dir,
+            working_dir: WorkingDir::new(),
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Resolve relative paths against a shared working directory.
+    pub fn with_working_dir(mut self, working_dir: WorkingDir) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
+    /// Preview changes instead of writing while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
     }
 }
 
@@ -248,7 +378,11 @@ impl Tool for EditFileTool {
         let path_str = require_string(&params, "path")?;
         let old_text = require_string(&params, "old_text")?;
         let new_text = require_string(&params, "new_text")?;
-        let path = resolve_path(&path_str, self.allowed_dir.as_deref())?;
+        let path = resolve_path(
+            &path_str,
+            self.working_dir.get().await.as_deref(),
+            self.allowed_dir.as_deref(),
+        )?;
 
         if !path.is_file() {
             anyhow::bail!("File not found: {}", path.display());
@@ -271,6 +405,13 @@ impl Tool for EditFileTool {
 
         // Replace exactly one occurrence
         let updated = content.replacen(&old_text, &new_text, 1);
+        if self.dry_run.enabled() {
+            let shown = path.display().to_string();
+            return Ok(format!(
+                "{PREVIEW_PREFIX} {warning}Would edit {shown}; nothing was written.\n{}",
+                diff_preview(&shown, &content, &updated)
+            ));
+        }
         std::fs::write(&path, &updated)
             .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", path.display()))?;
 
@@ -288,11 +429,21 @@ impl Tool for EditFileTool {
 /// Lists the contents of a directory.
 pub struct ListDirTool {
     allowed_dir: Option<PathBuf>,
+    working_dir: WorkingDir,
 }
 
 impl ListDirTool {
     pub fn new(allowed_dir: Option<PathBuf>) -> Self {
-        Self { allowed_dir }
+        Self {
+            allowed_dir,
+            working_dir: WorkingDir::new(),
+        }
+    }
+
+    /// Resolve relative paths against a shared working directory.
+    pub fn with_working_dir(mut self, working_dir: WorkingDir) -> Self {
+        self.working_dir = working_dir;
+        self
     }
 }
 
@@ -321,7 +472,11 @@ impl Tool for ListDirTool {
 
     async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
         let path_str = require_string(&params, "path")?;
-        let path = resolve_path(&path_str, self.allowed_dir.as_deref())?;
+        let path = resolve_path(
+            &path_str,
+            self.working_dir.get().await.as_deref(),
+            self.allowed_dir.as_deref(),
+        )?;
 
         if !path.is_dir() {
             anyhow::bail!("Not a directory: {}", path.display());
@@ -450,6 +605,48 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&file).unwrap(), "deep content");
     }
 
+    #[tokio::test]
+    async fn test_write_file_relative_uses_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let scratch = dir.path().join("sessions").join("cli_direct");
+        std::fs::create_dir_all(&scratch).unwrap();
+
+        let wd = WorkingDir::new();
+        wd.set(Some(scratch.clone())).await;
+        let tool = WriteFileTool::new(Some(dir.path().to_path_buf())).with_working_dir(wd);
+        tool.execute(make_params(&[("path", "notes/tmp.txt"), ("content", "scratch")]))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(scratch.join("notes/tmp.txt")).unwrap(),
+            "scratch"
+        );
+
+        // Absolute paths still reach the shared workspace
+        let shared = dir.path().join("shared.txt");
+        tool.execute(make_params(&[("path", shared.to_str().unwrap()), ("content", "shared")]))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&shared).unwrap(), "shared");
+    }
+
+    #[tokio::test]
+    async fn test_relative_escape_from_working_dir_denied() {
+        let dir = tempfile::tempdir().unwrap();
+        let allowed = dir.path().join("workspace");
+        let scratch = allowed.join("sessions").join("a");
+        std::fs::create_dir_all(&scratch).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "nope").unwrap();
+
+        let wd = WorkingDir::new();
+        wd.set(Some(scratch)).await;
+        let tool = ReadFileTool::new(Some(allowed)).with_working_dir(wd);
+        let result = tool
+            .execute(make_params(&[("path", "../../../secret.txt")]))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("Access denied"));
+    }
+
     // ── EditFileTool ──
 
     #[tokio::test]
@@ -508,6 +705,46 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&file).unwrap(), "ccc bbb aaa");
     }
 
+    #[tokio::test]
+    async fn test_dry_run_previews_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("plan.txt");
+        std::fs::write(&file, "one\ntwo\n").unwrap();
+        let dry_run = DryRun::new();
+        dry_run.set(true);
+
+        let edit = EditFileTool::new(None).with_dry_run(dry_run.clone());
+        let result = edit
+            .execute(make_params(&[
+                ("path", file.to_str().unwrap()),
+                ("old_text", "two"),
+                ("new_text", "2"),
+            ]))
+            .await
+            .unwrap();
+        assert!(result.starts_with("[dry-run] Would edit"));
+        assert!(result.ends_with(" one\n-two\n+2"));
+
+        let write = WriteFileTool::new(None).with_dry_run(dry_run.clone());
+        let new_file = dir.path().join("sub/new.txt");
+        let result = write
+            .execute(make_params(&[("path", new_file.to_str().unwrap()), ("content", "hi")]))
+            .await
+            .unwrap();
+        assert!(result.starts_with("[dry-run] Would create"));
+        assert!(result.ends_with("\nhi"));
+        assert!(!dir.path().join("sub").exists());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\ntwo\n");
+
+        // Turning the shared switch off makes the same tools write again
+        dry_run.set(false);
+        write
+            .execute(make_params(&[("path", file.to_str().unwrap()), ("content", "x")]))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "x");
+    }
+
     // ── ListDirTool ──
 
     #[tokio::test]
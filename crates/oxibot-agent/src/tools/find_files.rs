@@ -0,0 +1,140 @@
+//! `find_files` tool — look files up in the workspace [`FileIndex`].
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use super::base::{optional_i64, optional_string, Tool};
+use crate::file_index::{FileIndex, FileQuery};
+
+/// Results returned when the LLM doesn't ask for a limit.
+const DEFAULT_LIMIT: usize = 50;
+
+/// Hard cap on results per call.
+const MAX_LIMIT: usize = 500;
+
+/// Tool to find workspace files by name, path or content.
+pub struct FindFilesTool {
+    index: FileIndex,
+}
+
+impl FindFilesTool {
+    /// Create a new `find_files` tool over `index`.
+    pub fn new(index: FileIndex) -> Self {
+        Self { index }
+    }
+}
+
+#[async_trait]
+impl Tool for FindFilesTool {
+    fn name(&self) -> &str {
+        "find_files"
+    }
+
+    fn description(&self) -> &str {
+        "Find files in the workspace by name, path or content, using a prebuilt \
+         index. Much faster than `exec find`/`grep` on large workspaces; prefer it. \
+         Returns paths relative to the workspace with size and modification time. \
+         The index is refreshed every few seconds, so files written just now may \
+         not show up yet."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Glob on the file name (`*.md`), or on the path when it contains `/` (`notes/**/*.txt`). Without `*`/`?`, any path containing the text matches. Case-insensitive."
+                },
+                "contains": {
+                    "type": "string",
+                    "description": "Only files containing this text (case-insensitive)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only files under this workspace folder"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum results (default 50)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let query = FileQuery {
+            pattern: optional_string(&params, "pattern"),
+            contains: optional_string(&params, "contains"),
+            under: optional_string(&params, "path"),
+            limit: optional_i64(&params, "limit")
+                .map_or(DEFAULT_LIMIT, |l| l.clamp(1, MAX_LIMIT as i64) as usize),
+        };
+        if query.pattern.is_none() && query.contains.is_none() && query.under.is_none() {
+            anyhow::bail!("give at least one of `pattern`, `contains` or `path`");
+        }
+
+        self.index.ensure_started().await;
+        let index = self.index.clone();
+        let matches = tokio::task::spawn_blocking(move || index.find(&query)).await?;
+
+        if matches.files.is_empty() {
+            return Ok("No matching files.".into());
+        }
+        let mut out: Vec<String> = matches
+            .files
+            .iter()
+            .map(|(path, size, modified)| {
+                let modified: DateTime<Utc> = (*modified).into();
+                format!("{path}  {}  {}", format_size(*size), modified.format("%Y-%m-%d %H:%M"))
+            })
+            .collect();
+        if matches.more > 0 {
+            if params.contains_key("contains") {
+                out.push("… more matches not shown, narrow the search".into());
+            } else {
+                out.push(format!("… {} more, narrow the pattern", matches.more));
+            }
+        }
+        Ok(out.join("\n"))
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{b} B"),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::config::schema::FileIndexConfig;
+
+    #[tokio::test]
+    async fn test_find_files_tool() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("notes")).unwrap();
+        std::fs::write(dir.path().join("notes/todo.md"), "x".repeat(2048)).unwrap();
+        std::fs::write(dir.path().join("notes/done.txt"), "y").unwrap();
+        let tool = FindFilesTool::new(FileIndex::new(dir.path(), FileIndexConfig::default()));
+
+        let params = HashMap::from([("pattern".to_string(), json!("*.md"))]);
+        let out = tool.execute(params).await.unwrap();
+        assert!(out.starts_with("notes/todo.md  2.0 KB  "), "{out}");
+        assert_eq!(out.lines().count(), 1);
+
+        let params = HashMap::from([("pattern".to_string(), json!("*.pdf"))]);
+        assert_eq!(tool.execute(params).await.unwrap(), "No matching files.");
+        assert!(tool.execute(HashMap::new()).await.is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! Form tool — start, fill in and cancel multi-turn forms.
+//!
+//! Thin wrapper over [`Form`]: values are validated as they are set, and
+//! the form is delivered as soon as the last required field is filled.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use oxibot_core::session::manager::SessionManager;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::base::{optional_string, require_string, Tool};
+use super::context::current_session;
+use crate::forms::{Form, FormField, FormSpec};
+
+/// How long a webhook may take to accept a completed form.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Tool that lets the agent collect a form over several turns.
+pub struct FormTool {
+    sessions: Arc<SessionManager>,
+    /// Completed forms are appended to `forms/<name>.jsonl` under it.
+    workspace: PathBuf,
+    client: reqwest::Client,
+    /// Configured webhook URLs, by name.
+    webhooks: HashMap<String, String>,
+    /// Session key outside agent turns.
+    session_key: Mutex<String>,
+}
+
+impl FormTool {
+    /// Create a new form tool.
+    pub fn new(sessions: Arc<SessionManager>, workspace: PathBuf) -> Self {
+        Self {
+            sessions,
+            workspace,
+            client: reqwest::Client::new(),
+            webhooks: HashMap::new(),
+            session_key: Mutex::new("cli:direct".into()),
+        }
+    }
+
+    /// Webhooks forms may name (`tools.forms.webhooks`).
+    pub fn with_webhooks(mut self, webhooks: HashMap<String, String>) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
+    /// Set the session used outside agent turns.
+    pub async fn set_context(&self, session_key: &str) {
+        *self.session_key.lock().await = session_key.to_string();
+    }
+
+    fn start(&self, params: &HashMap<String, Value>, key: &str) -> anyhow::Result<String> {
+        let fields: Vec<FormField> = serde_json::from_value(
+            params
+                .get("fields")
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Missing required parameter: fields"))?,
+        )
+        .map_err(|e| anyhow::anyhow!("invalid fields: {e}"))?;
+        let webhook = optional_string(params, "webhook").unwrap_or_default();
+        if !webhook.is_empty() && !self.webhooks.contains_key(&webhook) {
+            anyhow::bail!("unknown webhook '{webhook}'; configured: {}", self.webhook_names());
+        }
+        let form = Form::new(FormSpec {
+            name: require_string(params, "name")?,
+            fields,
+            webhook,
+            tool: optional_string(params, "tool").unwrap_or_default(),
+        })?;
+        let replaced = Form::load(&self.sessions, key).is_some();
+        form.save(&self.sessions, key);
+        Ok(format!(
+            "Started form{}:\n{}\nAsk the user for the fields now.",
+            if replaced { " (replacing the previous one)" } else { "" },
+            form.render()
+        ))
+    }
+
+    fn webhook_names(&self) -> String {
+        let mut names: Vec<&str> = self.webhooks.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        if names.is_empty() {
+            "none".into()
+        } else {
+            names.join(", ")
+        }
+    }
+
+    async fn set(&self, params: &HashMap<String, Value>, key: &str) -> anyhow::Result<String> {
+        let Some(mut form) = Form::load(&self.sessions, key) else {
+            anyhow::bail!("no form is being filled in this conversation; start one first");
+        };
+        let mut values: Vec<(String, Value)> = match params.get("values").and_then(Value::as_object) {
+            Some(values) => values.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            None => Vec::new(),
+        };
+        if let Some(field) = optional_string(params, "field") {
+            values.push((field, params.get("value").cloned().unwrap_or(Value::Null)));
+        }
+        if values.is_empty() {
+            anyhow::bail!("pass 'field' and 'value', or 'values'");
+        }
+
+        let today = chrono::Local::now().date_naive();
+        let mut lines = Vec::new();
+        for (field, value) in &values {
+            match form.set(field, value, today) {
+                Ok(stored) => lines.push(format!("✅ {field} = {stored}")),
+                Err(e) => lines.push(format!("❌ {e:#}")),
+            }
+        }
+
+        if form.is_complete() {
+            lines.push(self.finish(&form, key).await);
+            return Ok(lines.join("\n"));
+        }
+        form.save(&self.sessions, key);
+        let missing: Vec<&str> = form.missing().iter().map(|f| f.name.as_str()).collect();
+        lines.push(format!("Still missing: {}", missing.join(", ")));
+        Ok(lines.join("\n"))
+    }
+
+    /// Deliver a completed form and remove it from the session.
+    async fn finish(&self, form: &Form, key: &str) -> String {
+        let result = form.result(key);
+        Form::clear(&self.sessions, key);
+        info!(form = %form.spec.name, session = %key, "form completed");
+
+        let mut out = format!("Form '{}' is complete.", form.spec.name);
+        let dir = self.workspace.join("forms");
+        let path = dir.join(format!("{}.jsonl", form.spec.name));
+        let saved = std::fs::create_dir_all(&dir).and_then(|_| {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+            writeln!(file, "{result}")
+        });
+        match saved {
+            Ok(()) => out.push_str(&format!(" Saved to forms/{}.jsonl.", form.spec.name)),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "failed to save completed form");
+                out.push_str(&format!(" Could not save it: {e}."));
+            }
+        }
+
+        // Forms started through the API name a webhook too; unknown names
+        // are reported rather than treated as URLs
+        let webhook = &form.spec.webhook;
+        if !webhook.is_empty() && !self.webhooks.contains_key(webhook) {
+            warn!(form = %form.spec.name, webhook = %webhook, "form names an unknown webhook");
+            out.push_str(&format!(" Not sent: webhook '{webhook}' is not configured."));
+        }
+        if let Some(url) = self.webhooks.get(webhook) {
+            let sent = self
+                .client
+                .post(url)
+                .timeout(WEBHOOK_TIMEOUT)
+                .json(&result)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status());
+            match sent {
+                Ok(response) => out.push_str(&format!(" Sent to the webhook ({}).", response.status())),
+                Err(e) => {
+                    warn!(form = %form.spec.name, error = %e, "form webhook failed");
+                    out.push_str(&format!(" Sending it to the webhook failed: {e}."));
+                }
+            }
+        }
+
+        if !form.spec.tool.is_empty() {
+            out.push_str(&format!(" Now call `{}` with these values.", form.spec.tool));
+        }
+        format!("{out}\nValues: {}", result["values"])
+    }
+}
+
+#[async_trait]
+impl Tool for FormTool {
+    fn name(&self) -> &str {
+        "form"
+    }
+
+    fn description(&self) -> &str {
+        "Collect structured information from the user over several messages. \
+         'start' defines the fields to ask for; 'set' records answers as you get \
+         them (they are validated, and the form is delivered once every required \
+         field is filled); 'status' shows progress; 'cancel' drops the form."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["start", "set", "status", "cancel"],
+                    "description": "What to do"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Form name, letters, digits, '-' or '_' (for 'start')"
+                },
+                "fields": {
+                    "type": "array",
+                    "description": "Fields to collect (for 'start')",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "label": { "type": "string", "description": "What to ask for" },
+                            "type": {
+                                "type": "string",
+                                "enum": ["text", "number", "integer", "boolean", "email", "phone", "date", "choice"]
+                            },
+                            "options": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Allowed answers of a choice field"
+                            },
+                            "required": { "type": "boolean", "description": "Default true" }
+                        },
+                        "required": ["name"]
+                    }
+                },
+                "webhook": {
+                    "type": "string",
+                    "description": "Name of a configured webhook to POST the completed form to (for 'start')"
+                },
+                "tool": {
+                    "type": "string",
+                    "description": "Tool to pass the completed values to (for 'start')"
+                },
+                "field": {
+                    "type": "string",
+                    "description": "Field to set (for 'set')"
+                },
+                "value": {
+                    "description": "The user's answer for 'field'"
+                },
+                "values": {
+                    "type": "object",
+                    "description": "Several answers at once, by field name (for 'set')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let key = current_session(&self.session_key).await;
+        match require_string(&params, "action")?.as_str() {
+            "start" => self.start(&params, &key),
+            "set" => self.set(&params, &key).await,
+            "status" => Ok(match Form::load(&self.sessions, &key) {
+                Some(form) => form.render(),
+                None => "No form is being filled.".into(),
+            }),
+            "cancel" => match Form::load(&self.sessions, &key) {
+                Some(form) => {
+                    Form::clear(&self.sessions, &key);
+                    Ok(format!("Cancelled form '{}'.", form.spec.name))
+                }
+                None => Ok("No form is being filled.".into()),
+            },
+            other => anyhow::bail!("Unknown action: {other}"),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_form_tool_lifecycle() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = Arc::new(SessionManager::new(Some(dir.path().join("sessions"))).unwrap());
+        let webhooks = HashMap::from([("crm".to_string(), format!("{}/hook", server.uri()))]);
+        let tool = FormTool::new(sessions.clone(), dir.path().to_path_buf()).with_webhooks(webhooks);
+
+        let out = tool.execute(params(json!({"action": "set", "field": "name", "value": "Ana"}))).await;
+        assert!(out.is_err());
+
+        // Only configured webhooks, by name
+        let start = |webhook: String| {
+            params(json!({
+                "action": "start",
+                "name": "intake",
+                "fields": [{"name": "name"}, {"name": "email", "type": "email"}],
+                "webhook": webhook,
+                "tool": "github_create_issue"
+            }))
+        };
+        let err = tool.execute(start(format!("{}/hook", server.uri()))).await.unwrap_err();
+        assert_eq!(err.to_string(), "unknown webhook '{}/hook'; configured: crm".replace("{}", &server.uri()));
+
+        let out = tool.execute(start("crm".into())).await.unwrap();
+        assert!(out.starts_with("Started form:\n📝 intake"), "{out}");
+
+        let out = tool
+            .execute(params(json!({"action": "set", "values": {"name": "Ana", "email": "nope"}})))
+            .await
+            .unwrap();
+        assert!(out.contains("✅ name = \"Ana\"") && out.contains("❌ email must be an email address"), "{out}");
+        assert!(out.ends_with("Still missing: email"));
+        assert!(Form::load(&sessions, "cli:direct").is_some());
+
+        let out = tool
+            .execute(params(json!({"action": "set", "field": "email", "value": "ana@example.com"})))
+            .await
+            .unwrap();
+        assert!(out.contains("Form 'intake' is complete. Saved to forms/intake.jsonl. Sent to the webhook (200 OK)."), "{out}");
+        assert!(out.contains("Now call `github_create_issue`"));
+        assert!(Form::load(&sessions, "cli:direct").is_none());
+
+        let saved = std::fs::read_to_string(dir.path().join("forms/intake.jsonl")).unwrap();
+        let record: Value = serde_json::from_str(saved.trim()).unwrap();
+        assert_eq!(record["values"], json!({"name": "Ana", "email": "ana@example.com"}));
+        assert_eq!(tool.execute(params(json!({"action": "status"}))).await.unwrap(), "No form is being filled.");
+    }
+}
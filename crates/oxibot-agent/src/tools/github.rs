@@ -0,0 +1,611 @@
+//! GitHub tools — notifications, issues and pull requests over the REST API.
+//!
+//! Work without the `gh` binary (e.g. in containers): every call goes to
+//! the API root from `tools.github` with the configured token. Large pull
+//! request diffs are returned in parts, split between files where possible.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::base::{optional_bool, optional_i64, optional_string, require_string, Tool};
+use super::dry_run::{content_preview, DryRun, PREVIEW_PREFIX};
+
+/// Characters of diff returned per part.
+const DIFF_PART_CHARS: usize = 20_000;
+
+/// Default and maximum number of notifications listed.
+const DEFAULT_NOTIFICATIONS: i64 = 20;
+const MAX_NOTIFICATIONS: i64 = 50;
+
+// ─────────────────────────────────────────────
+// GitHubClient
+// ─────────────────────────────────────────────
+
+/// Authenticated access to the GitHub REST API, shared by the tools.
+pub struct GitHubClient {
+    token: String,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl GitHubClient {
+    /// Create a client for `base_url` (e.g. "https://api.github.com").
+    pub fn new(token: &str, base_url: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{path}", self.base_url))
+            .bearer_auth(&self.token)
+            .header("User-Agent", "oxibot")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+    }
+
+    /// Send `request`, turning error statuses into errors with GitHub's message.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.send().await.context("GitHub request failed")?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body: Value = response.json().await.unwrap_or_default();
+        match body["message"].as_str() {
+            Some(message) => anyhow::bail!("GitHub returned {status}: {message}"),
+            None => anyhow::bail!("GitHub returned {status}"),
+        }
+    }
+
+    async fn get_json(&self, path: &str, query: &[(&str, String)]) -> Result<Value> {
+        let request = self
+            .request(reqwest::Method::GET, path)
+            .header("Accept", "application/vnd.github+json")
+            .query(query);
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    async fn post_json(&self, path: &str, body: &Value) -> Result<Value> {
+        let request = self
+            .request(reqwest::Method::POST, path)
+            .header("Accept", "application/vnd.github+json")
+            .json(body);
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    /// The unified diff of a pull request.
+    async fn pull_diff(&self, repo: &str, number: i64) -> Result<String> {
+        let request = self
+            .request(reqwest::Method::GET, &format!("/repos/{repo}/pulls/{number}"))
+            .header("Accept", "application/vnd.github.diff");
+        Ok(self.send(request).await?.text().await?)
+    }
+}
+
+/// The `repo` parameter, checked to be `owner/name`.
+fn require_repo(params: &HashMap<String, Value>) -> Result<String> {
+    let repo = require_string(params, "repo")?;
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repo.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(repo),
+        _ => anyhow::bail!("invalid repo '{repo}': expected 'owner/name'"),
+    }
+}
+
+/// The issue or pull request `number` parameter.
+fn require_number(params: &HashMap<String, Value>) -> Result<i64> {
+    optional_i64(params, "number")
+        .filter(|n| *n > 0)
+        .ok_or_else(|| anyhow::anyhow!("missing or invalid 'number' (issue or pull request number)"))
+}
+
+/// Split a unified diff into parts of at most `max_chars`, between files
+/// where possible and between lines otherwise.
+fn split_diff(diff: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    let mut push = |piece: &str, current: &mut String, current_chars: &mut usize| {
+        let chars = piece.chars().count();
+        if *current_chars > 0 && *current_chars + chars > max_chars {
+            parts.push(std::mem::take(current));
+            *current_chars = 0;
+        }
+        current.push_str(piece);
+        *current_chars += chars;
+    };
+
+    let mut files: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (i, _) in diff.match_indices("\ndiff --git ") {
+        files.push(&diff[start..=i]);
+        start = i + 1;
+    }
+    files.push(&diff[start..]);
+
+    for file in files.into_iter().filter(|f| !f.is_empty()) {
+        if file.chars().count() <= max_chars {
+            push(file, &mut current, &mut current_chars);
+        } else {
+            for line in file.split_inclusive('\n') {
+                push(line, &mut current, &mut current_chars);
+            }
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+// ─────────────────────────────────────────────
+// GitHubNotificationsTool
+// ─────────────────────────────────────────────
+
+/// Tool to list the user's GitHub notifications.
+pub struct GitHubNotificationsTool {
+    github: Arc<GitHubClient>,
+}
+
+impl GitHubNotificationsTool {
+    pub fn new(github: Arc<GitHubClient>) -> Self {
+        Self { github }
+    }
+}
+
+#[async_trait]
+impl Tool for GitHubNotificationsTool {
+    fn name(&self) -> &str {
+        "github_notifications"
+    }
+
+    fn description(&self) -> &str {
+        "List the user's GitHub notifications (review requests, mentions, issue and \
+         PR activity), newest first. Unread only unless 'all' is set."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "all": {
+                    "type": "boolean",
+                    "description": "Include notifications already read (default false)"
+                },
+                "participating": {
+                    "type": "boolean",
+                    "description": "Only threads the user takes part in or is mentioned in (default false)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "How many to list (default 20, max 50)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let limit = optional_i64(&params, "limit")
+            .unwrap_or(DEFAULT_NOTIFICATIONS)
+            .clamp(1, MAX_NOTIFICATIONS);
+        let query = [
+            ("all", optional_bool(&params, "all").to_string()),
+            ("participating", optional_bool(&params, "participating").to_string()),
+            ("per_page", limit.to_string()),
+        ];
+        let body = self.github.get_json("/notifications", &query).await?;
+        let items = body.as_array().map(Vec::as_slice).unwrap_or_default();
+        if items.is_empty() {
+            return Ok("No notifications.".into());
+        }
+
+        let lines: Vec<String> = items
+            .iter()
+            .map(|n| {
+                let subject = &n["subject"];
+                // API URLs of issues and pulls end in their number
+                let number = subject["url"]
+                    .as_str()
+                    .and_then(|u| u.rsplit('/').next())
+                    .filter(|n| n.chars().all(|c| c.is_ascii_digit()))
+                    .map(|n| format!("#{n} "))
+                    .unwrap_or_default();
+                format!(
+                    "- {}{} [{}] {}{} ({}, {})",
+                    if n["unread"].as_bool().unwrap_or(false) { "● " } else { "" },
+                    n["repository"]["full_name"].as_str().unwrap_or("?"),
+                    subject["type"].as_str().unwrap_or("?"),
+                    number,
+                    subject["title"].as_str().unwrap_or("(no title)"),
+                    n["reason"].as_str().unwrap_or("?").replace('_', " "),
+                    n["updated_at"].as_str().unwrap_or("?"),
+                )
+            })
+            .collect();
+        Ok(format!("{} notification(s):\n{}", lines.len(), lines.join("\n")))
+    }
+}
+
+// ─────────────────────────────────────────────
+// GitHubPrDiffTool
+// ─────────────────────────────────────────────
+
+/// Tool to read a pull request's diff, part by part.
+pub struct GitHubPrDiffTool {
+    github: Arc<GitHubClient>,
+}
+
+impl GitHubPrDiffTool {
+    pub fn new(github: Arc<GitHubClient>) -> Self {
+        Self { github }
+    }
+}
+
+#[async_trait]
+impl Tool for GitHubPrDiffTool {
+    fn name(&self) -> &str {
+        "github_pr_diff"
+    }
+
+    fn description(&self) -> &str {
+        "Fetch the diff of a GitHub pull request for review. Large diffs come in \
+         parts; the result says how many there are — ask for the next with 'part'."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "repo": {
+                    "type": "string",
+                    "description": "Repository as 'owner/name'"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Pull request number"
+                },
+                "part": {
+                    "type": "integer",
+                    "description": "Which part of the diff to return (default 1)"
+                }
+            },
+            "required": ["repo", "number"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let repo = require_repo(&params)?;
+        let number = require_number(&params)?;
+        let diff = self.github.pull_diff(&repo, number).await?;
+        let parts = split_diff(&diff, DIFF_PART_CHARS);
+        if parts.is_empty() {
+            return Ok(format!("{repo}#{number} has no changes."));
+        }
+
+        let count = parts.len();
+        let part = optional_i64(&params, "part").unwrap_or(1);
+        if part < 1 || part as usize > count {
+            anyhow::bail!("part {part} out of range: the diff of {repo}#{number} has {count} part(s)");
+        }
+        let header = if count == 1 {
+            format!("Diff of {repo}#{number}:")
+        } else if (part as usize) < count {
+            format!("Diff of {repo}#{number}, part {part} of {count} (next: part={}):", part + 1)
+        } else {
+            format!("Diff of {repo}#{number}, part {part} of {count} (last):")
+        };
+        Ok(format!("{header}\n{}", parts[part as usize - 1]))
+    }
+}
+
+// ─────────────────────────────────────────────
+// GitHubCommentTool
+// ─────────────────────────────────────────────
+
+/// Tool to comment on an issue or pull request.
+pub struct GitHubCommentTool {
+    github: Arc<GitHubClient>,
+    dry_run: DryRun,
+}
+
+impl GitHubCommentTool {
+    pub fn new(github: Arc<GitHubClient>) -> Self {
+        Self {
+            github,
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Describe the comment instead of posting it while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for GitHubCommentTool {
+    fn name(&self) -> &str {
+        "github_comment"
+    }
+
+    fn description(&self) -> &str {
+        "Post a comment on a GitHub issue or pull request (Markdown)."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "repo": {
+                    "type": "string",
+                    "description": "Repository as 'owner/name'"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Comment text (Markdown)"
+                }
+            },
+            "required": ["repo", "number", "body"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let repo = require_repo(&params)?;
+        let number = require_number(&params)?;
+        let body = require_string(&params, "body")?;
+        if body.trim().is_empty() {
+            anyhow::bail!("comment body is empty");
+        }
+        if self.dry_run.enabled() {
+            return Ok(format!(
+                "{PREVIEW_PREFIX} would comment on {repo}#{number}:\n{}",
+                content_preview(&body)
+            ));
+        }
+        let created = self
+            .github
+            .post_json(&format!("/repos/{repo}/issues/{number}/comments"), &json!({ "body": body }))
+            .await?;
+        Ok(format!(
+            "Commented on {repo}#{number}: {}",
+            created["html_url"].as_str().unwrap_or_default()
+        ))
+    }
+}
+
+// ─────────────────────────────────────────────
+// GitHubCreateIssueTool
+// ─────────────────────────────────────────────
+
+/// Tool to open a new issue.
+pub struct GitHubCreateIssueTool {
+    github: Arc<GitHubClient>,
+    dry_run: DryRun,
+}
+
+impl GitHubCreateIssueTool {
+    pub fn new(github: Arc<GitHubClient>) -> Self {
+        Self {
+            github,
+            dry_run: DryRun::new(),
+        }
+    }
+
+    /// Describe the issue instead of opening it while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for GitHubCreateIssueTool {
+    fn name(&self) -> &str {
+        "github_create_issue"
+    }
+
+    fn description(&self) -> &str {
+        "Open a new issue in a GitHub repository."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "repo": {
+                    "type": "string",
+                    "description": "Repository as 'owner/name'"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Issue title"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Issue description (Markdown)"
+                },
+                "labels": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels to add (must exist in the repository)"
+                }
+            },
+            "required": ["repo", "title"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let repo = require_repo(&params)?;
+        let title = require_string(&params, "title")?;
+        if title.trim().is_empty() {
+            anyhow::bail!("issue title is empty");
+        }
+        let mut issue = json!({ "title": title });
+        if let Some(body) = optional_string(&params, "body").filter(|b| !b.is_empty()) {
+            issue["body"] = json!(body);
+        }
+        let labels: Vec<&str> = params
+            .get("labels")
+            .and_then(Value::as_array)
+            .map(|l| l.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if !labels.is_empty() {
+            issue["labels"] = json!(labels);
+        }
+
+        if self.dry_run.enabled() {
+            return Ok(format!("{PREVIEW_PREFIX} would open an issue in {repo}: {title}"));
+        }
+        let created = self.github.post_json(&format!("/repos/{repo}/issues"), &issue).await?;
+        Ok(format!(
+            "Opened {repo}#{}: {}",
+            created["number"].as_i64().unwrap_or_default(),
+            created["html_url"].as_str().unwrap_or_default()
+        ))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_split_diff_and_repo() {
+        let file = |name: &str, lines: usize| {
+            let body: String = (0..lines).map(|i| format!("+line {i}\n")).collect();
+            format!("diff --git a/{name} b/{name}\n{body}")
+        };
+        let diff = format!("{}{}{}", file("a.rs", 3), file("b.rs", 3), file("big.rs", 30));
+        let parts = split_diff(&diff, 120);
+        assert_eq!(parts.concat(), diff);
+        // Small files stay whole, the big one is split between lines
+        assert!(parts[0].starts_with("diff --git a/a.rs") && !parts[0].contains("big.rs"));
+        assert!(parts.iter().all(|p| p.chars().count() <= 120));
+        assert!(parts.iter().all(|p| p.ends_with('\n')));
+        assert!(split_diff("", 100).is_empty());
+
+        assert!(require_repo(&params(json!({"repo": "rust-lang/rust.vim"}))).is_ok());
+        for bad in ["rust", "a/b/c", "../x", "a/..", "a b/c"] {
+            assert!(require_repo(&params(json!({"repo": bad}))).is_err(), "{bad}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifications_and_diff() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/notifications"))
+            .and(header("Authorization", "Bearer ghp_test"))
+            .and(query_param("all", "false"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "unread": true,
+                "reason": "review_requested",
+                "updated_at": "2026-10-16T09:00:00Z",
+                "repository": { "full_name": "acme/api" },
+                "subject": {
+                    "title": "Add retries",
+                    "type": "PullRequest",
+                    "url": "https://api.github.com/repos/acme/api/pulls/42"
+                }
+            }])))
+            .mount(&server)
+            .await;
+        let diff = "diff --git a/x b/x\n+1\ndiff --git a/y b/y\n+2\n";
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/api/pulls/42"))
+            .and(header("Accept", "application/vnd.github.diff"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(diff))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/api/pulls/7"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({"message": "Not Found"})))
+            .mount(&server)
+            .await;
+        let github = Arc::new(GitHubClient::new("ghp_test", &server.uri()));
+
+        let out = GitHubNotificationsTool::new(github.clone()).execute(HashMap::new()).await.unwrap();
+        assert_eq!(
+            out,
+            "1 notification(s):\n- ● acme/api [PullRequest] #42 Add retries (review requested, 2026-10-16T09:00:00Z)"
+        );
+
+        let tool = GitHubPrDiffTool::new(github);
+        let out = tool.execute(params(json!({"repo": "acme/api", "number": 42}))).await.unwrap();
+        assert_eq!(out, format!("Diff of acme/api#42:\n{diff}"));
+        let err = tool.execute(params(json!({"repo": "acme/api", "number": 42, "part": 2}))).await.unwrap_err();
+        assert!(err.to_string().contains("has 1 part(s)"));
+        let err = tool.execute(params(json!({"repo": "acme/api", "number": 7}))).await.unwrap_err();
+        assert_eq!(err.to_string(), "GitHub returned 404 Not Found: Not Found");
+    }
+
+    #[tokio::test]
+    async fn test_comment_and_create_issue() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/api/issues/42/comments"))
+            .and(body_json(json!({"body": "LGTM"})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "html_url": "https://github.com/acme/api/pull/42#issuecomment-1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/api/issues"))
+            .and(body_json(json!({"title": "Crash on start", "labels": ["bug"]})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "number": 43,
+                "html_url": "https://github.com/acme/api/issues/43"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let github = Arc::new(GitHubClient::new("ghp_test", &server.uri()));
+        let dry_run = DryRun::new();
+
+        let comment = GitHubCommentTool::new(github.clone()).with_dry_run(dry_run.clone());
+        let args = params(json!({"repo": "acme/api", "number": 42, "body": "LGTM"}));
+        dry_run.set(true);
+        assert!(comment.execute(args.clone()).await.unwrap().starts_with(PREVIEW_PREFIX));
+        dry_run.set(false);
+        assert_eq!(
+            comment.execute(args).await.unwrap(),
+            "Commented on acme/api#42: https://github.com/acme/api/pull/42#issuecomment-1"
+        );
+
+        let create = GitHubCreateIssueTool::new(github).with_dry_run(dry_run);
+        let out = create
+            .execute(params(json!({"repo": "acme/api", "title": "Crash on start", "labels": ["bug"]})))
+            .await
+            .unwrap();
+        assert_eq!(out, "Opened acme/api#43: https://github.com/acme/api/issues/43");
+    }
+}
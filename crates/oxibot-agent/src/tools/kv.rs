@@ -0,0 +1,272 @@
+//! Key-value tools — `kv_get`, `kv_set`, `kv_delete` and `kv_list`.
+//!
+//! Thin wrappers over [`KvStore`]. Keys are scoped to the current session
+//! unless `scope` is `"global"`.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::base::{optional_string, require_string, Tool};
+use super::context::TurnContext;
+use crate::kv::{self, KvStore};
+
+/// The `scope` parameter shared by the tools.
+fn scope_parameter() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["session", "global"],
+        "description": "\"session\" (default): only this conversation sees the key; \"global\": every conversation does"
+    })
+}
+
+/// Namespace selected by the `scope` parameter.
+fn namespace(params: &HashMap<String, Value>) -> anyhow::Result<String> {
+    match optional_string(params, "scope").as_deref().unwrap_or("session") {
+        "global" => Ok(kv::GLOBAL.to_string()),
+        "session" => match TurnContext::current() {
+            Some(turn) if !turn.session_key.is_empty() => Ok(kv::session_namespace(&turn.session_key)),
+            _ => anyhow::bail!("no current session; use scope \"global\""),
+        },
+        other => anyhow::bail!("invalid scope '{other}' (expected \"session\" or \"global\")"),
+    }
+}
+
+// ─────────────────────────────────────────────
+// KvGetTool
+// ─────────────────────────────────────────────
+
+/// Tool to read a stored value.
+pub struct KvGetTool {
+    store: KvStore,
+}
+
+impl KvGetTool {
+    /// Create a new `kv_get` tool.
+    pub fn new(store: KvStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for KvGetTool {
+    fn name(&self) -> &str {
+        "kv_get"
+    }
+
+    fn description(&self) -> &str {
+        "Read a value saved with kv_set, such as a cursor or the last id a polling job saw."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {"type": "string", "description": "Key to read"},
+                "scope": scope_parameter()
+            },
+            "required": ["key"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let key = require_string(&params, "key")?;
+        let namespace = namespace(&params)?;
+        Ok(match self.store.get(&namespace, key.trim())? {
+            Some(entry) => entry.value,
+            None => format!("No value for '{}'.", key.trim()),
+        })
+    }
+}
+
+// ─────────────────────────────────────────────
+// KvSetTool
+// ─────────────────────────────────────────────
+
+/// Tool to save a value.
+pub struct KvSetTool {
+    store: KvStore,
+}
+
+impl KvSetTool {
+    /// Create a new `kv_set` tool.
+    pub fn new(store: KvStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for KvSetTool {
+    fn name(&self) -> &str {
+        "kv_set"
+    }
+
+    fn description(&self) -> &str {
+        "Save a small value under a key so it survives restarts (up to 16 KB). Use it \
+         for state such as cursors, last-seen ids or settings, instead of writing files. \
+         Prefix keys with the skill name, e.g. \"rss.last_id\"."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {"type": "string", "description": "Key to write"},
+                "value": {"type": "string", "description": "Value to store (use JSON text for structured data)"},
+                "scope": scope_parameter()
+            },
+            "required": ["key", "value"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let key = require_string(&params, "key")?;
+        let value = require_string(&params, "value")?;
+        let namespace = namespace(&params)?;
+        self.store.set(&namespace, &key, &value)?;
+        Ok(format!("Saved '{}'.", key.trim()))
+    }
+}
+
+// ─────────────────────────────────────────────
+// KvDeleteTool
+// ─────────────────────────────────────────────
+
+/// Tool to remove a value.
+pub struct KvDeleteTool {
+    store: KvStore,
+}
+
+impl KvDeleteTool {
+    /// Create a new `kv_delete` tool.
+    pub fn new(store: KvStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for KvDeleteTool {
+    fn name(&self) -> &str {
+        "kv_delete"
+    }
+
+    fn description(&self) -> &str {
+        "Delete a value saved with kv_set."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {"type": "string", "description": "Key to delete"},
+                "scope": scope_parameter()
+            },
+            "required": ["key"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let key = require_string(&params, "key")?;
+        let namespace = namespace(&params)?;
+        Ok(if self.store.delete(&namespace, &key)? {
+            format!("Deleted '{}'.", key.trim())
+        } else {
+            format!("No value for '{}'.", key.trim())
+        })
+    }
+}
+
+// ─────────────────────────────────────────────
+// KvListTool
+// ─────────────────────────────────────────────
+
+/// Tool to list stored keys.
+pub struct KvListTool {
+    store: KvStore,
+}
+
+impl KvListTool {
+    /// Create a new `kv_list` tool.
+    pub fn new(store: KvStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for KvListTool {
+    fn name(&self) -> &str {
+        "kv_list"
+    }
+
+    fn description(&self) -> &str {
+        "List saved keys with their values, optionally only those starting with a prefix."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "prefix": {"type": "string", "description": "Only keys starting with this (e.g. \"rss.\")"},
+                "scope": scope_parameter()
+            }
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let prefix = optional_string(&params, "prefix").unwrap_or_default();
+        let namespace = namespace(&params)?;
+        let entries = self.store.list(&namespace, &prefix)?;
+        if entries.is_empty() {
+            return Ok("No keys.".into());
+        }
+        Ok(entries
+            .iter()
+            .map(|(key, entry)| {
+                format!("- {key} = {} (updated {})", entry.value, entry.updated_at.format("%Y-%m-%d %H:%M UTC"))
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_kv_tools_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KvStore::new(dir.path());
+        let get = KvGetTool::new(store.clone());
+        let set = KvSetTool::new(store.clone());
+        let delete = KvDeleteTool::new(store.clone());
+        let list = KvListTool::new(store);
+
+        // Session scope needs a turn
+        assert!(set.execute(params(json!({"key": "a", "value": "1"}))).await.is_err());
+        set.execute(params(json!({"key": "rss.last_id", "value": "99", "scope": "global"}))).await.unwrap();
+
+        TurnContext::new("telegram", "42", "telegram:42")
+            .scope(async {
+                set.execute(params(json!({"key": "rss.last_id", "value": "7"}))).await.unwrap();
+                assert_eq!(get.execute(params(json!({"key": "rss.last_id"}))).await.unwrap(), "7");
+                let global = params(json!({"key": "rss.last_id", "scope": "global"}));
+                assert_eq!(get.execute(global).await.unwrap(), "99");
+                assert!(list.execute(params(json!({"prefix": "rss."}))).await.unwrap().starts_with("- rss.last_id = 7 (updated "));
+                assert_eq!(delete.execute(params(json!({"key": "rss.last_id"}))).await.unwrap(), "Deleted 'rss.last_id'.");
+                assert_eq!(list.execute(params(json!({}))).await.unwrap(), "No keys.");
+            })
+            .await;
+
+        assert!(get.execute(params(json!({"key": "x", "scope": "user"}))).await.is_err());
+    }
+}
@@ -14,7 +14,8 @@ use tracing::debug;
 
 use oxibot_core::bus::types::OutboundMessage;
 
-use super::base::{optional_string, require_string, Tool};
+use super::base::{optional_bool, optional_string, require_string, Tool};
+use super::context::{current_chat, current_sender};
 
 /// Callback type for sending outbound messages.
 pub type SendCallback = Arc<dyn Fn(OutboundMessage) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send + Sync>;
@@ -25,11 +26,11 @@ pub type SendCallback = Arc<dyn Fn(OutboundMessage) -> Pin<Box<dyn Future<Output
 
 /// Allows the agent to send messages to channels.
 ///
-/// The agent loop calls `set_context` before each interaction to set
-/// the default channel/chat_id for the current conversation.
+/// Replies go to the chat of the current [`TurnContext`](super::context::TurnContext)
+/// by default; `set_context` sets the default outside agent turns.
 pub struct MessageTool {
     send_callback: Option<SendCallback>,
-    /// Default channel / chat_id set per-interaction by the agent loop.
+    /// Default channel / chat_id outside agent turns.
     context: Mutex<(String, String)>,
 }
 
@@ -42,7 +43,7 @@ impl MessageTool {
         }
     }
 
-    /// Set the current context (called by the agent loop per-message).
+    /// Set the chat used outside agent turns.
     pub async fn set_context(&self, channel: &str, chat_id: &str) {
         let mut ctx = self.context.lock().await;
         *ctx = (channel.to_string(), chat_id.to_string());
@@ -57,7 +58,9 @@ impl Tool for MessageTool {
 
     fn description(&self) -> &str {
         "Send a message to a channel. By default sends to the current conversation. \
-         Can optionally specify a different channel and chat_id to send to."
+         Can optionally specify a different channel and chat_id to send to. \
+         For email, to/cc/bcc add recipients, subject overrides the subject line \
+         and reply_all copies everyone on the thread being answered."
     }
 
     fn parameters(&self) -> Value {
@@ -75,6 +78,29 @@ impl Tool for MessageTool {
                 "chat_id": {
                     "type": "string",
                     "description": "Target chat ID (optional, defaults to current)"
+                },
+                "to": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Email only: additional To addresses"
+                },
+                "cc": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Email only: CC addresses"
+                },
+                "bcc": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Email only: BCC addresses"
+                },
+                "subject": {
+                    "type": "string",
+                    "description": "Email only: subject line (defaults to Re: <original subject>)"
+                },
+                "reply_all": {
+                    "type": "boolean",
+                    "description": "Email only: also send to everyone else on the thread"
                 }
             },
             "required": ["content"]
@@ -86,14 +112,34 @@ impl Tool for MessageTool {
         let param_channel = optional_string(&params, "channel");
         let param_chat_id = optional_string(&params, "chat_id");
 
-        let ctx = self.context.lock().await;
-        let channel = param_channel.unwrap_or_else(|| ctx.0.clone());
-        let chat_id = param_chat_id.unwrap_or_else(|| ctx.1.clone());
-        drop(ctx);
+        let (ctx_channel, ctx_chat_id) = current_chat(&self.context).await;
+        let channel = param_channel.unwrap_or_else(|| ctx_channel.clone());
+        let chat_id = param_chat_id.unwrap_or_else(|| ctx_chat_id.clone());
 
         debug!(channel = %channel, chat_id = %chat_id, "sending message via tool");
 
-        let msg = OutboundMessage::new(&channel, &chat_id, &content);
+        let mut msg = OutboundMessage::new(&channel, &chat_id, &content);
+        // Lets the target channel ask the current chat before sending
+        if (channel.as_str(), chat_id.as_str()) != (ctx_channel.as_str(), ctx_chat_id.as_str()) {
+            msg = msg.with_origin(&ctx_channel, &ctx_chat_id);
+            if let Some(sender) = current_sender() {
+                msg = msg.with_origin_sender(&sender);
+            }
+        } else {
+            // Part of the answer to the chat's message (not held for quiet hours)
+            msg = msg.as_reply();
+        }
+        for key in ["to", "cc", "bcc"] {
+            if let Some(list) = address_list(&params, key) {
+                msg.metadata.insert(key.into(), list);
+            }
+        }
+        if let Some(subject) = optional_string(&params, "subject") {
+            msg.metadata.insert("subject".into(), subject);
+        }
+        if optional_bool(&params, "reply_all") {
+            msg.metadata.insert("reply_all".into(), "true".into());
+        }
 
         if let Some(cb) = &self.send_callback {
             cb(msg).await.map_err(|e| anyhow::anyhow!("Failed to send message: {e}"))?;
@@ -106,6 +152,22 @@ impl Tool for MessageTool {
     }
 }
 
+/// Read an address list given as an array or a comma-separated string.
+fn address_list(params: &HashMap<String, Value>, key: &str) -> Option<String> {
+    let list = match params.get(key)? {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::String(s) => s.trim().to_string(),
+        _ => return None,
+    };
+    (!list.is_empty()).then_some(list)
+}
+
 // ─────────────────────────────────────────────
 // Tests
 // ─────────────────────────────────────────────
@@ -153,6 +215,46 @@ mod tests {
         assert_eq!(result, "Message sent to slack:C12345");
     }
 
+    #[tokio::test]
+    async fn test_execute_email_recipients_in_metadata() {
+        let sent = Arc::new(std::sync::Mutex::new(None));
+        let sent_clone = sent.clone();
+        let callback: SendCallback = Arc::new(move |msg| {
+            *sent_clone.lock().unwrap() = Some(msg);
+            Box::pin(async { Ok(()) })
+        });
+
+        let tool = MessageTool::new(Some(callback));
+        tool.set_context("email", "alice@example.com").await;
+        let params: HashMap<String, Value> = serde_json::from_value(json!({
+            "content": "Minutes attached",
+            "cc": ["bob@example.com", " ", "carol@example.com"],
+            "bcc": "audit@example.com",
+            "subject": "Meeting minutes",
+            "reply_all": true
+        }))
+        .unwrap();
+        tool.execute(params).await.unwrap();
+
+        let msg = sent.lock().unwrap().take().unwrap();
+        assert_eq!(msg.chat_id, "alice@example.com");
+        assert_eq!(msg.metadata["cc"], "bob@example.com, carol@example.com");
+        assert_eq!(msg.metadata["bcc"], "audit@example.com");
+        assert_eq!(msg.metadata["subject"], "Meeting minutes");
+        assert_eq!(msg.metadata["reply_all"], "true");
+        assert!(!msg.metadata.contains_key("to"));
+        assert_eq!(msg.origin(), None);
+        assert!(msg.is_reply());
+
+        // Sent from another chat: the origin goes along
+        tool.set_context("telegram", "42").await;
+        let params = serde_json::from_value(json!({"content": "Hi", "channel": "email", "chat_id": "bob@example.com"})).unwrap();
+        tool.execute(params).await.unwrap();
+        let msg = sent.lock().unwrap().take().unwrap();
+        assert_eq!(msg.origin(), Some(("telegram", "42")));
+        assert!(!msg.is_reply());
+    }
+
     #[tokio::test]
     async fn test_execute_missing_content() {
         let tool = MessageTool::new(None);
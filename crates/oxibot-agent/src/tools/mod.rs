@@ -1,12 +1,33 @@
 //! Tool modules for Oxibot agent.
 
 pub mod base;
+pub mod context;
+pub mod dry_run;
 pub mod registry;
 pub mod filesystem;
+pub mod attachments;
+pub mod find_files;
 pub mod shell;
+pub mod skill_secrets;
 pub mod web;
 pub mod message;
 pub mod spawn;
+pub mod pin;
+pub mod plan;
+pub mod form;
+pub mod tag;
+pub mod contacts;
+pub mod tasks;
+pub mod kv;
+pub mod calendar;
+pub mod github;
+pub mod cron;
+pub mod time;
+pub mod calc;
+pub mod units;
+pub mod tool_stats;
+#[cfg(feature = "browser")]
+pub mod browser;
 
-pub use base::{Tool, require_string, optional_string, optional_i64, optional_bool};
+pub use base::{Tool, require_string, optional_string, optional_i64, optional_bool, parse_arguments};
 pub use registry::ToolRegistry;
@@ -0,0 +1,179 @@
+//! Pin tool — keep important facts in the conversation context.
+//!
+//! Pinned notes are stored on the session and injected into the system
+//! prompt on every turn, so they survive history trimming.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use oxibot_core::session::manager::SessionManager;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::base::{optional_i64, optional_string, Tool};
+use super::context::current_session;
+
+// ─────────────────────────────────────────────
+// PinTool
+// ─────────────────────────────────────────────
+
+/// Tool that lets the agent pin, list and remove notes for the current session.
+///
+/// Notes are stored on the session of the current
+/// [`TurnContext`](super::context::TurnContext) (or the one from
+/// `set_context` outside agent turns).
+pub struct PinTool {
+    /// Session store holding the pinned notes.
+    sessions: Arc<SessionManager>,
+    /// Session key outside agent turns.
+    session_key: Mutex<String>,
+}
+
+impl PinTool {
+    /// Create a new pin tool.
+    pub fn new(sessions: Arc<SessionManager>) -> Self {
+        Self {
+            sessions,
+            session_key: Mutex::new("cli:direct".into()),
+        }
+    }
+
+    /// Set the session used outside agent turns.
+    pub async fn set_context(&self, session_key: &str) {
+        let mut key = self.session_key.lock().await;
+        *key = session_key.to_string();
+    }
+}
+
+/// Render pinned notes as a numbered list.
+pub fn format_pins(pins: &[String]) -> String {
+    if pins.is_empty() {
+        return "No pinned notes.".into();
+    }
+    pins.iter()
+        .enumerate()
+        .map(|(i, note)| format!("{}. {}", i + 1, note))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[async_trait]
+impl Tool for PinTool {
+    fn name(&self) -> &str {
+        "pin"
+    }
+
+    fn description(&self) -> &str {
+        "Pin an important fact so it stays in your context for the rest of this \
+         conversation, even after older messages are trimmed. Use action 'list' to \
+         show pinned notes and 'remove' with a 1-based index to unpin one."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "list", "remove"],
+                    "description": "What to do (default: add)"
+                },
+                "note": {
+                    "type": "string",
+                    "description": "The fact to pin (for 'add')"
+                },
+                "index": {
+                    "type": "integer",
+                    "description": "1-based index of the note to remove (for 'remove')"
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let key = current_session(&self.session_key).await;
+        let action = optional_string(&params, "action").unwrap_or_else(|| "add".into());
+
+        match action.as_str() {
+            "add" => {
+                let note = optional_string(&params, "note").unwrap_or_default();
+                let note = note.trim();
+                if note.is_empty() {
+                    anyhow::bail!("Missing required parameter: note");
+                }
+                let n = self.sessions.pin(&key, note);
+                Ok(format!("Pinned note #{n}: {note}"))
+            }
+            "list" => Ok(format_pins(&self.sessions.pinned(&key))),
+            "remove" => {
+                let index = optional_i64(&params, "index")
+                    .ok_or_else(|| anyhow::anyhow!("Missing required parameter: index"))?;
+                match usize::try_from(index).ok().and_then(|i| self.sessions.unpin(&key, i)) {
+                    Some(note) => Ok(format!("Unpinned: {note}")),
+                    None => anyhow::bail!("No pinned note #{index}"),
+                }
+            }
+            other => anyhow::bail!("Unknown action: {other}"),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tool() -> (PinTool, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = Arc::new(SessionManager::new(Some(dir.path().to_path_buf())).unwrap());
+        (PinTool::new(sessions), dir)
+    }
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pin_add_list_remove() {
+        let (tool, _dir) = make_tool();
+        tool.set_context("telegram:1").await;
+
+        let out = tool.execute(params(json!({"note": "Budget is 500 EUR"}))).await.unwrap();
+        assert!(out.contains("#1"));
+        tool.execute(params(json!({"action": "add", "note": "Deadline Friday"}))).await.unwrap();
+
+        let list = tool.execute(params(json!({"action": "list"}))).await.unwrap();
+        assert_eq!(list, "1. Budget is 500 EUR\n2. Deadline Friday");
+
+        let out = tool.execute(params(json!({"action": "remove", "index": 1}))).await.unwrap();
+        assert!(out.contains("Budget"));
+        let list = tool.execute(params(json!({"action": "list"}))).await.unwrap();
+        assert_eq!(list, "1. Deadline Friday");
+    }
+
+    #[tokio::test]
+    async fn test_pin_is_per_session() {
+        let (tool, _dir) = make_tool();
+        tool.set_context("telegram:1").await;
+        tool.execute(params(json!({"note": "secret"}))).await.unwrap();
+
+        tool.set_context("telegram:2").await;
+        let list = tool.execute(params(json!({"action": "list"}))).await.unwrap();
+        assert_eq!(list, "No pinned notes.");
+    }
+
+    #[tokio::test]
+    async fn test_pin_errors() {
+        let (tool, _dir) = make_tool();
+        assert!(tool.execute(params(json!({"note": "  "}))).await.is_err());
+        assert!(tool.execute(params(json!({"action": "remove", "index": 3}))).await.is_err());
+        assert!(tool.execute(params(json!({"action": "remove", "index": -1}))).await.is_err());
+        assert!(tool.execute(params(json!({"action": "remove"}))).await.is_err());
+        assert!(tool.execute(params(json!({"action": "nope"}))).await.is_err());
+    }
+}
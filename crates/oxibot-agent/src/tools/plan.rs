@@ -0,0 +1,322 @@
+//! Plan tools — `propose_plan` and `plan_step`.
+//!
+//! Thin wrappers over [`Plan`] for plan mode: proposing asks the user for
+//! approval on the bus, and each step update is posted to the chat as a
+//! progress message.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::bus::Decision;
+use oxibot_core::session::manager::SessionManager;
+use serde_json::{json, Value};
+use tracing::debug;
+
+use super::base::{optional_i64, optional_string, require_string, Tool};
+use super::context::{current_sender, TurnContext};
+use crate::plan::{Plan, PlanState, StepStatus};
+
+/// `(channel, chat_id, session_key)` of the current turn.
+fn current_turn() -> (String, String, String) {
+    match TurnContext::current() {
+        Some(turn) => (turn.channel, turn.chat_id, turn.session_key),
+        None => ("cli".into(), "direct".into(), "cli:direct".into()),
+    }
+}
+
+/// Post a progress message to the chat. Best-effort: never blocks on a
+/// full outbound queue. The CLI shows tool calls itself.
+fn post_progress(bus: &MessageBus, channel: &str, chat_id: &str, content: String) {
+    if channel == "cli" {
+        return;
+    }
+    if let Err(e) = bus.outbound_sender().try_send(OutboundMessage::new(channel, chat_id, content)) {
+        debug!(error = %e, "dropping plan progress update");
+    }
+}
+
+// ─────────────────────────────────────────────
+// ProposePlanTool
+// ─────────────────────────────────────────────
+
+/// Tool to propose a plan and wait for the user's approval.
+pub struct ProposePlanTool {
+    sessions: Arc<SessionManager>,
+    bus: Arc<MessageBus>,
+    /// Approve without asking.
+    auto_approve: bool,
+    /// How long to wait for an answer.
+    timeout: Duration,
+}
+
+impl ProposePlanTool {
+    /// Create a new `propose_plan` tool.
+    pub fn new(sessions: Arc<SessionManager>, bus: Arc<MessageBus>, auto_approve: bool, timeout: Duration) -> Self {
+        Self {
+            sessions,
+            bus,
+            auto_approve,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ProposePlanTool {
+    fn name(&self) -> &str {
+        "propose_plan"
+    }
+
+    fn description(&self) -> &str {
+        "Propose a numbered plan for a multi-step request and wait for the user \
+         to approve it. Returns whether the plan was approved; only start the \
+         work once it is. Replaces any earlier plan of this conversation."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "goal": {
+                    "type": "string",
+                    "description": "What the plan achieves, in one sentence"
+                },
+                "steps": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "The steps in order, each a short imperative sentence"
+                }
+            },
+            "required": ["goal", "steps"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let goal = require_string(&params, "goal")?;
+        let steps: Vec<String> = params
+            .get("steps")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter: steps"))?
+            .iter()
+            .filter_map(|s| s.as_str().map(str::to_string))
+            .collect();
+        let mut plan = Plan::new(&goal, &steps)?;
+        let (channel, chat_id, session_key) = current_turn();
+        plan.save(&self.sessions, &session_key);
+
+        // The CLI can't answer while the turn runs, like exec approvals
+        let decision = if self.auto_approve || channel == "cli" {
+            post_progress(&self.bus, &channel, &chat_id, plan.render());
+            Decision::Approved
+        } else {
+            let prompt = format!("{}\n\nGo ahead with this plan?", plan.render());
+            let requester = current_sender();
+            self.bus
+                .request_approval(&channel, &chat_id, requester.as_deref(), &prompt, self.timeout)
+                .await
+        };
+
+        let reply = match decision {
+            Decision::Approved => {
+                plan.approve()?;
+                format!(
+                    "Plan approved. Work through the {} steps in order, calling `plan_step` \
+                     when you start and finish each one.",
+                    plan.steps.len()
+                )
+            }
+            Decision::Denied => {
+                plan.reject()?;
+                "The user rejected the plan. Don't carry it out; ask what they would \
+                 like changed."
+                    .to_string()
+            }
+            Decision::TimedOut => {
+                plan.reject()?;
+                "The user did not answer in time, so the plan was not approved. Don't \
+                 carry it out; tell the user they can ask again."
+                    .to_string()
+            }
+        };
+        plan.save(&self.sessions, &session_key);
+        Ok(reply)
+    }
+}
+
+// ─────────────────────────────────────────────
+// PlanStepTool
+// ─────────────────────────────────────────────
+
+/// Tool to record progress on the approved plan.
+pub struct PlanStepTool {
+    sessions: Arc<SessionManager>,
+    bus: Arc<MessageBus>,
+}
+
+impl PlanStepTool {
+    /// Create a new `plan_step` tool.
+    pub fn new(sessions: Arc<SessionManager>, bus: Arc<MessageBus>) -> Self {
+        Self { sessions, bus }
+    }
+}
+
+#[async_trait]
+impl Tool for PlanStepTool {
+    fn name(&self) -> &str {
+        "plan_step"
+    }
+
+    fn description(&self) -> &str {
+        "Report progress on the approved plan: mark a step in_progress when you \
+         start it, and done (or skipped, with the reason) when you finish. The \
+         user sees each update."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "step": {
+                    "type": "integer",
+                    "description": "1-based step number"
+                },
+                "status": {
+                    "type": "string",
+                    "enum": ["in_progress", "done", "skipped"],
+                    "description": "New status of the step"
+                },
+                "note": {
+                    "type": "string",
+                    "description": "Optional outcome, or why the step was skipped"
+                }
+            },
+            "required": ["step", "status"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let number = optional_i64(&params, "step")
+            .ok_or_else(|| anyhow::anyhow!("Missing required parameter: step"))?;
+        let status = match require_string(&params, "status")?.as_str() {
+            "in_progress" => StepStatus::InProgress,
+            "done" => StepStatus::Done,
+            "skipped" => StepStatus::Skipped,
+            other => anyhow::bail!("Unknown status: {other}"),
+        };
+        let note = optional_string(&params, "note").filter(|n| !n.trim().is_empty());
+
+        let (channel, chat_id, session_key) = current_turn();
+        let mut plan = Plan::load(&self.sessions, &session_key)
+            .ok_or_else(|| anyhow::anyhow!("There is no plan; call propose_plan first"))?;
+        let number = usize::try_from(number).unwrap_or(0);
+        let step = plan.update_step(number, status, note)?.clone();
+        plan.save(&self.sessions, &session_key);
+
+        let total = plan.steps.len();
+        let label = match status {
+            StepStatus::InProgress => "⏳ Working on",
+            StepStatus::Skipped => "⏭️ Skipped",
+            _ => "✅ Finished",
+        };
+        let mut progress = format!("{label} step {number}/{total}: {}", step.text);
+        if let Some(ref note) = step.note {
+            progress.push_str(&format!(" — {note}"));
+        }
+        post_progress(&self.bus, &channel, &chat_id, progress);
+
+        Ok(if plan.state == PlanState::Done {
+            format!("Step {number} updated. All {total} steps are complete; summarize the result for the user.")
+        } else {
+            format!("Step {number} updated ({}/{total} complete).", plan.completed())
+        })
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::bus::types::InboundMessage;
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_propose_plan_waits_for_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = Arc::new(SessionManager::new(Some(dir.path().to_path_buf())).unwrap());
+        let bus = Arc::new(MessageBus::new(8));
+        let propose = ProposePlanTool::new(sessions.clone(), bus.clone(), false, Duration::from_secs(5));
+        let step = PlanStepTool::new(sessions.clone(), bus.clone());
+
+        let turn = TurnContext::new("telegram", "42", "telegram:42").with_sender("u");
+        let answer = {
+            let bus = bus.clone();
+            tokio::spawn(async move {
+                let question = bus.consume_outbound().await.unwrap();
+                let (id, prompt) = question.approval().unwrap();
+                assert!(prompt.starts_with("📋 Move the blog\n⬜ 1. Export posts\n⬜ 2. Import"));
+                bus.publish_inbound(InboundMessage::approval_response("telegram", "u", "42", id, Decision::Approved))
+                    .await
+                    .unwrap();
+            })
+        };
+        let reply = turn
+            .clone()
+            .scope(propose.execute(params(json!({
+                "goal": "Move the blog",
+                "steps": ["Export posts", "Import posts"]
+            }))))
+            .await
+            .unwrap();
+        answer.await.unwrap();
+        assert!(reply.starts_with("Plan approved"), "{reply}");
+        assert_eq!(Plan::load(&sessions, "telegram:42").unwrap().state, PlanState::Approved);
+
+        let reply = turn
+            .clone()
+            .scope(step.execute(params(json!({"step": 1, "status": "done", "note": "12 posts"}))))
+            .await
+            .unwrap();
+        assert_eq!(reply, "Step 1 updated (1/2 complete).");
+        let progress = bus.consume_outbound().await.unwrap();
+        assert_eq!(progress.content, "✅ Finished step 1/2: Export posts — 12 posts");
+
+        let reply = turn
+            .scope(step.execute(params(json!({"step": 2, "status": "skipped"}))))
+            .await
+            .unwrap();
+        assert!(reply.contains("All 2 steps are complete"));
+        assert_eq!(Plan::load(&sessions, "telegram:42").unwrap().state, PlanState::Done);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_plan_cannot_be_worked_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = Arc::new(SessionManager::new(Some(dir.path().to_path_buf())).unwrap());
+        let bus = Arc::new(MessageBus::new(8));
+        let propose = ProposePlanTool::new(sessions.clone(), bus.clone(), false, Duration::from_millis(10));
+        let step = PlanStepTool::new(sessions.clone(), bus.clone());
+
+        let turn = TurnContext::new("slack", "C1", "slack:C1");
+        let reply = turn
+            .clone()
+            .scope(propose.execute(params(json!({"goal": "x", "steps": ["a"]}))))
+            .await
+            .unwrap();
+        assert!(reply.contains("did not answer in time"));
+        let err = turn
+            .scope(step.execute(params(json!({"step": 1, "status": "done"}))))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Rejected"));
+    }
+}
@@ -6,6 +6,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use oxibot_core::error::ToolError;
 use oxibot_core::types::ToolDefinition;
 use tracing::{info, warn};
 
@@ -86,7 +87,8 @@ impl ToolRegistry {
             Ok(result) => result,
             Err(e) => {
                 warn!(tool = name, error = %e, "tool execution failed");
-                format!("Error executing {name}: {e}")
+                // The LLM gets the full error so it can recover
+                ToolError { tool: name.into(), message: e.to_string() }.to_string()
             }
         }
     }
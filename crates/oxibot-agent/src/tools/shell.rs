@@ -2,18 +2,36 @@
 //!
 //! Port of nanobot's `agent/tools/shell.py` `ExecTool`.
 //! Includes deny-pattern safety guard and optional workspace restriction.
+//!
+//! Output is read incrementally. When a progress callback is configured,
+//! long-running commands post the tail of their output every few seconds
+//! as a progress message (edited in place on channels that support it).
+//!
+//! Secrets of the skills in use in the conversation are set on the child
+//! process and redacted from its output (see [`SkillSecrets`]).
 
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use regex::Regex;
 use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
-use tracing::{info, warn};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::bus::Decision;
 
 use super::base::{optional_string, require_string, Tool};
+use super::context::{current_chat, current_sender};
+use super::dry_run::{DryRun, PREVIEW_PREFIX};
+use super::message::SendCallback;
+use super::skill_secrets::{redact, SkillSecrets};
 
 /// Maximum output length before truncation (characters).
 const MAX_OUTPUT_LEN: usize = 10_000;
@@ -21,7 +39,19 @@ const MAX_OUTPUT_LEN: usize = 10_000;
 /// Default command timeout in seconds.
 const DEFAULT_TIMEOUT_SECS: u64 = 60;
 
-/// Dangerous command patterns that are always blocked.
+/// Default interval between progress updates.
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Output lines shown in a progress update.
+const PROGRESS_TAIL_LINES: usize = 15;
+
+/// Maximum output characters shown in a progress update.
+const PROGRESS_TAIL_CHARS: usize = 1500;
+
+/// Maximum bytes captured per stream; later output is dropped.
+const MAX_CAPTURE_BYTES: usize = 1_000_000;
+
+/// Dangerous command patterns, blocked unless the user approves them.
 const DENY_PATTERNS: &[&str] = &[
     r"\brm\s+-[rf]{1,2}\b",
     r"\bdel\s+/[fq]\b",
@@ -47,6 +77,19 @@ pub struct ExecTool {
     restrict_to_workspace: bool,
     /// Compiled deny regexes (built once at construction).
     deny_regexes: Vec<Regex>,
+    /// Callback used to post progress updates (`None` = no streaming).
+    progress: Option<SendCallback>,
+    /// Interval between progress updates.
+    progress_interval: Duration,
+    /// Channel / chat_id for progress updates outside agent turns.
+    context: Mutex<(String, String)>,
+    /// Echo commands instead of running them while enabled.
+    dry_run: DryRun,
+    /// Bus to ask for approval of dangerous commands on, and how long to
+    /// wait (`None` = always block them).
+    approval: Option<(Arc<MessageBus>, Duration)>,
+    /// Env vars of the skills in use (`None` = no skill secrets).
+    skill_secrets: Option<SkillSecrets>,
 }
 
 impl ExecTool {
@@ -66,23 +109,104 @@ impl ExecTool {
             timeout: Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
             restrict_to_workspace,
             deny_regexes,
+            progress: None,
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            context: Mutex::new(("cli".into(), "direct".into())),
+            dry_run: DryRun::new(),
+            approval: None,
+            skill_secrets: None,
         }
     }
 
-    /// Check if a command is safe to execute. Returns an error message if blocked.
-    fn guard_command(&self, command: &str, cwd: &str) -> Option<String> {
-        let lower = command.to_lowercase();
+    /// Echo commands instead of running them while `dry_run` is enabled.
+    pub fn with_dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
 
-        // Check deny patterns
-        for re in &self.deny_regexes {
-            if re.is_match(&lower) {
-                warn!(command = command, "command blocked by safety guard");
-                return Some(
-                    "Error: Command blocked by safety guard (dangerous pattern detected)".into(),
-                );
+    /// Ask in chat before running a dangerous command instead of blocking
+    /// it, waiting up to `timeout` for an answer. The CLI has no chat to ask
+    /// in, so dangerous commands stay blocked there.
+    pub fn with_approval(mut self, bus: Arc<MessageBus>, timeout: Duration) -> Self {
+        self.approval = Some((bus, timeout));
+        self
+    }
+
+    /// Pass the secrets of the skills in use in the conversation to commands
+    /// as env vars, and redact them from the output.
+    pub fn with_skill_secrets(mut self, skill_secrets: SkillSecrets) -> Self {
+        self.skill_secrets = Some(skill_secrets);
+        self
+    }
+
+    /// Post the tail of the output as a progress message while commands run.
+    pub fn with_progress(mut self, callback: SendCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Set the interval between progress updates (default 3 seconds).
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    /// Set the chat used outside agent turns.
+    pub async fn set_context(&self, channel: &str, chat_id: &str) {
+        let mut ctx = self.context.lock().await;
+        *ctx = (channel.to_string(), chat_id.to_string());
+    }
+
+    /// Post a progress update. Failures are logged, never fatal.
+    async fn send_progress(&self, progress_id: &str, content: String, done: bool) {
+        let Some(ref cb) = self.progress else { return };
+        let (channel, chat_id) = current_chat(&self.context).await;
+        let msg = OutboundMessage::progress(channel, chat_id, content, progress_id, done);
+        if let Err(e) = cb(msg).await {
+            debug!(error = %e, "failed to send exec progress");
+        }
+    }
+
+    /// Whether the user can be asked to approve dangerous commands in the
+    /// current chat.
+    async fn can_ask(&self) -> bool {
+        self.approval.is_some() && current_chat(&self.context).await.0 != "cli"
+    }
+
+    /// Ask the user to approve `command`. Returns an error message unless
+    /// they allow it.
+    async fn ask_approval(&self, command: &str) -> Option<String> {
+        let (bus, timeout) = self.approval.as_ref()?;
+        let (channel, chat_id) = current_chat(&self.context).await;
+        let prompt = format!("Allow exec of `{command}`?");
+        let requester = current_sender();
+        match bus.request_approval(&channel, &chat_id, requester.as_deref(), &prompt, *timeout).await {
+            Decision::Approved => {
+                info!(command = command, "dangerous command approved");
+                None
             }
+            Decision::Denied => Some("Error: Command denied by the user".into()),
+            Decision::TimedOut => Some("Error: Command not run (no answer to the approval request)".into()),
         }
+    }
+
+    /// Whether a command matches a deny pattern.
+    fn is_dangerous(&self, command: &str) -> bool {
+        let lower = command.to_lowercase();
+        self.deny_regexes.iter().any(|re| re.is_match(&lower))
+    }
 
+    /// Check if a command is safe to execute. Returns an error message if blocked.
+    fn guard_command(&self, command: &str, cwd: &str) -> Option<String> {
+        if self.is_dangerous(command) {
+            warn!(command = command, "command blocked by safety guard");
+            return Some("Error: Command blocked by safety guard (dangerous pattern detected)".into());
+        }
+        self.guard_paths(command, cwd)
+    }
+
+    /// Check the paths a command touches. Returns an error message if blocked.
+    fn guard_paths(&self, command: &str, cwd: &str) -> Option<String> {
         // Workspace restriction
         if self.restrict_to_workspace {
             // Block path traversal
@@ -151,34 +275,116 @@ impl Tool for ExecTool {
         let cwd = optional_string(&params, "working_dir")
             .unwrap_or_else(|| self.working_dir.to_string_lossy().to_string());
 
-        // Safety check
-        if let Some(err) = self.guard_command(&command, &cwd) {
+        // Safety check; dangerous commands are asked about where possible
+        let ask = self.is_dangerous(&command) && self.can_ask().await;
+        let guard = if ask {
+            self.guard_paths(&command, &cwd)
+        } else {
+            self.guard_command(&command, &cwd)
+        };
+        if let Some(err) = guard {
             return Ok(err); // return as tool output, not Rust error
         }
 
-        info!(command = %command, cwd = %cwd, "executing shell command");
+        if self.dry_run.enabled() {
+            return Ok(format!(
+                "{PREVIEW_PREFIX} Would run in {cwd}; nothing was executed.\n$ {command}"
+            ));
+        }
+
+        if ask {
+            if let Some(err) = self.ask_approval(&command).await {
+                return Ok(err);
+            }
+        }
+
+        let env = self.skill_secrets.as_ref().map(SkillSecrets::env).unwrap_or_default();
+        info!(
+            command = %command,
+            cwd = %cwd,
+            secrets = env.len(),
+            "executing shell command"
+        );
 
         // Spawn the process
-        let child = Command::new(if cfg!(target_os = "windows") { "cmd" } else { "sh" })
+        let mut child = Command::new(if cfg!(target_os = "windows") { "cmd" } else { "sh" })
             .args(if cfg!(target_os = "windows") {
                 vec!["/C", &command]
             } else {
                 vec!["-c", &command]
             })
             .current_dir(&cwd)
+            .envs(env.iter().map(|(name, value)| (name, value)))
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
             .spawn()
             .map_err(|e| anyhow::anyhow!("Failed to spawn command: {e}"))?;
 
-        // Wait with timeout
-        let result = tokio::time::timeout(self.timeout, child.wait_with_output()).await;
+        // Read stdout/stderr as they are produced
+        let captured = Arc::new(StdMutex::new(Captured::default()));
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(tokio::spawn(pump(stdout, captured.clone(), false)));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(tokio::spawn(pump(stderr, captured.clone(), true)));
+        }
+
+        let started = Instant::now();
+        let progress_id = format!("exec-{:x}", std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos());
+        let mut ticker = tokio::time::interval_at(
+            tokio::time::Instant::now() + self.progress_interval,
+            self.progress_interval,
+        );
+        let deadline = tokio::time::sleep(self.timeout);
+        tokio::pin!(deadline);
+        let mut progress_sent = false;
+        let mut last_version = 0;
+
+        // Wait with timeout, posting progress while the command runs
+        let status = loop {
+            tokio::select! {
+                status = child.wait() => break Some(status),
+                _ = &mut deadline => {
+                    let _ = child.kill().await;
+                    break None;
+                }
+                _ = ticker.tick(), if self.progress.is_some() => {
+                    let (version, tail) = {
+                        let c = captured.lock().unwrap();
+                        (c.version, c.tail())
+                    };
+                    if !progress_sent || version != last_version {
+                        let header = format!(
+                            "⏳ Running `{}` ({}s)",
+                            short_command(&command),
+                            started.elapsed().as_secs()
+                        );
+                        self.send_progress(&progress_id, redact(&progress_text(&header, &tail), &env), false)
+                            .await;
+                        progress_sent = true;
+                        last_version = version;
+                    }
+                }
+            }
+        };
 
-        match result {
-            Ok(Ok(output)) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                let code = output.status.code().unwrap_or(-1);
+        // Collect remaining output (background children may keep the pipes open)
+        for reader in readers {
+            let _ = tokio::time::timeout(Duration::from_secs(1), reader).await;
+        }
+        let (stdout, stderr, tail) = {
+            let c = captured.lock().unwrap();
+            (c.stdout.clone(), c.stderr.clone(), c.tail())
+        };
+
+        let result = match status {
+            Some(Ok(status)) => {
+                let code = status.code().unwrap_or(-1);
 
                 let mut parts = Vec::new();
                 if !stdout.is_empty() {
@@ -191,33 +397,148 @@ impl Tool for ExecTool {
                     parts.push(format!("Exit code: {code}"));
                 }
 
-                let mut combined = if parts.is_empty() {
+                let combined = if parts.is_empty() {
                     "(no output)".to_string()
                 } else {
                     parts.join("\n")
                 };
 
-                // Truncate if too long
-                if combined.len() > MAX_OUTPUT_LEN {
-                    let remaining = combined.len() - MAX_OUTPUT_LEN;
-                    combined.truncate(MAX_OUTPUT_LEN);
-                    combined.push_str(&format!("\n... (truncated, {remaining} more chars)"));
+                if progress_sent {
+                    let header = format!(
+                        "{} `{}` finished in {}s (exit code {code})",
+                        if code == 0 { "✅" } else { "❌" },
+                        short_command(&command),
+                        started.elapsed().as_secs()
+                    );
+                    self.send_progress(&progress_id, redact(&progress_text(&header, &tail), &env), true)
+                        .await;
                 }
 
-                Ok(combined)
+                truncate_output(redact(&combined, &env))
             }
-            Ok(Err(e)) => {
+            Some(Err(e)) => {
                 anyhow::bail!("Command failed: {e}");
             }
-            Err(_) => {
+            None => {
                 // Timeout
-                Ok(format!(
+                if progress_sent {
+                    let header = format!(
+                        "⏱️ `{}` timed out after {}s",
+                        short_command(&command),
+                        self.timeout.as_secs()
+                    );
+                    self.send_progress(&progress_id, redact(&progress_text(&header, &tail), &env), true)
+                        .await;
+                }
+
+                let mut msg = format!(
                     "Error: Command timed out after {} seconds",
                     self.timeout.as_secs()
-                ))
+                );
+                let partial = [stdout, stderr].join("\n");
+                if !partial.trim().is_empty() {
+                    msg.push_str("\n\nOutput before timeout:\n");
+                    msg.push_str(&partial);
+                }
+                truncate_output(redact(&msg, &env))
             }
+        };
+
+        Ok(result)
+    }
+}
+
+// ─────────────────────────────────────────────
+// Output capture
+// ─────────────────────────────────────────────
+
+/// Output collected from a running command.
+#[derive(Default)]
+struct Captured {
+    stdout: String,
+    stderr: String,
+    /// Recent interleaved stdout/stderr, for progress updates.
+    recent: String,
+    /// Incremented on every write (to skip unchanged progress updates).
+    version: u64,
+}
+
+impl Captured {
+    /// Append a chunk from stdout or stderr.
+    fn push(&mut self, text: &str, is_stderr: bool) {
+        let stream = if is_stderr { &mut self.stderr } else { &mut self.stdout };
+        if stream.len() < MAX_CAPTURE_BYTES {
+            stream.push_str(text);
         }
+
+        self.recent.push_str(text);
+        if self.recent.len() > PROGRESS_TAIL_CHARS * 4 {
+            let mut cut = self.recent.len() - PROGRESS_TAIL_CHARS * 2;
+            while !self.recent.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.recent.drain(..cut);
+        }
+        self.version += 1;
+    }
+
+    /// Last lines of output, capped for a progress message.
+    fn tail(&self) -> String {
+        let lines: Vec<&str> = self.recent.lines().collect();
+        let start = lines.len().saturating_sub(PROGRESS_TAIL_LINES);
+        let tail = lines[start..].join("\n");
+        let skip = tail.chars().count().saturating_sub(PROGRESS_TAIL_CHARS);
+        tail.chars().skip(skip).collect()
+    }
+}
+
+/// Read a child stream line by line into the shared capture.
+async fn pump<R: AsyncRead + Unpin>(reader: R, captured: Arc<StdMutex<Captured>>, is_stderr: bool) {
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => captured
+                .lock()
+                .unwrap()
+                .push(&String::from_utf8_lossy(&buf), is_stderr),
+        }
+    }
+}
+
+/// Command shortened for display in progress messages.
+fn short_command(command: &str) -> String {
+    let first_line = command.lines().next().unwrap_or_default();
+    if first_line.chars().count() > 60 || command.contains('\n') {
+        format!("{}…", first_line.chars().take(60).collect::<String>())
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Progress message: a header line plus the output tail in a code block.
+fn progress_text(header: &str, tail: &str) -> String {
+    if tail.trim().is_empty() {
+        header.to_string()
+    } else {
+        format!("{header}\n```\n{}\n```", tail.replace("```", "'''"))
+    }
+}
+
+/// Truncate tool output to `MAX_OUTPUT_LEN` bytes (on a char boundary).
+fn truncate_output(mut output: String) -> String {
+    if output.len() > MAX_OUTPUT_LEN {
+        let mut cut = MAX_OUTPUT_LEN;
+        while !output.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let remaining = output.len() - cut;
+        output.truncate(cut);
+        output.push_str(&format!("\n... (truncated, {remaining} more chars)"));
     }
+    output
 }
 
 // ─────────────────────────────────────────────
@@ -227,6 +548,8 @@ impl Tool for ExecTool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::context::TurnContext;
+    use oxibot_core::bus::types::InboundMessage;
 
     fn make_params(pairs: &[(&str, &str)]) -> HashMap<String, Value> {
         pairs
@@ -257,6 +580,89 @@ mod tests {
         assert!(result.contains("Exit code: 42"));
     }
 
+    #[tokio::test]
+    async fn test_exec_passes_skill_secrets_and_redacts_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = dir.path().join("skills").join("weather");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: weather\nmetadata: {\"nanobot\":{\"requires\":{\"env\":[\"WEATHER_KEY\"]},\"always\":true}}\n---\n\n# Weather",
+        )
+        .unwrap();
+        let skill_secrets = SkillSecrets::new(dir.path());
+        skill_secrets.set(HashMap::from([(
+            "weather".to_string(),
+            HashMap::from([("WEATHER_KEY".to_string(), "w-12345".to_string())]),
+        )]));
+        let tool = ExecTool::new(dir.path().to_path_buf(), Some(10), false).with_skill_secrets(skill_secrets);
+
+        let command = r#"[ "$WEATHER_KEY" = w-12345 ] && echo "key=$WEATHER_KEY""#;
+        let result = tool.execute(make_params(&[("command", command)])).await.unwrap();
+        assert_eq!(result.trim(), "key=[redacted WEATHER_KEY]");
+        assert!(std::env::var("WEATHER_KEY").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exec_dry_run_echoes_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let dry_run = DryRun::new();
+        dry_run.set(true);
+        let tool = ExecTool::new(dir.path().to_path_buf(), Some(10), false).with_dry_run(dry_run);
+        let result = tool
+            .execute(make_params(&[("command", "touch created")]))
+            .await
+            .unwrap();
+        assert!(result.starts_with("[dry-run] Would run in "));
+        assert!(result.ends_with("\n$ touch created"));
+        assert!(!dir.path().join("created").exists());
+
+        // The safety guard still reports blocked commands
+        let result = tool.execute(make_params(&[("command", "rm -rf /")])).await.unwrap();
+        assert!(result.contains("dangerous pattern"));
+    }
+
+    #[tokio::test]
+    async fn test_exec_asks_for_dangerous_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("build")).unwrap();
+        let bus = Arc::new(MessageBus::new(10));
+        let tool = ExecTool::new(dir.path().to_path_buf(), Some(10), false)
+            .with_approval(bus.clone(), Duration::from_secs(5));
+
+        // No chat to ask in from the CLI
+        let result = tool.execute(make_params(&[("command", "rm -rf build")])).await.unwrap();
+        assert!(result.contains("dangerous pattern"));
+
+        let turn = TurnContext::new("telegram", "42", "telegram:42").with_sender("u");
+        let answer = |sender: &'static str, reply: &'static str| {
+            let bus = bus.clone();
+            tokio::spawn(async move {
+                let question = bus.consume_outbound().await.unwrap();
+                assert_eq!(question.approval().unwrap().1, "Allow exec of `rm -rf build`?");
+                bus.publish_inbound(InboundMessage::new("telegram", sender, "42", reply)).await.unwrap();
+                if sender != "u" {
+                    // Not the requester: an ordinary message, then the real answer
+                    assert_eq!(bus.consume_inbound().await.unwrap().content, reply);
+                    bus.publish_inbound(InboundMessage::new("telegram", "u", "42", "no")).await.unwrap();
+                }
+            })
+        };
+
+        answer("someone-else", "yes");
+        let result = turn.clone().scope(tool.execute(make_params(&[("command", "rm -rf build")]))).await.unwrap();
+        assert_eq!(result, "Error: Command denied by the user");
+        assert!(dir.path().join("build").exists());
+
+        answer("u", "yes");
+        turn.clone().scope(tool.execute(make_params(&[("command", "rm -rf build")]))).await.unwrap();
+        assert!(!dir.path().join("build").exists());
+
+        // Safe commands run without asking
+        tool.execute(make_params(&[("command", "echo hi")])).await.unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(50), bus.consume_outbound()).await.is_err());
+    }
+
     #[test]
     fn test_guard_blocks_rm_rf() {
         let tool = ExecTool::new(PathBuf::from("/tmp"), None, false);
@@ -307,6 +713,84 @@ mod tests {
         assert!(result.contains("timed out"));
     }
 
+    #[tokio::test]
+    async fn test_exec_streams_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let sent: Arc<StdMutex<Vec<OutboundMessage>>> = Arc::new(StdMutex::new(Vec::new()));
+        let sink = sent.clone();
+        let callback: SendCallback = Arc::new(move |msg| {
+            sink.lock().unwrap().push(msg);
+            Box::pin(async { Ok(()) })
+        });
+        let tool = ExecTool::new(dir.path().to_path_buf(), Some(10), false)
+            .with_progress(callback)
+            .with_progress_interval(Duration::from_millis(100));
+        tool.set_context("discord", "chan_1").await;
+
+        let result = tool
+            .execute(make_params(&[("command", "echo step1; sleep 0.5; echo step2")]))
+            .await
+            .unwrap();
+        assert!(result.contains("step1") && result.contains("step2"));
+
+        let sent = sent.lock().unwrap();
+        assert!(sent.len() >= 2);
+        assert!(sent.iter().all(|m| m.channel == "discord" && m.chat_id == "chan_1"));
+        assert!(sent.iter().all(|m| m.progress_id() == sent[0].progress_id()));
+        assert!(sent[0].content.contains("step1"));
+        let last = sent.last().unwrap();
+        assert!(last.is_progress_done());
+        assert!(last.content.contains("finished"));
+        assert!(last.content.contains("step2"));
+    }
+
+    #[tokio::test]
+    async fn test_exec_fast_command_sends_no_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let count = Arc::new(StdMutex::new(0));
+        let counter = count.clone();
+        let callback: SendCallback = Arc::new(move |_msg| {
+            *counter.lock().unwrap() += 1;
+            Box::pin(async { Ok(()) })
+        });
+        let tool = ExecTool::new(dir.path().to_path_buf(), Some(10), false).with_progress(callback);
+        tool.execute(make_params(&[("command", "echo quick")])).await.unwrap();
+        assert_eq!(*count.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_exec_timeout_keeps_partial_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = ExecTool::new(dir.path().to_path_buf(), Some(1), false);
+        let result = tool
+            .execute(make_params(&[("command", "echo started; sleep 30")]))
+            .await
+            .unwrap();
+        assert!(result.contains("timed out"));
+        assert!(result.contains("started"));
+    }
+
+    #[test]
+    fn test_captured_tail() {
+        let mut c = Captured::default();
+        for i in 0..40 {
+            c.push(&format!("line {i}\n"), i % 2 == 1);
+        }
+        let tail = c.tail();
+        assert!(tail.starts_with("line 25"));
+        assert!(tail.ends_with("line 39"));
+        assert!(c.stderr.contains("line 1\n"));
+        assert!(!c.stdout.contains("line 1\n"));
+    }
+
+    #[test]
+    fn test_truncate_output_char_boundary() {
+        let long = "é".repeat(MAX_OUTPUT_LEN);
+        let out = truncate_output(long);
+        assert!(out.contains("truncated"));
+        assert!(truncate_output("short".into()) == "short");
+    }
+
     #[test]
     fn test_tool_definition() {
         let tool = ExecTool::new(PathBuf::from("/tmp"), None, false);
@@ -0,0 +1,205 @@
+//! Skill secrets — env vars for `exec`, scoped to the conversations that
+//! use a skill.
+//!
+//! Secrets are configured per skill under `skills.secrets`. A skill is in
+//! use in a conversation once the agent has read its `SKILL.md` there with
+//! `read_file`; always-on skills are in use everywhere. `exec` then sets the
+//! secrets the skill declares in `requires.env` on the child process only —
+//! nothing is exported to oxibot's own environment — and replaces their
+//! values in the command output with `[redacted NAME]`, so they never reach
+//! the LLM, the session history or the logs.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use tracing::debug;
+
+use super::context::TurnContext;
+use crate::skills::SkillsLoader;
+
+/// Secrets per skill: skill name → env var → value.
+pub type Secrets = HashMap<String, HashMap<String, String>>;
+
+/// Skill secrets shared by `read_file` (which marks skills as in use) and
+/// `exec` (which passes them on).
+#[derive(Clone)]
+pub struct SkillSecrets(Arc<Inner>);
+
+struct Inner {
+    /// Workspace root (contains `skills/`).
+    workspace: PathBuf,
+    /// Configured secrets.
+    secrets: RwLock<Secrets>,
+    /// Skills in use, per session key.
+    active: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl SkillSecrets {
+    /// Create an empty set of secrets for the skills in `workspace`.
+    pub fn new(workspace: &Path) -> Self {
+        Self(Arc::new(Inner {
+            workspace: workspace.to_path_buf(),
+            secrets: RwLock::new(Secrets::new()),
+            active: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Replace the configured secrets.
+    pub fn set(&self, secrets: Secrets) {
+        *self.0.secrets.write().unwrap() = secrets;
+    }
+
+    /// Mark the skill whose `SKILL.md` is at `path` as in use in the current
+    /// conversation, if it has secrets. Called after `read_file` reads `path`.
+    pub fn note_read(&self, path: &Path) {
+        let Some(turn) = TurnContext::current() else {
+            return;
+        };
+        let secrets = self.0.secrets.read().unwrap();
+        if secrets.is_empty() || path.file_name().is_none_or(|name| name != "SKILL.md") {
+            return;
+        }
+        let Ok(path) = path.canonicalize() else {
+            return;
+        };
+        let loader = SkillsLoader::new(&self.0.workspace, None);
+        let skill = loader
+            .list_skills(false)
+            .into_iter()
+            .find(|skill| skill.path.canonicalize().is_ok_and(|p| p == path));
+        if let Some(skill) = skill.filter(|skill| secrets.contains_key(&skill.name)) {
+            debug!(skill = %skill.name, session = %turn.session_key, "skill secrets in use");
+            self.0
+                .active
+                .lock()
+                .unwrap()
+                .entry(turn.session_key)
+                .or_default()
+                .insert(skill.name);
+        }
+    }
+
+    /// Forget which skills `session_key` uses (the conversation was reset).
+    pub fn forget(&self, session_key: &str) {
+        self.0.active.lock().unwrap().remove(session_key);
+    }
+
+    /// Env vars for `exec` in the current conversation: the secrets that the
+    /// skills in use declare in `requires.env`.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let secrets = self.0.secrets.read().unwrap();
+        if secrets.is_empty() {
+            return Vec::new();
+        }
+        let loader = SkillsLoader::new(&self.0.workspace, None).with_secrets(&secrets);
+        let mut skills: HashSet<String> = loader.get_always_skills().into_iter().collect();
+        if let Some(turn) = TurnContext::current() {
+            if let Some(active) = self.0.active.lock().unwrap().get(&turn.session_key) {
+                skills.extend(active.iter().cloned());
+            }
+        }
+
+        let mut env: Vec<(String, String)> = skills
+            .iter()
+            .filter_map(|skill| Some((skill, secrets.get(skill)?)))
+            .flat_map(|(skill, vars)| {
+                loader
+                    .get_skill_meta(skill)
+                    .requires
+                    .env
+                    .into_iter()
+                    .filter_map(|var| Some((var.clone(), vars.get(&var)?.clone())))
+            })
+            .collect();
+        env.sort();
+        env.dedup_by(|a, b| a.0 == b.0);
+        env
+    }
+}
+
+/// Replace the values of `env` in `text` with `[redacted NAME]`.
+pub fn redact(text: &str, env: &[(String, String)]) -> String {
+    let mut values: Vec<&(String, String)> = env.iter().filter(|(_, value)| !value.is_empty()).collect();
+    // Longest first, so a value containing another is replaced whole
+    values.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+    values.into_iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(value.as_str(), &format!("[redacted {name}]"))
+    })
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(pairs: &[(&str, &str, &str)]) -> Secrets {
+        let mut secrets = Secrets::new();
+        for (skill, var, value) in pairs {
+            secrets
+                .entry(skill.to_string())
+                .or_default()
+                .insert(var.to_string(), value.to_string());
+        }
+        secrets
+    }
+
+    fn create_skill(workspace: &Path, name: &str, env: &[&str], always: bool) -> PathBuf {
+        let dir = workspace.join("skills").join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("SKILL.md");
+        let metadata = serde_json::json!({"nanobot": {"requires": {"env": env}, "always": always}});
+        std::fs::write(&path, format!("---\nname: {name}\nmetadata: {metadata}\n---\n\n# {name}")).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn secrets_follow_the_conversation_that_read_the_skill() {
+        let dir = tempfile::tempdir().unwrap();
+        let github = create_skill(dir.path(), "github", &["GH_TOKEN"], false);
+        create_skill(dir.path(), "notes", &["NOTES_KEY"], true);
+        let skill_secrets = SkillSecrets::new(dir.path());
+        skill_secrets.set(secrets(&[
+            ("github", "GH_TOKEN", "ghp_secret"),
+            ("github", "UNDECLARED", "nope"),
+            ("notes", "NOTES_KEY", "n0tes"),
+        ]));
+        let notes = vec![("NOTES_KEY".to_string(), "n0tes".to_string())];
+
+        let alice = TurnContext::new("telegram", "1", "telegram:1");
+        let bob = TurnContext::new("telegram", "2", "telegram:2");
+        let s = skill_secrets.clone();
+        assert_eq!(alice.clone().scope(async move { s.env() }).await, notes);
+
+        let s = skill_secrets.clone();
+        alice.clone().scope(async move { s.note_read(&github) }).await;
+        let s = skill_secrets.clone();
+        let env = alice.clone().scope(async move { s.env() }).await;
+        assert_eq!(
+            env,
+            [("GH_TOKEN".to_string(), "ghp_secret".to_string()), notes[0].clone()]
+        );
+        let s = skill_secrets.clone();
+        assert_eq!(bob.scope(async move { s.env() }).await, notes);
+
+        skill_secrets.forget("telegram:1");
+        let s = skill_secrets.clone();
+        assert_eq!(alice.scope(async move { s.env() }).await, notes);
+    }
+
+    #[test]
+    fn redact_replaces_values() {
+        let env = vec![
+            ("SHORT".to_string(), "abc".to_string()),
+            ("LONG".to_string(), "abcdef".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ];
+        assert_eq!(
+            redact("token=abcdef, key=abc", &env),
+            "token=[redacted LONG], key=[redacted SHORT]"
+        );
+    }
+}
@@ -15,6 +15,7 @@ use serde_json::{json, Value};
 use tokio::sync::Mutex;
 
 use super::base::{optional_string, require_string, Tool};
+use super::context::current_chat;
 use crate::subagent::SubagentManager;
 
 // ─────────────────────────────────────────────
@@ -23,12 +24,13 @@ use crate::subagent::SubagentManager;
 
 /// Tool that allows the agent to spawn background subagent tasks.
 ///
-/// The agent loop calls `set_context` before each interaction to set
-/// the current channel/chat_id so subagent results route back correctly.
+/// Subagent results route back to the chat of the current
+/// [`TurnContext`](super::context::TurnContext) (or the one from
+/// `set_context` outside agent turns).
 pub struct SpawnTool {
     /// Reference to the subagent manager.
     manager: Arc<SubagentManager>,
-    /// Current origin context (channel, chat_id) — set per-interaction.
+    /// Origin (channel, chat_id) outside agent turns.
     context: Mutex<(String, String)>,
 }
 
@@ -41,7 +43,7 @@ impl SpawnTool {
         }
     }
 
-    /// Set the current context (called by the agent loop per-message).
+    /// Set the chat used outside agent turns.
     ///
     /// This ensures subagent results are routed back to the correct
     /// channel/chat that originated the spawn request.
@@ -84,10 +86,7 @@ impl Tool for SpawnTool {
         let task = require_string(&params, "task")?;
         let label = optional_string(&params, "label");
 
-        let ctx = self.context.lock().await;
-        let origin_channel = ctx.0.clone();
-        let origin_chat_id = ctx.1.clone();
-        drop(ctx);
+        let (origin_channel, origin_chat_id) = current_chat(&self.context).await;
 
         let confirmation = self
             .manager
@@ -0,0 +1,176 @@
+//! Tag tool — label the current conversation for later lookup.
+//!
+//! Tags are stored on the session, so `oxibot sessions list --tag ...` and
+//! friends can find conversations by topic.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use oxibot_core::session::manager::SessionManager;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::base::{optional_string, Tool};
+use super::context::current_session;
+
+// ─────────────────────────────────────────────
+// TagSessionTool
+// ─────────────────────────────────────────────
+
+/// Tool that lets the agent add, remove and list tags on the current session.
+///
+/// Tags land on the session of the current
+/// [`TurnContext`](super::context::TurnContext) (or the one from
+/// `set_context` outside agent turns).
+pub struct TagSessionTool {
+    /// Session store holding the tags.
+    sessions: Arc<SessionManager>,
+    /// Session key outside agent turns.
+    session_key: Mutex<String>,
+}
+
+impl TagSessionTool {
+    /// Create a new tag tool.
+    pub fn new(sessions: Arc<SessionManager>) -> Self {
+        Self {
+            sessions,
+            session_key: Mutex::new("cli:direct".into()),
+        }
+    }
+
+    /// Set the session used outside agent turns.
+    pub async fn set_context(&self, session_key: &str) {
+        let mut key = self.session_key.lock().await;
+        *key = session_key.to_string();
+    }
+}
+
+/// Read `tags` as either an array of strings or a comma-separated string.
+fn tag_list(params: &HashMap<String, Value>) -> Vec<String> {
+    match params.get("tags") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(String::from)
+            .collect(),
+        Some(Value::String(s)) => s.split(',').map(String::from).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[async_trait]
+impl Tool for TagSessionTool {
+    fn name(&self) -> &str {
+        "tag_session"
+    }
+
+    fn description(&self) -> &str {
+        "Tag this conversation by topic (e.g. 'work', 'trip-planning') so it can be \
+         found later. Use action 'remove' to drop tags and 'list' to show them. \
+         Tags like channel:*, model:* and lang:* are added automatically."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "remove", "list"],
+                    "description": "What to do (default: add)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Tags to add or remove"
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let key = current_session(&self.session_key).await;
+        let action = optional_string(&params, "action").unwrap_or_else(|| "add".into());
+        let tags = tag_list(&params);
+
+        match action.as_str() {
+            "add" => {
+                if tags.iter().all(|t| t.trim().is_empty()) {
+                    anyhow::bail!("Missing required parameter: tags");
+                }
+                let added = self.sessions.add_tags(&key, &tags);
+                if added.is_empty() {
+                    Ok("Already tagged.".into())
+                } else {
+                    Ok(format!("Tagged: {}", added.join(", ")))
+                }
+            }
+            "remove" => {
+                let removed: Vec<&String> = tags
+                    .iter()
+                    .filter(|t| self.sessions.remove_tag(&key, t))
+                    .collect();
+                if removed.is_empty() {
+                    anyhow::bail!("None of those tags are on this conversation");
+                }
+                Ok(format!(
+                    "Removed: {}",
+                    removed.iter().map(|t| t.trim()).collect::<Vec<_>>().join(", ")
+                ))
+            }
+            "list" => {
+                let tags = self.sessions.tags(&key);
+                if tags.is_empty() {
+                    Ok("No tags.".into())
+                } else {
+                    Ok(tags.join(", "))
+                }
+            }
+            other => anyhow::bail!("Unknown action: {other}"),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tag_add_list_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = Arc::new(SessionManager::new(Some(dir.path().to_path_buf())).unwrap());
+        let tool = TagSessionTool::new(sessions.clone());
+        tool.set_context("telegram:1").await;
+
+        assert_eq!(tool.execute(params(json!({"action": "list"}))).await.unwrap(), "No tags.");
+        let out = tool.execute(params(json!({"tags": ["Work", "Q3 planning"]}))).await.unwrap();
+        assert_eq!(out, "Tagged: work, q3-planning");
+        let out = tool.execute(params(json!({"tags": "work"}))).await.unwrap();
+        assert_eq!(out, "Already tagged.");
+        assert_eq!(sessions.tags("telegram:1"), vec!["q3-planning", "work"]);
+
+        let out = tool
+            .execute(params(json!({"action": "remove", "tags": ["work"]})))
+            .await
+            .unwrap();
+        assert_eq!(out, "Removed: work");
+        assert!(tool
+            .execute(params(json!({"action": "remove", "tags": ["nope"]})))
+            .await
+            .is_err());
+        assert!(tool.execute(params(json!({}))).await.is_err());
+
+        // Other sessions are untouched
+        assert!(sessions.tags("telegram:2").is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+//! Task tools — `task_add`, `task_list` and `task_done`.
+//!
+//! Thin wrappers over [`TaskStore`] so the agent can track commitments
+//! that outlive a single conversation.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use super::base::{optional_bool, optional_i64, optional_string, require_string, Tool};
+use crate::tasks::{parse_due, TaskStore};
+
+// ─────────────────────────────────────────────
+// TaskAddTool
+// ─────────────────────────────────────────────
+
+/// Tool to add a task to the persistent task list.
+pub struct TaskAddTool {
+    store: TaskStore,
+}
+
+impl TaskAddTool {
+    /// Create a new `task_add` tool.
+    pub fn new(store: TaskStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for TaskAddTool {
+    fn name(&self) -> &str {
+        "task_add"
+    }
+
+    fn description(&self) -> &str {
+        "Add a task to your persistent task list. Use it for anything you or the \
+         user committed to doing later, especially across days. Open tasks are \
+         shown to you at the start of every conversation."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "title": {
+                    "type": "string",
+                    "description": "What needs to be done"
+                },
+                "due": {
+                    "type": "string",
+                    "description": "Optional due date (YYYY-MM-DD)"
+                }
+            },
+            "required": ["title"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let title = require_string(&params, "title")?;
+        let due = optional_string(&params, "due")
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| parse_due(&s))
+            .transpose()?;
+        let task = self.store.add(&title, due)?;
+        Ok(format!("Added task {}", task.summary(Utc::now().date_naive())))
+    }
+}
+
+// ─────────────────────────────────────────────
+// TaskListTool
+// ─────────────────────────────────────────────
+
+/// Tool to list tasks.
+pub struct TaskListTool {
+    store: TaskStore,
+}
+
+impl TaskListTool {
+    /// Create a new `task_list` tool.
+    pub fn new(store: TaskStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for TaskListTool {
+    fn name(&self) -> &str {
+        "task_list"
+    }
+
+    fn description(&self) -> &str {
+        "List open tasks (soonest due first). Set include_done to also show completed ones."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "include_done": {
+                    "type": "boolean",
+                    "description": "Also list completed tasks (default: false)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let today = Utc::now().date_naive();
+        let mut tasks = self.store.open();
+        if optional_bool(&params, "include_done") {
+            tasks.extend(self.store.all().into_iter().filter(|t| !t.is_open()));
+        }
+        if tasks.is_empty() {
+            return Ok("No tasks.".into());
+        }
+        Ok(tasks
+            .iter()
+            .map(|t| format!("- {}", t.summary(today)))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+// ─────────────────────────────────────────────
+// TaskDoneTool
+// ─────────────────────────────────────────────
+
+/// Tool to mark a task as done.
+pub struct TaskDoneTool {
+    store: TaskStore,
+}
+
+impl TaskDoneTool {
+    /// Create a new `task_done` tool.
+    pub fn new(store: TaskStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for TaskDoneTool {
+    fn name(&self) -> &str {
+        "task_done"
+    }
+
+    fn description(&self) -> &str {
+        "Mark a task as done by its id (the number after '#')."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "description": "Task id"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let id = optional_i64(&params, "id")
+            .filter(|id| *id > 0)
+            .ok_or_else(|| anyhow::anyhow!("Missing or invalid parameter: id"))?;
+        let task = self.store.complete(id as u64)?;
+        Ok(format!("Completed task #{}: {}", task.id, task.title))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_task_tools_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskStore::new(dir.path());
+        let add = TaskAddTool::new(store.clone());
+        let list = TaskListTool::new(store.clone());
+        let done = TaskDoneTool::new(store);
+
+        assert_eq!(list.execute(params(json!({}))).await.unwrap(), "No tasks.");
+
+        let out = add
+            .execute(params(json!({"title": "Renew domain", "due": "2099-03-01"})))
+            .await
+            .unwrap();
+        assert_eq!(out, "Added task #1 Renew domain (due 2099-03-01)");
+        add.execute(params(json!({"title": "Buy milk"}))).await.unwrap();
+
+        let out = done.execute(params(json!({"id": 2}))).await.unwrap();
+        assert_eq!(out, "Completed task #2: Buy milk");
+
+        let open = list.execute(params(json!({}))).await.unwrap();
+        assert_eq!(open, "- #1 Renew domain (due 2099-03-01)");
+        let all = list.execute(params(json!({"include_done": true}))).await.unwrap();
+        assert!(all.contains("#2 Buy milk (done "));
+    }
+
+    #[tokio::test]
+    async fn test_task_tools_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskStore::new(dir.path());
+        let add = TaskAddTool::new(store.clone());
+        let done = TaskDoneTool::new(store);
+
+        assert!(add.execute(params(json!({}))).await.is_err());
+        assert!(add
+            .execute(params(json!({"title": "x", "due": "tomorrow"})))
+            .await
+            .is_err());
+        assert!(done.execute(params(json!({"id": 1}))).await.is_err());
+        assert!(done.execute(params(json!({"id": "one"}))).await.is_err());
+    }
+}
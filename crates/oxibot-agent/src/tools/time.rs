@@ -0,0 +1,238 @@
+//! Time tools — turn "tuesday at 9" into an exact timestamp, and convert
+//! times between timezones.
+//!
+//! Times are read in the user's timezone (the `Timezone` line of the
+//! workspace `USER.md`, else the configured default), so the LLM never does
+//! date arithmetic or DST conversions itself.
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use oxibot_core::datetime::{parse_time, to_iso, Tz, UserTimezone};
+use serde_json::{json, Value};
+
+use super::base::{optional_string, require_string, Tool};
+
+/// Parse an IANA timezone name.
+fn parse_tz(name: &str) -> anyhow::Result<Tz> {
+    name.trim()
+        .parse::<Tz>()
+        .ok()
+        .with_context(|| format!("unknown timezone '{name}' (use an IANA name like Europe/Madrid)"))
+}
+
+// ─────────────────────────────────────────────
+// ParseTimeTool
+// ─────────────────────────────────────────────
+
+/// Tool that resolves a natural-language time to ISO 8601.
+pub struct ParseTimeTool {
+    timezone: UserTimezone,
+}
+
+impl ParseTimeTool {
+    /// Create a new parse-time tool.
+    pub fn new(timezone: UserTimezone) -> Self {
+        Self { timezone }
+    }
+
+    fn parse(&self, params: &HashMap<String, Value>, now: DateTime<Utc>) -> anyhow::Result<String> {
+        let text = require_string(params, "text")?;
+        let tz = match optional_string(params, "timezone") {
+            Some(name) => parse_tz(&name)?,
+            None => self.timezone.get(),
+        };
+        let at = parse_time(&text, now.with_timezone(&tz))?;
+
+        let mut out = format!(
+            "{} ({tz})\nISO 8601: {}\nUTC: {}",
+            at.format("%A %Y-%m-%d %H:%M"),
+            to_iso(&at),
+            to_iso(&at.with_timezone(&Utc)),
+        );
+        if at < now {
+            out.push_str("\nNote: this is in the past.");
+        }
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl Tool for ParseTimeTool {
+    fn name(&self) -> &str {
+        "parse_time"
+    }
+
+    fn description(&self) -> &str {
+        "Resolve a date/time like 'tuesday at 9', 'tomorrow 18:30', 'in 2 hours' or \
+         '2026-03-01 10:00' to an exact ISO 8601 timestamp in the user's timezone. \
+         Use it before scheduling anything instead of computing dates yourself."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "The date/time as the user said it"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone (default: the user's)"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        self.parse(&params, Utc::now())
+    }
+}
+
+// ─────────────────────────────────────────────
+// TimezoneConvertTool
+// ─────────────────────────────────────────────
+
+/// Tool that shows a time in other timezones.
+pub struct TimezoneConvertTool {
+    timezone: UserTimezone,
+}
+
+impl TimezoneConvertTool {
+    /// Create a new timezone-convert tool.
+    pub fn new(timezone: UserTimezone) -> Self {
+        Self { timezone }
+    }
+
+    fn convert(&self, params: &HashMap<String, Value>, now: DateTime<Utc>) -> anyhow::Result<String> {
+        let from = match optional_string(params, "from") {
+            Some(name) => parse_tz(&name)?,
+            None => self.timezone.get(),
+        };
+        let targets = require_string(params, "to")?
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(parse_tz)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if targets.is_empty() {
+            anyhow::bail!("no target timezone given");
+        }
+        let text = optional_string(params, "time").unwrap_or_else(|| "now".into());
+        let at = parse_time(&text, now.with_timezone(&from))?;
+
+        let line = |tz: Tz| {
+            let local = at.with_timezone(&tz);
+            format!("{} {tz} ({})", local.format("%a %Y-%m-%d %H:%M"), local.format("%Z"))
+        };
+        let mut out = line(from);
+        for tz in targets {
+            out.push_str(&format!("\n= {}", line(tz)));
+        }
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl Tool for TimezoneConvertTool {
+    fn name(&self) -> &str {
+        "timezone_convert"
+    }
+
+    fn description(&self) -> &str {
+        "Show a time ('now', 'tomorrow 15:00', '2026-03-01 10:00') from one timezone in \
+         one or more others, DST included."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "time": {
+                    "type": "string",
+                    "description": "The time in the source timezone (default: now)"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "IANA source timezone (default: the user's)"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "IANA target timezone(s), comma-separated, e.g. 'America/New_York, Asia/Tokyo'"
+                }
+            },
+            "required": ["to"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        self.convert(&params, Utc::now())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, Value> {
+        pairs.iter().map(|(k, v)| (k.to_string(), json!(v))).collect()
+    }
+
+    #[test]
+    fn test_parse_time_in_user_timezone() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = dir.path().join("USER.md");
+        std::fs::write(&profile, "- **Timezone**: Europe/Madrid\n").unwrap();
+        let tool = ParseTimeTool::new(UserTimezone::new(profile, Tz::UTC));
+        // Saturday 2026-10-17 12:00 UTC (14:00 in Madrid)
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+
+        // Tuesday is still summer time, the Monday after is winter time
+        let out = tool.parse(&params(&[("text", "tuesday at 9")]), now).unwrap();
+        assert!(out.starts_with("Tuesday 2026-10-20 09:00 (Europe/Madrid)"), "{out}");
+        assert!(out.contains("ISO 8601: 2026-10-20T09:00:00+02:00"), "{out}");
+        assert!(out.contains("UTC: 2026-10-20T07:00:00+00:00"), "{out}");
+
+        let out = tool
+            .parse(&params(&[("text", "2026-10-26 09:00"), ("timezone", "Europe/Madrid")]), now)
+            .unwrap();
+        assert!(out.contains("ISO 8601: 2026-10-26T09:00:00+01:00"), "{out}");
+
+        let out = tool.parse(&params(&[("text", "2026-01-01")]), now).unwrap();
+        assert!(out.ends_with("in the past."), "{out}");
+
+        assert!(tool.parse(&params(&[("text", "9"), ("timezone", "Mars/Base")]), now).is_err());
+        assert!(tool.parse(&params(&[("text", "whenever")]), now).is_err());
+    }
+
+    #[test]
+    fn test_timezone_convert() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = TimezoneConvertTool::new(UserTimezone::new(dir.path().join("USER.md"), Tz::Europe__Madrid));
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+
+        let out = tool
+            .convert(&params(&[("time", "2026-10-20 09:00"), ("to", "America/New_York, Asia/Tokyo")]), now)
+            .unwrap();
+        assert_eq!(
+            out,
+            "Tue 2026-10-20 09:00 Europe/Madrid (CEST)\n\
+             = Tue 2026-10-20 03:00 America/New_York (EDT)\n\
+             = Tue 2026-10-20 16:00 Asia/Tokyo (JST)"
+        );
+
+        let out = tool.convert(&params(&[("from", "UTC"), ("to", "Asia/Kolkata")]), now).unwrap();
+        assert!(out.ends_with("= Sat 2026-10-17 17:30 Asia/Kolkata (IST)"), "{out}");
+
+        assert!(tool.convert(&params(&[("to", "Mars/Base")]), now).is_err());
+        assert!(tool.convert(&params(&[("to", " , ")]), now).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Tool stats tool — lets the agent see how its tools have been performing.
+//!
+//! Reads the stats the agent loop records after every tool call, so the
+//! agent (or a user debugging it) can tell which tools are slow or failing.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use oxibot_core::tool_stats::{ToolStat, ToolStatsStore};
+use serde_json::{json, Value};
+
+use super::base::{optional_string, Tool};
+
+// ─────────────────────────────────────────────
+// ToolStatsTool
+// ─────────────────────────────────────────────
+
+/// Tool that reports call counts, durations and error rates per tool.
+pub struct ToolStatsTool {
+    store: Arc<ToolStatsStore>,
+}
+
+impl ToolStatsTool {
+    /// Create a new tool stats tool.
+    pub fn new(store: Arc<ToolStatsStore>) -> Self {
+        Self { store }
+    }
+}
+
+/// One line of the report.
+fn format_stat(name: &str, stat: &ToolStat) -> String {
+    let mut line = format!(
+        "- {name}: {} calls, avg {}ms, max {}ms, {:.0}% errors",
+        stat.calls,
+        stat.avg_ms(),
+        stat.max_ms,
+        stat.error_rate() * 100.0
+    );
+    if stat.is_slow() {
+        line.push_str(" [slow]");
+    }
+    if stat.is_flaky() {
+        line.push_str(" [flaky]");
+    }
+    if let Some(ref error) = stat.last_error {
+        line.push_str(&format!("\n  last error: {error}"));
+    }
+    line
+}
+
+#[async_trait]
+impl Tool for ToolStatsTool {
+    fn name(&self) -> &str {
+        "tool_stats"
+    }
+
+    fn description(&self) -> &str {
+        "Show how often each tool has been called, how long calls take and how \
+         often they fail. Useful for finding slow or unreliable tools."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "tool": {
+                    "type": "string",
+                    "description": "Only show this tool"
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let only = optional_string(&params, "tool");
+        let lines: Vec<String> = self
+            .store
+            .slowest()
+            .iter()
+            .filter(|(name, _)| only.as_ref().is_none_or(|o| o == name))
+            .map(|(name, stat)| format_stat(name, stat))
+            .collect();
+
+        if lines.is_empty() {
+            return Ok(match only {
+                Some(tool) => format!("No calls to {tool} recorded."),
+                None => "No tool calls recorded yet.".into(),
+            });
+        }
+        Ok(format!("Tool stats (slowest first):\n{}", lines.join("\n")))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_reports_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(ToolStatsStore::new(Some(dir.path().join("stats.json"))));
+        let tool = ToolStatsTool::new(store.clone());
+        assert_eq!(tool.execute(HashMap::new()).await.unwrap(), "No tool calls recorded yet.");
+
+        store.record("web_fetch", Duration::from_millis(900), Some("Error: timed out"));
+        store.record("read_file", Duration::from_millis(3), None);
+
+        let out = tool.execute(HashMap::new()).await.unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[1].starts_with("- web_fetch: 1 calls, avg 900ms"));
+        assert!(lines[1].ends_with("100% errors"));
+        assert_eq!(lines[2], "  last error: Error: timed out");
+        assert!(lines[3].starts_with("- read_file"));
+
+        let params = HashMap::from([("tool".to_string(), json!("read_file"))]);
+        let out = tool.execute(params).await.unwrap();
+        assert!(!out.contains("web_fetch"));
+        let params = HashMap::from([("tool".to_string(), json!("exec"))]);
+        assert_eq!(tool.execute(params).await.unwrap(), "No calls to exec recorded.");
+    }
+}
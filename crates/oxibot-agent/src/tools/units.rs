@@ -0,0 +1,278 @@
+//! Unit conversion tool — length, mass, volume, temperature and friends,
+//! from a built-in table, without a web search.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::base::{require_string, Tool};
+use super::calc::format_number;
+
+// ─────────────────────────────────────────────
+// Units
+// ─────────────────────────────────────────────
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Area,
+    Speed,
+    Time,
+    Data,
+    Energy,
+    Pressure,
+    Temperature,
+}
+
+impl Dimension {
+    fn name(self) -> &'static str {
+        match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Volume => "volume",
+            Dimension::Area => "area",
+            Dimension::Speed => "speed",
+            Dimension::Time => "time",
+            Dimension::Data => "data",
+            Dimension::Energy => "energy",
+            Dimension::Pressure => "pressure",
+            Dimension::Temperature => "temperature",
+        }
+    }
+}
+
+/// A unit: its names, and `factor` base units per unit (metre, kilogram,
+/// litre, square metre, metre/second, second, byte, joule, pascal).
+/// Temperatures use `factor` as a scale to kelvin after adding `offset`.
+struct Unit {
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit { names, dimension, factor, offset: 0.0 }
+}
+
+#[rustfmt::skip]
+const UNITS: &[Unit] = &[
+    unit(&["m", "meter", "meters", "metre", "metres"], Dimension::Length, 1.0),
+    unit(&["km", "kilometer", "kilometers", "kilometre", "kilometres"], Dimension::Length, 1000.0),
+    unit(&["cm", "centimeter", "centimeters", "centimetre", "centimetres"], Dimension::Length, 0.01),
+    unit(&["mm", "millimeter", "millimeters", "millimetre", "millimetres"], Dimension::Length, 0.001),
+    unit(&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    unit(&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    unit(&["ft", "foot", "feet", "'"], Dimension::Length, 0.3048),
+    unit(&["in", "inch", "inches", "\""], Dimension::Length, 0.0254),
+    unit(&["nmi", "nautical mile", "nautical miles"], Dimension::Length, 1852.0),
+
+    unit(&["kg", "kilogram", "kilograms", "kilo", "kilos"], Dimension::Mass, 1.0),
+    unit(&["g", "gram", "grams"], Dimension::Mass, 0.001),
+    unit(&["mg", "milligram", "milligrams"], Dimension::Mass, 1e-6),
+    unit(&["t", "tonne", "tonnes", "metric ton", "metric tons"], Dimension::Mass, 1000.0),
+    unit(&["lb", "lbs", "pound", "pounds"], Dimension::Mass, 0.453_592_37),
+    unit(&["oz", "ounce", "ounces"], Dimension::Mass, 0.028_349_523_125),
+    unit(&["st", "stone", "stones"], Dimension::Mass, 6.350_293_18),
+
+    unit(&["l", "liter", "liters", "litre", "litres"], Dimension::Volume, 1.0),
+    unit(&["ml", "milliliter", "milliliters", "millilitre", "millilitres"], Dimension::Volume, 0.001),
+    unit(&["cl", "centiliter", "centiliters", "centilitre", "centilitres"], Dimension::Volume, 0.01),
+    unit(&["m3", "m³", "cubic meter", "cubic meters", "cubic metre", "cubic metres"], Dimension::Volume, 1000.0),
+    unit(&["gal", "gallon", "gallons"], Dimension::Volume, 3.785_411_784),
+    unit(&["qt", "quart", "quarts"], Dimension::Volume, 0.946_352_946),
+    unit(&["pt", "pint", "pints"], Dimension::Volume, 0.473_176_473),
+    unit(&["cup", "cups"], Dimension::Volume, 0.236_588_236_5),
+    unit(&["floz", "fl oz", "fluid ounce", "fluid ounces"], Dimension::Volume, 0.029_573_529_562_5),
+    unit(&["tbsp", "tablespoon", "tablespoons"], Dimension::Volume, 0.014_786_764_781_25),
+    unit(&["tsp", "teaspoon", "teaspoons"], Dimension::Volume, 0.004_928_921_593_75),
+
+    unit(&["m2", "m²", "sqm", "square meter", "square meters", "square metre", "square metres"], Dimension::Area, 1.0),
+    unit(&["km2", "km²", "square kilometer", "square kilometers", "square kilometre", "square kilometres"], Dimension::Area, 1e6),
+    unit(&["ft2", "ft²", "sqft", "square foot", "square feet"], Dimension::Area, 0.092_903_04),
+    unit(&["mi2", "mi²", "square mile", "square miles"], Dimension::Area, 2_589_988.110_336),
+    unit(&["ha", "hectare", "hectares"], Dimension::Area, 10_000.0),
+    unit(&["acre", "acres", "ac"], Dimension::Area, 4_046.856_422_4),
+
+    unit(&["m/s", "mps", "meters per second", "metres per second"], Dimension::Speed, 1.0),
+    unit(&["km/h", "kmh", "kph", "kilometers per hour", "kilometres per hour"], Dimension::Speed, 1000.0 / 3600.0),
+    unit(&["mph", "mi/h", "miles per hour"], Dimension::Speed, 0.447_04),
+    unit(&["kn", "kt", "knot", "knots"], Dimension::Speed, 1852.0 / 3600.0),
+    unit(&["ft/s", "fps", "feet per second"], Dimension::Speed, 0.3048),
+
+    unit(&["s", "sec", "secs", "second", "seconds"], Dimension::Time, 1.0),
+    unit(&["ms", "millisecond", "milliseconds"], Dimension::Time, 0.001),
+    unit(&["min", "mins", "minute", "minutes"], Dimension::Time, 60.0),
+    unit(&["h", "hr", "hrs", "hour", "hours"], Dimension::Time, 3600.0),
+    unit(&["d", "day", "days"], Dimension::Time, 86_400.0),
+    unit(&["wk", "week", "weeks"], Dimension::Time, 604_800.0),
+    unit(&["yr", "year", "years"], Dimension::Time, 31_557_600.0),
+
+    unit(&["B", "byte", "bytes"], Dimension::Data, 1.0),
+    unit(&["KB", "kB", "kilobyte", "kilobytes"], Dimension::Data, 1e3),
+    unit(&["MB", "megabyte", "megabytes"], Dimension::Data, 1e6),
+    unit(&["GB", "gigabyte", "gigabytes"], Dimension::Data, 1e9),
+    unit(&["TB", "terabyte", "terabytes"], Dimension::Data, 1e12),
+    unit(&["KiB", "kibibyte", "kibibytes"], Dimension::Data, 1024.0),
+    unit(&["MiB", "mebibyte", "mebibytes"], Dimension::Data, 1_048_576.0),
+    unit(&["GiB", "gibibyte", "gibibytes"], Dimension::Data, 1_073_741_824.0),
+    unit(&["TiB", "tebibyte", "tebibytes"], Dimension::Data, 1_099_511_627_776.0),
+    unit(&["bit", "bits"], Dimension::Data, 0.125),
+    unit(&["kbit", "Kbit", "kb", "kilobit", "kilobits"], Dimension::Data, 125.0),
+    unit(&["Mbit", "Mb", "megabit", "megabits"], Dimension::Data, 125_000.0),
+    unit(&["Gbit", "Gb", "gigabit", "gigabits"], Dimension::Data, 125_000_000.0),
+
+    unit(&["J", "joule", "joules"], Dimension::Energy, 1.0),
+    unit(&["kJ", "kilojoule", "kilojoules"], Dimension::Energy, 1000.0),
+    unit(&["cal", "calorie", "calories"], Dimension::Energy, 4.184),
+    unit(&["kcal", "Cal", "kilocalorie", "kilocalories"], Dimension::Energy, 4184.0),
+    unit(&["Wh", "watt hour", "watt hours"], Dimension::Energy, 3600.0),
+    unit(&["kWh", "kilowatt hour", "kilowatt hours"], Dimension::Energy, 3_600_000.0),
+    unit(&["BTU", "btu"], Dimension::Energy, 1_055.055_852_62),
+
+    unit(&["Pa", "pascal", "pascals"], Dimension::Pressure, 1.0),
+    unit(&["kPa", "kilopascal", "kilopascals"], Dimension::Pressure, 1000.0),
+    unit(&["hPa", "hectopascal", "hectopascals", "mbar", "millibar", "millibars"], Dimension::Pressure, 100.0),
+    unit(&["bar", "bars"], Dimension::Pressure, 100_000.0),
+    unit(&["atm", "atmosphere", "atmospheres"], Dimension::Pressure, 101_325.0),
+    unit(&["psi"], Dimension::Pressure, 6_894.757_293_168),
+    unit(&["mmHg", "torr"], Dimension::Pressure, 133.322_387_415),
+
+    Unit { names: &["K", "kelvin"], dimension: Dimension::Temperature, factor: 1.0, offset: 0.0 },
+    Unit { names: &["C", "°C", "celsius", "centigrade"], dimension: Dimension::Temperature, factor: 1.0, offset: 273.15 },
+    Unit { names: &["F", "°F", "fahrenheit"], dimension: Dimension::Temperature, factor: 5.0 / 9.0, offset: 459.67 },
+];
+
+/// Look a unit up by name: exact match first (so "MB" and "Mb" differ),
+/// then ignoring case.
+fn find_unit(name: &str) -> Result<&'static Unit> {
+    let name = name.trim().trim_start_matches("degrees ").trim();
+    UNITS
+        .iter()
+        .find(|u| u.names.contains(&name))
+        .or_else(|| UNITS.iter().find(|u| u.names.iter().any(|n| n.eq_ignore_ascii_case(name))))
+        .with_context(|| format!("unknown unit '{name}'"))
+}
+
+/// Convert `value` between two units of the same dimension.
+fn convert(value: f64, from: &str, to: &str) -> Result<f64> {
+    let (from_unit, to_unit) = (find_unit(from)?, find_unit(to)?);
+    if from_unit.dimension != to_unit.dimension {
+        bail!(
+            "can't convert {} ({}) to {} ({})",
+            from.trim(),
+            from_unit.dimension.name(),
+            to.trim(),
+            to_unit.dimension.name()
+        );
+    }
+    let base = (value + from_unit.offset) * from_unit.factor;
+    Ok(base / to_unit.factor - to_unit.offset)
+}
+
+// ─────────────────────────────────────────────
+// UnitConvertTool
+// ─────────────────────────────────────────────
+
+/// Tool that converts between units.
+pub struct UnitConvertTool;
+
+#[async_trait]
+impl Tool for UnitConvertTool {
+    fn name(&self) -> &str {
+        "unit_convert"
+    }
+
+    fn description(&self) -> &str {
+        "Convert a value between units of length, mass, volume, area, speed, time, \
+         data size, energy, pressure or temperature (e.g. 5 km to mi, 350 F to C, \
+         2 cups to ml, 1.5 GiB to MB)."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "value": {
+                    "type": "number",
+                    "description": "The amount to convert"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Source unit, e.g. 'km', 'lb', 'F'"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Target unit, e.g. 'mi', 'kg', 'C'"
+                }
+            },
+            "required": ["value", "from", "to"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> Result<String> {
+        let value = match params.get("value") {
+            Some(Value::Number(n)) => n.as_f64(),
+            Some(Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        }
+        .context("'value' must be a number")?;
+        let (from, to) = (require_string(&params, "from")?, require_string(&params, "to")?);
+        let result = convert(value, &from, &to)?;
+        Ok(format!(
+            "{} {} = {} {}",
+            format_number(value),
+            from.trim(),
+            format_number(result),
+            to.trim()
+        ))
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let cases = [
+            (5.0, "km", "mi", 3.106_855_961_186_9),
+            (100.0, "C", "F", 212.0),
+            (350.0, "°F", "celsius", 176.666_666_666_67),
+            (0.0, "K", "C", -273.15),
+            (2.0, "cups", "ml", 473.176_473),
+            (1.0, "GiB", "MB", 1_073.741_824),
+            (100.0, "Mb", "MB", 12.5),
+            (60.0, "mph", "km/h", 96.560_64),
+            (1.0, "kWh", "kcal", 860.420_650_095_6),
+            (1.0, "Acres", "m2", 4_046.856_422_4),
+        ];
+        for (value, from, to, expected) in cases {
+            let result = convert(value, from, to).unwrap();
+            assert!((result - expected).abs() < 1e-6, "{value} {from} -> {to} = {result}");
+        }
+
+        assert!(convert(1.0, "km", "kg").unwrap_err().to_string().contains("length"));
+        assert!(convert(1.0, "furlong", "m").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unit_convert_tool() {
+        let params: HashMap<String, Value> = [
+            ("value".to_string(), json!("5")),
+            ("from".to_string(), json!("km")),
+            ("to".to_string(), json!("m")),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(UnitConvertTool.execute(params).await.unwrap(), "5 km = 5000 m");
+    }
+}
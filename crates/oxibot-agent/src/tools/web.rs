@@ -1,15 +1,22 @@
-//! Web tools — search (Brave API) and fetch (HTTP content extraction).
+//! Web tools — search (Brave API, or the provider's own web search) and
+//! fetch (HTTP content extraction), and recall of cached pages.
 //!
 //! Port of nanobot's `agent/tools/web.py`.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::{json, Value};
-use tracing::debug;
+use tracing::{debug, warn};
 
-use super::base::{optional_i64, require_string, Tool};
+use oxibot_core::types::{LlmResponse, Message};
+use oxibot_providers::LlmRequestConfig;
+
+use super::base::{optional_i64, optional_string, require_string, Tool};
+use crate::page_cache::PageCache;
+use crate::routing::ModelRoute;
 
 /// User-Agent header.
 const USER_AGENT: &str =
@@ -21,14 +28,26 @@ const DEFAULT_MAX_CHARS: usize = 50_000;
 /// Max search results.
 const DEFAULT_MAX_RESULTS: usize = 5;
 
+/// Chunks returned by `recall_page` by default, and at most.
+const DEFAULT_RECALL_LIMIT: usize = 3;
+const MAX_RECALL_LIMIT: usize = 10;
+
+/// Instructions for provider-side searches.
+const NATIVE_SEARCH_PROMPT: &str = "Search the web for the user's query and answer it concisely \
+    from what you find. Include dates, numbers and names where relevant. Say so if the results \
+    don't answer the query.";
+
 // ─────────────────────────────────────────────
 // WebSearchTool (Brave API)
 // ─────────────────────────────────────────────
 
-/// Searches the web using the Brave Search API.
+/// Searches the web using the Brave Search API, or the provider's own web
+/// search when one is set with [`WebSearchTool::with_native`].
 pub struct WebSearchTool {
     api_key: Option<String>,
     client: Client,
+    /// Provider that searches itself (falls back to Brave when it fails).
+    native: Option<ModelRoute>,
 }
 
 impl WebSearchTool {
@@ -42,50 +61,40 @@ impl WebSearchTool {
                 .user_agent(USER_AGENT)
                 .build()
                 .unwrap_or_default(),
+            native: None,
         }
     }
 
+    /// Delegate searches to `route`, whose provider must support web
+    /// search. Brave is used when the provider call fails and a key is set.
+    pub fn with_native(mut self, route: Option<ModelRoute>) -> Self {
+        self.native = route;
+        self
+    }
+
     fn resolve_api_key(&self) -> Option<String> {
         self.api_key
             .clone()
             .or_else(|| std::env::var("BRAVE_API_KEY").ok())
     }
-}
 
-#[async_trait]
-impl Tool for WebSearchTool {
-    fn name(&self) -> &str {
-        "web_search"
-    }
-
-    fn description(&self) -> &str {
-        "Search the web using Brave Search API. Returns a numbered list of results with titles, URLs, and descriptions."
-    }
-
-    fn parameters(&self) -> Value {
-        json!({
-            "type": "object",
-            "properties": {
-                "query": {
-                    "type": "string",
-                    "description": "The search query"
-                },
-                "count": {
-                    "type": "integer",
-                    "description": "Number of results (1-10, default 5)",
-                    "minimum": 1,
-                    "maximum": 10
-                }
-            },
-            "required": ["query"]
-        })
+    /// Ask the provider to search and answer `query`.
+    async fn native_search(&self, route: &ModelRoute, query: &str) -> anyhow::Result<String> {
+        debug!(query = %query, provider = route.provider.display_name(), "searching web via provider");
+        let messages = [Message::system(NATIVE_SEARCH_PROMPT), Message::user(query)];
+        let config = LlmRequestConfig {
+            web_search: true,
+            ..Default::default()
+        };
+        let resp = route.provider.chat(&messages, None, &route.model, &config).await;
+        if resp.is_error() {
+            anyhow::bail!("{}", resp.content.unwrap_or_default());
+        }
+        Ok(format_native_answer(&resp))
     }
 
-    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
-        let query = require_string(&params, "query")?;
-        let count = optional_i64(&params, "count").unwrap_or(DEFAULT_MAX_RESULTS as i64) as usize;
-        let count = count.min(10).max(1);
-
+    /// Search with the Brave API.
+    async fn brave_search(&self, query: &str, count: usize) -> anyhow::Result<String> {
         let api_key = self
             .resolve_api_key()
             .ok_or_else(|| anyhow::anyhow!("No Brave API key configured (set BRAVE_API_KEY env var)"))?;
@@ -96,7 +105,7 @@ impl Tool for WebSearchTool {
             .client
             .get("https://api.search.brave.com/res/v1/web/search")
             .header("X-Subscription-Token", &api_key)
-            .query(&[("q", &query), ("count", &count.to_string())])
+            .query(&[("q", query), ("count", &count.to_string())])
             .send()
             .await
             .map_err(|e| anyhow::anyhow!("Brave API request failed: {e}"))?;
@@ -133,6 +142,76 @@ impl Tool for WebSearchTool {
     }
 }
 
+/// The provider's answer followed by a numbered list of the pages it cited.
+fn format_native_answer(resp: &LlmResponse) -> String {
+    let mut output = resp.content.clone().unwrap_or_default().trim().to_string();
+    if output.is_empty() {
+        output = "No results found.".into();
+    }
+    if !resp.citations.is_empty() {
+        output.push_str("\n\nSources:");
+        for (i, citation) in resp.citations.iter().enumerate() {
+            if citation.title.is_empty() {
+                output.push_str(&format!("\n{}. {}", i + 1, citation.url));
+            } else {
+                output.push_str(&format!("\n{}. {} — {}", i + 1, citation.title, citation.url));
+            }
+        }
+    }
+    output
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        if self.native.is_some() {
+            "Search the web. Returns an answer based on current web results, followed by the cited sources."
+        } else {
+            "Search the web using Brave Search API. Returns a numbered list of results with titles, URLs, and descriptions."
+        }
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The search query"
+                },
+                "count": {
+                    "type": "integer",
+                    "description": "Number of results (1-10, default 5)",
+                    "minimum": 1,
+                    "maximum": 10
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let query = require_string(&params, "query")?;
+        let count = optional_i64(&params, "count").unwrap_or(DEFAULT_MAX_RESULTS as i64) as usize;
+        let count = count.clamp(1, 10);
+
+        if let Some(route) = &self.native {
+            match self.native_search(route, &query).await {
+                Ok(answer) => return Ok(answer),
+                Err(e) if self.resolve_api_key().is_some() => {
+                    warn!(error = %e, "provider web search failed, falling back to Brave");
+                }
+                Err(e) => return Err(e.context("provider web search failed")),
+            }
+        }
+        self.brave_search(&query, count).await
+    }
+}
+
 // ─────────────────────────────────────────────
 // WebFetchTool
 // ─────────────────────────────────────────────
@@ -140,6 +219,8 @@ impl Tool for WebSearchTool {
 /// Fetches and extracts content from a web page.
 pub struct WebFetchTool {
     client: Client,
+    /// Extractions served again without a request (`None` = no cache).
+    cache: Option<Arc<PageCache>>,
 }
 
 impl WebFetchTool {
@@ -151,8 +232,15 @@ impl WebFetchTool {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .unwrap_or_default(),
+            cache: None,
         }
     }
+
+    /// Serve pages from `cache` while they are fresh, and cache new fetches.
+    pub fn with_cache(mut self, cache: Option<Arc<PageCache>>) -> Self {
+        self.cache = cache;
+        self
+    }
 }
 
 impl Default for WebFetchTool {
@@ -200,6 +288,23 @@ impl Tool for WebFetchTool {
             anyhow::bail!("Invalid URL: must start with http:// or https://");
         }
 
+        if let Some(page) = self.cache.as_ref().and_then(|cache| cache.get(&url)) {
+            debug!(url = %url, fetched_at = %page.fetched_at, "web page served from cache");
+            let (text, truncated) = truncate(page.text, max_chars);
+            let result = json!({
+                "url": url,
+                "finalUrl": page.final_url,
+                "status": 200,
+                "extractor": page.extractor,
+                "cached": true,
+                "fetchedAt": page.fetched_at.to_rfc3339(),
+                "truncated": truncated,
+                "length": text.len(),
+                "text": text,
+            });
+            return Ok(serde_json::to_string_pretty(&result).unwrap_or_default());
+        }
+
         debug!(url = %url, "fetching web page");
 
         let resp = self
@@ -240,13 +345,19 @@ impl Tool for WebFetchTool {
             (body, "raw")
         };
 
-        // Truncate
-        let truncated = text.len() > max_chars;
-        let text = if truncated {
-            text[..max_chars].to_string()
-        } else {
-            text
-        };
+        // Cache the whole extraction in the background (embedding may take a while)
+        if let Some(ref cache) = self.cache {
+            if (200..300).contains(&status) {
+                let (cache, url, final_url, text) = (cache.clone(), url.clone(), final_url.clone(), text.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = cache.put(&url, &final_url, extractor, &text).await {
+                        warn!(url = %url, error = %e, "failed to cache web page");
+                    }
+                });
+            }
+        }
+
+        let (text, truncated) = truncate(text, max_chars);
 
         let result = json!({
             "url": url,
@@ -262,6 +373,99 @@ impl Tool for WebFetchTool {
     }
 }
 
+/// `text` cut to `max_chars` bytes (at a char boundary), and whether it was cut.
+fn truncate(mut text: String, max_chars: usize) -> (String, bool) {
+    if text.len() <= max_chars {
+        return (text, false);
+    }
+    let mut end = max_chars;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    (text, true)
+}
+
+// ─────────────────────────────────────────────
+// RecallPageTool
+// ─────────────────────────────────────────────
+
+/// Searches pages fetched earlier (kept by [`PageCache`]) for the parts
+/// that answer a question, instead of fetching them again.
+pub struct RecallPageTool {
+    cache: Arc<PageCache>,
+}
+
+impl RecallPageTool {
+    pub fn new(cache: Arc<PageCache>) -> Self {
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl Tool for RecallPageTool {
+    fn name(&self) -> &str {
+        "recall_page"
+    }
+
+    fn description(&self) -> &str {
+        "Look up the passages of previously fetched web pages that match a question. \
+         Much cheaper than fetching a page again; use it for documentation you have \
+         already read with web_fetch, in this or an earlier conversation."
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "Only search this page (default: all cached pages)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Passages to return (default 3, max 10)",
+                    "minimum": 1,
+                    "maximum": 10
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, params: HashMap<String, Value>) -> anyhow::Result<String> {
+        let query = require_string(&params, "query")?;
+        let url = optional_string(&params, "url");
+        let limit = optional_i64(&params, "limit")
+            .map_or(DEFAULT_RECALL_LIMIT, |n| n.clamp(1, MAX_RECALL_LIMIT as i64) as usize);
+
+        let hits = self.cache.recall(&query, url.as_deref(), limit).await?;
+        if hits.is_empty() {
+            return Ok(match url {
+                Some(url) => format!("No cached passage of {url} matches. Use web_fetch to read it."),
+                None => "No cached page matches. Use web_fetch to read the page.".into(),
+            });
+        }
+        let passages: Vec<String> = hits
+            .iter()
+            .map(|hit| {
+                format!(
+                    "[{}] (fetched {}, score {:.2})\n{}",
+                    hit.url,
+                    hit.fetched_at.format("%Y-%m-%d %H:%M UTC"),
+                    hit.score,
+                    hit.text
+                )
+            })
+            .collect();
+        Ok(passages.join("\n\n---\n\n"))
+    }
+}
+
 // ─────────────────────────────────────────────
 // HTML helpers
 // ─────────────────────────────────────────────
@@ -296,9 +500,10 @@ fn strip_html_tags(html: &str) -> String {
                 in_style = true;
             } else if lower == "/style" {
                 in_style = false;
-            } else if lower == "br" || lower == "br/" || lower == "br /" {
-                result.push('\n');
-            } else if lower == "p" || lower == "/p" || lower == "div" || lower == "/div" {
+            } else if matches!(
+                lower.as_str(),
+                "br" | "br/" | "br /" | "p" | "/p" | "div" | "/div"
+            ) {
                 result.push('\n');
             }
             continue;
@@ -374,6 +579,62 @@ fn strip_html_tags(html: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
+
+    use oxibot_core::types::{Citation, ToolDefinition};
+    use oxibot_providers::LlmProvider;
+
+    /// Answers with citations, but only when asked to search.
+    struct SearchingProvider;
+
+    #[async_trait]
+    impl LlmProvider for SearchingProvider {
+        async fn chat(
+            &self,
+            messages: &[Message],
+            tools: Option<&[ToolDefinition]>,
+            _model: &str,
+            config: &LlmRequestConfig,
+        ) -> LlmResponse {
+            if !config.web_search || tools.is_some() || messages.len() != 2 {
+                return LlmResponse::error("expected a search-only request");
+            }
+            LlmResponse {
+                content: Some("Rust 1.90 is out.\n".into()),
+                citations: vec![
+                    Citation { url: "https://blog.rust-lang.org/".into(), title: "Rust Blog".into() },
+                    Citation { url: "https://releases.rs/".into(), title: String::new() },
+                ],
+                ..Default::default()
+            }
+        }
+
+        fn default_model(&self) -> &str {
+            "search-model"
+        }
+
+        fn display_name(&self) -> &str {
+            "Searching"
+        }
+
+        fn supports_web_search(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_web_search_native() {
+        let route = ModelRoute::new(Arc::new(SearchingProvider), "search-model");
+        let tool = WebSearchTool::new(None).with_native(Some(route));
+        assert!(tool.description().contains("cited sources"));
+
+        let params = HashMap::from([("query".to_string(), json!("latest rust release"))]);
+        let output = tool.execute(params).await.unwrap();
+        assert_eq!(
+            output,
+            "Rust 1.90 is out.\n\nSources:\n1. Rust Blog — https://blog.rust-lang.org/\n2. https://releases.rs/"
+        );
+    }
 
     #[test]
     fn test_strip_html_basic() {
@@ -439,6 +700,58 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Invalid URL"));
     }
 
+    #[tokio::test]
+    async fn test_web_fetch_cache_and_recall() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/docs"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                "<html><p>Rate limits</p><p>Each API key may send 60 requests per minute.</p></html>",
+                "text/html",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Arc::new(PageCache::new(dir.path().to_path_buf(), 24));
+        let tool = WebFetchTool::new().with_cache(Some(cache.clone()));
+        let url = format!("{}/docs", server.uri());
+        let params = HashMap::from([("url".to_string(), json!(url))]);
+
+        let first: Value = serde_json::from_str(&tool.execute(params.clone()).await.unwrap()).unwrap();
+        assert!(first.get("cached").is_none());
+        for _ in 0..100 {
+            if cache.get(&url).is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        // Served from the cache: the server expects a single request
+        let second: Value = serde_json::from_str(&tool.execute(params).await.unwrap()).unwrap();
+        assert_eq!(second["cached"], true);
+        assert_eq!(second["text"], first["text"]);
+
+        let recall = RecallPageTool::new(cache);
+        let out = recall
+            .execute(HashMap::from([("query".to_string(), json!("how many requests per minute?"))]))
+            .await
+            .unwrap();
+        assert!(out.starts_with(&format!("[{url}] (fetched ")), "{out}");
+        assert!(out.contains("60 requests per minute"));
+        let out = recall
+            .execute(HashMap::from([
+                ("query".to_string(), json!("requests")),
+                ("url".to_string(), json!("https://example.com/unseen")),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(out, "No cached passage of https://example.com/unseen matches. Use web_fetch to read it.");
+    }
+
     #[tokio::test]
     async fn test_web_search_no_api_key() {
         // Unset the env var to ensure no key
@@ -0,0 +1,116 @@
+//! Translation — talk to senders in languages the operator doesn't read.
+//!
+//! On channels listed in `channels.translation.channels`, each incoming
+//! message goes through one call on the translation model, which names
+//! its language and, unless the operator reads it, translates it into the
+//! first operator language. The agent then sees (and the session keeps)
+//! the translation followed by the original. The sender's language is
+//! remembered on the session, and replies are translated back into it.
+
+use serde::Deserialize;
+
+use oxibot_core::types::Message;
+
+/// Session metadata field holding the sender's language (ISO 639-1).
+pub const LANGUAGE_KEY: &str = "language";
+
+/// What the detection call found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detected {
+    /// Language of the message (ISO 639-1, lowercase).
+    pub language: String,
+    /// The message in the operator's language (`None` when the operator
+    /// reads the original).
+    pub translation: Option<String>,
+}
+
+/// Whether `language` is one of `operator_languages`, comparing the
+/// primary subtag only ("pt-BR" matches "pt").
+pub fn is_operator_language(language: &str, operator_languages: &[String]) -> bool {
+    let primary = |code: &str| code.split(['-', '_']).next().unwrap_or_default().trim().to_lowercase();
+    let language = primary(language);
+    operator_languages.iter().any(|l| primary(l) == language)
+}
+
+/// Messages for the detection call.
+pub fn detection_messages(text: &str, operator_languages: &[String]) -> Vec<Message> {
+    let languages = operator_languages.join(", ");
+    let target = operator_languages.first().map_or("en", String::as_str);
+    let system = format!(
+        "Identify the language of the user's message and give its ISO 639-1 code. If the \
+         language is one of: {languages}, respond with JSON only: {{\"language\": \"<code>\"}}. \
+         Otherwise translate the message into the language with code \"{target}\", keeping \
+         formatting, code, links, numbers and names unchanged, and respond with JSON only: \
+         {{\"language\": \"<code>\", \"translation\": \"<translated message>\"}}"
+    );
+    vec![Message::system(system), Message::user(text)]
+}
+
+#[derive(Deserialize)]
+struct Detection {
+    language: String,
+    #[serde(default)]
+    translation: Option<String>,
+}
+
+/// Parse the detection output. A translation is dropped when the language
+/// turns out to be an operator language.
+pub fn parse_detection(output: &str, operator_languages: &[String]) -> Option<Detected> {
+    let start = output.find('{')?;
+    let end = output.rfind('}')?;
+    let parsed: Detection = serde_json::from_str(output.get(start..=end)?).ok()?;
+    let language = parsed.language.trim().to_lowercase();
+    if language.is_empty() {
+        return None;
+    }
+    let translation = parsed
+        .translation
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty() && !is_operator_language(&language, operator_languages));
+    Some(Detected { language, translation })
+}
+
+/// Messages for translating a reply into `language`.
+pub fn reply_messages(text: &str, language: &str) -> Vec<Message> {
+    let system = format!(
+        "Translate the assistant's message below into the language with ISO 639-1 code \
+         \"{language}\". Keep formatting, code, links, numbers and names unchanged. Respond \
+         with the translation only."
+    );
+    vec![Message::system(system), Message::user(text)]
+}
+
+/// The text the agent sees for a translated message: the translation,
+/// then the original.
+pub fn with_original(translation: &str, language: &str, original: &str) -> String {
+    format!("{translation}\n\n[Translated from \"{language}\". Original: {original}]")
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_detection() {
+        let operator = vec!["en".to_string(), "es".to_string()];
+        assert!(is_operator_language("ES", &operator));
+        assert!(is_operator_language("en-GB", &operator));
+        assert!(!is_operator_language("pt-BR", &operator));
+
+        let detected =
+            parse_detection("```json\n{\"language\": \"DE\", \"translation\": \"Where is my order?\"}\n```", &operator).unwrap();
+        assert_eq!(detected.language, "de");
+        assert_eq!(detected.translation.as_deref(), Some("Where is my order?"));
+
+        // Operator languages are left alone, even if the model translated them
+        let detected = parse_detection("{\"language\": \"es\", \"translation\": \"Hello\"}", &operator).unwrap();
+        assert_eq!(detected.translation, None);
+
+        assert_eq!(parse_detection("no idea", &operator), None);
+        assert_eq!(parse_detection("{\"language\": \"\"}", &operator), None);
+    }
+}
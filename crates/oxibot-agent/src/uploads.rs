@@ -0,0 +1,257 @@
+//! Document uploads — attach documents by provider file id.
+//!
+//! Documents sent to the agent (PDFs by default) are uploaded to the
+//! provider's file store and passed to the model as file references
+//! instead of inlined data. Each conversation remembers its uploads in the
+//! session, so the documents stay attached on later turns without being
+//! uploaded again. Only the most recent `maxPerSession` are kept; older
+//! ones are deleted from the provider.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use oxibot_core::config::schema::FileUploadConfig;
+use oxibot_core::session::manager::SessionManager;
+use oxibot_core::types::FileRef;
+use oxibot_providers::FileUploader;
+
+/// Session metadata field holding the conversation's uploads (JSON).
+pub const SESSION_FIELD: &str = "files";
+
+/// A document uploaded for a conversation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UploadedFile {
+    pub file_id: String,
+    pub filename: String,
+    /// SHA-256 of the contents, so a document sent twice is uploaded once.
+    pub sha256: String,
+}
+
+impl UploadedFile {
+    pub fn to_ref(&self) -> FileRef {
+        FileRef {
+            file_id: self.file_id.clone(),
+            filename: Some(self.filename.clone()),
+        }
+    }
+}
+
+/// Uploads documents and tracks them per conversation.
+pub struct FileUploads {
+    uploader: Arc<dyn FileUploader>,
+    sessions: Arc<SessionManager>,
+    config: FileUploadConfig,
+}
+
+impl FileUploads {
+    pub fn new(uploader: Arc<dyn FileUploader>, sessions: Arc<SessionManager>, config: FileUploadConfig) -> Self {
+        Self {
+            uploader,
+            sessions,
+            config,
+        }
+    }
+
+    /// Whether `path` has one of the configured extensions.
+    pub fn is_document(&self, path: &str) -> bool {
+        let ext = Path::new(path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        self.config.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+    }
+
+    /// The conversation's uploads, oldest first.
+    pub fn list(&self, session_key: &str) -> Vec<UploadedFile> {
+        self.sessions
+            .metadata(session_key, SESSION_FIELD)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Upload the documents among `media` (removing them from it) and
+    /// return references to all of the conversation's uploads.
+    ///
+    /// Documents that can't be uploaded stay in `media`.
+    pub async fn attach(&self, session_key: &str, media: &mut Vec<String>) -> Vec<FileRef> {
+        let mut files = self.list(session_key);
+        let before = files.clone();
+        let mut kept = Vec::new();
+        for path in std::mem::take(media) {
+            if !self.is_document(&path) {
+                kept.push(path);
+                continue;
+            }
+            match self.upload(&path, &files).await {
+                Ok(file) => {
+                    files.retain(|f| f.sha256 != file.sha256);
+                    files.push(file);
+                }
+                Err(e) => {
+                    warn!(path = %path, error = %format!("{e:#}"), "document upload failed");
+                    kept.push(path);
+                }
+            }
+        }
+        *media = kept;
+
+        if files != before {
+            let evicted = files.len().saturating_sub(self.config.max_per_session.max(1));
+            self.delete_remote(files.drain(..evicted).collect());
+            self.save(session_key, &files);
+        }
+        files.iter().map(UploadedFile::to_ref).collect()
+    }
+
+    /// Drop the conversation's uploads and delete them from the provider.
+    /// Returns how many there were.
+    pub fn clear(&self, session_key: &str) -> usize {
+        let files = self.list(session_key);
+        let count = files.len();
+        self.sessions.set_metadata(session_key, SESSION_FIELD, None);
+        self.delete_remote(files);
+        count
+    }
+
+    async fn upload(&self, path: &str, known: &[UploadedFile]) -> anyhow::Result<UploadedFile> {
+        let data = tokio::fs::read(path).await?;
+        if data.len() as u64 > self.config.max_file_mb * 1024 * 1024 {
+            anyhow::bail!("larger than {} MB", self.config.max_file_mb);
+        }
+        let sha256 = hex(&Sha256::digest(&data));
+        if let Some(file) = known.iter().find(|f| f.sha256 == sha256) {
+            debug!(file_id = %file.file_id, "document already uploaded");
+            return Ok(file.clone());
+        }
+
+        let filename = Path::new(path)
+            .file_name()
+            .map_or_else(|| "document".to_string(), |n| n.to_string_lossy().into_owned());
+        let file_id = self.uploader.upload(&filename, data, mime_type(&filename)).await?;
+        info!(file_id = %file_id, filename = %filename, provider = self.uploader.display_name(), "document uploaded");
+        Ok(UploadedFile {
+            file_id,
+            filename,
+            sha256,
+        })
+    }
+
+    fn save(&self, session_key: &str, files: &[UploadedFile]) {
+        let json = serde_json::to_string(files).expect("uploads serialize");
+        self.sessions.set_metadata(session_key, SESSION_FIELD, Some(&json));
+    }
+
+    /// Delete files from the provider in the background (best-effort).
+    fn delete_remote(&self, files: Vec<UploadedFile>) {
+        if files.is_empty() {
+            return;
+        }
+        let uploader = self.uploader.clone();
+        tokio::spawn(async move {
+            for file in files {
+                if let Err(e) = uploader.delete(&file.file_id).await {
+                    debug!(file_id = %file.file_id, error = %e, "failed to delete uploaded file");
+                }
+            }
+        });
+    }
+}
+
+fn mime_type(filename: &str) -> &'static str {
+    let ext = Path::new(filename)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "txt" | "md" => "text/plain",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "application/octet-stream",
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records uploads and deletions.
+    #[derive(Default)]
+    struct FakeUploader {
+        uploaded: Mutex<Vec<String>>,
+        deleted: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl FileUploader for FakeUploader {
+        async fn upload(&self, filename: &str, _data: Vec<u8>, mime: &str) -> anyhow::Result<String> {
+            assert_eq!(mime, "application/pdf");
+            let mut uploaded = self.uploaded.lock().unwrap();
+            uploaded.push(filename.to_string());
+            Ok(format!("file-{}", uploaded.len()))
+        }
+
+        async fn delete(&self, file_id: &str) -> anyhow::Result<()> {
+            self.deleted.lock().unwrap().push(file_id.to_string());
+            Ok(())
+        }
+
+        fn display_name(&self) -> &str {
+            "Fake"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_attach_uploads_once_and_evicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = Arc::new(SessionManager::new(Some(dir.path().join("sessions"))).unwrap());
+        let uploader = Arc::new(FakeUploader::default());
+        let config = FileUploadConfig {
+            enabled: true,
+            max_per_session: 2,
+            ..Default::default()
+        };
+        let uploads = FileUploads::new(uploader.clone(), sessions, config);
+
+        let pdf = |name: &str, body: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, body).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let photo = pdf("photo.jpg", "jpeg");
+        let mut media = vec![pdf("a.pdf", "A"), photo.clone()];
+        let refs = uploads.attach("s", &mut media).await;
+        assert_eq!(media, [photo]);
+        assert_eq!(refs[0].file_id, "file-1");
+        assert_eq!(refs[0].filename.as_deref(), Some("a.pdf"));
+
+        // Same contents under another name: not uploaded again
+        let refs = uploads.attach("s", &mut vec![pdf("copy.PDF", "A")]).await;
+        assert_eq!(refs.len(), 1);
+        assert_eq!(uploader.uploaded.lock().unwrap().len(), 1);
+
+        // Later turns keep the documents; the oldest is evicted past the limit
+        uploads.attach("s", &mut vec![pdf("b.pdf", "B"), pdf("c.pdf", "C")]).await;
+        let ids: Vec<String> = uploads.attach("s", &mut Vec::new()).await.into_iter().map(|r| r.file_id).collect();
+        assert_eq!(ids, ["file-2", "file-3"]);
+
+        assert_eq!(uploads.clear("s"), 2);
+        assert!(uploads.list("s").is_empty());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(*uploader.deleted.lock().unwrap(), ["file-1", "file-2", "file-3"]);
+    }
+}
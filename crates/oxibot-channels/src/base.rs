@@ -7,9 +7,42 @@
 //! - `stop()` — graceful shutdown
 //! - `send()` — deliver an outbound message to the channel
 //! - `name()` — channel identifier matching config keys
+//! - `acknowledge()` — optionally react to a message the agent picked up
+//!
+//! Also provides `FollowUpWindows`, short-lived per-conversation state that
+//! lets a user keep talking to the bot in a group without re-mentioning it,
+//! `ProgressMessages`, which remembers the message edited by progress
+//! updates, `ActivityTracker`, which backs a channel's `HealthReport`, the
+//! `TranscribeFn` callback channels use for voice messages, and the labels
+//! of approval buttons.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::bus::Decision;
+use oxibot_core::config::schema::AckConfig;
+use oxibot_core::types::Transcript;
+use tracing::{debug, warn};
+
+/// Text posted by channels acknowledging with a reply.
+pub const ACK_REPLY_TEXT: &str = "…";
+
+/// Label of the button that allows an approval request.
+pub const APPROVE_BUTTON_TEXT: &str = "✅ Allow";
+
+/// Label of the button that denies an approval request.
+pub const DENY_BUTTON_TEXT: &str = "❌ Deny";
+
+/// An approval question with its answer, replacing the buttons once one is
+/// pressed.
+pub fn answered_prompt(prompt: &str, decision: Decision) -> String {
+    format!("{prompt}\n\n{}", decision.label())
+}
 
 /// Every chat channel implements this trait.
 ///
@@ -37,6 +70,300 @@ pub trait Channel: Send + Sync {
     /// Called by the `ChannelManager`'s outbound dispatcher when
     /// it receives a message targeted at this channel.
     async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()>;
+
+    /// Whether the channel can edit sent messages.
+    ///
+    /// Only such channels receive progress updates (messages carrying a
+    /// `progress_id`); they must edit the existing message for that id
+    /// instead of posting a new one.
+    fn supports_edits(&self) -> bool {
+        false
+    }
+
+    /// Whether `send` uploads the files in `OutboundMessage.media`.
+    ///
+    /// On such channels the `ChannelManager` sends long code blocks as
+    /// file attachments (see [`crate::code_files`]).
+    fn supports_uploads(&self) -> bool {
+        false
+    }
+
+    /// Show the sender that the agent started working on their message.
+    ///
+    /// `ack` is an acknowledgment (see `OutboundMessage::acknowledgment`)
+    /// carrying the inbound message's metadata and id. Channels react to
+    /// the message or post a quick reply as configured; the default does
+    /// nothing.
+    async fn acknowledge(&self, _ack: &OutboundMessage) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Longest message the platform accepts, in characters (`None` = no
+    /// limit). The `ChannelManager` splits longer replies and paces the
+    /// parts.
+    fn max_message_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Show a typing indicator in `chat_id` (best-effort; the default does
+    /// nothing). Used while waiting between the parts of a long reply.
+    async fn typing(&self, _chat_id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Current connection health, for channels that track it.
+    ///
+    /// The `ChannelManager` restarts a channel whose report shows no
+    /// activity for longer than `channels.supervisor.maxSilenceSecs`.
+    fn health(&self) -> Option<HealthReport> {
+        None
+    }
+
+    /// Ask the platform whether the channel's credentials still work
+    /// (`None` = the channel has no such check).
+    ///
+    /// The `ChannelManager` calls this on channels that failed to send, to
+    /// tell when they are back.
+    async fn check(&self) -> Option<anyhow::Result<()>> {
+        None
+    }
+}
+
+// ─────────────────────────────────────────────
+// Acknowledgment
+// ─────────────────────────────────────────────
+
+/// How a channel acknowledges messages, parsed from its `AckConfig`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AckMode {
+    /// React to the message with this emoji.
+    Reaction(String),
+    /// Post `ACK_REPLY_TEXT` in the conversation.
+    Reply,
+    /// Don't acknowledge.
+    Off,
+}
+
+impl AckMode {
+    /// Parse `config`, reacting with `default_emoji` when none is set.
+    ///
+    /// Unknown modes are logged and treated as `Off`.
+    pub fn from_config(config: &AckConfig, default_emoji: &str) -> Self {
+        match config.mode.as_str() {
+            "reaction" if config.emoji.is_empty() => Self::Reaction(default_emoji.to_string()),
+            "reaction" => Self::Reaction(config.emoji.clone()),
+            "reply" => Self::Reply,
+            "off" | "" => Self::Off,
+            other => {
+                warn!(mode = other, "unknown acknowledge mode (use reaction, reply or off)");
+                Self::Off
+            }
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Health
+// ─────────────────────────────────────────────
+
+/// Snapshot of a channel's connection health.
+#[derive(Clone, Copy, Debug)]
+pub struct HealthReport {
+    /// Last time the channel heard from its platform (a poll, a heartbeat
+    /// ACK, an event).
+    pub last_activity: Instant,
+}
+
+/// Records platform activity for `Channel::health`.
+pub struct ActivityTracker(Mutex<Instant>);
+
+impl ActivityTracker {
+    /// Create a tracker that counts now as the last activity.
+    pub fn new() -> Self {
+        Self(Mutex::new(Instant::now()))
+    }
+
+    /// Record activity now.
+    pub fn touch(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Health report with the last recorded activity.
+    pub fn report(&self) -> HealthReport {
+        HealthReport {
+            last_activity: *self.0.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+impl Default for ActivityTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Transcription
+// ─────────────────────────────────────────────
+
+/// Callback for voice/audio transcription.
+///
+/// Receives a file path, returns the transcript (text plus duration/confidence).
+pub type TranscribeFn = Arc<
+    dyn Fn(String) -> Pin<Box<dyn Future<Output = anyhow::Result<Transcript>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Transcribe an audio file, logging failures. Returns `None` when there is
+/// no transcriber, the call fails, or nothing was said.
+pub async fn transcribe(transcriber: Option<&TranscribeFn>, path: &str) -> Option<Transcript> {
+    match transcriber?(path.to_string()).await {
+        Ok(t) if !t.text.is_empty() => {
+            debug!(
+                path = %path,
+                chars = t.text.len(),
+                chunks = t.chunks,
+                "voice transcribed"
+            );
+            Some(t)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!(error = %e, "voice transcription failed");
+            None
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Follow-up windows
+// ─────────────────────────────────────────────
+
+/// Per-conversation follow-up windows for group chats.
+///
+/// After the bot is mentioned in a group conversation (a chat, or a thread
+/// within it), the same user may keep talking without mentioning the bot
+/// until the window expires. Each reply from the bot extends the window.
+/// Expired entries are pruned lazily.
+pub struct FollowUpWindows {
+    /// Window length (zero = disabled).
+    window: Duration,
+    /// Conversation scope → user → window expiry.
+    open: Mutex<HashMap<String, HashMap<String, Instant>>>,
+}
+
+impl FollowUpWindows {
+    /// Create follow-up windows of the given length (zero disables them).
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether follow-up windows are enabled.
+    pub fn is_enabled(&self) -> bool {
+        !self.window.is_zero()
+    }
+
+    /// Open (or restart) the window for `user` in `scope`.
+    pub fn open(&self, scope: &str, user: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        Self::prune(&mut open);
+        open.entry(scope.to_string())
+            .or_default()
+            .insert(user.to_string(), Instant::now() + self.window);
+    }
+
+    /// Restart every open window in `scope` (called when the bot replies).
+    pub fn extend(&self, scope: &str) {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        Self::prune(&mut open);
+        if let Some(users) = open.get_mut(scope) {
+            let expiry = Instant::now() + self.window;
+            users.values_mut().for_each(|e| *e = expiry);
+        }
+    }
+
+    /// Whether `user` may talk in `scope` without mentioning the bot.
+    pub fn is_open(&self, scope: &str, user: &str) -> bool {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        Self::prune(&mut open);
+        open.get(scope).is_some_and(|users| users.contains_key(user))
+    }
+
+    fn prune(open: &mut HashMap<String, HashMap<String, Instant>>) {
+        let now = Instant::now();
+        open.retain(|_, users| {
+            users.retain(|_, expiry| *expiry > now);
+            !users.is_empty()
+        });
+    }
+}
+
+// ─────────────────────────────────────────────
+// Progress messages
+// ─────────────────────────────────────────────
+
+/// How long a progress message may go without an update before it is
+/// forgotten. Running commands post updates every few seconds.
+pub const PROGRESS_MAX_IDLE: Duration = Duration::from_secs(600);
+
+/// The message each progress id was posted as, so later updates edit it.
+///
+/// An entry is dropped by the final ("done") update. If that update never
+/// arrives (dropped on a full outbound queue), the entry is pruned once it
+/// has gone `max_idle` without an update.
+pub struct ProgressMessages<T> {
+    /// Progress id → (last update, message id).
+    sent: Mutex<HashMap<String, (Instant, T)>>,
+    max_idle: Duration,
+}
+
+impl<T: Clone> ProgressMessages<T> {
+    pub fn new() -> Self {
+        Self {
+            sent: Mutex::new(HashMap::new()),
+            max_idle: PROGRESS_MAX_IDLE,
+        }
+    }
+
+    /// Forget progress messages after `max_idle` without an update.
+    pub fn with_max_idle(mut self, max_idle: Duration) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// The message posted for `progress_id`, if any. Counts as an update.
+    pub fn get(&self, progress_id: &str) -> Option<T> {
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        let (updated, id) = sent.get_mut(progress_id)?;
+        *updated = Instant::now();
+        Some(id.clone())
+    }
+
+    /// Remember that `progress_id` was posted as `message_id`, and forget
+    /// progress messages that have gone idle.
+    pub fn insert(&self, progress_id: &str, message_id: T) {
+        let mut sent = self.sent.lock().unwrap_or_else(|e| e.into_inner());
+        sent.retain(|_, (updated, _)| updated.elapsed() < self.max_idle);
+        sent.insert(progress_id.to_string(), (Instant::now(), message_id));
+    }
+
+    /// Forget `progress_id` (its final update was sent).
+    pub fn finish(&self, progress_id: &str) {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).remove(progress_id);
+    }
+}
+
+impl<T: Clone> Default for ProgressMessages<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -105,6 +432,81 @@ mod tests {
         assert!(ch.stopped.load(Ordering::SeqCst));
     }
 
+    #[test]
+    fn test_follow_up_window() {
+        let windows = FollowUpWindows::new(Duration::from_millis(100));
+        assert!(!windows.is_open("C1:T1", "alice"));
+
+        windows.open("C1:T1", "alice");
+        assert!(windows.is_open("C1:T1", "alice"));
+        assert!(!windows.is_open("C1:T1", "bob"));
+        assert!(!windows.is_open("C1:T2", "alice"));
+
+        std::thread::sleep(Duration::from_millis(60));
+        windows.extend("C1:T1");
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(windows.is_open("C1:T1", "alice"));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!windows.is_open("C1:T1", "alice"));
+    }
+
+    #[tokio::test]
+    async fn test_ack_mode() {
+        let config = |mode: &str, emoji: &str| AckConfig {
+            mode: mode.into(),
+            emoji: emoji.into(),
+        };
+        assert_eq!(AckMode::from_config(&AckConfig::default(), "👀"), AckMode::Reaction("👀".into()));
+        assert_eq!(AckMode::from_config(&config("reaction", "🤔"), "👀"), AckMode::Reaction("🤔".into()));
+        assert_eq!(AckMode::from_config(&config("reply", ""), "👀"), AckMode::Reply);
+        assert_eq!(AckMode::from_config(&config("off", ""), "👀"), AckMode::Off);
+        assert_eq!(AckMode::from_config(&config("confetti", ""), "👀"), AckMode::Off);
+
+        // Channels without acknowledgments ignore them
+        let ch = MockChannel::new();
+        let ack = OutboundMessage::new("mock", "chat_1", "");
+        ch.acknowledge(&ack).await.unwrap();
+        assert!(ch.sent.lock().await.is_empty());
+    }
+
+    #[test]
+    fn test_follow_up_window_disabled() {
+        let windows = FollowUpWindows::new(Duration::ZERO);
+        windows.open("C1", "alice");
+        assert!(!windows.is_open("C1", "alice"));
+    }
+
+    #[test]
+    fn test_progress_messages_pruned_when_idle() {
+        let progress = ProgressMessages::new().with_max_idle(Duration::from_millis(50));
+        progress.insert("exec-1", 10);
+        progress.insert("exec-2", 20);
+        assert_eq!(progress.get("exec-1"), Some(10));
+
+        progress.finish("exec-1");
+        assert_eq!(progress.get("exec-1"), None);
+
+        // The "done" update for exec-2 never arrived; exec-3 keeps updating
+        std::thread::sleep(Duration::from_millis(30));
+        progress.insert("exec-3", 30);
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(progress.get("exec-3"), Some(30));
+        progress.insert("exec-4", 40);
+        assert_eq!(progress.get("exec-2"), None);
+        assert_eq!(progress.get("exec-3"), Some(30));
+    }
+
+    #[test]
+    fn test_activity_tracker() {
+        let tracker = ActivityTracker::new();
+        let before = tracker.report().last_activity;
+        std::thread::sleep(Duration::from_millis(5));
+        tracker.touch();
+        assert!(tracker.report().last_activity > before);
+        assert!(MockChannel::new().health().is_none());
+    }
+
     #[tokio::test]
     async fn test_mock_channel_send() {
         let ch = MockChannel::new();
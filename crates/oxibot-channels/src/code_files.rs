@@ -0,0 +1,279 @@
+//! Code files — long code blocks go out as attachments, not walls of text.
+//!
+//! A reply holding a 300-line script would be split into a dozen messages
+//! on Telegram or Discord. [`CodeFiles`] takes fenced code blocks of at
+//! least `channels.codeFiles.minLines` lines out of a message, writes each
+//! to a file attached to the message, and leaves a one-line summary in its
+//! place. The `ChannelManager` applies it on channels that can upload files
+//! (see [`Channel::supports_uploads`](crate::base::Channel::supports_uploads)).
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, warn};
+
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::schema::CodeFilesConfig;
+use oxibot_core::types::MediaAttachment;
+
+/// A closed fenced code block in a message.
+#[derive(Debug, PartialEq)]
+struct CodeBlock {
+    /// Byte offset of the opening fence.
+    start: usize,
+    /// Byte offset just past the closing fence (before its line break).
+    end: usize,
+    /// Language of the info string, lowercased (empty when none).
+    language: String,
+    /// File name given after the language (` ```rust src/main.rs`).
+    name: Option<String>,
+    /// The code between the fences.
+    code: String,
+}
+
+/// Writes long code blocks of outbound messages to files and attaches them.
+#[derive(Clone, Debug)]
+pub struct CodeFiles {
+    config: CodeFilesConfig,
+    /// Files go to a new folder under it for every message.
+    dir: PathBuf,
+}
+
+impl CodeFiles {
+    pub fn new(config: CodeFilesConfig, dir: PathBuf) -> Self {
+        Self { config, dir }
+    }
+
+    /// Move the long code blocks of `msg` into attachments, replacing each
+    /// with a summary line. Returns how many were moved.
+    ///
+    /// Progress updates and approval requests are left alone.
+    pub fn apply(&self, msg: &mut OutboundMessage) -> usize {
+        if !self.config.enabled || msg.progress_id().is_some() || msg.approval().is_some() {
+            return 0;
+        }
+        let blocks: Vec<CodeBlock> = code_blocks(&msg.content)
+            .into_iter()
+            .filter(|b| self.wanted(b))
+            .collect();
+        if blocks.is_empty() {
+            return 0;
+        }
+
+        let folder = self
+            .dir
+            .join(chrono::Local::now().format("%Y%m%d-%H%M%S-%f").to_string());
+        if let Err(e) = std::fs::create_dir_all(&folder) {
+            warn!(dir = %folder.display(), error = %e, "cannot create folder for code files, sending code inline");
+            return 0;
+        }
+
+        let mut content = String::with_capacity(msg.content.len());
+        let mut last = 0;
+        let mut names = HashSet::new();
+        let mut attached = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            let mut name = block
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("code-{}.{}", i + 1, extension(&block.language)));
+            if !names.insert(name.clone()) {
+                name = format!("{}-{name}", i + 1);
+                names.insert(name.clone());
+            }
+            let path = folder.join(&name);
+            if let Err(e) = std::fs::write(&path, &block.code) {
+                warn!(path = %path.display(), error = %e, "cannot write code file, sending code inline");
+                continue;
+            }
+
+            content.push_str(&msg.content[last..block.start]);
+            content.push_str(&summary(&name, &block.language, block.code.lines().count()));
+            last = block.end;
+            attached.push(MediaAttachment {
+                mime_type: "text/plain".into(),
+                path: path.to_string_lossy().into_owned(),
+                filename: Some(name),
+                size: Some(block.code.len() as u64),
+            });
+        }
+        content.push_str(&msg.content[last..]);
+
+        let count = attached.len();
+        msg.content = content;
+        msg.media.extend(attached);
+        debug!(channel = %msg.channel, chat_id = %msg.chat_id, files = count, "sending code blocks as files");
+        count
+    }
+
+    /// Whether `block` is long enough, and in one of the allowed languages.
+    fn wanted(&self, block: &CodeBlock) -> bool {
+        block.code.lines().count() >= self.config.min_lines.max(1)
+            && (self.config.languages.is_empty()
+                || self
+                    .config
+                    .languages
+                    .iter()
+                    .any(|l| l.eq_ignore_ascii_case(&block.language)))
+    }
+}
+
+/// The line standing in for a block sent as `name`.
+fn summary(name: &str, language: &str, lines: usize) -> String {
+    if language.is_empty() {
+        format!("📎 {name} ({lines} lines, attached)")
+    } else {
+        format!("📎 {name} ({lines} lines of {language}, attached)")
+    }
+}
+
+/// The closed ```-fenced code blocks of `text`, in order.
+fn code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    // (opening fence offset, info string, code offset)
+    let mut open: Option<(usize, &str, usize)> = None;
+    let mut pos = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        match open {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```").filter(|i| !i.contains('`')) {
+                    open = Some((pos + line.find('`').unwrap_or(0), info, pos + line.len()));
+                }
+            }
+            Some((start, info, code_start)) if trimmed == "```" => {
+                let (language, name) = parse_info(info);
+                blocks.push(CodeBlock {
+                    start,
+                    end: pos + line.trim_end().len(),
+                    language,
+                    name,
+                    code: text[code_start..pos].to_string(),
+                });
+                open = None;
+            }
+            Some(_) => {}
+        }
+        pos += line.len();
+    }
+    blocks
+}
+
+/// Language and file name of an info string (`rust`, `rust src/main.rs`,
+/// `python:tools/fetch.py`). Only plain file names are kept.
+fn parse_info(info: &str) -> (String, Option<String>) {
+    let mut words = info.split(|c: char| c.is_whitespace() || c == ':').filter(|w| !w.is_empty());
+    let language = words.next().unwrap_or_default().to_lowercase();
+    let name = words
+        .find(|w| w.contains('.'))
+        .and_then(|w| Path::new(w).file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .filter(|n| {
+            !n.starts_with('.')
+                && n.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        });
+    (language, name)
+}
+
+/// File extension for code in `language`.
+fn extension(language: &str) -> &str {
+    match language {
+        "rust" => "rs",
+        "python" => "py",
+        "javascript" | "node" => "js",
+        "typescript" => "ts",
+        "shell" | "bash" | "zsh" | "console" => "sh",
+        "yaml" => "yml",
+        "golang" => "go",
+        "kotlin" => "kt",
+        "c++" => "cpp",
+        "csharp" | "c#" => "cs",
+        "ruby" => "rb",
+        "markdown" => "md",
+        "" | "text" | "plaintext" => "txt",
+        other if other.len() <= 10 && other.chars().all(|c| c.is_ascii_alphanumeric()) => other,
+        _ => "txt",
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(language: &str, lines: usize) -> String {
+        let code: String = (1..=lines).map(|i| format!("line {i}\n")).collect();
+        format!("```{language}\n{code}```")
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let text = format!("Intro\n{}\nmiddle\n  ```python:tools/fetch.py\nprint()\n  ```\n```\nunclosed", block("rust", 2));
+        let blocks = code_blocks(&text);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].language, "rust");
+        assert_eq!(blocks[0].code, "line 1\nline 2\n");
+        assert_eq!(&text[blocks[0].start..blocks[0].end], block("rust", 2));
+        assert_eq!(blocks[1].language, "python");
+        assert_eq!(blocks[1].name.as_deref(), Some("fetch.py"));
+        assert_eq!(&text[blocks[1].start..blocks[1].end], "```python:tools/fetch.py\nprint()\n  ```");
+
+        assert_eq!(parse_info("Rust ../../etc/passwd"), ("rust".into(), Some("passwd".into())));
+        assert_eq!(parse_info("sh ..").1, None);
+        assert_eq!(parse_info("").0, "");
+        assert_eq!(extension("bash"), "sh");
+        assert_eq!(extension("toml"), "toml");
+        assert_eq!(extension("objective-c"), "txt");
+    }
+
+    #[test]
+    fn test_apply_moves_long_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = CodeFiles::new(
+            CodeFilesConfig { min_lines: 5, ..Default::default() },
+            dir.path().to_path_buf(),
+        );
+        let content = format!("Here it is:\n\n{}\n\nShort one:\n{}\nDone.", block("rust", 6), block("sh", 2));
+        let mut msg = OutboundMessage::new("telegram", "42", content);
+
+        assert_eq!(files.apply(&mut msg), 1);
+        assert_eq!(
+            msg.content,
+            format!("Here it is:\n\n📎 code-1.rs (6 lines of rust, attached)\n\nShort one:\n{}\nDone.", block("sh", 2))
+        );
+        assert_eq!(msg.media.len(), 1);
+        assert_eq!(msg.media[0].filename.as_deref(), Some("code-1.rs"));
+        let written = std::fs::read_to_string(&msg.media[0].path).unwrap();
+        assert!(written.starts_with("line 1\n") && written.ends_with("line 6\n"));
+
+        // Nothing left to move
+        assert_eq!(files.apply(&mut msg), 0);
+    }
+
+    #[test]
+    fn test_apply_respects_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = format!("{}\n{}", block("python", 5), block("", 5));
+
+        let only_rust = CodeFiles::new(
+            CodeFilesConfig { min_lines: 5, languages: vec!["Rust".into()], ..Default::default() },
+            dir.path().to_path_buf(),
+        );
+        let mut msg = OutboundMessage::new("discord", "1", content.clone());
+        assert_eq!(only_rust.apply(&mut msg), 0);
+
+        let disabled = CodeFiles::new(
+            CodeFilesConfig { enabled: false, min_lines: 1, ..Default::default() },
+            dir.path().to_path_buf(),
+        );
+        assert_eq!(disabled.apply(&mut msg), 0);
+        assert_eq!(msg.content, content);
+
+        let any = CodeFiles::new(CodeFilesConfig { min_lines: 5, ..Default::default() }, dir.path().to_path_buf());
+        assert_eq!(any.apply(&mut msg), 2);
+        assert_eq!(msg.content, "📎 code-1.py (5 lines of python, attached)\n📎 code-2.txt (5 lines, attached)");
+    }
+}
@@ -0,0 +1,395 @@
+//! Delivery — retry failed sends and deliver kept messages late.
+//!
+//! A send that fails is retried `retries` times with growing pauses. If it
+//! still fails, the message is kept in the outbox (see
+//! [`oxibot_core::bus::outbox`]) and the admin chat is told — once per
+//! outage, not once per message. While a channel is down the manager keeps
+//! asking it whether it is back ([`Channel::check`], or new activity in its
+//! health report); a message that goes through also counts. Kept messages
+//! are then sent with a `[delayed]` prefix.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{error, info, warn};
+
+use oxibot_core::bus::outbox::{Outbox, OutboxEntry, OutboxStatus};
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::schema::DeliveryConfig;
+
+use crate::base::Channel;
+
+/// A channel that failed to send.
+#[derive(Clone, Copy, Debug)]
+struct Outage {
+    /// Last failed send.
+    since: Instant,
+    /// A later send went through.
+    back: bool,
+    /// The admin chat was told.
+    notified: bool,
+}
+
+/// Sends messages with retries and keeps track of channels that are down.
+pub struct Delivery {
+    channels: HashMap<String, Arc<dyn Channel>>,
+    config: DeliveryConfig,
+    /// Where messages that fail to send are kept.
+    outbox: Option<Arc<Outbox>>,
+    /// Admin chat `(channel, chat_id)` told about outages.
+    admin: Option<(String, String)>,
+    /// Channels that are down, by name.
+    down: Mutex<HashMap<String, Outage>>,
+}
+
+impl Delivery {
+    pub fn new(channels: HashMap<String, Arc<dyn Channel>>, config: DeliveryConfig) -> Self {
+        Self {
+            channels,
+            config,
+            outbox: None,
+            admin: None,
+            down: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keep messages that fail to send in `outbox`.
+    ///
+    /// Channels with messages kept before a restart count as down, so the
+    /// messages go out once the channel is back.
+    pub fn with_outbox(mut self, outbox: Option<Arc<Outbox>>) -> Self {
+        if let Some(ref outbox) = outbox {
+            let down = self.down.get_mut().unwrap_or_else(|e| e.into_inner());
+            for entry in outbox.list() {
+                if entry.status == OutboxStatus::Failed {
+                    down.entry(entry.message.channel).or_insert(Outage {
+                        since: Instant::now(),
+                        back: false,
+                        notified: true,
+                    });
+                }
+            }
+        }
+        self.outbox = outbox;
+        self
+    }
+
+    /// Tell the chat `chat_id` on `channel` when a channel goes down
+    /// (ignored when either is empty).
+    pub fn with_admin(mut self, channel: &str, chat_id: &str) -> Self {
+        if !channel.is_empty() && !chat_id.is_empty() {
+            self.admin = Some((channel.to_string(), chat_id.to_string()));
+        }
+        self
+    }
+
+    pub fn outbox(&self) -> Option<&Outbox> {
+        self.outbox.as_deref()
+    }
+
+    /// Send `msg` through `channel`, retrying on failure. A message that
+    /// still fails is kept (see [`Delivery::undeliverable`]).
+    pub async fn send(&self, channel: &dyn Channel, msg: &OutboundMessage) {
+        match self.send_with_retries(channel, msg).await {
+            Ok(()) => {
+                if let Some(outage) = self.lock_down().get_mut(channel.name()) {
+                    outage.back = true;
+                }
+            }
+            Err(e) => {
+                error!(channel = %msg.channel, error = %e, "failed to send outbound message");
+                self.undeliverable(msg, &format!("{e:#}")).await;
+            }
+        }
+    }
+
+    /// Progress updates are stale by the time a retry could go out and
+    /// are sent once.
+    async fn send_with_retries(&self, channel: &dyn Channel, msg: &OutboundMessage) -> anyhow::Result<()> {
+        let retries = if msg.progress_id().is_some() { 0 } else { self.config.retries };
+        let mut delay = Duration::from_millis(self.config.retry_delay_ms);
+        let mut attempt = 0;
+        loop {
+            match channel.send(msg).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < retries => {
+                    attempt += 1;
+                    warn!(
+                        channel = %msg.channel,
+                        error = %e,
+                        attempt,
+                        retry_in_ms = delay.as_millis() as u64,
+                        "send failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Keep `msg`, which could not be delivered, and mark its channel as
+    /// down. The admin chat is told the first time. Progress updates are
+    /// dropped.
+    pub async fn undeliverable(&self, msg: &OutboundMessage, error: &str) {
+        if msg.progress_id().is_some() {
+            return;
+        }
+        let kept = self.keep(msg, error);
+        if self.mark_down(&msg.channel) {
+            self.notify_admin(msg, error, kept.as_ref()).await;
+        }
+    }
+
+    /// Record `msg` in the outbox, if there is one.
+    fn keep(&self, msg: &OutboundMessage, error: &str) -> Option<OutboxEntry> {
+        let outbox = self.outbox.as_deref()?;
+        match outbox.add(msg, error) {
+            Ok(entry) => {
+                info!(id = %entry.id, channel = %msg.channel, "kept undelivered message in outbox");
+                Some(entry)
+            }
+            Err(e) => {
+                error!(error = %e, "failed to write outbox entry");
+                None
+            }
+        }
+    }
+
+    /// Mark `channel` as down now. Returns whether the admin chat should
+    /// be told (the first failure of an outage).
+    fn mark_down(&self, channel: &str) -> bool {
+        let mut down = self.lock_down();
+        let outage = down.entry(channel.to_string()).or_insert(Outage {
+            since: Instant::now(),
+            back: false,
+            notified: false,
+        });
+        outage.since = Instant::now();
+        outage.back = false;
+        !std::mem::replace(&mut outage.notified, true)
+    }
+
+    async fn notify_admin(&self, msg: &OutboundMessage, error: &str, kept: Option<&OutboxEntry>) {
+        let Some((ref admin_channel, ref admin_chat)) = self.admin else { return };
+        // The admin chat can't hear about its own outage
+        if *admin_channel == msg.channel {
+            return;
+        }
+        let Some(channel) = self.channels.get(admin_channel) else { return };
+        let then = match kept {
+            Some(entry) if self.config.redeliver => format!(
+                "It is kept in the outbox (`{}`) and will be sent when `{}` is back.",
+                entry.id, msg.channel
+            ),
+            Some(entry) => format!("It is kept in the outbox (`{}`).", entry.id),
+            None => "It was dropped.".to_string(),
+        };
+        let text = format!(
+            "⚠️ Couldn't deliver a message on `{}` (chat {}) after {} attempts.\nError: {error}\n{then}",
+            msg.channel,
+            msg.chat_id,
+            self.config.retries + 1
+        );
+        if let Err(e) = channel.send(&OutboundMessage::new(admin_channel, admin_chat, text)).await {
+            warn!(error = %e, "failed to notify admin chat of undelivered message");
+        }
+    }
+
+    /// Send the kept messages of channels that are back, oldest first.
+    ///
+    /// Messages that fail again go back to the outbox; after `maxAttempts`
+    /// they are left to the operator.
+    pub async fn redeliver(&self) {
+        let Some(outbox) = self.outbox.as_deref() else { return };
+        if !self.config.redeliver {
+            return;
+        }
+        let down: Vec<(String, Outage)> = self.lock_down().iter().map(|(k, v)| (k.clone(), *v)).collect();
+        for (name, outage) in down {
+            let Some(channel) = self.channels.get(&name) else { continue };
+            if !outage.back && !is_back(channel.as_ref(), outage.since).await {
+                continue;
+            }
+            self.lock_down().remove(&name);
+            let kept: Vec<OutboxEntry> = outbox
+                .list()
+                .into_iter()
+                .filter(|e| e.message.channel == name)
+                .filter(|e| e.status == OutboxStatus::Failed && e.attempts < self.config.max_attempts)
+                .collect();
+            if kept.is_empty() {
+                continue;
+            }
+            info!(channel = %name, messages = kept.len(), "channel is back, delivering kept messages");
+            for entry in kept {
+                // Removed first, so an operator retry can't send it twice
+                let Ok(entry) = outbox.remove(&entry.id) else { continue };
+                let late = self.delayed(&entry.message);
+                match channel.send(&late).await {
+                    Ok(()) => info!(id = %entry.id, channel = %name, "delivered kept message"),
+                    Err(e) => {
+                        warn!(id = %entry.id, channel = %name, error = %e, "late delivery failed");
+                        if let Err(e) = outbox.fail(entry, &format!("{e:#}")) {
+                            error!(error = %e, "failed to update outbox entry");
+                        }
+                        // Down again, but the admin chat already knows
+                        let mut down = self.lock_down();
+                        down.insert(
+                            name.clone(),
+                            Outage {
+                                since: Instant::now(),
+                                back: false,
+                                notified: true,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// `msg` with the delayed prefix.
+    fn delayed(&self, msg: &OutboundMessage) -> OutboundMessage {
+        let mut late = msg.clone();
+        if !late.content.is_empty() && !late.content.starts_with(&self.config.delayed_prefix) {
+            late.content = format!("{}{}", self.config.delayed_prefix, late.content);
+        }
+        late
+    }
+
+    fn lock_down(&self) -> std::sync::MutexGuard<'_, HashMap<String, Outage>> {
+        self.down.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Whether a channel that failed at `since` is back: its check passes, or
+/// without one, its health report shows activity since.
+async fn is_back(channel: &dyn Channel, since: Instant) -> bool {
+    match channel.check().await {
+        Some(result) => result.is_ok(),
+        None => channel.health().is_some_and(|h| h.last_activity > since),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Channel that fails while `up` is false and records what it sent.
+    #[derive(Default)]
+    struct Switch {
+        name: &'static str,
+        up: AtomicBool,
+        attempts: AtomicUsize,
+        sent: Mutex<Vec<OutboundMessage>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for Switch {
+        fn name(&self) -> &str {
+            self.name
+        }
+        async fn start(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn stop(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            if !self.up.load(Ordering::SeqCst) {
+                anyhow::bail!("token_revoked");
+            }
+            self.sent.lock().unwrap().push(msg.clone());
+            Ok(())
+        }
+        async fn check(&self) -> Option<anyhow::Result<()>> {
+            Some(match self.up.load(Ordering::SeqCst) {
+                true => Ok(()),
+                false => Err(anyhow::anyhow!("token_revoked")),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keep_notify_and_redeliver() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Arc::new(Outbox::new(dir.path()));
+        let slack = Arc::new(Switch { name: "slack", ..Default::default() });
+        let admin = Arc::new(Switch { name: "telegram", up: AtomicBool::new(true), ..Default::default() });
+        let channels: HashMap<String, Arc<dyn Channel>> = HashMap::from([
+            ("slack".to_string(), slack.clone() as Arc<dyn Channel>),
+            ("telegram".to_string(), admin.clone() as Arc<dyn Channel>),
+        ]);
+        let config = DeliveryConfig {
+            retries: 1,
+            retry_delay_ms: 1,
+            ..DeliveryConfig::default()
+        };
+        let delivery = Delivery::new(channels, config)
+            .with_outbox(Some(outbox.clone()))
+            .with_admin("telegram", "admin");
+
+        delivery.send(slack.as_ref(), &OutboundMessage::new("slack", "C1", "first")).await;
+        delivery.send(slack.as_ref(), &OutboundMessage::new("slack", "C1", "second")).await;
+        assert_eq!(slack.attempts.load(Ordering::SeqCst), 4);
+        assert_eq!(outbox.list().len(), 2);
+
+        // One notice for the outage, not one per message
+        let notices = admin.sent.lock().unwrap().clone();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].chat_id, "admin");
+        assert!(notices[0].content.contains("on `slack` (chat C1) after 2 attempts"));
+        assert!(notices[0].content.contains("token_revoked"));
+
+        // Still down: nothing is sent
+        delivery.redeliver().await;
+        assert_eq!(outbox.list().len(), 2);
+
+        slack.up.store(true, Ordering::SeqCst);
+        delivery.redeliver().await;
+        let sent: Vec<String> = slack.sent.lock().unwrap().iter().map(|m| m.content.clone()).collect();
+        assert_eq!(sent, ["[delayed] first", "[delayed] second"]);
+        assert!(outbox.list().is_empty());
+        assert!(delivery.lock_down().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redeliver_gives_up_after_max_attempts() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Arc::new(Outbox::new(dir.path()));
+        let kept = outbox.add(&OutboundMessage::new("slack", "C1", "hi"), "channel_not_found").unwrap();
+        let slack = Arc::new(Switch { name: "slack", ..Default::default() });
+        let channels: HashMap<String, Arc<dyn Channel>> =
+            HashMap::from([("slack".to_string(), slack.clone() as Arc<dyn Channel>)]);
+        let config = DeliveryConfig {
+            max_attempts: 2,
+            ..DeliveryConfig::default()
+        };
+        // Kept before a restart: counts as down from the start
+        let delivery = Delivery::new(channels, config).with_outbox(Some(outbox.clone()));
+        assert!(delivery.lock_down().contains_key("slack"));
+
+        // The channel is back but this message keeps failing
+        let up_but_failing = Outage {
+            since: Instant::now(),
+            back: true,
+            notified: true,
+        };
+        delivery.lock_down().insert("slack".into(), up_but_failing);
+        delivery.redeliver().await;
+        assert_eq!(outbox.get(&kept.id).unwrap().attempts, 2);
+
+        delivery.lock_down().insert("slack".into(), up_but_failing);
+        delivery.redeliver().await;
+        assert_eq!(slack.attempts.load(Ordering::SeqCst), 1);
+    }
+}
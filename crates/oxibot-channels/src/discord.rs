@@ -8,10 +8,17 @@
 //! Features:
 //! - Gateway v10 WebSocket with heartbeat + resume
 //! - Text and attachment handling
+//! - Voice messages transcribed (when a transcriber is configured)
 //! - Typing indicator while agent processes
 //! - Allow-list by Discord user ID
 //! - Message chunking for >2000 char responses
+//! - Search results, task lists and command output rendered as embeds
 //! - Rate-limit retry (HTTP 429)
+//! - Progress messages edited in place (e.g. streaming `exec` output)
+//! - Approval requests with Allow / Deny buttons
+//! - 👀 reaction (or a "…" reply) when the agent picks up a message
+//! - Spoken requests in a voice channel (`discord-voice` feature, see
+//!   [`discord_voice`](crate::discord_voice))
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -22,10 +29,21 @@ use serde_json::{json, Value};
 use tokio::sync::{Mutex, Notify, RwLock};
 use tracing::{debug, error, info, warn};
 
+use oxibot_core::bus::approval::{self, Decision};
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
-
-use crate::base::Channel;
+use oxibot_core::config::schema::AckConfig;
+use oxibot_core::types::{MediaAttachment, Transcript};
+use oxibot_core::utils::truncate_string;
+
+use crate::base::{
+    answered_prompt, AckMode, ActivityTracker, Channel, HealthReport, ProgressMessages, TranscribeFn,
+    ACK_REPLY_TEXT, APPROVE_BUTTON_TEXT, DENY_BUTTON_TEXT,
+};
+#[cfg(feature = "discord-voice")]
+use crate::discord_voice::VoiceListener;
+use crate::enrollment::Enrollment;
+use crate::formatting::{Card, CardKind, RichContent};
 
 // ─────────────────────────────────────────────
 // Constants
@@ -40,15 +58,50 @@ const DEFAULT_GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=10&encoding=json"
 /// Discord message length limit.
 const DISCORD_MAX_LEN: usize = 2000;
 
+/// Files per message.
+const DISCORD_MAX_FILES: usize = 10;
+
+/// Embed limits: per message, per title, description, field name and
+/// value, fields per embed, and characters across all embeds.
+const MAX_EMBEDS: usize = 10;
+const EMBED_TITLE_LEN: usize = 256;
+const EMBED_DESCRIPTION_LEN: usize = 4096;
+const EMBED_FIELD_NAME_LEN: usize = 256;
+const EMBED_FIELD_VALUE_LEN: usize = 1024;
+const EMBED_MAX_FIELDS: usize = 25;
+const EMBED_TOTAL_LEN: usize = 6000;
+
+/// Embed colors (Discord's palette).
+const COLOR_BLURPLE: u32 = 0x5865F2;
+const COLOR_GREEN: u32 = 0x57F287;
+const COLOR_YELLOW: u32 = 0xFEE75C;
+const COLOR_RED: u32 = 0xED4245;
+
 /// Maximum attachment download size (20 MB).
 const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
 
+/// Reaction used to acknowledge messages unless configured otherwise.
+const DEFAULT_ACK_EMOJI: &str = "👀";
+
 /// Typing indicator refresh interval (Discord typing lasts ~10s).
 const TYPING_INTERVAL_SECS: u64 = 8;
 
+/// Message flag set on voice messages (IS_VOICE_MESSAGE).
+const FLAG_VOICE_MESSAGE: u64 = 1 << 13;
+
+/// Interaction type of a button press.
+const INTERACTION_MESSAGE_COMPONENT: u64 = 3;
+
+/// Interaction response type that edits the message the button is on.
+const RESPONSE_UPDATE_MESSAGE: u64 = 7;
+
 /// Default intents: GUILDS(1) + GUILD_MESSAGES(512) + DMs(4096) + MESSAGE_CONTENT(32768).
 const DEFAULT_INTENTS: u64 = 1 + 512 + 4096 + 32768;
 
+/// Intent needed to see the bot's own voice state (GUILD_VOICE_STATES).
+#[cfg(feature = "discord-voice")]
+const INTENT_GUILD_VOICE_STATES: u64 = 128;
+
 // Gateway opcodes
 const OP_DISPATCH: u64 = 0;
 const OP_HEARTBEAT: u64 = 1;
@@ -89,6 +142,25 @@ pub struct DiscordChannel {
     session_id: Arc<Mutex<Option<String>>>,
     /// Resume gateway URL.
     resume_url: Arc<Mutex<Option<String>>>,
+    /// Sent progress messages (progress id → Discord message id).
+    progress_messages: Arc<ProgressMessages<String>>,
+    /// Last heartbeat ACK, for health reports.
+    activity: ActivityTracker,
+    /// How messages are acknowledged when the agent picks them up.
+    ack: AckMode,
+    /// Optional voice transcription callback.
+    transcriber: Option<TranscribeFn>,
+    /// Admin-approved access for unknown senders.
+    enrollment: Option<Arc<Enrollment>>,
+    /// Render structured replies as embeds.
+    embeds: bool,
+    /// Only record guild messages unless the bot is addressed.
+    observe_only: bool,
+    /// The bot's user id, from the READY event.
+    bot_id: Arc<Mutex<Option<String>>>,
+    /// Listener for the configured voice channel.
+    #[cfg(feature = "discord-voice")]
+    voice: Option<Arc<VoiceListener>>,
 }
 
 impl DiscordChannel {
@@ -114,10 +186,83 @@ impl DiscordChannel {
             heartbeat_acked: Arc::new(Mutex::new(true)),
             session_id: Arc::new(Mutex::new(None)),
             resume_url: Arc::new(Mutex::new(None)),
+            progress_messages: Arc::new(ProgressMessages::new()),
+            activity: ActivityTracker::new(),
+            ack: AckMode::from_config(&AckConfig::default(), DEFAULT_ACK_EMOJI),
+            transcriber: None,
+            enrollment: None,
+            embeds: true,
+            observe_only: false,
+            bot_id: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "discord-voice")]
+            voice: None,
+        }
+    }
+
+    /// Join a voice channel and answer spoken requests in a text channel
+    /// (ignored unless enabled with a server, voice and text channel).
+    #[cfg(feature = "discord-voice")]
+    pub fn with_voice(mut self, config: &oxibot_core::config::schema::DiscordVoiceConfig) -> Self {
+        let complete = !config.guild_id.is_empty() && !config.channel_id.is_empty() && !config.text_channel_id.is_empty();
+        if !config.enabled || !complete {
+            if config.enabled {
+                warn!("discord voice needs guildId, channelId and textChannelId; not joining");
+            }
+            return self;
         }
+        self.intents |= INTENT_GUILD_VOICE_STATES;
+        self.voice = Some(Arc::new(VoiceListener::new(
+            config.clone(),
+            self.bus.clone(),
+            self.allowed_users.clone(),
+        )));
+        self
+    }
+
+    /// Render search results, task lists and command output as embeds
+    /// (default true).
+    pub fn with_embeds(mut self, enabled: bool) -> Self {
+        self.embeds = enabled;
+        self
+    }
+
+    /// Only record guild messages in the session, answering just the ones
+    /// that mention the bot or reply to it.
+    pub fn with_observe_only(mut self, observe_only: bool) -> Self {
+        self.observe_only = observe_only;
+        self
+    }
+
+    /// Whether a message mentions the bot or replies to it.
+    async fn addresses_bot(&self, data: &Value) -> bool {
+        let Some(bot_id) = self.bot_id.lock().await.clone() else {
+            return false;
+        };
+        let mentioned = data["mentions"]
+            .as_array()
+            .is_some_and(|mentions| mentions.iter().any(|user| user["id"].as_str() == Some(&bot_id)));
+        mentioned || data["referenced_message"]["author"]["id"].as_str() == Some(&bot_id)
+    }
+
+    /// Set how messages are acknowledged (default: 👀 reaction).
+    pub fn with_acknowledgment(mut self, config: &AckConfig) -> Self {
+        self.ack = AckMode::from_config(config, DEFAULT_ACK_EMOJI);
+        self
+    }
+
+    /// Set the voice transcription callback.
+    pub fn with_transcriber(mut self, transcriber: TranscribeFn) -> Self {
+        self.transcriber = Some(transcriber);
+        self
+    }
+
+    /// Let unknown senders in direct chats ask the admin for access.
+    pub fn with_enrollment(mut self, enrollment: Option<Arc<Enrollment>>) -> Self {
+        self.enrollment = enrollment;
+        self
     }
 
-    /// Check if a sender is allowed.
+    /// Check if a sender is allowed (allow-list or enrolled).
     fn is_allowed(&self, sender_id: &str) -> bool {
         if self.allowed_users.is_empty() {
             return true;
@@ -130,7 +275,9 @@ impl DiscordChannel {
                 return true;
             }
         }
-        false
+        self.enrollment
+            .as_ref()
+            .is_some_and(|e| e.is_enrolled("discord", sender_id))
     }
 
     /// Run the Gateway WebSocket connection with auto-reconnect.
@@ -158,6 +305,7 @@ impl DiscordChannel {
     }
 
     /// Single Gateway WebSocket session.
+    #[allow(unused_assignments)]
     async fn gateway_session(&self) -> anyhow::Result<()> {
         use tokio_tungstenite::tungstenite::Message as WsMessage;
 
@@ -177,7 +325,6 @@ impl DiscordChannel {
         let (mut write, mut read) = ws_stream.split();
 
         // Heartbeat handle
-        #[allow(unused_assignments)]
         let mut heartbeat_handle: Option<tokio::task::JoinHandle<()>> = None;
 
         loop {
@@ -381,8 +528,24 @@ impl DiscordChannel {
                                                         if let Some(url) = payload["d"]["resume_gateway_url"].as_str() {
                                                             *self.resume_url.lock().await = Some(url.to_string());
                                                         }
+                                                        if let Some(id) = payload["d"]["user"]["id"].as_str() {
+                                                            *self.bot_id.lock().await = Some(id.to_string());
+                                                        }
                                                         let user = payload["d"]["user"]["username"].as_str().unwrap_or("unknown");
                                                         info!(user = user, "discord bot READY");
+                                                        #[cfg(feature = "discord-voice")]
+                                                        if let Some(ref voice) = self.voice {
+                                                            let _ = ws_tx.send(voice.join_request().to_string()).await;
+                                                        }
+                                                    }
+                                                    #[cfg(feature = "discord-voice")]
+                                                    "VOICE_STATE_UPDATE" | "VOICE_SERVER_UPDATE" => {
+                                                        if let Some(ref voice) = self.voice {
+                                                            let bot_id = self.bot_id.lock().await.clone();
+                                                            voice
+                                                                .handle_dispatch(event_name, &payload["d"], bot_id.as_deref(), self.transcriber.clone())
+                                                                .await;
+                                                        }
                                                     }
                                                     "RESUMED" => {
                                                         info!("discord session resumed");
@@ -390,6 +553,9 @@ impl DiscordChannel {
                                                     "MESSAGE_CREATE" => {
                                                         self.handle_message_create(&payload["d"]).await;
                                                     }
+                                                    "INTERACTION_CREATE" => {
+                                                        self.handle_interaction(&payload["d"]).await;
+                                                    }
                                                     _ => {
                                                         debug!(event = event_name, "discord event (unhandled)");
                                                     }
@@ -397,6 +563,7 @@ impl DiscordChannel {
                                             }
                                             OP_HEARTBEAT_ACK => {
                                                 *self.heartbeat_acked.lock().await = true;
+                                                self.activity.touch();
                                             }
                                             OP_RECONNECT => {
                                                 info!("discord server requested reconnect");
@@ -498,12 +665,21 @@ impl DiscordChannel {
                 channel = %channel_id,
                 "discord message from unauthorized user, ignoring"
             );
+            // Only in DMs (no guild)
+            if let Some(enrollment) = self.enrollment.as_ref().filter(|_| data["guild_id"].is_null()) {
+                enrollment.challenge("discord", &sender_id, &channel_id, &username);
+            }
             return;
         }
 
+        // Guild messages that don't address the bot are only recorded
+        let observed = self.observe_only && !data["guild_id"].is_null() && !self.addresses_bot(data).await;
+
         // Collect content
         let mut content_parts: Vec<String> = Vec::new();
-        let mut media_paths: Vec<String> = Vec::new();
+        let mut media: Vec<(String, String, String)> = Vec::new();
+        let mut extra_metadata: HashMap<String, String> = HashMap::new();
+        let flags = data["flags"].as_u64().unwrap_or(0);
 
         // Text content
         if let Some(text) = data["content"].as_str() {
@@ -533,11 +709,25 @@ impl DiscordChannel {
                     content_parts.push(format!("[attachment: {filename} — too large]"));
                     continue;
                 }
+                if observed {
+                    content_parts.push(format!("[attachment: {filename}]"));
+                    continue;
+                }
 
                 match self.download_attachment(url, &att_id, &filename).await {
                     Ok(path) => {
-                        content_parts.push(format!("[attachment: {path}]"));
-                        media_paths.push(path);
+                        if is_voice_attachment(flags, att) {
+                            let transcript =
+                                crate::base::transcribe(self.transcriber.as_ref(), &path).await;
+                            content_parts.push(voice_content(&path, att, transcript, &mut extra_metadata));
+                        } else {
+                            content_parts.push(format!("[attachment: {path}]"));
+                        }
+                        let mime = att["content_type"]
+                            .as_str()
+                            .unwrap_or("application/octet-stream")
+                            .to_string();
+                        media.push((path, mime, filename));
                     }
                     Err(e) => {
                         warn!(error = %e, filename = %filename, "failed to download attachment");
@@ -561,18 +751,21 @@ impl DiscordChannel {
         );
 
         // Start typing indicator
-        self.start_typing(&channel_id).await;
+        if !observed {
+            self.start_typing(&channel_id).await;
+        }
 
         // Build inbound message
         let mut inbound = InboundMessage::new("discord", &sender_id, &channel_id, &content);
-        for path in &media_paths {
+        for (path, mime_type, filename) in media {
             inbound.media.push(oxibot_core::types::MediaAttachment {
-                path: path.clone(),
-                mime_type: "application/octet-stream".into(),
-                filename: None,
+                path,
+                mime_type,
+                filename: Some(filename),
                 size: None,
             });
         }
+        inbound.metadata.extend(extra_metadata);
         inbound
             .metadata
             .insert("username".into(), username);
@@ -592,6 +785,9 @@ impl DiscordChannel {
                 .metadata
                 .insert("reply_to".into(), ref_msg.to_string());
         }
+        if observed {
+            inbound = inbound.observed();
+        }
 
         if let Err(e) = self.bus.publish_inbound(inbound).await {
             error!(error = %e, "failed to publish discord message to bus");
@@ -605,7 +801,7 @@ impl DiscordChannel {
         att_id: &str,
         filename: &str,
     ) -> anyhow::Result<String> {
-        let media_dir = oxibot_core::utils::get_data_path().join("media");
+        let media_dir = oxibot_core::utils::get_media_path();
         std::fs::create_dir_all(&media_dir)?;
 
         // Sanitize filename
@@ -673,20 +869,62 @@ impl DiscordChannel {
         }
     }
 
+    /// Handle an INTERACTION_CREATE event: Allow / Deny button presses.
+    async fn handle_interaction(&self, data: &Value) {
+        let Some((sender_id, channel_id, id, decision)) = parse_approval_click(data) else {
+            return;
+        };
+        if !self.is_allowed(sender_id) {
+            warn!(sender = %sender_id, channel = %channel_id, "discord button press from unauthorized user, ignoring");
+            return;
+        }
+
+        let inbound = InboundMessage::approval_response("discord", sender_id, channel_id, id, decision);
+        if let Err(e) = self.bus.publish_inbound(inbound).await {
+            error!(error = %e, "failed to publish approval answer");
+        }
+
+        // Replace the buttons with the answer
+        let prompt = data["message"]["content"].as_str().unwrap_or_default();
+        let (Some(interaction_id), Some(token)) = (data["id"].as_str(), data["token"].as_str()) else {
+            return;
+        };
+        let url = format!("{DISCORD_API_BASE}/interactions/{interaction_id}/{token}/callback");
+        let body = json!({
+            "type": RESPONSE_UPDATE_MESSAGE,
+            "data": { "content": answered_prompt(prompt, decision), "components": [] },
+        });
+        match self.http.post(&url).json(&body).send().await {
+            Ok(resp) if !resp.status().is_success() => {
+                debug!(status = %resp.status(), "discord interaction response failed");
+            }
+            Err(e) => debug!(error = %e, "discord interaction response failed"),
+            Ok(_) => {}
+        }
+    }
+
     /// Send a message via the REST API with retry on rate-limit.
+    ///
+    /// Returns the ID of the created message.
     async fn send_rest(
         &self,
         channel_id: &str,
         content: &str,
         reply_to: Option<&str>,
-    ) -> anyhow::Result<()> {
-        let url = format!("{DISCORD_API_BASE}/channels/{channel_id}/messages");
-
+    ) -> anyhow::Result<String> {
         let mut body = json!({ "content": content });
         if let Some(ref_id) = reply_to {
             body["message_reference"] = json!({ "message_id": ref_id });
             body["allowed_mentions"] = json!({ "replied_user": false });
         }
+        self.post_rest(channel_id, &body).await
+    }
+
+    /// Post a message body via the REST API with retry on rate-limit.
+    ///
+    /// Returns the ID of the created message.
+    async fn post_rest(&self, channel_id: &str, body: &Value) -> anyhow::Result<String> {
+        let url = format!("{DISCORD_API_BASE}/channels/{channel_id}/messages");
 
         let mut attempts = 0u32;
         loop {
@@ -695,14 +933,15 @@ impl DiscordChannel {
                 .http
                 .post(&url)
                 .header("Authorization", format!("Bot {}", self.token))
-                .json(&body)
+                .json(body)
                 .send()
                 .await?;
 
             let status = resp.status();
 
             if status.is_success() {
-                return Ok(());
+                let created: Value = resp.json().await.unwrap_or_default();
+                return Ok(created["id"].as_str().unwrap_or_default().to_string());
             }
 
             if status.as_u16() == 429 {
@@ -740,6 +979,94 @@ impl DiscordChannel {
     }
 }
 
+impl DiscordChannel {
+    /// Upload local files to a channel, up to [`DISCORD_MAX_FILES`] per
+    /// message (no retry). URLs can't be uploaded and are skipped.
+    async fn post_files(&self, channel_id: &str, media: &[MediaAttachment]) -> anyhow::Result<()> {
+        let url = format!("{DISCORD_API_BASE}/channels/{channel_id}/messages");
+        let files: Vec<&MediaAttachment> = media
+            .iter()
+            .filter(|m| !m.path.starts_with("http://") && !m.path.starts_with("https://"))
+            .collect();
+        for batch in files.chunks(DISCORD_MAX_FILES) {
+            let mut form = reqwest::multipart::Form::new();
+            for (i, file) in batch.iter().enumerate() {
+                let bytes = tokio::fs::read(&file.path).await?;
+                let name = file.filename.clone().unwrap_or_else(|| {
+                    std::path::Path::new(&file.path)
+                        .file_name()
+                        .map_or_else(|| "file".into(), |n| n.to_string_lossy().into_owned())
+                });
+                form = form.part(format!("files[{i}]"), reqwest::multipart::Part::bytes(bytes).file_name(name));
+            }
+            self.http
+                .post(&url)
+                .header("Authorization", format!("Bot {}", self.token))
+                .multipart(form)
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+        Ok(())
+    }
+
+    /// React to a message with a unicode emoji via the REST API (no retry).
+    async fn add_reaction(&self, channel_id: &str, message_id: &str, emoji: &str) -> anyhow::Result<()> {
+        let emoji: String = url::form_urlencoded::byte_serialize(emoji.as_bytes()).collect();
+        let url = format!("{DISCORD_API_BASE}/channels/{channel_id}/messages/{message_id}/reactions/{emoji}/@me");
+        let resp = self
+            .http
+            .put(&url)
+            .header("Authorization", format!("Bot {}", self.token))
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("discord reaction failed (HTTP {})", resp.status());
+        }
+        Ok(())
+    }
+
+    /// Edit an existing message via the REST API (no retry).
+    async fn edit_rest(&self, channel_id: &str, message_id: &str, content: &str) -> anyhow::Result<()> {
+        let url = format!("{DISCORD_API_BASE}/channels/{channel_id}/messages/{message_id}");
+        let resp = self
+            .http
+            .patch(&url)
+            .header("Authorization", format!("Bot {}", self.token))
+            .json(&json!({ "content": content }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("discord edit failed (HTTP {})", resp.status());
+        }
+        Ok(())
+    }
+
+    /// Send or edit the progress message for `progress_id`.
+    async fn send_progress(&self, msg: &OutboundMessage, progress_id: &str) -> anyhow::Result<()> {
+        let content: String = msg.content.chars().take(DISCORD_MAX_LEN).collect();
+        match self.progress_messages.get(progress_id) {
+            Some(message_id) => {
+                if let Err(e) = self.edit_rest(&msg.chat_id, &message_id, &content).await {
+                    debug!(error = %e, "discord progress edit failed");
+                }
+            }
+            None => {
+                let message_id = self.send_rest(&msg.chat_id, &content, None).await?;
+                if !message_id.is_empty() {
+                    self.progress_messages.insert(progress_id, message_id);
+                }
+            }
+        }
+
+        if msg.is_progress_done() {
+            self.progress_messages.finish(progress_id);
+        }
+        Ok(())
+    }
+}
+
 /// Split a message into chunks respecting Discord's 2000 char limit.
 /// Tries to split at newline boundaries.
 pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
@@ -798,12 +1125,40 @@ impl Channel for DiscordChannel {
         info!("stopping discord channel");
         self.shutdown.notify_waiters();
         self.stop_all_typing().await;
+        #[cfg(feature = "discord-voice")]
+        if let Some(ref voice) = self.voice {
+            voice.leave().await;
+        }
         Ok(())
     }
 
     async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
+        if let Some(progress_id) = msg.progress_id() {
+            return self.send_progress(msg, progress_id).await;
+        }
+        if let Some((id, prompt)) = msg.approval() {
+            let content: String = prompt.chars().take(DISCORD_MAX_LEN).collect();
+            let body = json!({ "content": content, "components": approval_components(id) });
+            return self.post_rest(&msg.chat_id, &body).await.map(|_| ());
+        }
         let reply_to = msg.metadata.get("reply_to").map(|s| s.as_str());
 
+        // Structured replies go out as one message with embeds, if they fit
+        let rich = self.embeds.then(|| RichContent::parse(&msg.content)).flatten();
+        if let Some(mut body) = rich.as_ref().and_then(embed_message) {
+            if let Some(ref_id) = reply_to {
+                body["message_reference"] = json!({ "message_id": ref_id });
+                body["allowed_mentions"] = json!({ "replied_user": false });
+            }
+            self.post_rest(&msg.chat_id, &body).await?;
+            if !msg.media.is_empty() {
+                self.post_files(&msg.chat_id, &msg.media).await?;
+            }
+            self.stop_typing(&msg.chat_id).await;
+            debug!(chat_id = %msg.chat_id, "discord message sent with embeds");
+            return Ok(());
+        }
+
         // Split long messages
         let chunks = split_message(&msg.content, DISCORD_MAX_LEN);
 
@@ -812,6 +1167,9 @@ impl Channel for DiscordChannel {
             let ref_id = if i == 0 { reply_to } else { None };
             self.send_rest(&msg.chat_id, chunk, ref_id).await?;
         }
+        if !msg.media.is_empty() {
+            self.post_files(&msg.chat_id, &msg.media).await?;
+        }
 
         // Stop typing after sending
         self.stop_typing(&msg.chat_id).await;
@@ -819,6 +1177,196 @@ impl Channel for DiscordChannel {
         debug!(chat_id = %msg.chat_id, chunks = chunks.len(), "discord message sent");
         Ok(())
     }
+
+    async fn acknowledge(&self, ack: &OutboundMessage) -> anyhow::Result<()> {
+        let Some(message_id) = ack.message_id() else {
+            return Ok(());
+        };
+        match self.ack {
+            AckMode::Reaction(ref emoji) => self.add_reaction(&ack.chat_id, message_id, emoji).await,
+            AckMode::Reply => self
+                .send_rest(&ack.chat_id, ACK_REPLY_TEXT, Some(message_id))
+                .await
+                .map(|_| ()),
+            AckMode::Off => Ok(()),
+        }
+    }
+
+    fn supports_edits(&self) -> bool {
+        true
+    }
+
+    fn supports_uploads(&self) -> bool {
+        true
+    }
+
+    fn max_message_len(&self) -> Option<usize> {
+        Some(DISCORD_MAX_LEN)
+    }
+
+    async fn typing(&self, chat_id: &str) -> anyhow::Result<()> {
+        self.http
+            .post(format!("{DISCORD_API_BASE}/channels/{chat_id}/typing"))
+            .header("Authorization", format!("Bot {}", self.token))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn health(&self) -> Option<HealthReport> {
+        Some(self.activity.report())
+    }
+
+    async fn check(&self) -> Option<anyhow::Result<()>> {
+        let result = async {
+            self.http
+                .get(format!("{DISCORD_API_BASE}/users/@me"))
+                .header("Authorization", format!("Bot {}", self.token))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        };
+        Some(result.await)
+    }
+}
+
+// ─────────────────────────────────────────────
+// Embeds
+// ─────────────────────────────────────────────
+
+/// A message body with `rich`'s text as content and its cards as embeds,
+/// or `None` when it doesn't fit in one message.
+fn embed_message(rich: &RichContent) -> Option<Value> {
+    if rich.text.chars().count() > DISCORD_MAX_LEN || rich.cards.len() > MAX_EMBEDS {
+        return None;
+    }
+    let embeds: Vec<Value> = rich.cards.iter().map(embed).collect();
+    let total: usize = embeds.iter().map(embed_len).sum();
+    (total <= EMBED_TOTAL_LEN).then(|| json!({ "content": rich.text, "embeds": embeds }))
+}
+
+/// An embed for `card`, clipped to Discord's limits.
+fn embed(card: &Card) -> Value {
+    let color = match card.kind {
+        CardKind::Links => COLOR_BLURPLE,
+        CardKind::Tasks { done: true } => COLOR_GREEN,
+        CardKind::Tasks { done: false } => COLOR_YELLOW,
+        CardKind::CommandOutput { exit_code: 0 } => COLOR_GREEN,
+        CardKind::CommandOutput { .. } => COLOR_RED,
+    };
+    let mut embed = json!({ "color": color });
+    if let Some(ref title) = card.title {
+        embed["title"] = json!(truncate_string(title, EMBED_TITLE_LEN));
+    }
+    if !card.description.is_empty() {
+        embed["description"] = json!(clip_markdown(&card.description, EMBED_DESCRIPTION_LEN));
+    }
+    if !card.fields.is_empty() {
+        let fields: Vec<Value> = card
+            .fields
+            .iter()
+            .take(EMBED_MAX_FIELDS)
+            .map(|f| {
+                json!({
+                    "name": truncate_string(&f.name, EMBED_FIELD_NAME_LEN),
+                    "value": truncate_string(&f.value, EMBED_FIELD_VALUE_LEN),
+                    "inline": f.inline,
+                })
+            })
+            .collect();
+        embed["fields"] = json!(fields);
+    }
+    if let Some(ref footer) = card.footer {
+        embed["footer"] = json!({ "text": footer });
+    }
+    embed
+}
+
+/// Characters of an embed that count towards `EMBED_TOTAL_LEN`.
+fn embed_len(embed: &Value) -> usize {
+    let len = |v: &Value| v.as_str().map_or(0, |s| s.chars().count());
+    let fields = embed["fields"].as_array().map_or(0, |fields| {
+        fields.iter().map(|f| len(&f["name"]) + len(&f["value"])).sum()
+    });
+    len(&embed["title"]) + len(&embed["description"]) + len(&embed["footer"]["text"]) + fields
+}
+
+/// Clip `text` to `max` characters, closing a code block cut in half.
+fn clip_markdown(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let clipped = truncate_string(text, max - 4);
+    if clipped.matches("```").count() % 2 == 1 {
+        format!("{clipped}\n```")
+    } else {
+        clipped
+    }
+}
+
+// ─────────────────────────────────────────────
+// Voice messages
+// ─────────────────────────────────────────────
+
+/// Whether an attachment is a voice message (flagged message or audio file).
+/// An action row with Allow / Deny buttons for approval request `id`.
+fn approval_components(id: &str) -> Value {
+    let button = |label: &str, style: u64, decision: Decision| {
+        json!({
+            "type": 2,
+            "style": style,
+            "label": label,
+            "custom_id": approval::button_data(id, decision),
+        })
+    };
+    json!([{
+        "type": 1,
+        "components": [
+            button(APPROVE_BUTTON_TEXT, 3, Decision::Approved),
+            button(DENY_BUTTON_TEXT, 4, Decision::Denied),
+        ],
+    }])
+}
+
+/// Sender, channel, approval id and decision of an approval button press.
+///
+/// Guild interactions carry the user in `member`, DMs in `user`.
+fn parse_approval_click(data: &Value) -> Option<(&str, &str, &str, Decision)> {
+    if data["type"].as_u64() != Some(INTERACTION_MESSAGE_COMPONENT) {
+        return None;
+    }
+    let (id, decision) = approval::parse_button_data(data["data"]["custom_id"].as_str()?)?;
+    let user = if data["member"].is_object() { &data["member"]["user"] } else { &data["user"] };
+    Some((user["id"].as_str()?, data["channel_id"].as_str()?, id, decision))
+}
+
+fn is_voice_attachment(flags: u64, att: &Value) -> bool {
+    flags & FLAG_VOICE_MESSAGE != 0
+        || att["content_type"]
+            .as_str()
+            .is_some_and(|t| t.starts_with("audio/"))
+}
+
+/// Content line for a downloaded voice message; records the clip duration
+/// and transcription details in `metadata`.
+fn voice_content(
+    path: &str,
+    att: &Value,
+    transcript: Option<Transcript>,
+    metadata: &mut HashMap<String, String>,
+) -> String {
+    if let Some(secs) = att["duration_secs"].as_f64() {
+        metadata.insert("voice_duration_secs".into(), format!("{secs:.1}"));
+    }
+    match transcript {
+        Some(t) => {
+            metadata.extend(t.to_metadata());
+            t.to_content()
+        }
+        None => format!("[voice: {path}]"),
+    }
 }
 
 // ─────────────────────────────────────────────
@@ -931,6 +1479,58 @@ mod tests {
         assert_eq!(DEFAULT_INTENTS, 37377);
     }
 
+    #[test]
+    fn test_voice_messages() {
+        let voice = json!({ "content_type": "audio/ogg", "duration_secs": 4.3 });
+        let file = json!({ "content_type": "application/pdf" });
+        assert!(is_voice_attachment(0, &voice));
+        assert!(is_voice_attachment(FLAG_VOICE_MESSAGE, &json!({})));
+        assert!(!is_voice_attachment(0, &file));
+
+        let mut meta = HashMap::new();
+        let content = voice_content("/tmp/v.ogg", &voice, None, &mut meta);
+        assert_eq!(content, "[voice: /tmp/v.ogg]");
+        assert_eq!(meta.get("voice_duration_secs").unwrap(), "4.3");
+
+        let mut meta = HashMap::new();
+        let transcript = Transcript {
+            text: "call me back".into(),
+            duration_secs: Some(4.3),
+            confidence: None,
+            chunks: 1,
+            ..Default::default()
+        };
+        let content = voice_content("/tmp/v.ogg", &voice, Some(transcript), &mut meta);
+        assert_eq!(content, "[transcription: call me back]");
+        assert_eq!(meta.get("transcription_chunks").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_approval_buttons() {
+        let components = approval_components("ab12");
+        let allow = &components[0]["components"][0];
+        assert_eq!(allow["label"], APPROVE_BUTTON_TEXT);
+
+        let click = json!({
+            "type": INTERACTION_MESSAGE_COMPONENT,
+            "channel_id": "ch1",
+            "member": { "user": { "id": "user1" } },
+            "data": { "custom_id": allow["custom_id"] },
+        });
+        assert_eq!(parse_approval_click(&click), Some(("user1", "ch1", "ab12", Decision::Approved)));
+
+        let dm = json!({
+            "type": INTERACTION_MESSAGE_COMPONENT,
+            "channel_id": "dm1",
+            "user": { "id": "user2" },
+            "data": { "custom_id": components[0]["components"][1]["custom_id"] },
+        });
+        assert_eq!(parse_approval_click(&dm), Some(("user2", "dm1", "ab12", Decision::Denied)));
+
+        let command = json!({ "type": 2, "channel_id": "ch1", "user": { "id": "u" }, "data": { "name": "x" } });
+        assert_eq!(parse_approval_click(&command), None);
+    }
+
     #[tokio::test]
     async fn test_handle_message_create_ignores_bots() {
         let ch = create_test_channel();
@@ -984,6 +1584,41 @@ mod tests {
         assert_eq!(msg.metadata.get("guild_id").unwrap(), "guild1");
     }
 
+    #[tokio::test]
+    async fn test_observe_only() {
+        let bus = Arc::new(MessageBus::new(32));
+        let ch = DiscordChannel::new("test_token".into(), bus.clone(), vec![]).with_observe_only(true);
+        *ch.bot_id.lock().await = Some("bot1".into());
+
+        let message = |content: &str, extra: Value| {
+            let mut data = json!({
+                "author": { "id": "user1", "username": "testuser" },
+                "channel_id": "ch1",
+                "content": content,
+                "guild_id": "guild1"
+            });
+            data.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            data
+        };
+
+        ch.handle_message_create(&message("we ship friday", json!({}))).await;
+        assert!(bus.consume_inbound().await.unwrap().is_observed());
+
+        let mention = json!({ "mentions": [{ "id": "bot1" }] });
+        ch.handle_message_create(&message("<@bot1> what was decided?", mention)).await;
+        assert!(!bus.consume_inbound().await.unwrap().is_observed());
+
+        let reply = json!({ "referenced_message": { "id": "m0", "author": { "id": "bot1" } } });
+        ch.handle_message_create(&message("thanks", reply)).await;
+        assert!(!bus.consume_inbound().await.unwrap().is_observed());
+
+        let mut dm = message("hi", json!({}));
+        dm.as_object_mut().unwrap().remove("guild_id");
+        ch.handle_message_create(&dm).await;
+        assert!(!bus.consume_inbound().await.unwrap().is_observed());
+        ch.stop_all_typing().await;
+    }
+
     #[tokio::test]
     async fn test_handle_message_create_empty() {
         let bus = Arc::new(MessageBus::new(32));
@@ -1044,4 +1679,29 @@ mod tests {
         let tasks = ch.typing_tasks.read().await;
         assert!(tasks.is_empty());
     }
+
+    #[test]
+    fn test_embed_message() {
+        let reply = "Done:\n- [x] build\n- [x] test\n```\nok\n```\nexit code 0";
+        let body = embed_message(&RichContent::parse(reply).unwrap()).unwrap();
+        assert_eq!(body["content"], "Done:");
+        let embeds = body["embeds"].as_array().unwrap();
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0]["color"], COLOR_GREEN);
+        assert_eq!(embeds[0]["footer"]["text"], "2/2 done");
+        assert_eq!(embeds[1]["fields"][0]["name"], "Exit code");
+        assert_eq!(embeds[1]["fields"][0]["inline"], true);
+
+        // Long output is clipped, keeping the code block closed
+        let long = format!("```\n{}\n```\nexit code 2", "x".repeat(5000));
+        let body = embed_message(&RichContent::parse(&long).unwrap()).unwrap();
+        let description = body["embeds"][0]["description"].as_str().unwrap();
+        assert!(description.chars().count() <= EMBED_DESCRIPTION_LEN);
+        assert!(description.ends_with("...\n```"));
+        assert_eq!(body["embeds"][0]["color"], COLOR_RED);
+
+        // Text too long for one message: sent as plain chunks instead
+        let too_long = format!("{}\n- [ ] a", "y".repeat(2100));
+        assert!(embed_message(&RichContent::parse(&too_long).unwrap()).is_none());
+    }
 }
@@ -0,0 +1,794 @@
+//! Discord voice — listen in a voice channel and answer in text.
+//!
+//! With the `discord-voice` feature and `channels.discord.voice` enabled,
+//! the bot joins a voice channel through the main gateway, connects to the
+//! voice gateway and receives everyone's audio over UDP/RTP. Packets are
+//! decrypted (`aead_xchacha20_poly1305_rtpsize`), grouped per speaker into
+//! utterances that end at a pause, written as Ogg Opus and transcribed.
+//! Utterances that start with a wake word ("hey bot, …") go to the agent
+//! as messages in the configured text channel, where the answer appears.
+//!
+//! Audio is never decoded here: the Opus frames Discord sends are put into
+//! an Ogg container as they are, which the transcription API accepts.
+//! Calls with end-to-end encryption (DAVE) required are not supported.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::Utc;
+use serde_json::{json, Value};
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::config::schema::DiscordVoiceConfig;
+use oxibot_core::utils;
+
+use crate::base::{transcribe, TranscribeFn};
+
+// ─────────────────────────────────────────────
+// Constants
+// ─────────────────────────────────────────────
+
+/// Voice gateway version.
+const VOICE_GATEWAY_VERSION: u8 = 8;
+
+/// The only encryption mode supported.
+const ENCRYPTION_MODE: &str = "aead_xchacha20_poly1305_rtpsize";
+
+/// Main gateway opcode that joins or leaves a voice channel.
+const OP_VOICE_STATE_UPDATE: u64 = 4;
+
+// Voice gateway opcodes
+const VOP_IDENTIFY: u64 = 0;
+const VOP_SELECT_PROTOCOL: u64 = 1;
+const VOP_READY: u64 = 2;
+const VOP_HEARTBEAT: u64 = 3;
+const VOP_SESSION_DESCRIPTION: u64 = 4;
+const VOP_SPEAKING: u64 = 5;
+const VOP_HELLO: u64 = 8;
+const VOP_CLIENT_DISCONNECT: u64 = 13;
+
+/// Close code sent when the bot was removed from the voice channel.
+const CLOSE_DISCONNECTED: u16 = 4014;
+
+/// RTP payload type of Opus audio.
+const OPUS_PAYLOAD_TYPE: u8 = 120;
+
+/// Opus frame clients send when they stop talking.
+const OPUS_SILENCE: [u8; 3] = [0xF8, 0xFF, 0xFE];
+
+/// Samples per 20 ms Opus frame at 48 kHz.
+const SAMPLES_PER_FRAME: u64 = 960;
+
+/// Shortest utterance transcribed (0.3 s); shorter ones are noise.
+const MIN_FRAMES: usize = 15;
+
+/// Longest utterance (30 s); longer speech is cut into several.
+const MAX_FRAMES: usize = 1500;
+
+/// Opus frames per Ogg page. At most 6 lacing values per frame keep a
+/// page under the 255-segment limit.
+const FRAMES_PER_PAGE: usize = 40;
+
+/// How often finished utterances are looked for.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// UDP keepalive interval.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Connection attempts with the same voice server before giving up until
+/// the next gateway session.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wait between connection attempts.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// ─────────────────────────────────────────────
+// VoiceListener
+// ─────────────────────────────────────────────
+
+/// Where to connect for voice, from the main gateway's voice events.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct VoiceServer {
+    user_id: String,
+    session_id: String,
+    token: String,
+    endpoint: String,
+}
+
+/// Voice details received so far.
+#[derive(Default)]
+struct Pending {
+    user_id: Option<String>,
+    session_id: Option<String>,
+    token: Option<String>,
+    endpoint: Option<String>,
+    /// The server the running session is connected to.
+    connected: Option<VoiceServer>,
+}
+
+impl Pending {
+    /// The voice server, once both voice events arrived.
+    fn server(&self) -> Option<VoiceServer> {
+        Some(VoiceServer {
+            user_id: self.user_id.clone()?,
+            session_id: self.session_id.clone()?,
+            token: self.token.clone()?,
+            endpoint: self.endpoint.clone()?,
+        })
+    }
+}
+
+/// Listens in one voice channel and turns addressed speech into messages.
+pub struct VoiceListener {
+    config: DiscordVoiceConfig,
+    bus: Arc<MessageBus>,
+    /// Allow-list of Discord user IDs. Empty = everyone in the channel.
+    allowed_users: Vec<String>,
+    /// Where utterances are written while they are transcribed.
+    media_dir: PathBuf,
+    pending: Mutex<Pending>,
+    /// The running voice session.
+    session: Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl VoiceListener {
+    pub fn new(config: DiscordVoiceConfig, bus: Arc<MessageBus>, allowed_users: Vec<String>) -> Self {
+        Self {
+            config,
+            bus,
+            allowed_users,
+            media_dir: utils::get_media_path(),
+            pending: Mutex::default(),
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Main gateway payload that joins the voice channel (muted: the bot
+    /// only listens).
+    pub fn join_request(&self) -> Value {
+        json!({
+            "op": OP_VOICE_STATE_UPDATE,
+            "d": {
+                "guild_id": self.config.guild_id,
+                "channel_id": self.config.channel_id,
+                "self_mute": true,
+                "self_deaf": false
+            }
+        })
+    }
+
+    /// Handle a `VOICE_STATE_UPDATE` or `VOICE_SERVER_UPDATE` dispatch.
+    /// Connects to the voice server once both arrived, and again when it
+    /// changes.
+    pub async fn handle_dispatch(
+        self: &Arc<Self>,
+        event: &str,
+        data: &Value,
+        bot_id: Option<&str>,
+        transcriber: Option<TranscribeFn>,
+    ) {
+        if data["guild_id"].as_str() != Some(self.config.guild_id.as_str()) {
+            return;
+        }
+        let mut pending = self.pending.lock().await;
+        match event {
+            "VOICE_STATE_UPDATE" => {
+                if bot_id.is_none() || data["user_id"].as_str() != bot_id {
+                    return;
+                }
+                if data["channel_id"].is_null() {
+                    info!("left discord voice channel");
+                    *pending = Pending::default();
+                    drop(pending);
+                    self.leave().await;
+                    return;
+                }
+                pending.user_id = bot_id.map(String::from);
+                pending.session_id = data["session_id"].as_str().map(String::from);
+            }
+            "VOICE_SERVER_UPDATE" => {
+                pending.token = data["token"].as_str().map(String::from);
+                // A null endpoint means the server is being reallocated
+                pending.endpoint = data["endpoint"].as_str().map(String::from);
+            }
+            _ => return,
+        }
+        let Some(server) = pending.server() else { return };
+        if pending.connected.as_ref() == Some(&server) {
+            return;
+        }
+        pending.connected = Some(server.clone());
+        drop(pending);
+
+        let listener = self.clone();
+        let task = tokio::spawn(async move {
+            for attempt in 1..=MAX_ATTEMPTS {
+                match listener.run_session(&server, transcriber.clone()).await {
+                    Ok(()) => return,
+                    Err(e) => warn!(attempt, error = %e, "discord voice connection failed"),
+                }
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            error!("giving up on the discord voice channel until the next gateway session");
+            listener.pending.lock().await.connected = None;
+        });
+        if let Some(old) = self.session.lock().await.replace(task) {
+            old.abort();
+        }
+    }
+
+    /// Stop listening.
+    pub async fn leave(&self) {
+        if let Some(task) = self.session.lock().await.take() {
+            task.abort();
+        }
+    }
+
+    /// One voice gateway connection, until it closes.
+    async fn run_session(self: &Arc<Self>, server: &VoiceServer, transcriber: Option<TranscribeFn>) -> anyhow::Result<()> {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let endpoint = server.endpoint.trim_start_matches("wss://").trim_end_matches('/');
+        let url = format!("wss://{endpoint}/?v={VOICE_GATEWAY_VERSION}");
+        debug!(url = %url, "connecting to discord voice gateway");
+        let (ws_stream, _) = tokio_tungstenite::connect_async(&url).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        let identify = json!({
+            "op": VOP_IDENTIFY,
+            "d": {
+                "server_id": self.config.guild_id,
+                "user_id": server.user_id,
+                "session_id": server.session_id,
+                "token": server.token
+            }
+        });
+        write.send(WsMessage::text(identify.to_string())).await?;
+
+        let silence = Duration::from_millis(self.config.silence_ms.max(200));
+        let mut seq_ack: i64 = -1;
+        let mut heartbeat: Option<tokio::time::Interval> = None;
+        let mut udp: Option<(UdpSocket, u32)> = None;
+        let mut cipher: Option<XChaCha20Poly1305> = None;
+        let mut speakers: HashMap<u32, String> = HashMap::new();
+        let mut utterances = Utterances::default();
+        let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        let mut buf = [0u8; 2048];
+
+        loop {
+            let mut finished = Vec::new();
+            tokio::select! {
+                msg = read.next() => {
+                    let text = match msg {
+                        Some(Ok(WsMessage::Text(t))) => t.to_string(),
+                        Some(Ok(WsMessage::Close(frame))) => {
+                            let code = frame.map(|f| u16::from(f.code)).unwrap_or(0);
+                            if code == CLOSE_DISCONNECTED {
+                                info!("removed from discord voice channel");
+                                return Ok(());
+                            }
+                            anyhow::bail!("voice gateway closed the connection ({code})");
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Err(e.into()),
+                        None => anyhow::bail!("voice gateway connection ended"),
+                    };
+                    let Ok(payload) = serde_json::from_str::<Value>(&text) else { continue };
+                    if let Some(seq) = payload["seq"].as_i64() {
+                        seq_ack = seq;
+                    }
+                    let d = &payload["d"];
+                    match payload["op"].as_u64().unwrap_or(u64::MAX) {
+                        VOP_HELLO => {
+                            let ms = d["heartbeat_interval"].as_f64().unwrap_or(13750.0);
+                            let mut interval = tokio::time::interval(Duration::from_millis(ms as u64));
+                            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                            heartbeat = Some(interval);
+                        }
+                        VOP_READY => {
+                            let modes = d["modes"].as_array().cloned().unwrap_or_default();
+                            if !modes.iter().any(|m| m.as_str() == Some(ENCRYPTION_MODE)) {
+                                anyhow::bail!("voice server does not offer {ENCRYPTION_MODE}");
+                            }
+                            let ssrc = d["ssrc"].as_u64().unwrap_or(0) as u32;
+                            let addr = format!("{}:{}", d["ip"].as_str().unwrap_or_default(), d["port"].as_u64().unwrap_or(0));
+                            let socket = UdpSocket::bind("0.0.0.0:0").await?;
+                            socket.connect(&addr).await?;
+                            let (ip, port) = discover_ip(&socket, ssrc).await?;
+                            let select = json!({
+                                "op": VOP_SELECT_PROTOCOL,
+                                "d": {
+                                    "protocol": "udp",
+                                    "data": { "address": ip, "port": port, "mode": ENCRYPTION_MODE }
+                                }
+                            });
+                            write.send(WsMessage::text(select.to_string())).await?;
+                            udp = Some((socket, ssrc));
+                        }
+                        VOP_SESSION_DESCRIPTION => {
+                            let key: Vec<u8> = d["secret_key"]
+                                .as_array()
+                                .map(|k| k.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect())
+                                .unwrap_or_default();
+                            cipher = Some(
+                                XChaCha20Poly1305::new_from_slice(&key)
+                                    .map_err(|_| anyhow::anyhow!("invalid voice secret key"))?,
+                            );
+                            info!(channel = %self.config.channel_id, "listening in discord voice channel");
+                        }
+                        VOP_SPEAKING => {
+                            if let (Some(ssrc), Some(user)) = (d["ssrc"].as_u64(), d["user_id"].as_str()) {
+                                speakers.insert(ssrc as u32, user.to_string());
+                            }
+                        }
+                        VOP_CLIENT_DISCONNECT => {
+                            if let Some(user) = d["user_id"].as_str() {
+                                speakers.retain(|_, u| u != user);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ = tick(&mut heartbeat) => {
+                    let beat = json!({
+                        "op": VOP_HEARTBEAT,
+                        "d": { "t": Utc::now().timestamp_millis(), "seq_ack": seq_ack }
+                    });
+                    write.send(WsMessage::text(beat.to_string())).await?;
+                }
+                received = recv(&udp, &mut buf) => {
+                    let len = received?;
+                    let Some(ref cipher) = cipher else { continue };
+                    if let Some((ssrc, opus)) = open_rtp(cipher, &buf[..len]) {
+                        if opus != OPUS_SILENCE {
+                            finished.extend(utterances.push(ssrc, opus, Instant::now()).map(|frames| (ssrc, frames)));
+                        }
+                    }
+                }
+                _ = flush.tick() => {
+                    finished = utterances.finished(Instant::now(), silence);
+                }
+                _ = keepalive.tick() => {
+                    if let Some((ref socket, ssrc)) = udp {
+                        let _ = socket.send(&ssrc.to_be_bytes()).await;
+                    }
+                }
+            }
+
+            for (ssrc, frames) in finished {
+                let Some(user_id) = speakers.get(&ssrc).cloned() else {
+                    debug!(ssrc, "utterance from unknown speaker, dropping");
+                    continue;
+                };
+                let listener = self.clone();
+                let transcriber = transcriber.clone();
+                tokio::spawn(async move { listener.answer(user_id, ssrc, frames, transcriber).await });
+            }
+        }
+    }
+
+    /// Transcribe an utterance and, if it addresses the bot, send it to
+    /// the agent.
+    async fn answer(&self, user_id: String, ssrc: u32, frames: Vec<Vec<u8>>, transcriber: Option<TranscribeFn>) {
+        if !self.allowed_users.is_empty() && !self.allowed_users.contains(&user_id) {
+            debug!(user = %user_id, "ignoring voice from user not in allow list");
+            return;
+        }
+        let path = self.media_dir.join(format!("voice_{user_id}_{}.ogg", Utc::now().timestamp_millis()));
+        let written = std::fs::create_dir_all(&self.media_dir).and_then(|()| std::fs::write(&path, ogg_opus(&frames, ssrc)));
+        if let Err(e) = written {
+            warn!(error = %e, "failed to write voice utterance");
+            return;
+        }
+        let transcript = transcribe(transcriber.as_ref(), &path.display().to_string()).await;
+        let _ = std::fs::remove_file(&path);
+        let Some(transcript) = transcript else { return };
+        let Some(request) = addressed(&transcript.text, &self.config.wake_words) else {
+            debug!(user = %user_id, "voice utterance without wake word");
+            return;
+        };
+
+        info!(user = %user_id, chars = request.len(), "discord voice request");
+        let mut inbound = InboundMessage::new("discord", &user_id, &self.config.text_channel_id, request);
+        inbound.metadata.extend(transcript.to_metadata());
+        inbound.metadata.insert("voice_channel_id".into(), self.config.channel_id.clone());
+        if let Err(e) = self.bus.publish_inbound(inbound).await {
+            error!(error = %e, "failed to publish discord voice message");
+        }
+    }
+}
+
+/// Wait for the next tick, or forever before the interval is known.
+async fn tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Receive a datagram, or wait forever before the socket exists.
+async fn recv(udp: &Option<(UdpSocket, u32)>, buf: &mut [u8]) -> std::io::Result<usize> {
+    match udp {
+        Some((socket, _)) => socket.recv(buf).await,
+        None => std::future::pending().await,
+    }
+}
+
+// ─────────────────────────────────────────────
+// UDP and RTP
+// ─────────────────────────────────────────────
+
+/// Ask the voice server for our external address and port.
+async fn discover_ip(socket: &UdpSocket, ssrc: u32) -> anyhow::Result<(String, u16)> {
+    socket.send(&discovery_request(ssrc)).await?;
+    let mut buf = [0u8; 74];
+    let len = tokio::time::timeout(Duration::from_secs(5), socket.recv(&mut buf)).await??;
+    parse_discovery(&buf[..len]).ok_or_else(|| anyhow::anyhow!("invalid IP discovery response"))
+}
+
+/// IP discovery request: type 1, length 70, our SSRC, empty address and port.
+fn discovery_request(ssrc: u32) -> [u8; 74] {
+    let mut packet = [0u8; 74];
+    packet[0..2].copy_from_slice(&1u16.to_be_bytes());
+    packet[2..4].copy_from_slice(&70u16.to_be_bytes());
+    packet[4..8].copy_from_slice(&ssrc.to_be_bytes());
+    packet
+}
+
+/// Address and port from an IP discovery response.
+fn parse_discovery(packet: &[u8]) -> Option<(String, u16)> {
+    if packet.len() < 74 || packet[0..2] != 2u16.to_be_bytes() {
+        return None;
+    }
+    let address = &packet[8..72];
+    let end = address.iter().position(|&b| b == 0).unwrap_or(address.len());
+    let address = std::str::from_utf8(&address[..end]).ok()?.to_string();
+    Some((address, u16::from_be_bytes([packet[72], packet[73]])))
+}
+
+/// The SSRC and Opus frame of an encrypted RTP packet, or `None` for
+/// anything else (RTCP, other payloads, packets that fail to decrypt).
+///
+/// In the `rtpsize` modes the header up to the extension's 4-byte preamble
+/// is authenticated but not encrypted, the extension body is encrypted
+/// with the payload, and the last 4 bytes are the nonce.
+fn open_rtp(cipher: &XChaCha20Poly1305, packet: &[u8]) -> Option<(u32, Vec<u8>)> {
+    if packet.len() < 12 + 4 + 16 || packet[0] >> 6 != 2 || packet[1] & 0x7F != OPUS_PAYLOAD_TYPE {
+        return None;
+    }
+    let padded = packet[0] & 0x20 != 0;
+    let extended = packet[0] & 0x10 != 0;
+    let csrcs = (packet[0] & 0x0F) as usize;
+    let ssrc = u32::from_be_bytes(packet[8..12].try_into().ok()?);
+
+    let mut header_len = 12 + 4 * csrcs;
+    if extended {
+        header_len += 4;
+    }
+    let body_end = packet.len() - 4;
+    if header_len + 16 > body_end {
+        return None;
+    }
+    let mut nonce = [0u8; 24];
+    nonce[..4].copy_from_slice(&packet[body_end..]);
+    let payload = Payload {
+        msg: &packet[header_len..body_end],
+        aad: &packet[..header_len],
+    };
+    let mut plain = cipher.decrypt(XNonce::from_slice(&nonce), payload).ok()?;
+
+    if padded {
+        let pad = *plain.last()? as usize;
+        plain.truncate(plain.len().checked_sub(pad)?);
+    }
+    if extended {
+        let words = u16::from_be_bytes([packet[header_len - 2], packet[header_len - 1]]) as usize;
+        if words * 4 > plain.len() {
+            return None;
+        }
+        plain.drain(..words * 4);
+    }
+    Some((ssrc, plain))
+}
+
+// ─────────────────────────────────────────────
+// Utterances
+// ─────────────────────────────────────────────
+
+/// Opus frames of one speaker since they started talking.
+struct Utterance {
+    frames: Vec<Vec<u8>>,
+    last: Instant,
+}
+
+/// Splits each speaker's audio into utterances at pauses.
+#[derive(Default)]
+struct Utterances {
+    open: HashMap<u32, Utterance>,
+}
+
+impl Utterances {
+    /// Add a frame from `ssrc`. Returns the utterance if it reached the
+    /// maximum length.
+    fn push(&mut self, ssrc: u32, frame: Vec<u8>, now: Instant) -> Option<Vec<Vec<u8>>> {
+        let utterance = self.open.entry(ssrc).or_insert_with(|| Utterance {
+            frames: Vec::new(),
+            last: now,
+        });
+        utterance.frames.push(frame);
+        utterance.last = now;
+        if utterance.frames.len() < MAX_FRAMES {
+            return None;
+        }
+        self.open.remove(&ssrc).map(|u| u.frames)
+    }
+
+    /// Utterances whose speaker has been quiet for `silence`, long enough
+    /// to be worth transcribing.
+    fn finished(&mut self, now: Instant, silence: Duration) -> Vec<(u32, Vec<Vec<u8>>)> {
+        let done: Vec<u32> = self
+            .open
+            .iter()
+            .filter(|(_, u)| now.duration_since(u.last) >= silence)
+            .map(|(&ssrc, _)| ssrc)
+            .collect();
+        done.into_iter()
+            .filter_map(|ssrc| self.open.remove(&ssrc).map(|u| (ssrc, u.frames)))
+            .filter(|(_, frames)| frames.len() >= MIN_FRAMES)
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Ogg Opus
+// ─────────────────────────────────────────────
+
+/// Ogg page flags.
+const OGG_FIRST_PAGE: u8 = 0x02;
+const OGG_LAST_PAGE: u8 = 0x04;
+
+/// An Ogg Opus file holding `frames` (20 ms stereo Opus frames, as sent
+/// by Discord), without re-encoding them.
+fn ogg_opus(frames: &[Vec<u8>], serial: u32) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1); // version
+    head.push(2); // channels
+    head.extend(312u16.to_le_bytes()); // pre-skip
+    head.extend(48_000u32.to_le_bytes()); // input sample rate
+    head.extend(0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+
+    let vendor = b"oxibot";
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend((vendor.len() as u32).to_le_bytes());
+    tags.extend(vendor);
+    tags.extend(0u32.to_le_bytes());
+
+    let mut out = Vec::new();
+    ogg_page(&mut out, serial, 0, 0, OGG_FIRST_PAGE, &[head]);
+    ogg_page(&mut out, serial, 1, 0, 0, &[tags]);
+    let pages = frames.len().div_ceil(FRAMES_PER_PAGE);
+    let mut granule = 0;
+    for (i, chunk) in frames.chunks(FRAMES_PER_PAGE).enumerate() {
+        granule += chunk.len() as u64 * SAMPLES_PER_FRAME;
+        let flags = if i + 1 == pages { OGG_LAST_PAGE } else { 0 };
+        ogg_page(&mut out, serial, i as u32 + 2, granule, flags, chunk);
+    }
+    out
+}
+
+/// Append one Ogg page holding `packets`.
+fn ogg_page(out: &mut Vec<u8>, serial: u32, sequence: u32, granule: u64, flags: u8, packets: &[Vec<u8>]) {
+    let mut lacing = Vec::new();
+    for packet in packets {
+        lacing.extend(std::iter::repeat_n(255u8, packet.len() / 255));
+        lacing.push((packet.len() % 255) as u8);
+    }
+    let start = out.len();
+    out.extend(b"OggS");
+    out.push(0); // version
+    out.push(flags);
+    out.extend(granule.to_le_bytes());
+    out.extend(serial.to_le_bytes());
+    out.extend(sequence.to_le_bytes());
+    out.extend([0u8; 4]); // checksum, filled in below
+    out.push(lacing.len() as u8);
+    out.extend(&lacing);
+    for packet in packets {
+        out.extend(packet);
+    }
+    let crc = ogg_crc(&out[start..]);
+    out[start + 22..start + 26].copy_from_slice(&crc.to_le_bytes());
+}
+
+/// Ogg's CRC-32 (polynomial 0x04C11DB7, no reflection, zero init).
+fn ogg_crc(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in data {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 { (crc << 1) ^ 0x04C1_1DB7 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+// ─────────────────────────────────────────────
+// Wake words
+// ─────────────────────────────────────────────
+
+/// What was said after the first wake word in `text`, or `None` if there
+/// is none or nothing follows it. Matching ignores case and punctuation
+/// ("Hey, bot!"). Without wake words everything said is returned.
+fn addressed(text: &str, wake_words: &[String]) -> Option<String> {
+    let text = text.trim();
+    if wake_words.is_empty() {
+        return (!text.is_empty()).then(|| text.to_string());
+    }
+    let spoken = words(text);
+    for wake in wake_words {
+        let wake: Vec<String> = words(wake).into_iter().map(|(_, _, w)| w).collect();
+        if wake.is_empty() || wake.len() > spoken.len() {
+            continue;
+        }
+        let found = spoken
+            .windows(wake.len())
+            .find(|window| window.iter().zip(&wake).all(|((_, _, a), b)| a == b));
+        if let Some(window) = found {
+            let end = window[wake.len() - 1].1;
+            let rest = text[end..].trim_start_matches(|c: char| !c.is_alphanumeric()).trim();
+            return (!rest.is_empty()).then(|| rest.to_string());
+        }
+    }
+    None
+}
+
+/// Words of `text` as `(start, end, lowercase word)`.
+fn words(text: &str) -> Vec<(usize, usize, String)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            start.get_or_insert(i);
+        } else if let Some(s) = start.take() {
+            out.push((s, i, text[s..i].to_lowercase()));
+        }
+    }
+    if let Some(s) = start {
+        out.push((s, text.len(), text[s..].to_lowercase()));
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ogg_crc() {
+        // CRC-32/CKSUM check value without the final inversion
+        assert_eq!(ogg_crc(b"123456789"), !0x765E_7680);
+    }
+
+    #[test]
+    fn test_ogg_opus_pages() {
+        let frames: Vec<Vec<u8>> = (0..100).map(|i| vec![0xFC; 100 + i * 3]).collect();
+        let ogg = ogg_opus(&frames, 7);
+
+        let mut pages = Vec::new();
+        let mut at = 0;
+        while at < ogg.len() {
+            assert_eq!(&ogg[at..at + 4], b"OggS");
+            let segments = ogg[at + 26] as usize;
+            let body: usize = ogg[at + 27..at + 27 + segments].iter().map(|&s| s as usize).sum();
+            let len = 27 + segments + body;
+            let mut page = ogg[at..at + len].to_vec();
+            let crc = u32::from_le_bytes(page[22..26].try_into().unwrap());
+            page[22..26].fill(0);
+            assert_eq!(ogg_crc(&page), crc);
+            pages.push((ogg[at + 5], u64::from_le_bytes(ogg[at + 6..at + 14].try_into().unwrap())));
+            at += len;
+        }
+        // OpusHead, OpusTags, then 40 + 40 + 20 frames
+        assert_eq!(pages.len(), 5);
+        assert_eq!(&ogg[28..36], b"OpusHead");
+        assert_eq!(pages[0].0, OGG_FIRST_PAGE);
+        assert_eq!(pages[4], (OGG_LAST_PAGE, 100 * SAMPLES_PER_FRAME));
+    }
+
+    #[test]
+    fn test_open_rtp() {
+        let key = [7u8; 32];
+        let cipher = XChaCha20Poly1305::new_from_slice(&key).unwrap();
+        let opus = vec![0xFC, 0x01, 0x02, 0x03];
+
+        // Header with an extension of one word, encrypted with the payload
+        let mut header = vec![0x90, OPUS_PAYLOAD_TYPE, 0, 1, 0, 0, 0, 0];
+        header.extend(42u32.to_be_bytes());
+        header.extend([0xBE, 0xDE, 0, 1]);
+        let mut plain = vec![0x10, 0xAA, 0, 0];
+        plain.extend(&opus);
+        let counter = 9u32.to_be_bytes();
+        let mut nonce = [0u8; 24];
+        nonce[..4].copy_from_slice(&counter);
+        let sealed = cipher
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: &plain, aad: &header })
+            .unwrap();
+        let mut packet = header.clone();
+        packet.extend(&sealed);
+        packet.extend(counter);
+
+        assert_eq!(open_rtp(&cipher, &packet), Some((42, opus)));
+
+        // Tampered header, RTCP
+        let mut tampered = packet.clone();
+        tampered[3] = 2;
+        assert_eq!(open_rtp(&cipher, &tampered), None);
+        let mut rtcp = packet;
+        rtcp[1] = 0xC9;
+        assert_eq!(open_rtp(&cipher, &rtcp), None);
+    }
+
+    #[test]
+    fn test_ip_discovery() {
+        let request = discovery_request(0x0102_0304);
+        assert_eq!(&request[..8], &[0, 1, 0, 70, 1, 2, 3, 4]);
+
+        let mut response = [0u8; 74];
+        response[..2].copy_from_slice(&2u16.to_be_bytes());
+        response[8..19].copy_from_slice(b"203.0.113.9");
+        response[72..].copy_from_slice(&50004u16.to_be_bytes());
+        assert_eq!(parse_discovery(&response), Some(("203.0.113.9".into(), 50004)));
+        assert_eq!(parse_discovery(&request), None);
+    }
+
+    #[test]
+    fn test_utterances_end_at_pauses() {
+        let mut utterances = Utterances::default();
+        let start = Instant::now();
+        let silence = Duration::from_millis(800);
+        for i in 0..20 {
+            let at = start + Duration::from_millis(20 * i);
+            assert_eq!(utterances.push(1, vec![i as u8], at), None);
+        }
+        utterances.push(2, vec![0], start); // too short to transcribe
+
+        let talking = start + Duration::from_millis(500);
+        assert!(utterances.finished(talking, silence).is_empty());
+        let quiet = start + Duration::from_millis(2000);
+        let done = utterances.finished(quiet, silence);
+        assert_eq!(done.len(), 1);
+        assert_eq!((done[0].0, done[0].1.len()), (1, 20));
+        assert!(utterances.open.is_empty());
+
+        // Long speech is cut at the maximum length
+        let cut = (0..MAX_FRAMES).filter_map(|_| utterances.push(3, vec![0], start)).next();
+        assert_eq!(cut.map(|f| f.len()), Some(MAX_FRAMES));
+    }
+
+    #[test]
+    fn test_addressed() {
+        let wake = vec!["hey bot".to_string(), "oxibot".to_string()];
+        assert_eq!(addressed("Hey, bot! What's the weather?", &wake).as_deref(), Some("What's the weather?"));
+        assert_eq!(addressed("so I said to oxibot: set a timer", &wake).as_deref(), Some("set a timer"));
+        assert_eq!(addressed("hey everyone, the bot is here", &wake), None);
+        assert_eq!(addressed("hey bot", &wake), None);
+        assert_eq!(addressed(" anything ", &[]).as_deref(), Some("anything"));
+    }
+}
@@ -10,25 +10,31 @@
 //! - IMAP/IMAPS polling for unread emails
 //! - SMTP/SMTPS sending via lettre
 //! - Allow-list by sender email address
-//! - Thread tracking via subject prefix (Re:)
+//! - Thread tracking via subject prefix (Re:) and In-Reply-To
+//! - To/CC/BCC, subject overrides and reply-all via outbound metadata,
+//!   guarded by a recipient allow-list
+//! - Optional approval in a chat before mail to chosen recipients is sent
 //! - HTML-to-text conversion for inbound emails
 //! - Body truncation for long emails
 //! - UID-based deduplication
 //! - New IMAP connection per poll cycle (matching nanobot)
+//! - Daily digest for low-priority senders/subjects (newsletters, notifications)
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
 use tokio::sync::{Mutex, Notify, RwLock};
 use tracing::{debug, error, info, warn};
 
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
+use oxibot_core::bus::Decision;
 use oxibot_core::config::schema::EmailConfig;
 
-use crate::base::Channel;
+use crate::base::{ActivityTracker, Channel, HealthReport};
 
 // ─────────────────────────────────────────────
 // Constants
@@ -52,6 +58,18 @@ const DEFAULT_IMAP_PORT: u16 = 993;
 /// Default SMTP port (STARTTLS).
 const DEFAULT_SMTP_PORT: u16 = 587;
 
+/// Body characters quoted per email in the digest.
+const DIGEST_SNIPPET_CHARS: usize = 200;
+
+/// Subject used for the digest thread.
+const DIGEST_SUBJECT: &str = "Daily email digest";
+
+/// Body characters shown when asking to approve an email.
+const PREVIEW_BODY_CHARS: usize = 1500;
+
+/// SMTP transport used for sending.
+type SmtpTransport = lettre::AsyncSmtpTransport<lettre::Tokio1Executor>;
+
 // ─────────────────────────────────────────────
 // Parsed email struct
 // ─────────────────────────────────────────────
@@ -67,10 +85,139 @@ struct ParsedEmail {
     date: String,
     /// Message-ID header.
     message_id: String,
+    /// `To` addresses (lowercase).
+    to: Vec<String>,
+    /// `Cc` addresses (lowercase).
+    cc: Vec<String>,
     /// Text body (plain text; HTML converted).
     body: String,
 }
 
+/// Resolved recipients of an outbound email.
+#[derive(Debug, Default, PartialEq)]
+struct Recipients {
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+}
+
+impl Recipients {
+    fn all(&self) -> impl Iterator<Item = &String> {
+        self.to.iter().chain(&self.cc).chain(&self.bcc)
+    }
+}
+
+/// An email ready to go out.
+struct Draft {
+    email: lettre::Message,
+    rcpt: Recipients,
+    subject: String,
+    body: String,
+}
+
+impl Draft {
+    /// The approval question shown in the confirming chat.
+    fn preview(&self) -> String {
+        let mut preview = format!("📧 Send this email?\n\nTo: {}\n", self.rcpt.to.join(", "));
+        if !self.rcpt.cc.is_empty() {
+            preview.push_str(&format!("Cc: {}\n", self.rcpt.cc.join(", ")));
+        }
+        if !self.rcpt.bcc.is_empty() {
+            preview.push_str(&format!("Bcc: {}\n", self.rcpt.bcc.join(", ")));
+        }
+        preview.push_str(&format!(
+            "Subject: {}\n\n{}",
+            self.subject,
+            EmailChannel::truncate(&self.body, PREVIEW_BODY_CHARS)
+        ));
+        preview
+    }
+
+    async fn send(&self, transport: &SmtpTransport) -> anyhow::Result<()> {
+        use lettre::AsyncTransport;
+
+        transport
+            .send(self.email.clone())
+            .await
+            .map_err(|e| anyhow::anyhow!("SMTP send error: {}", e))?;
+
+        info!(
+            to = %self.rcpt.to.join(", "),
+            cc = self.rcpt.cc.len(),
+            bcc = self.rcpt.bcc.len(),
+            subject = %self.subject,
+            "email sent"
+        );
+        Ok(())
+    }
+}
+
+/// Whether `addr` matches `pattern`: an exact address, an `@domain` suffix
+/// or `*`.
+fn address_matches(addr: &str, pattern: &str) -> bool {
+    let (addr, pattern) = (addr.to_lowercase(), pattern.trim().to_lowercase());
+    if pattern == "*" {
+        true
+    } else if pattern.starts_with('@') {
+        addr.ends_with(&pattern)
+    } else {
+        addr == pattern
+    }
+}
+
+/// What to do with an inbound email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    /// Publish to the agent right away.
+    RealTime,
+    /// Hold for the daily digest.
+    Digest,
+    /// Drop (sender not allowed).
+    Reject,
+}
+
+// ─────────────────────────────────────────────
+// Digest rules
+// ─────────────────────────────────────────────
+
+/// Compiled sender/subject patterns for the daily digest.
+#[derive(Debug, Default)]
+struct DigestRules {
+    senders: Vec<regex::Regex>,
+    subjects: Vec<regex::Regex>,
+}
+
+impl DigestRules {
+    /// Compile the configured patterns (case-insensitive). Invalid patterns
+    /// are logged and skipped.
+    fn from_config(config: &EmailConfig) -> Self {
+        let compile = |patterns: &[String]| -> Vec<regex::Regex> {
+            patterns
+                .iter()
+                .filter_map(|p| {
+                    match regex::RegexBuilder::new(p).case_insensitive(true).build() {
+                        Ok(re) => Some(re),
+                        Err(e) => {
+                            warn!(pattern = %p, error = %e, "email: invalid digest pattern");
+                            None
+                        }
+                    }
+                })
+                .collect()
+        };
+        Self {
+            senders: compile(&config.digest_senders),
+            subjects: compile(&config.digest_subjects),
+        }
+    }
+
+    /// Whether an email belongs in the digest.
+    fn matches(&self, sender: &str, subject: &str) -> bool {
+        self.senders.iter().any(|re| re.is_match(sender))
+            || self.subjects.iter().any(|re| re.is_match(subject))
+    }
+}
+
 // ─────────────────────────────────────────────
 // Minimal async IMAP client
 // ─────────────────────────────────────────────
@@ -286,6 +433,17 @@ impl ImapClient {
         Ok(())
     }
 
+    /// UID STORE +FLAGS (\Seen)
+    async fn store_seen_uid(&mut self, uid: &str) -> anyhow::Result<()> {
+        let cmd = format!("UID STORE {} +FLAGS (\\Seen)", uid);
+        let tag = self.send_command(&cmd).await?;
+        let (_, status) = self.read_response(&tag).await?;
+        if !status.to_uppercase().contains("OK") {
+            anyhow::bail!("IMAP UID STORE failed: {}", status);
+        }
+        Ok(())
+    }
+
     /// LOGOUT
     async fn logout(&mut self) -> anyhow::Result<()> {
         let tag = self.send_command("LOGOUT").await?;
@@ -313,11 +471,32 @@ pub struct EmailChannel {
     last_subject: Arc<RwLock<HashMap<String, String>>>,
     /// Last inbound Message-ID per sender (for In-Reply-To).
     last_message_id: Arc<RwLock<HashMap<String, String>>>,
+    /// Other To/Cc addresses of the last inbound email per sender (for reply-all).
+    thread_participants: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Patterns routing emails to the digest.
+    digest_rules: DigestRules,
+    /// Emails waiting for the next digest, by UID. They stay unseen on the
+    /// server until the digest is out, so a restart doesn't lose them.
+    pending_digest: Arc<Mutex<Vec<(String, ParsedEmail)>>>,
+    /// UIDs of emails in a delivered digest, to mark seen on the next poll.
+    digested_uids: Arc<Mutex<Vec<String>>>,
+    /// Date of the last digest delivery slot.
+    last_digest: Arc<Mutex<Option<NaiveDate>>>,
+    /// Last successful IMAP poll, for health reports.
+    activity: ActivityTracker,
 }
 
 impl EmailChannel {
     /// Create a new email channel.
     pub fn new(config: EmailConfig, bus: Arc<MessageBus>) -> Self {
+        let digest_rules = DigestRules::from_config(&config);
+        // Don't deliver a digest right after startup if today's slot has passed
+        let now = chrono::Local::now().naive_local();
+        let last_digest = if now.hour() >= config.digest_hour as u32 {
+            Some(now.date())
+        } else {
+            None
+        };
         Self {
             config,
             bus,
@@ -325,6 +504,12 @@ impl EmailChannel {
             processed_uids: Arc::new(Mutex::new(HashSet::new())),
             last_subject: Arc::new(RwLock::new(HashMap::new())),
             last_message_id: Arc::new(RwLock::new(HashMap::new())),
+            thread_participants: Arc::new(RwLock::new(HashMap::new())),
+            digest_rules,
+            pending_digest: Arc::new(Mutex::new(Vec::new())),
+            digested_uids: Arc::new(Mutex::new(Vec::new())),
+            last_digest: Arc::new(Mutex::new(last_digest)),
+            activity: ActivityTracker::new(),
         }
     }
 
@@ -344,6 +529,29 @@ impl EmailChannel {
             .any(|u| u.to_lowercase() == sender_lower)
     }
 
+    /// Check if a sender is explicitly listed in `allowed_users`.
+    fn is_listed(&self, sender: &str) -> bool {
+        !self.config.allowed_users.is_empty() && self.is_allowed(sender)
+    }
+
+    /// Decide how to handle an email.
+    ///
+    /// Explicitly allow-listed senders are always handled in real time.
+    /// Other emails matching a digest rule are held for the daily digest,
+    /// even if the sender isn't allow-listed (newsletters rarely are).
+    /// Emails matching neither are rejected.
+    fn route(&self, email: &ParsedEmail) -> Route {
+        if self.is_listed(&email.sender) {
+            Route::RealTime
+        } else if self.digest_rules.matches(&email.sender, &email.subject) {
+            Route::Digest
+        } else if self.is_allowed(&email.sender) {
+            Route::RealTime
+        } else {
+            Route::Reject
+        }
+    }
+
     /// Effective poll interval (minimum 5 seconds).
     fn poll_interval(&self) -> Duration {
         let secs = (self.config.poll_interval_seconds as u64).max(MIN_POLL_INTERVAL_SECS);
@@ -373,6 +581,30 @@ impl EmailChannel {
         from_header.trim().to_lowercase()
     }
 
+    /// Split an address list (`To`/`Cc` header or metadata value) into
+    /// lowercase addresses. Commas inside quoted display names are ignored.
+    fn split_addresses(list: &str) -> Vec<String> {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+        for c in list.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    current.push(c);
+                }
+                ',' | ';' if !quoted => parts.push(std::mem::take(&mut current)),
+                _ => current.push(c),
+            }
+        }
+        parts.push(current);
+        parts
+            .iter()
+            .map(|p| Self::extract_sender_email(p))
+            .filter(|a| a.contains('@'))
+            .collect()
+    }
+
     /// Convert minimal HTML to plain text.
     fn html_to_text(html: &str) -> String {
         let mut text = html.to_string();
@@ -421,6 +653,8 @@ impl EmailChannel {
         let subject = get_header("Subject");
         let date = get_header("Date");
         let message_id = get_header("Message-ID");
+        let to = Self::split_addresses(&get_header("To"));
+        let cc = Self::split_addresses(&get_header("Cc"));
 
         // Extract body
         let body = Self::extract_body(&parsed, max_body_chars);
@@ -430,6 +664,8 @@ impl EmailChannel {
             subject,
             date,
             message_id,
+            to,
+            cc,
             body,
         })
     }
@@ -567,6 +803,14 @@ impl EmailChannel {
         // Select mailbox
         imap.select(mailbox).await?;
 
+        // Mark the emails of the last digest as seen
+        let digested = std::mem::take(&mut *self.digested_uids.lock().await);
+        for uid in digested {
+            if let Err(e) = imap.store_seen_uid(&uid).await {
+                warn!(uid = %uid, error = %e, "failed to mark digested email as seen");
+            }
+        }
+
         // Search unseen
         let seqnums = imap.search_unseen().await?;
         debug!(count = seqnums.len(), "found unseen emails");
@@ -603,49 +847,21 @@ impl EmailChannel {
                 }
             };
 
-            // Allow-list check
-            if !self.is_allowed(&email.sender) {
-                warn!(sender = %email.sender, "email sender not in allow-list");
-                continue;
-            }
-
-            // Track subject and message-id for threading
-            {
-                let mut subjects = self.last_subject.write().await;
-                subjects.insert(email.sender.clone(), email.subject.clone());
-            }
-            if !email.message_id.is_empty() {
-                let mut msg_ids = self.last_message_id.write().await;
-                msg_ids.insert(email.sender.clone(), email.message_id.clone());
-            }
-
-            // Build content string (matching nanobot)
-            let content = format!(
-                "Email received.\nFrom: {}\nSubject: {}\nDate: {}\n\n{}",
-                email.sender, email.subject, email.date, email.body
-            );
-
-            // Build metadata
-            let mut metadata = HashMap::new();
-            metadata.insert("message_id".to_string(), email.message_id);
-            metadata.insert("subject".to_string(), email.subject);
-            metadata.insert("date".to_string(), email.date);
-            metadata.insert("sender_email".to_string(), email.sender.clone());
-            metadata.insert("uid".to_string(), uid.clone());
-
-            // Publish inbound
-            let inbound = InboundMessage {
-                sender_id: email.sender.clone(),
-                chat_id: email.sender.clone(), // sender email = chat_id
-                channel: "email".to_string(),
-                content,
-                timestamp: chrono::Utc::now(),
-                media: Vec::new(),
-                metadata,
-            };
-
-            if let Err(e) = self.bus.publish_inbound(inbound).await {
-                error!(error = %e, "failed to publish email inbound");
+            match self.route(&email) {
+                Route::Reject => {
+                    warn!(sender = %email.sender, "email sender not in allow-list");
+                    continue;
+                }
+                Route::Digest => {
+                    let mut pending = self.pending_digest.lock().await;
+                    if !pending.iter().any(|(held, _)| *held == uid) {
+                        debug!(sender = %email.sender, subject = %email.subject, "email held for digest");
+                        pending.push((uid, email));
+                    }
+                    // Marked seen once the digest is delivered
+                    continue;
+                }
+                Route::RealTime => self.publish_email(email, &uid).await,
             }
 
             // Mark as seen
@@ -664,14 +880,264 @@ impl EmailChannel {
         Ok(())
     }
 
+    /// Publish a single email to the agent.
+    async fn publish_email(&self, email: ParsedEmail, uid: &str) {
+        // Track subject and message-id for threading
+        {
+            let mut subjects = self.last_subject.write().await;
+            subjects.insert(email.sender.clone(), email.subject.clone());
+        }
+        if !email.message_id.is_empty() {
+            let mut msg_ids = self.last_message_id.write().await;
+            msg_ids.insert(email.sender.clone(), email.message_id.clone());
+        }
+        let others: Vec<String> = email
+            .to
+            .iter()
+            .chain(&email.cc)
+            .filter(|a| **a != email.sender && !self.is_own_address(a))
+            .cloned()
+            .collect();
+        self.thread_participants
+            .write()
+            .await
+            .insert(email.sender.clone(), others.clone());
+
+        // Build content string (matching nanobot); other recipients are
+        // listed so the agent knows when reply-all makes sense
+        let mut content = format!(
+            "Email received.\nFrom: {}\nSubject: {}\nDate: {}\n",
+            email.sender, email.subject, email.date
+        );
+        if !others.is_empty() {
+            content.push_str(&format!("Also sent to: {}\n", others.join(", ")));
+        }
+        content.push('\n');
+        content.push_str(&email.body);
+
+        // Build metadata
+        let mut metadata = HashMap::new();
+        metadata.insert("message_id".to_string(), email.message_id);
+        metadata.insert("subject".to_string(), email.subject);
+        metadata.insert("date".to_string(), email.date);
+        metadata.insert("sender_email".to_string(), email.sender.clone());
+        metadata.insert("uid".to_string(), uid.to_string());
+        if !email.to.is_empty() {
+            metadata.insert("to".to_string(), email.to.join(", "));
+        }
+        if !email.cc.is_empty() {
+            metadata.insert("cc".to_string(), email.cc.join(", "));
+        }
+
+        // Publish inbound
+        let inbound = InboundMessage {
+            sender_id: email.sender.clone(),
+            chat_id: email.sender.clone(), // sender email = chat_id
+            channel: "email".to_string(),
+            content,
+            timestamp: chrono::Utc::now(),
+            media: Vec::new(),
+            metadata,
+        };
+
+        if let Err(e) = self.bus.publish_inbound(inbound).await {
+            error!(error = %e, "failed to publish email inbound");
+        }
+    }
+
+    // ─────────────────────────────────────────
+    // Digest
+    // ─────────────────────────────────────────
+
+    /// Whether the daily digest slot has been reached and not yet used.
+    fn digest_due(now: NaiveDateTime, last: Option<NaiveDate>, hour: u8) -> bool {
+        now.hour() >= hour as u32 && last != Some(now.date())
+    }
+
+    /// Render held emails as a single digest message.
+    fn build_digest(emails: &[ParsedEmail]) -> String {
+        let noun = if emails.len() == 1 { "email" } else { "emails" };
+        let mut out = format!(
+            "Email digest: {} low-priority {} arrived since the last digest.\n",
+            emails.len(),
+            noun
+        );
+        for (i, email) in emails.iter().enumerate() {
+            let snippet: String = email
+                .body
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(DIGEST_SNIPPET_CHARS)
+                .collect();
+            out.push_str(&format!(
+                "\n{}. From: {}\n   Subject: {}\n   Date: {}\n   {}\n",
+                i + 1,
+                email.sender,
+                email.subject,
+                email.date,
+                snippet
+            ));
+        }
+        out
+    }
+
+    /// Address the digest conversation is tied to.
+    fn digest_recipient(&self) -> String {
+        [
+            &self.config.digest_deliver_to,
+            &self.config.from_address,
+            &self.config.smtp_username,
+            &self.config.imap_username,
+        ]
+        .into_iter()
+        .find(|a| !a.is_empty())
+        .cloned()
+        .unwrap_or_default()
+    }
+
+    /// Deliver held emails as one message once the daily slot is reached.
+    async fn maybe_deliver_digest(&self) {
+        let now = chrono::Local::now().naive_local();
+        {
+            let mut last = self.last_digest.lock().await;
+            if !Self::digest_due(now, *last, self.config.digest_hour) {
+                return;
+            }
+            *last = Some(now.date());
+        }
+
+        let pending = std::mem::take(&mut *self.pending_digest.lock().await);
+        if pending.is_empty() {
+            return;
+        }
+        let (uids, emails): (Vec<_>, Vec<_>) = pending.into_iter().unzip();
+
+        let recipient = self.digest_recipient();
+        self.last_subject
+            .write()
+            .await
+            .insert(recipient.clone(), DIGEST_SUBJECT.to_string());
+
+        let mut metadata = HashMap::new();
+        metadata.insert("digest".to_string(), "true".to_string());
+        metadata.insert("digest_count".to_string(), emails.len().to_string());
+        metadata.insert("subject".to_string(), DIGEST_SUBJECT.to_string());
+
+        let inbound = InboundMessage {
+            sender_id: "digest".to_string(),
+            chat_id: recipient,
+            channel: "email".to_string(),
+            content: Self::build_digest(&emails),
+            timestamp: chrono::Utc::now(),
+            media: Vec::new(),
+            metadata,
+        };
+
+        info!(count = emails.len(), "delivering email digest");
+        match self.bus.publish_inbound(inbound).await {
+            Ok(()) if self.config.mark_seen => self.digested_uids.lock().await.extend(uids),
+            Ok(()) => {}
+            Err(e) => error!(error = %e, "failed to publish email digest"),
+        }
+    }
+
     // ─────────────────────────────────────────
     // SMTP sending
     // ─────────────────────────────────────────
 
-    /// Send an email reply via SMTP using lettre.
-    async fn send_email(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
-        use lettre::transport::smtp::authentication::Credentials;
-        use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+    /// Whether `addr` is one of the channel's own addresses.
+    fn is_own_address(&self, addr: &str) -> bool {
+        [
+            &self.config.from_address,
+            &self.config.smtp_username,
+            &self.config.imap_username,
+        ]
+        .into_iter()
+        .any(|own| !own.is_empty() && own.eq_ignore_ascii_case(addr))
+    }
+
+    /// Check an address against `allowed_recipients` (exact, `@domain` or
+    /// `*`). With none configured, only the thread's participants get mail.
+    fn recipient_allowed(&self, addr: &str) -> bool {
+        self.config.allowed_recipients.iter().any(|p| address_matches(addr, p))
+    }
+
+    /// Whether mail to `addr` waits for approval (`confirm_recipients`).
+    fn needs_confirmation(&self, addr: &str) -> bool {
+        self.config.confirm_recipients.iter().any(|p| address_matches(addr, p))
+    }
+
+    /// Chat to ask for approval of `msg` in, with who may answer: the chat
+    /// it was sent from and its sender, else `confirm_channel`/`confirm_chat_id`
+    /// and only the admins.
+    fn confirmation_chat(&self, msg: &OutboundMessage) -> Option<(String, String, Option<String>)> {
+        match msg.origin() {
+            // Buttons can't be clicked in an email
+            Some((channel, chat_id)) if channel != "email" => Some((
+                channel.to_string(),
+                chat_id.to_string(),
+                msg.origin_sender().map(str::to_string),
+            )),
+            _ if !self.config.confirm_channel.is_empty() && !self.config.confirm_chat_id.is_empty() => {
+                Some((self.config.confirm_channel.clone(), self.config.confirm_chat_id.clone(), None))
+            }
+            _ => None,
+        }
+    }
+
+    /// Work out To/CC/BCC for an outbound message.
+    ///
+    /// `chat_id` is always the primary recipient. Metadata can add `to`,
+    /// `cc` and `bcc` lists; `reply_all = "true"` copies the other
+    /// participants of the thread. `thread` holds those participants when
+    /// `chat_id` is a sender we received mail from. Recipients outside the
+    /// thread (and not our own addresses) must pass `allowed_recipients`.
+    fn resolve_recipients(
+        &self,
+        msg: &OutboundMessage,
+        thread: Option<&[String]>,
+    ) -> anyhow::Result<Recipients> {
+        let list = |key: &str| {
+            msg.metadata
+                .get(key)
+                .map(|v| Self::split_addresses(v))
+                .unwrap_or_default()
+        };
+
+        let mut to = vec![msg.chat_id.trim().to_lowercase()];
+        to.extend(list("to"));
+        let mut cc = list("cc");
+        if msg.metadata.get("reply_all").is_some_and(|v| v == "true") {
+            cc.extend(thread.unwrap_or_default().iter().cloned());
+        }
+        let mut bcc = list("bcc");
+
+        // Each address appears once, in the most visible list
+        let mut seen = HashSet::new();
+        for list in [&mut to, &mut cc, &mut bcc] {
+            list.retain(|a| seen.insert(a.clone()));
+        }
+        let rcpt = Recipients { to, cc, bcc };
+
+        let in_thread = |addr: &str| {
+            thread.is_some_and(|t| addr == msg.chat_id.to_lowercase() || t.iter().any(|p| p == addr))
+        };
+        let blocked: Vec<&str> = rcpt
+            .all()
+            .map(String::as_str)
+            .filter(|a| !in_thread(a) && !self.is_own_address(a) && !self.recipient_allowed(a))
+            .collect();
+        if !blocked.is_empty() {
+            anyhow::bail!("recipients not in allowedRecipients: {}", blocked.join(", "));
+        }
+        Ok(rcpt)
+    }
+
+    /// Build the email for an outbound message.
+    async fn draft(&self, msg: &OutboundMessage) -> anyhow::Result<Draft> {
+        use lettre::Message;
 
         if self.config.smtp_host.is_empty() {
             anyhow::bail!("SMTP host not configured");
@@ -693,12 +1159,29 @@ impl EmailChannel {
             anyhow::bail!("no from_address configured");
         }
 
+        // Resolve recipients (known senders are threads we can answer)
+        let chat_key = &msg.chat_id;
+        let known = self.last_subject.read().await.contains_key(chat_key);
+        let thread = if known {
+            Some(
+                self.thread_participants
+                    .read()
+                    .await
+                    .get(chat_key)
+                    .cloned()
+                    .unwrap_or_default(),
+            )
+        } else {
+            None
+        };
+        let rcpt = self.resolve_recipients(msg, thread.as_deref())?;
+
         // Build subject
         let subject = if let Some(s) = msg.metadata.get("subject") {
             s.clone()
         } else {
             let subjects = self.last_subject.read().await;
-            let orig = subjects.get(&msg.chat_id).cloned().unwrap_or_default();
+            let orig = subjects.get(chat_key).cloned().unwrap_or_default();
             let prefix = if self.config.subject_prefix.is_empty() {
                 DEFAULT_SUBJECT_PREFIX
             } else {
@@ -708,14 +1191,41 @@ impl EmailChannel {
         };
 
         // Build lettre message
-        let email = Message::builder()
+        let mailbox = |addr: &str| -> anyhow::Result<lettre::message::Mailbox> {
+            addr.parse()
+                .map_err(|e| anyhow::anyhow!("invalid recipient address {addr}: {e}"))
+        };
+        let mut builder = Message::builder()
             .from(from_addr.parse().map_err(|e| anyhow::anyhow!("invalid from address: {}", e))?)
-            .to(msg.chat_id.parse().map_err(|e| anyhow::anyhow!("invalid to address: {}", e))?)
-            .subject(&subject)
+            .subject(&subject);
+        for addr in &rcpt.to {
+            builder = builder.to(mailbox(addr)?);
+        }
+        for addr in &rcpt.cc {
+            builder = builder.cc(mailbox(addr)?);
+        }
+        for addr in &rcpt.bcc {
+            builder = builder.bcc(mailbox(addr)?);
+        }
+        if let Some(id) = self.last_message_id.read().await.get(chat_key) {
+            builder = builder.in_reply_to(id.clone()).references(id.clone());
+        }
+        let email = builder
             .body(msg.content.clone())
             .map_err(|e| anyhow::anyhow!("failed to build email: {}", e))?;
 
-        // Build SMTP transport
+        Ok(Draft {
+            email,
+            rcpt,
+            subject,
+            body: msg.content.clone(),
+        })
+    }
+
+    /// Build the SMTP transport.
+    fn transport(&self) -> anyhow::Result<SmtpTransport> {
+        use lettre::transport::smtp::authentication::Credentials;
+
         let port = if self.config.smtp_port > 0 {
             self.config.smtp_port
         } else {
@@ -729,32 +1239,72 @@ impl EmailChannel {
 
         let transport = if self.config.smtp_use_ssl {
             // Implicit TLS (SMTPS, port 465)
-            AsyncSmtpTransport::<Tokio1Executor>::relay(&self.config.smtp_host)
+            SmtpTransport::relay(&self.config.smtp_host)
                 .map_err(|e| anyhow::anyhow!("SMTP relay error: {}", e))?
                 .port(port)
                 .credentials(creds)
                 .build()
         } else if self.config.smtp_use_tls {
             // STARTTLS (port 587)
-            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.config.smtp_host)
+            SmtpTransport::starttls_relay(&self.config.smtp_host)
                 .map_err(|e| anyhow::anyhow!("SMTP STARTTLS error: {}", e))?
                 .port(port)
                 .credentials(creds)
                 .build()
         } else {
             // Plain (no TLS)
-            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.config.smtp_host)
+            SmtpTransport::builder_dangerous(&self.config.smtp_host)
                 .port(port)
                 .credentials(creds)
                 .build()
         };
+        Ok(transport)
+    }
 
-        transport
-            .send(email)
-            .await
-            .map_err(|e| anyhow::anyhow!("SMTP send error: {}", e))?;
+    /// Send an email reply via SMTP using lettre, after approval when a
+    /// recipient is in `confirm_recipients`.
+    async fn send_email(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
+        let draft = self.draft(msg).await?;
+        let transport = self.transport()?;
+
+        let held: Vec<&str> = draft
+            .rcpt
+            .all()
+            .map(String::as_str)
+            .filter(|a| self.needs_confirmation(a))
+            .collect();
+        if held.is_empty() {
+            return draft.send(&transport).await;
+        }
+        let Some((channel, chat_id, requester)) = self.confirmation_chat(msg) else {
+            anyhow::bail!(
+                "email to {} needs approval, but there is no chat to ask in (set confirmChannel and confirmChatId)",
+                held.join(", ")
+            );
+        };
 
-        info!(to = %msg.chat_id, subject = %subject, "email sent");
+        // Wait for the answer in the background: the approval request goes
+        // out through the same dispatcher that is delivering this message
+        debug!(channel = %channel, chat_id = %chat_id, held = %held.join(", "), "email waiting for approval");
+        let bus = self.bus.clone();
+        let timeout = Duration::from_secs(self.config.confirm_timeout_secs);
+        tokio::spawn(async move {
+            let decision = bus
+                .request_approval(&channel, &chat_id, requester.as_deref(), &draft.preview(), timeout)
+                .await;
+            info!(to = %draft.rcpt.to.join(", "), decision = decision.as_str(), "email approval decided");
+            let note = match decision {
+                Decision::Approved => match draft.send(&transport).await {
+                    Ok(()) => "📧 Email sent.".to_string(),
+                    Err(e) => format!("❌ Email not sent: {e}"),
+                },
+                Decision::Denied => "🗑️ Email discarded.".to_string(),
+                Decision::TimedOut => "⌛ No answer, email discarded.".to_string(),
+            };
+            if let Err(e) = bus.publish_outbound(OutboundMessage::new(&channel, &chat_id, note)).await {
+                warn!(error = %e, "failed to report email approval outcome");
+            }
+        });
         Ok(())
     }
 }
@@ -787,9 +1337,11 @@ impl Channel for EmailChannel {
 
         loop {
             // Poll for new emails
-            if let Err(e) = self.poll_once().await {
-                warn!(error = %e, "email poll error (will retry)");
+            match self.poll_once().await {
+                Ok(()) => self.activity.touch(),
+                Err(e) => warn!(error = %e, "email poll error (will retry)"),
             }
+            self.maybe_deliver_digest().await;
 
             // Wait for interval or shutdown
             tokio::select! {
@@ -808,6 +1360,10 @@ impl Channel for EmailChannel {
         Ok(())
     }
 
+    fn health(&self) -> Option<HealthReport> {
+        Some(self.activity.report())
+    }
+
     async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
         self.send_email(msg).await
     }
@@ -841,9 +1397,37 @@ mod tests {
             max_body_chars: 12000,
             subject_prefix: "Re: ".into(),
             allowed_users: Vec::new(),
+            allowed_recipients: Vec::new(),
+            confirm_recipients: Vec::new(),
+            confirm_channel: String::new(),
+            confirm_chat_id: String::new(),
+            confirm_timeout_secs: 3600,
+            digest_senders: Vec::new(),
+            digest_subjects: Vec::new(),
+            digest_hour: 8,
+            digest_deliver_to: String::new(),
+        }
+    }
+
+    fn make_email(sender: &str, subject: &str) -> ParsedEmail {
+        ParsedEmail {
+            sender: sender.into(),
+            subject: subject.into(),
+            date: "Mon, 1 Jan 2024 00:00:00 +0000".into(),
+            message_id: String::new(),
+            to: vec!["bot@example.com".into()],
+            cc: Vec::new(),
+            body: "Hello\n\nworld".into(),
         }
     }
 
+    fn at(date: &str, hour: u32) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
     fn make_bus() -> Arc<MessageBus> {
         Arc::new(MessageBus::new(10))
     }
@@ -903,6 +1487,101 @@ mod tests {
         assert!(!ch.is_allowed("bob@example.com"));
     }
 
+    // ── Digest routing ──
+
+    #[test]
+    fn test_route_without_digest_rules() {
+        let ch = EmailChannel::new(make_config(), make_bus());
+        assert_eq!(ch.route(&make_email("news@shop.com", "Sale")), Route::RealTime);
+    }
+
+    #[test]
+    fn test_route_digest_by_sender_and_subject() {
+        let mut cfg = make_config();
+        cfg.digest_senders = vec![r"@substack\.com$".into()];
+        cfg.digest_subjects = vec!["newsletter".into()];
+        let ch = EmailChannel::new(cfg, make_bus());
+        assert_eq!(ch.route(&make_email("writer@substack.com", "Issue 42")), Route::Digest);
+        assert_eq!(ch.route(&make_email("shop@example.com", "Weekly NEWSLETTER")), Route::Digest);
+        assert_eq!(ch.route(&make_email("bob@example.com", "Lunch?")), Route::RealTime);
+    }
+
+    #[test]
+    fn test_route_allow_listed_sender_is_realtime() {
+        let mut cfg = make_config();
+        cfg.allowed_users = vec!["alice@example.com".into()];
+        cfg.digest_subjects = vec!["newsletter".into()];
+        let ch = EmailChannel::new(cfg, make_bus());
+        assert_eq!(ch.route(&make_email("alice@example.com", "My newsletter draft")), Route::RealTime);
+        assert_eq!(ch.route(&make_email("alice@example.com", "Lunch?")), Route::RealTime);
+        assert_eq!(ch.route(&make_email("news@shop.com", "Newsletter")), Route::Digest);
+        assert_eq!(ch.route(&make_email("bob@example.com", "Hi")), Route::Reject);
+    }
+
+    #[test]
+    fn test_invalid_digest_pattern_skipped() {
+        let mut cfg = make_config();
+        cfg.digest_senders = vec!["(".into(), "promo".into()];
+        let rules = DigestRules::from_config(&cfg);
+        assert_eq!(rules.senders.len(), 1);
+        assert!(rules.matches("promo@shop.com", ""));
+    }
+
+    #[test]
+    fn test_digest_due() {
+        assert!(!EmailChannel::digest_due(at("2024-01-02", 7), None, 8));
+        assert!(EmailChannel::digest_due(at("2024-01-02", 8), None, 8));
+        let yesterday = NaiveDate::from_ymd_opt(2024, 1, 1);
+        assert!(EmailChannel::digest_due(at("2024-01-02", 9), yesterday, 8));
+        let today = NaiveDate::from_ymd_opt(2024, 1, 2);
+        assert!(!EmailChannel::digest_due(at("2024-01-02", 20), today, 8));
+    }
+
+    #[test]
+    fn test_build_digest() {
+        let emails = vec![
+            make_email("a@news.com", "Issue 1"),
+            make_email("b@news.com", "Issue 2"),
+        ];
+        let digest = EmailChannel::build_digest(&emails);
+        assert!(digest.starts_with("Email digest: 2 low-priority emails arrived"));
+        assert!(digest.contains("1. From: a@news.com\n   Subject: Issue 1"));
+        assert!(digest.contains("2. From: b@news.com"));
+        assert!(digest.contains("   Hello world\n"));
+    }
+
+    #[test]
+    fn test_digest_recipient_fallback() {
+        let mut cfg = make_config();
+        let ch = EmailChannel::new(cfg.clone(), make_bus());
+        assert_eq!(ch.digest_recipient(), "bot@example.com");
+        cfg.digest_deliver_to = "me@example.com".into();
+        let ch = EmailChannel::new(cfg, make_bus());
+        assert_eq!(ch.digest_recipient(), "me@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_digest_delivered_once_per_day() {
+        let mut cfg = make_config();
+        cfg.digest_hour = 0;
+        let bus = make_bus();
+        let ch = EmailChannel::new(cfg, bus.clone());
+        *ch.last_digest.lock().await = None;
+        ch.pending_digest.lock().await.push(("7".into(), make_email("a@news.com", "Issue 1")));
+
+        ch.maybe_deliver_digest().await;
+        let msg = bus.consume_inbound().await.unwrap();
+        assert_eq!(msg.chat_id, "bot@example.com");
+        assert_eq!(msg.metadata.get("digest_count").unwrap(), "1");
+        assert!(ch.pending_digest.lock().await.is_empty());
+        assert_eq!(*ch.digested_uids.lock().await, vec!["7".to_string()]);
+
+        // Same day: held emails wait for tomorrow
+        ch.pending_digest.lock().await.push(("8".into(), make_email("b@news.com", "Issue 2")));
+        ch.maybe_deliver_digest().await;
+        assert_eq!(ch.pending_digest.lock().await.len(), 1);
+    }
+
     // ── Poll interval ──
 
     #[test]
@@ -1157,6 +1836,127 @@ mod tests {
 
     // ── Subject tracking ──
 
+    // ── Recipients ──
+
+    fn outbound(chat_id: &str, meta: &[(&str, &str)]) -> OutboundMessage {
+        let mut msg = OutboundMessage::new("email", chat_id, "hi");
+        for (k, v) in meta {
+            msg.metadata.insert(k.to_string(), v.to_string());
+        }
+        msg
+    }
+
+    #[test]
+    fn test_split_addresses() {
+        assert_eq!(
+            EmailChannel::split_addresses("\"Doe, John\" <John@X.com>, bob@y.org; not-an-address"),
+            vec!["john@x.com", "bob@y.org"]
+        );
+        assert!(EmailChannel::split_addresses("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_email_to_and_cc() {
+        let raw = b"From: alice@example.com\r\n\
+            To: bot@example.com, Carol <carol@example.com>\r\n\
+            Cc: dave@other.org\r\n\
+            Subject: Plans\r\n\
+            \r\n\
+            Lunch?\r\n";
+        let parsed = EmailChannel::parse_email(raw, 12000).unwrap();
+        assert_eq!(parsed.to, vec!["bot@example.com", "carol@example.com"]);
+        assert_eq!(parsed.cc, vec!["dave@other.org"]);
+    }
+
+    #[test]
+    fn test_resolve_recipients_reply_all() {
+        let mut config = make_config();
+        config.allowed_recipients = vec!["@x.com".into()];
+        let ch = EmailChannel::new(config, make_bus());
+        let thread = vec!["carol@example.com".to_string(), "dave@other.org".to_string()];
+        let msg = outbound(
+            "alice@example.com",
+            &[("reply_all", "true"), ("cc", "carol@example.com"), ("bcc", "Erin <erin@x.com>")],
+        );
+        let rcpt = ch.resolve_recipients(&msg, Some(&thread)).unwrap();
+        assert_eq!(rcpt.to, vec!["alice@example.com"]);
+        assert_eq!(rcpt.cc, vec!["carol@example.com", "dave@other.org"]);
+        assert_eq!(rcpt.bcc, vec!["erin@x.com"]);
+
+        // Without reply_all only the sender gets the answer
+        let rcpt = ch.resolve_recipients(&outbound("alice@example.com", &[]), Some(&thread)).unwrap();
+        assert_eq!(rcpt, Recipients { to: vec!["alice@example.com".into()], ..Default::default() });
+    }
+
+    #[test]
+    fn test_resolve_recipients_allow_list() {
+        let mut config = make_config();
+        config.allowed_recipients = vec!["@corp.io".into(), "carol@example.com".into()];
+        let ch = EmailChannel::new(config, make_bus());
+        let thread = vec!["dave@other.org".to_string()];
+
+        // Thread participants and allow-listed addresses pass
+        let msg = outbound(
+            "stranger@else.net",
+            &[("reply_all", "true"), ("to", "carol@example.com"), ("bcc", "boss@corp.io")],
+        );
+        assert!(ch.resolve_recipients(&msg, Some(&thread)).is_ok());
+
+        // New destinations outside the list are refused
+        let msg = outbound("team@corp.io", &[("cc", "leak@evil.com")]);
+        let err = ch.resolve_recipients(&msg, None).unwrap_err().to_string();
+        assert!(err.contains("leak@evil.com"));
+        assert!(ch.resolve_recipients(&outbound("stranger@else.net", &[]), None).is_err());
+
+        // Our own address is always allowed (e.g. digest delivery)
+        assert!(ch.resolve_recipients(&outbound("bot@example.com", &[]), None).is_ok());
+
+        // Without a list, only the thread is
+        let ch = EmailChannel::new(make_config(), make_bus());
+        assert!(ch.resolve_recipients(&outbound("alice@example.com", &[]), Some(&thread)).is_ok());
+        assert!(ch.resolve_recipients(&outbound("alice@example.com", &[("cc", "dave@other.org")]), Some(&thread)).is_ok());
+        assert!(ch.resolve_recipients(&outbound("alice@example.com", &[]), None).is_err());
+        assert!(ch.resolve_recipients(&outbound("alice@example.com", &[("cc", "eve@x.com")]), Some(&thread)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_before_sending() {
+        use oxibot_core::bus::types::ORIGIN_CHANNEL_KEY;
+
+        let mut config = make_config();
+        config.smtp_use_tls = false;
+        config.allowed_recipients = vec!["@corp.io".into()];
+        config.confirm_recipients = vec!["@corp.io".into()];
+        let bus = make_bus();
+        let ch = EmailChannel::new(config, bus.clone());
+        assert!(ch.needs_confirmation("Team@Corp.io"));
+        assert!(!ch.needs_confirmation("alice@example.com"));
+
+        // Sent from a Telegram chat: the preview goes back there
+        let msg = outbound("team@corp.io", &[("subject", "Offer")])
+            .with_origin("telegram", "42")
+            .with_origin_sender("42");
+        ch.send(&msg).await.unwrap();
+        let request = bus.consume_outbound().await.unwrap();
+        assert_eq!((request.channel.as_str(), request.chat_id.as_str()), ("telegram", "42"));
+        let (id, prompt) = request.approval().unwrap();
+        assert!(prompt.contains("To: team@corp.io\nSubject: Offer\n\nhi"), "{prompt}");
+
+        // Only the sender who asked can answer
+        let click = InboundMessage::approval_response("telegram", "7", "42", id, Decision::Approved);
+        bus.publish_inbound(click).await.unwrap();
+        let click = InboundMessage::approval_response("telegram", "42", "42", id, Decision::Denied);
+        bus.publish_inbound(click).await.unwrap();
+        let note = bus.consume_outbound().await.unwrap();
+        assert_eq!((note.chat_id.as_str(), note.content.as_str()), ("42", "🗑️ Email discarded."));
+
+        // Nowhere to ask: refused rather than sent
+        let mut msg = msg;
+        msg.metadata.remove(ORIGIN_CHANNEL_KEY);
+        let err = ch.send(&msg).await.unwrap_err().to_string();
+        assert!(err.contains("needs approval"), "{err}");
+    }
+
     #[tokio::test]
     async fn test_subject_tracking() {
         let ch = EmailChannel::new(make_config(), make_bus());
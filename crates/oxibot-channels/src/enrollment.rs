@@ -0,0 +1,229 @@
+//! Enrollment — let unknown senders ask the admin for access.
+//!
+//! A channel that turns away a sender in a direct chat hands them to
+//! [`Enrollment::challenge`]. The sender gets a polite reply, and the admin
+//! chat an approval request (Allow / Deny buttons where the channel has
+//! them). Approved senders are let in right away and added to the
+//! channel's `allowedUsers` in the config file, so they stay allowed after
+//! a restart. A sender is asked about once; after a denial or an
+//! unanswered request, only again once `retryAfterSecs` have passed. At
+//! most `maxPending` requests are open at once, so a burst of strangers
+//! can't flood the admin chat.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info, warn};
+
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::bus::{Decision, MessageBus};
+use oxibot_core::config::add_allowed_user;
+use oxibot_core::config::schema::EnrollmentConfig;
+
+/// Sent to a sender the admin let in.
+const WELCOME: &str = "✅ You're in! Send me a message to get started.";
+
+/// Sent to a sender the admin turned away.
+const DENIED: &str = "Sorry, the admin didn't approve access.";
+
+/// Asks the admin about unknown senders and remembers the answers.
+pub struct Enrollment {
+    bus: Arc<MessageBus>,
+    config: EnrollmentConfig,
+    /// Config file approved senders are saved to (`None` = default path).
+    config_path: Option<PathBuf>,
+    /// Senders approved since startup, as `(channel, id)`.
+    approved: Mutex<HashSet<(String, String)>>,
+    /// When each sender was last asked about, as `(channel, id)`.
+    asked: Mutex<HashMap<(String, String), Instant>>,
+    /// Approval requests waiting for the admin.
+    pending: AtomicUsize,
+}
+
+impl Enrollment {
+    pub fn new(bus: Arc<MessageBus>, config: EnrollmentConfig) -> Self {
+        Self {
+            bus,
+            config,
+            config_path: None,
+            approved: Mutex::new(HashSet::new()),
+            asked: Mutex::new(HashMap::new()),
+            pending: AtomicUsize::new(0),
+        }
+    }
+
+    /// Save approved senders to `path` instead of the default config file.
+    pub fn with_config_path(mut self, path: PathBuf) -> Self {
+        self.config_path = Some(path);
+        self
+    }
+
+    /// Whether `sender_id` was approved since startup. Like the allow-lists,
+    /// matches any part of an `id|username` sender.
+    pub fn is_enrolled(&self, channel: &str, sender_id: &str) -> bool {
+        let approved = self.approved.lock().unwrap();
+        sender_id
+            .split('|')
+            .any(|part| approved.contains(&(channel.to_string(), part.to_string())))
+    }
+
+    /// Challenge an unknown sender in a direct chat and ask the admin to
+    /// approve them, unless they were asked about recently or too many
+    /// requests are open. Runs in the background.
+    pub fn challenge(self: &Arc<Self>, channel: &str, sender_id: &str, chat_id: &str, name: &str) {
+        // The numeric id, not the username, goes on the allow-list
+        let id = sender_id.split('|').next().unwrap_or(sender_id).to_string();
+        let key = (channel.to_string(), id.clone());
+        {
+            let mut asked = self.asked.lock().unwrap();
+            let retry_after = Duration::from_secs(self.config.retry_after_secs);
+            asked.retain(|_, at| at.elapsed() < retry_after);
+            if asked.contains_key(&key) {
+                debug!(channel, sender = %id, "enrollment already requested");
+                return;
+            }
+            // Checked under the lock, so concurrent challenges can't overshoot
+            if self.pending.load(Ordering::SeqCst) >= self.config.max_pending {
+                warn!(channel, sender = %id, "too many enrollment requests open, ignoring sender");
+                return;
+            }
+            self.pending.fetch_add(1, Ordering::SeqCst);
+            asked.insert(key, Instant::now());
+        }
+
+        let enrollment = self.clone();
+        let (channel, chat_id) = (channel.to_string(), chat_id.to_string());
+        let who = if name.is_empty() || name == id {
+            format!("`{id}`")
+        } else {
+            format!("{name} (`{id}`)")
+        };
+        tokio::spawn(async move {
+            enrollment.send(&channel, &chat_id, &enrollment.config.challenge).await;
+            let prompt = format!("🔐 {who} wants to talk to me on {channel}. Let them in?");
+            let decision = enrollment
+                .bus
+                .request_approval(
+                    &enrollment.config.admin_channel,
+                    &enrollment.config.admin_chat_id,
+                    None,
+                    &prompt,
+                    Duration::from_secs(enrollment.config.timeout_secs),
+                )
+                .await;
+            enrollment.pending.fetch_sub(1, Ordering::SeqCst);
+            info!(channel = %channel, sender = %id, decision = decision.as_str(), "enrollment decided");
+            match decision {
+                Decision::Approved => {
+                    enrollment.approve(&channel, &id);
+                    enrollment.send(&channel, &chat_id, WELCOME).await;
+                }
+                Decision::Denied => enrollment.send(&channel, &chat_id, DENIED).await,
+                Decision::TimedOut => {}
+            }
+        });
+    }
+
+    /// Let `id` in now and add it to the channel's allow-list on disk.
+    fn approve(&self, channel: &str, id: &str) {
+        self.approved
+            .lock()
+            .unwrap()
+            .insert((channel.to_string(), id.to_string()));
+        if let Err(e) = add_allowed_user(self.config_path.as_deref(), channel, id) {
+            warn!(channel, sender = %id, error = %e, "failed to save enrolled sender to the config");
+        }
+    }
+
+    async fn send(&self, channel: &str, chat_id: &str, text: &str) {
+        if let Err(e) = self.bus.publish_outbound(OutboundMessage::new(channel, chat_id, text)).await {
+            warn!(channel, chat_id, error = %e, "failed to send enrollment message");
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::bus::types::InboundMessage;
+
+    #[tokio::test]
+    async fn test_challenge_and_approve() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let bus = Arc::new(MessageBus::new(16).with_admins(vec!["telegram:root".into()]));
+        let config = EnrollmentConfig {
+            enabled: true,
+            admin_channel: "telegram".into(),
+            admin_chat_id: "admin".into(),
+            ..Default::default()
+        };
+        let enrollment = Arc::new(Enrollment::new(bus.clone(), config).with_config_path(path.clone()));
+
+        enrollment.challenge("telegram", "42|alice", "42", "Alice");
+        // Asked once only
+        enrollment.challenge("telegram", "42|alice", "42", "Alice");
+
+        let challenge = bus.consume_outbound().await.unwrap();
+        assert_eq!((challenge.chat_id.as_str(), challenge.content.starts_with("👋")), ("42", true));
+        let request = bus.consume_outbound().await.unwrap();
+        assert_eq!(request.chat_id, "admin");
+        let (id, prompt) = request.approval().unwrap();
+        assert!(prompt.contains("Alice (`42`)"), "{prompt}");
+        assert!(!enrollment.is_enrolled("telegram", "42|alice"));
+
+        // Only an admin can let someone in
+        let click = InboundMessage::approval_response("telegram", "guest", "admin", id, Decision::Approved);
+        bus.publish_inbound(click).await.unwrap();
+        let click = InboundMessage::approval_response("telegram", "root", "admin", id, Decision::Approved);
+        bus.publish_inbound(click).await.unwrap();
+        let welcome = bus.consume_outbound().await.unwrap();
+        assert_eq!((welcome.chat_id.as_str(), welcome.content.as_str()), ("42", WELCOME));
+
+        assert!(enrollment.is_enrolled("telegram", "42|alice"));
+        assert!(!enrollment.is_enrolled("discord", "42"));
+        let saved = oxibot_core::config::load_config(Some(&path));
+        assert_eq!(saved.channels.telegram.allowed_users, ["42"]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_capped() {
+        let bus = Arc::new(MessageBus::new(16));
+        let config = EnrollmentConfig {
+            enabled: true,
+            admin_channel: "telegram".into(),
+            admin_chat_id: "admin".into(),
+            timeout_secs: 1,
+            retry_after_secs: 0,
+            max_pending: 2,
+            ..Default::default()
+        };
+        let enrollment = Arc::new(Enrollment::new(bus.clone(), config));
+
+        for id in ["1", "2", "3", "4"] {
+            enrollment.challenge("telegram", id, id, "");
+        }
+        // Two strangers were challenged and two requests reached the admin
+        let mut sent = Vec::new();
+        for _ in 0..4 {
+            sent.push(bus.consume_outbound().await.unwrap().chat_id);
+        }
+        sent.sort();
+        assert_eq!(sent, ["1", "2", "admin", "admin"]);
+
+        // Once the requests time out, a new stranger can ask again, and
+        // entries past retryAfterSecs are pruned
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        assert_eq!(enrollment.pending.load(Ordering::SeqCst), 0);
+        enrollment.challenge("telegram", "5", "5", "");
+        assert_eq!(bus.consume_outbound().await.unwrap().chat_id, "5");
+        assert_eq!(enrollment.asked.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,336 @@
+//! File-drop channel — watch a directory for text files.
+//!
+//! Dropping `question.txt` or `question.md` into the watched directory
+//! publishes its content as an inbound message; the agent's reply is
+//! written next to it as `question.reply.md`.
+//!
+//! Features:
+//! - No chat platform or network access needed (scripting, air-gapped setups)
+//! - Polling-based, no extra dependencies
+//! - Files are picked up once their size and mtime are stable across two scans
+//! - Re-dropping a file with new content continues the same conversation
+//! - Files that already have a newer reply are skipped on startup
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use tokio::sync::{Mutex, Notify};
+use tracing::{debug, error, info, warn};
+
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
+
+use crate::base::{ActivityTracker, Channel, HealthReport};
+
+// ─────────────────────────────────────────────
+// Constants
+// ─────────────────────────────────────────────
+
+/// Suffix of reply files written by the channel.
+const REPLY_SUFFIX: &str = ".reply.md";
+
+/// File extensions picked up as messages.
+const INPUT_EXTENSIONS: &[&str] = &["txt", "md"];
+
+/// Minimum scan interval in milliseconds.
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+
+/// Size + modification time of a file, used to detect changes.
+type FileStamp = (SystemTime, u64);
+
+// ─────────────────────────────────────────────
+// FileDropChannel
+// ─────────────────────────────────────────────
+
+/// File-drop channel — turns files dropped into a directory into messages.
+pub struct FileDropChannel {
+    /// Watched directory.
+    dir: PathBuf,
+    /// Scan interval.
+    poll_interval: Duration,
+    /// Message bus.
+    bus: Arc<MessageBus>,
+    /// Shutdown signal.
+    shutdown: Arc<Notify>,
+    /// Stamp seen on the previous scan (files still being written change).
+    last_seen: Mutex<HashMap<PathBuf, FileStamp>>,
+    /// Stamp of each file when it was last published.
+    processed: Mutex<HashMap<PathBuf, FileStamp>>,
+    /// Last completed scan, for health reports.
+    activity: ActivityTracker,
+}
+
+impl FileDropChannel {
+    /// Create a new file-drop channel watching `dir`.
+    pub fn new(dir: PathBuf, poll_interval_ms: u64, bus: Arc<MessageBus>) -> Self {
+        Self {
+            dir,
+            poll_interval: Duration::from_millis(poll_interval_ms.max(MIN_POLL_INTERVAL_MS)),
+            bus,
+            shutdown: Arc::new(Notify::new()),
+            last_seen: Mutex::new(HashMap::new()),
+            processed: Mutex::new(HashMap::new()),
+            activity: ActivityTracker::new(),
+        }
+    }
+
+    /// Whether a file name is a message to pick up.
+    fn is_input_file(name: &str) -> bool {
+        if name.starts_with('.') || name.ends_with(REPLY_SUFFIX) {
+            return false;
+        }
+        Path::new(name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| INPUT_EXTENSIONS.iter().any(|x| ext.eq_ignore_ascii_case(x)))
+    }
+
+    /// Conversation id for a file: its name without the extension.
+    fn chat_id_for(path: &Path) -> String {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Path of the reply file for a conversation.
+    ///
+    /// Returns `None` if the chat id could escape the watched directory.
+    fn reply_path(&self, chat_id: &str) -> Option<PathBuf> {
+        if chat_id.is_empty()
+            || chat_id.contains(['/', '\\'])
+            || chat_id == ".."
+        {
+            return None;
+        }
+        Some(self.dir.join(format!("{chat_id}{REPLY_SUFFIX}")))
+    }
+
+    /// Whether the file already has a reply written after it was last modified.
+    fn has_newer_reply(&self, path: &Path, modified: SystemTime) -> bool {
+        self.reply_path(&Self::chat_id_for(path))
+            .and_then(|p| std::fs::metadata(p).ok())
+            .and_then(|m| m.modified().ok())
+            .is_some_and(|reply_modified| reply_modified >= modified)
+    }
+
+    /// Scan the directory once and return files ready to be published.
+    ///
+    /// A file is ready when its stamp is unchanged since the previous scan
+    /// (so half-written files are not read) and differs from the stamp it
+    /// had when last published.
+    async fn scan(&self) -> Vec<PathBuf> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(e) => e,
+            Err(e) => {
+                warn!(dir = %self.dir.display(), error = %e, "filedrop: cannot read directory");
+                return Vec::new();
+            }
+        };
+
+        let mut current = HashMap::new();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !Self::is_input_file(&name) {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            if !meta.is_file() {
+                continue;
+            }
+            let Ok(modified) = meta.modified() else { continue };
+            current.insert(entry.path(), (modified, meta.len()));
+        }
+
+        let mut last_seen = self.last_seen.lock().await;
+        let processed = self.processed.lock().await;
+        let mut ready: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, stamp)| {
+                last_seen.get(*path) == Some(*stamp)
+                    && processed.get(*path) != Some(*stamp)
+                    && !self.has_newer_reply(path, stamp.0)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+        *last_seen = current;
+        ready
+    }
+
+    /// Read a dropped file and publish it to the bus.
+    async fn publish_file(&self, path: &Path) {
+        let stamp = self.last_seen.lock().await.get(path).copied();
+        if let Some(stamp) = stamp {
+            self.processed.lock().await.insert(path.to_path_buf(), stamp);
+        }
+
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                warn!(file = %path.display(), error = %e, "filedrop: failed to read file");
+                return;
+            }
+        };
+        if content.trim().is_empty() {
+            debug!(file = %path.display(), "filedrop: skipping empty file");
+            return;
+        }
+
+        let chat_id = Self::chat_id_for(path);
+        let mut msg = InboundMessage::new("filedrop", "local", &chat_id, content);
+        msg.metadata
+            .insert("file".to_string(), path.display().to_string());
+
+        info!(file = %path.display(), "filedrop: new message");
+        if let Err(e) = self.bus.publish_inbound(msg).await {
+            error!(error = %e, "failed to publish filedrop message to bus");
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for FileDropChannel {
+    fn name(&self) -> &str {
+        "filedrop"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        info!(dir = %self.dir.display(), "starting filedrop channel");
+
+        loop {
+            for path in self.scan().await {
+                self.publish_file(&path).await;
+            }
+            self.activity.touch();
+
+            tokio::select! {
+                _ = tokio::time::sleep(self.poll_interval) => {}
+                _ = self.shutdown.notified() => {
+                    info!("filedrop channel shutting down");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        info!("stopping filedrop channel");
+        self.shutdown.notify_waiters();
+        Ok(())
+    }
+
+    async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
+        let path = self
+            .reply_path(&msg.chat_id)
+            .ok_or_else(|| anyhow::anyhow!("invalid filedrop chat id: {}", msg.chat_id))?;
+        std::fs::write(&path, &msg.content)?;
+        debug!(file = %path.display(), "filedrop: reply written");
+        Ok(())
+    }
+
+    fn health(&self) -> Option<HealthReport> {
+        Some(self.activity.report())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_channel(dir: &Path) -> (FileDropChannel, Arc<MessageBus>) {
+        let bus = Arc::new(MessageBus::new(10));
+        (FileDropChannel::new(dir.to_path_buf(), 1000, bus.clone()), bus)
+    }
+
+    #[test]
+    fn test_is_input_file() {
+        assert!(FileDropChannel::is_input_file("question.txt"));
+        assert!(FileDropChannel::is_input_file("notes.MD"));
+        assert!(!FileDropChannel::is_input_file("question.reply.md"));
+        assert!(!FileDropChannel::is_input_file(".hidden.txt"));
+        assert!(!FileDropChannel::is_input_file("image.png"));
+        assert!(!FileDropChannel::is_input_file("README"));
+    }
+
+    #[test]
+    fn test_reply_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ch, _) = make_channel(dir.path());
+        assert_eq!(
+            ch.reply_path("question").unwrap(),
+            dir.path().join("question.reply.md")
+        );
+        assert!(ch.reply_path("../etc/passwd").is_none());
+        assert!(ch.reply_path("").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_published_once_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ch, bus) = make_channel(dir.path());
+        std::fs::write(dir.path().join("question.txt"), "What is 2+2?").unwrap();
+        std::fs::write(dir.path().join("ignored.png"), "x").unwrap();
+
+        // First scan only records the file
+        assert!(ch.scan().await.is_empty());
+
+        let ready = ch.scan().await;
+        assert_eq!(ready, vec![dir.path().join("question.txt")]);
+        ch.publish_file(&ready[0]).await;
+
+        let msg = bus.consume_inbound().await.unwrap();
+        assert_eq!(msg.channel, "filedrop");
+        assert_eq!(msg.chat_id, "question");
+        assert_eq!(msg.content, "What is 2+2?");
+
+        // Already processed
+        assert!(ch.scan().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_writes_reply_next_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ch, _) = make_channel(dir.path());
+        let out = OutboundMessage::new("filedrop", "question", "4");
+        ch.send(&out).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("question.reply.md")).unwrap(),
+            "4"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_with_newer_reply_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ch, _) = make_channel(dir.path());
+        std::fs::write(dir.path().join("done.md"), "old question").unwrap();
+        std::fs::write(dir.path().join("done.reply.md"), "old answer").unwrap();
+
+        assert!(ch.scan().await.is_empty());
+        assert!(ch.scan().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stop_ends_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let (ch, _) = make_channel(&dir.path().join("inbox"));
+        let ch = Arc::new(ch);
+        let runner = {
+            let ch = ch.clone();
+            tokio::spawn(async move { ch.start().await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        ch.stop().await.unwrap();
+        runner.await.unwrap().unwrap();
+        assert!(dir.path().join("inbox").is_dir());
+    }
+}
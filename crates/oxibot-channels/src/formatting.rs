@@ -14,6 +14,13 @@
 //! - Headers (# ...) → stripped to plain text
 //! - Blockquotes (> ...) → stripped to plain text
 //! - Bullets (- / *) → `•`
+//!
+//! It also finds structured parts of a reply — lists of links (search
+//! results), task lists and command output with an exit code — as
+//! channel-agnostic [`RichContent`], which channels with rich layouts
+//! render as cards (Discord embeds).
+
+use std::sync::OnceLock;
 
 use regex::Regex;
 
@@ -133,14 +140,221 @@ pub fn split_message(text: &str, max_len: usize) -> Vec<String> {
         chunks.push(chunk.to_string());
 
         // Skip the newline character if we split there
-        remaining = if rest.starts_with('\n') {
-            &rest[1..]
+        remaining = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+
+    chunks
+}
+
+// ─────────────────────────────────────────────
+// Rich content
+// ─────────────────────────────────────────────
+
+/// `- [Title](https://…) — snippet`, numbered or bulleted, optionally bold.
+fn link_item_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+(?:\*\*)?\[([^\]]+)\]\((https?://[^)\s]+)\)(?:\*\*)?\s*(?:[-—–:]\s*)?(.*)$")
+            .unwrap()
+    })
+}
+
+/// `- [ ] task` / `- [x] task`.
+fn task_item_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*[-*+]\s+\[([ xX])\]\s+(.*)$").unwrap())
+}
+
+/// `Exit code: 1`, `exited with status 0`, `**Exit code**: 127`.
+fn exit_line_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\W*exit(?:ed)?\s+(?:with\s+)?(?:code|status)\W*(-?\d+)\W*$").unwrap())
+}
+
+/// `## Heading`.
+fn heading_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^#{1,6}\s+(.+?)\s*#*$").unwrap())
+}
+
+/// What a card shows; channels style it accordingly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardKind {
+    /// Links with snippets, like web search results.
+    Links,
+    /// A checklist; `done` when every task is checked.
+    Tasks { done: bool },
+    /// Output of a command and its exit code.
+    CommandOutput { exit_code: i32 },
+}
+
+/// A labelled value on a card.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardField {
+    pub name: String,
+    pub value: String,
+    /// Short enough to sit next to other inline fields.
+    pub inline: bool,
+}
+
+/// A structured part of a reply.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub kind: CardKind,
+    /// The heading right above the part, if any.
+    pub title: Option<String>,
+    /// Markdown body.
+    pub description: String,
+    pub fields: Vec<CardField>,
+    pub footer: Option<String>,
+}
+
+/// A reply split into plain text and cards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RichContent {
+    /// What is left of the reply, with the cards' parts taken out.
+    pub text: String,
+    pub cards: Vec<Card>,
+}
+
+impl RichContent {
+    /// Find structured parts in a Markdown reply: two or more links in a
+    /// list, a task list, or a code block followed by an exit code line.
+    /// `None` when there are none.
+    pub fn parse(markdown: &str) -> Option<Self> {
+        let lines: Vec<&str> = markdown.lines().collect();
+        let mut text: Vec<&str> = Vec::new();
+        let mut cards = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let (card, end) = match parse_card(&lines, i) {
+                Some(found) => found,
+                None => {
+                    text.push(lines[i]);
+                    i += 1;
+                    continue;
+                }
+            };
+            // A heading right above (blank lines aside) titles the card
+            while text.last().is_some_and(|l| l.trim().is_empty()) {
+                text.pop();
+            }
+            let title = text
+                .last()
+                .and_then(|l| heading_regex().captures(l.trim()))
+                .map(|c| c[1].trim_matches('*').to_string());
+            if title.is_some() {
+                text.pop();
+            }
+            cards.push(Card { title, ..card });
+            i = end;
+        }
+        if cards.is_empty() {
+            return None;
+        }
+        Some(Self {
+            text: text.join("\n").trim().to_string(),
+            cards,
+        })
+    }
+}
+
+/// The card starting at line `start`, and the line after it.
+fn parse_card(lines: &[&str], start: usize) -> Option<(Card, usize)> {
+    parse_links(lines, start)
+        .or_else(|| parse_tasks(lines, start))
+        .or_else(|| parse_command_output(lines, start))
+}
+
+fn parse_links(lines: &[&str], start: usize) -> Option<(Card, usize)> {
+    let mut fields: Vec<CardField> = Vec::new();
+    let mut i = start;
+    while i < lines.len() {
+        if let Some(caps) = link_item_regex().captures(lines[i]) {
+            let snippet = caps[3].trim();
+            let value = if snippet.is_empty() {
+                caps[2].to_string()
+            } else {
+                format!("{snippet}\n{}", &caps[2])
+            };
+            fields.push(CardField {
+                name: caps[1].trim().to_string(),
+                value,
+                inline: false,
+            });
+        } else if fields.is_empty() || !is_continuation(lines[i]) {
+            break;
         } else {
-            rest
+            // An indented line under an item continues its snippet
+            let field = fields.last_mut()?;
+            let url_at = field.value.rfind('\n').map_or(0, |n| n + 1);
+            field.value.insert_str(url_at, &format!("{}\n", lines[i].trim()));
+        }
+        i += 1;
+    }
+    (fields.len() >= 2).then(|| {
+        let card = Card {
+            kind: CardKind::Links,
+            title: None,
+            description: String::new(),
+            fields,
+            footer: None,
         };
+        (card, i)
+    })
+}
+
+fn parse_tasks(lines: &[&str], start: usize) -> Option<(Card, usize)> {
+    let mut tasks: Vec<(bool, String)> = Vec::new();
+    let mut i = start;
+    while let Some(caps) = lines.get(i).and_then(|l| task_item_regex().captures(l)) {
+        tasks.push((&caps[1] != " ", caps[2].trim().to_string()));
+        i += 1;
+    }
+    if tasks.is_empty() {
+        return None;
     }
+    let done = tasks.iter().filter(|(checked, _)| *checked).count();
+    let description = tasks
+        .iter()
+        .map(|(checked, task)| format!("{} {task}", if *checked { "✅" } else { "⬜" }))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let card = Card {
+        kind: CardKind::Tasks { done: done == tasks.len() },
+        title: None,
+        description,
+        fields: Vec::new(),
+        footer: Some(format!("{done}/{} done", tasks.len())),
+    };
+    Some((card, i))
+}
 
-    chunks
+fn parse_command_output(lines: &[&str], start: usize) -> Option<(Card, usize)> {
+    if !lines[start].trim_start().starts_with("```") {
+        return None;
+    }
+    let close = (start + 1..lines.len()).find(|&i| lines[i].trim_start().starts_with("```"))?;
+    // The exit code follows the block, blank lines aside
+    let exit_at = (close + 1..lines.len()).find(|&i| !lines[i].trim().is_empty())?;
+    let exit_code: i32 = exit_line_regex().captures(lines[exit_at])?[1].parse().ok()?;
+    let card = Card {
+        kind: CardKind::CommandOutput { exit_code },
+        title: None,
+        description: lines[start..=close].join("\n"),
+        fields: vec![CardField {
+            name: "Exit code".into(),
+            value: exit_code.to_string(),
+            inline: true,
+        }],
+        footer: None,
+    };
+    Some((card, exit_at + 1))
+}
+
+/// An indented, non-empty line that isn't a new list item.
+fn is_continuation(line: &str) -> bool {
+    line.starts_with([' ', '\t']) && !line.trim().is_empty() && !link_item_regex().is_match(line)
 }
 
 // ─────────────────────────────────────────────
@@ -295,4 +509,56 @@ mod tests {
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0], "");
     }
+
+    #[test]
+    fn test_rich_content_links_and_tasks() {
+        let reply = "Here is what I found.\n\n\
+                     ## Rust async runtimes\n\
+                     1. [Tokio](https://tokio.rs) — the most used runtime\n\
+                     \x20  with a large ecosystem\n\
+                     2. **[smol](https://github.com/smol-rs/smol)**\n\
+                     \n\
+                     - [x] compare runtimes\n\
+                     - [ ] write benchmark\n\
+                     \n\
+                     Want more?";
+        let rich = RichContent::parse(reply).unwrap();
+        assert_eq!(rich.text, "Here is what I found.\n\nWant more?");
+        assert_eq!(rich.cards.len(), 2);
+
+        let links = &rich.cards[0];
+        assert_eq!(links.kind, CardKind::Links);
+        assert_eq!(links.title.as_deref(), Some("Rust async runtimes"));
+        assert_eq!(links.fields[0].name, "Tokio");
+        assert_eq!(
+            links.fields[0].value,
+            "the most used runtime\nwith a large ecosystem\nhttps://tokio.rs"
+        );
+        assert_eq!(links.fields[1].value, "https://github.com/smol-rs/smol");
+
+        let tasks = &rich.cards[1];
+        assert_eq!(tasks.kind, CardKind::Tasks { done: false });
+        assert_eq!(tasks.description, "✅ compare runtimes\n⬜ write benchmark");
+        assert_eq!(tasks.footer.as_deref(), Some("1/2 done"));
+    }
+
+    #[test]
+    fn test_rich_content_command_output() {
+        let reply = "Ran the tests:\n```\n2 passed, 1 failed\n```\n**Exit code:** 1";
+        let rich = RichContent::parse(reply).unwrap();
+        assert_eq!(rich.text, "Ran the tests:");
+        let card = &rich.cards[0];
+        assert_eq!(card.kind, CardKind::CommandOutput { exit_code: 1 });
+        assert_eq!(card.description, "```\n2 passed, 1 failed\n```");
+        assert_eq!(card.fields[0].value, "1");
+    }
+
+    #[test]
+    fn test_rich_content_plain_replies() {
+        assert_eq!(RichContent::parse("Just text.\n- a bullet\n- another"), None);
+        // One link is just a link
+        assert_eq!(RichContent::parse("- [Docs](https://docs.rs)"), None);
+        // Code without an exit code is just code
+        assert_eq!(RichContent::parse("```rust\nfn main() {}\n```"), None);
+    }
 }
@@ -3,13 +3,24 @@
 //! This crate provides:
 //! - **base**: The `Channel` trait that all channel implementations must satisfy
 //! - **manager**: `ChannelManager` — lifecycle orchestration and outbound message routing
+//! - **delivery**: Retries of failed sends and late delivery once a channel is back
+//! - **enrollment**: Admin-approved access for unknown senders
+//! - **repeats**: Suppression of unprompted messages that repeat a recent one
+//! - **code_files**: Long code blocks sent as file attachments
+//! - **quiet_hours**: Unprompted messages held back during a channel's quiet hours
 //!
 //! Individual channel implementations (Telegram, Discord, etc.) will be added
 //! as feature-gated modules.
 
 pub mod base;
+pub mod code_files;
+pub mod delivery;
+pub mod enrollment;
 pub mod formatting;
 pub mod manager;
+pub mod pacing;
+pub mod quiet_hours;
+pub mod repeats;
 
 #[cfg(feature = "telegram")]
 pub mod telegram;
@@ -17,14 +28,23 @@ pub mod telegram;
 #[cfg(feature = "discord")]
 pub mod discord;
 
+#[cfg(feature = "discord-voice")]
+pub mod discord_voice;
+
 #[cfg(feature = "whatsapp")]
 pub mod whatsapp;
 
 #[cfg(feature = "slack")]
 pub mod slack;
 
+#[cfg(feature = "signal")]
+pub mod signal;
+
 #[cfg(feature = "email")]
 pub mod email;
 
+#[cfg(feature = "filedrop")]
+pub mod filedrop;
+
 pub use base::Channel;
 pub use manager::ChannelManager;
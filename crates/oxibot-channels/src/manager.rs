@@ -5,19 +5,53 @@
 //! Responsibilities:
 //! - Register enabled channels
 //! - Start/stop all channels concurrently via `tokio::spawn`
-//! - Dispatch outbound messages from the bus to the correct channel
+//! - Restart channels that fail or go silent, with exponential backoff
+//! - Dispatch outbound messages from the bus to the correct channel,
+//!   pacing long replies (see [`crate::pacing`])
+//! - Retry failed sends, keep messages that still fail in the outbox and
+//!   deliver them once the channel is back (see [`crate::delivery`]);
+//!   re-send the ones the operator marks for retry
+//! - Drop unprompted messages that repeat a recent one (see [`crate::repeats`])
+//! - Send long code blocks as files where uploads work (see [`crate::code_files`])
+//! - Hold unprompted messages during quiet hours (see [`crate::quiet_hours`])
+//! - Write a crash report when a channel or the dispatcher panics, and
+//!   restart it
 //! - Report channel status
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use tokio::sync::Notify;
 use tracing::{debug, error, info, warn};
 
+use oxibot_core::bus::events::StatusEvent;
+use oxibot_core::bus::outbox::{Outbox, OutboxEntry};
 use oxibot_core::bus::queue::MessageBus;
-
-use crate::base::Channel;
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::schema::{
+    CodeFilesConfig, DeliveryConfig, PacingConfig, QuietHoursConfig, RepeatsConfig, SupervisorConfig,
+};
+use oxibot_core::crash::{self, CrashReporter};
+
+use crate::base::{Channel, HealthReport};
+use crate::code_files::CodeFiles;
+use crate::delivery::Delivery;
+use crate::pacing::PacedSender;
+use crate::quiet_hours::QuietHours;
+use crate::repeats::RepeatFilter;
+
+/// Shortest interval between health checks.
+const MIN_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the outbox is checked for messages marked for retry, and
+/// channels that are down for whether they are back.
+const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often messages held for quiet hours are checked for whether their
+/// window has ended.
+const QUIET_HOURS_POLL_INTERVAL: Duration = Duration::from_secs(60);
 
 // ─────────────────────────────────────────────
 // ChannelManager
@@ -26,9 +60,10 @@ use crate::base::Channel;
 /// Manages the lifecycle and message routing for all chat channels.
 ///
 /// Channels are registered with `register()`, started concurrently with
-/// `start_all()`, and stopped with `stop_all()`. An outbound dispatcher
-/// task reads from the message bus and routes responses to the correct
-/// channel.
+/// `start_all()`, and stopped with `stop_all()`. Each channel runs under a
+/// supervisor that restarts it when it fails or goes silent. An outbound
+/// dispatcher task reads from the message bus and routes responses to the
+/// correct channel.
 pub struct ChannelManager {
     /// Registered channels, keyed by name.
     channels: HashMap<String, Arc<dyn Channel>>,
@@ -36,6 +71,22 @@ pub struct ChannelManager {
     bus: Arc<MessageBus>,
     /// Shutdown signal.
     shutdown: Arc<Notify>,
+    /// Restart and alerting policy.
+    supervisor: SupervisorConfig,
+    /// How long replies are split and paced.
+    pacing: PacingConfig,
+    /// Where messages that fail to send are kept.
+    outbox: Option<Arc<Outbox>>,
+    /// Retries and late delivery of failed sends.
+    delivery: DeliveryConfig,
+    /// Suppression of repeated unprompted messages.
+    repeats: RepeatsConfig,
+    /// Which code blocks are sent as files.
+    code_files: CodeFilesConfig,
+    /// When unprompted messages are held back.
+    quiet_hours: QuietHoursConfig,
+    /// Crash reports for channels and the dispatcher that panic.
+    crashes: Option<CrashReporter>,
 }
 
 impl ChannelManager {
@@ -45,9 +96,66 @@ impl ChannelManager {
             channels: HashMap::new(),
             bus,
             shutdown: Arc::new(Notify::new()),
+            supervisor: SupervisorConfig::default(),
+            pacing: PacingConfig::default(),
+            outbox: None,
+            delivery: DeliveryConfig::default(),
+            repeats: RepeatsConfig::default(),
+            code_files: CodeFilesConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            crashes: None,
         }
     }
 
+    /// Set the restart and alerting policy.
+    pub fn with_supervisor(mut self, config: SupervisorConfig) -> Self {
+        self.supervisor = config;
+        self
+    }
+
+    /// Set how long replies are split and paced.
+    pub fn with_pacing(mut self, config: PacingConfig) -> Self {
+        self.pacing = config;
+        self
+    }
+
+    /// Keep messages that fail to send in `outbox`, and re-send the ones
+    /// marked for retry there.
+    pub fn with_outbox(mut self, outbox: Outbox) -> Self {
+        self.outbox = Some(Arc::new(outbox));
+        self
+    }
+
+    /// Set how failed sends are retried and delivered late.
+    pub fn with_delivery(mut self, config: DeliveryConfig) -> Self {
+        self.delivery = config;
+        self
+    }
+
+    /// Set how unprompted messages that repeat a recent one are suppressed.
+    pub fn with_repeats(mut self, config: RepeatsConfig) -> Self {
+        self.repeats = config;
+        self
+    }
+
+    /// Set which code blocks are sent as file attachments.
+    pub fn with_code_files(mut self, config: CodeFilesConfig) -> Self {
+        self.code_files = config;
+        self
+    }
+
+    /// Set the quiet hours of each channel.
+    pub fn with_quiet_hours(mut self, config: QuietHoursConfig) -> Self {
+        self.quiet_hours = config;
+        self
+    }
+
+    /// Write a crash report when a channel or the outbound dispatcher panics.
+    pub fn with_crash_reporter(mut self, crashes: CrashReporter) -> Self {
+        self.crashes = Some(crashes);
+        self
+    }
+
     /// Register a channel. Overwrites any previous channel with the same name.
     pub fn register(&mut self, channel: Arc<dyn Channel>) {
         let name = channel.name().to_string();
@@ -88,7 +196,8 @@ impl ChannelManager {
 
     /// Start all channels + the outbound dispatcher.
     ///
-    /// Each channel's `start()` is spawned as a `tokio::spawn` task.
+    /// Each channel's `start()` is spawned as a `tokio::spawn` task under
+    /// a supervisor (see [`ChannelManager::supervise`]).
     /// The outbound dispatcher runs as an additional task that routes
     /// messages from the bus to the correct channel.
     ///
@@ -107,18 +216,16 @@ impl ChannelManager {
 
         let mut handles = Vec::new();
 
-        // Spawn each channel's start() as a background task
+        // Spawn each channel's supervisor as a background task
         for (name, channel) in &self.channels {
-            let ch = channel.clone();
-            let ch_name = name.clone();
-
-            let handle = tokio::spawn(async move {
-                info!(channel = %ch_name, "channel starting");
-                if let Err(e) = ch.start().await {
-                    error!(channel = %ch_name, error = %e, "channel start failed");
-                }
-                info!(channel = %ch_name, "channel stopped");
-            });
+            let handle = tokio::spawn(Self::supervise(
+                name.clone(),
+                channel.clone(),
+                self.supervisor.clone(),
+                self.bus.clone(),
+                self.crashes.clone(),
+                self.shutdown.clone(),
+            ));
 
             handles.push(handle);
         }
@@ -127,9 +234,44 @@ impl ChannelManager {
         let bus = self.bus.clone();
         let channels = self.channels.clone();
         let shutdown = self.shutdown.clone();
+        let pacing = self.pacing.clone();
+        let repeats = self.repeats.clone();
+        let code_files = CodeFiles::new(
+            self.code_files.clone(),
+            oxibot_core::utils::get_media_path().join("outbound"),
+        );
+        let quiet_hours = self.quiet_hours.clone();
+        let crashes = self.crashes.clone();
+        let delivery = Arc::new(
+            Delivery::new(channels.clone(), self.delivery.clone())
+                .with_outbox(self.outbox.clone())
+                .with_admin(&self.supervisor.alert_channel, &self.supervisor.alert_chat_id),
+        );
 
+        // Restarted when it panics; the message being sent is lost
         let dispatcher_handle = tokio::spawn(async move {
-            Self::dispatch_outbound(bus, channels, shutdown).await;
+            loop {
+                let task = tokio::spawn(Self::dispatch_outbound(
+                    bus.clone(),
+                    channels.clone(),
+                    pacing.clone(),
+                    RepeatFilter::new(repeats.clone()),
+                    code_files.clone(),
+                    QuietHours::new(&quiet_hours, Some(oxibot_core::utils::get_data_path().join("quiet_hours.json"))),
+                    delivery.clone(),
+                    shutdown.clone(),
+                ));
+                match task.await {
+                    Err(e) if e.is_panic() => {
+                        let payload = e.into_panic();
+                        error!(error = %crash::panic_message(&*payload), "outbound dispatcher panicked, restarting");
+                        if let Some(ref crashes) = crashes {
+                            crashes.report("outbound dispatcher", payload).await;
+                        }
+                    }
+                    _ => break,
+                }
+            }
         });
 
         handles.push(dispatcher_handle);
@@ -164,15 +306,157 @@ impl ChannelManager {
         self.shutdown.notify_waiters();
     }
 
+    /// Run a channel until shutdown, restarting it when it fails.
+    ///
+    /// A channel fails when `start()` returns an error or panics, or when
+    /// its health report shows no activity for `maxSilenceSecs` (it is then
+    /// stopped first). Restarts wait `initialBackoffMs`, doubling on each
+    /// consecutive failure up to `maxBackoffMs`; after `alertAfter`
+    /// consecutive failures an alert goes to the admin chat. A panic also
+    /// writes a crash report (see [`CrashReporter`]). A channel that
+    /// returns `Ok` has stopped on purpose and is not restarted.
+    ///
+    /// Starts, stops and failures are reported to bus subscribers.
+    async fn supervise(
+        name: String,
+        channel: Arc<dyn Channel>,
+        config: SupervisorConfig,
+        bus: Arc<MessageBus>,
+        crashes: Option<CrashReporter>,
+        shutdown: Arc<Notify>,
+    ) {
+        let max_silence = Duration::from_secs(config.max_silence_secs);
+        let mut failures: u32 = 0;
+
+        loop {
+            info!(channel = %name, "channel starting");
+            bus.publish_status(StatusEvent::ChannelStarted { channel: name.clone() });
+            let started = Instant::now();
+            let ch = channel.clone();
+            let mut task = tokio::spawn(async move { ch.start().await });
+
+            let reason = tokio::select! {
+                joined = &mut task => match joined {
+                    Ok(Ok(())) => {
+                        info!(channel = %name, "channel stopped");
+                        bus.publish_status(StatusEvent::ChannelStopped { channel: name.clone() });
+                        return;
+                    }
+                    Ok(Err(e)) => format!("{e:#}"),
+                    Err(e) if e.is_panic() => {
+                        let payload = e.into_panic();
+                        let reason = format!("channel task panicked: {}", crash::panic_message(&*payload));
+                        if let Some(ref crashes) = crashes {
+                            crashes.report(&format!("channel {name}"), payload).await;
+                        }
+                        reason
+                    }
+                    Err(e) => format!("channel task failed: {e}"),
+                },
+                _ = Self::wait_silent(channel.as_ref(), started, max_silence) => {
+                    if let Err(e) = channel.stop().await {
+                        warn!(channel = %name, error = %e, "failed to stop silent channel");
+                    }
+                    task.abort();
+                    format!("no activity for {}s", config.max_silence_secs)
+                }
+                _ = shutdown.notified() => return,
+            };
+
+            // A channel that stayed up for a while starts a fresh streak
+            if started.elapsed() >= Duration::from_millis(config.max_backoff_ms) {
+                failures = 0;
+            }
+            failures += 1;
+            bus.publish_status(StatusEvent::ChannelFailed {
+                channel: name.clone(),
+                reason: reason.clone(),
+                failures,
+            });
+
+            if !config.enabled {
+                error!(channel = %name, reason = %reason, "channel failed");
+                return;
+            }
+            let delay = restart_delay(&config, failures);
+            error!(
+                channel = %name,
+                reason = %reason,
+                failures,
+                retry_in_ms = delay.as_millis() as u64,
+                "channel failed, restarting"
+            );
+            if failures == config.alert_after {
+                Self::alert(&bus, &config, &name, failures, &reason).await;
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = shutdown.notified() => return,
+            }
+        }
+    }
+
+    /// Resolve once the channel has been silent for `max_silence`
+    /// (never, when zero or when the channel doesn't report health).
+    async fn wait_silent(channel: &dyn Channel, started: Instant, max_silence: Duration) {
+        if max_silence.is_zero() {
+            return std::future::pending().await;
+        }
+        let interval = (max_silence / 4).max(MIN_HEALTH_CHECK_INTERVAL);
+        loop {
+            tokio::time::sleep(interval).await;
+            if is_silent(channel.health(), started, max_silence) {
+                return;
+            }
+        }
+    }
+
+    /// Tell the admin chat that a channel keeps failing.
+    async fn alert(bus: &MessageBus, config: &SupervisorConfig, name: &str, failures: u32, reason: &str) {
+        if config.alert_channel.is_empty() || config.alert_chat_id.is_empty() {
+            return;
+        }
+        let text = format!(
+            "⚠️ Channel `{name}` has failed {failures} times in a row and keeps restarting.\n\
+             Last error: {reason}"
+        );
+        let alert = OutboundMessage::new(&config.alert_channel, &config.alert_chat_id, text).urgent();
+        if let Err(e) = bus.publish_outbound(alert).await {
+            warn!(error = %e, "failed to publish channel alert");
+        }
+    }
+
     /// Outbound message dispatcher — routes agent responses to the correct channel.
     ///
-    /// Runs as a background task, polling the bus outbound queue.
+    /// Runs as a background task, polling the bus outbound queue. With
+    /// pacing enabled, messages go through per-chat queues so a long reply
+    /// being paced doesn't hold up other chats. On channels that upload
+    /// files, long code blocks are moved into attachments first. Messages
+    /// held for quiet hours are checked every [`QUIET_HOURS_POLL_INTERVAL`]
+    /// and sent once their window ends.
+    ///
+    /// Sends go through `delivery`, which retries them and keeps messages
+    /// that still fail (or name no registered channel) in its outbox. At
+    /// startup and every [`OUTBOX_POLL_INTERVAL`], entries marked for retry
+    /// are re-sent and kept messages of channels that are back delivered.
+    #[allow(clippy::too_many_arguments)]
     async fn dispatch_outbound(
         bus: Arc<MessageBus>,
         channels: HashMap<String, Arc<dyn Channel>>,
+        pacing: PacingConfig,
+        mut repeats: RepeatFilter,
+        code_files: CodeFiles,
+        mut quiet: QuietHours,
+        delivery: Arc<Delivery>,
         shutdown: Arc<Notify>,
     ) {
         info!("outbound dispatcher started");
+        let mut paced = pacing
+            .enabled
+            .then(|| PacedSender::new(channels.clone(), pacing).with_delivery(delivery.clone()));
+        let mut outbox_poll = tokio::time::interval(OUTBOX_POLL_INTERVAL);
+        let mut quiet_poll = tokio::time::interval(QUIET_HOURS_POLL_INTERVAL);
 
         loop {
             tokio::select! {
@@ -186,19 +470,19 @@ impl ChannelManager {
                                 "dispatching outbound message"
                             );
 
-                            if let Some(channel) = channels.get(&outbound.channel) {
-                                if let Err(e) = channel.send(&outbound).await {
-                                    error!(
-                                        channel = %outbound.channel,
-                                        error = %e,
-                                        "failed to send outbound message"
-                                    );
+                            if outbound.progress_id().is_some()
+                                && !channels.get(&outbound.channel).is_some_and(|c| c.supports_edits())
+                            {
+                                debug!(channel = %outbound.channel, "dropping progress update (no edit support)");
+                            } else if outbound.is_acknowledgment() {
+                                // Best-effort: the reply follows either way
+                                if let Some(channel) = channels.get(&outbound.channel) {
+                                    if let Err(e) = channel.acknowledge(&outbound).await {
+                                        debug!(channel = %outbound.channel, error = %e, "acknowledgment failed (non-fatal)");
+                                    }
                                 }
-                            } else {
-                                warn!(
-                                    channel = %outbound.channel,
-                                    "no channel registered for outbound message"
-                                );
+                            } else if !quiet.hold(&outbound) {
+                                Self::deliver(&channels, &mut repeats, &code_files, &mut paced, &delivery, outbound).await;
                             }
                         }
                         None => {
@@ -207,6 +491,19 @@ impl ChannelManager {
                         }
                     }
                 }
+                _ = outbox_poll.tick(), if delivery.outbox().is_some() => {
+                    if let Some(outbox) = delivery.outbox() {
+                        for entry in outbox.take_retries() {
+                            Self::resend(&channels, outbox, entry).await;
+                        }
+                    }
+                    delivery.redeliver().await;
+                }
+                _ = quiet_poll.tick(), if quiet.is_holding() => {
+                    for outbound in quiet.release() {
+                        Self::deliver(&channels, &mut repeats, &code_files, &mut paced, &delivery, outbound).await;
+                    }
+                }
                 _ = shutdown.notified() => {
                     info!("dispatcher received shutdown signal");
                     break;
@@ -214,6 +511,69 @@ impl ChannelManager {
             }
         }
     }
+
+    /// Send a message that is due: repeats are dropped, long code blocks
+    /// attached, and the rest paced or handed to `delivery`.
+    async fn deliver(
+        channels: &HashMap<String, Arc<dyn Channel>>,
+        repeats: &mut RepeatFilter,
+        code_files: &CodeFiles,
+        paced: &mut Option<PacedSender>,
+        delivery: &Delivery,
+        mut outbound: OutboundMessage,
+    ) {
+        if !repeats.allow(&mut outbound) {
+            debug!(channel = %outbound.channel, "dropping repeated message");
+            return;
+        }
+        if channels.get(&outbound.channel).is_some_and(|c| c.supports_uploads()) {
+            code_files.apply(&mut outbound);
+        }
+        if let Some(paced) = paced {
+            paced.send(outbound);
+        } else if let Some(channel) = channels.get(&outbound.channel) {
+            delivery.send(channel.as_ref(), &outbound).await;
+        } else {
+            warn!(
+                channel = %outbound.channel,
+                "no channel registered for outbound message"
+            );
+            delivery.undeliverable(&outbound, "no channel registered").await;
+        }
+    }
+
+    /// Send an outbox entry marked for retry, putting it back on failure.
+    ///
+    /// Paced messages were kept part by part, so entries go out as is.
+    async fn resend(channels: &HashMap<String, Arc<dyn Channel>>, outbox: &Outbox, entry: OutboxEntry) {
+        let result = match channels.get(&entry.message.channel) {
+            Some(channel) => channel.send(&entry.message).await.map_err(|e| format!("{e:#}")),
+            None => Err("no channel registered".to_string()),
+        };
+        match result {
+            Ok(()) => info!(id = %entry.id, channel = %entry.message.channel, "re-sent outbox message"),
+            Err(error) => {
+                warn!(id = %entry.id, channel = %entry.message.channel, error = %error, "outbox retry failed");
+                if let Err(e) = outbox.fail(entry, &error) {
+                    error!(error = %e, "failed to update outbox entry");
+                }
+            }
+        }
+    }
+}
+
+/// Delay before restart number `failures` (1-based): doubles from
+/// `initialBackoffMs`, capped at `maxBackoffMs`.
+fn restart_delay(config: &SupervisorConfig, failures: u32) -> Duration {
+    let exp = failures.saturating_sub(1).min(31);
+    let ms = config.initial_backoff_ms.saturating_mul(1u64 << exp);
+    Duration::from_millis(ms.min(config.max_backoff_ms))
+}
+
+/// Whether a channel's last activity (or its start, if later) is older
+/// than `max_silence`.
+fn is_silent(report: Option<HealthReport>, started: Instant, max_silence: Duration) -> bool {
+    report.is_some_and(|r| r.last_activity.max(started).elapsed() > max_silence)
 }
 
 // ─────────────────────────────────────────────
@@ -224,6 +584,7 @@ impl ChannelManager {
 mod tests {
     use super::*;
     use crate::base::Channel;
+    use oxibot_core::bus::events::{BusEvent, Topic};
     use oxibot_core::bus::types::OutboundMessage;
     use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
@@ -233,6 +594,8 @@ mod tests {
         started: Arc<AtomicBool>,
         stopped: Arc<AtomicBool>,
         send_count: Arc<AtomicUsize>,
+        ack_count: Arc<AtomicUsize>,
+        edits: bool,
     }
 
     impl MockChannel {
@@ -242,8 +605,15 @@ mod tests {
                 started: Arc::new(AtomicBool::new(false)),
                 stopped: Arc::new(AtomicBool::new(false)),
                 send_count: Arc::new(AtomicUsize::new(0)),
+                ack_count: Arc::new(AtomicUsize::new(0)),
+                edits: false,
             }
         }
+
+        fn with_edits(mut self) -> Self {
+            self.edits = true;
+            self
+        }
     }
 
     #[async_trait::async_trait]
@@ -268,6 +638,31 @@ mod tests {
             self.send_count.fetch_add(1, Ordering::SeqCst);
             Ok(())
         }
+
+        fn supports_edits(&self) -> bool {
+            self.edits
+        }
+
+        async fn acknowledge(&self, _ack: &OutboundMessage) -> anyhow::Result<()> {
+            self.ack_count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn test_delivery(channels: &HashMap<String, Arc<dyn Channel>>, outbox: Option<Arc<Outbox>>) -> Arc<Delivery> {
+        Arc::new(Delivery::new(channels.clone(), DeliveryConfig::default()).with_outbox(outbox))
+    }
+
+    fn code_files() -> CodeFiles {
+        CodeFiles::new(CodeFilesConfig::default(), std::env::temp_dir())
+    }
+
+    fn quiet_hours() -> QuietHours {
+        QuietHours::new(&QuietHoursConfig::default(), None)
+    }
+
+    fn repeats() -> RepeatFilter {
+        RepeatFilter::new(RepeatsConfig::default())
     }
 
     #[test]
@@ -392,8 +787,9 @@ mod tests {
         // Spawn the dispatcher
         let bus_clone = bus.clone();
         let shutdown_clone = shutdown.clone();
+        let delivery = test_delivery(&channels, None);
         let handle = tokio::spawn(async move {
-            ChannelManager::dispatch_outbound(bus_clone, channels, shutdown_clone).await;
+            ChannelManager::dispatch_outbound(bus_clone, channels, PacingConfig::default(), repeats(), code_files(), quiet_hours(), delivery, shutdown_clone).await;
         });
 
         // Send messages
@@ -418,6 +814,109 @@ mod tests {
         assert_eq!(ch2_count.load(Ordering::SeqCst), 1); // discord got 1
     }
 
+    #[tokio::test]
+    async fn test_dispatch_progress_only_to_editable_channels() {
+        let bus = Arc::new(MessageBus::new(32));
+
+        let editable = Arc::new(MockChannel::new("discord").with_edits());
+        let plain = Arc::new(MockChannel::new("whatsapp"));
+        let editable_count = editable.send_count.clone();
+        let plain_count = plain.send_count.clone();
+
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("discord".into(), editable);
+        channels.insert("whatsapp".into(), plain);
+
+        let shutdown = Arc::new(Notify::new());
+        let bus_clone = bus.clone();
+        let shutdown_clone = shutdown.clone();
+        let delivery = test_delivery(&channels, None);
+        let handle = tokio::spawn(async move {
+            ChannelManager::dispatch_outbound(bus_clone, channels, PacingConfig::default(), repeats(), code_files(), quiet_hours(), delivery, shutdown_clone).await;
+        });
+
+        for channel in ["discord", "whatsapp"] {
+            bus.publish_outbound(OutboundMessage::progress(channel, "c", "working", "p1", false))
+                .await
+                .unwrap();
+            bus.publish_outbound(OutboundMessage::new(channel, "c", "done"))
+                .await
+                .unwrap();
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        shutdown.notify_waiters();
+        let _ = handle.await;
+
+        assert_eq!(editable_count.load(Ordering::SeqCst), 2);
+        assert_eq!(plain_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_acknowledgments() {
+        let bus = Arc::new(MessageBus::new(32));
+        let ch = Arc::new(MockChannel::new("telegram"));
+        let (send_count, ack_count) = (ch.send_count.clone(), ch.ack_count.clone());
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("telegram".into(), ch);
+
+        let shutdown = Arc::new(Notify::new());
+        let (bus_clone, shutdown_clone) = (bus.clone(), shutdown.clone());
+        let delivery = test_delivery(&channels, None);
+        let handle = tokio::spawn(async move {
+            ChannelManager::dispatch_outbound(bus_clone, channels, PacingConfig::default(), repeats(), code_files(), quiet_hours(), delivery, shutdown_clone).await;
+        });
+
+        let mut inbound = oxibot_core::bus::types::InboundMessage::new("telegram", "u", "c", "hi");
+        inbound.metadata.insert("message_id".into(), "42".into());
+        bus.publish_outbound(OutboundMessage::acknowledgment(&inbound).unwrap())
+            .await
+            .unwrap();
+        bus.publish_outbound(OutboundMessage::new("telegram", "c", "hello"))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        shutdown.notify_waiters();
+        let _ = handle.await;
+
+        assert_eq!(ack_count.load(Ordering::SeqCst), 1);
+        assert_eq!(send_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_outbox() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Arc::new(Outbox::new(dir.path()));
+        let stuck = outbox.add(&OutboundMessage::new("telegram", "c", "late"), "bot was blocked").unwrap();
+        outbox.retry(&stuck.id).unwrap();
+
+        let bus = Arc::new(MessageBus::new(32));
+        let ch = Arc::new(MockChannel::new("telegram"));
+        let send_count = ch.send_count.clone();
+        let channels: HashMap<String, Arc<dyn Channel>> = HashMap::from([("telegram".into(), ch as Arc<dyn Channel>)]);
+        let shutdown = Arc::new(Notify::new());
+        let (bus_clone, shutdown_clone, outbox_clone) = (bus.clone(), shutdown.clone(), outbox.clone());
+        let delivery = test_delivery(&channels, Some(outbox_clone));
+        let handle = tokio::spawn(async move {
+            ChannelManager::dispatch_outbound(bus_clone, channels, PacingConfig::default(), repeats(), code_files(), quiet_hours(), delivery, shutdown_clone).await;
+        });
+
+        bus.publish_outbound(OutboundMessage::new("unknown", "chat", "msg"))
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        shutdown.notify_waiters();
+        let _ = handle.await;
+
+        // The retry went out at startup; the unroutable message was kept
+        assert_eq!(send_count.load(Ordering::SeqCst), 1);
+        let kept = outbox.list();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].message.channel, "unknown");
+        assert_eq!(kept[0].error, "no channel registered");
+    }
+
     #[tokio::test]
     async fn test_dispatch_outbound_unknown_channel() {
         let bus = Arc::new(MessageBus::new(32));
@@ -426,8 +925,9 @@ mod tests {
 
         let bus_clone = bus.clone();
         let shutdown_clone = shutdown.clone();
+        let delivery = test_delivery(&channels, None);
         let handle = tokio::spawn(async move {
-            ChannelManager::dispatch_outbound(bus_clone, channels, shutdown_clone).await;
+            ChannelManager::dispatch_outbound(bus_clone, channels, PacingConfig::default(), repeats(), code_files(), quiet_hours(), delivery, shutdown_clone).await;
         });
 
         // Send to a channel that doesn't exist
@@ -442,6 +942,196 @@ mod tests {
         let _ = handle.await;
     }
 
+    /// Channel whose `start()` fails a number of times before it runs.
+    struct FlakyChannel {
+        failures_left: AtomicUsize,
+        starts: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for FlakyChannel {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn start(&self) -> anyhow::Result<()> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            if self.failures_left.load(Ordering::SeqCst) > 0 {
+                self.failures_left.fetch_sub(1, Ordering::SeqCst);
+                anyhow::bail!("connection refused");
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+            Ok(())
+        }
+
+        async fn stop(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, _msg: &OutboundMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn test_supervisor() -> SupervisorConfig {
+        SupervisorConfig {
+            initial_backoff_ms: 10,
+            max_backoff_ms: 1000,
+            alert_after: 2,
+            alert_channel: "telegram".into(),
+            alert_chat_id: "admin".into(),
+            ..SupervisorConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_supervise_restarts_failed_channel_and_alerts() {
+        let bus = Arc::new(MessageBus::new(32));
+        let starts = Arc::new(AtomicUsize::new(0));
+        let channel = Arc::new(FlakyChannel {
+            failures_left: AtomicUsize::new(3),
+            starts: starts.clone(),
+        });
+        let shutdown = Arc::new(Notify::new());
+
+        let handle = tokio::spawn(ChannelManager::supervise(
+            "flaky".into(),
+            channel,
+            test_supervisor(),
+            bus.clone(),
+            None,
+            shutdown.clone(),
+        ));
+
+        // Backoff: 10 + 20 + 40 ms before the fourth start
+        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 4);
+
+        let alert = bus.consume_outbound().await.unwrap();
+        assert_eq!(alert.channel, "telegram");
+        assert_eq!(alert.chat_id, "admin");
+        assert!(alert.content.contains("`flaky` has failed 2 times"));
+        assert!(alert.content.contains("connection refused"));
+
+        shutdown.notify_waiters();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_supervise_disabled_does_not_restart() {
+        let bus = Arc::new(MessageBus::new(32));
+        let starts = Arc::new(AtomicUsize::new(0));
+        let channel = Arc::new(FlakyChannel {
+            failures_left: AtomicUsize::new(1),
+            starts: starts.clone(),
+        });
+        let config = SupervisorConfig { enabled: false, ..test_supervisor() };
+        let mut status = bus.subscribe(&[Topic::Status]);
+
+        ChannelManager::supervise("flaky".into(), channel, config, bus, None, Arc::new(Notify::new())).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+
+        let mut events = Vec::new();
+        while let Some(event) = status.recv().await {
+            if let BusEvent::Status(ref event) = *event {
+                events.push(event.clone());
+            }
+        }
+        assert_eq!(
+            events,
+            [
+                StatusEvent::ChannelStarted { channel: "flaky".into() },
+                StatusEvent::ChannelFailed {
+                    channel: "flaky".into(),
+                    reason: "connection refused".into(),
+                    failures: 1,
+                },
+            ]
+        );
+    }
+
+    /// Channel whose first start panics.
+    struct PanickyChannel {
+        starts: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for PanickyChannel {
+        fn name(&self) -> &str {
+            "panicky"
+        }
+
+        async fn start(&self) -> anyhow::Result<()> {
+            if self.starts.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("index out of bounds");
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+            Ok(())
+        }
+
+        async fn stop(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, _msg: &OutboundMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_supervise_reports_panic_and_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(32));
+        let crashes = CrashReporter::new(bus.clone(), Some(dir.path().to_path_buf())).with_admin("telegram", "admin");
+        let starts = Arc::new(AtomicUsize::new(0));
+        let channel = Arc::new(PanickyChannel { starts: starts.clone() });
+        let shutdown = Arc::new(Notify::new());
+
+        let handle = tokio::spawn(ChannelManager::supervise(
+            "panicky".into(),
+            channel,
+            test_supervisor(),
+            bus.clone(),
+            Some(crashes),
+            shutdown.clone(),
+        ));
+
+        let alert = bus.consume_outbound().await.unwrap();
+        assert!(alert.content.contains("`channel panicky` crashed: index out of bounds"));
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        shutdown.notify_waiters();
+        handle.await.unwrap();
+    }
+
+    #[test]
+    fn test_restart_delay_backoff() {
+        let config = SupervisorConfig {
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 5000,
+            ..SupervisorConfig::default()
+        };
+        assert_eq!(restart_delay(&config, 1), Duration::from_secs(1));
+        assert_eq!(restart_delay(&config, 3), Duration::from_secs(4));
+        assert_eq!(restart_delay(&config, 4), Duration::from_secs(5));
+        assert_eq!(restart_delay(&config, 100), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_is_silent() {
+        let max = Duration::from_millis(50);
+        let old = Instant::now() - Duration::from_secs(10);
+        let report = HealthReport { last_activity: old };
+
+        assert!(is_silent(Some(report), old, max));
+        // Silence is measured from the (re)start when that is more recent
+        assert!(!is_silent(Some(report), Instant::now(), max));
+        assert!(!is_silent(Some(HealthReport { last_activity: Instant::now() }), old, max));
+        assert!(!is_silent(None, old, max));
+    }
+
     #[tokio::test]
     async fn test_signal_shutdown() {
         let bus = Arc::new(MessageBus::new(32));
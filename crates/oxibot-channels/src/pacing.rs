@@ -0,0 +1,340 @@
+//! Paced sending — long replies go out as several messages, one at a time.
+//!
+//! Channels with a message length limit used to split long replies
+//! themselves and post the parts back-to-back, which trips platform rate
+//! limits and can deliver them out of order. The [`PacedSender`] splits
+//! replies at the channel's limit (see [`Channel::max_message_len`]) on
+//! paragraph, line or word boundaries, and sends the parts with a pause in
+//! between, showing a typing indicator meanwhile. Each chat has its own
+//! queue: messages to one chat go out in order, while other chats don't
+//! wait for it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::schema::{DeliveryConfig, PacingConfig};
+
+use crate::base::Channel;
+use crate::delivery::Delivery;
+
+/// A chat queue with nothing to send for this long is closed.
+const QUEUE_IDLE: Duration = Duration::from_secs(60);
+
+/// Metadata key some channels read the message to reply to from.
+const REPLY_TO_KEY: &str = "reply_to";
+
+/// Sends outbound messages through per-chat queues, pacing long replies.
+pub struct PacedSender {
+    channels: HashMap<String, Arc<dyn Channel>>,
+    config: PacingConfig,
+    /// Open queues, keyed by `(channel, chat_id)`.
+    queues: HashMap<(String, String), mpsc::UnboundedSender<OutboundMessage>>,
+    /// Retries and keeps parts that fail to send.
+    delivery: Arc<Delivery>,
+}
+
+impl PacedSender {
+    pub fn new(channels: HashMap<String, Arc<dyn Channel>>, config: PacingConfig) -> Self {
+        let delivery = Arc::new(Delivery::new(channels.clone(), DeliveryConfig::default()));
+        Self {
+            channels,
+            config,
+            queues: HashMap::new(),
+            delivery,
+        }
+    }
+
+    /// Send parts through `delivery`, which retries and keeps them.
+    pub fn with_delivery(mut self, delivery: Arc<Delivery>) -> Self {
+        self.delivery = delivery;
+        self
+    }
+
+    /// Queue `msg` behind earlier messages to the same chat.
+    pub fn send(&mut self, msg: OutboundMessage) {
+        let key = (msg.channel.clone(), msg.chat_id.clone());
+        // A queue that closed after idling hands the message back
+        let msg = match self.queues.get(&key) {
+            Some(queue) => match queue.send(msg) {
+                Ok(()) => return,
+                Err(mpsc::error::SendError(msg)) => msg,
+            },
+            None => msg,
+        };
+        let Some(channel) = self.channels.get(&msg.channel).cloned() else {
+            warn!(channel = %msg.channel, "no channel registered for outbound message");
+            let delivery = self.delivery.clone();
+            tokio::spawn(async move { delivery.undeliverable(&msg, "no channel registered").await });
+            return;
+        };
+
+        self.queues.retain(|_, queue| !queue.is_closed());
+        let (queue, rx) = mpsc::unbounded_channel();
+        let _ = queue.send(msg);
+        let delay = Duration::from_millis(
+            self.config
+                .channel_delays_ms
+                .get(channel.name())
+                .copied()
+                .unwrap_or(self.config.chunk_delay_ms),
+        );
+        tokio::spawn(run_queue(channel, delay, self.config.min_tail_chars, self.delivery.clone(), rx));
+        self.queues.insert(key, queue);
+    }
+}
+
+/// Send a chat's messages in order until it has been idle for a while.
+async fn run_queue(
+    channel: Arc<dyn Channel>,
+    delay: Duration,
+    min_tail: usize,
+    delivery: Arc<Delivery>,
+    mut rx: mpsc::UnboundedReceiver<OutboundMessage>,
+) {
+    loop {
+        match tokio::time::timeout(QUEUE_IDLE, rx.recv()).await {
+            Ok(Some(msg)) => send_paced(channel.as_ref(), &msg, delay, min_tail, &delivery).await,
+            Ok(None) => return,
+            Err(_) => {
+                // Refuse new messages, then send what raced in
+                rx.close();
+                while let Ok(msg) = rx.try_recv() {
+                    send_paced(channel.as_ref(), &msg, delay, min_tail, &delivery).await;
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Send `msg`, split into parts of at most the channel's message length,
+/// waiting `delay` between parts.
+///
+/// The first part replies to the original message, the last one carries
+/// the attachments. Progress updates and approval requests are sent as is.
+/// Parts go through `delivery`, which retries and keeps those that fail.
+pub async fn send_paced(
+    channel: &dyn Channel,
+    msg: &OutboundMessage,
+    delay: Duration,
+    min_tail: usize,
+    delivery: &Delivery,
+) {
+    let chunks = match channel.max_message_len() {
+        Some(max_len) if msg.progress_id().is_none() && msg.approval().is_none() => {
+            chunk_message(&msg.content, max_len, min_tail)
+        }
+        _ => Vec::new(),
+    };
+    if chunks.len() <= 1 {
+        return delivery.send(channel, msg).await;
+    }
+
+    let count = chunks.len();
+    debug!(channel = %msg.channel, chat_id = %msg.chat_id, parts = count, "sending paced reply");
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        if i > 0 {
+            if let Err(e) = channel.typing(&msg.chat_id).await {
+                debug!(channel = %msg.channel, error = %e, "typing indicator failed (non-fatal)");
+            }
+            tokio::time::sleep(delay).await;
+        }
+        let mut part = OutboundMessage {
+            content: chunk,
+            media: Vec::new(),
+            ..msg.clone()
+        };
+        if i > 0 {
+            part.reply_to = None;
+            part.metadata.remove(REPLY_TO_KEY);
+        }
+        if i == count - 1 {
+            part.media = msg.media.clone();
+        }
+        delivery.send(channel, &part).await;
+    }
+}
+
+/// Split `text` into parts of at most `max_len` characters, preferring
+/// paragraph breaks, then line breaks, then spaces.
+///
+/// A last part shorter than `min_tail` characters is merged with the one
+/// before it and the two are split again near the middle, so replies don't
+/// end with a stray line.
+pub fn chunk_message(text: &str, max_len: usize, min_tail: usize) -> Vec<String> {
+    let max_len = max_len.max(1);
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    loop {
+        let rest = &text[start..];
+        if rest.chars().count() <= max_len {
+            if !rest.trim().is_empty() || ranges.is_empty() {
+                ranges.push((start, text.len()));
+            }
+            break;
+        }
+        let window = &rest[..byte_offset(rest, max_len)];
+        let end = start + split_point(window);
+        ranges.push((start, end));
+        start = skip_whitespace(text, end);
+    }
+
+    if let [.., (prev_start, _), (tail_start, tail_end)] = ranges[..] {
+        if text[tail_start..tail_end].trim().chars().count() < min_tail {
+            if let Some(at) = even_split(&text[prev_start..tail_end], max_len) {
+                let n = ranges.len();
+                ranges[n - 2] = (prev_start, prev_start + at);
+                ranges[n - 1] = (skip_whitespace(text, prev_start + at), tail_end);
+            }
+        }
+    }
+
+    ranges.into_iter().map(|(s, e)| text[s..e].trim_end().to_string()).collect()
+}
+
+/// Byte offset of character `n` of `s` (or its length).
+fn byte_offset(s: &str, n: usize) -> usize {
+    s.char_indices().nth(n).map_or(s.len(), |(i, _)| i)
+}
+
+/// Offset of the first non-whitespace character at or after `at`.
+fn skip_whitespace(text: &str, at: usize) -> usize {
+    text.len() - text[at..].trim_start().len()
+}
+
+/// Where to end a part that must fit in `window`.
+fn split_point(window: &str) -> usize {
+    let half = window.len() / 2;
+    window
+        .rfind("\n\n")
+        .filter(|&i| i > 0 && i >= half)
+        .or_else(|| window.rfind('\n').filter(|&i| i > 0 && i >= half))
+        .or_else(|| window.rfind(' ').filter(|&i| i > 0))
+        .or_else(|| window.rfind('\n').filter(|&i| i > 0))
+        .unwrap_or(window.len())
+}
+
+/// A boundary near the middle of `s` leaving two parts of at most
+/// `max_len` characters.
+fn even_split(s: &str, max_len: usize) -> Option<usize> {
+    let mid = s.len() / 2;
+    let fits = |i: usize| {
+        s[..i].trim_end().chars().count() <= max_len && s[i..].trim_start().chars().count() <= max_len
+    };
+    ["\n\n", "\n", " "].iter().find_map(|sep| {
+        s.match_indices(sep)
+            .map(|(i, _)| i)
+            .filter(|&i| i > 0 && i.abs_diff(mid) <= s.len() / 4 && fits(i))
+            .min_by_key(|&i| i.abs_diff(mid))
+    })
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    #[test]
+    fn test_chunk_message_boundaries() {
+        assert_eq!(chunk_message("short", 100, 0), ["short"]);
+        assert_eq!(chunk_message("", 100, 0), [""]);
+
+        let text = format!("{}\n\n{}", "a".repeat(30), "b".repeat(30));
+        assert_eq!(chunk_message(&text, 40, 0), ["a".repeat(30), "b".repeat(30)]);
+
+        // Words aren't cut, multibyte characters aren't split
+        let words = "ñandú ".repeat(10);
+        let chunks = chunk_message(words.trim(), 20, 0);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 20 && !c.starts_with(' ')));
+        assert_eq!(chunks.join(" "), words.trim());
+        assert_eq!(chunk_message(&"x".repeat(25), 10, 0), ["x".repeat(10), "x".repeat(10), "x".repeat(5)]);
+    }
+
+    #[test]
+    fn test_chunk_message_rebalances_tiny_tail() {
+        let lines: Vec<String> = (0..10).map(|i| format!("line {i:02} ....")).collect();
+        let text = lines.join("\n");
+        // 129 chars: without rebalancing, 7 lines + 3 lines (38 chars)
+        assert_eq!(chunk_message(&text, 100, 0)[1].len(), 38);
+        let chunks = chunk_message(&text, 100, 40);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.len() == 64), "{chunks:?}");
+        assert_eq!(chunks.join("\n"), text);
+    }
+
+    /// Records what was sent and when.
+    struct Recorder {
+        sent: Mutex<Vec<(Instant, OutboundMessage)>>,
+        typing: Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for Recorder {
+        fn name(&self) -> &str {
+            "rec"
+        }
+        async fn start(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn stop(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
+            self.sent.lock().unwrap().push((Instant::now(), msg.clone()));
+            Ok(())
+        }
+        fn max_message_len(&self) -> Option<usize> {
+            Some(10)
+        }
+        async fn typing(&self, _chat_id: &str) -> anyhow::Result<()> {
+            *self.typing.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paced_sender_orders_and_paces() {
+        let recorder = Arc::new(Recorder {
+            sent: Mutex::new(Vec::new()),
+            typing: Mutex::new(0),
+        });
+        let channels = HashMap::from([("rec".to_string(), recorder.clone() as Arc<dyn Channel>)]);
+        let config = PacingConfig {
+            chunk_delay_ms: 1000,
+            channel_delays_ms: HashMap::from([("rec".to_string(), 30)]),
+            min_tail_chars: 0,
+            ..Default::default()
+        };
+        let mut sender = PacedSender::new(channels, config);
+
+        let mut long = OutboundMessage::new("rec", "1", "aaaa bbbb cccc dddd");
+        long.reply_to = Some("m1".into());
+        sender.send(long);
+        sender.send(OutboundMessage::new("rec", "1", "next"));
+        sender.send(OutboundMessage::new("rec", "2", "other chat"));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let sent = recorder.sent.lock().unwrap();
+        let chat1: Vec<_> = sent.iter().filter(|(_, m)| m.chat_id == "1").collect();
+        let contents: Vec<&str> = chat1.iter().map(|(_, m)| m.content.as_str()).collect();
+        assert_eq!(contents, ["aaaa bbbb", "cccc dddd", "next"]);
+        assert_eq!(chat1[0].1.reply_to.as_deref(), Some("m1"));
+        assert_eq!(chat1[1].1.reply_to, None);
+        assert!(chat1[1].0 - chat1[0].0 >= Duration::from_millis(30));
+        assert_eq!(*recorder.typing.lock().unwrap(), 1);
+
+        // The other chat didn't wait for the paced reply
+        let other = sent.iter().find(|(_, m)| m.chat_id == "2").unwrap();
+        assert!(other.0 < chat1[1].0);
+    }
+}
@@ -0,0 +1,219 @@
+//! Quiet hours — hold unprompted messages overnight.
+//!
+//! A cron job reporting at 03:00 shouldn't buzz a phone. [`QuietHours`]
+//! holds messages the bot sends on its own to a channel during its window
+//! (`channels.quietHours`) and hands them back once the window ends.
+//! Replies to the user ([`OutboundMessage::as_reply`]), urgent messages
+//! ([`OutboundMessage::urgent`]), progress updates, acknowledgments and
+//! approval requests are never held. Held messages are saved to a file,
+//! so a restart during the night doesn't lose them.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveTime, Utc};
+use tracing::{debug, info, warn};
+
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::schema::QuietHoursConfig;
+use oxibot_core::datetime::Tz;
+
+/// Holds unprompted messages sent during a channel's quiet hours.
+pub struct QuietHours {
+    timezone: Tz,
+    /// `(start, end)` of the window per channel name.
+    windows: HashMap<String, (NaiveTime, NaiveTime)>,
+    /// Where held messages are saved (`None` keeps them in memory only).
+    path: Option<PathBuf>,
+    held: Vec<OutboundMessage>,
+}
+
+impl QuietHours {
+    pub fn new(config: &QuietHoursConfig, path: Option<PathBuf>) -> Self {
+        let timezone = config.timezone.parse().unwrap_or_else(|_| {
+            warn!(timezone = %config.timezone, "unknown quiet hours timezone, using UTC");
+            Tz::UTC
+        });
+        let mut windows = HashMap::new();
+        for (channel, window) in &config.channels {
+            match (parse_time(&window.start), parse_time(&window.end)) {
+                (Some(start), Some(end)) => {
+                    windows.insert(channel.clone(), (start, end));
+                }
+                _ => warn!(
+                    channel = %channel,
+                    start = %window.start,
+                    end = %window.end,
+                    "invalid quiet hours window (expected HH:MM), ignoring it"
+                ),
+            }
+        }
+        let held: Vec<OutboundMessage> = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        if !held.is_empty() {
+            info!(count = held.len(), "loaded messages held for quiet hours");
+        }
+        Self {
+            timezone,
+            windows,
+            path,
+            held,
+        }
+    }
+
+    /// Whether any messages are waiting for their window to end.
+    pub fn is_holding(&self) -> bool {
+        !self.held.is_empty()
+    }
+
+    /// Hold `msg` if its channel is in quiet hours and it may wait.
+    /// Returns whether it was held.
+    pub fn hold(&mut self, msg: &OutboundMessage) -> bool {
+        self.hold_at(msg, Utc::now())
+    }
+
+    /// Take the held messages whose channel's window has ended, in order.
+    pub fn release(&mut self) -> Vec<OutboundMessage> {
+        self.release_at(Utc::now())
+    }
+
+    fn hold_at(&mut self, msg: &OutboundMessage, now: DateTime<Utc>) -> bool {
+        if may_wait(msg) && self.is_quiet(&msg.channel, now) {
+            debug!(channel = %msg.channel, chat_id = %msg.chat_id, "holding message until quiet hours end");
+            self.held.push(msg.clone());
+            self.save();
+            return true;
+        }
+        false
+    }
+
+    fn release_at(&mut self, now: DateTime<Utc>) -> Vec<OutboundMessage> {
+        let (due, held): (Vec<_>, Vec<_>) = std::mem::take(&mut self.held)
+            .into_iter()
+            .partition(|msg| !self.is_quiet(&msg.channel, now));
+        self.held = held;
+        if !due.is_empty() {
+            info!(count = due.len(), "quiet hours over, sending held messages");
+            self.save();
+        }
+        due
+    }
+
+    /// Whether `now` falls in `channel`'s quiet window.
+    fn is_quiet(&self, channel: &str, now: DateTime<Utc>) -> bool {
+        let Some(&(start, end)) = self.windows.get(channel) else {
+            return false;
+        };
+        let time = now.with_timezone(&self.timezone).time();
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+
+    fn save(&self) {
+        let Some(ref path) = self.path else { return };
+        let result = if self.held.is_empty() {
+            std::fs::remove_file(path).or_else(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => Ok(()),
+                _ => Err(e),
+            })
+        } else {
+            let json = serde_json::to_string_pretty(&self.held).unwrap_or_default();
+            path.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(path, json))
+        };
+        if let Err(e) = result {
+            warn!(path = %path.display(), error = %e, "failed to save held messages");
+        }
+    }
+}
+
+/// Whether `msg` is one the bot sends on its own, and not urgent.
+fn may_wait(msg: &OutboundMessage) -> bool {
+    !msg.is_reply()
+        && !msg.is_urgent()
+        && msg.progress_id().is_none()
+        && !msg.is_acknowledgment()
+        && msg.approval().is_none()
+}
+
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M").ok()
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use oxibot_core::config::schema::QuietWindow;
+
+    fn config() -> QuietHoursConfig {
+        QuietHoursConfig {
+            timezone: "Europe/Madrid".into(),
+            channels: HashMap::from([(
+                "telegram".to_string(),
+                QuietWindow { start: "23:00".into(), end: "08:00".into() },
+            )]),
+        }
+    }
+
+    /// `hour`:00 in Madrid on a summer day (UTC+2).
+    fn madrid(day: u32, hour: u32) -> DateTime<Utc> {
+        Tz::Europe__Madrid
+            .with_ymd_and_hms(2026, 7, day, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_window_spans_midnight() {
+        let quiet = QuietHours::new(&config(), None);
+        assert!(quiet.is_quiet("telegram", madrid(1, 23)));
+        assert!(quiet.is_quiet("telegram", madrid(2, 3)));
+        assert!(!quiet.is_quiet("telegram", madrid(2, 8)));
+        assert!(!quiet.is_quiet("telegram", madrid(2, 12)));
+        assert!(!quiet.is_quiet("discord", madrid(2, 3)));
+
+        let mut daytime = config();
+        daytime.channels.insert("slack".into(), QuietWindow { start: "12:00".into(), end: "14:00".into() });
+        daytime.channels.insert("email".into(), QuietWindow { start: "noon".into(), end: "14:00".into() });
+        let quiet = QuietHours::new(&daytime, None);
+        assert!(quiet.is_quiet("slack", madrid(2, 13)));
+        assert!(!quiet.is_quiet("slack", madrid(2, 3)));
+        assert!(!quiet.is_quiet("email", madrid(2, 13)));
+    }
+
+    #[test]
+    fn test_hold_and_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("held.json");
+        let mut quiet = QuietHours::new(&config(), Some(path.clone()));
+
+        let night = madrid(2, 3);
+        assert!(quiet.hold_at(&OutboundMessage::new("telegram", "42", "Daily report"), night));
+        assert!(!quiet.hold_at(&OutboundMessage::new("telegram", "42", "Sure!").as_reply(), night));
+        assert!(!quiet.hold_at(&OutboundMessage::new("telegram", "1", "Crashed").urgent(), night));
+        assert!(!quiet.hold_at(&OutboundMessage::new("discord", "7", "Daily report"), night));
+        assert!(!quiet.hold_at(&OutboundMessage::new("telegram", "42", "Lunch"), madrid(2, 12)));
+
+        // Held messages survive a restart
+        let mut quiet = QuietHours::new(&config(), Some(path.clone()));
+        assert!(quiet.is_holding());
+        assert!(quiet.release_at(madrid(2, 7)).is_empty());
+        let due = quiet.release_at(madrid(2, 8));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].content, "Daily report");
+        assert!(!quiet.is_holding());
+        assert!(!path.exists());
+    }
+}
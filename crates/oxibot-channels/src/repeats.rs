@@ -0,0 +1,193 @@
+//! Repeat suppression — don't send the same unprompted message over and over.
+//!
+//! A cron job or subagent that keeps finding nothing new ("no new emails")
+//! would post the same result every time it runs. The [`RepeatFilter`]
+//! remembers a hash of each message sent to a chat, and drops a message
+//! that repeats one sent there within the window (`channels.repeats`).
+//! Messages marked with [`OutboundMessage::forced`] — the agent's replies
+//! to the user, cron jobs with `forceDelivery` — always go out, and reset
+//! the window for their content.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::schema::RepeatsConfig;
+
+/// A message recently sent to a chat.
+#[derive(Clone, Copy, Debug)]
+struct Sent {
+    /// When it last went out.
+    at: Instant,
+    /// Repeats dropped since then.
+    dropped: u32,
+}
+
+/// Drops unprompted messages that repeat a recent one to the same chat.
+pub struct RepeatFilter {
+    config: RepeatsConfig,
+    /// Recently sent messages per `(channel, chat_id)`, by content hash.
+    sent: HashMap<(String, String), HashMap<u64, Sent>>,
+}
+
+impl RepeatFilter {
+    pub fn new(config: RepeatsConfig) -> Self {
+        Self {
+            config,
+            sent: HashMap::new(),
+        }
+    }
+
+    /// Whether `msg` should be sent. In `"count"` mode, a message sent
+    /// again after its repeats were dropped gets a note saying how many.
+    pub fn allow(&mut self, msg: &mut OutboundMessage) -> bool {
+        self.allow_at(msg, Instant::now())
+    }
+
+    fn allow_at(&mut self, msg: &mut OutboundMessage, now: Instant) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+        let window = Duration::from_secs(self.config.window_secs);
+        let count = self.config.mode == "count";
+        let chat = self
+            .sent
+            .entry((msg.channel.clone(), msg.chat_id.clone()))
+            .or_default();
+        // Dropped repeats are remembered until the message goes out again
+        chat.retain(|_, sent| now.duration_since(sent.at) < window || (count && sent.dropped > 0));
+
+        let hash = content_hash(msg);
+        match chat.get_mut(&hash) {
+            Some(sent) if now.duration_since(sent.at) < window && !msg.is_forced() => {
+                sent.dropped += 1;
+                debug!(
+                    channel = %msg.channel,
+                    chat_id = %msg.chat_id,
+                    dropped = sent.dropped,
+                    "dropping repeated message"
+                );
+                false
+            }
+            previous => {
+                let dropped = previous.map_or(0, |sent| sent.dropped);
+                if count && dropped > 0 {
+                    let times = if dropped == 1 { "time" } else { "times" };
+                    msg.content.push_str(&format!(
+                        "\n\n(repeated {dropped} more {times} since last sent)"
+                    ));
+                }
+                chat.insert(
+                    hash,
+                    Sent {
+                        at: now,
+                        dropped: 0,
+                    },
+                );
+                true
+            }
+        }
+    }
+}
+
+/// Hash of a message's text (whitespace-normalized) and attachments.
+fn content_hash(msg: &OutboundMessage) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for word in msg.content.split_whitespace() {
+        word.hash(&mut hasher);
+    }
+    for media in &msg.media {
+        media.path.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(mode: &str) -> RepeatFilter {
+        RepeatFilter::new(RepeatsConfig {
+            enabled: true,
+            window_secs: 60,
+            mode: mode.into(),
+        })
+    }
+
+    fn msg(chat_id: &str, content: &str) -> OutboundMessage {
+        OutboundMessage::new("telegram", chat_id, content)
+    }
+
+    #[test]
+    fn test_repeats_are_dropped_within_window() {
+        let mut repeats = filter("skip");
+        let start = Instant::now();
+        assert!(repeats.allow_at(&mut msg("1", "No new emails."), start));
+        assert!(!repeats.allow_at(
+            &mut msg("1", "No  new emails.\n"),
+            start + Duration::from_secs(10)
+        ));
+        // Other chats and other content are unaffected
+        assert!(repeats.allow_at(
+            &mut msg("2", "No new emails."),
+            start + Duration::from_secs(10)
+        ));
+        assert!(repeats.allow_at(
+            &mut msg("1", "2 new emails."),
+            start + Duration::from_secs(10)
+        ));
+        // Forced messages go out and restart the window
+        assert!(repeats.allow_at(
+            &mut msg("1", "No new emails.").forced(),
+            start + Duration::from_secs(30)
+        ));
+        assert!(!repeats.allow_at(
+            &mut msg("1", "No new emails."),
+            start + Duration::from_secs(80)
+        ));
+        // After the window the message is sent again, unchanged
+        let mut later = msg("1", "No new emails.");
+        assert!(repeats.allow_at(&mut later, start + Duration::from_secs(100)));
+        assert_eq!(later.content, "No new emails.");
+    }
+
+    #[test]
+    fn test_count_mode_notes_dropped_repeats() {
+        let mut repeats = filter("count");
+        let start = Instant::now();
+        assert!(repeats.allow_at(&mut msg("1", "No new emails."), start));
+        assert!(!repeats.allow_at(
+            &mut msg("1", "No new emails."),
+            start + Duration::from_secs(20)
+        ));
+        assert!(!repeats.allow_at(
+            &mut msg("1", "No new emails."),
+            start + Duration::from_secs(40)
+        ));
+
+        let mut later = msg("1", "No new emails.");
+        assert!(repeats.allow_at(&mut later, start + Duration::from_secs(300)));
+        assert_eq!(
+            later.content,
+            "No new emails.\n\n(repeated 2 more times since last sent)"
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut repeats = RepeatFilter::new(RepeatsConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        assert!(repeats.allow(&mut msg("1", "hi")));
+        assert!(repeats.allow(&mut msg("1", "hi")));
+    }
+}
@@ -0,0 +1,495 @@
+//! Signal channel — JSON-RPC client for a `signal-cli` daemon.
+//!
+//! Architecture:
+//! - `signal-cli -a +15551234567 daemon --tcp 127.0.0.1:7583` holds the
+//!   Signal account; this channel connects to it over TCP
+//! - Inbound: the daemon pushes `receive` notifications, one JSON-RPC
+//!   object per line
+//! - Outbound: we send `send` requests with a `recipient` or `groupId`,
+//!   plus local file paths as attachments
+//!
+//! A direct chat's id is the sender's number (or UUID for accounts that
+//! hide it); a group's is `group:<groupId>`. Attachments are read from the
+//! folder where signal-cli stores them.
+//!
+//! Features:
+//! - Auto-reconnect with backoff
+//! - Allow-lists for senders and for groups
+//! - Inbound and outbound attachments
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, Notify};
+use tracing::{debug, error, info, warn};
+
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::{InboundMessage, OutboundMessage, MESSAGE_ID_KEY};
+use oxibot_core::config::schema::SignalConfig;
+use oxibot_core::types::MediaAttachment;
+
+use crate::base::Channel;
+use crate::enrollment::Enrollment;
+
+// ─────────────────────────────────────────────
+// Constants
+// ─────────────────────────────────────────────
+
+/// Default `signal-cli daemon --tcp` address.
+const DEFAULT_DAEMON_ADDR: &str = "127.0.0.1:7583";
+
+/// Prefix of group chat ids.
+const GROUP_PREFIX: &str = "group:";
+
+/// Reconnect backoff (seconds).
+const RECONNECT_DELAY_SECS: u64 = 5;
+
+// ─────────────────────────────────────────────
+// SignalChannel
+// ─────────────────────────────────────────────
+
+/// Signal channel — connects to a signal-cli daemon.
+pub struct SignalChannel {
+    config: SignalConfig,
+    /// Where signal-cli saves received attachments.
+    attachments_dir: PathBuf,
+    /// Message bus for inbound/outbound.
+    bus: Arc<MessageBus>,
+    /// Shutdown signal.
+    shutdown: Arc<Notify>,
+    /// Write half of the daemon connection, while connected.
+    writer: Arc<Mutex<Option<OwnedWriteHalf>>>,
+    /// JSON-RPC request ids.
+    next_id: AtomicU64,
+    /// Admin-approved access for unknown senders.
+    enrollment: Option<Arc<Enrollment>>,
+}
+
+impl SignalChannel {
+    /// Create a new Signal channel.
+    pub fn new(mut config: SignalConfig, bus: Arc<MessageBus>) -> Self {
+        if config.daemon_addr.is_empty() {
+            config.daemon_addr = DEFAULT_DAEMON_ADDR.to_string();
+        }
+        let attachments_dir = if config.attachments_dir.is_empty() {
+            default_attachments_dir()
+        } else {
+            oxibot_core::utils::expand_home(&config.attachments_dir)
+        };
+        Self {
+            config,
+            attachments_dir,
+            bus,
+            shutdown: Arc::new(Notify::new()),
+            writer: Arc::new(Mutex::new(None)),
+            next_id: AtomicU64::new(1),
+            enrollment: None,
+        }
+    }
+
+    /// Let unknown senders in direct chats ask the admin for access.
+    pub fn with_enrollment(mut self, enrollment: Option<Arc<Enrollment>>) -> Self {
+        self.enrollment = enrollment;
+        self
+    }
+
+    /// Check if a sender (number or UUID) is allowed or enrolled.
+    fn is_allowed(&self, sender: &str, uuid: Option<&str>) -> bool {
+        self.config.allowed_users.is_empty()
+            || self
+                .config
+                .allowed_users
+                .iter()
+                .any(|u| u == sender || Some(u.as_str()) == uuid)
+            || self
+                .enrollment
+                .as_ref()
+                .is_some_and(|e| e.is_enrolled("signal", sender))
+    }
+
+    /// Check if the bot answers in a group.
+    fn is_group_allowed(&self, group_id: &str) -> bool {
+        self.config.allowed_groups.is_empty() || self.config.allowed_groups.iter().any(|g| g == group_id)
+    }
+
+    /// Run the daemon connection with auto-reconnect.
+    async fn run_daemon_loop(&self) -> anyhow::Result<()> {
+        loop {
+            match self.daemon_session().await {
+                Ok(()) => {
+                    info!("signal daemon session ended normally");
+                    return Ok(());
+                }
+                Err(e) => {
+                    *self.writer.lock().await = None;
+                    warn!(error = %e, "signal daemon error, reconnecting in {RECONNECT_DELAY_SECS}s");
+                    tokio::select! {
+                        _ = tokio::time::sleep(std::time::Duration::from_secs(RECONNECT_DELAY_SECS)) => {}
+                        _ = self.shutdown.notified() => {
+                            info!("signal shutdown during reconnect wait");
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Single connection to the daemon.
+    async fn daemon_session(&self) -> anyhow::Result<()> {
+        debug!(addr = %self.config.daemon_addr, "connecting to signal-cli daemon");
+        let stream = TcpStream::connect(&self.config.daemon_addr).await?;
+        info!(addr = %self.config.daemon_addr, "connected to signal-cli daemon");
+
+        let (read, write) = stream.into_split();
+        *self.writer.lock().await = Some(write);
+        let mut lines = BufReader::new(read).lines();
+
+        loop {
+            tokio::select! {
+                line = lines.next_line() => {
+                    match line? {
+                        Some(line) if line.trim().is_empty() => {}
+                        Some(line) => {
+                            if let Err(e) = self.handle_daemon_message(&line).await {
+                                warn!(error = %e, "failed to handle signal daemon message");
+                            }
+                        }
+                        None => anyhow::bail!("signal-cli daemon closed the connection"),
+                    }
+                }
+                _ = self.shutdown.notified() => {
+                    info!("signal shutdown signal received");
+                    *self.writer.lock().await = None;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Parse and handle one JSON-RPC line from the daemon.
+    async fn handle_daemon_message(&self, raw: &str) -> anyhow::Result<()> {
+        let payload: Value = serde_json::from_str(raw)?;
+        if let Some(err) = payload.get("error") {
+            // Reply to one of our requests
+            error!(id = %payload["id"], error = %err["message"].as_str().unwrap_or("unknown"), "signal-cli request failed");
+            return Ok(());
+        }
+        match payload["method"].as_str() {
+            Some("receive") => {
+                let Some(inbound) = self.parse_envelope(&payload["params"]["envelope"]) else {
+                    return Ok(());
+                };
+                debug!(
+                    sender = %inbound.sender_id,
+                    chat_id = %inbound.chat_id,
+                    content_len = inbound.content.len(),
+                    attachments = inbound.media.len(),
+                    "signal inbound message"
+                );
+                if let Err(e) = self.bus.publish_inbound(inbound).await {
+                    error!(error = %e, "failed to publish signal message to bus");
+                }
+            }
+            Some(method) => debug!(method, "signal daemon: ignoring notification"),
+            None => debug!(id = %payload["id"], "signal daemon: request succeeded"),
+        }
+        Ok(())
+    }
+
+    /// The inbound message in a `receive` envelope, if it is a chat message
+    /// the bot should answer (not a receipt, typing indicator or sync).
+    fn parse_envelope(&self, envelope: &Value) -> Option<InboundMessage> {
+        let data = envelope.get("dataMessage")?;
+        let uuid = envelope["sourceUuid"].as_str().filter(|s| !s.is_empty());
+        let sender = envelope["sourceNumber"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .or_else(|| envelope["source"].as_str().filter(|s| !s.is_empty()))
+            .or(uuid)?;
+
+        if !self.is_allowed(sender, uuid) {
+            warn!(sender = %sender, "signal message from unauthorized user, ignoring");
+            if let Some(enrollment) = self.enrollment.as_ref().filter(|_| data["groupInfo"].is_null()) {
+                let name = envelope["sourceName"].as_str().unwrap_or_default();
+                enrollment.challenge("signal", sender, sender, name);
+            }
+            return None;
+        }
+
+        let group_id = data["groupInfo"]["groupId"].as_str().filter(|g| !g.is_empty());
+        if let Some(group_id) = group_id {
+            if !self.is_group_allowed(group_id) {
+                debug!(group = %group_id, "signal message in a group not on the allow-list, ignoring");
+                return None;
+            }
+        }
+
+        let content = data["message"].as_str().unwrap_or_default();
+        let media: Vec<MediaAttachment> = data["attachments"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|a| {
+                let id = a["id"].as_str()?;
+                Some(MediaAttachment {
+                    mime_type: a["contentType"].as_str().unwrap_or("application/octet-stream").to_string(),
+                    path: self.attachments_dir.join(id).to_string_lossy().into_owned(),
+                    filename: a["filename"].as_str().map(str::to_string),
+                    size: a["size"].as_u64(),
+                })
+            })
+            .collect();
+        if content.is_empty() && media.is_empty() {
+            return None;
+        }
+
+        let chat_id = match group_id {
+            Some(group_id) => format!("{GROUP_PREFIX}{group_id}"),
+            None => sender.to_string(),
+        };
+        let mut inbound = InboundMessage::new("signal", sender, &chat_id, content);
+        inbound.media = media;
+        // Signal identifies a message by its author and timestamp
+        if let Some(ts) = envelope["timestamp"].as_i64() {
+            inbound.metadata.insert(MESSAGE_ID_KEY.into(), ts.to_string());
+            inbound.metadata.insert("timestamp".into(), ts.to_string());
+        }
+        if let Some(name) = envelope["sourceName"].as_str().filter(|n| !n.is_empty()) {
+            inbound.metadata.insert("sender_name".into(), name.to_string());
+        }
+        inbound.metadata.insert("is_group".into(), group_id.is_some().to_string());
+        Some(inbound)
+    }
+
+    /// The JSON-RPC `send` request for `msg`.
+    fn send_request(&self, msg: &OutboundMessage, id: u64) -> Value {
+        let mut params = json!({ "message": msg.content });
+        match msg.chat_id.strip_prefix(GROUP_PREFIX) {
+            Some(group_id) => params["groupId"] = json!(group_id),
+            None => params["recipient"] = json!([msg.chat_id]),
+        }
+        let attachments: Vec<&str> = msg
+            .media
+            .iter()
+            .map(|m| m.path.as_str())
+            .filter(|p| !p.starts_with("http://") && !p.starts_with("https://"))
+            .collect();
+        if !attachments.is_empty() {
+            params["attachments"] = json!(attachments);
+        }
+        if !self.config.account.is_empty() {
+            params["account"] = json!(self.config.account);
+        }
+        json!({ "jsonrpc": "2.0", "method": "send", "params": params, "id": id })
+    }
+}
+
+/// signal-cli's attachment folder (`$XDG_DATA_HOME/signal-cli/attachments`).
+fn default_attachments_dir() -> PathBuf {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| oxibot_core::utils::expand_home("~/.local/share"))
+        .join("signal-cli")
+        .join("attachments")
+}
+
+#[async_trait]
+impl Channel for SignalChannel {
+    fn name(&self) -> &str {
+        "signal"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        info!(addr = %self.config.daemon_addr, "starting signal channel");
+        self.run_daemon_loop().await
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        info!("stopping signal channel");
+        self.shutdown.notify_waiters();
+        *self.writer.lock().await = None;
+        Ok(())
+    }
+
+    async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut frame = self.send_request(msg, id).to_string();
+        frame.push('\n');
+
+        let mut guard = self.writer.lock().await;
+        let Some(writer) = guard.as_mut() else {
+            anyhow::bail!("signal-cli daemon not connected");
+        };
+        writer.write_all(frame.as_bytes()).await?;
+        debug!(chat_id = %msg.chat_id, id, "signal message sent");
+        Ok(())
+    }
+
+    fn supports_uploads(&self) -> bool {
+        true
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn channel(config: SignalConfig) -> (SignalChannel, Arc<MessageBus>) {
+        let bus = Arc::new(MessageBus::new(32));
+        let config = SignalConfig {
+            attachments_dir: "/tmp/signal-attachments".into(),
+            ..config
+        };
+        (SignalChannel::new(config, bus.clone()), bus)
+    }
+
+    fn envelope(group: Option<&str>) -> Value {
+        let mut data = json!({ "timestamp": 1700000000000i64, "message": "hi bot" });
+        if let Some(group) = group {
+            data["groupInfo"] = json!({ "groupId": group, "type": "DELIVER" });
+        }
+        json!({
+            "source": "+15550001111",
+            "sourceNumber": "+15550001111",
+            "sourceUuid": "9d0c-uuid",
+            "sourceName": "Bob",
+            "timestamp": 1700000000000i64,
+            "dataMessage": data
+        })
+    }
+
+    #[test]
+    fn test_defaults() {
+        let ch = SignalChannel::new(SignalConfig::default(), Arc::new(MessageBus::new(1)));
+        assert_eq!(ch.name(), "signal");
+        assert_eq!(ch.config.daemon_addr, "127.0.0.1:7583");
+        assert!(ch.attachments_dir.ends_with("signal-cli/attachments"));
+    }
+
+    #[test]
+    fn test_parse_direct_and_group_messages() {
+        let (ch, _) = channel(SignalConfig::default());
+        let msg = ch.parse_envelope(&envelope(None)).unwrap();
+        assert_eq!((msg.sender_id.as_str(), msg.chat_id.as_str()), ("+15550001111", "+15550001111"));
+        assert_eq!(msg.content, "hi bot");
+        assert_eq!(msg.message_id(), Some("1700000000000"));
+        assert_eq!(msg.metadata["sender_name"], "Bob");
+        assert_eq!(msg.metadata["is_group"], "false");
+
+        let msg = ch.parse_envelope(&envelope(Some("Z3JvdXA="))).unwrap();
+        assert_eq!(msg.chat_id, "group:Z3JvdXA=");
+        assert_eq!(msg.metadata["is_group"], "true");
+
+        // Receipts and typing indicators carry no dataMessage
+        assert!(ch.parse_envelope(&json!({ "source": "+1", "receiptMessage": {} })).is_none());
+    }
+
+    #[test]
+    fn test_attachments() {
+        let (ch, _) = channel(SignalConfig::default());
+        let mut env = envelope(None);
+        env["dataMessage"]["message"] = Value::Null;
+        env["dataMessage"]["attachments"] = json!([
+            { "contentType": "image/jpeg", "filename": "cat.jpg", "id": "A1b2.jpg", "size": 2048 }
+        ]);
+        let msg = ch.parse_envelope(&env).unwrap();
+        assert_eq!(msg.content, "");
+        assert_eq!(msg.media[0].path, "/tmp/signal-attachments/A1b2.jpg");
+        assert_eq!(msg.media[0].mime_type, "image/jpeg");
+        assert_eq!(msg.media[0].size, Some(2048));
+    }
+
+    #[test]
+    fn test_allow_lists() {
+        let (ch, _) = channel(SignalConfig {
+            allowed_users: vec!["9d0c-uuid".into()],
+            allowed_groups: vec!["b2s=".into()],
+            ..Default::default()
+        });
+        // Allowed by UUID
+        assert!(ch.parse_envelope(&envelope(None)).is_some());
+        assert!(ch.parse_envelope(&envelope(Some("b2s="))).is_some());
+        assert!(ch.parse_envelope(&envelope(Some("other="))).is_none());
+
+        let mut stranger = envelope(None);
+        stranger["sourceNumber"] = json!("+19999");
+        stranger["sourceUuid"] = json!("other-uuid");
+        assert!(ch.parse_envelope(&stranger).is_none());
+    }
+
+    #[test]
+    fn test_send_request() {
+        let (ch, _) = channel(SignalConfig {
+            account: "+15559990000".into(),
+            ..Default::default()
+        });
+        let mut msg = OutboundMessage::new("signal", "group:Z3JvdXA=", "hello");
+        msg.media.push(MediaAttachment {
+            mime_type: "image/png".into(),
+            path: "/tmp/chart.png".into(),
+            filename: None,
+            size: None,
+        });
+        let request = ch.send_request(&msg, 7);
+        assert_eq!(request["method"], "send");
+        assert_eq!(request["id"], 7);
+        assert_eq!(request["params"]["groupId"], "Z3JvdXA=");
+        assert_eq!(request["params"]["attachments"], json!(["/tmp/chart.png"]));
+        assert_eq!(request["params"]["account"], "+15559990000");
+
+        let request = ch.send_request(&OutboundMessage::new("signal", "+15550001111", "hi"), 8);
+        assert_eq!(request["params"]["recipient"], json!(["+15550001111"]));
+        assert!(request["params"].get("groupId").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_daemon_roundtrip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (ch, bus) = channel(SignalConfig {
+            daemon_addr: listener.local_addr().unwrap().to_string(),
+            ..Default::default()
+        });
+        let ch = Arc::new(ch);
+        assert!(ch.send(&OutboundMessage::new("signal", "+1", "x")).await.is_err());
+
+        let running = tokio::spawn({
+            let ch = ch.clone();
+            async move { ch.start().await }
+        });
+        let (daemon, _) = listener.accept().await.unwrap();
+        let (read, mut write) = daemon.into_split();
+
+        // Inbound: a receive notification reaches the bus
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "receive",
+            "params": { "envelope": envelope(None), "account": "+15559990000" }
+        });
+        write.write_all(format!("{notification}\n").as_bytes()).await.unwrap();
+        let inbound = bus.consume_inbound().await.unwrap();
+        assert_eq!(inbound.content, "hi bot");
+
+        // Outbound: one JSON-RPC request per line
+        ch.send(&OutboundMessage::new("signal", "+15550001111", "hello back"))
+            .await
+            .unwrap();
+        let line = BufReader::new(read).lines().next_line().await.unwrap().unwrap();
+        let request: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(request["params"]["message"], "hello back");
+
+        ch.stop().await.unwrap();
+        running.await.unwrap().unwrap();
+    }
+}
@@ -10,10 +10,14 @@
 //! - Two-tiered access: DM policy + channel/group policy
 //! - De-duplication of `message` vs `app_mention` events
 //! - Thread support (DMs skip thread_ts, channels use it)
-//! - `:eyes:` reaction as acknowledgment indicator
+//! - Optional per-thread sessions (`threadSessions`)
+//! - `:eyes:` reaction (or a "…" reply) when the agent picks up a message
 //! - Bot-mention stripping
+//! - Follow-up window: keep talking in a thread without re-mentioning the bot
 //! - Message chunking for >4000 char responses
 //! - Auto-reconnect with backoff
+//! - Progress messages edited in place via `chat.update`
+//! - Approval requests with Allow / Deny buttons (needs Interactivity enabled)
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -24,11 +28,15 @@ use serde_json::{json, Value};
 use tokio::sync::{Mutex, Notify, RwLock};
 use tracing::{debug, error, info, warn};
 
+use oxibot_core::bus::approval::{self, Decision};
 use oxibot_core::bus::queue::MessageBus;
-use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
+use oxibot_core::bus::types::{InboundMessage, OutboundMessage, THREAD_ID_KEY};
 use oxibot_core::config::schema::SlackConfig;
 
-use crate::base::Channel;
+use crate::base::{
+    answered_prompt, AckMode, ActivityTracker, Channel, FollowUpWindows, HealthReport, ProgressMessages,
+    ACK_REPLY_TEXT, APPROVE_BUTTON_TEXT, DENY_BUTTON_TEXT,
+};
 
 // ─────────────────────────────────────────────
 // Constants
@@ -40,12 +48,19 @@ const SLACK_API_BASE: &str = "https://slack.com/api";
 /// Slack message length limit for `chat.postMessage`.
 const SLACK_MAX_LEN: usize = 4000;
 
+/// Reaction name used to acknowledge messages by default.
+const DEFAULT_ACK_EMOJI: &str = "eyes";
+
 /// Reconnect backoff (seconds).
 const RECONNECT_DELAY_SECS: u64 = 5;
 
 /// Maximum reconnect attempts before giving up.
 const MAX_RECONNECT_ATTEMPTS: u32 = 10;
 
+/// How often the Socket Mode connection is pinged, so a quiet workspace
+/// still shows activity.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
 // ─────────────────────────────────────────────
 // Socket Mode types
 // ─────────────────────────────────────────────
@@ -87,6 +102,14 @@ pub struct SlackChannel {
     bot_user_id: Arc<RwLock<Option<String>>>,
     /// Active WebSocket write half (for sending ACKs).
     ws_write: Arc<Mutex<Option<WsSender>>>,
+    /// Sent progress messages (progress id → message `ts`).
+    progress_messages: Arc<ProgressMessages<String>>,
+    /// Follow-up windows per `channel:thread_ts`.
+    follow_ups: FollowUpWindows,
+    /// How messages are acknowledged when the agent picks them up.
+    ack: AckMode,
+    /// Last frame from the Socket Mode connection, for health reports.
+    activity: ActivityTracker,
 }
 
 /// Type alias for the WebSocket sink.
@@ -98,6 +121,8 @@ type WsSender = futures_util::stream::SplitSink<
 impl SlackChannel {
     /// Create a new Slack channel from config.
     pub fn new(config: SlackConfig, bus: Arc<MessageBus>) -> Self {
+        let follow_ups = FollowUpWindows::new(Duration::from_secs(config.follow_up_secs));
+        let ack = AckMode::from_config(&config.acknowledge, DEFAULT_ACK_EMOJI);
         Self {
             config,
             bus,
@@ -105,6 +130,10 @@ impl SlackChannel {
             http: reqwest::Client::new(),
             bot_user_id: Arc::new(RwLock::new(None)),
             ws_write: Arc::new(Mutex::new(None)),
+            progress_messages: Arc::new(ProgressMessages::new()),
+            follow_ups,
+            ack,
+            activity: ActivityTracker::new(),
         }
     }
 
@@ -248,12 +277,14 @@ impl SlackChannel {
     }
 
     /// Send a chat message via `chat.postMessage`.
+    ///
+    /// Returns the `ts` of the posted message.
     async fn post_message(
         &self,
         channel: &str,
         text: &str,
         thread_ts: Option<&str>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<String> {
         let mut body = json!({
             "channel": channel,
             "text": text,
@@ -263,20 +294,74 @@ impl SlackChannel {
             body["thread_ts"] = json!(ts);
         }
 
+        let resp_body = self.call_api("chat.postMessage", &body).await?;
+        Ok(resp_body["ts"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Replace the text of a posted message via `chat.update`.
+    async fn update_message(&self, channel: &str, ts: &str, text: &str) -> anyhow::Result<()> {
+        self.call_api("chat.update", &json!({ "channel": channel, "ts": ts, "text": text }))
+            .await
+            .map(|_| ())
+    }
+
+    /// Post an approval request with Allow / Deny buttons.
+    async fn post_approval(&self, channel: &str, id: &str, prompt: &str, thread_ts: Option<&str>) -> anyhow::Result<()> {
+        let text: String = prompt.chars().take(SLACK_MAX_LEN).collect();
+        let mut body = json!({
+            "channel": channel,
+            "text": text,
+            "blocks": approval_blocks(id, &text),
+        });
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = json!(ts);
+        }
+        self.call_api("chat.postMessage", &body).await.map(|_| ())
+    }
+
+    /// Call a Web API method, failing unless Slack answers `ok`.
+    async fn call_api(&self, method: &str, body: &Value) -> anyhow::Result<Value> {
         let resp = self
             .http
-            .post(format!("{}/chat.postMessage", SLACK_API_BASE))
+            .post(format!("{}/{}", SLACK_API_BASE, method))
             .bearer_auth(&self.config.bot_token)
-            .json(&body)
+            .json(body)
             .send()
             .await?;
 
         let resp_body: Value = resp.json().await?;
         if resp_body["ok"].as_bool() != Some(true) {
             let err = resp_body["error"].as_str().unwrap_or("unknown");
-            anyhow::bail!("chat.postMessage failed: {}", err);
+            anyhow::bail!("{} failed: {}", method, err);
         }
+        Ok(resp_body)
+    }
 
+    /// Send or edit the progress message for `progress_id`.
+    async fn send_progress(
+        &self,
+        msg: &OutboundMessage,
+        progress_id: &str,
+        thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let text: String = msg.content.chars().take(SLACK_MAX_LEN).collect();
+        match self.progress_messages.get(progress_id) {
+            Some(ts) => {
+                if let Err(e) = self.update_message(&msg.chat_id, &ts, &text).await {
+                    debug!(error = %e, "slack progress update failed");
+                }
+            }
+            None => {
+                let ts = self.post_message(&msg.chat_id, &text, thread_ts).await?;
+                if !ts.is_empty() {
+                    self.progress_messages.insert(progress_id, ts);
+                }
+            }
+        }
+
+        if msg.is_progress_done() {
+            self.progress_messages.finish(progress_id);
+        }
         Ok(())
     }
 
@@ -313,7 +398,11 @@ impl SlackChannel {
 
     /// Process a Socket Mode envelope.
     async fn process_envelope(&self, envelope: SocketEnvelope) {
-        // Only handle events_api envelopes
+        if envelope.envelope_type == "interactive" {
+            return self.handle_interactive(&envelope.payload).await;
+        }
+
+        // Otherwise only handle events_api envelopes
         if envelope.envelope_type != "events_api" {
             debug!(
                 envelope_type = %envelope.envelope_type,
@@ -341,11 +430,13 @@ impl SlackChannel {
         let chat_id = event["channel"].as_str().unwrap_or("").to_string();
         let text = event["text"].as_str().unwrap_or("").to_string();
         let ts = event["ts"].as_str().unwrap_or("").to_string();
-        let thread_ts = event
+        // Set only for replies inside a thread, not for top-level messages
+        let reply_thread = event
             .get("thread_ts")
             .and_then(|v| v.as_str())
-            .unwrap_or(&ts)
-            .to_string();
+            .filter(|t| *t != ts)
+            .map(str::to_string);
+        let thread_ts = reply_thread.clone().unwrap_or_else(|| ts.clone());
         let channel_type = event["channel_type"]
             .as_str()
             .unwrap_or("channel")
@@ -380,12 +471,25 @@ impl SlackChannel {
             return;
         }
 
-        // Channel/group response policy (DMs always respond if allowed)
-        if channel_type != "im"
-            && !self.should_respond_in_channel(event_type, &text, &chat_id, &bot_id)
-        {
-            debug!("not responding in channel per group_policy");
-            return;
+        // Channel/group response policy (DMs always respond if allowed).
+        // A user inside an open follow-up window needs no mention.
+        // Observe-only channels record everything and answer mentions only.
+        let scope = format!("{chat_id}:{thread_ts}");
+        let mut observed = false;
+        if channel_type != "im" {
+            let respond = if self.config.observe_only {
+                event_type == "app_mention" || text.contains(&format!("<@{bot_id}>"))
+            } else {
+                self.should_respond_in_channel(event_type, &text, &chat_id, &bot_id)
+            };
+            if respond || self.follow_ups.is_open(&scope, &sender_id) {
+                self.follow_ups.open(&scope, &sender_id);
+            } else if self.config.observe_only {
+                observed = true;
+            } else {
+                debug!("not responding in channel per group_policy");
+                return;
+            }
         }
 
         // Strip bot mention from text
@@ -400,17 +504,20 @@ impl SlackChannel {
             return;
         }
 
-        // Add :eyes: reaction as acknowledgment
-        self.add_reaction(&chat_id, &ts, "eyes").await;
-
         // Build metadata
         let mut metadata = std::collections::HashMap::new();
         metadata.insert("channel_type".to_string(), channel_type.clone());
         metadata.insert("thread_ts".to_string(), thread_ts.clone());
+        metadata.insert("message_id".to_string(), ts.clone());
         metadata.insert("ts".to_string(), ts);
+        if self.config.thread_sessions {
+            if let Some(thread) = reply_thread {
+                metadata.insert(THREAD_ID_KEY.to_string(), thread);
+            }
+        }
 
         // Publish inbound message
-        let inbound = InboundMessage {
+        let mut inbound = InboundMessage {
             sender_id: sender_id.clone(),
             chat_id: chat_id.clone(),
             channel: "slack".to_string(),
@@ -419,12 +526,48 @@ impl SlackChannel {
             media: Vec::new(),
             metadata,
         };
+        if observed {
+            inbound = inbound.observed();
+        }
 
         if let Err(e) = self.bus.publish_inbound(inbound).await {
             error!(error = %e, "failed to publish inbound message");
         }
     }
 
+    /// Handle an interactive payload: Allow / Deny button presses.
+    async fn handle_interactive(&self, payload: &Value) {
+        let Some((sender_id, chat_id, id, decision)) = parse_approval_click(payload) else {
+            debug!("ignoring interactive payload");
+            return;
+        };
+        // DM channel ids start with "D"
+        let channel_type = if chat_id.starts_with('D') { "im" } else { "channel" };
+        if !self.is_allowed(sender_id, chat_id, channel_type) {
+            warn!(sender = %sender_id, chat = %chat_id, "button press denied by policy");
+            return;
+        }
+
+        let inbound = InboundMessage::approval_response("slack", sender_id, chat_id, id, decision);
+        if let Err(e) = self.bus.publish_inbound(inbound).await {
+            error!(error = %e, "failed to publish approval answer");
+        }
+
+        // Replace the buttons with the answer
+        let message = &payload["message"];
+        let Some(ts) = message["ts"].as_str() else { return };
+        let text = answered_prompt(message["text"].as_str().unwrap_or_default(), decision);
+        let body = json!({
+            "channel": chat_id,
+            "ts": ts,
+            "text": text,
+            "blocks": [{ "type": "section", "text": { "type": "mrkdwn", "text": text } }],
+        });
+        if let Err(e) = self.call_api("chat.update", &body).await {
+            debug!(error = %e, "slack approval update failed");
+        }
+    }
+
     // ─────────────────────────────────────────
     // WebSocket loop
     // ─────────────────────────────────────────
@@ -495,19 +638,23 @@ impl SlackChannel {
             }
 
             // Read loop
+            let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
             loop {
                 tokio::select! {
                     msg = read.next() => {
                         match msg {
                             Some(Ok(WsMessage::Text(text))) => {
+                                self.activity.touch();
                                 self.handle_ws_message(&text).await;
                             }
                             Some(Ok(WsMessage::Ping(data))) => {
+                                self.activity.touch();
                                 let mut guard = self.ws_write.lock().await;
                                 if let Some(ref mut w) = *guard {
                                     let _ = w.send(WsMessage::Pong(data)).await;
                                 }
                             }
+                            Some(Ok(WsMessage::Pong(_))) => self.activity.touch(),
                             Some(Ok(WsMessage::Close(_))) => {
                                 info!("Slack WebSocket closed by server");
                                 break;
@@ -523,6 +670,12 @@ impl SlackChannel {
                             _ => {} // Binary, etc.
                         }
                     }
+                    _ = keepalive.tick() => {
+                        let mut guard = self.ws_write.lock().await;
+                        if let Some(ref mut w) = *guard {
+                            let _ = w.send(WsMessage::Ping(Default::default())).await;
+                        }
+                    }
                     _ = self.shutdown.notified() => {
                         info!("shutdown signal received");
                         let mut guard = self.ws_write.lock().await;
@@ -590,6 +743,43 @@ impl SlackChannel {
     }
 }
 
+// ─────────────────────────────────────────────
+// Approval buttons
+// ─────────────────────────────────────────────
+
+/// Block Kit layout of an approval request: the question, then Allow / Deny.
+fn approval_blocks(id: &str, prompt: &str) -> Value {
+    let button = |label: &str, style: &str, decision: Decision| {
+        json!({
+            "type": "button",
+            "text": { "type": "plain_text", "text": label, "emoji": true },
+            "style": style,
+            "action_id": format!("approval_{}", decision.as_str()),
+            "value": approval::button_data(id, decision),
+        })
+    };
+    json!([
+        { "type": "section", "text": { "type": "mrkdwn", "text": prompt } },
+        {
+            "type": "actions",
+            "elements": [
+                button(APPROVE_BUTTON_TEXT, "primary", Decision::Approved),
+                button(DENY_BUTTON_TEXT, "danger", Decision::Denied),
+            ],
+        },
+    ])
+}
+
+/// Sender, channel, approval id and decision of an approval button press.
+fn parse_approval_click(payload: &Value) -> Option<(&str, &str, &str, Decision)> {
+    if payload["type"].as_str() != Some("block_actions") {
+        return None;
+    }
+    let value = payload["actions"].as_array()?.first()?["value"].as_str()?;
+    let (id, decision) = approval::parse_button_data(value)?;
+    Some((payload["user"]["id"].as_str()?, payload["channel"]["id"].as_str()?, id, decision))
+}
+
 // ─────────────────────────────────────────────
 // Channel trait implementation
 // ─────────────────────────────────────────────
@@ -651,6 +841,10 @@ impl Channel for SlackChannel {
         Ok(())
     }
 
+    fn health(&self) -> Option<HealthReport> {
+        Some(self.activity.report())
+    }
+
     async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
         let channel_type = msg
             .metadata
@@ -658,13 +852,27 @@ impl Channel for SlackChannel {
             .map(|s| s.as_str())
             .unwrap_or("channel");
 
-        // Thread support: use thread_ts for channels, skip for DMs
-        let thread_ts = if channel_type != "im" {
+        // Thread support: replies to a thread session go to that thread;
+        // otherwise use thread_ts for channels, skip for DMs
+        let thread_ts = if let Some(thread) = msg.metadata.get(THREAD_ID_KEY) {
+            Some(thread.as_str())
+        } else if channel_type != "im" {
             msg.metadata.get("thread_ts").map(|s| s.as_str())
         } else {
             None
         };
 
+        if let Some(progress_id) = msg.progress_id() {
+            return self.send_progress(msg, progress_id, thread_ts).await;
+        }
+        if let Some((id, prompt)) = msg.approval() {
+            return self.post_approval(&msg.chat_id, id, prompt, thread_ts).await;
+        }
+        // The bot answered: restart follow-up windows in this thread
+        if let Some(ts) = thread_ts {
+            self.follow_ups.extend(&format!("{}:{}", msg.chat_id, ts));
+        }
+
         // Split long messages
         let chunks = Self::split_message(&msg.content);
 
@@ -677,6 +885,36 @@ impl Channel for SlackChannel {
 
         Ok(())
     }
+
+    async fn acknowledge(&self, ack: &OutboundMessage) -> anyhow::Result<()> {
+        let Some(ts) = ack.message_id() else {
+            return Ok(());
+        };
+        match self.ack {
+            AckMode::Reaction(ref emoji) => {
+                self.add_reaction(&ack.chat_id, ts, emoji).await;
+                Ok(())
+            }
+            AckMode::Reply => {
+                let mut reply = ack.clone();
+                reply.content = ACK_REPLY_TEXT.to_string();
+                self.send(&reply).await
+            }
+            AckMode::Off => Ok(()),
+        }
+    }
+
+    fn supports_edits(&self) -> bool {
+        true
+    }
+
+    fn max_message_len(&self) -> Option<usize> {
+        Some(SLACK_MAX_LEN)
+    }
+
+    async fn check(&self) -> Option<anyhow::Result<()>> {
+        Some(self.resolve_bot_id().await.map(|_| ()))
+    }
 }
 
 // ─────────────────────────────────────────────
@@ -699,6 +937,10 @@ mod tests {
                 policy: "open".into(),
                 allow_from: Vec::new(),
             },
+            follow_up_secs: 0,
+            acknowledge: Default::default(),
+            thread_sessions: false,
+            observe_only: false,
         }
     }
 
@@ -994,6 +1236,133 @@ mod tests {
         ch.process_envelope(envelope).await;
     }
 
+    #[tokio::test]
+    async fn test_follow_up_window_without_mention() {
+        let mut cfg = make_config();
+        cfg.follow_up_secs = 120;
+        let bus = make_bus();
+        let ch = SlackChannel::new(cfg, bus.clone());
+        {
+            let mut guard = ch.bot_user_id.write().await;
+            *guard = Some("BBOT".into());
+        }
+        let event = |event_type: &str, user: &str, text: &str, ts: &str| SocketEnvelope {
+            envelope_id: "eid".into(),
+            envelope_type: "events_api".into(),
+            payload: json!({
+                "event": {
+                    "type": event_type,
+                    "user": user,
+                    "channel": "C456",
+                    "channel_type": "channel",
+                    "text": text,
+                    "ts": ts,
+                    "thread_ts": "100.000001"
+                }
+            }),
+        };
+        let next = || async {
+            tokio::time::timeout(Duration::from_millis(200), bus.consume_inbound())
+                .await
+                .ok()
+                .flatten()
+        };
+
+        // No mention yet → ignored
+        ch.process_envelope(event("message", "U1", "hello?", "100.000002")).await;
+        assert!(next().await.is_none());
+
+        // Mention opens the window
+        ch.process_envelope(event("app_mention", "U1", "<@BBOT> help", "100.000003")).await;
+        assert_eq!(next().await.unwrap().content, "help");
+
+        // Same user, same thread, no mention → answered
+        ch.process_envelope(event("message", "U1", "and also", "100.000004")).await;
+        assert_eq!(next().await.unwrap().content, "and also");
+
+        // Other users still need a mention
+        ch.process_envelope(event("message", "U2", "me too", "100.000005")).await;
+        assert!(next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_observe_only() {
+        let mut cfg = make_config();
+        cfg.group_policy = "open".into();
+        cfg.observe_only = true;
+        let bus = make_bus();
+        let ch = SlackChannel::new(cfg, bus.clone());
+        *ch.bot_user_id.write().await = Some("BBOT".into());
+        let event = |event_type: &str, channel_type: &str, text: &str, ts: &str| SocketEnvelope {
+            envelope_id: "eid".into(),
+            envelope_type: "events_api".into(),
+            payload: json!({
+                "event": {
+                    "type": event_type,
+                    "user": "U1",
+                    "channel": "C456",
+                    "channel_type": channel_type,
+                    "text": text,
+                    "ts": ts
+                }
+            }),
+        };
+
+        // Channel chatter is recorded, not answered, even with an open policy
+        ch.process_envelope(event("message", "channel", "we ship friday", "100.000001")).await;
+        let msg = bus.consume_inbound().await.unwrap();
+        assert_eq!(msg.content, "we ship friday");
+        assert!(msg.is_observed());
+
+        // A mention is answered
+        ch.process_envelope(event("app_mention", "channel", "<@BBOT> summary?", "100.000002")).await;
+        assert!(!bus.consume_inbound().await.unwrap().is_observed());
+
+        // So are direct messages
+        ch.process_envelope(event("message", "im", "what was decided?", "100.000003")).await;
+        assert!(!bus.consume_inbound().await.unwrap().is_observed());
+    }
+
+    #[tokio::test]
+    async fn test_thread_sessions() {
+        let mut cfg = make_config();
+        cfg.group_policy = "open".into();
+        cfg.thread_sessions = true;
+        let bus = make_bus();
+        let ch = SlackChannel::new(cfg, bus.clone());
+        let event = |ts: &str, thread_ts: Option<&str>| {
+            let mut event = json!({
+                "type": "message",
+                "user": "U1",
+                "channel": "C456",
+                "channel_type": "channel",
+                "text": "hi",
+                "ts": ts
+            });
+            if let Some(t) = thread_ts {
+                event["thread_ts"] = json!(t);
+            }
+            SocketEnvelope {
+                envelope_id: "eid".into(),
+                envelope_type: "events_api".into(),
+                payload: json!({ "event": event }),
+            }
+        };
+
+        // Top-level messages (including thread parents) keep the channel session
+        ch.process_envelope(event("100.000001", None)).await;
+        assert_eq!(bus.consume_inbound().await.unwrap().session_key(), "slack:C456");
+        ch.process_envelope(event("100.000003", Some("100.000003"))).await;
+        assert_eq!(bus.consume_inbound().await.unwrap().session_key(), "slack:C456");
+
+        // Thread replies get the thread's session
+        ch.process_envelope(event("100.000002", Some("100.000001"))).await;
+        assert_eq!(
+            bus.consume_inbound().await.unwrap().session_key(),
+            "slack:C456:100.000001"
+        );
+    }
+
     #[tokio::test]
     async fn test_process_envelope_dm_disabled() {
         let mut cfg = make_config();
@@ -1042,6 +1411,26 @@ mod tests {
 
     // ── Handle WS message ──
 
+    #[test]
+    fn test_approval_buttons() {
+        let blocks = approval_blocks("ab12", "Allow exec of `make clean`?");
+        assert_eq!(blocks[0]["text"]["text"], "Allow exec of `make clean`?");
+        let deny = &blocks[1]["elements"][1];
+        assert_eq!(deny["text"]["text"], DENY_BUTTON_TEXT);
+
+        let payload = json!({
+            "type": "block_actions",
+            "user": { "id": "U1" },
+            "channel": { "id": "D1" },
+            "message": { "ts": "1700.01", "text": "Allow exec of `make clean`?" },
+            "actions": [{ "action_id": deny["action_id"], "value": deny["value"] }],
+        });
+        assert_eq!(parse_approval_click(&payload), Some(("U1", "D1", "ab12", Decision::Denied)));
+
+        let other = json!({ "type": "view_submission", "user": { "id": "U1" } });
+        assert_eq!(parse_approval_click(&other), None);
+    }
+
     #[tokio::test]
     async fn test_handle_ws_hello() {
         let ch = SlackChannel::new(make_config(), make_bus());
@@ -4,45 +4,56 @@
 //!
 //! Features:
 //! - Long polling (no webhook/public IP needed)
-//! - Text, photo, voice, document handling
+//! - Text, photo, video, voice, document handling
+//! - Albums (media groups) merged into one message with every attachment
 //! - Typing indicator while agent processes
 //! - Markdown → Telegram HTML conversion
 //! - Allow-list by user ID or username
-//! - Commands: /start, /reset, /help
+//! - Command menu: /start, /help, /reset, /status, /model, /persona
+//! - Deep links (`t.me/<bot>?start=<token>`) that bind a chat to an agent profile
 //! - Message splitting for >4096 char responses
+//! - Progress messages edited in place (e.g. streaming `exec` output)
+//! - 👀 reaction (or a "…" reply) when the agent picks up a message
 
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::future::Future;
-use std::pin::Pin;
-use std::path::Path;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use teloxide::net::Download;
 use teloxide::prelude::*;
 use teloxide::types::{
-    ChatAction, MediaKind, MessageKind, ParseMode, UpdateKind,
+    CallbackQuery, ChatAction, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, MaybeInaccessibleMessage,
+    MediaKind, MessageId, MessageKind, ParseMode, ReactionType, UpdateKind,
 };
 use tokio::io::AsyncWriteExt;
-use tokio::sync::Notify;
+use tokio::sync::{Mutex, Notify};
 use tracing::{debug, error, info, warn};
 
+use oxibot_core::bus::approval::{self, Decision};
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
+use oxibot_core::config::schema::AckConfig;
+use oxibot_core::types::Transcript;
 
-use crate::base::Channel;
+use crate::base::{
+    answered_prompt, AckMode, ActivityTracker, Channel, HealthReport, ProgressMessages, ACK_REPLY_TEXT,
+    APPROVE_BUTTON_TEXT, DENY_BUTTON_TEXT,
+};
+use crate::enrollment::Enrollment;
 use crate::formatting::{markdown_to_telegram_html, split_message};
 
 /// Telegram message length limit.
 const TELEGRAM_MAX_LEN: usize = 4096;
 
-/// Callback for voice/audio transcription.
-///
-/// Receives a file path, returns the transcribed text.
-pub type TranscribeFn = Arc<
-    dyn Fn(String) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send>>
-        + Send
-        + Sync,
->;
+/// Reaction used to acknowledge messages unless configured otherwise.
+const DEFAULT_ACK_EMOJI: &str = "👀";
+
+/// Default quiet period after the last album part before the album is sent.
+pub const DEFAULT_MEDIA_GROUP_WINDOW: Duration = Duration::from_millis(1500);
+
+pub use crate::base::TranscribeFn;
 
 // ─────────────────────────────────────────────
 // TelegramChannel
@@ -60,6 +71,26 @@ pub struct TelegramChannel {
     transcriber: Option<TranscribeFn>,
     /// Shutdown signal.
     shutdown: Arc<Notify>,
+    /// Sent progress messages keyed by progress id.
+    progress_messages: Arc<ProgressMessages<MessageId>>,
+    /// Deep-link start tokens → agent profile name.
+    deep_links: HashMap<String, String>,
+    /// Chats linked to a profile through a deep link.
+    linked_chats: Option<LinkedChats>,
+    /// Albums still receiving parts, keyed by media group id.
+    media_groups: PendingAlbums,
+    /// Quiet period that ends an album.
+    media_group_window: Duration,
+    /// How messages are acknowledged when the agent picks them up.
+    ack: AckMode,
+    /// Admin-approved access for unknown senders.
+    enrollment: Option<Arc<Enrollment>>,
+    /// Only record group messages unless the bot is addressed.
+    observe_only: bool,
+    /// The bot's username, fetched on start in observe-only mode.
+    bot_username: std::sync::OnceLock<String>,
+    /// Last successful `getUpdates`, for health reports.
+    activity: ActivityTracker,
 }
 
 impl TelegramChannel {
@@ -75,38 +106,110 @@ impl TelegramChannel {
             allowed_users,
             transcriber: None,
             shutdown: Arc::new(Notify::new()),
+            progress_messages: Arc::new(ProgressMessages::new()),
+            deep_links: HashMap::new(),
+            linked_chats: None,
+            media_groups: Arc::new(Mutex::new(HashMap::new())),
+            media_group_window: DEFAULT_MEDIA_GROUP_WINDOW,
+            ack: AckMode::from_config(&AckConfig::default(), DEFAULT_ACK_EMOJI),
+            enrollment: None,
+            observe_only: false,
+            bot_username: std::sync::OnceLock::new(),
+            activity: ActivityTracker::new(),
         }
     }
 
+    /// Set how messages are acknowledged (default: 👀 reaction).
+    ///
+    /// Bots may only use Telegram's standard reaction emoji.
+    pub fn with_acknowledgment(mut self, config: &AckConfig) -> Self {
+        self.ack = AckMode::from_config(config, DEFAULT_ACK_EMOJI);
+        self
+    }
+
+    /// Let unknown senders in direct chats ask the admin for access.
+    pub fn with_enrollment(mut self, enrollment: Option<Arc<Enrollment>>) -> Self {
+        self.enrollment = enrollment;
+        self
+    }
+
+    /// Only record group messages in the session, answering just the ones
+    /// that address the bot: commands, `@mentions` and replies to it.
+    pub fn with_observe_only(mut self, observe_only: bool) -> Self {
+        self.observe_only = observe_only;
+        self
+    }
+
+    /// Whether a group message addresses the bot.
+    fn addresses_bot(&self, message: &Message) -> bool {
+        let text = message.text().or(message.caption()).unwrap_or("");
+        if text.starts_with('/') {
+            return true;
+        }
+        let Some(username) = self.bot_username.get() else {
+            return false;
+        };
+        let mention = format!("@{}", username.to_lowercase());
+        text.to_lowercase().contains(&mention)
+            || message
+                .reply_to_message()
+                .and_then(|reply| reply.from.as_ref()?.username.as_deref())
+                .is_some_and(|from| from.eq_ignore_ascii_case(username))
+    }
+
+    /// Set how long to wait for more parts of an album (default 1.5 s).
+    ///
+    /// Telegram delivers each photo of an album as its own update; parts
+    /// sharing a media group id are merged until none arrives for `window`.
+    pub fn with_media_group_window(mut self, window: Duration) -> Self {
+        self.media_group_window = window;
+        self
+    }
+
+    /// Enable deep links: `/start <token>` binds the chat to `links[token]`.
+    ///
+    /// Linked chats are stored in `store` (default
+    /// `~/.oxibot/telegram_links.json`) and tagged with `agent_profile`
+    /// metadata so the gateway routes them to that profile.
+    pub fn with_deep_links(mut self, links: HashMap<String, String>, store: Option<PathBuf>) -> Self {
+        self.deep_links = links;
+        self.linked_chats = Some(LinkedChats::load(
+            store.unwrap_or_else(|| oxibot_core::utils::get_data_path().join("telegram_links.json")),
+        ));
+        self
+    }
+
+    /// Link `chat_id` to the profile for a deep-link `token`.
+    ///
+    /// Returns the profile name, or `None` if the token is unknown.
+    fn link_chat(&self, chat_id: &str, token: &str) -> Option<&str> {
+        let profile = self.deep_links.get(token)?;
+        let linked = self.linked_chats.as_ref()?;
+        linked.link(chat_id, profile);
+        info!(chat = %chat_id, profile = %profile, "telegram chat linked via deep link");
+        Some(profile)
+    }
+
+    /// Profile a chat was linked to, if any.
+    fn linked_profile(&self, chat_id: &str) -> Option<String> {
+        self.linked_chats.as_ref()?.get(chat_id)
+    }
+
     /// Set the voice transcription callback.
     pub fn with_transcriber(mut self, transcriber: TranscribeFn) -> Self {
         self.transcriber = Some(transcriber);
         self
     }
 
-    /// Try to transcribe an audio file. Returns transcribed text or None.
-    async fn try_transcribe(&self, path: &str) -> Option<String> {
-        if let Some(ref transcriber) = self.transcriber {
-            match transcriber(path.to_string()).await {
-                Ok(text) if !text.is_empty() => {
-                    debug!(path = %path, chars = text.len(), "voice transcribed");
-                    Some(text)
-                }
-                Ok(_) => None,
-                Err(e) => {
-                    warn!(error = %e, "voice transcription failed");
-                    None
-                }
-            }
-        } else {
-            None
-        }
+    /// Try to transcribe an audio file. Returns the transcript or None.
+    async fn try_transcribe(&self, path: &str) -> Option<Transcript> {
+        crate::base::transcribe(self.transcriber.as_ref(), path).await
     }
 
     /// Check if a sender is allowed.
     ///
     /// Sender ID format: "user_id|username" — matches either part.
-    /// Empty allow-list = allow everyone.
+    /// Empty allow-list = allow everyone. Enrolled senders are allowed too.
     fn is_allowed(&self, sender_id: &str) -> bool {
         if self.allowed_users.is_empty() {
             return true;
@@ -124,13 +227,16 @@ impl TelegramChannel {
             }
         }
 
-        false
+        self.enrollment
+            .as_ref()
+            .is_some_and(|e| e.is_enrolled("telegram", sender_id))
     }
 
     /// Handle an incoming Telegram update.
     async fn handle_update(&self, bot: &Bot, update: &Update) {
         let message = match &update.kind {
             UpdateKind::Message(msg) => msg,
+            UpdateKind::CallbackQuery(query) => return self.handle_callback(bot, query).await,
             _ => return,
         };
 
@@ -158,14 +264,19 @@ impl TelegramChannel {
                 chat = %chat_id,
                 "telegram message from unauthorized user, ignoring"
             );
+            if let Some(enrollment) = self.enrollment.as_ref().filter(|_| !is_group) {
+                enrollment.challenge("telegram", &sender_id, &chat_id, &first_name);
+            }
             return;
         }
 
         // Handle commands
         if let Some(text) = message.text() {
-            if text.starts_with('/') {
-                self.handle_command(bot, message, text, &first_name, &chat_id)
-                    .await;
+            if text.starts_with('/')
+                && self
+                    .handle_command(bot, message, text, &first_name, &chat_id)
+                    .await
+            {
                 return;
             }
         }
@@ -173,6 +284,9 @@ impl TelegramChannel {
         // Extract content
         let mut content_parts: Vec<String> = Vec::new();
         let mut media_paths: Vec<String> = Vec::new();
+        // Original name of a document, kept so it can be saved under it
+        let mut document_name: Option<String> = None;
+        let mut extra_metadata: HashMap<String, String> = HashMap::new();
 
         // Text content
         match &message.kind {
@@ -204,8 +318,9 @@ impl TelegramChannel {
                         match self.download_file(bot, &voice.voice.file.id.0).await {
                             Ok(path) => {
                                 // Try transcription first
-                                if let Some(text) = self.try_transcribe(&path).await {
-                                    content_parts.push(format!("[transcription: {text}]"));
+                                if let Some(t) = self.try_transcribe(&path).await {
+                                    content_parts.push(t.to_content());
+                                    extra_metadata.extend(t.to_metadata());
                                 } else {
                                     content_parts.push(format!("[voice: {path}]"));
                                 }
@@ -224,8 +339,9 @@ impl TelegramChannel {
                         match self.download_file(bot, &audio.audio.file.id.0).await {
                             Ok(path) => {
                                 // Try transcription first
-                                if let Some(text) = self.try_transcribe(&path).await {
-                                    content_parts.push(format!("[transcription: {text}]"));
+                                if let Some(t) = self.try_transcribe(&path).await {
+                                    content_parts.push(t.to_content());
+                                    extra_metadata.extend(t.to_metadata());
                                 } else {
                                     content_parts.push(format!("[audio: {path}]"));
                                 }
@@ -237,6 +353,21 @@ impl TelegramChannel {
                             }
                         }
                     }
+                    MediaKind::Video(video) => {
+                        if let Some(caption) = &video.caption {
+                            content_parts.push(caption.clone());
+                        }
+                        match self.download_file(bot, &video.video.file.id.0).await {
+                            Ok(path) => {
+                                content_parts.push(format!("[video: {path}]"));
+                                media_paths.push(path);
+                            }
+                            Err(e) => {
+                                warn!(error = %e, "failed to download video");
+                                content_parts.push("[video: download failed]".into());
+                            }
+                        }
+                    }
                     MediaKind::Document(doc) => {
                         if let Some(caption) = &doc.caption {
                             content_parts.push(caption.clone());
@@ -244,6 +375,7 @@ impl TelegramChannel {
                         match self.download_file(bot, &doc.document.file.id.0).await {
                             Ok(path) => {
                                 content_parts.push(format!("[file: {path}]"));
+                                document_name = doc.document.file_name.clone();
                                 media_paths.push(path);
                             }
                             Err(e) => {
@@ -273,31 +405,12 @@ impl TelegramChannel {
             "telegram inbound message"
         );
 
-        // Start typing indicator
-        let typing_bot = bot.clone();
-        let typing_chat_id = ChatId(message.chat.id.0);
-        let typing_shutdown = Arc::new(Notify::new());
-        let typing_signal = typing_shutdown.clone();
-
-        let typing_handle = tokio::spawn(async move {
-            loop {
-                let _ = typing_bot
-                    .send_chat_action(typing_chat_id, ChatAction::Typing)
-                    .await;
-                tokio::select! {
-                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(4)) => {}
-                    _ = typing_signal.notified() => break,
-                }
-            }
-        });
-
-        // Publish to bus
         let mut inbound = InboundMessage::new("telegram", &sender_id, &chat_id, &content);
         for path in &media_paths {
             inbound.media.push(oxibot_core::types::MediaAttachment {
                 path: path.clone(),
                 mime_type: "application/octet-stream".into(),
-                filename: None,
+                filename: document_name.clone(),
                 size: None,
             });
         }
@@ -317,68 +430,151 @@ impl TelegramChannel {
             "message_id".into(),
             message.id.0.to_string(),
         );
+        if let Some(profile) = self.linked_profile(&chat_id) {
+            inbound.metadata.insert("agent_profile".into(), profile);
+        }
+        inbound.metadata.extend(extra_metadata);
+        if self.observe_only && is_group && !self.addresses_bot(message) {
+            inbound = inbound.observed();
+        }
 
-        if let Err(e) = self.bus.publish_inbound(inbound).await {
-            error!(error = %e, "failed to publish telegram message to bus");
+        // Album parts are held back and sent as one message
+        if let Some(group) = message.media_group_id() {
+            inbound.metadata.insert("media_group_id".into(), group.0.clone());
+            if add_album_part(&self.media_groups, &group.0, inbound).await {
+                let (groups, group_id, window) =
+                    (self.media_groups.clone(), group.0.clone(), self.media_group_window);
+                let (bot, bus) = (bot.clone(), self.bus.clone());
+                tokio::spawn(async move {
+                    if let Some(album) = take_album_when_idle(&groups, &group_id, window).await {
+                        publish_with_typing(bot, bus, album).await;
+                    }
+                });
+            }
+            return;
         }
 
-        // Stop typing when response arrives (handled by the outbound dispatcher)
-        // For now, stop after a reasonable timeout
-        tokio::spawn(async move {
-            tokio::time::sleep(tokio::time::Duration::from_secs(120)).await;
-            typing_shutdown.notify_waiters();
-            typing_handle.abort();
-        });
+        publish_with_typing(bot.clone(), self.bus.clone(), inbound).await;
     }
 
     /// Handle a bot command.
+    ///
+    /// Only `/start` with a deep-link token is answered here. Returns
+    /// `false` for everything else so it is forwarded to the agent, which
+    /// answers `/start`, `/help` and its other commands (`/reset`,
+    /// `/status`, `/model`, `/pin`) without calling the LLM.
     async fn handle_command(
         &self,
         bot: &Bot,
         message: &Message,
         text: &str,
         first_name: &str,
-        _chat_id: &str,
-    ) {
-        let command = text.split_whitespace().next().unwrap_or("");
+        chat_id: &str,
+    ) -> bool {
+        let mut words = text.split_whitespace();
+        let command = words.next().unwrap_or("");
         // Strip @botname from command (e.g. /start@mybot)
         let command = command.split('@').next().unwrap_or(command);
+        let token = words.next().unwrap_or("");
 
-        let chat = message.chat.id;
-
-        match command {
-            "/start" => {
-                let greeting = format!(
-                    "👋 Hi {first_name}! I'm Oxibot, your AI assistant.\n\n\
-                     Send me any message and I'll do my best to help!\n\n\
-                     Commands:\n\
-                     /help — Show available commands\n\
-                     /reset — Clear conversation history"
+        if command == "/start" && !token.is_empty() {
+            if let Some(profile) = self.link_chat(chat_id, token) {
+                let reply = format!(
+                    "👋 Hi {first_name}! This chat is now handled by the '{profile}' assistant."
                 );
-                let _ = bot.send_message(chat, greeting).await;
+                let _ = bot.send_message(message.chat.id, reply).await;
+                return true;
             }
-            "/help" => {
-                let help = "🤖 <b>Oxibot Commands</b>\n\n\
-                     /start — Start the bot\n\
-                     /reset — Clear conversation history\n\
-                     /help — Show this message\n\n\
-                     Just send me text, photos, voice messages, or documents \
-                     and I'll process them!";
-                let _ = bot
-                    .send_message(chat, help)
-                    .parse_mode(ParseMode::Html)
-                    .await;
+            warn!(chat = %chat_id, "unknown telegram deep-link token");
+        }
+        debug!(command = command, "forwarding telegram command to agent");
+        false
+    }
+
+    /// Handle an Allow / Deny button press on an approval request.
+    async fn handle_callback(&self, bot: &Bot, query: &CallbackQuery) {
+        let Some((id, decision)) = query.data.as_deref().and_then(approval::parse_button_data) else {
+            return;
+        };
+        let Some(ref message) = query.message else { return };
+        let user = &query.from;
+        let sender_id = format!("{}|{}", user.id.0, user.username.as_deref().unwrap_or(""));
+        let chat_id = message.chat().id;
+
+        if !self.is_allowed(&sender_id) {
+            warn!(sender = %sender_id, chat = %chat_id, "telegram button press from unauthorized user, ignoring");
+            let _ = bot.answer_callback_query(query.id.clone()).await;
+            return;
+        }
+
+        if let Err(e) = bot.answer_callback_query(query.id.clone()).text(decision.label()).await {
+            debug!(error = %e, "telegram callback answer failed");
+        }
+        // Replace the buttons with the answer
+        if let MaybeInaccessibleMessage::Regular(asked) = message {
+            let text = answered_prompt(asked.text().unwrap_or_default(), decision);
+            if let Err(e) = bot.edit_message_text(chat_id, asked.id, text).await {
+                debug!(error = %e, "telegram approval edit failed");
             }
-            "/reset" => {
-                // TODO: Wire session manager for session clearing
-                let _ = bot
-                    .send_message(chat, "🔄 Conversation history cleared.")
-                    .await;
+        }
+
+        let inbound = InboundMessage::approval_response("telegram", sender_id, chat_id.0.to_string(), id, decision);
+        if let Err(e) = self.bus.publish_inbound(inbound).await {
+            error!(error = %e, "failed to publish approval answer");
+        }
+    }
+
+    /// Send an approval request with Allow / Deny buttons.
+    async fn send_approval(&self, bot: &Bot, chat_id: i64, id: &str, prompt: &str) -> anyhow::Result<()> {
+        let html = markdown_to_telegram_html(prompt);
+        let sent = bot
+            .send_message(ChatId(chat_id), html)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(approval_keyboard(id))
+            .await;
+        if let Err(e) = sent {
+            debug!(error = %e, "HTML send failed, retrying as plain text");
+            let plain: String = prompt.chars().take(TELEGRAM_MAX_LEN).collect();
+            bot.send_message(ChatId(chat_id), plain)
+                .reply_markup(approval_keyboard(id))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Send or edit the progress message for `progress_id`.
+    async fn send_progress(
+        &self,
+        bot: &Bot,
+        chat_id: i64,
+        msg: &OutboundMessage,
+        progress_id: &str,
+    ) -> anyhow::Result<()> {
+        let html = markdown_to_telegram_html(&msg.content);
+        match self.progress_messages.get(progress_id) {
+            Some(message_id) => {
+                // Editing with unchanged text fails; nothing to do then
+                if let Err(e) = bot
+                    .edit_message_text(ChatId(chat_id), message_id, html)
+                    .parse_mode(ParseMode::Html)
+                    .await
+                {
+                    debug!(error = %e, "telegram progress edit failed");
+                }
             }
-            _ => {
-                debug!(command = command, "unknown telegram command");
+            None => {
+                let sent = bot
+                    .send_message(ChatId(chat_id), html)
+                    .parse_mode(ParseMode::Html)
+                    .await?;
+                self.progress_messages.insert(progress_id, sent.id);
             }
         }
+
+        if msg.is_progress_done() {
+            self.progress_messages.finish(progress_id);
+        }
+        Ok(())
     }
 
     /// Download a file from Telegram to a local temp path.
@@ -387,7 +583,7 @@ impl TelegramChannel {
         let file = bot.get_file(FileId(file_id.to_string())).send().await?;
 
         // Create media directory
-        let media_dir = oxibot_core::utils::get_data_path().join("media");
+        let media_dir = oxibot_core::utils::get_media_path();
         std::fs::create_dir_all(&media_dir)?;
 
         // Determine extension from file path
@@ -413,6 +609,159 @@ impl TelegramChannel {
     }
 }
 
+/// Allow / Deny buttons for approval request `id`.
+fn approval_keyboard(id: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[
+        InlineKeyboardButton::callback(APPROVE_BUTTON_TEXT, approval::button_data(id, Decision::Approved)),
+        InlineKeyboardButton::callback(DENY_BUTTON_TEXT, approval::button_data(id, Decision::Denied)),
+    ]])
+}
+
+/// Publish an inbound message and show the typing indicator meanwhile
+/// (not for observed messages, which get no reply).
+async fn publish_with_typing(bot: Bot, bus: Arc<MessageBus>, inbound: InboundMessage) {
+    let Ok(chat_id) = inbound.chat_id.parse::<i64>() else { return };
+    if inbound.is_observed() {
+        if let Err(e) = bus.publish_inbound(inbound).await {
+            error!(error = %e, "failed to publish telegram message to bus");
+        }
+        return;
+    }
+
+    // Start typing indicator
+    let typing_shutdown = Arc::new(Notify::new());
+    let typing_signal = typing_shutdown.clone();
+    let typing_handle = tokio::spawn(async move {
+        loop {
+            let _ = bot.send_chat_action(ChatId(chat_id), ChatAction::Typing).await;
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(4)) => {}
+                _ = typing_signal.notified() => break,
+            }
+        }
+    });
+
+    if let Err(e) = bus.publish_inbound(inbound).await {
+        error!(error = %e, "failed to publish telegram message to bus");
+    }
+
+    // Stop typing when response arrives (handled by the outbound dispatcher)
+    // For now, stop after a reasonable timeout
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(120)).await;
+        typing_shutdown.notify_waiters();
+        typing_handle.abort();
+    });
+}
+
+// ─────────────────────────────────────────────
+// Albums
+// ─────────────────────────────────────────────
+
+/// Albums being assembled, keyed by media group id.
+type PendingAlbums = Arc<Mutex<HashMap<String, PendingAlbum>>>;
+
+/// An album whose parts are still arriving.
+struct PendingAlbum {
+    /// Parts merged so far (content, media and the first part's metadata).
+    message: InboundMessage,
+    /// Number of parts received.
+    parts: usize,
+}
+
+/// Add one part of an album. Returns `true` for the first part, whose
+/// caller is responsible for flushing the album.
+async fn add_album_part(groups: &PendingAlbums, group_id: &str, part: InboundMessage) -> bool {
+    let mut groups = groups.lock().await;
+    match groups.get_mut(group_id) {
+        Some(album) => {
+            album.message.content.push('\n');
+            album.message.content.push_str(&part.content);
+            album.message.media.extend(part.media);
+            for (key, value) in part.metadata {
+                album.message.metadata.entry(key).or_insert(value);
+            }
+            album.parts += 1;
+            false
+        }
+        None => {
+            groups.insert(group_id.to_string(), PendingAlbum { message: part, parts: 1 });
+            true
+        }
+    }
+}
+
+/// Wait until no part has arrived for `window`, then remove the album.
+async fn take_album_when_idle(
+    groups: &PendingAlbums,
+    group_id: &str,
+    window: Duration,
+) -> Option<InboundMessage> {
+    let mut seen = 1;
+    loop {
+        tokio::time::sleep(window).await;
+        let mut pending = groups.lock().await;
+        let parts = pending.get(group_id)?.parts;
+        if parts == seen {
+            debug!(group = %group_id, parts, "telegram album complete");
+            return pending.remove(group_id).map(|album| album.message);
+        }
+        seen = parts;
+    }
+}
+
+// ─────────────────────────────────────────────
+// Deep-link store
+// ─────────────────────────────────────────────
+
+/// Chat → agent profile links created by deep links, persisted as JSON.
+struct LinkedChats {
+    /// Backing file.
+    path: PathBuf,
+    /// Profile name keyed by chat id.
+    chats: std::sync::Mutex<HashMap<String, String>>,
+}
+
+impl LinkedChats {
+    /// Load links from `path`; a missing or invalid file yields none.
+    fn load(path: PathBuf) -> Self {
+        let chats = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            chats: std::sync::Mutex::new(chats),
+        }
+    }
+
+    fn get(&self, chat_id: &str) -> Option<String> {
+        self.chats
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(chat_id)
+            .cloned()
+    }
+
+    /// Link a chat and persist. Write failures are logged, not fatal.
+    fn link(&self, chat_id: &str, profile: &str) {
+        let mut chats = self.chats.lock().unwrap_or_else(|e| e.into_inner());
+        chats.insert(chat_id.to_string(), profile.to_string());
+        let result = serde_json::to_string_pretty(&*chats)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                if let Some(parent) = self.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&self.path, json)?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            warn!(path = %self.path.display(), error = %e, "failed to save telegram deep links");
+        }
+    }
+}
+
 #[async_trait]
 impl Channel for TelegramChannel {
     fn name(&self) -> &str {
@@ -430,11 +779,23 @@ impl Channel for TelegramChannel {
             BotCommand::new("start", "Start the bot"),
             BotCommand::new("help", "Show available commands"),
             BotCommand::new("reset", "Clear conversation history"),
+            BotCommand::new("status", "Show session status"),
+            BotCommand::new("model", "Show the current model"),
+            BotCommand::new("persona", "Show or switch the persona"),
         ];
         if let Err(e) = bot.set_my_commands(commands).await {
             warn!(error = %e, "failed to set bot commands menu");
         }
 
+        if self.observe_only {
+            match bot.get_me().await {
+                Ok(me) => {
+                    let _ = self.bot_username.set(me.username().to_string());
+                }
+                Err(e) => warn!(error = %e, "failed to get bot username, only commands will be answered in groups"),
+            }
+        }
+
         info!("telegram bot connected, polling for updates");
 
         // Manual polling loop (we need control over the bus integration)
@@ -445,6 +806,7 @@ impl Channel for TelegramChannel {
                 updates = bot.get_updates().offset(offset).timeout(30).send() => {
                     match updates {
                         Ok(updates) => {
+                            self.activity.touch();
                             for update in &updates {
                                 offset = (update.id.0 as i32).wrapping_add(1);
                                 self.handle_update(&bot, update).await;
@@ -472,6 +834,10 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    fn health(&self) -> Option<HealthReport> {
+        Some(self.activity.report())
+    }
+
     async fn send(&self, msg: &OutboundMessage) -> anyhow::Result<()> {
         let bot = Bot::new(&self.token);
         let chat_id: i64 = msg
@@ -479,6 +845,12 @@ impl Channel for TelegramChannel {
             .parse()
             .map_err(|_| anyhow::anyhow!("invalid telegram chat_id: {}", msg.chat_id))?;
 
+        if let Some(progress_id) = msg.progress_id() {
+            return self.send_progress(&bot, chat_id, msg, progress_id).await;
+        }
+        if let Some((id, prompt)) = msg.approval() {
+            return self.send_approval(&bot, chat_id, id, prompt).await;
+        }
         // Convert markdown to Telegram HTML
         let html = markdown_to_telegram_html(&msg.content);
 
@@ -499,13 +871,80 @@ impl Channel for TelegramChannel {
                 for plain_chunk in &plain_chunks {
                     let _ = bot.send_message(ChatId(chat_id), plain_chunk).await;
                 }
-                return Ok(());
+                break;
+            }
+        }
+
+        // Attachments follow as documents
+        for media in &msg.media {
+            let file = if media.path.starts_with("http://") || media.path.starts_with("https://") {
+                let Ok(url) = media.path.parse() else {
+                    warn!(url = %media.path, "invalid attachment URL, skipping");
+                    continue;
+                };
+                InputFile::url(url)
+            } else {
+                InputFile::file(&media.path)
+            };
+            let file = match media.filename {
+                Some(ref name) => file.file_name(name.clone()),
+                None => file,
+            };
+            bot.send_document(ChatId(chat_id), file).await?;
+        }
+
+        debug!(chat_id = chat_id, attachments = msg.media.len(), "telegram message sent");
+        Ok(())
+    }
+
+    async fn acknowledge(&self, ack: &OutboundMessage) -> anyhow::Result<()> {
+        let Some(message_id) = ack.message_id().and_then(|id| id.parse().ok()) else {
+            return Ok(());
+        };
+        let chat_id: i64 = ack
+            .chat_id
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid telegram chat_id: {}", ack.chat_id))?;
+        let bot = Bot::new(&self.token);
+        match self.ack {
+            AckMode::Reaction(ref emoji) => {
+                bot.set_message_reaction(ChatId(chat_id), MessageId(message_id))
+                    .reaction(vec![ReactionType::Emoji { emoji: emoji.clone() }])
+                    .await?;
+            }
+            AckMode::Reply => {
+                bot.send_message(ChatId(chat_id), ACK_REPLY_TEXT).await?;
             }
+            AckMode::Off => {}
         }
+        Ok(())
+    }
+
+    fn supports_edits(&self) -> bool {
+        true
+    }
+
+    fn supports_uploads(&self) -> bool {
+        true
+    }
+
+    fn max_message_len(&self) -> Option<usize> {
+        Some(TELEGRAM_MAX_LEN)
+    }
 
-        debug!(chat_id = chat_id, "telegram message sent");
+    async fn typing(&self, chat_id: &str) -> anyhow::Result<()> {
+        let chat_id: i64 = chat_id
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid telegram chat_id: {chat_id}"))?;
+        Bot::new(&self.token)
+            .send_chat_action(ChatId(chat_id), ChatAction::Typing)
+            .await?;
         Ok(())
     }
+
+    async fn check(&self) -> Option<anyhow::Result<()>> {
+        Some(Bot::new(&self.token).get_me().await.map(|_| ()).map_err(Into::into))
+    }
 }
 
 // ─────────────────────────────────────────────
@@ -567,6 +1006,117 @@ mod tests {
         assert!(ch.is_allowed("123456"));
     }
 
+    #[test]
+    fn test_deep_link_binds_chat() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("links.json");
+        let links = HashMap::from([("vip".to_string(), "support".to_string())]);
+        let ch = create_test_channel().with_deep_links(links.clone(), Some(store.clone()));
+
+        assert_eq!(ch.linked_profile("42"), None);
+        assert_eq!(ch.link_chat("42", "bogus"), None);
+        assert_eq!(ch.link_chat("42", "vip"), Some("support"));
+        assert_eq!(ch.linked_profile("42").as_deref(), Some("support"));
+
+        // Links survive a restart
+        let ch = create_test_channel().with_deep_links(links, Some(store));
+        assert_eq!(ch.linked_profile("42").as_deref(), Some("support"));
+        assert_eq!(ch.linked_profile("43"), None);
+    }
+
+    #[test]
+    fn test_approval_keyboard() {
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let keyboard = approval_keyboard("ab12");
+        let decisions: Vec<_> = keyboard.inline_keyboard[0]
+            .iter()
+            .map(|button| match button.kind {
+                InlineKeyboardButtonKind::CallbackData(ref data) => approval::parse_button_data(data),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(decisions, [Some(("ab12", Decision::Approved)), Some(("ab12", Decision::Denied))]);
+        assert_eq!(keyboard.inline_keyboard[0][0].text, APPROVE_BUTTON_TEXT);
+    }
+
+    #[test]
+    fn test_deep_links_disabled_by_default() {
+        let ch = create_test_channel();
+        assert_eq!(ch.link_chat("42", "vip"), None);
+        assert_eq!(ch.linked_profile("42"), None);
+    }
+
+    #[tokio::test]
+    async fn test_album_parts_are_merged() {
+        let groups: PendingAlbums = Arc::new(Mutex::new(HashMap::new()));
+        let part = |content: &str, path: &str, message_id: &str| {
+            let mut msg = InboundMessage::new("telegram", "1|ann", "42", content);
+            msg.media.push(oxibot_core::types::MediaAttachment {
+                path: path.into(),
+                mime_type: "application/octet-stream".into(),
+                filename: None,
+                size: None,
+            });
+            msg.metadata.insert("message_id".into(), message_id.into());
+            msg
+        };
+
+        assert!(add_album_part(&groups, "g1", part("Trip photos\n[image: a.jpg]", "a.jpg", "10")).await);
+        let window = Duration::from_millis(80);
+        let flush = {
+            let groups = groups.clone();
+            tokio::spawn(async move { take_album_when_idle(&groups, "g1", window).await })
+        };
+
+        // A part arriving inside the window extends it
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!add_album_part(&groups, "g1", part("[image: b.jpg]", "b.jpg", "11")).await);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!add_album_part(&groups, "g1", part("[video: c.mp4]", "c.mp4", "12")).await);
+
+        let album = flush.await.unwrap().expect("album flushed");
+        assert_eq!(album.content, "Trip photos\n[image: a.jpg]\n[image: b.jpg]\n[video: c.mp4]");
+        let paths: Vec<&str> = album.media.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["a.jpg", "b.jpg", "c.mp4"]);
+        assert_eq!(album.metadata["message_id"], "10");
+        assert!(groups.lock().await.is_empty());
+
+        // A later part with the same id starts a new album
+        assert!(add_album_part(&groups, "g1", part("[image: d.jpg]", "d.jpg", "13")).await);
+    }
+
+    #[test]
+    fn test_addresses_bot() {
+        let ch = create_test_channel().with_observe_only(true);
+        ch.bot_username.set("oxi_bot".into()).unwrap();
+        let message = |text: &str, reply_from: Option<&str>| -> Message {
+            let mut json = serde_json::json!({
+                "message_id": 2,
+                "date": 1_700_000_000,
+                "chat": { "id": -100, "type": "supergroup", "title": "team" },
+                "from": { "id": 1, "is_bot": false, "first_name": "Ann" },
+                "text": text,
+            });
+            if let Some(username) = reply_from {
+                json["reply_to_message"] = serde_json::json!({
+                    "message_id": 1,
+                    "date": 1_700_000_000,
+                    "chat": { "id": -100, "type": "supergroup", "title": "team" },
+                    "from": { "id": 9, "is_bot": true, "first_name": "Oxi", "username": username },
+                    "text": "earlier",
+                });
+            }
+            serde_json::from_value(json).unwrap()
+        };
+
+        assert!(!ch.addresses_bot(&message("we ship friday", None)));
+        assert!(ch.addresses_bot(&message("@Oxi_Bot what was decided?", None)));
+        assert!(ch.addresses_bot(&message("/status", None)));
+        assert!(ch.addresses_bot(&message("thanks", Some("oxi_bot"))));
+        assert!(!ch.addresses_bot(&message("thanks", Some("someone_else"))));
+    }
+
     #[test]
     fn test_is_allowed_pipe_split() {
         let ch = create_restricted_channel();
@@ -25,6 +25,7 @@ use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::{InboundMessage, OutboundMessage};
 
 use crate::base::Channel;
+use crate::enrollment::Enrollment;
 
 // ─────────────────────────────────────────────
 // Constants
@@ -54,6 +55,8 @@ pub struct WhatsAppChannel {
     ws_write: Arc<Mutex<Option<WsSender>>>,
     /// Whether bridge reports connected to WhatsApp.
     connected: Arc<Mutex<bool>>,
+    /// Admin-approved access for unknown senders.
+    enrollment: Option<Arc<Enrollment>>,
 }
 
 /// Type alias for the WebSocket sink.
@@ -82,9 +85,16 @@ impl WhatsAppChannel {
             shutdown: Arc::new(Notify::new()),
             ws_write: Arc::new(Mutex::new(None)),
             connected: Arc::new(Mutex::new(false)),
+            enrollment: None,
         }
     }
 
+    /// Let unknown senders in direct chats ask the admin for access.
+    pub fn with_enrollment(mut self, enrollment: Option<Arc<Enrollment>>) -> Self {
+        self.enrollment = enrollment;
+        self
+    }
+
     /// Check if a sender is allowed.
     fn is_allowed(&self, sender_id: &str) -> bool {
         if self.allowed_users.is_empty() {
@@ -98,7 +108,9 @@ impl WhatsAppChannel {
                 return true;
             }
         }
-        false
+        self.enrollment
+            .as_ref()
+            .is_some_and(|e| e.is_enrolled("whatsapp", sender_id))
     }
 
     /// Run the WebSocket connection with auto-reconnect.
@@ -252,6 +264,10 @@ impl WhatsAppChannel {
                 sender = %sender_id,
                 "whatsapp message from unauthorized user, ignoring"
             );
+            let is_group = payload["isGroup"].as_bool().unwrap_or(false);
+            if let Some(enrollment) = self.enrollment.as_ref().filter(|_| !is_group) {
+                enrollment.challenge("whatsapp", &sender_id, &chat_id, "");
+            }
             return;
         }
 
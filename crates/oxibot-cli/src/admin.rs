@@ -0,0 +1,163 @@
+//! Gateway admin API — runtime controls under `/admin/`.
+//!
+//! Served behind [`Guarded`](oxibot_core::auth::Guarded): every request
+//! needs a credential with the admin scope (`gateway.adminToken`, an admin
+//! API key or a JWT).
+//!
+//! - `GET /admin/log-level` — the active log filter
+//! - `PUT /admin/log-level` — replace it with the filter in the body
+//!   (e.g. `info,oxibot_channels::discord=trace`)
+//! - `POST /admin/oauth/login/CLIENT` — start an OAuth login; returns the
+//!   URL to open, and the gateway's `/oauth/callback` completes it
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::info;
+
+use oxibot_core::http::{Handler, Request, Response};
+use oxibot_core::oauth::OAuthManager;
+
+use crate::logging;
+
+/// Path prefix of the admin endpoints.
+pub const ADMIN_PREFIX: &str = "/admin/";
+
+/// Handler for the `/admin/` endpoints. Does no authentication itself.
+pub struct AdminApi {
+    oauth: Arc<OAuthManager>,
+}
+
+impl AdminApi {
+    pub fn new(oauth: Arc<OAuthManager>) -> Self {
+        Self { oauth }
+    }
+
+    fn oauth_login(&self, client: &str) -> Response {
+        match self.oauth.authorization_url(client) {
+            Ok(url) => {
+                info!(client, "oauth login started from the admin api");
+                Response::ok(url)
+            }
+            Err(e) => Response::new("404 Not Found", format!("{e:#}")),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for AdminApi {
+    async fn handle(&self, request: &Request) -> Option<Response> {
+        let path = request.path().strip_prefix(ADMIN_PREFIX)?;
+        Some(match (request.method.as_str(), path) {
+            ("GET", "log-level") => log_level(),
+            ("PUT" | "POST", "log-level") => set_log_level(&request.body),
+            ("POST", path) if path.starts_with("oauth/login/") => {
+                self.oauth_login(&path["oauth/login/".len()..])
+            }
+            _ => Response::not_found(),
+        })
+    }
+}
+
+fn log_level() -> Response {
+    match logging::control() {
+        Some(control) => Response::ok(control.directives()),
+        None => Response::new("503 Service Unavailable", "Logging is not initialized."),
+    }
+}
+
+fn set_log_level(body: &str) -> Response {
+    let Some(control) = logging::control() else {
+        return Response::new("503 Service Unavailable", "Logging is not initialized.");
+    };
+    match control.set(body) {
+        Ok(()) => {
+            info!(filter = %body.trim(), "log filter changed");
+            Response::ok(control.directives())
+        }
+        Err(e) => Response::new("400 Bad Request", format!("{e:#}")),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use oxibot_core::auth::{ApiAuth, Guarded, Scope};
+    use oxibot_core::config::schema::{JwtConfig, OAuthClientConfig};
+    use oxibot_core::oauth::TokenStore;
+
+    use super::*;
+
+    fn admin_api(dir: &Path) -> Arc<AdminApi> {
+        let client = OAuthClientConfig {
+            auth_url: "https://auth.example.com/authorize".into(),
+            token_url: "https://auth.example.com/token".into(),
+            client_id: "oxibot".into(),
+            ..Default::default()
+        };
+        let oauth = OAuthManager::new(
+            HashMap::from([("google".into(), client)]),
+            "http://localhost:18790/oauth/callback",
+            TokenStore::load(Some(dir.join("tokens.json"))),
+        );
+        Arc::new(AdminApi::new(Arc::new(oauth)))
+    }
+
+    fn request(method: &str, target: &str, token: Option<&str>) -> Request {
+        let mut request = Request {
+            method: method.into(),
+            target: target.into(),
+            ..Default::default()
+        };
+        if let Some(token) = token {
+            request.headers.insert("authorization".into(), format!("Bearer {token}"));
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_admin_requires_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = Some(dir.path().join("api_keys.json"));
+        let guard = |token: &str| {
+            let auth = Arc::new(ApiAuth::new(keys.clone(), token, JwtConfig::default()));
+            Guarded::new(ADMIN_PREFIX, Scope::Admin, auth, admin_api(dir.path()))
+        };
+        let api = guard("s3cret");
+        assert_eq!(api.handle(&request("GET", "/oauth/callback", None)).await, None);
+
+        let denied = api.handle(&request("GET", "/admin/log-level", None)).await.unwrap();
+        assert_eq!(denied.status, "401 Unauthorized");
+        let denied = api.handle(&request("GET", "/admin/log-level", Some("wrong"))).await.unwrap();
+        assert_eq!(denied.status, "401 Unauthorized");
+
+        let missing = api.handle(&request("GET", "/admin/other", Some("s3cret"))).await.unwrap();
+        assert_eq!(missing.status, "404 Not Found");
+
+        // An empty token never authorizes
+        let open = guard("");
+        let denied = open.handle(&request("GET", "/admin/log-level", Some(""))).await.unwrap();
+        assert_eq!(denied.status, "401 Unauthorized");
+    }
+
+    #[tokio::test]
+    async fn test_oauth_login() {
+        let dir = tempfile::tempdir().unwrap();
+        let api = admin_api(dir.path());
+
+        let started = api.handle(&request("POST", "/admin/oauth/login/google", None)).await.unwrap();
+        assert_eq!(started.status, "200 OK");
+        assert!(started.body.starts_with("https://auth.example.com/authorize?"), "{}", started.body);
+        assert!(started.body.contains("state="));
+
+        let unknown = api.handle(&request("POST", "/admin/oauth/login/github", None)).await.unwrap();
+        assert_eq!(unknown.status, "404 Not Found");
+        assert!(unknown.body.contains("unknown oauth client 'github'"));
+    }
+}
@@ -0,0 +1,501 @@
+//! `oxibot analytics` — export and summarize usage data.
+//!
+//! - `oxibot analytics export [--out DIR] [--since DATE]` — write Parquet files
+//!   (`messages`, `llm_calls`, `tool_calls`) for DuckDB/Grafana
+//! - `oxibot analytics export --report NAME` — print a built-in report instead
+//!
+//! Data comes from the usage log (`~/.oxibot/usage.jsonl`) written by the
+//! agent loop. Parquet export needs the `analytics` feature.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::{Subcommand, ValueEnum};
+use colored::Colorize;
+
+use oxibot_core::config::load_config;
+use oxibot_core::config::schema::ModelPrice;
+use oxibot_core::usage::{self, price_for, UsageEvent, UsageLog};
+use oxibot_core::utils::get_data_path;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Analytics subcommands.
+#[derive(Subcommand)]
+pub enum AnalyticsCommands {
+    /// Export usage data as Parquet (or print a report with --report)
+    Export {
+        /// Output directory (default: ~/.oxibot/analytics)
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+
+        /// Print a built-in report instead of writing files
+        #[arg(short, long, value_enum)]
+        report: Option<Report>,
+
+        /// Only include events on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+/// Built-in reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Report {
+    /// Tool invocations, failures and average latency
+    TopTools,
+    /// Token usage and estimated cost per channel
+    CostByChannel,
+    /// Messages and response latency per day
+    MessagesByDay,
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch an analytics subcommand.
+pub fn dispatch(cmd: AnalyticsCommands) -> Result<()> {
+    match cmd {
+        AnalyticsCommands::Export { out, report, since } => {
+            let since = since
+                .map(|s| {
+                    NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                        .with_context(|| format!("invalid --since date '{s}' (expected YYYY-MM-DD)"))
+                })
+                .transpose()?;
+            let events: Vec<UsageEvent> = UsageLog::new(None)
+                .read_all()
+                .into_iter()
+                .filter(|e| since.is_none_or(|d| e.timestamp().date_naive() >= d))
+                .collect();
+
+            match report {
+                Some(Report::TopTools) => print_top_tools(&events),
+                Some(Report::CostByChannel) => {
+                    let config = load_config(None);
+                    print_cost_by_channel(&events, &config.analytics.prices)
+                }
+                Some(Report::MessagesByDay) => print_messages_by_day(&events),
+                None => {
+                    let out = out.unwrap_or_else(|| get_data_path().join("analytics"));
+                    export_parquet(&events, &out)
+                }
+            }
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Reports
+// ─────────────────────────────────────────────
+
+/// Per-tool statistics.
+#[derive(Debug, Default, PartialEq)]
+struct ToolStats {
+    calls: u64,
+    failures: u64,
+    total_latency_ms: u64,
+}
+
+/// Tools sorted by number of calls (most used first).
+fn top_tools(events: &[UsageEvent]) -> Vec<(String, ToolStats)> {
+    let mut stats: BTreeMap<String, ToolStats> = BTreeMap::new();
+    for event in events {
+        if let UsageEvent::ToolCall { tool, latency_ms, success, .. } = event {
+            let s = stats.entry(tool.clone()).or_default();
+            s.calls += 1;
+            s.total_latency_ms += latency_ms;
+            if !success {
+                s.failures += 1;
+            }
+        }
+    }
+    let mut rows: Vec<_> = stats.into_iter().collect();
+    rows.sort_by_key(|(_, s)| std::cmp::Reverse(s.calls));
+    rows
+}
+
+/// Per-channel token usage and cost.
+#[derive(Debug, Default, PartialEq)]
+struct ChannelCost {
+    llm_calls: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    /// Estimated cost in USD (`None` if no model used had a price).
+    cost: Option<f64>,
+}
+
+/// Token usage and cost grouped by channel.
+fn cost_by_channel(
+    events: &[UsageEvent],
+    prices: &HashMap<String, ModelPrice>,
+) -> BTreeMap<String, ChannelCost> {
+    let mut rows: BTreeMap<String, ChannelCost> = BTreeMap::new();
+    for event in events {
+        if let UsageEvent::LlmCall { channel, model, prompt_tokens, completion_tokens, .. } = event {
+            let row = rows.entry(channel.clone()).or_default();
+            row.llm_calls += 1;
+            row.prompt_tokens += *prompt_tokens as u64;
+            row.completion_tokens += *completion_tokens as u64;
+            if let Some(price) = price_for(prices, model) {
+                *row.cost.get_or_insert(0.0) += usage::cost(price, *prompt_tokens as u64, *completion_tokens as u64);
+            }
+        }
+    }
+    rows
+}
+
+/// Per-day message statistics: (messages, average latency, p95 latency).
+fn messages_by_day(events: &[UsageEvent]) -> BTreeMap<NaiveDate, (u64, u64, u64)> {
+    let mut latencies: BTreeMap<NaiveDate, Vec<u64>> = BTreeMap::new();
+    for event in events {
+        if let UsageEvent::Message { timestamp, latency_ms, .. } = event {
+            latencies.entry(timestamp.date_naive()).or_default().push(*latency_ms);
+        }
+    }
+    latencies
+        .into_iter()
+        .map(|(day, mut values)| {
+            values.sort_unstable();
+            let count = values.len() as u64;
+            let avg = values.iter().sum::<u64>() / count;
+            let p95 = values[((values.len() * 95).div_ceil(100)).saturating_sub(1)];
+            (day, (count, avg, p95))
+        })
+        .collect()
+}
+
+/// `oxibot analytics export --report top-tools`
+fn print_top_tools(events: &[UsageEvent]) -> Result<()> {
+    let rows = top_tools(events);
+    if rows.is_empty() {
+        println!("  No tool calls recorded.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  Top Tools".cyan().bold());
+    println!();
+    println!("  {:<20} {:>8} {:>9} {:>12}", "Tool".bold(), "Calls".bold(), "Failures".bold(), "Avg (ms)".bold());
+    println!("  {}", "─".repeat(52));
+    for (tool, s) in rows {
+        println!(
+            "  {:<20} {:>8} {:>9} {:>12}",
+            tool,
+            s.calls,
+            s.failures,
+            s.total_latency_ms / s.calls
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// `oxibot analytics export --report cost-by-channel`
+fn print_cost_by_channel(
+    events: &[UsageEvent],
+    prices: &HashMap<String, ModelPrice>,
+) -> Result<()> {
+    let rows = cost_by_channel(events, prices);
+    if rows.is_empty() {
+        println!("  No LLM calls recorded.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  Cost by Channel".cyan().bold());
+    println!();
+    println!(
+        "  {:<14} {:>8} {:>14} {:>14} {:>10}",
+        "Channel".bold(),
+        "Calls".bold(),
+        "Prompt tok".bold(),
+        "Output tok".bold(),
+        "Cost ($)".bold()
+    );
+    println!("  {}", "─".repeat(64));
+    for (channel, row) in rows {
+        let cost = row.cost.map_or_else(|| "—".to_string(), |c| format!("{c:.4}"));
+        println!(
+            "  {:<14} {:>8} {:>14} {:>14} {:>10}",
+            channel, row.llm_calls, row.prompt_tokens, row.completion_tokens, cost
+        );
+    }
+    if prices.is_empty() {
+        println!();
+        println!("  {}", "Set analytics.prices in config.json to estimate costs.".dimmed());
+    }
+    println!();
+    Ok(())
+}
+
+/// `oxibot analytics export --report messages-by-day`
+fn print_messages_by_day(events: &[UsageEvent]) -> Result<()> {
+    let rows = messages_by_day(events);
+    if rows.is_empty() {
+        println!("  No messages recorded.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  Messages by Day".cyan().bold());
+    println!();
+    println!("  {:<12} {:>10} {:>12} {:>12}", "Day".bold(), "Messages".bold(), "Avg (ms)".bold(), "p95 (ms)".bold());
+    println!("  {}", "─".repeat(50));
+    for (day, (count, avg, p95)) in rows {
+        println!("  {:<12} {:>10} {:>12} {:>12}", day.to_string(), count, avg, p95);
+    }
+    println!();
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// Parquet export
+// ─────────────────────────────────────────────
+
+/// Write `messages.parquet`, `llm_calls.parquet` and `tool_calls.parquet`.
+#[cfg(feature = "analytics")]
+fn export_parquet(events: &[UsageEvent], out: &std::path::Path) -> Result<()> {
+    std::fs::create_dir_all(out)
+        .with_context(|| format!("failed to create {}", out.display()))?;
+
+    let files = [
+        ("messages.parquet", parquet_export::messages(events)?),
+        ("llm_calls.parquet", parquet_export::llm_calls(events)?),
+        ("tool_calls.parquet", parquet_export::tool_calls(events)?),
+    ];
+    for (name, batch) in files {
+        let path = out.join(name);
+        let rows = batch.num_rows();
+        parquet_export::write(&path, &batch)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        println!("  {} {} ({} rows)", "✓".green(), path.display(), rows);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "analytics"))]
+fn export_parquet(_events: &[UsageEvent], _out: &std::path::Path) -> Result<()> {
+    anyhow::bail!(
+        "Parquet export is not available in this build. \
+         Rebuild with `--features analytics`, or use --report."
+    )
+}
+
+#[cfg(feature = "analytics")]
+mod parquet_export {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use anyhow::Result;
+    use arrow_array::{
+        ArrayRef, BooleanArray, RecordBatch, StringArray, TimestampMillisecondArray, UInt32Array,
+        UInt64Array,
+    };
+    use parquet::arrow::ArrowWriter;
+
+    use oxibot_core::usage::UsageEvent;
+
+    fn timestamps(values: Vec<i64>) -> ArrayRef {
+        Arc::new(TimestampMillisecondArray::from(values).with_timezone("UTC"))
+    }
+
+    fn strings(values: Vec<&str>) -> ArrayRef {
+        Arc::new(StringArray::from(values))
+    }
+
+    /// One row per answered message.
+    pub fn messages(events: &[UsageEvent]) -> Result<RecordBatch> {
+        let (mut ts, mut channel, mut chat, mut latency) = (vec![], vec![], vec![], vec![]);
+        for event in events {
+            if let UsageEvent::Message { timestamp, channel: ch, chat_id, latency_ms } = event {
+                ts.push(timestamp.timestamp_millis());
+                channel.push(ch.as_str());
+                chat.push(chat_id.as_str());
+                latency.push(*latency_ms);
+            }
+        }
+        Ok(RecordBatch::try_from_iter(vec![
+            ("timestamp", timestamps(ts)),
+            ("channel", strings(channel)),
+            ("chat_id", strings(chat)),
+            ("latency_ms", Arc::new(UInt64Array::from(latency)) as ArrayRef),
+        ])?)
+    }
+
+    /// One row per LLM request.
+    pub fn llm_calls(events: &[UsageEvent]) -> Result<RecordBatch> {
+        let (mut ts, mut channel, mut chat, mut model) = (vec![], vec![], vec![], vec![]);
+        let (mut latency, mut prompt, mut completion) = (vec![], vec![], vec![]);
+        for event in events {
+            if let UsageEvent::LlmCall {
+                timestamp,
+                channel: ch,
+                chat_id,
+                model: m,
+                latency_ms,
+                prompt_tokens,
+                completion_tokens,
+            } = event
+            {
+                ts.push(timestamp.timestamp_millis());
+                channel.push(ch.as_str());
+                chat.push(chat_id.as_str());
+                model.push(m.as_str());
+                latency.push(*latency_ms);
+                prompt.push(*prompt_tokens);
+                completion.push(*completion_tokens);
+            }
+        }
+        Ok(RecordBatch::try_from_iter(vec![
+            ("timestamp", timestamps(ts)),
+            ("channel", strings(channel)),
+            ("chat_id", strings(chat)),
+            ("model", strings(model)),
+            ("latency_ms", Arc::new(UInt64Array::from(latency)) as ArrayRef),
+            ("prompt_tokens", Arc::new(UInt32Array::from(prompt)) as ArrayRef),
+            ("completion_tokens", Arc::new(UInt32Array::from(completion)) as ArrayRef),
+        ])?)
+    }
+
+    /// One row per tool invocation.
+    pub fn tool_calls(events: &[UsageEvent]) -> Result<RecordBatch> {
+        let (mut ts, mut channel, mut chat, mut tool) = (vec![], vec![], vec![], vec![]);
+        let (mut latency, mut success) = (vec![], vec![]);
+        for event in events {
+            if let UsageEvent::ToolCall {
+                timestamp,
+                channel: ch,
+                chat_id,
+                tool: t,
+                latency_ms,
+                success: ok,
+            } = event
+            {
+                ts.push(timestamp.timestamp_millis());
+                channel.push(ch.as_str());
+                chat.push(chat_id.as_str());
+                tool.push(t.as_str());
+                latency.push(*latency_ms);
+                success.push(*ok);
+            }
+        }
+        Ok(RecordBatch::try_from_iter(vec![
+            ("timestamp", timestamps(ts)),
+            ("channel", strings(channel)),
+            ("chat_id", strings(chat)),
+            ("tool", strings(tool)),
+            ("latency_ms", Arc::new(UInt64Array::from(latency)) as ArrayRef),
+            ("success", Arc::new(BooleanArray::from(success)) as ArrayRef),
+        ])?)
+    }
+
+    /// Write a record batch to a Parquet file.
+    pub fn write(path: &Path, batch: &RecordBatch) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn tool(name: &str, latency_ms: u64, success: bool) -> UsageEvent {
+        UsageEvent::ToolCall {
+            timestamp: Utc::now(),
+            channel: "cli".into(),
+            chat_id: "direct".into(),
+            tool: name.into(),
+            latency_ms,
+            success,
+        }
+    }
+
+    fn llm(channel: &str, model: &str, prompt: u32, completion: u32) -> UsageEvent {
+        UsageEvent::LlmCall {
+            timestamp: Utc::now(),
+            channel: channel.into(),
+            chat_id: "1".into(),
+            model: model.into(),
+            latency_ms: 100,
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+        }
+    }
+
+    #[test]
+    fn test_top_tools() {
+        let events = vec![
+            tool("read_file", 10, true),
+            tool("exec", 100, true),
+            tool("exec", 300, false),
+        ];
+        let rows = top_tools(&events);
+        assert_eq!(rows[0].0, "exec");
+        assert_eq!(
+            rows[0].1,
+            ToolStats { calls: 2, failures: 1, total_latency_ms: 400 }
+        );
+        assert_eq!(rows[1].0, "read_file");
+    }
+
+    #[test]
+    fn test_cost_by_channel() {
+        let mut prices = HashMap::new();
+        prices.insert("gpt-4o".to_string(), ModelPrice { input: 2.5, output: 10.0 });
+        let events = vec![
+            llm("slack", "openai/gpt-4o", 1_000_000, 100_000),
+            llm("slack", "openai/gpt-4o", 0, 0),
+            llm("telegram", "unknown-model", 500, 50),
+        ];
+        let rows = cost_by_channel(&events, &prices);
+        let slack = &rows["slack"];
+        assert_eq!(slack.llm_calls, 2);
+        assert_eq!(slack.prompt_tokens, 1_000_000);
+        assert!((slack.cost.unwrap() - 3.5).abs() < 1e-9);
+        assert_eq!(rows["telegram"].cost, None);
+    }
+
+    #[test]
+    fn test_messages_by_day() {
+        let message = |day: u32, latency_ms: u64| UsageEvent::Message {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            channel: "cli".into(),
+            chat_id: "direct".into(),
+            latency_ms,
+        };
+        let mut events: Vec<UsageEvent> = (1..=20).map(|i| message(1, i * 10)).collect();
+        events.push(message(2, 50));
+
+        let rows = messages_by_day(&events);
+        let day1 = rows[&NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()];
+        assert_eq!(day1, (20, 105, 190));
+        assert_eq!(rows[&NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()], (1, 50, 50));
+    }
+
+    #[cfg(feature = "analytics")]
+    #[test]
+    fn test_export_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let events = vec![tool("exec", 10, true), llm("cli", "gpt-4o", 10, 5)];
+        export_parquet(&events, dir.path()).unwrap();
+        for name in ["messages.parquet", "llm_calls.parquet", "tool_calls.parquet"] {
+            let bytes = std::fs::read(dir.path().join(name)).unwrap();
+            assert_eq!(&bytes[..4], b"PAR1");
+        }
+    }
+}
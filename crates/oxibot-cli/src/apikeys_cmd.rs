@@ -0,0 +1,101 @@
+//! `oxibot apikeys` — manage keys for the gateway HTTP API.
+//!
+//! - `oxibot apikeys list` — list keys (never the keys themselves)
+//! - `oxibot apikeys create NAME [--scope chat|admin]` — create a key and print it once
+//! - `oxibot apikeys revoke ID` — delete a key
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_core::auth::{ApiKeyStore, Scope};
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// API key subcommands.
+#[derive(Subcommand)]
+pub enum ApiKeysCommands {
+    /// List API keys
+    List,
+
+    /// Create an API key
+    Create {
+        /// What the key is for
+        name: String,
+
+        /// What the key may do: chat (talk to the agent) or admin (everything)
+        #[arg(short, long, default_value = "chat")]
+        scope: String,
+    },
+
+    /// Revoke an API key
+    Revoke {
+        /// Key id (from `oxibot apikeys list`)
+        id: String,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch an apikeys subcommand.
+pub fn dispatch(cmd: ApiKeysCommands) -> Result<()> {
+    let mut store = ApiKeyStore::load(None);
+    match cmd {
+        ApiKeysCommands::List => list_keys(&store),
+        ApiKeysCommands::Create { name, scope } => {
+            let Some(scope) = Scope::parse(&scope) else {
+                bail!("unknown scope '{scope}' (expected chat or admin)");
+            };
+            let (key, token) = store.create(&name, scope);
+            save(&store)?;
+            println!("  {} Created {} key {} ({})", "✓".green(), scope, key.id.cyan(), name);
+            println!();
+            println!("  {}", token.bold());
+            println!();
+            println!("  {}", "Store it now — it can't be shown again.".dimmed());
+            Ok(())
+        }
+        ApiKeysCommands::Revoke { id } => {
+            if store.revoke(&id) {
+                save(&store)?;
+                println!("  {} Revoked key {}", "✓".green(), id.cyan());
+            } else {
+                println!("  {} Key {} not found", "✗".red(), id);
+            }
+            Ok(())
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+fn save(store: &ApiKeyStore) -> Result<()> {
+    store.save()
+        .with_context(|| format!("failed to write {}", store.path().display()))
+}
+
+/// `oxibot apikeys list`
+fn list_keys(store: &ApiKeyStore) -> Result<()> {
+    if store.keys().is_empty() {
+        println!("  No API keys. Create one with `oxibot apikeys create NAME`.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  API keys".cyan().bold());
+    println!();
+    println!("  {:<10} {:<8} {:<26} {}", "Id".bold(), "Scope".bold(), "Created".bold(), "Name".bold());
+    println!("  {}", "─".repeat(64));
+    for key in store.keys() {
+        let created = key.created_at.get(..19).unwrap_or(&key.created_at).replace('T', " ");
+        println!("  {:<10} {:<8} {:<26} {}", key.id, key.scope.to_string(), created.dimmed(), key.name);
+    }
+    println!();
+    Ok(())
+}
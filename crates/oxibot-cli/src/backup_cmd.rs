@@ -0,0 +1,684 @@
+//! `oxibot backup` — move oxibot's state between machines.
+//!
+//! - `oxibot backup create [--output FILE] [--no-secrets]` — write config,
+//!   sessions, memory, skills and cron jobs to a `.tar.gz` archive
+//! - `oxibot backup restore FILE [--overwrite] [--dry-run]` — check the
+//!   archive, then merge it into the current state
+//!
+//! The archive has the same layout wherever oxibot keeps its files:
+//!
+//! ```text
+//! manifest.json      format version, creation time, whether secrets are in
+//! config.json
+//! sessions/…         ~/.oxibot/sessions
+//! memory/…           <workspace>/memory
+//! skills/…           <workspace>/skills
+//! cron/jobs.json
+//! ```
+//!
+//! Restoring never loses local data unless asked to: missing files are
+//! added, memory files and cron jobs are merged, and any other file that
+//! differs locally is kept (and reported) unless `--overwrite` is given.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use oxibot_agent::memory::merge_memory;
+use oxibot_core::config::{get_config_path, load_config};
+use oxibot_core::utils::{get_data_path, get_sessions_path};
+use oxibot_cron::CronStore;
+
+/// Version of the backup format.
+const BACKUP_VERSION: u32 = 1;
+
+/// Name of the manifest inside the archive.
+const MANIFEST: &str = "manifest.json";
+
+/// Archive entries: name → contents.
+type Files = Vec<(String, Vec<u8>)>;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Backup subcommands.
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// Archive config, sessions, memory, skills and cron jobs
+    Create {
+        /// Archive to write (default: ./oxibot-backup-<date>.tar.gz)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Blank out API keys, tokens and passwords in the config
+        #[arg(long, default_value_t = false)]
+        no_secrets: bool,
+    },
+
+    /// Restore a backup, merging it into the current state
+    Restore {
+        /// Archive to restore
+        file: PathBuf,
+
+        /// Replace local files that differ from the backup
+        #[arg(long, default_value_t = false)]
+        overwrite: bool,
+
+        /// Show what would change without writing anything
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a backup subcommand.
+pub fn dispatch(cmd: BackupCommands) -> Result<()> {
+    let layout = Layout::current();
+    match cmd {
+        BackupCommands::Create { output, no_secrets } => {
+            let output = output.unwrap_or_else(|| {
+                PathBuf::from(format!("oxibot-backup-{}.tar.gz", Utc::now().format("%Y-%m-%d")))
+            });
+            let manifest = create(&layout, &output, !no_secrets)?;
+            println!(
+                "  {} Backed up {} files to {}",
+                "✓".green(),
+                manifest.files,
+                output.display()
+            );
+            if !manifest.secrets {
+                println!("  Secrets were left out; restoring keeps the ones already configured.");
+            }
+            Ok(())
+        }
+        BackupCommands::Restore { file, overwrite, dry_run } => {
+            let report = restore(&layout, &file, overwrite, dry_run)?;
+            print_report(&report, dry_run);
+            Ok(())
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Layout
+// ─────────────────────────────────────────────
+
+/// Where each part of the archive lives on this machine.
+struct Layout {
+    config: PathBuf,
+    sessions: PathBuf,
+    workspace: PathBuf,
+    cron: PathBuf,
+}
+
+impl Layout {
+    /// The paths the current config uses.
+    fn current() -> Self {
+        let config = load_config(None);
+        Self {
+            config: get_config_path(),
+            sessions: get_sessions_path(),
+            workspace: crate::helpers::expand_tilde(&config.agents.defaults.workspace),
+            cron: get_data_path().join("cron").join("jobs.json"),
+        }
+    }
+
+    /// Archive directories and the local directories they hold.
+    fn dirs(&self) -> [(&'static str, PathBuf); 3] {
+        [
+            ("sessions", self.sessions.clone()),
+            ("memory", self.workspace.join("memory")),
+            ("skills", self.workspace.join("skills")),
+        ]
+    }
+
+    /// Local path for an archive entry, or `None` if it isn't part of a backup.
+    fn target(&self, name: &str) -> Option<PathBuf> {
+        match name {
+            "config.json" => return Some(self.config.clone()),
+            "cron/jobs.json" => return Some(self.cron.clone()),
+            _ => {}
+        }
+        let (dir, rest) = name.split_once('/')?;
+        let (_, local) = self.dirs().into_iter().find(|(d, _)| *d == dir)?;
+        Some(local.join(rest))
+    }
+}
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u32,
+    created_at: DateTime<Utc>,
+    oxibot_version: String,
+    /// Whether the config still has its secrets.
+    secrets: bool,
+    files: usize,
+}
+
+// ─────────────────────────────────────────────
+// Create
+// ─────────────────────────────────────────────
+
+/// Write a backup of `layout` to `output`.
+fn create(layout: &Layout, output: &Path, secrets: bool) -> Result<Manifest> {
+    let mut files = Files::new();
+
+    if layout.config.exists() {
+        let text = std::fs::read_to_string(&layout.config)
+            .with_context(|| format!("failed to read {}", layout.config.display()))?;
+        let text = if secrets {
+            text
+        } else {
+            let mut config: Value = serde_json::from_str(&text).context("config is not valid JSON")?;
+            strip_secrets(&mut config);
+            serde_json::to_string_pretty(&config)?
+        };
+        files.push(("config.json".into(), text.into_bytes()));
+    }
+    if layout.cron.exists() {
+        files.push(("cron/jobs.json".into(), read(&layout.cron)?));
+    }
+    for (name, dir) in layout.dirs() {
+        for path in walk(&dir) {
+            let relative = path.strip_prefix(&dir).unwrap_or(&path);
+            let relative: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push((format!("{name}/{}", relative.join("/")), read(&path)?));
+        }
+    }
+
+    let manifest = Manifest {
+        version: BACKUP_VERSION,
+        created_at: Utc::now(),
+        oxibot_version: env!("CARGO_PKG_VERSION").into(),
+        secrets,
+        files: files.len(),
+    };
+
+    // The archive may hold provider keys and tokens: owner-only, like the
+    // OAuth token store
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(output).with_context(|| format!("failed to create {}", output.display()))?;
+    #[cfg(unix)]
+    {
+        // An existing file keeps its mode when opened
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append(&mut archive, MANIFEST, &serde_json::to_vec_pretty(&manifest)?)?;
+    for (name, data) in &files {
+        append(&mut archive, name, data)?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Add a file to the archive.
+fn append<W: std::io::Write>(archive: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    archive
+        .append_data(&mut header, name, data)
+        .with_context(|| format!("failed to add {name} to the backup"))
+}
+
+/// Files under `dir`, recursively, in a stable order.
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .flat_map(|path| if path.is_dir() { walk(&path) } else { vec![path] })
+        .collect()
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Whether a config key holds a secret (API keys, tokens, passwords,
+/// skill secrets, provider headers).
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    matches!(key.as_str(), "secrets" | "extraheaders" | "accesskeyid")
+        || ["key", "token", "secret", "password"].iter().any(|s| key.ends_with(s))
+}
+
+/// Blank out every secret in a config.
+fn strip_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if is_secret_key(key) => s.clear(),
+                    Value::Object(inner) if is_secret_key(key) => inner.clear(),
+                    _ => strip_secrets(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+/// Fill secrets that are blank in `incoming` from `existing`.
+fn keep_secrets(incoming: &mut Value, existing: &Value) {
+    let (Value::Object(incoming), Value::Object(existing)) = (incoming, existing) else {
+        return;
+    };
+    for (key, value) in incoming.iter_mut() {
+        let Some(current) = existing.get(key) else { continue };
+        let blank = match value {
+            Value::String(s) => s.is_empty(),
+            Value::Object(map) => map.is_empty(),
+            _ => false,
+        };
+        if is_secret_key(key) && blank {
+            *value = current.clone();
+        } else {
+            keep_secrets(value, current);
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Restore
+// ─────────────────────────────────────────────
+
+/// What restoring did to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Action {
+    /// Didn't exist locally.
+    Added,
+    /// Combined with the local copy.
+    Merged,
+    /// Replaced the local copy (`--overwrite`).
+    Replaced,
+    /// Same as the local copy.
+    Unchanged,
+    /// Differs from the local copy, which was kept.
+    Kept,
+}
+
+/// Result of a restore: the action taken for each archive entry.
+#[derive(Debug)]
+struct Report {
+    manifest: Manifest,
+    files: BTreeMap<String, Action>,
+}
+
+/// Check the archive at `file`, then merge it into `layout`.
+///
+/// Nothing is written if the archive is invalid.
+fn restore(layout: &Layout, file: &Path, overwrite: bool, dry_run: bool) -> Result<Report> {
+    let (manifest, files) = read_archive(layout, file)?;
+
+    let mut report = BTreeMap::new();
+    for (name, incoming) in files {
+        let target = layout.target(&name).expect("validated");
+        let existing = std::fs::read(&target).ok();
+        let (action, data) = match existing {
+            None => (Action::Added, incoming),
+            Some(existing) if existing == incoming => (Action::Unchanged, existing),
+            Some(existing) => merge(&name, existing, incoming, overwrite)?,
+        };
+        if matches!(action, Action::Added | Action::Merged | Action::Replaced) && !dry_run {
+            if name == "config.json" && target.exists() {
+                std::fs::copy(&target, target.with_extension("json.bak"))?;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&target, data).with_context(|| format!("failed to write {}", target.display()))?;
+        }
+        report.insert(name, action);
+    }
+    Ok(Report { manifest, files: report })
+}
+
+/// Read and check every entry of an archive before anything is restored.
+fn read_archive(layout: &Layout, file: &Path) -> Result<(Manifest, Files)> {
+    let reader = std::fs::File::open(file).with_context(|| format!("failed to open {}", file.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+
+    let mut manifest = None;
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in archive.entries().context("not an oxibot backup")? {
+        let mut entry = entry.context("backup is corrupt")?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        if !entry.header().entry_type().is_file() {
+            bail!("invalid backup: {} is not a regular file", entry.path()?.display());
+        }
+        let path = entry.path()?.into_owned();
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            bail!("invalid backup: unsafe path {}", path.display());
+        }
+        let name = path.to_string_lossy().replace('\\', "/");
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .with_context(|| format!("backup is corrupt at {name}"))?;
+
+        if name == MANIFEST {
+            manifest = Some(serde_json::from_slice::<Manifest>(&data).context("invalid backup manifest")?);
+            continue;
+        }
+        if layout.target(&name).is_none() {
+            bail!("invalid backup: unexpected file {name}");
+        }
+        if !seen.insert(name.clone()) {
+            bail!("invalid backup: {name} appears twice");
+        }
+        files.push((name, data));
+    }
+
+    let manifest = manifest.context("not an oxibot backup (no manifest.json)")?;
+    if manifest.version > BACKUP_VERSION {
+        bail!(
+            "backup format {} is newer than this oxibot supports ({BACKUP_VERSION}); upgrade oxibot first",
+            manifest.version
+        );
+    }
+    if manifest.files != files.len() {
+        bail!("backup is incomplete: {} of {} files", files.len(), manifest.files);
+    }
+    for (name, data) in &files {
+        match name.as_str() {
+            "config.json" => {
+                serde_json::from_slice::<Value>(data).context("invalid backup: config.json is not valid JSON")?;
+            }
+            "cron/jobs.json" => {
+                serde_json::from_slice::<CronStore>(data).context("invalid backup: cron/jobs.json is not a job list")?;
+            }
+            _ => {}
+        }
+    }
+    Ok((manifest, files))
+}
+
+/// Combine a backed-up file with a different local copy.
+fn merge(name: &str, existing: Vec<u8>, incoming: Vec<u8>, overwrite: bool) -> Result<(Action, Vec<u8>)> {
+    let is_memory_note = name
+        .strip_prefix("memory/")
+        .is_some_and(|rest| !rest.contains('/') && rest.ends_with(".md"));
+
+    if is_memory_note {
+        let (Ok(existing), Ok(incoming)) = (String::from_utf8(existing.clone()), String::from_utf8(incoming)) else {
+            return Ok((Action::Kept, existing));
+        };
+        let merged = merge_memory(&existing, &incoming);
+        return Ok(if merged == existing {
+            (Action::Unchanged, existing.into_bytes())
+        } else {
+            (Action::Merged, merged.into_bytes())
+        });
+    }
+
+    if name == "cron/jobs.json" {
+        let Ok(mut store) = serde_json::from_slice::<CronStore>(&existing) else {
+            return Ok(if overwrite { (Action::Replaced, incoming) } else { (Action::Kept, existing) });
+        };
+        let backup: CronStore = serde_json::from_slice(&incoming)?;
+        let ids: HashSet<String> = store.jobs.iter().map(|job| job.id.clone()).collect();
+        let before = store.jobs.len();
+        store.jobs.extend(backup.jobs.into_iter().filter(|job| !ids.contains(&job.id)));
+        return Ok(if store.jobs.len() == before {
+            (Action::Unchanged, existing)
+        } else {
+            (Action::Merged, serde_json::to_vec_pretty(&store)?)
+        });
+    }
+
+    if !overwrite {
+        return Ok((Action::Kept, existing));
+    }
+    if name == "config.json" {
+        // A backup made with --no-secrets keeps the local ones
+        if let Ok(current) = serde_json::from_slice::<Value>(&existing) {
+            let mut config: Value = serde_json::from_slice(&incoming)?;
+            keep_secrets(&mut config, &current);
+            return Ok((Action::Replaced, serde_json::to_vec_pretty(&config)?));
+        }
+    }
+    Ok((Action::Replaced, incoming))
+}
+
+fn print_report(report: &Report, dry_run: bool) {
+    let mut counts: BTreeMap<Action, usize> = BTreeMap::new();
+    for action in report.files.values() {
+        *counts.entry(*action).or_default() += 1;
+    }
+    let count = |action| counts.get(&action).copied().unwrap_or(0);
+
+    println!(
+        "  Backup from {} (oxibot {})",
+        report.manifest.created_at.format("%Y-%m-%d %H:%M UTC"),
+        report.manifest.oxibot_version
+    );
+    let verb = if dry_run { "Would restore" } else { "Restored" };
+    println!(
+        "  {} {verb}: {} added, {} merged, {} replaced, {} unchanged",
+        "✓".green(),
+        count(Action::Added),
+        count(Action::Merged),
+        count(Action::Replaced),
+        count(Action::Unchanged)
+    );
+    let kept: Vec<&String> = report
+        .files
+        .iter()
+        .filter(|(_, action)| **action == Action::Kept)
+        .map(|(name, _)| name)
+        .collect();
+    if !kept.is_empty() {
+        println!("  {} Kept {} local files that differ from the backup:", "!".yellow(), kept.len());
+        for name in kept {
+            println!("    {name}");
+        }
+        println!("  Run again with --overwrite to replace them.");
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(root: &Path) -> Layout {
+        Layout {
+            config: root.join("config.json"),
+            sessions: root.join("sessions"),
+            workspace: root.join("workspace"),
+            cron: root.join("cron").join("jobs.json"),
+        }
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn jobs(ids: &[&str]) -> String {
+        let jobs: Vec<Value> = ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({
+                    "id": id,
+                    "name": id,
+                    "schedule": {"kind": "every", "everyMs": 60000},
+                    "payload": {"kind": "agent_turn", "message": "ping"},
+                })
+            })
+            .collect();
+        serde_json::json!({"version": 1, "jobs": jobs}).to_string()
+    }
+
+    fn populate(layout: &Layout) {
+        write(
+            &layout.config,
+            r#"{"providers": {"openai": {"apiKey": "sk-1"}}, "agents": {"defaults": {"maxTokens": 10}}}"#,
+        );
+        write(&layout.sessions.join("telegram_1.jsonl"), "{\"_type\":\"metadata\"}\n");
+        write(&layout.workspace.join("memory/MEMORY.md"), "## Prefs\n\n- likes tea\n");
+        write(&layout.workspace.join("skills/notes/SKILL.md"), "# notes\n");
+        write(&layout.cron, &jobs(&["a"]));
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = layout(&dir.path().join("old"));
+        populate(&source);
+        let archive = dir.path().join("backup.tar.gz");
+        let manifest = create(&source, &archive, true).unwrap();
+        assert_eq!(manifest.files, 5);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&archive).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let target = layout(&dir.path().join("new"));
+        let report = restore(&target, &archive, false, false).unwrap();
+        assert!(report.files.values().all(|action| *action == Action::Added));
+        for path in [&target.config, &target.cron] {
+            assert!(path.exists());
+        }
+        assert_eq!(
+            std::fs::read_to_string(target.workspace.join("skills/notes/SKILL.md")).unwrap(),
+            "# notes\n"
+        );
+        assert!(target.sessions.join("telegram_1.jsonl").exists());
+
+        // Restoring again changes nothing
+        let report = restore(&target, &archive, false, false).unwrap();
+        assert!(report.files.values().all(|action| *action == Action::Unchanged));
+    }
+
+    #[test]
+    fn test_restore_merges_and_keeps_local_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = layout(&dir.path().join("old"));
+        populate(&source);
+        let archive = dir.path().join("backup.tar.gz");
+        create(&source, &archive, false).unwrap();
+
+        let target = layout(&dir.path().join("new"));
+        write(&target.config, r#"{"providers": {"openai": {"apiKey": "sk-local"}}}"#);
+        write(&target.workspace.join("memory/MEMORY.md"), "## Prefs\n\n- works remotely\n");
+        write(&target.workspace.join("skills/notes/SKILL.md"), "# my notes\n");
+        write(&target.cron, &jobs(&["b"]));
+
+        let report = restore(&target, &archive, false, true).unwrap();
+        assert_eq!(report.files["config.json"], Action::Kept);
+        assert_eq!(report.files["memory/MEMORY.md"], Action::Merged);
+        // A dry run writes nothing
+        assert_eq!(
+            std::fs::read_to_string(target.workspace.join("memory/MEMORY.md")).unwrap(),
+            "## Prefs\n\n- works remotely\n"
+        );
+
+        let report = restore(&target, &archive, false, false).unwrap();
+        assert_eq!(report.files["skills/notes/SKILL.md"], Action::Kept);
+        assert_eq!(report.files["sessions/telegram_1.jsonl"], Action::Added);
+        let memory = std::fs::read_to_string(target.workspace.join("memory/MEMORY.md")).unwrap();
+        assert!(memory.contains("- works remotely") && memory.contains("- likes tea"));
+        let store: CronStore = serde_json::from_str(&std::fs::read_to_string(&target.cron).unwrap()).unwrap();
+        let ids: Vec<&str> = store.jobs.iter().map(|job| job.id.as_str()).collect();
+        assert_eq!(ids, ["b", "a"]);
+
+        // Overwriting with a backup without secrets keeps the local key
+        let report = restore(&target, &archive, true, false).unwrap();
+        assert_eq!(report.files["config.json"], Action::Replaced);
+        assert_eq!(report.files["skills/notes/SKILL.md"], Action::Replaced);
+        let config: Value = serde_json::from_str(&std::fs::read_to_string(&target.config).unwrap()).unwrap();
+        assert_eq!(config["providers"]["openai"]["apiKey"], "sk-local");
+        assert_eq!(config["agents"]["defaults"]["maxTokens"], 10);
+        assert!(target.config.with_extension("json.bak").exists());
+    }
+
+    #[test]
+    fn test_strip_secrets() {
+        let mut config = serde_json::json!({
+            "providers": {"openai": {"apiKey": "sk-1", "extraHeaders": {"X-Auth": "t"}}},
+            "channels": {"slack": {"botToken": "xoxb", "appToken": "xapp", "groupPolicy": "mention"}},
+            "agents": {"defaults": {"maxTokens": 10, "maxContextTokens": 0, "tokenizer": "auto"}},
+            "skills": {"secrets": {"github": {"GH_TOKEN": "ghp"}}},
+        });
+        strip_secrets(&mut config);
+        assert_eq!(config["providers"]["openai"]["apiKey"], "");
+        assert_eq!(config["providers"]["openai"]["extraHeaders"], serde_json::json!({}));
+        assert_eq!(config["channels"]["slack"]["botToken"], "");
+        assert_eq!(config["channels"]["slack"]["groupPolicy"], "mention");
+        assert_eq!(config["agents"]["defaults"]["maxTokens"], 10);
+        assert_eq!(config["agents"]["defaults"]["tokenizer"], "auto");
+        assert_eq!(config["skills"]["secrets"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_restore_rejects_foreign_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = layout(&dir.path().join("new"));
+        let archive = dir.path().join("other.tar.gz");
+        let build = |files: &[(&str, &str)]| {
+            let file = std::fs::File::create(&archive).unwrap();
+            let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+            for (name, data) in files {
+                append(&mut builder, name, data.as_bytes()).unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        };
+
+        build(&[("sessions/a.jsonl", "{}")]);
+        assert!(restore(&target, &archive, false, false).is_err());
+
+        let manifest = serde_json::json!({
+            "version": 1, "createdAt": Utc::now(), "oxibotVersion": "0.1.0", "secrets": true, "files": 1
+        })
+        .to_string();
+        build(&[(MANIFEST, &manifest), ("etc/passwd", "root")]);
+        let err = restore(&target, &archive, false, false).unwrap_err();
+        assert!(err.to_string().contains("unexpected file etc/passwd"));
+
+        build(&[(MANIFEST, &manifest), ("cron/jobs.json", "not json")]);
+        assert!(restore(&target, &archive, false, false).is_err());
+        assert!(!target.cron.exists());
+    }
+}
@@ -0,0 +1,478 @@
+//! `oxibot batch` — run many prompts through the agent non-interactively.
+//!
+//! Input is JSONL, one `{"id": ..., "prompt": ...}` object per line (`id`
+//! defaults to the line number). Each prompt runs in its own session
+//! `batch:<id>`, cleared beforehand so reruns start fresh. Results are
+//! written as they finish, one `{"id", "response"}` or `{"id", "error"}`
+//! object per line with `durationMs`, so they may come out of input order.
+//!
+//! `--parallelism` agent loops share one provider, so concurrent prompts
+//! reuse its HTTP connection pool.
+//!
+//! For offline jobs that need no tools, the provider batch API is half the
+//! price and returns within 24 hours:
+//!
+//! - `oxibot batch submit -i FILE [--deliver CHANNEL:CHAT]` — submit the prompts
+//! - `oxibot batch status [ID]` — progress of submitted jobs
+//! - `oxibot batch fetch ID [-o FILE]` — results in the same JSONL format
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use oxibot_agent::AgentLoop;
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::config::load_config;
+use oxibot_core::session::SessionManager;
+use oxibot_providers::batch::{BatchRequest, BatchState};
+use oxibot_providers::LlmProvider;
+
+use crate::batch_jobs::{self, BatchJob, BatchJobs};
+
+/// Channel of batch sessions (`batch:<id>`).
+const BATCH_CHANNEL: &str = "batch";
+
+/// One prompt to run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchItem {
+    pub id: String,
+    pub prompt: String,
+}
+
+/// The result of one prompt.
+#[derive(Debug)]
+struct BatchOutcome {
+    id: String,
+    result: Result<String, String>,
+    duration: Duration,
+}
+
+impl BatchOutcome {
+    /// The output line for this result.
+    fn to_json(&self) -> Value {
+        let mut line = json!({
+            "id": self.id,
+            "durationMs": self.duration.as_millis() as u64,
+        });
+        match self.result {
+            Ok(ref response) => line["response"] = json!(response),
+            Err(ref error) => line["error"] = json!(error),
+        }
+        line
+    }
+}
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Provider batch API subcommands.
+#[derive(Subcommand)]
+pub enum BatchCommands {
+    /// Submit prompts to the provider's batch API (half price, done within 24h)
+    Submit {
+        /// Input JSONL file, one {"id", "prompt"} object per line ("-" for stdin)
+        #[arg(short, long)]
+        input: String,
+
+        /// Model to run (default: agents.defaults.model); OpenAI or Anthropic
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// System prompt sent with every prompt
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Maximum tokens per answer (default: agents.defaults.maxTokens)
+        #[arg(long)]
+        max_tokens: Option<u32>,
+
+        /// Post the results to CHANNEL:CHAT_ID when done (needs a running gateway)
+        #[arg(long)]
+        deliver: Option<String>,
+    },
+
+    /// Show the progress of submitted jobs
+    Status {
+        /// Job id (default: all jobs)
+        id: Option<String>,
+    },
+
+    /// Download the results of a finished job
+    Fetch {
+        /// Job id
+        id: String,
+
+        /// Output JSONL file ("-" for stdout)
+        #[arg(short, long, default_value = "-")]
+        output: String,
+    },
+}
+
+/// Dispatch a batch API subcommand.
+pub async fn dispatch(cmd: BatchCommands) -> Result<()> {
+    let jobs = BatchJobs::new(None);
+    match cmd {
+        BatchCommands::Submit {
+            input,
+            model,
+            system,
+            max_tokens,
+            deliver,
+        } => submit(&jobs, &input, model, system, max_tokens, deliver).await,
+        BatchCommands::Status { id } => status(&jobs, id.as_deref()).await,
+        BatchCommands::Fetch { id, output } => fetch(&jobs, &id, &output).await,
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command
+// ─────────────────────────────────────────────
+
+/// Run every prompt in `input` and write the results to `output`.
+pub async fn run(input: &str, output: &str, parallelism: usize, dry_run: bool) -> Result<()> {
+    let items = parse_items(&read_input(input)?)?;
+    if items.is_empty() {
+        eprintln!("  No prompts in {input}");
+        return Ok(());
+    }
+
+    let mut config = load_config(None);
+    config.tools.dry_run |= dry_run;
+    let provider: Arc<dyn LlmProvider> = Arc::new(crate::default_provider(&config)?);
+    let parallelism = parallelism.clamp(1, items.len());
+    let agents = (0..parallelism)
+        .map(|_| crate::build_agent_loop_with(&config, provider.clone()).map(Arc::new))
+        .collect::<Result<Vec<_>>>()?;
+
+    let sessions = SessionManager::new(None).context("failed to create session manager")?;
+    for item in &items {
+        sessions.delete(&format!("{BATCH_CHANNEL}:{}", item.id));
+    }
+
+    let mut writer: Box<dyn Write> = if output == "-" {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(std::fs::File::create(output).with_context(|| format!("failed to create {output}"))?)
+    };
+
+    let total = items.len();
+    eprintln!("  Running {total} prompts, {parallelism} at a time");
+    let started = Instant::now();
+    let mut results = run_pool(agents, items, |agent: Arc<AgentLoop>, item: BatchItem| async move {
+        let msg = InboundMessage::new(BATCH_CHANNEL, "batch", &item.id, &item.prompt);
+        Ok(agent.process_message(&msg).await?.content)
+    });
+
+    let (mut done, mut failed) = (0, 0);
+    while let Some(outcome) = results.recv().await {
+        writeln!(writer, "{}", outcome.to_json())?;
+        writer.flush()?;
+        done += 1;
+        let secs = format!("({:.1}s)", outcome.duration.as_secs_f64());
+        match outcome.result {
+            Ok(_) => eprintln!("  [{done}/{total}] {} {} {}", "✓".green(), outcome.id, secs.dimmed()),
+            Err(ref e) => {
+                failed += 1;
+                eprintln!("  [{done}/{total}] {} {} {}: {e}", "✗".red(), outcome.id, secs.dimmed());
+            }
+        }
+    }
+
+    eprintln!(
+        "  {} succeeded, {failed} failed in {:.1}s",
+        done - failed,
+        started.elapsed().as_secs_f64()
+    );
+    if done < total {
+        anyhow::bail!("{} of {total} prompts did not finish", total - done);
+    }
+    if failed > 0 {
+        anyhow::bail!("{failed} of {total} prompts failed");
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// Provider batch API
+// ─────────────────────────────────────────────
+
+/// `oxibot batch submit`
+async fn submit(
+    jobs: &BatchJobs,
+    input: &str,
+    model: Option<String>,
+    system: Option<String>,
+    max_tokens: Option<u32>,
+    deliver: Option<String>,
+) -> Result<()> {
+    if deliver.as_deref().is_some_and(|d| !d.contains(':')) {
+        anyhow::bail!("--deliver must be CHANNEL:CHAT_ID, e.g. telegram:123456");
+    }
+    let items = parse_items(&read_input(input)?)?;
+    if items.is_empty() {
+        eprintln!("  No prompts in {input}");
+        return Ok(());
+    }
+
+    let config = load_config(None);
+    let model = model.unwrap_or_else(|| config.agents.defaults.model.clone());
+    let provider = batch_jobs::provider_for(&config, &model)?;
+    let max_tokens = max_tokens.unwrap_or(config.agents.defaults.max_tokens);
+    let requests: Vec<BatchRequest> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| BatchRequest {
+            custom_id: BatchJob::custom_id(index),
+            system: system.clone(),
+            prompt: item.prompt.clone(),
+            max_tokens,
+        })
+        .collect();
+
+    let id = provider.submit(&model, &requests).await?;
+    jobs.save(BatchJob {
+        id: id.clone(),
+        model,
+        submitted_at: oxibot_core::utils::timestamp(),
+        ids: items.into_iter().map(|item| item.id).collect(),
+        deliver: deliver.clone(),
+        delivered: false,
+    })?;
+
+    println!(
+        "  {} Submitted {} prompts to {} as {}",
+        "✓".green(),
+        requests.len(),
+        provider.display_name(),
+        id.cyan()
+    );
+    match deliver {
+        Some(target) => println!("  The gateway will post the results to {target} when the job ends."),
+        None => println!("  Check with `oxibot batch status {id}`, then `oxibot batch fetch {id}`."),
+    }
+    Ok(())
+}
+
+/// `oxibot batch status`
+async fn status(jobs: &BatchJobs, id: Option<&str>) -> Result<()> {
+    let selected: Vec<BatchJob> = match id {
+        Some(id) => vec![jobs.get(id).with_context(|| format!("unknown batch job '{id}'"))?],
+        None => jobs.load(),
+    };
+    if selected.is_empty() {
+        println!("  No batch jobs. Submit one with `oxibot batch submit --input FILE`.");
+        return Ok(());
+    }
+
+    let config = load_config(None);
+    for job in selected {
+        let status = match batch_jobs::provider_for(&config, &job.model) {
+            Ok(provider) => provider.status(&job.id).await,
+            Err(e) => Err(e),
+        };
+        let submitted = job.submitted_at.get(..16).unwrap_or(&job.submitted_at).replace('T', " ");
+        match status {
+            Ok(status) => {
+                let state = match status.state {
+                    BatchState::InProgress => status.detail.yellow(),
+                    BatchState::Ended => status.detail.green(),
+                    BatchState::Failed => status.detail.red(),
+                };
+                println!(
+                    "  {} {state} — {}/{} done, {} failed ({}, {})",
+                    job.id.cyan(),
+                    status.succeeded + status.failed,
+                    status.total,
+                    status.failed,
+                    job.model,
+                    submitted.dimmed()
+                );
+            }
+            Err(e) => println!("  {} {} {e:#}", job.id.cyan(), "✗".red()),
+        }
+    }
+    Ok(())
+}
+
+/// `oxibot batch fetch`
+async fn fetch(jobs: &BatchJobs, id: &str, output: &str) -> Result<()> {
+    let job = jobs.get(id).with_context(|| format!("unknown batch job '{id}'"))?;
+    let config = load_config(None);
+    let provider = batch_jobs::provider_for(&config, &job.model)?;
+    let status = provider.status(id).await?;
+    match status.state {
+        BatchState::InProgress => anyhow::bail!(
+            "batch {id} is still {} ({}/{} done)",
+            status.detail,
+            status.succeeded + status.failed,
+            status.total
+        ),
+        BatchState::Failed => anyhow::bail!("batch {id} {}; it has no results", status.detail),
+        BatchState::Ended => {}
+    }
+
+    let lines = job.result_lines(&provider.results(id).await?);
+    let mut writer: Box<dyn Write> = if output == "-" {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(std::fs::File::create(output).with_context(|| format!("failed to create {output}"))?)
+    };
+    for line in &lines {
+        writeln!(writer, "{line}")?;
+    }
+    writer.flush()?;
+    let failed = lines.iter().filter(|l| l.get("error").is_some()).count();
+    eprintln!("  {} succeeded, {failed} failed", lines.len() - failed);
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────
+
+/// Read the input file, or stdin for `-`.
+fn read_input(input: &str) -> Result<String> {
+    if input == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).context("failed to read stdin")?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(input).with_context(|| format!("failed to read {input}"))
+    }
+}
+
+/// Parse the input JSONL, skipping blank lines.
+fn parse_items(text: &str) -> Result<Vec<BatchItem>> {
+    let mut items = Vec::new();
+    let mut ids = HashSet::new();
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value =
+            serde_json::from_str(line).with_context(|| format!("line {number}: invalid JSON"))?;
+        let prompt = value["prompt"]
+            .as_str()
+            .with_context(|| format!("line {number}: missing \"prompt\""))?;
+        let id = match value["id"] {
+            Value::Null => number.to_string(),
+            Value::String(ref id) => id.clone(),
+            Value::Number(ref id) => id.to_string(),
+            _ => anyhow::bail!("line {number}: \"id\" must be a string or number"),
+        };
+        if !ids.insert(id.clone()) {
+            anyhow::bail!("line {number}: duplicate id '{id}'");
+        }
+        items.push(BatchItem {
+            id,
+            prompt: prompt.to_string(),
+        });
+    }
+    Ok(items)
+}
+
+/// Process `items` with one task per worker; results arrive as they finish.
+fn run_pool<W, F, Fut>(workers: Vec<Arc<W>>, items: Vec<BatchItem>, process: F) -> mpsc::UnboundedReceiver<BatchOutcome>
+where
+    W: Send + Sync + 'static,
+    F: Fn(Arc<W>, BatchItem) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<String>> + Send,
+{
+    let queue = Arc::new(Mutex::new(items.into_iter()));
+    let (tx, rx) = mpsc::unbounded_channel();
+    for worker in workers {
+        let (queue, tx, process) = (queue.clone(), tx.clone(), process.clone());
+        tokio::spawn(async move {
+            loop {
+                let next = queue.lock().unwrap().next();
+                let Some(item) = next else { break };
+                let id = item.id.clone();
+                let started = Instant::now();
+                let result = process(worker.clone(), item).await.map_err(|e| format!("{e:#}"));
+                let outcome = BatchOutcome {
+                    id,
+                    result,
+                    duration: started.elapsed(),
+                };
+                if tx.send(outcome).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    rx
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_parse_items() {
+        let items = parse_items("{\"prompt\": \"hi\"}\n\n{\"id\": 7, \"prompt\": \"a\"}\n{\"id\": \"x\", \"prompt\": \"b\"}\n").unwrap();
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["1", "7", "x"]);
+        assert_eq!(items[0].prompt, "hi");
+
+        let err = parse_items("{\"prompt\": \"a\"}\n{\"id\": 1, \"prompt\": \"b\"}").unwrap_err();
+        assert!(err.to_string().contains("line 2: duplicate id '1'"));
+        assert!(parse_items("{\"id\": 1}").unwrap_err().to_string().contains("missing \"prompt\""));
+        assert!(parse_items("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_pool_limits_concurrency() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let items: Vec<BatchItem> = (0..10)
+            .map(|n| BatchItem {
+                id: n.to_string(),
+                prompt: if n == 3 { "fail".into() } else { format!("p{n}") },
+            })
+            .collect();
+        let workers = vec![Arc::new(()), Arc::new(())];
+
+        let (r, p) = (running.clone(), peak.clone());
+        let mut rx = run_pool(workers, items, move |_: Arc<()>, item: BatchItem| {
+            let (running, peak) = (r.clone(), p.clone());
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                if item.prompt == "fail" {
+                    anyhow::bail!("boom");
+                }
+                Ok(item.prompt.to_uppercase())
+            }
+        });
+
+        let mut outcomes = Vec::new();
+        while let Some(outcome) = rx.recv().await {
+            outcomes.push(outcome);
+        }
+        assert_eq!(outcomes.len(), 10);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+        let failed = outcomes.iter().find(|o| o.id == "3").unwrap();
+        assert_eq!(failed.to_json()["error"], "boom");
+        let ok = outcomes.iter().find(|o| o.id == "4").unwrap();
+        assert_eq!(ok.to_json()["response"], "P4");
+        assert!(ok.to_json().get("error").is_none());
+    }
+}
@@ -0,0 +1,245 @@
+//! Submitted provider batch jobs, remembered in `~/.oxibot/batches.json`.
+//!
+//! `oxibot batch submit` records each job with the ids of its prompts so
+//! `status` and `fetch` can map results back. Jobs submitted with
+//! `--deliver channel:chat_id` are watched by the gateway, which posts the
+//! results to that chat once the job has ended.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::OutboundMessage;
+use oxibot_core::config::Config;
+use oxibot_core::utils;
+use oxibot_providers::batch::{BatchResult, BatchState};
+use oxibot_providers::{create_batch_provider, BatchProvider};
+
+/// How often the gateway checks jobs waiting for delivery.
+const BATCH_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// A submitted job.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJob {
+    /// Provider's job id.
+    pub id: String,
+    pub model: String,
+    pub submitted_at: String,
+    /// Prompt ids, in input order; prompt `n` is sent as `item-<n>`.
+    pub ids: Vec<String>,
+    /// `channel:chat_id` to post the results to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deliver: Option<String>,
+    #[serde(default)]
+    pub delivered: bool,
+}
+
+impl BatchJob {
+    /// The provider-side id of prompt `index`.
+    pub fn custom_id(index: usize) -> String {
+        format!("item-{index}")
+    }
+
+    /// Index of the prompt a result's `custom_id` belongs to.
+    fn index(&self, custom_id: &str) -> Option<usize> {
+        let index: usize = custom_id.strip_prefix("item-")?.parse().ok()?;
+        (index < self.ids.len()).then_some(index)
+    }
+
+    /// One `{"id", "response"}` or `{"id", "error"}` object per result, in
+    /// input order.
+    pub fn result_lines(&self, results: &[BatchResult]) -> Vec<Value> {
+        let mut lines: Vec<(Option<usize>, Value)> = results
+            .iter()
+            .map(|r| {
+                let index = self.index(&r.custom_id);
+                let id = index.map_or(r.custom_id.as_str(), |i| self.ids[i].as_str());
+                let line = match r.result {
+                    Ok(ref response) => json!({"id": id, "response": response}),
+                    Err(ref error) => json!({"id": id, "error": error}),
+                };
+                (index, line)
+            })
+            .collect();
+        // Unknown ids last
+        lines.sort_by_key(|(index, _)| index.unwrap_or(usize::MAX));
+        lines.into_iter().map(|(_, line)| line).collect()
+    }
+}
+
+/// The job list file.
+pub struct BatchJobs {
+    path: PathBuf,
+}
+
+impl BatchJobs {
+    /// Jobs at `path` (default `~/.oxibot/batches.json`).
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path: path.unwrap_or_else(utils::get_batches_path),
+        }
+    }
+
+    /// All jobs, oldest first. A missing or unreadable file is empty.
+    pub fn load(&self) -> Vec<BatchJob> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// The job with `id`.
+    pub fn get(&self, id: &str) -> Option<BatchJob> {
+        self.load().into_iter().find(|job| job.id == id)
+    }
+
+    /// Add or replace `job`.
+    pub fn save(&self, job: BatchJob) -> Result<()> {
+        let mut jobs = self.load();
+        match jobs.iter_mut().find(|j| j.id == job.id) {
+            Some(existing) => *existing = job,
+            None => jobs.push(job),
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&jobs)?)?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+/// The batch provider for `model` with the configured API keys.
+pub fn provider_for(config: &Config, model: &str) -> Result<Box<dyn BatchProvider>> {
+    oxibot_providers::register_custom_providers(&config.providers.custom);
+    create_batch_provider(model, &config.providers.to_map())
+}
+
+/// Message posted to the chat when `job` has ended.
+fn delivery_text(job: &BatchJob, results: &[BatchResult]) -> String {
+    let lines = job.result_lines(results);
+    let failed = lines.iter().filter(|l| l.get("error").is_some()).count();
+    let mut text = format!(
+        "📦 Batch {} finished: {} succeeded, {failed} failed.",
+        job.id,
+        lines.len() - failed
+    );
+    for line in &lines {
+        let id = line["id"].as_str().unwrap_or_default();
+        match line["response"].as_str() {
+            Some(response) => text.push_str(&format!("\n\n**{id}**\n{response}")),
+            None => text.push_str(&format!("\n\n**{id}** — ⚠️ {}", line["error"].as_str().unwrap_or_default())),
+        }
+    }
+    text
+}
+
+/// Post the results of every ended job that asked for delivery.
+pub async fn deliver_finished(config: &Config, jobs: &BatchJobs, bus: &MessageBus) {
+    for mut job in jobs.load().into_iter().filter(|j| !j.delivered) {
+        let Some((channel, chat_id)) = job.deliver.as_deref().and_then(|d| d.split_once(':')) else {
+            continue;
+        };
+        let (channel, chat_id) = (channel.to_string(), chat_id.to_string());
+        let text = match check(config, &job).await {
+            Ok(Some(text)) => text,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!(batch = %job.id, error = %format!("{e:#}"), "cannot check batch job");
+                continue;
+            }
+        };
+        if let Err(e) = bus.publish_outbound(OutboundMessage::new(&channel, &chat_id, &text)).await {
+            warn!(batch = %job.id, error = %e, "failed to deliver batch results");
+            continue;
+        }
+        info!(batch = %job.id, channel = %channel, "delivered batch results");
+        job.delivered = true;
+        if let Err(e) = jobs.save(job) {
+            warn!(error = %format!("{e:#}"), "failed to update batch job list");
+        }
+    }
+}
+
+/// The delivery text once `job` is done, `None` while it runs.
+async fn check(config: &Config, job: &BatchJob) -> Result<Option<String>> {
+    let provider = provider_for(config, &job.model)?;
+    let status = provider.status(&job.id).await?;
+    Ok(match status.state {
+        BatchState::InProgress => None,
+        BatchState::Failed => Some(format!("📦 Batch {} failed ({}).", job.id, status.detail)),
+        BatchState::Ended => Some(delivery_text(job, &provider.results(&job.id).await?)),
+    })
+}
+
+/// Gateway task: deliver finished jobs every few minutes.
+pub async fn watch(config: Config, bus: Arc<MessageBus>) {
+    let jobs = BatchJobs::new(None);
+    loop {
+        deliver_finished(&config, &jobs, &bus).await;
+        tokio::time::sleep(BATCH_POLL_INTERVAL).await;
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job() -> BatchJob {
+        BatchJob {
+            id: "batch_1".into(),
+            model: "gpt-4o-mini".into(),
+            submitted_at: "2026-01-01T00:00:00Z".into(),
+            ids: vec!["a".into(), "b".into()],
+            deliver: Some("telegram:42".into()),
+            delivered: false,
+        }
+    }
+
+    #[test]
+    fn test_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let jobs = BatchJobs::new(Some(dir.path().join("batches.json")));
+        assert!(jobs.load().is_empty());
+
+        jobs.save(job()).unwrap();
+        let mut updated = job();
+        updated.delivered = true;
+        jobs.save(updated.clone()).unwrap();
+        assert_eq!(jobs.load(), vec![updated]);
+        assert_eq!(jobs.get("missing"), None);
+    }
+
+    #[test]
+    fn test_results_map_back_to_prompt_ids() {
+        let results = [
+            BatchResult {
+                custom_id: BatchJob::custom_id(1),
+                result: Err("overloaded".into()),
+            },
+            BatchResult {
+                custom_id: BatchJob::custom_id(0),
+                result: Ok("hello".into()),
+            },
+        ];
+        let lines = job().result_lines(&results);
+        assert_eq!(lines[0], json!({"id": "a", "response": "hello"}));
+        assert_eq!(lines[1], json!({"id": "b", "error": "overloaded"}));
+
+        let text = delivery_text(&job(), &results);
+        assert!(text.starts_with("📦 Batch batch_1 finished: 1 succeeded, 1 failed."));
+        assert!(text.contains("**a**\nhello"));
+    }
+}
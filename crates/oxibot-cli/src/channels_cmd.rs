@@ -3,12 +3,16 @@
 //! Replaces nanobot's `channels` subcommands:
 //! - `oxibot channels status` — show channel configuration status
 //! - `oxibot channels login` — link WhatsApp via bridge (QR code)
+//! - `oxibot channels outbox list|retry ID|drop ID` — inspect messages that
+//!   failed to send
 
 use anyhow::Result;
 use clap::Subcommand;
 use colored::Colorize;
 
-use oxibot_core::config::load_config;
+use oxibot_core::bus::outbox::{Outbox, OutboxStatus};
+use oxibot_core::config::{load_config, Config};
+use oxibot_core::utils::{get_outbox_path, truncate_string};
 
 // ─────────────────────────────────────────────
 // Subcommand enum
@@ -22,6 +26,31 @@ pub enum ChannelsCommands {
 
     /// Link WhatsApp device via QR code (starts the bridge)
     Login,
+
+    /// Inspect, retry or drop messages that failed to send
+    Outbox {
+        #[command(subcommand)]
+        action: OutboxCommands,
+    },
+}
+
+/// Outbox subcommands.
+#[derive(Subcommand)]
+pub enum OutboxCommands {
+    /// List messages that failed to send
+    List,
+
+    /// Mark a message for retry (a running gateway re-sends it within seconds)
+    Retry {
+        /// Entry id from `outbox list`
+        id: String,
+    },
+
+    /// Delete a message without sending it
+    Drop {
+        /// Entry id from `outbox list`
+        id: String,
+    },
 }
 
 // ─────────────────────────────────────────────
@@ -33,6 +62,7 @@ pub fn dispatch(cmd: ChannelsCommands) -> Result<()> {
     match cmd {
         ChannelsCommands::Status => channel_status(),
         ChannelsCommands::Login => channel_login(),
+        ChannelsCommands::Outbox { action } => outbox(action),
     }
 }
 
@@ -40,101 +70,130 @@ pub fn dispatch(cmd: ChannelsCommands) -> Result<()> {
 // Channel status
 // ─────────────────────────────────────────────
 
-/// Row for the status table.
-struct ChannelRow {
-    name: &'static str,
-    configured: bool,
-    detail: String,
+/// A channel's configuration, for `channels status` and `status --json`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChannelRow {
+    pub name: &'static str,
+    /// Required settings are filled in.
+    pub configured: bool,
+    /// This build can run the channel (its cargo feature is enabled).
+    pub available: bool,
+    pub detail: String,
+    /// Settings that look wrong.
+    pub problems: Vec<String>,
+}
+
+impl ChannelRow {
+    fn new(name: &'static str, configured: bool, available: bool, detail: impl FnOnce() -> String) -> Self {
+        Self {
+            name,
+            configured,
+            available,
+            detail: if configured { detail() } else { "not configured".into() },
+            problems: Vec::new(),
+        }
+    }
+
+    /// Record `problem` when `bad` and the channel is configured.
+    fn check(mut self, bad: bool, problem: &str) -> Self {
+        if self.configured && bad {
+            self.problems.push(problem.to_string());
+        }
+        self
+    }
+
+    /// Configured and without problems. A configured channel this build
+    /// can't run counts as a problem.
+    pub fn is_valid(&self) -> bool {
+        self.configured && self.available && self.problems.is_empty()
+    }
+}
+
+/// First `n` characters of a secret, for display.
+fn prefix(secret: &str, n: usize) -> &str {
+    secret.char_indices().nth(n).map_or(secret, |(i, _)| &secret[..i])
+}
+
+/// Status of every channel in `config`.
+pub(crate) fn channel_rows(config: &Config) -> Vec<ChannelRow> {
+    let ch = &config.channels;
+    let mut rows = vec![
+        ChannelRow::new("Telegram", !ch.telegram.token.is_empty(), cfg!(feature = "telegram"), || {
+            format!("token: {}...", prefix(&ch.telegram.token, 10))
+        })
+        .check(
+            !ch.telegram
+                .token
+                .split_once(':')
+                .is_some_and(|(id, _)| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())),
+            "token should look like 123456:ABC-DEF…",
+        ),
+        ChannelRow::new("Discord", !ch.discord.token.is_empty(), cfg!(feature = "discord"), || {
+            format!("token: {}...", prefix(&ch.discord.token, 10))
+        }),
+        ChannelRow::new("WhatsApp", !ch.whatsapp.bridge_url.is_empty(), cfg!(feature = "whatsapp"), || {
+            ch.whatsapp.bridge_url.clone()
+        })
+        .check(
+            !ch.whatsapp.bridge_url.starts_with("ws://") && !ch.whatsapp.bridge_url.starts_with("wss://"),
+            "bridgeUrl should start with ws:// or wss://",
+        ),
+        ChannelRow::new("Signal", !ch.signal.account.is_empty(), cfg!(feature = "signal"), || {
+            format!("{} via signal-cli", ch.signal.account)
+        })
+        .check(!ch.signal.account.starts_with('+'), "account should be a phone number like +15551234567"),
+        ChannelRow::new(
+            "Slack",
+            !ch.slack.bot_token.is_empty() || !ch.slack.app_token.is_empty(),
+            cfg!(feature = "slack"),
+            || "socket mode".to_string(),
+        )
+        .check(
+            !ch.slack.bot_token.starts_with("xoxb-"),
+            "botToken should be a bot token (xoxb-…)",
+        )
+        .check(
+            !ch.slack.app_token.starts_with("xapp-"),
+            "appToken should be an app-level token (xapp-…)",
+        ),
+        ChannelRow::new("Email", !ch.email.imap_host.is_empty(), cfg!(feature = "email"), || {
+            format!("{}:{}", ch.email.imap_host, ch.email.imap_port)
+        })
+        .check(
+            ch.email.imap_username.is_empty() || ch.email.imap_password.is_empty(),
+            "imapUsername and imapPassword are required",
+        )
+        .check(ch.email.smtp_host.is_empty(), "smtpHost is required to send replies"),
+        ChannelRow::new("File drop", !ch.filedrop.dir.is_empty(), cfg!(feature = "filedrop"), || {
+            ch.filedrop.dir.clone()
+        })
+        .check(
+            !crate::helpers::expand_tilde(&ch.filedrop.dir).is_dir(),
+            "dir does not exist",
+        ),
+    ];
+
+    // Configuration only; no channel implementation yet.
+    rows.extend([
+        ChannelRow::new("Feishu", !ch.feishu.app_id.is_empty(), false, || {
+            format!("app_id: {}...", prefix(&ch.feishu.app_id, 10))
+        }),
+        ChannelRow::new("DingTalk", !ch.dingtalk.client_id.is_empty(), false, || {
+            format!("client_id: {}...", prefix(&ch.dingtalk.client_id, 10))
+        }),
+        ChannelRow::new("QQ", !ch.qq.app_id.is_empty(), false, || {
+            format!("app_id: {}...", prefix(&ch.qq.app_id, 10))
+        }),
+        ChannelRow::new("Mochat", !ch.mochat.url.is_empty(), false, || ch.mochat.url.clone()),
+    ]);
+    rows
 }
 
 /// `oxibot channels status`
 fn channel_status() -> Result<()> {
     let config = load_config(None);
-    let ch = &config.channels;
-
-    let rows = vec![
-        ChannelRow {
-            name: "Telegram",
-            configured: !ch.telegram.token.is_empty(),
-            detail: if ch.telegram.token.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                format!("token: {}...", &ch.telegram.token[..ch.telegram.token.len().min(10)])
-            },
-        },
-        ChannelRow {
-            name: "Discord",
-            configured: !ch.discord.token.is_empty(),
-            detail: if ch.discord.token.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                format!("token: {}...", &ch.discord.token[..ch.discord.token.len().min(10)])
-            },
-        },
-        ChannelRow {
-            name: "WhatsApp",
-            configured: !ch.whatsapp.bridge_url.is_empty(),
-            detail: if ch.whatsapp.bridge_url.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                ch.whatsapp.bridge_url.clone()
-            },
-        },
-        ChannelRow {
-            name: "Slack",
-            configured: !ch.slack.bot_token.is_empty() && !ch.slack.app_token.is_empty(),
-            detail: if ch.slack.bot_token.is_empty() || ch.slack.app_token.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                "socket mode".to_string()
-            },
-        },
-        ChannelRow {
-            name: "Email",
-            configured: !ch.email.imap_host.is_empty(),
-            detail: if ch.email.imap_host.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                format!("{}:{}", ch.email.imap_host, ch.email.imap_port)
-            },
-        },
-        ChannelRow {
-            name: "Feishu",
-            configured: !ch.feishu.app_id.is_empty(),
-            detail: if ch.feishu.app_id.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                format!("app_id: {}...", &ch.feishu.app_id[..ch.feishu.app_id.len().min(10)])
-            },
-        },
-        ChannelRow {
-            name: "DingTalk",
-            configured: !ch.dingtalk.client_id.is_empty(),
-            detail: if ch.dingtalk.client_id.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                format!("client_id: {}...", &ch.dingtalk.client_id[..ch.dingtalk.client_id.len().min(10)])
-            },
-        },
-        ChannelRow {
-            name: "QQ",
-            configured: !ch.qq.app_id.is_empty(),
-            detail: if ch.qq.app_id.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                format!("app_id: {}...", &ch.qq.app_id[..ch.qq.app_id.len().min(10)])
-            },
-        },
-        ChannelRow {
-            name: "Mochat",
-            configured: !ch.mochat.url.is_empty(),
-            detail: if ch.mochat.url.is_empty() {
-                "not configured".dimmed().to_string()
-            } else {
-                ch.mochat.url.clone()
-            },
-        },
-    ];
+    let rows = channel_rows(&config);
 
     println!();
     println!("{}", "  Channel Status".cyan().bold());
@@ -150,18 +209,95 @@ fn channel_status() -> Result<()> {
     println!("  {}", "─".repeat(60));
 
     for row in &rows {
-        let status = if row.configured {
-            "✓".green().to_string()
+        let (status, detail) = if !row.configured {
+            ("✗".dimmed().to_string(), row.detail.dimmed().to_string())
+        } else if row.is_valid() {
+            ("✓".green().to_string(), row.detail.clone())
         } else {
-            "✗".dimmed().to_string()
+            ("!".yellow().to_string(), row.detail.clone())
         };
-        println!("  {:<12} {:<10} {}", row.name, status, row.detail);
+        println!("  {:<12} {:<10} {}", row.name, status, detail);
+        if row.configured && !row.available {
+            println!("  {:<12} {:<10} {}", "", "", "not available in this build".yellow());
+        }
+        for problem in &row.problems {
+            println!("  {:<12} {:<10} {}", "", "", problem.yellow());
+        }
     }
 
     println!();
     Ok(())
 }
 
+// ─────────────────────────────────────────────
+// Outbox
+// ─────────────────────────────────────────────
+
+/// `oxibot channels outbox ...`
+fn outbox(cmd: OutboxCommands) -> Result<()> {
+    let outbox = Outbox::new(get_outbox_path());
+    match cmd {
+        OutboxCommands::List => list_outbox(&outbox),
+        OutboxCommands::Retry { id } => {
+            let entry = outbox.retry(&id)?;
+            println!(
+                "  {} Marked {} for retry ({}:{}); the gateway re-sends it on its next poll",
+                "✓".green(),
+                entry.id.cyan(),
+                entry.message.channel,
+                entry.message.chat_id
+            );
+            Ok(())
+        }
+        OutboxCommands::Drop { id } => {
+            let entry = outbox.remove(&id)?;
+            println!("  {} Dropped {} ({}:{})", "✓".green(), entry.id.cyan(), entry.message.channel, entry.message.chat_id);
+            Ok(())
+        }
+    }
+}
+
+/// `oxibot channels outbox list`
+fn list_outbox(outbox: &Outbox) -> Result<()> {
+    let entries = outbox.list();
+    if entries.is_empty() {
+        println!("  No undelivered messages.");
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  {:<10} {:<28} {:<9} {:>8} {:<17} {}",
+        "Id".bold(),
+        "To".bold(),
+        "Status".bold(),
+        "Attempts".bold(),
+        "Last attempt".bold(),
+        "Error".bold(),
+    );
+    println!("  {}", "─".repeat(100));
+    for entry in &entries {
+        let to = format!("{}:{}", entry.message.channel, entry.message.chat_id);
+        let status = match entry.status {
+            OutboxStatus::Failed => "failed",
+            OutboxStatus::Retry => "retry",
+        };
+        println!(
+            "  {:<10} {:<28} {:<9} {:>8} {:<17} {}",
+            entry.id,
+            truncate_string(&to, 28),
+            status,
+            entry.attempts,
+            entry.updated_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            truncate_string(&entry.error, 60).red(),
+        );
+        println!("  {:<10} {}", "", truncate_string(&entry.message.content.replace('\n', " "), 88).dimmed());
+    }
+    println!();
+    println!("  {} message(s). Retry with `oxibot channels outbox retry ID`, or drop with `oxibot channels outbox drop ID`.", entries.len());
+    Ok(())
+}
+
 // ─────────────────────────────────────────────
 // Channel login (WhatsApp bridge)
 // ─────────────────────────────────────────────
@@ -328,8 +464,8 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_channel_rows_all_nine() {
-        // Ensure all 9 channels are represented by running channel_status
+    fn test_channel_rows_all_ten() {
+        // Ensure all 10 channels are represented by running channel_status
         // with default (empty) config — should not panic.
         // We can't easily capture stdout, so just verify no crash.
         let config = load_config(None);
@@ -337,6 +473,22 @@ mod tests {
         // If we got here, config loads fine
     }
 
+    #[test]
+    fn test_channel_rows_validity() {
+        let mut config = Config::default();
+        assert!(channel_rows(&config).iter().all(|r| !r.configured && r.problems.is_empty()));
+
+        config.channels.telegram.token = "not-a-token".into();
+        config.channels.slack.bot_token = "xoxb-1".into();
+        config.channels.signal.account = "+15551234567".into();
+        let rows = channel_rows(&config);
+        let row = |name| rows.iter().find(|r| r.name == name).unwrap();
+        assert_eq!(row("Telegram").problems, ["token should look like 123456:ABC-DEF…"]);
+        assert_eq!(row("Slack").problems, ["appToken should be an app-level token (xapp-…)"]);
+        assert!(row("Signal").problems.is_empty());
+        assert_eq!(row("Signal").is_valid(), cfg!(feature = "signal"));
+    }
+
     #[test]
     fn test_which_npm_returns_option() {
         // This may or may not find npm depending on environment
@@ -0,0 +1,423 @@
+//! `oxibot config` — change common settings without editing JSON.
+//!
+//! - `oxibot config edit` — guided prompts for the model, provider API
+//!   keys, channel tokens, `restrictToWorkspace` and allow-lists. Values
+//!   are checked as they are entered (models against the provider
+//!   registry, channel settings like `channels status` does) and the file
+//!   is written with [`save_config`] when you choose to save.
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::Colorize;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use serde_json::json;
+
+use oxibot_core::config::{get_config_path, read_config_file, save_config, Config};
+use oxibot_providers::registry::{find_by_model, match_provider, PROVIDERS};
+
+use crate::channels_cmd::channel_rows;
+use crate::workspace_cmd::apply_settings;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Config subcommands.
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Edit common settings with guided prompts
+    Edit,
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a config subcommand.
+pub fn dispatch(cmd: ConfigCommands) -> Result<()> {
+    match cmd {
+        ConfigCommands::Edit => edit(),
+    }
+}
+
+/// `oxibot config edit`
+fn edit() -> Result<()> {
+    let path = get_config_path();
+    let config = read_config_file(Some(&path))
+        .map_err(|e| anyhow::anyhow!("cannot read {}: {e} (fix or remove it first)", path.display()))?;
+    oxibot_providers::register_custom_providers(&config.providers.custom);
+
+    println!();
+    println!("{}", "🦀 Oxibot — Config editor".cyan().bold());
+    println!("  {}", path.display().to_string().dimmed());
+
+    let mut editor = ConfigEditor::new(config);
+    let mut terminal = DefaultEditor::new()?;
+    if editor.run(&mut terminal)? {
+        save_config(&editor.config, Some(&path))?;
+        println!("  {} saved {}", "✓".green(), path.display());
+    } else {
+        println!("  No changes saved.");
+    }
+    println!();
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// Prompts
+// ─────────────────────────────────────────────
+
+/// Where answers come from.
+trait Prompt {
+    /// The user's answer, trimmed (`None` on Ctrl-C / Ctrl-D).
+    fn ask(&mut self, question: &str) -> Result<Option<String>>;
+}
+
+impl Prompt for DefaultEditor {
+    fn ask(&mut self, question: &str) -> Result<Option<String>> {
+        match self.readline(question) {
+            Ok(line) => Ok(Some(line.trim().to_string())),
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// `(label, key)` of a channel setting.
+type ChannelField = (&'static str, &'static str);
+
+/// Channels whose connection settings can be set here: config name, name
+/// in `channels status`, and their settings.
+const CHANNELS: &[(&str, &str, &[ChannelField])] = &[
+    ("telegram", "Telegram", &[("Bot token", "token")]),
+    ("discord", "Discord", &[("Bot token", "token")]),
+    ("slack", "Slack", &[("Bot token (xoxb-…)", "botToken"), ("App token (xapp-…)", "appToken")]),
+    ("whatsapp", "WhatsApp", &[("Bridge URL (ws://…)", "bridgeUrl")]),
+    ("signal", "Signal", &[("Account phone number (+…)", "account")]),
+];
+
+/// Channels with an allow-list.
+const ALLOW_LIST_CHANNELS: &[&str] = &["telegram", "discord", "slack", "whatsapp", "signal", "email"];
+
+// ─────────────────────────────────────────────
+// Editor
+// ─────────────────────────────────────────────
+
+/// A config being edited.
+struct ConfigEditor {
+    config: Config,
+    changed: bool,
+}
+
+impl ConfigEditor {
+    fn new(config: Config) -> Self {
+        Self { config, changed: false }
+    }
+
+    /// Show the menu until the user saves or quits. Returns whether to save.
+    fn run(&mut self, prompt: &mut dyn Prompt) -> Result<bool> {
+        loop {
+            self.print_menu();
+            let Some(choice) = prompt.ask("  Choice: ")? else {
+                return Ok(false);
+            };
+            match choice.as_str() {
+                "1" => self.edit_model(prompt)?,
+                "2" => self.edit_api_key(prompt)?,
+                "3" => self.edit_channel(prompt)?,
+                "4" => {
+                    let restrict = &mut self.config.tools.restrict_to_workspace;
+                    *restrict = !*restrict;
+                    self.changed = true;
+                    println!("  {} restrictToWorkspace = {restrict}", "✓".green());
+                }
+                "5" => self.edit_allow_list(prompt)?,
+                "s" => return Ok(self.changed),
+                "q" => return Ok(false),
+                other => println!("  {} unknown choice '{other}'", "✗".red()),
+            }
+        }
+    }
+
+    fn print_menu(&self) {
+        let c = &self.config;
+        let keys: Vec<&str> = PROVIDERS
+            .iter()
+            .filter(|spec| c.providers.get_by_name(spec.name).is_some_and(|p| p.is_configured()))
+            .map(|spec| spec.name)
+            .collect();
+        let channels: Vec<&str> = channel_rows(c).iter().filter(|r| r.configured).map(|r| r.name).collect();
+        let ch = &c.channels;
+        let allowed: usize = [
+            &ch.telegram.allowed_users,
+            &ch.discord.allowed_users,
+            &ch.slack.allowed_users,
+            &ch.whatsapp.allowed_users,
+            &ch.signal.allowed_users,
+            &ch.email.allowed_users,
+        ]
+        .iter()
+        .map(|list| list.len())
+        .sum();
+        println!();
+        println!("  1. Model                  {}", c.agents.defaults.model);
+        println!("  2. Provider API keys      {}", list_or_none(&keys));
+        println!("  3. Channels               {}", list_or_none(&channels));
+        println!("  4. Restrict to workspace  {}", if c.tools.restrict_to_workspace { "on" } else { "off" });
+        println!("  5. Allow-lists            {allowed} users");
+        println!("  s. Save and quit{}", if self.changed { " (unsaved changes)" } else { "" });
+        println!("  q. Quit without saving");
+    }
+
+    fn edit_model(&mut self, prompt: &mut dyn Prompt) -> Result<()> {
+        let Some(model) = ask_value(prompt, "  Model (e.g. anthropic/claude-sonnet-4-5): ")? else {
+            return Ok(());
+        };
+        match check_model(&model, &self.config) {
+            Ok((provider, configured)) => {
+                self.config.agents.defaults.model = model.clone();
+                self.changed = true;
+                println!("  {} model = {model} ({provider})", "✓".green());
+                if !configured {
+                    println!("  {} {provider} has no API key yet; set one with 2.", "!".yellow());
+                }
+            }
+            Err(e) => println!("  {} {e}", "✗".red()),
+        }
+        Ok(())
+    }
+
+    fn edit_api_key(&mut self, prompt: &mut dyn Prompt) -> Result<()> {
+        let names: Vec<&str> = PROVIDERS.iter().map(|spec| spec.name).collect();
+        println!("  Providers: {}", names.join(", "));
+        let Some(name) = ask_value(prompt, "  Provider: ")? else {
+            return Ok(());
+        };
+        let Some(spec) = PROVIDERS.iter().find(|spec| spec.name == name.to_lowercase()) else {
+            println!("  {} unknown provider '{name}'", "✗".red());
+            return Ok(());
+        };
+        let Some(key) = ask_value(prompt, &format!("  {} API key (- to remove): ", spec.display_name))? else {
+            return Ok(());
+        };
+        let key = if key == "-" { String::new() } else { key };
+        if key.chars().any(char::is_whitespace) {
+            println!("  {} an API key has no spaces", "✗".red());
+            return Ok(());
+        }
+        if let Some(expected) = spec.detect_by_key_prefix.filter(|p| !key.is_empty() && !key.starts_with(p)) {
+            println!("  {} {} keys usually start with {expected}", "!".yellow(), spec.display_name);
+        }
+        let api_base = if spec.is_local && !key.is_empty() {
+            match ask_value(prompt, "  API base (e.g. http://localhost:8000/v1): ")? {
+                Some(base) if !base.starts_with("http://") && !base.starts_with("https://") => {
+                    println!("  {} the API base must start with http:// or https://", "✗".red());
+                    return Ok(());
+                }
+                base => base,
+            }
+        } else {
+            None
+        };
+        let Some(provider) = self.config.providers.get_by_name_mut(spec.name) else {
+            return Ok(());
+        };
+        provider.api_key = key;
+        if api_base.is_some() {
+            provider.api_base = api_base;
+        }
+        self.changed = true;
+        let state = if provider.is_configured() { "set" } else { "removed" };
+        println!("  {} {} API key {state}", "✓".green(), spec.display_name);
+        Ok(())
+    }
+
+    fn edit_channel(&mut self, prompt: &mut dyn Prompt) -> Result<()> {
+        let names: Vec<&str> = CHANNELS.iter().map(|(name, _, _)| *name).collect();
+        println!("  Channels: {}", names.join(", "));
+        let Some(name) = ask_value(prompt, "  Channel: ")? else {
+            return Ok(());
+        };
+        let Some(&(channel, row_name, fields)) = CHANNELS.iter().find(|(n, _, _)| *n == name.to_lowercase()) else {
+            println!("  {} unknown channel '{name}'", "✗".red());
+            return Ok(());
+        };
+
+        // Empty keeps a setting, "-" clears it (which turns the channel off)
+        let mut settings = Vec::new();
+        for (label, key) in fields {
+            let Some(value) = ask_value(prompt, &format!("  {label} (- to clear): "))? else {
+                continue;
+            };
+            let value = if value == "-" { String::new() } else { value };
+            settings.push((format!("channels.{channel}.{key}"), json!(value)));
+        }
+        if settings.is_empty() {
+            return Ok(());
+        }
+        let settings: Vec<(&str, serde_json::Value)> = settings.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+        let updated = apply_settings(&self.config, &settings)?;
+        let rows = channel_rows(&updated);
+        let Some(row) = rows.iter().find(|r| r.name == row_name) else {
+            return Ok(());
+        };
+        if !row.problems.is_empty() {
+            for problem in &row.problems {
+                println!("  {} {problem}", "✗".red());
+            }
+            println!("  Not changed.");
+            return Ok(());
+        }
+        self.config = updated;
+        self.changed = true;
+        if row.configured {
+            println!("  {} {row_name} configured ({})", "✓".green(), row.detail);
+            if !row.available {
+                println!("  {} this build has no {row_name} support; rebuild with --features {channel}", "!".yellow());
+            }
+        } else {
+            println!("  {} {row_name} turned off", "✓".green());
+        }
+        Ok(())
+    }
+
+    fn edit_allow_list(&mut self, prompt: &mut dyn Prompt) -> Result<()> {
+        println!("  Channels: {}", ALLOW_LIST_CHANNELS.join(", "));
+        let Some(name) = ask_value(prompt, "  Channel: ")? else {
+            return Ok(());
+        };
+        let name = name.to_lowercase();
+        let Some(allowed) = self.config.channels.allowed_users_mut(&name) else {
+            println!("  {} '{name}' has no allow-list", "✗".red());
+            return Ok(());
+        };
+        loop {
+            if allowed.is_empty() {
+                println!("  {name}: everyone may talk to the bot");
+            } else {
+                println!("  {name}: {}", allowed.join(", "));
+            }
+            let Some(entry) = ask_value(prompt, "  +id to add, -id to remove, empty when done: ")? else {
+                return Ok(());
+            };
+            if let Some(id) = entry.strip_prefix('-').map(str::trim).filter(|id| !id.is_empty()) {
+                match allowed.iter().position(|u| u == id) {
+                    Some(i) => {
+                        allowed.remove(i);
+                        self.changed = true;
+                    }
+                    None => println!("  {} {id} is not on the list", "✗".red()),
+                }
+            } else {
+                let id = entry.strip_prefix('+').unwrap_or(&entry).trim();
+                if id.is_empty() || id.contains(',') || id.chars().any(char::is_whitespace) {
+                    println!("  {} enter one id at a time, without spaces", "✗".red());
+                } else if allowed.iter().any(|u| u == id) {
+                    println!("  {id} is already on the list");
+                } else {
+                    allowed.push(id.to_string());
+                    self.changed = true;
+                }
+            }
+        }
+    }
+}
+
+/// Ask `question`; `None` when the answer is empty (keep the current value)
+/// or the user pressed Ctrl-C / Ctrl-D.
+fn ask_value(prompt: &mut dyn Prompt, question: &str) -> Result<Option<String>> {
+    Ok(prompt.ask(question)?.filter(|answer| !answer.is_empty()))
+}
+
+/// The provider `model` would use, and whether it has an API key (or a
+/// configured gateway routes it).
+fn check_model(model: &str, config: &Config) -> std::result::Result<(&'static str, bool), String> {
+    if model.chars().any(char::is_whitespace) {
+        return Err("a model name has no spaces".into());
+    }
+    if let Some((_, spec)) = match_provider(model, &config.providers.to_map()) {
+        return Ok((spec.display_name, true));
+    }
+    match find_by_model(model) {
+        Some(spec) => Ok((spec.display_name, false)),
+        None => Err(format!(
+            "no provider serves '{model}'; use a known model (e.g. anthropic/claude-sonnet-4-5, \
+             gpt-4o, deepseek-chat) or set a gateway key (OpenRouter) first"
+        )),
+    }
+}
+
+fn list_or_none(items: &[&str]) -> String {
+    if items.is_empty() {
+        "none".into()
+    } else {
+        items.join(", ")
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Answers from a script.
+    struct Script(VecDeque<&'static str>);
+
+    impl Prompt for Script {
+        fn ask(&mut self, _question: &str) -> Result<Option<String>> {
+            Ok(self.0.pop_front().map(String::from))
+        }
+    }
+
+    fn run(config: Config, answers: &[&'static str]) -> (bool, Config) {
+        let mut editor = ConfigEditor::new(config);
+        let mut script = Script(answers.iter().copied().collect());
+        let save = editor.run(&mut script).unwrap();
+        assert!(script.0.is_empty(), "unused answers: {:?}", script.0);
+        (save, editor.config)
+    }
+
+    #[test]
+    fn test_edit_and_save() {
+        let (save, config) = run(
+            Config::default(),
+            &[
+                "1", "no such model",
+                "1", "deepseek-chat",
+                "2", "deepseek", "sk-deep",
+                "3", "telegram", "not-a-token",
+                "3", "telegram", "123456:ABC-DEF",
+                "4",
+                "5", "telegram", "+42", "+42", "+7", "-42", "",
+                "s",
+            ],
+        );
+        assert!(save);
+        assert_eq!(config.agents.defaults.model, "deepseek-chat");
+        assert_eq!(config.providers.deepseek.api_key, "sk-deep");
+        assert_eq!(config.channels.telegram.token, "123456:ABC-DEF");
+        assert!(config.tools.restrict_to_workspace);
+        assert_eq!(config.channels.telegram.allowed_users, vec!["7"]);
+    }
+
+    #[test]
+    fn test_quit_discards_and_model_check() {
+        let (save, _) = run(Config::default(), &["4", "q"]);
+        assert!(!save);
+        let (save, _) = run(Config::default(), &["4"]);
+        assert!(!save, "Ctrl-D quits without saving");
+        let (save, _) = run(Config::default(), &["s"]);
+        assert!(!save, "nothing to save");
+
+        let mut config = Config::default();
+        assert_eq!(check_model("gpt-4o", &config), Ok(("OpenAI", false)));
+        assert!(check_model("mystery-model", &config).is_err());
+        config.providers.openrouter.api_key = "sk-or-123".into();
+        assert_eq!(check_model("mystery-model", &config), Ok(("OpenRouter", true)));
+    }
+}
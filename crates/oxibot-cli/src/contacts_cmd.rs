@@ -0,0 +1,126 @@
+//! `oxibot contacts` — manage the contact book from the CLI.
+//!
+//! - `oxibot contacts list` — list contacts and their ids
+//! - `oxibot contacts add NAME ID... [--note TEXT]` — add a contact or link more ids
+//! - `oxibot contacts unlink NAME ID` — remove one id from a contact
+//! - `oxibot contacts remove NAME` — delete a contact
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_core::contacts::ContactBook;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Contacts subcommands.
+#[derive(Subcommand)]
+pub enum ContactsCommands {
+    /// List contacts
+    List,
+
+    /// Add a contact or link more ids to it
+    Add {
+        /// Contact name
+        name: String,
+
+        /// Ids as channel:id (e.g. telegram:123, slack:U45) or email addresses
+        ids: Vec<String>,
+
+        /// Notes/preferences for this person (replaces existing notes)
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// Remove one id from a contact
+    Unlink {
+        /// Contact name
+        name: String,
+
+        /// Id to remove (channel:id or email address)
+        id: String,
+    },
+
+    /// Delete a contact
+    Remove {
+        /// Contact name
+        name: String,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a contacts subcommand.
+pub fn dispatch(cmd: ContactsCommands) -> Result<()> {
+    let mut book = ContactBook::load(None);
+    match cmd {
+        ContactsCommands::List => list_contacts(&book),
+        ContactsCommands::Add { name, ids, note } => add_contact(&mut book, &name, &ids, note),
+        ContactsCommands::Unlink { name, id } => {
+            if book.unlink(&name, &id) {
+                save(&book)?;
+                println!("  {} Unlinked {} from {}", "✓".green(), id, name.cyan());
+            } else {
+                println!("  {} {} has no id {}", "✗".red(), name, id);
+            }
+            Ok(())
+        }
+        ContactsCommands::Remove { name } => {
+            if book.remove(&name) {
+                save(&book)?;
+                println!("  {} Removed contact {}", "✓".green(), name.cyan());
+            } else {
+                println!("  {} Contact {} not found", "✗".red(), name);
+            }
+            Ok(())
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+fn save(book: &ContactBook) -> Result<()> {
+    book.save()
+        .with_context(|| format!("failed to write {}", book.path().display()))
+}
+
+/// `oxibot contacts list`
+fn list_contacts(book: &ContactBook) -> Result<()> {
+    if book.contacts().is_empty() {
+        println!("  No contacts. Add one with `oxibot contacts add NAME ID...`.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  Contacts".cyan().bold());
+    println!();
+    println!("  {:<16} {:<48} {}", "Name".bold(), "Ids".bold(), "Notes".bold());
+    println!("  {}", "─".repeat(76));
+    for contact in book.contacts() {
+        println!(
+            "  {:<16} {:<48} {}",
+            contact.name,
+            contact.ids.join(", "),
+            contact.notes.dimmed()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// `oxibot contacts add NAME ID... [--note TEXT]`
+fn add_contact(book: &mut ContactBook, name: &str, ids: &[String], note: Option<String>) -> Result<()> {
+    let summary = book.link(name, ids)?.summary();
+    if let Some(note) = note {
+        book.set_notes(name, &note);
+    }
+    save(book)?;
+    println!("  {} Saved {}", "✓".green(), summary);
+    Ok(())
+}
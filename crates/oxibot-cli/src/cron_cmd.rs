@@ -2,7 +2,7 @@
 //!
 //! Replaces nanobot's `cron` subcommands:
 //! - `oxibot cron list [--all]` — list scheduled jobs
-//! - `oxibot cron add --name NAME --message MSG (--every N | --cron EXPR | --at TIME)` — add a job
+//! - `oxibot cron add --name NAME --message MSG (--every N | --cron EXPR | --at TIME) [--tz ZONE]` — add a job
 //! - `oxibot cron remove <ID>` — remove a job
 //! - `oxibot cron enable <ID> [--disable]` — enable/disable a job
 //! - `oxibot cron run <ID> [--force]` — manually trigger a job
@@ -14,6 +14,8 @@ use clap::Subcommand;
 use colored::Colorize;
 
 use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::config::load_config;
+use oxibot_core::datetime::{parse_time, to_iso, Tz, UserTimezone};
 use oxibot_core::utils::get_data_path;
 use oxibot_cron::types::{CronJob, CronPayload, CronSchedule, ScheduleKind};
 use oxibot_cron::CronService;
@@ -46,14 +48,18 @@ pub enum CronCommands {
         #[arg(short, long)]
         every: Option<u64>,
 
-        /// Cron expression, e.g. "0 9 * * *" (cron schedule)
+        /// Cron expression, e.g. "0 0 9 * * *" (cron schedule)
         #[arg(short, long)]
         cron: Option<String>,
 
-        /// Run once at a specific time (ISO 8601 format, e.g. "2026-03-01T09:00:00")
+        /// Run once at a specific time, e.g. "2026-03-01T09:00", "tuesday at 9" or "in 2h"
         #[arg(long)]
         at: Option<String>,
 
+        /// IANA timezone for --cron and --at (default: the user's profile timezone)
+        #[arg(long)]
+        tz: Option<String>,
+
         /// Deliver the agent's response to a channel
         #[arg(short, long, default_value_t = false)]
         deliver: bool,
@@ -65,6 +71,10 @@ pub enum CronCommands {
         /// Channel name for delivery (e.g. "telegram", "whatsapp")
         #[arg(long)]
         channel: Option<String>,
+
+        /// Deliver the response even if it repeats a recent one
+        #[arg(long, default_value_t = false)]
+        force_delivery: bool,
     },
 
     /// Remove a scheduled job by ID
@@ -104,10 +114,12 @@ pub async fn dispatch(cmd: CronCommands) -> Result<()> {
             every,
             cron,
             at,
+            tz,
             deliver,
             to,
             channel,
-        } => add_job(name, message, every, cron, at, deliver, to, channel).await,
+            force_delivery,
+        } => add_job(name, message, every, cron, at, tz, deliver, to, channel, force_delivery).await,
         CronCommands::Remove { job_id } => remove_job(&job_id).await,
         CronCommands::Enable { job_id, disable } => enable_job(&job_id, !disable).await,
         CronCommands::Run { job_id } => run_job(&job_id).await,
@@ -149,6 +161,21 @@ fn format_timestamp_ms(ms: i64) -> String {
     }
 }
 
+/// The timezone named by `--tz`, else the one in the workspace `USER.md`,
+/// else `tools.calendar.timezone`.
+fn schedule_timezone(tz: Option<&str>) -> Result<Tz> {
+    if let Some(name) = tz {
+        return name
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Unknown timezone '{}' (use an IANA name like Europe/Madrid)", name));
+    }
+    let config = load_config(None);
+    let workspace = crate::helpers::expand_tilde(&config.agents.defaults.workspace);
+    let default = config.tools.calendar.timezone.parse().unwrap_or(Tz::UTC);
+    Ok(UserTimezone::new(workspace.join("USER.md"), default).get())
+}
+
 // ─────────────────────────────────────────────
 // Command implementations
 // ─────────────────────────────────────────────
@@ -205,10 +232,14 @@ async fn list_jobs(include_disabled: bool) -> Result<()> {
             "disabled".dimmed().to_string()
         };
 
-        // Format next run
-        let next_run = match job.state.next_run_at_ms {
-            Some(ms) => format_timestamp_ms(ms),
-            None => "—".to_string(),
+        // Format next run, in the job's timezone when it has one
+        let next_run = match (job.state.next_run_at_ms, job.schedule.tz.as_ref()) {
+            (Some(ms), Some(_)) => match (job.schedule.timezone(), chrono::DateTime::from_timestamp_millis(ms)) {
+                (Some(tz), Some(dt)) => dt.with_timezone(&tz).format("%Y-%m-%d %H:%M %Z").to_string(),
+                _ => format_timestamp_ms(ms),
+            },
+            (Some(ms), None) => format_timestamp_ms(ms),
+            (None, _) => "—".to_string(),
         };
 
         println!(
@@ -222,15 +253,18 @@ async fn list_jobs(include_disabled: bool) -> Result<()> {
 }
 
 /// `oxibot cron add`
+#[allow(clippy::too_many_arguments)]
 async fn add_job(
     name: String,
     message: String,
     every: Option<u64>,
     cron_expr: Option<String>,
     at: Option<String>,
+    tz: Option<String>,
     deliver: bool,
     to: Option<String>,
     channel: Option<String>,
+    force_delivery: bool,
 ) -> Result<()> {
     // Determine schedule
     let schedule = if let Some(secs) = every {
@@ -240,19 +274,15 @@ async fn add_job(
         let _ = expr
             .parse::<cron::Schedule>()
             .map_err(|e| anyhow::anyhow!("Invalid cron expression '{}': {}", expr, e))?;
-        CronSchedule::cron(expr)
+        let tz = schedule_timezone(tz.as_deref())?;
+        CronSchedule::cron(expr).with_tz(tz.name())
     } else if let Some(at_str) = at {
-        let dt = chrono::NaiveDateTime::parse_from_str(&at_str, "%Y-%m-%dT%H:%M:%S")
-            .or_else(|_| chrono::NaiveDateTime::parse_from_str(&at_str, "%Y-%m-%d %H:%M:%S"))
-            .or_else(|_| chrono::NaiveDateTime::parse_from_str(&at_str, "%Y-%m-%dT%H:%M"))
-            .map_err(|e| anyhow::anyhow!("Invalid datetime '{}': {} (expected ISO 8601, e.g. 2026-03-01T09:00:00)", at_str, e))?;
-        let local = chrono::Local::now().timezone();
-        let aware = dt.and_local_timezone(local);
-        let ts_ms = match aware {
-            chrono::LocalResult::Single(dt) => dt.timestamp_millis(),
-            _ => anyhow::bail!("Ambiguous or invalid local time: {}", at_str),
-        };
-        CronSchedule::at(ts_ms)
+        let tz = schedule_timezone(tz.as_deref())?;
+        let dt = parse_time(&at_str, chrono::Utc::now().with_timezone(&tz))?;
+        if dt < chrono::Utc::now() {
+            anyhow::bail!("{} is in the past", to_iso(&dt));
+        }
+        CronSchedule::at(dt.timestamp_millis()).with_tz(tz.name())
     } else {
         anyhow::bail!("Must specify one of: --every <seconds>, --cron <expression>, or --at <datetime>");
     };
@@ -262,6 +292,7 @@ async fn add_job(
         deliver,
         channel,
         to,
+        force_delivery,
     };
 
     let job = CronJob::new(name, schedule, payload);
@@ -270,12 +301,19 @@ async fn add_job(
     service.load().await.context("failed to load cron store")?;
     let id = service.add_job(job).await.context("failed to add job")?;
 
+    let job = service.get_job(&id).await;
     println!(
         "  {} Added job {} ({})",
         "✓".green(),
         id.cyan(),
-        service.get_job(&id).await.map(|j| j.name).unwrap_or_default()
+        job.as_ref().map(|j| j.name.clone()).unwrap_or_default()
     );
+    if let Some(job) = job {
+        let next = job.state.next_run_at_ms.and_then(chrono::DateTime::from_timestamp_millis);
+        if let (Some(next), Some(tz)) = (next, job.schedule.timezone()) {
+            println!("    Next run: {}", to_iso(&next.with_timezone(&tz)));
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,157 @@
+//! Gateway forms API — start a multi-turn form in a chat.
+//!
+//! Served behind [`Guarded`](oxibot_core::auth::Guarded) with the chat
+//! scope. `POST /api/forms` takes
+//! `{"channel": "telegram", "chatId": "42", "form": {...}, "message": "..."}`
+//! and hands the agent an inbound message carrying the form, so it starts
+//! asking for the fields in that chat. The completed form goes to the
+//! configured webhook the form names and to `forms/<name>.jsonl` in the
+//! workspace.
+//!
+//! Only channels the gateway runs are accepted. `cli` and `system` are
+//! internal and carry admin rights, so they are always refused.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::info;
+
+use oxibot_agent::forms::{Form, FormSpec, START_KEY};
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::http::{Handler, Request, Response};
+
+/// Path of the forms endpoint.
+pub const FORMS_PATH: &str = "/api/forms";
+
+/// Message the agent gets when the caller sends none.
+const DEFAULT_MESSAGE: &str = "Please start the form.";
+
+/// Internal channels a form may never be sent to.
+const INTERNAL_CHANNELS: &[&str] = &["cli", "system"];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartForm {
+    channel: String,
+    chat_id: String,
+    form: FormSpec,
+    #[serde(default)]
+    message: String,
+}
+
+/// Handler for `/api/forms`. Does no authentication itself.
+pub struct FormsApi {
+    bus: Arc<MessageBus>,
+    /// Channels registered with the gateway's channel manager.
+    channels: Vec<String>,
+}
+
+impl FormsApi {
+    pub fn new(bus: Arc<MessageBus>, channels: Vec<String>) -> Self {
+        Self { bus, channels }
+    }
+
+    async fn start(&self, body: &str) -> Response {
+        let start: StartForm = match serde_json::from_str(body) {
+            Ok(start) => start,
+            Err(e) => return Response::new("400 Bad Request", format!("Invalid request: {e}")),
+        };
+        if start.channel.trim().is_empty() || start.chat_id.trim().is_empty() {
+            return Response::new("400 Bad Request", "channel and chatId are required.");
+        }
+        if INTERNAL_CHANNELS.contains(&start.channel.as_str()) || !self.channels.contains(&start.channel) {
+            return Response::new("400 Bad Request", format!("Unknown channel '{}'.", start.channel));
+        }
+        let form = match Form::new(start.form) {
+            Ok(form) => form,
+            Err(e) => return Response::new("400 Bad Request", format!("{e:#}")),
+        };
+
+        let content = if start.message.trim().is_empty() { DEFAULT_MESSAGE } else { start.message.trim() };
+        let mut msg = InboundMessage::new(&start.channel, "api", &start.chat_id, content);
+        msg.metadata.insert(
+            START_KEY.into(),
+            serde_json::to_string(&form.spec).unwrap_or_default(),
+        );
+        if self.bus.publish_inbound(msg).await.is_err() {
+            return Response::new("503 Service Unavailable", "The agent is not running.");
+        }
+        info!(channel = %start.channel, chat_id = %start.chat_id, form = %form.spec.name, "form requested over the API");
+        Response::new("202 Accepted", format!("Form '{}' started.", form.spec.name))
+    }
+}
+
+#[async_trait]
+impl Handler for FormsApi {
+    async fn handle(&self, request: &Request) -> Option<Response> {
+        if request.path() != FORMS_PATH {
+            return None;
+        }
+        Some(match request.method.as_str() {
+            "POST" => self.start(&request.body).await,
+            _ => Response::new("405 Method Not Allowed", "Use POST."),
+        })
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(body: &str) -> Request {
+        Request {
+            method: "POST".into(),
+            target: FORMS_PATH.into(),
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_form_publishes_inbound() {
+        let bus = Arc::new(MessageBus::new(8));
+        let api = FormsApi::new(bus.clone(), vec!["telegram".into()]);
+
+        let other = Request { method: "GET".into(), target: "/admin/log-level".into(), ..Default::default() };
+        assert_eq!(api.handle(&other).await, None);
+
+        let bad = api.handle(&post(r#"{"channel": "telegram", "chatId": "42", "form": {"name": "x", "fields": []}}"#)).await.unwrap();
+        assert_eq!(bad.status, "400 Bad Request");
+
+        let ok = api
+            .handle(&post(
+                r#"{"channel": "telegram", "chatId": "42", "form": {"name": "intake", "fields": [{"name": "email", "type": "email"}]}}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(ok.status, "202 Accepted");
+
+        let msg = bus.consume_inbound().await.unwrap();
+        assert_eq!((msg.channel.as_str(), msg.chat_id.as_str(), msg.sender_id.as_str()), ("telegram", "42", "api"));
+        assert_eq!(msg.content, DEFAULT_MESSAGE);
+        let spec: FormSpec = serde_json::from_str(&msg.metadata[START_KEY]).unwrap();
+        assert_eq!(spec.name, "intake");
+    }
+
+    #[tokio::test]
+    async fn test_start_form_refuses_internal_and_unknown_channels() {
+        let bus = Arc::new(MessageBus::new(8));
+        // Even if an internal channel were registered, it is refused
+        let api = FormsApi::new(bus.clone(), vec!["cli".into(), "telegram".into()]);
+        let form = r#""form": {"name": "intake", "fields": [{"name": "email", "type": "email"}]}"#;
+
+        for channel in ["cli", "system", "discord"] {
+            let body = format!(r#"{{"channel": "{channel}", "chatId": "direct", "message": "/reset", {form}}}"#);
+            let refused = api.handle(&post(&body)).await.unwrap();
+            assert_eq!(refused.status, "400 Bad Request", "{channel}");
+        }
+        let next = tokio::time::timeout(std::time::Duration::from_millis(50), bus.consume_inbound()).await;
+        assert!(next.is_err(), "nothing reaches the agent");
+    }
+}
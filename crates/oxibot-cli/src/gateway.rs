@@ -5,9 +5,9 @@
 //! Startup sequence:
 //! 1. Load config
 //! 2. Create message bus
-//! 3. Create agent loop (with provider, tools, sessions)
+//! 3. Create agent loops (default + one per bound profile)
 //! 4. Create channel manager, register enabled channels
-//! 5. Run: `tokio::select!` of agent loop + channel manager
+//! 5. Run: `tokio::select!` of agent router + channel manager
 //! 6. Handle Ctrl+C for graceful shutdown
 
 use std::sync::Arc;
@@ -15,27 +15,43 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tracing::info;
 
-use oxibot_agent::{AgentLoop, ExecToolConfig};
+use oxibot_agent::{build_inbound_filters, AgentLoop, AgentRouter};
 use oxibot_channels::ChannelManager;
+use oxibot_core::auth::{ApiAuth, Guarded, Scope};
+use oxibot_core::bus::outbox::Outbox;
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::bus::types::OutboundMessage;
 use oxibot_core::config::load_config;
+use oxibot_core::config::schema::AgentProfile;
+use oxibot_core::config::Config;
+use oxibot_core::crash::{self, CrashReporter};
+use oxibot_core::error;
 use oxibot_core::heartbeat::HeartbeatService;
-use oxibot_core::session::SessionManager;
+use oxibot_core::oauth::OAuthManager;
+use oxibot_core::session::{SessionManager, TranscriptLog};
+use oxibot_core::sync::WorkspaceSync;
+use oxibot_core::tool_stats::ToolStatsStore;
+use oxibot_core::usage::UsageLog;
+use oxibot_core::utils::{get_contacts_path, get_outbox_path, get_provider_health_path};
 use oxibot_cron::CronService;
-use oxibot_providers::http_provider::create_provider;
+use oxibot_providers::health::{self, ProviderHealth};
 
+use crate::admin::{AdminApi, ADMIN_PREFIX};
+use crate::forms_api::{FormsApi, FORMS_PATH};
 use crate::helpers;
 
 /// Run the gateway — starts the agent loop + channel manager.
-pub async fn run() -> Result<()> {
+///
+/// `dry_run` forces `tools.dryRun` on for this run.
+pub async fn run(dry_run: bool) -> Result<()> {
     println!();
     helpers::print_banner();
     println!("  Mode: Gateway");
     println!();
 
     // 1. Load config
-    let config = load_config(None);
+    let mut config = load_config(None);
+    config.tools.dry_run |= dry_run;
     let defaults = &config.agents.defaults;
 
     // 2. Resolve workspace
@@ -43,61 +59,120 @@ pub async fn run() -> Result<()> {
     std::fs::create_dir_all(&workspace)
         .with_context(|| format!("failed to create workspace: {}", workspace.display()))?;
 
-    // 3. Create message bus (shared between agent + channels)
-    let bus = Arc::new(MessageBus::new(100));
+    // 2b. Pull the workspace from remote storage before anything reads it
+    let workspace_sync = WorkspaceSync::from_config(&config.sync, &workspace)
+        .context("invalid sync config")?
+        .map(Arc::new);
+    if let Some(sync) = &workspace_sync {
+        match sync.sync().await {
+            Ok(report) => info!(%report, backend = %config.sync.backend, "workspace synced"),
+            Err(e) => tracing::error!(error = %format!("{e:#}"), "workspace sync failed"),
+        }
+    }
 
-    // 4. Create provider
+    // 3. Create message bus (shared between agent + channels) with the inbound filters
+    let bus = build_inbound_filters(&config.channels.inbound)
+        .context("invalid channels.inbound config")?
+        .into_iter()
+        .fold(MessageBus::new(100), MessageBus::with_inbound_filter)
+        .with_admins(config.agents.defaults.commands.admins.clone());
+    let bus = Arc::new(bus);
+
+    // Crash reports for channels and agent turns that panic
+    crash::install_panic_hook();
+    let supervisor = &config.channels.supervisor;
+    let crashes = CrashReporter::new(bus.clone(), None).with_admin(&supervisor.alert_channel, &supervisor.alert_chat_id);
+    crashes.watch();
+
+    // 4. Create the default agent loop (Arc-wrapped for sharing with cron callback)
     let model = &defaults.model;
-    let providers_map = config.providers.to_map();
-    let provider = create_provider(model, &providers_map)
-        .map_err(|e| anyhow::anyhow!(e))?;
-
-    // 5. Brave API key
-    let brave_key = if config.tools.web.search.api_key.is_empty() {
-        None
-    } else {
-        Some(config.tools.web.search.api_key.clone())
-    };
-
-    // 6. Create session manager
-    let session_manager = SessionManager::new(None)
-        .context("failed to create session manager")?;
-
-    // 7. Create agent loop (Arc-wrapped for sharing with cron callback)
-    let agent_loop = Arc::new(AgentLoop::new(
-        bus.clone(),
-        Arc::new(provider),
-        workspace.clone(),
-        Some(model.to_string()),
-        Some(defaults.max_tool_iterations as usize),
-        None,
-        brave_key,
-        Some(ExecToolConfig::default()),
-        config.tools.restrict_to_workspace,
-        Some(session_manager),
-        None,
-    ));
-
-    // 8. Create cron service
+    oxibot_providers::register_custom_providers(&config.providers.custom);
+    let oauth = crate::oauth_cmd::build_manager(&config);
+    let tool_stats = Arc::new(ToolStatsStore::new(None));
     let cron_service = Arc::new(CronService::new(bus.clone(), None));
+    let health_config = &config.providers.health;
+    let health = Arc::new(ProviderHealth::new(Some(get_provider_health_path()), health_config.history));
+    if health_config.enabled {
+        health::spawn_monitor(
+            health.clone(),
+            health::probed_providers(&config.providers.to_map()),
+            std::time::Duration::from_secs(health_config.interval_secs.max(10)),
+        );
+    }
+    let agent_loop = Arc::new(build_agent(&config, &bus, &oauth, &tool_stats, &cron_service, &health, None)?);
+
+    // 5. Create one agent loop per profile referenced by a binding or deep link
+    let mut router = AgentRouter::new(agent_loop.clone())
+        .with_bindings(config.agents.clone())
+        .with_crash_reporter(crashes.clone());
+    let mut bound_profiles: Vec<&str> = Vec::new();
+    let referenced = config
+        .agents
+        .bindings
+        .iter()
+        .map(|b| (b.agent.as_str(), format!("binding for channel '{}'", b.channel)))
+        .chain(
+            config
+                .channels
+                .telegram
+                .deep_links
+                .iter()
+                .map(|(token, agent)| (agent.as_str(), format!("telegram deep link '{token}'"))),
+        );
+    for (name, source) in referenced {
+        if bound_profiles.contains(&name) {
+            continue;
+        }
+        let profile = config
+            .agents
+            .profiles
+            .get(name)
+            .with_context(|| format!("{source} uses unknown agent profile '{name}'"))?;
+        let agent = build_agent(&config, &bus, &oauth, &tool_stats, &cron_service, &health, Some(profile))
+            .with_context(|| format!("failed to build agent profile '{name}'"))?;
+        router = router.with_profile(name, Arc::new(agent));
+        bound_profiles.push(name);
+    }
+    let router = Arc::new(router);
+
+    // 8. Run cron jobs through the agent bound to the chat they report to
     {
-        let agent = agent_loop.clone();
+        let router = router.clone();
+        let default = agent_loop.clone();
         let bus = bus.clone();
+        let crashes = crashes.clone();
+        let show_error_ids = config.agents.defaults.show_error_ids;
         cron_service
             .set_on_job(Arc::new(move |job: oxibot_cron::CronJob| {
-                let agent = agent.clone();
+                let agent = match (&job.payload.channel, &job.payload.to) {
+                    (Some(channel), Some(chat_id)) => router.agent_for_chat(channel, chat_id).clone(),
+                    _ => default.clone(),
+                };
                 let bus = bus.clone();
+                let crashes = crashes.clone();
                 Box::pin(async move {
-                    let response = agent
-                        .process_direct(&job.payload.message)
+                    let message = job.payload.message.clone();
+                    let response = isolated(&crashes, "cron job", async move { agent.process_direct(&message).await })
                         .await
-                        .unwrap_or_else(|e| format!("Error: {e}"));
+                        .unwrap_or_else(|e| {
+                            let error_id = error::correlation_id();
+                            tracing::error!(
+                                error = %format!("{e:#}"),
+                                error_id = %error_id,
+                                job = %job.name,
+                                "cron job failed"
+                            );
+                            error::render(&e, show_error_ids.then_some(error_id.as_str()))
+                        });
 
                     // Deliver result to channel if configured
                     if job.payload.deliver {
                         if let Some(ref chat_id) = job.payload.to {
                             let channel = job.payload.channel.as_deref().unwrap_or("cli");
-                            let msg = OutboundMessage::new(channel, chat_id.as_str(), &response);
+                            let mut msg = OutboundMessage::new(channel, chat_id.as_str(), &response);
+                            if job.payload.force_delivery {
+                                msg = msg.forced();
+                            }
                             if let Err(e) = bus.publish_outbound(msg).await {
                                 tracing::error!(error = %e, "failed to deliver cron result");
                             }
@@ -119,9 +194,11 @@ pub async fn run() -> Result<()> {
     // 9. Create heartbeat service
     let heartbeat = {
         let agent = agent_loop.clone();
+        let crashes = crashes.clone();
         let callback: oxibot_core::heartbeat::OnHeartbeatFn = Arc::new(move |prompt| {
             let agent = agent.clone();
-            Box::pin(async move { agent.process_direct(&prompt).await })
+            let crashes = crashes.clone();
+            Box::pin(async move { isolated(&crashes, "heartbeat", async move { agent.process_direct(&prompt).await }).await })
         });
         Arc::new(HeartbeatService::new(
             workspace.clone(),
@@ -131,10 +208,27 @@ pub async fn run() -> Result<()> {
         ))
     };
 
+    // Provider batch jobs waiting to post their results
+    tokio::spawn(crate::batch_jobs::watch(config.clone(), bus.clone()));
+    if let Some(sync) = workspace_sync {
+        let interval = std::time::Duration::from_secs(config.sync.interval_secs.max(10));
+        tokio::spawn(sync.run(interval));
+    }
+
     // 10. Create channel manager
     // Register configured channels
     #[allow(unused_mut)]
-    let mut channel_manager = ChannelManager::new(bus.clone());
+    let mut channel_manager = ChannelManager::new(bus.clone())
+        .with_supervisor(config.channels.supervisor.clone())
+        .with_pacing(config.channels.pacing.clone())
+        .with_delivery(config.channels.delivery.clone())
+        .with_repeats(config.channels.repeats.clone())
+        .with_code_files(config.channels.code_files.clone())
+        .with_quiet_hours(config.channels.quiet_hours.clone())
+        .with_crash_reporter(crashes.clone())
+        .with_outbox(Outbox::new(get_outbox_path()));
+    #[allow(unused_variables)]
+    let enrollment = helpers::enrollment(&config, &bus)?;
 
     // Telegram
     #[cfg(feature = "telegram")]
@@ -146,35 +240,14 @@ pub async fn run() -> Result<()> {
                 tg.token.clone(),
                 bus.clone(),
                 tg.allowed_users.clone(),
-            );
-
-            // Wire voice transcription if configured
-            if config.transcription.enabled {
-                let tc = &config.transcription;
-                // Resolve API key: config > groq provider key > env var
-                let transcription_key = if !tc.api_key.is_empty() {
-                    tc.api_key.clone()
-                } else if !config.providers.groq.api_key.is_empty() {
-                    config.providers.groq.api_key.clone()
-                } else {
-                    String::new()
-                };
-
-                if !transcription_key.is_empty() {
-                    use oxibot_providers::GroqTranscriber;
-                    use oxibot_providers::TranscriptionProvider;
-                    let transcriber = Arc::new(GroqTranscriber::new(&transcription_key));
-                    if transcriber.is_configured() {
-                        let t = transcriber.clone();
-                        telegram = telegram.with_transcriber(Arc::new(move |path: String| {
-                            let t = t.clone();
-                            Box::pin(async move {
-                                t.transcribe(std::path::Path::new(&path)).await
-                            })
-                        }));
-                        info!("voice transcription enabled (Groq Whisper)");
-                    }
-                }
+            )
+            .with_deep_links(tg.deep_links.clone(), None)
+            .with_acknowledgment(&tg.acknowledge)
+            .with_observe_only(tg.observe_only)
+            .with_enrollment(enrollment.clone());
+
+            if let Some(transcriber) = build_transcriber(&config) {
+                telegram = telegram.with_transcriber(transcriber);
             }
 
             channel_manager.register(Arc::new(telegram));
@@ -188,11 +261,22 @@ pub async fn run() -> Result<()> {
         let dc = &config.channels.discord;
         if !dc.token.is_empty() {
             use oxibot_channels::discord::DiscordChannel;
-            let discord = DiscordChannel::new(
+            let mut discord = DiscordChannel::new(
                 dc.token.clone(),
                 bus.clone(),
                 dc.allowed_users.clone(),
-            );
+            )
+            .with_acknowledgment(&dc.acknowledge)
+            .with_embeds(dc.embeds)
+            .with_observe_only(dc.observe_only)
+            .with_enrollment(enrollment.clone());
+            if let Some(transcriber) = build_transcriber(&config) {
+                discord = discord.with_transcriber(transcriber);
+            }
+            #[cfg(feature = "discord-voice")]
+            {
+                discord = discord.with_voice(&dc.voice);
+            }
             channel_manager.register(Arc::new(discord));
             info!("registered discord channel");
         }
@@ -208,12 +292,25 @@ pub async fn run() -> Result<()> {
                 wa.bridge_url.clone(),
                 bus.clone(),
                 wa.allowed_users.clone(),
-            );
+            )
+            .with_enrollment(enrollment.clone());
             channel_manager.register(Arc::new(whatsapp));
             info!("registered whatsapp channel");
         }
     }
 
+    // Signal
+    #[cfg(feature = "signal")]
+    {
+        let sg = &config.channels.signal;
+        if !sg.account.is_empty() {
+            use oxibot_channels::signal::SignalChannel;
+            let signal = SignalChannel::new(sg.clone(), bus.clone()).with_enrollment(enrollment.clone());
+            channel_manager.register(Arc::new(signal));
+            info!("registered signal channel");
+        }
+    }
+
     // Slack
     #[cfg(feature = "slack")]
     {
@@ -237,6 +334,45 @@ pub async fn run() -> Result<()> {
             info!("registered email channel");
         }
     }
+
+    // File drop
+    #[cfg(feature = "filedrop")]
+    {
+        let fd = &config.channels.filedrop;
+        if !fd.dir.is_empty() {
+            use oxibot_channels::filedrop::FileDropChannel;
+            let filedrop = FileDropChannel::new(
+                helpers::expand_tilde(&fd.dir),
+                fd.poll_interval_ms,
+                bus.clone(),
+            );
+            channel_manager.register(Arc::new(filedrop));
+            info!("registered filedrop channel");
+        }
+    }
+    // HTTP server: OAuth callbacks and the admin and forms APIs. The APIs are
+    // always mounted: keys created while the gateway runs apply at once
+    let http_addr = format!("{}:{}", config.gateway.host, config.gateway.port);
+    let mut handlers: Vec<Arc<dyn oxibot_core::http::Handler>> = Vec::new();
+    if !oauth.clients().is_empty() {
+        handlers.push(oauth.clone());
+    }
+    let api_auth = Arc::new(ApiAuth::new(None, config.gateway.admin_token.clone(), config.gateway.jwt.clone()));
+    let admin_configured = api_auth.is_configured();
+    handlers.push(Arc::new(Guarded::new(ADMIN_PREFIX, Scope::Admin, api_auth.clone(), Arc::new(AdminApi::new(oauth.clone())))));
+    handlers.push(Arc::new(Guarded::new(FORMS_PATH, Scope::Chat, api_auth, Arc::new(FormsApi::new(bus.clone(), channel_manager.channel_names())))));
+    let http_listening = match tokio::net::TcpListener::bind(&http_addr).await {
+        Ok(listener) => {
+            tokio::spawn(oxibot_core::http::serve(listener, handlers));
+            true
+        }
+        Err(e) => {
+            tracing::warn!(addr = %http_addr, error = %e, "cannot start gateway http server");
+            false
+        }
+    };
+    let oauth_listening = http_listening && !oauth.clients().is_empty();
+
     info!(
         model = %model,
         workspace = %workspace.display(),
@@ -248,10 +384,27 @@ pub async fn run() -> Result<()> {
         "  Model:     {}",
         model
     );
+    if !defaults.planner_model.is_empty() || !defaults.responder_model.is_empty() {
+        println!(
+            "  Routing:   planner={} responder={}",
+            if defaults.planner_model.is_empty() { model } else { &defaults.planner_model },
+            if defaults.responder_model.is_empty() { model } else { &defaults.responder_model },
+        );
+    }
     println!(
         "  Workspace: {}",
         workspace.display()
     );
+    if config.tools.dry_run {
+        println!("  Dry run:   on (file writes, edits and commands are previewed)");
+    }
+    if !bound_profiles.is_empty() {
+        println!(
+            "  Bindings:  {} ({} profiles)",
+            config.agents.bindings.len(),
+            bound_profiles.len()
+        );
+    }
     println!(
         "  Channels:  {} registered",
         channel_manager.len()
@@ -261,6 +414,16 @@ pub async fn run() -> Result<()> {
         println!("  Cron:      {} jobs ({} enabled)", cron_jobs.len(), enabled);
     }
     println!("  Heartbeat: every 30m");
+    if oauth_listening {
+        println!("  OAuth:     {} clients (callbacks on {})", oauth.clients().len(), http_addr);
+    }
+    if http_listening {
+        println!("  Admin:     http://{}/admin/", http_addr);
+        println!("  Forms:     http://{}{}", http_addr, FORMS_PATH);
+        if !admin_configured {
+            println!("             (no credentials yet: run `oxibot apikeys create`)");
+        }
+    }
     println!();
 
     if channel_manager.is_empty() {
@@ -276,8 +439,8 @@ pub async fn run() -> Result<()> {
     // 11. Run: agent loop + channel manager + cron + heartbeat concurrently
     //     Ctrl+C triggers graceful shutdown
     tokio::select! {
-        _ = agent_loop.run() => {
-            info!("agent loop exited");
+        _ = router.run(&bus) => {
+            info!("agent router exited");
         }
         result = channel_manager.start_all() => {
             if let Err(e) = result {
@@ -308,6 +471,210 @@ pub async fn run() -> Result<()> {
     Ok(())
 }
 
+/// Run an agent call on its own task, so that a panic becomes an error
+/// (and a crash report) instead of stopping the gateway.
+async fn isolated(
+    crashes: &CrashReporter,
+    component: &str,
+    call: impl std::future::Future<Output = Result<String>> + Send + 'static,
+) -> Result<String> {
+    match tokio::spawn(call).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => {
+            let payload = e.into_panic();
+            let message = crash::panic_message(&*payload);
+            crashes.report(component, payload).await;
+            anyhow::bail!("{component} crashed: {message}")
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Build an agent loop from the agent defaults with a profile's overrides.
+fn build_agent(
+    config: &Config,
+    bus: &Arc<MessageBus>,
+    oauth: &Arc<OAuthManager>,
+    tool_stats: &Arc<ToolStatsStore>,
+    cron: &Arc<CronService>,
+    health: &Arc<ProviderHealth>,
+    profile: Option<&AgentProfile>,
+) -> Result<AgentLoop> {
+    let defaults = &config.agents.defaults;
+    let profile = profile.cloned().unwrap_or_default();
+
+    // Resolve workspace + model (empty profile values fall back to defaults)
+    let workspace = helpers::expand_tilde(if profile.workspace.is_empty() {
+        &defaults.workspace
+    } else {
+        &profile.workspace
+    });
+    std::fs::create_dir_all(&workspace)
+        .with_context(|| format!("failed to create workspace: {}", workspace.display()))?;
+    let model = if profile.model.is_empty() { &defaults.model } else { &profile.model };
+
+    // Create provider
+    let providers_map = config.providers.to_map();
+    let provider = helpers::agent_provider(config, model, &providers_map, Some(health))?;
+
+    // Brave API key
+    let brave_key = if config.tools.web.search.api_key.is_empty() {
+        None
+    } else {
+        Some(config.tools.web.search.api_key.clone())
+    };
+
+    // Session manager
+    let session_manager = SessionManager::new(None)
+        .context("failed to create session manager")?;
+
+    // Planner/responder routing only applies to the default model
+    let (planner, responder) = if profile.model.is_empty() {
+        (
+            helpers::model_route(config, &defaults.planner_model, &providers_map)?,
+            helpers::model_route(config, &defaults.responder_model, &providers_map)?,
+        )
+    } else {
+        (None, None)
+    };
+
+    let repair = &defaults.tool_call_repair;
+    let fallback = helpers::model_route(config, &repair.fallback_model, &providers_map)?;
+    let quick = &defaults.quick_replies;
+    let quick_route = helpers::model_route(config, &quick.model, &providers_map)?;
+    let ack = &defaults.ack_first;
+    let ack_route = helpers::model_route(config, &ack.model, &providers_map)?;
+    let translation = &config.channels.translation;
+    let translation_route = helpers::model_route(config, &translation.model, &providers_map)?;
+    let persona = helpers::persona(if profile.persona.is_empty() {
+        &defaults.persona
+    } else {
+        &profile.persona
+    })?;
+
+    let scratch = helpers::scratch_dirs(&config.tools.scratch, &workspace);
+    let (calendar, timezone) = helpers::calendar(config, oauth)?;
+    let context_providers = helpers::context_providers(config, &workspace, calendar.as_ref(), timezone);
+    Ok(AgentLoop::new(
+        bus.clone(),
+        provider,
+        workspace,
+        Some(model.to_string()),
+        Some(defaults.max_tool_iterations as usize),
+        None,
+        brave_key,
+        Some(helpers::exec_config(config)),
+        // Read-only agents never look outside their workspace
+        config.tools.restrict_to_workspace || profile.read_only,
+        Some(session_manager),
+        None,
+    )
+    .with_scratch(scratch)
+    .with_model_routing(planner, responder)
+    .with_quick_replies(quick.enabled.then(|| quick.clone()), quick_route)
+    .with_ack_first(ack.enabled.then(|| ack.clone()), ack_route)
+    .with_translation((!translation.channels.is_empty()).then(|| translation.clone()), translation_route)
+    .with_debug_footer(defaults.debug_footer.clone(), config.analytics.prices.clone())
+    .with_form_webhooks(config.tools.forms.webhooks.clone())
+    .with_persona(persona)
+    .with_responses(defaults.responses.clone())
+    .with_commands(defaults.commands.clone())
+    .with_tool_call_repair(repair.max_failures, fallback)
+    .with_repeated_failure_limit(repair.max_repeated_failures)
+    .with_tool_breaker(defaults.tool_breaker.clone())
+    .with_tool_result_compaction(
+        defaults.tool_results.compact.then(|| defaults.tool_results.clone()),
+    )
+    .with_tokenizers(config.tokenizer.clone())
+    .with_context_budget(defaults.max_context_tokens)
+    .with_context_windows(defaults.context_windows.clone())
+    .with_contacts(Some(get_contacts_path()))
+    .with_calendar(calendar, timezone)
+    .with_github(helpers::github(config))
+    .with_cron(Some(cron.clone()), timezone)
+    .with_browser(config.tools.browser.enabled.then(|| config.tools.browser.clone()))
+    .with_web_search(helpers::web_search_route(config, model, &providers_map)?)
+    .with_page_cache(helpers::page_cache(config, &providers_map)?)
+    .with_file_index(config.tools.file_index.enabled.then(|| config.tools.file_index.clone()))
+    .with_redaction(helpers::redactor(config)?)
+    .with_skill_secrets(config.skills.secrets.clone())
+    .with_plan_mode(defaults.plan_mode.enabled.then(|| defaults.plan_mode.clone()))
+    .with_file_uploads(helpers::file_uploader(config, model, &providers_map)?, defaults.file_uploads.clone())
+    .with_context_providers(
+        context_providers,
+        std::time::Duration::from_millis(defaults.context_providers.timeout_ms),
+    )
+    .with_usage_log(config.analytics.enabled.then(|| UsageLog::new(None)))
+    .with_transcripts(config.analytics.transcripts.then(|| TranscriptLog::new(None)))
+    .with_tool_stats(Some(tool_stats.clone()))
+    .with_dry_run(config.tools.dry_run)
+    .with_error_ids(defaults.show_error_ids)
+    .with_tool_filter(&profile.tools, profile.read_only))
+}
+
+/// Voice transcription callback for channels, if transcription is enabled
+/// and an API key is available.
+#[cfg(any(feature = "telegram", feature = "discord"))]
+fn build_transcriber(config: &Config) -> Option<oxibot_channels::base::TranscribeFn> {
+    use oxibot_providers::transcription::profile_language;
+    use oxibot_providers::{AudioPreprocessor, CachedTranscriber, GroqTranscriber};
+    use oxibot_providers::{TranscriptionOptions, TranscriptionProvider};
+
+    let tc = &config.transcription;
+    if !tc.enabled {
+        return None;
+    }
+    // Resolve API key: config > groq provider key
+    let transcription_key = if !tc.api_key.is_empty() {
+        tc.api_key.clone()
+    } else if !config.providers.groq.api_key.is_empty() {
+        config.providers.groq.api_key.clone()
+    } else {
+        return None;
+    };
+
+    let preprocessor = AudioPreprocessor::new()
+        .with_binaries(&tc.ffmpeg_path, &tc.ffprobe_path)
+        .with_chunking(tc.chunk_secs as f64, tc.chunk_overlap_secs as f64);
+    let groq = GroqTranscriber::new(&transcription_key)
+        .with_model(&tc.model)
+        .with_preprocessor(preprocessor);
+    if !groq.is_configured() {
+        return None;
+    }
+    let t: Arc<dyn TranscriptionProvider> = if tc.cache {
+        Arc::new(CachedTranscriber::new(groq))
+    } else {
+        Arc::new(groq)
+    };
+    let language = tc.language.trim().to_lowercase();
+    let profile = helpers::expand_tilde(&config.agents.defaults.workspace).join("USER.md");
+    let options = TranscriptionOptions {
+        language: (!language.is_empty() && language != "auto").then_some(language.clone()),
+        temperature: (tc.temperature > 0.0).then_some(tc.temperature),
+        word_timestamps: tc.word_timestamps,
+    };
+    let low_confidence = tc.mark_low_confidence.then_some(tc.low_confidence_threshold);
+    info!("voice transcription enabled (Groq Whisper)");
+    Some(Arc::new(move |path: String| {
+        let t = t.clone();
+        let mut options = options.clone();
+        // Re-read the profile so edits to USER.md apply without a restart.
+        if language.is_empty() {
+            options.language = std::fs::read_to_string(&profile)
+                .ok()
+                .and_then(|text| profile_language(&text));
+        }
+        Box::pin(async move {
+            let mut transcript = t.transcribe_with(std::path::Path::new(&path), &options).await?;
+            if let Some(threshold) = low_confidence {
+                transcript.mark_low_confidence(threshold);
+            }
+            Ok(transcript)
+        })
+    }))
+}
+
 // ─────────────────────────────────────────────
 // Tests
 // ─────────────────────────────────────────────
@@ -319,6 +686,7 @@ mod tests {
     // Here we just verify the module compiles and the imports work.
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_module_compiles() {
         // If this test runs, the gateway module compiles correctly
         assert!(true);
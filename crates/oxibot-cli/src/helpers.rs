@@ -1,8 +1,31 @@
 //! Shared CLI helpers — path expansion, response printing, version banner.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::{Context, Result};
 use colored::Colorize;
+use oxibot_agent::context_providers::{CalendarPeekProvider, ContextProvider, GitStatusProvider};
+use oxibot_agent::page_cache::PageCache;
+use oxibot_agent::persona::{self, Persona};
+use oxibot_agent::redaction::Redactor;
+use oxibot_agent::tools::calendar::{CalDavBackend, CalendarBackend, GoogleCalendarBackend, Tz, UserTimezone};
+use oxibot_agent::tools::github::GitHubClient;
+use oxibot_agent::{ExecToolConfig, ModelRoute, ScratchDirs};
+use oxibot_channels::enrollment::Enrollment;
+use oxibot_core::bus::MessageBus;
+use oxibot_core::config::schema::{CalendarConfig, ProviderConfig, ScratchConfig};
+use oxibot_core::config::Config;
+use oxibot_core::oauth::OAuthManager;
+use oxibot_core::error::ConfigError;
+use oxibot_core::utils::get_page_cache_path;
+use oxibot_providers::embeddings::create_embedder;
+use oxibot_providers::failover::{self, FailoverProvider};
+use oxibot_providers::files::{create_file_uploader, FileUploader};
+use oxibot_providers::http_provider::{create_provider, HttpProvider};
+use oxibot_providers::{LlmProvider, ProviderHealth, ResponseCache};
 
 /// Expand `~` at the start of a path to the user's home directory.
 pub fn expand_tilde(path: &str) -> PathBuf {
@@ -19,6 +42,229 @@ pub fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Build session scratch directories from config (`None` when disabled).
+pub fn scratch_dirs(config: &ScratchConfig, workspace: &Path) -> Option<ScratchDirs> {
+    config.enabled.then(|| {
+        ScratchDirs::new(workspace)
+            .with_retention(Duration::from_secs(config.retention_hours * 3600))
+    })
+}
+
+/// Exec tool settings from config.
+pub fn exec_config(config: &Config) -> ExecToolConfig {
+    let exec = &config.tools.exec;
+    ExecToolConfig {
+        timeout: exec.timeout,
+        approval_timeout: exec.ask_approval.then(|| Duration::from_secs(exec.approval_timeout)),
+    }
+}
+
+/// Create the provider for `model`, recording or replaying its responses
+/// when `providers.responseCache` is on.
+pub fn provider(
+    config: &Config,
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+) -> Result<HttpProvider> {
+    let provider = create_provider(model, providers).map_err(ConfigError)?;
+    Ok(provider.with_cache(response_cache(config)?))
+}
+
+/// The response cache from `providers.responseCache` (`None` when off).
+fn response_cache(config: &Config) -> Result<Option<ResponseCache>> {
+    let mut cache_config = config.providers.response_cache.clone();
+    cache_config.dir = expand_tilde(&cache_config.dir).display().to_string();
+    Ok(ResponseCache::from_config(&cache_config)
+        .map_err(|e| ConfigError(format!("providers.responseCache: {e}")))?)
+}
+
+/// Create the agent's provider for `model`. With `health` and
+/// `providers.health.failover` on, and more than one configured provider
+/// able to serve the model, calls go to the healthiest of them.
+pub fn agent_provider(
+    config: &Config,
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+    health: Option<&Arc<ProviderHealth>>,
+) -> Result<Arc<dyn LlmProvider>> {
+    let candidates = failover::candidates(model, providers);
+    let Some(health) = health.filter(|_| config.providers.health.failover && candidates.len() > 1) else {
+        return Ok(Arc::new(provider(config, model, providers)?));
+    };
+    let cache = response_cache(config)?;
+    let candidates = candidates
+        .into_iter()
+        .map(|(provider_config, spec)| HttpProvider::new(provider_config, spec, model).with_cache(cache.clone()))
+        .collect();
+    Ok(Arc::new(FailoverProvider::new(candidates, health.clone())))
+}
+
+/// Build a provider route for a phase-specific model (`None` when unset).
+pub fn model_route(
+    config: &Config,
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+) -> Result<Option<ModelRoute>> {
+    if model.is_empty() {
+        return Ok(None);
+    }
+    let provider = provider(config, model, providers)?;
+    Ok(Some(ModelRoute::new(Arc::new(provider), model)))
+}
+
+/// Route for provider-side web search (`None` when `tools.web.search.native`
+/// is off). Uses `nativeModel`, or `model` when that is empty.
+pub fn web_search_route(
+    config: &Config,
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+) -> Result<Option<ModelRoute>> {
+    let search = &config.tools.web.search;
+    if !search.native {
+        return Ok(None);
+    }
+    let model = if search.native_model.is_empty() { model } else { &search.native_model };
+    model_route(config, model, providers)
+}
+
+/// Resolve a persona preset name (`None` when unset).
+pub fn persona(name: &str) -> Result<Option<&'static Persona>> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    persona::find(name)
+        .map(Some)
+        .ok_or_else(|| {
+            ConfigError(format!("unknown persona '{name}' (available: {})", persona::names())).into()
+        })
+}
+
+/// Build the secret redactor (`None` when disabled).
+pub fn redactor(config: &Config) -> Result<Option<Arc<Redactor>>> {
+    if !config.redaction.enabled {
+        return Ok(None);
+    }
+    let redactor = Redactor::new(&config.redaction).context("invalid redaction config")?;
+    Ok(Some(Arc::new(redactor)))
+}
+
+/// Build the document uploader for `model` (`None` when disabled).
+pub fn file_uploader(
+    config: &Config,
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+) -> Result<Option<Arc<dyn FileUploader>>> {
+    if !config.agents.defaults.file_uploads.enabled {
+        return Ok(None);
+    }
+    let uploader = create_file_uploader(model, providers)
+        .map_err(|e| ConfigError(format!("agents.defaults.fileUploads: {e}")))?;
+    Ok(Some(Arc::from(uploader)))
+}
+
+/// Build the cache of fetched web pages (`None` when disabled), embedding
+/// with `embeddingModel` when one is set.
+pub fn page_cache(config: &Config, providers: &HashMap<String, ProviderConfig>) -> Result<Option<Arc<PageCache>>> {
+    let settings = &config.tools.web.page_cache;
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let mut cache = PageCache::new(get_page_cache_path(), settings.ttl_hours);
+    if !settings.embedding_model.is_empty() {
+        let embedder = create_embedder(&settings.embedding_model, providers)
+            .map_err(|e| ConfigError(format!("tools.web.pageCache.embeddingModel: {e}")))?;
+        cache = cache.with_embedder(Arc::from(embedder));
+    }
+    Ok(Some(Arc::new(cache)))
+}
+
+/// Build the enabled built-in context providers.
+pub fn context_providers(
+    config: &Config,
+    workspace: &Path,
+    calendar: Option<&Arc<dyn CalendarBackend>>,
+    timezone: Tz,
+) -> Vec<Arc<dyn ContextProvider>> {
+    let settings = &config.agents.defaults.context_providers;
+    let mut providers: Vec<Arc<dyn ContextProvider>> = Vec::new();
+    if settings.git_status {
+        providers.push(Arc::new(GitStatusProvider::new(workspace)));
+    }
+    if let Some(backend) = calendar.filter(|_| settings.calendar_hours > 0) {
+        let timezone = UserTimezone::new(workspace.join("USER.md"), timezone);
+        providers.push(Arc::new(CalendarPeekProvider::new(backend.clone(), timezone, settings.calendar_hours)));
+    }
+    providers
+}
+
+/// Build the sender enrollment flow (`None` when disabled).
+pub fn enrollment(config: &Config, bus: &Arc<MessageBus>) -> Result<Option<Arc<Enrollment>>> {
+    let enrollment = &config.channels.enrollment;
+    if !enrollment.enabled {
+        return Ok(None);
+    }
+    if enrollment.admin_channel.is_empty() || enrollment.admin_chat_id.is_empty() {
+        return Err(ConfigError(
+            "channels.enrollment needs adminChannel and adminChatId to send approval requests to".into(),
+        )
+        .into());
+    }
+    if config.agents.defaults.commands.admins.is_empty() {
+        return Err(ConfigError("channels.enrollment needs agents.defaults.commands.admins: only admins can let senders in".into()).into());
+    }
+    Ok(Some(Arc::new(Enrollment::new(bus.clone(), enrollment.clone()))))
+}
+
+/// Build the calendar backend and default timezone (`None` when disabled).
+pub fn calendar(config: &Config, oauth: &Arc<OAuthManager>) -> Result<(Option<Arc<dyn CalendarBackend>>, Tz)> {
+    let calendar: &CalendarConfig = &config.tools.calendar;
+    let timezone = calendar.timezone.parse::<Tz>().map_err(|_| {
+        ConfigError(format!("unknown timezone '{}' in tools.calendar.timezone", calendar.timezone))
+    })?;
+    let backend: Option<Arc<dyn CalendarBackend>> = match calendar.backend.as_str() {
+        "" => None,
+        "caldav" => {
+            if calendar.url.is_empty() {
+                return Err(ConfigError("tools.calendar.url is required for the caldav backend".into()).into());
+            }
+            Some(Arc::new(CalDavBackend::new(&calendar.url, &calendar.username, &calendar.password)))
+        }
+        "google" => {
+            if !config.oauth.clients.contains_key(&calendar.oauth_client) {
+                return Err(ConfigError(format!(
+                    "tools.calendar.oauthClient '{}' is not in oauth.clients",
+                    calendar.oauth_client
+                ))
+                .into());
+            }
+            Some(Arc::new(GoogleCalendarBackend::new(
+                oauth.clone(),
+                &calendar.oauth_client,
+                &calendar.calendar_id,
+            )))
+        }
+        other => {
+            return Err(ConfigError(format!(
+                "unknown calendar backend '{other}' (available: caldav, google)"
+            ))
+            .into())
+        }
+    };
+    Ok((backend, timezone))
+}
+
+/// Build the GitHub API client from `tools.github`, or `GITHUB_TOKEN`
+/// (`None` when no token is set).
+pub fn github(config: &Config) -> Option<Arc<GitHubClient>> {
+    let github = &config.tools.github;
+    let token = if github.token.is_empty() {
+        std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty())?
+    } else {
+        github.token.clone()
+    };
+    Some(Arc::new(GitHubClient::new(&token, &github.api_base)))
+}
+
 /// Print an agent response to stdout.
 pub fn print_response(response: &str, _render_markdown: bool) {
     // TODO: add termimad or similar markdown renderer when render_markdown=true
@@ -32,6 +278,14 @@ pub fn print_response(response: &str, _render_markdown: bool) {
     println!();
 }
 
+/// Print the debug footer of a turn, if it has one.
+pub fn print_footer(footer: Option<&str>) {
+    if let Some(footer) = footer {
+        println!("{}", format!("  {footer}").dimmed());
+        println!();
+    }
+}
+
 /// Print the banner shown at REPL start.
 pub fn print_banner() {
     let version = env!("CARGO_PKG_VERSION");
@@ -53,9 +307,15 @@ pub fn print_thinking() {
     eprint!("{}", "⠿ thinking...".dimmed());
 }
 
+/// Replace the "thinking" placeholder with `text`.
+pub fn print_status(text: &str) {
+    clear_thinking();
+    eprint!("{}", text.dimmed());
+}
+
 /// Clear the "thinking" placeholder.
 pub fn clear_thinking() {
-    eprint!("\r{}\r", " ".repeat(40));
+    eprint!("\r{}\r", " ".repeat(80));
 }
 
 // ─────────────────────────────────────────────
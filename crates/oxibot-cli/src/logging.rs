@@ -0,0 +1,162 @@
+//! Logging setup — filters, formats and log files from `logging` in the config.
+//!
+//! Logs go to stderr (stdout stays clean for piped output) and optionally
+//! to rotating files. The filter sits behind a reload handle so the gateway's
+//! admin endpoint can change levels at runtime.
+
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+use oxibot_core::config::schema::LoggingConfig;
+
+use crate::helpers;
+
+/// Filter used with `-v`, before per-target levels.
+const VERBOSE_LEVEL: &str = "oxibot=debug,info";
+
+static CONTROL: OnceLock<LogControl> = OnceLock::new();
+
+/// Runtime access to the active filter.
+pub struct LogControl {
+    handle: reload::Handle<EnvFilter, Registry>,
+    directives: Mutex<String>,
+}
+
+impl LogControl {
+    /// The active filter directives.
+    pub fn directives(&self) -> String {
+        self.directives.lock().unwrap().clone()
+    }
+
+    /// Replace the filter, e.g. `"warn,oxibot_channels::discord=trace"`.
+    pub fn set(&self, directives: &str) -> Result<()> {
+        let directives = directives.trim();
+        let filter = EnvFilter::try_new(directives).context("invalid log filter")?;
+        self.handle.reload(filter).context("cannot reload log filter")?;
+        *self.directives.lock().unwrap() = directives.to_string();
+        Ok(())
+    }
+}
+
+/// The filter control, once [`init`] has run.
+pub fn control() -> Option<&'static LogControl> {
+    CONTROL.get()
+}
+
+/// Filter directives for the config: `RUST_LOG` if set, else the base
+/// level (or debug with `-v`) followed by per-target levels.
+pub fn directives(verbose: bool, config: &LoggingConfig) -> String {
+    if let Ok(env) = std::env::var(EnvFilter::DEFAULT_ENV) {
+        if !env.trim().is_empty() {
+            return env;
+        }
+    }
+    let base = if verbose { VERBOSE_LEVEL } else { config.level.as_str() };
+    let mut targets: Vec<_> = config.targets.iter().collect();
+    targets.sort();
+    std::iter::once(base.to_string())
+        .chain(targets.into_iter().map(|(target, level)| format!("{target}={level}")))
+        .filter(|d| !d.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Install the global subscriber. Invalid settings fall back to the
+/// defaults with a warning rather than failing the command.
+pub fn init(verbose: bool, config: &LoggingConfig) {
+    let mut directives = directives(verbose, config);
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|e| {
+        eprintln!("warning: invalid log filter {directives:?}: {e}");
+        directives = if verbose { VERBOSE_LEVEL } else { "warn" }.to_string();
+        EnvFilter::new(&directives)
+    });
+    let (filter, handle) = reload::Layer::new(filter);
+    let json = config.format.eq_ignore_ascii_case("json");
+
+    let mut layers = vec![if json {
+        fmt::layer().json().with_writer(std::io::stderr).boxed()
+    } else {
+        fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(false)
+            .compact()
+            .boxed()
+    }];
+    if config.file.enabled {
+        match file_appender(config) {
+            Ok(appender) if json => layers.push(fmt::layer().json().with_writer(appender).boxed()),
+            Ok(appender) => layers.push(fmt::layer().with_ansi(false).with_writer(appender).boxed()),
+            Err(e) => eprintln!("warning: log files disabled: {e:#}"),
+        }
+    }
+
+    if tracing_subscriber::registry().with(filter).with(layers).try_init().is_ok() {
+        let _ = CONTROL.set(LogControl {
+            handle,
+            directives: Mutex::new(directives),
+        });
+    }
+}
+
+/// Rotating `oxibot.*.log` files in the configured directory.
+fn file_appender(config: &LoggingConfig) -> Result<RollingFileAppender> {
+    let file = &config.file;
+    let dir = helpers::expand_tilde(&file.dir);
+    std::fs::create_dir_all(&dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation(&file.rotation)?)
+        .filename_prefix("oxibot")
+        .filename_suffix("log");
+    if file.max_files > 0 {
+        builder = builder.max_log_files(file.max_files);
+    }
+    Ok(builder.build(&dir)?)
+}
+
+fn rotation(name: &str) -> Result<Rotation> {
+    match name.to_lowercase().as_str() {
+        "hourly" => Ok(Rotation::HOURLY),
+        "" | "daily" => Ok(Rotation::DAILY),
+        "never" => Ok(Rotation::NEVER),
+        other => anyhow::bail!("unknown log rotation {other:?} (use hourly, daily or never)"),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() {
+        if std::env::var(EnvFilter::DEFAULT_ENV).is_ok() {
+            return;
+        }
+        let mut config = LoggingConfig::default();
+        assert_eq!(directives(false, &config), "warn");
+        assert_eq!(directives(true, &config), VERBOSE_LEVEL);
+
+        config.level = "info".into();
+        config.targets.insert("oxibot_channels::discord".into(), "trace".into());
+        config.targets.insert("hyper".into(), "error".into());
+        assert_eq!(
+            directives(false, &config),
+            "info,hyper=error,oxibot_channels::discord=trace"
+        );
+    }
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(rotation("Hourly").unwrap(), Rotation::HOURLY);
+        assert_eq!(rotation("").unwrap(), Rotation::DAILY);
+        assert!(rotation("weekly").is_err());
+    }
+}
@@ -5,16 +5,48 @@
 //! # Commands
 //!
 //! - `oxibot agent [-m MESSAGE] [-s SESSION]` — main chat (single-shot or REPL)
-//! - `oxibot onboard` — initialize config + workspace
-//! - `oxibot status` — show configuration and provider status
-
+//! - `oxibot agent --stdin --json [--no-session]` — scripting mode
+//! - `oxibot onboard [--template NAME]` — initialize config + workspace
+//! - `oxibot workspace init <template>` — create another workspace from a template
+//! - `oxibot backup create|restore` — move config, sessions, memory, skills and cron jobs between machines
+//! - `oxibot status [--json]` — show configuration and provider status
+//! - `oxibot config edit` — change the model, API keys, channels and allow-lists with guided prompts
+//! - `oxibot contacts` — manage the contact book
+//! - `oxibot tasks` — view and manage the agent's task list
+//! - `oxibot memory export|import|edit` — back up, restore and edit memory
+//! - `oxibot skills lint` — validate workspace skills
+//! - `oxibot sessions` — list, tag, search and export saved conversations
+//! - `oxibot service` — run the gateway as a systemd/launchd/Task Scheduler service
+//! - `oxibot analytics` — export usage data and reports
+//! - `oxibot oauth` — authorize OAuth clients for tools and channels
+//! - `oxibot batch --input FILE --output FILE` — run many prompts concurrently
+//! - `oxibot batch submit|status|fetch` — provider batch API for cheap offline jobs
+
+mod admin;
+mod forms_api;
 mod helpers;
+mod logging;
 mod onboard;
 mod repl;
 mod status;
 mod gateway;
 mod cron_cmd;
 mod channels_cmd;
+mod config_cmd;
+mod contacts_cmd;
+mod apikeys_cmd;
+mod tasks_cmd;
+mod memory_cmd;
+mod skills_cmd;
+mod workspace_cmd;
+mod backup_cmd;
+mod sessions_cmd;
+mod service_cmd;
+mod analytics_cmd;
+mod oauth_cmd;
+mod batch_cmd;
+mod batch_jobs;
+mod oneshot;
 
 use std::sync::Arc;
 
@@ -22,11 +54,15 @@ use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use tracing::info;
 
-use oxibot_agent::{AgentLoop, ExecToolConfig};
+use oxibot_agent::AgentLoop;
 use oxibot_core::bus::queue::MessageBus;
 use oxibot_core::config::{load_config, Config};
-use oxibot_core::session::SessionManager;
-use oxibot_providers::http_provider::create_provider;
+use oxibot_core::session::{SessionManager, TranscriptLog};
+use oxibot_core::tool_stats::ToolStatsStore;
+use oxibot_core::usage::UsageLog;
+use oxibot_core::utils::get_contacts_path;
+use oxibot_providers::http_provider::HttpProvider;
+use oxibot_providers::LlmProvider;
 
 // ─────────────────────────────────────────────
 // CLI definition
@@ -48,6 +84,18 @@ enum Commands {
         #[arg(short, long)]
         message: Option<String>,
 
+        /// Read the message from stdin (after --message, if both are given)
+        #[arg(long, default_value_t = false)]
+        stdin: bool,
+
+        /// Print the result as JSON (content, usage, tool calls, session key)
+        #[arg(long, default_value_t = false)]
+        json: bool,
+
+        /// Stateless call: don't load or save conversation history
+        #[arg(long, default_value_t = false)]
+        no_session: bool,
+
         /// Session identifier (format: "channel:id")
         #[arg(short, long, default_value = "cli:default")]
         session: String,
@@ -59,19 +107,46 @@ enum Commands {
         /// Enable debug logging
         #[arg(long, default_value_t = false)]
         logs: bool,
+
+        /// Preview file writes, edits and commands instead of running them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
     },
 
     /// Initialize configuration and workspace
-    Onboard,
+    Onboard {
+        /// Workspace template to add (skills, memory seed, README, tool settings)
+        #[arg(short, long)]
+        template: Option<workspace_cmd::Template>,
+    },
 
     /// Show configuration and provider status
-    Status,
+    Status {
+        /// Show call counts, durations and error rates per tool
+        #[arg(long)]
+        tools: bool,
+
+        /// Print a JSON report (with provider connectivity checks) for
+        /// monitoring scripts
+        #[arg(long, conflicts_with = "tools")]
+        json: bool,
+    },
+
+    /// Change common settings without editing config.json
+    Config {
+        #[command(subcommand)]
+        action: config_cmd::ConfigCommands,
+    },
 
     /// Start the gateway (all channels + agent loop)
     Gateway {
         /// Enable debug logging
         #[arg(long, default_value_t = false)]
         logs: bool,
+
+        /// Preview file writes, edits and commands instead of running them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
     },
 
     /// Manage scheduled tasks
@@ -85,6 +160,100 @@ enum Commands {
         #[command(subcommand)]
         action: channels_cmd::ChannelsCommands,
     },
+
+    /// Manage contacts (people and their ids on each channel)
+    Contacts {
+        #[command(subcommand)]
+        action: contacts_cmd::ContactsCommands,
+    },
+
+    /// View and manage the agent's task list
+    Tasks {
+        #[command(subcommand)]
+        action: tasks_cmd::TasksCommands,
+    },
+
+    /// Export, import and edit the agent's memory
+    Memory {
+        #[command(subcommand)]
+        action: memory_cmd::MemoryCommands,
+    },
+
+    /// Validate workspace skills
+    Skills {
+        #[command(subcommand)]
+        action: skills_cmd::SkillsCommands,
+    },
+
+    /// Create workspaces from templates
+    Workspace {
+        #[command(subcommand)]
+        action: workspace_cmd::WorkspaceCommands,
+    },
+
+    /// Back up and restore config, sessions, memory, skills and cron jobs
+    Backup {
+        #[command(subcommand)]
+        action: backup_cmd::BackupCommands,
+    },
+
+    /// List, tag, search and export saved conversations
+    Sessions {
+        #[command(subcommand)]
+        action: sessions_cmd::SessionsCommands,
+    },
+
+    /// Run the gateway as a background service (systemd, launchd, Task Scheduler)
+    Service {
+        #[command(subcommand)]
+        action: service_cmd::ServiceCommands,
+    },
+
+    /// Export usage analytics (Parquet) and print reports
+    Analytics {
+        #[command(subcommand)]
+        action: analytics_cmd::AnalyticsCommands,
+    },
+
+    /// Create and revoke keys for the gateway HTTP API
+    Apikeys {
+        #[command(subcommand)]
+        action: apikeys_cmd::ApiKeysCommands,
+    },
+
+    /// Authorize OAuth clients used by tools and channels
+    Oauth {
+        #[command(subcommand)]
+        action: oauth_cmd::OAuthCommands,
+    },
+
+    /// Run prompts from a JSONL file through the agent concurrently, or
+    /// through the provider's batch API
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Batch {
+        #[command(subcommand)]
+        action: Option<batch_cmd::BatchCommands>,
+
+        /// Input JSONL file, one {"id", "prompt"} object per line ("-" for stdin)
+        #[arg(short, long, required = true)]
+        input: Option<String>,
+
+        /// Output JSONL file, one result per line ("-" for stdout)
+        #[arg(short, long, required = true)]
+        output: Option<String>,
+
+        /// Maximum number of prompts processed at once
+        #[arg(short, long, default_value_t = 4)]
+        parallelism: usize,
+
+        /// Enable debug logging
+        #[arg(long, default_value_t = false)]
+        logs: bool,
+
+        /// Preview file writes, edits and commands instead of running them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
 }
 
 // ─────────────────────────────────────────────
@@ -98,24 +267,68 @@ async fn main() -> Result<()> {
     match cli.command {
         Commands::Agent {
             message,
+            stdin,
+            json,
+            no_session,
             session,
             no_markdown,
             logs,
+            dry_run,
         } => {
             init_logging(logs);
-            run_agent(message, session, !no_markdown, logs).await
+            let opts = oneshot::OneShot {
+                session,
+                json,
+                stateless: no_session,
+                render_markdown: !no_markdown,
+            };
+            let result = match oneshot::read_prompt(message, stdin) {
+                Ok(message) => run_agent(message, &opts, logs, dry_run).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(e) if opts.json => oneshot::exit_with_error(&e, &opts),
+                result => result,
+            }
         }
-        Commands::Onboard => onboard::run(),
-        Commands::Status => status::run(),
-        Commands::Gateway { logs } => {
+        Commands::Onboard { template } => onboard::run(template),
+        Commands::Status { tools, json } => status::run(tools, json).await,
+        Commands::Config { action } => config_cmd::dispatch(action),
+        Commands::Gateway { logs, dry_run } => {
             init_logging(logs);
-            gateway::run().await
+            gateway::run(dry_run).await
         }
         Commands::Cron { action } => {
             init_logging(false);
             cron_cmd::dispatch(action).await
         }
         Commands::Channels { action } => channels_cmd::dispatch(action),
+        Commands::Contacts { action } => contacts_cmd::dispatch(action),
+        Commands::Tasks { action } => tasks_cmd::dispatch(action),
+        Commands::Memory { action } => memory_cmd::dispatch(action),
+        Commands::Skills { action } => skills_cmd::dispatch(action),
+        Commands::Workspace { action } => workspace_cmd::dispatch(action).await,
+        Commands::Backup { action } => backup_cmd::dispatch(action),
+        Commands::Sessions { action } => sessions_cmd::dispatch(action).await,
+        Commands::Service { action } => service_cmd::dispatch(action),
+        Commands::Analytics { action } => analytics_cmd::dispatch(action),
+        Commands::Apikeys { action } => apikeys_cmd::dispatch(action),
+        Commands::Oauth { action } => oauth_cmd::dispatch(action).await,
+        Commands::Batch {
+            action,
+            input,
+            output,
+            parallelism,
+            logs,
+            dry_run,
+        } => {
+            init_logging(logs);
+            match (action, input, output) {
+                (Some(action), _, _) => batch_cmd::dispatch(action).await,
+                (None, Some(input), Some(output)) => batch_cmd::run(&input, &output, parallelism, dry_run).await,
+                _ => anyhow::bail!("--input and --output are required"),
+            }
+        }
     }
 }
 
@@ -125,26 +338,29 @@ async fn main() -> Result<()> {
 
 async fn run_agent(
     message: Option<String>,
-    session_id: String,
-    render_markdown: bool,
+    opts: &oneshot::OneShot,
     show_logs: bool,
+    dry_run: bool,
 ) -> Result<()> {
-    let config = load_config(None);
+    if opts.json && message.is_none() {
+        anyhow::bail!("--json needs a message: pass --message or --stdin");
+    }
+    let mut config = load_config(None);
+    config.tools.dry_run |= dry_run;
+    if config.tools.dry_run && !opts.json {
+        println!("  🧪 Dry run: file writes, edits and commands are previewed, not run");
+    }
     let agent_loop = build_agent_loop(&config)?;
 
     match message {
         Some(msg) => {
             // Single-shot mode
-            info!(session = %session_id, "processing single message");
-            let response = agent_loop
-                .process_direct(&msg)
-                .await
-                .context("agent processing failed")?;
-            helpers::print_response(&response, render_markdown);
+            info!(session = %opts.session, "processing single message");
+            oneshot::run(&agent_loop, &msg, opts).await?;
         }
         None => {
             // Interactive REPL mode
-            repl::run(agent_loop, &session_id, render_markdown, show_logs).await?;
+            repl::run(agent_loop, &opts.session, opts.render_markdown, show_logs).await?;
         }
     }
 
@@ -153,6 +369,19 @@ async fn run_agent(
 
 /// Build an `AgentLoop` from the loaded configuration.
 pub fn build_agent_loop(config: &Config) -> Result<AgentLoop> {
+    build_agent_loop_with(config, Arc::new(default_provider(config)?))
+}
+
+/// Create the provider for the default model.
+pub fn default_provider(config: &Config) -> Result<HttpProvider> {
+    oxibot_providers::register_custom_providers(&config.providers.custom);
+    helpers::provider(config, &config.agents.defaults.model, &config.providers.to_map())
+}
+
+/// Build an `AgentLoop` that talks to the default model through `provider`.
+///
+/// Loops built from the same provider share its HTTP connection pool.
+pub fn build_agent_loop_with(config: &Config, provider: Arc<dyn LlmProvider>) -> Result<AgentLoop> {
     let defaults = &config.agents.defaults;
 
     // Resolve workspace path (expand ~)
@@ -162,11 +391,7 @@ pub fn build_agent_loop(config: &Config) -> Result<AgentLoop> {
 
     // Resolve model
     let model = &defaults.model;
-
-    // Create provider
     let providers_map = config.providers.to_map();
-    let provider = create_provider(model, &providers_map)
-        .map_err(|e| anyhow::anyhow!(e))?;
 
     // Brave API key
     let brave_key = if config.tools.web.search.api_key.is_empty() {
@@ -180,36 +405,71 @@ pub fn build_agent_loop(config: &Config) -> Result<AgentLoop> {
     let session_manager = SessionManager::new(None)
         .context("failed to create session manager")?;
 
+    let scratch = helpers::scratch_dirs(&config.tools.scratch, &workspace);
+    let planner = helpers::model_route(config, &defaults.planner_model, &providers_map)?;
+    let responder = helpers::model_route(config, &defaults.responder_model, &providers_map)?;
+    let repair = &defaults.tool_call_repair;
+    let fallback = helpers::model_route(config, &repair.fallback_model, &providers_map)?;
+    let quick = &defaults.quick_replies;
+    let quick_route = helpers::model_route(config, &quick.model, &providers_map)?;
+    let persona = helpers::persona(&defaults.persona)?;
+    let (calendar, timezone) = helpers::calendar(config, &oauth_cmd::build_manager(config))?;
+    let context_providers = helpers::context_providers(config, &workspace, calendar.as_ref(), timezone);
     let agent_loop = AgentLoop::new(
         bus,
-        Arc::new(provider),
+        provider,
         workspace,
         Some(model.to_string()),
         Some(defaults.max_tool_iterations as usize),
         None, // uses defaults for temperature/max_tokens
         brave_key,
-        Some(ExecToolConfig::default()),
+        Some(helpers::exec_config(config)),
         config.tools.restrict_to_workspace,
         Some(session_manager),
         None, // default agent name "Oxibot"
-    );
+    )
+    .with_scratch(scratch)
+    .with_model_routing(planner, responder)
+    .with_quick_replies(quick.enabled.then(|| quick.clone()), quick_route)
+    .with_debug_footer(defaults.debug_footer.clone(), config.analytics.prices.clone())
+    .with_form_webhooks(config.tools.forms.webhooks.clone())
+    .with_persona(persona)
+    .with_responses(defaults.responses.clone())
+    .with_commands(defaults.commands.clone())
+    .with_tool_call_repair(repair.max_failures, fallback)
+    .with_repeated_failure_limit(repair.max_repeated_failures)
+    .with_tool_breaker(defaults.tool_breaker.clone())
+    .with_tool_result_compaction(
+        defaults.tool_results.compact.then(|| defaults.tool_results.clone()),
+    )
+    .with_tokenizers(config.tokenizer.clone())
+    .with_context_budget(defaults.max_context_tokens)
+    .with_context_windows(defaults.context_windows.clone())
+    .with_contacts(Some(get_contacts_path()))
+    .with_calendar(calendar, timezone)
+    .with_github(helpers::github(config))
+    .with_browser(config.tools.browser.enabled.then(|| config.tools.browser.clone()))
+    .with_web_search(helpers::web_search_route(config, model, &providers_map)?)
+    .with_page_cache(helpers::page_cache(config, &providers_map)?)
+    .with_file_index(config.tools.file_index.enabled.then(|| config.tools.file_index.clone()))
+    .with_redaction(helpers::redactor(config)?)
+    .with_skill_secrets(config.skills.secrets.clone())
+    .with_plan_mode(defaults.plan_mode.enabled.then(|| defaults.plan_mode.clone()))
+    .with_file_uploads(helpers::file_uploader(config, model, &providers_map)?, defaults.file_uploads.clone())
+    .with_context_providers(
+        context_providers,
+        std::time::Duration::from_millis(defaults.context_providers.timeout_ms),
+    )
+    .with_usage_log(config.analytics.enabled.then(|| UsageLog::new(None)))
+    .with_transcripts(config.analytics.transcripts.then(|| TranscriptLog::new(None)))
+    .with_tool_stats(Some(Arc::new(ToolStatsStore::new(None))))
+    .with_dry_run(config.tools.dry_run)
+    .with_error_ids(defaults.show_error_ids);
 
     Ok(agent_loop)
 }
 
-/// Initialize tracing/logging.
+/// Initialize tracing/logging from the config's `logging` section.
 fn init_logging(verbose: bool) {
-    use tracing_subscriber::EnvFilter;
-
-    let filter = if verbose {
-        EnvFilter::new("oxibot=debug,info")
-    } else {
-        EnvFilter::new("warn")
-    };
-
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
-        .compact()
-        .init();
+    logging::init(verbose, &load_config(None).logging);
 }
@@ -0,0 +1,151 @@
+//! `oxibot memory` — back up, restore and edit the agent's memory.
+//!
+//! - `oxibot memory export [--output FILE]` — write the store as JSON
+//! - `oxibot memory import FILE [--replace]` — merge (or restore) an export
+//! - `oxibot memory edit [--date YYYY-MM-DD]` — open MEMORY.md (or a daily
+//!   note) in `$VISUAL`/`$EDITOR`
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_agent::memory::{normalize_memory, MemoryExport};
+use oxibot_agent::MemoryStore;
+use oxibot_core::config::load_config;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Memory subcommands.
+#[derive(Subcommand)]
+pub enum MemoryCommands {
+    /// Export long-term memory and daily notes as JSON
+    Export {
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a memory export, merging it into the current memory
+    Import {
+        /// Export file ("-" for stdin)
+        file: String,
+
+        /// Overwrite files instead of merging
+        #[arg(long, default_value_t = false)]
+        replace: bool,
+    },
+
+    /// Edit long-term memory (or a daily note) in your editor
+    Edit {
+        /// Daily note to edit (YYYY-MM-DD)
+        #[arg(short, long)]
+        date: Option<String>,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a memory subcommand.
+pub fn dispatch(cmd: MemoryCommands) -> Result<()> {
+    let config = load_config(None);
+    let workspace = crate::helpers::expand_tilde(&config.agents.defaults.workspace);
+    let store = MemoryStore::new(&workspace).context("failed to open memory directory")?;
+
+    match cmd {
+        MemoryCommands::Export { output } => export(&store, output.as_deref()),
+        MemoryCommands::Import { file, replace } => import(&store, &file, replace),
+        MemoryCommands::Edit { date } => {
+            let path = match date {
+                Some(date) => {
+                    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                        .with_context(|| format!("invalid date '{date}', expected YYYY-MM-DD"))?;
+                    store.memory_dir().join(format!("{date}.md"))
+                }
+                None => store.memory_file().to_path_buf(),
+            };
+            edit(&path)
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Handlers
+// ─────────────────────────────────────────────
+
+fn export(store: &MemoryStore, output: Option<&Path>) -> Result<()> {
+    let export = store.export();
+    let json = serde_json::to_string_pretty(&export)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))?;
+            println!(
+                "  {} Exported long-term memory and {} daily notes to {}",
+                "✓".green(),
+                export.daily.len(),
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+fn import(store: &MemoryStore, file: &str, replace: bool) -> Result<()> {
+    let text = if file == "-" {
+        std::io::read_to_string(std::io::stdin()).context("failed to read stdin")?
+    } else {
+        std::fs::read_to_string(file).with_context(|| format!("failed to read {file}"))?
+    };
+    let export: MemoryExport = serde_json::from_str(&text).context("not a memory export")?;
+    let written = store.import(&export, replace)?;
+    let verb = if replace { "Restored" } else { "Merged" };
+    println!("  {} {verb} memory ({written} files changed)", "✓".green());
+    Ok(())
+}
+
+/// Edit a copy of `path`, then put it back — unless the file changed while
+/// the editor was open, in which case the edited copy is kept aside.
+fn edit(path: &Path) -> Result<()> {
+    let before = std::fs::read_to_string(path).unwrap_or_default();
+    let draft = path.with_extension("md.edit");
+    std::fs::write(&draft, &before).with_context(|| format!("failed to write {}", draft.display()))?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".into() } else { "vi".into() });
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&draft)
+        .status()
+        .with_context(|| format!("failed to run editor '{editor}'"))?;
+    if !status.success() {
+        bail!("editor exited with {status}; your draft is at {}", draft.display());
+    }
+
+    let edited = normalize_memory(&std::fs::read_to_string(&draft)?);
+    let current = std::fs::read_to_string(path).unwrap_or_default();
+    if current != before {
+        bail!(
+            "{} changed while you were editing; your draft is at {}",
+            path.display(),
+            draft.display()
+        );
+    }
+    std::fs::remove_file(&draft)?;
+    if edited == normalize_memory(&before) {
+        println!("  {} No changes", "✓".green());
+        return Ok(());
+    }
+    std::fs::write(path, edited).with_context(|| format!("failed to write {}", path.display()))?;
+    println!("  {} Saved {}", "✓".green(), path.display());
+    Ok(())
+}
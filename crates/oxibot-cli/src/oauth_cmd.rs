@@ -0,0 +1,118 @@
+//! `oxibot oauth` — authorize OAuth clients from the CLI.
+//!
+//! - `oxibot oauth list` — configured clients and their token status
+//! - `oxibot oauth login CLIENT` — open the authorization URL and wait for the callback
+//! - `oxibot oauth logout CLIENT` — forget a client's tokens
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_core::config::{load_config, Config};
+use oxibot_core::oauth::{self, OAuthManager, TokenStore};
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// OAuth subcommands.
+#[derive(Subcommand)]
+pub enum OAuthCommands {
+    /// List configured clients and whether they are authorized
+    List,
+
+    /// Authorize a client (the gateway must not be running)
+    Login {
+        /// Client name from `oauth.clients`
+        client: String,
+    },
+
+    /// Forget a client's tokens
+    Logout {
+        /// Client name from `oauth.clients`
+        client: String,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch an oauth subcommand.
+pub async fn dispatch(cmd: OAuthCommands) -> Result<()> {
+    let config = load_config(None);
+    let manager = build_manager(&config);
+    match cmd {
+        OAuthCommands::List => list_clients(&manager).await,
+        OAuthCommands::Login { client } => login(&config, &manager, &client).await,
+        OAuthCommands::Logout { client } => {
+            if manager.logout(&client).await? {
+                println!("  {} Removed tokens for {}", "✓".green(), client.cyan());
+            } else {
+                println!("  {} {} has no stored tokens", "✗".red(), client);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Build the OAuth manager from config (shared with the gateway).
+pub fn build_manager(config: &Config) -> Arc<OAuthManager> {
+    Arc::new(OAuthManager::new(
+        config.oauth.clients.clone(),
+        config.oauth.redirect_url(config.gateway.port),
+        TokenStore::load(None),
+    ))
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+/// `oxibot oauth list`
+async fn list_clients(manager: &OAuthManager) -> Result<()> {
+    let clients = manager.clients();
+    if clients.is_empty() {
+        println!("  No OAuth clients. Add them under `oauth.clients` in ~/.oxibot/config.json.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  OAuth clients".cyan().bold());
+    println!();
+    for client in clients {
+        let status = match manager.token(&client).await {
+            None => "not authorized".dimmed().to_string(),
+            Some(token) if token.refresh_token.is_some() => {
+                format!("{} authorized (auto-refresh)", "✓".green())
+            }
+            Some(token) if token.is_fresh() => format!("{} authorized", "✓".green()),
+            Some(_) => format!("{} expired, log in again", "✗".red()),
+        };
+        println!("  {:<16} {}", client, status);
+    }
+    println!();
+    Ok(())
+}
+
+/// `oxibot oauth login CLIENT`
+async fn login(config: &Config, manager: &OAuthManager, client: &str) -> Result<()> {
+    let url = manager.authorization_url(client)?;
+    let addr = format!("{}:{}", config.gateway.host, config.gateway.port);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("cannot listen on {addr} for the OAuth callback; stop the gateway and retry, or use POST /admin/oauth/login/{client} on it"))?;
+
+    println!();
+    println!("  Open this URL in a browser to authorize {}:", client.cyan());
+    println!();
+    println!("  {url}");
+    println!();
+    println!("  Waiting for the callback on {}...", config.oauth.redirect_url(config.gateway.port).dimmed());
+
+    let authorized = oauth::wait_for_login(&listener, manager).await?;
+    println!("  {} Authorized {}", "✓".green(), authorized.cyan());
+    Ok(())
+}
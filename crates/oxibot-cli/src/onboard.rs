@@ -3,6 +3,9 @@
 //! Replaces nanobot's `onboard` command:
 //! - Creates `~/.oxibot/config.json` with defaults
 //! - Creates workspace directory with template files
+//! - `--template` adds a workspace template (see [`Template`])
+
+use std::path::Path;
 
 use anyhow::Result;
 use colored::Colorize;
@@ -10,8 +13,10 @@ use colored::Colorize;
 use oxibot_core::config::{load_config, save_config};
 use oxibot_core::utils::{get_data_path, get_default_workspace_path};
 
+use crate::workspace_cmd::{self, Template};
+
 /// Run the onboard command.
-pub fn run() -> Result<()> {
+pub fn run(template: Option<Template>) -> Result<()> {
     println!();
     println!("{}", "🦀 Oxibot — Setup".cyan().bold());
     println!();
@@ -26,8 +31,14 @@ pub fn run() -> Result<()> {
             "✓".green(),
             config_path.display()
         );
+        if let Some(template) = template {
+            workspace_cmd::print_settings(template);
+        }
     } else {
-        let config = load_config(None); // defaults
+        let mut config = load_config(None); // defaults
+        if let Some(template) = template {
+            config = workspace_cmd::apply_settings(&config, &template.settings())?;
+        }
         save_config(&config, Some(&config_path))?;
         println!(
             "  {} created config at {}",
@@ -36,9 +47,31 @@ pub fn run() -> Result<()> {
         );
     }
 
+    // 2-5. Workspace files
+    init_workspace(&get_default_workspace_path(), template)?;
+
+    // 6. Create sessions + history directories
+    let sessions_dir = data_dir.join("sessions");
+    std::fs::create_dir_all(&sessions_dir)?;
+    let history_dir = data_dir.join("history");
+    std::fs::create_dir_all(&history_dir)?;
+
+    println!();
+    println!(
+        "{}",
+        "  Setup complete! Run `oxibot agent` to start chatting.".green()
+    );
+    println!();
+
+    Ok(())
+}
+
+/// Create a workspace with the standard files, plus `template`'s.
+///
+/// Existing files are kept.
+pub fn init_workspace(workspace: &Path, template: Option<Template>) -> Result<()> {
     // 2. Ensure workspace directory
-    let workspace = get_default_workspace_path();
-    std::fs::create_dir_all(&workspace)?;
+    std::fs::create_dir_all(workspace)?;
     println!(
         "  {} workspace at {}",
         "✓".green(),
@@ -69,24 +102,14 @@ pub fn run() -> Result<()> {
         println!("  {} skill-creator already exists", "✓".green());
     }
 
-    // 6. Create sessions + history directories
-    let sessions_dir = data_dir.join("sessions");
-    std::fs::create_dir_all(&sessions_dir)?;
-    let history_dir = data_dir.join("history");
-    std::fs::create_dir_all(&history_dir)?;
-
-    println!();
-    println!(
-        "{}",
-        "  Setup complete! Run `oxibot agent` to start chatting.".green()
-    );
-    println!();
-
+    if let Some(template) = template {
+        template.install(workspace)?;
+    }
     Ok(())
 }
 
 /// Create a template file if it doesn't exist.
-fn create_template(path: &std::path::Path, content: &str) -> Result<()> {
+pub fn create_template(path: &Path, content: &str) -> Result<()> {
     let name = path.file_name().unwrap_or_default().to_string_lossy();
     if path.exists() {
         println!("  {} {} already exists", "✓".green(), name);
@@ -121,6 +144,8 @@ Tell Oxibot about yourself so it can personalize its responses.
 - **Name**: (your name)
 - **Role**: (your role/profession)
 - **Preferences**: (communication preferences)
+- **Timezone**: (e.g. Europe/Madrid)
+- **Language**: (e.g. Spanish)
 "#;
 
 const SOUL_TEMPLATE: &str = r#"# Soul
@@ -160,7 +185,8 @@ If this file has no tasks (only headers and comments), the agent will skip the h
 const MEMORY_TEMPLATE: &str = r#"# Long-term Memory
 
 Oxibot persists important information here automatically.
-You can also edit this file directly.
+You can also edit this file directly (or with `oxibot memory edit`):
+keep facts as `- ` bullets under `## ` headings.
 "#;
 
 const SKILL_CREATOR_TEMPLATE: &str = include_str!("../../oxibot-agent/skills/skill-creator/SKILL.md");
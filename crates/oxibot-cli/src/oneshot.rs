@@ -0,0 +1,187 @@
+//! Single-shot agent runs for shell scripts and Makefiles.
+//!
+//! - `oxibot agent -m "..."` — answer one message and exit
+//! - `--stdin` — read the prompt from stdin (appended to `-m` if both are given)
+//! - `--json` — print one JSON object with the reply, token usage, tool
+//!   calls and session key instead of formatted text
+//! - `--no-session` — stateless call: no history is loaded or saved
+//!
+//! The exit code is non-zero when the agent fails; with `--json` the error
+//! is reported as `{"ok": false, "error": ...}` on stdout (see [`exit_with_error`]).
+
+use std::io::Read;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use oxibot_agent::{AgentLoop, TurnResult};
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::session::SessionManager;
+
+/// Channel used when `--session` has no `channel:` prefix.
+const DEFAULT_CHANNEL: &str = "cli";
+
+/// How to run a single message.
+pub struct OneShot {
+    /// Session key (`channel:id`) the message belongs to.
+    pub session: String,
+    /// Print JSON instead of formatted text.
+    pub json: bool,
+    /// Forget the exchange afterwards.
+    pub stateless: bool,
+    /// Render Markdown in text output.
+    pub render_markdown: bool,
+}
+
+/// Build the prompt from `-m` and, with `--stdin`, standard input.
+pub fn read_prompt(message: Option<String>, stdin: bool) -> Result<Option<String>> {
+    if !stdin {
+        return Ok(message);
+    }
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .context("failed to read stdin")?;
+    let prompt = join_prompt(message.as_deref(), &input);
+    if prompt.is_empty() {
+        anyhow::bail!("no prompt given on stdin");
+    }
+    Ok(Some(prompt))
+}
+
+/// Answer `prompt` and print the result.
+pub async fn run(agent_loop: &AgentLoop, prompt: &str, opts: &OneShot) -> Result<()> {
+    let key = if opts.stateless {
+        let stamp = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        format!("{DEFAULT_CHANNEL}:oneshot-{}-{stamp}", std::process::id())
+    } else {
+        opts.session.clone()
+    };
+    let msg = inbound(&key, prompt);
+
+    let started = Instant::now();
+    let result = agent_loop.process_turn(&msg).await;
+    if opts.stateless {
+        SessionManager::new(None)
+            .context("failed to create session manager")?
+            .delete(&key);
+    }
+    let session_key = (!opts.stateless).then_some(key.as_str());
+
+    match result {
+        Ok(turn) if opts.json => {
+            println!("{}", turn_json(&turn, session_key, started.elapsed().as_millis() as u64));
+            Ok(())
+        }
+        Ok(turn) => {
+            crate::helpers::print_response(&turn.reply.content, opts.render_markdown);
+            crate::helpers::print_footer(turn.footer.as_deref());
+            Ok(())
+        }
+        Err(e) => Err(e.context("agent processing failed")),
+    }
+}
+
+/// Report `error` as `--json` output and exit with status 1.
+pub fn exit_with_error(error: &anyhow::Error, opts: &OneShot) -> ! {
+    let session_key = (!opts.stateless).then_some(opts.session.as_str());
+    println!("{}", json!({ "ok": false, "error": format!("{error:#}"), "sessionKey": session_key }));
+    std::process::exit(1);
+}
+
+// ─────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────
+
+/// `-m` text followed by the stdin text, either of which may be missing.
+fn join_prompt(message: Option<&str>, input: &str) -> String {
+    let input = input.trim();
+    match message.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(message) if !input.is_empty() => format!("{message}\n\n{input}"),
+        Some(message) => message.to_string(),
+        None => input.to_string(),
+    }
+}
+
+/// An inbound message whose session key is `key`.
+fn inbound(key: &str, prompt: &str) -> InboundMessage {
+    let (channel, chat_id) = key.split_once(':').unwrap_or((DEFAULT_CHANNEL, key));
+    InboundMessage::new(channel, "user", chat_id, prompt)
+}
+
+/// The `--json` output for a finished turn.
+fn turn_json(turn: &TurnResult, session_key: Option<&str>, duration_ms: u64) -> Value {
+    let tool_calls: Vec<Value> = turn
+        .tool_calls
+        .iter()
+        .map(|tc| {
+            let arguments = serde_json::from_str(&tc.function.arguments)
+                .unwrap_or_else(|_| json!(tc.function.arguments));
+            json!({ "name": tc.function.name, "arguments": arguments })
+        })
+        .collect();
+    json!({
+        "ok": true,
+        "content": turn.reply.content,
+        "sessionKey": session_key,
+        "usage": {
+            "promptTokens": turn.usage.prompt_tokens,
+            "completionTokens": turn.usage.completion_tokens,
+            "totalTokens": turn.usage.total_tokens,
+        },
+        "toolCalls": tool_calls,
+        "durationMs": duration_ms,
+    })
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::bus::types::OutboundMessage;
+    use oxibot_core::types::{ToolCall, UsageInfo};
+
+    #[test]
+    fn test_join_prompt() {
+        assert_eq!(join_prompt(None, " data \n"), "data");
+        assert_eq!(join_prompt(Some("Summarize:"), "data\n"), "Summarize:\n\ndata");
+        assert_eq!(join_prompt(Some("hi"), "  "), "hi");
+        assert_eq!(join_prompt(Some(" "), ""), "");
+    }
+
+    #[test]
+    fn test_inbound_session_key() {
+        assert_eq!(inbound("cli:default", "hi").session_key(), "cli:default");
+        assert_eq!(inbound("ci:build-42", "hi").session_key(), "ci:build-42");
+        assert_eq!(inbound("nightly", "hi").session_key(), "cli:nightly");
+    }
+
+    #[test]
+    fn test_turn_json() {
+        let turn = TurnResult {
+            reply: OutboundMessage::new("cli", "default", "Done."),
+            usage: UsageInfo {
+                prompt_tokens: 120,
+                completion_tokens: 8,
+                total_tokens: 128,
+            },
+            tool_calls: vec![
+                ToolCall::new("c1", "read_file", r#"{"path": "a.txt"}"#),
+                ToolCall::new("c2", "exec", "{broken"),
+            ],
+            footer: None,
+        };
+        let out = turn_json(&turn, Some("cli:default"), 42);
+        assert_eq!(out["ok"], true);
+        assert_eq!(out["content"], "Done.");
+        assert_eq!(out["sessionKey"], "cli:default");
+        assert_eq!(out["usage"]["totalTokens"], 128);
+        assert_eq!(out["toolCalls"][0]["arguments"]["path"], "a.txt");
+        assert_eq!(out["toolCalls"][1]["arguments"], "{broken");
+        assert!(turn_json(&turn, None, 0)["sessionKey"].is_null());
+    }
+}
@@ -1,6 +1,8 @@
 //! Interactive REPL — replaces nanobot's prompt_toolkit loop.
 //!
 //! Uses `rustyline` for readline-style editing with persistent history.
+//! While the agent works, its progress events (LLM calls, tools) replace
+//! the "thinking" line.
 
 use anyhow::Result;
 use rustyline::config::Configurer;
@@ -9,6 +11,8 @@ use rustyline::{DefaultEditor, Editor};
 use tracing::debug;
 
 use oxibot_agent::AgentLoop;
+use oxibot_core::bus::types::InboundMessage;
+use oxibot_core::bus::{AgentEvent, BusEvent, Topic};
 
 use crate::helpers;
 
@@ -25,6 +29,7 @@ pub async fn run(
     helpers::print_banner();
 
     let mut editor = create_editor()?;
+    let mut events = agent.bus().subscribe(&[Topic::Agent]);
 
     loop {
         // Read input
@@ -62,10 +67,26 @@ pub async fn run(
         debug!(session = session_id, input = trimmed, "processing input");
         helpers::print_thinking();
 
-        match agent.process_direct(trimmed).await {
-            Ok(response) => {
+        let msg = InboundMessage::new("cli", "user", "direct", trimmed);
+        let turn = agent.process_turn(&msg);
+        tokio::pin!(turn);
+        let reply = loop {
+            tokio::select! {
+                biased;
+                Some(event) = events.recv() => {
+                    if let BusEvent::Agent(ref event) = *event {
+                        show_progress(event);
+                    }
+                }
+                reply = &mut turn => break reply,
+            }
+        };
+
+        match reply {
+            Ok(turn) => {
                 helpers::clear_thinking();
-                helpers::print_response(&response, render_markdown);
+                helpers::print_response(&turn.reply.content, render_markdown);
+                helpers::print_footer(turn.footer.as_deref());
             }
             Err(e) => {
                 helpers::clear_thinking();
@@ -80,6 +101,43 @@ pub async fn run(
     Ok(())
 }
 
+/// Update the status line for one step of the turn. Finished tools stay
+/// on screen; everything else is overwritten by the next step.
+fn show_progress(event: &AgentEvent) {
+    match event {
+        AgentEvent::LlmCallStarted { iteration: 0, .. } => helpers::print_status("⠿ thinking..."),
+        AgentEvent::LlmCallStarted { iteration, .. } => {
+            helpers::print_status(&format!("⠿ thinking (step {})...", iteration + 1))
+        }
+        AgentEvent::ToolStarted { name, args, .. } => {
+            helpers::print_status(&format!("⚙ {name} {}", summarize_args(args)))
+        }
+        AgentEvent::ToolFinished { name, duration_ms, ok, .. } => {
+            helpers::clear_thinking();
+            let mark = if *ok { "✓" } else { "✗" };
+            helpers::print_status(&format!("  {mark} {name} ({:.1}s)\n", *duration_ms as f64 / 1000.0));
+        }
+        AgentEvent::FinalResponse { .. } => {}
+    }
+}
+
+/// Tool arguments on one short line.
+fn summarize_args(args: &serde_json::Value) -> String {
+    let text = match args {
+        serde_json::Value::Object(map) => map
+            .values()
+            .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+            .collect::<Vec<_>>()
+            .join(" "),
+        other => other.to_string(),
+    };
+    let text = text.replace('\n', " ");
+    match text.char_indices().nth(60) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
 /// Create a rustyline editor with history.
 fn create_editor() -> Result<Editor<(), DefaultHistory>> {
     let mut editor = DefaultEditor::new()?;
@@ -135,6 +193,14 @@ mod tests {
         assert!(!is_exit_command(""));
     }
 
+    #[test]
+    fn args_summary_is_one_short_line() {
+        let args = serde_json::json!({"command": "ls -la\ncat x", "timeout": 5});
+        assert_eq!(summarize_args(&args), "ls -la cat x 5");
+        let long = serde_json::json!({"content": "x".repeat(100)});
+        assert_eq!(summarize_args(&long).chars().count(), 61);
+    }
+
     #[test]
     fn history_path_under_data_dir() {
         let path = history_path();
@@ -0,0 +1,482 @@
+//! `oxibot service` — run the gateway as a background service.
+//!
+//! - `oxibot service install [--force]` — write and register the service definition
+//! - `oxibot service uninstall` — stop and remove it
+//! - `oxibot service start|stop|status` — control the installed service
+//!
+//! The service runs `oxibot gateway` at login, restarts it when it crashes and
+//! appends its output to `~/.oxibot/logs/gateway.log`:
+//!
+//! - **Linux** — systemd user unit `~/.config/systemd/user/oxibot.service`
+//! - **macOS** — launchd agent `~/Library/LaunchAgents/com.oxibot.gateway.plist`
+//! - **Windows** — Task Scheduler task `Oxibot` (logon trigger, restart on failure);
+//!   the gateway is a console program rather than an SCM service binary, so a
+//!   scheduled task is the supported way to keep it running
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_core::utils::get_data_path;
+
+/// systemd unit file name.
+const SYSTEMD_UNIT: &str = "oxibot.service";
+/// launchd job label.
+const LAUNCHD_LABEL: &str = "com.oxibot.gateway";
+/// Task Scheduler task name.
+const TASK_NAME: &str = "Oxibot";
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Service subcommands.
+#[derive(Subcommand)]
+pub enum ServiceCommands {
+    /// Install the gateway as a service that starts at login
+    Install {
+        /// Overwrite an existing service definition
+        #[arg(short, long, default_value_t = false)]
+        force: bool,
+    },
+
+    /// Stop and remove the service
+    Uninstall,
+
+    /// Start the service
+    Start,
+
+    /// Stop the service
+    Stop,
+
+    /// Show service status
+    Status,
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a service subcommand.
+pub fn dispatch(cmd: ServiceCommands) -> Result<()> {
+    let platform = Platform::current()?;
+    let home = dirs_next::home_dir().context("could not determine home directory")?;
+    let definition = platform.definition_path(&home);
+
+    match cmd {
+        ServiceCommands::Install { force } => install(platform, &definition, force),
+        ServiceCommands::Uninstall => uninstall(platform, &definition),
+        ServiceCommands::Start => {
+            ensure_installed(&definition)?;
+            platform.start(&definition)?;
+            println!("  {} Gateway service started", "✓".green());
+            Ok(())
+        }
+        ServiceCommands::Stop => {
+            ensure_installed(&definition)?;
+            platform.stop(&definition)?;
+            println!("  {} Gateway service stopped", "✓".green());
+            Ok(())
+        }
+        ServiceCommands::Status => {
+            ensure_installed(&definition)?;
+            println!("  Definition: {}", definition.display());
+            println!("  Log:        {}", log_path().display());
+            println!();
+            platform.status()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Platform
+// ─────────────────────────────────────────────
+
+/// The service manager used on this OS.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Platform {
+    Systemd,
+    Launchd,
+    TaskScheduler,
+}
+
+impl Platform {
+    fn current() -> Result<Self> {
+        if cfg!(target_os = "linux") {
+            Ok(Self::Systemd)
+        } else if cfg!(target_os = "macos") {
+            Ok(Self::Launchd)
+        } else if cfg!(windows) {
+            Ok(Self::TaskScheduler)
+        } else {
+            anyhow::bail!("`oxibot service` supports Linux (systemd), macOS and Windows")
+        }
+    }
+
+    /// Where the service definition file lives.
+    fn definition_path(self, home: &Path) -> PathBuf {
+        match self {
+            Self::Systemd => home.join(".config/systemd/user").join(SYSTEMD_UNIT),
+            Self::Launchd => home
+                .join("Library/LaunchAgents")
+                .join(format!("{LAUNCHD_LABEL}.plist")),
+            // Kept alongside the data dir so `uninstall` and re-installs can find it
+            Self::TaskScheduler => get_data_path().join("oxibot-task.xml"),
+        }
+    }
+
+    /// Render the service definition.
+    fn render(self, exe: &Path, log: &Path, path_env: Option<&str>) -> Vec<u8> {
+        match self {
+            Self::Systemd => render_systemd_unit(exe, log, path_env).into_bytes(),
+            Self::Launchd => render_launchd_plist(exe, log, path_env).into_bytes(),
+            // schtasks expects UTF-16 XML
+            Self::TaskScheduler => {
+                let xml = render_task_xml(exe, log);
+                let mut bytes = vec![0xFF, 0xFE];
+                bytes.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+                bytes
+            }
+        }
+    }
+
+    /// Register a freshly written definition.
+    fn register(self, definition: &Path) -> Result<()> {
+        match self {
+            Self::Systemd => {
+                run("systemctl", &["--user", "daemon-reload"])?;
+                run("systemctl", &["--user", "enable", SYSTEMD_UNIT])
+            }
+            // Loading starts the job, so launchd registration happens on `start`
+            Self::Launchd => Ok(()),
+            Self::TaskScheduler => run(
+                "schtasks",
+                &["/Create", "/TN", TASK_NAME, "/XML", &definition.to_string_lossy(), "/F"],
+            ),
+        }
+    }
+
+    fn start(self, definition: &Path) -> Result<()> {
+        match self {
+            Self::Systemd => run("systemctl", &["--user", "start", SYSTEMD_UNIT]),
+            Self::Launchd => run("launchctl", &["load", "-w", &definition.to_string_lossy()]),
+            Self::TaskScheduler => run("schtasks", &["/Run", "/TN", TASK_NAME]),
+        }
+    }
+
+    fn stop(self, definition: &Path) -> Result<()> {
+        match self {
+            Self::Systemd => run("systemctl", &["--user", "stop", SYSTEMD_UNIT]),
+            Self::Launchd => run("launchctl", &["unload", "-w", &definition.to_string_lossy()]),
+            Self::TaskScheduler => run("schtasks", &["/End", "/TN", TASK_NAME]),
+        }
+    }
+
+    /// Print the service manager's own status report.
+    fn status(self) -> Result<()> {
+        // Status commands exit non-zero for stopped services; that's not an error here
+        let (program, args): (&str, &[&str]) = match self {
+            Self::Systemd => ("systemctl", &["--user", "status", SYSTEMD_UNIT, "--no-pager"]),
+            Self::Launchd => ("launchctl", &["list", LAUNCHD_LABEL]),
+            Self::TaskScheduler => ("schtasks", &["/Query", "/TN", TASK_NAME, "/V", "/FO", "LIST"]),
+        };
+        Command::new(program)
+            .args(args)
+            .status()
+            .with_context(|| format!("failed to run {program}"))?;
+        Ok(())
+    }
+
+    /// Stop and deregister the service (best effort).
+    fn deregister(self, definition: &Path) {
+        let _ = match self {
+            Self::Systemd => run("systemctl", &["--user", "disable", "--now", SYSTEMD_UNIT]),
+            Self::Launchd => run("launchctl", &["unload", "-w", &definition.to_string_lossy()]),
+            Self::TaskScheduler => {
+                let _ = run("schtasks", &["/End", "/TN", TASK_NAME]);
+                run("schtasks", &["/Delete", "/TN", TASK_NAME, "/F"])
+            }
+        };
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+/// Gateway log file (e.g. `~/.oxibot/logs/gateway.log`).
+fn log_path() -> PathBuf {
+    get_data_path().join("logs").join("gateway.log")
+}
+
+fn ensure_installed(definition: &Path) -> Result<()> {
+    if !definition.exists() {
+        anyhow::bail!("service not installed (run `oxibot service install`)");
+    }
+    Ok(())
+}
+
+/// `oxibot service install [--force]`
+fn install(platform: Platform, definition: &Path, force: bool) -> Result<()> {
+    if definition.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite)",
+            definition.display()
+        );
+    }
+
+    let exe = std::env::current_exe().context("could not locate the oxibot executable")?;
+    let log = log_path();
+    if let Some(dir) = log.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    if let Some(dir) = definition.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let path_env = std::env::var("PATH").ok();
+    std::fs::write(definition, platform.render(&exe, &log, path_env.as_deref()))
+        .with_context(|| format!("failed to write {}", definition.display()))?;
+    platform.register(definition)?;
+
+    println!("  {} Installed gateway service", "✓".green());
+    println!("    Definition: {}", definition.display());
+    println!("    Log:        {}", log.display());
+    println!();
+    println!("  Start it now with {}", "oxibot service start".cyan());
+    if platform == Platform::Systemd {
+        println!(
+            "  To keep it running while logged out: {}",
+            "loginctl enable-linger $USER".cyan()
+        );
+    }
+    Ok(())
+}
+
+/// `oxibot service uninstall`
+fn uninstall(platform: Platform, definition: &Path) -> Result<()> {
+    ensure_installed(definition)?;
+    platform.deregister(definition);
+    std::fs::remove_file(definition)
+        .with_context(|| format!("failed to remove {}", definition.display()))?;
+    if platform == Platform::Systemd {
+        let _ = run("systemctl", &["--user", "daemon-reload"]);
+    }
+    println!("  {} Removed gateway service", "✓".green());
+    Ok(())
+}
+
+/// Run a service-manager command, failing on a non-zero exit.
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to run {program}"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`{} {}` failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// Definition templates
+// ─────────────────────────────────────────────
+
+/// Quote a path for a systemd `ExecStart=` line.
+fn systemd_quote(path: &Path) -> String {
+    let s = path.to_string_lossy().replace('%', "%%");
+    if s.contains(char::is_whitespace) {
+        format!("\"{}\"", s.replace('"', "\\\""))
+    } else {
+        s
+    }
+}
+
+/// Escape text for XML content and attributes.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// systemd user unit: restart on failure, output appended to the log.
+fn render_systemd_unit(exe: &Path, log: &Path, path_env: Option<&str>) -> String {
+    let mut unit = format!(
+        "[Unit]\n\
+         Description=Oxibot gateway\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={} gateway\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         StandardOutput=append:{log}\n\
+         StandardError=append:{log}\n",
+        systemd_quote(exe),
+        log = log.display(),
+    );
+    if let Some(path) = path_env {
+        // User units don't inherit the login shell's PATH; the exec tool needs it
+        unit.push_str(&format!("Environment=\"PATH={}\"\n", path.replace('%', "%%")));
+    }
+    unit.push_str("\n[Install]\nWantedBy=default.target\n");
+    unit
+}
+
+/// launchd agent: started at load/login, restarted when it exits abnormally.
+fn render_launchd_plist(exe: &Path, log: &Path, path_env: Option<&str>) -> String {
+    let log = xml_escape(&log.to_string_lossy());
+    let env = path_env
+        .map(|p| {
+            format!(
+                "    <key>EnvironmentVariables</key>\n    <dict>\n        <key>PATH</key>\n        <string>{}</string>\n    </dict>\n",
+                xml_escape(p)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCHD_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>gateway</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ThrottleInterval</key>
+    <integer>5</integer>
+    <key>StandardOutPath</key>
+    <string>{log}</string>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+{env}</dict>
+</plist>
+"#,
+        exe = xml_escape(&exe.to_string_lossy()),
+    )
+}
+
+/// Task Scheduler task: runs at logon, restarts every minute on failure,
+/// output appended to the log through `cmd.exe`.
+fn render_task_xml(exe: &Path, log: &Path) -> String {
+    let arguments = format!(
+        "/c \"\"{}\" gateway >> \"{}\" 2>&1\"",
+        exe.display(),
+        log.display()
+    );
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>Oxibot gateway</Description>
+  </RegistrationInfo>
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+    </LogonTrigger>
+  </Triggers>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <RestartOnFailure>
+      <Interval>PT1M</Interval>
+      <Count>999</Count>
+    </RestartOnFailure>
+    <StartWhenAvailable>true</StartWhenAvailable>
+  </Settings>
+  <Actions>
+    <Exec>
+      <Command>cmd.exe</Command>
+      <Arguments>{}</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        xml_escape(&arguments)
+    )
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_unit() {
+        let unit = render_systemd_unit(
+            Path::new("/opt/my tools/oxibot"),
+            Path::new("/home/u/.oxibot/logs/gateway.log"),
+            Some("/usr/bin:/bin"),
+        );
+        assert!(unit.contains("ExecStart=\"/opt/my tools/oxibot\" gateway\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("StandardOutput=append:/home/u/.oxibot/logs/gateway.log\n"));
+        assert!(unit.contains("Environment=\"PATH=/usr/bin:/bin\"\n"));
+        assert!(unit.ends_with("[Install]\nWantedBy=default.target\n"));
+
+        let unit = render_systemd_unit(Path::new("/usr/bin/oxibot"), Path::new("/tmp/g.log"), None);
+        assert!(unit.contains("ExecStart=/usr/bin/oxibot gateway\n"));
+        assert!(!unit.contains("Environment="));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let plist = render_launchd_plist(
+            Path::new("/Applications/Oxi & Co/oxibot"),
+            Path::new("/Users/u/.oxibot/logs/gateway.log"),
+            Some("/usr/bin"),
+        );
+        assert!(plist.contains("<string>com.oxibot.gateway</string>"));
+        assert!(plist.contains("<string>/Applications/Oxi &amp; Co/oxibot</string>"));
+        assert!(plist.contains("<key>SuccessfulExit</key>\n        <false/>"));
+        assert!(plist.contains("<key>StandardErrorPath</key>\n    <string>/Users/u/.oxibot/logs/gateway.log</string>"));
+        assert!(plist.contains("<key>PATH</key>\n        <string>/usr/bin</string>"));
+    }
+
+    #[test]
+    fn test_task_xml() {
+        let xml = render_task_xml(
+            Path::new(r"C:\Program Files\oxibot.exe"),
+            Path::new(r"C:\Users\u\.oxibot\logs\gateway.log"),
+        );
+        assert!(xml.contains("<LogonTrigger>"));
+        assert!(xml.contains("<RestartOnFailure>"));
+        assert!(xml.contains(
+            r"<Arguments>/c &quot;&quot;C:\Program Files\oxibot.exe&quot; gateway &gt;&gt; &quot;C:\Users\u\.oxibot\logs\gateway.log&quot; 2&gt;&amp;1&quot;</Arguments>"
+        ));
+    }
+
+    #[test]
+    fn test_task_definition_is_utf16() {
+        let bytes = Platform::TaskScheduler.render(Path::new("oxibot.exe"), Path::new("g.log"), None);
+        assert_eq!(&bytes[..4], &[0xFF, 0xFE, b'<', 0]);
+    }
+}
@@ -0,0 +1,456 @@
+//! `oxibot sessions` — browse, tag, search and export saved conversations.
+//!
+//! - `oxibot sessions list [--tag TAG]...` — list sessions, newest first
+//! - `oxibot sessions tag KEY TAG...` — add tags to a session
+//! - `oxibot sessions untag KEY TAG` — remove a tag from a session
+//! - `oxibot sessions search QUERY [--tag TAG]...` — find messages containing QUERY
+//! - `oxibot sessions export [KEY] [--tag TAG]... [-o FILE]` — dump sessions as JSON
+//! - `oxibot sessions replay KEY [--verbose] [--rerun [--live]]` — show (or re-run)
+//!   what the agent did, from the session's transcript
+//!
+//! `--tag` may be repeated; a session must carry every given tag to match.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_agent::replay::{self, ReplayProvider, ReplayTurn};
+use oxibot_agent::AgentLoop;
+use oxibot_core::bus::queue::MessageBus;
+use oxibot_core::config::load_config;
+use oxibot_core::session::{normalize_tag, SessionManager, SessionSummary, TranscriptEvent, TranscriptLog};
+use oxibot_core::types::{ContentPart, Message, MessageContent};
+use oxibot_core::utils::{get_contacts_path, truncate_string};
+
+/// Characters of context shown around a search match.
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Characters of tool arguments and results shown without `--verbose`.
+const REPLAY_PREVIEW_CHARS: usize = 100;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Sessions subcommands.
+#[derive(Subcommand)]
+pub enum SessionsCommands {
+    /// List sessions
+    List {
+        /// Only sessions with this tag (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+    },
+
+    /// Add tags to a session
+    Tag {
+        /// Session key (e.g. telegram:12345)
+        key: String,
+
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove a tag from a session
+    Untag {
+        /// Session key
+        key: String,
+
+        /// Tag to remove
+        tag: String,
+    },
+
+    /// Search message text across sessions
+    Search {
+        /// Text to look for (case-insensitive)
+        query: String,
+
+        /// Only sessions with this tag (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+
+        /// Maximum number of matches to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Export sessions as JSON
+    Export {
+        /// Session key (omit to export every matching session)
+        key: Option<String>,
+
+        /// Only sessions with this tag (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Show what the agent did in a session, tool calls included
+    Replay {
+        /// Session key
+        key: String,
+
+        /// Show full tool arguments and results, models and times
+        #[arg(short, long, default_value_t = false)]
+        verbose: bool,
+
+        /// Run the turns again on the recorded LLM responses and report
+        /// tool results that differ
+        #[arg(long, default_value_t = false)]
+        rerun: bool,
+
+        /// With --rerun, let tools make changes (default: dry run)
+        #[arg(long, default_value_t = false, requires = "rerun")]
+        live: bool,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a sessions subcommand.
+pub async fn dispatch(cmd: SessionsCommands) -> Result<()> {
+    let sessions = SessionManager::new(None).context("failed to open sessions directory")?;
+    match cmd {
+        SessionsCommands::List { tags } => list_sessions(&sessions, &tags),
+        SessionsCommands::Tag { key, tags } => {
+            ensure_exists(&sessions, &key)?;
+            let added = sessions.add_tags(&key, &tags);
+            if added.is_empty() {
+                println!("  {} is already tagged", key.cyan());
+            } else {
+                println!("  {} Tagged {} with {}", "✓".green(), key.cyan(), added.join(", "));
+            }
+            Ok(())
+        }
+        SessionsCommands::Untag { key, tag } => {
+            ensure_exists(&sessions, &key)?;
+            if sessions.remove_tag(&key, &tag) {
+                println!("  {} Removed {} from {}", "✓".green(), tag, key.cyan());
+            } else {
+                println!("  {} {} has no tag {}", "✗".red(), key, tag);
+            }
+            Ok(())
+        }
+        SessionsCommands::Search { query, tags, limit } => {
+            search_sessions(&sessions, &query, &tags, limit)
+        }
+        SessionsCommands::Export { key, tags, output } => {
+            export_sessions(&sessions, key.as_deref(), &tags, output)
+        }
+        SessionsCommands::Replay { key, verbose, rerun, live } => {
+            replay_session(&sessions, &key, verbose, rerun, live).await
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+fn ensure_exists(sessions: &SessionManager, key: &str) -> Result<()> {
+    if !sessions.exists(key) {
+        anyhow::bail!("no session '{key}' (see `oxibot sessions list`)");
+    }
+    Ok(())
+}
+
+/// Sessions carrying all of `tags`, newest first.
+fn matching(sessions: &SessionManager, tags: &[String]) -> Vec<SessionSummary> {
+    sessions
+        .list_sessions()
+        .into_iter()
+        .filter(|s| s.has_tags(tags))
+        .collect()
+}
+
+/// `oxibot sessions list [--tag TAG]...`
+fn list_sessions(sessions: &SessionManager, tags: &[String]) -> Result<()> {
+    let summaries = matching(sessions, tags);
+    if summaries.is_empty() {
+        println!("  No sessions.");
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "  Sessions".cyan().bold());
+    println!();
+    println!("  {:<32} {:<18} {}", "Key".bold(), "Updated".bold(), "Tags".bold());
+    println!("  {}", "─".repeat(76));
+    for s in &summaries {
+        println!(
+            "  {:<32} {:<18} {}",
+            s.key,
+            s.updated_at.format("%Y-%m-%d %H:%M"),
+            s.tags.join(", ").dimmed()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// `oxibot sessions search QUERY [--tag TAG]...`
+fn search_sessions(sessions: &SessionManager, query: &str, tags: &[String], limit: usize) -> Result<()> {
+    let needle = query.to_lowercase();
+    let mut shown = 0;
+    'sessions: for summary in matching(sessions, tags) {
+        let session = sessions.get_or_create(&summary.key);
+        for msg in &session.messages {
+            let Some((role, text)) = message_text(msg) else {
+                continue;
+            };
+            if let Some(snippet) = snippet(&text, &needle) {
+                if shown == limit {
+                    println!("  {}", "(more matches not shown; raise --limit)".dimmed());
+                    break 'sessions;
+                }
+                println!("  {} {} {}", summary.key.cyan(), format!("[{role}]").dimmed(), snippet);
+                shown += 1;
+            }
+        }
+    }
+    if shown == 0 {
+        println!("  No matches.");
+    }
+    Ok(())
+}
+
+/// `oxibot sessions export [KEY] [--tag TAG]... [-o FILE]`
+fn export_sessions(
+    sessions: &SessionManager,
+    key: Option<&str>,
+    tags: &[String],
+    output: Option<std::path::PathBuf>,
+) -> Result<()> {
+    let keys: Vec<String> = match key {
+        Some(key) => {
+            ensure_exists(sessions, key)?;
+            let session_tags = sessions.tags(key);
+            if let Some(missing) = tags
+                .iter()
+                .filter_map(|t| normalize_tag(t))
+                .find(|t| !session_tags.contains(t))
+            {
+                anyhow::bail!("session '{key}' is not tagged {missing}");
+            }
+            vec![key.to_string()]
+        }
+        None => matching(sessions, tags).into_iter().map(|s| s.key).collect(),
+    };
+
+    let exported: Vec<_> = keys.iter().map(|k| sessions.get_or_create(k)).collect();
+    let json = serde_json::to_string_pretty(&exported)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, json)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("  {} Exported {} session(s) to {}", "✓".green(), exported.len(), path.display());
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+/// `oxibot sessions replay KEY [--verbose] [--rerun [--live]]`
+async fn replay_session(sessions: &SessionManager, key: &str, verbose: bool, rerun: bool, live: bool) -> Result<()> {
+    ensure_exists(sessions, key)?;
+    let events = TranscriptLog::new(None).read(key);
+    if events.is_empty() {
+        if rerun {
+            anyhow::bail!("no transcript of '{key}' to re-run (turn on analytics.transcripts)");
+        }
+        println!(
+            "  {}",
+            "No transcript of this session (turn on analytics.transcripts); showing saved messages only.".dimmed()
+        );
+        for msg in &sessions.get_or_create(key).messages {
+            match message_text(msg) {
+                Some(("user", text)) => println!("  👤 {text}"),
+                Some((_, text)) => println!("  💬 {text}"),
+                None => {}
+            }
+        }
+        return Ok(());
+    }
+
+    let turns = replay::split_turns(&events);
+    for (i, turn) in turns.iter().enumerate() {
+        print_turn(i + 1, turn, verbose);
+    }
+    if rerun {
+        rerun_turns(key, &turns, verbose, live).await?;
+    }
+    Ok(())
+}
+
+/// Print one recorded turn.
+fn print_turn(number: usize, turn: &ReplayTurn, verbose: bool) {
+    let clip = |s: &str| {
+        if verbose {
+            s.to_string()
+        } else {
+            truncate_string(&s.split_whitespace().collect::<Vec<_>>().join(" "), REPLAY_PREVIEW_CHARS)
+        }
+    };
+
+    println!();
+    println!("  {}", format!("── Turn {number} ──").cyan().bold());
+    println!("  👤 {}", turn.user);
+    for step in &turn.steps {
+        match step {
+            TranscriptEvent::Assistant { timestamp, model, content, tool_calls } => {
+                if verbose {
+                    println!("  {}", format!("🤖 {model} at {}", timestamp.format("%Y-%m-%d %H:%M:%S")).dimmed());
+                }
+                // Text beside tool calls; the last response's text is the reply
+                if let Some(text) = content.as_ref().filter(|_| !tool_calls.is_empty()) {
+                    println!("  🤖 {}", clip(text));
+                }
+                for tc in tool_calls {
+                    println!("  🤖 → {} {}", tc.function.name.bold(), clip(&tc.function.arguments).dimmed());
+                }
+            }
+            TranscriptEvent::ToolResult { tool, result, duration_ms, .. } => {
+                println!("  🔧 {tool} {} {}", format!("({duration_ms} ms)").dimmed(), clip(result));
+            }
+            _ => {}
+        }
+    }
+    match turn.reply {
+        Some(ref reply) => println!("  💬 {}", clip(reply)),
+        None => println!("  {}", "(turn did not finish)".dimmed()),
+    }
+}
+
+/// Run `turns` again on their recorded LLM responses and report tool
+/// results and replies that differ.
+async fn rerun_turns(key: &str, turns: &[ReplayTurn], verbose: bool, live: bool) -> Result<()> {
+    let config = load_config(None);
+    let defaults = &config.agents.defaults;
+    let workspace = crate::helpers::expand_tilde(&defaults.workspace);
+    // Sessions and transcript of the re-run stay out of the real ones
+    let dir = std::env::temp_dir().join(format!("oxibot-replay-{}", std::process::id()));
+    let sessions = SessionManager::new(Some(dir.join("sessions"))).context("failed to create session manager")?;
+    let transcripts = TranscriptLog::new(Some(dir.join("transcripts")));
+
+    let provider = Arc::new(ReplayProvider::new(&defaults.model));
+    let (calendar, timezone) = crate::helpers::calendar(&config, &crate::oauth_cmd::build_manager(&config))?;
+    let agent = AgentLoop::new(
+        Arc::new(MessageBus::new(100)),
+        provider.clone(),
+        workspace.clone(),
+        Some(defaults.model.clone()),
+        Some(defaults.max_tool_iterations as usize),
+        None,
+        None,
+        Some(crate::helpers::exec_config(&config)),
+        config.tools.restrict_to_workspace,
+        Some(sessions),
+        None,
+    )
+    .with_scratch(crate::helpers::scratch_dirs(&config.tools.scratch, &workspace))
+    .with_contacts(Some(get_contacts_path()))
+    .with_calendar(calendar, timezone)
+    .with_github(crate::helpers::github(&config))
+    .with_file_index(config.tools.file_index.enabled.then(|| config.tools.file_index.clone()))
+    .with_transcripts(Some(TranscriptLog::new(Some(dir.join("transcripts")))))
+    .with_dry_run(!live);
+
+    let replayed = replay::rerun(&agent, &provider, &transcripts, key, turns).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    let divergences = replay::compare(turns, &replayed?);
+
+    println!();
+    if !live {
+        println!("  {}", "Dry run: tools that change things only described the change (--live runs them).".dimmed());
+    }
+    if divergences.is_empty() {
+        println!("  {} Re-ran {} turn(s): every tool call and reply came out the same.", "✓".green(), turns.len());
+        return Ok(());
+    }
+    println!("  {} Re-ran {} turn(s), {} difference(s):", "✗".red(), turns.len(), divergences.len());
+    let clip = |s: &str| if verbose { s.to_string() } else { truncate_string(s, REPLAY_PREVIEW_CHARS) };
+    for d in &divergences {
+        println!();
+        println!("  Turn {}, {}", d.turn, d.step.bold());
+        println!("    {} {}", "recorded:".dimmed(), clip(&d.recorded));
+        println!("    {} {}", "replayed:".dimmed(), clip(&d.replayed));
+    }
+    Ok(())
+}
+
+/// Role and plain text of a stored message (tool traffic is skipped).
+fn message_text(msg: &Message) -> Option<(&'static str, String)> {
+    match msg {
+        Message::User { content: MessageContent::Text(text) } => Some(("user", text.clone())),
+        Message::User { content: MessageContent::Parts(parts) } => Some((
+            "user",
+            parts
+                .iter()
+                .filter_map(|p| match p {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        )),
+        Message::Assistant { content: Some(text), .. } => Some(("assistant", text.clone())),
+        _ => None,
+    }
+}
+
+/// A one-line excerpt around the first case-insensitive match of `needle`.
+fn snippet(text: &str, needle: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    // Lowercasing can change the length of a few characters; fall back to
+    // the start of the text rather than mis-slicing.
+    let haystack: String = lower.iter().collect();
+    let byte_pos = haystack.find(needle)?;
+    let pos = if lower.len() == chars.len() {
+        haystack[..byte_pos].chars().count()
+    } else {
+        0
+    };
+    let start = pos.saturating_sub(SNIPPET_CONTEXT);
+    let end = (pos + needle.chars().count() + SNIPPET_CONTEXT).min(chars.len());
+    let body: String = chars[start..end].iter().collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < chars.len() { "…" } else { "" };
+    Some(format!("{prefix}{body}{suffix}"))
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("Book the Flight to Lisbon", "flight").as_deref(), Some("Book the Flight to Lisbon"));
+        assert_eq!(snippet("nothing here", "flight"), None);
+
+        let long = format!("{} needle {}", "a ".repeat(50), "b ".repeat(50));
+        let s = snippet(&long, "needle").unwrap();
+        assert!(s.starts_with('…') && s.ends_with('…'));
+        assert!(s.contains("needle"));
+    }
+
+    #[test]
+    fn test_message_text() {
+        assert_eq!(message_text(&Message::user("hi")), Some(("user", "hi".to_string())));
+        assert_eq!(message_text(&Message::assistant("yo")), Some(("assistant", "yo".to_string())));
+        assert_eq!(message_text(&Message::tool_result("c1", "out")), None);
+    }
+}
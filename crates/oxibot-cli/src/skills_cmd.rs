@@ -0,0 +1,88 @@
+//! `oxibot skills` — validate the workspace skills.
+//!
+//! - `oxibot skills lint` — check every `SKILL.md` for frontmatter, metadata
+//!   and requirement problems; exits non-zero if any skill has errors
+
+use anyhow::Result;
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_agent::skills::{IssueLevel, SkillIssue, SkillsLoader};
+use oxibot_core::config::load_config;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Skills subcommands.
+#[derive(Subcommand)]
+pub enum SkillsCommands {
+    /// Validate every skill's SKILL.md
+    Lint,
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a skills subcommand.
+pub fn dispatch(cmd: SkillsCommands) -> Result<()> {
+    let config = load_config(None);
+    let workspace = crate::helpers::expand_tilde(&config.agents.defaults.workspace);
+    let loader = SkillsLoader::new(&workspace, None).with_secrets(&config.skills.secrets);
+
+    match cmd {
+        SkillsCommands::Lint => lint(&loader),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+/// `oxibot skills lint`
+fn lint(loader: &SkillsLoader) -> Result<()> {
+    let skills = loader.list_skills(false);
+    let issues = loader.lint();
+
+    println!();
+    println!("{}", "  Skills Lint".cyan().bold());
+    println!();
+    for skill in &skills {
+        if !issues.iter().any(|i| i.skill == skill.name) {
+            println!("  {} {}", "✓".green(), skill.name);
+        }
+    }
+
+    let mut shown: Vec<&str> = Vec::new();
+    for issue in &issues {
+        if shown.contains(&issue.skill.as_str()) {
+            continue;
+        }
+        shown.push(&issue.skill);
+        let own: Vec<&SkillIssue> = issues.iter().filter(|i| i.skill == issue.skill).collect();
+        let mark = if own.iter().any(|i| i.level == IssueLevel::Error) {
+            "✗".red()
+        } else {
+            "!".yellow()
+        };
+        println!("  {} {} {}", mark, issue.skill, issue.path.display().to_string().dimmed());
+        for i in own {
+            let level = match i.level {
+                IssueLevel::Error => "error".red(),
+                IssueLevel::Warning => "warning".yellow(),
+            };
+            println!("      {level}: {}", i.message);
+        }
+    }
+
+    let errors = issues.iter().filter(|i| i.level == IssueLevel::Error).count();
+    let warnings = issues.len() - errors;
+    println!();
+    println!("  {} skills checked: {errors} errors, {warnings} warnings", skills.len());
+    println!();
+    if errors > 0 {
+        anyhow::bail!("{errors} skill errors found");
+    }
+    Ok(())
+}
@@ -2,18 +2,42 @@
 //!
 //! Replaces nanobot's `status` command:
 //! - Shows config path, workspace, model
-//! - Shows API key status for each provider
+//! - Shows API key status for each provider, with the latency and errors
+//!   of the gateway's periodic probes
+//! - `--tools`: call counts, durations and error rates per tool, slowest
+//!   first, with slow and flaky tools flagged
+//! - `--json`: everything above plus provider connectivity checks, channel
+//!   config validity, workspace paths, skills and version, for monitoring
+
+use std::path::Path;
 
 use anyhow::Result;
 use colored::Colorize;
+use serde_json::{json, Value};
+
+use oxibot_agent::skills::{SkillSource, SkillsLoader};
+use oxibot_core::config::{load_config, Config};
+use oxibot_core::tool_stats::{ToolStatsStore, FLAKY_ERROR_RATE, SLOW_TOOL_MS};
+use oxibot_core::utils::{get_data_path, get_outbox_path, get_provider_health_path, get_sessions_path};
+use oxibot_providers::health::{self, HealthSummary};
+use oxibot_providers::registry::{all_providers, register_custom_providers, AuthStyle};
+use oxibot_providers::ProviderHealth;
 
-use oxibot_core::config::load_config;
-use oxibot_core::utils::get_data_path;
-use oxibot_providers::registry::PROVIDERS;
+use crate::channels_cmd::channel_rows;
 
-/// Run the status command.
-pub fn run() -> Result<()> {
+/// Run the status command (`tools` shows tool stats instead, `json` prints
+/// a machine-readable report).
+pub async fn run(tools: bool, json: bool) -> Result<()> {
+    if tools {
+        print_tool_stats(&ToolStatsStore::new(None));
+        return Ok(());
+    }
     let config = load_config(None);
+    if json {
+        let report = status_json(&config).await;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
     let data_dir = get_data_path();
     let config_path = data_dir.join("config.json");
 
@@ -66,19 +90,26 @@ pub fn run() -> Result<()> {
     // Providers
     println!();
     println!("  {}", "Providers:".bold());
+    register_custom_providers(&config.providers.custom);
     let providers_map = config.providers.to_map();
+    let health = recorded_health(&config);
 
-    for spec in PROVIDERS {
+    for spec in all_providers() {
         let status = if let Some(prov_config) = providers_map.get(spec.name) {
             if prov_config.is_configured() {
                 format!("{} (key set)", "✓".green())
+            } else if spec.auth == AuthStyle::None {
+                format!("{} (no auth)", "✓".green())
             } else {
                 format!("{}", "· not configured".dimmed())
             }
         } else {
             format!("{}", "· not configured".dimmed())
         };
-        println!("    {:<20} {}", spec.display_name, status);
+        match health.summary(spec.name) {
+            Some(summary) => println!("    {:<20} {}  {}", spec.display_name, status, health_line(&summary)),
+            None => println!("    {:<20} {}", spec.display_name, status),
+        }
     }
 
     // Brave Search
@@ -89,8 +120,240 @@ pub fn run() -> Result<()> {
         format!("{} (key set)", "✓".green())
     };
     println!("  {:<18} {}", "Brave Search:".bold(), brave_status);
+    let search = &config.tools.web.search;
+    if search.native {
+        let model = if search.native_model.is_empty() { "agent model" } else { &search.native_model };
+        println!("  {:<18} {} via provider ({})", "Web search:".bold(), "✓".green(), model);
+    }
 
     println!();
 
     Ok(())
 }
+
+/// Probe history saved by the gateway.
+fn recorded_health(config: &Config) -> ProviderHealth {
+    ProviderHealth::new(Some(get_provider_health_path()), config.providers.health.history)
+}
+
+/// `avg 420 ms, 2/50 probes failed` plus the last error, if the last probe
+/// failed.
+fn health_line(summary: &HealthSummary) -> String {
+    let latency = summary
+        .avg_latency_ms
+        .map_or_else(|| "no successful probe".to_string(), |ms| format!("avg {ms} ms"));
+    let mut line = format!("{latency}, {}/{} probes failed", summary.failures, summary.probes);
+    if let Some(ref error) = summary.last.error {
+        line.push_str(&format!(", last: {error}"));
+        return line.red().to_string();
+    }
+    line.dimmed().to_string()
+}
+
+/// Print per-tool stats, slowest first.
+fn print_tool_stats(store: &ToolStatsStore) {
+    println!();
+    println!("{}", "🦀 Tool Stats".cyan().bold());
+    println!();
+
+    let rows = store.slowest();
+    if rows.is_empty() {
+        println!("  No tool calls recorded yet.");
+        println!();
+        return;
+    }
+
+    println!(
+        "  {:<24} {:>7} {:>9} {:>9} {:>7}",
+        "Tool".bold(),
+        "Calls".bold(),
+        "Avg".bold(),
+        "Max".bold(),
+        "Errors".bold()
+    );
+    for (name, stat) in &rows {
+        let mut flags = Vec::new();
+        if stat.is_slow() {
+            flags.push("slow".yellow().to_string());
+        }
+        if stat.is_flaky() {
+            flags.push("flaky".red().to_string());
+        }
+        let line = format!(
+            "  {:<24} {:>7} {:>7}ms {:>7}ms {:>6.0}% {}",
+            name,
+            stat.calls,
+            stat.avg_ms(),
+            stat.max_ms,
+            stat.error_rate() * 100.0,
+            flags.join(" ")
+        );
+        println!("{}", line.trim_end());
+    }
+
+    let flagged: Vec<_> = rows.iter().filter(|(_, s)| s.is_slow() || s.is_flaky()).collect();
+    if !flagged.is_empty() {
+        println!();
+        println!(
+            "  {}",
+            format!(
+                "slow = avg ≥ {}s, flaky = ≥ {:.0}% errors",
+                SLOW_TOOL_MS / 1000,
+                FLAKY_ERROR_RATE * 100.0
+            )
+            .dimmed()
+        );
+        for (name, stat) in flagged {
+            if let Some(ref error) = stat.last_error {
+                println!("  {name} {} {error}", "last error:".dimmed());
+            }
+        }
+    }
+    println!();
+    println!("  {}", store.path().display().to_string().dimmed());
+    println!();
+}
+
+// ─────────────────────────────────────────────
+// JSON report
+// ─────────────────────────────────────────────
+
+/// Cargo features this binary was built with.
+fn build_features() -> Vec<&'static str> {
+    [
+        ("telegram", cfg!(feature = "telegram")),
+        ("discord", cfg!(feature = "discord")),
+        ("whatsapp", cfg!(feature = "whatsapp")),
+        ("slack", cfg!(feature = "slack")),
+        ("signal", cfg!(feature = "signal")),
+        ("email", cfg!(feature = "email")),
+        ("filedrop", cfg!(feature = "filedrop")),
+        ("analytics", cfg!(feature = "analytics")),
+        ("browser", cfg!(feature = "browser")),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect()
+}
+
+fn path_entry(path: &Path) -> Value {
+    json!({ "path": path.display().to_string(), "exists": path.exists() })
+}
+
+/// The full status report. Configured providers are checked concurrently.
+async fn status_json(config: &Config) -> Value {
+    let data_dir = get_data_path();
+    let workspace = crate::helpers::expand_tilde(&config.agents.defaults.workspace);
+
+    register_custom_providers(&config.providers.custom);
+    let providers_map = config.providers.to_map();
+    let health = recorded_health(config);
+    let client = reqwest::Client::new();
+    let mut checks = tokio::task::JoinSet::new();
+    let mut providers = Vec::new();
+    for spec in all_providers() {
+        let prov_config = providers_map.get(spec.name).cloned().unwrap_or_default();
+        let configured = prov_config.is_configured()
+            || (spec.auth == AuthStyle::None && prov_config.api_base.is_some());
+        if configured {
+            let client = client.clone();
+            checks.spawn(async move { (spec.name, health::probe(&client, spec, &prov_config).await) });
+        }
+        providers.push(json!({
+            "name": spec.name,
+            "displayName": spec.display_name,
+            "configured": configured,
+            "check": null,
+            "health": health.summary(spec.name).map(|s| json!({
+                "probes": s.probes,
+                "failures": s.failures,
+                "avgLatencyMs": s.avg_latency_ms,
+                "last": s.last,
+            })),
+        }));
+    }
+    while let Some(Ok((name, check))) = checks.join_next().await {
+        if let Some(entry) = providers.iter_mut().find(|p| p["name"] == name) {
+            entry["check"] = json!({
+                "ok": check.ok(),
+                "url": check.url,
+                "status": check.status,
+                "latencyMs": check.latency_ms,
+                "error": check.error,
+            });
+        }
+    }
+
+    let channels: Vec<Value> = channel_rows(config)
+        .into_iter()
+        .map(|row| {
+            let valid = row.is_valid();
+            let mut value = serde_json::to_value(row).unwrap_or_default();
+            value["valid"] = valid.into();
+            value
+        })
+        .collect();
+
+    let loader = SkillsLoader::new(&workspace, None).with_secrets(&config.skills.secrets);
+    let skills: Vec<Value> = loader
+        .list_skills(false)
+        .into_iter()
+        .map(|skill| {
+            let meta = loader.get_skill_meta(&skill.name);
+            let missing = loader.missing_requirements(&skill.name);
+            json!({
+                "name": skill.name,
+                "source": if skill.source == SkillSource::Builtin { "builtin" } else { "workspace" },
+                "path": skill.path.display().to_string(),
+                "description": meta.description,
+                "always": meta.always,
+                "available": missing.is_empty(),
+                "missing": missing,
+            })
+        })
+        .collect();
+
+    let healthy = providers
+        .iter()
+        .filter(|p| !p["check"].is_null())
+        .all(|p| p["check"]["ok"] == true)
+        && channels
+            .iter()
+            .filter(|c| c["configured"] == true)
+            .all(|c| c["valid"] == true);
+
+    let defaults = &config.agents.defaults;
+    let search = &config.tools.web.search;
+    json!({
+        "healthy": healthy,
+        "version": {
+            "oxibot": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "features": build_features(),
+        },
+        "paths": {
+            "config": path_entry(&data_dir.join("config.json")),
+            "dataDir": path_entry(&data_dir),
+            "workspace": path_entry(&workspace),
+            "memory": path_entry(&workspace.join("memory").join("MEMORY.md")),
+            "skills": path_entry(&workspace.join("skills")),
+            "sessions": path_entry(&get_sessions_path()),
+            "outbox": path_entry(&get_outbox_path()),
+        },
+        "agent": {
+            "model": defaults.model,
+            "temperature": defaults.temperature,
+            "maxTokens": defaults.max_tokens,
+        },
+        "webSearch": {
+            "brave": !search.api_key.is_empty(),
+            "native": search.native,
+            "nativeModel": search.native_model,
+        },
+        "providers": providers,
+        "channels": channels,
+        "skills": skills,
+    })
+}
+
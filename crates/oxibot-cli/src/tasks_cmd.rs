@@ -0,0 +1,107 @@
+//! `oxibot tasks` — view and manage the agent's task list from the CLI.
+//!
+//! - `oxibot tasks list [--all]` — list open tasks (or all tasks)
+//! - `oxibot tasks add TITLE [--due YYYY-MM-DD]` — add a task
+//! - `oxibot tasks done ID` — mark a task as done
+
+use anyhow::Result;
+use chrono::Utc;
+use clap::Subcommand;
+use colored::Colorize;
+
+use oxibot_agent::tasks::{parse_due, TaskStore};
+use oxibot_core::config::load_config;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Tasks subcommands.
+#[derive(Subcommand)]
+pub enum TasksCommands {
+    /// List open tasks
+    List {
+        /// Include completed tasks
+        #[arg(short, long, default_value_t = false)]
+        all: bool,
+    },
+
+    /// Add a task
+    Add {
+        /// What needs doing
+        title: String,
+
+        /// Due date (YYYY-MM-DD)
+        #[arg(short, long)]
+        due: Option<String>,
+    },
+
+    /// Mark a task as done
+    Done {
+        /// Task id
+        id: u64,
+    },
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a tasks subcommand.
+pub fn dispatch(cmd: TasksCommands) -> Result<()> {
+    let config = load_config(None);
+    let workspace = crate::helpers::expand_tilde(&config.agents.defaults.workspace);
+    let store = TaskStore::new(&workspace);
+    let today = Utc::now().date_naive();
+
+    match cmd {
+        TasksCommands::List { all } => list_tasks(&store, all),
+        TasksCommands::Add { title, due } => {
+            let due = due.map(|d| parse_due(&d)).transpose()?;
+            let task = store.add(&title, due)?;
+            println!("  {} Added {}", "✓".green(), task.summary(today));
+            Ok(())
+        }
+        TasksCommands::Done { id } => {
+            let task = store.complete(id)?;
+            println!("  {} Completed #{} {}", "✓".green(), task.id, task.title.cyan());
+            Ok(())
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Command implementations
+// ─────────────────────────────────────────────
+
+/// `oxibot tasks list [--all]`
+fn list_tasks(store: &TaskStore, all: bool) -> Result<()> {
+    let mut tasks = store.open();
+    if all {
+        tasks.extend(store.all().into_iter().filter(|t| !t.is_open()));
+    }
+    if tasks.is_empty() {
+        println!("  No tasks.");
+        return Ok(());
+    }
+
+    let today = Utc::now().date_naive();
+    println!();
+    println!("{}", "  Tasks".cyan().bold());
+    println!();
+    println!("  {:<6} {:<12} {:<12} {}", "Id".bold(), "Status".bold(), "Due".bold(), "Title".bold());
+    println!("  {}", "─".repeat(76));
+    for task in &tasks {
+        let status = if !task.is_open() {
+            "done".dimmed()
+        } else if task.due.is_some_and(|d| d < today) {
+            "overdue".red()
+        } else {
+            "open".green()
+        };
+        let due = task.due.map(|d| d.to_string()).unwrap_or_else(|| "—".into());
+        println!("  {:<6} {:<12} {:<12} {}", task.id, status, due, task.title);
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,293 @@
+//! `oxibot workspace` — create agent workspaces from templates.
+//!
+//! - `oxibot workspace init <template> [--path DIR]` — create a workspace
+//!   (default `~/.oxibot/workspace-<template>`) with the standard files plus
+//!   the template's skills, memory seed, README and tool notes
+//! - `oxibot workspace sync` — sync the workspace with remote storage once
+//!   (see the `sync` config section)
+//!
+//! Templates are also available to `oxibot onboard --template`. Each one
+//! suggests tool settings; `onboard` applies them to a new config, other
+//! times they are printed for the user to copy.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Subcommand, ValueEnum};
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use oxibot_core::config::{load_config, Config};
+use oxibot_core::sync::WorkspaceSync;
+use oxibot_core::utils::get_data_path;
+
+// ─────────────────────────────────────────────
+// Subcommand enum
+// ─────────────────────────────────────────────
+
+/// Workspace subcommands.
+#[derive(Subcommand)]
+pub enum WorkspaceCommands {
+    /// Create a workspace from a template
+    Init {
+        /// Template to use
+        template: Template,
+
+        /// Workspace directory (default: ~/.oxibot/workspace-<template>)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Sync the workspace with the configured WebDAV/S3 storage now
+    Sync,
+}
+
+// ─────────────────────────────────────────────
+// Templates
+// ─────────────────────────────────────────────
+
+/// A workspace template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// Runbooks, incident reports, long-running shell commands
+    Devops,
+    /// Web research, source notes, literature reviews
+    Research,
+    /// Daily briefings, journal, reminders; file tools kept in the workspace
+    Personal,
+}
+
+impl Template {
+    /// Template name as typed on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Template::Devops => "devops",
+            Template::Research => "research",
+            Template::Personal => "personal",
+        }
+    }
+
+    /// Files to create, relative to the workspace root.
+    fn files(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Template::Devops => &[
+                ("README.md", include_str!("../templates/devops/README.md")),
+                ("TOOLS.md", include_str!("../templates/devops/TOOLS.md")),
+                ("skills/runbook/SKILL.md", include_str!("../templates/devops/skills/runbook/SKILL.md")),
+                (
+                    "skills/incident-report/SKILL.md",
+                    include_str!("../templates/devops/skills/incident-report/SKILL.md"),
+                ),
+            ],
+            Template::Research => &[
+                ("README.md", include_str!("../templates/research/README.md")),
+                ("TOOLS.md", include_str!("../templates/research/TOOLS.md")),
+                (
+                    "skills/literature-review/SKILL.md",
+                    include_str!("../templates/research/skills/literature-review/SKILL.md"),
+                ),
+                (
+                    "skills/source-notes/SKILL.md",
+                    include_str!("../templates/research/skills/source-notes/SKILL.md"),
+                ),
+            ],
+            Template::Personal => &[
+                ("README.md", include_str!("../templates/personal/README.md")),
+                ("TOOLS.md", include_str!("../templates/personal/TOOLS.md")),
+                (
+                    "skills/daily-briefing/SKILL.md",
+                    include_str!("../templates/personal/skills/daily-briefing/SKILL.md"),
+                ),
+                ("skills/journal/SKILL.md", include_str!("../templates/personal/skills/journal/SKILL.md")),
+            ],
+        }
+    }
+
+    /// Empty directories the README describes.
+    fn dirs(self) -> &'static [&'static str] {
+        match self {
+            Template::Devops => &["runbooks", "incidents"],
+            Template::Research => &["notes", "reviews"],
+            Template::Personal => &["journal", "lists"],
+        }
+    }
+
+    /// Section appended to `memory/MEMORY.md`.
+    fn memory_seed(self) -> &'static str {
+        match self {
+            Template::Devops => include_str!("../templates/devops/MEMORY.md"),
+            Template::Research => include_str!("../templates/research/MEMORY.md"),
+            Template::Personal => include_str!("../templates/personal/MEMORY.md"),
+        }
+    }
+
+    /// Suggested config settings, as dotted camelCase paths.
+    pub fn settings(self) -> Vec<(&'static str, Value)> {
+        match self {
+            Template::Devops => vec![("tools.exec.timeout", json!(300))],
+            Template::Research => vec![("tools.web.search.maxResults", json!(10))],
+            Template::Personal => vec![("tools.restrictToWorkspace", json!(true))],
+        }
+    }
+
+    /// Add the template's files to `workspace`, keeping existing files.
+    pub fn install(self, workspace: &Path) -> Result<()> {
+        for (relative, content) in self.files() {
+            let path = workspace.join(relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            match relative.strip_prefix("skills/").and_then(|r| r.split('/').next()) {
+                Some(skill) if path.exists() => println!("  {} {skill} already exists", "✓".green()),
+                Some(skill) => {
+                    std::fs::write(&path, content)?;
+                    println!("  {} created skill: {skill}", "✓".green());
+                }
+                None => crate::onboard::create_template(&path, content)?,
+            }
+        }
+        for dir in self.dirs() {
+            std::fs::create_dir_all(workspace.join(dir))?;
+        }
+
+        let memory_path = workspace.join("memory").join("MEMORY.md");
+        let memory = std::fs::read_to_string(&memory_path).unwrap_or_default();
+        let seed = self.memory_seed();
+        let heading = seed.lines().next().unwrap_or_default();
+        if !memory.contains(heading) {
+            std::fs::write(&memory_path, format!("{}\n\n{seed}", memory.trim_end()).trim_start())?;
+            println!("  {} added {} notes to MEMORY.md", "✓".green(), self.name());
+        }
+        Ok(())
+    }
+}
+
+/// `config` with `settings` applied.
+pub fn apply_settings(config: &Config, settings: &[(&str, Value)]) -> Result<Config> {
+    let mut value = serde_json::to_value(config)?;
+    for (path, setting) in settings {
+        let pointer = format!("/{}", path.replace('.', "/"));
+        *value
+            .pointer_mut(&pointer)
+            .with_context(|| format!("unknown setting {path}"))? = setting.clone();
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Print the template's settings for the user to add to config.json.
+pub fn print_settings(template: Template) {
+    println!("  {} suggested settings for {}:", "!".yellow(), template.name());
+    for (path, value) in template.settings() {
+        println!("      {path} = {value}");
+    }
+}
+
+// ─────────────────────────────────────────────
+// Dispatcher
+// ─────────────────────────────────────────────
+
+/// Dispatch a workspace subcommand.
+pub async fn dispatch(cmd: WorkspaceCommands) -> Result<()> {
+    match cmd {
+        WorkspaceCommands::Init { template, path } => init(template, path),
+        WorkspaceCommands::Sync => sync().await,
+    }
+}
+
+/// `oxibot workspace init`
+fn init(template: Template, path: Option<PathBuf>) -> Result<()> {
+    let workspace = match path {
+        Some(path) => crate::helpers::expand_tilde(&path.to_string_lossy()),
+        None => get_data_path().join(format!("workspace-{}", template.name())),
+    };
+
+    println!();
+    println!("{}", format!("  Workspace: {}", template.name()).cyan().bold());
+    println!();
+    crate::onboard::init_workspace(&workspace, Some(template))?;
+    println!();
+    print_settings(template);
+    println!();
+    println!(
+        "  Use it with \"agents.defaults.workspace\": \"{}\" or in an agent profile.",
+        workspace.display()
+    );
+    println!();
+    Ok(())
+}
+
+/// `oxibot workspace sync`
+async fn sync() -> Result<()> {
+    let config = load_config(None);
+    let workspace = crate::helpers::expand_tilde(&config.agents.defaults.workspace);
+    let Some(sync) = WorkspaceSync::from_config(&config.sync, &workspace)? else {
+        anyhow::bail!("workspace sync is not enabled (set \"sync.enabled\" in the config)");
+    };
+    println!("  Syncing {} ({})…", workspace.display(), config.sync.backend);
+    let report = sync.sync().await?;
+    if report.is_empty() {
+        println!("  {} Already in sync", "✓".green());
+    } else {
+        println!("  {} {report}", "✓".green());
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_agent::skills::{IssueLevel, SkillsLoader};
+
+    #[test]
+    fn test_templates_install_valid_skills() {
+        for template in Template::value_variants() {
+            let dir = tempfile::tempdir().unwrap();
+            crate::onboard::init_workspace(dir.path(), Some(*template)).unwrap();
+
+            assert!(dir.path().join("README.md").exists());
+            assert!(dir.path().join(template.dirs()[0]).is_dir());
+            let memory = std::fs::read_to_string(dir.path().join("memory/MEMORY.md")).unwrap();
+            assert!(memory.starts_with("# Long-term Memory"));
+            assert!(memory.contains(template.memory_seed().lines().next().unwrap()));
+
+            let loader = SkillsLoader::new(dir.path(), None);
+            let issues = loader.lint();
+            assert!(
+                !issues.iter().any(|i| i.level == IssueLevel::Error || i.path.starts_with(dir.path())),
+                "{template:?}: {issues:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_install_keeps_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("memory")).unwrap();
+        std::fs::write(dir.path().join("README.md"), "mine").unwrap();
+        std::fs::write(dir.path().join("memory/MEMORY.md"), "# Notes\n").unwrap();
+
+        Template::Devops.install(dir.path()).unwrap();
+        Template::Devops.install(dir.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("README.md")).unwrap(), "mine");
+        let memory = std::fs::read_to_string(dir.path().join("memory/MEMORY.md")).unwrap();
+        assert_eq!(memory.matches("## Infrastructure").count(), 1);
+        assert!(memory.starts_with("# Notes\n\n## Infrastructure"));
+    }
+
+    #[test]
+    fn test_apply_settings() {
+        for template in Template::value_variants() {
+            apply_settings(&Config::default(), &template.settings()).unwrap();
+        }
+        let config = apply_settings(&Config::default(), &Template::Devops.settings()).unwrap();
+        assert_eq!(config.tools.exec.timeout, 300);
+        let config = apply_settings(&Config::default(), &Template::Personal.settings()).unwrap();
+        assert!(config.tools.restrict_to_workspace);
+
+        let err = apply_settings(&Config::default(), &[("tools.nope", json!(1))]).unwrap_err();
+        assert!(err.to_string().contains("unknown setting tools.nope"));
+    }
+}
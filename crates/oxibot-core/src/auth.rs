@@ -0,0 +1,452 @@
+//! Authentication for the gateway HTTP API.
+//!
+//! A request proves who it is with `Authorization: Bearer <credential>`,
+//! where the credential is one of:
+//!
+//! - an API key created with `oxibot apikeys create`. Only a SHA-256 hash
+//!   of each key is kept, in `~/.oxibot/api_keys.json`;
+//! - an HS256 JWT signed with `gateway.jwt.secret`, whose `iss` matches
+//!   `gateway.jwt.issuer` when one is set. Its `scope` claim picks the
+//!   scope;
+//! - `gateway.adminToken`, which has the admin scope.
+//!
+//! [`Guarded`] puts a handler behind a path prefix that requires a scope.
+//! `chat` keys may only talk to the agent; `admin` keys may do anything.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::config::schema::JwtConfig;
+use crate::http::{Handler, Request, Response};
+use crate::sync::s3::hmac_sha256;
+use crate::utils;
+
+/// Prefix of every key, so leaked keys are easy to recognize.
+const KEY_PREFIX: &str = "oxk_";
+
+/// What a credential may do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Talk to the agent.
+    Chat,
+    /// Everything, including the `/admin/` endpoints.
+    Admin,
+}
+
+impl Scope {
+    /// Parse `chat` or `admin`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "chat" => Some(Self::Chat),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether a credential with this scope may use an endpoint needing `needed`.
+    pub fn allows(self, needed: Scope) -> bool {
+        self >= needed
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Chat => "chat",
+            Self::Admin => "admin",
+        })
+    }
+}
+
+// ─────────────────────────────────────────────
+// API keys
+// ─────────────────────────────────────────────
+
+/// A stored API key. The key itself is only shown once, when created.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    /// Short id, used to revoke the key.
+    pub id: String,
+    /// What the key is for.
+    pub name: String,
+    pub scope: Scope,
+    /// SHA-256 of the key, hex.
+    pub hash: String,
+    /// When the key was created (RFC 3339).
+    pub created_at: String,
+}
+
+/// The API keys in `~/.oxibot/api_keys.json`.
+pub struct ApiKeyStore {
+    path: PathBuf,
+    keys: Vec<ApiKey>,
+}
+
+impl ApiKeyStore {
+    /// Load the store from `path` (default `~/.oxibot/api_keys.json`).
+    /// A missing or invalid file gives an empty store.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let path = path.unwrap_or_else(utils::get_api_keys_path);
+        let keys = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "invalid api keys file");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { path, keys }
+    }
+
+    /// Write the store back to disk.
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.keys)?)?;
+        Ok(())
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All keys.
+    pub fn keys(&self) -> &[ApiKey] {
+        &self.keys
+    }
+
+    /// Create a key. Returns its record and the key itself, which is not
+    /// stored and can't be shown again.
+    pub fn create(&mut self, name: &str, scope: Scope) -> (ApiKey, String) {
+        let id = loop {
+            let id = format!("{:08x}", rand::random::<u32>());
+            if !self.keys.iter().any(|k| k.id == id) {
+                break id;
+            }
+        };
+        let secret: String = rand::random::<[u8; 24]>().iter().map(|b| format!("{b:02x}")).collect();
+        let token = format!("{KEY_PREFIX}{id}_{secret}");
+        let key = ApiKey {
+            id,
+            name: name.to_string(),
+            scope,
+            hash: hash_key(&token),
+            created_at: Utc::now().to_rfc3339(),
+        };
+        self.keys.push(key.clone());
+        (key, token)
+    }
+
+    /// Remove the key with `id`. Returns whether it existed.
+    pub fn revoke(&mut self, id: &str) -> bool {
+        let before = self.keys.len();
+        self.keys.retain(|k| k.id != id);
+        self.keys.len() != before
+    }
+
+    /// The key matching `token`, if any.
+    pub fn verify(&self, token: &str) -> Option<&ApiKey> {
+        if !token.starts_with(KEY_PREFIX) {
+            return None;
+        }
+        let hash = hash_key(token);
+        self.keys.iter().find(|k| k.hash == hash)
+    }
+}
+
+fn hash_key(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ─────────────────────────────────────────────
+// JWT
+// ─────────────────────────────────────────────
+
+/// The claims checked in a JWT.
+#[derive(Deserialize)]
+struct Claims {
+    iss: Option<String>,
+    exp: Option<i64>,
+    nbf: Option<i64>,
+    /// Space-separated scopes, e.g. `"chat admin"`.
+    #[serde(default)]
+    scope: String,
+}
+
+/// The scope granted by an HS256 `token` signed with `config.secret`, or
+/// `None` if the token is invalid, expired, from another issuer or grants
+/// no known scope.
+pub fn validate_jwt(config: &JwtConfig, token: &str) -> Option<Scope> {
+    if config.secret.is_empty() {
+        return None;
+    }
+    let mut parts = token.split('.');
+    let (head, payload, signature) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let header: serde_json::Value = serde_json::from_slice(&base64url_decode(head)?).ok()?;
+    if header["alg"] != "HS256" {
+        return None;
+    }
+    let expected = hmac_sha256(config.secret.as_bytes(), format!("{head}.{payload}").as_bytes());
+    let signature = base64url_decode(signature)?;
+    if !constant_time_eq(&signature, &expected) {
+        return None;
+    }
+
+    let claims: Claims = serde_json::from_slice(&base64url_decode(payload)?).ok()?;
+    let now = Utc::now().timestamp();
+    if claims.exp.is_some_and(|exp| now >= exp) || claims.nbf.is_some_and(|nbf| now < nbf) {
+        return None;
+    }
+    if !config.issuer.is_empty() && claims.iss.as_deref() != Some(config.issuer.as_str()) {
+        return None;
+    }
+    claims.scope.split_whitespace().filter_map(Scope::parse).max()
+}
+
+/// Compare secrets without stopping at the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Decode unpadded base64url.
+fn base64url_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in text.trim_end_matches('=').bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+        acc = acc << 6 | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+// ─────────────────────────────────────────────
+// Middleware
+// ─────────────────────────────────────────────
+
+/// Checks credentials against the API keys, the JWT settings and the
+/// admin token.
+pub struct ApiAuth {
+    /// API key file (`None` = `~/.oxibot/api_keys.json`). Read on every
+    /// request, so new and revoked keys apply without a restart.
+    keys_path: Option<PathBuf>,
+    admin_token: String,
+    jwt: JwtConfig,
+}
+
+impl ApiAuth {
+    pub fn new(keys_path: Option<PathBuf>, admin_token: impl Into<String>, jwt: JwtConfig) -> Self {
+        Self {
+            keys_path,
+            admin_token: admin_token.into(),
+            jwt,
+        }
+    }
+
+    /// Whether any credential could be accepted right now.
+    pub fn is_configured(&self) -> bool {
+        !self.admin_token.is_empty()
+            || !self.jwt.secret.is_empty()
+            || !ApiKeyStore::load(self.keys_path.clone()).keys().is_empty()
+    }
+
+    /// The scope of the request's bearer credential, if it is valid.
+    pub fn authorize(&self, request: &Request) -> Option<Scope> {
+        let token = request.bearer_token().filter(|t| !t.is_empty())?;
+        // Digests have a fixed length, so neither the token's content nor its length leaks
+        if !self.admin_token.is_empty()
+            && constant_time_eq(&Sha256::digest(token), &Sha256::digest(&self.admin_token))
+        {
+            return Some(Scope::Admin);
+        }
+        if token.starts_with(KEY_PREFIX) {
+            return ApiKeyStore::load(self.keys_path.clone()).verify(token).map(|k| k.scope);
+        }
+        validate_jwt(&self.jwt, token)
+    }
+}
+
+/// A handler whose requests under `prefix` need a credential with `scope`.
+pub struct Guarded {
+    prefix: String,
+    scope: Scope,
+    auth: Arc<ApiAuth>,
+    inner: Arc<dyn Handler>,
+}
+
+impl Guarded {
+    pub fn new(prefix: impl Into<String>, scope: Scope, auth: Arc<ApiAuth>, inner: Arc<dyn Handler>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            scope,
+            auth,
+            inner,
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Guarded {
+    async fn handle(&self, request: &Request) -> Option<Response> {
+        if !request.path().starts_with(&self.prefix) {
+            return None;
+        }
+        match self.auth.authorize(request) {
+            None => Some(Response::new("401 Unauthorized", "Missing or invalid credentials.")),
+            Some(scope) if !scope.allows(self.scope) => {
+                Some(Response::new("403 Forbidden", format!("This endpoint needs the {} scope.", self.scope)))
+            }
+            Some(_) => Some(self.inner.handle(request).await.unwrap_or_else(Response::not_found)),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base64url(data: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let mut out = String::new();
+        for chunk in data.chunks(3) {
+            let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+            let triple = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[((triple >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            }
+        }
+        out
+    }
+
+    fn jwt(secret: &str, claims: serde_json::Value) -> String {
+        let head = format!(
+            "{}.{}",
+            base64url(br#"{"alg":"HS256","typ":"JWT"}"#),
+            base64url(claims.to_string().as_bytes())
+        );
+        format!("{head}.{}", base64url(&hmac_sha256(secret.as_bytes(), head.as_bytes())))
+    }
+
+    fn request(path: &str, token: Option<&str>) -> Request {
+        let mut request = Request {
+            method: "GET".into(),
+            target: path.into(),
+            ..Default::default()
+        };
+        if let Some(token) = token {
+            request.headers.insert("authorization".into(), format!("Bearer {token}"));
+        }
+        request
+    }
+
+    struct Hello;
+
+    #[async_trait]
+    impl Handler for Hello {
+        async fn handle(&self, request: &Request) -> Option<Response> {
+            (request.path() == "/admin/hello").then(|| Response::ok("hi"))
+        }
+    }
+
+    #[test]
+    fn test_key_store_create_verify_revoke() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api_keys.json");
+        let mut store = ApiKeyStore::load(Some(path.clone()));
+        let (key, token) = store.create("ci", Scope::Chat);
+        store.save().unwrap();
+
+        assert!(token.starts_with("oxk_"));
+        assert!(!std::fs::read_to_string(&path).unwrap().contains(&token));
+
+        let store = ApiKeyStore::load(Some(path.clone()));
+        assert_eq!(store.verify(&token), Some(&key));
+        assert_eq!(store.verify(&format!("{token}x")), None);
+
+        let mut store = store;
+        assert!(store.revoke(&key.id));
+        assert!(!store.revoke(&key.id));
+        assert_eq!(store.verify(&token), None);
+    }
+
+    #[test]
+    fn test_validate_jwt() {
+        let config = JwtConfig {
+            secret: "shh".into(),
+            issuer: "https://auth.example.com".into(),
+        };
+        let exp = Utc::now().timestamp() + 60;
+        let iss = "https://auth.example.com";
+
+        let token = jwt("shh", serde_json::json!({"iss": iss, "exp": exp, "scope": "read chat"}));
+        assert_eq!(validate_jwt(&config, &token), Some(Scope::Chat));
+        let token = jwt("shh", serde_json::json!({"iss": iss, "scope": "chat admin"}));
+        assert_eq!(validate_jwt(&config, &token), Some(Scope::Admin));
+
+        // Wrong secret, issuer, expired, no scope
+        let token = jwt("other", serde_json::json!({"iss": iss, "scope": "chat"}));
+        assert_eq!(validate_jwt(&config, &token), None);
+        let token = jwt("shh", serde_json::json!({"iss": "https://evil.example.com", "scope": "chat"}));
+        assert_eq!(validate_jwt(&config, &token), None);
+        let token = jwt("shh", serde_json::json!({"iss": iss, "exp": exp - 120, "scope": "chat"}));
+        assert_eq!(validate_jwt(&config, &token), None);
+        let token = jwt("shh", serde_json::json!({"iss": iss}));
+        assert_eq!(validate_jwt(&config, &token), None);
+
+        // No secret configured: JWTs are not accepted
+        let token = jwt("", serde_json::json!({"scope": "admin"}));
+        assert_eq!(validate_jwt(&JwtConfig::default(), &token), None);
+    }
+
+    #[tokio::test]
+    async fn test_guarded_enforces_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api_keys.json");
+        let mut store = ApiKeyStore::load(Some(path.clone()));
+        let (_, chat) = store.create("bot", Scope::Chat);
+        let (_, admin) = store.create("ops", Scope::Admin);
+        store.save().unwrap();
+
+        let auth = Arc::new(ApiAuth::new(Some(path), "s3cret", JwtConfig::default()));
+        let guarded = Guarded::new("/admin/", Scope::Admin, auth, Arc::new(Hello));
+
+        assert_eq!(guarded.handle(&request("/oauth/callback", None)).await, None);
+        let status = |r: Option<Response>| r.unwrap().status;
+        assert_eq!(status(guarded.handle(&request("/admin/hello", None)).await), "401 Unauthorized");
+        assert_eq!(status(guarded.handle(&request("/admin/hello", Some("oxk_nope"))).await), "401 Unauthorized");
+        assert_eq!(status(guarded.handle(&request("/admin/hello", Some(&chat))).await), "403 Forbidden");
+        assert_eq!(status(guarded.handle(&request("/admin/hello", Some(&admin))).await), "200 OK");
+        assert_eq!(status(guarded.handle(&request("/admin/hello", Some("s3cret"))).await), "200 OK");
+        assert_eq!(status(guarded.handle(&request("/admin/hello", Some("s3cre"))).await), "401 Unauthorized");
+        assert_eq!(status(guarded.handle(&request("/admin/other", Some("s3cret"))).await), "404 Not Found");
+    }
+}
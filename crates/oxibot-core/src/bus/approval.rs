@@ -0,0 +1,327 @@
+//! Approvals — ask the user before doing something risky.
+//!
+//! A tool calls [`MessageBus::request_approval`](super::queue::MessageBus::request_approval);
+//! the question goes out as an outbound message carrying [`APPROVAL_ID_KEY`].
+//! Channels with buttons render Allow / Deny and publish the click as an
+//! [`InboundMessage::approval_response`]; elsewhere the user types "yes" or
+//! "no". Answers are taken off the bus as soon as they are published — the
+//! agent loop is still busy with the turn that asked — and never reach the
+//! agent as chat messages.
+//!
+//! Only the sender whose message led to the request, or an admin (see
+//! [`MessageBus::with_admins`](super::queue::MessageBus::with_admins)), can
+//! answer it; in a group chat, other members' replies are ordinary
+//! messages.
+
+use std::sync::Mutex;
+
+use tokio::sync::oneshot;
+
+use super::types::{InboundMessage, OutboundMessage};
+
+/// Metadata key of the approval a message asks for or answers.
+pub const APPROVAL_ID_KEY: &str = "approval_id";
+
+/// Inbound metadata key: the decision picked with a button.
+pub const APPROVAL_DECISION_KEY: &str = "approval_decision";
+
+/// Outbound metadata key: the question without the typing hint, for
+/// channels that render buttons instead.
+pub const APPROVAL_PROMPT_KEY: &str = "approval_prompt";
+
+/// Prefix of button callback data (`approval:<id>:<decision>`).
+const BUTTON_PREFIX: &str = "approval:";
+
+/// Shown under the question on channels without buttons.
+const TYPING_HINT: &str = "Reply \"yes\" to allow or \"no\" to deny.";
+
+// ─────────────────────────────────────────────
+// Decision
+// ─────────────────────────────────────────────
+
+/// The answer to an approval request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Approved,
+    Denied,
+    /// Nobody answered in time.
+    TimedOut,
+}
+
+impl Decision {
+    /// Parse a typed reply ("yes", "no", ...) or a button value. Only
+    /// explicit words approve; a casual "ok" does not.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().trim_end_matches(['.', '!']).to_lowercase().as_str() {
+            "yes" | "allow" | "approve" => Some(Decision::Approved),
+            "no" | "n" | "deny" | "cancel" => Some(Decision::Denied),
+            _ => None,
+        }
+    }
+
+    /// Value used in button data and metadata.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Decision::Approved => "approve",
+            Decision::Denied => "deny",
+            Decision::TimedOut => "timeout",
+        }
+    }
+
+    /// Line appended to the question once it is answered.
+    pub fn label(self) -> &'static str {
+        match self {
+            Decision::Approved => "✅ Allowed",
+            Decision::Denied => "❌ Denied",
+            Decision::TimedOut => "⌛ Timed out",
+        }
+    }
+}
+
+/// Callback data for an Allow or Deny button.
+pub fn button_data(id: &str, decision: Decision) -> String {
+    format!("{BUTTON_PREFIX}{id}:{}", decision.as_str())
+}
+
+/// Parse button callback data back into the approval id and decision.
+pub fn parse_button_data(data: &str) -> Option<(&str, Decision)> {
+    let (id, decision) = data.strip_prefix(BUTTON_PREFIX)?.rsplit_once(':')?;
+    Some((id, Decision::parse(decision)?))
+}
+
+// ─────────────────────────────────────────────
+// Message helpers
+// ─────────────────────────────────────────────
+
+impl OutboundMessage {
+    /// Ask the user to allow or deny `prompt`.
+    pub fn approval_request(
+        channel: impl Into<String>,
+        chat_id: impl Into<String>,
+        id: &str,
+        prompt: &str,
+    ) -> Self {
+        let mut msg = Self::new(channel, chat_id, format!("{prompt}\n\n{TYPING_HINT}"));
+        msg.metadata.insert(APPROVAL_ID_KEY.into(), id.into());
+        msg.metadata.insert(APPROVAL_PROMPT_KEY.into(), prompt.into());
+        msg
+    }
+
+    /// The approval this message asks for, with the bare question.
+    pub fn approval(&self) -> Option<(&str, &str)> {
+        let id = self.metadata.get(APPROVAL_ID_KEY)?;
+        let prompt = self.metadata.get(APPROVAL_PROMPT_KEY).unwrap_or(&self.content);
+        Some((id, prompt))
+    }
+}
+
+impl InboundMessage {
+    /// A button click answering approval `id`.
+    pub fn approval_response(
+        channel: impl Into<String>,
+        sender_id: impl Into<String>,
+        chat_id: impl Into<String>,
+        id: &str,
+        decision: Decision,
+    ) -> Self {
+        let mut msg = Self::new(channel, sender_id, chat_id, decision.as_str());
+        msg.metadata.insert(APPROVAL_ID_KEY.into(), id.into());
+        msg.metadata.insert(APPROVAL_DECISION_KEY.into(), decision.as_str().into());
+        msg
+    }
+}
+
+// ─────────────────────────────────────────────
+// Approvals
+// ─────────────────────────────────────────────
+
+/// A question waiting for an answer.
+struct Pending {
+    id: String,
+    channel: String,
+    chat_id: String,
+    /// Sender whose message led to the request (`None`: admins only).
+    requester: Option<String>,
+    tx: oneshot::Sender<Decision>,
+}
+
+impl Pending {
+    fn is_in(&self, msg: &InboundMessage) -> bool {
+        self.channel == msg.channel && self.chat_id == msg.chat_id
+    }
+
+    /// Whether the sender of `msg` may answer: the requester or an admin.
+    fn may_answer(&self, msg: &InboundMessage, admins: &[String]) -> bool {
+        self.requester.as_deref().is_some_and(|r| !r.is_empty() && r == msg.sender_id)
+            || admins.iter().any(|admin| msg.is_from(admin))
+    }
+}
+
+/// Approval requests waiting for an answer, oldest first.
+#[derive(Default)]
+pub(crate) struct Approvals {
+    pending: Mutex<Vec<Pending>>,
+    /// Senders who may answer any request (`commands.admins` syntax).
+    pub(crate) admins: Vec<String>,
+}
+
+impl Approvals {
+    /// Register a new request in a chat, answerable by `requester`.
+    pub(crate) fn register(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        requester: Option<&str>,
+    ) -> (String, oneshot::Receiver<Decision>) {
+        let (tx, rx) = oneshot::channel();
+        let id = format!("{:08x}", rand::random::<u32>());
+        self.pending.lock().unwrap().push(Pending {
+            id: id.clone(),
+            channel: channel.to_string(),
+            chat_id: chat_id.to_string(),
+            requester: requester.map(str::to_string),
+            tx,
+        });
+        (id, rx)
+    }
+
+    /// Forget a request nobody answered.
+    pub(crate) fn cancel(&self, id: &str) {
+        self.pending.lock().unwrap().retain(|p| p.id != id);
+    }
+
+    /// Answer a pending request from `msg`. Returns `true` if the message
+    /// was an answer (including clicks on requests that already expired)
+    /// and should not reach the agent.
+    pub(crate) fn resolve(&self, msg: &InboundMessage) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        // Drop requests whose asker gave up (e.g. its turn was stopped)
+        pending.retain(|p| !p.tx.is_closed());
+        let (index, decision) = match msg.metadata.get(APPROVAL_ID_KEY) {
+            // A button: only the chat that was asked can answer, and only
+            // the requester or an admin there
+            Some(id) => {
+                let index = pending
+                    .iter()
+                    .position(|p| p.id == *id && p.is_in(msg) && p.may_answer(msg, &self.admins));
+                let decision = msg
+                    .metadata
+                    .get(APPROVAL_DECISION_KEY)
+                    .and_then(|d| Decision::parse(d));
+                match (index, decision) {
+                    (Some(index), Some(decision)) => (index, decision),
+                    _ => return true,
+                }
+            }
+            // A typed reply answers the oldest question in the chat that
+            // the sender may answer
+            None => {
+                let Some(decision) = Decision::parse(&msg.content) else {
+                    return false;
+                };
+                match pending.iter().position(|p| p.is_in(msg) && p.may_answer(msg, &self.admins)) {
+                    Some(index) => (index, decision),
+                    None => return false,
+                }
+            }
+        };
+        let _ = pending.remove(index).tx.send(decision);
+        true
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decision() {
+        assert_eq!(Decision::parse("Yes!"), Some(Decision::Approved));
+        assert_eq!(Decision::parse(" n "), Some(Decision::Denied));
+        assert_eq!(Decision::parse("approve"), Some(Decision::Approved));
+        assert_eq!(Decision::parse("yes please do it"), None);
+        assert_eq!(Decision::parse("ok"), None);
+        assert_eq!(Decision::parse("y"), None);
+
+        let data = button_data("ab12", Decision::Denied);
+        assert_eq!(data, "approval:ab12:deny");
+        assert_eq!(parse_button_data(&data), Some(("ab12", Decision::Denied)));
+        assert_eq!(parse_button_data("other:ab12:deny"), None);
+        assert_eq!(parse_button_data("approval:ab12:maybe"), None);
+    }
+
+    #[test]
+    fn test_request_message() {
+        let msg = OutboundMessage::approval_request("telegram", "42", "ab12", "Run `rm -rf build/`?");
+        assert!(msg.content.starts_with("Run `rm -rf build/`?\n\n"));
+        assert_eq!(msg.approval(), Some(("ab12", "Run `rm -rf build/`?")));
+        assert_eq!(OutboundMessage::new("telegram", "42", "hi").approval(), None);
+    }
+
+    #[tokio::test]
+    async fn test_typed_reply_answers_oldest_in_chat() {
+        let approvals = Approvals::default();
+        let (first, rx1) = approvals.register("telegram", "42", Some("u"));
+        let (_, rx2) = approvals.register("telegram", "42", Some("u"));
+        let (_, _rx3) = approvals.register("telegram", "7", Some("u"));
+
+        assert!(!approvals.resolve(&InboundMessage::new("telegram", "u", "42", "what is this?")));
+        assert!(!approvals.resolve(&InboundMessage::new("slack", "u", "42", "yes")));
+        assert!(approvals.resolve(&InboundMessage::new("telegram", "u", "42", "yes")));
+        assert_eq!(rx1.await.unwrap(), Decision::Approved);
+        assert!(approvals.resolve(&InboundMessage::new("telegram", "u", "42", "no")));
+        assert_eq!(rx2.await.unwrap(), Decision::Denied);
+        assert!(!approvals.resolve(&InboundMessage::new("telegram", "u", "42", "yes")));
+
+        // Late clicks are swallowed
+        let late = InboundMessage::approval_response("telegram", "u", "42", &first, Decision::Denied);
+        assert!(approvals.resolve(&late));
+    }
+
+    #[tokio::test]
+    async fn test_button_answers_by_id() {
+        let approvals = Approvals::default();
+        let (_, rx1) = approvals.register("discord", "c1", Some("u"));
+        let (second, rx2) = approvals.register("discord", "c1", Some("u"));
+
+        // A click from another chat cannot answer it
+        let elsewhere = InboundMessage::approval_response("discord", "u", "c2", &second, Decision::Approved);
+        assert!(approvals.resolve(&elsewhere));
+
+        let click = InboundMessage::approval_response("discord", "u", "c1", &second, Decision::Approved);
+        assert!(approvals.resolve(&click));
+        assert_eq!(rx2.await.unwrap(), Decision::Approved);
+
+        let first = approvals.pending.lock().unwrap()[0].id.clone();
+        approvals.cancel(&first);
+        assert!(rx1.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_requester_or_admin_answers() {
+        let approvals = Approvals {
+            admins: vec!["telegram:1".into()],
+            ..Default::default()
+        };
+        let (id, rx) = approvals.register("telegram", "group", Some("2|bob"));
+        let (_, admin_rx) = approvals.register("telegram", "group", None);
+
+        // Another group member's "yes" is an ordinary message, their click is ignored
+        assert!(!approvals.resolve(&InboundMessage::new("telegram", "3|eve", "group", "yes")));
+        let click = InboundMessage::approval_response("telegram", "3|eve", "group", &id, Decision::Approved);
+        assert!(approvals.resolve(&click));
+        assert_eq!(approvals.pending.lock().unwrap().len(), 2);
+
+        assert!(approvals.resolve(&InboundMessage::new("telegram", "2|bob", "group", "yes")));
+        assert_eq!(rx.await.unwrap(), Decision::Approved);
+
+        // Requests without a requester wait for an admin
+        assert!(!approvals.resolve(&InboundMessage::new("telegram", "2|bob", "group", "no")));
+        assert!(approvals.resolve(&InboundMessage::new("telegram", "1|ann", "group", "no")));
+        assert_eq!(admin_rx.await.unwrap(), Decision::Denied);
+    }
+}
@@ -0,0 +1,150 @@
+//! Turn cancellation — let the user stop a reply that is still running.
+//!
+//! The agent registers each turn with
+//! [`MessageBus::start_turn`](super::queue::MessageBus::start_turn). When the
+//! user sends "stop" (or "cancel", "/stop", ...) to the same session while it
+//! runs, the message is taken off the bus in `publish_inbound` — the agent
+//! loop is still busy with that turn — and the turn's [`RunningTurn`] is
+//! signalled instead. The agent then wraps up and replies with what it did.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+/// Messages that stop the running turn.
+const STOP_WORDS: &[&str] = &["stop", "cancel", "abort", "/stop", "/cancel"];
+
+/// Whether `text` asks to stop the running turn.
+pub fn is_stop_request(text: &str) -> bool {
+    let text = text.trim().trim_end_matches(['.', '!']).to_lowercase();
+    // Telegram groups address commands as `/stop@botname`
+    let text = text.split('@').next().unwrap_or_default();
+    STOP_WORDS.contains(&text)
+}
+
+/// Turns in progress, by session key.
+#[derive(Default)]
+pub(crate) struct Turns {
+    running: Mutex<HashMap<String, (u64, watch::Sender<bool>)>>,
+    next_id: AtomicU64,
+}
+
+impl Turns {
+    /// Register a turn for `session_key`, replacing any earlier one.
+    pub(crate) fn start(self: &Arc<Self>, session_key: &str) -> RunningTurn {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = watch::channel(false);
+        self.running
+            .lock()
+            .unwrap()
+            .insert(session_key.to_string(), (id, tx));
+        RunningTurn {
+            session_key: session_key.to_string(),
+            id,
+            rx,
+            turns: self.clone(),
+        }
+    }
+
+    /// Signal the turn running for `session_key`. Returns `false` if none is.
+    pub(crate) fn cancel(&self, session_key: &str) -> bool {
+        match self.running.lock().unwrap().get(session_key) {
+            Some((_, tx)) => {
+                tx.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A turn in progress. Unregisters itself when dropped.
+pub struct RunningTurn {
+    session_key: String,
+    id: u64,
+    rx: watch::Receiver<bool>,
+    turns: Arc<Turns>,
+}
+
+impl RunningTurn {
+    /// The user asked to stop.
+    pub fn is_cancelled(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Resolves once the user asks to stop.
+    pub async fn cancelled(&self) {
+        let mut rx = self.rx.clone();
+        if rx.wait_for(|stop| *stop).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Run `fut` unless the user asks to stop first (`None`). The future
+    /// is dropped on cancellation, so it must be safe to abandon.
+    pub async fn until_cancelled<F: Future>(&self, fut: F) -> Option<F::Output> {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => None,
+            output = fut => Some(output),
+        }
+    }
+}
+
+impl Drop for RunningTurn {
+    fn drop(&mut self) {
+        let mut running = self.turns.running.lock().unwrap();
+        if running.get(&self.session_key).is_some_and(|(id, _)| *id == self.id) {
+            running.remove(&self.session_key);
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_words() {
+        assert!(is_stop_request("stop"));
+        assert!(is_stop_request(" Cancel! "));
+        assert!(is_stop_request("/stop@oxibot"));
+        assert!(!is_stop_request("stop the server on port 80"));
+        assert!(!is_stop_request("don't stop"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_turn() {
+        let turns = Arc::new(Turns::default());
+        assert!(!turns.cancel("telegram:42"));
+
+        let turn = turns.start("telegram:42");
+        assert!(!turn.is_cancelled());
+        assert_eq!(turn.until_cancelled(async { 1 }).await, Some(1));
+
+        assert!(!turns.cancel("telegram:7"));
+        assert!(turns.cancel("telegram:42"));
+        assert!(turn.is_cancelled());
+        assert_eq!(turn.until_cancelled(std::future::pending::<()>()).await, None);
+
+        drop(turn);
+        assert!(!turns.cancel("telegram:42"));
+    }
+
+    #[test]
+    fn test_old_turn_keeps_newer_registration() {
+        let turns = Arc::new(Turns::default());
+        let old = turns.start("slack:C1");
+        let new = turns.start("slack:C1");
+        drop(old);
+        assert!(turns.cancel("slack:C1"));
+        assert!(new.is_cancelled());
+    }
+}
@@ -0,0 +1,227 @@
+//! Chat commands — `/reset`, `/status` and friends, shared by all channels.
+//!
+//! A [`CommandRouter`] recognizes messages starting with the configured
+//! prefix (`/` by default; Telegram's `/cmd@botname` form included), checks
+//! that the sender may use the command, and runs its handler. Messages that
+//! name no registered command — including plain text and paths like
+//! `/etc/hosts` — are left for the agent.
+//!
+//! Handlers get the state they were registered for (`S`, e.g. the agent
+//! loop) and the parsed [`CommandCall`], and return the reply text.
+
+use crate::bus::types::InboundMessage;
+use crate::config::schema::CommandsConfig;
+
+/// Who may run a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    /// Anyone allowed to talk to the bot.
+    Anyone,
+    /// Only senders in `commands.admins`.
+    Admin,
+}
+
+/// A parsed command invocation.
+#[derive(Clone, Copy, Debug)]
+pub struct CommandCall<'a> {
+    /// Command name, without prefix or `@botname`.
+    pub name: &'a str,
+    /// Everything after the command, trimmed.
+    pub args: &'a str,
+    /// The message that carried the command.
+    pub msg: &'a InboundMessage,
+}
+
+type Handler<S> = Box<dyn Fn(&S, &CommandCall<'_>) -> String + Send + Sync>;
+
+struct Command<S> {
+    name: String,
+    usage: String,
+    description: String,
+    permission: Permission,
+    handler: Handler<S>,
+}
+
+/// Dispatches chat commands to their handlers.
+pub struct CommandRouter<S> {
+    config: CommandsConfig,
+    commands: Vec<Command<S>>,
+}
+
+impl<S> CommandRouter<S> {
+    /// Create a router with no commands.
+    pub fn new(config: CommandsConfig) -> Self {
+        Self { config, commands: Vec::new() }
+    }
+
+    /// Replace the prefix and permission settings, keeping the commands.
+    pub fn configure(&mut self, config: CommandsConfig) {
+        self.config = config;
+    }
+
+    /// The command prefix (e.g. `"/"`).
+    pub fn prefix(&self) -> &str {
+        &self.config.prefix
+    }
+
+    /// Register `name` (without prefix). `usage` describes the arguments
+    /// for the help text (may be empty). A command registered twice is
+    /// replaced.
+    pub fn register(
+        &mut self,
+        name: &str,
+        usage: &str,
+        description: &str,
+        permission: Permission,
+        handler: impl Fn(&S, &CommandCall<'_>) -> String + Send + Sync + 'static,
+    ) {
+        self.commands.retain(|c| c.name != name);
+        self.commands.push(Command {
+            name: name.to_string(),
+            usage: usage.to_string(),
+            description: description.to_string(),
+            permission,
+            handler: Box::new(handler),
+        });
+    }
+
+    /// Split `text` into `(name, args)` when it starts with the prefix.
+    /// The name may still be unknown.
+    pub fn parse<'a>(&self, text: &'a str) -> Option<(&'a str, &'a str)> {
+        let text = text.trim();
+        let rest = text.strip_prefix(self.config.prefix.as_str()).filter(|_| !self.config.prefix.is_empty())?;
+        let (word, args) = match rest.split_once(char::is_whitespace) {
+            Some((word, args)) => (word, args.trim()),
+            None => (rest, ""),
+        };
+        // Telegram groups address commands as `/cmd@botname`
+        let name = word.split('@').next().unwrap_or(word);
+        (!name.is_empty()).then_some((name, args))
+    }
+
+    fn find(&self, name: &str) -> Option<&Command<S>> {
+        if self.config.disabled.iter().any(|d| d.eq_ignore_ascii_case(name)) {
+            return None;
+        }
+        self.commands.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    fn permission(&self, command: &Command<S>) -> Permission {
+        if self.config.admin_only.iter().any(|n| n.eq_ignore_ascii_case(&command.name)) {
+            Permission::Admin
+        } else {
+            command.permission
+        }
+    }
+
+    /// Whether the sender of `msg` is an admin. Telegram senders
+    /// ("42|alice") match on the id or the username.
+    pub fn is_admin(&self, msg: &InboundMessage) -> bool {
+        msg.channel == "cli" || self.config.admins.iter().any(|admin| msg.is_from(admin))
+    }
+
+    /// Whether `msg` is a command this router handles.
+    pub fn handles(&self, msg: &InboundMessage) -> bool {
+        self.parse(&msg.content).is_some_and(|(name, _)| self.find(name).is_some())
+    }
+
+    /// Run the command in `msg` and return its reply, or `None` when `msg`
+    /// is not one of the registered commands (so the agent should handle
+    /// it).
+    pub fn dispatch(&self, state: &S, msg: &InboundMessage) -> Option<String> {
+        let (name, args) = self.parse(&msg.content)?;
+        let command = self.find(name)?;
+        if self.permission(command) == Permission::Admin && !self.is_admin(msg) {
+            return Some(format!("⛔ {}{} is only available to admins.", self.config.prefix, command.name));
+        }
+        let call = CommandCall { name: &command.name, args, msg };
+        Some((command.handler)(state, &call))
+    }
+
+    /// One line per enabled command (`/pin <note> — Keep a fact in
+    /// context`), for help texts. Admin-only commands are marked.
+    pub fn help(&self) -> String {
+        self.commands
+            .iter()
+            .filter(|c| self.find(&c.name).is_some())
+            .map(|c| {
+                let usage = if c.usage.is_empty() { String::new() } else { format!(" {}", c.usage) };
+                let admin = if self.permission(c) == Permission::Admin { " (admin)" } else { "" };
+                format!("{}{}{usage} — {}{admin}", self.config.prefix, c.name, c.description)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(config: CommandsConfig) -> CommandRouter<String> {
+        let mut router = CommandRouter::new(config);
+        router.register("echo", "<text>", "Repeat text", Permission::Anyone, |_, call| call.args.to_string());
+        router.register("whoami", "", "Show the state", Permission::Anyone, |state: &String, _| state.clone());
+        router.register("shutdown", "", "Stop the bot", Permission::Admin, |_, _| "bye".into());
+        router
+    }
+
+    fn msg(channel: &str, sender: &str, text: &str) -> InboundMessage {
+        InboundMessage::new(channel, sender, "chat", text)
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let router = router(CommandsConfig::default());
+        let state = "bot state".to_string();
+
+        assert_eq!(router.dispatch(&state, &msg("telegram", "1", "/echo  hi there ")), Some("hi there".into()));
+        assert_eq!(router.dispatch(&state, &msg("telegram", "1", "/WHOAMI@oxibot")), Some("bot state".into()));
+        // Not commands: fall through to the agent
+        assert_eq!(router.dispatch(&state, &msg("telegram", "1", "echo hi")), None);
+        assert_eq!(router.dispatch(&state, &msg("telegram", "1", "/etc/hosts is broken")), None);
+        assert_eq!(router.dispatch(&state, &msg("telegram", "1", "/")), None);
+        assert!(router.handles(&msg("slack", "U1", "/echo")));
+        assert!(!router.handles(&msg("slack", "U1", "/unknown")));
+    }
+
+    #[test]
+    fn test_permissions_and_config() {
+        let config = CommandsConfig {
+            prefix: "!".into(),
+            admins: vec!["telegram:42".into(), "U7".into()],
+            admin_only: vec!["echo".into()],
+            disabled: vec!["whoami".into()],
+        };
+        let router = router(config);
+        let state = String::new();
+
+        assert_eq!(router.dispatch(&state, &msg("telegram", "1", "/echo hi")), None);
+        let denied = router.dispatch(&state, &msg("telegram", "1", "!echo hi")).unwrap();
+        assert_eq!(denied, "⛔ !echo is only available to admins.");
+        assert_eq!(router.dispatch(&state, &msg("telegram", "42", "!echo hi")), Some("hi".into()));
+        assert_eq!(router.dispatch(&state, &msg("slack", "U7", "!shutdown")), Some("bye".into()));
+        assert_eq!(router.dispatch(&state, &msg("slack", "42", "!shutdown")).unwrap(), "⛔ !shutdown is only available to admins.");
+        assert_eq!(router.dispatch(&state, &msg("cli", "user", "!shutdown")), Some("bye".into()));
+        assert_eq!(router.dispatch(&state, &msg("telegram", "42", "!whoami")), None);
+
+        // Telegram senders are "<id>|<username>"
+        assert_eq!(router.dispatch(&state, &msg("telegram", "42|alice", "!echo hi")), Some("hi".into()));
+        assert!(router.dispatch(&state, &msg("telegram", "420|bob", "!echo hi")).unwrap().starts_with('⛔'));
+        let by_name = self::router(CommandsConfig {
+            admins: vec!["telegram:alice".into()],
+            ..Default::default()
+        });
+        assert!(by_name.is_admin(&msg("telegram", "42|alice", "")));
+        assert!(!by_name.is_admin(&msg("slack", "alice", "")));
+
+        assert_eq!(
+            router.help(),
+            "!echo <text> — Repeat text (admin)\n!shutdown — Stop the bot (admin)"
+        );
+    }
+}
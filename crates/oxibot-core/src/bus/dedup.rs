@@ -0,0 +1,125 @@
+//! Inbound deduplication — each platform message is handled at most once.
+//!
+//! Reconnects can deliver a message twice: a Discord session resume replays
+//! events, overlapping Telegram `getUpdates` calls return the same update.
+//! The bus remembers the `message_id` of recent inbound messages per chat
+//! and drops repeats. Messages without an id are never dropped.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::types::InboundMessage;
+
+/// Message ids remembered at most.
+pub const DEDUP_CAPACITY: usize = 4096;
+
+/// How long a message id is remembered.
+pub const DEDUP_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Recently seen message ids, oldest first.
+pub(crate) struct Dedup {
+    capacity: usize,
+    ttl: Duration,
+    seen: Mutex<Seen>,
+}
+
+#[derive(Default)]
+struct Seen {
+    keys: HashSet<String>,
+    order: VecDeque<(Instant, String)>,
+}
+
+impl Seen {
+    fn pop_oldest(&mut self) {
+        if let Some((_, key)) = self.order.pop_front() {
+            self.keys.remove(&key);
+        }
+    }
+}
+
+impl Default for Dedup {
+    fn default() -> Self {
+        Self::new(DEDUP_CAPACITY, DEDUP_TTL)
+    }
+}
+
+impl Dedup {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            seen: Mutex::default(),
+        }
+    }
+
+    /// Record `msg`; `true` if the same message was seen recently.
+    pub(crate) fn is_duplicate(&self, msg: &InboundMessage) -> bool {
+        let Some(id) = msg.message_id().filter(|id| !id.is_empty()) else {
+            return false;
+        };
+        // Telegram ids are only unique within a chat
+        let key = format!("{}:{}:{id}", msg.channel, msg.chat_id);
+        let now = Instant::now();
+
+        let mut seen = self.seen.lock().unwrap();
+        while seen.order.front().is_some_and(|(at, _)| now.duration_since(*at) >= self.ttl) {
+            seen.pop_oldest();
+        }
+        if seen.keys.contains(&key) {
+            return true;
+        }
+        while seen.order.len() >= self.capacity.max(1) {
+            seen.pop_oldest();
+        }
+        seen.keys.insert(key.clone());
+        seen.order.push_back((now, key));
+        false
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::types::MESSAGE_ID_KEY;
+
+    fn msg(channel: &str, chat_id: &str, id: Option<&str>) -> InboundMessage {
+        let mut msg = InboundMessage::new(channel, "u", chat_id, "hi");
+        if let Some(id) = id {
+            msg.metadata.insert(MESSAGE_ID_KEY.into(), id.into());
+        }
+        msg
+    }
+
+    #[test]
+    fn test_repeats_are_dropped() {
+        let dedup = Dedup::default();
+        assert!(!dedup.is_duplicate(&msg("telegram", "42", Some("10"))));
+        assert!(dedup.is_duplicate(&msg("telegram", "42", Some("10"))));
+        // Same id in another chat or channel is a different message
+        assert!(!dedup.is_duplicate(&msg("telegram", "7", Some("10"))));
+        assert!(!dedup.is_duplicate(&msg("discord", "42", Some("10"))));
+        // No id, no dedup
+        assert!(!dedup.is_duplicate(&msg("cli", "direct", None)));
+        assert!(!dedup.is_duplicate(&msg("cli", "direct", None)));
+    }
+
+    #[test]
+    fn test_ids_expire_and_are_evicted() {
+        let dedup = Dedup::new(2, Duration::from_secs(60));
+        for id in ["1", "2", "3"] {
+            assert!(!dedup.is_duplicate(&msg("slack", "C1", Some(id))));
+        }
+        // "1" was evicted to stay within capacity
+        assert!(!dedup.is_duplicate(&msg("slack", "C1", Some("1"))));
+        assert!(dedup.is_duplicate(&msg("slack", "C1", Some("3"))));
+
+        let dedup = Dedup::new(10, Duration::ZERO);
+        assert!(!dedup.is_duplicate(&msg("slack", "C1", Some("1"))));
+        assert!(!dedup.is_duplicate(&msg("slack", "C1", Some("1"))));
+    }
+}
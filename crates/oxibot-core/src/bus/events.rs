@@ -0,0 +1,157 @@
+//! Bus observers — read-only subscriptions to everything crossing the bus.
+//!
+//! [`MessageBus::subscribe`](super::queue::MessageBus::subscribe) hands out
+//! a [`Subscription`] that sees a copy of every event on the chosen
+//! [`Topic`]s without taking messages away from the agent loop or the
+//! channel manager. Any number of subscribers can attach; each gets its own
+//! buffer, and one that falls too far behind skips the oldest events rather
+//! than slowing the bus down.
+//!
+//! Messages are reported when they are taken off the bus, so inbound events
+//! show what the agent actually receives (after inbound filters) and
+//! outbound events include messages sent through cloned senders.
+//!
+//! The agent loop reports each turn's progress on [`Topic::Agent`] (LLM
+//! calls, tool calls, the final reply), so a REPL or UI can show what is
+//! happening between the prompt and the answer.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use super::types::{InboundMessage, OutboundMessage};
+
+/// Kind of bus event a subscriber can ask for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Topic {
+    /// Messages from channels to the agent.
+    Inbound,
+    /// Replies, progress updates and acknowledgments to channels.
+    Outbound,
+    /// Channel lifecycle and dropped messages.
+    Status,
+    /// Progress of agent turns.
+    Agent,
+}
+
+/// Something that happened to a channel or a message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatusEvent {
+    /// A channel was (re)started.
+    ChannelStarted { channel: String },
+    /// A channel stopped on purpose.
+    ChannelStopped { channel: String },
+    /// A channel failed; `failures` counts consecutive failures.
+    ChannelFailed {
+        channel: String,
+        reason: String,
+        failures: u32,
+    },
+    /// An inbound filter dropped a message.
+    InboundDropped {
+        channel: String,
+        chat_id: String,
+        filter: String,
+    },
+}
+
+/// A step of an agent turn. Serializes as `{"type": "tool_started", ...}`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentEvent {
+    /// The agent asked the model for the next step.
+    LlmCallStarted {
+        session_key: String,
+        model: String,
+        iteration: usize,
+    },
+    /// A tool call started; `args` as the model sent them.
+    ToolStarted {
+        session_key: String,
+        name: String,
+        args: Value,
+    },
+    /// A tool call returned (`ok` is false for malformed calls).
+    ToolFinished {
+        session_key: String,
+        name: String,
+        duration_ms: u64,
+        ok: bool,
+    },
+    /// The turn is over; `content` is the reply.
+    FinalResponse { session_key: String, content: String },
+}
+
+impl AgentEvent {
+    /// The session whose turn this event belongs to.
+    pub fn session_key(&self) -> &str {
+        match self {
+            AgentEvent::LlmCallStarted { session_key, .. }
+            | AgentEvent::ToolStarted { session_key, .. }
+            | AgentEvent::ToolFinished { session_key, .. }
+            | AgentEvent::FinalResponse { session_key, .. } => session_key,
+        }
+    }
+}
+
+/// An event seen by subscribers.
+#[derive(Clone, Debug)]
+pub enum BusEvent {
+    Inbound(InboundMessage),
+    Outbound(OutboundMessage),
+    Status(StatusEvent),
+    Agent(AgentEvent),
+}
+
+impl BusEvent {
+    /// The topic this event belongs to.
+    pub fn topic(&self) -> Topic {
+        match self {
+            BusEvent::Inbound(_) => Topic::Inbound,
+            BusEvent::Outbound(_) => Topic::Outbound,
+            BusEvent::Status(_) => Topic::Status,
+            BusEvent::Agent(_) => Topic::Agent,
+        }
+    }
+}
+
+/// A stream of bus events on some topics.
+///
+/// Dropping it unsubscribes.
+pub struct Subscription {
+    rx: broadcast::Receiver<Arc<BusEvent>>,
+    /// Topics to deliver (empty = all).
+    topics: Vec<Topic>,
+}
+
+impl Subscription {
+    pub(crate) fn new(rx: broadcast::Receiver<Arc<BusEvent>>, topics: &[Topic]) -> Self {
+        Self {
+            rx,
+            topics: topics.to_vec(),
+        }
+    }
+
+    /// Wait for the next event on the subscribed topics.
+    ///
+    /// Returns `None` once the bus is gone.
+    pub async fn recv(&mut self) -> Option<Arc<BusEvent>> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) if self.wants(event.topic()) => return Some(event),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "bus subscriber fell behind, events skipped");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn wants(&self, topic: Topic) -> bool {
+        self.topics.is_empty() || self.topics.contains(&topic)
+    }
+}
@@ -0,0 +1,20 @@
+//! Inbound middleware — rewrite or drop messages before the agent sees them.
+//!
+//! Filters are attached to the [`MessageBus`](super::queue::MessageBus) with
+//! `with_inbound_filter` and run in order on every message a consumer takes
+//! off the inbound queue. Internal `system` messages (subagent results) are
+//! never filtered.
+
+use async_trait::async_trait;
+
+use super::types::InboundMessage;
+
+/// A step in the inbound middleware stack.
+#[async_trait]
+pub trait InboundFilter: Send + Sync {
+    /// Short name used in logs (e.g. `"spam"`).
+    fn name(&self) -> &str;
+
+    /// Transform the message, or return `None` to drop it.
+    async fn apply(&self, msg: InboundMessage) -> Option<InboundMessage>;
+}
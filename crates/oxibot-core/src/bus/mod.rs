@@ -1,2 +1,51 @@
+//! Message bus — how channels, the agent loop and observers talk.
+//!
+//! - Channels publish user messages with `publish_inbound`
+//! - The agent loop takes them with `consume_inbound` (after any
+//!   [`InboundFilter`]s) and publishes replies with `publish_outbound`
+//! - The channel manager takes replies with `consume_outbound` and sends
+//!   them, reporting channel starts and failures with `publish_status`
+//!
+//! Tools that need the user's go-ahead call `request_approval`; see
+//! [`approval`]. The agent registers each turn with `start_turn` so the
+//! user can stop it; see [`cancel`]. Messages delivered twice by a channel
+//! are dropped on publish; see [`dedup`]. Chat commands like `/reset` are
+//! parsed and permission-checked by a [`CommandRouter`]; see [`commands`].
+//! Messages a channel failed to send wait in the [`Outbox`] until the
+//! operator retries or drops them; see [`outbox`].
+//!
+//! Consuming is destructive, so extensions that only watch traffic
+//! subscribe instead:
+//!
+//! ```no_run
+//! use oxibot_core::bus::{BusEvent, MessageBus, Topic};
+//!
+//! # async fn archive(bus: &MessageBus) {
+//! let mut events = bus.subscribe(&[Topic::Inbound, Topic::Outbound]);
+//! while let Some(event) = events.recv().await {
+//!     match event.as_ref() {
+//!         BusEvent::Inbound(msg) => println!("{} <- {}", msg.session_key(), msg.content),
+//!         BusEvent::Outbound(msg) => println!("{}:{} -> {}", msg.channel, msg.chat_id, msg.content),
+//!         BusEvent::Status(_) | BusEvent::Agent(_) => {}
+//!     }
+//! }
+//! # }
+//! ```
+
 pub mod types;
 pub mod queue;
+pub mod filter;
+pub mod events;
+pub mod approval;
+pub mod cancel;
+pub mod dedup;
+pub mod commands;
+pub mod outbox;
+
+pub use approval::Decision;
+pub use cancel::RunningTurn;
+pub use commands::{CommandRouter, Permission};
+pub use events::{AgentEvent, BusEvent, StatusEvent, Subscription, Topic};
+pub use filter::InboundFilter;
+pub use outbox::Outbox;
+pub use queue::MessageBus;
@@ -0,0 +1,207 @@
+//! Outbox — outbound messages a channel failed to send.
+//!
+//! When a send fails (bad email recipient, expired token, unknown channel),
+//! the channel manager records the message here instead of only logging it.
+//! Each entry is a JSON file in `~/.oxibot/outbox/<id>.json`, so the
+//! operator can inspect them with `oxibot channels outbox list`, fix the
+//! config, and mark them for retry (the gateway re-sends them on its next
+//! poll) or drop them. Failed entries of a channel that comes back are
+//! also delivered by the gateway on its own, up to `maxAttempts`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::bus::types::OutboundMessage;
+use crate::error::correlation_id;
+
+/// Where an entry stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutboxStatus {
+    /// Sending failed; waiting for the operator.
+    Failed,
+    /// Marked for retry; the gateway re-sends it on its next poll.
+    Retry,
+}
+
+/// An undelivered message and why it failed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEntry {
+    /// Short hex id (`3f9a0c1e`).
+    pub id: String,
+    /// The message as it was published.
+    pub message: OutboundMessage,
+    /// Error of the last attempt.
+    pub error: String,
+    /// Send attempts so far.
+    pub attempts: u32,
+    pub status: OutboxStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Directory of undelivered messages.
+#[derive(Clone, Debug)]
+pub struct Outbox {
+    dir: PathBuf,
+}
+
+impl Outbox {
+    /// Create an outbox in `dir` (created on first write).
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            bail!("invalid outbox id '{id}'");
+        }
+        Ok(self.dir.join(format!("{id}.json")))
+    }
+
+    fn save(&self, entry: &OutboxEntry) -> Result<()> {
+        let path = self.path(&entry.id)?;
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let json = serde_json::to_string_pretty(entry)?;
+        std::fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Record `msg`, which failed to send with `error`.
+    pub fn add(&self, msg: &OutboundMessage, error: &str) -> Result<OutboxEntry> {
+        let now = Utc::now();
+        let entry = OutboxEntry {
+            id: correlation_id(),
+            message: msg.clone(),
+            error: error.to_string(),
+            attempts: 1,
+            status: OutboxStatus::Failed,
+            created_at: now,
+            updated_at: now,
+        };
+        self.save(&entry)?;
+        Ok(entry)
+    }
+
+    /// Put back an entry whose retry failed with `error`.
+    pub fn fail(&self, mut entry: OutboxEntry, error: &str) -> Result<OutboxEntry> {
+        entry.error = error.to_string();
+        entry.attempts += 1;
+        entry.status = OutboxStatus::Failed;
+        entry.updated_at = Utc::now();
+        self.save(&entry)?;
+        Ok(entry)
+    }
+
+    /// All entries, oldest first. Unreadable files are skipped.
+    pub fn list(&self) -> Vec<OutboxEntry> {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<OutboxEntry> = dir
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let text = std::fs::read_to_string(&path).ok()?;
+                serde_json::from_str(&text)
+                    .map_err(|e| warn!(file = %path.display(), error = %e, "skipping invalid outbox entry"))
+                    .ok()
+            })
+            .collect();
+        entries.sort_by_key(|e| e.created_at);
+        entries
+    }
+
+    /// The entry with `id`.
+    pub fn get(&self, id: &str) -> Result<OutboxEntry> {
+        let path = self.path(id)?;
+        let text = std::fs::read_to_string(&path).with_context(|| format!("no outbox entry '{id}'"))?;
+        serde_json::from_str(&text).with_context(|| format!("invalid outbox entry {}", path.display()))
+    }
+
+    /// Mark the entry with `id` for retry.
+    pub fn retry(&self, id: &str) -> Result<OutboxEntry> {
+        let mut entry = self.get(id)?;
+        entry.status = OutboxStatus::Retry;
+        entry.updated_at = Utc::now();
+        self.save(&entry)?;
+        Ok(entry)
+    }
+
+    /// Delete the entry with `id` without sending it.
+    pub fn remove(&self, id: &str) -> Result<OutboxEntry> {
+        let entry = self.get(id)?;
+        let path = self.path(id)?;
+        std::fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+        Ok(entry)
+    }
+
+    /// Remove and return the entries marked for retry, oldest first.
+    pub fn take_retries(&self) -> Vec<OutboxEntry> {
+        self.list()
+            .into_iter()
+            .filter(|e| e.status == OutboxStatus::Retry)
+            .filter(|e| match self.path(&e.id).and_then(|p| Ok(std::fs::remove_file(p)?)) {
+                Ok(()) => true,
+                Err(err) => {
+                    warn!(id = %e.id, error = %err, "failed to take outbox entry");
+                    false
+                }
+            })
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_retry_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::new(dir.path().join("outbox"));
+        assert!(outbox.list().is_empty());
+
+        let mut msg = OutboundMessage::new("email", "bob@example", "Your report");
+        msg.metadata.insert("subject".into(), "Report".into());
+        let first = outbox.add(&msg, "invalid recipient").unwrap();
+        let second = outbox.add(&OutboundMessage::new("slack", "C1", "hi"), "not_in_channel").unwrap();
+
+        let listed = outbox.list();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, first.id);
+        assert_eq!(listed[0].message.metadata["subject"], "Report");
+        assert_eq!(listed[0].status, OutboxStatus::Failed);
+        assert!(outbox.take_retries().is_empty());
+
+        assert_eq!(outbox.retry(&first.id).unwrap().status, OutboxStatus::Retry);
+        let taken = outbox.take_retries();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].message.chat_id, "bob@example");
+        assert_eq!(outbox.list().len(), 1);
+
+        let failed = outbox.fail(taken.into_iter().next().unwrap(), "still invalid").unwrap();
+        assert_eq!((failed.attempts, failed.error.as_str()), (2, "still invalid"));
+        assert_eq!(outbox.get(&first.id).unwrap().status, OutboxStatus::Failed);
+
+        outbox.remove(&second.id).unwrap();
+        assert!(outbox.get(&second.id).is_err());
+        assert!(outbox.retry("missing").is_err());
+        assert!(outbox.remove("../config").is_err());
+        assert_eq!(outbox.list().len(), 1);
+    }
+}
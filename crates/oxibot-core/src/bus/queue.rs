@@ -3,19 +3,52 @@
 //! Replaces nanobot's `bus/queue.py` (asyncio.Queue-based MessageBus).
 //! Uses tokio::sync::mpsc bounded channels.
 
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::approval::{Approvals, Decision};
+use super::cancel::{self, RunningTurn, Turns};
+use super::dedup::Dedup;
+use super::events::{AgentEvent, BusEvent, StatusEvent, Subscription, Topic};
+use super::filter::InboundFilter;
 use super::types::{InboundMessage, OutboundMessage};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
+use tracing::debug;
+
+/// Events buffered per subscriber before the oldest are skipped.
+const SUBSCRIBER_BUFFER: usize = 256;
 
 /// The message bus connecting channels ↔ agent loop.
 ///
 /// - Channels publish to `inbound` (user messages arriving)
 /// - Agent loop consumes from `inbound`, processes, publishes to `outbound`
 /// - Channel manager consumes from `outbound` and routes to correct channel
+///
+/// Inbound filters run when a message is consumed, so they cover every
+/// producer, including channels holding a cloned [`inbound_sender`](Self::inbound_sender).
+///
+/// Answers to approval requests are taken off in [`publish_inbound`](Self::publish_inbound),
+/// so channels publish through the bus rather than a cloned sender. So are
+/// requests to stop a running turn.
+///
+/// Consuming is destructive: each message goes to one consumer. Observers
+/// that only want to watch (archivers, analytics, dashboards) use
+/// [`subscribe`](Self::subscribe) instead.
 pub struct MessageBus {
     inbound_tx: mpsc::Sender<InboundMessage>,
     inbound_rx: tokio::sync::Mutex<mpsc::Receiver<InboundMessage>>,
     outbound_tx: mpsc::Sender<OutboundMessage>,
     outbound_rx: tokio::sync::Mutex<mpsc::Receiver<OutboundMessage>>,
+    /// Middleware applied to inbound messages, in order.
+    inbound_filters: Vec<Arc<dyn InboundFilter>>,
+    /// Copies of bus traffic for subscribers.
+    events: broadcast::Sender<Arc<BusEvent>>,
+    /// Approval requests waiting for an answer.
+    approvals: Approvals,
+    /// Turns the agent is working on, for cancellation.
+    turns: Arc<Turns>,
+    /// Recently published message ids, to drop redeliveries.
+    dedup: Dedup,
 }
 
 impl MessageBus {
@@ -29,19 +62,156 @@ impl MessageBus {
             inbound_rx: tokio::sync::Mutex::new(inbound_rx),
             outbound_tx,
             outbound_rx: tokio::sync::Mutex::new(outbound_rx),
+            inbound_filters: Vec::new(),
+            events: broadcast::channel(SUBSCRIBER_BUFFER).0,
+            approvals: Approvals::default(),
+            turns: Arc::default(),
+            dedup: Dedup::default(),
+        }
+    }
+
+    /// Append a filter to the inbound middleware stack.
+    pub fn with_inbound_filter(mut self, filter: Arc<dyn InboundFilter>) -> Self {
+        self.inbound_filters.push(filter);
+        self
+    }
+
+    /// Let `admins` answer every approval request, as well as the sender
+    /// who caused it. Entries are sender ids or `channel:id`, like
+    /// `commands.admins`.
+    pub fn with_admins(mut self, admins: Vec<String>) -> Self {
+        self.approvals.admins = admins;
+        self
+    }
+
+    /// Watch bus traffic on `topics` (empty = all topics).
+    ///
+    /// Subscribers get copies; the agent loop and channel manager still
+    /// receive every message. Only events after this call are seen.
+    pub fn subscribe(&self, topics: &[Topic]) -> Subscription {
+        Subscription::new(self.events.subscribe(), topics)
+    }
+
+    /// Report a status event to subscribers.
+    pub fn publish_status(&self, event: StatusEvent) {
+        self.notify(|| BusEvent::Status(event));
+    }
+
+    /// Report a step of an agent turn to subscribers.
+    pub fn publish_agent_event(&self, event: AgentEvent) {
+        self.notify(|| BusEvent::Agent(event));
+    }
+
+    /// Send an event to subscribers, if there are any.
+    fn notify(&self, event: impl FnOnce() -> BusEvent) {
+        if self.events.receiver_count() > 0 {
+            let _ = self.events.send(Arc::new(event()));
         }
     }
 
     /// Publish a message from a channel to the agent (inbound).
+    ///
+    /// A message with the same `message_id` as a recent one in the chat is
+    /// a redelivery and dropped. Answers to pending approval requests
+    /// resolve them here and are not passed on; so are "stop" messages for
+    /// a session with a running turn.
     pub async fn publish_inbound(&self, msg: InboundMessage) -> Result<(), mpsc::error::SendError<InboundMessage>> {
+        if self.dedup.is_duplicate(&msg) {
+            debug!(channel = %msg.channel, chat_id = %msg.chat_id, message_id = ?msg.message_id(), "duplicate message dropped");
+            self.publish_status(StatusEvent::InboundDropped {
+                channel: msg.channel,
+                chat_id: msg.chat_id,
+                filter: "dedup".into(),
+            });
+            return Ok(());
+        }
+        if self.approvals.resolve(&msg) {
+            debug!(channel = %msg.channel, chat_id = %msg.chat_id, "approval answered");
+            return Ok(());
+        }
+        if cancel::is_stop_request(&msg.content) && self.cancel_turn(&msg.session_key()) {
+            debug!(session_key = %msg.session_key(), "turn cancelled by user");
+            return Ok(());
+        }
         self.inbound_tx.send(msg).await
     }
 
+    /// Ask in `channel`/`chat_id` to allow or deny `prompt` and wait up to
+    /// `timeout` for the answer. Only `requester` (a sender id) and the
+    /// admins can answer; with no requester, only the admins.
+    ///
+    /// Returns [`Decision::Denied`] if the question cannot be sent.
+    pub async fn request_approval(
+        &self,
+        channel: &str,
+        chat_id: &str,
+        requester: Option<&str>,
+        prompt: &str,
+        timeout: Duration,
+    ) -> Decision {
+        let (id, rx) = self.approvals.register(channel, chat_id, requester);
+        let msg = OutboundMessage::approval_request(channel, chat_id, &id, prompt);
+        if self.publish_outbound(msg).await.is_err() {
+            self.approvals.cancel(&id);
+            return Decision::Denied;
+        }
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(decision)) => decision,
+            _ => {
+                self.approvals.cancel(&id);
+                Decision::TimedOut
+            }
+        }
+    }
+
+    /// Register a turn the agent is working on for `session_key`. It can
+    /// be stopped until the returned handle is dropped.
+    pub fn start_turn(&self, session_key: &str) -> RunningTurn {
+        self.turns.start(session_key)
+    }
+
+    /// Stop the turn running for `session_key`. Returns `false` if none is.
+    pub fn cancel_turn(&self, session_key: &str) -> bool {
+        self.turns.cancel(session_key)
+    }
+
     /// Consume the next inbound message (blocks until available).
     /// Returns None if all senders are dropped.
+    ///
+    /// Messages dropped by an inbound filter are skipped. Filters run
+    /// after the queue is released, so a slow one holds up only this
+    /// message, not other consumers.
     pub async fn consume_inbound(&self) -> Option<InboundMessage> {
-        let mut rx = self.inbound_rx.lock().await;
-        rx.recv().await
+        loop {
+            let msg = self.inbound_rx.lock().await.recv().await?;
+            if let Some(msg) = self.apply_inbound_filters(msg).await {
+                self.notify(|| BusEvent::Inbound(msg.clone()));
+                return Some(msg);
+            }
+        }
+    }
+
+    /// Run a message through the inbound filters.
+    async fn apply_inbound_filters(&self, mut msg: InboundMessage) -> Option<InboundMessage> {
+        if msg.channel == "system" {
+            return Some(msg);
+        }
+        for filter in &self.inbound_filters {
+            let (channel, chat_id) = (msg.channel.clone(), msg.chat_id.clone());
+            match filter.apply(msg).await {
+                Some(next) => msg = next,
+                None => {
+                    debug!(filter = filter.name(), channel = %channel, chat_id = %chat_id, "inbound message dropped");
+                    self.publish_status(StatusEvent::InboundDropped {
+                        channel,
+                        chat_id,
+                        filter: filter.name().to_string(),
+                    });
+                    return None;
+                }
+            }
+        }
+        Some(msg)
     }
 
     /// Publish a response from the agent to a channel (outbound).
@@ -53,7 +223,9 @@ impl MessageBus {
     /// Returns None if all senders are dropped.
     pub async fn consume_outbound(&self) -> Option<OutboundMessage> {
         let mut rx = self.outbound_rx.lock().await;
-        rx.recv().await
+        let msg = rx.recv().await?;
+        self.notify(|| BusEvent::Outbound(msg.clone()));
+        Some(msg)
     }
 
     /// Get a clone of the inbound sender (for channels to use).
@@ -70,6 +242,7 @@ impl MessageBus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bus::types::MESSAGE_ID_KEY;
 
     #[tokio::test]
     async fn test_inbound_message_flow() {
@@ -95,6 +268,141 @@ mod tests {
         assert_eq!(received.content, "Response here");
     }
 
+    /// Uppercases content and drops messages containing "spam".
+    struct TestFilter;
+
+    #[async_trait::async_trait]
+    impl InboundFilter for TestFilter {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn apply(&self, mut msg: InboundMessage) -> Option<InboundMessage> {
+            if msg.content.contains("spam") {
+                return None;
+            }
+            msg.content = msg.content.to_uppercase();
+            Some(msg)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inbound_filters() {
+        let bus = MessageBus::new(10).with_inbound_filter(Arc::new(TestFilter));
+        let sender = bus.inbound_sender();
+
+        sender.send(InboundMessage::new("telegram", "u", "c", "buy spam now")).await.unwrap();
+        sender.send(InboundMessage::new("telegram", "u", "c", "hello")).await.unwrap();
+        sender.send(InboundMessage::new("system", "subagent", "telegram:c", "spam result")).await.unwrap();
+
+        // Dropped messages are skipped, system messages pass untouched
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "HELLO");
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "spam result");
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_see_copies() {
+        let bus = MessageBus::new(10).with_inbound_filter(Arc::new(TestFilter));
+        let mut all = bus.subscribe(&[]);
+        let mut status = bus.subscribe(&[Topic::Status]);
+
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "c", "spam")).await.unwrap();
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "c", "hi")).await.unwrap();
+        bus.publish_outbound(OutboundMessage::new("telegram", "c", "hello")).await.unwrap();
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "HI");
+        assert_eq!(bus.consume_outbound().await.unwrap().content, "hello");
+
+        let dropped = StatusEvent::InboundDropped {
+            channel: "telegram".into(),
+            chat_id: "c".into(),
+            filter: "test".into(),
+        };
+        match all.recv().await.unwrap().as_ref() {
+            BusEvent::Status(event) => assert_eq!(*event, dropped),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match all.recv().await.unwrap().as_ref() {
+            BusEvent::Inbound(msg) => assert_eq!(msg.content, "HI"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert_eq!(all.recv().await.unwrap().topic(), Topic::Outbound);
+
+        // Filtered subscribers skip other topics
+        match status.recv().await.unwrap().as_ref() {
+            BusEvent::Status(event) => assert_eq!(*event, dropped),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        drop(bus);
+        assert!(status.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_request_approval() {
+        let bus = Arc::new(MessageBus::new(10));
+        let asking = {
+            let bus = bus.clone();
+            tokio::spawn(async move {
+                bus.request_approval("telegram", "42", Some("u"), "Run `make clean`?", Duration::from_secs(5)).await
+            })
+        };
+
+        let question = bus.consume_outbound().await.unwrap();
+        let (id, prompt) = question.approval().unwrap();
+        assert_eq!(prompt, "Run `make clean`?");
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "42", "hello")).await.unwrap();
+        bus.publish_inbound(InboundMessage::approval_response("telegram", "u", "42", id, Decision::Approved))
+            .await
+            .unwrap();
+        assert_eq!(asking.await.unwrap(), Decision::Approved);
+
+        // Only the unrelated message reaches the agent
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "hello");
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "42", "yes")).await.unwrap();
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "yes");
+
+        let decision = bus.request_approval("telegram", "42", Some("u"), "Again?", Duration::from_millis(10)).await;
+        assert_eq!(decision, Decision::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_redelivered_messages_are_dropped() {
+        let bus = MessageBus::new(10);
+        let mut status = bus.subscribe(&[Topic::Status]);
+        let mut msg = InboundMessage::new("discord", "u", "c1", "hello");
+        msg.metadata.insert(MESSAGE_ID_KEY.into(), "m1".into());
+
+        bus.publish_inbound(msg.clone()).await.unwrap();
+        bus.publish_inbound(msg.clone()).await.unwrap();
+        msg.metadata.insert(MESSAGE_ID_KEY.into(), "m2".into());
+        msg.content = "again".into();
+        bus.publish_inbound(msg).await.unwrap();
+
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "hello");
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "again");
+        match status.recv().await.unwrap().as_ref() {
+            BusEvent::Status(StatusEvent::InboundDropped { filter, .. }) => assert_eq!(filter, "dedup"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_cancels_running_turn() {
+        let bus = MessageBus::new(10);
+        // Nothing running: "stop" is an ordinary message
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "42", "stop")).await.unwrap();
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "stop");
+
+        let turn = bus.start_turn("telegram:42");
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "7", "stop")).await.unwrap();
+        assert!(!turn.is_cancelled());
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "42", "Stop!")).await.unwrap();
+        assert!(turn.is_cancelled());
+
+        bus.publish_inbound(InboundMessage::new("telegram", "u", "42", "next")).await.unwrap();
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "stop");
+        assert_eq!(bus.consume_inbound().await.unwrap().content, "next");
+    }
+
     #[tokio::test]
     async fn test_message_ordering() {
         let bus = MessageBus::new(10);
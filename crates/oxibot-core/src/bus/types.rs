@@ -4,8 +4,55 @@
 
 use crate::types::MediaAttachment;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Outbound metadata key: updates with the same id edit one progress message.
+pub const PROGRESS_ID_KEY: &str = "progress_id";
+
+/// Outbound metadata key: set on the last update of a progress message.
+pub const PROGRESS_DONE_KEY: &str = "progress_done";
+
+/// Metadata key of the platform's id for a message.
+pub const MESSAGE_ID_KEY: &str = "message_id";
+
+/// Outbound metadata key: marks an acknowledgment of an inbound message.
+pub const ACK_KEY: &str = "ack";
+
+/// Metadata key of the thread a message belongs to.
+///
+/// Set on inbound messages, it gives the thread its own session; replies
+/// carry it back so the channel posts them in that thread.
+pub const THREAD_ID_KEY: &str = "thread_id";
+
+/// Outbound metadata key: channel of the chat a message to another chat
+/// was sent from.
+pub const ORIGIN_CHANNEL_KEY: &str = "origin_channel";
+
+/// Outbound metadata key: id of the chat a message to another chat was
+/// sent from.
+pub const ORIGIN_CHAT_ID_KEY: &str = "origin_chat_id";
+
+/// Outbound metadata key: sender whose message led to a message to
+/// another chat.
+pub const ORIGIN_SENDER_KEY: &str = "origin_sender";
+
+/// Outbound metadata key: send the message even if it repeats a recent
+/// one (see `channels.repeats`).
+pub const FORCE_DELIVERY_KEY: &str = "force_delivery";
+
+/// Outbound metadata key: the message answers one from the user, rather
+/// than being sent by the bot on its own.
+pub const REPLY_KEY: &str = "reply";
+
+/// Outbound metadata key: send the message even during quiet hours (see
+/// `channels.quietHours`).
+pub const URGENT_KEY: &str = "urgent";
+
+/// Inbound metadata key: the message is from a chat the bot only reads
+/// along in, and was not addressed to it. It is recorded, not answered.
+pub const OBSERVE_KEY: &str = "observe";
+
 /// An inbound message from a channel to the agent.
 #[derive(Clone, Debug)]
 pub struct InboundMessage {
@@ -44,16 +91,56 @@ impl InboundMessage {
         }
     }
 
-    /// Session key combining channel and chat_id (e.g. "telegram:123456").
+    /// Whether the sender matches `entry`: a sender id, or `channel:id` to
+    /// match on one channel only. Channels that report several ids
+    /// (`"123456|alice"` on Telegram) match on any of them.
+    pub fn is_from(&self, entry: &str) -> bool {
+        let id = match entry.split_once(':') {
+            Some((channel, id)) if channel == self.channel => id,
+            Some(_) => return false,
+            None => entry,
+        };
+        !id.is_empty() && (self.sender_id == id || self.sender_id.split('|').any(|part| part == id))
+    }
+
+    /// Session key combining channel and chat_id (e.g. "telegram:123456"),
+    /// plus the thread id for messages in a thread ("slack:C1:1700.01").
     ///
     /// Used as the key for session persistence and history lookup.
     pub fn session_key(&self) -> String {
-        format!("{}:{}", self.channel, self.chat_id)
+        match self.thread_id() {
+            Some(thread) => format!("{}:{}:{}", self.channel, self.chat_id, thread),
+            None => format!("{}:{}", self.channel, self.chat_id),
+        }
+    }
+
+    /// Thread this message belongs to, if the channel set one.
+    pub fn thread_id(&self) -> Option<&str> {
+        self.metadata
+            .get(THREAD_ID_KEY)
+            .map(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Platform id of the message, if the channel gave one.
+    pub fn message_id(&self) -> Option<&str> {
+        self.metadata.get(MESSAGE_ID_KEY).map(|s| s.as_str())
+    }
+
+    /// Mark the message as only observed: recorded, not answered.
+    pub fn observed(mut self) -> Self {
+        self.metadata.insert(OBSERVE_KEY.into(), "true".into());
+        self
+    }
+
+    /// Whether the message is only observed (see [`OBSERVE_KEY`]).
+    pub fn is_observed(&self) -> bool {
+        self.metadata.get(OBSERVE_KEY).is_some_and(|v| v == "true")
     }
 }
 
 /// An outbound message from the agent to a channel.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OutboundMessage {
     /// Target channel name.
     pub channel: String,
@@ -85,6 +172,125 @@ impl OutboundMessage {
             metadata: HashMap::new(),
         }
     }
+
+    /// Create a progress update.
+    ///
+    /// Channels that support edits show all updates sharing `progress_id`
+    /// as a single message edited in place; other channels drop them.
+    pub fn progress(
+        channel: impl Into<String>,
+        chat_id: impl Into<String>,
+        content: impl Into<String>,
+        progress_id: impl Into<String>,
+        done: bool,
+    ) -> Self {
+        let mut msg = Self::new(channel, chat_id, content);
+        msg.metadata.insert(PROGRESS_ID_KEY.to_string(), progress_id.into());
+        if done {
+            msg.metadata.insert(PROGRESS_DONE_KEY.to_string(), "true".to_string());
+        }
+        msg
+    }
+
+    /// Progress id, if this message is a progress update.
+    pub fn progress_id(&self) -> Option<&str> {
+        self.metadata.get(PROGRESS_ID_KEY).map(|s| s.as_str())
+    }
+
+    /// Whether this is the final update of a progress message.
+    pub fn is_progress_done(&self) -> bool {
+        self.metadata.contains_key(PROGRESS_DONE_KEY)
+    }
+
+    /// Create an acknowledgment telling the sender of `msg` that the agent
+    /// started working on it (`None` when the channel gave no message id).
+    ///
+    /// It carries the inbound metadata, so channels can react to the
+    /// message or reply in its thread.
+    pub fn acknowledgment(msg: &InboundMessage) -> Option<Self> {
+        msg.metadata.get(MESSAGE_ID_KEY)?;
+        let mut ack = Self::new(&msg.channel, &msg.chat_id, "");
+        ack.metadata = msg.metadata.clone();
+        ack.metadata.insert(ACK_KEY.to_string(), "true".to_string());
+        Some(ack)
+    }
+
+    /// Address this message to the thread `msg` came from, if any.
+    pub fn in_thread_of(mut self, msg: &InboundMessage) -> Self {
+        if let Some(thread) = msg.thread_id() {
+            self.metadata.insert(THREAD_ID_KEY.to_string(), thread.to_string());
+        }
+        self
+    }
+
+    /// Whether this is an acknowledgment rather than a message to send.
+    pub fn is_acknowledgment(&self) -> bool {
+        self.metadata.contains_key(ACK_KEY)
+    }
+
+    /// Platform id of the message this refers to, if any.
+    pub fn message_id(&self) -> Option<&str> {
+        self.metadata.get(MESSAGE_ID_KEY).map(|s| s.as_str())
+    }
+
+    /// Send this message even if it repeats a recent one.
+    pub fn forced(mut self) -> Self {
+        self.metadata.insert(FORCE_DELIVERY_KEY.to_string(), "true".to_string());
+        self
+    }
+
+    /// Whether this message is sent even if it repeats a recent one.
+    pub fn is_forced(&self) -> bool {
+        self.metadata.get(FORCE_DELIVERY_KEY).is_some_and(|v| v == "true")
+    }
+
+    /// Mark this message as a reply to the user's message.
+    pub fn as_reply(mut self) -> Self {
+        self.metadata.insert(REPLY_KEY.to_string(), "true".to_string());
+        self
+    }
+
+    /// Whether this message answers one from the user.
+    pub fn is_reply(&self) -> bool {
+        self.metadata.get(REPLY_KEY).is_some_and(|v| v == "true")
+    }
+
+    /// Send this message even during quiet hours.
+    pub fn urgent(mut self) -> Self {
+        self.metadata.insert(URGENT_KEY.to_string(), "true".to_string());
+        self
+    }
+
+    /// Whether this message is sent even during quiet hours.
+    pub fn is_urgent(&self) -> bool {
+        self.metadata.get(URGENT_KEY).is_some_and(|v| v == "true")
+    }
+
+    /// Record `channel`/`chat_id` as the chat this message was sent from.
+    pub fn with_origin(mut self, channel: &str, chat_id: &str) -> Self {
+        self.metadata.insert(ORIGIN_CHANNEL_KEY.to_string(), channel.to_string());
+        self.metadata.insert(ORIGIN_CHAT_ID_KEY.to_string(), chat_id.to_string());
+        self
+    }
+
+    /// Record the sender whose message led to this one.
+    pub fn with_origin_sender(mut self, sender_id: &str) -> Self {
+        self.metadata.insert(ORIGIN_SENDER_KEY.to_string(), sender_id.to_string());
+        self
+    }
+
+    /// The sender whose message led to this one, when known.
+    pub fn origin_sender(&self) -> Option<&str> {
+        self.metadata.get(ORIGIN_SENDER_KEY).map(String::as_str)
+    }
+
+    /// The chat this message was sent from, when it is not `chat_id`.
+    pub fn origin(&self) -> Option<(&str, &str)> {
+        Some((
+            self.metadata.get(ORIGIN_CHANNEL_KEY)?.as_str(),
+            self.metadata.get(ORIGIN_CHAT_ID_KEY)?.as_str(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -109,12 +315,39 @@ mod tests {
         assert_eq!(msg.session_key(), "discord:channel_abc");
     }
 
+    #[test]
+    fn test_is_from() {
+        let msg = InboundMessage::new("telegram", "123456789|alice", "42", "hi");
+        assert!(msg.is_from("telegram:123456789"));
+        assert!(msg.is_from("alice"));
+        assert!(msg.is_from("123456789|alice"));
+        assert!(!msg.is_from("slack:123456789"));
+        assert!(!msg.is_from("12345"));
+        assert!(!msg.is_from("telegram:"));
+    }
+
     #[test]
     fn test_session_key_format_cli() {
         let msg = InboundMessage::new("cli", "local", "default", "hello");
         assert_eq!(msg.session_key(), "cli:default");
     }
 
+    #[test]
+    fn test_session_key_with_thread() {
+        let mut msg = InboundMessage::new("slack", "U1", "C1", "hi");
+        msg.metadata.insert(THREAD_ID_KEY.to_string(), String::new());
+        assert_eq!(msg.session_key(), "slack:C1");
+
+        msg.metadata.insert(THREAD_ID_KEY.to_string(), "1700.01".to_string());
+        assert_eq!(msg.session_key(), "slack:C1:1700.01");
+
+        let reply = OutboundMessage::new("slack", "C1", "ok").in_thread_of(&msg);
+        assert_eq!(reply.metadata.get(THREAD_ID_KEY).unwrap(), "1700.01");
+        let top = OutboundMessage::new("slack", "C1", "ok")
+            .in_thread_of(&InboundMessage::new("slack", "U1", "C1", "hi"));
+        assert!(top.metadata.is_empty());
+    }
+
     #[test]
     fn test_outbound_message_creation() {
         let msg = OutboundMessage::new("telegram", "chat_99", "Here's your answer!");
@@ -126,6 +359,32 @@ mod tests {
         assert!(msg.media.is_empty());
     }
 
+    #[test]
+    fn test_outbound_progress() {
+        let msg = OutboundMessage::progress("discord", "c1", "50%", "exec-1", false);
+        assert_eq!(msg.progress_id(), Some("exec-1"));
+        assert!(!msg.is_progress_done());
+
+        let done = OutboundMessage::progress("discord", "c1", "done", "exec-1", true);
+        assert!(done.is_progress_done());
+        assert!(OutboundMessage::new("discord", "c1", "hi").progress_id().is_none());
+    }
+
+    #[test]
+    fn test_acknowledgment() {
+        let mut msg = InboundMessage::new("slack", "U1", "C1", "hi");
+        assert!(OutboundMessage::acknowledgment(&msg).is_none());
+
+        msg.metadata.insert(MESSAGE_ID_KEY.to_string(), "171.2".to_string());
+        msg.metadata.insert("thread_ts".to_string(), "170.1".to_string());
+        let ack = OutboundMessage::acknowledgment(&msg).unwrap();
+        assert!(ack.is_acknowledgment());
+        assert_eq!((ack.channel.as_str(), ack.chat_id.as_str()), ("slack", "C1"));
+        assert_eq!(ack.message_id(), Some("171.2"));
+        assert_eq!(ack.metadata.get("thread_ts").unwrap(), "170.1");
+        assert!(!OutboundMessage::new("slack", "C1", "hi").is_acknowledgment());
+    }
+
     #[test]
     fn test_inbound_with_metadata() {
         let mut msg = InboundMessage::new("telegram", "user_1", "chat_1", "hi");
@@ -1,5 +1,8 @@
 //! Config loader — reads `~/.oxibot/config.json`, merges env vars, and
-//! applies legacy migrations.
+//! upgrades files written for an older schema.
+//!
+//! An upgraded file is saved back next to a backup of the original
+//! (`config.json.v<old version>.bak`); see [`migrate`](super::migrate).
 //!
 //! Replaces nanobot's `config/loader.py`.
 //!
@@ -11,7 +14,8 @@
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
-use super::schema::Config;
+use super::migrate::{self, config_version};
+use super::schema::{Config, CONFIG_VERSION};
 
 /// Default config file path.
 pub fn get_config_path() -> PathBuf {
@@ -55,11 +59,19 @@ fn load_config_from_path(path: &Path) -> Config {
         }
     };
 
-    // Apply legacy migrations
-    migrate_config(&mut raw);
+    // Upgrade configs written for an older schema
+    let from = config_version(&raw);
+    if from > CONFIG_VERSION {
+        warn!(
+            "Config {} is from a newer oxibot (configVersion {}), settings it adds are ignored",
+            path.display(),
+            from
+        );
+    }
+    let applied = migrate::migrate(&mut raw);
 
     // Deserialize into typed Config
-    let config: Config = match serde_json::from_value(raw) {
+    let config: Config = match serde_json::from_value(raw.clone()) {
         Ok(c) => c,
         Err(e) => {
             warn!("Failed to deserialize config: {}", e);
@@ -67,9 +79,34 @@ fn load_config_from_path(path: &Path) -> Config {
         }
     };
 
+    if from < CONFIG_VERSION {
+        match save_upgraded(path, &content, &raw, from) {
+            Ok(backup) => info!(
+                "Upgraded config {} from version {} to {} ({} migrations, backup at {})",
+                path.display(),
+                from,
+                CONFIG_VERSION,
+                applied.len(),
+                backup.display()
+            ),
+            Err(e) => warn!("Failed to save upgraded config {}: {}", path.display(), e),
+        }
+    }
+
     apply_env_overrides(config)
 }
 
+/// Back up the original file and replace it with the upgraded JSON.
+///
+/// Returns the backup path.
+fn save_upgraded(path: &Path, original: &str, upgraded: &serde_json::Value, from: u32) -> std::io::Result<PathBuf> {
+    let backup = path.with_extension(format!("json.v{from}.bak"));
+    std::fs::write(&backup, original)?;
+    let json = serde_json::to_string_pretty(upgraded).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)?;
+    Ok(backup)
+}
+
 /// Save configuration to disk (pretty-printed JSON with camelCase keys).
 pub fn save_config(config: &Config, path: Option<&Path>) -> std::io::Result<()> {
     let config_path = path
@@ -82,29 +119,44 @@ pub fn save_config(config: &Config, path: Option<&Path>) -> std::io::Result<()>
     }
 
     let json = serde_json::to_string_pretty(config)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        .map_err(std::io::Error::other)?;
 
     std::fs::write(&config_path, json)?;
     debug!("Config saved to {}", config_path.display());
     Ok(())
 }
 
-/// Apply legacy config migrations.
+/// Read the config file as is, for changing and saving it back: without
+/// environment overrides, which must not be written to the file. A missing
+/// file gives the defaults; one that can't be parsed is an error.
+pub fn read_config_file(path: Option<&Path>) -> std::io::Result<Config> {
+    let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
+    match std::fs::read_to_string(&config_path) {
+        Ok(content) => serde_json::from_str(&content).map_err(std::io::Error::other),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Add `user` to the allow-list of `channel` in the config file and save
+/// it. Returns `false` if the user was already on it.
 ///
-/// Moves `tools.exec.restrictToWorkspace` → `tools.restrictToWorkspace`.
-fn migrate_config(raw: &mut serde_json::Value) {
-    // Migration: tools.exec.restrictToWorkspace → tools.restrictToWorkspace
-    if let Some(tools) = raw.get_mut("tools") {
-        if let Some(exec) = tools.get("exec") {
-            if let Some(restrict) = exec.get("restrictToWorkspace") {
-                if tools.get("restrictToWorkspace").is_none() {
-                    let val = restrict.clone();
-                    tools["restrictToWorkspace"] = val;
-                    debug!("Migrated tools.exec.restrictToWorkspace → tools.restrictToWorkspace");
-                }
-            }
-        }
+/// The file is read with [`read_config_file`], so a file that can't be
+/// parsed is left untouched.
+pub fn add_allowed_user(path: Option<&Path>, channel: &str, user: &str) -> std::io::Result<bool> {
+    let config_path = path.map(PathBuf::from).unwrap_or_else(get_config_path);
+    let mut config = read_config_file(Some(&config_path))?;
+    let allowed = config
+        .channels
+        .allowed_users_mut(channel)
+        .ok_or_else(|| std::io::Error::other(format!("channel '{channel}' has no allow-list")))?;
+    if allowed.iter().any(|u| u == user) {
+        return Ok(false);
     }
+    allowed.push(user.to_string());
+    save_config(&config, Some(&config_path))?;
+    info!(channel, user, "added to allow-list in {}", config_path.display());
+    Ok(true)
 }
 
 /// Apply environment variable overrides on top of a loaded config.
@@ -117,6 +169,8 @@ fn migrate_config(raw: &mut serde_json::Value) {
 /// - `OXIBOT_AGENTS__DEFAULTS__TEMPERATURE` → `agents.defaults.temperature`
 /// - `OXIBOT_PROVIDERS__<NAME>__API_KEY` → `providers.<name>.api_key`
 /// - `OXIBOT_PROVIDERS__<NAME>__API_BASE` → `providers.<name>.api_base`
+/// - `OXIBOT_PROVIDERS__RESPONSE_CACHE__MODE` → `providers.response_cache.mode`
+/// - `OXIBOT_PROVIDERS__RESPONSE_CACHE__DIR` → `providers.response_cache.dir`
 /// - `OXIBOT_GATEWAY__HOST` → `gateway.host`
 /// - `OXIBOT_GATEWAY__PORT` → `gateway.port`
 /// - `OXIBOT_TOOLS__RESTRICT_TO_WORKSPACE` → `tools.restrict_to_workspace`
@@ -157,6 +211,12 @@ fn apply_env_overrides(mut config: Config) -> Config {
     apply_provider_env(&mut config.providers.moonshot, "MOONSHOT");
     apply_provider_env(&mut config.providers.minimax, "MINIMAX");
     apply_provider_env(&mut config.providers.aihubmix, "AIHUBMIX");
+    if let Ok(val) = std::env::var("OXIBOT_PROVIDERS__RESPONSE_CACHE__MODE") {
+        config.providers.response_cache.mode = val;
+    }
+    if let Ok(val) = std::env::var("OXIBOT_PROVIDERS__RESPONSE_CACHE__DIR") {
+        config.providers.response_cache.dir = val;
+    }
 
     // Gateway
     if let Ok(val) = std::env::var("OXIBOT_GATEWAY__HOST") {
@@ -193,14 +253,15 @@ fn apply_provider_env(provider: &mut super::schema::ProviderConfig, name: &str)
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
+    use tempfile::TempDir;
 
-    fn write_temp_json(content: &str) -> NamedTempFile {
-        let mut file = NamedTempFile::new().unwrap();
-        file.write_all(content.as_bytes()).unwrap();
-        file.flush().unwrap();
-        file
+    /// Write `content` to `config.json` in a fresh directory (upgrades write
+    /// a backup next to it).
+    fn write_temp_json(content: &str) -> (TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, content).unwrap();
+        (dir, path)
     }
 
     #[test]
@@ -213,7 +274,7 @@ mod tests {
 
     #[test]
     fn test_load_valid_json() {
-        let file = write_temp_json(r#"{
+        let (_dir, path) = write_temp_json(r#"{
             "agents": {
                 "defaults": {
                     "model": "gpt-4o",
@@ -222,7 +283,7 @@ mod tests {
             }
         }"#);
 
-        let config = load_config_from_path(file.path());
+        let config = load_config_from_path(&path);
         assert_eq!(config.agents.defaults.model, "gpt-4o");
         assert_eq!(config.agents.defaults.max_tokens, 2048);
         // Default preserved
@@ -231,15 +292,15 @@ mod tests {
 
     #[test]
     fn test_load_invalid_json_returns_defaults() {
-        let file = write_temp_json("not valid json {{{");
-        let config = load_config_from_path(file.path());
+        let (_dir, path) = write_temp_json("not valid json {{{");
+        let config = load_config_from_path(&path);
         assert_eq!(config.agents.defaults.max_tokens, 8192);
     }
 
     #[test]
     fn test_load_empty_json() {
-        let file = write_temp_json("{}");
-        let config = load_config_from_path(file.path());
+        let (_dir, path) = write_temp_json("{}");
+        let config = load_config_from_path(&path);
         assert_eq!(config.agents.defaults.model, "anthropic/claude-sonnet-4-20250514");
     }
 
@@ -259,9 +320,26 @@ mod tests {
         assert_eq!(reloaded.providers.anthropic.api_key, "sk-ant-test");
     }
 
+    #[test]
+    fn test_add_allowed_user() {
+        let (_dir, path) = write_temp_json(r#"{"channels": {"telegram": {"token": "t", "allowedUsers": ["1"]}}}"#);
+        assert!(add_allowed_user(Some(&path), "telegram", "42").unwrap());
+        assert!(!add_allowed_user(Some(&path), "telegram", "42").unwrap());
+        assert!(add_allowed_user(Some(&path), "nowhere", "42").is_err());
+
+        let reloaded = load_config_from_path(&path);
+        assert_eq!(reloaded.channels.telegram.allowed_users, ["1", "42"]);
+        assert_eq!(reloaded.channels.telegram.token, "t");
+
+        // A broken file is not overwritten
+        std::fs::write(&path, "{ broken").unwrap();
+        assert!(add_allowed_user(Some(&path), "telegram", "7").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ broken");
+    }
+
     #[test]
     fn test_migrate_restrict_to_workspace() {
-        let file = write_temp_json(r#"{
+        let (_dir, path) = write_temp_json(r#"{
             "tools": {
                 "exec": {
                     "restrictToWorkspace": true,
@@ -270,14 +348,14 @@ mod tests {
             }
         }"#);
 
-        let config = load_config_from_path(file.path());
+        let config = load_config_from_path(&path);
         assert!(config.tools.restrict_to_workspace);
         assert_eq!(config.tools.exec.timeout, 30);
     }
 
     #[test]
     fn test_migrate_no_overwrite() {
-        let file = write_temp_json(r#"{
+        let (_dir, path) = write_temp_json(r#"{
             "tools": {
                 "restrictToWorkspace": false,
                 "exec": {
@@ -286,11 +364,44 @@ mod tests {
             }
         }"#);
 
-        let config = load_config_from_path(file.path());
+        let config = load_config_from_path(&path);
         // Existing value should NOT be overwritten by migration
         assert!(!config.tools.restrict_to_workspace);
     }
 
+    #[test]
+    fn test_upgrade_saves_backup() {
+        let original = r#"{ "tools": { "exec": { "restrictToWorkspace": true } } }"#;
+        let (dir, path) = write_temp_json(original);
+
+        let config = load_config_from_path(&path);
+        assert!(config.tools.restrict_to_workspace);
+        assert_eq!(config.config_version, CONFIG_VERSION);
+
+        let backup = dir.path().join("config.json.v0.bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), original);
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["configVersion"], CONFIG_VERSION);
+        assert_eq!(saved["tools"]["restrictToWorkspace"], true);
+        assert!(saved["tools"]["exec"].get("restrictToWorkspace").is_none());
+
+        // Current configs are left as they are
+        std::fs::remove_file(&backup).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        load_config_from_path(&path);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn test_broken_config_is_not_rewritten() {
+        let original = r#"{ "gateway": { "port": "not a number" } }"#;
+        let (dir, path) = write_temp_json(original);
+        load_config_from_path(&path);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+        assert!(!dir.path().join("config.json.v0.bak").exists());
+    }
+
     #[test]
     fn test_env_override_model() {
         // Set env var, apply overrides
@@ -326,13 +437,14 @@ mod tests {
         let content = std::fs::read_to_string(&path).unwrap();
         let raw: serde_json::Value = serde_json::from_str(&content).unwrap();
 
+        assert_eq!(raw["configVersion"], CONFIG_VERSION);
         assert!(raw["agents"]["defaults"].get("maxTokens").is_some());
         assert!(raw["agents"]["defaults"].get("max_tokens").is_none());
     }
 
     #[test]
     fn test_full_config_with_providers() {
-        let file = write_temp_json(r#"{
+        let (_dir, path) = write_temp_json(r#"{
             "providers": {
                 "anthropic": { "apiKey": "sk-ant-123" },
                 "openrouter": { "apiKey": "sk-or-456", "apiBase": "https://custom.io/v1" },
@@ -347,7 +459,7 @@ mod tests {
             }
         }"#);
 
-        let config = load_config_from_path(file.path());
+        let config = load_config_from_path(&path);
         assert!(config.providers.anthropic.is_configured());
         assert!(config.providers.openrouter.is_configured());
         assert_eq!(
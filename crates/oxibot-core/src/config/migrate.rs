@@ -0,0 +1,163 @@
+//! Config migrations — upgrade older config files to the current schema.
+//!
+//! Every config file records the schema it was written for in
+//! `configVersion` (missing = 0). On load, the migrations newer than that
+//! run in order on the raw JSON, so renamed or moved settings are carried
+//! over instead of being silently dropped by deserialization.
+//!
+//! To change the schema incompatibly, bump [`CONFIG_VERSION`] and append a
+//! [`Migration`] with that version to [`MIGRATIONS`].
+
+use serde_json::{Map, Value};
+use tracing::debug;
+
+use super::schema::CONFIG_VERSION;
+
+/// JSON key holding the schema version.
+pub const VERSION_KEY: &str = "configVersion";
+
+/// One schema upgrade, from `version - 1` to `version`.
+pub struct Migration {
+    /// Version the config is at after this migration.
+    pub version: u32,
+    /// What changed, for logs.
+    pub description: &'static str,
+    /// Rewrite the raw config in place.
+    pub apply: fn(&mut Value),
+}
+
+/// Registered migrations, oldest first.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "tools.exec.restrictToWorkspace moved to tools.restrictToWorkspace",
+    apply: |raw| move_key(raw, "tools.exec.restrictToWorkspace", "tools.restrictToWorkspace"),
+}];
+
+/// Schema version a raw config was written for.
+pub fn config_version(raw: &Value) -> u32 {
+    raw.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+/// Apply the registered migrations newer than the config's version and
+/// stamp it with [`CONFIG_VERSION`].
+///
+/// Returns the descriptions of the migrations applied.
+pub fn migrate(raw: &mut Value) -> Vec<&'static str> {
+    migrate_with(raw, MIGRATIONS, CONFIG_VERSION)
+}
+
+fn migrate_with(raw: &mut Value, migrations: &[Migration], latest: u32) -> Vec<&'static str> {
+    let from = config_version(raw);
+    if from >= latest || !raw.is_object() {
+        return Vec::new();
+    }
+
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| m.version > from && m.version <= latest) {
+        (migration.apply)(raw);
+        debug!(version = migration.version, "config migration: {}", migration.description);
+        applied.push(migration.description);
+    }
+    raw[VERSION_KEY] = Value::from(latest);
+    applied
+}
+
+// ─────────────────────────────────────────────
+// Helpers for migrations
+// ─────────────────────────────────────────────
+
+/// Move the value at dotted path `from` to `to`, creating parent objects
+/// as needed. Renames a key when both share a parent. A value already at
+/// `to` wins; the old key is removed either way.
+pub fn move_key(raw: &mut Value, from: &str, to: &str) {
+    let Some(value) = take(raw, from) else { return };
+    let (parents, key) = match to.rsplit_once('.') {
+        Some((parents, key)) => (Some(parents), key),
+        None => (None, to),
+    };
+    let mut target = raw;
+    for part in parents.into_iter().flat_map(|p| p.split('.')) {
+        let Some(object) = target.as_object_mut() else { return };
+        target = object.entry(part).or_insert_with(|| Value::Object(Map::new()));
+    }
+    if let Some(object) = target.as_object_mut() {
+        object.entry(key).or_insert(value);
+    }
+}
+
+/// Remove and return the value at dotted path `path`.
+fn take(raw: &mut Value, path: &str) -> Option<Value> {
+    let (parents, key) = match path.rsplit_once('.') {
+        Some((parents, key)) => (Some(parents), key),
+        None => (None, path),
+    };
+    let mut target = raw;
+    for part in parents.into_iter().flat_map(|p| p.split('.')) {
+        target = target.get_mut(part)?;
+    }
+    target.as_object_mut()?.remove(key)
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_registry_ends_at_current_version() {
+        assert_eq!(MIGRATIONS.last().unwrap().version, CONFIG_VERSION);
+        assert!(MIGRATIONS.windows(2).all(|w| w[1].version == w[0].version + 1));
+    }
+
+    #[test]
+    fn test_move_key() {
+        let mut raw = json!({ "tools": { "exec": { "restrictToWorkspace": true, "timeout": 30 } } });
+        move_key(&mut raw, "tools.exec.restrictToWorkspace", "tools.restrictToWorkspace");
+        assert_eq!(raw, json!({ "tools": { "restrictToWorkspace": true, "exec": { "timeout": 30 } } }));
+
+        // Whole sections move, into parents that don't exist yet
+        move_key(&mut raw, "tools.exec", "agents.defaults.exec");
+        assert_eq!(raw["agents"]["defaults"]["exec"]["timeout"], 30);
+        assert!(raw["tools"].get("exec").is_none());
+
+        // An existing value is kept; missing keys are a no-op
+        let mut raw = json!({ "old": 1, "new": 2 });
+        move_key(&mut raw, "old", "new");
+        move_key(&mut raw, "missing.key", "other");
+        assert_eq!(raw, json!({ "new": 2 }));
+    }
+
+    #[test]
+    fn test_migrations_run_once_in_order() {
+        let migrations = [
+            Migration {
+                version: 1,
+                description: "rename a to b",
+                apply: |raw| move_key(raw, "a", "b"),
+            },
+            Migration {
+                version: 2,
+                description: "move b into section",
+                apply: |raw| move_key(raw, "b", "section.b"),
+            },
+        ];
+
+        let mut raw = json!({ "a": "kept" });
+        assert_eq!(migrate_with(&mut raw, &migrations, 2).len(), 2);
+        assert_eq!(raw, json!({ "section": { "b": "kept" }, "configVersion": 2 }));
+        assert!(migrate_with(&mut raw, &migrations, 2).is_empty());
+
+        let mut raw = json!({ "configVersion": 1, "b": "x", "a": "untouched" });
+        assert_eq!(migrate_with(&mut raw, &migrations, 2), ["move b into section"]);
+        assert_eq!(raw["a"], "untouched");
+
+        // Configs from a newer version are left alone
+        let mut raw = json!({ "configVersion": 3, "b": "x" });
+        assert!(migrate_with(&mut raw, &migrations, 2).is_empty());
+        assert_eq!(raw["b"], "x");
+    }
+}
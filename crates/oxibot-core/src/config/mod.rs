@@ -9,8 +9,9 @@
 //! ```
 
 pub mod loader;
+pub mod migrate;
 pub mod schema;
 
 // Re-export key types
-pub use loader::{get_config_path, load_config, save_config};
+pub use loader::{add_allowed_user, get_config_path, load_config, read_config_file, save_config};
 pub use schema::Config;
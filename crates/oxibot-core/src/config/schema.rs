@@ -13,12 +13,18 @@ use std::collections::HashMap;
 // Root Config
 // ─────────────────────────────────────────────
 
+/// Current config schema version. Bump it together with a new migration in
+/// [`migrate::MIGRATIONS`](super::migrate::MIGRATIONS).
+pub const CONFIG_VERSION: u32 = 1;
+
 /// Root configuration — loaded from `~/.oxibot/config.json` + env vars.
 ///
 /// Replaces nanobot's `Config(BaseSettings)`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Config {
+    /// Schema version the config was written for (missing = 0).
+    pub config_version: u32,
     pub agents: AgentsConfig,
     pub providers: ProvidersConfig,
     pub channels: ChannelsConfig,
@@ -26,17 +32,39 @@ pub struct Config {
     pub gateway: GatewayConfig,
     #[serde(default)]
     pub transcription: TranscriptionConfig,
+    #[serde(default)]
+    pub analytics: AnalyticsConfig,
+    #[serde(default)]
+    pub oauth: OAuthConfig,
+    #[serde(default)]
+    pub tokenizer: TokenizerConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    #[serde(default)]
+    pub skills: SkillsConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            config_version: CONFIG_VERSION,
             agents: AgentsConfig::default(),
             providers: ProvidersConfig::default(),
             channels: ChannelsConfig::default(),
             tools: ToolsConfig::default(),
             gateway: GatewayConfig::default(),
             transcription: TranscriptionConfig::default(),
+            analytics: AnalyticsConfig::default(),
+            oauth: OAuthConfig::default(),
+            tokenizer: TokenizerConfig::default(),
+            logging: LoggingConfig::default(),
+            sync: SyncConfig::default(),
+            redaction: RedactionConfig::default(),
+            skills: SkillsConfig::default(),
         }
     }
 }
@@ -46,17 +74,73 @@ impl Default for Config {
 // ─────────────────────────────────────────────
 
 /// Agent configuration container.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct AgentsConfig {
     pub defaults: AgentDefaults,
+    /// Named agent profiles that bindings can route to.
+    #[serde(default)]
+    pub profiles: HashMap<String, AgentProfile>,
+    /// Channel/chat → profile bindings used by the gateway.
+    #[serde(default)]
+    pub bindings: Vec<AgentBinding>,
 }
 
-impl Default for AgentsConfig {
-    fn default() -> Self {
-        Self {
-            defaults: AgentDefaults::default(),
-        }
+/// An agent profile — overrides applied on top of `AgentDefaults`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentProfile {
+    /// Model for this profile (empty = default model).
+    #[serde(default)]
+    pub model: String,
+    /// Workspace for this profile (empty = default workspace).
+    #[serde(default)]
+    pub workspace: String,
+    /// Remove tools that write files, run commands, spawn subagents or
+    /// message other chats, and keep file access inside the workspace.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Only these tools are available (empty = all tools).
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Persona preset for this profile (empty = default persona).
+    #[serde(default)]
+    pub persona: String,
+}
+
+/// Routes messages from a channel (optionally a single chat) to a profile.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentBinding {
+    /// Channel name (e.g. "slack", "telegram").
+    #[serde(default)]
+    pub channel: String,
+    /// Chat id within the channel (empty = every chat of the channel).
+    #[serde(default)]
+    pub chat_id: String,
+    /// Name of the profile in `agents.profiles`.
+    #[serde(default)]
+    pub agent: String,
+}
+
+impl AgentBinding {
+    /// Whether this binding applies to a message from `channel`/`chat_id`.
+    pub fn matches(&self, channel: &str, chat_id: &str) -> bool {
+        self.channel == channel && (self.chat_id.is_empty() || self.chat_id == chat_id)
+    }
+}
+
+impl AgentsConfig {
+    /// Profile name bound to `channel`/`chat_id`, if any.
+    ///
+    /// A binding for the specific chat wins over a channel-wide binding;
+    /// otherwise the first matching binding is used.
+    pub fn binding_for(&self, channel: &str, chat_id: &str) -> Option<&str> {
+        let matching = || self.bindings.iter().filter(|b| b.matches(channel, chat_id));
+        matching()
+            .find(|b| !b.chat_id.is_empty())
+            .or_else(|| matching().next())
+            .map(|b| b.agent.as_str())
     }
 }
 
@@ -76,6 +160,369 @@ pub struct AgentDefaults {
     pub temperature: f64,
     /// Maximum tool-calling loop iterations before forcing a response.
     pub max_tool_iterations: u32,
+    /// Model for tool-call planning and summarization (empty = `model`).
+    pub planner_model: String,
+    /// Model for final user-facing answers (empty = `model`).
+    pub responder_model: String,
+    /// Fast path for trivial messages ("thanks", "ok", greetings).
+    pub quick_replies: QuickReplyConfig,
+    /// Short acknowledgment before replies that take a while.
+    pub ack_first: AckFirstConfig,
+    /// Per-reply footer with model, tokens, cost, tools and time.
+    pub debug_footer: DebugFooterConfig,
+    /// Persona preset (e.g. "concise-assistant"; empty = none).
+    /// Chats can switch with `/persona`.
+    pub persona: String,
+    /// Compaction of tool results from earlier iterations.
+    pub tool_results: ToolResultsConfig,
+    /// Show the correlation ID of errors in chat replies (default false).
+    /// The ID is always logged with the full error.
+    pub show_error_ids: bool,
+    /// Recovery from tool calls with malformed JSON arguments.
+    pub tool_call_repair: ToolCallRepairConfig,
+    /// Tools taken out of use while they keep failing.
+    pub tool_breaker: ToolBreakerConfig,
+    /// Token budget for the prompt (0 = the model's context window). Tool
+    /// results and then the oldest history are left out to stay under it.
+    pub max_context_tokens: usize,
+    /// Model → context window in tokens, for models the built-in table
+    /// doesn't know or gets wrong (e.g. `"llama3.2": 8192`).
+    pub context_windows: HashMap<String, usize>,
+    /// Conversations the gateway works on at once (default 4, min 1).
+    /// Messages of one conversation are always handled in order.
+    pub max_concurrent_sessions: usize,
+    /// Greeting and help replies sent without calling the LLM.
+    pub responses: ResponsesConfig,
+    /// Chat commands (`/reset`, `/status`, ...): prefix and permissions.
+    pub commands: CommandsConfig,
+    /// Plan-first mode for multi-step requests.
+    pub plan_mode: PlanModeConfig,
+    /// Upload documents to the provider instead of inlining them.
+    pub file_uploads: FileUploadConfig,
+    /// Extra system prompt sections (workspace git status, calendar peek).
+    pub context_providers: ContextProvidersConfig,
+}
+
+/// Plan-first mode: for multi-step requests the agent proposes a numbered
+/// plan, waits for the user's approval, then reports progress per step.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlanModeConfig {
+    pub enabled: bool,
+    /// Start on proposed plans without asking (they are still shown).
+    pub auto_approve: bool,
+    /// How long to wait for approval, in seconds (default 600).
+    pub approval_timeout_secs: u64,
+}
+
+impl Default for PlanModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            auto_approve: false,
+            approval_timeout_secs: 600,
+        }
+    }
+}
+
+/// Documents attached to messages are uploaded to the provider's file
+/// store (OpenAI or Anthropic models only) and referenced by id, on this
+/// and later turns of the conversation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileUploadConfig {
+    pub enabled: bool,
+    /// File extensions to upload (default `["pdf"]`).
+    pub extensions: Vec<String>,
+    /// Larger files are not uploaded (default 32).
+    pub max_file_mb: u64,
+    /// Files kept attached to a conversation; older ones are deleted from
+    /// the provider (default 5).
+    pub max_per_session: usize,
+}
+
+impl Default for FileUploadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            extensions: vec!["pdf".into()],
+            max_file_mb: 32,
+            max_per_session: 5,
+        }
+    }
+}
+
+/// Built-in context providers: sections added to the system prompt on
+/// every turn, each within its own token budget.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContextProvidersConfig {
+    /// Add the workspace's `git status` (branch and uncommitted changes).
+    pub git_status: bool,
+    /// Add calendar events in the next this many hours (needs
+    /// `tools.calendar`; 0 = off).
+    pub calendar_hours: u32,
+    /// A provider slower than this is left out of the turn (default 2000).
+    pub timeout_ms: u64,
+}
+
+impl Default for ContextProvidersConfig {
+    fn default() -> Self {
+        Self {
+            git_status: false,
+            calendar_hours: 0,
+            timeout_ms: 2000,
+        }
+    }
+}
+
+/// Chat commands answered without the LLM.
+///
+/// A message starting with `prefix` and naming a known command is handled
+/// by it; anything else goes to the agent. Commands in `admin_only` answer
+/// only senders listed in `admins` (the local CLI always counts as admin).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommandsConfig {
+    /// What commands start with (default `"/"`).
+    pub prefix: String,
+    /// Admin senders, as `"<channel>:<sender id>"` or a bare sender id.
+    pub admins: Vec<String>,
+    /// Commands only admins may use (names without the prefix).
+    pub admin_only: Vec<String>,
+    /// Commands turned off; such messages go to the agent like any other.
+    pub disabled: Vec<String>,
+}
+
+impl Default for CommandsConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "/".to_string(),
+            admins: Vec::new(),
+            admin_only: Vec::new(),
+            disabled: Vec::new(),
+        }
+    }
+}
+
+/// Canned replies: `/start` and first-contact greetings, `/help` (and
+/// "help" in direct messages).
+///
+/// Texts may use `{agent_name}`, `{name}` (the sender), `{channel}` and
+/// `{commands}`. Empty texts fall back to `prompts/greeting.md` /
+/// `prompts/help.md` in the workspace (`prompts/<channel>/...` per
+/// channel), then to the built-in replies.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResponsesConfig {
+    /// Value of `{agent_name}` (default "Oxibot").
+    pub agent_name: String,
+    /// Reply to `/start` and greeting for new conversations.
+    pub greeting: String,
+    /// Reply to `/help`.
+    pub help: String,
+    /// Greet a conversation before answering its first message (default false).
+    pub greet_new_chats: bool,
+    /// Overrides per channel name (e.g. `"slack"`).
+    pub channels: HashMap<String, ChannelResponses>,
+}
+
+impl Default for ResponsesConfig {
+    fn default() -> Self {
+        Self {
+            agent_name: "Oxibot".to_string(),
+            greeting: String::new(),
+            help: String::new(),
+            greet_new_chats: false,
+            channels: HashMap::new(),
+        }
+    }
+}
+
+/// Per-channel greeting and help; unset fields use the defaults.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelResponses {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub greeting: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub greet_new_chats: Option<bool>,
+}
+
+/// Recovery from bad tool calls: arguments that are not valid JSON, or
+/// tools that don't exist.
+///
+/// The problem is returned to the model as the tool result so it can fix
+/// the call. After `maxFailures` bad calls in one turn, planning moves to
+/// `fallbackModel`; without one (or if it fails as well), tools are
+/// disabled for the rest of the turn. A call that fails the same way
+/// `maxRepeatedFailures` times ends the turn with an explanation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolCallRepairConfig {
+    /// Bad tool calls allowed per turn before falling back (default 2, 0 = never).
+    pub max_failures: usize,
+    /// Stricter model used after too many bad calls (empty = disable tools).
+    pub fallback_model: String,
+    /// Identical failing calls allowed per turn before it ends (default 3,
+    /// 0 = never).
+    pub max_repeated_failures: usize,
+}
+
+impl Default for ToolCallRepairConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: 2,
+            fallback_model: String::new(),
+            max_repeated_failures: 3,
+        }
+    }
+}
+
+/// Circuit breaking for tools that keep failing (an expired API key, a
+/// site that is down).
+///
+/// When at least `minCalls` calls to a tool within the last `windowSecs`
+/// seconds fail at a rate of `errorRate` or more, the tool is no longer
+/// offered to the model, and the system prompt says why. After
+/// `cooldownSecs` it is offered again for one trial call: success puts it
+/// back for good, failure takes it out for another cool-down.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolBreakerConfig {
+    /// Take failing tools out of use (default false). The core filesystem
+    /// and exec tools are never taken out.
+    pub enabled: bool,
+    /// Rolling window the error rate is measured over (default 600).
+    pub window_secs: u64,
+    /// Calls in the window needed before a tool can trip (default 5).
+    pub min_calls: usize,
+    /// Share of failed calls that trips a tool, 0.0 – 1.0 (default 0.8).
+    pub error_rate: f64,
+    /// How long a tripped tool stays out before a trial call (default 900).
+    pub cooldown_secs: u64,
+}
+
+impl Default for ToolBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: 600,
+            min_calls: 5,
+            error_rate: 0.8,
+            cooldown_secs: 900,
+        }
+    }
+}
+
+/// Tool result compaction — keeps large outputs from filling the context.
+///
+/// The newest tool results are always sent in full. Once the LLM has seen
+/// them, results longer than `maxChars` (or `maxTokens`, when set) are cut
+/// down to their start and end, or summarized by the planner model when
+/// `summarize` is set.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolResultsConfig {
+    /// Compact older tool results (default true).
+    pub compact: bool,
+    /// Results longer than this many characters are compacted (default 4000).
+    pub max_chars: usize,
+    /// Results longer than this many tokens are compacted; replaces
+    /// `maxChars` when set (default 0 = unset).
+    pub max_tokens: usize,
+    /// Summarize with an LLM call instead of truncating (default false).
+    pub summarize: bool,
+}
+
+impl Default for ToolResultsConfig {
+    fn default() -> Self {
+        Self {
+            compact: true,
+            max_chars: 4000,
+            max_tokens: 0,
+            summarize: false,
+        }
+    }
+}
+
+/// Quick replies — answer small talk without the full agent loop.
+///
+/// Exact small-talk phrases get a canned reply; other short messages are
+/// classified by one cheap model call that also writes the reply.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuickReplyConfig {
+    /// Enable quick replies (default true).
+    pub enabled: bool,
+    /// Model for classification (empty = `plannerModel`, else `model`).
+    pub model: String,
+    /// Minimum classifier confidence to reply directly (default 0.8).
+    pub min_confidence: f64,
+    /// Only messages with at most this many words are considered (default 6).
+    pub max_words: usize,
+}
+
+impl Default for QuickReplyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: String::new(),
+            min_confidence: 0.8,
+            max_words: 6,
+        }
+    }
+}
+
+/// When a reply isn't ready after `afterMs`, a one-line acknowledgment
+/// ("Working on it — checking 3 sources…") is sent first and the full
+/// answer follows.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AckFirstConfig {
+    /// Enable ack-first replies (default false).
+    pub enabled: bool,
+    /// How long a reply may take before it is acknowledged (default 4000).
+    pub after_ms: u64,
+    /// Per-channel thresholds overriding `afterMs` (0 = never on that channel).
+    pub channel_after_ms: HashMap<String, u64>,
+    /// Model writing the acknowledgment (empty = always use `template`).
+    pub model: String,
+    /// Acknowledgment without a model, or when the model fails.
+    pub template: String,
+}
+
+impl Default for AckFirstConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_ms: 4000,
+            channel_after_ms: HashMap::new(),
+            model: String::new(),
+            template: "Working on it…".into(),
+        }
+    }
+}
+
+/// A footer under each reply with the models used, tokens in/out, the
+/// estimated cost (from `analytics.prices`), the tools called and the
+/// wall-clock time. For tuning prompts and comparing providers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DebugFooterConfig {
+    /// Add the footer (default false).
+    pub enabled: bool,
+    /// Channels that get it (e.g. `["cli", "telegram"]`; empty = all).
+    pub channels: Vec<String>,
+}
+
+impl DebugFooterConfig {
+    /// Whether replies on `channel` get the footer.
+    pub fn applies_to(&self, channel: &str) -> bool {
+        self.enabled && (self.channels.is_empty() || self.channels.iter().any(|c| c == channel))
+    }
 }
 
 impl Default for AgentDefaults {
@@ -86,6 +533,24 @@ impl Default for AgentDefaults {
             max_tokens: 8192,
             temperature: 0.7,
             max_tool_iterations: 20,
+            planner_model: String::new(),
+            responder_model: String::new(),
+            quick_replies: QuickReplyConfig::default(),
+            ack_first: AckFirstConfig::default(),
+            debug_footer: DebugFooterConfig::default(),
+            persona: String::new(),
+            tool_results: ToolResultsConfig::default(),
+            show_error_ids: false,
+            tool_call_repair: ToolCallRepairConfig::default(),
+            tool_breaker: ToolBreakerConfig::default(),
+            max_context_tokens: 0,
+            context_windows: HashMap::new(),
+            max_concurrent_sessions: 4,
+            responses: ResponsesConfig::default(),
+            commands: CommandsConfig::default(),
+            plan_mode: PlanModeConfig::default(),
+            file_uploads: FileUploadConfig::default(),
+            context_providers: ContextProvidersConfig::default(),
         }
     }
 }
@@ -109,6 +574,13 @@ pub struct ProviderConfig {
     /// Extra HTTP headers to send with each request.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra_headers: Option<HashMap<String, String>>,
+    /// Extra fields merged into every chat request body, e.g. OpenRouter's
+    /// `provider` preferences, `route: "fallback"`, `models` and `transforms`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_body: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Timeout, retry and circuit-breaker settings.
+    #[serde(skip_serializing_if = "RequestPolicy::is_default")]
+    pub request: RequestPolicy,
 }
 
 impl ProviderConfig {
@@ -118,6 +590,50 @@ impl ProviderConfig {
     }
 }
 
+/// Timeout, retry and circuit-breaker settings for a provider's HTTP calls.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequestPolicy {
+    /// Per-request timeout in seconds (default 120).
+    pub timeout_secs: u64,
+    /// Retries after a timeout, connection error, 429 or 5xx (default 2).
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds, doubled on each
+    /// further retry (default 1000). A `Retry-After` header takes precedence.
+    pub retry_backoff_ms: u64,
+    /// Consecutive failed calls that open the circuit breaker
+    /// (0 = disabled, default 5).
+    pub breaker_threshold: u32,
+    /// How long an open breaker rejects calls, in seconds (default 60).
+    pub breaker_cooldown_secs: u64,
+    /// Requests per minute the provider allows (0 = no limit, default).
+    /// Calls beyond it wait their turn instead of failing with 429.
+    pub requests_per_minute: u32,
+    /// Prompt and completion tokens per minute the provider allows
+    /// (0 = no limit, default).
+    pub tokens_per_minute: u32,
+}
+
+impl RequestPolicy {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 120,
+            max_retries: 2,
+            retry_backoff_ms: 1000,
+            breaker_threshold: 5,
+            breaker_cooldown_secs: 60,
+            requests_per_minute: 0,
+            tokens_per_minute: 0,
+        }
+    }
+}
+
 /// All provider configurations.
 ///
 /// One `ProviderConfig` per supported LLM backend.
@@ -148,6 +664,126 @@ pub struct ProvidersConfig {
     pub minimax: ProviderConfig,
     #[serde(default)]
     pub aihubmix: ProviderConfig,
+    /// User-defined OpenAI-compatible providers, merged into the registry at startup.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<CustomProviderConfig>,
+    /// Record/replay of LLM responses for deterministic tests and development.
+    #[serde(default, skip_serializing_if = "ResponseCacheConfig::is_off")]
+    pub response_cache: ResponseCacheConfig,
+    /// Background latency probes and health-based failover (gateway).
+    #[serde(default)]
+    pub health: ProviderHealthConfig,
+}
+
+/// Periodic provider probes (`GET /models`) run by the gateway.
+///
+/// Results are kept in `~/.oxibot/provider_health.json` and shown by
+/// `oxibot status`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderHealthConfig {
+    /// Probe every configured provider (default true).
+    pub enabled: bool,
+    /// Seconds between probe rounds (default 300).
+    pub interval_secs: u64,
+    /// Probe results kept per provider (default 50).
+    pub history: usize,
+    /// Send requests through the healthiest provider able to serve the
+    /// model — its own provider or a configured gateway — and fall back to
+    /// the others when a call fails (default true).
+    pub failover: bool,
+}
+
+impl Default for ProviderHealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 300,
+            history: 50,
+            failover: true,
+        }
+    }
+}
+
+/// Record/replay of LLM responses.
+///
+/// In `"record"` mode every successful response is saved under a hash of
+/// its request; in `"replay"` mode responses come from those files and no
+/// network call is made.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResponseCacheConfig {
+    /// `"record"`, `"replay"`, or empty/`"off"` (default).
+    pub mode: String,
+    /// Directory holding the responses (default `~/.oxibot/provider-cache/`).
+    pub dir: String,
+}
+
+impl ResponseCacheConfig {
+    fn is_off(&self) -> bool {
+        matches!(self.mode.as_str(), "" | "off") && self.dir.is_empty()
+    }
+}
+
+/// A user-defined OpenAI-compatible provider (LiteLLM proxy, LM Studio,
+/// llama.cpp server, ...).
+///
+/// Models are routed to it when their name starts with one of
+/// `model_prefixes`; the prefix is stripped before the request is sent.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CustomProviderConfig {
+    /// Unique provider name (e.g. `"lmstudio"`).
+    pub name: String,
+    /// Human-readable name for logs and `oxibot status`. Defaults to `name`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub display_name: String,
+    /// Base URL of the OpenAI-compatible API (e.g. `"http://localhost:1234/v1"`).
+    pub api_base: String,
+    /// API key (may be empty for servers without auth).
+    pub api_key: String,
+    /// How the API key is sent: `"bearer"` (default), `"none"`, or the name
+    /// of a header that carries the raw key (e.g. `"x-api-key"`).
+    #[serde(default = "default_auth_header")]
+    pub auth_header: String,
+    /// Model name prefixes routed to this provider (e.g. `["lmstudio/"]`).
+    pub model_prefixes: Vec<String>,
+    /// Tool schema dialect of the backing model: `"openai"` (default),
+    /// `"gemini"` or `"anthropic"`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub tool_schema: String,
+    /// Tokenizer for the backing model (see `tokenizer`; empty = heuristic).
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub tokenizer: String,
+    /// How the backing API runs web searches itself: `"options"`
+    /// (`web_search_options`), `"plugin"` (OpenRouter), `"google"`,
+    /// `"anthropic"`, or empty for none.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub web_search: String,
+    /// Extra HTTP headers to send with each request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_headers: Option<HashMap<String, String>>,
+    /// Extra fields merged into every chat request body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_body: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Timeout, retry and circuit-breaker settings.
+    #[serde(skip_serializing_if = "RequestPolicy::is_default")]
+    pub request: RequestPolicy,
+}
+
+fn default_auth_header() -> String { "bearer".into() }
+
+impl CustomProviderConfig {
+    /// Provider config view used by the registry and HTTP client.
+    pub fn to_provider_config(&self) -> ProviderConfig {
+        ProviderConfig {
+            api_key: self.api_key.clone(),
+            api_base: Some(self.api_base.clone()),
+            extra_headers: self.extra_headers.clone(),
+            extra_body: self.extra_body.clone(),
+            request: self.request.clone(),
+        }
+    }
 }
 
 impl ProvidersConfig {
@@ -170,6 +806,30 @@ impl ProvidersConfig {
         }
     }
 
+    /// Get a provider config by name, for changing it.
+    pub fn get_by_name_mut(&mut self, name: &str) -> Option<&mut ProviderConfig> {
+        match name {
+            "anthropic" => Some(&mut self.anthropic),
+            "openai" => Some(&mut self.openai),
+            "openrouter" => Some(&mut self.openrouter),
+            "deepseek" => Some(&mut self.deepseek),
+            "groq" => Some(&mut self.groq),
+            "zhipu" => Some(&mut self.zhipu),
+            "dashscope" => Some(&mut self.dashscope),
+            "vllm" => Some(&mut self.vllm),
+            "gemini" => Some(&mut self.gemini),
+            "moonshot" => Some(&mut self.moonshot),
+            "minimax" => Some(&mut self.minimax),
+            "aihubmix" => Some(&mut self.aihubmix),
+            _ => None,
+        }
+    }
+
+    /// Get a custom provider config by name.
+    pub fn get_custom(&self, name: &str) -> Option<&CustomProviderConfig> {
+        self.custom.iter().find(|c| c.name == name)
+    }
+
     /// Convert to a HashMap<String, ProviderConfig> for use with the provider registry.
     pub fn to_map(&self) -> HashMap<String, ProviderConfig> {
         let mut map = HashMap::new();
@@ -190,6 +850,10 @@ impl ProvidersConfig {
         for (name, config) in entries {
             map.insert(name.to_string(), (*config).clone());
         }
+        for custom in &self.custom {
+            map.entry(custom.name.clone())
+                .or_insert_with(|| custom.to_provider_config());
+        }
         map
     }
 }
@@ -209,6 +873,8 @@ pub struct ChannelsConfig {
     #[serde(default)]
     pub whatsapp: WhatsAppConfig,
     #[serde(default)]
+    pub signal: SignalConfig,
+    #[serde(default)]
     pub feishu: FeishuConfig,
     #[serde(default)]
     pub dingtalk: DingTalkConfig,
@@ -220,48 +886,518 @@ pub struct ChannelsConfig {
     pub qq: QQConfig,
     #[serde(default)]
     pub mochat: MochatConfig,
-}
-
-/// Telegram channel config.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", default)]
-pub struct TelegramConfig {
     #[serde(default)]
-    pub token: String,
+    pub filedrop: FileDropConfig,
+    /// Rewrites and spam filtering applied to every inbound message.
     #[serde(default)]
-    pub allowed_users: Vec<String>,
-}
-
-/// Discord channel config.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", default)]
-pub struct DiscordConfig {
+    pub inbound: InboundFiltersConfig,
+    /// Automatic restart of crashed or silent channels.
     #[serde(default)]
-    pub token: String,
+    pub supervisor: SupervisorConfig,
+    /// Pacing of long replies split into several messages.
     #[serde(default)]
-    pub allowed_users: Vec<String>,
-}
-
-/// WhatsApp channel config.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", default)]
-pub struct WhatsAppConfig {
+    pub pacing: PacingConfig,
+    /// Retries and late delivery of messages that fail to send.
     #[serde(default)]
-    pub bridge_url: String,
+    pub delivery: DeliveryConfig,
+    /// Suppression of unprompted messages that repeat a recent one.
     #[serde(default)]
-    pub allowed_users: Vec<String>,
-}
-
-/// Feishu/Lark channel config.
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", default)]
-pub struct FeishuConfig {
+    pub repeats: RepeatsConfig,
+    /// Long code blocks sent as file attachments.
     #[serde(default)]
-    pub app_id: String,
+    pub code_files: CodeFilesConfig,
+    /// Hours in which messages the bot sends on its own are held back.
     #[serde(default)]
-    pub app_secret: String,
+    pub quiet_hours: QuietHoursConfig,
+    /// Translation of messages in languages the operator doesn't read.
     #[serde(default)]
-    pub allowed_users: Vec<String>,
+    pub translation: TranslationConfig,
+    /// Admin-approved enrollment of unknown senders.
+    #[serde(default)]
+    pub enrollment: EnrollmentConfig,
+}
+
+impl ChannelsConfig {
+    /// The allow-list of `channel` (by name), if it has one.
+    pub fn allowed_users_mut(&mut self, channel: &str) -> Option<&mut Vec<String>> {
+        Some(match channel {
+            "telegram" => &mut self.telegram.allowed_users,
+            "discord" => &mut self.discord.allowed_users,
+            "whatsapp" => &mut self.whatsapp.allowed_users,
+            "signal" => &mut self.signal.allowed_users,
+            "feishu" => &mut self.feishu.allowed_users,
+            "dingtalk" => &mut self.dingtalk.allowed_users,
+            "slack" => &mut self.slack.allowed_users,
+            "email" => &mut self.email.allowed_users,
+            "qq" => &mut self.qq.allowed_users,
+            "mochat" => &mut self.mochat.allowed_users,
+            _ => return None,
+        })
+    }
+}
+
+/// Unknown senders in direct chats get a short challenge, and the admin
+/// chat is asked to approve them. Approved senders are added to the
+/// channel's `allowedUsers` in the config file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EnrollmentConfig {
+    pub enabled: bool,
+    /// Channel of the admin chat approval requests go to.
+    pub admin_channel: String,
+    /// Chat ID of the admin chat.
+    pub admin_chat_id: String,
+    /// Reply to an unknown sender while the admin decides.
+    pub challenge: String,
+    /// How long an approval request waits for the admin (default 86400).
+    pub timeout_secs: u64,
+    /// A denied or unanswered sender is not asked about again for this
+    /// long (default 86400).
+    pub retry_after_secs: u64,
+    /// Approval requests open at once (default 5). Further unknown senders
+    /// are ignored until one is answered or times out.
+    pub max_pending: usize,
+}
+
+impl Default for EnrollmentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            admin_channel: String::new(),
+            admin_chat_id: String::new(),
+            challenge: "👋 Hi! I only talk to people I know. I've asked my admin to let you in — \
+                        you'll get a message here once they decide."
+                .into(),
+            timeout_secs: 86_400,
+            retry_after_secs: 86_400,
+            max_pending: 5,
+        }
+    }
+}
+
+/// Long replies are split at the channel's message limit and the parts
+/// sent one by one, with a pause (and typing indicator) in between.
+/// Messages to one chat always go out in order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PacingConfig {
+    /// Split and pace long replies (default true). When off, channels
+    /// send all parts back-to-back.
+    pub enabled: bool,
+    /// Pause between parts of one reply (default 1000).
+    pub chunk_delay_ms: u64,
+    /// Per-channel pauses, overriding `chunkDelayMs` (e.g. `{"discord": 1500}`).
+    pub channel_delays_ms: HashMap<String, u64>,
+    /// A last part shorter than this many characters is merged with the
+    /// one before it, and the two are split evenly (default 200).
+    pub min_tail_chars: usize,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chunk_delay_ms: 1000,
+            channel_delays_ms: HashMap::new(),
+            min_tail_chars: 200,
+        }
+    }
+}
+
+/// Retries of failed sends. A message that still fails is kept in the
+/// outbox, the admin chat (`supervisor.alertChannel`) is told once per
+/// outage, and kept messages go out once the channel is back.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeliveryConfig {
+    /// Extra attempts before a send counts as failed (default 2).
+    pub retries: u32,
+    /// Pause before the first retry; doubles on each one (default 2000).
+    pub retry_delay_ms: u64,
+    /// Send kept messages once their channel is back (default true).
+    pub redeliver: bool,
+    /// Prepended to messages delivered late (default "[delayed] ").
+    pub delayed_prefix: String,
+    /// Leave a message to the operator after this many attempts (default 5).
+    pub max_attempts: u32,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            retry_delay_ms: 2000,
+            redeliver: true,
+            delayed_prefix: "[delayed] ".into(),
+            max_attempts: 5,
+        }
+    }
+}
+
+/// Messages the bot sends on its own (cron results, subagent reports) that
+/// repeat one sent to the same chat within `windowSecs` are not sent
+/// again. Replies to the user and messages marked for forced delivery
+/// always go out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RepeatsConfig {
+    /// Suppress repeats (default true).
+    pub enabled: bool,
+    /// How long a sent message suppresses identical ones (default 3600).
+    pub window_secs: u64,
+    /// `"skip"` (default) drops repeats; `"count"` also drops them, and
+    /// notes how many were dropped the next time the message goes out.
+    pub mode: String,
+}
+
+impl Default for RepeatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_secs: 3600,
+            mode: "skip".into(),
+        }
+    }
+}
+
+/// Code blocks of at least `minLines` lines in a reply are sent as file
+/// attachments, with a one-line summary in their place, on channels that
+/// can upload files (Telegram, Discord, Signal).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodeFilesConfig {
+    /// Send long code blocks as files (default true).
+    pub enabled: bool,
+    /// Shortest block sent as a file, in lines (default 40).
+    pub min_lines: usize,
+    /// Only blocks in these languages (e.g. `["rust", "python"]`; empty =
+    /// any, including blocks without a language).
+    pub languages: Vec<String>,
+}
+
+impl Default for CodeFilesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_lines: 40,
+            languages: Vec::new(),
+        }
+    }
+}
+
+/// Quiet hours: messages the bot sends on its own (cron results,
+/// reminders, subagent reports) to a channel during its window are held
+/// and sent when the window ends. Replies to the user and urgent messages
+/// (crash and channel alerts) always go out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietHoursConfig {
+    /// Timezone of the windows (IANA name, e.g. "Europe/Madrid"; default "UTC").
+    pub timezone: String,
+    /// Window per channel name (e.g. `{"telegram": {"start": "23:00", "end": "08:00"}}`).
+    pub channels: HashMap<String, QuietWindow>,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".into(),
+            channels: HashMap::new(),
+        }
+    }
+}
+
+/// Daily quiet window, from `start` to `end` ("HH:MM"); it may span midnight.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietWindow {
+    /// When the window opens (default "23:00").
+    pub start: String,
+    /// When it ends and held messages go out (default "08:00").
+    pub end: String,
+}
+
+impl Default for QuietWindow {
+    fn default() -> Self {
+        Self {
+            start: "23:00".into(),
+            end: "08:00".into(),
+        }
+    }
+}
+
+/// Translation of incoming messages into the operator's language, and of
+/// replies back into the sender's. Only the listed channels are translated.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranslationConfig {
+    /// Model that detects languages and translates (empty = the agent's
+    /// model). A local model served through vLLM works too.
+    pub model: String,
+    /// Languages the operator reads, as ISO 639-1 codes (default `["en"]`).
+    /// Messages in them are not translated; others are translated into the
+    /// first one.
+    pub operator_languages: Vec<String>,
+    /// What to translate per channel name (e.g. `{"telegram": {}}`).
+    pub channels: HashMap<String, ChannelTranslation>,
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            model: String::new(),
+            operator_languages: vec!["en".into()],
+            channels: HashMap::new(),
+        }
+    }
+}
+
+/// Translation on one channel.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelTranslation {
+    /// Translate incoming messages for the agent (default true).
+    pub inbound: bool,
+    /// Translate replies into the sender's language (default true).
+    pub replies: bool,
+}
+
+impl Default for ChannelTranslation {
+    fn default() -> Self {
+        Self {
+            inbound: true,
+            replies: true,
+        }
+    }
+}
+
+/// Channel supervision: restart channels that fail or go silent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SupervisorConfig {
+    /// Restart channels that exit with an error (default true).
+    pub enabled: bool,
+    /// Delay before the first restart; doubles on each consecutive failure (default 1000).
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the restart delay (default 300000).
+    pub max_backoff_ms: u64,
+    /// Restart a channel whose health report shows no activity for this
+    /// long (0 = never, default 600).
+    pub max_silence_secs: u64,
+    /// Alert the admin chat after this many consecutive failures (default 3).
+    pub alert_after: u32,
+    /// Channel of the admin chat alerts go to (empty = no alerts).
+    pub alert_channel: String,
+    /// Chat ID of the admin chat.
+    pub alert_chat_id: String,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 300_000,
+            max_silence_secs: 600,
+            alert_after: 3,
+            alert_channel: String::new(),
+            alert_chat_id: String::new(),
+        }
+    }
+}
+
+/// Inbound message filters, applied in order before the agent sees a message.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InboundFiltersConfig {
+    /// Drop messages matching any of these regexes (case-insensitive).
+    #[serde(default)]
+    pub spam_patterns: Vec<String>,
+    /// Resolve shortened links (t.co, bit.ly, ...) to their targets (default false).
+    #[serde(default)]
+    pub expand_short_links: bool,
+    /// Hosts treated as link shorteners by `expandShortLinks`.
+    #[serde(default = "default_short_link_hosts")]
+    pub short_link_hosts: Vec<String>,
+    /// Remove tracking query parameters (`utm_*`, `fbclid`, ...) from links (default true).
+    #[serde(default = "default_true")]
+    pub strip_tracking: bool,
+    /// Replace curly quotes and apostrophes with plain ASCII ones (default true).
+    #[serde(default = "default_true")]
+    pub normalize_quotes: bool,
+}
+
+fn default_short_link_hosts() -> Vec<String> {
+    ["t.co", "bit.ly", "tinyurl.com", "goo.gl", "ow.ly", "buff.ly", "lnkd.in", "is.gd"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+impl Default for InboundFiltersConfig {
+    fn default() -> Self {
+        Self {
+            spam_patterns: Vec::new(),
+            expand_short_links: false,
+            short_link_hosts: default_short_link_hosts(),
+            strip_tracking: true,
+            normalize_quotes: true,
+        }
+    }
+}
+
+/// How a channel shows that the agent started working on a message.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AckConfig {
+    /// "reaction" (emoji on the user's message, default), "reply" (a quick
+    /// "…" message) or "off".
+    pub mode: String,
+    /// Reaction emoji (empty = the channel's default: 👀, or `eyes` on Slack).
+    pub emoji: String,
+}
+
+impl Default for AckConfig {
+    fn default() -> Self {
+        Self {
+            mode: "reaction".into(),
+            emoji: String::new(),
+        }
+    }
+}
+
+/// Telegram channel config.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelegramConfig {
+    #[serde(default)]
+    pub token: String,
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Deep-link start tokens → agent profile name.
+    /// `t.me/<bot>?start=<token>` links the chat to that profile.
+    #[serde(default)]
+    pub deep_links: HashMap<String, String>,
+    /// Reaction or reply when the agent starts working on a message.
+    #[serde(default)]
+    pub acknowledge: AckConfig,
+    /// Read along in group chats without answering unless the bot is
+    /// mentioned; the messages still go to the chat's history and memory.
+    #[serde(default)]
+    pub observe_only: bool,
+}
+
+/// Discord channel config.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiscordConfig {
+    #[serde(default)]
+    pub token: String,
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Reaction or reply when the agent starts working on a message.
+    #[serde(default)]
+    pub acknowledge: AckConfig,
+    /// Render search results, task lists and command output as embeds (default true).
+    #[serde(default = "default_true")]
+    pub embeds: bool,
+    /// Read along in servers without answering unless the bot is
+    /// mentioned; the messages still go to the chat's history and memory.
+    #[serde(default)]
+    pub observe_only: bool,
+    /// Listen in a voice channel (needs the `discord-voice` feature).
+    #[serde(default)]
+    pub voice: DiscordVoiceConfig,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            token: String::new(),
+            allowed_users: Vec::new(),
+            acknowledge: AckConfig::default(),
+            embeds: true,
+            observe_only: false,
+            voice: DiscordVoiceConfig::default(),
+        }
+    }
+}
+
+/// Discord voice: join a voice channel, transcribe what is said and answer
+/// in a text channel.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiscordVoiceConfig {
+    /// Join the voice channel (default false).
+    pub enabled: bool,
+    /// Server the voice channel is in.
+    pub guild_id: String,
+    /// Voice channel to listen in.
+    pub channel_id: String,
+    /// Text channel the answers are posted in.
+    pub text_channel_id: String,
+    /// Phrases that address the bot; only what is said after one of them
+    /// is answered (empty = answer everything).
+    pub wake_words: Vec<String>,
+    /// Pause that ends an utterance, in milliseconds (default 800).
+    pub silence_ms: u64,
+}
+
+impl Default for DiscordVoiceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            guild_id: String::new(),
+            channel_id: String::new(),
+            text_channel_id: String::new(),
+            wake_words: vec!["hey bot".to_string()],
+            silence_ms: 800,
+        }
+    }
+}
+
+/// WhatsApp channel config.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WhatsAppConfig {
+    #[serde(default)]
+    pub bridge_url: String,
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+}
+
+/// Signal channel config (via a `signal-cli` JSON-RPC daemon).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SignalConfig {
+    /// The bot's number as registered with signal-cli, e.g. `+15551234567`.
+    #[serde(default)]
+    pub account: String,
+    /// `host:port` of `signal-cli daemon --tcp` (default `127.0.0.1:7583`).
+    #[serde(default)]
+    pub daemon_addr: String,
+    /// Where signal-cli stores received attachments
+    /// (default `~/.local/share/signal-cli/attachments`).
+    #[serde(default)]
+    pub attachments_dir: String,
+    /// Numbers or UUIDs allowed to talk to the bot (empty = everyone).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Group ids the bot answers in (empty = every group).
+    #[serde(default)]
+    pub allowed_groups: Vec<String>,
+}
+
+/// Feishu/Lark channel config.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FeishuConfig {
+    #[serde(default)]
+    pub app_id: String,
+    #[serde(default)]
+    pub app_secret: String,
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
 }
 
 /// DingTalk channel config.
@@ -302,6 +1438,21 @@ pub struct SlackConfig {
     /// DM-specific settings.
     #[serde(default)]
     pub dm: SlackDMConfig,
+    /// Seconds after a mention during which the same user's messages in
+    /// that thread are answered without re-mentioning the bot (0 = off).
+    #[serde(default)]
+    pub follow_up_secs: u64,
+    /// Reaction or reply when the agent starts working on a message.
+    #[serde(default)]
+    pub acknowledge: AckConfig,
+    /// Give each thread its own session instead of sharing the channel's.
+    #[serde(default)]
+    pub thread_sessions: bool,
+    /// Read along in channels without answering unless the bot is
+    /// mentioned (overrides `group_policy`); the messages still go to the
+    /// chat's history and memory.
+    #[serde(default)]
+    pub observe_only: bool,
 }
 
 fn default_group_policy() -> String {
@@ -391,100 +1542,454 @@ pub struct EmailConfig {
     #[serde(default)]
     pub from_address: String,
 
-    // ── Behavior ──
-    /// Poll interval in seconds (minimum 5, default 30).
-    #[serde(default = "default_poll_interval")]
-    pub poll_interval_seconds: u32,
-    /// Mark fetched emails as \\Seen (default true).
-    #[serde(default = "default_true")]
-    pub mark_seen: bool,
-    /// Truncate email body to this many characters (default 12000).
-    #[serde(default = "default_max_body_chars")]
-    pub max_body_chars: u32,
-    /// Subject prefix for replies (default "Re: ").
-    #[serde(default = "default_subject_prefix")]
-    pub subject_prefix: String,
-    /// Allowed sender emails (empty = allow everyone).
-    #[serde(default)]
-    pub allowed_users: Vec<String>,
+    // ── Behavior ──
+    /// Poll interval in seconds (minimum 5, default 30).
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_seconds: u32,
+    /// Mark fetched emails as \\Seen (default true).
+    #[serde(default = "default_true")]
+    pub mark_seen: bool,
+    /// Truncate email body to this many characters (default 12000).
+    #[serde(default = "default_max_body_chars")]
+    pub max_body_chars: u32,
+    /// Subject prefix for replies (default "Re: ").
+    #[serde(default = "default_subject_prefix")]
+    pub subject_prefix: String,
+    /// Allowed sender emails (empty = allow everyone).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Addresses (or `@domain` suffixes, or `*`) outbound mail may go to,
+    /// beyond the participants of the thread being answered (empty = only
+    /// those participants).
+    #[serde(default)]
+    pub allowed_recipients: Vec<String>,
+
+    // ── Confirmation ──
+    /// Addresses (or `@domain` suffixes, `*` for all) whose mail is previewed
+    /// in a chat and only sent once approved (empty = send right away).
+    #[serde(default)]
+    pub confirm_recipients: Vec<String>,
+    /// Channel previews go to when the email wasn't sent from another chat.
+    #[serde(default)]
+    pub confirm_channel: String,
+    /// Chat previews go to when the email wasn't sent from another chat.
+    #[serde(default)]
+    pub confirm_chat_id: String,
+    /// Seconds to wait for approval before dropping the email (default 3600).
+    #[serde(default = "default_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+
+    // ── Digest ──
+    /// Sender patterns (case-insensitive regex) batched into the daily digest.
+    #[serde(default)]
+    pub digest_senders: Vec<String>,
+    /// Subject patterns (case-insensitive regex) batched into the daily digest.
+    #[serde(default)]
+    pub digest_subjects: Vec<String>,
+    /// Local hour (0-23) at which the digest is delivered (default 8).
+    #[serde(default = "default_digest_hour")]
+    pub digest_hour: u8,
+    /// Address the agent's digest summary is sent to; falls back to from_address.
+    #[serde(default)]
+    pub digest_deliver_to: String,
+}
+
+fn default_imap_port() -> u16 { 993 }
+fn default_smtp_port() -> u16 { 587 }
+fn default_imap_mailbox() -> String { "INBOX".to_string() }
+fn default_poll_interval() -> u32 { 30 }
+fn default_max_body_chars() -> u32 { 12000 }
+fn default_subject_prefix() -> String { "Re: ".to_string() }
+fn default_digest_hour() -> u8 { 8 }
+fn default_confirm_timeout_secs() -> u64 { 3600 }
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            imap_host: String::new(),
+            imap_port: 993,
+            imap_username: String::new(),
+            imap_password: String::new(),
+            imap_mailbox: "INBOX".to_string(),
+            imap_use_ssl: true,
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            smtp_use_tls: true,
+            smtp_use_ssl: false,
+            from_address: String::new(),
+            poll_interval_seconds: 30,
+            mark_seen: true,
+            max_body_chars: 12000,
+            subject_prefix: "Re: ".to_string(),
+            allowed_users: Vec::new(),
+            allowed_recipients: Vec::new(),
+            confirm_recipients: Vec::new(),
+            confirm_channel: String::new(),
+            confirm_chat_id: String::new(),
+            confirm_timeout_secs: 3600,
+            digest_senders: Vec::new(),
+            digest_subjects: Vec::new(),
+            digest_hour: 8,
+            digest_deliver_to: String::new(),
+        }
+    }
+}
+
+/// QQ channel config.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QQConfig {
+    #[serde(default)]
+    pub app_id: String,
+    #[serde(default)]
+    pub token: String,
+    #[serde(default)]
+    pub app_secret: String,
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+}
+
+/// File-drop channel config.
+///
+/// Watches a directory: each dropped `.txt`/`.md` file becomes a message
+/// and the reply is written next to it as `<name>.reply.md`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileDropConfig {
+    /// Directory to watch (empty = disabled). Supports `~`.
+    #[serde(default)]
+    pub dir: String,
+    /// How often the directory is scanned, in milliseconds (default 1000).
+    #[serde(default = "default_filedrop_poll_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_filedrop_poll_ms() -> u64 { 1000 }
+
+impl Default for FileDropConfig {
+    fn default() -> Self {
+        Self {
+            dir: String::new(),
+            poll_interval_ms: 1000,
+        }
+    }
+}
+
+/// Mochat channel config.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MochatConfig {
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    #[serde(default)]
+    pub mention: MochatMentionConfig,
+    #[serde(default)]
+    pub groups: HashMap<String, MochatGroupRule>,
+}
+
+/// Mochat mention settings.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MochatMentionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Mochat group rule.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MochatGroupRule {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub mention_only: bool,
+}
+
+// ─────────────────────────────────────────────
+// Tokenizer
+// ─────────────────────────────────────────────
+
+/// Token counting.
+///
+/// Tokenizer files (`<name>.tiktoken`) are looked up in `dir`. `models`
+/// maps a model (with or without its `provider/` prefix) to a tokenizer
+/// name or file path; other models use their provider's default. Models
+/// without an available file get a character-based estimate.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TokenizerConfig {
+    /// Directory holding tokenizer files (default `~/.oxibot/tokenizers`).
+    pub dir: String,
+    /// Model → tokenizer name or path (e.g. `"gpt-4o": "o200k_base"`).
+    pub models: HashMap<String, String>,
+}
+
+impl Default for TokenizerConfig {
+    fn default() -> Self {
+        Self {
+            dir: "~/.oxibot/tokenizers".into(),
+            models: HashMap::new(),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Logging
+// ─────────────────────────────────────────────
+
+/// Log output.
+///
+/// `level` and `targets` are `tracing` filter directives; `RUST_LOG`
+/// overrides both. `-v` on the command line switches the base level to
+/// debug for oxibot's own crates.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingConfig {
+    /// Base level: `"error"`, `"warn"`, `"info"`, `"debug"` or `"trace"`.
+    pub level: String,
+    /// Per-target levels (e.g. `"oxibot_channels::discord": "trace"`).
+    pub targets: HashMap<String, String>,
+    /// `"text"` (default) or `"json"` (one object per line).
+    pub format: String,
+    /// Also write logs to rotating files.
+    pub file: LogFileConfig,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "warn".into(),
+            targets: HashMap::new(),
+            format: "text".into(),
+            file: LogFileConfig::default(),
+        }
+    }
+}
+
+/// Rotating log files (`oxibot.<date>.log`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogFileConfig {
+    pub enabled: bool,
+    /// Directory for log files.
+    pub dir: String,
+    /// Start a new file `"hourly"`, `"daily"` (default) or `"never"`.
+    pub rotation: String,
+    /// Rotated files to keep (0 = keep all).
+    pub max_files: usize,
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "~/.oxibot/logs".into(),
+            rotation: "daily".into(),
+            max_files: 7,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Workspace sync
+// ─────────────────────────────────────────────
+
+/// Mirror the workspace to remote storage, so several machines share the
+/// same memory, skills and notes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncConfig {
+    pub enabled: bool,
+    /// `"webdav"` or `"s3"`.
+    pub backend: String,
+    /// Seconds between checks for changes (default 60).
+    pub interval_secs: u64,
+    /// Paths left out: `"dir/"` for a directory, `"*.ext"` for an
+    /// extension, anything else for one file.
+    pub exclude: Vec<String>,
+    pub webdav: WebDavSyncConfig,
+    pub s3: S3SyncConfig,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: "webdav".into(),
+            interval_secs: 60,
+            exclude: vec!["sessions/".into(), ".git/".into(), "*.tmp".into()],
+            webdav: WebDavSyncConfig::default(),
+            s3: S3SyncConfig::default(),
+        }
+    }
 }
 
-fn default_imap_port() -> u16 { 993 }
-fn default_smtp_port() -> u16 { 587 }
-fn default_imap_mailbox() -> String { "INBOX".to_string() }
-fn default_poll_interval() -> u32 { 30 }
-fn default_max_body_chars() -> u32 { 12000 }
-fn default_subject_prefix() -> String { "Re: ".to_string() }
+/// A WebDAV folder (Nextcloud, ownCloud, Apache `mod_dav`, ...).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebDavSyncConfig {
+    /// Folder URL, e.g. `https://cloud.example.com/remote.php/dav/files/ana/oxibot/`.
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
 
-impl Default for EmailConfig {
+/// An S3 bucket (AWS, MinIO, Cloudflare R2, ...).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct S3SyncConfig {
+    pub bucket: String,
+    /// Key prefix for the workspace files (e.g. `"oxibot/"`).
+    pub prefix: String,
+    pub region: String,
+    /// Endpoint URL (empty = AWS for `region`).
+    pub endpoint: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+impl Default for S3SyncConfig {
     fn default() -> Self {
         Self {
-            imap_host: String::new(),
-            imap_port: 993,
-            imap_username: String::new(),
-            imap_password: String::new(),
-            imap_mailbox: "INBOX".to_string(),
-            imap_use_ssl: true,
-            smtp_host: String::new(),
-            smtp_port: 587,
-            smtp_username: String::new(),
-            smtp_password: String::new(),
-            smtp_use_tls: true,
-            smtp_use_ssl: false,
-            from_address: String::new(),
-            poll_interval_seconds: 30,
-            mark_seen: true,
-            max_body_chars: 12000,
-            subject_prefix: "Re: ".to_string(),
-            allowed_users: Vec::new(),
+            bucket: String::new(),
+            prefix: String::new(),
+            region: "us-east-1".into(),
+            endpoint: String::new(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
         }
     }
 }
 
-/// QQ channel config.
+// ─────────────────────────────────────────────
+// Secret redaction
+// ─────────────────────────────────────────────
+
+/// Replace secrets with placeholders before content is sent to LLM
+/// providers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Use the built-in patterns (API keys, private keys, tokens, and
+    /// `password=...`-style assignments).
+    pub builtin: bool,
+    /// Extra regexes. When a pattern has a `secret` group, only that part
+    /// is replaced, e.g. `(?i)pin:\s*(?P<secret>\d{4,})`.
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            builtin: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Skills
+// ─────────────────────────────────────────────
+
+/// Skills configuration.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
-pub struct QQConfig {
-    #[serde(default)]
-    pub app_id: String,
-    #[serde(default)]
-    pub token: String,
+pub struct SkillsConfig {
+    /// Secrets per skill: skill name → env var → value. A variable is
+    /// passed to `exec` only when the skill's `SKILL.md` declares it in
+    /// `requires.env`, and only in conversations where the skill is in use.
+    pub secrets: HashMap<String, HashMap<String, String>>,
+}
+
+// ─────────────────────────────────────────────
+// Analytics
+// ─────────────────────────────────────────────
+
+/// Usage analytics configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnalyticsConfig {
+    /// Record messages, LLM calls and tool calls to `~/.oxibot/usage.jsonl`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Prices per model in USD per million tokens (for cost reports).
     #[serde(default)]
-    pub app_secret: String,
+    pub prices: HashMap<String, ModelPrice>,
+    /// Log every LLM response and tool result to `~/.oxibot/transcripts/`,
+    /// for `oxibot sessions replay`. Off by default: tool output can be large
+    /// and sensitive.
     #[serde(default)]
-    pub allowed_users: Vec<String>,
+    pub transcripts: bool,
 }
 
-/// Mochat channel config.
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            prices: HashMap::new(),
+            transcripts: false,
+        }
+    }
+}
+
+/// Model price in USD per million tokens.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
-pub struct MochatConfig {
-    #[serde(default)]
-    pub url: String,
-    #[serde(default)]
-    pub allowed_users: Vec<String>,
+pub struct ModelPrice {
+    /// Price per million prompt tokens.
     #[serde(default)]
-    pub mention: MochatMentionConfig,
+    pub input: f64,
+    /// Price per million completion tokens.
     #[serde(default)]
-    pub groups: HashMap<String, MochatGroupRule>,
+    pub output: f64,
 }
 
-/// Mochat mention settings.
+// ─────────────────────────────────────────────
+// OAuth
+// ─────────────────────────────────────────────
+
+/// OAuth2 clients that tools and channels can get access tokens for.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
-pub struct MochatMentionConfig {
-    #[serde(default)]
-    pub enabled: bool,
+pub struct OAuthConfig {
+    /// Clients by name (e.g. "google", "microsoft").
+    pub clients: HashMap<String, OAuthClientConfig>,
+    /// Redirect URL registered with every client
+    /// (empty = `http://localhost:<gateway.port>/oauth/callback`).
+    pub redirect_url: String,
 }
 
-/// Mochat group rule.
+impl OAuthConfig {
+    /// Redirect URL for the authorization-code flow.
+    pub fn redirect_url(&self, gateway_port: u16) -> String {
+        if self.redirect_url.is_empty() {
+            format!("http://localhost:{gateway_port}/oauth/callback")
+        } else {
+            self.redirect_url.clone()
+        }
+    }
+}
+
+/// One OAuth2 client (authorization-code flow with PKCE).
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
-pub struct MochatGroupRule {
-    #[serde(default)]
-    pub enabled: bool,
-    #[serde(default)]
-    pub mention_only: bool,
+pub struct OAuthClientConfig {
+    /// Authorization endpoint.
+    pub auth_url: String,
+    /// Token endpoint.
+    pub token_url: String,
+    pub client_id: String,
+    /// Client secret (empty for public clients).
+    pub client_secret: String,
+    pub scopes: Vec<String>,
+    /// Extra authorization parameters (e.g. `"access_type": "offline"`).
+    pub extra_params: HashMap<String, String>,
 }
 
 // ─────────────────────────────────────────────
@@ -492,7 +1997,7 @@ pub struct MochatGroupRule {
 // ─────────────────────────────────────────────
 
 /// Tool configuration.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct ToolsConfig {
     /// Web tools configuration (search, fetch).
@@ -504,16 +2009,27 @@ pub struct ToolsConfig {
     /// Whether to restrict file/exec operations to the workspace directory.
     #[serde(default)]
     pub restrict_to_workspace: bool,
-}
-
-impl Default for ToolsConfig {
-    fn default() -> Self {
-        Self {
-            web: WebToolsConfig::default(),
-            exec: ExecToolConfig::default(),
-            restrict_to_workspace: false,
-        }
-    }
+    /// Per-session scratch directories (`workspace/sessions/<key>/`).
+    #[serde(default)]
+    pub scratch: ScratchConfig,
+    /// Preview `write_file`, `edit_file` and `exec` calls instead of running them.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Calendar tools (`calendar_list_events`, `calendar_create_event`).
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    /// Headless browser tool (`browser_fetch`, needs the `browser` feature).
+    #[serde(default)]
+    pub browser: BrowserToolConfig,
+    /// Workspace file index behind the `find_files` tool.
+    #[serde(default)]
+    pub file_index: FileIndexConfig,
+    /// GitHub tools (`github_notifications`, `github_pr_diff`, ...).
+    #[serde(default)]
+    pub github: GitHubToolConfig,
+    /// Where completed forms may be sent (`form` tool).
+    #[serde(default)]
+    pub forms: FormsToolConfig,
 }
 
 /// Web tools configuration.
@@ -522,9 +2038,35 @@ impl Default for ToolsConfig {
 pub struct WebToolsConfig {
     #[serde(default)]
     pub search: WebSearchConfig,
+    /// On-disk cache of `web_fetch` extractions, searched by `recall_page`.
+    #[serde(default)]
+    pub page_cache: PageCacheConfig,
+}
+
+/// Cache of fetched web pages (cleaned text + embeddings).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PageCacheConfig {
+    /// Cache `web_fetch` results and offer the `recall_page` tool (default true).
+    pub enabled: bool,
+    /// How long a cached page is served before it is fetched again (default 24).
+    pub ttl_hours: u64,
+    /// Embedding model for `recall_page`, e.g. `"openai/text-embedding-3-small"`
+    /// (empty = a local keyword-hashing embedding, no API calls).
+    pub embedding_model: String,
+}
+
+impl Default for PageCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_hours: 24,
+            embedding_model: String::new(),
+        }
+    }
 }
 
-/// Web search configuration (Brave API).
+/// Web search configuration (Brave API, or the provider's own search).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct WebSearchConfig {
@@ -533,6 +2075,12 @@ pub struct WebSearchConfig {
     pub api_key: String,
     /// Maximum number of search results to return.
     pub max_results: u32,
+    /// Let the model's provider run searches itself when it can (OpenAI,
+    /// OpenRouter, Gemini, Anthropic), with Brave as the fallback.
+    pub native: bool,
+    /// Model for provider-side searches (empty = the agent's model), e.g.
+    /// `"gpt-4o-mini-search-preview"`.
+    pub native_model: String,
 }
 
 impl Default for WebSearchConfig {
@@ -540,6 +2088,8 @@ impl Default for WebSearchConfig {
         Self {
             api_key: String::new(),
             max_results: 5,
+            native: false,
+            native_model: String::new(),
         }
     }
 }
@@ -550,11 +2100,161 @@ impl Default for WebSearchConfig {
 pub struct ExecToolConfig {
     /// Timeout in seconds for shell commands.
     pub timeout: u64,
+    /// Ask in chat before running a dangerous command instead of blocking it.
+    pub ask_approval: bool,
+    /// Seconds to wait for an answer before treating it as denied.
+    pub approval_timeout: u64,
 }
 
 impl Default for ExecToolConfig {
     fn default() -> Self {
-        Self { timeout: 60 }
+        Self {
+            timeout: 60,
+            ask_approval: false,
+            approval_timeout: 300,
+        }
+    }
+}
+
+/// Session scratch directory configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScratchConfig {
+    /// Whether relative file paths resolve into a per-session scratch directory.
+    pub enabled: bool,
+    /// Remove scratch directories unused for this many hours.
+    pub retention_hours: u64,
+}
+
+impl Default for ScratchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_hours: 168,
+        }
+    }
+}
+
+/// Calendar tools configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CalendarConfig {
+    /// Calendar backend: "caldav", "google" or empty to disable the tools.
+    pub backend: String,
+    /// CalDAV calendar collection URL.
+    pub url: String,
+    /// CalDAV username (empty = no authentication).
+    pub username: String,
+    /// CalDAV password or app password.
+    pub password: String,
+    /// OAuth client (from `oauth.clients`) used for Google Calendar.
+    pub oauth_client: String,
+    /// Google calendar ID.
+    pub calendar_id: String,
+    /// Timezone used when USER.md names none (IANA name, e.g. "Europe/Madrid").
+    pub timezone: String,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            backend: String::new(),
+            url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            oauth_client: "google".into(),
+            calendar_id: "primary".into(),
+            timezone: "UTC".into(),
+        }
+    }
+}
+
+/// GitHub REST API access for the `github_*` tools.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GitHubToolConfig {
+    /// Personal access token (falls back to `GITHUB_TOKEN`; the tools are
+    /// off when neither is set).
+    pub token: String,
+    /// API root, for GitHub Enterprise (default "https://api.github.com").
+    pub api_base: String,
+}
+
+impl Default for GitHubToolConfig {
+    fn default() -> Self {
+        Self {
+            token: String::new(),
+            api_base: "https://api.github.com".into(),
+        }
+    }
+}
+
+/// Headless browser tool configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BrowserToolConfig {
+    /// Whether to register the `browser_fetch` tool.
+    pub enabled: bool,
+    /// Chrome/Chromium executable (empty = auto-detect).
+    pub chrome_path: String,
+    /// Domains pages may be loaded from, including subdomains (empty = any).
+    pub allowed_domains: Vec<String>,
+    /// Limit for loading a page and waiting for a selector, in seconds.
+    pub timeout_secs: u64,
+    /// Default maximum characters of page text returned.
+    pub max_chars: usize,
+    /// Run Chromium without its sandbox (needed as root, e.g. in Docker).
+    pub no_sandbox: bool,
+}
+
+impl Default for BrowserToolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chrome_path: String::new(),
+            allowed_domains: Vec::new(),
+            timeout_secs: 30,
+            max_chars: 50_000,
+            no_sandbox: false,
+        }
+    }
+}
+
+/// Form delivery configuration (`form` tool).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormsToolConfig {
+    /// Webhooks completed forms can be POSTed to, by name. A form names
+    /// one of these; the URL never comes from the agent.
+    pub webhooks: HashMap<String, String>,
+}
+
+/// Workspace file index configuration (`find_files` tool).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileIndexConfig {
+    /// Whether to register the `find_files` tool.
+    pub enabled: bool,
+    /// Also index file contents (trigrams), so `contains` searches only
+    /// read likely matches instead of every file.
+    pub content: bool,
+    /// Seconds between background rescans of the workspace.
+    pub refresh_secs: u64,
+    /// Larger files are not content-indexed, in KB.
+    pub max_file_kb: u64,
+    /// Paths left out: `dir/` (at any depth), `*.ext`, or exact paths.
+    pub exclude: Vec<String>,
+}
+
+impl Default for FileIndexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            content: false,
+            refresh_secs: 30,
+            max_file_kb: 256,
+            exclude: vec![".git/".into(), "node_modules/".into(), "target/".into()],
+        }
     }
 }
 
@@ -579,10 +2279,49 @@ pub struct TranscriptionConfig {
     /// Whisper model name.
     #[serde(default = "default_whisper_model")]
     pub model: String,
+    /// Path to the `ffmpeg` binary used to transcode and chunk audio.
+    /// If neither ffmpeg nor ffprobe is available, audio is uploaded as-is.
+    #[serde(default = "default_ffmpeg")]
+    pub ffmpeg_path: String,
+    /// Path to the `ffprobe` binary used to read audio duration.
+    #[serde(default = "default_ffprobe")]
+    pub ffprobe_path: String,
+    /// Maximum length of a single uploaded chunk, in seconds.
+    #[serde(default = "default_chunk_secs")]
+    pub chunk_secs: u64,
+    /// Overlap between consecutive chunks, in seconds.
+    #[serde(default = "default_chunk_overlap_secs")]
+    pub chunk_overlap_secs: u64,
+    /// Reuse transcripts of identical audio (keyed by content hash) instead
+    /// of transcribing forwarded voice notes again.
+    #[serde(default = "default_true")]
+    pub cache: bool,
+    /// Spoken language as an ISO-639-1 code (`"es"`). Empty = take it from
+    /// the `Language` line of `USER.md`; `"auto"` = let Whisper detect it.
+    #[serde(default)]
+    pub language: String,
+    /// Sampling temperature (0 = provider default).
+    #[serde(default)]
+    pub temperature: f64,
+    /// Request word-level timestamps.
+    #[serde(default)]
+    pub word_timestamps: bool,
+    /// Append `[low-confidence transcription]` to transcripts whose
+    /// confidence is below `lowConfidenceThreshold`.
+    #[serde(default)]
+    pub mark_low_confidence: bool,
+    /// Confidence (0–1) below which a transcript counts as low-confidence.
+    #[serde(default = "default_low_confidence_threshold")]
+    pub low_confidence_threshold: f64,
 }
 
 fn default_groq() -> String { "groq".into() }
 fn default_whisper_model() -> String { "whisper-large-v3".into() }
+fn default_ffmpeg() -> String { "ffmpeg".into() }
+fn default_ffprobe() -> String { "ffprobe".into() }
+fn default_chunk_secs() -> u64 { 600 }
+fn default_chunk_overlap_secs() -> u64 { 5 }
+fn default_low_confidence_threshold() -> f64 { 0.6 }
 
 impl Default for TranscriptionConfig {
     fn default() -> Self {
@@ -591,6 +2330,16 @@ impl Default for TranscriptionConfig {
             provider: "groq".into(),
             api_key: String::new(),
             model: "whisper-large-v3".into(),
+            ffmpeg_path: default_ffmpeg(),
+            ffprobe_path: default_ffprobe(),
+            chunk_secs: default_chunk_secs(),
+            chunk_overlap_secs: default_chunk_overlap_secs(),
+            cache: true,
+            language: String::new(),
+            temperature: 0.0,
+            word_timestamps: false,
+            mark_low_confidence: false,
+            low_confidence_threshold: default_low_confidence_threshold(),
         }
     }
 }
@@ -603,6 +2352,11 @@ pub struct GatewayConfig {
     pub host: String,
     /// Listen port.
     pub port: u16,
+    /// Bearer token with the admin scope (empty = none). API keys from
+    /// `oxibot apikeys create` work as well.
+    pub admin_token: String,
+    /// JWTs accepted as bearer credentials.
+    pub jwt: JwtConfig,
 }
 
 impl Default for GatewayConfig {
@@ -610,10 +2364,23 @@ impl Default for GatewayConfig {
         Self {
             host: "0.0.0.0".to_string(),
             port: 18790,
+            admin_token: String::new(),
+            jwt: JwtConfig::default(),
         }
     }
 }
 
+/// HS256 JWTs accepted by the gateway API. The `scope` claim (`chat`
+/// and/or `admin`, space-separated) sets what the token may do.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct JwtConfig {
+    /// Shared signing secret (empty = JWTs not accepted).
+    pub secret: String,
+    /// Required `iss` claim (empty = any issuer).
+    pub issuer: String,
+}
+
 // ─────────────────────────────────────────────
 // Tests
 // ─────────────────────────────────────────────
@@ -632,6 +2399,29 @@ mod tests {
         assert!(!config.tools.restrict_to_workspace);
     }
 
+    #[test]
+    fn test_agent_bindings() {
+        let json = serde_json::json!({
+            "agents": {
+                "profiles": {
+                    "support": { "model": "gpt-4o-mini", "readOnly": true },
+                    "full": {}
+                },
+                "bindings": [
+                    { "channel": "slack", "agent": "full" },
+                    { "channel": "slack", "chatId": "C_SUPPORT", "agent": "support" }
+                ]
+            }
+        });
+        let config: Config = serde_json::from_value(json).unwrap();
+        let agents = &config.agents;
+        assert!(agents.profiles["support"].read_only);
+        assert_eq!(agents.profiles["support"].model, "gpt-4o-mini");
+        assert_eq!(agents.binding_for("slack", "C_SUPPORT"), Some("support"));
+        assert_eq!(agents.binding_for("slack", "C_OTHER"), Some("full"));
+        assert_eq!(agents.binding_for("telegram", "C_SUPPORT"), None);
+    }
+
     #[test]
     fn test_config_from_json_camel_case() {
         let json = serde_json::json!({
@@ -694,6 +2484,22 @@ mod tests {
         assert!(with_key.is_configured());
     }
 
+    #[test]
+    fn test_request_policy_defaults_and_overrides() {
+        let config: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "apiKey": "sk-1",
+            "request": { "timeoutSecs": 30, "breakerThreshold": 0 }
+        }))
+        .unwrap();
+        assert_eq!(config.request.timeout_secs, 30);
+        assert_eq!(config.request.breaker_threshold, 0);
+        assert_eq!(config.request.max_retries, 2);
+
+        // Default policies are left out of saved configs
+        let json = serde_json::to_value(ProviderConfig::default()).unwrap();
+        assert!(json.get("request").is_none());
+    }
+
     #[test]
     fn test_providers_get_by_name() {
         let mut providers = ProvidersConfig::default();
@@ -704,6 +2510,30 @@ mod tests {
         assert!(providers.get_by_name("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_custom_providers_from_json() {
+        let json = serde_json::json!({
+            "providers": {
+                "custom": [{
+                    "name": "lmstudio",
+                    "apiBase": "http://localhost:1234/v1",
+                    "modelPrefixes": ["lmstudio/"]
+                }]
+            }
+        });
+        let config: Config = serde_json::from_value(json).unwrap();
+        let custom = config.providers.get_custom("lmstudio").unwrap();
+        assert_eq!(custom.auth_header, "bearer");
+        assert_eq!(custom.model_prefixes, vec!["lmstudio/"]);
+
+        let map = config.providers.to_map();
+        assert_eq!(
+            map["lmstudio"].api_base.as_deref(),
+            Some("http://localhost:1234/v1")
+        );
+        assert_eq!(map.len(), 13);
+    }
+
     #[test]
     fn test_partial_json_uses_defaults() {
         let json = serde_json::json!({
@@ -737,6 +2567,11 @@ mod tests {
                     "dm": {
                         "enabled": true
                     }
+                },
+                "supervisor": {
+                    "alertChannel": "telegram",
+                    "alertChatId": "42",
+                    "maxSilenceSecs": 0
                 }
             }
         });
@@ -746,6 +2581,36 @@ mod tests {
         assert_eq!(config.channels.telegram.allowed_users, vec!["user1", "user2"]);
         assert_eq!(config.channels.slack.bot_token, "xoxb-123");
         assert!(config.channels.slack.dm.enabled);
+        let supervisor = &config.channels.supervisor;
+        assert!(supervisor.enabled);
+        assert_eq!(supervisor.alert_channel, "telegram");
+        assert_eq!(supervisor.alert_chat_id, "42");
+        assert_eq!(supervisor.max_silence_secs, 0);
+        assert_eq!(supervisor.initial_backoff_ms, 1000);
+        assert_eq!(supervisor.alert_after, 3);
+    }
+
+    #[test]
+    fn test_oauth_config_from_json() {
+        let json = serde_json::json!({
+            "oauth": {
+                "clients": {
+                    "google": {
+                        "authUrl": "https://accounts.google.com/o/oauth2/v2/auth",
+                        "tokenUrl": "https://oauth2.googleapis.com/token",
+                        "clientId": "abc.apps.googleusercontent.com",
+                        "scopes": ["https://www.googleapis.com/auth/calendar"],
+                        "extraParams": { "access_type": "offline" }
+                    }
+                }
+            }
+        });
+        let config: Config = serde_json::from_value(json).unwrap();
+        let google = &config.oauth.clients["google"];
+        assert_eq!(google.client_id, "abc.apps.googleusercontent.com");
+        assert!(google.client_secret.is_empty());
+        assert_eq!(google.extra_params["access_type"], "offline");
+        assert_eq!(config.oauth.redirect_url(18790), "http://localhost:18790/oauth/callback");
     }
 
     #[test]
@@ -770,6 +2635,8 @@ mod tests {
         assert_eq!(config.tools.web.search.max_results, 10);
         assert_eq!(config.tools.exec.timeout, 120);
         assert!(config.tools.restrict_to_workspace);
+        assert!(config.tools.scratch.enabled);
+        assert_eq!(config.tools.scratch.retention_hours, 168);
     }
 
     #[test]
@@ -779,4 +2646,14 @@ mod tests {
         assert_eq!(config.agents.defaults.max_tokens, 8192);
         assert_eq!(config.gateway.port, 18790);
     }
+
+    #[test]
+    fn test_transcription_chunking_config() {
+        let json = r#"{"transcription": {"chunkSecs": 300, "ffmpegPath": "/usr/local/bin/ffmpeg"}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.transcription.chunk_secs, 300);
+        assert_eq!(config.transcription.chunk_overlap_secs, 5);
+        assert_eq!(config.transcription.ffmpeg_path, "/usr/local/bin/ffmpeg");
+        assert_eq!(config.transcription.ffprobe_path, "ffprobe");
+    }
 }
@@ -0,0 +1,322 @@
+//! Contact book — map people to their identities across channels.
+//!
+//! A contact has a name and a list of channel identities such as
+//! `telegram:123`, `slack:U45` or `email:alice@x.com`. The agent uses it to
+//! recognize who it is talking to on any channel and to resolve addresses
+//! ("email Alice the notes").
+//!
+//! Stored as JSON in `~/.oxibot/contacts.json`. The file is re-read on every
+//! use so edits from the CLI are picked up by a running gateway.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::utils;
+
+// ─────────────────────────────────────────────
+// Contact
+// ─────────────────────────────────────────────
+
+/// A person and their identities on each channel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Display name (unique, case-insensitive).
+    pub name: String,
+    /// Channel identities, `channel:id` (e.g. `telegram:123`).
+    #[serde(default)]
+    pub ids: Vec<String>,
+    /// Free-form notes and preferences, shared across channels.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+impl Contact {
+    /// Identity on a given channel, without the `channel:` prefix.
+    pub fn id_on(&self, channel: &str) -> Option<&str> {
+        self.ids.iter().find_map(|id| {
+            let (ch, value) = id.split_once(':')?;
+            ch.eq_ignore_ascii_case(channel).then_some(value)
+        })
+    }
+
+    /// Whether this contact owns the sender on `channel`.
+    ///
+    /// Sender ids like `123|alice` match on any `|`-separated part.
+    pub fn matches_sender(&self, channel: &str, sender_id: &str) -> bool {
+        self.ids.iter().any(|id| {
+            let Some((ch, value)) = id.split_once(':') else {
+                return false;
+            };
+            ch.eq_ignore_ascii_case(channel)
+                && (value.eq_ignore_ascii_case(sender_id)
+                    || sender_id
+                        .split('|')
+                        .any(|part| !part.is_empty() && value.eq_ignore_ascii_case(part)))
+        })
+    }
+
+    /// One-line summary: `Alice — telegram:123, email:alice@x.com`.
+    pub fn summary(&self) -> String {
+        let ids = if self.ids.is_empty() {
+            "(no ids)".to_string()
+        } else {
+            self.ids.join(", ")
+        };
+        let mut line = format!("{} — {ids}", self.name);
+        if !self.notes.is_empty() {
+            line.push_str(&format!(" ({})", self.notes));
+        }
+        line
+    }
+}
+
+/// Normalize an identity to `channel:id`.
+///
+/// A bare address containing `@` is treated as `email:`. Returns `None` for
+/// anything else without a channel prefix.
+pub fn normalize_id(id: &str) -> Option<String> {
+    let id = id.trim();
+    match id.split_once(':') {
+        Some((ch, value)) if !ch.is_empty() && !value.is_empty() && !ch.contains('@') => {
+            let ch = ch.to_lowercase();
+            let value = if ch == "email" { value.to_lowercase() } else { value.to_string() };
+            Some(format!("{ch}:{value}"))
+        }
+        _ if id.contains('@') && !id.contains(char::is_whitespace) => {
+            Some(format!("email:{}", id.to_lowercase()))
+        }
+        _ => None,
+    }
+}
+
+// ─────────────────────────────────────────────
+// ContactBook
+// ─────────────────────────────────────────────
+
+/// The set of known contacts, backed by a JSON file.
+#[derive(Debug, Clone, Default)]
+pub struct ContactBook {
+    /// Backing file.
+    path: PathBuf,
+    /// Contacts in insertion order.
+    contacts: Vec<Contact>,
+}
+
+impl ContactBook {
+    /// Load the contact book from `path` (default `~/.oxibot/contacts.json`).
+    ///
+    /// A missing or unreadable file yields an empty book.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let path = path.unwrap_or_else(utils::get_contacts_path);
+        let contacts = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "invalid contacts file");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { path, contacts }
+    }
+
+    /// Write the contact book back to disk.
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.contacts)?)?;
+        Ok(())
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All contacts.
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    /// Find a contact by name (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&Contact> {
+        self.contacts
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Find the contact owning `sender_id` on `channel`.
+    pub fn resolve_sender(&self, channel: &str, sender_id: &str) -> Option<&Contact> {
+        self.contacts
+            .iter()
+            .find(|c| c.matches_sender(channel, sender_id))
+    }
+
+    /// Add identities to a contact, creating it if needed.
+    ///
+    /// An identity already linked to a different contact is moved.
+    pub fn link(&mut self, name: &str, ids: &[String]) -> anyhow::Result<&Contact> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("contact name must not be empty");
+        }
+        let ids = ids
+            .iter()
+            .map(|id| {
+                normalize_id(id).ok_or_else(|| {
+                    anyhow::anyhow!("invalid id '{id}' (expected channel:id or an email address)")
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for contact in &mut self.contacts {
+            if !contact.name.eq_ignore_ascii_case(name) {
+                contact.ids.retain(|existing| !ids.contains(existing));
+            }
+        }
+
+        let idx = match self.contacts.iter().position(|c| c.name.eq_ignore_ascii_case(name)) {
+            Some(idx) => idx,
+            None => {
+                self.contacts.push(Contact {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.contacts.len() - 1
+            }
+        };
+        let contact = &mut self.contacts[idx];
+        for id in ids {
+            if !contact.ids.contains(&id) {
+                contact.ids.push(id);
+            }
+        }
+        Ok(&self.contacts[idx])
+    }
+
+    /// Remove one identity from a contact. Returns whether it was linked.
+    pub fn unlink(&mut self, name: &str, id: &str) -> bool {
+        let Some(id) = normalize_id(id) else {
+            return false;
+        };
+        let Some(contact) = self
+            .contacts
+            .iter_mut()
+            .find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+        else {
+            return false;
+        };
+        let before = contact.ids.len();
+        contact.ids.retain(|existing| *existing != id);
+        contact.ids.len() != before
+    }
+
+    /// Replace a contact's notes. Returns `false` if the contact is unknown.
+    pub fn set_notes(&mut self, name: &str, notes: &str) -> bool {
+        match self
+            .contacts
+            .iter_mut()
+            .find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+        {
+            Some(contact) => {
+                contact.notes = notes.trim().to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a contact. Returns whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.contacts.len();
+        self.contacts
+            .retain(|c| !c.name.eq_ignore_ascii_case(name.trim()));
+        self.contacts.len() != before
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_id() {
+        assert_eq!(normalize_id("telegram:123").unwrap(), "telegram:123");
+        assert_eq!(normalize_id("Slack:U45").unwrap(), "slack:U45");
+        assert_eq!(normalize_id("Alice@X.com").unwrap(), "email:alice@x.com");
+        assert_eq!(normalize_id("email:Alice@X.com").unwrap(), "email:alice@x.com");
+        assert!(normalize_id("alice").is_none());
+        assert!(normalize_id("telegram:").is_none());
+    }
+
+    #[test]
+    fn test_link_and_resolve() {
+        let mut book = ContactBook::default();
+        book.link("Alice", &ids(&["telegram:123", "slack:U45", "alice@x.com"]))
+            .unwrap();
+
+        let alice = book.resolve_sender("telegram", "123|alice_tg").unwrap();
+        assert_eq!(alice.name, "Alice");
+        assert_eq!(book.resolve_sender("email", "ALICE@x.com").unwrap().name, "Alice");
+        assert!(book.resolve_sender("slack", "U99").is_none());
+        assert!(book.resolve_sender("discord", "123").is_none());
+
+        assert_eq!(book.get("alice").unwrap().id_on("email"), Some("alice@x.com"));
+        assert_eq!(book.get("alice").unwrap().id_on("whatsapp"), None);
+    }
+
+    #[test]
+    fn test_link_moves_id_between_contacts() {
+        let mut book = ContactBook::default();
+        book.link("Alice", &ids(&["telegram:1"])).unwrap();
+        book.link("Bob", &ids(&["telegram:1"])).unwrap();
+        assert!(book.get("Alice").unwrap().ids.is_empty());
+        assert_eq!(book.resolve_sender("telegram", "1").unwrap().name, "Bob");
+    }
+
+    #[test]
+    fn test_link_rejects_invalid_id() {
+        let mut book = ContactBook::default();
+        assert!(book.link("Alice", &ids(&["nonsense"])).is_err());
+        assert!(book.link(" ", &ids(&["telegram:1"])).is_err());
+        assert!(book.contacts().is_empty());
+    }
+
+    #[test]
+    fn test_unlink_notes_remove() {
+        let mut book = ContactBook::default();
+        book.link("Alice", &ids(&["telegram:1", "slack:U1"])).unwrap();
+        assert!(book.unlink("alice", "telegram:1"));
+        assert!(!book.unlink("alice", "telegram:1"));
+        assert!(book.set_notes("ALICE", "Prefers short answers"));
+        assert!(!book.set_notes("Carol", "x"));
+        assert_eq!(
+            book.get("Alice").unwrap().summary(),
+            "Alice — slack:U1 (Prefers short answers)"
+        );
+        assert!(book.remove("alice"));
+        assert!(!book.remove("alice"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contacts.json");
+        let mut book = ContactBook::load(Some(path.clone()));
+        assert!(book.contacts().is_empty());
+        book.link("Alice", &ids(&["telegram:123"])).unwrap();
+        book.save().unwrap();
+
+        let loaded = ContactBook::load(Some(path));
+        assert_eq!(loaded.contacts(), book.contacts());
+    }
+}
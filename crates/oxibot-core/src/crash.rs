@@ -0,0 +1,267 @@
+//! Crash reports — what the gateway was doing when a component panicked.
+//!
+//! [`install_panic_hook`] records the message, location and backtrace of
+//! every panic. When a channel, the outbound dispatcher or an agent turn
+//! dies with a panic, [`CrashReporter::report`] writes them to
+//! `~/.oxibot/crash/` as JSON, together with the last bus events before
+//! the crash, and tells the admin chat. Restarting the component is up to
+//! the caller.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Once, OnceLock};
+
+use chrono::Utc;
+use serde::Serialize;
+use tracing::{error, warn};
+
+use crate::bus::events::BusEvent;
+use crate::bus::queue::MessageBus;
+use crate::bus::types::OutboundMessage;
+use crate::utils;
+
+/// Bus events kept for the next crash report.
+const RECENT_EVENTS: usize = 50;
+
+/// Panics kept until a report claims them.
+const RECENT_PANICS: usize = 16;
+
+/// Longest event summary in a report.
+const MAX_EVENT_CHARS: usize = 200;
+
+/// A panic as seen by the panic hook.
+#[derive(Clone, Debug)]
+struct PanicRecord {
+    message: String,
+    location: Option<String>,
+    thread: String,
+    backtrace: String,
+}
+
+fn recent_panics() -> &'static Mutex<VecDeque<PanicRecord>> {
+    static PANICS: OnceLock<Mutex<VecDeque<PanicRecord>>> = OnceLock::new();
+    PANICS.get_or_init(Mutex::default)
+}
+
+/// Record every panic for crash reports. The previous hook (which prints
+/// the panic) still runs. Installing it more than once has no effect.
+pub fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let record = PanicRecord {
+                message: panic_message(info.payload()),
+                location: info.location().map(ToString::to_string),
+                thread: std::thread::current().name().unwrap_or("unnamed").to_string(),
+                backtrace: Backtrace::force_capture().to_string(),
+            };
+            let mut panics = recent_panics().lock().unwrap_or_else(|e| e.into_inner());
+            if panics.len() == RECENT_PANICS {
+                panics.pop_front();
+            }
+            panics.push_back(record);
+            drop(panics);
+            previous(info);
+        }));
+    });
+}
+
+/// The message of a panic payload (`panic!` arguments).
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// The latest recorded panic with `message`, removed from the list.
+fn take_panic(message: &str) -> Option<PanicRecord> {
+    let mut panics = recent_panics().lock().unwrap_or_else(|e| e.into_inner());
+    let index = panics.iter().rposition(|p| p.message == message)?;
+    panics.remove(index)
+}
+
+/// A crash report as written to disk.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    /// When the crash was reported (RFC 3339).
+    pub time: String,
+    /// What crashed, e.g. `channel telegram`.
+    pub component: String,
+    /// The panic message.
+    pub message: String,
+    /// Source location of the panic, if the hook saw it.
+    pub location: Option<String>,
+    /// Thread the panic happened on, if the hook saw it.
+    pub thread: Option<String>,
+    /// Backtrace, if the hook saw the panic.
+    pub backtrace: Option<String>,
+    /// The last bus events before the crash, oldest first.
+    pub recent_events: Vec<String>,
+}
+
+/// Writes crash reports and tells the admin chat about them.
+#[derive(Clone)]
+pub struct CrashReporter {
+    bus: Arc<MessageBus>,
+    /// Directory the reports go to.
+    dir: PathBuf,
+    /// `(channel, chat_id)` told about each crash.
+    admin: Option<(String, String)>,
+    /// Summaries of the last bus events.
+    events: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl CrashReporter {
+    /// Create a reporter writing to `dir` (default `~/.oxibot/crash/`).
+    pub fn new(bus: Arc<MessageBus>, dir: Option<PathBuf>) -> Self {
+        Self {
+            bus,
+            dir: dir.unwrap_or_else(utils::get_crash_path),
+            admin: None,
+            events: Arc::default(),
+        }
+    }
+
+    /// Tell `channel`/`chat_id` about each crash (ignored when either is empty).
+    pub fn with_admin(mut self, channel: &str, chat_id: &str) -> Self {
+        self.admin = (!channel.is_empty() && !chat_id.is_empty()).then(|| (channel.to_string(), chat_id.to_string()));
+        self
+    }
+
+    /// Start keeping the last bus events for reports. Only events after
+    /// this call are seen.
+    pub fn watch(&self) {
+        let mut events = self.bus.subscribe(&[]);
+        let recent = self.events.clone();
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let mut recent = recent.lock().unwrap_or_else(|e| e.into_inner());
+                if recent.len() == RECENT_EVENTS {
+                    recent.pop_front();
+                }
+                recent.push_back(summarize(&event));
+            }
+        });
+    }
+
+    /// Write a report for `component`, which died with `payload`, and tell
+    /// the admin chat. Returns the report's path, or `None` if it could
+    /// not be written.
+    pub async fn report(&self, component: &str, payload: Box<dyn Any + Send>) -> Option<PathBuf> {
+        let message = panic_message(&*payload);
+        let record = take_panic(&message);
+        let now = Utc::now();
+        let report = CrashReport {
+            time: now.to_rfc3339(),
+            component: component.to_string(),
+            message: message.clone(),
+            location: record.as_ref().and_then(|r| r.location.clone()),
+            thread: record.as_ref().map(|r| r.thread.clone()),
+            backtrace: record.map(|r| r.backtrace),
+            recent_events: self.events.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect(),
+        };
+
+        let name: String = component
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let path = self.dir.join(format!("{}-{name}.json", now.format("%Y%m%d-%H%M%S%.3f")));
+        let written = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| std::fs::write(&path, serde_json::to_string_pretty(&report).unwrap_or_default()));
+        let path = match written {
+            Ok(()) => {
+                error!(component, message = %message, path = %path.display(), "component crashed, wrote crash report");
+                Some(path)
+            }
+            Err(e) => {
+                error!(component, message = %message, error = %e, "component crashed, failed to write crash report");
+                None
+            }
+        };
+
+        if let Some((channel, chat_id)) = &self.admin {
+            let mut text = format!("💥 `{component}` crashed: {message}");
+            if let Some(ref path) = path {
+                text.push_str(&format!("\nCrash report: {}", path.display()));
+            }
+            if let Err(e) = self.bus.publish_outbound(OutboundMessage::new(channel, chat_id, text).forced().urgent()).await {
+                warn!(error = %e, "failed to publish crash alert");
+            }
+        }
+        path
+    }
+}
+
+/// One line describing a bus event.
+fn summarize(event: &BusEvent) -> String {
+    let text = match event {
+        BusEvent::Inbound(msg) => format!("inbound {}:{} from {}: {}", msg.channel, msg.chat_id, msg.sender_id, msg.content),
+        BusEvent::Outbound(msg) => format!("outbound {}:{}: {}", msg.channel, msg.chat_id, msg.content),
+        BusEvent::Status(status) => format!("status {status:?}"),
+        BusEvent::Agent(agent) => format!("agent {}", serde_json::to_string(agent).unwrap_or_default()),
+    };
+    let mut line = format!("{} {}", Utc::now().format("%H:%M:%S%.3f"), text.replace('\n', " "));
+    if let Some((cut, _)) = line.char_indices().nth(MAX_EVENT_CHARS) {
+        line.truncate(cut);
+        line.push('…');
+    }
+    line
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::types::InboundMessage;
+
+    #[tokio::test]
+    async fn test_report_includes_panic_and_recent_events() {
+        install_panic_hook();
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(MessageBus::new(8));
+        let crashes = CrashReporter::new(bus.clone(), Some(dir.path().to_path_buf())).with_admin("telegram", "admin");
+        crashes.watch();
+
+        bus.publish_inbound(InboundMessage::new("telegram", "u1", "c1", "hello\nthere"))
+            .await
+            .unwrap();
+        bus.consume_inbound().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        let payload = tokio::spawn(async { panic!("boom in test") }).await.unwrap_err().into_panic();
+        let path = crashes.report("channel telegram", payload).await.unwrap();
+        assert!(path.file_name().unwrap().to_str().unwrap().ends_with("-channel-telegram.json"));
+
+        let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(report["component"], "channel telegram");
+        assert_eq!(report["message"], "boom in test");
+        assert!(report["location"].as_str().unwrap().contains("crash.rs"));
+        assert!(!report["backtrace"].as_str().unwrap().is_empty());
+        let events = report["recentEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].as_str().unwrap().ends_with("inbound telegram:c1 from u1: hello there"));
+
+        let alert = bus.consume_outbound().await.unwrap();
+        assert_eq!((alert.channel.as_str(), alert.chat_id.as_str()), ("telegram", "admin"));
+        assert!(alert.content.contains("`channel telegram` crashed: boom in test"));
+        assert!(alert.content.contains(&path.display().to_string()));
+    }
+
+    #[test]
+    fn test_panic_message() {
+        assert_eq!(panic_message(&"static"), "static");
+        assert_eq!(panic_message(&String::from("owned")), "owned");
+        assert_eq!(panic_message(&42), "unknown panic");
+    }
+}
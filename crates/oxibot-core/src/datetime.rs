@@ -0,0 +1,343 @@
+//! Dates and times in the user's timezone.
+//!
+//! Shared by the calendar tools, the `parse_time` tool and the cron
+//! subsystem: the user's timezone (from the workspace `USER.md`), DST-safe
+//! conversion of local times, and parsing of the times people write
+//! ("tuesday at 9", "tomorrow 3pm", "in 2 hours", ISO 8601). Instants are
+//! exchanged as ISO 8601 with an explicit offset.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Weekday};
+pub use chrono_tz::Tz;
+
+/// Time used when only a day is given.
+const DEFAULT_HOUR: u32 = 9;
+
+// ─────────────────────────────────────────────
+// User timezone
+// ─────────────────────────────────────────────
+
+/// The user's timezone, re-read from `USER.md` on every call.
+#[derive(Clone)]
+pub struct UserTimezone {
+    profile: PathBuf,
+    default: Tz,
+}
+
+impl UserTimezone {
+    /// Read the timezone from the profile at `profile`, else use `default`.
+    pub fn new(profile: PathBuf, default: Tz) -> Self {
+        Self { profile, default }
+    }
+
+    /// The current timezone.
+    pub fn get(&self) -> Tz {
+        std::fs::read_to_string(&self.profile)
+            .ok()
+            .and_then(|text| profile_timezone(&text))
+            .unwrap_or(self.default)
+    }
+}
+
+/// Timezone named on a `Timezone` line of a user profile.
+pub fn profile_timezone(profile: &str) -> Option<Tz> {
+    profile
+        .lines()
+        .filter(|line| line.to_ascii_lowercase().contains("timezone"))
+        .find_map(|line| {
+            let value = line.split_once(':')?.1;
+            value
+                .trim_matches(|c: char| c == '*' || c.is_whitespace())
+                .split_whitespace()
+                .next()?
+                .parse()
+                .ok()
+        })
+}
+
+// ─────────────────────────────────────────────
+// Local times
+// ─────────────────────────────────────────────
+
+/// Resolve a local time, taking the earlier instant when ambiguous and
+/// skipping forward over a DST gap.
+pub fn resolve_local(tz: Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(naive + Duration::hours(1))).earliest())
+}
+
+/// ISO 8601 with the offset, to the second (`2026-10-20T09:00:00+02:00`).
+pub fn to_iso<Z: TimeZone>(dt: &DateTime<Z>) -> String
+where
+    Z::Offset: std::fmt::Display,
+{
+    dt.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// Parse "today", "tomorrow", a weekday (its next occurrence) or YYYY-MM-DD.
+pub fn parse_day(text: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let text = text.trim().to_ascii_lowercase();
+    match text.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        _ => {}
+    }
+    if let Ok(weekday) = text.parse::<Weekday>() {
+        return Ok(next_weekday(today, weekday, false));
+    }
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("invalid day '{text}': use today, tomorrow, a weekday or YYYY-MM-DD"))
+}
+
+/// The next `weekday` after `today` (or `today` itself if `include_today`).
+fn next_weekday(today: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    let ahead = if ahead == 0 && !include_today { 7 } else { ahead };
+    today + Duration::days(i64::from(ahead))
+}
+
+// ─────────────────────────────────────────────
+// Parsing
+// ─────────────────────────────────────────────
+
+/// The day part of a written time.
+enum Day {
+    Date(NaiveDate),
+    /// A weekday: today if the time is still ahead, else its next occurrence.
+    Weekday(Weekday),
+}
+
+/// Parse a time as people write it, relative to `now` and in its timezone.
+///
+/// Accepts ISO 8601 (with or without an offset), "now", "in 2 hours" /
+/// "in 3 days", and a day and/or a time of day: "tuesday at 9",
+/// "next friday 3:30pm", "tomorrow morning", "2026-11-02 14:00", "noon".
+/// A time without a day is its next occurrence; a day without a time is
+/// 09:00. Times are 24-hour unless followed by am/pm.
+pub fn parse_time(text: &str, now: DateTime<Tz>) -> Result<DateTime<Tz>> {
+    let tz = now.timezone();
+    let text = text.trim().to_lowercase();
+    let invalid = || anyhow::anyhow!("couldn't understand the time '{text}'");
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(&text.to_uppercase()) {
+        return Ok(dt.with_timezone(&tz));
+    }
+    for format in ["%Y-%m-%dt%H:%M:%S", "%Y-%m-%dt%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(&text, format) {
+            return resolve_local(tz, naive).ok_or_else(invalid);
+        }
+    }
+    if text == "now" {
+        return Ok(now);
+    }
+    if let Some(rest) = text.strip_prefix("in ") {
+        return parse_offset(rest, now).ok_or_else(invalid);
+    }
+
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .filter(|w| !matches!(*w, "at" | "on" | "the" | "this"))
+        .collect();
+    let mut day = None;
+    let mut time = None;
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i].trim_end_matches(',');
+        let next = words.get(i + 1).copied();
+        let today = now.date_naive();
+        let (parsed_day, parsed_time) = match word {
+            "today" => (Some(Day::Date(today)), None),
+            "tomorrow" => (Some(Day::Date(today + Duration::days(1))), None),
+            "tonight" => (Some(Day::Date(today)), Some(hm(20, 0))),
+            "morning" => (None, Some(hm(9, 0))),
+            "afternoon" => (None, Some(hm(15, 0))),
+            "evening" => (None, Some(hm(19, 0))),
+            "noon" | "midday" => (None, Some(hm(12, 0))),
+            "midnight" => (None, Some(hm(0, 0))),
+            "next" => {
+                let weekday = next.and_then(|w| w.parse::<Weekday>().ok()).ok_or_else(invalid)?;
+                i += 1;
+                (Some(Day::Date(next_weekday(today, weekday, false))), None)
+            }
+            _ => {
+                if let Ok(weekday) = word.parse::<Weekday>() {
+                    (Some(Day::Weekday(weekday)), None)
+                } else if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                    (Some(Day::Date(date)), None)
+                } else {
+                    // "9", "9:30", "9pm", or "9" followed by "pm"
+                    let meridiem = next.filter(|w| is_meridiem(w));
+                    let joined = format!("{word}{}", meridiem.unwrap_or_default());
+                    if meridiem.is_some() {
+                        i += 1;
+                    }
+                    (None, Some(parse_clock(&joined).ok_or_else(invalid)?))
+                }
+            }
+        };
+        if parsed_day.is_some() {
+            if day.is_some() {
+                return Err(invalid());
+            }
+            day = parsed_day;
+        }
+        // "tomorrow morning at 8": the clock time wins
+        if parsed_time.is_some() && (time.is_none() || !matches!(word, "morning" | "afternoon" | "evening")) {
+            time = parsed_time;
+        }
+        i += 1;
+    }
+
+    let at = |date: NaiveDate, time: NaiveTime| resolve_local(tz, date.and_time(time)).ok_or_else(invalid);
+    let today = now.date_naive();
+    match (day, time) {
+        (None, None) => Err(invalid()),
+        (None, Some(time)) => {
+            let today_at = at(today, time)?;
+            if today_at > now {
+                Ok(today_at)
+            } else {
+                at(today + Duration::days(1), time)
+            }
+        }
+        (Some(Day::Date(date)), time) => at(date, time.unwrap_or(hm(DEFAULT_HOUR, 0))),
+        (Some(Day::Weekday(weekday)), time) => {
+            let time = time.unwrap_or(hm(DEFAULT_HOUR, 0));
+            let date = next_weekday(today, weekday, true);
+            let dt = at(date, time)?;
+            if dt > now {
+                Ok(dt)
+            } else {
+                at(next_weekday(today, weekday, false), time)
+            }
+        }
+    }
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).expect("valid time")
+}
+
+fn is_meridiem(word: &str) -> bool {
+    matches!(word, "am" | "pm" | "a.m." | "p.m.")
+}
+
+/// "9", "09:30", "9.30", "9am", "9:30pm".
+fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let text = text.replace('.', ":").replace("a:m:", "am").replace("p:m:", "pm");
+    let (clock, pm) = match (text.strip_suffix("am"), text.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(false)),
+        (_, Some(clock)) => (clock, Some(true)),
+        _ => (text.as_str(), None),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None if clock.len() <= 2 => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match pm {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// "2 hours", "an hour", "30 min", "3 days", "1 week".
+///
+/// Days and weeks keep the local time of day across DST changes.
+fn parse_offset(text: &str, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let mut words = text.split_whitespace();
+    let first = words.next()?;
+    let (amount, unit) = match words.next() {
+        Some(unit) => (first, unit),
+        // "2h", "30min"
+        None => first.split_at(first.find(|c: char| !c.is_ascii_digit())?),
+    };
+    let amount: i64 = match amount {
+        "a" | "an" => 1,
+        n => n.parse().ok()?,
+    };
+    let unit = unit.trim_end_matches('s');
+    match unit {
+        "m" | "min" | "minute" => Some(now + Duration::minutes(amount)),
+        "h" | "hr" | "hour" => Some(now + Duration::hours(amount)),
+        "d" | "day" | "w" | "week" => {
+            let days = if unit.starts_with('w') { amount * 7 } else { amount };
+            resolve_local(now.timezone(), now.naive_local() + Duration::days(days))
+        }
+        _ => None,
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn madrid(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Tz> {
+        Tz::Europe__Madrid.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_profile_timezone_and_days() {
+        assert_eq!(profile_timezone("- **Timezone**: America/New_York (EST)"), Some(Tz::America__New_York));
+        assert_eq!(profile_timezone("Timezone: UTC"), Some(Tz::UTC));
+        assert_eq!(profile_timezone("- **Timezone**: (e.g. Europe/Madrid)"), None);
+
+        // Thursday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(parse_day("Friday", today).unwrap(), NaiveDate::from_ymd_opt(2026, 10, 16).unwrap());
+        assert_eq!(parse_day("thursday", today).unwrap(), NaiveDate::from_ymd_opt(2026, 10, 22).unwrap());
+        assert_eq!(parse_day("tomorrow", today).unwrap(), NaiveDate::from_ymd_opt(2026, 10, 16).unwrap());
+        assert!(parse_day("someday", today).is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        // Thursday 2026-10-15 10:00 in Madrid
+        let now = madrid(2026, 10, 15, 10, 0);
+        let parse = |text: &str| parse_time(text, now).map(|dt| to_iso(&dt));
+
+        assert_eq!(parse("Tuesday at 9").unwrap(), "2026-10-20T09:00:00+02:00");
+        assert_eq!(parse("thursday 11:30").unwrap(), "2026-10-15T11:30:00+02:00");
+        assert_eq!(parse("Thursday at 9").unwrap(), "2026-10-22T09:00:00+02:00");
+        assert_eq!(parse("next thursday").unwrap(), "2026-10-22T09:00:00+02:00");
+        assert_eq!(parse("tomorrow 3pm").unwrap(), "2026-10-16T15:00:00+02:00");
+        assert_eq!(parse("tomorrow morning at 7:45 am").unwrap(), "2026-10-16T07:45:00+02:00");
+        assert_eq!(parse("tonight").unwrap(), "2026-10-15T20:00:00+02:00");
+        assert_eq!(parse("9").unwrap(), "2026-10-16T09:00:00+02:00");
+        assert_eq!(parse("12 am").unwrap(), "2026-10-16T00:00:00+02:00");
+        assert_eq!(parse("in 2 hours").unwrap(), "2026-10-15T12:00:00+02:00");
+        assert_eq!(parse("in 30min").unwrap(), "2026-10-15T10:30:00+02:00");
+        assert_eq!(parse("2026-11-02 14:00").unwrap(), "2026-11-02T14:00:00+01:00");
+        assert_eq!(parse("2026-11-02T14:00:00Z").unwrap(), "2026-11-02T15:00:00+01:00");
+        assert_eq!(parse("on 2026-11-02").unwrap(), "2026-11-02T09:00:00+01:00");
+
+        assert!(parse("someday").is_err());
+        assert!(parse("13pm").is_err());
+        assert!(parse("tuesday friday").is_err());
+    }
+
+    #[test]
+    fn test_dst_changes() {
+        // Clocks go back on Sunday 2026-10-25 in Madrid
+        let now = madrid(2026, 10, 24, 9, 0);
+        let in_a_day = parse_time("in 1 day", now).unwrap();
+        assert_eq!(to_iso(&in_a_day), "2026-10-25T09:00:00+01:00");
+        assert_eq!((in_a_day - now).num_hours(), 25);
+        // The repeated hour resolves to its first occurrence
+        assert_eq!(to_iso(&parse_time("sunday 2:30", now).unwrap()), "2026-10-25T02:30:00+02:00");
+
+        // Clocks go forward on 2026-03-29: 02:30 doesn't exist
+        let now = madrid(2026, 3, 28, 12, 0);
+        assert_eq!(to_iso(&parse_time("tomorrow 2:30", now).unwrap()), "2026-03-29T03:30:00+02:00");
+    }
+}
@@ -0,0 +1,140 @@
+//! User-facing error taxonomy.
+//!
+//! Internal errors carry provider response bodies, file paths and other
+//! details that don't belong in a chat. Errors are tagged with one of the
+//! types below where they arise; before an error reaches a user it is
+//! classified by [`ErrorKind::of`] and rendered as a short message, while
+//! the full error is logged under a correlation ID.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The LLM provider failed (HTTP error, timeout, open circuit breaker).
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct ProviderError(pub String);
+
+/// A tool failed to execute.
+#[derive(Debug, thiserror::Error)]
+#[error("Error executing {tool}: {message}")]
+pub struct ToolError {
+    pub tool: String,
+    pub message: String,
+}
+
+/// The configuration is invalid or incomplete.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct ConfigError(pub String);
+
+/// A chat channel failed to receive or deliver a message.
+#[derive(Debug, thiserror::Error)]
+#[error("{channel}: {message}")]
+pub struct ChannelError {
+    pub channel: String,
+    pub message: String,
+}
+
+/// Category of an error, deciding what the user is told.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Provider,
+    Tool,
+    Config,
+    Channel,
+    /// Anything untagged.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Classify an error by the first tagged error in its chain.
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.chain()
+            .find_map(|e| {
+                if e.is::<ProviderError>() {
+                    Some(Self::Provider)
+                } else if e.is::<ToolError>() {
+                    Some(Self::Tool)
+                } else if e.is::<ConfigError>() {
+                    Some(Self::Config)
+                } else if e.is::<ChannelError>() {
+                    Some(Self::Channel)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(Self::Internal)
+    }
+
+    /// Message shown to the user for this kind of error.
+    pub fn user_message(self) -> &'static str {
+        match self {
+            Self::Provider => "⚠️ The AI provider couldn't answer right now. Please try again in a moment.",
+            Self::Tool => "⚠️ A tool I needed failed while working on your request.",
+            Self::Config => "⚠️ I'm not set up correctly for this request. Please let the bot admin know.",
+            Self::Channel => "⚠️ I couldn't deliver a message on this channel. Please try again.",
+            Self::Internal => "⚠️ Something went wrong while handling your message. Please try again.",
+        }
+    }
+}
+
+/// Short ID tying a user-facing error to its log entry (8 hex chars).
+pub fn correlation_id() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mixed = nanos.wrapping_mul(6364136223846793005).wrapping_add(count as u64);
+    format!("{:08x}", (mixed >> 32) as u32)
+}
+
+/// Render an error for a chat, with the correlation ID when given.
+pub fn render(err: &anyhow::Error, correlation_id: Option<&str>) -> String {
+    let message = ErrorKind::of(err).user_message();
+    match correlation_id {
+        Some(id) => format!("{message} (error ID: {id})"),
+        None => message.to_string(),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_error_kind_of() {
+        let provider: anyhow::Error = ProviderError("Error calling LLM: 500 — {\"trace\": \"…\"}".into()).into();
+        assert_eq!(ErrorKind::of(&provider), ErrorKind::Provider);
+
+        // Tags survive added context
+        let tool = Err::<(), _>(ToolError { tool: "exec".into(), message: "/etc/shadow".into() })
+            .context("running tools")
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&tool), ErrorKind::Tool);
+
+        let config: anyhow::Error = ConfigError("unknown persona".into()).into();
+        assert_eq!(ErrorKind::of(&config), ErrorKind::Config);
+        assert_eq!(ErrorKind::of(&anyhow::anyhow!("disk full")), ErrorKind::Internal);
+    }
+
+    #[test]
+    fn test_render_hides_details() {
+        let err: anyhow::Error = ProviderError("401 — invalid key sk-ant-123 at /home/me".into()).into();
+        let text = render(&err, None);
+        assert!(!text.contains("sk-ant"));
+        assert!(!text.contains("/home"));
+        assert_eq!(text, ErrorKind::Provider.user_message());
+
+        let id = correlation_id();
+        assert_eq!(id.len(), 8);
+        assert!(render(&err, Some(&id)).ends_with(&format!("(error ID: {id})")));
+    }
+}
@@ -0,0 +1,224 @@
+//! Minimal HTTP/1.1 for the gateway's own endpoints (OAuth callbacks, admin API).
+//!
+//! One request per connection, no keep-alive and small bodies only — enough
+//! for a browser redirect or a `curl` from an operator, without a web
+//! framework. Each endpoint is a [`Handler`]; [`serve`] asks them in order
+//! and answers 404 when none claims the request.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::info;
+
+/// Maximum size of a request, head and body.
+pub const MAX_REQUEST_BYTES: usize = 8192;
+
+/// A parsed request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Path and query, as sent.
+    pub target: String,
+    /// Headers, names lowercased.
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Request {
+    /// The target without its query string.
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+
+    /// A header value (`name` lowercase).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|s| s.as_str())
+    }
+
+    /// The token of an `Authorization: Bearer <token>` header.
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.header("authorization")?.strip_prefix("Bearer ").map(str::trim)
+    }
+}
+
+/// A plain-text response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+    /// Status line, e.g. `"200 OK"`.
+    pub status: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new(status: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::new("200 OK", body)
+    }
+
+    pub fn not_found() -> Self {
+        Self::new("404 Not Found", "Not found.")
+    }
+}
+
+/// An endpoint served by [`serve`].
+#[async_trait]
+pub trait Handler: Send + Sync {
+    /// Answer `request`, or `None` if it is not for this handler.
+    async fn handle(&self, request: &Request) -> Option<Response>;
+}
+
+/// Serve `handlers` on `listener` until the task is dropped.
+pub async fn serve(listener: TcpListener, handlers: Vec<Arc<dyn Handler>>) -> Result<()> {
+    info!(addr = %listener.local_addr()?, "gateway http server listening");
+    let handlers = Arc::new(handlers);
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let handlers = handlers.clone();
+        tokio::spawn(async move {
+            let Some(request) = read_request(&mut stream).await else { return };
+            let mut response = Response::not_found();
+            for handler in handlers.iter() {
+                if let Some(answer) = handler.handle(&request).await {
+                    response = answer;
+                    break;
+                }
+            }
+            write_response(&mut stream, &response).await;
+        });
+    }
+}
+
+/// Read one request. `None` if the connection closed or sent garbage.
+pub async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() >= MAX_REQUEST_BYTES {
+            return None;
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let mut request = parse_head(&String::from_utf8_lossy(&buf[..head_end]))?;
+    let length: usize = request
+        .header("content-length")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_BYTES);
+    let mut body = buf[head_end + 4..].to_vec();
+    while body.len() < length {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => body.extend_from_slice(&chunk[..n]),
+        }
+    }
+    body.truncate(length);
+    request.body = String::from_utf8_lossy(&body).into_owned();
+    Some(request)
+}
+
+/// Parse the request line and headers.
+fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    Some(Request {
+        method,
+        target,
+        headers,
+        body: String::new(),
+    })
+}
+
+/// Write `response` and close the connection.
+pub async fn write_response(stream: &mut TcpStream, response: &Response) {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(response.body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echoes the body of `PUT /echo`.
+    struct Echo;
+
+    #[async_trait]
+    impl Handler for Echo {
+        async fn handle(&self, request: &Request) -> Option<Response> {
+            (request.path() == "/echo").then(|| {
+                let token = request.bearer_token().unwrap_or("-");
+                Response::ok(format!("{} {} {}", request.method, token, request.body))
+            })
+        }
+    }
+
+    async fn send(addr: std::net::SocketAddr, raw: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head("GET /oauth/callback?code=1 HTTP/1.1\r\nHost: x\r\nAuthorization: Bearer abc").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path(), "/oauth/callback");
+        assert_eq!(request.header("host"), Some("x"));
+        assert_eq!(request.bearer_token(), Some("abc"));
+        assert_eq!(parse_head(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_serve_routes_to_handlers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, vec![Arc::new(Echo)]));
+
+        let body = "oxibot=debug";
+        let response = send(
+            addr,
+            &format!("PUT /echo HTTP/1.1\r\nAuthorization: Bearer t0k\r\nContent-Length: {}\r\n\r\n{body}", body.len()),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("\r\n\r\nPUT t0k oxibot=debug"));
+
+        let response = send(addr, "GET /other HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+    }
+}
@@ -1,6 +1,26 @@
+//! Core types, message bus, config and utilities shared by all Oxibot crates.
+//!
+//! The `auth`, `http`, `oauth` and `sync` modules need the default `native` feature;
+//! without it the crate builds for `wasm32-wasip1`.
+
 pub mod types;
+#[cfg(feature = "native")]
+pub mod auth;
 pub mod bus;
 pub mod config;
+pub mod contacts;
+pub mod crash;
+pub mod datetime;
+pub mod error;
 pub mod heartbeat;
+#[cfg(feature = "native")]
+pub mod http;
+#[cfg(feature = "native")]
+pub mod oauth;
 pub mod session;
+#[cfg(feature = "native")]
+pub mod sync;
+pub mod tokenizer;
+pub mod tool_stats;
+pub mod usage;
 pub mod utils;
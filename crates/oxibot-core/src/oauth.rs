@@ -0,0 +1,578 @@
+//! OAuth2 token manager — authorization-code flow, token storage, refresh.
+//!
+//! Integrations that call OAuth-protected APIs (Google, Microsoft Graph,
+//! Slack user tokens) share one [`OAuthManager`]:
+//!
+//! 1. [`OAuthManager::authorization_url`] starts a login (with PKCE) for a
+//!    client configured under `oauth.clients`.
+//! 2. The provider redirects the browser to `/oauth/callback` on the
+//!    gateway's HTTP address, served by the manager's [`Handler`], which exchanges
+//!    the code for tokens.
+//! 3. Tools and channels call [`OAuthManager::access_token`], which returns
+//!    a live access token and refreshes it shortly before it expires.
+//!
+//! Tokens are stored in `~/.oxibot/oauth_tokens.json`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::config::schema::OAuthClientConfig;
+use crate::error::ConfigError;
+use crate::http::{self, Handler, Request, Response};
+use crate::utils;
+
+/// Path of the redirect endpoint on the gateway's HTTP server.
+pub const CALLBACK_PATH: &str = "/oauth/callback";
+
+/// Refresh tokens this long before they expire.
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// How long a started login waits for its callback.
+const LOGIN_TTL: Duration = Duration::from_secs(600);
+
+// ─────────────────────────────────────────────
+// Tokens
+// ─────────────────────────────────────────────
+
+/// Tokens issued for one client.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// When the access token expires (`None` = unknown, assumed valid).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Granted scopes, space-separated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl OAuthToken {
+    /// Whether the access token is still usable (not within the refresh margin).
+    pub fn is_fresh(&self) -> bool {
+        self.expires_at
+            .is_none_or(|at| at - chrono::Duration::seconds(REFRESH_MARGIN_SECS) > Utc::now())
+    }
+}
+
+/// Token endpoint response (RFC 6749 §5.1).
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+/// Tokens by client name, persisted as JSON.
+pub struct TokenStore {
+    path: PathBuf,
+    tokens: HashMap<String, OAuthToken>,
+}
+
+impl TokenStore {
+    /// Load the store (default `~/.oxibot/oauth_tokens.json`); a missing
+    /// or invalid file gives an empty store.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let path = path.unwrap_or_else(utils::get_oauth_tokens_path);
+        let tokens = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "invalid oauth token file");
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self { path, tokens }
+    }
+
+    /// Write the store back to disk (owner-only on Unix).
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.tokens)?)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Token for a client.
+    pub fn get(&self, client: &str) -> Option<&OAuthToken> {
+        self.tokens.get(client)
+    }
+
+    /// Store a client's token (call `save` to persist).
+    pub fn insert(&mut self, client: &str, token: OAuthToken) {
+        self.tokens.insert(client.to_string(), token);
+    }
+
+    /// Remove a client's token; returns whether one existed.
+    pub fn remove(&mut self, client: &str) -> bool {
+        self.tokens.remove(client).is_some()
+    }
+}
+
+// ─────────────────────────────────────────────
+// OAuthManager
+// ─────────────────────────────────────────────
+
+/// A login waiting for its callback.
+struct PendingLogin {
+    client: String,
+    verifier: String,
+    started: Instant,
+}
+
+/// Runs the authorization-code flow and hands out live access tokens.
+pub struct OAuthManager {
+    clients: HashMap<String, OAuthClientConfig>,
+    redirect_url: String,
+    /// Held across refreshes, so concurrent callers share one refresh.
+    store: Mutex<TokenStore>,
+    /// Started logins by `state`.
+    pending: std::sync::Mutex<HashMap<String, PendingLogin>>,
+    http: reqwest::Client,
+}
+
+impl OAuthManager {
+    /// Create a manager for the configured clients.
+    pub fn new(
+        clients: HashMap<String, OAuthClientConfig>,
+        redirect_url: impl Into<String>,
+        store: TokenStore,
+    ) -> Self {
+        Self {
+            clients,
+            redirect_url: redirect_url.into(),
+            store: Mutex::new(store),
+            pending: std::sync::Mutex::new(HashMap::new()),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .expect("failed to create HTTP client"),
+        }
+    }
+
+    /// Configured client names, sorted.
+    pub fn clients(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.clients.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Stored token for a client, if it has been authorized.
+    pub async fn token(&self, client: &str) -> Option<OAuthToken> {
+        self.store.lock().await.get(client).cloned()
+    }
+
+    /// Start a login: returns the URL the user must open.
+    pub fn authorization_url(&self, client: &str) -> Result<String> {
+        let config = self.client(client)?;
+        let state = random_token();
+        let verifier = random_token();
+
+        let mut params: Vec<(&str, &str)> = vec![
+            ("response_type", "code"),
+            ("client_id", &config.client_id),
+            ("redirect_uri", &self.redirect_url),
+            ("state", &state),
+            ("code_challenge_method", "S256"),
+        ];
+        let challenge = base64url(&Sha256::digest(verifier.as_bytes()));
+        params.push(("code_challenge", &challenge));
+        let scope = config.scopes.join(" ");
+        if !scope.is_empty() {
+            params.push(("scope", &scope));
+        }
+        let mut extra: Vec<_> = config.extra_params.iter().collect();
+        extra.sort();
+        params.extend(extra.into_iter().map(|(k, v)| (k.as_str(), v.as_str())));
+
+        let url = reqwest::Url::parse_with_params(&config.auth_url, &params)
+            .with_context(|| format!("invalid authUrl for oauth client '{client}'"))?;
+
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|_, p| p.started.elapsed() < LOGIN_TTL);
+        pending.insert(
+            state,
+            PendingLogin {
+                client: client.to_string(),
+                verifier,
+                started: Instant::now(),
+            },
+        );
+        Ok(url.to_string())
+    }
+
+    /// Finish a login from the callback's `state` and `code`; returns the
+    /// client name.
+    pub async fn complete(&self, state: &str, code: &str) -> Result<String> {
+        let login = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(state)
+            .filter(|p| p.started.elapsed() < LOGIN_TTL)
+            .context("unknown or expired login, please start again")?;
+        let config = self.client(&login.client)?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_url),
+            ("code_verifier", &login.verifier),
+        ];
+        add_client_auth(&mut form, config);
+        let token = self.request_token(config, &form, None).await?;
+
+        let mut store = self.store.lock().await;
+        store.insert(&login.client, token);
+        store.save()?;
+        info!(client = %login.client, "oauth client authorized");
+        Ok(login.client)
+    }
+
+    /// A live access token for a client, refreshed if it is about to expire.
+    pub async fn access_token(&self, client: &str) -> Result<String> {
+        let config = self.client(client)?;
+        let mut store = self.store.lock().await;
+        let token = store.get(client).cloned().ok_or_else(|| {
+            ConfigError(format!("oauth client '{client}' is not authorized; run `oxibot oauth login {client}`"))
+        })?;
+        if token.is_fresh() {
+            return Ok(token.access_token);
+        }
+
+        let refresh_token = token.refresh_token.clone().ok_or_else(|| {
+            ConfigError(format!(
+                "oauth token for '{client}' expired and has no refresh token; run `oxibot oauth login {client}`"
+            ))
+        })?;
+        debug!(client, "refreshing oauth token");
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+        ];
+        add_client_auth(&mut form, config);
+        let refreshed = self.request_token(config, &form, Some(&token)).await?;
+
+        store.insert(client, refreshed.clone());
+        store.save()?;
+        Ok(refreshed.access_token)
+    }
+
+    /// Forget a client's tokens; returns whether any were stored.
+    pub async fn logout(&self, client: &str) -> Result<bool> {
+        let mut store = self.store.lock().await;
+        let removed = store.remove(client);
+        if removed {
+            store.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn client(&self, name: &str) -> Result<&OAuthClientConfig> {
+        self.clients
+            .get(name)
+            .ok_or_else(|| ConfigError(format!("unknown oauth client '{name}'")).into())
+    }
+
+    /// POST to the token endpoint. A refresh keeps the previous refresh
+    /// token and scope when the response leaves them out.
+    async fn request_token(
+        &self,
+        config: &OAuthClientConfig,
+        form: &[(&str, &str)],
+        previous: Option<&OAuthToken>,
+    ) -> Result<OAuthToken> {
+        let response = self
+            .http
+            .post(&config.token_url)
+            .header("Accept", "application/json")
+            .form(form)
+            .send()
+            .await
+            .context("token request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("token endpoint returned {status}: {body}");
+        }
+        let parsed: TokenResponse = response.json().await.context("invalid token response")?;
+
+        Ok(OAuthToken {
+            access_token: parsed.access_token,
+            refresh_token: parsed
+                .refresh_token
+                .or_else(|| previous.and_then(|t| t.refresh_token.clone())),
+            expires_at: parsed.expires_in.map(|s| Utc::now() + chrono::Duration::seconds(s)),
+            scope: parsed.scope.or_else(|| previous.and_then(|t| t.scope.clone())),
+        })
+    }
+}
+
+/// Send the client credentials in the form body (`client_secret_post`).
+fn add_client_auth<'a>(form: &mut Vec<(&'a str, &'a str)>, config: &'a OAuthClientConfig) {
+    form.push(("client_id", &config.client_id));
+    if !config.client_secret.is_empty() {
+        form.push(("client_secret", &config.client_secret));
+    }
+}
+
+/// 32 random bytes, base64url-encoded (state and PKCE verifier).
+fn random_token() -> String {
+    base64url(&rand::random::<[u8; 32]>())
+}
+
+/// Base64url without padding (RFC 4648 §5).
+fn base64url(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[((triple >> (18 - 6 * i)) & 0x3F) as usize] as char);
+        }
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// Callback server
+// ─────────────────────────────────────────────
+
+/// What the provider sent to the redirect URL.
+#[derive(Debug, PartialEq)]
+enum Callback {
+    Code { state: String, code: String },
+    Denied(String),
+}
+
+#[async_trait]
+impl Handler for OAuthManager {
+    /// Serves `/oauth/callback` on the gateway's HTTP server.
+    async fn handle(&self, request: &Request) -> Option<Response> {
+        answer_callback(self, request).await.map(|(response, _)| response)
+    }
+}
+
+/// Serve callbacks until a login completes; returns the authorized client.
+pub async fn wait_for_login(listener: &TcpListener, manager: &OAuthManager) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let Some(request) = http::read_request(&mut stream).await else { continue };
+        let (response, client) = answer_callback(manager, &request)
+            .await
+            .unwrap_or((Response::not_found(), None));
+        http::write_response(&mut stream, &response).await;
+        if let Some(client) = client {
+            return Ok(client);
+        }
+    }
+}
+
+/// Answer a callback request (`None` for any other request); also returns
+/// the client name when it completed a login.
+async fn answer_callback(manager: &OAuthManager, request: &Request) -> Option<(Response, Option<String>)> {
+    let answer = match parse_callback(&request.method, &request.target)? {
+        Callback::Denied(reason) => {
+            warn!(reason = %reason, "oauth login denied");
+            (Response::new("400 Bad Request", format!("Authorization failed: {reason}")), None)
+        }
+        Callback::Code { state, code } => match manager.complete(&state, &code).await {
+            Ok(client) => (
+                Response::ok(format!("Oxibot is now authorized for {client}. You can close this tab.")),
+                Some(client),
+            ),
+            Err(e) => {
+                warn!(error = %format!("{e:#}"), "oauth login failed");
+                (Response::new("400 Bad Request", format!("Authorization failed: {e}")), None)
+            }
+        },
+    };
+    Some(answer)
+}
+
+/// Parse `GET /oauth/callback?...` (`None` for any other request).
+fn parse_callback(method: &str, target: &str) -> Option<Callback> {
+    if method != "GET" {
+        return None;
+    }
+    let url = reqwest::Url::parse(&format!("http://localhost{target}")).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    if let Some(error) = params.get("error") {
+        let detail = params.get("error_description").map_or(String::new(), |d| format!(" ({d})"));
+        return Some(Callback::Denied(format!("{error}{detail}")));
+    }
+    match (params.get("state"), params.get("code")) {
+        (Some(state), Some(code)) => Some(Callback::Code {
+            state: state.clone(),
+            code: code.clone(),
+        }),
+        _ => Some(Callback::Denied("missing code or state".into())),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn manager(server: &MockServer, dir: &Path) -> OAuthManager {
+        let client = OAuthClientConfig {
+            auth_url: "https://auth.example.com/authorize".into(),
+            token_url: format!("{}/token", server.uri()),
+            client_id: "oxibot".into(),
+            client_secret: "s3cret".into(),
+            scopes: vec!["calendar".into(), "mail.read".into()],
+            extra_params: HashMap::from([("access_type".into(), "offline".into())]),
+        };
+        OAuthManager::new(
+            HashMap::from([("google".into(), client)]),
+            "http://localhost:18790/oauth/callback",
+            TokenStore::load(Some(dir.join("tokens.json"))),
+        )
+    }
+
+    fn query(url: &str) -> HashMap<String, String> {
+        reqwest::Url::parse(url).unwrap().query_pairs().into_owned().collect()
+    }
+
+    #[test]
+    fn test_base64url() {
+        assert_eq!(base64url(b""), "");
+        assert_eq!(base64url(b"f"), "Zg");
+        assert_eq!(base64url(b"foob"), "Zm9vYg");
+        assert_eq!(base64url(&[0xfb, 0xff]), "-_8");
+        // RFC 7636 appendix B
+        let challenge = base64url(&Sha256::digest(b"dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"));
+        assert_eq!(challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        assert_eq!(
+            parse_callback("GET", "/oauth/callback?state=abc&code=x%2Fy"),
+            Some(Callback::Code { state: "abc".into(), code: "x/y".into() })
+        );
+        assert_eq!(
+            parse_callback("GET", "/oauth/callback?error=access_denied"),
+            Some(Callback::Denied("access_denied".into()))
+        );
+        assert_eq!(parse_callback("GET", "/favicon.ico"), None);
+        assert_eq!(parse_callback("POST", "/oauth/callback"), None);
+    }
+
+    #[tokio::test]
+    async fn test_login_and_refresh() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let oauth = manager(&server, dir.path());
+
+        let url = oauth.authorization_url("google").unwrap();
+        let params = query(&url);
+        assert_eq!(params["scope"], "calendar mail.read");
+        assert_eq!(params["access_type"], "offline");
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=authorization_code"))
+            .and(body_string_contains("code_verifier="))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "at-1", "refresh_token": "rt-1", "expires_in": 30
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .and(body_string_contains("refresh_token=rt-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "at-2", "expires_in": 3600
+            })))
+            .mount(&server)
+            .await;
+
+        assert!(oauth.complete("wrong-state", "code").await.is_err());
+        assert_eq!(oauth.complete(&params["state"], "code").await.unwrap(), "google");
+        // The state is single-use
+        assert!(oauth.complete(&params["state"], "code").await.is_err());
+
+        // Expires within the refresh margin → refreshed, refresh token kept
+        assert_eq!(oauth.access_token("google").await.unwrap(), "at-2");
+        assert_eq!(oauth.access_token("google").await.unwrap(), "at-2");
+        let stored = TokenStore::load(Some(dir.path().join("tokens.json")));
+        assert_eq!(stored.get("google").unwrap().refresh_token.as_deref(), Some("rt-1"));
+
+        assert!(oauth.logout("google").await.unwrap());
+        let err = oauth.access_token("google").await.unwrap_err();
+        assert!(err.to_string().contains("oxibot oauth login google"));
+        assert!(oauth.authorization_url("slack").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_callback_server_completes_login() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "at-1"
+            })))
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let oauth = manager(&server, dir.path());
+        let state = query(&oauth.authorization_url("google").unwrap())["state"].clone();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let browser = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            let request = format!("GET /oauth/callback?code=c1&state={state} HTTP/1.1\r\nHost: localhost\r\n\r\n");
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        });
+
+        assert_eq!(wait_for_login(&listener, &oauth).await.unwrap(), "google");
+        let response = browser.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(oauth.token("google").await.unwrap().access_token, "at-1");
+    }
+}
@@ -3,16 +3,21 @@
 //! File format: JSONL in `~/.oxibot/sessions/{safe_key}.jsonl`
 //! - Line 1: `{"_type":"metadata","created_at":"...","updated_at":"...","metadata":{}}`
 //! - Line 2+: `{"role":"user","content":"hello","timestamp":"..."}`
+//!
+//! Each session has its own lock: a change and the save that follows it
+//! happen together, so two writers can't interleave or save out of order.
+//! A whole agent turn (history in, replies out) holds [`SessionManager::lock`].
 
 use std::collections::HashMap;
 use std::io::{BufRead, Write};
 use std::path::PathBuf;
-use std::sync::RwLock;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 
+use super::tags::normalize_tag;
 use crate::types::{Message, Session};
 use crate::utils;
 
@@ -29,20 +34,30 @@ struct SessionMetadata {
     updated_at: DateTime<Utc>,
     #[serde(default)]
     metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pinned: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
 }
 
 // ─────────────────────────────────────────────
 // SessionManager
 // ─────────────────────────────────────────────
 
+/// Held for the duration of an agent turn on a session (see [`SessionManager::lock`]).
+pub type SessionLock = tokio::sync::OwnedMutexGuard<()>;
+
 /// Manages conversation sessions with in-memory caching and JSONL persistence.
 ///
-/// Thread-safe via `RwLock` — multiple readers, exclusive writer.
+/// Thread-safe: the cache is behind a `RwLock`, and each session behind its
+/// own `Mutex`, so sessions don't wait for each other.
 pub struct SessionManager {
     /// Directory where `.jsonl` session files are stored.
     sessions_dir: PathBuf,
     /// In-memory cache of active sessions.
-    cache: RwLock<HashMap<String, Session>>,
+    cache: RwLock<HashMap<String, Arc<Mutex<Session>>>>,
+    /// Turn locks, by session key.
+    turns: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 impl SessionManager {
@@ -57,92 +72,182 @@ impl SessionManager {
         Ok(SessionManager {
             sessions_dir: dir,
             cache: RwLock::new(HashMap::new()),
+            turns: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Wait until no other turn runs on session `key`, and keep others
+    /// out until the returned guard is dropped.
+    ///
+    /// Single calls like [`add_message`](Self::add_message) are atomic on
+    /// their own; this is for a sequence of them, like a turn that reads
+    /// the history, calls the LLM and saves the replies.
+    pub async fn lock(&self, key: &str) -> SessionLock {
+        let lock = {
+            let mut turns = self.turns.lock().unwrap_or_else(|e| e.into_inner());
+            // Forget locks nobody holds or waits for
+            turns.retain(|_, lock| Arc::strong_count(lock) > 1);
+            turns.entry(key.to_string()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    /// The cached session `key`: loaded from disk, or new.
+    fn entry(&self, key: &str) -> Arc<Mutex<Session>> {
+        if let Some(entry) = self.cache.read().unwrap().get(key) {
+            return entry.clone();
+        }
+        let mut cache = self.cache.write().unwrap();
+        // Another thread may have loaded it meanwhile
+        cache
+            .entry(key.to_string())
+            .or_insert_with(|| {
+                let session = self.load_from_disk(key).unwrap_or_else(|| Session::new(key));
+                Arc::new(Mutex::new(session))
+            })
+            .clone()
+    }
+
     /// Get an existing session or create a new one.
     ///
     /// 1. Check in-memory cache
     /// 2. Try to load from disk
     /// 3. Create new empty session
     pub fn get_or_create(&self, key: &str) -> Session {
-        // Check cache first
-        {
-            let cache = self.cache.read().unwrap();
-            if let Some(session) = cache.get(key) {
-                return session.clone();
-            }
-        }
-
-        // Try loading from disk
-        if let Some(session) = self.load_from_disk(key) {
-            let mut cache = self.cache.write().unwrap();
-            cache.insert(key.to_string(), session.clone());
-            return session;
-        }
-
-        // Create new empty session
-        let session = Session::new(key);
-        let mut cache = self.cache.write().unwrap();
-        cache.insert(key.to_string(), session.clone());
-        session
+        lock_session(&self.entry(key)).clone()
     }
 
     /// Add a message to a session and persist to disk.
     pub fn add_message(&self, key: &str, message: Message) {
-        let mut session = self.get_or_create(key);
-        session.messages.push(message);
-        session.updated_at = Utc::now();
-
-        // Update cache and save
-        {
-            let mut cache = self.cache.write().unwrap();
-            cache.insert(key.to_string(), session.clone());
-        }
-
-        if let Err(e) = self.save_to_disk(&session) {
-            warn!("Failed to persist session {}: {}", key, e);
-        }
+        self.update(key, |session| session.messages.push(message));
     }
 
     /// Get the last `max_messages` from a session's history.
     ///
     /// Returns messages in LLM format (role + content).
     pub fn get_history(&self, key: &str, max_messages: usize) -> Vec<Message> {
-        let session = self.get_or_create(key);
+        let entry = self.entry(key);
+        let session = lock_session(&entry);
         let len = session.messages.len();
-        if len <= max_messages {
-            session.messages
-        } else {
-            session.messages[len - max_messages..].to_vec()
-        }
+        session.messages[len.saturating_sub(max_messages)..].to_vec()
     }
 
-    /// Clear all messages in a session (reset conversation).
-    pub fn clear(&self, key: &str) {
-        let mut session = self.get_or_create(key);
-        session.messages.clear();
-        session.updated_at = Utc::now();
+    /// Pin a note to a session. Returns the new number of pinned notes.
+    pub fn pin(&self, key: &str, note: &str) -> usize {
+        let count = self.update(key, |session| {
+            session.pinned.push(note.trim().to_string());
+            session.pinned.len()
+        });
+        debug!("Pinned note to session '{}' ({} pinned)", key, count);
+        count
+    }
 
-        {
-            let mut cache = self.cache.write().unwrap();
-            cache.insert(key.to_string(), session.clone());
+    /// Remove a pinned note by 1-based index. Returns the removed note.
+    pub fn unpin(&self, key: &str, index: usize) -> Option<String> {
+        self.update(key, |session| {
+            if index == 0 || index > session.pinned.len() {
+                None
+            } else {
+                Some(session.pinned.remove(index - 1))
+            }
+        })
+    }
+
+    /// Get the pinned notes of a session.
+    pub fn pinned(&self, key: &str) -> Vec<String> {
+        lock_session(&self.entry(key)).pinned.clone()
+    }
+
+    /// Add tags to a session. Returns the tags that were not already present.
+    ///
+    /// The session is only rewritten when something changed, so callers can
+    /// re-apply automatic tags on every turn.
+    pub fn add_tags<S: AsRef<str>>(&self, key: &str, tags: &[S]) -> Vec<String> {
+        let added = self.update_if(key, |session| {
+            let mut added: Vec<String> = Vec::new();
+            for tag in tags.iter().filter_map(|t| normalize_tag(t.as_ref())) {
+                if !session.tags.contains(&tag) && !added.contains(&tag) {
+                    added.push(tag);
+                }
+            }
+            session.tags.extend(added.iter().cloned());
+            session.tags.sort();
+            let changed = !added.is_empty();
+            (added, changed)
+        });
+        if !added.is_empty() {
+            debug!("Tagged session '{}' with {:?}", key, added);
         }
+        added
+    }
+
+    /// Remove a tag from a session. Returns `true` if it was present.
+    pub fn remove_tag(&self, key: &str, tag: &str) -> bool {
+        let Some(tag) = normalize_tag(tag) else {
+            return false;
+        };
+        self.update_if(key, |session| {
+            let before = session.tags.len();
+            session.tags.retain(|t| *t != tag);
+            let removed = session.tags.len() < before;
+            (removed, removed)
+        })
+    }
+
+    /// Get the tags of a session.
+    pub fn tags(&self, key: &str) -> Vec<String> {
+        lock_session(&self.entry(key)).tags.clone()
+    }
+
+    /// A free-form metadata field of a session.
+    pub fn metadata(&self, key: &str, field: &str) -> Option<String> {
+        lock_session(&self.entry(key)).metadata.get(field).cloned()
+    }
+
+    /// Set (or with `None`, remove) a free-form metadata field on a session.
+    pub fn set_metadata(&self, key: &str, field: &str, value: Option<&str>) {
+        self.update(key, |session| match value {
+            Some(value) => {
+                session.metadata.insert(field.to_string(), value.to_string());
+            }
+            None => {
+                session.metadata.remove(field);
+            }
+        });
+    }
 
-        if let Err(e) = self.save_to_disk(&session) {
-            warn!("Failed to persist cleared session {}: {}", key, e);
+    /// Apply a change to a session and persist it.
+    fn update<T>(&self, key: &str, f: impl FnOnce(&mut Session) -> T) -> T {
+        self.update_if(key, |session| (f(session), true))
+    }
+
+    /// Apply a change to a session under its lock, and persist it if `f`
+    /// reports that something changed.
+    fn update_if<T>(&self, key: &str, f: impl FnOnce(&mut Session) -> (T, bool)) -> T {
+        let entry = self.entry(key);
+        let mut session = lock_session(&entry);
+        let (result, changed) = f(&mut session);
+        if changed {
+            session.updated_at = Utc::now();
+            if let Err(e) = self.save_to_disk(&session) {
+                warn!("Failed to persist session {}: {}", key, e);
+            }
         }
+        result
+    }
+
+    /// Clear all messages in a session (reset conversation).
+    pub fn clear(&self, key: &str) {
+        self.update(key, |session| session.messages.clear());
     }
 
     /// Delete a session entirely (from cache and disk).
     ///
     /// Returns `true` if the session file existed on disk.
     pub fn delete(&self, key: &str) -> bool {
-        // Remove from cache
-        {
-            let mut cache = self.cache.write().unwrap();
-            cache.remove(key);
-        }
+        // Remove from cache, waiting for a change in progress to be saved
+        let entry = self.cache.write().unwrap().remove(key);
+        let _session = entry.as_ref().map(lock_session);
 
         // Remove from disk
         let path = self.session_path(key);
@@ -174,7 +279,7 @@ impl SessionManager {
 
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.extension().map_or(true, |ext| ext != "jsonl") {
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
                 continue;
             }
 
@@ -193,6 +298,7 @@ impl SessionManager {
                             key,
                             created_at: meta.created_at,
                             updated_at: meta.updated_at,
+                            tags: meta.tags,
                             path: path.clone(),
                         });
                     }
@@ -201,10 +307,15 @@ impl SessionManager {
         }
 
         // Sort by updated_at descending
-        summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
         summaries
     }
 
+    /// Whether a session has been saved to disk.
+    pub fn exists(&self, key: &str) -> bool {
+        self.session_path(key).exists()
+    }
+
     /// Get the JSONL file path for a session key.
     fn session_path(&self, key: &str) -> PathBuf {
         let safe_key = utils::safe_filename(&key.replace(':', "_"));
@@ -246,6 +357,8 @@ impl SessionManager {
                     session.created_at = meta.created_at;
                     session.updated_at = meta.updated_at;
                     session.metadata = meta.metadata;
+                    session.pinned = meta.pinned;
+                    session.tags = meta.tags;
                     continue;
                 }
             }
@@ -266,10 +379,13 @@ impl SessionManager {
     }
 
     /// Save a session to a JSONL file (overwrite).
+    ///
+    /// Written to a temporary file first, so readers never see half a session.
     fn save_to_disk(&self, session: &Session) -> std::io::Result<()> {
         let path = self.session_path(&session.key);
+        let tmp = path.with_extension("jsonl.tmp");
 
-        let mut file = std::fs::File::create(&path)?;
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&tmp)?);
 
         // Write metadata line
         let meta = SessionMetadata {
@@ -277,6 +393,8 @@ impl SessionManager {
             created_at: session.created_at,
             updated_at: session.updated_at,
             metadata: session.metadata.clone(),
+            pinned: session.pinned.clone(),
+            tags: session.tags.clone(),
         };
         writeln!(file, "{}", serde_json::to_string(&meta)?)?;
 
@@ -284,6 +402,8 @@ impl SessionManager {
         for msg in &session.messages {
             writeln!(file, "{}", serde_json::to_string(msg)?)?;
         }
+        file.into_inner().map_err(|e| e.into_error())?;
+        std::fs::rename(&tmp, &path)?;
 
         debug!(
             "Saved session '{}' ({} messages) to {}",
@@ -295,6 +415,11 @@ impl SessionManager {
     }
 }
 
+/// Lock a cached session, recovering from a panic in another holder.
+fn lock_session(entry: &Arc<Mutex<Session>>) -> MutexGuard<'_, Session> {
+    entry.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 /// Summary of a session for listing purposes.
 #[derive(Clone, Debug)]
 pub struct SessionSummary {
@@ -304,10 +429,21 @@ pub struct SessionSummary {
     pub created_at: DateTime<Utc>,
     /// When the session was last updated.
     pub updated_at: DateTime<Utc>,
+    /// Session tags.
+    pub tags: Vec<String>,
     /// Path to the JSONL file.
     pub path: PathBuf,
 }
 
+impl SessionSummary {
+    /// Whether the session carries every one of `tags` (normalized first).
+    pub fn has_tags<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        tags.iter()
+            .filter_map(|t| normalize_tag(t.as_ref()))
+            .all(|t| self.tags.contains(&t))
+    }
+}
+
 // ─────────────────────────────────────────────
 // Tests
 // ─────────────────────────────────────────────
@@ -409,6 +545,40 @@ mod tests {
         assert!(!existed);
     }
 
+    #[test]
+    fn test_tags_persist_and_filter() {
+        let dir = tempdir().unwrap();
+        {
+            let mgr = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+            mgr.add_message("telegram:42", Message::user("Hello"));
+            let added = mgr.add_tags("telegram:42", &["Work", "channel:telegram", "work"]);
+            assert_eq!(added, vec!["work", "channel:telegram"]);
+            assert!(mgr.add_tags("telegram:42", &["work"]).is_empty());
+            mgr.add_message("cli:direct", Message::user("Hi"));
+            mgr.set_metadata("telegram:42", "title", Some("Q3 planning"));
+        }
+
+        let mgr = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+        let session = mgr.get_or_create("telegram:42");
+        assert_eq!(session.tags, vec!["channel:telegram", "work"]);
+        assert_eq!(session.metadata["title"], "Q3 planning");
+        assert_eq!(mgr.metadata("telegram:42", "title").as_deref(), Some("Q3 planning"));
+        assert_eq!(mgr.metadata("telegram:42", "persona"), None);
+        assert_eq!(session.messages.len(), 1);
+
+        let tagged: Vec<String> = mgr
+            .list_sessions()
+            .into_iter()
+            .filter(|s| s.has_tags(&["WORK"]))
+            .map(|s| s.key)
+            .collect();
+        assert_eq!(tagged, vec!["telegram:42"]);
+
+        assert!(mgr.remove_tag("telegram:42", "work"));
+        assert!(!mgr.remove_tag("telegram:42", "work"));
+        assert_eq!(mgr.tags("telegram:42"), vec!["channel:telegram"]);
+    }
+
     #[test]
     fn test_persistence_round_trip() {
         let dir = tempdir().unwrap();
@@ -502,4 +672,89 @@ mod tests {
             assert!(session.messages.is_empty());
         }
     }
+
+    #[test]
+    fn test_pin_and_unpin() {
+        let (mgr, _dir) = make_manager();
+        assert_eq!(mgr.pin("cli:direct", "the production DB is db-prod-3"), 1);
+        assert_eq!(mgr.pin("cli:direct", "  deploys happen on Tuesdays "), 2);
+        assert_eq!(
+            mgr.pinned("cli:direct"),
+            vec!["the production DB is db-prod-3", "deploys happen on Tuesdays"]
+        );
+
+        assert_eq!(mgr.unpin("cli:direct", 3), None);
+        assert_eq!(mgr.unpin("cli:direct", 0), None);
+        assert_eq!(
+            mgr.unpin("cli:direct", 1).as_deref(),
+            Some("the production DB is db-prod-3")
+        );
+        assert_eq!(mgr.pinned("cli:direct").len(), 1);
+    }
+
+    #[test]
+    fn test_pins_persist_and_survive_clear() {
+        let dir = tempdir().unwrap();
+
+        {
+            let mgr = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+            mgr.add_message("test:1", Message::user("hello"));
+            mgr.pin("test:1", "remember me");
+            mgr.clear("test:1");
+        }
+
+        {
+            let mgr = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+            let session = mgr.get_or_create("test:1");
+            assert!(session.messages.is_empty());
+            assert_eq!(session.pinned, vec!["remember me"]);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_writers_keep_every_message() {
+        let (mgr, dir) = make_manager();
+        std::thread::scope(|s| {
+            for t in 0..8 {
+                let mgr = &mgr;
+                s.spawn(move || {
+                    for i in 0..25 {
+                        mgr.add_message("test:1", Message::user(format!("{t}-{i}")));
+                        mgr.add_tags("test:1", &[format!("t{t}")]);
+                    }
+                });
+            }
+        });
+        assert_eq!(mgr.get_history("test:1", 1000).len(), 200);
+        assert_eq!(mgr.tags("test:1").len(), 8);
+
+        let reloaded = SessionManager::new(Some(dir.path().to_path_buf())).unwrap();
+        assert_eq!(reloaded.get_or_create("test:1").messages.len(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_lock_serializes_turns() {
+        let (mgr, _dir) = make_manager();
+        let mgr = Arc::new(mgr);
+        let first = mgr.lock("test:1").await;
+
+        let waiter = {
+            let mgr = mgr.clone();
+            tokio::spawn(async move {
+                let _turn = mgr.lock("test:1").await;
+                mgr.add_message("test:1", Message::user("second"));
+            })
+        };
+        // Other sessions don't wait
+        drop(mgr.lock("test:2").await);
+
+        mgr.add_message("test:1", Message::user("first"));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(mgr.get_history("test:1", 10).len(), 1);
+
+        drop(first);
+        waiter.await.unwrap();
+        let history = mgr.get_history("test:1", 10);
+        assert_eq!(history, [Message::user("first"), Message::user("second")]);
+    }
 }
@@ -9,5 +9,9 @@
 //! - Lines 2+: messages `{"role": "user", "content": "hello", "timestamp": "..."}`
 
 pub mod manager;
+pub mod tags;
+pub mod transcript;
 
-pub use manager::SessionManager;
+pub use manager::{SessionLock, SessionManager, SessionSummary};
+pub use tags::{detect_language, normalize_tag};
+pub use transcript::{TranscriptEvent, TranscriptLog};
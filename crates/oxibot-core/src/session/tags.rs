@@ -0,0 +1,103 @@
+//! Session tags — labels for organizing saved conversations.
+//!
+//! Tags are short lowercase strings stored in the session header. Users and
+//! the agent add free-form ones (`work`, `trip-planning`); the agent loop adds
+//! automatic `channel:*`, `model:*` and `lang:*` tags as conversations happen.
+
+/// Normalize a tag: trimmed, lowercase, inner whitespace collapsed to `-`.
+///
+/// Returns `None` for empty tags.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Common function words per language, used by [`detect_language`].
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "are", "you", "what", "with", "this", "that", "for", "have", "can"]),
+    ("es", &["el", "la", "los", "las", "que", "es", "por", "para", "con", "una", "como", "pero"]),
+    ("fr", &["le", "les", "est", "une", "des", "pour", "avec", "que", "pas", "vous", "dans", "mais"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ich", "mit", "ein", "eine", "sie", "auch"]),
+    ("pt", &["o", "os", "uma", "não", "você", "com", "para", "que", "do", "da", "mas", "como"]),
+    ("it", &["il", "gli", "che", "non", "una", "per", "sono", "con", "come", "della", "anche", "ma"]),
+];
+
+/// Best-effort language guess for a user message (ISO 639-1 code).
+///
+/// Non-Latin scripts are recognised by their characters; Latin-script
+/// languages by counting common function words. Returns `None` when the text
+/// is too short or ambiguous to tell.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+    if letters == 0 {
+        return None;
+    }
+    // Whether most letters belong to a script
+    let script = |in_script: &dyn Fn(char) -> bool| {
+        text.chars().filter(|c| in_script(*c)).count() * 2 > letters
+    };
+    let kana = |c: char| ('\u{3040}'..='\u{30ff}').contains(&c);
+    if script(&|c| kana(c) || ('\u{4e00}'..='\u{9fff}').contains(&c)) {
+        // Kanji-only text is indistinguishable from Chinese; any kana means Japanese
+        return Some(if text.chars().any(kana) { "ja" } else { "zh" });
+    }
+    if script(&|c| ('\u{ac00}'..='\u{d7af}').contains(&c)) {
+        return Some("ko");
+    }
+    if script(&|c| ('\u{0400}'..='\u{04ff}').contains(&c)) {
+        return Some("ru");
+    }
+    if script(&|c| ('\u{0600}'..='\u{06ff}').contains(&c)) {
+        return Some("ar");
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < 4 {
+        return None;
+    }
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(lang, list)| (*lang, words.iter().filter(|w| list.contains(&w.as_str())).count()))
+        .collect();
+    scores.sort_by_key(|s| std::cmp::Reverse(s.1));
+    let (best, top) = scores[0];
+    // Require at least two hits and a clear winner
+    (top >= 2 && top > scores[1].1).then_some(best)
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("  Trip Planning ").as_deref(), Some("trip-planning"));
+        assert_eq!(normalize_tag("model:GPT-4o").as_deref(), Some("model:gpt-4o"));
+        assert_eq!(normalize_tag("   "), None);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("What is the weather like with this wind?"), Some("en"));
+        assert_eq!(detect_language("¿Qué tiempo hace para el fin de semana en la sierra?"), Some("es"));
+        assert_eq!(detect_language("Est-ce que vous avez une table pour ce soir?"), Some("fr"));
+        assert_eq!(detect_language("Ich weiß nicht, ob das Wetter und die Straße gut ist"), Some("de"));
+        assert_eq!(detect_language("Привет, как дела?"), Some("ru"));
+        assert_eq!(detect_language("今日はいい天気ですね"), Some("ja"));
+        assert_eq!(detect_language("你好，今天天气怎么样"), Some("zh"));
+        assert_eq!(detect_language("ok thanks"), None);
+        assert_eq!(detect_language("12345 !!"), None);
+    }
+}
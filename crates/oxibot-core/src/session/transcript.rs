@@ -0,0 +1,169 @@
+//! Transcripts — everything the agent did in a session, step by step.
+//!
+//! Sessions keep only the user's messages and the final replies. With
+//! transcripts enabled, each turn is also logged in full — every LLM
+//! response with its tool calls, and every tool result — as JSON lines in
+//! `~/.oxibot/transcripts/<session>.jsonl`, for `oxibot sessions replay`.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::types::ToolCall;
+use crate::utils;
+
+// ─────────────────────────────────────────────
+// Events
+// ─────────────────────────────────────────────
+
+/// One step of a turn.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptEvent {
+    /// The user's message, starting a turn.
+    User { timestamp: DateTime<Utc>, content: String },
+    /// One LLM response.
+    Assistant {
+        timestamp: DateTime<Utc>,
+        model: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tool_calls: Vec<ToolCall>,
+    },
+    /// The result of a tool call, as the LLM saw it.
+    ToolResult {
+        timestamp: DateTime<Utc>,
+        call_id: String,
+        tool: String,
+        result: String,
+        duration_ms: u64,
+    },
+    /// The reply sent to the user, ending the turn.
+    Reply { timestamp: DateTime<Utc>, content: String },
+}
+
+impl TranscriptEvent {
+    /// When the step happened.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            TranscriptEvent::User { timestamp, .. }
+            | TranscriptEvent::Assistant { timestamp, .. }
+            | TranscriptEvent::ToolResult { timestamp, .. }
+            | TranscriptEvent::Reply { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// TranscriptLog
+// ─────────────────────────────────────────────
+
+/// Append-only JSONL transcripts, one file per session.
+pub struct TranscriptLog {
+    /// Directory holding the files.
+    dir: PathBuf,
+    /// Serializes writers within the process.
+    lock: Mutex<()>,
+}
+
+impl TranscriptLog {
+    /// Create a transcript log in `dir` (default `~/.oxibot/transcripts/`).
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: dir.unwrap_or_else(utils::get_transcripts_path),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// File holding the transcript of `session_key`.
+    pub fn path(&self, session_key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.jsonl", utils::safe_filename(&session_key.replace(':', "_"))))
+    }
+
+    /// Append a step to `session_key`'s transcript. Failures are logged,
+    /// never fatal.
+    pub fn record(&self, session_key: &str, event: &TranscriptEvent) {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let path = self.path(session_key);
+        if let Err(e) = Self::append(&path, event) {
+            warn!(path = %path.display(), error = %e, "failed to write transcript");
+        }
+    }
+
+    fn append(path: &Path, event: &TranscriptEvent) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+
+    /// Read `session_key`'s transcript, skipping malformed lines. A missing
+    /// file is empty.
+    pub fn read(&self, session_key: &str) -> Vec<TranscriptEvent> {
+        let Ok(file) = std::fs::File::open(self.path(session_key)) else {
+            return Vec::new();
+        };
+        std::io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = TranscriptLog::new(Some(dir.path().to_path_buf()));
+        assert!(log.read("telegram:42").is_empty());
+
+        let now = Utc::now();
+        let events = vec![
+            TranscriptEvent::User { timestamp: now, content: "list files".into() },
+            TranscriptEvent::Assistant {
+                timestamp: now,
+                model: "gpt-4o".into(),
+                content: None,
+                tool_calls: vec![ToolCall::new("c1", "list_dir", r#"{"path":"."}"#)],
+            },
+            TranscriptEvent::ToolResult {
+                timestamp: now,
+                call_id: "c1".into(),
+                tool: "list_dir".into(),
+                result: "a.txt".into(),
+                duration_ms: 3,
+            },
+            TranscriptEvent::Reply { timestamp: now, content: "Just a.txt".into() },
+        ];
+        for event in &events {
+            log.record("telegram:42", event);
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(log.path("telegram:42"))
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        assert_eq!(log.read("telegram:42"), events);
+        assert!(log.path("telegram:42").ends_with("telegram_42.jsonl"));
+        assert!(log.read("telegram:43").is_empty());
+    }
+}
@@ -0,0 +1,645 @@
+//! Workspace sync — mirror the workspace to WebDAV or S3.
+//!
+//! Memory, skills and notes live as files in the workspace. With `sync`
+//! enabled, the gateway pulls remote changes on startup and then checks
+//! both sides every `intervalSecs`, so a laptop and a server share the
+//! same agent state without a manual rsync.
+//!
+//! Each pass compares local files and the remote listing with what the
+//! previous pass synced (`.oxibot-sync.json` in the workspace): changes on
+//! one side are copied to the other, deletions are mirrored. When both
+//! sides changed, the last writer wins and the other version is kept next
+//! to it as `name.conflict-<time>.ext`.
+
+pub mod s3;
+pub mod webdav;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::config::schema::SyncConfig;
+
+/// Sync state file in the workspace root (never synced itself).
+pub const STATE_FILE: &str = ".oxibot-sync.json";
+
+/// A file in remote storage.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteFile {
+    /// Changes whenever the content changes.
+    pub etag: String,
+    pub modified: DateTime<Utc>,
+}
+
+/// Remote storage holding a copy of the workspace. Paths are relative,
+/// `/`-separated.
+#[async_trait]
+pub trait SyncBackend: Send + Sync {
+    /// Every file under the sync root.
+    async fn list(&self) -> Result<HashMap<String, RemoteFile>>;
+
+    async fn get(&self, path: &str) -> Result<Vec<u8>>;
+
+    /// Upload `data`; returns the new etag when the server reports one.
+    async fn put(&self, path: &str, data: Vec<u8>) -> Result<Option<String>>;
+
+    async fn delete(&self, path: &str) -> Result<()>;
+}
+
+/// What one pass changed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub deleted_remote: usize,
+    pub deleted_local: usize,
+    pub conflicts: usize,
+    /// Files that could not be synced this pass (logged).
+    pub failed: usize,
+}
+
+impl SyncReport {
+    /// Nothing changed on either side.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pushed, {} pulled, {} deleted remotely, {} deleted locally, {} conflicts",
+            self.pushed, self.pulled, self.deleted_remote, self.deleted_local, self.conflicts
+        )?;
+        if self.failed > 0 {
+            write!(f, ", {} failed", self.failed)?;
+        }
+        Ok(())
+    }
+}
+
+/// The synced version of a file, as of the last pass.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Synced {
+    /// SHA-256 of the content.
+    hash: String,
+    /// Remote etag (empty when the server reported none on upload).
+    etag: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    files: BTreeMap<String, Synced>,
+}
+
+/// A file in the workspace.
+#[derive(Clone, Debug)]
+struct LocalFile {
+    hash: String,
+    modified: DateTime<Utc>,
+}
+
+/// Keeps a workspace and a [`SyncBackend`] in step.
+pub struct WorkspaceSync {
+    root: PathBuf,
+    backend: Box<dyn SyncBackend>,
+    exclude: Vec<String>,
+    /// One pass at a time.
+    running: Mutex<()>,
+}
+
+impl WorkspaceSync {
+    pub fn new(root: &Path, backend: Box<dyn SyncBackend>, exclude: Vec<String>) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            backend,
+            exclude,
+            running: Mutex::new(()),
+        }
+    }
+
+    /// Sync for `root` as configured; `None` when sync is disabled.
+    pub fn from_config(config: &SyncConfig, root: &Path) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let backend: Box<dyn SyncBackend> = match config.backend.as_str() {
+            "webdav" => Box::new(webdav::WebDavBackend::new(&config.webdav)?),
+            "s3" => Box::new(s3::S3Backend::new(&config.s3)?),
+            other => anyhow::bail!("unknown sync backend {other:?} (use webdav or s3)"),
+        };
+        Ok(Some(Self::new(root, backend, config.exclude.clone())))
+    }
+
+    /// Sync every `interval`, forever. Failed passes are logged and retried.
+    pub async fn run(self: Arc<Self>, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            match self.sync().await {
+                Ok(report) if !report.is_empty() => info!(%report, "workspace synced"),
+                Ok(_) => {}
+                Err(e) => warn!(error = %format!("{e:#}"), "workspace sync failed"),
+            }
+        }
+    }
+
+    /// Run one pass in both directions.
+    pub async fn sync(&self) -> Result<SyncReport> {
+        let _running = self.running.lock().await;
+        let mut state = self.load_state();
+        let local = self.scan_local()?;
+        let remote: HashMap<String, RemoteFile> = self
+            .backend
+            .list()
+            .await
+            .context("cannot list remote files")?
+            .into_iter()
+            .filter(|(path, _)| {
+                let safe = is_safe_path(path);
+                if !safe {
+                    warn!(path = %path, "skipping remote file with an unsafe path");
+                }
+                safe && !self.is_excluded(path)
+            })
+            .collect();
+
+        state.files.retain(|path, _| is_safe_path(path));
+        let paths: BTreeSet<String> = local
+            .keys()
+            .chain(remote.keys())
+            .chain(state.files.keys())
+            .cloned()
+            .collect();
+        let mut report = SyncReport::default();
+        for path in paths {
+            let (l, r) = (local.get(&path), remote.get(&path));
+            if let Err(e) = self.reconcile(&path, l, r, &mut state, &mut report).await {
+                warn!(path = %path, error = %format!("{e:#}"), "cannot sync file");
+                report.failed += 1;
+            }
+        }
+        self.save_state(&state)?;
+        Ok(report)
+    }
+
+    /// Bring one path in step.
+    async fn reconcile(
+        &self,
+        path: &str,
+        local: Option<&LocalFile>,
+        remote: Option<&RemoteFile>,
+        state: &mut SyncState,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let base = state.files.get(path);
+        let local_changed = match (base, local) {
+            (Some(base), Some(local)) => base.hash != local.hash,
+            (None, None) => false,
+            _ => true,
+        };
+        let remote_changed = match (base, remote) {
+            (Some(base), Some(remote)) => base.etag != remote.etag,
+            (None, None) => false,
+            _ => true,
+        };
+
+        match (local_changed, remote_changed, local, remote) {
+            (false, false, _, _) => {}
+            (_, _, None, None) => {
+                state.files.remove(path);
+            }
+            // Edits win over deletions
+            (true, false, Some(_), _) | (true, true, Some(_), None) => {
+                self.push(path, state, report).await?;
+            }
+            (false, true, _, Some(remote)) | (true, true, None, Some(remote)) => {
+                self.pull(path, local, remote, state, report).await?;
+            }
+            (true, false, None, Some(_)) => {
+                self.backend.delete(path).await?;
+                state.files.remove(path);
+                report.deleted_remote += 1;
+            }
+            (false, true, Some(_), None) => {
+                std::fs::remove_file(self.local_path(path)?)?;
+                state.files.remove(path);
+                report.deleted_local += 1;
+            }
+            (true, true, Some(local), Some(remote)) => {
+                // Both sides changed: unless they agree, the newer one wins
+                let data = self.backend.get(path).await?;
+                let hash = sha256_hex(&data);
+                if hash == local.hash {
+                    state.files.insert(path.to_string(), Synced { hash, etag: remote.etag.clone() });
+                    return Ok(());
+                }
+                let copy = conflict_path(path, Utc::now());
+                report.conflicts += 1;
+                if local.modified >= remote.modified {
+                    self.write_local(&copy, &data)?;
+                    warn!(path, copy = %copy, "sync conflict, kept the remote version as a copy");
+                    self.push(path, state, report).await?;
+                } else {
+                    std::fs::rename(self.local_path(path)?, self.local_path(&copy)?)?;
+                    self.write_local(path, &data)?;
+                    warn!(path, copy = %copy, "sync conflict, kept the local version as a copy");
+                    state.files.insert(path.to_string(), Synced { hash, etag: remote.etag.clone() });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn push(&self, path: &str, state: &mut SyncState, report: &mut SyncReport) -> Result<()> {
+        let data = std::fs::read(self.local_path(path)?)?;
+        let hash = sha256_hex(&data);
+        let etag = self.backend.put(path, data).await?.unwrap_or_default();
+        state.files.insert(path.to_string(), Synced { hash, etag });
+        report.pushed += 1;
+        Ok(())
+    }
+
+    async fn pull(
+        &self,
+        path: &str,
+        local: Option<&LocalFile>,
+        remote: &RemoteFile,
+        state: &mut SyncState,
+        report: &mut SyncReport,
+    ) -> Result<()> {
+        let data = self.backend.get(path).await?;
+        let hash = sha256_hex(&data);
+        // A new etag for content we already have (e.g. after our own upload)
+        if local.is_none_or(|local| local.hash != hash) {
+            self.write_local(path, &data)?;
+            report.pulled += 1;
+        }
+        state.files.insert(path.to_string(), Synced { hash, etag: remote.etag.clone() });
+        Ok(())
+    }
+
+    /// Where `path` lives in the workspace; fails for paths that would
+    /// leave it.
+    fn local_path(&self, path: &str) -> Result<PathBuf> {
+        if !is_safe_path(path) {
+            anyhow::bail!("unsafe path {path:?}");
+        }
+        Ok(self.root.join(path))
+    }
+
+    /// Write via a temporary file, so readers never see half a file.
+    fn write_local(&self, path: &str, data: &[u8]) -> Result<()> {
+        let target = self.local_path(path)?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = target.with_file_name(format!(
+            ".{}.sync-tmp",
+            target.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, &target)?;
+        Ok(())
+    }
+
+    /// Whether `path` is left out of the sync.
+    fn is_excluded(&self, path: &str) -> bool {
+        path == STATE_FILE
+            || path.ends_with(".sync-tmp")
+            || self.exclude.iter().any(|pattern| {
+                if let Some(ext) = pattern.strip_prefix('*') {
+                    path.ends_with(ext)
+                } else if pattern.ends_with('/') {
+                    path.starts_with(pattern.as_str())
+                } else {
+                    path == pattern
+                }
+            })
+    }
+
+    /// Every workspace file that is not excluded.
+    fn scan_local(&self) -> Result<HashMap<String, LocalFile>> {
+        let mut files = HashMap::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if dir == self.root => {
+                    return Err(e).with_context(|| format!("cannot read {}", dir.display()))
+                }
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(relative) = path.strip_prefix(&self.root) else { continue };
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let Ok(file_type) = entry.file_type() else { continue };
+                if file_type.is_dir() {
+                    if !self.is_excluded(&format!("{relative}/")) {
+                        dirs.push(path);
+                    }
+                } else if file_type.is_file() && !self.is_excluded(&relative) {
+                    let data = std::fs::read(&path)?;
+                    let modified = entry.metadata()?.modified()?;
+                    files.insert(
+                        relative,
+                        LocalFile {
+                            hash: sha256_hex(&data),
+                            modified: modified.into(),
+                        },
+                    );
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    fn load_state(&self) -> SyncState {
+        std::fs::read_to_string(self.root.join(STATE_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_state(&self, state: &SyncState) -> Result<()> {
+        self.write_local(STATE_FILE, serde_json::to_string_pretty(state)?.as_bytes())
+            .context("cannot save sync state")
+    }
+}
+
+// ─────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────
+
+/// Whether `path` stays inside the sync root: relative, with no `..` or
+/// root components.
+pub(crate) fn is_safe_path(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Name for the losing version of `path`: `notes/a.conflict-20250101-120000.md`.
+fn conflict_path(path: &str, now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%d-%H%M%S");
+    let (dir, name) = path.rsplit_once('/').map_or(("", path), |(d, n)| (d, n));
+    let name = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem}.conflict-{stamp}.{ext}"),
+        _ => format!("{name}.conflict-{stamp}"),
+    };
+    if dir.is_empty() {
+        name
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters (and `/`
+/// when `keep_slash`).
+pub(crate) fn percent_encode(s: &str, keep_slash: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+pub(crate) fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// The contents of every `<name>` element in `xml`, whatever its namespace
+/// prefix. Enough for WebDAV and S3 listings, not a general XML parser.
+pub(crate) fn xml_elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let tag_end = rest.find('>').unwrap_or(rest.len());
+        let tag = &rest[..tag_end];
+        let full_name = tag.split_whitespace().next().unwrap_or_default();
+        if full_name.rsplit(':').next() != Some(name) || tag.ends_with('/') {
+            continue;
+        }
+        let body = &rest[(tag_end + 1).min(rest.len())..];
+        let close = format!("</{full_name}>");
+        if let Some(end) = body.find(&close) {
+            found.push(&body[..end]);
+            rest = &body[end + close.len()..];
+        }
+    }
+    found
+}
+
+/// Text of the first `<name>` element, entities decoded.
+pub(crate) fn xml_text(xml: &str, name: &str) -> Option<String> {
+    let text = xml_elements(xml, name).into_iter().next()?;
+    Some(
+        text.trim()
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    type Files = HashMap<String, (Vec<u8>, RemoteFile)>;
+
+    /// Remote storage in memory; clones share the files.
+    #[derive(Clone, Default)]
+    struct MemoryBackend {
+        files: Arc<StdMutex<Files>>,
+    }
+
+    impl MemoryBackend {
+        fn write(&self, path: &str, data: &str, modified: DateTime<Utc>) {
+            let mut files = self.files.lock().unwrap();
+            let etag = format!("e{}", files.len() + 100 + data.len());
+            files.insert(path.into(), (data.as_bytes().to_vec(), RemoteFile { etag, modified }));
+        }
+
+        fn read(&self, path: &str) -> Option<String> {
+            let files = self.files.lock().unwrap();
+            files.get(path).map(|(data, _)| String::from_utf8(data.clone()).unwrap())
+        }
+    }
+
+    #[async_trait]
+    impl SyncBackend for MemoryBackend {
+        async fn list(&self) -> Result<HashMap<String, RemoteFile>> {
+            let files = self.files.lock().unwrap();
+            Ok(files.iter().map(|(p, (_, f))| (p.clone(), f.clone())).collect())
+        }
+
+        async fn get(&self, path: &str) -> Result<Vec<u8>> {
+            let files = self.files.lock().unwrap();
+            files.get(path).map(|(d, _)| d.clone()).context("not found")
+        }
+
+        async fn put(&self, path: &str, data: Vec<u8>) -> Result<Option<String>> {
+            self.write(path, &String::from_utf8(data).unwrap(), Utc::now());
+            Ok(None)
+        }
+
+        async fn delete(&self, path: &str) -> Result<()> {
+            self.files.lock().unwrap().remove(path);
+            Ok(())
+        }
+    }
+
+    fn setup() -> (tempfile::TempDir, WorkspaceSync, MemoryBackend) {
+        let dir = tempfile::tempdir().unwrap();
+        let remote = MemoryBackend::default();
+        let sync = WorkspaceSync::new(dir.path(), Box::new(remote.clone()), vec!["sessions/".into(), "*.tmp".into()]);
+        (dir, sync, remote)
+    }
+
+    #[tokio::test]
+    async fn test_push_pull_and_delete() {
+        let (dir, sync, remote) = setup();
+        std::fs::create_dir_all(dir.path().join("memory")).unwrap();
+        std::fs::create_dir_all(dir.path().join("sessions/x")).unwrap();
+        std::fs::write(dir.path().join("memory/MEMORY.md"), "likes tea").unwrap();
+        std::fs::write(dir.path().join("sessions/x/scratch.txt"), "skip").unwrap();
+        std::fs::write(dir.path().join("draft.tmp"), "skip").unwrap();
+
+        let report = sync.sync().await.unwrap();
+        assert_eq!(report.pushed, 1);
+        assert_eq!(remote.read("memory/MEMORY.md").as_deref(), Some("likes tea"));
+        assert_eq!(remote.files.lock().unwrap().len(), 1);
+
+        // Unchanged: nothing to do (the missing upload etag is picked up)
+        assert_eq!(sync.sync().await.unwrap(), SyncReport::default());
+
+        // Remote edit and a new remote file are pulled
+        remote.write("memory/MEMORY.md", "likes coffee", Utc::now());
+        remote.write("skills/x/SKILL.md", "# X", Utc::now());
+        assert_eq!(sync.sync().await.unwrap().pulled, 2);
+        assert_eq!(std::fs::read_to_string(dir.path().join("memory/MEMORY.md")).unwrap(), "likes coffee");
+
+        // Deletions are mirrored both ways
+        std::fs::remove_file(dir.path().join("skills/x/SKILL.md")).unwrap();
+        remote.files.lock().unwrap().remove("memory/MEMORY.md");
+        let report = sync.sync().await.unwrap();
+        assert_eq!((report.deleted_remote, report.deleted_local), (1, 1));
+        assert!(remote.files.lock().unwrap().is_empty());
+        assert!(!dir.path().join("memory/MEMORY.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_conflict_keeps_both_versions() {
+        let (dir, sync, remote) = setup();
+        std::fs::write(dir.path().join("notes.md"), "v1").unwrap();
+        sync.sync().await.unwrap();
+        sync.sync().await.unwrap();
+
+        // Both sides edit; the remote edit is newer and wins
+        std::fs::write(dir.path().join("notes.md"), "local v2").unwrap();
+        remote.write("notes.md", "remote v2", Utc::now() + chrono::Duration::hours(1));
+        let report = sync.sync().await.unwrap();
+        assert_eq!(report.conflicts, 1);
+        assert_eq!(std::fs::read_to_string(dir.path().join("notes.md")).unwrap(), "remote v2");
+        let copy = std::fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .find(|n| n.starts_with("notes.conflict-"))
+            .unwrap();
+        assert!(copy.ends_with(".md"));
+        assert_eq!(std::fs::read_to_string(dir.path().join(&copy)).unwrap(), "local v2");
+
+        // The conflict copy is synced like any other file
+        assert_eq!(sync.sync().await.unwrap().pushed, 1);
+        assert_eq!(remote.read(&copy).as_deref(), Some("local v2"));
+    }
+
+    #[tokio::test]
+    async fn test_unsafe_remote_paths_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        let remote = MemoryBackend::default();
+        let sync = WorkspaceSync::new(&workspace, Box::new(remote.clone()), Vec::new());
+        remote.write("../escape.txt", "x", Utc::now());
+        remote.write("/tmp/oxibot-sync-abs.txt", "x", Utc::now());
+        remote.write("notes/../../up.txt", "x", Utc::now());
+        remote.write("notes/ok.md", "fine", Utc::now());
+
+        let report = sync.sync().await.unwrap();
+        assert_eq!((report.pulled, report.failed), (1, 0));
+        assert!(!dir.path().join("escape.txt").exists());
+        assert!(!dir.path().join("up.txt").exists());
+        assert!(!Path::new("/tmp/oxibot-sync-abs.txt").exists());
+        assert_eq!(std::fs::read_to_string(workspace.join("notes/ok.md")).unwrap(), "fine");
+        assert!(sync.local_path("../x").is_err());
+    }
+
+    #[test]
+    fn test_conflict_path() {
+        let now = DateTime::parse_from_rfc3339("2025-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+        assert_eq!(conflict_path("notes/a.md", now), "notes/a.conflict-20250102-030405.md");
+        assert_eq!(conflict_path("Makefile", now), "Makefile.conflict-20250102-030405");
+        assert_eq!(conflict_path(".env", now), ".env.conflict-20250102-030405");
+    }
+
+    #[test]
+    fn test_xml_helpers() {
+        let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/a%20b.md</d:href>
+            <d:getetag>&quot;abc&quot;</d:getetag><d:resourcetype/></d:response>
+            <d:response><d:href>/dir/</d:href><d:resourcetype><d:collection/></d:resourcetype></d:response></d:multistatus>"#;
+        let responses = xml_elements(xml, "response");
+        assert_eq!(responses.len(), 2);
+        assert_eq!(xml_text(responses[0], "getetag").as_deref(), Some("\"abc\""));
+        assert_eq!(percent_decode(&xml_text(responses[0], "href").unwrap()), "/a b.md");
+        assert!(xml_elements(responses[0], "resourcetype").is_empty());
+        assert!(xml_elements(responses[1], "resourcetype")[0].contains("collection"));
+        assert_eq!(percent_encode("notes/a b+c.md", true), "notes/a%20b%2Bc.md");
+    }
+}
@@ -0,0 +1,309 @@
+//! S3 sync backend (AWS S3, MinIO, Cloudflare R2, Backblaze B2…).
+//!
+//! Requests are signed with AWS Signature Version 4 and use path-style
+//! URLs (`{endpoint}/{bucket}/{key}`), which every S3-compatible store
+//! accepts.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use sha2::{Digest, Sha256};
+
+use super::{hex, is_safe_path, percent_encode, sha256_hex, xml_elements, xml_text, RemoteFile, SyncBackend};
+use crate::config::schema::S3SyncConfig;
+
+/// Files under a prefix of an S3 bucket.
+pub struct S3Backend {
+    client: reqwest::Client,
+    /// `https://host[:port]`, no trailing slash.
+    endpoint: String,
+    /// `host[:port]`, as signed.
+    host: String,
+    bucket: String,
+    /// Key prefix, empty or ending with `/`.
+    prefix: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3Backend {
+    pub fn new(config: &S3SyncConfig) -> Result<Self> {
+        if config.bucket.is_empty() {
+            bail!("sync.s3.bucket is not set");
+        }
+        if config.access_key_id.is_empty() || config.secret_access_key.is_empty() {
+            bail!("sync.s3.accessKeyId and sync.s3.secretAccessKey are required");
+        }
+        let endpoint = if config.endpoint.is_empty() {
+            format!("https://s3.{}.amazonaws.com", config.region)
+        } else {
+            config.endpoint.trim_end_matches('/').to_string()
+        };
+        let host = endpoint
+            .split_once("://")
+            .map_or(endpoint.as_str(), |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let prefix = config.prefix.trim_matches('/');
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            host,
+            bucket: config.bucket.clone(),
+            prefix: if prefix.is_empty() { String::new() } else { format!("{prefix}/") },
+            region: config.region.clone(),
+            access_key_id: config.access_key_id.clone(),
+            secret_access_key: config.secret_access_key.clone(),
+        })
+    }
+
+    /// A signed request for `key` (the bucket itself when `None`).
+    fn request(
+        &self,
+        method: Method,
+        key: Option<&str>,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> reqwest::RequestBuilder {
+        let mut uri = format!("/{}", percent_encode(&self.bucket, false));
+        if let Some(key) = key {
+            uri.push('/');
+            uri.push_str(&percent_encode(&format!("{}{key}", self.prefix), true));
+        }
+        let mut query: Vec<String> = query
+            .iter()
+            .map(|(k, v)| format!("{}={}", percent_encode(k, false), percent_encode(v, false)))
+            .collect();
+        query.sort();
+        let query = query.join("&");
+
+        let now = Utc::now();
+        let headers = self.sign(method.as_str(), &uri, &query, &sha256_hex(&body), now);
+        let url = if query.is_empty() {
+            format!("{}{uri}", self.endpoint)
+        } else {
+            format!("{}{uri}?{query}", self.endpoint)
+        };
+        let mut request = self.client.request(method, url).body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    /// The SigV4 headers for a request (`x-amz-date`, `x-amz-content-sha256`,
+    /// `Authorization`).
+    fn sign(
+        &self,
+        method: &str,
+        uri: &str,
+        query: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{method}\n{uri}\n{query}\nhost:{}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+            self.host
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let key = signing_key(&self.secret_access_key, &date, &self.region, "s3");
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+        vec![
+            ("x-amz-date", amz_date),
+            ("x-amz-content-sha256", payload_hash.to_string()),
+            (
+                "authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                    self.access_key_id
+                ),
+            ),
+        ]
+    }
+}
+
+#[async_trait]
+impl SyncBackend for S3Backend {
+    async fn list(&self) -> Result<HashMap<String, RemoteFile>> {
+        let mut files = HashMap::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", self.prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token));
+            }
+            let response = self.request(Method::GET, None, &query, Vec::new()).send().await?;
+            let status = response.status();
+            let body = response.text().await?;
+            if !status.is_success() {
+                bail!("listing bucket {} failed: HTTP {status}: {body}", self.bucket);
+            }
+            let (page, next) = parse_list(&body);
+            for (key, file) in page {
+                if let Some(path) = key.strip_prefix(&self.prefix) {
+                    if is_safe_path(path) && !path.ends_with('/') {
+                        files.insert(path.to_string(), file);
+                    }
+                }
+            }
+            match next {
+                Some(next) => token = Some(next),
+                None => return Ok(files),
+            }
+        }
+    }
+
+    async fn get(&self, path: &str) -> Result<Vec<u8>> {
+        let response = self.request(Method::GET, Some(path), &[], Vec::new()).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("GET {path} failed: HTTP {status}");
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn put(&self, path: &str, data: Vec<u8>) -> Result<Option<String>> {
+        let response = self.request(Method::PUT, Some(path), &[], data).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("PUT {path} failed: HTTP {status}: {}", response.text().await.unwrap_or_default());
+        }
+        Ok(response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string))
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let response = self.request(Method::DELETE, Some(path), &[], Vec::new()).send().await?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            bail!("DELETE {path} failed: HTTP {status}");
+        }
+        Ok(())
+    }
+}
+
+/// Objects of a ListObjectsV2 page, and the continuation token if truncated.
+fn parse_list(xml: &str) -> (Vec<(String, RemoteFile)>, Option<String>) {
+    let objects = xml_elements(xml, "Contents")
+        .into_iter()
+        .filter_map(|object| {
+            let key = xml_text(object, "Key")?;
+            let etag = xml_text(object, "ETag").unwrap_or_default();
+            let modified = xml_text(object, "LastModified")
+                .and_then(|m| DateTime::parse_from_rfc3339(&m).ok())
+                .map_or(DateTime::UNIX_EPOCH, |m| m.with_timezone(&Utc));
+            Some((key, RemoteFile { etag, modified }))
+        })
+        .collect();
+    let truncated = xml_text(xml, "IsTruncated").is_some_and(|t| t == "true");
+    let next = xml_text(xml, "NextContinuationToken").filter(|_| truncated);
+    (objects, next)
+}
+
+/// SigV4 signing key for `date` (`YYYYMMDD`), region and service.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// HMAC-SHA256 (RFC 2104).
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut padded = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        padded[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        padded[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(padded.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(padded.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_and_signing_key() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Example from the AWS SigV4 documentation
+        assert_eq!(
+            hex(&signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam")),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_sign_headers() {
+        let backend = S3Backend::new(&S3SyncConfig {
+            bucket: "oxibot".into(),
+            prefix: "/laptop/".into(),
+            endpoint: "http://localhost:9000/".into(),
+            access_key_id: "AKID".into(),
+            secret_access_key: "secret".into(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(backend.host, "localhost:9000");
+        assert_eq!(backend.prefix, "laptop/");
+
+        let now = DateTime::parse_from_rfc3339("2025-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+        let headers = backend.sign("GET", "/oxibot/laptop/a.md", "", &sha256_hex(b""), now);
+        assert_eq!(headers[0], ("x-amz-date", "20250102T030405Z".to_string()));
+        let auth = &headers[2].1;
+        assert!(auth.starts_with("AWS4-HMAC-SHA256 Credential=AKID/20250102/us-east-1/s3/aws4_request, "));
+        assert!(auth.contains("SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="));
+        // Deterministic for the same input
+        assert_eq!(headers, backend.sign("GET", "/oxibot/laptop/a.md", "", &sha256_hex(b""), now));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>oxibot</Name><IsTruncated>true</IsTruncated>
+  <Contents><Key>laptop/memory/MEMORY.md</Key><LastModified>2025-01-02T03:04:05.000Z</LastModified>
+    <ETag>&quot;9b2cf535f27731c974343645a3985328&quot;</ETag><Size>10</Size></Contents>
+  <Contents><Key>laptop/notes &amp; ideas.md</Key><ETag>"abc"</ETag></Contents>
+  <NextContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</NextContinuationToken>
+</ListBucketResult>"#;
+        let (objects, next) = parse_list(xml);
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].0, "laptop/memory/MEMORY.md");
+        assert_eq!(objects[0].1.etag, "\"9b2cf535f27731c974343645a3985328\"");
+        assert_eq!(objects[0].1.modified.to_rfc3339(), "2025-01-02T03:04:05+00:00");
+        assert_eq!(objects[1].0, "laptop/notes & ideas.md");
+        assert_eq!(next.as_deref(), Some("1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM="));
+    }
+}
@@ -0,0 +1,291 @@
+//! WebDAV sync backend (Nextcloud, ownCloud, Apache mod_dav, rclone serve…).
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+
+use super::{percent_decode, percent_encode, xml_elements, xml_text, RemoteFile, SyncBackend};
+use crate::config::schema::WebDavSyncConfig;
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/><d:getlastmodified/><d:resourcetype/></d:prop></d:propfind>"#;
+
+/// Files under a WebDAV folder URL.
+pub struct WebDavBackend {
+    client: reqwest::Client,
+    /// Folder URL, ending with `/`.
+    base: String,
+    /// Path part of `base`, decoded, to turn hrefs into relative paths.
+    base_path: String,
+    username: String,
+    password: String,
+    /// Folders known to exist remotely.
+    folders: Mutex<HashSet<String>>,
+}
+
+impl WebDavBackend {
+    pub fn new(config: &WebDavSyncConfig) -> Result<Self> {
+        if config.url.is_empty() {
+            bail!("sync.webdav.url is not set");
+        }
+        let base = format!("{}/", config.url.trim_end_matches('/'));
+        Ok(Self {
+            client: reqwest::Client::new(),
+            base_path: percent_decode(url_path(&base)),
+            base,
+            username: config.username.clone(),
+            password: config.password.clone(),
+            folders: Mutex::new(HashSet::new()),
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .request(method, format!("{}{}", self.base, percent_encode(path, true)));
+        if self.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.username, Some(&self.password))
+        }
+    }
+
+    /// Relative path of a PROPFIND href, `None` outside the sync folder.
+    fn relative(&self, href: &str) -> Option<String> {
+        let path = percent_decode(url_path(href));
+        path.strip_prefix(&self.base_path).map(str::to_string)
+    }
+
+    /// Create the folders above `path`, like `mkdir -p`.
+    async fn create_parents(&self, path: &str) -> Result<()> {
+        let mut folder = String::new();
+        for part in path.split('/').rev().skip(1).collect::<Vec<_>>().into_iter().rev() {
+            folder.push_str(part);
+            folder.push('/');
+            if self.folders.lock().unwrap().contains(&folder) {
+                continue;
+            }
+            let response = self
+                .request(Method::from_bytes(b"MKCOL")?, &folder)
+                .send()
+                .await?;
+            // 405: it already exists
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                bail!("cannot create folder {folder}: HTTP {status}");
+            }
+            self.folders.lock().unwrap().insert(folder.clone());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SyncBackend for WebDavBackend {
+    async fn list(&self) -> Result<HashMap<String, RemoteFile>> {
+        let mut files = HashMap::new();
+        let mut pending = vec![String::new()];
+        while let Some(folder) = pending.pop() {
+            let response = self
+                .request(Method::from_bytes(b"PROPFIND")?, &folder)
+                .header("Depth", "1")
+                .header("Content-Type", "application/xml")
+                .body(PROPFIND_BODY)
+                .send()
+                .await?;
+            let status = response.status();
+            if status == StatusCode::NOT_FOUND && folder.is_empty() {
+                // Nothing synced yet: create the sync folder
+                let response = self.request(Method::from_bytes(b"MKCOL")?, "").send().await?;
+                if !response.status().is_success() {
+                    bail!("cannot create {}: HTTP {}", self.base, response.status());
+                }
+                return Ok(files);
+            }
+            if !status.is_success() {
+                bail!("PROPFIND {folder} failed: HTTP {status}");
+            }
+            let body = response.text().await?;
+            for entry in parse_multistatus(&body) {
+                let Some(path) = self.relative(&entry.href) else { continue };
+                let path = path.trim_end_matches('/').to_string();
+                if path == folder.trim_end_matches('/') {
+                    continue; // the folder itself
+                }
+                if entry.is_folder {
+                    let folder = format!("{path}/");
+                    self.folders.lock().unwrap().insert(folder.clone());
+                    pending.push(folder);
+                } else {
+                    files.insert(
+                        path,
+                        RemoteFile {
+                            etag: entry.etag,
+                            modified: entry.modified,
+                        },
+                    );
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    async fn get(&self, path: &str) -> Result<Vec<u8>> {
+        let response = self.request(Method::GET, path).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("GET {path} failed: HTTP {status}");
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn put(&self, path: &str, data: Vec<u8>) -> Result<Option<String>> {
+        self.create_parents(path).await?;
+        let response = self.request(Method::PUT, path).body(data).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("PUT {path} failed: HTTP {status}");
+        }
+        Ok(response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string))
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let response = self.request(Method::DELETE, path).send().await?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            bail!("DELETE {path} failed: HTTP {status}");
+        }
+        Ok(())
+    }
+}
+
+/// One `<response>` of a PROPFIND reply.
+#[derive(Debug, PartialEq)]
+struct DavEntry {
+    href: String,
+    etag: String,
+    modified: DateTime<Utc>,
+    is_folder: bool,
+}
+
+fn parse_multistatus(xml: &str) -> Vec<DavEntry> {
+    xml_elements(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = xml_text(response, "href")?;
+            let modified = xml_text(response, "getlastmodified")
+                .and_then(|m| DateTime::parse_from_rfc2822(&m).ok())
+                .map_or(DateTime::UNIX_EPOCH, |m| m.with_timezone(&Utc));
+            let is_folder = xml_elements(response, "resourcetype")
+                .first()
+                .is_some_and(|t| t.contains("collection"));
+            // Servers without etags: the modification time has to do
+            let etag = xml_text(response, "getetag").unwrap_or_else(|| modified.to_rfc3339());
+            Some(DavEntry {
+                href,
+                etag,
+                modified,
+                is_folder,
+            })
+        })
+        .collect()
+}
+
+/// The path of a URL (`https://host/a/b` → `/a/b`); paths pass through.
+fn url_path(url: &str) -> &str {
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]),
+        None => url,
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response><d:href>/dav/oxibot/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>
+  <d:response><d:href>https://cloud.example.com/dav/oxibot/My%20Notes.md</d:href>
+    <d:propstat><d:prop><d:getetag>"5f2"</d:getetag>
+      <d:getlastmodified>Tue, 07 Jan 2025 10:00:00 GMT</d:getlastmodified><d:resourcetype/></d:prop></d:propstat></d:response>
+</d:multistatus>"#;
+        let entries = parse_multistatus(xml);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_folder);
+        assert_eq!(entries[1].etag, "\"5f2\"");
+        assert_eq!(entries[1].modified.to_rfc3339(), "2025-01-07T10:00:00+00:00");
+
+        let backend = WebDavBackend::new(&WebDavSyncConfig {
+            url: "https://cloud.example.com/dav/oxibot".into(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(backend.relative(&entries[1].href).as_deref(), Some("My Notes.md"));
+        assert_eq!(backend.relative("/elsewhere/x.md"), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_recurses_and_put_creates_folders() {
+        let server = MockServer::start().await;
+        let root = r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/ws/</d:href><d:resourcetype><d:collection/></d:resourcetype></d:response>
+<d:response><d:href>/ws/memory/</d:href><d:resourcetype><d:collection/></d:resourcetype></d:response>
+</d:multistatus>"#;
+        let memory = r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/ws/memory/</d:href><d:resourcetype><d:collection/></d:resourcetype></d:response>
+<d:response><d:href>/ws/memory/MEMORY.md</d:href><d:getetag>"1"</d:getetag><d:resourcetype/></d:response>
+</d:multistatus>"#;
+        Mock::given(method("PROPFIND"))
+            .and(path("/ws/"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(root))
+            .mount(&server)
+            .await;
+        Mock::given(method("PROPFIND"))
+            .and(path("/ws/memory/"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(memory))
+            .mount(&server)
+            .await;
+        Mock::given(method("MKCOL"))
+            .and(path("/ws/skills/"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/ws/skills/SKILL.md"))
+            .respond_with(ResponseTemplate::new(201).insert_header("ETag", "\"2\""))
+            .mount(&server)
+            .await;
+
+        let backend = WebDavBackend::new(&WebDavSyncConfig {
+            url: format!("{}/ws", server.uri()),
+            username: "u".into(),
+            password: "p".into(),
+        })
+        .unwrap();
+        let files = backend.list().await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files["memory/MEMORY.md"].etag, "\"1\"");
+
+        let etag = backend.put("skills/SKILL.md", b"# Skill".to_vec()).await.unwrap();
+        assert_eq!(etag.as_deref(), Some("\"2\""));
+    }
+}
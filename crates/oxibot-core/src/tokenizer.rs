@@ -0,0 +1,442 @@
+//! Token counting — tiktoken-style BPE files with a heuristic fallback.
+//!
+//! Tokenizer files are brought by the user: `<name>.tiktoken` in the
+//! tokenizer directory (default `~/.oxibot/tokenizers`), in the format
+//! OpenAI publishes (`<base64 token> <rank>` per line). Which file a model
+//! uses comes from `tokenizer.models` in the config or from the provider
+//! registry. Without a file, counts are estimated from the text.
+//!
+//! Counts are used for context budgeting, estimating usage when a provider
+//! reports none, and tool result compaction.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{bail, Context};
+use tracing::{debug, info, warn};
+
+use crate::types::{ContentPart, Message, MessageContent};
+
+/// Name that always selects the heuristic tokenizer.
+pub const HEURISTIC: &str = "heuristic";
+
+/// Tokens added per message for the role and separators.
+const MESSAGE_OVERHEAD: usize = 4;
+
+/// Tokens priming the assistant reply.
+const REPLY_OVERHEAD: usize = 3;
+
+/// Tokens counted per image (a high-detail 512px tile layout).
+const IMAGE_TOKENS: usize = 765;
+
+/// Tokens counted per uploaded file. The real cost depends on the
+/// document, which only the provider sees; this is a few pages' worth.
+const FILE_TOKENS: usize = 2000;
+
+/// Longer pieces (e.g. a minified blob) are merged in chunks of this size,
+/// keeping BPE merging from going quadratic on them.
+const MAX_PIECE_BYTES: usize = 256;
+
+// ─────────────────────────────────────────────
+// Tokenizer trait
+// ─────────────────────────────────────────────
+
+/// Something that can count tokens.
+pub trait Tokenizer: Send + Sync {
+    /// Tokenizer name (e.g. "cl100k_base", "heuristic").
+    fn name(&self) -> &str;
+
+    /// Number of tokens in `text`.
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Estimate used when no tokenizer file is available: about four ASCII
+/// characters per token, one token per other character (CJK, emoji).
+///
+/// Errs on the high side for accented Latin text.
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        HEURISTIC
+    }
+
+    fn count(&self, text: &str) -> usize {
+        let ascii = text.bytes().filter(u8::is_ascii).count();
+        let other = text.chars().filter(|c| !c.is_ascii()).count();
+        ascii.div_ceil(4) + other
+    }
+}
+
+// ─────────────────────────────────────────────
+// BPE
+// ─────────────────────────────────────────────
+
+/// Byte-pair encoding tokenizer loaded from a tiktoken file.
+///
+/// Text is split into words, numbers, punctuation and whitespace the way
+/// tiktoken's pre-tokenizer does (approximately), then each piece is merged
+/// by rank.
+pub struct BpeTokenizer {
+    name: String,
+    ranks: HashMap<Vec<u8>, u32>,
+}
+
+impl BpeTokenizer {
+    /// Parse tiktoken data (`<base64 token> <rank>` per line).
+    pub fn parse(name: &str, data: &str) -> anyhow::Result<Self> {
+        let mut ranks = HashMap::new();
+        for (i, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (token, rank) = line
+                .split_once(' ')
+                .with_context(|| format!("line {}: expected '<token> <rank>'", i + 1))?;
+            let token = base64_decode(token).with_context(|| format!("line {}: invalid base64", i + 1))?;
+            let rank = rank
+                .trim()
+                .parse()
+                .with_context(|| format!("line {}: invalid rank", i + 1))?;
+            ranks.insert(token, rank);
+        }
+        if ranks.is_empty() {
+            bail!("no tokens");
+        }
+        Ok(Self {
+            name: name.to_string(),
+            ranks,
+        })
+    }
+
+    /// Load a tiktoken file; the name is the file stem.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        let name = path.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+        Self::parse(&name, &data)
+    }
+
+    /// Number of tokens in the vocabulary.
+    pub fn vocab_size(&self) -> usize {
+        self.ranks.len()
+    }
+
+    /// Tokens in one pre-tokenized piece.
+    fn count_piece(&self, piece: &[u8]) -> usize {
+        if piece.len() < 2 || self.ranks.contains_key(piece) {
+            return 1;
+        }
+        // Token boundaries; merge the lowest-ranked adjacent pair until none is known
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let best = (0..bounds.len() - 2)
+                .filter_map(|i| self.ranks.get(&piece[bounds[i]..bounds[i + 2]]).map(|r| (*r, i)))
+                .min();
+            match best {
+                Some((_, i)) => {
+                    bounds.remove(i + 1);
+                }
+                None => return bounds.len() - 1,
+            }
+        }
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count(&self, text: &str) -> usize {
+        pieces(text)
+            .flat_map(|p| p.as_bytes().chunks(MAX_PIECE_BYTES))
+            .map(|chunk| self.count_piece(chunk))
+            .sum()
+    }
+}
+
+/// Character classes used by the pre-tokenizer.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Letter,
+    Digit,
+    Space,
+    Newline,
+    Other,
+}
+
+fn class(c: char) -> Class {
+    if c == '\n' || c == '\r' {
+        Class::Newline
+    } else if c.is_whitespace() {
+        Class::Space
+    } else if c.is_alphabetic() || c == '\'' {
+        Class::Letter
+    } else if c.is_numeric() {
+        Class::Digit
+    } else {
+        Class::Other
+    }
+}
+
+/// Split text into pieces: words and punctuation runs (with one leading
+/// space), numbers of up to three digits, and whitespace runs.
+fn pieces(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let mut chars = rest.char_indices().peekable();
+        let (_, first) = chars.next()?;
+        let mut start_class = class(first);
+        // A single space joins the word or punctuation that follows it
+        if first == ' ' {
+            if let Some(&(_, next)) = chars.peek() {
+                if matches!(class(next), Class::Letter | Class::Other) {
+                    start_class = class(next);
+                    chars.next();
+                }
+            }
+        }
+        let mut digits = usize::from(start_class == Class::Digit);
+        let mut end = rest.len();
+        while let Some(&(i, c)) = chars.peek() {
+            let same = match start_class {
+                Class::Digit => class(c) == Class::Digit && digits < 3,
+                Class::Space | Class::Newline => {
+                    // Leave the last space for the next word
+                    let next_is_word = rest[i..]
+                        .chars()
+                        .nth(1)
+                        .is_some_and(|n| matches!(class(n), Class::Letter | Class::Other));
+                    matches!(class(c), Class::Space | Class::Newline) && !(c == ' ' && next_is_word)
+                }
+                class_ => class(c) == class_,
+            };
+            if !same {
+                end = i;
+                break;
+            }
+            digits += 1;
+            chars.next();
+        }
+        let (piece, tail) = rest.split_at(end);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+/// Decode standard base64 (with or without padding).
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buf = 0u32;
+    let mut bits = 0;
+    for b in s.bytes().take_while(|&b| b != b'=') {
+        let v = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buf = (buf << 6) | u32::from(v);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buf >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+// ─────────────────────────────────────────────
+// Loading
+// ─────────────────────────────────────────────
+
+/// Loaded tokenizer files by path (`None` = could not be loaded).
+static LOADED: OnceLock<Mutex<HashMap<PathBuf, Option<Arc<BpeTokenizer>>>>> = OnceLock::new();
+
+/// Tokenizer for `name`: `<dir>/<name>.tiktoken`, or a path when `name`
+/// ends in `.tiktoken`. Falls back to the heuristic when the file is
+/// missing or invalid. Files are loaded once per process.
+pub fn load(name: &str, dir: &Path) -> Arc<dyn Tokenizer> {
+    if name.is_empty() || name == HEURISTIC {
+        return Arc::new(HeuristicTokenizer);
+    }
+    let path = if name.ends_with(".tiktoken") {
+        crate::utils::expand_home(name)
+    } else {
+        dir.join(format!("{name}.tiktoken"))
+    };
+
+    let mut loaded = LOADED.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    let tokenizer = loaded.entry(path.clone()).or_insert_with(|| {
+        if !path.exists() {
+            debug!(path = %path.display(), "tokenizer file not found, estimating tokens");
+            return None;
+        }
+        match BpeTokenizer::load(&path) {
+            Ok(t) => {
+                info!(path = %path.display(), tokens = t.vocab_size(), "loaded tokenizer");
+                Some(Arc::new(t))
+            }
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "invalid tokenizer file, estimating tokens");
+                None
+            }
+        }
+    });
+    match tokenizer {
+        Some(t) => t.clone(),
+        None => Arc::new(HeuristicTokenizer),
+    }
+}
+
+// ─────────────────────────────────────────────
+// Messages
+// ─────────────────────────────────────────────
+
+/// Tokens in one chat message, including role overhead.
+pub fn count_message(tokenizer: &dyn Tokenizer, message: &Message) -> usize {
+    let content = match message {
+        Message::System { content } | Message::Tool { content, .. } => tokenizer.count(content),
+        Message::User { content: MessageContent::Text(text) } => tokenizer.count(text),
+        Message::User { content: MessageContent::Parts(parts) } => parts
+            .iter()
+            .map(|p| match p {
+                ContentPart::Text { text } => tokenizer.count(text),
+                ContentPart::ImageUrl { .. } => IMAGE_TOKENS,
+                ContentPart::File { .. } => FILE_TOKENS,
+            })
+            .sum(),
+        Message::Assistant {
+            content,
+            tool_calls,
+            reasoning_content,
+        } => {
+            content.as_deref().map_or(0, |c| tokenizer.count(c))
+                + reasoning_content.as_deref().map_or(0, |c| tokenizer.count(c))
+                + tool_calls.iter().flatten().map(|tc| {
+                    tokenizer.count(&tc.function.name) + tokenizer.count(&tc.function.arguments)
+                }).sum::<usize>()
+        }
+    };
+    content + MESSAGE_OVERHEAD
+}
+
+/// Tokens in a chat request's messages.
+pub fn count_messages(tokenizer: &dyn Tokenizer, messages: &[Message]) -> usize {
+    messages.iter().map(|m| count_message(tokenizer, m)).sum::<usize>() + REPLY_OVERHEAD
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// base64 of a token.
+    fn b64(token: &str) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let bytes = token.as_bytes();
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (u32::from(*b) << (16 - 8 * i)));
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            }
+        }
+        while !out.len().is_multiple_of(4) {
+            out.push('=');
+        }
+        out
+    }
+
+    /// A tiny vocabulary: all single bytes plus a few merges.
+    fn tiny_bpe() -> BpeTokenizer {
+        let mut lines: Vec<String> = (0u8..=127).map(|b| format!("{} {b}", b64(&(b as char).to_string()))).collect();
+        for (i, merge) in ["he", "ll", "hell", "hello", " w", " wor", "or", "ld", " world"].iter().enumerate() {
+            lines.push(format!("{} {}", b64(merge), 128 + i));
+        }
+        BpeTokenizer::parse("tiny", &lines.join("\n")).unwrap()
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(base64_decode("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(base64_decode("IHdvcmxk").unwrap(), b" world");
+        assert_eq!(base64_decode("IQ").unwrap(), b"!");
+        assert!(base64_decode("a$").is_none());
+    }
+
+    #[test]
+    fn test_pieces() {
+        let split: Vec<&str> = pieces("Hello world, it's 12345!\n\n  ok").collect();
+        assert_eq!(split, ["Hello", " world", ",", " it's", " ", "123", "45", "!", "\n\n ", " ok"]);
+        assert_eq!(pieces("").count(), 0);
+        assert_eq!(pieces("日本語").collect::<Vec<_>>(), ["日本語"]);
+    }
+
+    #[test]
+    fn test_bpe_count() {
+        let bpe = tiny_bpe();
+        assert_eq!(bpe.vocab_size(), 137);
+        assert_eq!(bpe.count("hello world"), 2);
+        assert_eq!(bpe.count("hello"), 1);
+        // "help" → "hel" is not a token: "he" + "l" + "p"
+        assert_eq!(bpe.count("help"), 3);
+        assert_eq!(bpe.count(""), 0);
+        // Long pieces are merged in chunks; only the chunk edges split a word
+        let long = bpe.count(&"hello".repeat(1000));
+        assert!((1000..1050).contains(&long), "{long}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(BpeTokenizer::parse("x", "").is_err());
+        let err = BpeTokenizer::parse("x", "aGk= 1\nbad").err().unwrap();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let h = HeuristicTokenizer;
+        assert_eq!(h.count(""), 0);
+        assert_eq!(h.count("abcd"), 1);
+        assert_eq!(h.count("abcde"), 2);
+        assert_eq!(h.count("日本"), 2);
+    }
+
+    #[test]
+    fn test_load_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load("o200k_base", dir.path()).name(), HEURISTIC);
+        assert_eq!(load("", dir.path()).name(), HEURISTIC);
+
+        std::fs::write(dir.path().join("broken.tiktoken"), "not a tokenizer").unwrap();
+        assert_eq!(load("broken", dir.path()).name(), HEURISTIC);
+
+        std::fs::write(dir.path().join("tiny.tiktoken"), format!("{} 0\n{} 1", b64("a"), b64("b"))).unwrap();
+        let t = load("tiny", dir.path());
+        assert_eq!(t.name(), "tiny");
+        let by_path = dir.path().join("tiny.tiktoken");
+        assert_eq!(load(&by_path.to_string_lossy(), Path::new("/nonexistent")).name(), "tiny");
+    }
+
+    #[test]
+    fn test_count_messages() {
+        let h = HeuristicTokenizer;
+        let messages = vec![Message::system("abcd"), Message::user("abcdefgh")];
+        assert_eq!(count_messages(&h, &messages), 1 + 2 + 2 * MESSAGE_OVERHEAD + REPLY_OVERHEAD);
+        let image = Message::user_parts(vec![ContentPart::ImageUrl {
+            image_url: crate::types::ImageUrl {
+                url: "data:image/png;base64,AAAA".into(),
+                detail: None,
+            },
+        }]);
+        assert_eq!(count_message(&h, &image), IMAGE_TOKENS + MESSAGE_OVERHEAD);
+    }
+}
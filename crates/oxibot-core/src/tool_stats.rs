@@ -0,0 +1,211 @@
+//! Tool stats — call counts, durations and error rates per tool.
+//!
+//! A small JSON file (`~/.oxibot/tool_stats.json`) updated after every tool
+//! call. Unlike the opt-in usage log it keeps only running totals, so it is
+//! always on. Read by `oxibot status --tools` and the `tool_stats` tool to
+//! spot slow or flaky tools.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::utils;
+
+/// Average duration at which a tool is reported as slow.
+pub const SLOW_TOOL_MS: u64 = 10_000;
+
+/// Error rate at which a tool is reported as flaky.
+pub const FLAKY_ERROR_RATE: f64 = 0.2;
+
+/// Calls needed before a tool is reported as slow or flaky.
+pub const MIN_CALLS_TO_FLAG: u64 = 5;
+
+/// Longest error message kept per tool.
+const MAX_ERROR_CHARS: usize = 200;
+
+// ─────────────────────────────────────────────
+// ToolStat
+// ─────────────────────────────────────────────
+
+/// Running totals for one tool.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolStat {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    pub last_used: Option<DateTime<Utc>>,
+    /// Most recent error, truncated.
+    pub last_error: Option<String>,
+}
+
+impl ToolStat {
+    /// Average call duration in milliseconds.
+    pub fn avg_ms(&self) -> u64 {
+        self.total_ms.checked_div(self.calls).unwrap_or(0)
+    }
+
+    /// Fraction of calls that failed.
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.calls as f64
+        }
+    }
+
+    /// Average duration is at least [`SLOW_TOOL_MS`].
+    pub fn is_slow(&self) -> bool {
+        self.calls >= MIN_CALLS_TO_FLAG && self.avg_ms() >= SLOW_TOOL_MS
+    }
+
+    /// Error rate is at least [`FLAKY_ERROR_RATE`].
+    pub fn is_flaky(&self) -> bool {
+        self.calls >= MIN_CALLS_TO_FLAG && self.error_rate() >= FLAKY_ERROR_RATE
+    }
+
+    fn record(&mut self, duration: Duration, error: Option<&str>) {
+        let ms = duration.as_millis() as u64;
+        self.calls += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+        self.last_used = Some(Utc::now());
+        if let Some(error) = error {
+            self.errors += 1;
+            self.last_error = Some(error.chars().take(MAX_ERROR_CHARS).collect());
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// ToolStatsStore
+// ─────────────────────────────────────────────
+
+/// Persistent per-tool stats.
+pub struct ToolStatsStore {
+    /// Backing file.
+    path: PathBuf,
+    /// Serializes writers within the process.
+    lock: Mutex<()>,
+}
+
+impl ToolStatsStore {
+    /// Create a store at `path` (default `~/.oxibot/tool_stats.json`).
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path: path.unwrap_or_else(utils::get_tool_stats_path),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record one call; `error` is set when it failed. Failures to write
+    /// are logged, never fatal.
+    pub fn record(&self, tool: &str, duration: Duration, error: Option<&str>) {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats = self.load();
+        stats.entry(tool.to_string()).or_default().record(duration, error);
+        if let Err(e) = self.save(&stats) {
+            warn!(path = %self.path.display(), error = %e, "failed to write tool stats");
+        }
+    }
+
+    /// Stats for every tool that has been called. A missing or unreadable
+    /// file is empty.
+    pub fn load(&self) -> BTreeMap<String, ToolStat> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Tools sorted by average duration (slowest first).
+    pub fn slowest(&self) -> Vec<(String, ToolStat)> {
+        let mut rows: Vec<_> = self.load().into_iter().collect();
+        rows.sort_by_key(|(_, s)| std::cmp::Reverse(s.avg_ms()));
+        rows
+    }
+
+    /// Forget all stats.
+    pub fn reset(&self) -> anyhow::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn save(&self, stats: &BTreeMap<String, ToolStat>) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so a concurrent reader never sees half a file
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(stats)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ToolStatsStore::new(Some(dir.path().join("tool_stats.json")));
+        assert!(store.load().is_empty());
+
+        store.record("exec", Duration::from_millis(300), None);
+        store.record("exec", Duration::from_millis(100), Some("Error: exit code 1"));
+        store.record("read_file", Duration::from_millis(2), None);
+
+        let stats = ToolStatsStore::new(Some(store.path().to_path_buf())).load();
+        let exec = &stats["exec"];
+        assert_eq!(exec.calls, 2);
+        assert_eq!(exec.errors, 1);
+        assert_eq!(exec.avg_ms(), 200);
+        assert_eq!(exec.max_ms, 300);
+        assert_eq!(exec.error_rate(), 0.5);
+        assert_eq!(exec.last_error.as_deref(), Some("Error: exit code 1"));
+        assert_eq!(stats["read_file"].calls, 1);
+
+        let slowest = store.slowest();
+        assert_eq!(slowest[0].0, "exec");
+
+        store.reset().unwrap();
+        assert!(store.load().is_empty());
+        store.reset().unwrap();
+    }
+
+    #[test]
+    fn test_flags_need_enough_calls() {
+        let mut stat = ToolStat::default();
+        for _ in 0..MIN_CALLS_TO_FLAG - 1 {
+            stat.record(Duration::from_millis(SLOW_TOOL_MS), Some("Error: timeout"));
+        }
+        assert!(!stat.is_slow());
+        assert!(!stat.is_flaky());
+
+        stat.record(Duration::from_millis(SLOW_TOOL_MS), None);
+        assert!(stat.is_slow());
+        assert!(stat.is_flaky());
+        assert!(!ToolStat::default().is_slow());
+        assert_eq!(ToolStat::default().avg_ms(), 0);
+    }
+}
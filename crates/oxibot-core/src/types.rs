@@ -117,6 +117,9 @@ pub enum ContentPart {
     /// Image URL part (can be a URL or base64 data URI).
     #[serde(rename = "image_url")]
     ImageUrl { image_url: ImageUrl },
+    /// File uploaded to the provider beforehand, referenced by id.
+    #[serde(rename = "file")]
+    File { file: FileRef },
 }
 
 /// Image URL payload.
@@ -127,6 +130,14 @@ pub struct ImageUrl {
     pub detail: Option<String>,
 }
 
+/// File reference payload.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FileRef {
+    pub file_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+}
+
 // ─────────────────────────────────────────────
 // Tool Calls (function calling)
 // ─────────────────────────────────────────────
@@ -225,17 +236,36 @@ pub struct LlmResponse {
     pub usage: Option<UsageInfo>,
     /// Reasoning/thinking content (DeepSeek-R1, Kimi).
     pub reasoning_content: Option<String>,
+    /// Web pages the answer cites (provider-side web search).
+    pub citations: Vec<Citation>,
 }
 
+/// A web page cited by a response.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Citation {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+}
+
+/// `finish_reason` of responses created by [`LlmResponse::error`].
+pub const ERROR_FINISH_REASON: &str = "error";
+
 impl LlmResponse {
     /// Create an error response (error message as content, no tool calls).
     pub fn error(msg: impl Into<String>) -> Self {
         LlmResponse {
             content: Some(msg.into()),
+            finish_reason: Some(ERROR_FINISH_REASON.into()),
             ..Default::default()
         }
     }
 
+    /// Whether this is an error response rather than model output.
+    pub fn is_error(&self) -> bool {
+        self.finish_reason.as_deref() == Some(ERROR_FINISH_REASON)
+    }
+
     /// Whether the response contains tool calls.
     pub fn has_tool_calls(&self) -> bool {
         !self.tool_calls.is_empty()
@@ -243,13 +273,22 @@ impl LlmResponse {
 }
 
 /// Token usage statistics from the LLM.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct UsageInfo {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
 }
 
+impl UsageInfo {
+    /// Add the tokens of another call.
+    pub fn add(&mut self, other: &UsageInfo) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
 // ─────────────────────────────────────────────
 // Media attachments
 // ─────────────────────────────────────────────
@@ -269,47 +308,159 @@ pub struct MediaAttachment {
     pub size: Option<u64>,
 }
 
+/// Result of transcribing a voice note or audio file.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Transcript {
+    /// Transcribed text (stitched across chunks for long audio).
+    pub text: String,
+    /// Audio duration in seconds, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// Average confidence in `[0, 1]`, derived from segment log-probabilities.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Number of chunks the audio was split into (1 = no chunking).
+    pub chunks: usize,
+    /// Language of the audio (ISO-639-1 or the provider's name for it).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Word-level timestamps, when requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptWord>,
+    /// Whether this transcript was served from the transcription cache.
+    #[serde(skip)]
+    pub cached: bool,
+    /// Whether confidence fell below the configured threshold.
+    #[serde(skip)]
+    pub low_confidence: bool,
+}
+
+/// A transcribed word and when it was spoken, in seconds from the start.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Transcript {
+    /// Create a transcript from plain text with no extra details.
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            duration_secs: None,
+            confidence: None,
+            chunks: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Message content for the transcript, marked when low-confidence so
+    /// the model knows to ask for clarification.
+    pub fn to_content(&self) -> String {
+        if self.low_confidence {
+            format!("[transcription: {}] [low-confidence transcription]", self.text)
+        } else {
+            format!("[transcription: {}]", self.text)
+        }
+    }
+
+    /// Flag the transcript as low-confidence when its confidence is known
+    /// and below `threshold`.
+    pub fn mark_low_confidence(&mut self, threshold: f64) {
+        self.low_confidence = self.confidence.is_some_and(|c| c < threshold);
+    }
+
+    /// Metadata entries describing this transcript, for `InboundMessage::metadata`.
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        let mut meta = HashMap::new();
+        if let Some(d) = self.duration_secs {
+            meta.insert("transcription_duration_secs".into(), format!("{d:.1}"));
+        }
+        if let Some(c) = self.confidence {
+            meta.insert("transcription_confidence".into(), format!("{c:.3}"));
+        }
+        meta.insert("transcription_chunks".into(), self.chunks.to_string());
+        if let Some(language) = &self.language {
+            meta.insert("transcription_language".into(), language.clone());
+        }
+        if self.cached {
+            meta.insert("transcription_cached".into(), "true".into());
+        }
+        if self.low_confidence {
+            meta.insert("transcription_low_confidence".into(), "true".into());
+        }
+        meta
+    }
+}
+
 // ─────────────────────────────────────────────
 // Provider-related types
 // ─────────────────────────────────────────────
 
 /// Raw chat completion response from an OpenAI-compatible API.
-/// Used internally for deserialization.
-#[derive(Debug, Deserialize)]
+/// Used internally for deserialization (and serialized by the response cache).
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ChatCompletionResponse {
     pub id: Option<String>,
     pub choices: Vec<ChatChoice>,
     pub usage: Option<UsageInfo>,
+    /// Source URLs of a web-searching model (Perplexity style).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citations: Option<Vec<String>>,
 }
 
 /// A single choice in a chat completion response.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ChatChoice {
     pub message: AssistantMessage,
     pub finish_reason: Option<String>,
 }
 
 /// The assistant message within a chat completion choice.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AssistantMessage {
     pub content: Option<String>,
     #[serde(default)]
     pub tool_calls: Option<Vec<ToolCall>>,
     #[serde(default)]
     pub reasoning_content: Option<String>,
+    /// `url_citation` annotations of a web-searching model (OpenAI style).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<Annotation>>,
+}
+
+/// An annotation on the assistant message; only `url_citation` is used.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_citation: Option<Citation>,
 }
 
 impl From<ChatCompletionResponse> for LlmResponse {
     fn from(resp: ChatCompletionResponse) -> Self {
         let choice = resp.choices.into_iter().next();
         match choice {
-            Some(c) => LlmResponse {
-                content: c.message.content,
-                tool_calls: c.message.tool_calls.unwrap_or_default(),
-                finish_reason: c.finish_reason,
-                usage: resp.usage,
-                reasoning_content: c.message.reasoning_content,
-            },
+            Some(c) => {
+                let mut citations: Vec<Citation> = Vec::new();
+                let annotated = c.message.annotations.into_iter().flatten().filter_map(|a| a.url_citation);
+                let listed = resp.citations.into_iter().flatten().map(|url| Citation { url, title: String::new() });
+                for citation in annotated.chain(listed) {
+                    if !citation.url.is_empty() && !citations.iter().any(|c| c.url == citation.url) {
+                        citations.push(citation);
+                    }
+                }
+                LlmResponse {
+                    content: c.message.content,
+                    tool_calls: c.message.tool_calls.unwrap_or_default(),
+                    finish_reason: c.finish_reason,
+                    usage: resp.usage,
+                    reasoning_content: c.message.reasoning_content,
+                    citations,
+                }
+            }
             None => LlmResponse::error("No choices in response"),
         }
     }
@@ -329,9 +480,14 @@ pub struct ChatCompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f64>,
+    /// Provider-specific fields (e.g. `web_search_options`), sent as is.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 // ─────────────────────────────────────────────
@@ -347,6 +503,12 @@ pub struct Session {
     pub updated_at: chrono::DateTime<chrono::Utc>,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// Pinned notes, always injected into the context regardless of history trimming.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+    /// Tags for organizing sessions (normalized, lowercase, sorted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 impl Session {
@@ -359,6 +521,8 @@ impl Session {
             created_at: now,
             updated_at: now,
             metadata: HashMap::new(),
+            pinned: Vec::new(),
+            tags: Vec::new(),
         }
     }
 }
@@ -628,6 +792,38 @@ mod tests {
         assert_eq!(llm_resp.finish_reason.as_deref(), Some("tool_calls"));
     }
 
+    #[test]
+    fn test_chat_completion_citations_parsing() {
+        let api_json = json!({
+            "id": "chatcmpl-search",
+            "choices": [{
+                "message": {
+                    "content": "Rust 1.90 is out.",
+                    "annotations": [
+                        {"type": "url_citation", "url_citation": {
+                            "url": "https://blog.rust-lang.org/", "title": "Rust Blog",
+                            "start_index": 0, "end_index": 17
+                        }},
+                        {"type": "file_citation"}
+                    ]
+                },
+                "finish_reason": "stop"
+            }],
+            "citations": ["https://blog.rust-lang.org/", "https://releases.rs/"]
+        });
+
+        let resp: ChatCompletionResponse = serde_json::from_value(api_json).unwrap();
+        let llm_resp: LlmResponse = resp.into();
+
+        assert_eq!(
+            llm_resp.citations,
+            vec![
+                Citation { url: "https://blog.rust-lang.org/".into(), title: "Rust Blog".into() },
+                Citation { url: "https://releases.rs/".into(), title: String::new() },
+            ]
+        );
+    }
+
     #[test]
     fn test_chat_completion_empty_choices() {
         let api_json = json!({
@@ -657,8 +853,10 @@ mod tests {
             ],
             tools: None,
             tool_choice: None,
+            parallel_tool_calls: None,
             max_tokens: Some(4096),
             temperature: Some(0.7),
+            extra: Default::default(),
         };
 
         let json = serde_json::to_value(&request).unwrap();
@@ -685,8 +883,10 @@ mod tests {
             messages: vec![Message::user("Search for Rust")],
             tools: Some(vec![tool_def]),
             tool_choice: Some("auto".to_string()),
+            parallel_tool_calls: None,
             max_tokens: None,
             temperature: None,
+            extra: Default::default(),
         };
 
         let json = serde_json::to_value(&request).unwrap();
@@ -706,6 +906,8 @@ mod tests {
 
         assert_eq!(resp.content.as_deref(), Some("Something went wrong"));
         assert!(!resp.has_tool_calls());
+        assert!(resp.is_error());
+        assert!(!LlmResponse::default().is_error());
     }
 
     // ── Session ──
@@ -738,4 +940,47 @@ mod tests {
             Some("discord")
         );
     }
+
+    // ── Transcript ──
+
+    #[test]
+    fn test_transcript_metadata() {
+        let t = Transcript {
+            text: "hello".into(),
+            duration_secs: Some(754.25),
+            confidence: Some(0.91234),
+            chunks: 2,
+            language: Some("es".into()),
+            ..Default::default()
+        };
+        let meta = t.to_metadata();
+        assert_eq!(meta.get("transcription_duration_secs").unwrap(), "754.2");
+        assert_eq!(meta.get("transcription_confidence").unwrap(), "0.912");
+        assert_eq!(meta.get("transcription_chunks").unwrap(), "2");
+        assert_eq!(meta.get("transcription_language").unwrap(), "es");
+        assert!(!meta.contains_key("transcription_cached"));
+        assert!(!meta.contains_key("transcription_low_confidence"));
+
+        let mut cached = Transcript { cached: true, ..t };
+        assert_eq!(cached.to_metadata().get("transcription_cached").unwrap(), "true");
+
+        cached.mark_low_confidence(0.9);
+        assert!(!cached.low_confidence);
+        assert_eq!(cached.to_content(), "[transcription: hello]");
+        cached.mark_low_confidence(0.95);
+        assert_eq!(cached.to_metadata().get("transcription_low_confidence").unwrap(), "true");
+        assert_eq!(cached.to_content(), "[transcription: hello] [low-confidence transcription]");
+        let mut unknown = Transcript::from_text("hi");
+        unknown.mark_low_confidence(0.95);
+        assert!(!unknown.low_confidence);
+    }
+
+    #[test]
+    fn test_transcript_from_text() {
+        let t = Transcript::from_text("hi");
+        assert_eq!(t.chunks, 1);
+        let meta = t.to_metadata();
+        assert!(!meta.contains_key("transcription_duration_secs"));
+        assert!(!meta.contains_key("transcription_confidence"));
+    }
 }
@@ -0,0 +1,310 @@
+//! Usage log — one record per message, LLM call and tool call.
+//!
+//! Records are appended as JSON lines to `~/.oxibot/usage.jsonl` and read
+//! back by `oxibot analytics` for exports and reports. [`TurnUsage`] sums
+//! the records of a single turn for the debug footer.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::schema::ModelPrice;
+use crate::utils;
+
+// ─────────────────────────────────────────────
+// Events
+// ─────────────────────────────────────────────
+
+/// A single usage record.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsageEvent {
+    /// A user message answered by the agent (end-to-end latency).
+    Message {
+        timestamp: DateTime<Utc>,
+        channel: String,
+        chat_id: String,
+        latency_ms: u64,
+    },
+    /// One LLM request.
+    LlmCall {
+        timestamp: DateTime<Utc>,
+        channel: String,
+        chat_id: String,
+        model: String,
+        latency_ms: u64,
+        prompt_tokens: u32,
+        completion_tokens: u32,
+    },
+    /// One tool invocation.
+    ToolCall {
+        timestamp: DateTime<Utc>,
+        channel: String,
+        chat_id: String,
+        tool: String,
+        latency_ms: u64,
+        success: bool,
+    },
+}
+
+impl UsageEvent {
+    /// When the event happened.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            UsageEvent::Message { timestamp, .. }
+            | UsageEvent::LlmCall { timestamp, .. }
+            | UsageEvent::ToolCall { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// UsageLog
+// ─────────────────────────────────────────────
+
+/// Append-only JSONL usage log.
+pub struct UsageLog {
+    /// Backing file.
+    path: PathBuf,
+    /// Serializes writers within the process.
+    lock: Mutex<()>,
+}
+
+impl UsageLog {
+    /// Create a usage log at `path` (default `~/.oxibot/usage.jsonl`).
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path: path.unwrap_or_else(utils::get_usage_path),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an event. Failures are logged, never fatal.
+    pub fn record(&self, event: &UsageEvent) {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = self.append(event) {
+            warn!(path = %self.path.display(), error = %e, "failed to write usage log");
+        }
+    }
+
+    fn append(&self, event: &UsageEvent) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+
+    /// Read all events, skipping malformed lines. A missing file is empty.
+    pub fn read_all(&self) -> Vec<UsageEvent> {
+        let Ok(file) = std::fs::File::open(&self.path) else {
+            return Vec::new();
+        };
+        std::io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Prices
+// ─────────────────────────────────────────────
+
+/// Price for a model: exact match, or match without the `provider/` prefix.
+pub fn price_for<'a>(prices: &'a HashMap<String, ModelPrice>, model: &str) -> Option<&'a ModelPrice> {
+    prices.get(model).or_else(|| {
+        model
+            .split_once('/')
+            .and_then(|(_, bare)| prices.get(bare))
+    })
+}
+
+/// Cost in USD of `prompt_tokens` and `completion_tokens` at `price`.
+pub fn cost(price: &ModelPrice, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+    (prompt_tokens as f64 * price.input + completion_tokens as f64 * price.output) / 1_000_000.0
+}
+
+// ─────────────────────────────────────────────
+// TurnUsage
+// ─────────────────────────────────────────────
+
+/// Tokens per model and tools called during one turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TurnUsage {
+    /// Model, prompt tokens and completion tokens, in order of first use.
+    pub models: Vec<(String, u64, u64)>,
+    /// Tools called, with how often, in order of first call.
+    pub tools: Vec<(String, usize)>,
+}
+
+impl TurnUsage {
+    /// Count an LLM or tool call (other events are ignored).
+    pub fn add(&mut self, event: &UsageEvent) {
+        match event {
+            UsageEvent::LlmCall { model, prompt_tokens, completion_tokens, .. } => {
+                match self.models.iter_mut().find(|(m, _, _)| m == model) {
+                    Some((_, prompt, completion)) => {
+                        *prompt += *prompt_tokens as u64;
+                        *completion += *completion_tokens as u64;
+                    }
+                    None => self.models.push((model.clone(), *prompt_tokens as u64, *completion_tokens as u64)),
+                }
+            }
+            UsageEvent::ToolCall { tool, .. } => match self.tools.iter_mut().find(|(t, _)| t == tool) {
+                Some((_, count)) => *count += 1,
+                None => self.tools.push((tool.clone(), 1)),
+            },
+            UsageEvent::Message { .. } => {}
+        }
+    }
+
+    /// Estimated cost in USD of the models that have a price (`None` if
+    /// none has).
+    pub fn cost(&self, prices: &HashMap<String, ModelPrice>) -> Option<f64> {
+        self.models
+            .iter()
+            .filter_map(|(model, prompt, completion)| Some(cost(price_for(prices, model)?, *prompt, *completion)))
+            .reduce(|a, b| a + b)
+    }
+
+    /// One-line summary:
+    /// `gpt-4o · 1200 in / 80 out · ~$0.0038 · exec ×2 · 3.1 s`.
+    pub fn footer(&self, prices: &HashMap<String, ModelPrice>, elapsed: Duration) -> String {
+        let mut parts = Vec::new();
+        if self.models.is_empty() {
+            parts.push("no LLM call".to_string());
+        } else {
+            let names: Vec<&str> = self.models.iter().map(|(m, _, _)| m.as_str()).collect();
+            let prompt: u64 = self.models.iter().map(|(_, p, _)| p).sum();
+            let completion: u64 = self.models.iter().map(|(_, _, c)| c).sum();
+            parts.push(names.join(" + "));
+            parts.push(format!("{prompt} in / {completion} out"));
+        }
+        if let Some(cost) = self.cost(prices) {
+            // Only some models priced: the estimate is a lower bound
+            let partial = self.models.iter().any(|(m, _, _)| price_for(prices, m).is_none());
+            parts.push(format!("{}~${cost:.4}", if partial { "≥" } else { "" }));
+        }
+        if self.tools.is_empty() {
+            parts.push("no tools".to_string());
+        } else {
+            let tools: Vec<String> = self
+                .tools
+                .iter()
+                .map(|(tool, count)| if *count > 1 { format!("{tool} ×{count}") } else { tool.clone() })
+                .collect();
+            parts.push(tools.join(", "));
+        }
+        parts.push(format!("{:.1} s", elapsed.as_secs_f64()));
+        parts.join(" · ")
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = UsageLog::new(Some(dir.path().join("usage.jsonl")));
+        assert!(log.read_all().is_empty());
+
+        let events = vec![
+            UsageEvent::LlmCall {
+                timestamp: Utc::now(),
+                channel: "telegram".into(),
+                chat_id: "1".into(),
+                model: "gpt-4o".into(),
+                latency_ms: 850,
+                prompt_tokens: 1200,
+                completion_tokens: 80,
+            },
+            UsageEvent::ToolCall {
+                timestamp: Utc::now(),
+                channel: "telegram".into(),
+                chat_id: "1".into(),
+                tool: "exec".into(),
+                latency_ms: 40,
+                success: true,
+            },
+        ];
+        for event in &events {
+            log.record(event);
+        }
+        assert_eq!(log.read_all(), events);
+    }
+
+    #[test]
+    fn test_read_skips_bad_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usage.jsonl");
+        std::fs::write(
+            &path,
+            "not json\n{\"kind\":\"message\",\"timestamp\":\"2026-01-01T00:00:00Z\",\"channel\":\"cli\",\"chat_id\":\"direct\",\"latency_ms\":5}\n",
+        )
+        .unwrap();
+        let events = UsageLog::new(Some(path)).read_all();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], UsageEvent::Message { latency_ms: 5, .. }));
+    }
+
+    #[test]
+    fn test_turn_usage_footer() {
+        let llm = |model: &str, prompt_tokens, completion_tokens| UsageEvent::LlmCall {
+            timestamp: Utc::now(),
+            channel: "cli".into(),
+            chat_id: "direct".into(),
+            model: model.into(),
+            latency_ms: 500,
+            prompt_tokens,
+            completion_tokens,
+        };
+        let tool = |name: &str| UsageEvent::ToolCall {
+            timestamp: Utc::now(),
+            channel: "cli".into(),
+            chat_id: "direct".into(),
+            tool: name.into(),
+            latency_ms: 10,
+            success: true,
+        };
+        let mut usage = TurnUsage::default();
+        for event in [llm("openai/gpt-4o", 1000, 50), tool("exec"), tool("read_file"), tool("exec"), llm("openai/gpt-4o", 1200, 80)] {
+            usage.add(&event);
+        }
+        let mut prices = HashMap::new();
+        prices.insert("gpt-4o".to_string(), ModelPrice { input: 2.5, output: 10.0 });
+
+        assert!((usage.cost(&prices).unwrap() - 0.0068).abs() < 1e-12);
+        assert_eq!(
+            usage.footer(&prices, Duration::from_millis(3140)),
+            "openai/gpt-4o · 2200 in / 130 out · ~$0.0068 · exec ×2, read_file · 3.1 s"
+        );
+
+        usage.add(&llm("llama3", 300, 20));
+        assert!(usage.footer(&prices, Duration::ZERO).starts_with("openai/gpt-4o + llama3 · 2500 in / 150 out · ≥~$0.0068 ·"));
+        assert_eq!(TurnUsage::default().footer(&HashMap::new(), Duration::ZERO), "no LLM call · no tools · 0.0 s");
+    }
+}
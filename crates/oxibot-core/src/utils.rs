@@ -15,6 +15,71 @@ pub fn get_sessions_path() -> PathBuf {
     get_data_path().join("sessions")
 }
 
+/// Get the contacts file path (e.g. `~/.oxibot/contacts.json`).
+pub fn get_contacts_path() -> PathBuf {
+    get_data_path().join("contacts.json")
+}
+
+/// Get the directory channels download attachments to (e.g. `~/.oxibot/media/`).
+pub fn get_media_path() -> PathBuf {
+    get_data_path().join("media")
+}
+
+/// Get the gateway API key store path (e.g. `~/.oxibot/api_keys.json`).
+pub fn get_api_keys_path() -> PathBuf {
+    get_data_path().join("api_keys.json")
+}
+
+/// Get the OAuth token store path (e.g. `~/.oxibot/oauth_tokens.json`).
+pub fn get_oauth_tokens_path() -> PathBuf {
+    get_data_path().join("oauth_tokens.json")
+}
+
+/// Get the usage log path (e.g. `~/.oxibot/usage.jsonl`).
+pub fn get_usage_path() -> PathBuf {
+    get_data_path().join("usage.jsonl")
+}
+
+/// Get the tool stats path (e.g. `~/.oxibot/tool_stats.json`).
+pub fn get_tool_stats_path() -> PathBuf {
+    get_data_path().join("tool_stats.json")
+}
+
+/// Get the transcripts directory (e.g. `~/.oxibot/transcripts/`).
+pub fn get_transcripts_path() -> PathBuf {
+    get_data_path().join("transcripts")
+}
+
+/// Get the undelivered outbound messages directory (e.g. `~/.oxibot/outbox/`).
+pub fn get_outbox_path() -> PathBuf {
+    get_data_path().join("outbox")
+}
+
+/// Get the crash reports directory (e.g. `~/.oxibot/crash/`).
+pub fn get_crash_path() -> PathBuf {
+    get_data_path().join("crash")
+}
+
+/// Get the recorded LLM responses directory (e.g. `~/.oxibot/provider-cache/`).
+pub fn get_provider_cache_path() -> PathBuf {
+    get_data_path().join("provider-cache")
+}
+
+/// Get the cached web page extractions directory (e.g. `~/.oxibot/page-cache/`).
+pub fn get_page_cache_path() -> PathBuf {
+    get_data_path().join("page-cache")
+}
+
+/// Get the provider probe history path (e.g. `~/.oxibot/provider_health.json`).
+pub fn get_provider_health_path() -> PathBuf {
+    get_data_path().join("provider_health.json")
+}
+
+/// Get the batch job list path (e.g. `~/.oxibot/batches.json`).
+pub fn get_batches_path() -> PathBuf {
+    get_data_path().join("batches.json")
+}
+
 /// Get the default workspace path (e.g. `~/.oxibot/workspace/`).
 pub fn get_default_workspace_path() -> PathBuf {
     get_data_path().join("workspace")
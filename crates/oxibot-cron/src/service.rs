@@ -330,7 +330,7 @@ impl CronService {
                             if let (Some(channel), Some(to)) =
                                 (j.payload.channel.as_ref(), j.payload.to.as_ref())
                             {
-                                let outbound = OutboundMessage {
+                                let mut outbound = OutboundMessage {
                                     channel: channel.clone(),
                                     chat_id: to.clone(),
                                     content: response.clone(),
@@ -338,6 +338,9 @@ impl CronService {
                                     media: Vec::new(),
                                     metadata: std::collections::HashMap::new(),
                                 };
+                                if j.payload.force_delivery {
+                                    outbound = outbound.forced();
+                                }
                                 if let Err(e) = self.bus.publish_outbound(outbound).await {
                                     error!(error = %e, "failed to deliver cron response");
                                 }
@@ -489,6 +492,7 @@ mod tests {
                     deliver: true,
                     channel: Some("telegram".into()),
                     to: Some("12345".into()),
+                    force_delivery: false,
                 },
             );
             svc.add_job(job).await.unwrap();
@@ -640,6 +644,7 @@ mod tests {
                 deliver: true,
                 channel: Some("telegram".into()),
                 to: Some("user123".into()),
+                force_delivery: false,
             },
         );
         let id = svc.add_job(job).await.unwrap();
@@ -6,6 +6,7 @@
 //! for JSON compatibility.
 
 use chrono::{DateTime, Utc};
+use oxibot_core::datetime::Tz;
 use serde::{Deserialize, Serialize};
 
 // ─────────────────────────────────────────────
@@ -39,7 +40,9 @@ pub struct CronSchedule {
     /// Standard 5-field cron expression (e.g. `"0 9 * * *"`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expr: Option<String>,
-    /// Timezone (e.g. `"America/New_York"`). Reserved for future use.
+    /// IANA timezone cron expressions are read in (e.g. `"America/New_York"`,
+    /// default UTC), so `0 0 9 * * *` stays at 9:00 local across DST changes.
+    /// For one-shot schedules, the zone `at_ms` was entered in.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tz: Option<String>,
 }
@@ -77,6 +80,20 @@ impl CronSchedule {
             tz: None,
         }
     }
+
+    /// Set the timezone the schedule is read in.
+    pub fn with_tz(mut self, tz: impl Into<String>) -> Self {
+        self.tz = Some(tz.into());
+        self
+    }
+
+    /// The schedule's timezone (`None` if it names an unknown one).
+    pub fn timezone(&self) -> Option<Tz> {
+        match self.tz.as_deref() {
+            Some(name) => name.parse().ok(),
+            None => Some(Tz::UTC),
+        }
+    }
 }
 
 impl Default for CronSchedule {
@@ -90,7 +107,7 @@ impl Default for CronSchedule {
 // ─────────────────────────────────────────────
 
 /// What a cron job does when it fires.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CronPayload {
     /// Prompt text sent to the agent.
@@ -105,17 +122,9 @@ pub struct CronPayload {
     /// Recipient identifier within the channel.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub to: Option<String>,
-}
-
-impl Default for CronPayload {
-    fn default() -> Self {
-        Self {
-            message: String::new(),
-            deliver: false,
-            channel: None,
-            to: None,
-        }
-    }
+    /// Deliver the response even if it repeats a recent one.
+    #[serde(default)]
+    pub force_delivery: bool,
 }
 
 // ─────────────────────────────────────────────
@@ -238,10 +247,11 @@ pub fn compute_next_run_from(schedule: &CronSchedule, now_ms: i64) -> Option<i64
             let expr = schedule.expr.as_deref()?;
             // Parse standard cron expression using the `cron` crate
             let parsed: cron::Schedule = expr.parse().ok()?;
+            let tz = schedule.timezone()?;
             let now_dt: DateTime<Utc> =
                 DateTime::from_timestamp_millis(now_ms)?;
-            let next = parsed.upcoming(Utc).next()?;
-            let _ = now_dt; // use now_dt to anchor the computation
+            // Matched against local wall-clock time, so DST shifts the UTC instant
+            let next = parsed.after(&now_dt.with_timezone(&tz)).next()?;
             Some(next.timestamp_millis())
         }
     }
@@ -417,6 +427,27 @@ mod tests {
         assert!(next.unwrap() > now);
     }
 
+    #[test]
+    fn test_compute_next_cron_in_timezone() {
+        use chrono::TimeZone;
+        let schedule = CronSchedule::cron("0 0 9 * * *").with_tz("Europe/Madrid");
+        // Summer time ends overnight: Sunday 09:00 CET = 08:00 UTC
+        let now = Utc.with_ymd_and_hms(2026, 10, 24, 12, 0, 0).unwrap();
+        let next = compute_next_run_from(&schedule, now.timestamp_millis()).unwrap();
+        assert_eq!(DateTime::from_timestamp_millis(next).unwrap(), Utc.with_ymd_and_hms(2026, 10, 25, 8, 0, 0).unwrap());
+        // ...and stays 08:00 UTC the day after
+        let next = compute_next_run_from(&schedule, next).unwrap();
+        assert_eq!(DateTime::from_timestamp_millis(next).unwrap(), Utc.with_ymd_and_hms(2026, 10, 26, 8, 0, 0).unwrap());
+
+        // Still summer time: Saturday 09:00 CEST = 07:00 UTC
+        let before = Utc.with_ymd_and_hms(2026, 10, 23, 12, 0, 0).unwrap();
+        let next = compute_next_run_from(&schedule, before.timestamp_millis()).unwrap();
+        assert_eq!(DateTime::from_timestamp_millis(next).unwrap(), Utc.with_ymd_and_hms(2026, 10, 24, 7, 0, 0).unwrap());
+
+        let unknown = CronSchedule::cron("0 0 9 * * *").with_tz("Mars/Base");
+        assert!(compute_next_run_from(&unknown, before.timestamp_millis()).is_none());
+    }
+
     #[test]
     fn test_compute_next_cron_invalid() {
         let schedule = CronSchedule::cron("invalid");
@@ -488,6 +519,7 @@ mod tests {
                 deliver: true,
                 channel: Some("telegram".into()),
                 to: Some("12345".into()),
+                force_delivery: false,
             },
         );
         store.add(job);
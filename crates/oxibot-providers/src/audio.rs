@@ -0,0 +1,407 @@
+//! Audio preprocessing for transcription — a thin wrapper around `ffmpeg`.
+//!
+//! Whisper APIs reject uploads above a size limit (25 MB on Groq) and are
+//! picky about containers, so long voice notes are:
+//!
+//! 1. transcoded to 16 kHz mono MP3 (small and universally accepted),
+//! 2. split into fixed-length chunks with a short overlap,
+//! 3. transcribed chunk by chunk, then stitched back together.
+//!
+//! `ffmpeg`/`ffprobe` are optional: if they are not installed the
+//! transcriber falls back to uploading the original file as-is.
+
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+/// Default chunk length in seconds (10 minutes).
+pub const DEFAULT_CHUNK_SECS: f64 = 600.0;
+
+/// Default overlap between consecutive chunks in seconds.
+pub const DEFAULT_OVERLAP_SECS: f64 = 5.0;
+
+/// Maximum number of words compared when removing overlap between chunks.
+const MAX_STITCH_WORDS: usize = 40;
+
+// ─────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────
+
+/// A single chunk of a prepared audio file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioChunk {
+    /// Path of the chunk file.
+    pub path: PathBuf,
+    /// Offset of the chunk in the original audio, in seconds.
+    pub start_secs: f64,
+    /// Length of the chunk in seconds.
+    pub length_secs: f64,
+}
+
+/// Audio transcoded and split into chunks, ready for upload.
+///
+/// Chunk files live in a private temp directory that is removed by
+/// [`PreparedAudio::cleanup`].
+#[derive(Debug)]
+pub struct PreparedAudio {
+    /// Chunks in playback order.
+    pub chunks: Vec<AudioChunk>,
+    /// Total duration of the original audio, if it could be probed.
+    pub duration_secs: Option<f64>,
+    work_dir: PathBuf,
+}
+
+impl PreparedAudio {
+    /// Remove the temporary chunk files.
+    pub async fn cleanup(self) {
+        if let Err(e) = tokio::fs::remove_dir_all(&self.work_dir).await {
+            debug!(error = %e, dir = %self.work_dir.display(), "failed to remove audio work dir");
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// AudioPreprocessor
+// ─────────────────────────────────────────────
+
+/// Transcodes and chunks audio files using `ffmpeg` and `ffprobe`.
+#[derive(Debug)]
+pub struct AudioPreprocessor {
+    ffmpeg: String,
+    ffprobe: String,
+    chunk_secs: f64,
+    overlap_secs: f64,
+    available: OnceCell<bool>,
+}
+
+impl AudioPreprocessor {
+    /// Create a preprocessor using `ffmpeg`/`ffprobe` from `PATH`.
+    pub fn new() -> Self {
+        Self {
+            ffmpeg: "ffmpeg".into(),
+            ffprobe: "ffprobe".into(),
+            chunk_secs: DEFAULT_CHUNK_SECS,
+            overlap_secs: DEFAULT_OVERLAP_SECS,
+            available: OnceCell::new(),
+        }
+    }
+
+    /// Use explicit `ffmpeg`/`ffprobe` binaries.
+    pub fn with_binaries(mut self, ffmpeg: &str, ffprobe: &str) -> Self {
+        self.ffmpeg = ffmpeg.to_string();
+        self.ffprobe = ffprobe.to_string();
+        self
+    }
+
+    /// Set the chunk length and overlap (both in seconds).
+    ///
+    /// The overlap is clamped to at most half the chunk length.
+    pub fn with_chunking(mut self, chunk_secs: f64, overlap_secs: f64) -> Self {
+        self.chunk_secs = chunk_secs.max(1.0);
+        self.overlap_secs = overlap_secs.clamp(0.0, self.chunk_secs / 2.0);
+        self
+    }
+
+    /// Check (once) whether both `ffmpeg` and `ffprobe` can be executed.
+    pub async fn is_available(&self) -> bool {
+        *self
+            .available
+            .get_or_init(|| async {
+                let ok = binary_runs(&self.ffmpeg).await && binary_runs(&self.ffprobe).await;
+                if !ok {
+                    warn!(
+                        ffmpeg = %self.ffmpeg,
+                        "ffmpeg/ffprobe not found, long audio will not be chunked"
+                    );
+                }
+                ok
+            })
+            .await
+    }
+
+    /// Probe the duration of an audio file in seconds.
+    pub async fn probe_duration(&self, path: &Path) -> anyhow::Result<f64> {
+        let output = Command::new(&self.ffprobe)
+            .args([
+                "-v",
+                "error",
+                "-show_entries",
+                "format=duration",
+                "-of",
+                "default=noprint_wrappers=1:nokey=1",
+            ])
+            .arg(path)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "ffprobe failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        text.trim()
+            .parse::<f64>()
+            .map_err(|_| anyhow::anyhow!("ffprobe returned invalid duration: {}", text.trim()))
+    }
+
+    /// Transcode a slice of `input` to 16 kHz mono MP3 at `output`.
+    ///
+    /// `range` is `(start_secs, length_secs)`; `None` transcodes the whole file.
+    pub async fn transcode(
+        &self,
+        input: &Path,
+        output: &Path,
+        range: Option<(f64, f64)>,
+    ) -> anyhow::Result<()> {
+        let mut cmd = Command::new(&self.ffmpeg);
+        cmd.args(["-hide_banner", "-loglevel", "error", "-y"]);
+        if let Some((start, length)) = range {
+            cmd.args(["-ss", &format!("{start:.3}"), "-t", &format!("{length:.3}")]);
+        }
+        cmd.arg("-i")
+            .arg(input)
+            .args(["-vn", "-ac", "1", "-ar", "16000", "-c:a", "libmp3lame", "-b:a", "32k"])
+            .arg(output);
+
+        let result = cmd.output().await?;
+        if !result.status.success() {
+            anyhow::bail!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&result.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Transcode `input` and split it into overlapping chunks.
+    pub async fn prepare(&self, input: &Path) -> anyhow::Result<PreparedAudio> {
+        let work_dir = std::env::temp_dir().join(format!(
+            "oxibot-audio-{}-{}",
+            std::process::id(),
+            epoch_nanos()
+        ));
+        tokio::fs::create_dir_all(&work_dir).await?;
+
+        let duration_secs = match self.probe_duration(input).await {
+            Ok(d) => Some(d),
+            Err(e) => {
+                warn!(error = %e, path = %input.display(), "could not probe audio duration");
+                None
+            }
+        };
+
+        let plan = match duration_secs {
+            Some(d) => plan_chunks(d, self.chunk_secs, self.overlap_secs),
+            None => Vec::new(),
+        };
+
+        let mut chunks = Vec::new();
+        let result = if plan.len() <= 1 {
+            let path = work_dir.join("audio.mp3");
+            let r = self.transcode(input, &path, None).await;
+            chunks.push(AudioChunk {
+                path,
+                start_secs: 0.0,
+                length_secs: duration_secs.unwrap_or(0.0),
+            });
+            r
+        } else {
+            let mut r = Ok(());
+            for (i, (start, length)) in plan.into_iter().enumerate() {
+                let path = work_dir.join(format!("chunk_{i:03}.mp3"));
+                if let Err(e) = self.transcode(input, &path, Some((start, length))).await {
+                    r = Err(e);
+                    break;
+                }
+                chunks.push(AudioChunk {
+                    path,
+                    start_secs: start,
+                    length_secs: length,
+                });
+            }
+            r
+        };
+
+        let prepared = PreparedAudio {
+            chunks,
+            duration_secs,
+            work_dir,
+        };
+
+        match result {
+            Ok(()) => {
+                debug!(
+                    chunks = prepared.chunks.len(),
+                    duration = ?prepared.duration_secs,
+                    "audio prepared for transcription"
+                );
+                Ok(prepared)
+            }
+            Err(e) => {
+                prepared.cleanup().await;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Default for AudioPreprocessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run `<binary> -version` and report whether it succeeded.
+async fn binary_runs(binary: &str) -> bool {
+    Command::new(binary)
+        .arg("-version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Nanoseconds since the Unix epoch, used to name temp directories.
+fn epoch_nanos() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────
+// Chunk planning & stitching
+// ─────────────────────────────────────────────
+
+/// Compute `(start_secs, length_secs)` ranges covering `duration` seconds.
+///
+/// Each chunk is at most `chunk_secs` long and starts `overlap_secs` before
+/// the previous one ended. Audio that fits in one chunk yields one range.
+pub fn plan_chunks(duration: f64, chunk_secs: f64, overlap_secs: f64) -> Vec<(f64, f64)> {
+    if duration <= 0.0 {
+        return Vec::new();
+    }
+    if duration <= chunk_secs {
+        return vec![(0.0, duration)];
+    }
+
+    let step = (chunk_secs - overlap_secs).max(1.0);
+    let mut ranges = Vec::new();
+    let mut start = 0.0;
+    loop {
+        let length = chunk_secs.min(duration - start);
+        ranges.push((start, length));
+        if start + length >= duration {
+            break;
+        }
+        start += step;
+    }
+    ranges
+}
+
+/// Join chunk transcripts, dropping words repeated in the overlap.
+///
+/// Looks for the longest run of words at the end of the text so far that
+/// also starts the next chunk (ignoring case and punctuation), and removes
+/// it from the next chunk before appending.
+pub fn stitch_transcripts(parts: &[String]) -> String {
+    let mut words: Vec<&str> = Vec::new();
+
+    for part in parts {
+        let next: Vec<&str> = part.split_whitespace().collect();
+        let max = MAX_STITCH_WORDS.min(words.len()).min(next.len());
+
+        let overlap = (1..=max)
+            .rev()
+            .find(|&n| {
+                words[words.len() - n..]
+                    .iter()
+                    .zip(&next[..n])
+                    .all(|(a, b)| normalize_word(a) == normalize_word(b))
+            })
+            .unwrap_or(0);
+
+        words.extend_from_slice(&next[overlap..]);
+    }
+
+    words.join(" ")
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_chunks_short_audio() {
+        assert_eq!(plan_chunks(42.0, 600.0, 5.0), vec![(0.0, 42.0)]);
+        assert!(plan_chunks(0.0, 600.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn test_plan_chunks_long_audio() {
+        let plan = plan_chunks(25.0, 10.0, 2.0);
+        assert_eq!(plan, vec![(0.0, 10.0), (8.0, 10.0), (16.0, 9.0)]);
+    }
+
+    #[test]
+    fn test_plan_chunks_exact_fit() {
+        let plan = plan_chunks(18.0, 10.0, 2.0);
+        assert_eq!(plan, vec![(0.0, 10.0), (8.0, 10.0)]);
+    }
+
+    #[test]
+    fn test_stitch_removes_overlap() {
+        let parts = vec![
+            "so the plan for tomorrow is".to_string(),
+            "Plan for tomorrow is to ship the release".to_string(),
+        ];
+        assert_eq!(
+            stitch_transcripts(&parts),
+            "so the plan for tomorrow is to ship the release"
+        );
+    }
+
+    #[test]
+    fn test_stitch_without_overlap() {
+        let parts = vec!["hello there".to_string(), "general kenobi".to_string()];
+        assert_eq!(stitch_transcripts(&parts), "hello there general kenobi");
+    }
+
+    #[test]
+    fn test_stitch_single_part() {
+        assert_eq!(stitch_transcripts(&["  just one  ".to_string()]), "just one");
+        assert_eq!(stitch_transcripts(&[]), "");
+    }
+
+    #[test]
+    fn test_with_chunking_clamps_overlap() {
+        let p = AudioPreprocessor::new().with_chunking(10.0, 8.0);
+        assert_eq!(p.chunk_secs, 10.0);
+        assert_eq!(p.overlap_secs, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_binaries_not_available() {
+        let p = AudioPreprocessor::new()
+            .with_binaries("/nonexistent/ffmpeg", "/nonexistent/ffprobe");
+        assert!(!p.is_available().await);
+    }
+}
@@ -0,0 +1,516 @@
+//! Batch APIs — submit many prompts at once, collect the answers later.
+//!
+//! OpenAI's `/v1/batches` and Anthropic's `/v1/messages/batches` run jobs
+//! within 24 hours at half the price of regular calls, which suits offline
+//! work like digests, summarization backfills and evals. A job is submitted
+//! once, polled with [`BatchProvider::status`] and read with
+//! [`BatchProvider::results`] when it has ended.
+//!
+//! Requests are single-turn: an optional system prompt and one user prompt,
+//! no tools.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::debug;
+
+use crate::registry::{find_by_model, ProviderConfig};
+
+/// OpenAI API base when the config sets none.
+pub(crate) const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
+
+/// Anthropic API base when the config sets none.
+pub(crate) const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com/v1";
+
+/// `anthropic-version` header sent with Anthropic requests.
+pub(crate) const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Timeout for a single batch API call (uploads and downloads included).
+const BATCH_HTTP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// One prompt of a batch job.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchRequest {
+    /// Identifies the result; letters, digits, `-` and `_` only (Anthropic).
+    pub custom_id: String,
+    pub system: Option<String>,
+    pub prompt: String,
+    pub max_tokens: u32,
+}
+
+/// Where a job is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchState {
+    /// Queued, running or being finalized.
+    InProgress,
+    /// Finished; results can be fetched.
+    Ended,
+    /// Rejected or failed as a whole; there are no results.
+    Failed,
+}
+
+/// Progress of a job.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchStatus {
+    pub id: String,
+    pub state: BatchState,
+    /// Provider's own status word (e.g. `"finalizing"`, `"canceling"`).
+    pub detail: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// The answer to one [`BatchRequest`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchResult {
+    pub custom_id: String,
+    pub result: Result<String, String>,
+}
+
+/// A provider that runs batch jobs.
+#[async_trait]
+pub trait BatchProvider: Send + Sync {
+    /// Submit `requests` for `model`; returns the job id.
+    async fn submit(&self, model: &str, requests: &[BatchRequest]) -> Result<String>;
+
+    /// Current progress of job `id`.
+    async fn status(&self, id: &str) -> Result<BatchStatus>;
+
+    /// Results of an ended job, in no particular order.
+    async fn results(&self, id: &str) -> Result<Vec<BatchResult>>;
+
+    /// Display name for messages.
+    fn display_name(&self) -> &str;
+}
+
+/// Create the batch provider for `model`: OpenAI or Anthropic, with its API
+/// key from `providers`.
+pub fn create_batch_provider(
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+) -> Result<Box<dyn BatchProvider>> {
+    let spec = find_by_model(model)
+        .filter(|spec| matches!(spec.name, "openai" | "anthropic"))
+        .with_context(|| format!("batch jobs need an OpenAI or Anthropic model, not '{model}'"))?;
+    let config = providers
+        .get(spec.name)
+        .filter(|c| c.is_configured())
+        .with_context(|| format!("no API key configured for {}", spec.display_name))?;
+    let client = reqwest::Client::builder().timeout(BATCH_HTTP_TIMEOUT).build()?;
+    let api_key = config.api_key.clone();
+    Ok(match spec.name {
+        "openai" => Box::new(OpenAiBatch {
+            client,
+            api_base: api_base(config, OPENAI_API_BASE),
+            api_key,
+        }),
+        _ => Box::new(AnthropicBatch {
+            client,
+            api_base: api_base(config, ANTHROPIC_API_BASE),
+            api_key,
+        }),
+    })
+}
+
+/// `model` without a `provider/` routing prefix.
+fn native_model(model: &str, provider: &str) -> String {
+    model
+        .strip_prefix(provider)
+        .and_then(|m| m.strip_prefix('/'))
+        .unwrap_or(model)
+        .to_string()
+}
+
+pub(crate) fn api_base(config: &ProviderConfig, default: &str) -> String {
+    config
+        .api_base
+        .as_deref()
+        .unwrap_or(default)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Send `request` and parse the JSON body, turning HTTP errors into `Err`.
+pub(crate) async fn send_json(request: reqwest::RequestBuilder, what: &str) -> Result<Value> {
+    let text = send_text(request, what).await?;
+    serde_json::from_str(&text).with_context(|| format!("{what}: invalid JSON response"))
+}
+
+pub(crate) async fn send_text(request: reqwest::RequestBuilder, what: &str) -> Result<String> {
+    let response = request.send().await.with_context(|| format!("{what} failed"))?;
+    let status = response.status();
+    let text = response.text().await.with_context(|| format!("{what}: cannot read response"))?;
+    if !status.is_success() {
+        anyhow::bail!("{what} failed: {status} — {text}");
+    }
+    Ok(text)
+}
+
+fn count(value: &Value) -> usize {
+    value.as_u64().unwrap_or(0) as usize
+}
+
+// ─────────────────────────────────────────────
+// OpenAI
+// ─────────────────────────────────────────────
+
+/// OpenAI Batch API: the requests are uploaded as a JSONL file, results
+/// come back as another file.
+pub struct OpenAiBatch {
+    client: reqwest::Client,
+    api_base: String,
+    api_key: String,
+}
+
+impl OpenAiBatch {
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get(format!("{}{path}", self.api_base))
+            .bearer_auth(&self.api_key)
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .post(format!("{}{path}", self.api_base))
+            .bearer_auth(&self.api_key)
+    }
+
+    /// The lines of a result file; the error file has the same shape.
+    async fn read_file(&self, file_id: &str, results: &mut Vec<BatchResult>) -> Result<()> {
+        let text = send_text(self.get(&format!("/files/{file_id}/content")), "batch download").await?;
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            let value: Value = serde_json::from_str(line).context("invalid batch result line")?;
+            results.push(openai_result(&value));
+        }
+        Ok(())
+    }
+}
+
+/// The request file: one `/v1/chat/completions` call per line.
+fn openai_input(model: &str, requests: &[BatchRequest]) -> String {
+    requests
+        .iter()
+        .map(|request| {
+            let mut messages = Vec::new();
+            if let Some(ref system) = request.system {
+                messages.push(json!({"role": "system", "content": system}));
+            }
+            messages.push(json!({"role": "user", "content": request.prompt}));
+            json!({
+                "custom_id": request.custom_id,
+                "method": "POST",
+                "url": "/v1/chat/completions",
+                "body": {
+                    "model": model,
+                    "messages": messages,
+                    "max_tokens": request.max_tokens,
+                },
+            })
+            .to_string()
+                + "\n"
+        })
+        .collect()
+}
+
+fn openai_result(line: &Value) -> BatchResult {
+    let custom_id = line["custom_id"].as_str().unwrap_or_default().to_string();
+    let body = &line["response"]["body"];
+    let result = if let Some(message) = line["error"]["message"].as_str() {
+        Err(message.to_string())
+    } else if let Some(message) = body["error"]["message"].as_str() {
+        Err(message.to_string())
+    } else {
+        body["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "no content in response".to_string())
+    };
+    BatchResult { custom_id, result }
+}
+
+#[async_trait]
+impl BatchProvider for OpenAiBatch {
+    async fn submit(&self, model: &str, requests: &[BatchRequest]) -> Result<String> {
+        let file = reqwest::multipart::Part::text(openai_input(&native_model(model, "openai"), requests))
+            .file_name("batch.jsonl")
+            .mime_str("application/jsonl")?;
+        let form = reqwest::multipart::Form::new()
+            .text("purpose", "batch")
+            .part("file", file);
+        let uploaded = send_json(self.post("/files").multipart(form), "batch upload").await?;
+        let file_id = uploaded["id"].as_str().context("no file id in upload response")?;
+        debug!(file_id, requests = requests.len(), "uploaded openai batch input");
+
+        let body = json!({
+            "input_file_id": file_id,
+            "endpoint": "/v1/chat/completions",
+            "completion_window": "24h",
+        });
+        let batch = send_json(self.post("/batches").json(&body), "batch submit").await?;
+        Ok(batch["id"].as_str().context("no id in batch response")?.to_string())
+    }
+
+    async fn status(&self, id: &str) -> Result<BatchStatus> {
+        let batch = send_json(self.get(&format!("/batches/{id}")), "batch status").await?;
+        let detail = batch["status"].as_str().unwrap_or_default().to_string();
+        let state = match detail.as_str() {
+            "completed" | "expired" | "cancelled" => BatchState::Ended,
+            "failed" => BatchState::Failed,
+            _ => BatchState::InProgress,
+        };
+        let counts = &batch["request_counts"];
+        Ok(BatchStatus {
+            id: id.to_string(),
+            state,
+            detail,
+            total: count(&counts["total"]),
+            succeeded: count(&counts["completed"]),
+            failed: count(&counts["failed"]),
+        })
+    }
+
+    async fn results(&self, id: &str) -> Result<Vec<BatchResult>> {
+        let batch = send_json(self.get(&format!("/batches/{id}")), "batch status").await?;
+        let mut results = Vec::new();
+        for key in ["output_file_id", "error_file_id"] {
+            if let Some(file_id) = batch[key].as_str() {
+                self.read_file(file_id, &mut results).await?;
+            }
+        }
+        Ok(results)
+    }
+
+    fn display_name(&self) -> &str {
+        "OpenAI"
+    }
+}
+
+// ─────────────────────────────────────────────
+// Anthropic
+// ─────────────────────────────────────────────
+
+/// Anthropic Message Batches API: requests go in the create call, results
+/// are a JSONL download.
+pub struct AnthropicBatch {
+    client: reqwest::Client,
+    api_base: String,
+    api_key: String,
+}
+
+impl AnthropicBatch {
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+    }
+
+    async fn batch(&self, id: &str) -> Result<Value> {
+        let url = format!("{}/messages/batches/{id}", self.api_base);
+        send_json(self.request(reqwest::Method::GET, &url), "batch status").await
+    }
+}
+
+fn anthropic_input(model: &str, requests: &[BatchRequest]) -> Value {
+    let requests: Vec<Value> = requests
+        .iter()
+        .map(|request| {
+            let mut params = json!({
+                "model": model,
+                "max_tokens": request.max_tokens,
+                "messages": [{"role": "user", "content": request.prompt}],
+            });
+            if let Some(ref system) = request.system {
+                params["system"] = json!(system);
+            }
+            json!({"custom_id": request.custom_id, "params": params})
+        })
+        .collect();
+    json!({ "requests": requests })
+}
+
+fn anthropic_result(line: &Value) -> BatchResult {
+    let custom_id = line["custom_id"].as_str().unwrap_or_default().to_string();
+    let result = &line["result"];
+    let result = match result["type"].as_str().unwrap_or_default() {
+        "succeeded" => Ok(result["message"]["content"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("")),
+        "errored" => Err(result["error"]["error"]["message"]
+            .as_str()
+            .or(result["error"]["message"].as_str())
+            .unwrap_or("request failed")
+            .to_string()),
+        other => Err(format!("request {other}")),
+    };
+    BatchResult { custom_id, result }
+}
+
+#[async_trait]
+impl BatchProvider for AnthropicBatch {
+    async fn submit(&self, model: &str, requests: &[BatchRequest]) -> Result<String> {
+        let body = anthropic_input(&native_model(model, "anthropic"), requests);
+        let url = format!("{}/messages/batches", self.api_base);
+        let batch = send_json(self.request(reqwest::Method::POST, &url).json(&body), "batch submit").await?;
+        Ok(batch["id"].as_str().context("no id in batch response")?.to_string())
+    }
+
+    async fn status(&self, id: &str) -> Result<BatchStatus> {
+        let batch = self.batch(id).await?;
+        let detail = batch["processing_status"].as_str().unwrap_or_default().to_string();
+        let state = if detail == "ended" {
+            BatchState::Ended
+        } else {
+            BatchState::InProgress
+        };
+        let counts = &batch["request_counts"];
+        let failed = ["errored", "canceled", "expired"].iter().map(|k| count(&counts[k])).sum();
+        let succeeded = count(&counts["succeeded"]);
+        Ok(BatchStatus {
+            id: id.to_string(),
+            state,
+            detail,
+            total: count(&counts["processing"]) + succeeded + failed,
+            succeeded,
+            failed,
+        })
+    }
+
+    async fn results(&self, id: &str) -> Result<Vec<BatchResult>> {
+        let batch = self.batch(id).await?;
+        let url = batch["results_url"].as_str().context("batch has no results yet")?;
+        let text = send_text(self.request(reqwest::Method::GET, url), "batch download").await?;
+        text.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|line| {
+                let value: Value = serde_json::from_str(line).context("invalid batch result line")?;
+                Ok(anthropic_result(&value))
+            })
+            .collect()
+    }
+
+    fn display_name(&self) -> &str {
+        "Anthropic"
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::config::schema::RequestPolicy;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn providers(name: &str, api_base: &str) -> HashMap<String, ProviderConfig> {
+        let config = ProviderConfig {
+            api_key: "key".into(),
+            api_base: Some(api_base.into()),
+            extra_headers: None,
+            extra_body: None,
+            request: RequestPolicy::default(),
+        };
+        HashMap::from([(name.to_string(), config)])
+    }
+
+    fn request(id: &str) -> BatchRequest {
+        BatchRequest {
+            custom_id: id.into(),
+            system: Some("Be brief.".into()),
+            prompt: format!("prompt {id}"),
+            max_tokens: 100,
+        }
+    }
+
+    #[test]
+    fn test_create_batch_provider() {
+        let map = providers("openai", OPENAI_API_BASE);
+        assert_eq!(create_batch_provider("gpt-4o-mini", &map).unwrap().display_name(), "OpenAI");
+        let err = create_batch_provider("claude-sonnet-4-20250514", &map).err().unwrap();
+        assert!(err.to_string().contains("no API key configured for Anthropic"));
+        assert!(create_batch_provider("deepseek-chat", &map).is_err());
+    }
+
+    #[test]
+    fn test_openai_input_and_results() {
+        let input = openai_input("gpt-4o-mini", &[request("item-1")]);
+        let line: Value = serde_json::from_str(input.trim()).unwrap();
+        assert_eq!(line["url"], "/v1/chat/completions");
+        assert_eq!(line["body"]["messages"][0]["role"], "system");
+        assert_eq!(line["body"]["messages"][1]["content"], "prompt item-1");
+
+        let ok = openai_result(&json!({
+            "custom_id": "item-1",
+            "response": {"status_code": 200, "body": {"choices": [{"message": {"content": "hi"}}]}},
+            "error": null
+        }));
+        assert_eq!(ok.result, Ok("hi".into()));
+        let failed = openai_result(&json!({
+            "custom_id": "item-2",
+            "response": {"status_code": 400, "body": {"error": {"message": "bad"}}}
+        }));
+        assert_eq!(failed.result, Err("bad".into()));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_batch_roundtrip() {
+        let server = MockServer::start().await;
+        let batch = |status: &str| {
+            json!({
+                "id": "msgbatch_1",
+                "processing_status": status,
+                "request_counts": {"processing": 0, "succeeded": 1, "errored": 1, "canceled": 0, "expired": 0},
+                "results_url": format!("{}/results/msgbatch_1", server.uri()),
+            })
+        };
+        Mock::given(method("POST"))
+            .and(path("/messages/batches"))
+            .and(header("x-api-key", "key"))
+            .and(body_partial_json(json!({
+                "requests": [{"custom_id": "item-1", "params": {"model": "claude-sonnet-4-20250514", "system": "Be brief."}}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch("in_progress")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch("ended")))
+            .mount(&server)
+            .await;
+        let results = [
+            json!({"custom_id": "item-1", "result": {"type": "succeeded", "message": {"content": [{"type": "text", "text": "done"}]}}}),
+            json!({"custom_id": "item-2", "result": {"type": "errored", "error": {"type": "error", "error": {"message": "overloaded"}}}}),
+        ];
+        Mock::given(method("GET"))
+            .and(path("/results/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("{}\n{}\n", results[0], results[1])))
+            .mount(&server)
+            .await;
+
+        let provider = create_batch_provider("anthropic/claude-sonnet-4-20250514", &providers("anthropic", &server.uri())).unwrap();
+        let id = provider
+            .submit("anthropic/claude-sonnet-4-20250514", &[request("item-1")])
+            .await
+            .unwrap();
+        assert_eq!(id, "msgbatch_1");
+
+        let status = provider.status(&id).await.unwrap();
+        assert_eq!(status.state, BatchState::Ended);
+        assert_eq!((status.total, status.succeeded, status.failed), (2, 1, 1));
+
+        let results = provider.results(&id).await.unwrap();
+        assert_eq!(results[0].result, Ok("done".into()));
+        assert_eq!(results[1].result, Err("overloaded".into()));
+    }
+}
@@ -0,0 +1,169 @@
+//! Response cache — record LLM responses once, replay them offline.
+//!
+//! In record mode, [`HttpProvider`](crate::HttpProvider) saves every
+//! successful response to `<dir>/<hash>.json`, next to the request that
+//! produced it. In replay mode it answers from those files and never touches
+//! the network, so tests and prompt/skill development are deterministic and
+//! free. The hash covers the whole request body (model, messages, tools,
+//! settings) with dates and times masked, since the system prompt carries
+//! the current time.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use oxibot_core::config::schema::ResponseCacheConfig;
+use oxibot_core::types::{ChatCompletionRequest, ChatCompletionResponse};
+use oxibot_core::utils;
+
+/// Dates and times, masked before hashing.
+fn timestamp_regex() -> &'static Regex {
+    static RE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\d{4}-\d{2}-\d{2}([T ]\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z| ?UTC|[+-]\d{2}:\d{2})?)?").unwrap()
+    })
+}
+
+/// Whether responses are being recorded or replayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Call the provider and save each successful response.
+    Record,
+    /// Answer from saved responses only.
+    Replay,
+}
+
+/// A saved request/response pair.
+#[derive(Serialize, Deserialize)]
+struct Entry<Req, Resp> {
+    request: Req,
+    response: Resp,
+}
+
+/// Directory of recorded LLM responses.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    mode: CacheMode,
+}
+
+impl ResponseCache {
+    /// Create a cache in `dir`.
+    pub fn new(dir: impl Into<PathBuf>, mode: CacheMode) -> Self {
+        Self { dir: dir.into(), mode }
+    }
+
+    /// Build the cache from config (`None` when off). `dir` defaults to
+    /// `~/.oxibot/provider-cache/`.
+    pub fn from_config(config: &ResponseCacheConfig) -> Result<Option<Self>> {
+        let mode = match config.mode.trim().to_lowercase().as_str() {
+            "" | "off" => return Ok(None),
+            "record" => CacheMode::Record,
+            "replay" => CacheMode::Replay,
+            other => bail!("invalid responseCache mode '{other}' (expected record, replay or off)"),
+        };
+        let dir = if config.dir.is_empty() {
+            utils::get_provider_cache_path()
+        } else {
+            PathBuf::from(&config.dir)
+        };
+        Ok(Some(Self::new(dir, mode)))
+    }
+
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Hash identifying `request`.
+    pub fn key(request: &ChatCompletionRequest) -> String {
+        let body = serde_json::to_string(request).unwrap_or_default();
+        let masked = timestamp_regex().replace_all(&body, "<timestamp>");
+        Sha256::digest(masked.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// The recorded response for `key`, if any.
+    pub fn load(&self, key: &str) -> Option<ChatCompletionResponse> {
+        let path = self.path(key);
+        let text = std::fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<Entry<serde_json::Value, ChatCompletionResponse>>(&text) {
+            Ok(entry) => {
+                debug!(cache = %path.display(), "replaying recorded LLM response");
+                Some(entry.response)
+            }
+            Err(e) => {
+                warn!(cache = %path.display(), error = %e, "ignoring invalid recorded response");
+                None
+            }
+        }
+    }
+
+    /// Save `response` to `request` under `key`. Failures are logged.
+    pub fn store(&self, key: &str, request: &ChatCompletionRequest, response: &ChatCompletionResponse) {
+        let path = self.path(key);
+        let result = std::fs::create_dir_all(&self.dir)
+            .context("failed to create cache directory")
+            .and_then(|_| Ok(serde_json::to_string_pretty(&Entry { request, response })?))
+            .and_then(|json| Ok(std::fs::write(&path, json)?));
+        match result {
+            Ok(()) => debug!(cache = %path.display(), "recorded LLM response"),
+            Err(e) => warn!(cache = %path.display(), error = %e, "failed to record LLM response"),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::types::Message;
+
+    fn request(system: &str) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "gpt-4o".into(),
+            messages: vec![Message::system(system), Message::user("hi")],
+            tools: None,
+            tool_choice: None,
+            parallel_tool_calls: None,
+            max_tokens: Some(1024),
+            temperature: Some(0.7),
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_key_ignores_timestamps() {
+        let key = ResponseCache::key(&request("Date/time: 2026-10-17 12:00:00 UTC"));
+        assert_eq!(key, ResponseCache::key(&request("Date/time: 2026-10-18 08:30:12 UTC")));
+        assert_ne!(key, ResponseCache::key(&request("Date/time: 2026-10-17 12:00:00 UTC. Be terse.")));
+        assert_eq!(key.len(), 64);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = |mode: &str| ResponseCacheConfig { mode: mode.into(), dir: "/tmp/fixtures".into() };
+        assert!(ResponseCache::from_config(&config("")).unwrap().is_none());
+        assert!(ResponseCache::from_config(&config("off")).unwrap().is_none());
+        let cache = ResponseCache::from_config(&config("Replay")).unwrap().unwrap();
+        assert_eq!(cache.mode(), CacheMode::Replay);
+        assert_eq!(cache.dir(), Path::new("/tmp/fixtures"));
+        assert!(ResponseCache::from_config(&config("sometimes")).is_err());
+    }
+}
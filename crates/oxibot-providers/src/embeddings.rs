@@ -0,0 +1,156 @@
+//! Embeddings — turn text into vectors for similarity search.
+//!
+//! Used by the agent's page cache to find the parts of fetched pages that
+//! match a question. Any provider with an OpenAI-compatible `/embeddings`
+//! endpoint works (OpenAI, OpenRouter, a local vLLM or Ollama).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::debug;
+
+use crate::batch::{api_base, send_json, OPENAI_API_BASE};
+use crate::registry::{find_by_model, ProviderConfig};
+
+/// Timeout for one embeddings request.
+const EMBEDDINGS_HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Most inputs sent in one request.
+const MAX_BATCH: usize = 64;
+
+/// A model that embeds text.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// One vector per text, in order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+
+    /// The embedding model; vectors of different models don't compare.
+    fn model(&self) -> &str;
+}
+
+/// Create an embedder for `model` (e.g. `"openai/text-embedding-3-small"`),
+/// with its API key from `providers`.
+pub fn create_embedder(model: &str, providers: &HashMap<String, ProviderConfig>) -> Result<Box<dyn Embedder>> {
+    let spec = find_by_model(model).with_context(|| format!("no provider found for embedding model '{model}'"))?;
+    if spec.name == "anthropic" {
+        bail!("Anthropic has no embeddings API; use an OpenAI-compatible embedding model");
+    }
+    let config = providers
+        .get(spec.name)
+        .filter(|c| c.is_configured())
+        .with_context(|| format!("no API key configured for {}", spec.display_name))?;
+    let default_base = match spec.name {
+        "openai" => OPENAI_API_BASE,
+        _ => spec
+            .default_api_base
+            .with_context(|| format!("set an apiBase for {} to use its embeddings", spec.display_name))?,
+    };
+    // "openai/text-embedding-3-small" → "text-embedding-3-small" for OpenAI itself
+    let api_model = match model.split_once('/') {
+        Some((prefix, rest)) if prefix == spec.name && !spec.is_gateway => rest,
+        _ => model,
+    };
+    Ok(Box::new(OpenAiEmbeddings {
+        client: reqwest::Client::builder().timeout(EMBEDDINGS_HTTP_TIMEOUT).build()?,
+        api_base: api_base(config, default_base),
+        api_key: config.api_key.clone(),
+        model: api_model.to_string(),
+    }))
+}
+
+/// OpenAI-compatible `/embeddings` endpoint.
+pub struct OpenAiEmbeddings {
+    client: reqwest::Client,
+    api_base: String,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAiEmbeddings {
+    pub fn new(api_base: &str, api_key: &str, model: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_base: api_base.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbeddings {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(MAX_BATCH) {
+            let request = self
+                .client
+                .post(format!("{}/embeddings", self.api_base))
+                .bearer_auth(&self.api_key)
+                .json(&json!({ "model": self.model, "input": batch }));
+            let response = send_json(request, "embeddings").await?;
+            let mut data: Vec<(usize, Vec<f32>)> = response["data"]
+                .as_array()
+                .context("no data in embeddings response")?
+                .iter()
+                .map(|item| {
+                    let index = item["index"].as_u64().unwrap_or_default() as usize;
+                    let vector = item["embedding"]
+                        .as_array()
+                        .map(|v| v.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect())
+                        .unwrap_or_default();
+                    (index, vector)
+                })
+                .collect();
+            if data.len() != batch.len() {
+                bail!("embeddings response has {} vectors for {} inputs", data.len(), batch.len());
+            }
+            data.sort_by_key(|(index, _)| *index);
+            vectors.extend(data.into_iter().map(|(_, vector)| vector));
+        }
+        debug!(model = %self.model, count = texts.len(), "embedded texts");
+        Ok(vectors)
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_embed_orders_by_index() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/embeddings"))
+            .and(header("authorization", "Bearer sk-test"))
+            .and(body_partial_json(json!({"model": "text-embedding-3-small", "input": ["a", "b"]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"index": 1, "embedding": [0.0, 1.0]},
+                    {"index": 0, "embedding": [1.0, 0.0]}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let embedder = OpenAiEmbeddings::new(&server.uri(), "sk-test", "text-embedding-3-small");
+        let vectors = embedder.embed(&["a".into(), "b".into()]).await.unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+        let providers = HashMap::new();
+        assert!(create_embedder("claude-sonnet-4", &providers).is_err());
+        assert!(create_embedder("openai/text-embedding-3-small", &providers).is_err());
+    }
+}
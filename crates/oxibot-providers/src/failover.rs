@@ -0,0 +1,198 @@
+//! Failover — one model served by several providers, healthiest first.
+//!
+//! A model such as `claude-sonnet-4` can often be reached directly and
+//! through a gateway (OpenRouter, AiHubMix). [`FailoverProvider`] holds
+//! every configured provider that can serve the model, orders them by the
+//! probe history in [`ProviderHealth`], and moves on to the next one when
+//! a call fails.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::{debug, warn};
+
+use oxibot_core::types::{LlmResponse, Message, ToolDefinition};
+
+use crate::health::ProviderHealth;
+use crate::http_provider::HttpProvider;
+use crate::registry::{find_by_model, AuthStyle, ProviderConfig, ProviderSpec, PROVIDERS};
+use crate::traits::{LlmProvider, LlmRequestConfig};
+
+/// Providers able to serve `model`, in preference order: the model's own
+/// provider, then configured gateways.
+pub fn candidates<'a>(
+    model: &str,
+    providers: &'a HashMap<String, ProviderConfig>,
+) -> Vec<(&'a ProviderConfig, &'static ProviderSpec)> {
+    let mut found: Vec<(&ProviderConfig, &'static ProviderSpec)> = Vec::new();
+    if let Some(spec) = find_by_model(model) {
+        if let Some(config) = providers.get(spec.name) {
+            if config.is_configured() || spec.auth == AuthStyle::None {
+                found.push((config, spec));
+            }
+        }
+    }
+    for spec in PROVIDERS.iter().filter(|s| s.is_gateway) {
+        if found.iter().any(|(_, s)| s.name == spec.name) {
+            continue;
+        }
+        if let Some(config) = providers.get(spec.name).filter(|c| c.is_configured()) {
+            found.push((config, spec));
+        }
+    }
+    found
+}
+
+/// Calls the healthiest of several providers, falling back to the others.
+pub struct FailoverProvider {
+    providers: Vec<HttpProvider>,
+    health: Arc<ProviderHealth>,
+}
+
+impl FailoverProvider {
+    /// `providers` in preference order (used when health is unknown);
+    /// must not be empty.
+    pub fn new(providers: Vec<HttpProvider>, health: Arc<ProviderHealth>) -> Self {
+        assert!(!providers.is_empty(), "FailoverProvider needs at least one provider");
+        Self { providers, health }
+    }
+
+    /// The providers, healthiest first.
+    fn ordered(&self) -> Vec<&HttpProvider> {
+        let names: Vec<&str> = self.providers.iter().map(|p| p.spec().name).collect();
+        self.health.rank(&names).into_iter().map(|i| &self.providers[i]).collect()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FailoverProvider {
+    async fn chat(
+        &self,
+        messages: &[Message],
+        tools: Option<&[ToolDefinition]>,
+        model: &str,
+        config: &LlmRequestConfig,
+    ) -> LlmResponse {
+        let ordered = self.ordered();
+        let mut response = LlmResponse::default();
+        for (i, provider) in ordered.iter().enumerate() {
+            response = provider.chat(messages, tools, model, config).await;
+            if !response.is_error() {
+                if i > 0 {
+                    debug!(provider = provider.display_name(), "failover call succeeded");
+                }
+                return response;
+            }
+            if let Some(next) = ordered.get(i + 1) {
+                warn!(
+                    provider = provider.display_name(),
+                    next = next.display_name(),
+                    "LLM call failed, failing over"
+                );
+            }
+        }
+        response
+    }
+
+    fn default_model(&self) -> &str {
+        self.providers[0].default_model()
+    }
+
+    fn display_name(&self) -> &str {
+        self.ordered()[0].display_name()
+    }
+
+    fn supports_web_search(&self) -> bool {
+        self.providers[0].supports_web_search()
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::Probe;
+    use crate::registry::find_by_name;
+    use chrono::Utc;
+    use oxibot_core::config::schema::RequestPolicy;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config(api_key: &str, api_base: Option<String>) -> ProviderConfig {
+        ProviderConfig {
+            api_key: api_key.into(),
+            api_base,
+            request: RequestPolicy {
+                max_retries: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_candidates_direct_then_gateways() {
+        let mut providers = HashMap::new();
+        providers.insert("anthropic".to_string(), config("sk-ant", None));
+        providers.insert("openrouter".to_string(), config("sk-or", None));
+        providers.insert("openai".to_string(), config("sk-oa", None));
+        let names: Vec<&str> = candidates("claude-sonnet-4", &providers).iter().map(|(_, s)| s.name).collect();
+        assert_eq!(names, vec!["anthropic", "openrouter"]);
+
+        providers.remove("anthropic");
+        assert_eq!(candidates("claude-sonnet-4", &providers).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_and_prefers_healthy() {
+        let down = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&down)
+            .await;
+        let up = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "hi"}, "finish_reason": "stop"}]
+            })))
+            .mount(&up)
+            .await;
+
+        let anthropic = find_by_name("anthropic").unwrap();
+        let openrouter = find_by_name("openrouter").unwrap();
+        let health = Arc::new(ProviderHealth::new(None, 10));
+        let provider = FailoverProvider::new(
+            vec![
+                HttpProvider::new(&config("k", Some(down.uri())), openrouter, "claude-sonnet-4"),
+                HttpProvider::new(&config("k", Some(up.uri())), anthropic, "claude-sonnet-4"),
+            ],
+            health.clone(),
+        );
+        let messages = vec![Message::user("hello")];
+        let response = provider.chat(&messages, None, "claude-sonnet-4", &LlmRequestConfig::default()).await;
+        assert_eq!(response.content.as_deref(), Some("hi"));
+        assert_eq!(down.received_requests().await.unwrap().len(), 1);
+
+        // Once probes show the first provider failing, it is skipped
+        health.record(
+            "openrouter",
+            Probe {
+                at: Utc::now(),
+                url: down.uri(),
+                status: Some(500),
+                latency_ms: 5,
+                error: Some("500 Internal Server Error".into()),
+            },
+        );
+        assert_eq!(provider.display_name(), anthropic.display_name);
+        let response = provider.chat(&messages, None, "claude-sonnet-4", &LlmRequestConfig::default()).await;
+        assert!(!response.is_error());
+        assert_eq!(down.received_requests().await.unwrap().len(), 1);
+    }
+}
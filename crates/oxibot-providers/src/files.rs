@@ -0,0 +1,239 @@
+//! File uploads — send a document to the provider once, then refer to it
+//! by id.
+//!
+//! OpenAI's `/v1/files` and Anthropic's Files API store an uploaded
+//! document on the provider side. Messages then carry a small
+//! [`ContentPart::File`](oxibot_core::types::ContentPart::File) reference
+//! instead of megabytes of extracted text, and the same file can be
+//! referenced again on later turns without uploading it again.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::batch::{api_base, send_json, send_text, ANTHROPIC_API_BASE, ANTHROPIC_VERSION, OPENAI_API_BASE};
+use crate::registry::{find_by_model, ProviderConfig};
+
+/// `anthropic-beta` header enabling the Files API.
+const ANTHROPIC_FILES_BETA: &str = "files-api-2025-04-14";
+
+/// Timeout for a single upload or delete.
+const FILES_HTTP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A provider that stores uploaded files.
+#[async_trait]
+pub trait FileUploader: Send + Sync {
+    /// Upload `data` as `filename`; returns the file id.
+    async fn upload(&self, filename: &str, data: Vec<u8>, mime: &str) -> Result<String>;
+
+    /// Delete an uploaded file.
+    async fn delete(&self, file_id: &str) -> Result<()>;
+
+    /// Display name for messages.
+    fn display_name(&self) -> &str;
+}
+
+/// Create the file uploader for `model`: OpenAI or Anthropic, with its API
+/// key from `providers`.
+pub fn create_file_uploader(
+    model: &str,
+    providers: &HashMap<String, ProviderConfig>,
+) -> Result<Box<dyn FileUploader>> {
+    let spec = find_by_model(model)
+        .filter(|spec| matches!(spec.name, "openai" | "anthropic"))
+        .with_context(|| format!("file uploads need an OpenAI or Anthropic model, not '{model}'"))?;
+    let config = providers
+        .get(spec.name)
+        .filter(|c| c.is_configured())
+        .with_context(|| format!("no API key configured for {}", spec.display_name))?;
+    let client = reqwest::Client::builder().timeout(FILES_HTTP_TIMEOUT).build()?;
+    let api_key = config.api_key.clone();
+    Ok(match spec.name {
+        "openai" => Box::new(OpenAiFiles {
+            client,
+            api_base: api_base(config, OPENAI_API_BASE),
+            api_key,
+        }),
+        _ => Box::new(AnthropicFiles {
+            client,
+            api_base: api_base(config, ANTHROPIC_API_BASE),
+            api_key,
+        }),
+    })
+}
+
+fn file_part(filename: &str, data: Vec<u8>, mime: &str) -> Result<reqwest::multipart::Part> {
+    Ok(reqwest::multipart::Part::bytes(data)
+        .file_name(filename.to_string())
+        .mime_str(mime)?)
+}
+
+// ─────────────────────────────────────────────
+// OpenAI
+// ─────────────────────────────────────────────
+
+/// OpenAI Files API, with purpose `user_data` (files for model input).
+pub struct OpenAiFiles {
+    client: reqwest::Client,
+    api_base: String,
+    api_key: String,
+}
+
+#[async_trait]
+impl FileUploader for OpenAiFiles {
+    async fn upload(&self, filename: &str, data: Vec<u8>, mime: &str) -> Result<String> {
+        let size = data.len();
+        let form = reqwest::multipart::Form::new()
+            .text("purpose", "user_data")
+            .part("file", file_part(filename, data, mime)?);
+        let request = self
+            .client
+            .post(format!("{}/files", self.api_base))
+            .bearer_auth(&self.api_key)
+            .multipart(form);
+        let uploaded = send_json(request, "file upload").await?;
+        let file_id = uploaded["id"].as_str().context("no file id in upload response")?;
+        debug!(file_id, filename, size, "uploaded file to openai");
+        Ok(file_id.to_string())
+    }
+
+    async fn delete(&self, file_id: &str) -> Result<()> {
+        let request = self
+            .client
+            .delete(format!("{}/files/{file_id}", self.api_base))
+            .bearer_auth(&self.api_key);
+        send_text(request, "file delete").await?;
+        Ok(())
+    }
+
+    fn display_name(&self) -> &str {
+        "OpenAI"
+    }
+}
+
+// ─────────────────────────────────────────────
+// Anthropic
+// ─────────────────────────────────────────────
+
+/// Anthropic Files API (beta).
+pub struct AnthropicFiles {
+    client: reqwest::Client,
+    api_base: String,
+    api_key: String,
+}
+
+impl AnthropicFiles {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{path}", self.api_base))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("anthropic-beta", ANTHROPIC_FILES_BETA)
+    }
+}
+
+#[async_trait]
+impl FileUploader for AnthropicFiles {
+    async fn upload(&self, filename: &str, data: Vec<u8>, mime: &str) -> Result<String> {
+        let size = data.len();
+        let form = reqwest::multipart::Form::new().part("file", file_part(filename, data, mime)?);
+        let request = self.request(reqwest::Method::POST, "/files").multipart(form);
+        let uploaded = send_json(request, "file upload").await?;
+        let file_id = uploaded["id"].as_str().context("no file id in upload response")?;
+        debug!(file_id, filename, size, "uploaded file to anthropic");
+        Ok(file_id.to_string())
+    }
+
+    async fn delete(&self, file_id: &str) -> Result<()> {
+        let request = self.request(reqwest::Method::DELETE, &format!("/files/{file_id}"));
+        send_text(request, "file delete").await?;
+        Ok(())
+    }
+
+    fn display_name(&self) -> &str {
+        "Anthropic"
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::config::schema::RequestPolicy;
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn providers(name: &str, api_base: &str) -> HashMap<String, ProviderConfig> {
+        let config = ProviderConfig {
+            api_key: "key".into(),
+            api_base: Some(api_base.into()),
+            extra_headers: None,
+            extra_body: None,
+            request: RequestPolicy::default(),
+        };
+        HashMap::from([(name.to_string(), config)])
+    }
+
+    #[test]
+    fn test_create_file_uploader() {
+        let map = providers("anthropic", ANTHROPIC_API_BASE);
+        let uploader = create_file_uploader("claude-sonnet-4-20250514", &map).unwrap();
+        assert_eq!(uploader.display_name(), "Anthropic");
+        let err = create_file_uploader("gpt-4o", &map).err().unwrap();
+        assert!(err.to_string().contains("no API key configured for OpenAI"));
+        assert!(create_file_uploader("deepseek-chat", &map).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_openai_upload_and_delete() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/files"))
+            .and(header("Authorization", "Bearer key"))
+            .and(body_string_contains("user_data"))
+            .and(body_string_contains("%PDF-1.7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "file-abc", "object": "file"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/files/file-abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "file-abc", "deleted": true})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let uploader = create_file_uploader("gpt-4o", &providers("openai", &server.uri())).unwrap();
+        let id = uploader
+            .upload("report.pdf", b"%PDF-1.7 ...".to_vec(), "application/pdf")
+            .await
+            .unwrap();
+        assert_eq!(id, "file-abc");
+        uploader.delete(&id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_upload_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/files"))
+            .and(header("anthropic-beta", ANTHROPIC_FILES_BETA))
+            .respond_with(ResponseTemplate::new(413).set_body_string("file too large"))
+            .mount(&server)
+            .await;
+
+        let uploader = create_file_uploader("claude-sonnet-4-20250514", &providers("anthropic", &server.uri())).unwrap();
+        let err = uploader
+            .upload("big.pdf", vec![0; 16], "application/pdf")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("file too large"), "{err}");
+    }
+}
@@ -0,0 +1,304 @@
+//! Provider health — periodic latency probes and their history.
+//!
+//! The circuit breaker only learns a provider is down when a real call
+//! fails. [`ProviderHealth`] keeps the results of lightweight probes
+//! (`GET {apiBase}/models`: no tokens spent) per provider, saved to a file
+//! so `oxibot status` can show them. [`spawn_monitor`] probes every
+//! configured provider on an interval, and
+//! [`FailoverProvider`](crate::failover::FailoverProvider) uses the
+//! results to pick the healthiest provider for each call.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::registry::{all_providers, AuthStyle, ProviderConfig, ProviderSpec};
+
+/// How long one probe may take.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Successful probes averaged for a provider's latency.
+const LATENCY_PROBES: usize = 5;
+
+/// One probe of a provider.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Probe {
+    pub at: DateTime<Utc>,
+    pub url: String,
+    /// HTTP status (`None` when no response arrived).
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// Why the probe failed (`None` = it succeeded).
+    pub error: Option<String>,
+}
+
+impl Probe {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A provider's recent probes, summed up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthSummary {
+    pub probes: usize,
+    pub failures: usize,
+    /// Mean latency of the last successful probes (`None` = none succeeded).
+    pub avg_latency_ms: Option<u64>,
+    pub last: Probe,
+}
+
+impl HealthSummary {
+    /// Healthy when the last probe succeeded.
+    pub fn is_healthy(&self) -> bool {
+        self.last.ok()
+    }
+}
+
+/// Probe history per provider name.
+pub struct ProviderHealth {
+    /// Where the history is saved (`None` keeps it in memory only).
+    path: Option<PathBuf>,
+    /// Probes kept per provider.
+    max_history: usize,
+    probes: Mutex<HashMap<String, VecDeque<Probe>>>,
+}
+
+impl ProviderHealth {
+    /// Load the history saved at `path`, keeping `max_history` probes per
+    /// provider from now on.
+    pub fn new(path: Option<PathBuf>, max_history: usize) -> Self {
+        let probes = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            max_history: max_history.max(1),
+            probes: Mutex::new(probes),
+        }
+    }
+
+    /// Add a probe of `provider` and save the history.
+    pub fn record(&self, provider: &str, probe: Probe) {
+        let mut probes = self.probes.lock().unwrap();
+        let history = probes.entry(provider.to_string()).or_default();
+        history.push_back(probe);
+        while history.len() > self.max_history {
+            history.pop_front();
+        }
+        if let Some(ref path) = self.path {
+            let json = serde_json::to_string_pretty(&*probes).unwrap_or_default();
+            let saved = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(path, json));
+            if let Err(e) = saved {
+                warn!(path = %path.display(), error = %e, "failed to save provider health");
+            }
+        }
+    }
+
+    /// `provider`'s probes, oldest first.
+    pub fn history(&self, provider: &str) -> Vec<Probe> {
+        let probes = self.probes.lock().unwrap();
+        probes.get(provider).map(|h| h.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// `provider`'s health (`None` = never probed).
+    pub fn summary(&self, provider: &str) -> Option<HealthSummary> {
+        let probes = self.probes.lock().unwrap();
+        let history = probes.get(provider)?;
+        let last = history.back()?.clone();
+        let latencies: Vec<u64> = history
+            .iter()
+            .rev()
+            .filter(|p| p.ok())
+            .take(LATENCY_PROBES)
+            .map(|p| p.latency_ms)
+            .collect();
+        Some(HealthSummary {
+            probes: history.len(),
+            failures: history.iter().filter(|p| !p.ok()).count(),
+            avg_latency_ms: (!latencies.is_empty()).then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+            last,
+        })
+    }
+
+    /// Indexes of `providers`, healthiest first: providers whose last probe
+    /// succeeded by latency, then those never probed, then failing ones.
+    /// Ties keep the given order.
+    pub fn rank(&self, providers: &[&str]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..providers.len()).collect();
+        order.sort_by_key(|&i| match self.summary(providers[i]) {
+            Some(s) if s.is_healthy() => (0, s.avg_latency_ms.unwrap_or_default()),
+            None => (1, 0),
+            Some(_) => (2, 0),
+        });
+        order
+    }
+}
+
+/// Providers worth probing: those with a key, and keyless (local) ones
+/// with an `apiBase`.
+pub fn probed_providers(providers: &HashMap<String, ProviderConfig>) -> Vec<(&'static ProviderSpec, ProviderConfig)> {
+    all_providers()
+        .into_iter()
+        .filter_map(|spec| {
+            let config = providers.get(spec.name)?;
+            let probed = config.is_configured() || (spec.auth == AuthStyle::None && config.api_base.is_some());
+            probed.then(|| (spec, config.clone()))
+        })
+        .collect()
+}
+
+/// Probe `spec` by listing its models (`GET {apiBase}/models`).
+pub async fn probe(client: &reqwest::Client, spec: &ProviderSpec, config: &ProviderConfig) -> Probe {
+    let base = config
+        .api_base
+        .as_deref()
+        .or(spec.default_api_base)
+        .unwrap_or("https://api.openai.com/v1");
+    let url = format!("{}/models", base.trim_end_matches('/'));
+
+    let mut request = client.get(&url).timeout(PROBE_TIMEOUT);
+    request = match spec.auth {
+        AuthStyle::Bearer => request.bearer_auth(&config.api_key),
+        AuthStyle::Header(name) => request.header(name, &config.api_key),
+        AuthStyle::None => request,
+    };
+    for (name, value) in config.extra_headers.iter().flatten() {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let at = Utc::now();
+    let started = Instant::now();
+    let (status, error) = match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            (Some(status.as_u16()), (!status.is_success()).then(|| status.to_string()))
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+    Probe {
+        at,
+        url,
+        status,
+        latency_ms: started.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+/// Probe each of `providers` every `interval`, recording the results in
+/// `health`.
+pub fn spawn_monitor(
+    health: Arc<ProviderHealth>,
+    providers: Vec<(&'static ProviderSpec, ProviderConfig)>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    info!(providers = providers.len(), interval_secs = interval.as_secs(), "provider health probes started");
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let mut probes = tokio::task::JoinSet::new();
+            for (spec, config) in &providers {
+                let (client, spec, config) = (client.clone(), *spec, config.clone());
+                probes.spawn(async move { (spec, probe(&client, spec, &config).await) });
+            }
+            while let Some(Ok((spec, probe))) = probes.join_next().await {
+                match probe.error {
+                    Some(ref error) => warn!(provider = spec.name, error = %error, "provider probe failed"),
+                    None => debug!(provider = spec.name, latency_ms = probe.latency_ms, "provider probe ok"),
+                }
+                health.record(spec.name, probe);
+            }
+        }
+    })
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::find_by_name;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn probe_of(latency_ms: u64, error: Option<&str>) -> Probe {
+        Probe {
+            at: Utc::now(),
+            url: "https://example.com/models".into(),
+            status: Some(if error.is_some() { 500 } else { 200 }),
+            latency_ms,
+            error: error.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_history_summary_and_rank() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("health.json");
+        let health = ProviderHealth::new(Some(path.clone()), 3);
+        health.record("openai", probe_of(900, None));
+        health.record("openrouter", probe_of(100, None));
+        health.record("openrouter", probe_of(300, None));
+        health.record("anthropic", probe_of(50, None));
+        health.record("anthropic", probe_of(80, Some("500 Internal Server Error")));
+
+        let summary = health.summary("openrouter").unwrap();
+        assert_eq!((summary.probes, summary.failures, summary.avg_latency_ms), (2, 0, Some(200)));
+        assert!(!health.summary("anthropic").unwrap().is_healthy());
+        assert_eq!(health.summary("groq"), None);
+
+        // Healthy by latency, then unknown, then failing
+        assert_eq!(health.rank(&["anthropic", "groq", "openai", "openrouter"]), vec![3, 2, 1, 0]);
+
+        // Saved, and trimmed to the history size
+        for latency in [1, 2, 3, 4] {
+            health.record("openai", probe_of(latency, None));
+        }
+        let reloaded = ProviderHealth::new(Some(path), 3);
+        let latencies: Vec<u64> = reloaded.history("openai").iter().map(|p| p.latency_ms).collect();
+        assert_eq!(latencies, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_lists_models() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .and(header("authorization", "Bearer sk-ok"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{\"data\": []}"))
+            .mount(&server)
+            .await;
+        let spec = find_by_name("openai").unwrap();
+        let mut config = ProviderConfig {
+            api_key: "sk-ok".into(),
+            api_base: Some(format!("{}/v1", server.uri())),
+            ..Default::default()
+        };
+        let client = reqwest::Client::new();
+
+        let ok = probe(&client, spec, &config).await;
+        assert!(ok.ok(), "{ok:?}");
+        assert_eq!(ok.status, Some(200));
+        assert_eq!(ok.url, format!("{}/v1/models", server.uri()));
+
+        config.api_key = "sk-bad".into();
+        let bad = probe(&client, spec, &config).await;
+        assert_eq!(bad.status, Some(404));
+        assert!(!bad.ok());
+    }
+}
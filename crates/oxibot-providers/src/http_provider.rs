@@ -6,30 +6,52 @@
 //! Covers: OpenAI, Anthropic (via OpenRouter), DeepSeek, Groq, Gemini, ZhiPu,
 //!         DashScope, Moonshot, MiniMax, vLLM, AiHubMix, OpenRouter.
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use tracing::{debug, error, warn};
 
 use oxibot_core::types::{
     ChatCompletionRequest, ChatCompletionResponse, LlmResponse, Message, ToolDefinition,
 };
 
+use crate::cache::{CacheMode, ResponseCache};
 use crate::registry::{
-    apply_model_overrides, resolve_model_name, ProviderConfig, ProviderSpec,
+    apply_model_overrides, resolve_model_name, AuthStyle, ProviderConfig, ProviderSpec,
 };
+use crate::resilience::{backoff_delay, CircuitBreaker, RateLimiter};
+use crate::tool_schema;
+use oxibot_core::config::schema::RequestPolicy;
 use crate::traits::{LlmProvider, LlmRequestConfig};
+use crate::transport::{is_valid_header, HttpRequest, HttpTransport};
+use crate::web_search;
 
 // ─────────────────────────────────────────────
 // HttpProvider
 // ─────────────────────────────────────────────
 
+/// Request body fields built from the conversation and request config,
+/// which `extraBody` may not replace.
+const RESERVED_BODY_FIELDS: &[&str] = &[
+    "model",
+    "messages",
+    "tools",
+    "tool_choice",
+    "parallel_tool_calls",
+    "max_tokens",
+    "temperature",
+    "stream",
+];
+
 /// A generic LLM provider that talks to any OpenAI-compatible HTTP API.
 ///
 /// Replaces nanobot's `LiteLLMProvider` — instead of routing through LiteLLM,
-/// we make direct HTTP requests via `reqwest`.
+/// we make direct HTTP requests via `reqwest` (or the [`HttpTransport`]
+/// given to [`with_transport`](Self::with_transport)).
 pub struct HttpProvider {
-    /// HTTP client (shared, connection-pooled).
-    client: reqwest::Client,
+    /// Sends the requests.
+    transport: Arc<dyn HttpTransport>,
     /// API base URL (e.g. `"https://api.openai.com/v1"`).
     api_base: String,
     /// API key for Bearer authentication.
@@ -37,9 +59,30 @@ pub struct HttpProvider {
     /// Default model for this provider instance.
     default_model: String,
     /// Extra headers to send with each request (e.g. AiHubMix X-App-Code).
-    extra_headers: HeaderMap,
+    extra_headers: Vec<(String, String)>,
+    /// Extra request body fields (e.g. OpenRouter provider routing).
+    extra_body: serde_json::Map<String, serde_json::Value>,
     /// Reference to the provider spec for model resolution and overrides.
     spec: &'static ProviderSpec,
+    /// Timeout, retry and circuit-breaker settings.
+    policy: RequestPolicy,
+    /// Short-circuits calls while the provider keeps failing.
+    breaker: CircuitBreaker,
+    /// Requests/tokens-per-minute budget shared with every other instance
+    /// talking to the same endpoint (`None` = unlimited).
+    limiter: Option<Arc<RateLimiter>>,
+    /// Records or replays responses (`None` = always call the API).
+    cache: Option<ResponseCache>,
+}
+
+/// Outcome of a single failed HTTP attempt.
+enum AttemptError {
+    /// Worth retrying (timeout, connection error, 429, 5xx), with an
+    /// optional server-requested delay from `Retry-After`.
+    Retryable(String, Option<Duration>),
+    /// Retrying won't help (auth errors, bad request, unparseable body).
+    /// Holds the full user-facing message.
+    Fatal(String),
 }
 
 impl std::fmt::Debug for HttpProvider {
@@ -56,7 +99,7 @@ impl HttpProvider {
     /// Create a new HttpProvider from a provider config and spec.
     ///
     /// # Arguments
-    /// * `config`  — User's config (api_key, api_base, extra_headers)
+    /// * `config`  — User's config (api_key, api_base, extra_headers, extra_body)
     /// * `spec`    — Static provider spec from the registry
     /// * `model`   — The default model to use
     pub fn new(config: &ProviderConfig, spec: &'static ProviderSpec, model: &str) -> Self {
@@ -68,35 +111,66 @@ impl HttpProvider {
             .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
 
         // Build extra headers
-        let mut extra_headers = HeaderMap::new();
+        let mut extra_headers = Vec::new();
         if let Some(ref headers) = config.extra_headers {
             for (key, value) in headers {
-                if let (Ok(name), Ok(val)) = (
-                    HeaderName::from_bytes(key.as_bytes()),
-                    HeaderValue::from_str(value),
-                ) {
-                    extra_headers.insert(name, val);
+                if is_valid_header(key, value) {
+                    extra_headers.push((key.clone(), value.clone()));
                 } else {
                     warn!("Invalid header: {}={}", key, value);
                 }
             }
         }
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .expect("Failed to build HTTP client");
+        // Fields the request builder sets itself can't be overridden
+        let mut extra_body = config.extra_body.clone().unwrap_or_default();
+        extra_body.retain(|key, _| {
+            let reserved = RESERVED_BODY_FIELDS.contains(&key.as_str());
+            if reserved {
+                warn!(provider = spec.display_name, field = %key, "Ignoring reserved extraBody field");
+            }
+            !reserved
+        });
+
+        #[cfg(feature = "native")]
+        let transport: Arc<dyn HttpTransport> = Arc::new(crate::transport::ReqwestTransport::new());
+        #[cfg(not(feature = "native"))]
+        let transport: Arc<dyn HttpTransport> = Arc::new(crate::transport::MissingTransport);
+
+        let limiter = RateLimiter::shared(&format!("{}|{}", spec.name, api_base), &config.request);
 
         HttpProvider {
-            client,
+            transport,
             api_base,
             api_key: config.api_key.clone(),
             default_model: model.to_string(),
             extra_headers,
+            extra_body,
             spec,
+            policy: config.request.clone(),
+            breaker: CircuitBreaker::new(&config.request),
+            limiter,
+            cache: None,
         }
     }
 
+    /// Send requests through `transport` instead of the built-in client.
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Record responses to, or replay them from, `cache`.
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// The registry spec of the provider behind this client.
+    pub fn spec(&self) -> &'static ProviderSpec {
+        self.spec
+    }
+
     /// Build the full chat completions URL.
     fn completions_url(&self) -> String {
         let base = self.api_base.trim_end_matches('/');
@@ -107,6 +181,68 @@ impl HttpProvider {
     fn resolve_model(&self, model: &str) -> String {
         resolve_model_name(model, self.spec)
     }
+
+    /// Auth and extra headers, with the API key the way this provider
+    /// expects it.
+    fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = match self.spec.auth {
+            AuthStyle::Bearer => vec![("Authorization".to_string(), format!("Bearer {}", self.api_key))],
+            AuthStyle::Header(name) => vec![(name.to_string(), self.api_key.clone())],
+            AuthStyle::None => Vec::new(),
+        };
+        headers.extend(self.extra_headers.iter().cloned());
+        headers
+    }
+
+    /// Make one request and classify any failure.
+    async fn send_once(
+        &self,
+        url: &str,
+        body: &ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, AttemptError> {
+        let request = HttpRequest {
+            url: url.to_string(),
+            headers: self.headers(),
+            body: serde_json::to_string(body)
+                .map_err(|e| AttemptError::Fatal(format!("Error encoding LLM request: {}", e)))?,
+            timeout: Duration::from_secs(self.policy.timeout_secs.max(1)),
+        };
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(|e| AttemptError::Retryable(format!("{e:#}"), None))?;
+
+        let status = response.status;
+        if !response.is_success() {
+            let retry_after = response
+                .header("retry-after")
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            let error_text = &response.body;
+            error!(
+                provider = self.spec.display_name,
+                status = %status,
+                body = %error_text,
+                "API error"
+            );
+            let message = format!("{} — {}", status_line(status), error_text);
+            return Err(if status == 429 || status >= 500 {
+                AttemptError::Retryable(message, retry_after)
+            } else {
+                AttemptError::Fatal(format!("Error calling LLM: {}", message))
+            });
+        }
+
+        serde_json::from_str::<ChatCompletionResponse>(&response.body).map_err(|e| {
+            error!(
+                provider = self.spec.display_name,
+                error = %e,
+                "Failed to parse LLM response"
+            );
+            AttemptError::Fatal(format!("Error parsing LLM response: {}", e))
+        })
+    }
 }
 
 #[async_trait]
@@ -129,54 +265,120 @@ impl LlmProvider for HttpProvider {
             "Calling LLM"
         );
 
-        let request_body = ChatCompletionRequest {
+        // Adapt tool schemas and flags to what this provider accepts
+        let quirks = &self.spec.tools;
+        let tool_choice = tools.and_then(|_| tool_schema::tool_choice(config.tool_choice, quirks));
+        let tools = tools.filter(|_| tool_choice.is_some());
+        let mut request_body = ChatCompletionRequest {
             model: resolved_model.clone(),
             messages: messages.to_vec(),
-            tools: tools.map(|t| t.to_vec()),
-            tool_choice: tools.map(|_| "auto".to_string()),
+            tools: tools.map(|t| tool_schema::normalize_tools(t, quirks.schema)),
+            tool_choice: tool_choice.map(String::from),
+            parallel_tool_calls: tools
+                .and_then(|_| tool_schema::parallel_tool_calls(config.parallel_tool_calls, quirks)),
             max_tokens: Some(config.max_tokens),
             temperature: Some(temperature),
+            extra: self.extra_body.clone(),
         };
+        if config.web_search {
+            web_search::apply(self.spec.web_search, &mut request_body);
+        }
 
-        let url = self.completions_url();
-
-        let result = self
-            .client
-            .post(&url)
-            .bearer_auth(&self.api_key)
-            .headers(self.extra_headers.clone())
-            .json(&request_body)
-            .send()
-            .await;
-
-        let response = match result {
-            Ok(resp) => resp,
-            Err(e) => {
-                error!(provider = self.spec.display_name, error = %e, "HTTP request failed");
-                return LlmResponse::error(format!("Error calling LLM: {}", e));
+        let cache_key = self.cache.as_ref().map(|_| ResponseCache::key(&request_body));
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if cache.mode() == CacheMode::Replay {
+                return match cache.load(key) {
+                    Some(resp) => resp.into(),
+                    None => LlmResponse::error(format!(
+                        "Error calling LLM: no recorded response for this request ({key}.json in {}); \
+                         record it first with responseCache mode \"record\"",
+                        cache.dir().display()
+                    )),
+                };
             }
-        };
+        }
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Failed to read error body".to_string());
-            error!(
+        if let Err(remaining) = self.breaker.check() {
+            warn!(
                 provider = self.spec.display_name,
-                status = %status,
-                body = %error_text,
-                "API error"
+                retry_in_secs = remaining.as_secs(),
+                "Circuit breaker open, skipping LLM call"
             );
             return LlmResponse::error(format!(
-                "Error calling LLM: {} — {}",
-                status, error_text
+                "Error calling LLM: {} is unavailable (circuit breaker open after {} \
+                 consecutive failures); retrying in {}s",
+                self.spec.display_name,
+                self.breaker.failures(),
+                remaining.as_secs().max(1)
             ));
         }
 
-        match response.json::<ChatCompletionResponse>().await {
+        // Rough prompt size (~4 bytes per token) plus the completion budget
+        let estimated_tokens = serde_json::to_string(&request_body.messages).map_or(0, |s| s.len() / 4) as u32
+            + config.max_tokens;
+
+        let url = self.completions_url();
+        let mut attempt = 0;
+        let result = loop {
+            if let Some(ref limiter) = self.limiter {
+                let waited = limiter.acquire(estimated_tokens).await;
+                if waited >= Duration::from_secs(1) {
+                    debug!(
+                        provider = self.spec.display_name,
+                        waited_ms = waited.as_millis() as u64,
+                        "Rate limit reached, LLM call delayed"
+                    );
+                }
+            }
+            match self.send_once(&url, &request_body).await {
+                Err(AttemptError::Retryable(e, retry_after)) if attempt < self.policy.max_retries => {
+                    attempt += 1;
+                    let delay = retry_after
+                        .unwrap_or_else(|| backoff_delay(&self.policy, attempt))
+                        .min(Duration::from_secs(60));
+                    // The provider asked everyone to back off, not just this call
+                    if let (Some(limiter), Some(_)) = (&self.limiter, retry_after) {
+                        limiter.pause(delay);
+                    }
+                    warn!(
+                        provider = self.spec.display_name,
+                        error = %e,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        "LLM call failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(AttemptError::Retryable(e, _)) => {
+                    let message = if attempt > 0 {
+                        format!("Error calling LLM: {} (after {} attempts)", e, attempt + 1)
+                    } else {
+                        format!("Error calling LLM: {}", e)
+                    };
+                    // Only provider-side failures count towards the breaker.
+                    if self.breaker.record_failure() {
+                        error!(
+                            provider = self.spec.display_name,
+                            cooldown_secs = self.policy.breaker_cooldown_secs,
+                            "Circuit breaker opened"
+                        );
+                    }
+                    break Err(message);
+                }
+                Err(AttemptError::Fatal(message)) => break Err(message),
+                Ok(resp) => break Ok(resp),
+            }
+        };
+
+        match result {
             Ok(chat_resp) => {
+                self.breaker.record_success();
+                if let (Some(limiter), Some(usage)) = (&self.limiter, &chat_resp.usage) {
+                    limiter.settle(estimated_tokens, usage.total_tokens);
+                }
+                if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+                    cache.store(key, &request_body, &chat_resp);
+                }
                 let llm_resp: LlmResponse = chat_resp.into();
                 debug!(
                     provider = self.spec.display_name,
@@ -187,14 +389,7 @@ impl LlmProvider for HttpProvider {
                 );
                 llm_resp
             }
-            Err(e) => {
-                error!(
-                    provider = self.spec.display_name,
-                    error = %e,
-                    "Failed to parse LLM response"
-                );
-                LlmResponse::error(format!("Error parsing LLM response: {}", e))
-            }
+            Err(message) => LlmResponse::error(message),
         }
     }
 
@@ -205,6 +400,31 @@ impl LlmProvider for HttpProvider {
     fn display_name(&self) -> &str {
         self.spec.display_name
     }
+
+    fn supports_web_search(&self) -> bool {
+        self.spec.web_search.is_supported()
+    }
+}
+
+/// Status code with its reason phrase, for common error statuses
+/// (`429 Too Many Requests`).
+fn status_line(status: u16) -> String {
+    let reason = match status {
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => return status.to_string(),
+    };
+    format!("{status} {reason}")
 }
 
 // ─────────────────────────────────────────────
@@ -248,6 +468,7 @@ pub fn create_provider(
 mod tests {
     use super::*;
     use crate::registry::find_by_name;
+    use crate::traits::ToolChoice;
     use std::collections::HashMap;
     use wiremock::matchers::{body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -257,6 +478,11 @@ mod tests {
             api_key: api_key.to_string(),
             api_base: api_base.map(String::from),
             extra_headers: None,
+            extra_body: None,
+            request: RequestPolicy {
+                retry_backoff_ms: 1,
+                ..Default::default()
+            },
         }
     }
 
@@ -325,9 +551,10 @@ mod tests {
             api_key: "key".to_string(),
             api_base: None,
             extra_headers: Some(headers),
+            ..Default::default()
         };
         let provider = HttpProvider::new(&config, spec, "gpt-4o");
-        assert!(provider.extra_headers.contains_key("x-app-code"));
+        assert!(provider.headers().contains(&("X-App-Code".to_string(), "my-app-code".to_string())));
     }
 
     // ── Integration tests with mock server ──
@@ -375,6 +602,50 @@ mod tests {
         assert_eq!(resp.usage.as_ref().unwrap().total_tokens, 15);
     }
 
+    #[tokio::test]
+    async fn test_chat_custom_provider_header_auth() {
+        use oxibot_core::config::schema::CustomProviderConfig;
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(header("x-api-key", "proxy-key"))
+            .and(body_partial_json(serde_json::json!({"model": "llama-3.1-8b"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{
+                    "message": {"content": "local hello"},
+                    "finish_reason": "stop"
+                }]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let custom = CustomProviderConfig {
+            name: "test-proxy".into(),
+            api_base: format!("{}/v1", mock_server.uri()),
+            api_key: "proxy-key".into(),
+            auth_header: "x-api-key".into(),
+            model_prefixes: vec!["proxy/".into()],
+            ..Default::default()
+        };
+        let spec: &'static ProviderSpec =
+            Box::leak(Box::new(crate::registry::custom_spec(&custom)));
+        let provider = HttpProvider::new(&custom.to_provider_config(), spec, "proxy/llama-3.1-8b");
+
+        let resp = provider
+            .chat(
+                &[Message::user("hi")],
+                None,
+                "proxy/llama-3.1-8b",
+                &LlmRequestConfig::default(),
+            )
+            .await;
+
+        assert_eq!(resp.content.as_deref(), Some("local hello"));
+        assert_eq!(provider.display_name(), "test-proxy");
+    }
+
     #[tokio::test]
     async fn test_chat_with_tool_calls() {
         let mock_server = MockServer::start().await;
@@ -479,6 +750,124 @@ mod tests {
         assert!(resp.content.unwrap().contains("Error calling LLM"));
     }
 
+    #[tokio::test]
+    async fn test_chat_records_and_replays() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "Recorded hello"}, "finish_reason": "stop"}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let spec = find_by_name("openai").unwrap();
+        let req_config = LlmRequestConfig::default();
+        let messages = |now: &str| vec![Message::system(format!("Date/time: {now}")), Message::user("Hello")];
+
+        let recorder = HttpProvider::new(&make_config("key", Some(&mock_server.uri())), spec, "gpt-4o")
+            .with_cache(Some(ResponseCache::new(dir.path(), CacheMode::Record)));
+        let resp = recorder.chat(&messages("2026-10-17 12:00:00 UTC"), None, "gpt-4o", &req_config).await;
+        assert_eq!(resp.content.as_deref(), Some("Recorded hello"));
+
+        // Replay never reaches the (unreachable) API
+        let replayer = HttpProvider::new(&make_config("key", Some("http://127.0.0.1:1")), spec, "gpt-4o")
+            .with_cache(Some(ResponseCache::new(dir.path(), CacheMode::Replay)));
+        let resp = replayer.chat(&messages("2026-10-18 09:15:00 UTC"), None, "gpt-4o", &req_config).await;
+        assert_eq!(resp.content.as_deref(), Some("Recorded hello"));
+        assert_eq!(resp.finish_reason.as_deref(), Some("stop"));
+
+        let resp = replayer.chat(&[Message::user("Something else")], None, "gpt-4o", &req_config).await;
+        assert!(resp.content.unwrap().contains("no recorded response"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_retries_server_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("overloaded"))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{
+                    "message": { "role": "assistant", "content": "Recovered" },
+                    "finish_reason": "stop"
+                }]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let spec = find_by_name("openai").unwrap();
+        let config = make_config("key", Some(&mock_server.uri()));
+        let provider = HttpProvider::new(&config, spec, "gpt-4o");
+
+        let messages = vec![Message::user("Hello")];
+        let resp = provider
+            .chat(&messages, None, "gpt-4o", &LlmRequestConfig::default())
+            .await;
+        assert_eq!(resp.content.as_deref(), Some("Recovered"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_does_not_retry_client_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("bad key"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let spec = find_by_name("openai").unwrap();
+        let config = make_config("key", Some(&mock_server.uri()));
+        let provider = HttpProvider::new(&config, spec, "gpt-4o");
+
+        let messages = vec![Message::user("Hello")];
+        let resp = provider
+            .chat(&messages, None, "gpt-4o", &LlmRequestConfig::default())
+            .await;
+        assert!(resp.content.unwrap().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_circuit_breaker_short_circuits() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("down"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let spec = find_by_name("openai").unwrap();
+        let mut config = make_config("key", Some(&mock_server.uri()));
+        config.request.max_retries = 0;
+        config.request.breaker_threshold = 2;
+        let provider = HttpProvider::new(&config, spec, "gpt-4o");
+
+        let messages = vec![Message::user("Hello")];
+        let req_config = LlmRequestConfig::default();
+        for _ in 0..2 {
+            let resp = provider.chat(&messages, None, "gpt-4o", &req_config).await;
+            assert!(resp.content.unwrap().contains("500"));
+        }
+
+        // Third call never reaches the server
+        let resp = provider.chat(&messages, None, "gpt-4o", &req_config).await;
+        let content = resp.content.unwrap();
+        assert!(content.contains("circuit breaker open after 2 consecutive failures"));
+        assert!(content.contains("OpenAI"));
+    }
+
     #[tokio::test]
     async fn test_chat_sends_correct_body() {
         let mock_server = MockServer::start().await;
@@ -515,6 +904,180 @@ mod tests {
         assert_eq!(resp.content.as_deref(), Some("ok"));
     }
 
+    #[tokio::test]
+    async fn test_chat_sends_extra_body() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "provider": { "order": ["anthropic", "amazon-bedrock"], "allow_fallbacks": false },
+                "route": "fallback",
+                "transforms": ["middle-out"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "content": "ok" }, "finish_reason": "stop" }]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let spec = find_by_name("openrouter").unwrap();
+        let mut config = make_config("sk-or-key", Some(&mock_server.uri()));
+        let extra = serde_json::json!({
+            "provider": { "order": ["anthropic", "amazon-bedrock"], "allow_fallbacks": false },
+            "route": "fallback",
+            "transforms": ["middle-out"],
+            "model": "ignored/model"
+        });
+        config.extra_body = extra.as_object().cloned();
+        let provider = HttpProvider::new(&config, spec, "anthropic/claude-sonnet-4");
+        assert!(!provider.extra_body.contains_key("model"));
+
+        let resp = provider
+            .chat(&[Message::user("test")], None, "anthropic/claude-sonnet-4", &LlmRequestConfig::default())
+            .await;
+        assert_eq!(resp.content.as_deref(), Some("ok"));
+    }
+
+    struct CannedTransport {
+        seen: std::sync::Mutex<Vec<HttpRequest>>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for CannedTransport {
+        async fn send(&self, request: HttpRequest) -> anyhow::Result<crate::transport::HttpResponse> {
+            self.seen.lock().unwrap().push(request);
+            Ok(crate::transport::HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: r#"{"choices": [{"message": {"content": "from the host app"}, "finish_reason": "stop"}]}"#.into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chat_custom_transport() {
+        let transport = Arc::new(CannedTransport { seen: Default::default() });
+        let spec = find_by_name("anthropic").unwrap();
+        let config = make_config("sk-ant", Some("https://api.anthropic.com/v1"));
+        let provider = HttpProvider::new(&config, spec, "claude-3").with_transport(transport.clone());
+
+        let resp = provider
+            .chat(&[Message::user("hi")], None, "claude-3", &LlmRequestConfig::default())
+            .await;
+        assert_eq!(resp.content.as_deref(), Some("from the host app"));
+
+        let seen = transport.seen.lock().unwrap();
+        assert_eq!(seen[0].url, "https://api.anthropic.com/v1/chat/completions");
+        assert_eq!(seen[0].timeout, Duration::from_secs(config.request.timeout_secs));
+        assert!(seen[0].headers.iter().any(|(k, _)| k == "x-api-key" || k == "Authorization"));
+        assert!(seen[0].body.contains("\"messages\""));
+    }
+
+    #[tokio::test]
+    async fn test_chat_web_search() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(body_partial_json(serde_json::json!({ "plugins": [{ "id": "web" }] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "chatcmpl-search",
+                "choices": [{
+                    "message": {
+                        "content": "Rust 1.90 is out.",
+                        "annotations": [{
+                            "type": "url_citation",
+                            "url_citation": { "url": "https://blog.rust-lang.org/", "title": "Rust Blog" }
+                        }]
+                    },
+                    "finish_reason": "stop"
+                }],
+                "usage": null
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let spec = find_by_name("openrouter").unwrap();
+        let config = make_config("sk-or-key", Some(&mock_server.uri()));
+        let provider = HttpProvider::new(&config, spec, "openai/gpt-4o");
+        assert!(provider.supports_web_search());
+        assert!(!HttpProvider::new(&config, find_by_name("deepseek").unwrap(), "deepseek-chat").supports_web_search());
+
+        let req_config = LlmRequestConfig { web_search: true, ..Default::default() };
+        let resp = provider
+            .chat(&[Message::user("latest rust?")], None, "openai/gpt-4o", &req_config)
+            .await;
+
+        assert_eq!(resp.content.as_deref(), Some("Rust 1.90 is out."));
+        assert_eq!(resp.citations.len(), 1);
+        assert_eq!(resp.citations[0].title, "Rust Blog");
+    }
+
+    #[tokio::test]
+    async fn test_chat_translates_tools_for_gemini() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "chatcmpl-gemini",
+                "choices": [{
+                    "message": { "content": "ok" },
+                    "finish_reason": "stop"
+                }],
+                "usage": null
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let spec = find_by_name("gemini").unwrap();
+        let config = make_config("g-key", Some(&mock_server.uri()));
+        let provider = HttpProvider::new(&config, spec, "gemini-2.0-flash");
+        let tools = vec![ToolDefinition::new(
+            "lookup",
+            "Look something up",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"q": {"type": ["string", "null"]}},
+                "additionalProperties": false
+            }),
+        )];
+        let req_config = LlmRequestConfig {
+            tool_choice: ToolChoice::Required,
+            parallel_tool_calls: Some(false),
+            ..Default::default()
+        };
+
+        let messages = vec![Message::user("test")];
+        let resp = provider
+            .chat(&messages, Some(&tools), "gemini-2.0-flash", &req_config)
+            .await;
+        assert_eq!(resp.content.as_deref(), Some("ok"));
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["tool_choice"], "required");
+        assert!(body.get("parallel_tool_calls").is_none());
+        assert_eq!(
+            body["tools"][0]["function"]["parameters"],
+            serde_json::json!({"type": "object", "properties": {"q": {"type": "string", "nullable": true}}})
+        );
+
+        // `ToolChoice::None` sends no tools at all
+        let req_config = LlmRequestConfig {
+            tool_choice: ToolChoice::None,
+            ..Default::default()
+        };
+        provider
+            .chat(&messages, Some(&tools), "gemini-2.0-flash", &req_config)
+            .await;
+        let requests = mock_server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert!(body.get("tools").is_none());
+        assert!(body.get("tool_choice").is_none());
+    }
+
     #[tokio::test]
     async fn test_chat_with_reasoning_content() {
         let mock_server = MockServer::start().await;
@@ -5,17 +5,69 @@
 //! # Architecture
 //!
 //! - [`traits::LlmProvider`] — trait that all providers implement
-//! - [`registry`] — static specs for all 12 supported providers + matching logic
+//! - [`registry`] — static specs for all 12 supported providers, custom providers
+//!   from config, and matching logic
 //! - [`http_provider::HttpProvider`] — generic OpenAI-compatible HTTP client
 //! - [`http_provider::create_provider`] — convenience builder from model name + config
+//! - [`batch`] — OpenAI / Anthropic batch APIs for cheap offline jobs
+//! - [`files`] — OpenAI / Anthropic file uploads, referenced by id in messages
+//! - [`embeddings`] — OpenAI-compatible text embeddings for similarity search
+//! - [`resilience`] — retry backoff, per-provider circuit breaker and rate limiter
+//! - [`health`] — periodic provider probes with latency/error history
+//! - [`failover`] — one model over several providers, healthiest first
+//! - [`cache`] — record/replay of LLM responses for deterministic tests
+//! - [`tool_schema`] — per-provider tool schema and `tool_choice` translation
+//! - [`web_search`] — provider-side web search with cited sources
+//! - [`transcription`] / [`audio`] — Whisper speech-to-text with ffmpeg-based chunking
+//! - [`transport`] — the HTTP layer under `HttpProvider`, replaceable by embedders
+//!
+//! `batch`, `files`, `embeddings`, `health`, `failover`, `transcription` and `audio` need the default `native`
+//! feature. Without it the crate builds for `wasm32-wasip1` and
+//! `HttpProvider` sends requests through a transport supplied with
+//! [`HttpProvider::with_transport`].
 
+#[cfg(feature = "native")]
+pub mod audio;
+#[cfg(feature = "native")]
+pub mod batch;
+pub mod cache;
+#[cfg(feature = "native")]
+pub mod embeddings;
+#[cfg(feature = "native")]
+pub mod failover;
+#[cfg(feature = "native")]
+pub mod files;
+#[cfg(feature = "native")]
+pub mod health;
 pub mod http_provider;
 pub mod registry;
+pub mod resilience;
+pub mod tool_schema;
 pub mod traits;
+#[cfg(feature = "native")]
 pub mod transcription;
+pub mod transport;
+pub mod web_search;
 
 // Re-export main types for convenience
+#[cfg(feature = "native")]
+pub use batch::{create_batch_provider, BatchProvider};
+pub use cache::{CacheMode, ResponseCache};
+#[cfg(feature = "native")]
+pub use embeddings::{create_embedder, Embedder, OpenAiEmbeddings};
+#[cfg(feature = "native")]
+pub use failover::FailoverProvider;
+#[cfg(feature = "native")]
+pub use files::{create_file_uploader, FileUploader};
 pub use http_provider::{create_provider, HttpProvider};
-pub use registry::{ProviderConfig, ProviderSpec, PROVIDERS};
-pub use traits::{LlmProvider, LlmRequestConfig};
-pub use transcription::{GroqTranscriber, TranscriptionProvider};
+#[cfg(feature = "native")]
+pub use health::ProviderHealth;
+pub use resilience::{CircuitBreaker, RateLimiter};
+pub use registry::{register_custom_providers, ProviderConfig, ProviderSpec, PROVIDERS};
+pub use traits::{LlmProvider, LlmRequestConfig, ToolChoice};
+pub use transport::{HttpRequest, HttpResponse, HttpTransport};
+pub use web_search::WebSearchStyle;
+#[cfg(feature = "native")]
+pub use audio::AudioPreprocessor;
+#[cfg(feature = "native")]
+pub use transcription::{CachedTranscriber, GroqTranscriber, TranscriptionOptions, TranscriptionProvider};
@@ -3,8 +3,22 @@
 //! Ports nanobot's `providers/registry.py` PROVIDERS list.
 //! Each `ProviderSpec` describes how to connect to a provider:
 //! keywords for model matching, env var names, API bases, quirks, etc.
+//!
+//! Custom OpenAI-compatible providers from config are registered at startup
+//! with [`register_custom_providers`] and take precedence when a model name
+//! starts with one of their `model_prefixes`.
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use tracing::{info, warn};
+
+use oxibot_core::config::schema::{CustomProviderConfig, TokenizerConfig};
+use oxibot_core::tokenizer::{self, Tokenizer};
+use oxibot_core::utils::expand_home;
+
+use crate::tool_schema::{SchemaDialect, ToolQuirks};
+use crate::web_search::WebSearchStyle;
 
 // ─────────────────────────────────────────────
 // ProviderSpec — static metadata for one provider
@@ -49,6 +63,41 @@ pub struct ProviderSpec {
     /// (lowercase), force that key to that f64 value in the request.
     /// E.g. Kimi K2.5 requires `temperature >= 1.0`.
     pub model_overrides: &'static [ModelOverride],
+    /// How the API key is sent with each request.
+    pub auth: AuthStyle,
+    /// Model name prefixes routed to this provider and stripped before the
+    /// request (custom providers only). E.g. `&["lmstudio/"]`.
+    pub model_prefixes: &'static [&'static str],
+    /// Function-calling differences: schema dialect, `tool_choice` and
+    /// `parallel_tool_calls` support.
+    pub tools: ToolQuirks,
+    /// Tokenizer file name for this provider's models (e.g. `"o200k_base"`);
+    /// `None` = estimate. See [`tokenizer_for_model`].
+    pub tokenizer: Option<&'static str>,
+    /// How to ask for provider-side web search (see [`crate::web_search`]).
+    pub web_search: WebSearchStyle,
+}
+
+/// How a provider expects the API key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthStyle {
+    /// `Authorization: Bearer <key>`.
+    Bearer,
+    /// The raw key in a named header (e.g. `x-api-key`).
+    Header(&'static str),
+    /// No authentication.
+    None,
+}
+
+impl AuthStyle {
+    /// Parse a config value: `"bearer"`, `"none"`, or a header name.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "" | "bearer" | "authorization" => AuthStyle::Bearer,
+            "none" => AuthStyle::None,
+            header => AuthStyle::Header(leak(header.to_string())),
+        }
+    }
 }
 
 /// A per-model parameter override.
@@ -88,7 +137,12 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: Some("openrouter"),
         default_api_base: Some("https://openrouter.ai/api/v1"),
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks::OPENAI,
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::Plugin,
     },
     // 2. AiHubMix — gateway, strips model prefix then re-prefixes with "openai"
     ProviderSpec {
@@ -104,7 +158,12 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: Some("aihubmix"),
         default_api_base: Some("https://aihubmix.com/v1"),
         strip_model_prefix: true,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks::OPENAI,
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 3. Anthropic
     ProviderSpec {
@@ -120,7 +179,16 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::Anthropic,
+            required_choice: true,
+            parallel_tool_calls: true,
+        },
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::AnthropicTool,
     },
     // 4. OpenAI
     ProviderSpec {
@@ -136,7 +204,12 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks::OPENAI,
         model_overrides: &[],
+        tokenizer: Some("o200k_base"),
+        web_search: WebSearchStyle::Options,
     },
     // 5. DeepSeek
     ProviderSpec {
@@ -152,7 +225,16 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::OpenAi,
+            required_choice: true,
+            parallel_tool_calls: false,
+        },
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 6. Gemini
     ProviderSpec {
@@ -168,7 +250,16 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::Gemini,
+            required_choice: true,
+            parallel_tool_calls: false,
+        },
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::GoogleSearch,
     },
     // 7. ZhiPu (GLM)
     ProviderSpec {
@@ -184,7 +275,16 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::OpenAi,
+            required_choice: false,
+            parallel_tool_calls: false,
+        },
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 8. DashScope (Qwen)
     ProviderSpec {
@@ -200,7 +300,16 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::OpenAi,
+            required_choice: false,
+            parallel_tool_calls: true,
+        },
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 9. Moonshot (Kimi) — Kimi K2.5 forces temperature=1.0
     ProviderSpec {
@@ -216,11 +325,20 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: Some("https://api.moonshot.ai/v1"),
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::OpenAi,
+            required_choice: false,
+            parallel_tool_calls: false,
+        },
         model_overrides: &[ModelOverride {
             pattern: "kimi-k2.5",
             field: OverrideField::Temperature,
             value: 1.0,
         }],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 10. MiniMax
     ProviderSpec {
@@ -236,7 +354,16 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: Some("https://api.minimax.io/v1"),
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks {
+            schema: SchemaDialect::OpenAi,
+            required_choice: false,
+            parallel_tool_calls: false,
+        },
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 11. vLLM (self-hosted)
     ProviderSpec {
@@ -252,7 +379,12 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks::OPENAI,
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
     // 12. Groq
     ProviderSpec {
@@ -268,33 +400,245 @@ pub static PROVIDERS: &[ProviderSpec] = &[
         detect_by_base_keyword: None,
         default_api_base: None,
         strip_model_prefix: false,
+        auth: AuthStyle::Bearer,
+        model_prefixes: &[],
+        tools: ToolQuirks::OPENAI,
         model_overrides: &[],
+        tokenizer: None,
+        web_search: WebSearchStyle::None,
     },
 ];
 
+// ─────────────────────────────────────────────
+// Custom providers (from config)
+// ─────────────────────────────────────────────
+
+/// Custom provider specs registered at startup.
+///
+/// Specs are leaked so they can be handed out as `&'static` like the
+/// built-in ones. Each spec is leaked once: see [`LEAKED_SPECS`].
+static CUSTOM_PROVIDERS: RwLock<Vec<&'static ProviderSpec>> = RwLock::new(Vec::new());
+
+/// Every spec leaked so far, with the config fields it was built from.
+/// Registering an unchanged provider again reuses its spec.
+static LEAKED_SPECS: Mutex<Vec<(SpecSource, &'static ProviderSpec)>> = Mutex::new(Vec::new());
+
+/// The fields of a [`CustomProviderConfig`] that go into its spec.
+#[derive(PartialEq)]
+struct SpecSource {
+    name: String,
+    display_name: String,
+    api_base: String,
+    auth_header: String,
+    model_prefixes: Vec<String>,
+    tool_schema: String,
+    tokenizer: String,
+    web_search: String,
+}
+
+impl SpecSource {
+    fn new(config: &CustomProviderConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            display_name: config.display_name.clone(),
+            api_base: config.api_base.clone(),
+            auth_header: config.auth_header.clone(),
+            model_prefixes: config.model_prefixes.clone(),
+            tool_schema: config.tool_schema.clone(),
+            tokenizer: config.tokenizer.clone(),
+            web_search: config.web_search.clone(),
+        }
+    }
+}
+
+/// The leaked spec for `config`, leaking a new one only if no spec was
+/// built from the same fields before.
+fn leaked_spec(config: &CustomProviderConfig) -> &'static ProviderSpec {
+    let source = SpecSource::new(config);
+    let mut leaked = LEAKED_SPECS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, spec)) = leaked.iter().find(|(s, _)| *s == source) {
+        return spec;
+    }
+    let spec: &'static ProviderSpec = Box::leak(Box::new(custom_spec(config)));
+    leaked.push((source, spec));
+    spec
+}
+
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+/// Build a provider spec from a custom provider config.
+pub fn custom_spec(config: &CustomProviderConfig) -> ProviderSpec {
+    let display_name = if config.display_name.is_empty() {
+        config.name.clone()
+    } else {
+        config.display_name.clone()
+    };
+    let prefixes: Vec<&'static str> = config
+        .model_prefixes
+        .iter()
+        .filter(|p| !p.is_empty())
+        .map(|p| leak(p.clone()))
+        .collect();
+
+    ProviderSpec {
+        name: leak(config.name.clone()),
+        keywords: &[],
+        env_key: "",
+        display_name: leak(display_name),
+        prefix: None,
+        skip_prefixes: &[],
+        is_gateway: false,
+        is_local: false,
+        detect_by_key_prefix: None,
+        detect_by_base_keyword: None,
+        default_api_base: Some(leak(config.api_base.clone())),
+        strip_model_prefix: false,
+        model_overrides: &[],
+        auth: AuthStyle::parse(&config.auth_header),
+        model_prefixes: Box::leak(prefixes.into_boxed_slice()),
+        tools: ToolQuirks {
+            schema: SchemaDialect::parse(&config.tool_schema).unwrap_or_else(|| {
+                warn!(name = %config.name, schema = %config.tool_schema, "unknown toolSchema, using openai");
+                SchemaDialect::OpenAi
+            }),
+            ..ToolQuirks::OPENAI
+        },
+        tokenizer: (!config.tokenizer.is_empty()).then(|| leak(config.tokenizer.clone())),
+        web_search: WebSearchStyle::parse(&config.web_search).unwrap_or_else(|| {
+            warn!(name = %config.name, web_search = %config.web_search, "unknown webSearch, disabling it");
+            WebSearchStyle::None
+        }),
+    }
+}
+
+/// Replace the registry's custom providers with those from config.
+///
+/// Entries whose name clashes with a built-in provider or that have no
+/// `apiBase` are skipped; of entries sharing a name, the last one wins.
+/// Providers no longer in `configs` are dropped. Safe to call on every
+/// config load: unchanged providers reuse their spec.
+/// Returns the number of providers registered.
+pub fn register_custom_providers(configs: &[CustomProviderConfig]) -> usize {
+    let mut custom: Vec<&'static ProviderSpec> = Vec::new();
+
+    for config in configs {
+        if config.name.is_empty() || config.api_base.is_empty() {
+            warn!(name = %config.name, "custom provider needs a name and apiBase, skipping");
+            continue;
+        }
+        if PROVIDERS.iter().any(|s| s.name == config.name) {
+            warn!(name = %config.name, "custom provider name clashes with a built-in provider, skipping");
+            continue;
+        }
+
+        let spec = leaked_spec(config);
+        custom.retain(|s| s.name != spec.name);
+        custom.push(spec);
+
+        info!(
+            name = spec.name,
+            api_base = %config.api_base,
+            prefixes = ?spec.model_prefixes,
+            "registered custom provider"
+        );
+    }
+
+    let registered = custom.len();
+    *CUSTOM_PROVIDERS.write().unwrap() = custom;
+    registered
+}
+
+/// All provider specs: custom providers first, then the built-in list.
+pub fn all_providers() -> Vec<&'static ProviderSpec> {
+    let custom = CUSTOM_PROVIDERS.read().unwrap();
+    custom.iter().copied().chain(PROVIDERS.iter()).collect()
+}
+
 // ─────────────────────────────────────────────
 // Matching functions
 // ─────────────────────────────────────────────
 
 /// Find a provider spec by matching keywords against a model name.
 ///
-/// Skips gateways and local providers — those are fallback only.
+/// Custom providers match first, by model prefix. Built-in gateways and
+/// local providers are skipped — those are fallback only.
 /// Returns the first match in priority order.
 pub fn find_by_model(model: &str) -> Option<&'static ProviderSpec> {
     let model_lower = model.to_lowercase();
-    PROVIDERS.iter().find(|spec| {
-        !spec.is_gateway
-            && !spec.is_local
-            && spec
-                .keywords
-                .iter()
-                .any(|kw| model_lower.contains(kw))
+    all_providers().into_iter().find(|spec| {
+        spec.model_prefixes
+            .iter()
+            .any(|p| model_lower.starts_with(&p.to_lowercase()))
+            || (!spec.is_gateway
+                && !spec.is_local
+                && spec
+                    .keywords
+                    .iter()
+                    .any(|kw| model_lower.contains(kw)))
     })
 }
 
+/// Tokenizer for a model.
+///
+/// `config.models` wins (matched with or without the `provider/` prefix),
+/// then the provider's default tokenizer; unknown models and missing files
+/// fall back to the heuristic estimate.
+pub fn tokenizer_for_model(model: &str, config: &TokenizerConfig) -> Arc<dyn Tokenizer> {
+    let bare = model.split_once('/').map_or(model, |(_, bare)| bare);
+    let name = config
+        .models
+        .get(model)
+        .or_else(|| config.models.get(bare))
+        .map(String::as_str)
+        .or_else(|| find_by_model(model).and_then(|spec| spec.tokenizer))
+        .unwrap_or(tokenizer::HEURISTIC);
+    tokenizer::load(name, &expand_home(&config.dir))
+}
+
+/// Context windows (in tokens) of known model families, matched in order
+/// against the lowercase model name, so specific entries come first.
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5", 16_385),
+    ("gpt-5", 400_000),
+    ("claude", 200_000),
+    ("gemini-1.5-pro", 2_097_152),
+    ("gemini", 1_048_576),
+    ("deepseek", 64_000),
+    ("kimi", 131_072),
+    ("moonshot", 131_072),
+    ("glm-4", 128_000),
+    ("mistral-large", 128_000),
+    ("llama-3.1", 131_072),
+    ("llama-3.2", 131_072),
+    ("llama-3.3", 131_072),
+    ("qwen", 32_768),
+];
+
+/// Context window of a model in tokens.
+///
+/// `overrides` wins (matched with or without the `provider/` prefix), then
+/// the built-in table of model families; `None` for unknown models.
+pub fn context_window(model: &str, overrides: &HashMap<String, usize>) -> Option<usize> {
+    let bare = model.split_once('/').map_or(model, |(_, bare)| bare);
+    if let Some(&window) = overrides.get(model).or_else(|| overrides.get(bare)) {
+        return Some(window);
+    }
+    let model = model.to_lowercase();
+    CONTEXT_WINDOWS
+        .iter()
+        .find(|(family, _)| model.contains(family))
+        .map(|&(_, window)| window)
+}
+
 /// Find a provider spec by exact name.
 pub fn find_by_name(name: &str) -> Option<&'static ProviderSpec> {
-    PROVIDERS.iter().find(|spec| spec.name == name)
+    all_providers().into_iter().find(|spec| spec.name == name)
 }
 
 /// Try to auto-detect a gateway/local provider from key prefix or base URL.
@@ -322,7 +666,7 @@ pub fn find_gateway(
     if let Some(key) = api_key {
         if let Some(spec) = PROVIDERS.iter().find(|s| {
             s.detect_by_key_prefix
-                .map_or(false, |pfx| key.starts_with(pfx))
+                .is_some_and(|pfx| key.starts_with(pfx))
         }) {
             return Some(spec);
         }
@@ -333,7 +677,7 @@ pub fn find_gateway(
         let base_lower = base.to_lowercase();
         if let Some(spec) = PROVIDERS.iter().find(|s| {
             s.detect_by_base_keyword
-                .map_or(false, |kw| base_lower.contains(kw))
+                .is_some_and(|kw| base_lower.contains(kw))
         }) {
             return Some(spec);
         }
@@ -352,6 +696,15 @@ pub fn find_gateway(
 pub fn resolve_model_name(model: &str, spec: &ProviderSpec) -> String {
     let mut resolved = model.to_string();
 
+    // Strip routing prefix (custom providers)
+    if let Some(rest) = spec
+        .model_prefixes
+        .iter()
+        .find_map(|p| strip_prefix_ignore_case(&resolved, p))
+    {
+        resolved = rest.to_string();
+    }
+
     // Strip existing prefix (AiHubMix quirk)
     if spec.strip_model_prefix {
         if let Some(pos) = resolved.rfind('/') {
@@ -373,6 +726,12 @@ pub fn resolve_model_name(model: &str, spec: &ProviderSpec) -> String {
     resolved
 }
 
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
 /// Apply per-model overrides to request parameters.
 ///
 /// Returns overridden values for temperature (and potentially other fields).
@@ -403,7 +762,8 @@ pub use oxibot_core::config::schema::ProviderConfig;
 ///
 /// This replaces nanobot's `Config._match_provider()`.
 ///
-/// 1. Find by keyword match, only if that provider has an API key.
+/// 1. Find by keyword (or custom prefix) match, only if that provider has
+///    an API key — or needs none.
 /// 2. Fallback to the first configured gateway.
 pub fn match_provider<'a>(
     model: &str,
@@ -412,7 +772,7 @@ pub fn match_provider<'a>(
     // 1. Direct keyword match
     if let Some(spec) = find_by_model(model) {
         if let Some(config) = providers.get(spec.name) {
-            if config.is_configured() {
+            if config.is_configured() || spec.auth == AuthStyle::None {
                 return Some((config, spec));
             }
         }
@@ -657,4 +1017,151 @@ mod tests {
     fn test_provider_count() {
         assert_eq!(PROVIDERS.len(), 12);
     }
+
+    // ── Custom providers ──
+    // The custom registry is process-global and each registration replaces
+    // it, so tests that register hold this lock.
+    static CUSTOM_LOCK: Mutex<()> = Mutex::new(());
+
+    fn custom_lock() -> std::sync::MutexGuard<'static, ()> {
+        CUSTOM_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn custom(name: &str, prefix: &str, auth: &str) -> CustomProviderConfig {
+        CustomProviderConfig {
+            name: name.into(),
+            api_base: "http://localhost:1234/v1".into(),
+            auth_header: auth.into(),
+            model_prefixes: vec![prefix.into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_auth_style_parse() {
+        assert_eq!(AuthStyle::parse("bearer"), AuthStyle::Bearer);
+        assert_eq!(AuthStyle::parse(""), AuthStyle::Bearer);
+        assert_eq!(AuthStyle::parse("None"), AuthStyle::None);
+        assert_eq!(AuthStyle::parse("X-Api-Key"), AuthStyle::Header("x-api-key"));
+    }
+
+    #[test]
+    fn test_custom_provider_routing() {
+        let _lock = custom_lock();
+        let n = register_custom_providers(&[custom("test-lmstudio", "lmstudio-test/", "none")]);
+        assert_eq!(n, 1);
+
+        let spec = find_by_model("lmstudio-test/meta-llama/Llama-3.1-8B").unwrap();
+        assert_eq!(spec.name, "test-lmstudio");
+        assert_eq!(spec.default_api_base, Some("http://localhost:1234/v1"));
+        assert_eq!(
+            resolve_model_name("lmstudio-test/meta-llama/Llama-3.1-8B", spec),
+            "meta-llama/Llama-3.1-8B"
+        );
+        assert!(find_by_name("test-lmstudio").is_some());
+
+        // No API key needed when auth is "none"
+        let mut providers = HashMap::new();
+        providers.insert("test-lmstudio".to_string(), ProviderConfig::default());
+        let (_, matched) = match_provider("lmstudio-test/qwen2.5", &providers).unwrap();
+        assert_eq!(matched.name, "test-lmstudio");
+    }
+
+    #[test]
+    fn test_custom_provider_requires_key_with_bearer() {
+        let _lock = custom_lock();
+        register_custom_providers(&[custom("test-litellm", "litellm-test/", "bearer")]);
+
+        let mut providers = HashMap::new();
+        providers.insert("test-litellm".to_string(), ProviderConfig::default());
+        assert!(match_provider("litellm-test/gpt-4o", &providers).is_none());
+    }
+
+    #[test]
+    fn test_custom_provider_rejected() {
+        let _lock = custom_lock();
+        // Built-in name clash
+        assert_eq!(register_custom_providers(&[custom("openai", "x-test/", "bearer")]), 0);
+        // Missing apiBase
+        let mut no_base = custom("test-nobase", "nobase-test/", "bearer");
+        no_base.api_base.clear();
+        assert_eq!(register_custom_providers(&[no_base]), 0);
+        assert!(find_by_name("test-nobase").is_none());
+    }
+
+    #[test]
+    fn test_custom_provider_reregister_replaces() {
+        let _lock = custom_lock();
+        register_custom_providers(&[custom("test-llamacpp", "llamacpp-a/", "none")]);
+        register_custom_providers(&[custom("test-llamacpp", "llamacpp-b/", "none")]);
+
+        let count = all_providers()
+            .iter()
+            .filter(|s| s.name == "test-llamacpp")
+            .count();
+        assert_eq!(count, 1);
+        assert_eq!(find_by_model("llamacpp-b/model").unwrap().name, "test-llamacpp");
+        assert!(find_by_model("llamacpp-a/model").is_none_or(|s| s.name != "test-llamacpp"));
+
+        // Providers removed from the config are dropped
+        register_custom_providers(&[custom("test-ollama", "ollama-test/", "none")]);
+        assert!(find_by_name("test-llamacpp").is_none());
+        assert!(find_by_name("test-ollama").is_some());
+    }
+
+    #[test]
+    fn test_custom_provider_reregister_reuses_spec() {
+        let _lock = custom_lock();
+        let config = custom("test-vllm", "vllm-test/", "x-api-key");
+        register_custom_providers(std::slice::from_ref(&config));
+        let first = find_by_name("test-vllm").unwrap();
+        register_custom_providers(std::slice::from_ref(&config));
+        assert!(std::ptr::eq(first, find_by_name("test-vllm").unwrap()));
+
+        // A changed provider gets a new spec
+        let mut changed = config.clone();
+        changed.api_base = "http://localhost:8000/v1".into();
+        register_custom_providers(&[changed]);
+        let second = find_by_name("test-vllm").unwrap();
+        assert!(!std::ptr::eq(first, second));
+        assert_eq!(second.default_api_base, Some("http://localhost:8000/v1"));
+    }
+
+    #[test]
+    fn test_tokenizer_for_model() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["o200k_base", "mine"] {
+            std::fs::write(dir.path().join(format!("{name}.tiktoken")), "YQ== 0\nYg== 1\n").unwrap();
+        }
+        let mut config = TokenizerConfig {
+            dir: dir.path().display().to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(tokenizer_for_model("gpt-4o", &config).name(), "o200k_base");
+        assert_eq!(tokenizer_for_model("claude-sonnet-4", &config).name(), tokenizer::HEURISTIC);
+
+        config.models.insert("claude-sonnet-4".into(), "mine".into());
+        assert_eq!(tokenizer_for_model("anthropic/claude-sonnet-4", &config).name(), "mine");
+
+        let _lock = custom_lock();
+        let mut provider = custom("test-tokenized", "tokenized-test/", "none");
+        provider.tokenizer = "mine".into();
+        register_custom_providers(&[provider]);
+        assert_eq!(tokenizer_for_model("tokenized-test/llama", &config).name(), "mine");
+    }
+
+    #[test]
+    fn test_context_window() {
+        let mut overrides = HashMap::new();
+        assert_eq!(context_window("gpt-4o-mini", &overrides), Some(128_000));
+        assert_eq!(context_window("openai/gpt-4", &overrides), Some(8_192));
+        assert_eq!(context_window("anthropic/claude-sonnet-4", &overrides), Some(200_000));
+        assert_eq!(context_window("my-local-model", &overrides), None);
+
+        overrides.insert("my-local-model".into(), 8_192);
+        overrides.insert("gpt-4o".into(), 64_000);
+        assert_eq!(context_window("vllm/my-local-model", &overrides), Some(8_192));
+        assert_eq!(context_window("gpt-4o", &overrides), Some(64_000));
+    }
 }
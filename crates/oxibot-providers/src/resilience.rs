@@ -0,0 +1,377 @@
+//! Retry backoff, circuit breaking and rate limiting for provider HTTP calls.
+//!
+//! A provider that keeps failing (outage, exhausted quota, bad proxy) should
+//! not make every agent turn wait through the full timeout-and-retry cycle.
+//! [`CircuitBreaker`] counts consecutive failed calls; once the threshold is
+//! reached it rejects calls immediately until the cooldown has passed, then
+//! lets a single trial call through.
+//!
+//! [`RateLimiter`] keeps calls under the provider's requests- and
+//! tokens-per-minute limits, so a burst of group messages waits a little
+//! instead of ending in a storm of 429s.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use oxibot_core::config::schema::RequestPolicy;
+
+// ─────────────────────────────────────────────
+// CircuitBreaker
+// ─────────────────────────────────────────────
+
+/// Consecutive-failure circuit breaker.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// Failures that open the breaker (0 = never open).
+    threshold: u32,
+    /// How long the breaker stays open.
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker from a provider's request policy.
+    pub fn new(policy: &RequestPolicy) -> Self {
+        Self {
+            threshold: policy.breaker_threshold,
+            cooldown: Duration::from_secs(policy.breaker_cooldown_secs),
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a call may proceed. Returns the remaining cooldown when open.
+    ///
+    /// After the cooldown the breaker is half-open: calls go through, and a
+    /// single further failure opens it again.
+    pub fn check(&self) -> Result<(), Duration> {
+        let state = self.state.lock().unwrap();
+        match state.open_until {
+            Some(until) => {
+                let now = Instant::now();
+                if now < until {
+                    Err(until - now)
+                } else {
+                    Ok(())
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Record a successful call, closing the breaker.
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.failures = 0;
+        state.open_until = None;
+    }
+
+    /// Record a failed call. Returns `true` if this opened the breaker.
+    pub fn record_failure(&self) -> bool {
+        if self.threshold == 0 {
+            return false;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.failures = state.failures.saturating_add(1);
+        // A failed trial call after the cooldown re-opens straight away.
+        if state.failures >= self.threshold || state.open_until.is_some() {
+            state.open_until = Some(Instant::now() + self.cooldown);
+            return true;
+        }
+        false
+    }
+
+    /// Consecutive failures recorded so far.
+    pub fn failures(&self) -> u32 {
+        self.state.lock().unwrap().failures
+    }
+}
+
+// ─────────────────────────────────────────────
+// RateLimiter
+// ─────────────────────────────────────────────
+
+/// A token bucket holding up to `capacity`, refilled at `per_sec`. The
+/// level drops below zero when real usage exceeded the estimate.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    per_sec: f64,
+    level: f64,
+}
+
+impl Bucket {
+    /// A full bucket for `limit` per minute (`None` when 0).
+    fn per_minute(limit: u32) -> Option<Self> {
+        (limit > 0).then(|| Self {
+            capacity: limit as f64,
+            per_sec: limit as f64 / 60.0,
+            level: limit as f64,
+        })
+    }
+
+    /// Time until `amount` (at most the capacity) is available.
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.capacity) - self.level;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.per_sec)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    updated: Instant,
+    /// Set when the provider asked us to back off.
+    paused_until: Option<Instant>,
+}
+
+impl LimiterState {
+    /// Take one request of `tokens` from the budget if there is room.
+    /// Returns how long to wait otherwise (zero when taken).
+    fn reserve(&mut self, tokens: u32, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.updated = now;
+        for bucket in [&mut self.requests, &mut self.tokens].into_iter().flatten() {
+            bucket.level = (bucket.level + elapsed * bucket.per_sec).min(bucket.capacity);
+        }
+
+        let paused = self.paused_until.map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        let wait = [
+            paused,
+            self.requests.as_ref().map_or(Duration::ZERO, |b| b.wait_for(1.0)),
+            self.tokens.as_ref().map_or(Duration::ZERO, |b| b.wait_for(tokens as f64)),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default();
+        if wait.is_zero() {
+            if let Some(ref mut b) = self.requests {
+                b.level -= 1.0;
+            }
+            if let Some(ref mut b) = self.tokens {
+                b.level -= (tokens as f64).min(b.capacity);
+            }
+        }
+        wait
+    }
+}
+
+/// Client-side requests- and tokens-per-minute limits for one provider.
+///
+/// Calls wait in order until both budgets have room. The token cost of a
+/// call is estimated up front and corrected with [`settle`](Self::settle)
+/// once the response reports its usage.
+#[derive(Debug)]
+pub struct RateLimiter {
+    state: Mutex<LimiterState>,
+    /// Waiting callers, served first come, first served.
+    queue: tokio::sync::Mutex<()>,
+}
+
+impl RateLimiter {
+    /// A limiter for the policy's limits (`None` when it sets none).
+    pub fn new(policy: &RequestPolicy) -> Option<Self> {
+        let requests = Bucket::per_minute(policy.requests_per_minute);
+        let tokens = Bucket::per_minute(policy.tokens_per_minute);
+        if requests.is_none() && tokens.is_none() {
+            return None;
+        }
+        Some(Self {
+            state: Mutex::new(LimiterState {
+                requests,
+                tokens,
+                updated: Instant::now(),
+                paused_until: None,
+            }),
+            queue: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// The limiter of provider `key`, shared by every client of it in the
+    /// process — the agent loop, subagents and model routes all draw from
+    /// the same budget. The first caller's policy sets the limits.
+    pub fn shared(key: &str, policy: &RequestPolicy) -> Option<Arc<Self>> {
+        static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+        let limiter = Self::new(policy)?;
+        let mut limiters = LIMITERS.get_or_init(Mutex::default).lock().unwrap();
+        Some(limiters.entry(key.to_string()).or_insert_with(|| Arc::new(limiter)).clone())
+    }
+
+    /// Wait until a call of about `tokens` tokens fits in the budget, and
+    /// take it. Returns how long the call waited.
+    pub async fn acquire(&self, tokens: u32) -> Duration {
+        let _turn = self.queue.lock().await;
+        let start = Instant::now();
+        loop {
+            let wait = self.state.lock().unwrap().reserve(tokens, Instant::now());
+            if wait.is_zero() {
+                return start.elapsed();
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Correct the token budget with a call's real usage.
+    pub fn settle(&self, estimated: u32, actual: u32) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(ref mut b) = state.tokens {
+            b.level = (b.level + (estimated as f64).min(b.capacity) - actual as f64).min(b.capacity);
+        }
+    }
+
+    /// Hold every call for `delay`, e.g. after a 429 with `Retry-After`.
+    pub fn pause(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut state = self.state.lock().unwrap();
+        state.paused_until = Some(state.paused_until.map_or(until, |current| current.max(until)));
+    }
+}
+
+/// Delay before retry number `attempt` (1-based): the base backoff doubled
+/// for each earlier retry, capped at one minute.
+pub fn backoff_delay(policy: &RequestPolicy, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis(policy.retry_backoff_ms.saturating_mul(factor)).min(Duration::from_secs(60))
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(requests_per_minute: u32, tokens_per_minute: u32) -> RequestPolicy {
+        RequestPolicy {
+            requests_per_minute,
+            tokens_per_minute,
+            ..Default::default()
+        }
+    }
+
+    /// Whole seconds of a computed wait.
+    fn secs(wait: Duration) -> u64 {
+        wait.as_secs_f64().round() as u64
+    }
+
+    #[test]
+    fn test_rate_limiter_buckets() {
+        assert!(RateLimiter::new(&RequestPolicy::default()).is_none());
+
+        // A burst up to the request limit, then one refill every 30s
+        let limiter = RateLimiter::new(&limits(2, 0)).unwrap();
+        let mut state = limiter.state.lock().unwrap();
+        let start = state.updated;
+        assert_eq!(state.reserve(100, start), Duration::ZERO);
+        assert_eq!(state.reserve(100, start), Duration::ZERO);
+        assert_eq!(secs(state.reserve(100, start)), 30);
+        assert_eq!(state.reserve(100, start + Duration::from_secs(30)), Duration::ZERO);
+        drop(state);
+
+        // 600 tokens a minute refill at 10 per second
+        let limiter = RateLimiter::new(&limits(0, 600)).unwrap();
+        let mut state = limiter.state.lock().unwrap();
+        let start = state.updated;
+        assert_eq!(state.reserve(500, start), Duration::ZERO);
+        assert_eq!(secs(state.reserve(400, start)), 30);
+        assert_eq!(state.reserve(400, start + Duration::from_secs(30)), Duration::ZERO);
+        drop(state);
+
+        // Calls larger than the budget wait for a full bucket, not forever
+        let limiter = RateLimiter::new(&limits(0, 1000)).unwrap();
+        let mut state = limiter.state.lock().unwrap();
+        let start = state.updated;
+        assert_eq!(state.reserve(5000, start), Duration::ZERO);
+        assert_eq!(secs(state.reserve(1000, start)), 60);
+    }
+
+    #[test]
+    fn test_rate_limiter_settle() {
+        let limiter = RateLimiter::new(&limits(0, 600)).unwrap();
+        let start = limiter.state.lock().unwrap().updated;
+        assert_eq!(limiter.state.lock().unwrap().reserve(100, start), Duration::ZERO);
+        // The call really used 400 tokens: 200 left, 300 more need 10s
+        limiter.settle(100, 400);
+        assert_eq!(secs(limiter.state.lock().unwrap().reserve(300, start)), 10);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_pause_and_sharing() {
+        let policy = limits(600, 0);
+        let a = RateLimiter::shared("test-provider", &policy).unwrap();
+        let b = RateLimiter::shared("test-provider", &limits(1, 0)).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(RateLimiter::shared("other", &RequestPolicy::default()).is_none());
+
+        assert!(a.acquire(0).await < Duration::from_millis(20));
+        b.pause(Duration::from_millis(50));
+        assert!(a.acquire(0).await >= Duration::from_millis(40));
+    }
+
+    fn policy(threshold: u32, cooldown_secs: u64) -> RequestPolicy {
+        RequestPolicy {
+            breaker_threshold: threshold,
+            breaker_cooldown_secs: cooldown_secs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(&policy(2, 60));
+        assert!(breaker.check().is_ok());
+        assert!(!breaker.record_failure());
+        assert!(breaker.check().is_ok());
+        assert!(breaker.record_failure());
+        let remaining = breaker.check().unwrap_err();
+        assert!(remaining > Duration::from_secs(55));
+
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+        assert_eq!(breaker.failures(), 0);
+    }
+
+    #[test]
+    fn test_breaker_half_open_reopens_on_failure() {
+        let breaker = CircuitBreaker::new(&policy(3, 0));
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+        // Zero cooldown: immediately half-open
+        assert!(breaker.check().is_ok());
+        assert!(breaker.record_failure());
+    }
+
+    #[test]
+    fn test_breaker_disabled() {
+        let breaker = CircuitBreaker::new(&policy(0, 60));
+        for _ in 0..10 {
+            assert!(!breaker.record_failure());
+        }
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let policy = RequestPolicy {
+            retry_backoff_ms: 500,
+            ..Default::default()
+        };
+        assert_eq!(backoff_delay(&policy, 1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(&policy, 2), Duration::from_millis(1000));
+        assert_eq!(backoff_delay(&policy, 3), Duration::from_millis(2000));
+        assert_eq!(backoff_delay(&policy, 30), Duration::from_secs(60));
+    }
+}
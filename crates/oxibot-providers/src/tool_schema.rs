@@ -0,0 +1,341 @@
+//! Tool schema normalization — adapt tool definitions to each provider.
+//!
+//! Tools describe their parameters in OpenAI-style JSON Schema, but not every
+//! provider accepts all of it. Gemini takes an OpenAPI 3.0 subset (no type
+//! arrays, `nullable` instead of `"null"`, string-only enums, no
+//! `additionalProperties`), and Anthropic wants a plain object schema with no
+//! combinators at the top level. [`normalize_tools`] rewrites the definitions
+//! for a provider's [`SchemaDialect`] right before the request is sent, and
+//! [`tool_choice`] / [`parallel_tool_calls`] translate the request flags.
+
+use serde_json::{Map, Value};
+
+use oxibot_core::types::ToolDefinition;
+
+use crate::traits::ToolChoice;
+
+/// Schema keywords Gemini understands; everything else is dropped.
+const GEMINI_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "properties",
+    "required",
+    "items",
+    "minItems",
+    "maxItems",
+    "minimum",
+    "maximum",
+    "anyOf",
+];
+
+/// Keywords converted explicitly by [`gemini_schema`] rather than copied.
+const GEMINI_CONVERTED: &[&str] = &["type", "anyOf", "oneOf", "const"];
+
+/// Combinators Anthropic rejects at the top level of `input_schema`.
+const TOP_LEVEL_COMBINATORS: &[&str] = &["oneOf", "anyOf", "allOf", "not"];
+
+// ─────────────────────────────────────────────
+// Provider capabilities
+// ─────────────────────────────────────────────
+
+/// JSON Schema dialect a provider accepts for tool parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaDialect {
+    /// Full JSON Schema, sent unchanged.
+    OpenAi,
+    /// Gemini's OpenAPI 3.0 subset.
+    Gemini,
+    /// Anthropic's `input_schema`: a plain object at the top level.
+    Anthropic,
+}
+
+impl SchemaDialect {
+    /// Parse a config value: `"openai"` (default), `"gemini"` or `"anthropic"`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "openai" => Some(SchemaDialect::OpenAi),
+            "gemini" => Some(SchemaDialect::Gemini),
+            "anthropic" => Some(SchemaDialect::Anthropic),
+            _ => None,
+        }
+    }
+}
+
+/// How a provider's function calling differs from OpenAI's.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToolQuirks {
+    /// Schema dialect for tool parameters.
+    pub schema: SchemaDialect,
+    /// Whether `tool_choice: "required"` is accepted (otherwise `"auto"` is sent).
+    pub required_choice: bool,
+    /// Whether the `parallel_tool_calls` flag is accepted (otherwise omitted).
+    pub parallel_tool_calls: bool,
+}
+
+impl ToolQuirks {
+    /// Full OpenAI function-calling support.
+    pub const OPENAI: ToolQuirks = ToolQuirks {
+        schema: SchemaDialect::OpenAi,
+        required_choice: true,
+        parallel_tool_calls: true,
+    };
+}
+
+// ─────────────────────────────────────────────
+// Request flags
+// ─────────────────────────────────────────────
+
+/// The `tool_choice` value to send, or `None` to send no tools at all.
+///
+/// `ToolChoice::None` drops the tools instead of sending `"none"`, which
+/// works with every provider.
+pub fn tool_choice(choice: ToolChoice, quirks: &ToolQuirks) -> Option<&'static str> {
+    match choice {
+        ToolChoice::Auto => Some("auto"),
+        ToolChoice::Required if quirks.required_choice => Some("required"),
+        ToolChoice::Required => Some("auto"),
+        ToolChoice::None => None,
+    }
+}
+
+/// The `parallel_tool_calls` flag to send, if the provider accepts it.
+pub fn parallel_tool_calls(requested: Option<bool>, quirks: &ToolQuirks) -> Option<bool> {
+    requested.filter(|_| quirks.parallel_tool_calls)
+}
+
+// ─────────────────────────────────────────────
+// Schema normalization
+// ─────────────────────────────────────────────
+
+/// Rewrite tool parameter schemas for `dialect`.
+pub fn normalize_tools(tools: &[ToolDefinition], dialect: SchemaDialect) -> Vec<ToolDefinition> {
+    tools
+        .iter()
+        .map(|tool| {
+            let mut tool = tool.clone();
+            tool.function.parameters = normalize_parameters(&tool.function.parameters, dialect);
+            tool
+        })
+        .collect()
+}
+
+/// Rewrite one tool's parameter schema for `dialect`.
+pub fn normalize_parameters(schema: &Value, dialect: SchemaDialect) -> Value {
+    match dialect {
+        SchemaDialect::OpenAi => schema.clone(),
+        SchemaDialect::Gemini => gemini_schema(schema),
+        SchemaDialect::Anthropic => anthropic_schema(schema),
+    }
+}
+
+/// Convert a JSON Schema node (recursively) to Gemini's OpenAPI subset.
+fn gemini_schema(schema: &Value) -> Value {
+    let Some(node) = schema.as_object() else {
+        return schema.clone();
+    };
+    let mut out = Map::new();
+
+    // `"type": ["string", "null"]` → `"type": "string", "nullable": true`
+    match node.get("type") {
+        Some(Value::Array(types)) => {
+            let mut non_null = types.iter().filter(|t| t.as_str() != Some("null"));
+            if let Some(first) = non_null.next() {
+                out.insert("type".into(), first.clone());
+            }
+            if types.len() > types.iter().filter(|t| t.as_str() != Some("null")).count() {
+                out.insert("nullable".into(), Value::Bool(true));
+            }
+        }
+        Some(t) => {
+            out.insert("type".into(), t.clone());
+        }
+        None => {}
+    }
+
+    // `oneOf` behaves like `anyOf` for argument generation; a `null`
+    // branch becomes `nullable`, and a single remaining branch is inlined.
+    if let Some(Value::Array(branches)) = node.get("anyOf").or_else(|| node.get("oneOf")) {
+        let kept: Vec<Value> = branches
+            .iter()
+            .filter(|b| b.get("type").and_then(Value::as_str) != Some("null"))
+            .map(gemini_schema)
+            .collect();
+        if kept.len() < branches.len() {
+            out.insert("nullable".into(), Value::Bool(true));
+        }
+        match kept.len() {
+            0 => {}
+            1 => {
+                if let Value::Object(inner) = &kept[0] {
+                    for (key, value) in inner {
+                        out.entry(key.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+            }
+            _ => {
+                out.insert("anyOf".into(), Value::Array(kept));
+            }
+        }
+    }
+
+    // `const` → single-value enum
+    if let Some(value) = node.get("const") {
+        out.insert("enum".into(), Value::Array(vec![value.clone()]));
+    }
+
+    for (key, value) in node {
+        if out.contains_key(key)
+            || GEMINI_CONVERTED.contains(&key.as_str())
+            || !GEMINI_KEYWORDS.contains(&key.as_str())
+        {
+            continue;
+        }
+        let value = match key.as_str() {
+            "properties" => Value::Object(
+                value
+                    .as_object()
+                    .map(|props| props.iter().map(|(k, v)| (k.clone(), gemini_schema(v))).collect())
+                    .unwrap_or_default(),
+            ),
+            "items" => gemini_schema(value),
+            _ => value.clone(),
+        };
+        out.insert(key.clone(), value);
+    }
+
+    // Enums must be strings; other values are listed in the description.
+    if let Some(Value::Array(values)) = out.get("enum") {
+        if !values.iter().all(Value::is_string) {
+            let listed: Vec<String> = values.iter().map(Value::to_string).collect();
+            let note = format!("One of: {}.", listed.join(", "));
+            let description = match out.get("description").and_then(Value::as_str) {
+                Some(d) if !d.is_empty() => format!("{d} ({note})"),
+                _ => note,
+            };
+            out.remove("enum");
+            out.insert("description".into(), Value::String(description));
+        }
+    }
+
+    // `required` may only name declared properties.
+    if let Some(Value::Array(required)) = out.get("required") {
+        let props = out.get("properties").and_then(Value::as_object);
+        let kept: Vec<Value> = required
+            .iter()
+            .filter(|r| r.as_str().is_some_and(|name| props.is_some_and(|p| p.contains_key(name))))
+            .cloned()
+            .collect();
+        if kept.is_empty() {
+            out.remove("required");
+        } else {
+            out.insert("required".into(), Value::Array(kept));
+        }
+    }
+
+    Value::Object(out)
+}
+
+/// Make the top level a plain object schema for Anthropic.
+fn anthropic_schema(schema: &Value) -> Value {
+    let mut node = schema.as_object().cloned().unwrap_or_default();
+    for key in TOP_LEVEL_COMBINATORS {
+        node.remove(*key);
+    }
+    node.insert("type".into(), Value::String("object".into()));
+    node.entry("properties").or_insert_with(|| Value::Object(Map::new()));
+    Value::Object(node)
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_openai_unchanged() {
+        let schema = json!({"type": "object", "properties": {"a": {"type": ["string", "null"]}}});
+        assert_eq!(normalize_parameters(&schema, SchemaDialect::OpenAi), schema);
+    }
+
+    #[test]
+    fn test_gemini_schema() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "name": {"type": ["string", "null"], "description": "Name", "default": "x"},
+                "mode": {"const": "fast"},
+                "level": {"type": "integer", "enum": [1, 2, 3], "description": "Level"},
+                "when": {"anyOf": [{"type": "string", "format": "date-time"}, {"type": "null"}]},
+                "tags": {"type": "array", "items": {"type": "string", "pattern": "^[a-z]+$"}},
+                "id": {"oneOf": [{"type": "string"}, {"type": "integer"}]}
+            },
+            "required": ["name", "missing"]
+        });
+
+        let out = normalize_parameters(&schema, SchemaDialect::Gemini);
+        assert_eq!(
+            out,
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "nullable": true, "description": "Name"},
+                    "mode": {"enum": ["fast"]},
+                    "level": {"type": "integer", "description": "Level (One of: 1, 2, 3.)"},
+                    "when": {"type": "string", "format": "date-time", "nullable": true},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "id": {"anyOf": [{"type": "string"}, {"type": "integer"}]}
+                },
+                "required": ["name"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_anthropic_schema() {
+        let schema = json!({"anyOf": [{"required": ["a"]}, {"required": ["b"]}], "properties": {"a": {"type": ["string", "null"]}}});
+        let out = normalize_parameters(&schema, SchemaDialect::Anthropic);
+        assert_eq!(out, json!({"type": "object", "properties": {"a": {"type": ["string", "null"]}}}));
+        assert_eq!(normalize_parameters(&json!({}), SchemaDialect::Anthropic), json!({"type": "object", "properties": {}}));
+    }
+
+    #[test]
+    fn test_normalize_tools_keeps_names() {
+        let tools = vec![ToolDefinition::new("t", "d", json!({"type": "object", "additionalProperties": true}))];
+        let out = normalize_tools(&tools, SchemaDialect::Gemini);
+        assert_eq!(out[0].function.name, "t");
+        assert_eq!(out[0].function.parameters, json!({"type": "object"}));
+    }
+
+    #[test]
+    fn test_request_flags() {
+        let limited = ToolQuirks {
+            schema: SchemaDialect::OpenAi,
+            required_choice: false,
+            parallel_tool_calls: false,
+        };
+        assert_eq!(tool_choice(ToolChoice::Required, &ToolQuirks::OPENAI), Some("required"));
+        assert_eq!(tool_choice(ToolChoice::Required, &limited), Some("auto"));
+        assert_eq!(tool_choice(ToolChoice::None, &ToolQuirks::OPENAI), None);
+        assert_eq!(parallel_tool_calls(Some(false), &ToolQuirks::OPENAI), Some(false));
+        assert_eq!(parallel_tool_calls(Some(false), &limited), None);
+        assert_eq!(parallel_tool_calls(None, &ToolQuirks::OPENAI), None);
+    }
+
+    #[test]
+    fn test_dialect_parse() {
+        assert_eq!(SchemaDialect::parse(""), Some(SchemaDialect::OpenAi));
+        assert_eq!(SchemaDialect::parse("Gemini"), Some(SchemaDialect::Gemini));
+        assert_eq!(SchemaDialect::parse("anthropic"), Some(SchemaDialect::Anthropic));
+        assert_eq!(SchemaDialect::parse("xml"), None);
+    }
+}
@@ -15,6 +15,15 @@ pub struct LlmRequestConfig {
     pub max_tokens: u32,
     /// Sampling temperature (0.0 – 2.0).
     pub temperature: f64,
+    /// Whether the model may, must or must not call tools.
+    pub tool_choice: ToolChoice,
+    /// Allow (or forbid) several tool calls in one response; `None` leaves
+    /// the provider default. Ignored by providers without the flag.
+    pub parallel_tool_calls: Option<bool>,
+    /// Let the provider search the web while answering; cited pages are
+    /// returned in `LlmResponse::citations`. Ignored by providers without
+    /// [`LlmProvider::supports_web_search`].
+    pub web_search: bool,
 }
 
 impl Default for LlmRequestConfig {
@@ -22,10 +31,28 @@ impl Default for LlmRequestConfig {
         Self {
             max_tokens: 4096,
             temperature: 0.7,
+            tool_choice: ToolChoice::Auto,
+            parallel_tool_calls: None,
+            web_search: false,
         }
     }
 }
 
+/// Whether the model should call tools.
+///
+/// Translated per provider; `Required` falls back to `Auto` where the
+/// provider doesn't support it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToolChoice {
+    /// The model decides.
+    #[default]
+    Auto,
+    /// The model must call at least one tool.
+    Required,
+    /// The model must answer without tools.
+    None,
+}
+
 /// Trait that all LLM providers must implement.
 ///
 /// Replaces nanobot's `LLMProvider` ABC.
@@ -56,4 +83,10 @@ pub trait LlmProvider: Send + Sync {
 
     /// Display name for logging.
     fn display_name(&self) -> &str;
+
+    /// Whether the provider can search the web itself (see
+    /// [`LlmRequestConfig::web_search`]).
+    fn supports_web_search(&self) -> bool {
+        false
+    }
 }
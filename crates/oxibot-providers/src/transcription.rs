@@ -4,13 +4,27 @@
 //!
 //! Currently supports Groq's Whisper API (fast, free tier available).
 //! Any OpenAI-compatible `/v1/audio/transcriptions` endpoint will work.
+//!
+//! When an [`AudioPreprocessor`] is attached, audio is transcoded and long
+//! recordings are split into overlapping chunks whose transcripts are
+//! stitched back together (see [`crate::audio`]).
+//!
+//! [`CachedTranscriber`] wraps any provider and reuses earlier transcripts of
+//! identical audio, so re-forwarded voice notes are only transcribed once.
+//!
+//! [`TranscriptionOptions`] carry a language hint (see [`profile_language`]),
+//! the sampling temperature and whether to return word-level timestamps.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use async_trait::async_trait;
+use oxibot_core::types::{Transcript, TranscriptWord};
+use sha2::{Digest, Sha256};
 use tracing::{debug, error, warn};
 
+use crate::audio::{stitch_transcripts, AudioPreprocessor};
+
 // ─────────────────────────────────────────────
 // Trait
 // ─────────────────────────────────────────────
@@ -23,10 +37,57 @@ pub trait TranscriptionProvider: Send + Sync {
     /// Returns the transcribed text, or empty string on failure.
     async fn transcribe(&self, file_path: &Path) -> anyhow::Result<String>;
 
+    /// Transcribe an audio file, including duration and confidence when available.
+    ///
+    /// The default implementation wraps [`transcribe`](Self::transcribe).
+    async fn transcribe_detailed(&self, file_path: &Path) -> anyhow::Result<Transcript> {
+        Ok(Transcript::from_text(self.transcribe(file_path).await?))
+    }
+
+    /// Transcribe an audio file with a language hint, temperature and
+    /// word timestamps.
+    ///
+    /// The default implementation ignores the options and calls
+    /// [`transcribe_detailed`](Self::transcribe_detailed).
+    async fn transcribe_with(
+        &self,
+        file_path: &Path,
+        _options: &TranscriptionOptions,
+    ) -> anyhow::Result<Transcript> {
+        self.transcribe_detailed(file_path).await
+    }
+
     /// Display name for logging.
     fn display_name(&self) -> &str;
 }
 
+/// Per-request transcription settings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TranscriptionOptions {
+    /// ISO-639-1 code of the spoken language (`None` = detect).
+    pub language: Option<String>,
+    /// Sampling temperature (`None` = provider default).
+    pub temperature: Option<f64>,
+    /// Return word-level timestamps in [`Transcript::words`].
+    pub word_timestamps: bool,
+}
+
+impl TranscriptionOptions {
+    /// Suffix distinguishing cached transcripts made with these options
+    /// (empty for the defaults).
+    fn cache_key(&self) -> String {
+        if *self == Self::default() {
+            return String::new();
+        }
+        format!(
+            "{}:{}:{}",
+            self.language.as_deref().unwrap_or_default(),
+            self.temperature.map(|t| t.to_string()).unwrap_or_default(),
+            self.word_timestamps
+        )
+    }
+}
+
 // ─────────────────────────────────────────────
 // Groq Whisper
 // ─────────────────────────────────────────────
@@ -40,6 +101,17 @@ pub struct GroqTranscriber {
     api_url: String,
     model: String,
     client: reqwest::Client,
+    preprocessor: Option<AudioPreprocessor>,
+}
+
+/// Transcription of a single uploaded file (one chunk).
+#[derive(Debug, Default)]
+struct ChunkTranscript {
+    text: String,
+    duration_secs: Option<f64>,
+    confidence: Option<f64>,
+    language: Option<String>,
+    words: Vec<TranscriptWord>,
 }
 
 impl GroqTranscriber {
@@ -58,6 +130,7 @@ impl GroqTranscriber {
             api_url: "https://api.groq.com/openai/v1/audio/transcriptions".into(),
             model: "whisper-large-v3".into(),
             client: reqwest::Client::new(),
+            preprocessor: None,
         }
     }
 
@@ -68,25 +141,31 @@ impl GroqTranscriber {
         t
     }
 
+    /// Set the Whisper model name.
+    pub fn with_model(mut self, model: &str) -> Self {
+        if !model.is_empty() {
+            self.model = model.to_string();
+        }
+        self
+    }
+
+    /// Transcode and chunk audio with `ffmpeg` before uploading.
+    pub fn with_preprocessor(mut self, preprocessor: AudioPreprocessor) -> Self {
+        self.preprocessor = Some(preprocessor);
+        self
+    }
+
     /// Check if the transcriber is configured (has an API key).
     pub fn is_configured(&self) -> bool {
         !self.api_key.is_empty()
     }
-}
-
-#[async_trait]
-impl TranscriptionProvider for GroqTranscriber {
-    async fn transcribe(&self, file_path: &Path) -> anyhow::Result<String> {
-        if !self.is_configured() {
-            warn!("groq transcription: no API key configured, skipping");
-            return Ok(String::new());
-        }
-
-        if !file_path.exists() {
-            warn!(path = %file_path.display(), "transcription: file not found");
-            return Ok(String::new());
-        }
 
+    /// Upload a single file and parse the `verbose_json` response.
+    async fn transcribe_file(
+        &self,
+        file_path: &Path,
+        options: &TranscriptionOptions,
+    ) -> anyhow::Result<ChunkTranscript> {
         let file_name = file_path
             .file_name()
             .unwrap_or_default()
@@ -105,16 +184,29 @@ impl TranscriptionProvider for GroqTranscriber {
             .file_name(file_name)
             .mime_str("application/octet-stream")?;
 
-        let form = reqwest::multipart::Form::new()
+        let mut form = reqwest::multipart::Form::new()
             .part("file", file_part)
-            .text("model", self.model.clone());
+            .text("model", self.model.clone())
+            .text("response_format", "verbose_json");
+        if let Some(ref language) = options.language {
+            form = form.text("language", language.clone());
+        }
+        if let Some(temperature) = options.temperature {
+            form = form.text("temperature", temperature.to_string());
+        }
+        if options.word_timestamps {
+            // Segments are still needed for the confidence estimate.
+            form = form
+                .text("timestamp_granularities[]", "word")
+                .text("timestamp_granularities[]", "segment");
+        }
 
         let response = self
             .client
             .post(&self.api_url)
             .bearer_auth(&self.api_key)
             .multipart(form)
-            .timeout(Duration::from_secs(60))
+            .timeout(Duration::from_secs(120))
             .send()
             .await?;
 
@@ -134,14 +226,137 @@ impl TranscriptionProvider for GroqTranscriber {
         }
 
         let json: serde_json::Value = response.json().await?;
-        let text = json["text"].as_str().unwrap_or_default().to_string();
+        Ok(parse_verbose_json(&json))
+    }
+
+    /// Transcode, chunk, transcribe each chunk and stitch the results.
+    async fn transcribe_chunked(
+        &self,
+        preprocessor: &AudioPreprocessor,
+        file_path: &Path,
+        options: &TranscriptionOptions,
+    ) -> anyhow::Result<Transcript> {
+        let prepared = preprocessor.prepare(file_path).await?;
+
+        let mut parts = Vec::with_capacity(prepared.chunks.len());
+        let mut confidences = Vec::new();
+        let mut words: Vec<TranscriptWord> = Vec::new();
+        let mut language = None;
+        let mut api_duration = 0.0;
+        let mut failure = None;
+
+        for chunk in &prepared.chunks {
+            match self.transcribe_file(&chunk.path, options).await {
+                Ok(part) => {
+                    api_duration += part.duration_secs.unwrap_or(chunk.length_secs);
+                    confidences.extend(part.confidence);
+                    language = language.or(part.language);
+                    // Shift to the original timeline; words in the overlap
+                    // were already taken from the previous chunk.
+                    let covered = words.last().map_or(f64::MIN, |w| w.end);
+                    words.extend(
+                        part.words
+                            .into_iter()
+                            .map(|w| TranscriptWord {
+                                start: w.start + chunk.start_secs,
+                                end: w.end + chunk.start_secs,
+                                word: w.word,
+                            })
+                            .filter(|w| w.start >= covered),
+                    );
+                    parts.push(part.text);
+                }
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+
+        let chunks = prepared.chunks.len();
+        let duration_secs = prepared
+            .duration_secs
+            .or((api_duration > 0.0).then_some(api_duration));
+        prepared.cleanup().await;
+
+        if let Some(e) = failure {
+            return Err(e);
+        }
+
+        let confidence = (!confidences.is_empty())
+            .then(|| confidences.iter().sum::<f64>() / confidences.len() as f64);
+
+        Ok(Transcript {
+            text: stitch_transcripts(&parts),
+            duration_secs,
+            confidence,
+            chunks,
+            language,
+            words,
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for GroqTranscriber {
+    async fn transcribe(&self, file_path: &Path) -> anyhow::Result<String> {
+        Ok(self.transcribe_detailed(file_path).await?.text)
+    }
+
+    async fn transcribe_detailed(&self, file_path: &Path) -> anyhow::Result<Transcript> {
+        self.transcribe_with(file_path, &TranscriptionOptions::default()).await
+    }
+
+    async fn transcribe_with(
+        &self,
+        file_path: &Path,
+        options: &TranscriptionOptions,
+    ) -> anyhow::Result<Transcript> {
+        if !self.is_configured() {
+            warn!("groq transcription: no API key configured, skipping");
+            return Ok(Transcript::default());
+        }
+
+        if !file_path.exists() {
+            warn!(path = %file_path.display(), "transcription: file not found");
+            return Ok(Transcript::default());
+        }
+
+        if let Some(ref pre) = self.preprocessor {
+            if pre.is_available().await {
+                match self.transcribe_chunked(pre, file_path, options).await {
+                    Ok(t) => {
+                        debug!(
+                            chars = t.text.len(),
+                            chunks = t.chunks,
+                            "transcription complete"
+                        );
+                        return Ok(t);
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "chunked transcription failed, uploading original file");
+                    }
+                }
+            }
+        }
+
+        let part = self.transcribe_file(file_path, options).await?;
 
         debug!(
-            chars = text.len(),
+            chars = part.text.len(),
             "transcription complete"
         );
 
-        Ok(text)
+        Ok(Transcript {
+            text: part.text,
+            duration_secs: part.duration_secs,
+            confidence: part.confidence,
+            chunks: 1,
+            language: part.language,
+            words: part.words,
+            ..Default::default()
+        })
     }
 
     fn display_name(&self) -> &str {
@@ -149,10 +364,197 @@ impl TranscriptionProvider for GroqTranscriber {
     }
 }
 
+// ─────────────────────────────────────────────
+// Cache
+// ─────────────────────────────────────────────
+
+/// Transcription provider that caches results by audio content hash.
+///
+/// Transcripts are stored as `<sha256>.transcript.json` next to the media
+/// file. Channels download every voice note under a fresh name, so keying on
+/// content (not path) is what lets a forwarded or re-fetched note hit the
+/// cache. Cached transcripts come back with [`Transcript::cached`] set.
+/// Transcripts made with non-default [`TranscriptionOptions`] are cached
+/// separately.
+pub struct CachedTranscriber<P> {
+    inner: P,
+}
+
+impl<P: TranscriptionProvider> CachedTranscriber<P> {
+    /// Wrap a transcription provider.
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+
+    /// Cache file for the audio at `file_path`, or `None` if it can't be read.
+    async fn cache_path(file_path: &Path, options: &TranscriptionOptions) -> Option<PathBuf> {
+        let bytes = tokio::fs::read(file_path).await.ok()?;
+        let mut hash = content_hash(&bytes);
+        let key = options.cache_key();
+        if !key.is_empty() {
+            hash = content_hash(format!("{hash}:{key}").as_bytes());
+        }
+        let dir = file_path.parent().unwrap_or(Path::new("."));
+        Some(dir.join(format!("{hash}.transcript.json")))
+    }
+}
+
+#[async_trait]
+impl<P: TranscriptionProvider> TranscriptionProvider for CachedTranscriber<P> {
+    async fn transcribe(&self, file_path: &Path) -> anyhow::Result<String> {
+        Ok(self.transcribe_detailed(file_path).await?.text)
+    }
+
+    async fn transcribe_detailed(&self, file_path: &Path) -> anyhow::Result<Transcript> {
+        self.transcribe_with(file_path, &TranscriptionOptions::default()).await
+    }
+
+    async fn transcribe_with(
+        &self,
+        file_path: &Path,
+        options: &TranscriptionOptions,
+    ) -> anyhow::Result<Transcript> {
+        let Some(cache_path) = Self::cache_path(file_path, options).await else {
+            return self.inner.transcribe_with(file_path, options).await;
+        };
+
+        if let Ok(text) = tokio::fs::read_to_string(&cache_path).await {
+            match serde_json::from_str::<Transcript>(&text) {
+                Ok(mut t) => {
+                    debug!(cache = %cache_path.display(), "transcription cache hit");
+                    t.cached = true;
+                    return Ok(t);
+                }
+                Err(e) => warn!(cache = %cache_path.display(), error = %e, "ignoring invalid transcription cache"),
+            }
+        }
+
+        let t = self.inner.transcribe_with(file_path, options).await?;
+        // Empty results usually mean a misconfigured provider; don't pin them.
+        if !t.text.is_empty() {
+            let json = serde_json::to_string_pretty(&t)?;
+            if let Err(e) = tokio::fs::write(&cache_path, json).await {
+                warn!(cache = %cache_path.display(), error = %e, "failed to write transcription cache");
+            }
+        }
+        Ok(t)
+    }
+
+    fn display_name(&self) -> &str {
+        self.inner.display_name()
+    }
+}
+
 // ─────────────────────────────────────────────
 // Helper
 // ─────────────────────────────────────────────
 
+/// Parse a Whisper `verbose_json` (or plain `json`) response.
+///
+/// Confidence is the mean of `exp(avg_logprob)` over all segments.
+fn parse_verbose_json(json: &serde_json::Value) -> ChunkTranscript {
+    let text = json["text"].as_str().unwrap_or_default().trim().to_string();
+    let duration_secs = json["duration"].as_f64();
+    let language = json["language"]
+        .as_str()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string);
+
+    let words = json["words"]
+        .as_array()
+        .map(|words| {
+            words
+                .iter()
+                .filter_map(|w| {
+                    Some(TranscriptWord {
+                        word: w["word"].as_str()?.trim().to_string(),
+                        start: w["start"].as_f64()?,
+                        end: w["end"].as_f64()?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let probs: Vec<f64> = json["segments"]
+        .as_array()
+        .map(|segs| {
+            segs.iter()
+                .filter_map(|s| s["avg_logprob"].as_f64())
+                .map(|lp| lp.exp().clamp(0.0, 1.0))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let confidence = (!probs.is_empty()).then(|| probs.iter().sum::<f64>() / probs.len() as f64);
+
+    ChunkTranscript {
+        text,
+        duration_secs,
+        confidence,
+        language,
+        words,
+    }
+}
+
+/// Language named on a `Language` line of a user profile, as an
+/// ISO-639-1 code (`- **Language**: Spanish` → `es`).
+pub fn profile_language(profile: &str) -> Option<String> {
+    profile
+        .lines()
+        .filter(|line| line.to_ascii_lowercase().contains("language"))
+        .find_map(|line| {
+            let value = line.split_once(':')?.1;
+            let word = value
+                .split_whitespace()
+                .next()?
+                .split(['-', '_'])
+                .next()?
+                .trim_matches(|c: char| !c.is_alphabetic())
+                .to_lowercase();
+            language_code(&word)
+        })
+}
+
+/// ISO-639-1 code for a language code or (English or native) name.
+fn language_code(name: &str) -> Option<String> {
+    const NAMES: &[(&str, &[&str])] = &[
+        ("ar", &["arabic"]),
+        ("ca", &["catalan", "català"]),
+        ("de", &["german", "deutsch"]),
+        ("en", &["english"]),
+        ("es", &["spanish", "español", "castellano"]),
+        ("fr", &["french", "français"]),
+        ("hi", &["hindi"]),
+        ("it", &["italian", "italiano"]),
+        ("ja", &["japanese"]),
+        ("ko", &["korean"]),
+        ("nl", &["dutch", "nederlands"]),
+        ("pl", &["polish", "polski"]),
+        ("pt", &["portuguese", "português"]),
+        ("ru", &["russian"]),
+        ("sv", &["swedish", "svenska"]),
+        ("tr", &["turkish", "türkçe"]),
+        ("uk", &["ukrainian"]),
+        ("zh", &["chinese", "mandarin"]),
+    ];
+    if name.len() == 2 && name.chars().all(|c| c.is_ascii_lowercase()) {
+        return Some(name.to_string());
+    }
+    NAMES
+        .iter()
+        .find(|(_, names)| names.contains(&name))
+        .map(|(code, _)| code.to_string())
+}
+
+/// Hex-encoded SHA-256 of `bytes`.
+fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
 /// Check if a file path looks like an audio file.
 pub fn is_audio_file(path: &str) -> bool {
     let lower = path.to_lowercase();
@@ -210,6 +612,200 @@ mod tests {
         assert_eq!(t.api_url, "https://custom.api/v1/audio/transcriptions");
     }
 
+    #[test]
+    fn test_parse_verbose_json() {
+        let json = serde_json::json!({
+            "text": " Hello world. ",
+            "duration": 12.5,
+            "segments": [
+                {"avg_logprob": 0.0},
+                {"avg_logprob": -std::f64::consts::LN_2}
+            ]
+        });
+        let t = parse_verbose_json(&json);
+        assert_eq!(t.text, "Hello world.");
+        assert_eq!(t.duration_secs, Some(12.5));
+        assert!((t.confidence.unwrap() - 0.75).abs() < 1e-9);
+        assert!(t.language.is_none());
+        assert!(t.words.is_empty());
+    }
+
+    #[test]
+    fn test_parse_words_and_language() {
+        let json = serde_json::json!({
+            "text": "Hola mundo",
+            "language": "spanish",
+            "words": [
+                {"word": " Hola", "start": 0.0, "end": 0.4},
+                {"word": "mundo", "start": 0.5, "end": 0.9},
+                {"word": "broken"}
+            ]
+        });
+        let t = parse_verbose_json(&json);
+        assert_eq!(t.language.as_deref(), Some("spanish"));
+        assert_eq!(t.words.len(), 2);
+        assert_eq!(t.words[0], TranscriptWord { word: "Hola".into(), start: 0.0, end: 0.4 });
+    }
+
+    #[test]
+    fn test_profile_language() {
+        assert_eq!(profile_language("- **Language**: Spanish").as_deref(), Some("es"));
+        assert_eq!(profile_language("Language: pt-BR").as_deref(), Some("pt"));
+        assert_eq!(profile_language("- **Language**: Español (Castilian)").as_deref(), Some("es"));
+        assert_eq!(profile_language("- **Language**: (e.g. Spanish)"), None);
+        assert_eq!(profile_language("- **Language**: Klingon"), None);
+        assert_eq!(profile_language("- **Timezone**: UTC"), None);
+    }
+
+    #[test]
+    fn test_parse_plain_json() {
+        let t = parse_verbose_json(&serde_json::json!({"text": "hi"}));
+        assert_eq!(t.text, "hi");
+        assert!(t.duration_secs.is_none());
+        assert!(t.confidence.is_none());
+    }
+
+    #[test]
+    fn test_with_model() {
+        let t = GroqTranscriber::new("key").with_model("whisper-large-v3-turbo");
+        assert_eq!(t.model, "whisper-large-v3-turbo");
+        let t = GroqTranscriber::new("key").with_model("");
+        assert_eq!(t.model, "whisper-large-v3");
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_detailed_uploads_without_ffmpeg() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "text": "remind me to call mom",
+                "duration": 3.2,
+                "segments": [{"avg_logprob": -0.1}]
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("oxibot-transcribe-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("voice.ogg");
+        std::fs::write(&file, b"OggS fake audio").unwrap();
+
+        let t = GroqTranscriber::with_url(
+            "key",
+            &format!("{}/v1/audio/transcriptions", server.uri()),
+        )
+        .with_preprocessor(
+            AudioPreprocessor::new().with_binaries("/nonexistent/ffmpeg", "/nonexistent/ffprobe"),
+        );
+
+        let result = t.transcribe_detailed(&file).await.unwrap();
+        assert_eq!(result.text, "remind me to call mom");
+        assert_eq!(result.duration_secs, Some(3.2));
+        assert_eq!(result.chunks, 1);
+        assert!(result.confidence.unwrap() > 0.9);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_with_options() {
+        use wiremock::matchers::{body_string_contains, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_string_contains("name=\"language\"\r\n\r\nes\r\n"))
+            .and(body_string_contains("name=\"temperature\"\r\n\r\n0.2\r\n"))
+            .and(body_string_contains("name=\"timestamp_granularities[]\"\r\n\r\nword\r\n"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "text": "hola",
+                "language": "spanish",
+                "words": [{"word": "hola", "start": 0.1, "end": 0.5}]
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("voice.ogg");
+        std::fs::write(&file, b"OggS fake audio").unwrap();
+
+        let t = GroqTranscriber::with_url("key", &server.uri());
+        let options = TranscriptionOptions {
+            language: Some("es".into()),
+            temperature: Some(0.2),
+            word_timestamps: true,
+        };
+        let result = t.transcribe_with(&file, &options).await.unwrap();
+        assert_eq!(result.text, "hola");
+        assert_eq!(result.language.as_deref(), Some("spanish"));
+        assert_eq!(result.words.len(), 1);
+
+        // Without the options the mock doesn't match.
+        assert!(t.transcribe_detailed(&file).await.is_err());
+    }
+
+    struct CountingTranscriber {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for CountingTranscriber {
+        async fn transcribe(&self, _file_path: &Path) -> anyhow::Result<String> {
+            let n = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(format!("transcript {n}"))
+        }
+
+        fn display_name(&self) -> &str {
+            "Counting"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_transcriber_dedups_by_content() {
+        let dir = std::env::temp_dir().join(format!("oxibot-transcribe-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("voice_1.ogg");
+        let forwarded = dir.join("voice_2.ogg");
+        let other = dir.join("voice_3.ogg");
+        std::fs::write(&first, b"OggS same audio").unwrap();
+        std::fs::write(&forwarded, b"OggS same audio").unwrap();
+        std::fs::write(&other, b"OggS other audio").unwrap();
+
+        let t = CachedTranscriber::new(CountingTranscriber {
+            calls: Default::default(),
+        });
+
+        let a = t.transcribe_detailed(&first).await.unwrap();
+        assert_eq!(a.text, "transcript 0");
+        assert!(!a.cached);
+
+        let b = t.transcribe_detailed(&forwarded).await.unwrap();
+        assert_eq!(b.text, "transcript 0");
+        assert!(b.cached);
+        assert!(dir
+            .join(format!("{}.transcript.json", content_hash(b"OggS same audio")))
+            .exists());
+
+        let c = t.transcribe_detailed(&other).await.unwrap();
+        assert_eq!(c.text, "transcript 1");
+        assert!(!c.cached);
+        assert_eq!(t.inner.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
     #[tokio::test]
     async fn test_transcribe_file_not_found() {
         let t = GroqTranscriber::new("test-key");
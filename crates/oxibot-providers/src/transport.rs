@@ -0,0 +1,143 @@
+//! HTTP transport — how [`HttpProvider`](crate::HttpProvider) sends requests.
+//!
+//! The provider builds requests and interprets responses; an
+//! [`HttpTransport`] only moves bytes. Native builds use
+//! [`ReqwestTransport`] (the `native` feature). Apps embedding the provider
+//! client elsewhere — a `wasm32-wasip1` module, a Tauri or web frontend —
+//! build without default features and hand in their own transport with
+//! [`HttpProvider::with_transport`](crate::HttpProvider::with_transport).
+
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A POST request with a JSON body.
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub url: String,
+    /// Header names and values, in order (`Content-Type` is implied).
+    pub headers: Vec<(String, String)>,
+    /// Serialized JSON body.
+    pub body: String,
+    /// Give up after this long.
+    pub timeout: Duration,
+}
+
+/// A response, whatever its status.
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Value of the header `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends requests for a provider client.
+///
+/// Return `Err` only when no response arrived (timeout, connection
+/// failure); error statuses are regular responses.
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// Whether `name: value` is a valid HTTP header.
+pub fn is_valid_header(name: &str, value: &str) -> bool {
+    const SEPARATORS: &str = "()<>@,;:\\\"/[]?={}";
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_graphic() && !SEPARATORS.contains(c))
+        && value.chars().all(|c| c == '\t' || !c.is_control())
+}
+
+// ─────────────────────────────────────────────
+// reqwest
+// ─────────────────────────────────────────────
+
+/// Transport backed by a pooled `reqwest` client.
+#[cfg(feature = "native")]
+#[derive(Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "native")]
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "native")]
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .client
+            .post(&request.url)
+            .timeout(request.timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in &request.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder.body(request.body).send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.text().await?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+/// Placeholder used when no transport is compiled in; every request fails.
+#[cfg(not(feature = "native"))]
+pub(crate) struct MissingTransport;
+
+#[cfg(not(feature = "native"))]
+#[async_trait]
+impl HttpTransport for MissingTransport {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse> {
+        anyhow::bail!("no HTTP transport: build with the `native` feature or call HttpProvider::with_transport")
+    }
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_helpers() {
+        assert!(is_valid_header("X-App-Code", "abc def"));
+        assert!(!is_valid_header("X App", "v"));
+        assert!(!is_valid_header("", "v"));
+        assert!(!is_valid_header("X-Key", "a\nb"));
+
+        let resp = HttpResponse {
+            status: 429,
+            headers: vec![("retry-after".into(), "3".into())],
+            body: String::new(),
+        };
+        assert_eq!(resp.header("Retry-After"), Some("3"));
+        assert!(!resp.is_success());
+    }
+}
@@ -0,0 +1,136 @@
+//! Provider-side web search — let the provider search and cite sources.
+//!
+//! OpenAI, OpenRouter, Gemini and Anthropic can run web searches themselves
+//! and return an answer grounded in the results, but each asks for it
+//! differently. A provider's [`WebSearchStyle`] says how; [`apply`] adds the
+//! right fields to a request when
+//! [`LlmRequestConfig::web_search`](crate::LlmRequestConfig::web_search) is
+//! set. The cited pages come back in
+//! [`LlmResponse::citations`](oxibot_core::types::LlmResponse::citations).
+
+use serde_json::{json, Value};
+
+use oxibot_core::types::ChatCompletionRequest;
+
+/// Searches the provider may run per request (Anthropic `max_uses`).
+const MAX_SEARCHES: u32 = 5;
+
+/// How a provider is asked to search the web.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebSearchStyle {
+    /// No provider-side search.
+    None,
+    /// `web_search_options: {}` (OpenAI search models, e.g.
+    /// `gpt-4o-mini-search-preview`).
+    Options,
+    /// `plugins: [{"id": "web"}]` (OpenRouter, any model).
+    Plugin,
+    /// A `google_search` entry in `tools` (Gemini grounding).
+    GoogleSearch,
+    /// Anthropic's `web_search` server tool in `tools`.
+    AnthropicTool,
+}
+
+impl WebSearchStyle {
+    /// Parse a config value: `""`/`"none"`, `"options"`, `"plugin"`,
+    /// `"google"` or `"anthropic"`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "none" => Some(WebSearchStyle::None),
+            "options" | "openai" => Some(WebSearchStyle::Options),
+            "plugin" | "openrouter" => Some(WebSearchStyle::Plugin),
+            "google" | "gemini" => Some(WebSearchStyle::GoogleSearch),
+            "anthropic" => Some(WebSearchStyle::AnthropicTool),
+            _ => None,
+        }
+    }
+
+    /// Whether the provider can search at all.
+    pub fn is_supported(self) -> bool {
+        self != WebSearchStyle::None
+    }
+}
+
+/// Ask the provider to search the web while answering `request`.
+pub fn apply(style: WebSearchStyle, request: &mut ChatCompletionRequest) {
+    match style {
+        WebSearchStyle::None => {}
+        WebSearchStyle::Options => {
+            request.extra.insert("web_search_options".into(), json!({}));
+        }
+        WebSearchStyle::Plugin => {
+            request.extra.insert("plugins".into(), json!([{ "id": "web" }]));
+        }
+        WebSearchStyle::GoogleSearch => add_tool(request, json!({ "google_search": {} })),
+        WebSearchStyle::AnthropicTool => add_tool(
+            request,
+            json!({ "type": "web_search_20250305", "name": "web_search", "max_uses": MAX_SEARCHES }),
+        ),
+    }
+}
+
+/// Append a built-in tool after the function tools. The typed `tools`
+/// field only holds functions, so the whole list moves to `extra`.
+fn add_tool(request: &mut ChatCompletionRequest, tool: Value) {
+    let mut tools: Vec<Value> = request
+        .tools
+        .take()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|t| serde_json::to_value(t).ok())
+        .collect();
+    tools.push(tool);
+    request.extra.insert("tools".into(), Value::Array(tools));
+}
+
+// ─────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxibot_core::types::{Message, ToolDefinition};
+
+    fn request(tools: Option<Vec<ToolDefinition>>) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "m".into(),
+            messages: vec![Message::user("latest rust release?")],
+            tools,
+            tool_choice: None,
+            parallel_tool_calls: None,
+            max_tokens: None,
+            temperature: None,
+            extra: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let body = |style, tools| {
+            let mut req = request(tools);
+            apply(style, &mut req);
+            serde_json::to_value(&req).unwrap()
+        };
+
+        assert!(body(WebSearchStyle::None, None).get("web_search_options").is_none());
+        assert_eq!(body(WebSearchStyle::Options, None)["web_search_options"], json!({}));
+        assert_eq!(body(WebSearchStyle::Plugin, None)["plugins"], json!([{"id": "web"}]));
+        assert_eq!(body(WebSearchStyle::GoogleSearch, None)["tools"], json!([{"google_search": {}}]));
+
+        let calc = ToolDefinition::new("calc", "Evaluate", json!({"type": "object"}));
+        let tools = body(WebSearchStyle::AnthropicTool, Some(vec![calc]))["tools"].clone();
+        assert_eq!(tools.as_array().unwrap().len(), 2);
+        assert_eq!(tools[0]["function"]["name"], "calc");
+        assert_eq!(tools[1]["type"], "web_search_20250305");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(WebSearchStyle::parse(""), Some(WebSearchStyle::None));
+        assert_eq!(WebSearchStyle::parse("Plugin"), Some(WebSearchStyle::Plugin));
+        assert_eq!(WebSearchStyle::parse("gemini"), Some(WebSearchStyle::GoogleSearch));
+        assert_eq!(WebSearchStyle::parse("bing"), None);
+        assert!(!WebSearchStyle::None.is_supported());
+    }
+}